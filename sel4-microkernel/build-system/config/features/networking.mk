@@ -92,6 +92,9 @@ ifeq ($(PRODUCT),tvdemo)
 ifndef ISOLATED
 $(error NET_DRIVER=$(NET_DRIVER) on tvdemo requires ISOLATED=1 (three-PD system))
 endif
+ifdef SPLIT
+$(error NET_DRIVER=$(NET_DRIVER) is not supported with SPLIT=1 (tvdemo-split.system has no Network PD))
+endif
 SYSTEM_DESC := $(PRODUCT_SRC_DIR)/tvdemo-network.system
 # Build the Graphics PD with its network client enabled
 # (networking.mk is included after tvdemo.mk; recipe variables expand at
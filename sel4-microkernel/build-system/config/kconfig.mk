@@ -12,7 +12,18 @@
 # content changes, so it is safe as a rule prerequisite.
 #
 # Consumers wired up here:
-#   - input_pd cargo features (uart/usb) from CONFIG_INPUT_*
+#   - input_pd cargo features (uart/usb/debug-monitor/audit) from
+#     CONFIG_INPUT_*, CONFIG_DEBUG_MONITOR and CONFIG_SECURITY_AUDIT
+#   - the `audit` feature of the product's primary PD (the input ring's
+#     consumer) from CONFIG_SECURITY_AUDIT
+#   - the Storage PD image and the primary PD's `sdcard` feature from
+#     CONFIG_STORAGE_SDCARD, and its `signed-photos` feature from
+#     CONFIG_SIGNED_PHOTOS
+#   - the Audio PD image, its output (`pwm` or `i2s`) and the primary PD's
+#     `audio` feature from CONFIG_AUDIO and CONFIG_AUDIO_I2S
+#   - the Watchdog PD image and the `watchdog` feature of the PDs it
+#     watches (the primary PD and the Timer PD) from CONFIG_WATCHDOG
+#   - the Graphics PD's `early-console` feature from CONFIG_EARLY_CONSOLE
 #   - the product .system description is preprocessed with
 #     `kconfig-tool gensystem`, which keeps or strips
 #     <!-- @if CONFIG_X --> ... <!-- @endif --> blocks. Device MMIO is
@@ -69,6 +80,12 @@ endif
 ifeq ($(CONFIG_INPUT_USB_KEYBOARD),y)
 INPUT_PD_FEATURE_LIST += usb
 endif
+ifeq ($(CONFIG_DEBUG_MONITOR),y)
+INPUT_PD_FEATURE_LIST += debug-monitor
+endif
+ifeq ($(CONFIG_SECURITY_AUDIT),y)
+INPUT_PD_FEATURE_LIST += audit
+endif
 
 INPUT_PD_FEATURES := --no-default-features \
 	$(if $(strip $(INPUT_PD_FEATURE_LIST)),\
@@ -78,8 +95,65 @@ INPUT_PD_FEATURES := --no-default-features \
 # (same pattern networking.mk uses for the graphics PD features).
 $(INPUT_PD_ELF): CARGO_BUILD_STD += $(INPUT_PD_FEATURES)
 
+# The other end of the input ring checks its accesses too
+ifeq ($(CONFIG_SECURITY_AUDIT),y)
+$(PD_ELF): CARGO_BUILD_STD += --features audit
+endif
+
 endif # INPUT_PD_ELF
 
+# --- Storage PD ---------------------------------------------------------------
+# Products that can serve photos from the SD card define STORAGE_PD_ELF; the
+# image only gets the PD (and the primary PD its client) when configured.
+ifdef STORAGE_PD_ELF
+ifeq ($(CONFIG_STORAGE_SDCARD),y)
+$(PD_ELF): CARGO_BUILD_STD += --features sdcard
+$(LOADER_ELF): $(STORAGE_PD_ELF)
+ifeq ($(CONFIG_SIGNED_PHOTOS),y)
+ifeq ($(PHOTO_SIGNING_KEY),)
+$(error CONFIG_SIGNED_PHOTOS=y needs PHOTO_SIGNING_KEY, the photo signing public key in hex)
+endif
+export PHOTO_SIGNING_KEY
+$(PD_ELF): CARGO_BUILD_STD += --features signed-photos
+endif
+endif
+endif # STORAGE_PD_ELF
+
+# --- Audio PD -------------------------------------------------------------------
+# Products with a sound-capable system description define AUDIO_PD_ELF; the
+# Audio PD defaults to the PWM headphone output.
+ifdef AUDIO_PD_ELF
+ifeq ($(CONFIG_AUDIO),y)
+$(PD_ELF): CARGO_BUILD_STD += --features audio
+$(LOADER_ELF): $(AUDIO_PD_ELF)
+ifeq ($(CONFIG_AUDIO_I2S),y)
+$(AUDIO_PD_ELF): CARGO_BUILD_STD += --no-default-features --features i2s
+endif
+endif
+endif # AUDIO_PD_ELF
+
+# --- Watchdog PD ----------------------------------------------------------------
+# Products whose system description has a Watchdog PD define WATCHDOG_PD_ELF;
+# the PDs it watches only ping it when it is configured.
+ifdef WATCHDOG_PD_ELF
+ifeq ($(CONFIG_WATCHDOG),y)
+$(PD_ELF): CARGO_BUILD_STD += --features watchdog
+$(TIMER_PD_ELF): CARGO_BUILD_STD += --features watchdog
+$(LOADER_ELF): $(WATCHDOG_PD_ELF)
+endif
+endif # WATCHDOG_PD_ELF
+
+# --- Early console --------------------------------------------------------------
+# Only the isolated tvdemo Graphics PD (graphics_input_pd) has the feature,
+# and not as the split App PD, which has no framebuffer.
+ifdef GRAPHICS_PD_ELF
+ifndef SPLIT
+ifeq ($(CONFIG_EARLY_CONSOLE),y)
+$(GRAPHICS_PD_ELF): CARGO_BUILD_STD += --features early-console
+endif
+endif
+endif # GRAPHICS_PD_ELF
+
 # --- Configured system description -------------------------------------------
 # Preprocess the product's .system template into the build directory,
 # resolving @if CONFIG_X blocks, and point the Microkit rules at the result.
@@ -20,7 +20,8 @@ SYSTEM_DESC := $(PRODUCT_SRC_DIR)/graphics.system
 
 # Source files for dependency tracking
 PRODUCT_SOURCES := $(wildcard $(PRODUCT_SRC_DIR)/src/*.rs) \
-                   $(PRODUCT_SRC_DIR)/Cargo.toml
+                   $(PRODUCT_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-bcm2711/src/*.rs)
 
 # Output files
 SYSTEM_IMAGE := $(BUILD_DIR)/loader.img
@@ -10,7 +10,30 @@ PD_NAME := ipdemo_pd
 PD_ELF := $(BUILD_DIR)/$(PD_NAME).elf
 SYSTEM_DESC := $(PRODUCT_SRC_DIR)/ipdemo.system
 
-$(PD_ELF): CARGO_BUILD_STD += --features "net-virtio net-stack-smoltcp qemu-time-fallback"
+# IPV6=1 adds link-local and SLAAC addressing (slirp advertises fec0::/64)
+IPV6 ?= 0
+IPDEMO_FEATURES := net-virtio net-stack-smoltcp qemu-time-fallback
+ifeq ($(IPV6),1)
+IPDEMO_FEATURES += net-ipv6
+endif
+# MDNS=1 adds the mDNS responder. slirp does not pass multicast, so host
+# UDP 5354 is forwarded to it instead, for a plain resolver to ask:
+#   dig -p 5354 @127.0.0.1 photoframe.local
+MDNS ?= 0
+ifeq ($(MDNS),1)
+IPDEMO_FEATURES += net-mdns
+IPDEMO_HOSTFWD := ,hostfwd=udp::5354-:5353
+endif
+# HTTP=1 adds the control server front end on TCP 80, forwarded from host
+# TCP 8080:
+#   curl -i http://127.0.0.1:8080/
+HTTP ?= 0
+ifeq ($(HTTP),1)
+IPDEMO_FEATURES += net-http
+IPDEMO_HOSTFWD := $(IPDEMO_HOSTFWD),hostfwd=tcp::8080-:80
+endif
+
+$(PD_ELF): CARGO_BUILD_STD += --features "$(IPDEMO_FEATURES)"
 
 PRODUCT_SOURCES := $(wildcard $(PRODUCT_SRC_DIR)/src/*.rs) \
                    $(wildcard $(PRODUCT_SRC_DIR)/src/**/*.rs) \
@@ -21,4 +44,4 @@ SYSTEM_IMAGE := $(BUILD_DIR)/loader.img
 LOADER_ELF := $(BUILD_DIR)/loader.elf
 REPORT := $(BUILD_DIR)/report.txt
 
-QEMU_EXTRA_ARGS += -device virtio-net-device,netdev=net0 -netdev user,id=net0
+QEMU_EXTRA_ARGS += -device virtio-net-device,netdev=net0 -netdev user,id=net0$(IPDEMO_HOSTFWD)
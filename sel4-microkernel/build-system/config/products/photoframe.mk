@@ -10,6 +10,14 @@
 #   - Input PD: UART input handling (isolated from display)
 #   - Photoframe PD: Photo decoding and display
 #   - Only shared memory: 4KB ring buffer for input events
+#   - Timer PD: wall-clock time, set by the Input PD (Ctrl-T on the console)
+#     and published on a clock page the Photoframe PD maps read-only
+#   - Storage PD (CONFIG_STORAGE_SDCARD): SD card photos over PPC, file
+#     bytes in a buffer the Photoframe PD maps read-only
+#   - Audio PD (CONFIG_AUDIO): headphone jack or I2S DAC, plays the
+#     slideshow chime when the Photoframe PD calls
+#   - Watchdog PD (CONFIG_WATCHDOG): hardware watchdog, fed only while the
+#     Timer and Photoframe PDs keep pinging it
 
 # Validate platform
 ifneq ($(PLATFORM),rpi4)
@@ -20,18 +28,31 @@ endif
 PRODUCT_NAME := Secure Photo Frame
 PRODUCT_SRC_DIR := $(ROOT_DIR)/rpi4-photoframe
 INPUT_PD_SRC_DIR := $(ROOT_DIR)/rpi4-input-pd
+STORAGE_PD_SRC_DIR := $(ROOT_DIR)/rpi4-storage-pd
+TIMER_PD_SRC_DIR := $(ROOT_DIR)/rpi4-timer-pd
+AUDIO_PD_SRC_DIR := $(ROOT_DIR)/rpi4-audio-pd
+WATCHDOG_PD_SRC_DIR := $(ROOT_DIR)/rpi4-watchdog-pd
 
 # Protection Domain names
 INPUT_PD_NAME := input_pd
 PHOTOFRAME_PD_NAME := photoframe_pd
 INPUT_PD_ELF := $(BUILD_DIR)/$(INPUT_PD_NAME).elf
 PHOTOFRAME_PD_ELF := $(BUILD_DIR)/$(PHOTOFRAME_PD_NAME).elf
+STORAGE_PD_NAME := storage_pd
+STORAGE_PD_ELF := $(BUILD_DIR)/$(STORAGE_PD_NAME).elf
+TIMER_PD_NAME := timer_pd
+TIMER_PD_ELF := $(BUILD_DIR)/$(TIMER_PD_NAME).elf
+AUDIO_PD_NAME := audio_pd
+AUDIO_PD_ELF := $(BUILD_DIR)/$(AUDIO_PD_NAME).elf
+WATCHDOG_PD_NAME := watchdog_pd
+WATCHDOG_PD_ELF := $(BUILD_DIR)/$(WATCHDOG_PD_NAME).elf
 
 # Primary PD for build system
 PD_NAME := $(PHOTOFRAME_PD_NAME)
 PD_ELF := $(PHOTOFRAME_PD_ELF)
 
-# System descriptor (two-PD architecture)
+# System descriptor (Input, Photoframe and Timer PDs, plus Storage, Audio
+# and Watchdog)
 SYSTEM_DESC := $(PRODUCT_SRC_DIR)/photoframe.system
 
 # Source files for dependency tracking
@@ -41,8 +62,24 @@ PRODUCT_SOURCES := $(wildcard $(PRODUCT_SRC_DIR)/src/*.rs) \
                    $(wildcard $(INPUT_PD_SRC_DIR)/src/*.rs) \
                    $(INPUT_PD_SRC_DIR)/Cargo.toml \
                    $(wildcard $(ROOT_DIR)/rpi4-graphics/src/*.rs) \
+                   $(wildcard $(ROOT_DIR)/rpi4-bcm2711/src/*.rs) \
                    $(wildcard $(ROOT_DIR)/rpi4-input/src/*.rs) \
-                   $(wildcard $(ROOT_DIR)/rpi4-input-protocol/src/*.rs)
+                   $(wildcard $(ROOT_DIR)/rpi4-input-protocol/src/*.rs) \
+                   $(wildcard $(STORAGE_PD_SRC_DIR)/src/*.rs) \
+                   $(STORAGE_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-storage/src/*.rs) \
+                   $(wildcard $(ROOT_DIR)/rpi4-storage-protocol/src/*.rs) \
+                   $(wildcard $(TIMER_PD_SRC_DIR)/src/*.rs) \
+                   $(TIMER_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-clock-protocol/src/*.rs) \
+                   $(wildcard $(AUDIO_PD_SRC_DIR)/src/*.rs) \
+                   $(wildcard $(AUDIO_PD_SRC_DIR)/sounds/*) \
+                   $(AUDIO_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-audio/src/*.rs) \
+                   $(wildcard $(ROOT_DIR)/rpi4-audio-protocol/src/*.rs) \
+                   $(wildcard $(WATCHDOG_PD_SRC_DIR)/src/*.rs) \
+                   $(WATCHDOG_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-watchdog-protocol/src/*.rs)
 
 # Output files
 SYSTEM_IMAGE := $(BUILD_DIR)/loader.img
@@ -70,8 +107,51 @@ $(PHOTOFRAME_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
 	cp $(PRODUCT_SRC_DIR)/target/$(CARGO_TARGET)/release/$(PHOTOFRAME_PD_NAME).elf $@
 	@echo "Built: $@"
 
-# Ensure both PDs are built before system image
+# Build Storage PD (only linked into the image with CONFIG_STORAGE_SDCARD,
+# see config/kconfig.mk)
+$(STORAGE_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(STORAGE_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(STORAGE_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		$(CARGO_BUILD_STD)
+	cp $(STORAGE_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(STORAGE_PD_NAME).elf $@
+	@echo "Built: $@"
+
+# Build Audio PD (only linked into the image with CONFIG_AUDIO, see
+# config/kconfig.mk)
+$(AUDIO_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(AUDIO_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(AUDIO_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		$(CARGO_BUILD_STD)
+	cp $(AUDIO_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(AUDIO_PD_NAME).elf $@
+	@echo "Built: $@"
+
+# Build Watchdog PD (only linked into the image with CONFIG_WATCHDOG, see
+# config/kconfig.mk)
+$(WATCHDOG_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(WATCHDOG_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(WATCHDOG_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		$(CARGO_BUILD_STD)
+	cp $(WATCHDOG_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(WATCHDOG_PD_NAME).elf $@
+	@echo "Built: $@"
+
+# Build Timer PD
+$(TIMER_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(TIMER_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(TIMER_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		$(CARGO_BUILD_STD)
+	cp $(TIMER_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(TIMER_PD_NAME).elf $@
+	@echo "Built: $@"
+
+# Ensure every PD is built before system image
 .PHONY: build-photoframe-pds
-build-photoframe-pds: $(INPUT_PD_ELF) $(PHOTOFRAME_PD_ELF)
+build-photoframe-pds: $(INPUT_PD_ELF) $(PHOTOFRAME_PD_ELF) $(TIMER_PD_ELF)
 
 $(LOADER_ELF): build-photoframe-pds
@@ -33,6 +33,7 @@ SYSTEM_DESC := $(PRODUCT_SRC_DIR)/tpmtest.system
 # Source files for dependency tracking
 PRODUCT_SOURCES := $(wildcard $(PRODUCT_SRC_DIR)/src/*.rs) \
                    $(PRODUCT_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-bcm2711/src/*.rs) \
                    $(wildcard $(ROOT_DIR)/rpi4-tpm-boot/src/*.rs) \
                    $(ROOT_DIR)/rpi4-tpm-boot/Cargo.toml
 
@@ -6,6 +6,13 @@
 # Build modes (set ISOLATED=1 for two-PD architecture):
 #   make PRODUCT=tvdemo PLATFORM=rpi4              - Single PD (UART in graphics PD)
 #   make PRODUCT=tvdemo PLATFORM=rpi4 ISOLATED=1   - Isolated PDs (Input PD + Graphics PD)
+#   make PRODUCT=tvdemo PLATFORM=rpi4 ISOLATED=1 SPLIT=1
+#                                                  - Split rendering (Input PD + App PD + Present PD)
+#   make PRODUCT=tvdemo PLATFORM=rpi4 ISOLATED=1 DUAL=1
+#                                                  - HDMI + SPI LCD (Input PD + Graphics PD + Display PD)
+#
+# With ISOLATED=1 (not SPLIT or DUAL), CONFIG_AUDIO=y adds the Audio PD: menu
+# clicks and a jingle through the headphone jack.
 
 # Validate platform
 ifneq ($(PLATFORM),rpi4)
@@ -15,6 +22,8 @@ endif
 # Product info
 PRODUCT_SRC_DIR := $(ROOT_DIR)/rpi4-graphics
 INPUT_PD_SRC_DIR := $(ROOT_DIR)/rpi4-input-pd
+AUDIO_PD_SRC_DIR := $(ROOT_DIR)/rpi4-audio-pd
+DISPLAY_PD_SRC_DIR := $(ROOT_DIR)/rpi4-spi-display
 
 # Check for isolated mode
 ifdef ISOLATED
@@ -40,9 +49,61 @@ PRODUCT_SOURCES := $(wildcard $(PRODUCT_SRC_DIR)/src/*.rs) \
                    $(INPUT_PD_SRC_DIR)/Cargo.toml \
                    $(wildcard $(ROOT_DIR)/rpi4-tvdemo/src/*.rs) \
                    $(wildcard $(ROOT_DIR)/rpi4-input/src/*.rs) \
-                   $(wildcard $(ROOT_DIR)/rpi4-input-protocol/src/*.rs)
+                   $(wildcard $(ROOT_DIR)/rpi4-input-protocol/src/*.rs) \
+                   $(wildcard $(ROOT_DIR)/rpi4-audio-protocol/src/*.rs) \
+                   $(wildcard $(ROOT_DIR)/rpi4-bcm2711/src/*.rs)
+
+# The Audio PD only fits the two-PD system description (the Display PD of
+# DUAL owns the PWM, clock and DMA registers it would need); see
+# config/kconfig.mk for when it is linked in
+ifndef SPLIT
+ifndef DUAL
+AUDIO_PD_NAME := audio_pd
+AUDIO_PD_ELF := $(BUILD_DIR)/$(AUDIO_PD_NAME).elf
+PRODUCT_SOURCES += $(wildcard $(AUDIO_PD_SRC_DIR)/src/*.rs) \
+                   $(wildcard $(AUDIO_PD_SRC_DIR)/sounds/*) \
+                   $(AUDIO_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-audio/src/*.rs)
+endif
+endif
+
+# Split rendering: the graphics PD is built as the device-less App PD and
+# present_pd owns the framebuffer and vsync
+ifdef SPLIT
+PRODUCT_NAME := TV Demo (Split App/Present)
+PRESENT_PD_NAME := present_pd
+PRESENT_PD_ELF := $(BUILD_DIR)/$(PRESENT_PD_NAME).elf
+GRAPHICS_PD_FEATURES := --features split
+SYSTEM_DESC := $(PRODUCT_SRC_DIR)/tvdemo-split.system
+PRODUCT_SOURCES += $(wildcard $(ROOT_DIR)/rpi4-frame-protocol/src/*.rs)
+PRODUCT_SOURCES += $(wildcard $(ROOT_DIR)/rpi4-draw-protocol/src/*.rs)
+endif
+
+# HDMI + SPI LCD: the graphics PD keeps HDMI and routes a status panel to
+# the LCD as draw commands; display_pd owns the panel and draws them
+ifdef DUAL
+ifdef SPLIT
+$(error DUAL=1 and SPLIT=1 cannot be combined: the split App PD has no framebuffer to route beside)
+endif
+PRODUCT_NAME := TV Demo (HDMI + SPI LCD)
+DISPLAY_PD_NAME := display_pd
+DISPLAY_PD_ELF := $(BUILD_DIR)/$(DISPLAY_PD_NAME).elf
+GRAPHICS_PD_FEATURES := --features status-lcd
+SYSTEM_DESC := $(PRODUCT_SRC_DIR)/tvdemo-dual.system
+PRODUCT_SOURCES += $(wildcard $(DISPLAY_PD_SRC_DIR)/src/*.rs) \
+                   $(wildcard $(DISPLAY_PD_SRC_DIR)/src/*/*.rs) \
+                   $(DISPLAY_PD_SRC_DIR)/Cargo.toml \
+                   $(wildcard $(ROOT_DIR)/rpi4-draw-protocol/src/*.rs)
+endif
 
 else
+ifdef SPLIT
+$(error SPLIT=1 on tvdemo requires ISOLATED=1 (three-PD system))
+endif
+ifdef DUAL
+$(error DUAL=1 on tvdemo requires ISOLATED=1 (three-PD system))
+endif
+
 PRODUCT_NAME := TV Demo (HDMI)
 
 # Single PD mode: tvdemo_pd only
@@ -92,6 +153,57 @@ $(GRAPHICS_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
 .PHONY: build-isolated-pds
 build-isolated-pds: $(INPUT_PD_ELF) $(GRAPHICS_PD_ELF)
 
+ifdef SPLIT
+# The generic $(PD_ELF) recipe in include/rust.mk wins over the one above,
+# so pass the App PD feature through a target-specific variable
+$(GRAPHICS_PD_ELF): CARGO_BUILD_STD += $(GRAPHICS_PD_FEATURES)
+
+# Build Present PD
+$(PRESENT_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(PRESENT_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(PRODUCT_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		--bin $(PRESENT_PD_NAME) \
+		$(CARGO_BUILD_STD)
+	cp $(CARGO_TARGET_DIR)/$(CARGO_TARGET)/release/$(PRESENT_PD_NAME).elf $@
+	@echo "Built: $@"
+
+build-isolated-pds: $(PRESENT_PD_ELF)
+endif
+
+ifdef DUAL
+# Same as SPLIT: the generic $(PD_ELF) recipe wins, so pass the feature
+# through a target-specific variable
+$(GRAPHICS_PD_ELF): CARGO_BUILD_STD += $(GRAPHICS_PD_FEATURES)
+
+# Build Display PD (SPI LCD), drawing the graphics PD's draw ring
+$(DISPLAY_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(DISPLAY_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(DISPLAY_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		--bin $(DISPLAY_PD_NAME) \
+		--features draw-ring \
+		$(CARGO_BUILD_STD)
+	cp $(DISPLAY_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(DISPLAY_PD_NAME).elf $@
+	@echo "Built: $@"
+
+build-isolated-pds: $(DISPLAY_PD_ELF)
+endif
+
+ifdef AUDIO_PD_ELF
+# Build Audio PD
+$(AUDIO_PD_ELF): $(PRODUCT_SOURCES) | $(BUILD_DIR)
+	@echo "=== Building $(AUDIO_PD_NAME) Protection Domain ($(PLATFORM_ARCH)) ==="
+	cd $(AUDIO_PD_SRC_DIR) && $(CARGO) build \
+		--release \
+		--target $(TARGET_SPEC) \
+		$(CARGO_BUILD_STD)
+	cp $(AUDIO_PD_SRC_DIR)/target/$(CARGO_TARGET)/release/$(AUDIO_PD_NAME).elf $@
+	@echo "Built: $@"
+endif
+
 $(LOADER_ELF): build-isolated-pds
 $(SYSTEM_IMAGE): build-isolated-pds
 endif
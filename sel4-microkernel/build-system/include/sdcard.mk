@@ -16,8 +16,9 @@ $(FIRMWARE_DIR):
 
 firmware: $(FIRMWARE_DIR)
 
-# Create config.txt
-$(BUILD_DIR)/config.txt: | $(BUILD_DIR)
+# Create config.txt (rebuilt when the configuration changes: CONFIG_AUDIO
+# routes the audio pins)
+$(BUILD_DIR)/config.txt: $(DOT_CONFIG) | $(BUILD_DIR)
 	@echo "=== Creating config.txt ==="
 	@echo "# seL4 Microkit on Raspberry Pi 4" > $@
 	@echo "arm_64bit=1" >> $@
@@ -36,6 +37,16 @@ $(BUILD_DIR)/config.txt: | $(BUILD_DIR)
 	@echo "# Enable UART for debug output" >> $@
 	@echo "enable_uart=1" >> $@
 	@echo "uart_2ndstage=1" >> $@
+ifeq ($(CONFIG_AUDIO),y)
+	@echo "" >> $@
+ifeq ($(CONFIG_AUDIO_I2S),y)
+	@echo "# Audio PD: PCM/I2S on GPIO18-21 (ALT0)" >> $@
+	@echo "gpio=18-21=a0" >> $@
+else
+	@echo "# Audio PD: PWM1 on GPIO40/41 (ALT0), the headphone jack" >> $@
+	@echo "gpio=40,41=a0" >> $@
+endif
+endif
 	@echo "Created: $@"
 
 # Create SD card image using mtools (no root required)
@@ -0,0 +1,270 @@
+//! # Audio Protection Domain
+//!
+//! Owns the sound output: the PWM block behind the headphone jack (or,
+//! built with `i2s`, the PCM block and a DAC HAT), its clock and one DMA
+//! channel are mapped into this PD and no other. Clients start and stop
+//! sounds with the calls defined in `rpi4-audio-protocol`, which run on a
+//! verified fixed-voice mixer, and may stream samples they render
+//! themselves through the `audio_stream` ring:
+//!
+//! ```text
+//! ┌───────────┐  PLAY_TONE / PLAY_SINE / PLAY_SAMPLE (PPC)  ┌──────────┐
+//! │ Client PD │ ──────────────────────────────────────────► │ Audio PD │
+//! │ (demo)    │ ── audio_stream (rw / rw) ────────────────► │  Mixer   │
+//! └───────────┘                                             └────┬─────┘
+//!                                                                │ DMA
+//!                                                                ▼
+//!                                                        PWM1 ─► jack
+//! ```
+//!
+//! The DMA channel loops over two buffer halves and interrupts as each
+//! finishes; the PD then refills that half with the mix of its voices and
+//! whatever the stream holds. The sample bank is built at start-up from
+//! WAV files embedded in the image, resampled to the mixer's rate.
+//!
+//! ## Security Properties
+//!
+//! 1. **Isolated Access**: only this PD can reach the audio output, its
+//!    clock and its DMA channel; clients can only ask for sounds
+//! 2. **No Client Memory**: calls name tones by frequency and clips by
+//!    index, so the PD never follows a pointer a client chose; the stream
+//!    is read through a ring that treats a corrupt header as empty
+//! 3. **Checked Calls**: requests are decoded strictly and every volume,
+//!    frequency, duration, voice and clip is checked before it is played
+//! 4. **No Callbacks**: this PD cannot call or notify its client; a
+//!    compromised Audio PD can make noise, and nothing more
+
+#![no_std]
+#![no_main]
+
+#[cfg(all(feature = "pwm", feature = "i2s"))]
+compile_error!("the Audio PD drives one output: enable `pwm` or `i2s`, not both");
+#[cfg(not(any(feature = "pwm", feature = "i2s")))]
+compile_error!("the Audio PD needs an output: enable `pwm` or `i2s`");
+
+use core::cell::UnsafeCell;
+
+use sel4_microkit::{
+    debug_println, protection_domain, with_msg_regs, with_msg_regs_mut, Channel, ChannelSet, Handler, MessageInfo,
+};
+use rpi4_audio::{DmaChannel, DmaRegion, SampleSource, AUDIO_PRIORITY};
+use rpi4_audio_protocol::stream::{add_stream, stream_ring, StreamHeader, StreamRing};
+use rpi4_audio_protocol::{
+    clips, AudioRequest, Mixer, Wav, AUDIO_CHANNEL_ID, AUDIO_DMA_CHANNEL, AUDIO_DMA_PADDR, AUDIO_DMA_SIZE,
+    AUDIO_DMA_VADDR, AUDIO_IRQ_CHANNEL_ID, AUDIO_PD_CM_BASE, AUDIO_PD_DMA_BASE, AUDIO_PD_OUTPUT_BASE,
+    AUDIO_RATE_HZ, AUDIO_STREAM_VADDR,
+};
+use rpi4_pd_error::{reply_code, ErrorDomain, ErrorKind, PdError, PdResult, ResultExt};
+use verified_microkernel::rpc::{self, MessageWriter, RpcError};
+use verified_microkernel::IpcBuffer;
+
+#[cfg(feature = "i2s")]
+use rpi4_audio::{I2sConfig, I2sOutput as Output};
+#[cfg(feature = "pwm")]
+use rpi4_audio::PwmOutput as Output;
+
+const DMA_IRQ_CHANNEL: Channel = Channel::new(AUDIO_IRQ_CHANNEL_ID);
+
+/// Malformed call (the detail says why, see `RpcError::detail`)
+const INVALID_REQUEST: PdError = PdError::new(ErrorDomain::Audio, ErrorKind::InvalidArgument);
+
+/// Call on a channel other than the client's
+const WRONG_CHANNEL: PdError = PdError::new(ErrorDomain::Audio, ErrorKind::Denied);
+
+/// Voices the mixer runs: a chime and a few clicks over it
+const VOICES: usize = 4;
+
+/// Samples per pull from the stream ring
+const STREAM_CHUNK: usize = 64;
+
+/// Longest clip at the mixer's rate: half a second
+const CLIP_CAPACITY: usize = AUDIO_RATE_HZ as usize / 2;
+
+/// Clip files in bank order (see `rpi4_audio_protocol::clips`)
+const CLIP_FILES: [(&str, &[u8]); clips::COUNT] = [("chime", include_bytes!("../sounds/chime.wav"))];
+
+/// Resampled clips, filled once at start-up
+struct ClipMemory(UnsafeCell<[[i16; CLIP_CAPACITY]; clips::COUNT]>);
+
+/// The bank the mixer plays from: a slice of each clip's memory
+struct BankMemory(UnsafeCell<[&'static [i16]; clips::COUNT]>);
+
+// Safety: a PD is single-threaded, and both are only written by `load_bank`
+unsafe impl Sync for ClipMemory {}
+unsafe impl Sync for BankMemory {}
+
+static CLIPS: ClipMemory = ClipMemory(UnsafeCell::new([[0; CLIP_CAPACITY]; clips::COUNT]));
+static BANK: BankMemory = BankMemory(UnsafeCell::new([&[]; clips::COUNT]));
+
+/// Decode and resample every clip file into the bank, cut to
+/// [`CLIP_CAPACITY`]; a file that does not parse leaves its clip empty,
+/// which plays as silence
+///
+/// # Safety
+/// Call once, before any mixer holds the bank.
+unsafe fn load_bank() -> &'static [&'static [i16]] {
+    let clip_memory = &mut *CLIPS.0.get();
+    let bank = &mut *BANK.0.get();
+    for (index, ((slot, memory), (name, file))) in
+        bank.iter_mut().zip(clip_memory.iter_mut()).zip(CLIP_FILES).enumerate()
+    {
+        match Wav::parse(file).map(|wav| wav.resample_into(memory, AUDIO_RATE_HZ)) {
+            Ok(Some(n)) => {
+                debug_println!("Audio PD: Clip {} ({}), {} samples", index, name, n);
+                *slot = &memory[..n];
+            }
+            Ok(None) => debug_println!("Audio PD: Clip {} ({}) cannot play at {} Hz", index, name, AUDIO_RATE_HZ),
+            Err(e) => debug_println!("Audio PD: Clip {} ({}): {}", index, name, PdError::from(e)),
+        }
+    }
+    bank
+}
+
+/// What the output plays: the mixer's voices plus the client's stream
+struct Source {
+    mixer: Mixer<'static, VOICES>,
+    stream: StreamRing,
+}
+
+impl SampleSource for Source {
+    fn fill(&mut self, out: &mut [i16]) {
+        self.mixer.mix(out);
+        let mut streamed = [0i16; STREAM_CHUNK];
+        for part in out.chunks_mut(STREAM_CHUNK) {
+            let n = self.stream.pop_batch(&mut streamed[..part.len()]);
+            add_stream(part, &streamed[..n]);
+        }
+    }
+}
+
+struct AudioPd {
+    output: Output,
+    source: Source,
+}
+
+impl AudioPd {
+    /// Serve one call, writing the voice it played on or changed to `reply`
+    fn handle(&mut self, channel: Channel, label: u64, buf: &IpcBuffer, reply: &mut MessageWriter<'_>) -> PdResult<()> {
+        if channel.index() != AUDIO_CHANNEL_ID {
+            return Err(WRONG_CHANNEL);
+        }
+        let request: AudioRequest = rpc::decode_request(label, buf).map_err(invalid_request)?;
+        let voice = self.source.mixer.handle(request)?;
+        reply.put(&voice).map_err(invalid_request)
+    }
+}
+
+fn invalid_request(e: RpcError) -> PdError {
+    INVALID_REQUEST.with_detail(e.detail())
+}
+
+impl Handler for AudioPd {
+    type Error = PdError;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        if channels.contains(DMA_IRQ_CHANNEL) {
+            let underruns = self.output.underruns();
+            if let Err(e) = self.output.handle_irq(&mut self.source) {
+                // The channel stopped; stay silent rather than loop on a
+                // dead buffer
+                self.output.stop();
+                return Err(PdError::from(e).context("refilling audio buffer"));
+            }
+            if self.output.underruns() != underruns {
+                debug_println!("Audio PD: Output underrun ({} so far)", self.output.underruns());
+            }
+            DMA_IRQ_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking audio DMA IRQ")
+            })?;
+        }
+        Ok(())
+    }
+
+    fn protected(&mut self, channel: Channel, msg_info: MessageInfo) -> Result<MessageInfo, Self::Error> {
+        let mut buf = IpcBuffer::new();
+        with_msg_regs(|mrs| buf.load(&mrs[..msg_info.count().min(mrs.len())]));
+
+        // The reply label is the PdError wire code, or 0 with the voice
+        let mut reply = IpcBuffer::new();
+        let result = self.handle(channel, msg_info.label(), &buf, &mut MessageWriter::new(&mut reply));
+        if let Err(e) = &result {
+            debug_println!("Audio PD: {}", e);
+        }
+        let count = if result.is_ok() { reply.words().len() } else { 0 };
+        with_msg_regs_mut(|mrs| mrs[..count].copy_from_slice(&reply.words()[..count]));
+        Ok(MessageInfo::new(reply_code(&result) as u64, count))
+    }
+}
+
+/// Bring up the output the PD was built for
+///
+/// # Safety
+/// The .system description maps the output, clock manager and DMA
+/// registers and the DMA region at the protocol's addresses.
+#[cfg(feature = "pwm")]
+unsafe fn open_output(dma: DmaChannel, region: DmaRegion) -> PdResult<Output> {
+    let output = Output::new(AUDIO_PD_OUTPUT_BASE, AUDIO_PD_CM_BASE, dma, region, AUDIO_RATE_HZ)
+        .context("starting PWM output")?;
+    debug_println!("Audio PD: PWM headphone output, {} ticks per sample", output.range());
+    Ok(output)
+}
+
+/// Bring up the output the PD was built for
+///
+/// # Safety
+/// The .system description maps the output, clock manager and DMA
+/// registers and the DMA region at the protocol's addresses.
+#[cfg(feature = "i2s")]
+unsafe fn open_output(dma: DmaChannel, region: DmaRegion) -> PdResult<Output> {
+    let output = Output::new(AUDIO_PD_OUTPUT_BASE, AUDIO_PD_CM_BASE, dma, region, AUDIO_RATE_HZ, I2sConfig::default())
+        .context("starting I2S output")?;
+    debug_println!("Audio PD: I2S output, Pi as clock master");
+    Ok(output)
+}
+
+fn start() -> PdResult<AudioPd> {
+    // Safety: audio.system maps the DMA registers here and no other PD
+    // uses this channel
+    let dma = unsafe { DmaChannel::new(AUDIO_PD_DMA_BASE, AUDIO_DMA_CHANNEL, AUDIO_PRIORITY) }
+        .ok_or(PdError::new(ErrorDomain::Audio, ErrorKind::Unsupported).with_detail(AUDIO_DMA_CHANNEL as u16))?;
+    let region = DmaRegion { vaddr: AUDIO_DMA_VADDR, paddr: AUDIO_DMA_PADDR, size: AUDIO_DMA_SIZE };
+    // Safety: as above, for the output registers, the clock manager and
+    // the DMA region
+    let mut output = unsafe { open_output(dma, region)? };
+
+    // Safety: called once, here
+    let bank = unsafe { load_bank() };
+    let mixer = Mixer::new(bank, AUDIO_RATE_HZ).ok_or(PdError::new(ErrorDomain::Audio, ErrorKind::Unsupported))?;
+
+    // Safety: the stream region is mapped here, and this PD is its only
+    // reader; the client sees a full ring until the header is set
+    let stream = unsafe {
+        StreamHeader::init(AUDIO_STREAM_VADDR as *mut u8);
+        stream_ring(AUDIO_STREAM_VADDR as *mut u8)
+    };
+
+    let mut source = Source { mixer, stream };
+    output.start(&mut source);
+    debug_println!("Audio PD: Playing at {} Hz, DMA channel {} (IRQ {})", AUDIO_RATE_HZ, AUDIO_DMA_CHANNEL, output.irq());
+    Ok(AudioPd { output, source })
+}
+
+#[protection_domain]
+fn init() -> AudioPd {
+    debug_println!("");
+    debug_println!("========================================");
+    debug_println!("  Audio Protection Domain Starting");
+    debug_println!("========================================");
+    debug_println!("");
+    debug_println!("Audio PD: Output registers at 0x{:x}", AUDIO_PD_OUTPUT_BASE);
+    debug_println!("Audio PD: Stream at 0x{:x}", AUDIO_STREAM_VADDR);
+
+    match start() {
+        Ok(pd) => {
+            debug_println!("Audio PD: Ready, {} voices, {} clips", VOICES, clips::COUNT);
+            pd
+        }
+        // Without its output the PD has nothing to serve
+        Err(e) => panic!("Audio PD: {}", e),
+    }
+}
@@ -0,0 +1,432 @@
+//! Verified audio calls between the Audio PD and its clients.
+//!
+//! The Audio PD (`rpi4-audio-pd`) owns the sound output: the PWM
+//! headphone jack or an I2S DAC HAT, driven from `rpi4-audio`. This crate
+//! is the part both sides of it share: the calls a client (the TV demo,
+//! the photo frame) makes to start and stop sounds, the [`Mixer`] the
+//! Audio PD runs them on, which turns a fixed set of voices into one
+//! stream of signed 16-bit samples, and the [`stream`] ring a client fills
+//! with samples it renders itself. A driver then only has to move samples
+//! to the hardware ([`pwm_duty`] maps one to a mark-space duty).
+//!
+//! ```text
+//! ┌───────────┐  PLAY_TONE / PLAY_SAMPLE  ┌────────────────────────────┐
+//! │ Client PD │ ────────────────────────► │ Audio PD                   │
+//! │ (demo)    │ ◄──────── voice ───────── │ Mixer<N> ─┐                │
+//! │           │                           │           + ─► i16 ─► PWM  │
+//! │           │ ── audio_stream (i16) ──► │ stream ───┘                │
+//! └───────────┘                           └────────────────────────────┘
+//! ```
+//!
+//! ## Calls
+//!
+//! Calls are [`AudioRequest`]s, encoded with `verified_microkernel::rpc`.
+//! The reply label is zero or a `PdError` wire code; on success the reply
+//! carries one word (a `u8`):
+//!
+//! ```text
+//! Label  Operation    Fields                          Reply
+//! 1      PLAY_TONE    freq_hz, duration_ms, volume    voice
+//! 2      PLAY_SAMPLE  sample, volume, looped          voice
+//! 3      SET_VOLUME   voice, volume                   voice
+//! 4      STOP         voice                           voice
+//! 5      STOP_ALL     -                               voices stopped
+//! 6      PLAY_SINE    freq_hz, duration_ms, volume    voice
+//! ```
+//!
+//! Volumes are Q8 fixed point: [`Q8_ONE`] (256) plays a voice at full
+//! scale, 128 at half. `PLAY_TONE` plays a square wave, which carries
+//! further as a click; `PLAY_SINE` a sine from [`SINE_QUARTER`], for chimes
+//! and other softer tones. Samples are clips built into the Audio PD's
+//! image, named by their index in its sample bank, so a client never hands
+//! the Audio PD memory to read. They start out as 16-bit PCM WAV files,
+//! embedded or loaded from storage, which [`wav`] checks and resamples to
+//! the mixer's rate when the bank is built.
+//!
+//! ## Verification
+//!
+//! [`scale_q8`] is proven to keep a scaled sample within its volume's
+//! share of full scale, and [`saturate`] to give a valid sample for any
+//! sum, equal to the sum whenever it is one. Together they make the mix
+//! clip-free: the sum of `N` voices cannot overflow its `i32` accumulator
+//! ([`MAX_VOICES`] bounds `N`), and while the voices' volumes add up to at
+//! most [`Q8_ONE`] the sum is always a valid sample, so saturation never
+//! engages. [`tone_step`] only accepts tones below the Nyquist frequency,
+//! [`sine_index`] only indexes inside the quarter-wave table, and
+//! [`pwm_duty`] never exceeds the PWM range.
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::new_without_default)]
+// Verus proves plain comparisons; `RangeInclusive::contains` it does not know
+#![allow(clippy::manual_range_contains)]
+
+use core::fmt;
+use verus_builtin_macros::verus;
+use verified_microkernel::rpc_requests;
+
+pub mod mixer;
+pub mod stream;
+pub mod wav;
+
+pub use mixer::{Mixer, Voice, Waveform};
+pub use stream::{StreamHeader, StreamRing};
+pub use wav::{Wav, WavError};
+
+verus! {
+
+/// Client → Audio PD protected procedure channel
+pub const AUDIO_CHANNEL_ID: usize = 4;
+
+/// The output's DMA channel interrupt, in the Audio PD
+pub const AUDIO_IRQ_CHANNEL_ID: usize = 5;
+
+// ============================================================================
+// MEMORY REGIONS
+// ============================================================================
+
+/// Sample stream: written by the client, read by the Audio PD
+pub const AUDIO_STREAM_VADDR: usize = 0x5_0F00_0000;
+
+/// Output registers, mapped into the Audio PD only: the PWM page (PWM1 at
+/// +0x800) or the PCM page, whichever the build drives
+pub const AUDIO_PD_OUTPUT_BASE: usize = 0x5_1000_0000;
+
+/// Clock manager registers (the PWM or PCM clock), Audio PD only
+pub const AUDIO_PD_CM_BASE: usize = 0x5_1100_0000;
+
+/// DMA controller registers, Audio PD only
+pub const AUDIO_PD_DMA_BASE: usize = 0x5_1200_0000;
+
+/// Control blocks and sample halves the DMA channel plays from
+pub const AUDIO_DMA_VADDR: usize = 0x5_1300_0000;
+/// Its fixed physical address, below 1 GiB where the DMA engine reaches
+pub const AUDIO_DMA_PADDR: usize = 0x3E86_1000;
+pub const AUDIO_DMA_SIZE: usize = 0x3000;
+
+/// DMA channel the Audio PD claims (IRQ 118); the firmware leaves 0, 2
+/// and 4-6 to the ARM, and the SPI display uses 2, 4 and 5
+pub const AUDIO_DMA_CHANNEL: u8 = 6;
+
+// ============================================================================
+// SAMPLE FORMAT
+// ============================================================================
+
+/// Q8 volume that plays a voice at full scale
+pub const Q8_ONE: u16 = 256;
+
+/// Voices a mixer may have: enough that `N` full-scale voices still sum
+/// within an `i32`, with plenty of room
+pub const MAX_VOICES: usize = 16;
+
+/// Highest output rate a mixer runs at
+pub const MAX_RATE_HZ: u32 = 96_000;
+
+/// Longest tone a call can ask for
+pub const MAX_TONE_MS: u16 = 10_000;
+
+/// Rate the Audio PD mixes and plays at; clients rendering into the
+/// stream render at this rate too
+pub const AUDIO_RATE_HZ: u32 = 48_000;
+
+/// Entries of [`SINE_QUARTER`]: a quarter cycle and its end point
+pub const SINE_QUARTER_LEN: usize = 65;
+
+/// A scaled sample stays within `volume`'s share of full scale
+pub open spec fn within_volume(r: int, volume: int) -> bool {
+    -128 * volume <= r && r * 256 <= 32767 * volume
+}
+
+/// `sample` at Q8 `volume`, rounded toward zero
+pub fn scale_q8(sample: i16, volume: u16) -> (r: i32)
+    requires
+        volume <= Q8_ONE,
+    ensures
+        within_volume(r as int, volume as int),
+        -32768 <= r <= 32767,
+{
+    proof {
+        assert(-32768 * (volume as int) <= (sample as int) * (volume as int)) by (nonlinear_arith)
+            requires -32768 <= sample, 0 <= volume;
+        assert((sample as int) * (volume as int) <= 32767 * (volume as int)) by (nonlinear_arith)
+            requires sample <= 32767, 0 <= volume;
+    }
+    (sample as i32 * volume as i32) / Q8_ONE as i32
+}
+
+/// `acc` limited to the sample range
+pub fn saturate(acc: i32) -> (r: i16)
+    ensures
+        -32768 <= acc <= 32767 ==> r as int == acc as int,
+        acc < -32768 ==> r == i16::MIN,
+        acc > 32767 ==> r == i16::MAX,
+{
+    if acc < i16::MIN as i32 {
+        i16::MIN
+    } else if acc > i16::MAX as i32 {
+        i16::MAX
+    } else {
+        acc as i16
+    }
+}
+
+/// Phase step per output sample for a `freq_hz` tone at `rate_hz`, as a
+/// fraction of a cycle in 32-bit fixed point; `None` at or above the
+/// Nyquist frequency (half the rate), where a tone would alias
+pub fn tone_step(freq_hz: u16, rate_hz: u32) -> (r: Option<u32>)
+    ensures
+        r.is_some() ==> 0 < freq_hz && (freq_hz as int) * 2 < rate_hz as int && r.unwrap() < 0x8000_0000,
+{
+    if freq_hz == 0 || freq_hz as u64 * 2 >= rate_hz as u64 {
+        return None;
+    }
+    let step = ((freq_hz as u64) << 32) / rate_hz as u64;
+    proof {
+        assert(((freq_hz as int) * 0x1_0000_0000) / (rate_hz as int) < 0x8000_0000) by (nonlinear_arith)
+            requires (freq_hz as int) * 2 < rate_hz as int, 0 < rate_hz;
+    }
+    Some(step as u32)
+}
+
+/// Output samples in `ms` milliseconds at `rate_hz`
+pub fn samples_for_ms(ms: u16, rate_hz: u32) -> (n: u32)
+    requires
+        rate_hz <= MAX_RATE_HZ,
+    ensures
+        n as int == (ms as int) * (rate_hz as int) / 1000,
+{
+    proof {
+        assert((ms as int) * (rate_hz as int) <= 65535 * 96_000) by (nonlinear_arith)
+            requires ms <= 65535, rate_hz <= 96_000;
+    }
+    ((ms as u64 * rate_hz as u64) / 1000) as u32
+}
+
+/// Entry of [`SINE_QUARTER`] for a tone at `phase` (a fraction of a cycle
+/// in 32-bit fixed point), and whether the sample is its negation. The
+/// top two bits pick the quadrant, the next six the step within it; the
+/// second and fourth quadrants run the table backwards.
+pub fn sine_index(phase: u32) -> (r: (usize, bool))
+    ensures
+        r.0 < SINE_QUARTER_LEN,
+        r.1 == (phase >= 0x8000_0000),
+{
+    let quadrant = phase >> 30;
+    let step = (phase >> 24) & 0x3F;
+    assert(quadrant < 4 && step < 64) by (bit_vector)
+        requires quadrant == phase >> 30, step == (phase >> 24) & 0x3F;
+    assert((quadrant >= 2) == (phase >= 0x8000_0000)) by (bit_vector)
+        requires quadrant == phase >> 30;
+    let index = if quadrant & 1 == 0 { step } else { 64 - step };
+    (index as usize, quadrant >= 2)
+}
+
+/// Mark-space duty for `sample` on a PWM channel of `range` ticks:
+/// silence is half of `range`, full scale negative 0
+pub fn pwm_duty(sample: i16, range: u32) -> (d: u32)
+    ensures
+        d <= range,
+{
+    let level = (sample as i32 + 32768) as u64;
+    proof {
+        assert(level * (range as int) / 65536 <= range as int) by (nonlinear_arith)
+            requires level <= 65535;
+    }
+    ((level * range as u64) / 65536) as u32
+}
+
+} // verus!
+
+/// Clips in the Audio PD's sample bank, by the index `PLAY_SAMPLE` names
+pub mod clips {
+    /// Two-note bell, 0.4 s: the photo frame's slide change
+    pub const CHIME: u8 = 0;
+    /// Clips in the bank
+    pub const COUNT: usize = 1;
+}
+
+/// A quarter cycle of a full-scale sine, `round(32767 * sin(i * π / 128))`;
+/// [`sine_index`] unfolds it into the whole cycle
+pub const SINE_QUARTER: [i16; SINE_QUARTER_LEN] = [
+    0, 804, 1608, 2410, 3212, 4011, 4808, 5602,
+    6393, 7179, 7962, 8739, 9512, 10278, 11039, 11793,
+    12539, 13279, 14010, 14732, 15446, 16151, 16846, 17530,
+    18204, 18868, 19519, 20159, 20787, 21403, 22005, 22594,
+    23170, 23731, 24279, 24811, 25329, 25832, 26319, 26790,
+    27245, 27683, 28105, 28510, 28898, 29268, 29621, 29956,
+    30273, 30571, 30852, 31113, 31356, 31580, 31785, 31971,
+    32137, 32285, 32412, 32521, 32609, 32678, 32728, 32757,
+    32767,
+];
+
+/// Full-scale sine sample at `phase`
+pub fn sine_sample(phase: u32) -> i16 {
+    let (index, negative) = sine_index(phase);
+    let level = SINE_QUARTER[index];
+    if negative {
+        -level
+    } else {
+        level
+    }
+}
+
+rpc_requests! {
+    /// Calls an Audio PD serves
+    pub enum AudioRequest {
+        /// Play a square-wave tone on a free voice
+        PlayTone { freq_hz: u16, duration_ms: u16, volume: u16 } = 1,
+        /// Play clip `sample` of the sample bank on a free voice, once or
+        /// until stopped
+        PlaySample { sample: u8, volume: u16, looped: bool } = 2,
+        /// Change a playing voice's volume
+        SetVolume { voice: u8, volume: u16 } = 3,
+        /// Silence one voice
+        Stop { voice: u8 } = 4,
+        /// Silence every voice
+        StopAll = 5,
+        /// Play a sine tone on a free voice
+        PlaySine { freq_hz: u16, duration_ms: u16, volume: u16 } = 6,
+    }
+}
+
+/// Why an audio call was refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioError {
+    /// Volume above [`Q8_ONE`]
+    BadVolume(u16),
+    /// Tone frequency is zero or at or above the Nyquist frequency
+    BadFrequency(u16),
+    /// Tone longer than [`MAX_TONE_MS`]
+    BadDuration(u16),
+    /// The sample bank has no clip with this index
+    NoSuchSample(u8),
+    /// The mixer has no voice with this index
+    NoSuchVoice(u8),
+    /// Every voice is playing
+    NoFreeVoice,
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            AudioError::BadVolume(v) => write!(f, "volume {} above {}", v, Q8_ONE),
+            AudioError::BadFrequency(hz) => write!(f, "cannot play a {} Hz tone", hz),
+            AudioError::BadDuration(ms) => write!(f, "{} ms tone longer than {} ms", ms, MAX_TONE_MS),
+            AudioError::NoSuchSample(s) => write!(f, "no sample {}", s),
+            AudioError::NoSuchVoice(v) => write!(f, "no voice {}", v),
+            AudioError::NoFreeVoice => write!(f, "every voice is playing"),
+        }
+    }
+}
+
+impl From<AudioError> for rpi4_pd_error::PdError {
+    fn from(e: AudioError) -> Self {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let (kind, detail) = match e {
+            AudioError::BadVolume(v) => (ErrorKind::InvalidArgument, v),
+            AudioError::BadFrequency(hz) => (ErrorKind::InvalidArgument, hz),
+            AudioError::BadDuration(ms) => (ErrorKind::InvalidArgument, ms),
+            AudioError::NoSuchSample(s) => (ErrorKind::NotFound, s as u16),
+            AudioError::NoSuchVoice(v) => (ErrorKind::NotFound, v as u16),
+            AudioError::NoFreeVoice => (ErrorKind::Capacity, 0),
+        };
+        PdError::new(ErrorDomain::Audio, kind).with_detail(detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use verified_microkernel::{rpc, IpcBuffer};
+
+    #[test]
+    fn scaling_stays_within_the_volume() {
+        assert_eq!(scale_q8(i16::MAX, Q8_ONE), 32767);
+        assert_eq!(scale_q8(i16::MIN, Q8_ONE), -32768);
+        assert_eq!(scale_q8(i16::MIN, 128), -16384);
+        assert_eq!(scale_q8(-3, 128), -1);
+        assert_eq!(scale_q8(12345, 0), 0);
+        // Every sample at every volume, against the proven bound
+        for volume in [1u16, 3, 64, 255, 256] {
+            for sample in (i16::MIN..=i16::MAX).step_by(97).chain([i16::MAX]) {
+                let r = scale_q8(sample, volume) as i64;
+                assert!(-128 * volume as i64 <= r && r * 256 <= 32767 * volume as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn saturation() {
+        assert_eq!(saturate(0), 0);
+        assert_eq!(saturate(-32768), i16::MIN);
+        assert_eq!(saturate(40_000), i16::MAX);
+        assert_eq!(saturate(i32::MIN), i16::MIN);
+    }
+
+    #[test]
+    fn tones_stay_below_nyquist() {
+        assert_eq!(tone_step(440, 48_000), Some(39_370_533));
+        assert_eq!(tone_step(23_999, 48_000).map(|s| s < 0x8000_0000), Some(true));
+        assert_eq!(tone_step(24_000, 48_000), None);
+        assert_eq!(tone_step(0, 48_000), None);
+        assert_eq!(tone_step(1, 0), None);
+        assert_eq!(samples_for_ms(250, 48_000), 12_000);
+        assert_eq!(samples_for_ms(u16::MAX, MAX_RATE_HZ), 6_291_360);
+    }
+
+    #[test]
+    fn pwm_duty_spans_the_range() {
+        assert_eq!(pwm_duty(i16::MIN, 256), 0);
+        assert_eq!(pwm_duty(0, 256), 128);
+        assert_eq!(pwm_duty(i16::MAX, 256), 255);
+        assert_eq!(pwm_duty(i16::MAX, u32::MAX), u32::MAX - 65536);
+    }
+
+    #[test]
+    fn sine_unfolds_the_quarter_table() {
+        assert_eq!(sine_index(0), (0, false));
+        assert_eq!(sine_index(0x4000_0000), (64, false));
+        assert_eq!(sine_index(0x8000_0000), (0, true));
+        assert_eq!(sine_index(0xC000_0000), (64, true));
+        assert_eq!(sine_index(u32::MAX), (1, true));
+        assert_eq!(sine_sample(0x4000_0000), i16::MAX);
+        assert_eq!(sine_sample(0xC000_0000), -i16::MAX);
+        // Odd symmetry: the second half cycle mirrors the first
+        for phase in (0..0x8000_0000u32).step_by(0x0100_0000) {
+            assert_eq!(sine_sample(phase + 0x8000_0000), -sine_sample(phase));
+        }
+        // Against the real thing, to within a table step
+        for k in 0..256u32 {
+            let exact = 32767.0 * (k as f64 * core::f64::consts::PI / 128.0).sin();
+            assert!((sine_sample(k << 24) as f64 - exact).abs() < 1.0, "step {}", k);
+        }
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let mut buf = IpcBuffer::new();
+        for request in [
+            AudioRequest::PlayTone { freq_hz: 440, duration_ms: 200, volume: 128 },
+            AudioRequest::PlaySample { sample: 2, volume: Q8_ONE, looped: true },
+            AudioRequest::SetVolume { voice: 1, volume: 0 },
+            AudioRequest::Stop { voice: 3 },
+            AudioRequest::StopAll,
+            AudioRequest::PlaySine { freq_hz: 880, duration_ms: 150, volume: 96 },
+        ] {
+            let (label, _) = rpc::encode_request(&request, &mut buf).unwrap();
+            assert_eq!(rpc::decode_request(label, &buf), Ok(request));
+        }
+        buf.load(&[2, 256, 2]);
+        assert!(rpc::decode_request::<AudioRequest>(2, &buf).is_err());
+    }
+
+    #[test]
+    fn errors_carry_their_domain() {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let e = PdError::from(AudioError::NoFreeVoice);
+        assert_eq!((e.domain(), e.kind()), (ErrorDomain::Audio, ErrorKind::Capacity));
+        assert_eq!(PdError::from(AudioError::NoSuchVoice(9)).code().detail, 9);
+    }
+}
@@ -0,0 +1,317 @@
+//! Fixed-voice audio mixer.
+//!
+//! A [`Mixer<N>`](Mixer) has `N` voices, each idle, playing a square or
+//! sine tone for a set number of samples, or playing a clip from the
+//! sample bank the Audio PD was built with. [`Mixer::mix`] fills an output buffer
+//! with the voices summed at their Q8 volumes: every voice is scaled with
+//! [`scale_q8`], summed in an `i32` and limited with [`saturate`], so no
+//! step can overflow and a mix whose volumes add up to at most [`Q8_ONE`]
+//! comes out exactly as summed.
+
+use crate::{
+    samples_for_ms, saturate, scale_q8, sine_sample, tone_step, AudioError, AudioRequest, MAX_RATE_HZ,
+    MAX_TONE_MS, MAX_VOICES, Q8_ONE,
+};
+
+/// Peak level of a tone; negative half-cycles are its negation, so a
+/// full-volume tone is symmetric
+pub const TONE_LEVEL: i16 = i16::MAX;
+
+/// Shape of a tone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    /// High for the first half of each cycle, low for the second
+    Square,
+    /// From the quarter-wave table ([`crate::SINE_QUARTER`])
+    Sine,
+}
+
+/// What one voice is playing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Voice {
+    /// Silent and free for the next play call
+    Idle,
+    /// A tone of shape `wave`, `phase` through its cycle
+    Tone { wave: Waveform, phase: u32, step: u32, remaining: u32, volume: u16 },
+    /// Clip `sample` of the bank, from sample `pos`
+    Sample { sample: u8, pos: usize, looped: bool, volume: u16 },
+}
+
+impl Voice {
+    /// Volume, or 0 when idle
+    pub fn volume(&self) -> u16 {
+        match *self {
+            Voice::Idle => 0,
+            Voice::Tone { volume, .. } | Voice::Sample { volume, .. } => volume,
+        }
+    }
+
+    /// The voice's next sample at full scale; a voice that runs out goes
+    /// idle and plays silence
+    fn next(&mut self, bank: &[&[i16]]) -> i16 {
+        match self {
+            Voice::Idle => 0,
+            Voice::Tone { wave, phase, step, remaining, .. } => {
+                if *remaining == 0 {
+                    *self = Voice::Idle;
+                    return 0;
+                }
+                *remaining -= 1;
+                let at = *phase;
+                *phase = phase.wrapping_add(*step);
+                match wave {
+                    Waveform::Square if at < 0x8000_0000 => TONE_LEVEL,
+                    Waveform::Square => -TONE_LEVEL,
+                    Waveform::Sine => sine_sample(at),
+                }
+            }
+            Voice::Sample { sample, pos, looped, .. } => {
+                let clip = bank.get(*sample as usize).copied().unwrap_or(&[]);
+                if *pos >= clip.len() && *looped {
+                    *pos = 0;
+                }
+                match clip.get(*pos) {
+                    Some(&s) => {
+                        *pos += 1;
+                        s
+                    }
+                    None => {
+                        *self = Voice::Idle;
+                        0
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `N` voices mixed into one stream at `rate_hz`
+pub struct Mixer<'a, const N: usize> {
+    voices: [Voice; N],
+    /// Clips a `PLAY_SAMPLE` call names by index
+    bank: &'a [&'a [i16]],
+    rate_hz: u32,
+}
+
+impl<'a, const N: usize> Mixer<'a, N> {
+    /// A mixer with every voice idle, playing clips from `bank` at
+    /// `rate_hz`; `None` for a rate of 0 or above [`MAX_RATE_HZ`]
+    pub fn new(bank: &'a [&'a [i16]], rate_hz: u32) -> Option<Self> {
+        const { assert!(N > 0 && N <= MAX_VOICES, "a mixer has 1 to MAX_VOICES voices") };
+        if rate_hz == 0 || rate_hz > MAX_RATE_HZ {
+            return None;
+        }
+        Some(Self { voices: [Voice::Idle; N], bank, rate_hz })
+    }
+
+    pub fn rate_hz(&self) -> u32 {
+        self.rate_hz
+    }
+
+    /// What voice `voice` is playing
+    pub fn voice(&self, voice: u8) -> Option<&Voice> {
+        self.voices.get(voice as usize)
+    }
+
+    /// Carry out a call; the reply is the voice it played on or changed,
+    /// or for `STOP_ALL` how many voices it silenced
+    pub fn handle(&mut self, request: AudioRequest) -> Result<u8, AudioError> {
+        match request {
+            AudioRequest::PlayTone { freq_hz, duration_ms, volume } => self.play_tone(freq_hz, duration_ms, volume),
+            AudioRequest::PlaySample { sample, volume, looped } => self.play_sample(sample, volume, looped),
+            AudioRequest::SetVolume { voice, volume } => self.set_volume(voice, volume),
+            AudioRequest::Stop { voice } => self.stop(voice),
+            AudioRequest::StopAll => Ok(self.stop_all()),
+            AudioRequest::PlaySine { freq_hz, duration_ms, volume } => self.play_sine(freq_hz, duration_ms, volume),
+        }
+    }
+
+    /// Play a `freq_hz` square wave for `duration_ms` on a free voice
+    pub fn play_tone(&mut self, freq_hz: u16, duration_ms: u16, volume: u16) -> Result<u8, AudioError> {
+        self.play_wave(Waveform::Square, freq_hz, duration_ms, volume)
+    }
+
+    /// Play a `freq_hz` sine for `duration_ms` on a free voice
+    pub fn play_sine(&mut self, freq_hz: u16, duration_ms: u16, volume: u16) -> Result<u8, AudioError> {
+        self.play_wave(Waveform::Sine, freq_hz, duration_ms, volume)
+    }
+
+    fn play_wave(&mut self, wave: Waveform, freq_hz: u16, duration_ms: u16, volume: u16) -> Result<u8, AudioError> {
+        check_volume(volume)?;
+        let step = tone_step(freq_hz, self.rate_hz).ok_or(AudioError::BadFrequency(freq_hz))?;
+        if duration_ms == 0 || duration_ms > MAX_TONE_MS {
+            return Err(AudioError::BadDuration(duration_ms));
+        }
+        let remaining = samples_for_ms(duration_ms, self.rate_hz);
+        self.start(Voice::Tone { wave, phase: 0, step, remaining, volume })
+    }
+
+    /// Play clip `sample` of the bank on a free voice, once or until
+    /// stopped
+    pub fn play_sample(&mut self, sample: u8, volume: u16, looped: bool) -> Result<u8, AudioError> {
+        check_volume(volume)?;
+        if sample as usize >= self.bank.len() {
+            return Err(AudioError::NoSuchSample(sample));
+        }
+        self.start(Voice::Sample { sample, pos: 0, looped, volume })
+    }
+
+    /// Change a voice's volume; an idle voice stays idle
+    pub fn set_volume(&mut self, voice: u8, new: u16) -> Result<u8, AudioError> {
+        check_volume(new)?;
+        match self.voices.get_mut(voice as usize).ok_or(AudioError::NoSuchVoice(voice))? {
+            Voice::Idle => {}
+            Voice::Tone { volume, .. } | Voice::Sample { volume, .. } => *volume = new,
+        }
+        Ok(voice)
+    }
+
+    /// Silence one voice
+    pub fn stop(&mut self, voice: u8) -> Result<u8, AudioError> {
+        *self.voices.get_mut(voice as usize).ok_or(AudioError::NoSuchVoice(voice))? = Voice::Idle;
+        Ok(voice)
+    }
+
+    /// Silence every voice; returns how many were playing
+    pub fn stop_all(&mut self) -> u8 {
+        let mut stopped = 0;
+        for voice in self.voices.iter_mut() {
+            if *voice != Voice::Idle {
+                stopped += 1;
+            }
+            *voice = Voice::Idle;
+        }
+        stopped
+    }
+
+    /// Fill `out` with the next samples of the mix
+    pub fn mix(&mut self, out: &mut [i16]) {
+        for slot in out.iter_mut() {
+            // At most MAX_VOICES terms of magnitude at most 32768
+            let mut acc: i32 = 0;
+            for voice in self.voices.iter_mut() {
+                let volume = voice.volume();
+                acc += scale_q8(voice.next(self.bank), volume);
+            }
+            *slot = saturate(acc);
+        }
+    }
+
+    fn start(&mut self, voice: Voice) -> Result<u8, AudioError> {
+        let (i, slot) = self
+            .voices
+            .iter_mut()
+            .enumerate()
+            .find(|(_, v)| **v == Voice::Idle)
+            .ok_or(AudioError::NoFreeVoice)?;
+        *slot = voice;
+        Ok(i as u8)
+    }
+}
+
+fn check_volume(volume: u16) -> Result<(), AudioError> {
+    if volume > Q8_ONE {
+        return Err(AudioError::BadVolume(volume));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAMP: [i16; 4] = [i16::MIN, -1, 1, i16::MAX];
+
+    #[test]
+    fn tones_are_square_and_end() {
+        // 1 kHz at 8 kHz: four samples high, four low
+        let mut mixer = Mixer::<2>::new(&[], 8_000).unwrap();
+        assert_eq!(mixer.play_tone(1_000, 1, Q8_ONE), Ok(0));
+        let mut out = [0i16; 10];
+        mixer.mix(&mut out);
+        let h = TONE_LEVEL;
+        assert_eq!(out, [h, h, h, h, -h, -h, -h, -h, 0, 0]);
+        assert_eq!(mixer.voice(0), Some(&Voice::Idle));
+
+        assert_eq!(mixer.play_tone(4_000, 10, Q8_ONE), Err(AudioError::BadFrequency(4_000)));
+        assert_eq!(mixer.play_tone(440, 0, Q8_ONE), Err(AudioError::BadDuration(0)));
+        assert_eq!(mixer.play_tone(440, MAX_TONE_MS + 1, Q8_ONE), Err(AudioError::BadDuration(MAX_TONE_MS + 1)));
+        assert_eq!(mixer.play_tone(440, 10, Q8_ONE + 1), Err(AudioError::BadVolume(Q8_ONE + 1)));
+        assert!(Mixer::<2>::new(&[], 0).is_none());
+        assert!(Mixer::<2>::new(&[], MAX_RATE_HZ + 1).is_none());
+    }
+
+    #[test]
+    fn sines_follow_the_table() {
+        // 1 kHz at 8 kHz: an eighth of a cycle per sample
+        let mut mixer = Mixer::<1>::new(&[], 8_000).unwrap();
+        assert_eq!(mixer.handle(AudioRequest::PlaySine { freq_hz: 1_000, duration_ms: 1, volume: Q8_ONE }), Ok(0));
+        let mut out = [0i16; 9];
+        mixer.mix(&mut out);
+        let d = crate::SINE_QUARTER[32];
+        assert_eq!(out, [0, d, i16::MAX, d, 0, -d, -i16::MAX, -d, 0]);
+        assert_eq!(mixer.voice(0), Some(&Voice::Idle));
+        assert_eq!(mixer.play_sine(0, 10, Q8_ONE), Err(AudioError::BadFrequency(0)));
+    }
+
+    #[test]
+    fn samples_play_once_or_loop() {
+        let bank: [&[i16]; 2] = [&RAMP, &[]];
+        let mut mixer = Mixer::<3>::new(&bank, 48_000).unwrap();
+        assert_eq!(mixer.play_sample(0, Q8_ONE, false), Ok(0));
+        assert_eq!(mixer.play_sample(0, Q8_ONE, true), Ok(1));
+        assert_eq!(mixer.stop(1), Ok(1));
+        assert_eq!(mixer.play_sample(0, Q8_ONE, true), Ok(1));
+        assert_eq!(mixer.set_volume(1, 0), Ok(1));
+        assert_eq!(mixer.play_sample(2, Q8_ONE, false), Err(AudioError::NoSuchSample(2)));
+        let mut out = [0i16; 6];
+        mixer.mix(&mut out);
+        assert_eq!(out, [i16::MIN, -1, 1, i16::MAX, 0, 0]);
+        assert_eq!(mixer.voice(0), Some(&Voice::Idle));
+        assert!(matches!(mixer.voice(1), Some(Voice::Sample { pos: 2, .. })));
+
+        // An empty clip, even looped, just goes idle
+        assert_eq!(mixer.play_sample(1, Q8_ONE, true), Ok(0));
+        mixer.mix(&mut out[..1]);
+        assert_eq!(mixer.voice(0), Some(&Voice::Idle));
+    }
+
+    #[test]
+    fn voices_run_out() {
+        let mut mixer = Mixer::<2>::new(&[], 48_000).unwrap();
+        let tone = AudioRequest::PlayTone { freq_hz: 440, duration_ms: 100, volume: 64 };
+        assert_eq!(mixer.handle(tone), Ok(0));
+        assert_eq!(mixer.handle(tone), Ok(1));
+        assert_eq!(mixer.handle(tone), Err(AudioError::NoFreeVoice));
+        assert_eq!(mixer.handle(AudioRequest::Stop { voice: 2 }), Err(AudioError::NoSuchVoice(2)));
+        assert_eq!(mixer.handle(AudioRequest::StopAll), Ok(2));
+        assert_eq!(mixer.handle(AudioRequest::StopAll), Ok(0));
+    }
+
+    #[test]
+    fn mixing_within_full_volume_never_clips() {
+        // Four full-scale clips whose volumes add up to Q8_ONE
+        let high = [i16::MAX; 8];
+        let low = [i16::MIN; 8];
+        let bank: [&[i16]; 2] = [&high, &low];
+        for clip in 0..2 {
+            let mut mixer = Mixer::<4>::new(&bank, 48_000).unwrap();
+            for volume in [100, 100, 50, 6] {
+                mixer.play_sample(clip, volume, false).unwrap();
+            }
+            let mut out = [0i16; 8];
+            mixer.mix(&mut out);
+            let exact: i32 = [100, 100, 50, 6].iter().map(|&v| scale_q8(bank[clip as usize][0], v)).sum();
+            assert!(out.iter().all(|&s| s as i32 == exact), "{:?} vs {}", out, exact);
+        }
+
+        // Overdriven: saturates instead of wrapping
+        let mut mixer = Mixer::<4>::new(&bank, 48_000).unwrap();
+        for _ in 0..4 {
+            mixer.play_sample(0, Q8_ONE, false).unwrap();
+        }
+        let mut out = [0i16; 2];
+        mixer.mix(&mut out);
+        assert_eq!(out, [i16::MAX; 2]);
+    }
+}
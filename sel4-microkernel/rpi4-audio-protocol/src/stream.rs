@@ -0,0 +1,107 @@
+//! Sample stream from a client to the Audio PD.
+//!
+//! Calls cover sounds the Audio PD can make itself; a client that renders
+//! its own (a jingle, speech, a decoded file) writes the samples into the
+//! `audio_stream` region instead. It is a `verified_microkernel::ring` of
+//! signed 16-bit samples at [`AUDIO_RATE_HZ`](crate::AUDIO_RATE_HZ): the
+//! client owns `write_idx`, the Audio PD owns `read_idx`, and the Audio PD
+//! adds whatever is waiting to the mix each time it refills the output,
+//! so there is no notification either way. A client that falls behind is
+//! heard as silence, not as a stall.
+//!
+//! ```text
+//! ┌──────────────────┬──────────────────────────────────────────────┐
+//! │ StreamHeader     │ samples: [i16; STREAM_CAPACITY]              │
+//! │ (16 bytes)       │                                              │
+//! └──────────────────┴──────────────────────────────────────────────┘
+//! ```
+//!
+//! The Audio PD initializes the header. Until it has, the capacity word is
+//! 0, which the ring reads as full, so a client that starts first writes
+//! nothing rather than into an uninitialized ring.
+
+use verified_microkernel::ring::{RingHeader, VerifiedRing};
+
+use crate::saturate;
+
+/// Samples the stream holds: 170 ms at 48 kHz
+pub const STREAM_CAPACITY: usize = 8192;
+
+/// Offset of the sample array in the region
+pub const SAMPLES_OFFSET: usize = 16;
+
+/// The stream as a typed [`VerifiedRing`]
+pub type StreamRing = VerifiedRing<i16, STREAM_CAPACITY>;
+
+/// Bytes of the stream region the header and samples take
+pub const STREAM_REGION_SIZE: usize = StreamRing::region_size(SAMPLES_OFFSET);
+
+/// Ring header at the start of the stream region
+#[repr(C, align(16))]
+pub struct StreamHeader {
+    pub ring: RingHeader,
+}
+
+impl StreamHeader {
+    /// Reset the stream to empty (Audio PD only)
+    ///
+    /// # Safety
+    /// `base` must be the start of the mapped stream region.
+    pub unsafe fn init(base: *mut u8) {
+        (*(base as *mut Self)).ring = RingHeader::new(STREAM_CAPACITY as u32);
+    }
+}
+
+/// The stream whose region starts at `base`
+///
+/// # Safety
+/// `base` must be the start of the mapped stream region, at least
+/// [`STREAM_REGION_SIZE`] bytes, for as long as the ring is used.
+pub unsafe fn stream_ring(base: *mut u8) -> StreamRing {
+    StreamRing::new(&(*(base as *const StreamHeader)).ring, base.add(SAMPLES_OFFSET) as *mut i16)
+}
+
+/// Add `stream` to the mix in `out`, sample by sample, limited with
+/// [`saturate`]; samples past the shorter of the two are left alone
+pub fn add_stream(out: &mut [i16], stream: &[i16]) {
+    for (slot, &s) in out.iter_mut().zip(stream) {
+        *slot = saturate(*slot as i32 + s as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C, align(16))]
+    struct Region([u8; STREAM_REGION_SIZE]);
+
+    #[test]
+    fn client_to_audio_pd() {
+        let mut region = Region([0; STREAM_REGION_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let ring = unsafe { stream_ring(base) };
+
+        // Before the Audio PD initializes it, the stream takes nothing
+        assert_eq!(ring.push_batch(&[1, 2, 3]), 0);
+
+        unsafe { StreamHeader::init(base) };
+        assert_eq!(ring.free_slots() as usize, STREAM_CAPACITY - 1);
+        assert_eq!(ring.push_batch(&[100, -100, i16::MAX]), 3);
+
+        let mut mix = [1000i16; 4];
+        let mut popped = [0i16; 4];
+        let n = ring.pop_batch(&mut popped);
+        assert_eq!(n, 3);
+        add_stream(&mut mix, &popped[..n]);
+        assert_eq!(mix, [1100, 900, i16::MAX, 1000]);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(core::mem::size_of::<StreamHeader>(), SAMPLES_OFFSET);
+        // The audio_stream region in the .system files
+        const { assert!(STREAM_REGION_SIZE <= 0x5000) };
+    }
+}
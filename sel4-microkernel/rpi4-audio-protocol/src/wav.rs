@@ -0,0 +1,424 @@
+//! WAV clips for the sample bank.
+//!
+//! The bank a [`Mixer`](crate::Mixer) plays from is signed 16-bit mono at
+//! the mixer's rate. Sounds are easier to come by as WAV files: menu clicks
+//! built into the Audio PD's image with `include_bytes!`, or game sounds
+//! read from the card through the Storage PD. [`Wav::parse`] checks such a
+//! file and [`Wav::resample_into`] converts it into a bank clip at start-up,
+//! so the mixer's inner loop never sees a file format or a second rate.
+//!
+//! Only 16-bit PCM is accepted, mono or stereo (mixed down to mono), at
+//! [`MIN_CLIP_RATE_HZ`] to [`MAX_RATE_HZ`]: the 22.05 kHz of most sound
+//! effect packs as well as 44.1 and 48 kHz. Resampling is linear
+//! interpolation, which is plenty for clicks and effects.
+//!
+//! ## Verification
+//!
+//! [`chunk_body`] is proven to return a body only when the chunk header and
+//! body both lie within the file, and [`next_chunk`] to move strictly
+//! forward, so a walk over a hostile file neither reads out of bounds nor
+//! loops. [`resample_step`] is proven nonzero, [`resampled_len`] to keep
+//! the last output position on a source frame, and [`lerp`] to stay
+//! between the two samples it blends, so resampling cannot overshoot.
+
+use core::fmt;
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::MAX_RATE_HZ;
+
+verus! {
+
+/// `RIFF`, file size, `WAVE`
+pub const RIFF_HEADER_SIZE: usize = 12;
+/// Chunk ID and body size
+pub const CHUNK_HEADER_SIZE: usize = 8;
+/// Smallest `fmt ` body: format, channels, rate, byte rate, block align,
+/// bits per sample
+pub const FMT_BODY_SIZE: usize = 16;
+/// `fmt ` format tag of integer PCM
+pub const WAVE_FORMAT_PCM: u16 = 1;
+/// Lowest clip rate accepted
+pub const MIN_CLIP_RATE_HZ: u32 = 4_000;
+/// Fraction bits of a resampling position
+pub const RESAMPLE_FRAC_BITS: u64 = 16;
+/// One source frame in resampling position units
+pub const RESAMPLE_ONE: u64 = 65_536;
+
+/// Body of the chunk whose header starts at `offset` in a `buf_len`-byte
+/// file, as `(start, len)`, or `None` if the header or body runs past it
+pub fn chunk_body(buf_len: usize, offset: usize, size: u32) -> (r: Option<(usize, usize)>)
+    ensures
+        r.is_some() <==> offset as int + CHUNK_HEADER_SIZE + size as int <= buf_len as int,
+        r.is_some() ==> r.unwrap().0 == offset + CHUNK_HEADER_SIZE
+            && r.unwrap().1 == size as usize
+            && r.unwrap().0 + r.unwrap().1 <= buf_len,
+{
+    if offset > buf_len || buf_len - offset < CHUNK_HEADER_SIZE {
+        return None;
+    }
+    let start = offset + CHUNK_HEADER_SIZE;
+    let size = size as usize;
+    if size > buf_len - start {
+        None
+    } else {
+        Some((start, size))
+    }
+}
+
+/// Header offset of the chunk after a body at `start` of `len` bytes;
+/// bodies are padded to an even length
+pub fn next_chunk(start: usize, len: usize) -> (r: Option<usize>)
+    requires
+        start >= CHUNK_HEADER_SIZE,
+    ensures
+        r.is_some() ==> r.unwrap() >= start + len && r.unwrap() > start - CHUNK_HEADER_SIZE,
+{
+    let padded = len as u64 + (len as u64 & 1);
+    if padded > (usize::MAX - start) as u64 {
+        None
+    } else {
+        Some(start + padded as usize)
+    }
+}
+
+/// Source advance per output sample converting `from_hz` to `to_hz`, in
+/// [`RESAMPLE_ONE`] units
+pub fn resample_step(from_hz: u32, to_hz: u32) -> (r: u64)
+    requires
+        MIN_CLIP_RATE_HZ <= from_hz <= MAX_RATE_HZ,
+        0 < to_hz <= MAX_RATE_HZ,
+    ensures
+        r == from_hz as u64 * RESAMPLE_ONE / to_hz as u64,
+        r > 0,
+{
+    proof {
+        assert(from_hz as u64 * RESAMPLE_ONE >= to_hz as u64) by (nonlinear_arith)
+            requires MIN_CLIP_RATE_HZ <= from_hz, to_hz <= MAX_RATE_HZ;
+    }
+    from_hz as u64 * RESAMPLE_ONE / to_hz as u64
+}
+
+/// Output samples for `frames` source frames at `step`: every position
+/// from 0 up to the last frame
+pub fn resampled_len(frames: u32, step: u64) -> (r: u64)
+    requires
+        step > 0,
+    ensures
+        frames == 0 ==> r == 0,
+        frames > 0 ==> r > 0 && (r - 1) * step <= (frames - 1) as u64 * RESAMPLE_ONE,
+{
+    if frames == 0 {
+        0
+    } else {
+        let span = (frames - 1) as u64 * RESAMPLE_ONE;
+        proof {
+            assert((span / step) * step <= span) by (nonlinear_arith) requires step > 0;
+        }
+        span / step + 1
+    }
+}
+
+/// `a` blended towards `b` by `frac` / [`RESAMPLE_ONE`]
+pub fn lerp(a: i16, b: i16, frac: u64) -> (r: i16)
+    requires
+        frac < RESAMPLE_ONE,
+    ensures
+        a <= b ==> a <= r <= b,
+        b < a ==> b <= r <= a,
+        frac == 0 ==> r == a,
+{
+    let w = frac as i64;
+    let sum = a as i64 * (RESAMPLE_ONE as i64 - w) + b as i64 * w;
+    proof {
+        assert(a <= b ==> a as i64 * 65536 <= sum <= b as i64 * 65536) by (nonlinear_arith)
+            requires sum == a as i64 * (65536 - w) + b as i64 * w, 0 <= w < 65536;
+        assert(b < a ==> b as i64 * 65536 <= sum <= a as i64 * 65536) by (nonlinear_arith)
+            requires sum == a as i64 * (65536 - w) + b as i64 * w, 0 <= w < 65536;
+    }
+    (sum / RESAMPLE_ONE as i64) as i16
+}
+
+} // verus!
+
+/// Why a file is not a clip
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WavError {
+    /// No `RIFF`/`WAVE` header
+    NotWav,
+    /// The chunk at this offset runs past the end of the file
+    Truncated { offset: usize },
+    /// A `fmt ` body shorter than [`FMT_BODY_SIZE`]
+    ShortFormat,
+    /// Format tag other than integer PCM
+    NotPcm(u16),
+    /// Neither mono nor stereo
+    BadChannels(u16),
+    /// Samples other than 16-bit
+    BadBits(u16),
+    /// Rate outside [`MIN_CLIP_RATE_HZ`] to [`MAX_RATE_HZ`]
+    BadRate(u32),
+    /// No `fmt ` chunk before the `data` chunk
+    NoFormat,
+    /// No `data` chunk
+    NoData,
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WavError::NotWav => write!(f, "not a RIFF WAVE file"),
+            WavError::Truncated { offset } => write!(f, "chunk at {} runs past the end", offset),
+            WavError::ShortFormat => write!(f, "fmt chunk shorter than {} bytes", FMT_BODY_SIZE),
+            WavError::NotPcm(tag) => write!(f, "format {} is not PCM", tag),
+            WavError::BadChannels(n) => write!(f, "{} channels, need 1 or 2", n),
+            WavError::BadBits(n) => write!(f, "{}-bit samples, need 16", n),
+            WavError::BadRate(hz) => write!(f, "{} Hz outside {}-{} Hz", hz, MIN_CLIP_RATE_HZ, MAX_RATE_HZ),
+            WavError::NoFormat => write!(f, "no fmt chunk before the data"),
+            WavError::NoData => write!(f, "no data chunk"),
+        }
+    }
+}
+
+impl From<WavError> for rpi4_pd_error::PdError {
+    fn from(e: WavError) -> Self {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let (kind, detail) = match e {
+            WavError::NotWav | WavError::ShortFormat | WavError::NoFormat | WavError::NoData => {
+                (ErrorKind::InvalidArgument, 0)
+            }
+            WavError::Truncated { offset } => (ErrorKind::InvalidArgument, offset.min(u16::MAX as usize) as u16),
+            WavError::NotPcm(tag) => (ErrorKind::Unsupported, tag),
+            WavError::BadChannels(n) | WavError::BadBits(n) => (ErrorKind::Unsupported, n),
+            WavError::BadRate(hz) => (ErrorKind::Unsupported, hz.min(u16::MAX as u32) as u16),
+        };
+        PdError::new(ErrorDomain::Audio, kind).with_detail(detail)
+    }
+}
+
+/// A checked 16-bit PCM WAV file
+#[derive(Clone, Copy, Debug)]
+pub struct Wav<'a> {
+    /// Whole frames of the `data` chunk, little-endian
+    data: &'a [u8],
+    channels: u16,
+    rate_hz: u32,
+}
+
+impl<'a> Wav<'a> {
+    /// Check `file` and find its samples; chunks other than `fmt ` and
+    /// `data` are skipped
+    pub fn parse(file: &'a [u8]) -> Result<Self, WavError> {
+        if file.len() < RIFF_HEADER_SIZE || &file[0..4] != b"RIFF" || &file[8..12] != b"WAVE" {
+            return Err(WavError::NotWav);
+        }
+        let mut format = None;
+        let mut offset = RIFF_HEADER_SIZE;
+        // A trailing fragment too short for a chunk header is ignored
+        while file.len() - offset >= CHUNK_HEADER_SIZE {
+            let id = &file[offset..offset + 4];
+            let size = read_u32_le(file, offset + 4);
+            let (start, len) = chunk_body(file.len(), offset, size).ok_or(WavError::Truncated { offset })?;
+            let body = &file[start..start + len];
+            if id == b"fmt " {
+                format = Some(parse_format(body)?);
+            } else if id == b"data" {
+                let (channels, rate_hz) = format.ok_or(WavError::NoFormat)?;
+                let frame = 2 * channels as usize;
+                return Ok(Self { data: &body[..body.len() / frame * frame], channels, rate_hz });
+            }
+            match next_chunk(start, len) {
+                Some(next) if next <= file.len() => offset = next,
+                _ => break,
+            }
+        }
+        Err(WavError::NoData)
+    }
+
+    pub fn rate_hz(&self) -> u32 {
+        self.rate_hz
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Samples per channel
+    pub fn frames(&self) -> usize {
+        self.data.len() / (2 * self.channels as usize)
+    }
+
+    /// Frame `i` as one mono sample: stereo frames are the mean of the two
+    /// channels
+    pub fn frame(&self, i: usize) -> i16 {
+        let at = i * 2 * self.channels as usize;
+        let left = read_i16_le(self.data, at);
+        if self.channels == 1 {
+            return left;
+        }
+        let right = read_i16_le(self.data, at + 2);
+        ((left as i32 + right as i32) / 2) as i16
+    }
+
+    /// Write the clip at `to_hz` into `out`, linearly interpolated;
+    /// returns the samples written, which stop early if `out` is too
+    /// short. `None` for an output rate of 0 or above [`MAX_RATE_HZ`].
+    pub fn resample_into(&self, out: &mut [i16], to_hz: u32) -> Option<usize> {
+        if to_hz == 0 || to_hz > MAX_RATE_HZ {
+            return None;
+        }
+        let frames = self.frames();
+        let step = resample_step(self.rate_hz, to_hz);
+        let len = resampled_len(frames.min(u32::MAX as usize) as u32, step);
+        let n = (len.min(out.len() as u64)) as usize;
+        let mut pos: u64 = 0;
+        for slot in out[..n].iter_mut() {
+            let i = (pos >> RESAMPLE_FRAC_BITS) as usize;
+            let frac = pos & (RESAMPLE_ONE - 1);
+            let a = self.frame(i);
+            *slot = if frac == 0 { a } else { lerp(a, self.frame((i + 1).min(frames - 1)), frac) };
+            pos += step;
+        }
+        Some(n)
+    }
+}
+
+/// Channels and rate from a `fmt ` body
+fn parse_format(body: &[u8]) -> Result<(u16, u32), WavError> {
+    if body.len() < FMT_BODY_SIZE {
+        return Err(WavError::ShortFormat);
+    }
+    let tag = read_u16_le(body, 0);
+    let channels = read_u16_le(body, 2);
+    let rate_hz = read_u32_le(body, 4);
+    let bits = read_u16_le(body, 14);
+    if tag != WAVE_FORMAT_PCM {
+        return Err(WavError::NotPcm(tag));
+    }
+    if channels != 1 && channels != 2 {
+        return Err(WavError::BadChannels(channels));
+    }
+    if bits != 16 {
+        return Err(WavError::BadBits(bits));
+    }
+    if rate_hz < MIN_CLIP_RATE_HZ || rate_hz > MAX_RATE_HZ {
+        return Err(WavError::BadRate(rate_hz));
+    }
+    Ok((channels, rate_hz))
+}
+
+fn read_u16_le(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn read_i16_le(buf: &[u8], offset: usize) -> i16 {
+    read_u16_le(buf, offset) as i16
+}
+
+fn read_u32_le(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A WAV file of `samples` with an odd-sized chunk before the data
+    fn wav(channels: u16, rate_hz: u32, samples: &[i16], out: &mut [u8; 128]) -> usize {
+        let mut n = 0;
+        let mut put = |bytes: &[u8]| {
+            out[n..n + bytes.len()].copy_from_slice(bytes);
+            n += bytes.len();
+        };
+        put(b"RIFF");
+        put(&0u32.to_le_bytes());
+        put(b"WAVE");
+        put(b"fmt ");
+        put(&16u32.to_le_bytes());
+        put(&WAVE_FORMAT_PCM.to_le_bytes());
+        put(&channels.to_le_bytes());
+        put(&rate_hz.to_le_bytes());
+        put(&(rate_hz * 2 * channels as u32).to_le_bytes());
+        put(&(2 * channels).to_le_bytes());
+        put(&16u16.to_le_bytes());
+        put(b"LIST");
+        put(&3u32.to_le_bytes());
+        put(b"abc\0");
+        put(b"data");
+        put(&(2 * samples.len() as u32).to_le_bytes());
+        for s in samples {
+            put(&s.to_le_bytes());
+        }
+        n
+    }
+
+    #[test]
+    fn parses_pcm_and_skips_other_chunks() {
+        let mut buf = [0u8; 128];
+        let n = wav(1, 22_050, &[0, 100, -100, i16::MIN], &mut buf);
+        let clip = Wav::parse(&buf[..n]).unwrap();
+        assert_eq!((clip.channels(), clip.rate_hz(), clip.frames()), (1, 22_050, 4));
+        assert_eq!([0, 1, 2, 3].map(|i| clip.frame(i)), [0, 100, -100, i16::MIN]);
+
+        let n = wav(2, 44_100, &[100, 300, -1, -2, 7], &mut buf);
+        let clip = Wav::parse(&buf[..n]).unwrap();
+        // The odd trailing sample is not a whole frame
+        assert_eq!(clip.frames(), 2);
+        assert_eq!([clip.frame(0), clip.frame(1)], [200, -1]);
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_play() {
+        let mut buf = [0u8; 128];
+        let n = wav(1, 22_050, &[1, 2], &mut buf);
+        assert_eq!(Wav::parse(&buf[..4]).unwrap_err(), WavError::NotWav);
+        // The data chunk claims two samples but the file ends after one
+        assert_eq!(Wav::parse(&buf[..n - 2]).unwrap_err(), WavError::Truncated { offset: 48 });
+        assert_eq!(Wav::parse(&buf[..48]).unwrap_err(), WavError::NoData);
+
+        let mut bad = buf;
+        bad[20] = 3;
+        assert_eq!(Wav::parse(&bad[..n]).unwrap_err(), WavError::NotPcm(3));
+        let mut bad = buf;
+        bad[34] = 8;
+        assert_eq!(Wav::parse(&bad[..n]).unwrap_err(), WavError::BadBits(8));
+        let n = wav(3, 22_050, &[1, 2, 3], &mut buf);
+        assert_eq!(Wav::parse(&buf[..n]).unwrap_err(), WavError::BadChannels(3));
+        let n = wav(1, 1_000, &[1], &mut buf);
+        assert_eq!(Wav::parse(&buf[..n]).unwrap_err(), WavError::BadRate(1_000));
+    }
+
+    #[test]
+    fn resamples_to_the_output_rate() {
+        let mut buf = [0u8; 128];
+        let n = wav(1, 22_050, &[0, 1000, -1000, 3], &mut buf);
+        let clip = Wav::parse(&buf[..n]).unwrap();
+
+        let mut out = [0i16; 16];
+        assert_eq!(clip.resample_into(&mut out, 44_100), Some(7));
+        assert_eq!(out[..7], [0, 500, 1000, 0, -1000, -498, 3]);
+        // Same rate is a copy; a short buffer stops early
+        assert_eq!(clip.resample_into(&mut out, 22_050), Some(4));
+        assert_eq!(out[..4], [0, 1000, -1000, 3]);
+        assert_eq!(clip.resample_into(&mut out[..2], 48_000), Some(2));
+        assert_eq!(clip.resample_into(&mut out, 0), None);
+
+        // 22.05 kHz to 48 kHz: nothing overshoots the source
+        let n = clip.resample_into(&mut out, 48_000).unwrap();
+        assert_eq!(n as u64, resampled_len(4, resample_step(22_050, 48_000)));
+        assert_eq!(n, 7);
+        assert!(out[..n].iter().all(|&s| (-1000..=1000).contains(&s)));
+    }
+
+    #[test]
+    fn blending_stays_between_samples() {
+        assert_eq!(lerp(i16::MIN, i16::MAX, RESAMPLE_ONE - 1), i16::MAX - 1);
+        assert_eq!(lerp(i16::MAX, i16::MIN, 1), i16::MAX - 1);
+        assert_eq!(lerp(-7, 9, 0), -7);
+        assert_eq!(resampled_len(0, 1), 0);
+        assert_eq!(resampled_len(1, RESAMPLE_ONE), 1);
+        assert!(resample_step(MIN_CLIP_RATE_HZ, MAX_RATE_HZ) > 0);
+    }
+}
@@ -0,0 +1,39 @@
+//! DMA for the audio outputs.
+//!
+//! Each output loops a legacy channel over two control blocks, one per
+//! half of its sample buffer, with an interrupt at the end of each, so the
+//! channel plays one half while the CPU refills the other, and never stops
+//! on its own. The channel, control blocks and bus addresses are the
+//! shared BCM2711 ones from [`rpi4_bcm2711::dma`]; this module adds the
+//! region they live in and the priority audio runs at.
+//!
+//! Control blocks and samples must live in a region with a known physical
+//! address below 1 GiB, and the CPU must clean what it wrote from the data
+//! cache ([`clean_dcache_range`]) before the channel reads it.
+
+pub use rpi4_bcm2711::dma::{
+    clean_dcache_range, cs, dreq, peripheral_bus_address, ram_bus_address, ti, ControlBlock, DmaChannel,
+};
+
+/// AXI priority and panic priority: above the display's mid-range, as a
+/// late sample is audible
+pub const AUDIO_PRIORITY: u32 = cs::priority(12, 15);
+
+/// Memory the DMA channel reads control blocks and samples from
+#[derive(Clone, Copy, Debug)]
+pub struct DmaRegion {
+    /// Virtual address of the (cached) mapping
+    pub vaddr: usize,
+    /// Physical address, below 1 GiB
+    pub paddr: usize,
+    /// Bytes mapped; at least the output's `DMA_REGION_SIZE`
+    pub size: usize,
+}
+
+impl DmaRegion {
+    /// Does the region hold `needed` bytes, all of them below 1 GiB where
+    /// the DMA engine reaches?
+    pub fn fits(&self, needed: usize) -> bool {
+        self.size >= needed && self.paddr < 0x4000_0000 && needed <= 0x4000_0000 - self.paddr
+    }
+}
@@ -0,0 +1,400 @@
+//! BCM2711 PCM/I2S output to an external DAC.
+//!
+//! DAC and amplifier HATs (PCM5102A, HiFiBerry, MAX98357A and the like)
+//! take 16-bit stereo over I2S on GPIO18-21. The PCM block shifts samples
+//! out of its FIFO; a DMA channel keeps the FIFO fed from a buffer in two
+//! halves, with two control blocks chained into a loop. Each block raises
+//! an interrupt as it finishes, and [`I2sOutput::handle_irq`] refills the
+//! half just played from its [`SampleSource`] (the Audio PD's mixer) while
+//! the channel plays the other, so the CPU runs once per half rather than
+//! once per sample.
+//!
+//! The Pi is the clock master by default: the clock manager's PCM clock,
+//! fed from PLLD, makes the bit clock and the PCM block the word clock.
+//! HATs with their own crystal are masters themselves; [`I2sConfig::master`]
+//! false leaves both clocks to them.
+//!
+//! ## Verification
+//!
+//! Verus proves that [`stereo_word`] packs each channel into its own half
+//! of the FIFO word, that [`clock_divisor`] only returns a divisor the
+//! clock manager accepts and that it is the source rate over the bit clock
+//! to 1/4096, and that [`half_offset`] keeps both halves inside the DMA
+//! region.
+
+use core::fmt;
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use rpi4_bcm2711::clock::{start_clock, Clock, Source, MAX_DIVI, MIN_DIVI, PLLD_HZ};
+
+use crate::dma::{self, dreq, ti, ControlBlock, DmaChannel, DmaRegion};
+use crate::SampleSource;
+
+verus! {
+
+/// BCM2711 PCM/I2S base address
+pub const PCM_BASE: usize = 0xFE20_3000;
+
+/// Bit clocks per frame: two 32-bit slots, of which each channel uses 16
+pub const FRAME_BITS: u32 = 64;
+
+/// Stereo frames in each half of the DMA buffer: 10.7 ms at 48 kHz
+pub const HALF_FRAMES: usize = 512;
+
+/// Offset of the two control blocks in the DMA region
+pub const CB_OFFSET: usize = 0;
+
+/// Offset of the first half's samples in the DMA region
+pub const SAMPLES_OFFSET: usize = 64;
+
+/// Bytes of one half: one FIFO word per frame
+pub const HALF_BYTES: usize = HALF_FRAMES * 4;
+
+/// DMA region the output needs: control blocks, then both halves
+pub const DMA_REGION_SIZE: usize = SAMPLES_OFFSET + 2 * HALF_BYTES;
+
+/// One FIFO word in packed mode: channel 1 (left) in the low half,
+/// channel 2 (right) in the high half
+pub fn stereo_word(left: i16, right: i16) -> (w: u32)
+    ensures
+        w & 0xFFFF == left as u16 as u32,
+        w >> 16 == right as u16 as u32,
+{
+    let l = left as u16 as u32;
+    let r = right as u16 as u32;
+    assert((r << 16 | l) & 0xFFFF == l && (r << 16 | l) >> 16 == r) by (bit_vector)
+        requires l <= 0xFFFF, r <= 0xFFFF;
+    (r << 16) | l
+}
+
+/// PCM clock divisor for `rate_hz` frames a second from `source_hz`, as
+/// the clock manager's 12.12 fixed-point `DIV` field; `None` if the
+/// integer part falls outside [`MIN_DIVI`]..=[`MAX_DIVI`]
+pub fn clock_divisor(source_hz: u32, rate_hz: u32) -> (div: Option<u32>)
+    requires
+        0 < rate_hz <= 96_000,
+    ensures
+        div.is_some() ==> MIN_DIVI <= div.unwrap() / 4096 <= MAX_DIVI
+            && div.unwrap() / 4096 == source_hz / (rate_hz * FRAME_BITS),
+{
+    let bclk = rate_hz * FRAME_BITS;
+    let divi = source_hz / bclk;
+    if divi < MIN_DIVI || divi > MAX_DIVI {
+        return None;
+    }
+    let rem = source_hz % bclk;
+    proof {
+        assert(rem * 4096 < bclk * 4096) by (nonlinear_arith) requires rem < bclk;
+    }
+    let divf = (rem as u64 * 4096 / bclk as u64) as u32;
+    proof {
+        assert(divf < 4096) by (nonlinear_arith)
+            requires divf == rem as u64 * 4096 / bclk as u64, rem < bclk, bclk > 0;
+        assert((divi * 4096 + divf) / 4096 == divi) by (nonlinear_arith)
+            requires divf < 4096;
+    }
+    Some(divi * 4096 + divf)
+}
+
+/// Offset of half `half` (0 or 1) of the samples in the DMA region
+pub fn half_offset(half: usize) -> (offset: usize)
+    requires
+        half < 2,
+    ensures
+        offset == SAMPLES_OFFSET + half * HALF_BYTES,
+        offset + HALF_BYTES <= DMA_REGION_SIZE,
+{
+    SAMPLES_OFFSET + half * HALF_BYTES
+}
+
+} // verus!
+
+/// PCM register offsets
+#[allow(dead_code)]
+mod regs {
+    pub const CS: usize = 0x00;    // Control and status
+    pub const FIFO: usize = 0x04;  // FIFO data
+    pub const MODE: usize = 0x08;  // Mode
+    pub const TXC: usize = 0x10;   // Transmit configuration
+    pub const DREQ: usize = 0x14;  // DMA request levels
+}
+
+/// PCM CS bits
+mod cs {
+    pub const EN: u32 = 1 << 0;
+    pub const TXON: u32 = 1 << 2;
+    pub const TXCLR: u32 = 1 << 3;
+    pub const RXCLR: u32 = 1 << 4;
+    pub const DMAEN: u32 = 1 << 9;
+    pub const TXERR: u32 = 1 << 15;   // Write 1 to clear
+    pub const SYNC: u32 = 1 << 24;
+    pub const STBY: u32 = 1 << 25;
+}
+
+/// PCM MODE fields
+mod mode {
+    pub const fn fslen(bits: u32) -> u32 {
+        bits & 0x3FF
+    }
+    pub const fn flen(bits: u32) -> u32 {
+        ((bits - 1) & 0x3FF) << 10
+    }
+    /// Word clock low for the left channel
+    pub const FSI: u32 = 1 << 20;
+    /// Word clock input (the DAC is master)
+    pub const FSM: u32 = 1 << 21;
+    /// Data changes on the falling bit clock edge, as I2S wants
+    pub const CLKI: u32 = 1 << 22;
+    /// Bit clock input (the DAC is master)
+    pub const CLKM: u32 = 1 << 23;
+    /// Both channels from one FIFO word
+    pub const FTXP: u32 = 1 << 24;
+}
+
+/// PCM TXC: channel `n` (1 or 2) enabled, 16 bits wide, starting `pos`
+/// bit clocks into the frame
+const fn txc_channel(n: u32, pos: u32) -> u32 {
+    // Width is CHxWID + 8
+    let field = (1 << 14) | ((pos & 0x3FF) << 4) | 8;
+    if n == 1 { field << 16 } else { field }
+}
+
+/// DREQ levels: ask for data below 48 words, panic below 16
+const DREQ_LEVELS: u32 = (16 << 24) | (48 << 8);
+
+/// How the DAC is wired
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct I2sConfig {
+    /// Pi drives the bit and word clocks; false for a HAT that is master
+    pub master: bool,
+}
+
+impl Default for I2sConfig {
+    fn default() -> Self {
+        Self { master: true }
+    }
+}
+
+/// What stopped the output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum I2sError {
+    /// The PCM clock cannot be divided down to this rate
+    BadRate(u32),
+    /// The DMA region is smaller than [`DMA_REGION_SIZE`] or above 1 GiB
+    BadRegion,
+    /// The DMA channel stopped on a bus error
+    Dma,
+}
+
+impl fmt::Display for I2sError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            I2sError::BadRate(hz) => write!(f, "no PCM clock divisor for {} Hz", hz),
+            I2sError::BadRegion => write!(f, "DMA region too small or not DMA-addressable"),
+            I2sError::Dma => write!(f, "DMA channel error"),
+        }
+    }
+}
+
+impl From<I2sError> for rpi4_pd_error::PdError {
+    fn from(e: I2sError) -> Self {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let (kind, detail) = match e {
+            I2sError::BadRate(hz) => (ErrorKind::Unsupported, hz.min(u16::MAX as u32) as u16),
+            I2sError::BadRegion => (ErrorKind::InvalidArgument, 0),
+            I2sError::Dma => (ErrorKind::Hardware, 0),
+        };
+        PdError::new(ErrorDomain::Audio, kind).with_detail(detail)
+    }
+}
+
+/// Samples pulled per call to the source while refilling a half
+const MIX_CHUNK: usize = 64;
+
+/// I2S output fed by a looping DMA channel
+pub struct I2sOutput {
+    /// Virtual address of the PCM registers
+    base: usize,
+    dma: DmaChannel,
+    region: DmaRegion,
+    /// FIFO underruns seen so far
+    underruns: u32,
+}
+
+impl I2sOutput {
+    /// Set up the PCM block for 16-bit stereo I2S at `rate_hz`, with its
+    /// clock when the Pi is master; the output stays silent until
+    /// [`start`](Self::start)
+    ///
+    /// # Safety
+    /// `pcm_base` and `cm_base` must be the mapped PCM and clock manager
+    /// register pages, nothing else may use the PCM block or its clock,
+    /// and `region` must be mapped as described.
+    pub unsafe fn new(
+        pcm_base: usize,
+        cm_base: usize,
+        dma: DmaChannel,
+        region: DmaRegion,
+        rate_hz: u32,
+        config: I2sConfig,
+    ) -> Result<Self, I2sError> {
+        if !region.fits(DMA_REGION_SIZE) {
+            return Err(I2sError::BadRegion);
+        }
+        if rate_hz == 0 || rate_hz > rpi4_audio_protocol::MAX_RATE_HZ {
+            return Err(I2sError::BadRate(rate_hz));
+        }
+        let out = Self { base: pcm_base, dma, region, underruns: 0 };
+        out.write_reg(regs::CS, 0);
+        if config.master {
+            let div = clock_divisor(PLLD_HZ, rate_hz).ok_or(I2sError::BadRate(rate_hz))?;
+            start_clock(cm_base, Clock::Pcm, Source::Plld, div);
+        }
+
+        let clocks = if config.master { 0 } else { mode::CLKM | mode::FSM };
+        out.write_reg(
+            regs::MODE,
+            mode::flen(FRAME_BITS) | mode::fslen(FRAME_BITS / 2) | mode::FSI | mode::CLKI | mode::FTXP | clocks,
+        );
+        // I2S data starts one bit clock after the word clock edge
+        out.write_reg(regs::TXC, txc_channel(1, 1) | txc_channel(2, FRAME_BITS / 2 + 1));
+        out.write_reg(regs::DREQ, DREQ_LEVELS);
+        out.write_reg(regs::CS, cs::EN | cs::STBY);
+        out.clear_fifo();
+        Ok(out)
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// Empty the FIFOs; takes effect after two PCM clocks, seen as `SYNC`
+    /// following what was written
+    fn clear_fifo(&self) {
+        let cs = self.read_reg(regs::CS);
+        self.write_reg(regs::CS, cs | cs::TXCLR | cs::RXCLR);
+        let sync = self.read_reg(regs::CS) & cs::SYNC;
+        self.write_reg(regs::CS, (self.read_reg(regs::CS) & !cs::SYNC) | (sync ^ cs::SYNC));
+        while self.read_reg(regs::CS) & cs::SYNC == sync {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// GIC interrupt ID to route to the Audio PD
+    pub fn irq(&self) -> usize {
+        self.dma.irq()
+    }
+
+    /// FIFO underruns seen so far: the refill was late and the DAC
+    /// repeated a sample
+    pub fn underruns(&self) -> u32 {
+        self.underruns
+    }
+
+    /// Fill both halves from `source` and start playing
+    pub fn start(&mut self, source: &mut impl SampleSource) {
+        self.fill(0, source);
+        self.fill(1, source);
+
+        let fifo = dma::peripheral_bus_address(PCM_BASE + regs::FIFO);
+        let ti = ti::INTEN | ti::WAIT_RESP | ti::SRC_INC | ti::DEST_DREQ | ti::permap(dreq::PCM_TX);
+        let blocks = (self.region.vaddr + CB_OFFSET) as *mut ControlBlock;
+        for half in 0..2 {
+            let source = dma::ram_bus_address(self.region.paddr + half_offset(half));
+            let next = self.block_bus(1 - half);
+            // Safety: the region holds both blocks (checked in new)
+            unsafe { blocks.add(half).write(ControlBlock::new(ti, source, fifo, HALF_BYTES as u32, next)) };
+        }
+        dma::clean_dcache_range(blocks as *const u8, SAMPLES_OFFSET);
+
+        self.write_reg(regs::CS, self.read_reg(regs::CS) | cs::DMAEN);
+        self.dma.start(self.block_bus(0));
+        self.write_reg(regs::CS, self.read_reg(regs::CS) | cs::TXON);
+    }
+
+    /// Handle the DMA interrupt: refill the half that just finished.
+    /// Returns whether the interrupt was this channel's.
+    pub fn handle_irq(&mut self, source: &mut impl SampleSource) -> Result<bool, I2sError> {
+        if self.dma.failed() {
+            return Err(I2sError::Dma);
+        }
+        if !self.dma.take_interrupt() {
+            return Ok(false);
+        }
+        let cs = self.read_reg(regs::CS);
+        if cs & cs::TXERR != 0 {
+            self.underruns = self.underruns.saturating_add(1);
+            self.write_reg(regs::CS, cs);
+        }
+        // The channel has moved on to the other half's block
+        let playing = if self.dma.current_block() == self.block_bus(0) { 0 } else { 1 };
+        self.fill(1 - playing, source);
+        Ok(true)
+    }
+
+    /// Stop the channel and the transmitter
+    pub fn stop(&mut self) {
+        self.write_reg(regs::CS, self.read_reg(regs::CS) & !(cs::TXON | cs::DMAEN));
+        self.dma.reset();
+    }
+
+    /// Bus address of control block `half`
+    fn block_bus(&self, half: usize) -> u32 {
+        dma::ram_bus_address(self.region.paddr + CB_OFFSET + half * core::mem::size_of::<ControlBlock>())
+    }
+
+    /// Pull one half's worth of samples into half `half`, the same sample
+    /// on both channels, and clean it for the channel
+    fn fill(&mut self, half: usize, source: &mut impl SampleSource) {
+        let words = (self.region.vaddr + half_offset(half)) as *mut u32;
+        let mut chunk = [0i16; MIX_CHUNK];
+        for start in (0..HALF_FRAMES).step_by(MIX_CHUNK) {
+            source.fill(&mut chunk);
+            for (i, &s) in chunk.iter().enumerate() {
+                // Safety: start + i < HALF_FRAMES, inside the half
+                unsafe { words.add(start + i).write(stereo_word(s, s)) };
+            }
+        }
+        dma::clean_dcache_range(words as *const u8, HALF_BYTES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_pack_into_their_halves() {
+        assert_eq!(stereo_word(1, 2), 0x0002_0001);
+        assert_eq!(stereo_word(-1, 0), 0x0000_FFFF);
+        assert_eq!(stereo_word(i16::MIN, i16::MAX), 0x7FFF_8000);
+    }
+
+    #[test]
+    fn divisors_for_common_rates() {
+        // 750 MHz / (48 kHz * 64) = 244.140625
+        assert_eq!(clock_divisor(PLLD_HZ, 48_000), Some(244 * 4096 + 576));
+        // 750 MHz / (44.1 kHz * 64) = 265.731...
+        assert_eq!(clock_divisor(PLLD_HZ, 44_100).map(|d| d >> 12), Some(265));
+        assert_eq!(clock_divisor(PLLD_HZ, 1), None);
+        assert_eq!(clock_divisor(100, 48_000), None);
+    }
+
+    #[test]
+    fn layout_fits() {
+        assert!(CB_OFFSET + 2 * core::mem::size_of::<ControlBlock>() <= SAMPLES_OFFSET);
+        assert_eq!(half_offset(1) + HALF_BYTES, DMA_REGION_SIZE);
+        assert_eq!(HALF_FRAMES % MIX_CHUNK, 0);
+        assert_eq!(txc_channel(1, 1), 0x4018_0000);
+        assert_eq!(txc_channel(2, 33), 0x4218);
+    }
+}
@@ -0,0 +1,53 @@
+//! Audio output drivers for the Raspberry Pi 4.
+//!
+//! The other half of `rpi4-audio-protocol`: where that crate turns client
+//! calls into one stream of signed 16-bit samples from its [`Mixer`], the
+//! drivers here move the stream to the hardware. The Audio PD
+//! (`rpi4-audio-pd`) picks its output with a Cargo feature, so a build only
+//! carries the driver for the board's wiring:
+//!
+//! - `pwm`: [`pwm::PwmOutput`], the PWM1 block on the 3.5 mm headphone
+//!   jack, which every Pi 4 has.
+//! - `i2s`: [`i2s::I2sOutput`], the BCM2711 PCM block in I2S mode to an
+//!   external DAC or amplifier HAT.
+//!
+//! Either is fed by a looping [`dma`] channel, and pulls its samples from
+//! a [`SampleSource`] each time a half of the buffer needs refilling:
+//!
+//! ```text
+//! ┌──────────┐  i16   ┌─────────────┐  words  ┌──────────┐       ┌──────────┐
+//! │ Mixer<N> │ ─────► │ DMA buffer  │ ──────► │ PWM/PCM  │ ────► │ jack/DAC │
+//! └──────────┘ refill │ (two halves)│   DMA   │ FIFO     │       └──────────┘
+//!                     └─────────────┘         └──────────┘
+//! ```
+//!
+//! [`Mixer`]: rpi4_audio_protocol::Mixer
+
+#![no_std]
+#![allow(clippy::new_without_default)]
+
+use rpi4_audio_protocol::Mixer;
+
+pub mod dma;
+#[cfg(feature = "i2s")]
+pub mod i2s;
+#[cfg(feature = "pwm")]
+pub mod pwm;
+
+pub use dma::{ControlBlock, DmaChannel, DmaRegion, AUDIO_PRIORITY};
+#[cfg(feature = "i2s")]
+pub use i2s::{I2sConfig, I2sError, I2sOutput};
+#[cfg(feature = "pwm")]
+pub use pwm::{PwmError, PwmOutput};
+
+/// Where an output gets the samples it plays
+pub trait SampleSource {
+    /// Fill `out` with the next samples, at the output's rate
+    fn fill(&mut self, out: &mut [i16]);
+}
+
+impl<const N: usize> SampleSource for Mixer<'_, N> {
+    fn fill(&mut self, out: &mut [i16]) {
+        self.mix(out)
+    }
+}
@@ -0,0 +1,327 @@
+//! BCM2711 PWM output to the headphone jack.
+//!
+//! The Pi 4's 3.5 mm jack is driven by the two channels of the PWM1 block
+//! on GPIO40 and GPIO41, through an RC low-pass filter on the board. Each
+//! channel runs in mark-space mode: every output sample is one period of
+//! `range` clock ticks, high for [`pwm_duty`] of them, so the filtered
+//! level follows the sample. Both channels carry the same mono sample.
+//!
+//! The channels share one FIFO and take words from it in turn, so the
+//! samples go out as two duty words per frame. As for I2S, a DMA channel
+//! keeps the FIFO fed from a buffer in two halves with two control blocks
+//! chained into a loop, and [`PwmOutput::handle_irq`] refills the half
+//! just played from its [`SampleSource`].
+//!
+//! The PWM clock is PLLD divided by [`PWM_CLOCK_DIVI`], 125 MHz; at 48 kHz
+//! that is a range of 2604 ticks, a little over 11 bits per sample. The
+//! firmware must hand GPIO40/41 to the PWM block (`gpio=40,41=a0` in
+//! `config.txt`), so the Audio PD needs no access to the GPIO registers.
+//!
+//! ## Verification
+//!
+//! Verus proves that [`pwm_range`] only returns a range of at least
+//! [`MIN_RANGE`] whose periods fit in the clock at the requested rate,
+//! that [`pwm_duty`] keeps every duty within that range, and that
+//! [`half_offset`] keeps both halves inside the DMA region.
+
+use core::fmt;
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use rpi4_audio_protocol::pwm_duty;
+
+use rpi4_bcm2711::clock::{start_clock, Clock, Source, PLLD_HZ};
+use rpi4_bcm2711::pwm::{ctl, dmac, regs, sta, PWM1_BASE, PWM1_PAGE_OFFSET};
+
+use crate::dma::{self, dreq, ti, ControlBlock, DmaChannel, DmaRegion};
+use crate::SampleSource;
+
+verus! {
+
+/// PLLD divisor for the PWM clock
+pub const PWM_CLOCK_DIVI: u32 = 6;
+
+/// PWM clock, 125 MHz
+pub const PWM_CLOCK_HZ: u32 = PLLD_HZ / PWM_CLOCK_DIVI;
+
+/// Fewest ticks per sample period: 8 bits of resolution
+pub const MIN_RANGE: u32 = 256;
+
+/// Frames in each half of the DMA buffer: 10.7 ms at 48 kHz
+pub const HALF_FRAMES: usize = 512;
+
+/// Offset of the two control blocks in the DMA region
+pub const CB_OFFSET: usize = 0;
+
+/// Offset of the first half's samples in the DMA region
+pub const SAMPLES_OFFSET: usize = 64;
+
+/// Bytes of one half: a FIFO word per channel per frame
+pub const HALF_BYTES: usize = HALF_FRAMES * 8;
+
+/// DMA region the output needs: control blocks, then both halves
+pub const DMA_REGION_SIZE: usize = SAMPLES_OFFSET + 2 * HALF_BYTES;
+
+/// Clock ticks per sample period for `rate_hz` samples a second from a
+/// `clock_hz` PWM clock; `None` below [`MIN_RANGE`]
+pub fn pwm_range(clock_hz: u32, rate_hz: u32) -> (r: Option<u32>)
+    requires
+        0 < rate_hz,
+    ensures
+        r.is_some() ==> r.unwrap() >= MIN_RANGE
+            && r.unwrap() == clock_hz / rate_hz
+            && r.unwrap() * rate_hz <= clock_hz,
+{
+    let range = clock_hz / rate_hz;
+    if range < MIN_RANGE {
+        return None;
+    }
+    proof {
+        assert(range * rate_hz <= clock_hz) by (nonlinear_arith)
+            requires range == clock_hz / rate_hz, 0 < rate_hz;
+    }
+    Some(range)
+}
+
+/// Offset of half `half` (0 or 1) of the samples in the DMA region
+pub fn half_offset(half: usize) -> (offset: usize)
+    requires
+        half < 2,
+    ensures
+        offset == SAMPLES_OFFSET + half * HALF_BYTES,
+        offset + HALF_BYTES <= DMA_REGION_SIZE,
+{
+    SAMPLES_OFFSET + half * HALF_BYTES
+}
+
+} // verus!
+
+/// Both channels playing from the FIFO in mark-space mode
+const PLAY: u32 = ctl::PWEN1 | ctl::USEF1 | ctl::MSEN1 | ctl::PWEN2 | ctl::USEF2 | ctl::MSEN2;
+
+/// DMAC: DMA enabled, ask for data below 7 words, panic below 7
+const DMAC_ENABLE: u32 = dmac::ENAB | dmac::thresholds(7, 7);
+
+/// What stopped the output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PwmError {
+    /// The PWM clock gives fewer than [`MIN_RANGE`] ticks at this rate
+    BadRate(u32),
+    /// The DMA region is smaller than [`DMA_REGION_SIZE`] or above 1 GiB
+    BadRegion,
+    /// The DMA channel stopped on a bus error
+    Dma,
+}
+
+impl fmt::Display for PwmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PwmError::BadRate(hz) => write!(f, "no PWM range for {} Hz", hz),
+            PwmError::BadRegion => write!(f, "DMA region too small or not DMA-addressable"),
+            PwmError::Dma => write!(f, "DMA channel error"),
+        }
+    }
+}
+
+impl From<PwmError> for rpi4_pd_error::PdError {
+    fn from(e: PwmError) -> Self {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let (kind, detail) = match e {
+            PwmError::BadRate(hz) => (ErrorKind::Unsupported, hz.min(u16::MAX as u32) as u16),
+            PwmError::BadRegion => (ErrorKind::InvalidArgument, 0),
+            PwmError::Dma => (ErrorKind::Hardware, 0),
+        };
+        PdError::new(ErrorDomain::Audio, kind).with_detail(detail)
+    }
+}
+
+/// Samples pulled per call to the source while refilling a half
+const MIX_CHUNK: usize = 64;
+
+/// Headphone output fed by a looping DMA channel
+pub struct PwmOutput {
+    /// Virtual address of the PWM1 registers
+    base: usize,
+    dma: DmaChannel,
+    region: DmaRegion,
+    /// Ticks per sample period
+    range: u32,
+    /// FIFO underruns seen so far
+    underruns: u32,
+}
+
+impl PwmOutput {
+    /// Set up both PWM1 channels for mark-space output at `rate_hz`, with
+    /// the PWM clock; the output stays silent until [`start`](Self::start)
+    ///
+    /// # Safety
+    /// `pwm_page` and `cm_base` must be the mapped PWM and clock manager
+    /// register pages, nothing else may use PWM1 or the PWM clock (PWM0
+    /// shares that clock), and `region` must be mapped as described.
+    pub unsafe fn new(
+        pwm_page: usize,
+        cm_base: usize,
+        dma: DmaChannel,
+        region: DmaRegion,
+        rate_hz: u32,
+    ) -> Result<Self, PwmError> {
+        if !region.fits(DMA_REGION_SIZE) {
+            return Err(PwmError::BadRegion);
+        }
+        if rate_hz == 0 || rate_hz > rpi4_audio_protocol::MAX_RATE_HZ {
+            return Err(PwmError::BadRate(rate_hz));
+        }
+        let range = pwm_range(PWM_CLOCK_HZ, rate_hz).ok_or(PwmError::BadRate(rate_hz))?;
+        let out = Self { base: pwm_page + PWM1_PAGE_OFFSET, dma, region, range, underruns: 0 };
+
+        out.write_reg(regs::CTL, 0);
+        start_clock(cm_base, Clock::Pwm, Source::Plld, PWM_CLOCK_DIVI << 12);
+        out.write_reg(regs::RNG1, range);
+        out.write_reg(regs::RNG2, range);
+        out.write_reg(regs::CTL, ctl::CLRF);
+        out.write_reg(regs::STA, sta::ERRORS);
+        Ok(out)
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// GIC interrupt ID to route to the Audio PD
+    pub fn irq(&self) -> usize {
+        self.dma.irq()
+    }
+
+    /// Ticks per sample period
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    /// FIFO underruns seen so far: the refill was late and the jack held
+    /// a level
+    pub fn underruns(&self) -> u32 {
+        self.underruns
+    }
+
+    /// Fill both halves from `source` and start playing
+    pub fn start(&mut self, source: &mut impl SampleSource) {
+        self.fill(0, source);
+        self.fill(1, source);
+
+        let fifo = dma::peripheral_bus_address(PWM1_BASE + regs::FIF1);
+        let ti = ti::INTEN | ti::WAIT_RESP | ti::SRC_INC | ti::DEST_DREQ | ti::permap(dreq::PWM1);
+        let blocks = (self.region.vaddr + CB_OFFSET) as *mut ControlBlock;
+        for half in 0..2 {
+            let source = dma::ram_bus_address(self.region.paddr + half_offset(half));
+            let next = self.block_bus(1 - half);
+            // Safety: the region holds both blocks (checked in new)
+            unsafe { blocks.add(half).write(ControlBlock::new(ti, source, fifo, HALF_BYTES as u32, next)) };
+        }
+        dma::clean_dcache_range(blocks as *const u8, SAMPLES_OFFSET);
+
+        self.write_reg(regs::DMAC, DMAC_ENABLE);
+        self.dma.start(self.block_bus(0));
+        self.write_reg(regs::CTL, PLAY);
+    }
+
+    /// Handle the DMA interrupt: refill the half that just finished.
+    /// Returns whether the interrupt was this channel's.
+    pub fn handle_irq(&mut self, source: &mut impl SampleSource) -> Result<bool, PwmError> {
+        if self.dma.failed() {
+            return Err(PwmError::Dma);
+        }
+        if !self.dma.take_interrupt() {
+            return Ok(false);
+        }
+        let status = self.read_reg(regs::STA);
+        if status & sta::GAPS != 0 {
+            self.underruns = self.underruns.saturating_add(1);
+        }
+        if status & sta::ERRORS != 0 {
+            self.write_reg(regs::STA, status & sta::ERRORS);
+        }
+        // The channel has moved on to the other half's block
+        let playing = if self.dma.current_block() == self.block_bus(0) { 0 } else { 1 };
+        self.fill(1 - playing, source);
+        Ok(true)
+    }
+
+    /// Stop the channel and both PWM channels
+    pub fn stop(&mut self) {
+        self.write_reg(regs::CTL, 0);
+        self.write_reg(regs::DMAC, 0);
+        self.dma.reset();
+    }
+
+    /// Bus address of control block `half`
+    fn block_bus(&self, half: usize) -> u32 {
+        dma::ram_bus_address(self.region.paddr + CB_OFFSET + half * core::mem::size_of::<ControlBlock>())
+    }
+
+    /// Pull one half's worth of samples into half `half` as duty words,
+    /// the same on both channels, and clean it for the channel
+    fn fill(&mut self, half: usize, source: &mut impl SampleSource) {
+        let words = (self.region.vaddr + half_offset(half)) as *mut u32;
+        let mut chunk = [0i16; MIX_CHUNK];
+        for start in (0..HALF_FRAMES).step_by(MIX_CHUNK) {
+            source.fill(&mut chunk);
+            for (i, &s) in chunk.iter().enumerate() {
+                let duty = pwm_duty(s, self.range);
+                // Safety: start + i < HALF_FRAMES, two words each, inside
+                // the half
+                unsafe {
+                    words.add(2 * (start + i)).write(duty);
+                    words.add(2 * (start + i) + 1).write(duty);
+                }
+            }
+        }
+        dma::clean_dcache_range(words as *const u8, HALF_BYTES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_for_common_rates() {
+        assert_eq!(pwm_range(PWM_CLOCK_HZ, 48_000), Some(2604));
+        assert_eq!(pwm_range(PWM_CLOCK_HZ, 44_100), Some(2834));
+        assert_eq!(pwm_range(PWM_CLOCK_HZ, rpi4_audio_protocol::MAX_RATE_HZ), Some(1302));
+        assert_eq!(pwm_range(1_000_000, 48_000), None);
+        // Silence sits mid-range
+        assert_eq!(pwm_duty(0, 2604), 1302);
+    }
+
+    #[test]
+    fn layout_fits() {
+        assert!(CB_OFFSET + 2 * core::mem::size_of::<ControlBlock>() <= SAMPLES_OFFSET);
+        assert_eq!(half_offset(1) + HALF_BYTES, DMA_REGION_SIZE);
+        assert_eq!(HALF_FRAMES % MIX_CHUNK, 0);
+        assert_eq!(PLAY & ctl::CLRF, 0);
+    }
+
+    #[test]
+    fn region_checks() {
+        let region = DmaRegion { vaddr: 0, paddr: 0x3E86_1000, size: 0x3000 };
+        assert!(region.fits(DMA_REGION_SIZE));
+        assert!(!DmaRegion { size: 0x1000, ..region }.fits(DMA_REGION_SIZE));
+        assert!(!DmaRegion { paddr: 0x3FFF_F000, ..region }.fits(DMA_REGION_SIZE));
+    }
+
+    #[test]
+    fn errors_carry_their_domain() {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let e = PdError::from(PwmError::BadRate(1_000_000));
+        assert_eq!((e.domain(), e.kind()), (ErrorDomain::Audio, ErrorKind::Unsupported));
+        assert_eq!(e.code().detail, u16::MAX);
+    }
+}
@@ -0,0 +1,91 @@
+//! BCM2711 clock manager: the PCM and PWM clocks.
+//!
+//! Each clock is a control and a divisor register; the divisor is 12.12
+//! fixed point and may only change while the clock is stopped, and every
+//! write carries the manager's password. The PWM clock feeds both PWM
+//! blocks, so the backlight and the headphone jack cannot run from
+//! different rates in one system.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// BCM2711 clock manager base address
+pub const CM_BASE: usize = 0xFE10_1000;
+
+/// Crystal oscillator frequency
+pub const OSC_HZ: u32 = 54_000_000;
+
+/// PLLD on the Pi 4
+pub const PLLD_HZ: u32 = 750_000_000;
+
+/// Integer part limits of a clock manager divisor with MASH filtering
+pub const MIN_DIVI: u32 = 2;
+pub const MAX_DIVI: u32 = 4095;
+
+} // verus!
+
+/// Register bits
+mod cm {
+    /// Every clock manager write must carry this password
+    pub const PASSWD: u32 = 0x5A << 24;
+    pub const ENAB: u32 = 1 << 4;
+    pub const BUSY: u32 = 1 << 7;
+    /// First-order MASH filter, for fractional divisors
+    pub const MASH1: u32 = 1 << 9;
+}
+
+/// A clock manager clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clock {
+    /// Bit clock of the PCM/I2S block
+    Pcm,
+    /// Channel clock of the PWM blocks
+    Pwm,
+}
+
+impl Clock {
+    /// Offsets of the control and divisor registers from [`CM_BASE`]
+    const fn regs(self) -> (usize, usize) {
+        match self {
+            Clock::Pcm => (0x98, 0x9C),
+            Clock::Pwm => (0xA0, 0xA4),
+        }
+    }
+}
+
+/// What a clock divides down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Source {
+    /// The crystal, [`OSC_HZ`]
+    Osc = 1,
+    /// PLLD, [`PLLD_HZ`]
+    Plld = 6,
+}
+
+/// Stop, reprogram and restart `clock` from `source` with 12.12 divisor
+/// `div`; a fractional divisor is MASH filtered
+///
+/// # Safety
+/// `cm_base` must be the mapped clock manager register page, and nothing
+/// else may use `clock`.
+pub unsafe fn start_clock(cm_base: usize, clock: Clock, source: Source, div: u32) {
+    let (ctl, divr) = clock.regs();
+    let ctl = (cm_base + ctl) as *mut u32;
+    let divr = (cm_base + divr) as *mut u32;
+    let src = source as u32;
+    let mash = if div & 0xFFF != 0 { cm::MASH1 } else { 0 };
+
+    // The divisor may only change while the clock is stopped
+    ctl.write_volatile(cm::PASSWD | src);
+    while ctl.read_volatile() & cm::BUSY != 0 {
+        core::hint::spin_loop();
+    }
+    divr.write_volatile(cm::PASSWD | div);
+    ctl.write_volatile(cm::PASSWD | mash | src);
+    ctl.write_volatile(cm::PASSWD | mash | src | cm::ENAB);
+}
@@ -0,0 +1,431 @@
+//! BCM2711 legacy DMA channels.
+//!
+//! Channels 0-6 are full channels: a control block in RAM describes one
+//! transfer, linear or 2D, and names the block to run after it, so a
+//! channel can stop after one transfer or loop over a ring of them. The
+//! channel reads control blocks through their bus address, so they and the
+//! data they move must live in memory with a known physical address.
+//!
+//! # Addressing
+//!
+//! The engines see the VideoCore bus: peripherals at 0x7E00_0000 and RAM
+//! below 1 GiB through the uncached 0xC000_0000 alias.
+//!
+//! # Cache Maintenance
+//!
+//! Before a channel reads memory the CPU wrote through a cached mapping,
+//! [`clean_dcache_range`] writes the dirty lines back; after a channel
+//! writes such memory, [`clean_invalidate_dcache_range`] drops the stale
+//! lines. seL4 leaves EL0 cache maintenance by VA enabled (SCTLR_EL1.UCI),
+//! so a PD can do this without a kernel call.
+//!
+//! ## Verus Verification
+//! - Bus addresses land in the alias the engine expects
+//! - 2D `TXFR_LEN` and `STRIDE` words hold exactly the row length, row
+//!   count and skips asked for, with no field spilling into its neighbour
+//! - Cache maintenance covers every line of the range it is given
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// BCM2711 DMA controller base address (channels 0-14)
+pub const DMA_BASE: usize = 0xFE00_7000;
+
+/// Register stride between channels
+pub const CHANNEL_STRIDE: usize = 0x100;
+
+/// Highest full (non-lite) channel; only full channels have 2D mode
+pub const MAX_FULL_CHANNEL: u8 = 6;
+
+/// Largest transfer a full channel can do with one control block
+pub const MAX_TRANSFER_LEN: u32 = 1 << 30;
+
+/// GIC interrupt ID of DMA channel 0 (SPI 80); channels 1-6 follow on
+pub const DMA_CHANNEL0_IRQ: usize = 112;
+
+/// Longest row of a 2D transfer, in bytes (`XLENGTH`, 16 bits)
+pub const MAX_ROW_BYTES: u32 = 0xFFFF;
+
+/// Most rows in one 2D transfer (`YLENGTH` + 1, 14 bits)
+pub const MAX_ROWS: u32 = 0x4000;
+
+/// Largest skip between rows (`S_STRIDE`/`D_STRIDE`, signed 16 bits)
+pub const MAX_SKIP: u32 = 0x7FFF;
+
+/// Bus address the DMA engine uses for an ARM physical RAM address
+pub fn ram_bus_address(phys: usize) -> (bus: u32)
+    requires
+        phys < 0x4000_0000,
+    ensures
+        bus == (phys as u32) | 0xC000_0000,
+{
+    (phys as u32) | 0xC000_0000
+}
+
+/// Bus address of a peripheral register from its ARM physical address
+pub fn peripheral_bus_address(phys: usize) -> (bus: u32)
+    requires
+        0xFE00_0000 <= phys < 0xFF80_0000,
+    ensures
+        bus == ((phys - 0xFE00_0000) as u32) | 0x7E00_0000,
+{
+    ((phys - 0xFE00_0000) as u32) | 0x7E00_0000
+}
+
+/// `TXFR_LEN` for `rows` runs of `row_bytes`
+pub fn transfer_length_2d(row_bytes: u32, rows: u32) -> (len: u32)
+    requires
+        0 < row_bytes <= MAX_ROW_BYTES,
+        0 < rows <= MAX_ROWS,
+    ensures
+        len & 0xFFFF == row_bytes,
+        len >> 16 == rows - 1,
+{
+    let y = rows - 1;
+    assert((y << 16 | row_bytes) & 0xFFFF == row_bytes && (y << 16 | row_bytes) >> 16 == y) by (bit_vector)
+        requires row_bytes <= 0xFFFF, y < 0x4000;
+    (y << 16) | row_bytes
+}
+
+/// `STRIDE` for skipping `src_skip` source and `dst_skip` destination
+/// bytes after each row
+pub fn stride_2d(src_skip: u32, dst_skip: u32) -> (stride: u32)
+    requires
+        src_skip <= MAX_SKIP,
+        dst_skip <= MAX_SKIP,
+    ensures
+        stride & 0xFFFF == src_skip,
+        stride >> 16 == dst_skip,
+{
+    assert((dst_skip << 16 | src_skip) & 0xFFFF == src_skip && (dst_skip << 16 | src_skip) >> 16 == dst_skip)
+        by (bit_vector)
+        requires src_skip <= 0x7FFF, dst_skip <= 0x7FFF;
+    (dst_skip << 16) | src_skip
+}
+
+/// First and one-past-last address of the cache lines covering
+/// `[addr, addr + len)`
+pub fn line_range(addr: usize, len: usize, line: usize) -> (r: (usize, usize))
+    requires
+        line > 0,
+        addr as int + len as int + line as int <= usize::MAX as int,
+    ensures
+        r.0 <= addr,
+        r.0 % line as int == 0,
+        r.1 >= addr as int + len as int,
+{
+    let start = addr - addr % line;
+    proof {
+        assert((addr - addr % line) % line as int == 0) by (nonlinear_arith)
+            requires line > 0;
+    }
+    let end = addr + len;
+    (start, end + (line - end % line) % line)
+}
+
+} // verus!
+
+/// Per-channel register offsets
+pub mod regs {
+    pub const CS: usize = 0x00;        // Control and Status
+    pub const CONBLK_AD: usize = 0x04; // Control Block Address
+    pub const TI: usize = 0x08;        // Transfer Information (from CB)
+    pub const TXFR_LEN: usize = 0x14;  // Remaining length (from CB)
+    pub const DEBUG: usize = 0x20;     // Debug / error flags
+
+    /// Global enable register, offset from the controller base
+    pub const ENABLE: usize = 0xFF0;
+}
+
+/// Channel CS bits
+pub mod cs {
+    pub const ACTIVE: u32 = 1 << 0;
+    pub const END: u32 = 1 << 1;       // Write 1 to clear
+    pub const INT: u32 = 1 << 2;       // Write 1 to clear
+    pub const ERROR: u32 = 1 << 8;
+    pub const WAIT_FOR_OUTSTANDING_WRITES: u32 = 1 << 28;
+    pub const ABORT: u32 = 1 << 30;
+    pub const RESET: u32 = 1 << 31;
+
+    /// AXI priority and panic priority (0-15 each) a channel's transfers
+    /// run at
+    pub const fn priority(axi: u32, panic: u32) -> u32 {
+        ((axi & 0xF) << 16) | ((panic & 0xF) << 20)
+    }
+}
+
+/// DEBUG error flags (write 1 to clear)
+const DEBUG_ERRORS: u32 = 0b111;
+
+/// Control block TI bits
+pub mod ti {
+    pub const INTEN: u32 = 1 << 0;
+    pub const TDMODE: u32 = 1 << 1;
+    pub const WAIT_RESP: u32 = 1 << 3;
+    pub const DEST_INC: u32 = 1 << 4;
+    /// 128-bit destination writes
+    pub const DEST_WIDTH: u32 = 1 << 5;
+    pub const DEST_DREQ: u32 = 1 << 6;
+    pub const DEST_IGNORE: u32 = 1 << 7;
+    pub const SRC_INC: u32 = 1 << 8;
+    /// 128-bit source reads
+    pub const SRC_WIDTH: u32 = 1 << 9;
+    pub const SRC_DREQ: u32 = 1 << 10;
+
+    /// Beats per burst, minus one
+    pub const fn burst(len: u32) -> u32 {
+        (len & 0xF) << 12
+    }
+
+    /// Peripheral whose DREQ paces the transfer
+    pub const fn permap(dreq: u32) -> u32 {
+        (dreq & 0x1F) << 16
+    }
+}
+
+/// DREQ peripheral numbers
+pub mod dreq {
+    /// PWM1, the headphone jack's block (shared with DSI0)
+    pub const PWM1: u32 = 1;
+    pub const PCM_TX: u32 = 2;
+    pub const SPI0_TX: u32 = 6;
+    pub const SPI0_RX: u32 = 7;
+}
+
+/// DMA control block, read by the channel from RAM
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C, align(32))]
+pub struct ControlBlock {
+    pub ti: u32,
+    pub source_ad: u32,
+    pub dest_ad: u32,
+    pub txfr_len: u32,
+    pub stride: u32,
+    /// Bus address of the next control block; 0 stops the channel
+    pub nextconbk: u32,
+    _reserved: [u32; 2],
+}
+
+impl ControlBlock {
+    /// One transfer of `len` bytes (at most [`MAX_TRANSFER_LEN`]),
+    /// followed by the block at `next`
+    pub const fn new(ti: u32, source_ad: u32, dest_ad: u32, len: u32, next: u32) -> Self {
+        Self { ti, source_ad, dest_ad, txfr_len: len, stride: 0, nextconbk: next, _reserved: [0; 2] }
+    }
+
+    /// A 2D transfer of `rows` runs of `row_bytes` with no successor,
+    /// skipping `src_skip` and `dst_skip` bytes after each row
+    pub fn rect(ti: u32, source_ad: u32, dest_ad: u32, row_bytes: u32, rows: u32, src_skip: u32, dst_skip: u32) -> Self {
+        Self {
+            ti: ti | ti::TDMODE,
+            source_ad,
+            dest_ad,
+            txfr_len: transfer_length_2d(row_bytes, rows),
+            stride: stride_2d(src_skip, dst_skip),
+            nextconbk: 0,
+            _reserved: [0; 2],
+        }
+    }
+}
+
+/// What a channel reports when it stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaStatus {
+    /// Still transferring
+    Active,
+    /// Reached the end of its last control block
+    Done,
+    /// Stopped on an AXI read or FIFO error
+    Error,
+}
+
+/// One legacy DMA channel
+pub struct DmaChannel {
+    /// Virtual address of this channel's registers
+    base: usize,
+    channel: u8,
+    /// CS priority bits its transfers run at
+    priority: u32,
+}
+
+impl DmaChannel {
+    /// Claim `channel` of the controller mapped at `controller_base`, to
+    /// run at `priority` (from [`cs::priority`]); `None` for a lite
+    /// channel
+    ///
+    /// # Safety
+    /// `controller_base` must be the mapped DMA register page, and nothing
+    /// else (including the GPU firmware) may use `channel`.
+    pub unsafe fn new(controller_base: usize, channel: u8, priority: u32) -> Option<Self> {
+        if channel > MAX_FULL_CHANNEL {
+            return None;
+        }
+        let enable = (controller_base + regs::ENABLE) as *mut u32;
+        enable.write_volatile(enable.read_volatile() | (1 << channel));
+        let mut dma = Self { base: controller_base + channel as usize * CHANNEL_STRIDE, channel, priority };
+        dma.reset();
+        Some(dma)
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// GIC interrupt ID of this channel
+    pub fn irq(&self) -> usize {
+        DMA_CHANNEL0_IRQ + self.channel as usize
+    }
+
+    /// Stop the channel and clear its status and error flags
+    pub fn reset(&mut self) {
+        self.write_reg(regs::CS, cs::RESET);
+        self.write_reg(regs::CS, cs::END | cs::INT);
+        self.write_reg(regs::DEBUG, DEBUG_ERRORS);
+    }
+
+    /// Start the chain of control blocks at bus address `cb_bus`, which
+    /// must be 32-byte aligned
+    ///
+    /// The control blocks (and any memory they read) must already have been
+    /// cleaned from the data cache.
+    pub fn start(&mut self, cb_bus: u32) {
+        self.write_reg(regs::CS, cs::END | cs::INT);
+        self.write_reg(regs::CONBLK_AD, cb_bus);
+        self.write_reg(regs::CS, cs::ACTIVE | cs::WAIT_FOR_OUTSTANDING_WRITES | self.priority);
+    }
+
+    /// Current state of the channel
+    pub fn status(&self) -> DmaStatus {
+        let status = self.read_reg(regs::CS);
+        if status & cs::ERROR != 0 {
+            DmaStatus::Error
+        } else if status & cs::ACTIVE != 0 {
+            DmaStatus::Active
+        } else {
+            DmaStatus::Done
+        }
+    }
+
+    /// Has the channel stopped on an AXI read or FIFO error?
+    pub fn failed(&self) -> bool {
+        self.status() == DmaStatus::Error
+    }
+
+    /// Bus address of the control block the channel is working through
+    pub fn current_block(&self) -> u32 {
+        self.read_reg(regs::CONBLK_AD)
+    }
+
+    /// Did the channel raise its interrupt? Clears it, so the
+    /// (level-triggered) IRQ drops before the Microkit channel is acked.
+    pub fn take_interrupt(&mut self) -> bool {
+        let status = self.read_reg(regs::CS);
+        if status & cs::INT == 0 {
+            return false;
+        }
+        // A looping channel is still running: writing ACTIVE or the
+        // priorities as 0 would pause or demote it
+        let running = status & cs::ACTIVE;
+        self.write_reg(regs::CS, cs::INT | cs::END | running | cs::WAIT_FOR_OUTSTANDING_WRITES | self.priority);
+        true
+    }
+}
+
+/// Data cache line size, from CTR_EL0.DminLine
+#[cfg(target_arch = "aarch64")]
+fn dcache_line() -> usize {
+    let ctr: u64;
+    unsafe {
+        core::arch::asm!("mrs {}, ctr_el0", out(reg) ctr, options(nomem, nostack));
+    }
+    4 << ((ctr >> 16) & 0xF)
+}
+
+/// Write dirty lines covering `[ptr, ptr + len)` back to memory, so a DMA
+/// read sees what the CPU wrote
+pub fn clean_dcache_range(ptr: *const u8, len: usize) {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        let line = dcache_line();
+        let (mut addr, end) = line_range(ptr as usize, len, line);
+        while addr < end {
+            core::arch::asm!("dc cvac, {}", in(reg) addr, options(nostack));
+            addr += line;
+        }
+        core::arch::asm!("dsb sy", options(nostack));
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    let _ = (ptr, len);
+}
+
+/// Clean and invalidate lines covering `[ptr, ptr + len)`, so the CPU
+/// rereads memory a DMA transfer wrote
+pub fn clean_invalidate_dcache_range(ptr: *const u8, len: usize) {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        let line = dcache_line();
+        let (mut addr, end) = line_range(ptr as usize, len, line);
+        while addr < end {
+            core::arch::asm!("dc civac, {}", in(reg) addr, options(nostack));
+            addr += line;
+        }
+        core::arch::asm!("dsb sy", options(nostack));
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    let _ = (ptr, len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bus_addresses() {
+        assert_eq!(ram_bus_address(0x3E70_0000), 0xFE70_0000);
+        // SPI0 FIFO and PWM1 FIFO
+        assert_eq!(peripheral_bus_address(0xFE20_4004), 0x7E20_4004);
+        assert_eq!(peripheral_bus_address(0xFE20_C818), 0x7E20_C818);
+    }
+
+    #[test]
+    fn linear_block_chains() {
+        let cb = ControlBlock::new(ti::SRC_INC | ti::DEST_DREQ | ti::permap(dreq::PWM1), 0xC000_0040, 0x7E20_C818, 4096, 0xC000_0020);
+        assert_eq!(cb.ti >> 16, dreq::PWM1);
+        assert_eq!((cb.txfr_len, cb.stride, cb.nextconbk), (4096, 0, 0xC000_0020));
+        assert_eq!(core::mem::size_of::<ControlBlock>(), 32);
+        assert_eq!(core::mem::align_of::<ControlBlock>(), 32);
+    }
+
+    #[test]
+    fn rect_block_packs_the_2d_registers() {
+        // A 100x50 ARGB rectangle on a 1280-pixel-pitch screen
+        let cb = ControlBlock::rect(ti::DEST_INC, 0xC000_0020, 0xFE87_6000, 400, 50, 0, 5120 - 400);
+        assert_ne!(cb.ti & ti::TDMODE, 0);
+        assert_eq!(cb.txfr_len, (49 << 16) | 400);
+        assert_eq!(cb.stride, 4720 << 16);
+        assert_eq!(cb.nextconbk, 0);
+    }
+
+    #[test]
+    fn priority_fields() {
+        assert_eq!(cs::priority(8, 15), (8 << 16) | (15 << 20));
+        assert_eq!(cs::priority(0xF, 0xF) & (cs::ACTIVE | cs::INT | cs::END | cs::WAIT_FOR_OUTSTANDING_WRITES), 0);
+    }
+
+    #[test]
+    fn lines_cover_the_range() {
+        assert_eq!(line_range(0x1008, 0x40, 64), (0x1000, 0x1080));
+        assert_eq!(line_range(0x1000, 0x40, 64), (0x1000, 0x1040));
+        assert_eq!(line_range(0x1000, 0, 64), (0x1000, 0x1000));
+    }
+}
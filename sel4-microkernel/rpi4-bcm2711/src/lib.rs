@@ -0,0 +1,22 @@
+//! BCM2711 peripherals more than one driver programs.
+//!
+//! The SPI panel (`rpi4-spi-display`), the audio outputs (`rpi4-audio`)
+//! and the HDMI blitter (`rpi4-graphics`) all drive legacy DMA channels,
+//! and the panel backlight and the headphone jack both run from the PWM
+//! blocks on a clock manager clock. The register layouts and the code
+//! that touches them live here once; each driver keeps only its own use
+//! of them (which channel, at what priority, paced by which peripheral).
+//!
+//! - [`dma`]: control blocks, channels, bus addresses and the cache
+//!   maintenance around transfers
+//! - [`pwm`]: PWM0/PWM1 registers and bits
+//! - [`clock`]: the clock manager's PCM and PWM clocks
+//!
+//! Nothing here is PD-specific: every function takes the virtual address
+//! its caller mapped the registers at.
+
+#![no_std]
+
+pub mod clock;
+pub mod dma;
+pub mod pwm;
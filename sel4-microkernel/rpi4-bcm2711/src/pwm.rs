@@ -0,0 +1,97 @@
+//! BCM2711 PWM blocks.
+//!
+//! PWM0 and PWM1 share one 4 KiB page, PWM1 at [`PWM1_PAGE_OFFSET`], and
+//! both run from the clock manager's PWM clock
+//! ([`Clock::Pwm`](crate::clock::Clock::Pwm)). Each block has two
+//! channels; a channel either outputs its data register or takes words
+//! from the block's FIFO, which a DMA channel can keep fed (DREQ
+//! [`PWM1`](crate::dma::dreq::PWM1) for PWM1).
+//!
+//! In mark-space mode every period of `range` clock ticks is high for
+//! `duty` ticks, then low; the panel backlight (PWM0 channel 1 on GPIO18)
+//! and the headphone jack (both PWM1 channels on GPIO40/41) run that way.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// BCM2711 PWM0 base address
+pub const PWM0_BASE: usize = 0xFE20_C000;
+
+/// BCM2711 PWM1 base address, in the page that also holds PWM0
+pub const PWM1_BASE: usize = 0xFE20_C800;
+
+/// Offset of PWM1 in its 4 KiB page
+pub const PWM1_PAGE_OFFSET: usize = 0x800;
+
+} // verus!
+
+/// PWM register offsets from the block base
+pub mod regs {
+    pub const CTL: usize = 0x00;   // Control
+    pub const STA: usize = 0x04;   // Status
+    pub const DMAC: usize = 0x08;  // DMA configuration
+    pub const RNG1: usize = 0x10;  // Channel 1 range
+    pub const DAT1: usize = 0x14;  // Channel 1 data (duty)
+    pub const FIF1: usize = 0x18;  // FIFO input (both channels)
+    pub const RNG2: usize = 0x20;  // Channel 2 range
+    pub const DAT2: usize = 0x24;  // Channel 2 data (duty)
+}
+
+/// CTL bits
+pub mod ctl {
+    /// Channel 1 enable
+    pub const PWEN1: u32 = 1 << 0;
+    /// Channel 1 takes its data from the FIFO
+    pub const USEF1: u32 = 1 << 5;
+    /// Clear the FIFO (self-clearing)
+    pub const CLRF: u32 = 1 << 6;
+    /// Channel 1 in mark-space rather than balanced mode
+    pub const MSEN1: u32 = 1 << 7;
+    pub const PWEN2: u32 = 1 << 8;
+    pub const USEF2: u32 = 1 << 13;
+    pub const MSEN2: u32 = 1 << 15;
+}
+
+/// STA bits (the error flags are write 1 to clear)
+pub mod sta {
+    pub const WERR: u32 = 1 << 2;
+    pub const RERR: u32 = 1 << 3;
+    /// A channel found the FIFO empty and repeated its last duty
+    pub const GAPO1: u32 = 1 << 4;
+    pub const GAPO2: u32 = 1 << 5;
+    pub const BERR: u32 = 1 << 8;
+
+    pub const GAPS: u32 = GAPO1 | GAPO2;
+    pub const ERRORS: u32 = WERR | RERR | GAPS | BERR;
+}
+
+/// DMAC bits
+pub mod dmac {
+    /// DMA requests enabled
+    pub const ENAB: u32 = 1 << 31;
+
+    /// Ask for data below `dreq` FIFO words, and raise panic below `panic`
+    pub const fn thresholds(dreq: u32, panic: u32) -> u32 {
+        ((panic & 0xFF) << 8) | (dreq & 0xFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pwm1_shares_pwm0_page() {
+        assert_eq!(PWM1_BASE, PWM0_BASE + PWM1_PAGE_OFFSET);
+        assert_eq!(PWM1_BASE & 0xFFF, PWM1_PAGE_OFFSET);
+    }
+
+    #[test]
+    fn dmac_fields() {
+        assert_eq!(dmac::ENAB | dmac::thresholds(7, 7), (1 << 31) | (7 << 8) | 7);
+    }
+}
@@ -0,0 +1,911 @@
+//! Verified wall-clock protocol between the Timer PD and its clients.
+//!
+//! Nothing on the board knows the date: the system timer only counts
+//! microseconds since power-on. The Timer PD keeps the wall-clock time. A
+//! setter (the Input PD, from a time typed on the UART console; later a
+//! network PD) hands it the current time over a protected procedure call,
+//! and the Timer PD publishes it on the clock page, which readers map
+//! read-only:
+//!
+//! ```text
+//! ┌──────────┐  SET_TIME (PPC)  ┌──────────┐  clock_page (rw / r)  ┌─────────────┐
+//! │ Input PD │ ───────────────► │ Timer PD │ ────────────────────► │ Photoframe  │
+//! └──────────┘                  └──────────┘                       │ PD (clock)  │
+//!                                                                  └─────────────┘
+//! ```
+//!
+//! ## The clock page
+//!
+//! The page holds one [`ClockTime`]: a wall-clock time and the system timer
+//! reading it was taken at. Readers extrapolate from the pair with their own
+//! timer reading, so the Timer PD only writes the page when the time is set.
+//! The page is a sequence lock: the writer makes `seq` odd, writes, and makes
+//! it even again; a reader that sees `seq` odd or changed under it retries,
+//! at most [`READ_ATTEMPTS`] times, and never blocks the writer.
+//!
+//! ## Calls
+//!
+//! ```text
+//! Label  Operation  MR0             MR1
+//! 1      SET_TIME   seconds since   0 to keep the UTC offset, else
+//!                   1970-01-01 UTC  OFFSET_PRESENT | offset in minutes (i16)
+//! ```
+//!
+//! The reply label is zero or a `PdError` wire code. Where the time came
+//! from is not an argument: the Timer PD takes it from the channel the call
+//! arrived on ([`source_for_channel`]), so a client cannot claim to be
+//! another.
+//!
+//! ## Ticks
+//!
+//! The Timer PD also owns system timer compare channel 1 and notifies a
+//! client [`TICK_HZ`] times a second on [`CLIENT_TICK_CHANNEL_ID`]. A tick
+//! carries nothing: it only wakes the client, which reads the counter
+//! itself (`rpi4_graphics::Instant`) to see which of its deadlines are due,
+//! so a late or dropped tick delays work without miscounting time.
+//!
+//! ## Verification
+//!
+//! [`civil_from_days`] is proven to give a real month and day for every day
+//! up to [`MAX_DAYS`], [`local_time`] a minute of the day below
+//! [`MINUTES_PER_DAY`], and [`in_daily_window`] to match its definition for
+//! windows on either side of midnight. [`decode_request`] refuses times past
+//! [`MAX_EPOCH_S`] and offsets past [`MAX_UTC_OFFSET_MIN`], so nothing out of
+//! range reaches the page. The isolation proofs at the end show the clock
+//! page is all a reader is granted.
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::new_without_default)]
+// Verus proves plain comparisons; `RangeInclusive::contains` it does not know
+#![allow(clippy::manual_range_contains)]
+
+use core::fmt;
+use core::ptr::{addr_of, addr_of_mut, read_volatile, write_volatile};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+use verus_builtin_macros::verus;
+
+verus! {
+
+/// Input PD → Timer PD protected procedure channel (the Input PD's end)
+pub const INPUT_CLOCK_CHANNEL_ID: usize = 2;
+
+/// Timer PD end of the channel from the Input PD: times typed on the UART
+pub const TIMER_UART_CHANNEL_ID: usize = 1;
+/// Timer PD end of the channel from a network PD; no system wires one yet
+pub const TIMER_NETWORK_CHANNEL_ID: usize = 2;
+/// Timer PD end of the tick notification to the Photoframe PD
+pub const TIMER_TICK_CHANNEL_ID: usize = 3;
+/// Timer PD's system timer compare 1 IRQ (GIC SPI 65)
+pub const TIMER_IRQ_CHANNEL_ID: usize = 4;
+
+/// Client end of the tick notification
+pub const CLIENT_TICK_CHANNEL_ID: usize = 2;
+/// Tick notifications per second
+pub const TICK_HZ: u32 = 10;
+
+// ============================================================================
+// MEMORY REGIONS
+// ============================================================================
+
+/// Clock page: written by the Timer PD, read by the clients
+pub const CLOCK_PAGE_VADDR: usize = 0x5_0D00_0000;
+pub const CLOCK_PAGE_SIZE: usize = 0x1000;
+
+/// System timer registers, mapped into the Timer PD: read/write, since it
+/// programs compare channel 1 for the tick
+pub const TIMER_PD_SYSTIMER_BASE: usize = 0x5_0A00_0000;
+pub const TIMER_PD_SYSTIMER_SIZE: usize = 0x1000;
+
+pub open spec fn in_clock_page_region(addr: usize) -> bool {
+    addr >= CLOCK_PAGE_VADDR && addr < CLOCK_PAGE_VADDR + CLOCK_PAGE_SIZE
+}
+
+pub open spec fn in_systimer_region(addr: usize) -> bool {
+    addr >= TIMER_PD_SYSTIMER_BASE && addr < TIMER_PD_SYSTIMER_BASE + TIMER_PD_SYSTIMER_SIZE
+}
+
+/// Specification: can the Timer PD access this address?
+pub open spec fn timer_pd_can_access(addr: usize) -> bool {
+    in_systimer_region(addr) || in_clock_page_region(addr)
+}
+
+/// Specification: what this protocol grants a reader
+pub open spec fn clock_reader_can_access(addr: usize) -> bool {
+    in_clock_page_region(addr)
+}
+
+// ============================================================================
+// TIME RANGE
+// ============================================================================
+
+/// Seconds in a day
+pub const SECONDS_PER_DAY: u64 = 86_400;
+/// Minutes in a day
+pub const MINUTES_PER_DAY: u32 = 1440;
+
+/// The last second a time may name: 9999-12-31 23:59:59 UTC
+pub const MAX_EPOCH_S: u64 = 253_402_300_799;
+/// Days from 1970-01-01 to 9999-12-31
+pub const MAX_DAYS: u64 = 2_932_896;
+
+/// Widest UTC offset in use (UTC+14, Line Islands), in minutes
+pub const MAX_UTC_OFFSET_MIN: i16 = 14 * 60;
+
+pub open spec fn valid_utc_offset(offset: i16) -> bool {
+    -MAX_UTC_OFFSET_MIN <= offset <= MAX_UTC_OFFSET_MIN
+}
+
+/// Where the clock's time came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeSource {
+    /// Never set: the clock page only carries the UTC offset
+    Unset,
+    /// Typed on the UART console
+    Uart,
+    /// From the network
+    Network,
+}
+
+/// The source a set-time call on Timer PD channel `channel` speaks for
+pub fn source_for_channel(channel: usize) -> (s: Option<TimeSource>)
+    ensures
+        s != Some(TimeSource::Unset),
+        s.is_some() <==> (channel == TIMER_UART_CHANNEL_ID || channel == TIMER_NETWORK_CHANNEL_ID),
+{
+    if channel == TIMER_UART_CHANNEL_ID {
+        Some(TimeSource::Uart)
+    } else if channel == TIMER_NETWORK_CHANNEL_ID {
+        Some(TimeSource::Network)
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// OPERATIONS
+// ============================================================================
+
+pub const OP_SET_TIME: u64 = 1;
+
+/// Set in MR1 when it carries a UTC offset
+pub const OFFSET_PRESENT: u64 = 1 << 16;
+
+/// A decoded set-time call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetTime {
+    /// Seconds since 1970-01-01 00:00 UTC
+    pub epoch_s: u64,
+    /// New UTC offset in minutes, or `None` to keep the current one
+    pub utc_offset_min: Option<i16>,
+}
+
+/// Decode a call from its label and first two message registers
+pub fn decode_request(label: u64, epoch_s: u64, offset: u64) -> (r: Option<SetTime>)
+    ensures
+        r.is_some() ==> label == OP_SET_TIME,
+        r.is_some() ==> r.unwrap().epoch_s == epoch_s && epoch_s <= MAX_EPOCH_S,
+        r.is_some() && r.unwrap().utc_offset_min.is_some()
+            ==> valid_utc_offset(r.unwrap().utc_offset_min.unwrap()),
+{
+    if label != OP_SET_TIME || epoch_s > MAX_EPOCH_S {
+        return None;
+    }
+    if offset == 0 {
+        return Some(SetTime { epoch_s, utc_offset_min: None });
+    }
+    // Only the flag and a 16-bit offset; anything above is malformed
+    if offset >> 16 != 1 {
+        return None;
+    }
+    let minutes = (offset & 0xFFFF) as u16 as i16;
+    if minutes < -MAX_UTC_OFFSET_MIN || minutes > MAX_UTC_OFFSET_MIN {
+        return None;
+    }
+    Some(SetTime { epoch_s, utc_offset_min: Some(minutes) })
+}
+
+// ============================================================================
+// CALENDAR (Verified)
+// ============================================================================
+
+/// A date in the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CivilDate {
+    pub year: u32,
+    /// 1 = January
+    pub month: u8,
+    /// Day of the month, from 1
+    pub day: u8,
+}
+
+/// The date `days` days after 1970-01-01
+///
+/// Howard Hinnant's `civil_from_days`: shift to a year starting on March 1
+/// (so the leap day is last), split into 400-year eras, then day of era →
+/// year of era → day of year → month. All arithmetic is unsigned since
+/// `days` is never before the epoch.
+pub fn civil_from_days(days: u64) -> (d: CivilDate)
+    requires
+        days <= MAX_DAYS,
+    ensures
+        1 <= d.month <= 12,
+        1 <= d.day <= 31,
+        d.year <= 10_000,
+{
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    proof {
+        assert(doe < 146_097) by (nonlinear_arith)
+            requires era == z / 146_097, doe == z - era * 146_097;
+        assert(era <= 25) by (nonlinear_arith)
+            requires era == z / 146_097, z <= MAX_DAYS + 719_468;
+    }
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    proof {
+        assert(yoe <= 399) by (nonlinear_arith)
+            requires doe < 146_097, yoe == (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    }
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    proof {
+        assert(doy <= 365) by (nonlinear_arith)
+            requires
+                doe < 146_097,
+                yoe == (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365,
+                doy == doe - (365 * yoe + yoe / 4 - yoe / 100);
+    }
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    proof {
+        assert(mp <= 11) by (nonlinear_arith)
+            requires doy <= 365, mp == (5 * doy + 2) / 153;
+        assert(1 <= day <= 31) by (nonlinear_arith)
+            requires doy <= 365, mp == (5 * doy + 2) / 153, day == doy - (153 * mp + 2) / 5 + 1;
+    }
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1u64 } else { 0u64 };
+    CivilDate { year: year as u32, month: month as u8, day: day as u8 }
+}
+
+/// Day of the week `days` days after 1970-01-01, 0 = Sunday
+pub fn weekday(days: u64) -> (w: u8)
+    requires
+        days <= MAX_DAYS,
+    ensures
+        w < 7,
+{
+    // 1970-01-01 was a Thursday
+    ((days + 4) % 7) as u8
+}
+
+/// Wall-clock time in one time zone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalTime {
+    pub date: CivilDate,
+    /// 0 = Sunday
+    pub weekday: u8,
+    /// Minutes since local midnight
+    pub minute_of_day: u32,
+    pub second: u8,
+}
+
+/// `epoch_s` at a UTC offset of `utc_offset_min`, or `None` when the local
+/// time falls outside 1970-01-01 to 9999-12-31
+pub fn local_time(epoch_s: u64, utc_offset_min: i16) -> (t: Option<LocalTime>)
+    ensures
+        t.is_some() ==> t.unwrap().minute_of_day < MINUTES_PER_DAY,
+        t.is_some() ==> t.unwrap().second < 60,
+        t.is_some() ==> t.unwrap().weekday < 7,
+        t.is_some() ==> 1 <= t.unwrap().date.month <= 12 && 1 <= t.unwrap().date.day <= 31,
+{
+    if epoch_s > MAX_EPOCH_S {
+        return None;
+    }
+    let local = if utc_offset_min < 0 {
+        let back = (-(utc_offset_min as i64)) as u64 * 60;
+        if epoch_s < back {
+            return None;
+        }
+        epoch_s - back
+    } else {
+        epoch_s + utc_offset_min as u64 * 60
+    };
+    if local > MAX_EPOCH_S {
+        return None;
+    }
+    let days = local / SECONDS_PER_DAY;
+    let seconds = local % SECONDS_PER_DAY;
+    proof {
+        assert(days <= MAX_DAYS) by (nonlinear_arith)
+            requires days == local / SECONDS_PER_DAY, local <= MAX_EPOCH_S;
+    }
+    Some(LocalTime {
+        date: civil_from_days(days),
+        weekday: weekday(days),
+        minute_of_day: (seconds / 60) as u32,
+        second: (seconds % 60) as u8,
+    })
+}
+
+/// Is `minute` (of the day) inside the daily window from `start` up to
+/// `end`? A window whose start is after its end runs over midnight; an
+/// empty one (`start == end`) contains nothing.
+pub fn in_daily_window(minute: u32, start: u32, end: u32) -> (inside: bool)
+    requires
+        minute < MINUTES_PER_DAY,
+        start < MINUTES_PER_DAY,
+        end < MINUTES_PER_DAY,
+    ensures
+        start == end ==> !inside,
+        start < end ==> (inside <==> (start <= minute && minute < end)),
+        start > end ==> (inside <==> (minute >= start || minute < end)),
+{
+    if start <= end {
+        start <= minute && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// Wall-clock seconds at system timer reading `now_us`, for a time that was
+/// `epoch_s` at reading `at_us`. `None` if the reading is older than the
+/// time (a timer that went backwards) or the result would pass
+/// [`MAX_EPOCH_S`].
+pub fn wall_clock_at(epoch_s: u64, at_us: u64, now_us: u64) -> (t: Option<u64>)
+    ensures
+        t.is_some() ==> epoch_s <= t.unwrap() && t.unwrap() <= MAX_EPOCH_S,
+{
+    if now_us < at_us || epoch_s > MAX_EPOCH_S {
+        return None;
+    }
+    let elapsed = (now_us - at_us) / 1_000_000;
+    if elapsed > MAX_EPOCH_S - epoch_s {
+        return None;
+    }
+    Some(epoch_s + elapsed)
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: a reader cannot reach the system timer registers through this
+/// protocol
+proof fn reader_cannot_access_systimer()
+    ensures
+        forall|addr: usize| in_systimer_region(addr) ==> !clock_reader_can_access(addr)
+{
+}
+
+/// Prove: the clock page is the only region the Timer PD shares
+proof fn timer_and_reader_share_only_clock_page()
+    ensures
+        forall|addr: usize|
+            (timer_pd_can_access(addr) && clock_reader_can_access(addr))
+            ==> in_clock_page_region(addr)
+{
+}
+
+} // verus!
+
+// ============================================================================
+// CLOCK PAGE
+// ============================================================================
+
+/// Marks a clock page the Timer PD has published to ("CLK1")
+pub const CLOCK_MAGIC: u32 = 0x434C_4B31;
+
+/// Tries a reader makes before giving up on a page being rewritten
+pub const READ_ATTEMPTS: usize = 4;
+
+impl TimeSource {
+    /// Wire value on the clock page
+    pub const fn to_wire(self) -> u32 {
+        match self {
+            TimeSource::Unset => 0,
+            TimeSource::Uart => 1,
+            TimeSource::Network => 2,
+        }
+    }
+
+    pub const fn from_wire(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(TimeSource::Unset),
+            1 => Some(TimeSource::Uart),
+            2 => Some(TimeSource::Network),
+            _ => None,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            TimeSource::Unset => "unset",
+            TimeSource::Uart => "uart",
+            TimeSource::Network => "network",
+        }
+    }
+}
+
+/// The time published on the clock page
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockTime {
+    /// Seconds since 1970-01-01 00:00 UTC at `mono_us`
+    pub epoch_s: u64,
+    /// System timer reading (microseconds since power-on) at `epoch_s`
+    pub mono_us: u64,
+    /// Local time zone, in minutes east of UTC
+    pub utc_offset_min: i16,
+    pub source: TimeSource,
+}
+
+impl ClockTime {
+    /// No time yet, only the local time zone
+    pub const fn unset(utc_offset_min: i16) -> Self {
+        ClockTime { epoch_s: 0, mono_us: 0, utc_offset_min, source: TimeSource::Unset }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.source != TimeSource::Unset
+    }
+
+    /// Wall-clock seconds at system timer reading `now_us`; `None` until
+    /// the time has been set
+    pub fn epoch_at(&self, now_us: u64) -> Option<u64> {
+        if !self.is_set() {
+            return None;
+        }
+        wall_clock_at(self.epoch_s, self.mono_us, now_us)
+    }
+
+    /// Local time at system timer reading `now_us`
+    pub fn local_at(&self, now_us: u64) -> Option<LocalTime> {
+        local_time(self.epoch_at(now_us)?, self.utc_offset_min)
+    }
+}
+
+/// Layout of the clock page
+#[repr(C)]
+pub struct ClockPage {
+    magic: u32,
+    /// Odd while the Timer PD is writing
+    seq: AtomicU32,
+    epoch_s: u64,
+    mono_us: u64,
+    utc_offset_min: i32,
+    source: u32,
+}
+
+impl ClockPage {
+    /// Publish `time`
+    ///
+    /// # Safety
+    /// `base` must point to the mapped clock page, and the caller must be
+    /// its only writer.
+    pub unsafe fn publish(base: *mut u8, time: &ClockTime) {
+        let page = base as *mut ClockPage;
+        let seq = &(*page).seq;
+        let writing = seq.load(Ordering::Relaxed).wrapping_add(1) | 1;
+        seq.store(writing, Ordering::Relaxed);
+        fence(Ordering::Release);
+        write_volatile(addr_of_mut!((*page).magic), CLOCK_MAGIC);
+        write_volatile(addr_of_mut!((*page).epoch_s), time.epoch_s);
+        write_volatile(addr_of_mut!((*page).mono_us), time.mono_us);
+        write_volatile(addr_of_mut!((*page).utc_offset_min), time.utc_offset_min as i32);
+        write_volatile(addr_of_mut!((*page).source), time.source.to_wire());
+        seq.store(writing.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Read the published time; `None` if nothing has been published, the
+    /// page holds values no Timer PD would write, or it was being rewritten
+    /// on every one of [`READ_ATTEMPTS`] tries
+    ///
+    /// # Safety
+    /// `base` must point to the mapped clock page.
+    pub unsafe fn read(base: *const u8) -> Option<ClockTime> {
+        let page = base as *const ClockPage;
+        let seq = &(*page).seq;
+        for _ in 0..READ_ATTEMPTS {
+            let before = seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let magic = read_volatile(addr_of!((*page).magic));
+            let epoch_s = read_volatile(addr_of!((*page).epoch_s));
+            let mono_us = read_volatile(addr_of!((*page).mono_us));
+            let utc_offset_min = read_volatile(addr_of!((*page).utc_offset_min));
+            let source = read_volatile(addr_of!((*page).source));
+            fence(Ordering::Acquire);
+            if seq.load(Ordering::Relaxed) != before {
+                continue;
+            }
+            if magic != CLOCK_MAGIC {
+                return None;
+            }
+            let utc_offset_min = i16::try_from(utc_offset_min)
+                .ok()
+                .filter(|offset| offset.unsigned_abs() <= MAX_UTC_OFFSET_MIN as u16)?;
+            return Some(ClockTime { epoch_s, mono_us, utc_offset_min, source: TimeSource::from_wire(source)? });
+        }
+        None
+    }
+}
+
+// ============================================================================
+// SET-TIME CALLS
+// ============================================================================
+
+impl SetTime {
+    /// Call label
+    pub fn label(&self) -> u64 {
+        OP_SET_TIME
+    }
+
+    /// Values for message registers MR0-MR1
+    pub fn to_words(&self) -> [u64; 2] {
+        let offset = match self.utc_offset_min {
+            Some(minutes) => OFFSET_PRESENT | minutes as u16 as u64,
+            None => 0,
+        };
+        [self.epoch_s, offset]
+    }
+}
+
+/// Ctrl-T: starts typing a time on the UART console
+pub const SET_TIME_KEY: u8 = 0x14;
+
+/// Longest entry: 12 digits of seconds, then a sign and HHMM
+pub const SET_TIME_MAX_LEN: usize = 17;
+
+/// Parse a typed time: seconds since 1970 UTC, optionally followed by the
+/// UTC offset as `+HHMM` or `-HHMM` (`1760616000+0200`)
+pub fn parse_set_time(text: &[u8]) -> Option<SetTime> {
+    let split = text.iter().position(|&b| b == b'+' || b == b'-').unwrap_or(text.len());
+    let (seconds, zone) = text.split_at(split);
+    if seconds.is_empty() || seconds.len() > 12 {
+        return None;
+    }
+    let number = |digits: &[u8]| {
+        digits.iter().try_fold(0u64, |n, &b| b.is_ascii_digit().then(|| n * 10 + (b - b'0') as u64))
+    };
+    let epoch_s = number(seconds)?;
+    let utc_offset_min = match zone {
+        [] => None,
+        [sign, hhmm @ ..] if hhmm.len() == 4 => {
+            let (hours, minutes) = (number(&hhmm[..2])?, number(&hhmm[2..])?);
+            if minutes >= 60 {
+                return None;
+            }
+            let offset = (hours * 60 + minutes) as i16;
+            Some(if *sign == b'-' { -offset } else { offset })
+        }
+        _ => return None,
+    };
+    // The same checks the Timer PD makes on the call
+    let request = SetTime { epoch_s, utc_offset_min };
+    let [mr0, mr1] = request.to_words();
+    decode_request(OP_SET_TIME, mr0, mr1)
+}
+
+/// What a console byte did to a time entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryFeed {
+    /// No entry in progress: the byte is ordinary input
+    Pass(u8),
+    /// The byte was part of an entry
+    Held,
+    /// Enter finished a valid entry
+    Set(SetTime),
+    /// Enter finished an entry that does not parse
+    Rejected,
+    /// Escape or Ctrl-T abandoned the entry
+    Cancelled,
+}
+
+/// A time being typed on the UART console: Ctrl-T, the time as
+/// [`parse_set_time`] reads it, then Enter
+pub struct SetTimeEntry {
+    active: bool,
+    line: [u8; SET_TIME_MAX_LEN],
+    len: usize,
+    /// More was typed than an entry can hold
+    overflow: bool,
+}
+
+impl SetTimeEntry {
+    pub const fn new() -> Self {
+        SetTimeEntry { active: false, line: [0; SET_TIME_MAX_LEN], len: 0, overflow: false }
+    }
+
+    /// Is an entry in progress?
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Feed one console byte
+    pub fn feed(&mut self, byte: u8) -> EntryFeed {
+        if !self.active {
+            if byte != SET_TIME_KEY {
+                return EntryFeed::Pass(byte);
+            }
+            self.active = true;
+            self.len = 0;
+            self.overflow = false;
+            return EntryFeed::Held;
+        }
+        match byte {
+            0x1B | SET_TIME_KEY => {
+                self.active = false;
+                EntryFeed::Cancelled
+            }
+            b'\r' | b'\n' => {
+                self.active = false;
+                if self.overflow {
+                    return EntryFeed::Rejected;
+                }
+                parse_set_time(&self.line[..self.len]).map_or(EntryFeed::Rejected, EntryFeed::Set)
+            }
+            // Backspace / DEL
+            0x08 | 0x7F => {
+                self.len = self.len.saturating_sub(1);
+                EntryFeed::Held
+            }
+            _ => {
+                if self.len < SET_TIME_MAX_LEN {
+                    self.line[self.len] = byte;
+                    self.len += 1;
+                } else {
+                    self.overflow = true;
+                }
+                EntryFeed::Held
+            }
+        }
+    }
+}
+
+// ============================================================================
+// DISPLAY
+// ============================================================================
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+impl LocalTime {
+    pub fn hour(&self) -> u32 {
+        self.minute_of_day / 60
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minute_of_day % 60
+    }
+
+    /// Three-letter day name
+    pub fn weekday_name(&self) -> &'static str {
+        WEEKDAYS[self.weekday as usize % 7]
+    }
+
+    /// Three-letter month name
+    pub fn month_name(&self) -> &'static str {
+        MONTHS[(self.date.month as usize + 11) % 12]
+    }
+}
+
+/// `YYYY-MM-DD`
+impl fmt::Display for CivilDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// `YYYY-MM-DD HH:MM:SS`
+impl fmt::Display for LocalTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:02}:{:02}:{:02}", self.date, self.hour(), self.minute(), self.second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::format;
+
+    /// Days since 1970 of a date, by counting (for checking the fast path)
+    fn days_of(year: u32, month: u8, day: u8) -> u64 {
+        let leap = |y: u32| (y.is_multiple_of(4) && !y.is_multiple_of(100)) || y.is_multiple_of(400);
+        let month_days = |y: u32, m: u8| match m {
+            2 if leap(y) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        let mut days = 0;
+        for y in 1970..year {
+            days += if leap(y) { 366 } else { 365 };
+        }
+        for m in 1..month {
+            days += month_days(year, m);
+        }
+        days + day as u64 - 1
+    }
+
+    #[test]
+    fn known_dates() {
+        assert_eq!(civil_from_days(0), CivilDate { year: 1970, month: 1, day: 1 });
+        assert_eq!(weekday(0), 4);
+        // Leap days, century rules and the end of the range
+        for (year, month, day) in [(2000, 2, 29), (2024, 2, 29), (2100, 3, 1), (2026, 10, 16), (9999, 12, 31)] {
+            let days = days_of(year, month, day);
+            assert_eq!(civil_from_days(days), CivilDate { year, month, day });
+        }
+        assert_eq!(days_of(9999, 12, 31), MAX_DAYS);
+        // 2026-10-16 is a Friday
+        assert_eq!(weekday(days_of(2026, 10, 16)), 5);
+    }
+
+    #[test]
+    fn every_day_follows_the_last() {
+        let mut prev = civil_from_days(0);
+        for days in 1..=days_of(2401, 1, 1) {
+            let date = civil_from_days(days);
+            let next_day = date == CivilDate { day: prev.day + 1, ..prev };
+            let next_month = date.day == 1 && date == CivilDate { month: prev.month + 1, day: 1, ..prev };
+            let next_year = date == CivilDate { year: prev.year + 1, month: 1, day: 1 } && prev.month == 12;
+            assert!(next_day || next_month || next_year, "{} after {}", date, prev);
+            prev = date;
+        }
+    }
+
+    #[test]
+    fn local_time_applies_the_offset() {
+        // 2023-11-14 22:13:20 UTC
+        let t = local_time(1_700_000_000, 0).unwrap();
+        assert_eq!(format!("{}", t), "2023-11-14 22:13:20");
+        assert_eq!((t.weekday_name(), t.month_name()), ("TUE", "NOV"));
+        // Two hours east is past midnight
+        let t = local_time(1_700_000_000, 120).unwrap();
+        assert_eq!(format!("{}", t), "2023-11-15 00:13:20");
+        assert_eq!(t.weekday_name(), "WED");
+        // Before 1970 locally, and past the end of the range
+        assert_eq!(local_time(60, -5 * 60), None);
+        assert_eq!(local_time(MAX_EPOCH_S, 60), None);
+        assert_eq!(local_time(MAX_EPOCH_S + 1, 0), None);
+        assert_eq!(local_time(MAX_EPOCH_S, -60).map(|t| t.minute_of_day), Some(22 * 60 + 59));
+    }
+
+    #[test]
+    fn daily_windows() {
+        // 22:00 to 07:00 spans midnight
+        let (start, end) = (22 * 60, 7 * 60);
+        assert!(in_daily_window(23 * 60, start, end));
+        assert!(in_daily_window(0, start, end));
+        assert!(in_daily_window(start, start, end));
+        assert!(!in_daily_window(end, start, end));
+        assert!(!in_daily_window(12 * 60, start, end));
+        // 09:00 to 17:00 does not
+        assert!(in_daily_window(9 * 60, 9 * 60, 17 * 60));
+        assert!(!in_daily_window(17 * 60, 9 * 60, 17 * 60));
+        assert!(!in_daily_window(8 * 60, 9 * 60, 17 * 60));
+        // Empty
+        assert!(!in_daily_window(5, 5, 5));
+    }
+
+    #[test]
+    fn wall_clock_extrapolates_from_the_timer() {
+        assert_eq!(wall_clock_at(1000, 5_000_000, 5_000_000), Some(1000));
+        assert_eq!(wall_clock_at(1000, 5_000_000, 7_999_999), Some(1002));
+        assert_eq!(wall_clock_at(1000, 5_000_000, 4_999_999), None);
+        assert_eq!(wall_clock_at(MAX_EPOCH_S, 0, 1_000_000), None);
+
+        let time = ClockTime { epoch_s: 1_700_000_000, mono_us: 1_000_000, utc_offset_min: 0, source: TimeSource::Uart };
+        assert_eq!(time.epoch_at(61_000_000), Some(1_700_000_060));
+        assert_eq!(ClockTime::unset(60).epoch_at(61_000_000), None);
+        assert_eq!(ClockTime::unset(60).local_at(0), None);
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        for request in [
+            SetTime { epoch_s: 1_700_000_000, utc_offset_min: None },
+            SetTime { epoch_s: 0, utc_offset_min: Some(-MAX_UTC_OFFSET_MIN) },
+            SetTime { epoch_s: MAX_EPOCH_S, utc_offset_min: Some(330) },
+            SetTime { epoch_s: 1, utc_offset_min: Some(-1) },
+        ] {
+            let [mr0, mr1] = request.to_words();
+            assert_eq!(decode_request(request.label(), mr0, mr1), Some(request));
+        }
+        assert_eq!(decode_request(OP_SET_TIME + 1, 0, 0), None);
+        assert_eq!(decode_request(OP_SET_TIME, MAX_EPOCH_S + 1, 0), None);
+        // Offset past UTC+14, a set flag with garbage above it, no flag
+        assert_eq!(decode_request(OP_SET_TIME, 0, OFFSET_PRESENT | 841), None);
+        assert_eq!(decode_request(OP_SET_TIME, 0, (OFFSET_PRESENT << 1) | 60), None);
+        assert_eq!(decode_request(OP_SET_TIME, 0, 60), None);
+    }
+
+    #[test]
+    fn sources_come_from_channels() {
+        assert_eq!(source_for_channel(TIMER_UART_CHANNEL_ID), Some(TimeSource::Uart));
+        assert_eq!(source_for_channel(TIMER_NETWORK_CHANNEL_ID), Some(TimeSource::Network));
+        assert_eq!(source_for_channel(0), None);
+        assert_eq!(source_for_channel(TIMER_TICK_CHANNEL_ID), None);
+        assert_eq!(source_for_channel(TIMER_IRQ_CHANNEL_ID), None);
+        for source in [TimeSource::Unset, TimeSource::Uart, TimeSource::Network] {
+            assert_eq!(TimeSource::from_wire(source.to_wire()), Some(source));
+        }
+        assert_eq!(TimeSource::from_wire(3), None);
+    }
+
+    #[repr(C, align(8))]
+    struct Page([u8; CLOCK_PAGE_SIZE]);
+
+    #[test]
+    fn clock_page_publishes_and_reads_back() {
+        let mut page = Page([0; CLOCK_PAGE_SIZE]);
+        let base = page.0.as_mut_ptr();
+        unsafe {
+            // Zeroed: nothing published
+            assert_eq!(ClockPage::read(base), None);
+            ClockPage::publish(base, &ClockTime::unset(-300));
+            assert_eq!(ClockPage::read(base), Some(ClockTime::unset(-300)));
+            let time = ClockTime { epoch_s: 1_700_000_000, mono_us: 42, utc_offset_min: 60, source: TimeSource::Network };
+            ClockPage::publish(base, &time);
+            assert_eq!(ClockPage::read(base), Some(time));
+            let seq = (*(base as *const ClockPage)).seq.load(Ordering::Relaxed);
+            assert_eq!(seq, 4);
+
+            // Mid-write on every try
+            (*(base as *const ClockPage)).seq.store(5, Ordering::Relaxed);
+            assert_eq!(ClockPage::read(base), None);
+            (*(base as *const ClockPage)).seq.store(6, Ordering::Relaxed);
+
+            // Values no Timer PD writes
+            let raw = base as *mut ClockPage;
+            (*raw).source = 9;
+            assert_eq!(ClockPage::read(base), None);
+            (*raw).source = 1;
+            (*raw).utc_offset_min = 15 * 60;
+            assert_eq!(ClockPage::read(base), None);
+        }
+    }
+
+    #[test]
+    fn typed_times_parse() {
+        assert_eq!(parse_set_time(b"1760616000"), Some(SetTime { epoch_s: 1_760_616_000, utc_offset_min: None }));
+        assert_eq!(parse_set_time(b"1760616000+0530"), Some(SetTime { epoch_s: 1_760_616_000, utc_offset_min: Some(330) }));
+        assert_eq!(parse_set_time(b"0-1200"), Some(SetTime { epoch_s: 0, utc_offset_min: Some(-720) }));
+        for bad in [&b""[..], b"+0100", b"12a4", b"1+01", b"1+0160", b"1+1500", b"1+0100x", b"999999999999", b"1234567890123"] {
+            assert_eq!(parse_set_time(bad), None, "{:?}", core::str::from_utf8(bad));
+        }
+    }
+
+    #[test]
+    fn console_entry() {
+        let mut entry = SetTimeEntry::new();
+        assert_eq!(entry.feed(b'5'), EntryFeed::Pass(b'5'));
+        assert_eq!(entry.feed(SET_TIME_KEY), EntryFeed::Held);
+        for &b in b"17x\x7F60616000-0400" {
+            assert_eq!(entry.feed(b), EntryFeed::Held);
+        }
+        assert_eq!(entry.feed(b'\r'), EntryFeed::Set(SetTime { epoch_s: 1_760_616_000, utc_offset_min: Some(-240) }));
+        assert!(!entry.is_active());
+
+        // Escape abandons, garbage is rejected, overlong input never wraps
+        // into something valid
+        entry.feed(SET_TIME_KEY);
+        entry.feed(b'1');
+        assert_eq!(entry.feed(0x1B), EntryFeed::Cancelled);
+        assert_eq!(entry.feed(b'1'), EntryFeed::Pass(b'1'));
+        entry.feed(SET_TIME_KEY);
+        entry.feed(b'?');
+        assert_eq!(entry.feed(b'\n'), EntryFeed::Rejected);
+        entry.feed(SET_TIME_KEY);
+        for _ in 0..SET_TIME_MAX_LEN + 3 {
+            entry.feed(b'1');
+        }
+        for _ in 0..8 {
+            entry.feed(0x7F);
+        }
+        assert_eq!(entry.feed(b'\r'), EntryFeed::Rejected);
+    }
+}
@@ -0,0 +1,702 @@
+//! Live memory inspection for protection domains.
+//!
+//! A PD that owns a console byte stream can embed a [`Monitor`] to read and
+//! patch its own memory on the running target, without JTAG. The monitor
+//! only touches addresses inside the [`Region`]s it was built with (the
+//! PD's own Microkit mappings), so it cannot reach anything the PD could not
+//! already reach.
+//!
+//! The console stays a normal input stream until [`ENTER_BYTE`] (Ctrl-])
+//! arrives. From then on bytes are collected into command lines until
+//! `exit`:
+//!
+//! ```text
+//! peek <addr> [width]           read 1, 2, 4 or 8 bytes (default 4)
+//! poke <addr> <value> [width]   write 1, 2, 4 or 8 bytes (default 4)
+//! dump <addr> <len>             hex dump of at most 256 bytes
+//! watch <addr> [width]          report the value whenever it changes
+//! unwatch [addr]                drop one watch, or all of them
+//! regions                       list the accessible regions
+//! exit                          back to normal input
+//! ```
+//!
+//! Numbers are decimal or `0x`-prefixed hex. Every reply line starts with
+//! [`REPLY_PREFIX`] and each command ends with `@dbg ok` or
+//! `@dbg err <reason>`, so a host client can pick replies out of
+//! interleaved log output:
+//!
+//! ```text
+//! @dbg 0x504000000 = 0x00000003
+//! @dbg ok
+//! ```
+
+#![no_std]
+
+use core::fmt::{self, Write};
+use core::ptr::{read_volatile, write_volatile};
+
+/// Console byte that switches the monitor on (and off again): Ctrl-]
+pub const ENTER_BYTE: u8 = 0x1D;
+
+/// Prefix of every line the monitor writes
+pub const REPLY_PREFIX: &str = "@dbg";
+
+/// Longest accepted command line, in bytes
+pub const LINE_MAX: usize = 80;
+
+/// Largest `dump` length, in bytes
+pub const MAX_DUMP: usize = 256;
+
+/// Number of simultaneous watches
+pub const MAX_WATCHES: usize = 4;
+
+/// Bytes per `dump` output line
+const DUMP_ROW: usize = 16;
+
+/// What the monitor may do inside a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// peek, dump and watch only
+    ReadOnly,
+    /// poke is allowed as well
+    ReadWrite,
+}
+
+/// One virtual address range mapped into the PD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    /// Memory region name, as in the .system description
+    pub name: &'static str,
+    /// Virtual base address
+    pub base: usize,
+    /// Length in bytes
+    pub len: usize,
+    /// Permitted access
+    pub access: Access,
+}
+
+impl Region {
+    /// A region the monitor may only read
+    pub const fn read_only(name: &'static str, base: usize, len: usize) -> Self {
+        Self { name, base, len, access: Access::ReadOnly }
+    }
+
+    /// A region the monitor may read and write
+    pub const fn read_write(name: &'static str, base: usize, len: usize) -> Self {
+        Self { name, base, len, access: Access::ReadWrite }
+    }
+
+    /// Whether `[addr, addr + len)` lies entirely inside this region
+    pub const fn contains(&self, addr: usize, len: usize) -> bool {
+        if len == 0 || addr < self.base {
+            return false;
+        }
+        let offset = addr - self.base;
+        offset < self.len && len <= self.len - offset
+    }
+}
+
+/// Access width of a peek, poke or watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl Width {
+    /// Width from a byte count (1, 2, 4 or 8)
+    pub const fn from_bytes(bytes: u64) -> Option<Self> {
+        match bytes {
+            1 => Some(Width::U8),
+            2 => Some(Width::U16),
+            4 => Some(Width::U32),
+            8 => Some(Width::U64),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes
+    pub const fn bytes(self) -> usize {
+        match self {
+            Width::U8 => 1,
+            Width::U16 => 2,
+            Width::U32 => 4,
+            Width::U64 => 8,
+        }
+    }
+
+    /// Largest value that fits
+    const fn max(self) -> u64 {
+        match self {
+            Width::U8 => u8::MAX as u64,
+            Width::U16 => u16::MAX as u64,
+            Width::U32 => u32::MAX as u64,
+            Width::U64 => u64::MAX,
+        }
+    }
+}
+
+/// A parsed command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Peek { addr: usize, width: Width },
+    Poke { addr: usize, value: u64, width: Width },
+    Dump { addr: usize, len: usize },
+    Watch { addr: usize, width: Width },
+    /// `None` removes every watch
+    Unwatch { addr: Option<usize> },
+    Regions,
+    Help,
+    Exit,
+}
+
+/// Why a command was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorError {
+    /// First word is not a command
+    UnknownCommand,
+    /// Missing, extra or unparseable argument
+    BadArgument,
+    /// Command line longer than [`LINE_MAX`]
+    LineTooLong,
+    /// Range is not inside a single mapped region
+    NotMapped { addr: usize, len: usize },
+    /// poke into a read-only region
+    ReadOnly { addr: usize },
+    /// Address not aligned to the access width
+    Misaligned { addr: usize, width: Width },
+    /// dump longer than [`MAX_DUMP`]
+    DumpTooLong { len: usize },
+    /// poke value does not fit the access width
+    ValueTooWide { value: u64, width: Width },
+    /// All [`MAX_WATCHES`] watch slots are in use
+    WatchesFull,
+    /// unwatch of an address that is not watched
+    NotWatched { addr: usize },
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorError::UnknownCommand => write!(f, "unknown command, try help"),
+            MonitorError::BadArgument => write!(f, "bad argument"),
+            MonitorError::LineTooLong => write!(f, "line longer than {} bytes", LINE_MAX),
+            MonitorError::NotMapped { addr, len } => {
+                write!(f, "{:#x}+{:#x} is not mapped in this PD", addr, len)
+            }
+            MonitorError::ReadOnly { addr } => write!(f, "{:#x} is read-only", addr),
+            MonitorError::Misaligned { addr, width } => {
+                write!(f, "{:#x} is not {}-byte aligned", addr, width.bytes())
+            }
+            MonitorError::DumpTooLong { len } => {
+                write!(f, "dump of {} bytes exceeds {}", len, MAX_DUMP)
+            }
+            MonitorError::ValueTooWide { value, width } => {
+                write!(f, "{:#x} does not fit in {} bytes", value, width.bytes())
+            }
+            MonitorError::WatchesFull => write!(f, "all {} watches in use", MAX_WATCHES),
+            MonitorError::NotWatched { addr } => write!(f, "{:#x} is not watched", addr),
+        }
+    }
+}
+
+impl Command {
+    /// Parse one command line
+    pub fn parse(line: &str) -> Result<Self, MonitorError> {
+        let mut words = line.split_ascii_whitespace();
+        let name = words.next().ok_or(MonitorError::UnknownCommand)?;
+
+        let command = match name {
+            "peek" => Command::Peek {
+                addr: parse_addr(words.next())?,
+                width: parse_width(words.next())?,
+            },
+            "poke" => Command::Poke {
+                addr: parse_addr(words.next())?,
+                value: parse_number(words.next())?,
+                width: parse_width(words.next())?,
+            },
+            "dump" => Command::Dump {
+                addr: parse_addr(words.next())?,
+                len: parse_addr(words.next())?,
+            },
+            "watch" => Command::Watch {
+                addr: parse_addr(words.next())?,
+                width: parse_width(words.next())?,
+            },
+            "unwatch" => Command::Unwatch {
+                addr: match words.next() {
+                    Some(word) => Some(parse_addr(Some(word))?),
+                    None => None,
+                },
+            },
+            "regions" => Command::Regions,
+            "help" => Command::Help,
+            "exit" => Command::Exit,
+            _ => return Err(MonitorError::UnknownCommand),
+        };
+
+        if words.next().is_some() {
+            return Err(MonitorError::BadArgument);
+        }
+        Ok(command)
+    }
+}
+
+/// Decimal or `0x`-prefixed hex
+fn parse_number(word: Option<&str>) -> Result<u64, MonitorError> {
+    let word = word.ok_or(MonitorError::BadArgument)?;
+    let parsed = match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => word.parse(),
+    };
+    parsed.map_err(|_| MonitorError::BadArgument)
+}
+
+fn parse_addr(word: Option<&str>) -> Result<usize, MonitorError> {
+    usize::try_from(parse_number(word)?).map_err(|_| MonitorError::BadArgument)
+}
+
+fn parse_width(word: Option<&str>) -> Result<Width, MonitorError> {
+    match word {
+        Some(_) => Width::from_bytes(parse_number(word)?).ok_or(MonitorError::BadArgument),
+        None => Ok(Width::U32),
+    }
+}
+
+/// Write one `@dbg` line; console sinks do not fail, so errors are dropped
+fn reply<W: Write>(out: &mut W, args: fmt::Arguments<'_>) {
+    let _ = writeln!(out, "{} {}", REPLY_PREFIX, args);
+}
+
+/// Print `value` zero-padded to its access width
+struct Hex {
+    value: u64,
+    width: Width,
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#0digits$x}", self.value, digits = 2 + 2 * self.width.bytes())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Watch {
+    addr: usize,
+    width: Width,
+    last: u64,
+}
+
+/// Console-driven peek/poke monitor over a fixed set of regions
+pub struct Monitor<'r> {
+    regions: &'r [Region],
+    active: bool,
+    line: [u8; LINE_MAX],
+    len: usize,
+    overflow: bool,
+    watches: [Option<Watch>; MAX_WATCHES],
+}
+
+impl<'r> Monitor<'r> {
+    /// Create an inactive monitor
+    ///
+    /// # Safety
+    /// Every region must stay mapped in the calling PD, with at least the
+    /// given access, for as long as the monitor is used. Reads and writes are
+    /// volatile, so device registers inside a region are touched for real.
+    pub const unsafe fn new(regions: &'r [Region]) -> Self {
+        Self {
+            regions,
+            active: false,
+            line: [0; LINE_MAX],
+            len: 0,
+            overflow: false,
+            watches: [None; MAX_WATCHES],
+        }
+    }
+
+    /// Whether console bytes are currently taken as commands
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Regions the monitor may access
+    pub fn regions(&self) -> &'r [Region] {
+        self.regions
+    }
+
+    /// Feed one console byte
+    ///
+    /// Returns the byte back while the monitor is inactive, so the caller can
+    /// decode it as normal input; returns `None` when the monitor used it.
+    pub fn feed<W: Write>(&mut self, byte: u8, out: &mut W) -> Option<u8> {
+        if !self.active {
+            if byte != ENTER_BYTE {
+                return Some(byte);
+            }
+            self.active = true;
+            self.clear_line();
+            reply(out, format_args!("monitor ready, type help"));
+            return None;
+        }
+
+        match byte {
+            ENTER_BYTE => {
+                self.active = false;
+                reply(out, format_args!("monitor off"));
+            }
+            b'\r' | b'\n' => {
+                if self.overflow {
+                    Self::finish(Err(MonitorError::LineTooLong), out);
+                } else if self.len > 0 {
+                    let line = self.line;
+                    let len = self.len;
+                    // Non-ASCII input cannot name a command
+                    let result = match core::str::from_utf8(&line[..len]) {
+                        Ok(text) => self.execute(text, out),
+                        Err(_) => Err(MonitorError::UnknownCommand),
+                    };
+                    Self::finish(result, out);
+                }
+                self.clear_line();
+            }
+            // Backspace / DEL
+            0x08 | 0x7F => {
+                self.len = self.len.saturating_sub(1);
+            }
+            _ if self.len < LINE_MAX => {
+                self.line[self.len] = byte;
+                self.len += 1;
+            }
+            _ => self.overflow = true,
+        }
+        None
+    }
+
+    /// Run one command line, writing its output lines (but not the final
+    /// `ok`/`err` line) to `out`
+    pub fn execute<W: Write>(&mut self, line: &str, out: &mut W) -> Result<(), MonitorError> {
+        match Command::parse(line)? {
+            Command::Peek { addr, width } => {
+                let value = self.read(addr, width)?;
+                reply(out, format_args!("{:#x} = {}", addr, Hex { value, width }));
+            }
+            Command::Poke { addr, value, width } => {
+                if value > width.max() {
+                    return Err(MonitorError::ValueTooWide { value, width });
+                }
+                self.write(addr, value, width)?;
+            }
+            Command::Dump { addr, len } => self.dump(addr, len, out)?,
+            Command::Watch { addr, width } => {
+                let value = self.read(addr, width)?;
+                // Re-watching an address replaces its width
+                let index = self
+                    .watches
+                    .iter()
+                    .position(|w| w.is_some_and(|w| w.addr == addr))
+                    .or_else(|| self.watches.iter().position(Option::is_none))
+                    .ok_or(MonitorError::WatchesFull)?;
+                self.watches[index] = Some(Watch { addr, width, last: value });
+                reply(out, format_args!("watch {:#x} = {}", addr, Hex { value, width }));
+            }
+            Command::Unwatch { addr: Some(addr) } => {
+                let slot = self
+                    .watches
+                    .iter_mut()
+                    .find(|w| w.is_some_and(|w| w.addr == addr))
+                    .ok_or(MonitorError::NotWatched { addr })?;
+                *slot = None;
+            }
+            Command::Unwatch { addr: None } => self.watches = [None; MAX_WATCHES],
+            Command::Regions => {
+                for region in self.regions {
+                    let access = match region.access {
+                        Access::ReadOnly => "ro",
+                        Access::ReadWrite => "rw",
+                    };
+                    reply(
+                        out,
+                        format_args!("{} {:#x}+{:#x} {}", region.name, region.base, region.len, access),
+                    );
+                }
+            }
+            Command::Help => {
+                reply(out, format_args!("peek <addr> [1|2|4|8]"));
+                reply(out, format_args!("poke <addr> <value> [1|2|4|8]"));
+                reply(out, format_args!("dump <addr> <len>"));
+                reply(out, format_args!("watch <addr> [1|2|4|8]"));
+                reply(out, format_args!("unwatch [addr]"));
+                reply(out, format_args!("regions"));
+                reply(out, format_args!("exit"));
+            }
+            Command::Exit => self.active = false,
+        }
+        Ok(())
+    }
+
+    /// Report every watched value that changed since the last check
+    ///
+    /// Call this from the PD's periodic path; it works whether or not the
+    /// monitor is active.
+    pub fn poll_watches<W: Write>(&mut self, out: &mut W) {
+        for index in 0..MAX_WATCHES {
+            let Some(watch) = self.watches[index] else {
+                continue;
+            };
+            let Ok(value) = self.read(watch.addr, watch.width) else {
+                continue;
+            };
+            if value != watch.last {
+                let width = watch.width;
+                reply(
+                    out,
+                    format_args!(
+                        "watch {:#x} = {} (was {})",
+                        watch.addr,
+                        Hex { value, width },
+                        Hex { value: watch.last, width },
+                    ),
+                );
+                self.watches[index] = Some(Watch { last: value, ..watch });
+            }
+        }
+    }
+
+    fn finish<W: Write>(result: Result<(), MonitorError>, out: &mut W) {
+        match result {
+            Ok(()) => reply(out, format_args!("ok")),
+            Err(e) => reply(out, format_args!("err {}", e)),
+        }
+    }
+
+    fn clear_line(&mut self) {
+        self.len = 0;
+        self.overflow = false;
+    }
+
+    /// Check that `[addr, addr + len)` sits inside one region
+    fn region_for(&self, addr: usize, len: usize) -> Result<&'r Region, MonitorError> {
+        self.regions
+            .iter()
+            .find(|r| r.contains(addr, len))
+            .ok_or(MonitorError::NotMapped { addr, len })
+    }
+
+    fn checked(&self, addr: usize, width: Width) -> Result<&'r Region, MonitorError> {
+        if !addr.is_multiple_of(width.bytes()) {
+            return Err(MonitorError::Misaligned { addr, width });
+        }
+        self.region_for(addr, width.bytes())
+    }
+
+    fn read(&self, addr: usize, width: Width) -> Result<u64, MonitorError> {
+        self.checked(addr, width)?;
+        // Safety: aligned and inside a region the creator vouched for
+        let value = unsafe {
+            match width {
+                Width::U8 => read_volatile(addr as *const u8) as u64,
+                Width::U16 => read_volatile(addr as *const u16) as u64,
+                Width::U32 => read_volatile(addr as *const u32) as u64,
+                Width::U64 => read_volatile(addr as *const u64),
+            }
+        };
+        Ok(value)
+    }
+
+    fn write(&self, addr: usize, value: u64, width: Width) -> Result<(), MonitorError> {
+        if self.checked(addr, width)?.access != Access::ReadWrite {
+            return Err(MonitorError::ReadOnly { addr });
+        }
+        // Safety: aligned, inside a writable region, value fits the width
+        unsafe {
+            match width {
+                Width::U8 => write_volatile(addr as *mut u8, value as u8),
+                Width::U16 => write_volatile(addr as *mut u16, value as u16),
+                Width::U32 => write_volatile(addr as *mut u32, value as u32),
+                Width::U64 => write_volatile(addr as *mut u64, value),
+            }
+        }
+        Ok(())
+    }
+
+    fn dump<W: Write>(&self, addr: usize, len: usize, out: &mut W) -> Result<(), MonitorError> {
+        if len > MAX_DUMP {
+            return Err(MonitorError::DumpTooLong { len });
+        }
+        self.region_for(addr, len)?;
+
+        let mut offset = 0;
+        while offset < len {
+            let row = DUMP_ROW.min(len - offset);
+            let mut bytes = [0u8; DUMP_ROW];
+            for (i, byte) in bytes[..row].iter_mut().enumerate() {
+                // Safety: the whole range was checked above
+                *byte = unsafe { read_volatile((addr + offset + i) as *const u8) };
+            }
+
+            let _ = write!(out, "{} {:#x}:", REPLY_PREFIX, addr + offset);
+            for byte in &bytes[..row] {
+                let _ = write!(out, " {:02x}", byte);
+            }
+            let _ = writeln!(out);
+            offset += row;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn feed_line(monitor: &mut Monitor<'_>, line: &str, out: &mut String) {
+        for byte in line.bytes() {
+            assert_eq!(monitor.feed(byte, out), None);
+        }
+        monitor.feed(b'\r', out);
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            Command::parse("peek 0x1000"),
+            Ok(Command::Peek { addr: 0x1000, width: Width::U32 })
+        );
+        assert_eq!(
+            Command::parse("  poke 0x10 255 1 "),
+            Ok(Command::Poke { addr: 0x10, value: 255, width: Width::U8 })
+        );
+        assert_eq!(Command::parse("dump 0x20 64"), Ok(Command::Dump { addr: 0x20, len: 64 }));
+        assert_eq!(Command::parse("unwatch"), Ok(Command::Unwatch { addr: None }));
+        assert_eq!(Command::parse("peek 0x10 3"), Err(MonitorError::BadArgument));
+        assert_eq!(Command::parse("peek"), Err(MonitorError::BadArgument));
+        assert_eq!(Command::parse("peek 0x10 4 extra"), Err(MonitorError::BadArgument));
+        assert_eq!(Command::parse("reboot"), Err(MonitorError::UnknownCommand));
+    }
+
+    #[test]
+    fn test_region_bounds() {
+        let region = Region::read_only("ring", 0x1000, 0x100);
+        assert!(region.contains(0x1000, 0x100));
+        assert!(region.contains(0x10fc, 4));
+        assert!(!region.contains(0x10fd, 4));
+        assert!(!region.contains(0xfff, 1));
+        assert!(!region.contains(0x1000, 0));
+        assert!(!region.contains(usize::MAX, 2));
+    }
+
+    #[test]
+    fn test_peek_poke_limited_to_regions() {
+        let mut rw = [0u64; 4];
+        let ro = [0x1122_3344_5566_7788u64; 2];
+        let rw_base = rw.as_mut_ptr() as usize;
+        let ro_base = ro.as_ptr() as usize;
+        let regions = [
+            Region::read_write("scratch", rw_base, 16),
+            Region::read_only("table", ro_base, 16),
+        ];
+        let mut monitor = unsafe { Monitor::new(&regions) };
+        let mut out = String::new();
+
+        let poke = std::format!("poke {:#x} 0xdeadbeef", rw_base + 8);
+        assert_eq!(monitor.execute(&poke, &mut out), Ok(()));
+        let peek = std::format!("peek {:#x} 8", ro_base);
+        assert_eq!(monitor.execute(&peek, &mut out), Ok(()));
+        assert!(out.ends_with("= 0x1122334455667788\n"));
+
+        let read_only = std::format!("poke {:#x} 1", ro_base);
+        assert_eq!(
+            monitor.execute(&read_only, &mut out),
+            Err(MonitorError::ReadOnly { addr: ro_base })
+        );
+        let outside = std::format!("peek {:#x}", rw_base + 16);
+        assert_eq!(
+            monitor.execute(&outside, &mut out),
+            Err(MonitorError::NotMapped { addr: rw_base + 16, len: 4 })
+        );
+        let misaligned = std::format!("peek {:#x} 2", rw_base + 1);
+        assert!(matches!(
+            monitor.execute(&misaligned, &mut out),
+            Err(MonitorError::Misaligned { .. })
+        ));
+        let too_wide = std::format!("poke {:#x} 0x100 1", rw_base);
+        assert!(matches!(
+            monitor.execute(&too_wide, &mut out),
+            Err(MonitorError::ValueTooWide { .. })
+        ));
+
+        assert_eq!(rw[1] as u32, 0xdead_beef);
+        assert_eq!(rw[0], 0);
+    }
+
+    #[test]
+    fn test_console_mode_switch_and_replies() {
+        let mut memory = [0u8; 20];
+        for (i, byte) in memory.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let base = memory.as_ptr() as usize;
+        let regions = [Region::read_only("ring", base, memory.len())];
+        let mut monitor = unsafe { Monitor::new(&regions) };
+        let mut out = String::new();
+
+        // Inactive: everything but Ctrl-] is handed back untouched
+        assert_eq!(monitor.feed(b'w', &mut out), Some(b'w'));
+        assert!(out.is_empty());
+        assert_eq!(monitor.feed(ENTER_BYTE, &mut out), None);
+        assert!(monitor.is_active());
+
+        out.clear();
+        feed_line(&mut monitor, &std::format!("dump {:#x} 20", base), &mut out);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with(": 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f"));
+        assert!(lines[1].ends_with(": 10 11 12 13"));
+        assert_eq!(lines[2], "@dbg ok");
+
+        out.clear();
+        feed_line(&mut monitor, "pokx\x7fe 0 0", &mut out);
+        assert!(out.starts_with("@dbg err"));
+
+        out.clear();
+        feed_line(&mut monitor, "exit", &mut out);
+        assert_eq!(out, "@dbg ok\n");
+        assert!(!monitor.is_active());
+        assert_eq!(monitor.feed(b'\r', &mut out), Some(b'\r'));
+    }
+
+    #[test]
+    fn test_watch_reports_changes() {
+        let mut counter = [0u32; 1];
+        let base = counter.as_mut_ptr() as usize;
+        let regions = [Region::read_write("counter", base, 4)];
+        let mut monitor = unsafe { Monitor::new(&regions) };
+        let mut out = String::new();
+
+        assert_eq!(monitor.execute(&std::format!("watch {:#x}", base), &mut out), Ok(()));
+        out.clear();
+        monitor.poll_watches(&mut out);
+        assert!(out.is_empty());
+
+        unsafe { write_volatile(base as *mut u32, 7) };
+        monitor.poll_watches(&mut out);
+        assert!(out.ends_with("= 0x00000007 (was 0x00000000)\n"));
+
+        out.clear();
+        monitor.poll_watches(&mut out);
+        assert!(out.is_empty());
+
+        assert_eq!(monitor.execute(&std::format!("unwatch {:#x}", base), &mut out), Ok(()));
+        assert_eq!(
+            monitor.execute(&std::format!("unwatch {:#x}", base), &mut out),
+            Err(MonitorError::NotWatched { addr: base })
+        );
+    }
+}
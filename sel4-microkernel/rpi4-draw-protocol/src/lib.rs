@@ -0,0 +1,622 @@
+//! Verified draw-command protocol between untrusted App PDs and the
+//! Display (Present) PD.
+//!
+//! Instead of rendering into a shared framebuffer, an App PD queues compact
+//! [`DrawCommand`]s on a ring and the Display PD draws them. The App never
+//! maps a pixel the Display PD scans out, and every command is checked
+//! against the screen by [`DrawCommand::validate`] before it is executed, so
+//! a compromised App can at worst draw nonsense inside the screen.
+//!
+//! ## Memory Layout
+//!
+//! ```text
+//! +-------------------+ 0x00000
+//! | DrawRingHeader    | (16 bytes: ring indices, presented count)
+//! +-------------------+ 0x00010
+//! | DrawCommand[0]    | (16 bytes each)
+//! | ...               |
+//! +-------------------+ 0x01000
+//! | Text pool         | UTF-8 bytes referenced by TEXT commands
+//! +-------------------+ 0x02000
+//! | ImageDesc[0..4]   | (4 bytes each, rest of the page reserved)
+//! +-------------------+ 0x03000
+//! | Image pixels      | 4 slots of 64x64 ARGB8888
+//! +-------------------+ 0x13000
+//! ```
+//!
+//! ## Command Fields
+//!
+//! | op        | x, y              | w, h           | arg         | color |
+//! |-----------|-------------------|----------------|-------------|-------|
+//! | `CLEAR`   | -                 | -              | -           | fill  |
+//! | `RECT`    | top-left          | size           | -           | fill  |
+//! | `TEXT`    | top-left of line  | w: byte length | pool offset | ink   |
+//! | `IMAGE`   | top-left          | -              | image slot  | -     |
+//! | `PRESENT` | -                 | -              | -           | -     |
+//! | `MOVE`    | node translation  | -              | z-order     | -     |
+//! | `REMOVE`  | -                 | -              | -           | -     |
+//! | `RESET`   | -                 | -              | -           | fill  |
+//!
+//! ## Retained Scene
+//!
+//! A `RECT`, `TEXT` or `IMAGE` command with a nonzero `node` does not draw:
+//! it sets what that node of the Display PD's [`scene::Scene`] shows, in the
+//! node's own coordinates. `MOVE` translates a node and sets its z-order,
+//! `REMOVE` drops it and `RESET` drops every node and sets the background.
+//! The Display PD tracks which parts of the screen each change touched and,
+//! on `PRESENT`, repaints only those, so an App updates just the nodes that
+//! changed. An App should not mix immediate drawing with a scene: a repaint
+//! covers whatever was drawn immediately under the damaged area.
+//!
+//! Commands are copied out of the ring before they are validated, and an
+//! image's descriptor is copied before its placement is checked, so the App
+//! cannot change a command between the check and the draw. The text pool
+//! and image pixels are only ever read as data; the App manages reuse of
+//! both itself (e.g. by waiting for [`DrawRingHeader::presented`]).
+//! Image nodes show their slot's pixels as they are at each repaint, so an
+//! App rewriting a slot sets the node again to have it redrawn.
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::new_without_default)]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+use verified_microkernel::ring::{RingHeader, VerifiedRing};
+use verus_builtin_macros::verus;
+
+pub mod scene;
+
+verus! {
+
+/// App PD end of the draw channel (App notifies: commands queued)
+pub const APP_DRAW_CHANNEL_ID: usize = 4;
+/// Display PD end of the draw channel (Display notifies: frame presented)
+pub const DISPLAY_DRAW_CHANNEL_ID: usize = 3;
+
+/// Where both PDs map the draw region (tvdemo-split.system)
+pub const DRAW_REGION_VADDR: usize = 0x5_1100_0000;
+
+pub const HEADER_SIZE: usize = 16;
+pub const COMMAND_SIZE: usize = 16;
+pub const COMMANDS_OFFSET: usize = 16;
+/// Commands that fit in the first page after the header
+pub const RING_CAPACITY: u32 = 255;
+
+pub const TEXT_POOL_OFFSET: usize = 0x1000;
+pub const TEXT_POOL_SIZE: usize = 0x1000;
+/// Longest string one TEXT command draws
+pub const MAX_TEXT_LEN: u16 = 128;
+
+pub const IMAGE_SLOTS: u16 = 4;
+pub const IMAGE_DESC_OFFSET: usize = 0x2000;
+pub const IMAGE_DESC_SIZE: usize = 4;
+pub const IMAGE_PIXELS_OFFSET: usize = 0x3000;
+/// Longest side of an image
+pub const IMAGE_MAX_SIDE: u16 = 64;
+/// Largest image a slot holds (64x64 ARGB8888)
+pub const IMAGE_SLOT_PIXELS: u32 = IMAGE_MAX_SIDE as u32 * IMAGE_MAX_SIDE as u32;
+pub const IMAGE_SLOT_BYTES: usize = 0x4000;
+
+pub const DRAW_REGION_SIZE: usize = 0x13000;
+
+pub const OP_CLEAR: u8 = 1;
+pub const OP_RECT: u8 = 2;
+pub const OP_TEXT: u8 = 3;
+pub const OP_IMAGE: u8 = 4;
+pub const OP_PRESENT: u8 = 5;
+pub const OP_MOVE: u8 = 6;
+pub const OP_REMOVE: u8 = 7;
+pub const OP_RESET: u8 = 8;
+
+/// Scene nodes are numbered 1 to `MAX_NODES`; node 0 means "draw now"
+pub const MAX_NODES: u8 = 32;
+
+/// Why a command was refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawError {
+    /// Not one of the `OP_*` values
+    UnknownOp(u8),
+    /// A rectangle with no pixels
+    EmptyRect,
+    /// The command reaches past the screen edge
+    OutOfBounds,
+    /// Empty text, or longer than [`MAX_TEXT_LEN`]
+    BadTextLength,
+    /// The text runs past the end of the pool
+    TextOutOfPool,
+    /// Image slot at or above [`IMAGE_SLOTS`]
+    BadImageHandle(u16),
+    /// The image descriptor is empty or a side exceeds [`IMAGE_MAX_SIDE`]
+    BadImageSize,
+    /// Node above [`MAX_NODES`], or a node on an op that takes none
+    BadNode(u8),
+    /// `MOVE` or `REMOVE` of a node that has no content
+    UnknownNode(u8),
+}
+
+/// Does a `w` x `h` rectangle at (x, y) lie on a `sw` x `sh` screen?
+pub open spec fn rect_on_screen(x: u16, y: u16, w: u16, h: u16, sw: u16, sh: u16) -> bool {
+    x as int + w as int <= sw as int && y as int + h as int <= sh as int
+}
+
+/// Is `node` a scene node (not 0, not above [`MAX_NODES`])?
+pub open spec fn is_node(node: u8) -> bool {
+    0 < node && node <= MAX_NODES
+}
+
+/// Runtime check matching [`rect_on_screen`]
+fn rect_fits(x: u16, y: u16, w: u16, h: u16, sw: u16, sh: u16) -> (result: bool)
+    ensures result == rect_on_screen(x, y, w, h, sw, sh),
+{
+    (x as u32) + (w as u32) <= sw as u32 && (y as u32) + (h as u32) <= sh as u32
+}
+
+/// One queued draw operation; see the crate docs for what each field means
+/// per op
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct DrawCommand {
+    pub op: u8,
+    /// Scene node the command updates; 0 draws immediately
+    pub node: u8,
+    pub arg: u16,
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+    /// ARGB8888
+    pub color: u32,
+}
+
+impl DrawCommand {
+    /// Specification: may this command run on a `sw` x `sh` screen?
+    pub open spec fn valid_for(&self, sw: u16, sh: u16) -> bool {
+        if self.op == OP_MOVE || self.op == OP_REMOVE {
+            is_node(self.node) && (self.op == OP_REMOVE || (self.x < sw && self.y < sh))
+        } else if self.op == OP_CLEAR || self.op == OP_PRESENT || self.op == OP_RESET {
+            self.node == 0
+        } else if self.node > MAX_NODES {
+            false
+        } else if self.op == OP_RECT {
+            self.w > 0 && self.h > 0 && rect_on_screen(self.x, self.y, self.w, self.h, sw, sh)
+        } else if self.op == OP_TEXT {
+            self.x < sw && self.y < sh
+                && self.w > 0 && self.w <= MAX_TEXT_LEN
+                && self.arg as int + self.w as int <= TEXT_POOL_SIZE as int
+        } else if self.op == OP_IMAGE {
+            self.arg < IMAGE_SLOTS && self.x < sw && self.y < sh
+        } else {
+            false
+        }
+    }
+
+    /// Check the command against a `screen_width` x `screen_height` screen
+    ///
+    /// An image's own size is checked separately, against its descriptor
+    /// ([`ImageDesc::check_placement`]), when the Display PD draws it.
+    pub fn validate(&self, screen_width: u16, screen_height: u16) -> (result: Result<(), DrawError>)
+        ensures result.is_ok() <==> self.valid_for(screen_width, screen_height),
+    {
+        if self.op == OP_MOVE || self.op == OP_REMOVE {
+            if self.node == 0 || self.node > MAX_NODES {
+                Err(DrawError::BadNode(self.node))
+            } else if self.op == OP_MOVE && (self.x >= screen_width || self.y >= screen_height) {
+                Err(DrawError::OutOfBounds)
+            } else {
+                Ok(())
+            }
+        } else if self.op == OP_CLEAR || self.op == OP_PRESENT || self.op == OP_RESET {
+            if self.node != 0 {
+                Err(DrawError::BadNode(self.node))
+            } else {
+                Ok(())
+            }
+        } else if self.node > MAX_NODES && (self.op == OP_RECT || self.op == OP_TEXT || self.op == OP_IMAGE) {
+            Err(DrawError::BadNode(self.node))
+        } else if self.op == OP_RECT {
+            if self.w == 0 || self.h == 0 {
+                Err(DrawError::EmptyRect)
+            } else if !rect_fits(self.x, self.y, self.w, self.h, screen_width, screen_height) {
+                Err(DrawError::OutOfBounds)
+            } else {
+                Ok(())
+            }
+        } else if self.op == OP_TEXT {
+            if self.x >= screen_width || self.y >= screen_height {
+                Err(DrawError::OutOfBounds)
+            } else if self.w == 0 || self.w > MAX_TEXT_LEN {
+                Err(DrawError::BadTextLength)
+            } else if (self.arg as usize) + (self.w as usize) > TEXT_POOL_SIZE {
+                Err(DrawError::TextOutOfPool)
+            } else {
+                Ok(())
+            }
+        } else if self.op == OP_IMAGE {
+            if self.arg >= IMAGE_SLOTS {
+                Err(DrawError::BadImageHandle(self.arg))
+            } else if self.x >= screen_width || self.y >= screen_height {
+                Err(DrawError::OutOfBounds)
+            } else {
+                Ok(())
+            }
+        } else {
+            Err(DrawError::UnknownOp(self.op))
+        }
+    }
+
+    /// Fill the whole screen
+    pub fn clear(color: u32) -> (cmd: Self)
+        ensures cmd.op == OP_CLEAR, cmd.color == color,
+    {
+        DrawCommand { op: OP_CLEAR, node: 0, arg: 0, x: 0, y: 0, w: 0, h: 0, color }
+    }
+
+    /// Fill a `w` x `h` rectangle at (x, y)
+    pub fn rect(x: u16, y: u16, w: u16, h: u16, color: u32) -> (cmd: Self)
+        ensures cmd.op == OP_RECT, cmd.x == x, cmd.y == y, cmd.w == w, cmd.h == h,
+    {
+        DrawCommand { op: OP_RECT, node: 0, arg: 0, x, y, w, h, color }
+    }
+
+    /// Draw `len` bytes of UTF-8 from the text pool at `offset`
+    pub fn text(x: u16, y: u16, offset: u16, len: u16, color: u32) -> (cmd: Self)
+        ensures cmd.op == OP_TEXT, cmd.arg == offset, cmd.w == len,
+    {
+        DrawCommand { op: OP_TEXT, node: 0, arg: offset, x, y, w: len, h: 0, color }
+    }
+
+    /// Draw the image in `slot` with its top-left corner at (x, y)
+    pub fn image(x: u16, y: u16, slot: u16) -> (cmd: Self)
+        ensures cmd.op == OP_IMAGE, cmd.arg == slot,
+    {
+        DrawCommand { op: OP_IMAGE, node: 0, arg: slot, x, y, w: 0, h: 0, color: 0 }
+    }
+
+    /// End of a frame: make what was drawn visible
+    pub fn present() -> (cmd: Self)
+        ensures cmd.op == OP_PRESENT,
+    {
+        DrawCommand { op: OP_PRESENT, node: 0, arg: 0, x: 0, y: 0, w: 0, h: 0, color: 0 }
+    }
+
+    /// Set scene node `node` to show this command instead of drawing it
+    pub fn in_node(self, node: u8) -> (cmd: Self)
+        ensures cmd.op == self.op, cmd.node == node,
+    {
+        DrawCommand { node, ..self }
+    }
+
+    /// Translate scene node `node` to (x, y) and stack it at z-order `z`
+    /// (higher is drawn later)
+    pub fn move_node(node: u8, x: u16, y: u16, z: u16) -> (cmd: Self)
+        ensures cmd.op == OP_MOVE, cmd.node == node, cmd.x == x, cmd.y == y, cmd.arg == z,
+    {
+        DrawCommand { op: OP_MOVE, node, arg: z, x, y, w: 0, h: 0, color: 0 }
+    }
+
+    /// Drop scene node `node`
+    pub fn remove_node(node: u8) -> (cmd: Self)
+        ensures cmd.op == OP_REMOVE, cmd.node == node,
+    {
+        DrawCommand { op: OP_REMOVE, node, arg: 0, x: 0, y: 0, w: 0, h: 0, color: 0 }
+    }
+
+    /// Drop every scene node and fill the scene's background with `color`
+    pub fn reset(color: u32) -> (cmd: Self)
+        ensures cmd.op == OP_RESET, cmd.color == color,
+    {
+        DrawCommand { op: OP_RESET, node: 0, arg: 0, x: 0, y: 0, w: 0, h: 0, color }
+    }
+}
+
+/// Size of the image in one slot, written by the App
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ImageDesc {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl ImageDesc {
+    /// Specification: does the image fit its slot and, placed at (x, y),
+    /// the screen?
+    pub open spec fn placeable(&self, x: u16, y: u16, sw: u16, sh: u16) -> bool {
+        0 < self.width && self.width <= IMAGE_MAX_SIDE
+            && 0 < self.height && self.height <= IMAGE_MAX_SIDE
+            && rect_on_screen(x, y, self.width, self.height, sw, sh)
+    }
+
+    /// Check the image can be drawn at (x, y) on a `screen_width` x
+    /// `screen_height` screen
+    pub fn check_placement(&self, x: u16, y: u16, screen_width: u16, screen_height: u16) -> (result: Result<(), DrawError>)
+        ensures result.is_ok() <==> self.placeable(x, y, screen_width, screen_height),
+    {
+        if self.width == 0 || self.width > IMAGE_MAX_SIDE
+            || self.height == 0 || self.height > IMAGE_MAX_SIDE
+        {
+            Err(DrawError::BadImageSize)
+        } else if !rect_fits(x, y, self.width, self.height, screen_width, screen_height) {
+            Err(DrawError::OutOfBounds)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+} // verus!
+
+impl From<DrawError> for PdError {
+    fn from(e: DrawError) -> Self {
+        match e {
+            DrawError::UnknownOp(op) => {
+                PdError::new(ErrorDomain::Display, ErrorKind::Unsupported).with_detail(op as u16)
+            }
+            DrawError::BadImageHandle(slot) => {
+                PdError::new(ErrorDomain::Display, ErrorKind::NotFound).with_detail(slot)
+            }
+            DrawError::UnknownNode(node) => {
+                PdError::new(ErrorDomain::Display, ErrorKind::NotFound).with_detail(node as u16)
+            }
+            DrawError::BadNode(node) => {
+                PdError::new(ErrorDomain::Display, ErrorKind::InvalidArgument).with_detail(node as u16)
+            }
+            DrawError::EmptyRect
+            | DrawError::OutOfBounds
+            | DrawError::BadTextLength
+            | DrawError::TextOutOfPool
+            | DrawError::BadImageSize => PdError::new(ErrorDomain::Display, ErrorKind::InvalidArgument),
+        }
+    }
+}
+
+/// The draw ring as a typed [`VerifiedRing`]
+pub type DrawRing = VerifiedRing<DrawCommand, { RING_CAPACITY as usize }>;
+
+/// Ring header at the start of the draw region.
+///
+/// A `verified_microkernel::ring` header, same index discipline as the
+/// input ring: the App owns `write_idx`, the Display PD owns `read_idx`,
+/// and one slot stays empty to tell full from empty. `presented` counts
+/// `PRESENT` commands the Display PD has run.
+#[repr(C, align(16))]
+pub struct DrawRingHeader {
+    pub ring: RingHeader,
+    presented: AtomicU32,
+}
+
+impl DrawRingHeader {
+    /// # Safety
+    /// `ptr` must be valid, writable, and aligned for `DrawRingHeader`.
+    pub unsafe fn init(ptr: *mut Self) {
+        (*ptr).ring = RingHeader::new(RING_CAPACITY);
+        (*ptr).presented = AtomicU32::new(0);
+    }
+
+    /// The ring with this header and the commands at `commands`
+    ///
+    /// # Safety
+    /// `commands` must point at this ring's command array (see
+    /// [`commands_ptr`]).
+    pub unsafe fn with_commands(&self, commands: *mut DrawCommand) -> DrawRing {
+        DrawRing::new(&self.ring, commands)
+    }
+
+    pub fn has_data(&self) -> bool {
+        self.ring.len(RING_CAPACITY) > 0
+    }
+
+    /// Frames presented so far (wraps)
+    pub fn presented(&self) -> u32 {
+        self.presented.load(Ordering::Acquire)
+    }
+
+    /// Count a presented frame (Display PD only)
+    pub fn mark_presented(&self) {
+        self.presented.fetch_add(1, Ordering::Release);
+    }
+
+    /// Copy up to `out.len()` commands out of the ring, oldest first
+    ///
+    /// The read index is published once for the whole batch. Returns the
+    /// number of commands copied, never more than were queued; a corrupt
+    /// header reads as empty.
+    ///
+    /// # Safety
+    /// `commands` must point at this ring's command array (see
+    /// [`commands_ptr`]).
+    pub unsafe fn pop_batch(&self, commands: *const DrawCommand, out: &mut [DrawCommand]) -> usize {
+        self.with_commands(commands as *mut _).pop_batch(out)
+    }
+
+    /// Queue as much of `batch` as fits, oldest first; returns the number
+    /// queued, never more than were free
+    ///
+    /// # Safety
+    /// `commands` must point at this ring's command array (see
+    /// [`commands_ptr`]).
+    pub unsafe fn push_batch(&self, commands: *mut DrawCommand, batch: &[DrawCommand]) -> usize {
+        self.with_commands(commands).push_batch(batch)
+    }
+
+    /// Queue all of `batch`, or nothing if fewer slots are free
+    ///
+    /// The write index is published once, after every command is in place.
+    /// Returns whether the batch was queued.
+    ///
+    /// # Safety
+    /// `commands` must point at this ring's command array (see
+    /// [`commands_ptr`]).
+    pub unsafe fn write_batch(&self, commands: *mut DrawCommand, batch: &[DrawCommand]) -> bool {
+        // Only the App adds commands, so the free count can only grow
+        // before the push
+        if (self.ring.free_slots(RING_CAPACITY) as usize) < batch.len() {
+            return false;
+        }
+        self.push_batch(commands, batch);
+        true
+    }
+}
+
+/// # Safety
+/// `base` must be the start of the mapped draw region.
+pub unsafe fn header_ptr(base: *mut u8) -> *mut DrawRingHeader {
+    base as *mut DrawRingHeader
+}
+
+/// # Safety
+/// `base` must be the start of the mapped draw region.
+pub unsafe fn commands_ptr(base: *mut u8) -> *mut DrawCommand {
+    base.add(COMMANDS_OFFSET) as *mut DrawCommand
+}
+
+/// # Safety
+/// `base` must be the start of the mapped draw region.
+pub unsafe fn text_pool_ptr(base: *mut u8) -> *mut u8 {
+    base.add(TEXT_POOL_OFFSET)
+}
+
+/// # Safety
+/// `base` must be the start of the mapped draw region and `slot` below
+/// [`IMAGE_SLOTS`].
+pub unsafe fn image_desc_ptr(base: *mut u8, slot: u16) -> *mut ImageDesc {
+    base.add(IMAGE_DESC_OFFSET + slot as usize * IMAGE_DESC_SIZE) as *mut ImageDesc
+}
+
+/// Pixels of image `slot`, `width * height` packed ARGB8888 words
+///
+/// # Safety
+/// `base` must be the start of the mapped draw region and `slot` below
+/// [`IMAGE_SLOTS`].
+pub unsafe fn image_pixels_ptr(base: *mut u8, slot: u16) -> *mut u32 {
+    base.add(IMAGE_PIXELS_OFFSET + slot as usize * IMAGE_SLOT_BYTES) as *mut u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_is_stable() {
+        assert_eq!(core::mem::size_of::<DrawCommand>(), COMMAND_SIZE);
+        assert_eq!(core::mem::size_of::<DrawRingHeader>(), HEADER_SIZE);
+        assert_eq!(core::mem::size_of::<ImageDesc>(), IMAGE_DESC_SIZE);
+        assert!(COMMANDS_OFFSET + RING_CAPACITY as usize * COMMAND_SIZE <= TEXT_POOL_OFFSET);
+        assert!(IMAGE_DESC_OFFSET + IMAGE_SLOTS as usize * IMAGE_DESC_SIZE <= IMAGE_PIXELS_OFFSET);
+        assert_eq!(IMAGE_SLOT_BYTES, IMAGE_SLOT_PIXELS as usize * 4);
+        assert_eq!(DRAW_REGION_SIZE, IMAGE_PIXELS_OFFSET + IMAGE_SLOTS as usize * IMAGE_SLOT_BYTES);
+    }
+
+    #[test]
+    fn rects_must_fit_the_screen() {
+        assert_eq!(DrawCommand::rect(0, 0, 320, 240, 0).validate(320, 240), Ok(()));
+        assert_eq!(DrawCommand::rect(300, 0, 21, 10, 0).validate(320, 240), Err(DrawError::OutOfBounds));
+        assert_eq!(DrawCommand::rect(0, 239, 1, 2, 0).validate(320, 240), Err(DrawError::OutOfBounds));
+        assert_eq!(DrawCommand::rect(10, 10, 0, 5, 0).validate(320, 240), Err(DrawError::EmptyRect));
+        // Wrap-around in u16 arithmetic is not a way onto the screen
+        assert_eq!(
+            DrawCommand::rect(u16::MAX, 0, 2, 1, 0).validate(320, 240),
+            Err(DrawError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn text_must_stay_in_pool() {
+        assert_eq!(DrawCommand::text(4, 4, 0, 12, 0).validate(320, 240), Ok(()));
+        assert_eq!(DrawCommand::text(4, 4, 0, 0, 0).validate(320, 240), Err(DrawError::BadTextLength));
+        assert_eq!(
+            DrawCommand::text(4, 4, 0, MAX_TEXT_LEN + 1, 0).validate(320, 240),
+            Err(DrawError::BadTextLength)
+        );
+        let last = (TEXT_POOL_SIZE - 10) as u16;
+        assert_eq!(DrawCommand::text(4, 4, last, 10, 0).validate(320, 240), Ok(()));
+        assert_eq!(DrawCommand::text(4, 4, last, 11, 0).validate(320, 240), Err(DrawError::TextOutOfPool));
+        assert_eq!(DrawCommand::text(320, 4, 0, 4, 0).validate(320, 240), Err(DrawError::OutOfBounds));
+    }
+
+    #[test]
+    fn images_are_checked_against_their_descriptor() {
+        assert_eq!(DrawCommand::image(0, 0, IMAGE_SLOTS).validate(320, 240), Err(DrawError::BadImageHandle(IMAGE_SLOTS)));
+        assert_eq!(DrawCommand::image(10, 10, 1).validate(320, 240), Ok(()));
+
+        let icon = ImageDesc { width: 64, height: 64 };
+        assert_eq!(icon.check_placement(256, 176, 320, 240), Ok(()));
+        assert_eq!(icon.check_placement(257, 176, 320, 240), Err(DrawError::OutOfBounds));
+        let oversized = ImageDesc { width: 128, height: 64 };
+        assert_eq!(oversized.check_placement(0, 0, 320, 240), Err(DrawError::BadImageSize));
+        let strip = ImageDesc { width: 4096, height: 1 };
+        assert_eq!(strip.check_placement(0, 0, 320, 240), Err(DrawError::BadImageSize));
+        let empty = ImageDesc { width: 0, height: 8 };
+        assert_eq!(empty.check_placement(0, 0, 320, 240), Err(DrawError::BadImageSize));
+    }
+
+    #[test]
+    fn unknown_ops_are_refused() {
+        let mut cmd = DrawCommand::present();
+        assert_eq!(cmd.validate(320, 240), Ok(()));
+        cmd.op = 0;
+        assert_eq!(cmd.validate(320, 240), Err(DrawError::UnknownOp(0)));
+        cmd.op = 0xFF;
+        assert_eq!(cmd.validate(320, 240), Err(DrawError::UnknownOp(0xFF)));
+    }
+
+    #[test]
+    fn node_ops_are_checked() {
+        assert_eq!(DrawCommand::rect(0, 0, 8, 8, 0).in_node(MAX_NODES).validate(320, 240), Ok(()));
+        assert_eq!(
+            DrawCommand::rect(0, 0, 8, 8, 0).in_node(MAX_NODES + 1).validate(320, 240),
+            Err(DrawError::BadNode(MAX_NODES + 1))
+        );
+        // Node content is still checked like an immediate command
+        assert_eq!(
+            DrawCommand::rect(310, 0, 20, 8, 0).in_node(1).validate(320, 240),
+            Err(DrawError::OutOfBounds)
+        );
+        assert_eq!(DrawCommand::move_node(1, 319, 239, 7).validate(320, 240), Ok(()));
+        assert_eq!(DrawCommand::move_node(1, 320, 0, 7).validate(320, 240), Err(DrawError::OutOfBounds));
+        assert_eq!(DrawCommand::move_node(0, 0, 0, 0).validate(320, 240), Err(DrawError::BadNode(0)));
+        assert_eq!(DrawCommand::remove_node(0).validate(320, 240), Err(DrawError::BadNode(0)));
+        assert_eq!(DrawCommand::reset(0).validate(320, 240), Ok(()));
+        assert_eq!(DrawCommand::present().in_node(3).validate(320, 240), Err(DrawError::BadNode(3)));
+    }
+
+    #[test]
+    fn batch_read_write_wraps() {
+        #[repr(C, align(16))]
+        struct Region([u8; COMMANDS_OFFSET + 4 * COMMAND_SIZE]);
+        let mut region = Region([0; COMMANDS_OFFSET + 4 * COMMAND_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let mut out = [DrawCommand::present(); 3];
+        unsafe {
+            let header = header_ptr(base);
+            DrawRingHeader::init(header);
+            (*header).ring.capacity = 4;
+            let header = &*header;
+            let commands = commands_ptr(base);
+
+            assert!(header.write_batch(commands, &[DrawCommand::clear(1), DrawCommand::clear(2)]));
+            assert_eq!(header.pop_batch(commands, &mut out[..1]), 1);
+            assert!(header.write_batch(commands, &[DrawCommand::clear(3), DrawCommand::present()]));
+            assert!(!header.write_batch(commands, &[DrawCommand::clear(4)]));
+            assert_eq!(header.pop_batch(commands, &mut out), 3);
+            assert_eq!(out.map(|c| (c.op, c.color)), [(OP_CLEAR, 2), (OP_CLEAR, 3), (OP_PRESENT, 0)]);
+            assert!(!header.has_data());
+        }
+    }
+
+    #[test]
+    fn corrupt_header_reads_empty() {
+        #[repr(C, align(16))]
+        struct Region([u8; COMMANDS_OFFSET + 4 * COMMAND_SIZE]);
+        let mut region = Region([0; COMMANDS_OFFSET + 4 * COMMAND_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let mut out = [DrawCommand::present(); 2];
+        unsafe {
+            let header = header_ptr(base);
+            DrawRingHeader::init(header);
+            (*header).ring.write_idx.store(RING_CAPACITY + 7, Ordering::Relaxed);
+            assert_eq!((*header).pop_batch(commands_ptr(base), &mut out), 0);
+        }
+    }
+}
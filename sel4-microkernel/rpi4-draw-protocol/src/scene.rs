@@ -0,0 +1,432 @@
+//! Retained scene graph kept by the Display PD.
+//!
+//! Each node holds one primitive (rectangle, text or image) in its own
+//! coordinates, a translation and a z-order. Every change records the
+//! screen area it touched in a [`Damage`] list, and on `PRESENT` the Display
+//! PD repaints only those areas: background first, then every node over it
+//! in z-order, clipped to the area ([`Scene::paint`]).
+//!
+//! Clipping and merging are verified to stay on the screen, so a node moved
+//! against the edge, or a damage list collapsed under pressure, never sends
+//! a repaint past the framebuffer.
+
+use crate::{DrawError, MAX_NODES, MAX_TEXT_LEN};
+use verus_builtin_macros::verus;
+
+verus! {
+
+/// Separate damaged areas kept between presents; past this they are
+/// merged into one
+pub const MAX_DAMAGE: usize = 8;
+
+/// A rectangle of screen pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+}
+
+impl Rect {
+    /// Specification: non-empty and on a `sw` x `sh` screen
+    pub open spec fn within(&self, sw: u16, sh: u16) -> bool {
+        self.w > 0 && self.h > 0 && crate::rect_on_screen(self.x, self.y, self.w, self.h, sw, sh)
+    }
+
+    /// Specification: does `self` cover every pixel of `other`?
+    pub open spec fn covers(&self, other: Rect) -> bool {
+        self.x <= other.x && self.y <= other.y
+            && other.x as int + other.w as int <= self.x as int + self.w as int
+            && other.y as int + other.h as int <= self.y as int + self.h as int
+    }
+}
+
+/// The part of a `w` x `h` rectangle at (x, y) on a `sw` x `sh` screen
+pub fn clip_to_screen(x: u32, y: u32, w: u32, h: u32, sw: u16, sh: u16) -> (r: Option<Rect>)
+    ensures r.is_some() ==> r.unwrap().within(sw, sh),
+{
+    if x >= sw as u32 || y >= sh as u32 || w == 0 || h == 0 {
+        return None;
+    }
+    let right = if w > sw as u32 - x { sw as u32 } else { x + w };
+    let bottom = if h > sh as u32 - y { sh as u32 } else { y + h };
+    Some(Rect { x: x as u16, y: y as u16, w: (right - x) as u16, h: (bottom - y) as u16 })
+}
+
+/// Smallest rectangle covering both `a` and `b`
+pub fn union(a: Rect, b: Rect) -> (r: Rect)
+    ensures
+        r.covers(a) && r.covers(b),
+        forall|sw: u16, sh: u16| a.within(sw, sh) && b.within(sw, sh) ==> r.within(sw, sh),
+{
+    let x = if a.x < b.x { a.x } else { b.x };
+    let y = if a.y < b.y { a.y } else { b.y };
+    let a_right = a.x as u32 + a.w as u32;
+    let b_right = b.x as u32 + b.w as u32;
+    let a_bottom = a.y as u32 + a.h as u32;
+    let b_bottom = b.y as u32 + b.h as u32;
+    let right = if a_right > b_right { a_right } else { b_right };
+    let bottom = if a_bottom > b_bottom { a_bottom } else { b_bottom };
+    // Saturate: only rectangles off any u16 screen can get here
+    let w = if right - x as u32 > u16::MAX as u32 { u16::MAX } else { (right - x as u32) as u16 };
+    let h = if bottom - y as u32 > u16::MAX as u32 { u16::MAX } else { (bottom - y as u32) as u16 };
+    Rect { x, y, w, h }
+}
+
+/// Pixels `a` and `b` share, if any
+pub fn intersect(a: Rect, b: Rect) -> (r: Option<Rect>)
+    ensures r.is_some() ==> a.covers(r.unwrap()) && b.covers(r.unwrap()) && r.unwrap().w > 0 && r.unwrap().h > 0,
+{
+    let x = if a.x > b.x { a.x } else { b.x };
+    let y = if a.y > b.y { a.y } else { b.y };
+    let a_right = a.x as u32 + a.w as u32;
+    let b_right = b.x as u32 + b.w as u32;
+    let a_bottom = a.y as u32 + a.h as u32;
+    let b_bottom = b.y as u32 + b.h as u32;
+    let right = if a_right < b_right { a_right } else { b_right };
+    let bottom = if a_bottom < b_bottom { a_bottom } else { b_bottom };
+    if right <= x as u32 || bottom <= y as u32 {
+        None
+    } else {
+        Some(Rect { x, y, w: (right - x as u32) as u16, h: (bottom - y as u32) as u16 })
+    }
+}
+
+/// Do `a` and `b` overlap or share an edge? Such areas are repainted as one.
+fn touches(a: Rect, b: Rect) -> bool {
+    a.x as u32 <= b.x as u32 + b.w as u32 && b.x as u32 <= a.x as u32 + a.w as u32
+        && a.y as u32 <= b.y as u32 + b.h as u32 && b.y as u32 <= a.y as u32 + a.h as u32
+}
+
+} // verus!
+
+/// Screen areas to repaint
+#[derive(Clone, Copy, Debug)]
+pub struct Damage {
+    rects: [Rect; MAX_DAMAGE],
+    len: usize,
+}
+
+impl Damage {
+    pub const fn new() -> Self {
+        Self { rects: [Rect { x: 0, y: 0, w: 0, h: 0 }; MAX_DAMAGE], len: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The damaged areas; none of them overlap or touch
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects[..self.len]
+    }
+
+    /// Record `area` as damaged
+    ///
+    /// Areas it touches are merged into it. When [`MAX_DAMAGE`] separate
+    /// areas are already recorded, everything collapses into one covering
+    /// rectangle: repainting too much is safe, missing an area is not.
+    pub fn add(&mut self, area: Rect) {
+        let mut area = area;
+        let mut i = 0;
+        while i < self.len {
+            if touches(self.rects[i], area) {
+                area = union(self.rects[i], area);
+                self.len -= 1;
+                self.rects[i] = self.rects[self.len];
+                // The grown area may now touch one already passed
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+        if self.len == MAX_DAMAGE {
+            for rect in &self.rects {
+                area = union(*rect, area);
+            }
+            self.len = 0;
+        }
+        self.rects[self.len] = area;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a node shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Content {
+    /// Filled with `color`
+    Rect { color: u32 },
+    /// UTF-8 copied out of the text pool when the node was set
+    Text { bytes: [u8; MAX_TEXT_LEN as usize], len: u8, color: u32 },
+    /// The pixels stay in the App's image slot
+    Image { slot: u16 },
+}
+
+/// One retained primitive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Node {
+    pub content: Content,
+    /// Content position in the node's coordinates
+    pub x: u16,
+    pub y: u16,
+    /// Content size in pixels (for text, as the Display PD measured it)
+    pub w: u16,
+    pub h: u16,
+    /// Translation of the node's origin on the screen
+    pub tx: u16,
+    pub ty: u16,
+    /// Stacking order: higher is drawn later, ties by node number
+    pub z: u16,
+}
+
+impl Node {
+    /// Top-left corner of the content on the screen; may be past the edge
+    pub fn origin(&self) -> (u32, u32) {
+        (self.tx as u32 + self.x as u32, self.ty as u32 + self.y as u32)
+    }
+
+    /// The part of the node on a `sw` x `sh` screen
+    pub fn bounds(&self, sw: u16, sh: u16) -> Option<Rect> {
+        let (x, y) = self.origin();
+        clip_to_screen(x, y, self.w as u32, self.h as u32, sw, sh)
+    }
+}
+
+/// Nodes in slots 0..MAX_NODES, for node numbers 1..=MAX_NODES
+const NODE_SLOTS: usize = MAX_NODES as usize;
+
+/// The retained scene: nodes, background and what changed since the last
+/// repaint
+pub struct Scene {
+    nodes: [Option<Node>; NODE_SLOTS],
+    background: u32,
+    damage: Damage,
+    width: u16,
+    height: u16,
+}
+
+impl Scene {
+    /// An empty scene on a `width` x `height` screen; nothing is damaged
+    /// until a node is set
+    pub const fn new(width: u16, height: u16, background: u32) -> Self {
+        Self { nodes: [None; NODE_SLOTS], background, damage: Damage::new(), width, height }
+    }
+
+    /// Background colour under the nodes (ARGB8888)
+    pub fn background(&self) -> u32 {
+        self.background
+    }
+
+    /// Node `node`, if it has content
+    pub fn node(&self, node: u8) -> Option<&Node> {
+        let slot = Self::slot(node).ok()?;
+        self.nodes[slot].as_ref()
+    }
+
+    fn slot(node: u8) -> Result<usize, DrawError> {
+        if node == 0 || node > MAX_NODES {
+            return Err(DrawError::BadNode(node));
+        }
+        Ok(node as usize - 1)
+    }
+
+    /// Set what `node` shows: `content`, `w` x `h` at (x, y) in the node's
+    /// coordinates. A new node starts at the screen origin with z-order 0.
+    pub fn set_content(&mut self, node: u8, x: u16, y: u16, w: u16, h: u16, content: Content) -> Result<(), DrawError> {
+        let slot = Self::slot(node)?;
+        let (tx, ty, z) = self.nodes[slot].map_or((0, 0, 0), |old| (old.tx, old.ty, old.z));
+        self.replace(slot, Some(Node { content, x, y, w, h, tx, ty, z }));
+        Ok(())
+    }
+
+    /// Translate `node` to (tx, ty) and stack it at z-order `z`
+    pub fn move_node(&mut self, node: u8, tx: u16, ty: u16, z: u16) -> Result<(), DrawError> {
+        let slot = Self::slot(node)?;
+        let old = self.nodes[slot].ok_or(DrawError::UnknownNode(node))?;
+        self.replace(slot, Some(Node { tx, ty, z, ..old }));
+        Ok(())
+    }
+
+    /// Drop `node`
+    pub fn remove(&mut self, node: u8) -> Result<(), DrawError> {
+        let slot = Self::slot(node)?;
+        if self.nodes[slot].is_none() {
+            return Err(DrawError::UnknownNode(node));
+        }
+        self.replace(slot, None);
+        Ok(())
+    }
+
+    /// Drop every node and fill the screen with `background`
+    pub fn reset(&mut self, background: u32) {
+        self.nodes = [None; NODE_SLOTS];
+        self.background = background;
+        self.damage.clear();
+        if let Some(screen) = clip_to_screen(0, 0, self.width as u32, self.height as u32, self.width, self.height) {
+            self.damage.add(screen);
+        }
+    }
+
+    /// Store `node` in `slot`, damaging where it was and where it is now;
+    /// an unchanged node damages nothing
+    fn replace(&mut self, slot: usize, node: Option<Node>) {
+        let old = self.nodes[slot];
+        if old == node {
+            return;
+        }
+        for area in [old, node].iter().flatten().filter_map(|n| n.bounds(self.width, self.height)) {
+            self.damage.add(area);
+        }
+        self.nodes[slot] = node;
+    }
+
+    /// The areas changed since the last call; the list starts over empty
+    pub fn take_damage(&mut self) -> Damage {
+        core::mem::take(&mut self.damage)
+    }
+
+    /// Call `draw` with each node overlapping `area`, bottom to top, and
+    /// the part of it inside `area`
+    pub fn paint(&self, area: Rect, mut draw: impl FnMut(&Node, Rect)) {
+        // Insertion sort of node slots by (z, node number)
+        let mut order = [0usize; NODE_SLOTS];
+        let mut len = 0;
+        for (slot, node) in self.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            let mut at = len;
+            while at > 0 && self.nodes[order[at - 1]].is_some_and(|below| below.z > node.z) {
+                order[at] = order[at - 1];
+                at -= 1;
+            }
+            order[at] = slot;
+            len += 1;
+        }
+
+        for &slot in &order[..len] {
+            let Some(node) = &self.nodes[slot] else { continue };
+            if let Some(clip) = node.bounds(self.width, self.height).and_then(|bounds| intersect(bounds, area)) {
+                draw(node, clip);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: u16, y: u16, w: u16, h: u16) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    fn solid(color: u32) -> Content {
+        Content::Rect { color }
+    }
+
+    #[test]
+    fn clipping_keeps_rects_on_screen() {
+        assert_eq!(clip_to_screen(300, 230, 40, 40, 320, 240), Some(rect(300, 230, 20, 10)));
+        assert_eq!(clip_to_screen(320, 0, 4, 4, 320, 240), None);
+        assert_eq!(clip_to_screen(0, 0, 0, 4, 320, 240), None);
+        assert_eq!(clip_to_screen(10, 10, u32::MAX, u32::MAX, 320, 240), Some(rect(10, 10, 310, 230)));
+        assert_eq!(intersect(rect(0, 0, 10, 10), rect(10, 0, 5, 5)), None);
+        assert_eq!(intersect(rect(0, 0, 10, 10), rect(5, 5, 10, 10)), Some(rect(5, 5, 5, 5)));
+        assert_eq!(union(rect(0, 0, 10, 10), rect(20, 5, 10, 10)), rect(0, 0, 30, 15));
+    }
+
+    #[test]
+    fn damage_merges_touching_areas() {
+        let mut damage = Damage::new();
+        damage.add(rect(0, 0, 10, 10));
+        damage.add(rect(100, 100, 10, 10));
+        assert_eq!(damage.rects().len(), 2);
+        // Shares an edge with the first, then bridges to the second
+        damage.add(rect(10, 0, 5, 5));
+        assert_eq!(damage.rects(), &[rect(100, 100, 10, 10), rect(0, 0, 15, 10)]);
+        damage.add(rect(14, 9, 90, 92));
+        assert_eq!(damage.rects(), &[rect(0, 0, 110, 110)]);
+    }
+
+    #[test]
+    fn damage_collapses_when_full() {
+        let mut damage = Damage::new();
+        for i in 0..MAX_DAMAGE as u16 + 1 {
+            damage.add(rect(i * 20, 0, 10, 10));
+        }
+        assert_eq!(damage.rects(), &[rect(0, 0, 170, 10)]);
+    }
+
+    #[test]
+    fn changes_damage_old_and_new_bounds() {
+        let mut scene = Scene::new(320, 240, 0);
+        scene.set_content(1, 0, 0, 10, 10, solid(1)).unwrap();
+        assert_eq!(scene.take_damage().rects(), &[rect(0, 0, 10, 10)]);
+
+        // Setting the same content again damages nothing
+        scene.set_content(1, 0, 0, 10, 10, solid(1)).unwrap();
+        assert!(scene.take_damage().is_empty());
+
+        scene.move_node(1, 100, 50, 0).unwrap();
+        assert_eq!(scene.take_damage().rects(), &[rect(0, 0, 10, 10), rect(100, 50, 10, 10)]);
+
+        // Moved past the edge: only the visible part is damaged
+        scene.move_node(1, 315, 50, 0).unwrap();
+        assert_eq!(scene.take_damage().rects(), &[rect(100, 50, 10, 10), rect(315, 50, 5, 10)]);
+
+        scene.remove(1).unwrap();
+        assert_eq!(scene.take_damage().rects(), &[rect(315, 50, 5, 10)]);
+        assert_eq!(scene.remove(1), Err(DrawError::UnknownNode(1)));
+        assert_eq!(scene.move_node(2, 0, 0, 0), Err(DrawError::UnknownNode(2)));
+        assert_eq!(scene.set_content(0, 0, 0, 1, 1, solid(0)), Err(DrawError::BadNode(0)));
+    }
+
+    #[test]
+    fn set_content_keeps_the_transform() {
+        let mut scene = Scene::new(320, 240, 0);
+        scene.set_content(3, 2, 2, 10, 10, solid(1)).unwrap();
+        scene.move_node(3, 40, 40, 5).unwrap();
+        scene.set_content(3, 0, 0, 4, 4, solid(2)).unwrap();
+        let node = scene.node(3).unwrap();
+        assert_eq!((node.tx, node.ty, node.z, node.origin()), (40, 40, 5, (40, 40)));
+    }
+
+    #[test]
+    fn paint_runs_bottom_to_top_clipped_to_the_area() {
+        let mut scene = Scene::new(320, 240, 0);
+        scene.set_content(1, 0, 0, 50, 50, solid(1)).unwrap();
+        scene.set_content(2, 0, 0, 50, 50, solid(2)).unwrap();
+        scene.set_content(3, 200, 200, 10, 10, solid(3)).unwrap();
+        scene.move_node(1, 10, 10, 9).unwrap();
+
+        let mut drawn = [(0u32, rect(0, 0, 0, 0)); 3];
+        let mut n = 0;
+        scene.paint(rect(0, 0, 30, 30), |node, clip| {
+            let Content::Rect { color } = node.content else { unreachable!() };
+            drawn[n] = (color, clip);
+            n += 1;
+        });
+        // Node 2 (z 0) under node 1 (z 9); node 3 is outside the area
+        assert_eq!(&drawn[..n], &[(2, rect(0, 0, 30, 30)), (1, rect(10, 10, 20, 20))]);
+    }
+
+    #[test]
+    fn reset_drops_nodes_and_damages_the_screen() {
+        let mut scene = Scene::new(320, 240, 0);
+        scene.set_content(1, 0, 0, 5, 5, solid(1)).unwrap();
+        scene.reset(0xFF00_00FF);
+        assert!(scene.node(1).is_none());
+        assert_eq!(scene.background(), 0xFF00_00FF);
+        assert_eq!(scene.take_damage().rects(), &[rect(0, 0, 320, 240)]);
+    }
+}
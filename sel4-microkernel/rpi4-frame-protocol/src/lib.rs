@@ -0,0 +1,516 @@
+//! Verified frame handoff protocol for the split App/Present architecture.
+//!
+//! The App PD renders into one of [`FRAME_SLOTS`] buffers in a shared region
+//! and publishes it. The Present PD, the only PD with the scan-out
+//! framebuffer, mailbox and vsync IRQ, takes the newest published frame at
+//! each vertical blank, copies it out and hands the slot back.
+//!
+//! Every slot carries a state word. Each transition belongs to exactly one
+//! side, so ownership of the pixels follows from the state alone:
+//!
+//! ```text
+//!            Acquire (App)          Publish (App)
+//!   Free ─────────────────► Rendering ─────────────► Ready
+//!    ▲                                                │ │
+//!    │ Release (Present)       Take (Present)         │ │ Discard (Present,
+//!    └──────────── Presenting ◄───────────────────────┘ │  superseded frame)
+//!    └──────────────────────────────────────────────────┘
+//! ```
+//!
+//! The App only writes pixels in `Rendering`, the Present PD only reads them
+//! in `Presenting`, and no transition of one side enters or leaves a state
+//! owned by the other (`lemma_sides_never_share_a_slot`).
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::new_without_default)]
+
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+use verus_builtin_macros::verus;
+
+verus! {
+
+/// App PD end of the frame channel (App notifies: frame published)
+pub const APP_FRAME_CHANNEL_ID: usize = 3;
+/// Present PD end of the frame channel (Present notifies: slot released)
+pub const PRESENT_FRAME_CHANNEL_ID: usize = 1;
+
+pub const FRAME_SLOTS: usize = 3;
+pub const MAX_FRAME_WIDTH: u32 = 1280;
+pub const MAX_FRAME_HEIGHT: u32 = 720;
+
+/// Where both PDs map the shared frame region (tvdemo-split.system)
+pub const FRAME_REGION_VADDR: usize = 0x5_1000_0000;
+
+/// Header page at the start of the shared region
+pub const FRAME_HEADER_SIZE: usize = 0x1000;
+/// One ARGB8888 frame at the maximum geometry, rows packed (pitch == width)
+pub const FRAME_SLOT_BYTES: usize = 0x38_4000;
+pub const FRAME_REGION_SIZE: usize = 0xA8_D000;
+
+pub const FRAME_MAGIC: u32 = 0x4652_4D31; // "FRM1"
+
+pub const SLOT_FREE: u32 = 0;
+pub const SLOT_RENDERING: u32 = 1;
+pub const SLOT_READY: u32 = 2;
+pub const SLOT_PRESENTING: u32 = 3;
+
+pub open spec fn valid_slot_state(state: u32) -> bool {
+    state <= SLOT_PRESENTING
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    App,
+    Present,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandoffOp {
+    /// App claims a free slot to render into
+    Acquire,
+    /// App hands a finished frame over
+    Publish,
+    /// Present PD takes a published frame for scan-out
+    Take,
+    /// Present PD is done reading a frame
+    Release,
+    /// Present PD drops a published frame a newer one superseded
+    Discard,
+}
+
+pub open spec fn op_side(op: HandoffOp) -> Side {
+    match op {
+        HandoffOp::Acquire => Side::App,
+        HandoffOp::Publish => Side::App,
+        _ => Side::Present,
+    }
+}
+
+pub open spec fn transition_spec(state: u32, op: HandoffOp) -> Option<u32> {
+    match op {
+        HandoffOp::Acquire => if state == SLOT_FREE { Some(SLOT_RENDERING) } else { None },
+        HandoffOp::Publish => if state == SLOT_RENDERING { Some(SLOT_READY) } else { None },
+        HandoffOp::Take => if state == SLOT_READY { Some(SLOT_PRESENTING) } else { None },
+        HandoffOp::Release => if state == SLOT_PRESENTING { Some(SLOT_FREE) } else { None },
+        HandoffOp::Discard => if state == SLOT_READY { Some(SLOT_FREE) } else { None },
+    }
+}
+
+pub fn next_state(state: u32, op: HandoffOp) -> (result: Option<u32>)
+    ensures
+        result == transition_spec(state, op),
+{
+    match op {
+        HandoffOp::Acquire => if state == SLOT_FREE { Some(SLOT_RENDERING) } else { None },
+        HandoffOp::Publish => if state == SLOT_RENDERING { Some(SLOT_READY) } else { None },
+        HandoffOp::Take => if state == SLOT_READY { Some(SLOT_PRESENTING) } else { None },
+        HandoffOp::Release => if state == SLOT_PRESENTING { Some(SLOT_FREE) } else { None },
+        HandoffOp::Discard => if state == SLOT_READY { Some(SLOT_FREE) } else { None },
+    }
+}
+
+/// The App never touches a slot being scanned out and the Present PD never
+/// touches one being rendered; a frame reaches scan-out only once published.
+pub proof fn lemma_sides_never_share_a_slot(state: u32, op: HandoffOp)
+    requires
+        transition_spec(state, op).is_some(),
+    ensures
+        valid_slot_state(transition_spec(state, op).unwrap()),
+        op_side(op) == Side::App ==> state != SLOT_PRESENTING
+            && transition_spec(state, op).unwrap() != SLOT_PRESENTING,
+        op_side(op) == Side::Present ==> state != SLOT_RENDERING
+            && transition_spec(state, op).unwrap() != SLOT_RENDERING,
+        transition_spec(state, op).unwrap() == SLOT_PRESENTING ==> state == SLOT_READY,
+{
+}
+
+/// First free slot, if any
+pub fn pick_free(states: &[u32; FRAME_SLOTS]) -> (result: Option<usize>)
+    ensures
+        result.is_some() ==> result.unwrap() < FRAME_SLOTS
+            && states[result.unwrap() as int] == SLOT_FREE,
+        result.is_none() ==> forall|j: int| 0 <= j < FRAME_SLOTS ==> states[j] != SLOT_FREE,
+{
+    let mut i: usize = 0;
+    while i < FRAME_SLOTS
+        invariant
+            i <= FRAME_SLOTS,
+            forall|j: int| 0 <= j < i ==> states[j] != SLOT_FREE,
+        decreases FRAME_SLOTS - i,
+    {
+        if states[i] == SLOT_FREE {
+            return Some(i);
+        }
+        i = i + 1;
+    }
+    None
+}
+
+/// Published slot with the highest sequence number, if any
+pub fn pick_newest_ready(states: &[u32; FRAME_SLOTS], seqs: &[u64; FRAME_SLOTS]) -> (result: Option<usize>)
+    ensures
+        result.is_some() ==> result.unwrap() < FRAME_SLOTS
+            && states[result.unwrap() as int] == SLOT_READY
+            && forall|j: int| 0 <= j < FRAME_SLOTS && states[j] == SLOT_READY
+                ==> seqs[j] <= seqs[result.unwrap() as int],
+        result.is_none() ==> forall|j: int| 0 <= j < FRAME_SLOTS ==> states[j] != SLOT_READY,
+{
+    let mut best: Option<usize> = None;
+    let mut i: usize = 0;
+    while i < FRAME_SLOTS
+        invariant
+            i <= FRAME_SLOTS,
+            best.is_some() ==> best.unwrap() < i && states[best.unwrap() as int] == SLOT_READY
+                && forall|j: int| 0 <= j < i && states[j] == SLOT_READY
+                    ==> seqs[j] <= seqs[best.unwrap() as int],
+            best.is_none() ==> forall|j: int| 0 <= j < i ==> states[j] != SLOT_READY,
+        decreases FRAME_SLOTS - i,
+    {
+        if states[i] == SLOT_READY {
+            best = match best {
+                Some(b) => if seqs[i] > seqs[b] { Some(i) } else { Some(b) },
+                None => Some(i),
+            };
+        }
+        i = i + 1;
+    }
+    best
+}
+
+pub open spec fn valid_geometry(width: u32, height: u32) -> bool {
+    0 < width <= MAX_FRAME_WIDTH && 0 < height <= MAX_FRAME_HEIGHT
+}
+
+/// Frame size the App renders at for a scan-out framebuffer of the given
+/// size: the framebuffer itself, clipped to the slot capacity
+pub fn frame_geometry(fb_width: u32, fb_height: u32) -> (result: Option<(u32, u32)>)
+    ensures
+        result.is_some() <==> fb_width > 0 && fb_height > 0,
+        result.is_some() ==> valid_geometry(result.unwrap().0, result.unwrap().1),
+{
+    if fb_width == 0 || fb_height == 0 {
+        return None;
+    }
+    let width = if fb_width < MAX_FRAME_WIDTH { fb_width } else { MAX_FRAME_WIDTH };
+    let height = if fb_height < MAX_FRAME_HEIGHT { fb_height } else { MAX_FRAME_HEIGHT };
+    Some((width, height))
+}
+
+/// A frame of valid geometry fits its slot
+pub proof fn lemma_frame_fits_slot(width: u32, height: u32)
+    requires
+        valid_geometry(width, height),
+    ensures
+        (width as int) * (height as int) * 4 <= FRAME_SLOT_BYTES,
+{
+    assert((width as int) * (height as int) <= 1280 * 720) by (nonlinear_arith)
+        requires
+            0 < width <= 1280,
+            0 < height <= 720;
+}
+
+pub fn slot_offset(slot: usize) -> (offset: usize)
+    requires
+        slot < FRAME_SLOTS,
+    ensures
+        offset == FRAME_HEADER_SIZE + slot * FRAME_SLOT_BYTES,
+        offset + FRAME_SLOT_BYTES <= FRAME_REGION_SIZE,
+{
+    assert(slot * FRAME_SLOT_BYTES <= 2 * FRAME_SLOT_BYTES) by (nonlinear_arith)
+        requires
+            slot <= 2;
+    FRAME_HEADER_SIZE + slot * FRAME_SLOT_BYTES
+}
+
+} // verus!
+
+// ============================================================================
+// NON-VERIFIED RUNTIME HELPERS
+// ============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandoffError {
+    /// The Present PD has not published the header yet
+    NotReady,
+    /// Slot index out of range
+    InvalidSlot(usize),
+    /// The slot is not in a state the operation may leave it from
+    WrongState { slot: usize, state: u32 },
+}
+
+impl From<HandoffError> for PdError {
+    fn from(e: HandoffError) -> Self {
+        match e {
+            HandoffError::NotReady => PdError::new(ErrorDomain::FrameHandoff, ErrorKind::NotReady),
+            HandoffError::InvalidSlot(slot) => {
+                PdError::new(ErrorDomain::FrameHandoff, ErrorKind::InvalidArgument).with_detail(slot as u16)
+            }
+            // Detail: slot in the high byte, its state in the low byte
+            HandoffError::WrongState { slot, state } => {
+                PdError::new(ErrorDomain::FrameHandoff, ErrorKind::InvalidState)
+                    .with_detail(((slot as u16) << 8) | (state as u16 & 0xFF))
+            }
+        }
+    }
+}
+
+/// Shared header at the start of the frame region.
+///
+/// The Present PD writes the geometry and then the magic; the App must not
+/// touch a slot before it sees the magic.
+#[repr(C, align(64))]
+pub struct FrameHeader {
+    magic: AtomicU32,
+    width: AtomicU32,
+    height: AtomicU32,
+    _pad: u32,
+    states: [AtomicU32; FRAME_SLOTS],
+    _pad2: u32,
+    seqs: [AtomicU64; FRAME_SLOTS],
+}
+
+impl FrameHeader {
+    pub const fn new() -> Self {
+        Self {
+            magic: AtomicU32::new(0),
+            width: AtomicU32::new(0),
+            height: AtomicU32::new(0),
+            _pad: 0,
+            states: [AtomicU32::new(SLOT_FREE), AtomicU32::new(SLOT_FREE), AtomicU32::new(SLOT_FREE)],
+            _pad2: 0,
+            seqs: [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)],
+        }
+    }
+
+    /// Present PD: free every slot and publish the frame geometry.
+    /// Only call while the App is not rendering (at boot).
+    pub fn reset(&self, width: u32, height: u32) {
+        self.magic.store(0, Ordering::Release);
+        for slot in 0..FRAME_SLOTS {
+            self.states[slot].store(SLOT_FREE, Ordering::Relaxed);
+            self.seqs[slot].store(0, Ordering::Relaxed);
+        }
+        self.width.store(width, Ordering::Relaxed);
+        self.height.store(height, Ordering::Relaxed);
+        self.magic.store(FRAME_MAGIC, Ordering::Release);
+    }
+
+    /// Frame geometry, once the Present PD has published it
+    pub fn geometry(&self) -> Result<(u32, u32), HandoffError> {
+        if self.magic.load(Ordering::Acquire) != FRAME_MAGIC {
+            return Err(HandoffError::NotReady);
+        }
+        Ok((self.width.load(Ordering::Relaxed), self.height.load(Ordering::Relaxed)))
+    }
+
+    pub fn states(&self) -> [u32; FRAME_SLOTS] {
+        [
+            self.states[0].load(Ordering::Acquire),
+            self.states[1].load(Ordering::Acquire),
+            self.states[2].load(Ordering::Acquire),
+        ]
+    }
+
+    fn seqs(&self) -> [u64; FRAME_SLOTS] {
+        [
+            self.seqs[0].load(Ordering::Relaxed),
+            self.seqs[1].load(Ordering::Relaxed),
+            self.seqs[2].load(Ordering::Relaxed),
+        ]
+    }
+
+    /// Apply one transition. Only one side may move a slot out of any given
+    /// state, so a failed exchange means the peer broke the protocol (or
+    /// restarted) rather than a benign race.
+    fn apply(&self, slot: usize, op: HandoffOp) -> Result<(), HandoffError> {
+        if slot >= FRAME_SLOTS {
+            return Err(HandoffError::InvalidSlot(slot));
+        }
+        let state = self.states[slot].load(Ordering::Acquire);
+        let next = next_state(state, op).ok_or(HandoffError::WrongState { slot, state })?;
+        self.states[slot]
+            .compare_exchange(state, next, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+            .map_err(|state| HandoffError::WrongState { slot, state })
+    }
+}
+
+/// App PD end: renders into slots and publishes them
+pub struct FrameProducer<'a> {
+    header: &'a FrameHeader,
+    next_seq: u64,
+}
+
+impl<'a> FrameProducer<'a> {
+    pub fn new(header: &'a FrameHeader) -> Self {
+        Self { header, next_seq: 1 }
+    }
+
+    /// Frame geometry, once the Present PD has published it
+    pub fn geometry(&self) -> Result<(u32, u32), HandoffError> {
+        self.header.geometry()
+    }
+
+    /// Claim a free slot to render into; `Ok(None)` if all are busy
+    /// (wait for the Present PD to release one)
+    pub fn acquire(&mut self) -> Result<Option<usize>, HandoffError> {
+        self.header.geometry()?;
+        match pick_free(&self.header.states()) {
+            Some(slot) => {
+                self.header.apply(slot, HandoffOp::Acquire)?;
+                Ok(Some(slot))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Hand a rendered slot to the Present PD; returns its sequence number
+    pub fn publish(&mut self, slot: usize) -> Result<u64, HandoffError> {
+        if slot >= FRAME_SLOTS {
+            return Err(HandoffError::InvalidSlot(slot));
+        }
+        // The sequence word belongs to whoever owns the slot
+        let state = self.header.states[slot].load(Ordering::Acquire);
+        if state != SLOT_RENDERING {
+            return Err(HandoffError::WrongState { slot, state });
+        }
+        let seq = self.next_seq;
+        // Ordered before the state change by the exchange's release
+        self.header.seqs[slot].store(seq, Ordering::Relaxed);
+        self.header.apply(slot, HandoffOp::Publish)?;
+        self.next_seq += 1;
+        Ok(seq)
+    }
+}
+
+/// Present PD end: takes published frames for scan-out
+pub struct FrameConsumer<'a> {
+    header: &'a FrameHeader,
+}
+
+impl<'a> FrameConsumer<'a> {
+    pub fn new(header: &'a FrameHeader) -> Self {
+        Self { header }
+    }
+
+    /// Take the newest published frame, discarding older published ones.
+    /// `Ok(None)` if nothing new was published.
+    pub fn take_newest(&mut self) -> Result<Option<usize>, HandoffError> {
+        let states = self.header.states();
+        let seqs = self.header.seqs();
+        let newest = match pick_newest_ready(&states, &seqs) {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        // Only this side moves a slot out of Ready, so the snapshot holds
+        for (slot, &state) in states.iter().enumerate() {
+            if slot != newest && state == SLOT_READY {
+                self.header.apply(slot, HandoffOp::Discard)?;
+            }
+        }
+        self.header.apply(newest, HandoffOp::Take)?;
+        Ok(Some(newest))
+    }
+
+    /// Give a presented slot back to the App
+    pub fn release(&mut self, slot: usize) -> Result<(), HandoffError> {
+        self.header.apply(slot, HandoffOp::Release)
+    }
+}
+
+/// # Safety
+/// `base` must be the start of the mapped frame region.
+pub unsafe fn header_ptr(base: *mut u8) -> *const FrameHeader {
+    base as *const FrameHeader
+}
+
+/// Pixels of `slot`, `width * height` packed ARGB8888 words
+///
+/// # Safety
+/// `base` must be the start of the mapped frame region and `slot` below
+/// [`FRAME_SLOTS`].
+pub unsafe fn slot_ptr(base: *mut u8, slot: usize) -> *mut u32 {
+    base.add(slot_offset(slot)) as *mut u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_is_stable() {
+        assert!(core::mem::size_of::<FrameHeader>() <= FRAME_HEADER_SIZE);
+        assert_eq!(FRAME_SLOT_BYTES, (MAX_FRAME_WIDTH * MAX_FRAME_HEIGHT * 4) as usize);
+        assert_eq!(FRAME_REGION_SIZE, FRAME_HEADER_SIZE + FRAME_SLOTS * FRAME_SLOT_BYTES);
+        assert_eq!(FRAME_REGION_SIZE % 0x1000, 0);
+    }
+
+    #[test]
+    fn transitions_follow_the_state_machine() {
+        assert_eq!(next_state(SLOT_FREE, HandoffOp::Acquire), Some(SLOT_RENDERING));
+        assert_eq!(next_state(SLOT_RENDERING, HandoffOp::Publish), Some(SLOT_READY));
+        assert_eq!(next_state(SLOT_READY, HandoffOp::Take), Some(SLOT_PRESENTING));
+        assert_eq!(next_state(SLOT_PRESENTING, HandoffOp::Release), Some(SLOT_FREE));
+        assert_eq!(next_state(SLOT_READY, HandoffOp::Discard), Some(SLOT_FREE));
+        assert_eq!(next_state(SLOT_RENDERING, HandoffOp::Take), None);
+        assert_eq!(next_state(SLOT_PRESENTING, HandoffOp::Acquire), None);
+    }
+
+    #[test]
+    fn producer_waits_for_geometry() {
+        let header = FrameHeader::new();
+        let mut app = FrameProducer::new(&header);
+        assert_eq!(app.acquire(), Err(HandoffError::NotReady));
+        header.reset(640, 480);
+        assert_eq!(header.geometry(), Ok((640, 480)));
+        assert_eq!(app.acquire(), Ok(Some(0)));
+    }
+
+    #[test]
+    fn present_takes_newest_and_discards_stale() {
+        let header = FrameHeader::new();
+        header.reset(1280, 720);
+        let mut app = FrameProducer::new(&header);
+        let mut present = FrameConsumer::new(&header);
+
+        let a = app.acquire().unwrap().unwrap();
+        app.publish(a).unwrap();
+        let b = app.acquire().unwrap().unwrap();
+        app.publish(b).unwrap();
+        let c = app.acquire().unwrap().unwrap();
+        assert_eq!(app.acquire(), Ok(None));
+
+        assert_eq!(present.take_newest(), Ok(Some(b)));
+        assert_eq!(header.states()[a], SLOT_FREE);
+        assert_eq!(header.states()[c], SLOT_RENDERING);
+        assert_eq!(present.take_newest(), Ok(None));
+
+        // The App cannot reuse the slot on screen until it is released
+        assert_eq!(app.acquire(), Ok(Some(a)));
+        assert_eq!(app.acquire(), Ok(None));
+        present.release(b).unwrap();
+        assert_eq!(app.acquire(), Ok(Some(b)));
+    }
+
+    #[test]
+    fn out_of_order_operations_are_rejected() {
+        let header = FrameHeader::new();
+        header.reset(320, 240);
+        let mut app = FrameProducer::new(&header);
+        let mut present = FrameConsumer::new(&header);
+
+        assert_eq!(app.publish(1), Err(HandoffError::WrongState { slot: 1, state: SLOT_FREE }));
+        assert_eq!(present.release(0), Err(HandoffError::WrongState { slot: 0, state: SLOT_FREE }));
+        assert_eq!(present.release(FRAME_SLOTS), Err(HandoffError::InvalidSlot(FRAME_SLOTS)));
+    }
+
+    #[test]
+    fn geometry_is_clipped_to_slot() {
+        assert_eq!(frame_geometry(1920, 1080), Some((1280, 720)));
+        assert_eq!(frame_geometry(800, 480), Some((800, 480)));
+        assert_eq!(frame_geometry(0, 480), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! # Layer Compositor
+//!
+//! A background layer (the photo) and an on-screen display layer (info
+//! bars, captions) kept in memory and combined into the framebuffer, so an
+//! overlay can be drawn, changed or cleared without re-rendering what is
+//! under it.
+//!
+//! Both layers are ARGB8888 buffers the size of the screen, in memory the
+//! PD provides. The background is treated as opaque; OSD pixels carry their
+//! own alpha, so a bar filled with `0x8000_0000` darkens the photo under it
+//! by half and a cleared OSD pixel shows the photo unchanged. Each layer
+//! remembers the area changed since the last [`Compositor::present`], and
+//! only that area is recomposed.
+
+use crate::blend::{over, TRANSPARENT};
+use crate::font::TextTarget;
+use crate::framebuffer::Framebuffer;
+use crate::graphics::{Color, Rect};
+
+/// Pixel area `x0..x1` by `y0..y1`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Area {
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+}
+
+impl Area {
+    /// Bounding box of both areas
+    fn union(a: Option<Area>, b: Option<Area>) -> Option<Area> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(Area {
+                x0: a.x0.min(b.x0),
+                y0: a.y0.min(b.y0),
+                x1: a.x1.max(b.x1),
+                y1: a.y1.max(b.y1),
+            }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn rect(&self) -> Rect {
+        Rect::new(self.x0 as i32, self.y0 as i32, self.x1 - self.x0, self.y1 - self.y0)
+    }
+}
+
+/// One screen-sized ARGB layer over caller-provided pixels
+pub struct Layer<'a> {
+    pixels: &'a mut [u32],
+    width: u32,
+    height: u32,
+    /// Changed since the last present
+    damage: Option<Area>,
+    /// Everything drawn since the last clear
+    painted: Option<Area>,
+}
+
+impl<'a> Layer<'a> {
+    /// Layer of `width` x `height` pixels, row-major in `pixels`; `None` if
+    /// `pixels` is too short
+    pub fn new(pixels: &'a mut [u32], width: u32, height: u32) -> Option<Self> {
+        if (pixels.len() as u64) < width as u64 * height as u64 {
+            return None;
+        }
+        Some(Self { pixels, width, height, damage: None, painted: None })
+    }
+
+    /// Layer dimensions
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Pixel at (x, y), or `None` off the layer
+    pub fn pixel(&self, x: u32, y: u32) -> Option<u32> {
+        (x < self.width && y < self.height)
+            .then(|| self.pixels[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Set a rectangle, clipped to the layer, to `argb`. The alpha is
+    /// stored, not applied: it decides how the pixel covers the layers
+    /// below when presented.
+    pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, argb: u32) {
+        let x1 = x.saturating_add(w).min(self.width);
+        let y1 = y.saturating_add(h).min(self.height);
+        if x >= x1 || y >= y1 {
+            return;
+        }
+        for py in y..y1 {
+            let row = py as usize * self.width as usize;
+            self.pixels[row + x as usize..row + x1 as usize].fill(argb);
+        }
+        self.touch(Area { x0: x, y0: y, x1, y1 });
+    }
+
+    /// Set the whole layer to `argb`
+    pub fn fill(&mut self, argb: u32) {
+        self.fill_rect(0, 0, self.width, self.height, argb);
+    }
+
+    /// Make the layer fully transparent. Only what was drawn since the last
+    /// clear is rewritten and recomposed.
+    pub fn clear(&mut self) {
+        if let Some(area) = self.painted.take() {
+            self.fill_rect(area.x0, area.y0, area.x1 - area.x0, area.y1 - area.y0, TRANSPARENT);
+            self.painted = None;
+        }
+    }
+
+    /// The pixels, row-major with `width` per row, for renderers that write
+    /// whole images; the whole layer counts as changed
+    pub fn pixels_mut(&mut self) -> &mut [u32] {
+        self.touch(self.full());
+        &mut self.pixels[..self.width as usize * self.height as usize]
+    }
+
+    /// Area changed since the last present
+    pub fn damage(&self) -> Option<Rect> {
+        self.damage.map(|area| area.rect())
+    }
+
+    fn full(&self) -> Area {
+        Area { x0: 0, y0: 0, x1: self.width, y1: self.height }
+    }
+
+    fn touch(&mut self, area: Area) {
+        self.damage = Area::union(self.damage, Some(area));
+        self.painted = Area::union(self.painted, Some(area));
+    }
+}
+
+impl TextTarget for Layer<'_> {
+    fn plot(&mut self, x: i32, y: i32, color: Color) {
+        let (x, y) = (x as u32, y as u32);
+        // Negative coordinates wrap to huge values and fail here too
+        if x < self.width && y < self.height {
+            self.pixels[y as usize * self.width as usize + x as usize] = color.to_argb();
+            self.touch(Area { x0: x, y0: y, x1: x + 1, y1: y + 1 });
+        }
+    }
+}
+
+/// A background layer with an OSD layer over it
+pub struct Compositor<'a> {
+    background: Layer<'a>,
+    osd: Layer<'a>,
+}
+
+impl<'a> Compositor<'a> {
+    /// Stack `osd` over `background`; `None` if their sizes differ
+    pub fn new(background: Layer<'a>, osd: Layer<'a>) -> Option<Self> {
+        (background.dimensions() == osd.dimensions()).then_some(Self { background, osd })
+    }
+
+    /// The background layer (drawn opaque)
+    pub fn background(&mut self) -> &mut Layer<'a> {
+        &mut self.background
+    }
+
+    /// The OSD layer (blended by each pixel's alpha)
+    pub fn osd(&mut self) -> &mut Layer<'a> {
+        &mut self.osd
+    }
+
+    /// Recompose the whole screen on the next present, e.g. after
+    /// something was drawn straight to the framebuffer
+    pub fn invalidate(&mut self) {
+        let full = self.background.full();
+        self.background.damage = Some(full);
+    }
+
+    /// Write the changed area of both layers, OSD over background, to `fb`
+    /// (clipped to it). Returns the area recomposed, or `None` if nothing
+    /// changed.
+    pub fn present(&mut self, fb: &mut Framebuffer) -> Option<Rect> {
+        let area = Area::union(self.background.damage.take(), self.osd.damage.take())?;
+        let (fb_width, fb_height) = fb.dimensions();
+        let width = self.background.width as usize;
+
+        for y in area.y0..area.y1.min(fb_height) {
+            let row = y as usize * width;
+            for x in area.x0..area.x1.min(fb_width) {
+                let i = row + x as usize;
+                let under = self.background.pixels[i] | 0xFF00_0000;
+                // The screen is opaque whatever the OSD's coverage
+                let argb = over(self.osd.pixels[i], under) | 0xFF00_0000;
+                fb.put_pixel(x, y, Color::from_argb(argb));
+            }
+        }
+        Some(area.rect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: u32 = 8;
+    const H: u32 = 4;
+    const N: usize = (W * H) as usize;
+
+    #[test]
+    fn osd_blends_over_background_and_clears_without_redrawing_it() {
+        let (mut bg, mut osd, mut screen) = ([0u32; N], [0u32; N], [0u32; N]);
+        let mut fb = Framebuffer::over(&mut screen, W, H);
+        let mut layers = Compositor::new(
+            Layer::new(&mut bg, W, H).unwrap(),
+            Layer::new(&mut osd, W, H).unwrap(),
+        )
+        .unwrap();
+
+        layers.background().fill(0xFF80_8080);
+        assert_eq!(layers.present(&mut fb), Some(Rect::new(0, 0, W, H)));
+        assert_eq!(layers.present(&mut fb), None);
+
+        // Half-transparent bar over the top row, opaque text pixel under it
+        layers.osd().fill_rect(0, 0, W, 1, 0x8000_0000);
+        layers.osd().plot(2, 1, Color::WHITE);
+        assert_eq!(layers.osd().damage(), Some(Rect::new(0, 0, W, 2)));
+        assert_eq!(layers.present(&mut fb), Some(Rect::new(0, 0, W, 2)));
+        assert_eq!(screen[0], 0xFF40_4040);
+        assert_eq!(screen[W as usize + 2], 0xFFFF_FFFF);
+        assert_eq!(screen[W as usize + 3], 0xFF80_8080);
+
+        // Clearing only recomposes what the OSD covered
+        let mut fb = Framebuffer::over(&mut screen, W, H);
+        layers.osd().clear();
+        assert_eq!(layers.present(&mut fb), Some(Rect::new(0, 0, W, 2)));
+        layers.osd().clear();
+        assert_eq!(layers.present(&mut fb), None);
+        assert!(screen.iter().all(|&px| px == 0xFF80_8080));
+    }
+
+    #[test]
+    fn layers_must_fit_their_memory_and_each_other() {
+        let (mut a, mut b) = ([0u32; N], [0u32; N - 1]);
+        assert!(Layer::new(&mut b, W, H).is_none());
+        let small = Layer::new(&mut b, W - 1, H).unwrap();
+        assert!(Compositor::new(Layer::new(&mut a, W, H).unwrap(), small).is_none());
+    }
+}
@@ -0,0 +1,401 @@
+//! # Ed25519 Signature Verification
+//!
+//! RFC 8032 Ed25519 verification for signed packages: photos the Decoder
+//! PD fetches from the SD card, and firmware images an update PD installs.
+//! Verification only; signing happens on the host (`update-capsule-cli
+//! sign-file`).
+//!
+//! A signed package is the payload followed by a trailer:
+//!
+//! ```text
+//! payload | signature (64) | SIGNED_MAGIC (8)
+//! ```
+//!
+//! The signature covers the payload alone. [`verify_signed`] reports a
+//! package without the trailer as `NotChecked`, so a PD that requires
+//! signatures refuses it like a forged one, and one that does not can still
+//! show it.
+//!
+//! ## Verus Verification
+//! Point decoding, addition and scalar multiplication are written against
+//! `field25519`, whose limb operations are proven overflow-free; Verus
+//! checks that every field operation here gets well-formed operands. The
+//! scalar handling (S < L, reducing the SHA-512 of R || A || M mod L) and the
+//! hash are plain Rust, checked by the RFC 8032 vectors.
+//!
+//! Verification is cofactorless and compares encodings: the signature is
+//! valid when `[S]B - [k]A` encodes to exactly the bytes of R.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use super::field25519::{self as fe, FieldElement};
+use super::sha512::Sha512;
+use super::VerifyResult;
+
+/// Ed25519 public key size in bytes
+pub const ED25519_PUBLIC_KEY_SIZE: usize = 32;
+
+/// Ed25519 signature size in bytes (R, then S)
+pub const ED25519_SIGNATURE_SIZE: usize = 64;
+
+/// Last bytes of a signed package
+pub const SIGNED_MAGIC: [u8; 8] = *b"SIGNED25";
+
+/// Bytes a signed package adds after its payload
+pub const SIGNED_TRAILER_LEN: usize = ED25519_SIGNATURE_SIZE + SIGNED_MAGIC.len();
+
+/// The group order L = 2^252 + 27742317777372353535851937790883648493, as
+/// little-endian 64-bit limbs
+const L: [u64; 4] = [0x5812631a5cf5d3ed, 0x14def9dea2f79cd6, 0, 0x1000000000000000];
+
+verus! {
+
+/// Encoding of the base point B (y = 4/5, x even)
+const BASE_POINT: [u8; 32] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+/// A point in extended coordinates: x = X/Z, y = Y/Z, xy = T/Z
+#[derive(Clone, Copy)]
+pub struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    pub open spec fn wf(&self) -> bool {
+        self.x.wf() && self.y.wf() && self.z.wf() && self.t.wf()
+    }
+}
+
+fn identity() -> (p: EdwardsPoint)
+    ensures p.wf(),
+{
+    EdwardsPoint { x: fe::zero(), y: fe::one(), z: fe::one(), t: fe::zero() }
+}
+
+/// `p + q` by the unified formula for a = -1 (Hisil-Wong-Carter-Dawson
+/// 2008, "add-2008-hwcd-3"), which also doubles
+fn add(p: &EdwardsPoint, q: &EdwardsPoint) -> (r: EdwardsPoint)
+    requires p.wf(), q.wf(),
+    ensures r.wf(),
+{
+    let a = fe::mul(&fe::sub(&p.y, &p.x), &fe::sub(&q.y, &q.x));
+    let b = fe::mul(&fe::add(&p.y, &p.x), &fe::add(&q.y, &q.x));
+    let c = fe::mul(&fe::mul(&p.t, &fe::edwards_d2()), &q.t);
+    let zz = fe::mul(&p.z, &q.z);
+    let d = fe::add(&zz, &zz);
+    let e = fe::sub(&b, &a);
+    let f = fe::sub(&d, &c);
+    let g = fe::add(&d, &c);
+    let h = fe::add(&b, &a);
+    EdwardsPoint { x: fe::mul(&e, &f), y: fe::mul(&g, &h), z: fe::mul(&f, &g), t: fe::mul(&e, &h) }
+}
+
+fn negate(p: &EdwardsPoint) -> (r: EdwardsPoint)
+    requires p.wf(),
+    ensures r.wf(),
+{
+    EdwardsPoint { x: fe::neg(&p.x), y: p.y, z: p.z, t: fe::neg(&p.t) }
+}
+
+/// Decode a point (RFC 8032 §5.1.3). `None` for a y that is not
+/// canonical (>= p), a y with no x on the curve, or -0.
+pub fn decompress(bytes: &[u8; 32]) -> (p: Option<EdwardsPoint>)
+    ensures p.is_some() ==> p.unwrap().wf(),
+{
+    let y = fe::from_bytes(bytes);
+    let sign = bytes[31] >> 7;
+    let mut canonical = fe::to_bytes(&y);
+    let top = canonical[31] | (sign << 7);
+    canonical[31] = top;
+    if !fe::bytes_eq(&canonical, bytes) {
+        return None;
+    }
+
+    // x^2 = (y^2 - 1) / (d y^2 + 1) = u / v; candidate x = u v^3 (u v^7)^((p-5)/8)
+    let one = fe::one();
+    let yy = fe::square(&y);
+    let u = fe::sub(&yy, &one);
+    let v = fe::add(&fe::mul(&yy, &fe::edwards_d()), &one);
+    let v3 = fe::mul(&fe::square(&v), &v);
+    let v7 = fe::mul(&fe::square(&v3), &v);
+    let mut x = fe::mul(&fe::mul(&u, &v3), &fe::pow_p58(&fe::mul(&u, &v7)));
+
+    let vxx = fe::mul(&v, &fe::square(&x));
+    if !fe::equal(&vxx, &u) {
+        if !fe::equal(&vxx, &fe::neg(&u)) {
+            return None;
+        }
+        x = fe::mul(&x, &fe::sqrt_m1());
+    }
+    if fe::is_negative(&x) != (sign == 1) {
+        if fe::is_zero(&x) {
+            return None;
+        }
+        x = fe::neg(&x);
+    }
+    Some(EdwardsPoint { x, y, z: one, t: fe::mul(&x, &y) })
+}
+
+/// Encode a point (RFC 8032 §5.1.2)
+pub fn compress(p: &EdwardsPoint) -> [u8; 32]
+    requires p.wf(),
+{
+    let z_inv = fe::invert(&p.z);
+    let x = fe::mul(&p.x, &z_inv);
+    let y = fe::mul(&p.y, &z_inv);
+    let mut s = fe::to_bytes(&y);
+    let top = s[31] | if fe::is_negative(&x) { 0x80 } else { 0 };
+    s[31] = top;
+    s
+}
+
+/// `[a]p + [b]q` for little-endian 256-bit scalars, by double-and-add.
+/// Variable time: every input to verification is public.
+fn double_scalar_mul(a: &[u8; 32], p: &EdwardsPoint, b: &[u8; 32], q: &EdwardsPoint) -> (r: EdwardsPoint)
+    requires p.wf(), q.wf(),
+    ensures r.wf(),
+{
+    let mut acc = identity();
+    let mut i: usize = 256;
+    while i > 0
+        invariant
+            i <= 256,
+            acc.wf(),
+            p.wf(),
+            q.wf(),
+        decreases i,
+    {
+        i -= 1;
+        acc = add(&acc, &acc);
+        let shift = (i % 8) as u8;
+        if (a[i / 8] >> shift) & 1 == 1 {
+            acc = add(&acc, p);
+        }
+        if (b[i / 8] >> shift) & 1 == 1 {
+            acc = add(&acc, q);
+        }
+    }
+    acc
+}
+
+/// Does `[s]B - [k]A` encode to `r`?
+fn check_equation(s: &[u8; 32], k: &[u8; 32], a: &EdwardsPoint, r: &[u8; 32]) -> bool
+    requires a.wf(),
+{
+    match decompress(&BASE_POINT) {
+        Some(base) => {
+            let check = double_scalar_mul(s, &base, k, &negate(a));
+            fe::bytes_eq(&compress(&check), r)
+        }
+        None => false,
+    }
+}
+
+} // verus!
+
+/// Verify an Ed25519 signature over `message`
+pub fn verify_ed25519(
+    message: &[u8],
+    public_key: &[u8; ED25519_PUBLIC_KEY_SIZE],
+    signature: &[u8; ED25519_SIGNATURE_SIZE],
+) -> VerifyResult {
+    let (r, s) = signature.split_at(32);
+    let (Ok(r), Ok(s)) = (<&[u8; 32]>::try_from(r), <&[u8; 32]>::try_from(s)) else {
+        return VerifyResult::Invalid;
+    };
+    // S >= L would let anyone rewrite the signature as S + L
+    if !less_than_l(&scalar_limbs(s)) {
+        return VerifyResult::Invalid;
+    }
+    let Some(a) = decompress(public_key) else {
+        return VerifyResult::Invalid;
+    };
+
+    let k = reduce_scalar(&Sha512::hash(&[r, public_key, message]));
+    if check_equation(s, &k, &a, r) {
+        VerifyResult::Valid
+    } else {
+        VerifyResult::Invalid
+    }
+}
+
+/// Split a signed package into its payload and signature; `None` if it
+/// has no trailer
+pub fn split_signed(package: &[u8]) -> Option<(&[u8], &[u8; ED25519_SIGNATURE_SIZE])> {
+    let (rest, magic) = package.split_last_chunk::<8>()?;
+    if *magic != SIGNED_MAGIC {
+        return None;
+    }
+    let (payload, signature) = rest.split_last_chunk::<ED25519_SIGNATURE_SIZE>()?;
+    Some((payload, signature))
+}
+
+/// Check a signed package against `public_key`. Returns the result and
+/// the payload: `NotChecked` and the whole package if it has no trailer.
+pub fn verify_signed<'a>(package: &'a [u8], public_key: &[u8; ED25519_PUBLIC_KEY_SIZE]) -> (VerifyResult, &'a [u8]) {
+    match split_signed(package) {
+        Some((payload, signature)) => (verify_ed25519(payload, public_key, signature), payload),
+        None => (VerifyResult::NotChecked, package),
+    }
+}
+
+// ============================================================================
+// SCALARS MOD L
+// ============================================================================
+
+fn scalar_limbs(s: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(s.chunks_exact(8)) {
+        let mut le = [0u8; 8];
+        le.copy_from_slice(bytes);
+        *limb = u64::from_le_bytes(le);
+    }
+    limbs
+}
+
+fn less_than_l(x: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if x[i] != L[i] {
+            return x[i] < L[i];
+        }
+    }
+    false
+}
+
+/// `h` (512 bits, little-endian) mod L, one bit at a time from the top:
+/// r stays below L < 2^253, so 2r + 1 always fits in 256 bits
+fn reduce_scalar(h: &[u8; 64]) -> [u8; 32] {
+    let mut r = [0u64; 4];
+    for bit in (0..512).rev() {
+        let mut carry = ((h[bit / 8] >> (bit % 8)) & 1) as u64;
+        for limb in r.iter_mut() {
+            let top = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = top;
+        }
+        if !less_than_l(&r) {
+            let mut borrow = false;
+            for (limb, l) in r.iter_mut().zip(L) {
+                let (diff, b1) = limb.overflowing_sub(l);
+                let (diff, b2) = diff.overflowing_sub(borrow as u64);
+                *limb = diff;
+                borrow = b1 || b2;
+            }
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (bytes, limb) in out.chunks_exact_mut(8).zip(r) {
+        bytes.copy_from_slice(&limb.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex_to_bytes;
+
+    /// RFC 8032 §7.1 tests 1-3: (public key, message, signature)
+    const RFC8032: [(&str, &[u8], &str); 3] = [
+        (
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            b"",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            &[0x72],
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            &[0xaf, 0x82],
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+    ];
+
+    fn vector(i: usize) -> ([u8; 32], &'static [u8], [u8; 64]) {
+        let (key, message, signature) = RFC8032[i];
+        (hex_to_bytes(key).unwrap(), message, hex_to_bytes(signature).unwrap())
+    }
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        for i in 0..RFC8032.len() {
+            let (key, message, signature) = vector(i);
+            assert!(verify_ed25519(message, &key, &signature) == VerifyResult::Valid, "vector {i}");
+        }
+    }
+
+    #[test]
+    fn test_tampering_is_invalid() {
+        let (key, message, signature) = vector(2);
+        // Message, R, S and key bits flipped one at a time
+        assert!(verify_ed25519(&[0xaf, 0x83], &key, &signature) == VerifyResult::Invalid);
+        for byte in [0, 31, 32, 63] {
+            let mut bad = signature;
+            bad[byte] ^= 0x01;
+            assert!(verify_ed25519(message, &key, &bad) == VerifyResult::Invalid, "byte {byte}");
+        }
+        let mut bad_key = key;
+        bad_key[0] ^= 0x01;
+        assert!(verify_ed25519(message, &bad_key, &signature) == VerifyResult::Invalid);
+        // Another vector's key
+        assert!(verify_ed25519(message, &vector(1).0, &signature) == VerifyResult::Invalid);
+    }
+
+    #[test]
+    fn test_non_canonical_s_is_invalid() {
+        // S + L verifies the same equation; only S < L is accepted
+        let (key, message, mut signature) = vector(1);
+        let mut carry = 0u16;
+        let l_bytes = hex_to_bytes::<32>("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010").unwrap();
+        for i in 0..32 {
+            let sum = signature[32 + i] as u16 + l_bytes[i] as u16 + carry;
+            signature[32 + i] = sum as u8;
+            carry = sum >> 8;
+        }
+        assert!(verify_ed25519(message, &key, &signature) == VerifyResult::Invalid);
+    }
+
+    #[test]
+    fn test_signed_package() {
+        let (key, message, signature) = vector(1);
+        let mut package = message.to_vec();
+        package.extend_from_slice(&signature);
+        package.extend_from_slice(&SIGNED_MAGIC);
+        assert_eq!(package.len(), message.len() + SIGNED_TRAILER_LEN);
+
+        let (result, payload) = verify_signed(&package, &key);
+        assert!(result == VerifyResult::Valid);
+        assert_eq!(payload, message);
+
+        // No trailer: not checked, and the whole buffer is the payload
+        let (result, payload) = verify_signed(message, &key);
+        assert!(result == VerifyResult::NotChecked);
+        assert_eq!(payload, message);
+
+        package[0] ^= 0x01;
+        assert!(verify_signed(&package, &key).0 == VerifyResult::Invalid);
+    }
+
+    #[test]
+    fn test_base_point_round_trip() {
+        let base = decompress(&BASE_POINT).unwrap();
+        assert_eq!(compress(&base), BASE_POINT);
+        assert_eq!(compress(&add(&base, &negate(&base))), compress(&identity()));
+        // y = 2 is not on the curve
+        let mut y2 = [0u8; 32];
+        y2[0] = 2;
+        assert!(decompress(&y2).is_none());
+    }
+}
@@ -0,0 +1,446 @@
+//! # GF(2^255 - 19) Arithmetic
+//!
+//! Field elements for Ed25519 as five 51-bit limbs in `u64`s, multiplied
+//! through `u128`, in the style of curve25519-dalek's `FieldElement51`.
+//!
+//! ## Verus Verification
+//! Every limb operation is checked for overflow:
+//! - All operations take and return elements whose limbs are below 2^52
+//!   (`wf()`), so their results can feed any other operation
+//! - `add`, `sub` and `mul` are proven never to overflow their `u64` or
+//!   `u128` intermediates, with the carries of `mul` bounded at each step
+//! - `to_bytes` subtracts p at most once and its carries stay in range
+//!
+//! The proofs are about bounds, not values: that the limbs compute the
+//! right element mod p is checked by the RFC 8032 vectors in `ed25519`.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// 2^51 - 1: the bits of a fully carried limb
+pub const MASK51: u64 = 0x7_FFFF_FFFF_FFFF;
+
+/// 2^51: the weight of one limb over the next lower one
+pub const TWO51: u64 = 0x8_0000_0000_0000;
+
+/// Every limb of a well-formed element is below this (2^52)
+pub const LIMB_BOUND: u64 = 0x10_0000_0000_0000;
+
+/// Sums of limb products in `mul` stay below this (2^112)
+pub const WIDE_BOUND: u128 = 0x1_0000_0000_0000_0000_0000_0000_0000;
+
+/// An element of GF(2^255 - 19): `limbs[0] + limbs[1]·2^51 + ... + limbs[4]·2^204`
+#[derive(Clone, Copy)]
+pub struct FieldElement {
+    pub limbs: [u64; 5],
+}
+
+impl FieldElement {
+    /// Specification: every limb below 2^52, the bound all operations take
+    /// and return
+    pub open spec fn wf(&self) -> bool {
+        &&& self.limbs[0] < LIMB_BOUND
+        &&& self.limbs[1] < LIMB_BOUND
+        &&& self.limbs[2] < LIMB_BOUND
+        &&& self.limbs[3] < LIMB_BOUND
+        &&& self.limbs[4] < LIMB_BOUND
+    }
+}
+
+pub fn zero() -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement { limbs: [0, 0, 0, 0, 0] }
+}
+
+pub fn one() -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement { limbs: [1, 0, 0, 0, 0] }
+}
+
+/// The curve constant d = -121665/121666
+pub fn edwards_d() -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement { limbs: [0x34dca135978a3, 0x1a8283b156ebd, 0x5e7a26001c029, 0x739c663a03cbb, 0x52036cee2b6ff] }
+}
+
+/// 2d, used by point addition
+pub fn edwards_d2() -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement { limbs: [0x69b9426b2f159, 0x35050762add7a, 0x3cf44c0038052, 0x6738cc7407977, 0x2406d9dc56dff] }
+}
+
+/// A square root of -1 (2^((p-1)/4))
+pub fn sqrt_m1() -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement { limbs: [0x61b274a0ea0b0, 0xd5a5fc8f189d, 0x7ef5e9cbd0c60, 0x78595a6804c9e, 0x2b8324804fc1d] }
+}
+
+// ============================================================================
+// CARRIES
+// ============================================================================
+
+/// Split `x` into its low 51 bits and the carry above them
+fn split(x: u64) -> (r: (u64, u64))
+    ensures
+        r.0 <= MASK51,
+        r.1 < 0x2000,
+        r.1 * TWO51 + r.0 == x,
+{
+    proof {
+        assert(x % TWO51 <= MASK51 && x / TWO51 < 0x2000 && (x / TWO51) * TWO51 + x % TWO51 == x)
+            by (nonlinear_arith)
+            requires x <= u64::MAX;
+    }
+    (x % TWO51, x / TWO51)
+}
+
+/// Split a sum of limb products into its low 51 bits and the carry above them
+fn split_wide(x: u128) -> (r: (u64, u128))
+    requires x < WIDE_BOUND,
+    ensures
+        r.0 <= MASK51,
+        r.1 < 0x2000_0000_0000_0000,
+        r.1 * TWO51 + r.0 == x,
+{
+    proof {
+        assert(x % (TWO51 as u128) <= MASK51 && x / (TWO51 as u128) < 0x2000_0000_0000_0000
+            && (x / (TWO51 as u128)) * TWO51 + x % (TWO51 as u128) == x) by (nonlinear_arith)
+            requires x < WIDE_BOUND;
+    }
+    ((x % (TWO51 as u128)) as u64, x / (TWO51 as u128))
+}
+
+/// Carry each limb into the next, the top one back into the bottom times
+/// 19 (2^255 = 19 mod p). Takes any limbs and returns a well-formed
+/// element.
+fn reduce(l: [u64; 5]) -> (f: FieldElement)
+    ensures f.wf(),
+{
+    let (r0, c0) = split(l[0]);
+    let (r1, c1) = split(l[1]);
+    let (r2, c2) = split(l[2]);
+    let (r3, c3) = split(l[3]);
+    let (r4, c4) = split(l[4]);
+    FieldElement { limbs: [r0 + c4 * 19, r1 + c0, r2 + c1, r3 + c2, r4 + c3] }
+}
+
+// ============================================================================
+// ARITHMETIC
+// ============================================================================
+
+pub fn add(a: &FieldElement, b: &FieldElement) -> (f: FieldElement)
+    requires a.wf(), b.wf(),
+    ensures f.wf(),
+{
+    reduce([
+        a.limbs[0] + b.limbs[0],
+        a.limbs[1] + b.limbs[1],
+        a.limbs[2] + b.limbs[2],
+        a.limbs[3] + b.limbs[3],
+        a.limbs[4] + b.limbs[4],
+    ])
+}
+
+/// `a - b`, computed as `a + 16p - b` so no limb goes negative
+pub fn sub(a: &FieldElement, b: &FieldElement) -> (f: FieldElement)
+    requires a.wf(), b.wf(),
+    ensures f.wf(),
+{
+    // 16p: 2^55 - 304 in the bottom limb, 2^55 - 16 in the others
+    reduce([
+        a.limbs[0] + 0x7F_FFFF_FFFF_FED0 - b.limbs[0],
+        a.limbs[1] + 0x7F_FFFF_FFFF_FFF0 - b.limbs[1],
+        a.limbs[2] + 0x7F_FFFF_FFFF_FFF0 - b.limbs[2],
+        a.limbs[3] + 0x7F_FFFF_FFFF_FFF0 - b.limbs[3],
+        a.limbs[4] + 0x7F_FFFF_FFFF_FFF0 - b.limbs[4],
+    ])
+}
+
+pub fn neg(a: &FieldElement) -> (f: FieldElement)
+    requires a.wf(),
+    ensures f.wf(),
+{
+    sub(&zero(), a)
+}
+
+/// `x * y` widened; `y` may be a limb times 19
+fn mul_wide(x: u64, y: u64) -> (r: u128)
+    requires
+        x < LIMB_BOUND,
+        y < 19 * LIMB_BOUND,
+    ensures r < 19 * LIMB_BOUND * LIMB_BOUND,
+{
+    proof {
+        assert(x * y < 19 * LIMB_BOUND * LIMB_BOUND) by (nonlinear_arith)
+            requires x < LIMB_BOUND, y < 19 * LIMB_BOUND;
+    }
+    (x as u128) * (y as u128)
+}
+
+/// Schoolbook product of the limbs; the terms above 2^255 come back times 19
+pub fn mul(a: &FieldElement, b: &FieldElement) -> (f: FieldElement)
+    requires a.wf(), b.wf(),
+    ensures f.wf(),
+{
+    let a = a.limbs;
+    let b = b.limbs;
+    let b1_19 = b[1] * 19;
+    let b2_19 = b[2] * 19;
+    let b3_19 = b[3] * 19;
+    let b4_19 = b[4] * 19;
+
+    // Five terms below 19·2^104 each: every sum is below 2^111
+    let c0 = mul_wide(a[0], b[0]) + mul_wide(a[1], b4_19) + mul_wide(a[2], b3_19)
+        + mul_wide(a[3], b2_19) + mul_wide(a[4], b1_19);
+    let c1 = mul_wide(a[0], b[1]) + mul_wide(a[1], b[0]) + mul_wide(a[2], b4_19)
+        + mul_wide(a[3], b3_19) + mul_wide(a[4], b2_19);
+    let c2 = mul_wide(a[0], b[2]) + mul_wide(a[1], b[1]) + mul_wide(a[2], b[0])
+        + mul_wide(a[3], b4_19) + mul_wide(a[4], b3_19);
+    let c3 = mul_wide(a[0], b[3]) + mul_wide(a[1], b[2]) + mul_wide(a[2], b[1])
+        + mul_wide(a[3], b[0]) + mul_wide(a[4], b4_19);
+    let c4 = mul_wide(a[0], b[4]) + mul_wide(a[1], b[3]) + mul_wide(a[2], b[2])
+        + mul_wide(a[3], b[1]) + mul_wide(a[4], b[0]);
+
+    // Carries are below 2^61, so adding one keeps each sum below 2^112
+    let (r0, k) = split_wide(c0);
+    let (r1, k) = split_wide(c1 + k);
+    let (r2, k) = split_wide(c2 + k);
+    let (r3, k) = split_wide(c3 + k);
+    let (r4, k) = split_wide(c4 + k);
+    let t = r0 as u128 + k * 19;
+    let (r0, k) = split_wide(t);
+    proof {
+        assert(k < 0x10000) by (nonlinear_arith)
+            requires k * TWO51 + r0 == t, t < TWO51 + 19 * 0x2000_0000_0000_0000, r0 >= 0;
+    }
+    FieldElement { limbs: [r0, r1 + k as u64, r2, r3, r4] }
+}
+
+pub fn square(a: &FieldElement) -> (f: FieldElement)
+    requires a.wf(),
+    ensures f.wf(),
+{
+    mul(a, a)
+}
+
+/// `a^(2^k)`
+fn pow2k(a: &FieldElement, k: u32) -> (f: FieldElement)
+    requires a.wf(),
+    ensures f.wf(),
+{
+    let mut f = *a;
+    let mut i: u32 = 0;
+    while i < k
+        invariant
+            f.wf(),
+            i <= k,
+        decreases k - i,
+    {
+        f = square(&f);
+        i += 1;
+    }
+    f
+}
+
+/// `(a^(2^250 - 1), a^11)`, the common start of `invert` and `pow_p58`
+fn pow22501(a: &FieldElement) -> (r: (FieldElement, FieldElement))
+    requires a.wf(),
+    ensures r.0.wf(), r.1.wf(),
+{
+    let a2 = square(a);
+    let a9 = mul(&pow2k(&a2, 2), a);
+    let a11 = mul(&a9, &a2);
+    let a_5_0 = mul(&square(&a11), &a9);
+    let a_10_0 = mul(&pow2k(&a_5_0, 5), &a_5_0);
+    let a_20_0 = mul(&pow2k(&a_10_0, 10), &a_10_0);
+    let a_40_0 = mul(&pow2k(&a_20_0, 20), &a_20_0);
+    let a_50_0 = mul(&pow2k(&a_40_0, 10), &a_10_0);
+    let a_100_0 = mul(&pow2k(&a_50_0, 50), &a_50_0);
+    let a_200_0 = mul(&pow2k(&a_100_0, 100), &a_100_0);
+    let a_250_0 = mul(&pow2k(&a_200_0, 50), &a_50_0);
+    (a_250_0, a11)
+}
+
+/// `a^(p - 2) = 1/a` (0 for 0)
+pub fn invert(a: &FieldElement) -> (f: FieldElement)
+    requires a.wf(),
+    ensures f.wf(),
+{
+    let (a_250_0, a11) = pow22501(a);
+    mul(&pow2k(&a_250_0, 5), &a11)
+}
+
+/// `a^((p - 5) / 8)`, the exponent of the square root in point decoding
+pub fn pow_p58(a: &FieldElement) -> (f: FieldElement)
+    requires a.wf(),
+    ensures f.wf(),
+{
+    let (a_250_0, _) = pow22501(a);
+    mul(&pow2k(&a_250_0, 2), a)
+}
+
+// ============================================================================
+// ENCODING
+// ============================================================================
+
+/// Little-endian u64 at `off`
+fn load8(b: &[u8; 32], off: usize) -> u64
+    requires off + 8 <= 32,
+{
+    (b[off] as u64)
+        | ((b[off + 1] as u64) << 8)
+        | ((b[off + 2] as u64) << 16)
+        | ((b[off + 3] as u64) << 24)
+        | ((b[off + 4] as u64) << 32)
+        | ((b[off + 5] as u64) << 40)
+        | ((b[off + 6] as u64) << 48)
+        | ((b[off + 7] as u64) << 56)
+}
+
+/// Decode 255 little-endian bits; the top bit of `b[31]` is ignored
+pub fn from_bytes(b: &[u8; 32]) -> (f: FieldElement)
+    ensures f.wf(),
+{
+    FieldElement {
+        limbs: [
+            split(load8(b, 0)).0,
+            split(load8(b, 6) >> 3).0,
+            split(load8(b, 12) >> 6).0,
+            split(load8(b, 19) >> 1).0,
+            split(load8(b, 24) >> 12).0,
+        ],
+    }
+}
+
+/// The canonical (fully reduced, < p) little-endian encoding
+pub fn to_bytes(a: &FieldElement) -> [u8; 32]
+    requires a.wf(),
+{
+    // Below 2p after `reduce`, so subtracting p once suffices: q is 1
+    // exactly when a + 19 carries out of 2^255
+    let l = reduce(a.limbs).limbs;
+    let (_, q) = split(l[0] + 19);
+    let (_, q) = split(l[1] + q);
+    let (_, q) = split(l[2] + q);
+    let (_, q) = split(l[3] + q);
+    let (_, q) = split(l[4] + q);
+
+    // a + 19q - 2^255·q: add, carry, and drop the carry out of the top limb
+    let (l0, k) = split(l[0] + 19 * q);
+    let (l1, k) = split(l[1] + k);
+    let (l2, k) = split(l[2] + k);
+    let (l3, k) = split(l[3] + k);
+    let (l4, _) = split(l[4] + k);
+
+    [
+        l0 as u8,
+        (l0 >> 8) as u8,
+        (l0 >> 16) as u8,
+        (l0 >> 24) as u8,
+        (l0 >> 32) as u8,
+        (l0 >> 40) as u8,
+        ((l0 >> 48) | (l1 << 3)) as u8,
+        (l1 >> 5) as u8,
+        (l1 >> 13) as u8,
+        (l1 >> 21) as u8,
+        (l1 >> 29) as u8,
+        (l1 >> 37) as u8,
+        ((l1 >> 45) | (l2 << 6)) as u8,
+        (l2 >> 2) as u8,
+        (l2 >> 10) as u8,
+        (l2 >> 18) as u8,
+        (l2 >> 26) as u8,
+        (l2 >> 34) as u8,
+        (l2 >> 42) as u8,
+        ((l2 >> 50) | (l3 << 1)) as u8,
+        (l3 >> 7) as u8,
+        (l3 >> 15) as u8,
+        (l3 >> 23) as u8,
+        (l3 >> 31) as u8,
+        (l3 >> 39) as u8,
+        ((l3 >> 47) | (l4 << 4)) as u8,
+        (l4 >> 4) as u8,
+        (l4 >> 12) as u8,
+        (l4 >> 20) as u8,
+        (l4 >> 28) as u8,
+        (l4 >> 36) as u8,
+        (l4 >> 44) as u8,
+    ]
+}
+
+/// Byte-wise equality of two encodings
+pub fn bytes_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let mut diff: u8 = 0;
+    let mut i: usize = 0;
+    while i < 32
+        invariant i <= 32,
+        decreases 32 - i,
+    {
+        diff |= a[i] ^ b[i];
+        i += 1;
+    }
+    diff == 0
+}
+
+pub fn equal(a: &FieldElement, b: &FieldElement) -> bool
+    requires a.wf(), b.wf(),
+{
+    bytes_eq(&to_bytes(a), &to_bytes(b))
+}
+
+/// Is the canonical encoding odd? ("negative" in RFC 8032 §5.1.3)
+pub fn is_negative(a: &FieldElement) -> bool
+    requires a.wf(),
+{
+    to_bytes(a)[0] & 1 == 1
+}
+
+pub fn is_zero(a: &FieldElement) -> bool
+    requires a.wf(),
+{
+    bytes_eq(&to_bytes(a), &[0u8; 32])
+}
+
+} // verus!
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small(n: u64) -> FieldElement {
+        FieldElement { limbs: [n, 0, 0, 0, 0] }
+    }
+
+    #[test]
+    fn test_constants() {
+        // d·121666 = -121665
+        assert!(equal(&mul(&edwards_d(), &small(121666)), &neg(&small(121665))));
+        assert!(equal(&add(&edwards_d(), &edwards_d()), &edwards_d2()));
+        assert!(equal(&square(&sqrt_m1()), &neg(&one())));
+    }
+
+    #[test]
+    fn test_invert_and_encoding() {
+        let x = from_bytes(&[0xA5; 32]);
+        assert!(equal(&mul(&x, &invert(&x)), &one()));
+        assert!(is_zero(&invert(&zero())));
+
+        // p itself and p + 1 decode to 0 and 1; encoding is canonical
+        let mut p = [0xFF; 32];
+        p[0] = 0xED;
+        p[31] = 0x7F;
+        assert_eq!(to_bytes(&from_bytes(&p)), [0; 32]);
+        p[0] = 0xEE;
+        assert_eq!(to_bytes(&from_bytes(&p)), to_bytes(&one()));
+        assert!(is_negative(&one()) && !is_negative(&neg(&one())));
+    }
+}
@@ -0,0 +1,777 @@
+//! # Verified Cryptography Primitives
+//!
+//! SHA-256 (FIPS 180-4) and the constant-time digest comparison, written
+//! here rather than taken from a crate so that Verus can check them.
+//!
+//! ## Verus Verification
+//! - The compression function equals `spec_compress`, a pure statement of
+//!   the 64 FIPS 180-4 rounds over the message schedule `spec_schedule`
+//! - Every message schedule access (`W[t-2]`, `W[t-7]`, `W[t-15]`,
+//!   `W[t-16]`) is proven in bounds
+//! - Padding always ends on a block boundary with room for the 0x80 byte
+//!   and the 64-bit length, and `pad_tail` writes exactly that layout
+//! - `constant_time_compare` returns whether the digests are equal, after
+//!   reading every byte of both
+//!
+//! [`Sha256`] itself (buffering input into blocks) is plain Rust on top of
+//! the verified `compress` and `pad_tail`, and is checked against RustCrypto's
+//! `sha2` in the tests.
+//!
+//! ## Signatures
+//! [`ed25519`] verifies Ed25519 signatures on packages (signed photos and
+//! firmware images) over the field arithmetic in `field25519`, whose limb
+//! operations Verus proves overflow-free.
+
+pub mod ed25519;
+mod field25519;
+mod sha512;
+
+pub use ed25519::{verify_ed25519, verify_signed};
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// SHA-256 digest size in bytes
+pub const SHA256_DIGEST_SIZE: usize = 32;
+
+/// SHA-256 block size in bytes
+pub const SHA256_BLOCK_SIZE: usize = 64;
+
+/// Longest message in bytes whose bit length fits the 64-bit length field
+pub const MAX_MESSAGE_LEN: u64 = 0x1FFF_FFFF_FFFF_FFFF;
+
+/// Round constants (FIPS 180-4 §4.2.2)
+pub const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Initial hash value (FIPS 180-4 §5.3.3)
+pub const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// ============================================================================
+// FUNCTION SPECIFICATIONS (FIPS 180-4 §4.1.2)
+// ============================================================================
+
+/// Specification: addition modulo 2^32
+pub open spec fn add32(a: u32, b: u32) -> u32 {
+    (a + b) as u32
+}
+
+/// Specification: rotate right by `n`
+pub open spec fn spec_rotr(x: u32, n: u32) -> u32 {
+    (x >> n) | (x << ((32 - n) as u32))
+}
+
+pub open spec fn spec_ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+pub open spec fn spec_maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+pub open spec fn spec_big_sigma0(x: u32) -> u32 {
+    spec_rotr(x, 2) ^ spec_rotr(x, 13) ^ spec_rotr(x, 22)
+}
+
+pub open spec fn spec_big_sigma1(x: u32) -> u32 {
+    spec_rotr(x, 6) ^ spec_rotr(x, 11) ^ spec_rotr(x, 25)
+}
+
+pub open spec fn spec_small_sigma0(x: u32) -> u32 {
+    spec_rotr(x, 7) ^ spec_rotr(x, 18) ^ (x >> 3)
+}
+
+pub open spec fn spec_small_sigma1(x: u32) -> u32 {
+    spec_rotr(x, 17) ^ spec_rotr(x, 19) ^ (x >> 10)
+}
+
+// ============================================================================
+// COMPRESSION SPECIFICATION
+// ============================================================================
+
+/// Specification: big-endian u32 at byte offset `off`
+pub open spec fn spec_u32_be(buf: Seq<u8>, off: int) -> u32 {
+    ((buf[off] as u32) << 24)
+        | ((buf[off + 1] as u32) << 16)
+        | ((buf[off + 2] as u32) << 8)
+        | (buf[off + 3] as u32)
+}
+
+/// Specification: big-endian u64 at byte offset `off`
+pub open spec fn spec_u64_be(buf: Seq<u8>, off: int) -> u64 {
+    ((buf[off] as u64) << 56)
+        | ((buf[off + 1] as u64) << 48)
+        | ((buf[off + 2] as u64) << 40)
+        | ((buf[off + 3] as u64) << 32)
+        | ((buf[off + 4] as u64) << 24)
+        | ((buf[off + 5] as u64) << 16)
+        | ((buf[off + 6] as u64) << 8)
+        | (buf[off + 7] as u64)
+}
+
+/// Specification: the 16 message words M0..M15 of a 64-byte block
+pub open spec fn spec_block_words(block: Seq<u8>) -> Seq<u32> {
+    Seq::new(16, |i: int| spec_u32_be(block, 4 * i))
+}
+
+/// Specification: message schedule word W_t of the block words `m`
+pub open spec fn spec_schedule(m: Seq<u32>, t: nat) -> u32
+    decreases t,
+{
+    if t < 16 {
+        m[t as int]
+    } else {
+        add32(
+            add32(
+                add32(spec_small_sigma1(spec_schedule(m, (t - 2) as nat)), spec_schedule(m, (t - 7) as nat)),
+                spec_small_sigma0(spec_schedule(m, (t - 15) as nat)),
+            ),
+            spec_schedule(m, (t - 16) as nat),
+        )
+    }
+}
+
+/// Specification: one round on the working variables `s` = a..h
+pub open spec fn spec_round(s: Seq<u32>, k: u32, w: u32) -> Seq<u32> {
+    let t1 = add32(add32(add32(add32(s[7], spec_big_sigma1(s[4])), spec_ch(s[4], s[5], s[6])), k), w);
+    let t2 = add32(spec_big_sigma0(s[0]), spec_maj(s[0], s[1], s[2]));
+    seq![add32(t1, t2), s[0], s[1], s[2], add32(s[3], t1), s[4], s[5], s[6]]
+}
+
+/// Specification: the working variables after the first `n` rounds from `h`
+pub open spec fn spec_rounds(h: Seq<u32>, m: Seq<u32>, n: nat) -> Seq<u32>
+    decreases n,
+{
+    if n == 0 {
+        h
+    } else {
+        let t = (n - 1) as nat;
+        spec_round(spec_rounds(h, m, t), K[t as int], spec_schedule(m, t))
+    }
+}
+
+/// Specification: the hash value after compressing block words `m` into `h`
+pub open spec fn spec_compress(h: Seq<u32>, m: Seq<u32>) -> Seq<u32> {
+    let v = spec_rounds(h, m, 64);
+    Seq::new(8, |i: int| add32(h[i], v[i]))
+}
+
+// ============================================================================
+// VERIFIED COMPRESSION
+// ============================================================================
+
+fn add32_exec(a: u32, b: u32) -> (r: u32)
+    ensures r == add32(a, b),
+{
+    (a as u64 + b as u64) as u32
+}
+
+// Spelled out rather than `rotate_right` so it matches `spec_rotr` as written
+#[allow(clippy::manual_rotate)]
+fn rotr(x: u32, n: u32) -> (r: u32)
+    requires 0 < n < 32,
+    ensures r == spec_rotr(x, n),
+{
+    (x >> n) | (x << (32 - n))
+}
+
+fn ch(e: u32, f: u32, g: u32) -> (r: u32)
+    ensures r == spec_ch(e, f, g),
+{
+    (e & f) ^ (!e & g)
+}
+
+fn maj(a: u32, b: u32, c: u32) -> (r: u32)
+    ensures r == spec_maj(a, b, c),
+{
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+fn big_sigma0(x: u32) -> (r: u32)
+    ensures r == spec_big_sigma0(x),
+{
+    rotr(x, 2) ^ rotr(x, 13) ^ rotr(x, 22)
+}
+
+fn big_sigma1(x: u32) -> (r: u32)
+    ensures r == spec_big_sigma1(x),
+{
+    rotr(x, 6) ^ rotr(x, 11) ^ rotr(x, 25)
+}
+
+fn small_sigma0(x: u32) -> (r: u32)
+    ensures r == spec_small_sigma0(x),
+{
+    rotr(x, 7) ^ rotr(x, 18) ^ (x >> 3)
+}
+
+fn small_sigma1(x: u32) -> (r: u32)
+    ensures r == spec_small_sigma1(x),
+{
+    rotr(x, 17) ^ rotr(x, 19) ^ (x >> 10)
+}
+
+/// Split a block into its 16 big-endian message words
+fn load_block(block: &[u8; 64]) -> (m: [u32; 16])
+    ensures m@ == spec_block_words(block@),
+{
+    let mut m = [0u32; 16];
+    let mut i: usize = 0;
+    while i < 16
+        invariant
+            i <= 16,
+            forall|k: int| 0 <= k < i ==> m[k] == spec_u32_be(block@, 4 * k),
+        decreases 16 - i,
+    {
+        let off = 4 * i;
+        m[i] = ((block[off] as u32) << 24)
+            | ((block[off + 1] as u32) << 16)
+            | ((block[off + 2] as u32) << 8)
+            | (block[off + 3] as u32);
+        i += 1;
+    }
+    proof {
+        assert(m@ =~= spec_block_words(block@));
+    }
+    m
+}
+
+/// Expand the message words into the 64-word schedule W
+fn schedule(m: &[u32; 16]) -> (w: [u32; 64])
+    ensures forall|t: int| 0 <= t < 64 ==> w[t] == spec_schedule(m@, t as nat),
+{
+    let mut w = [0u32; 64];
+    let mut t: usize = 0;
+    while t < 16
+        invariant
+            t <= 16,
+            forall|k: int| 0 <= k < t ==> w[k] == spec_schedule(m@, k as nat),
+        decreases 16 - t,
+    {
+        w[t] = m[t];
+        t += 1;
+    }
+    while t < 64
+        invariant
+            16 <= t <= 64,
+            forall|k: int| 0 <= k < t ==> w[k] == spec_schedule(m@, k as nat),
+        decreases 64 - t,
+    {
+        // t >= 16, so all four earlier words are in bounds and already final
+        let s1 = small_sigma1(w[t - 2]);
+        let s0 = small_sigma0(w[t - 15]);
+        w[t] = add32_exec(add32_exec(add32_exec(s1, w[t - 7]), s0), w[t - 16]);
+        t += 1;
+    }
+    w
+}
+
+/// Compress one 64-byte block into the hash value `state`
+pub fn compress(state: &mut [u32; 8], block: &[u8; 64])
+    ensures state@ == spec_compress(old(state)@, spec_block_words(block@)),
+{
+    let init = *state;
+    let m = load_block(block);
+    let w = schedule(&m);
+
+    let mut a = state[0];
+    let mut b = state[1];
+    let mut c = state[2];
+    let mut d = state[3];
+    let mut e = state[4];
+    let mut f = state[5];
+    let mut g = state[6];
+    let mut h = state[7];
+    proof {
+        assert(seq![a, b, c, d, e, f, g, h] =~= spec_rounds(init@, m@, 0));
+    }
+
+    let mut t: usize = 0;
+    while t < 64
+        invariant
+            t <= 64,
+            m@ == spec_block_words(block@),
+            forall|k: int| 0 <= k < 64 ==> w[k] == spec_schedule(m@, k as nat),
+            seq![a, b, c, d, e, f, g, h] == spec_rounds(init@, m@, t as nat),
+        decreases 64 - t,
+    {
+        let t1 = add32_exec(add32_exec(add32_exec(add32_exec(h, big_sigma1(e)), ch(e, f, g)), K[t]), w[t]);
+        let t2 = add32_exec(big_sigma0(a), maj(a, b, c));
+        h = g;
+        g = f;
+        f = e;
+        e = add32_exec(d, t1);
+        d = c;
+        c = b;
+        b = a;
+        a = add32_exec(t1, t2);
+        t += 1;
+        proof {
+            assert(seq![a, b, c, d, e, f, g, h] =~= spec_rounds(init@, m@, t as nat));
+        }
+    }
+
+    state[0] = add32_exec(init[0], a);
+    state[1] = add32_exec(init[1], b);
+    state[2] = add32_exec(init[2], c);
+    state[3] = add32_exec(init[3], d);
+    state[4] = add32_exec(init[4], e);
+    state[5] = add32_exec(init[5], f);
+    state[6] = add32_exec(init[6], g);
+    state[7] = add32_exec(init[7], h);
+    proof {
+        assert(state@ =~= spec_compress(init@, m@));
+    }
+}
+
+// ============================================================================
+// VERIFIED PADDING
+// ============================================================================
+
+/// Specification: length in bytes of a `len`-byte message after padding
+/// (FIPS 180-4 §5.1.1): the 0x80 byte, zeros, and the 8-byte bit length,
+/// rounded up to whole blocks
+pub open spec fn spec_padded_len(len: int) -> int {
+    len - len % 64 + if len % 64 < 56 { 64int } else { 128int }
+}
+
+/// Padding ends on a block boundary, leaves room for the 0x80 byte and the
+/// length, and adds less than two blocks. It depends only on the last
+/// partial block, which is all `pad_tail` sees.
+pub proof fn lemma_padded_len(len: int)
+    requires len >= 0,
+    ensures
+        spec_padded_len(len) % 64 == 0,
+        len + 9 <= spec_padded_len(len) < len + 73,
+        spec_padded_len(len) == len - len % 64 + spec_padded_len(len % 64),
+{
+    assert(0 <= len % 64 < 64 && (len - len % 64) % 64 == 0 && (len % 64) % 64 == len % 64)
+        by (nonlinear_arith)
+        requires len >= 0;
+    assert((len - len % 64 + 64) % 64 == 0 && (len - len % 64 + 128) % 64 == 0) by (nonlinear_arith)
+        requires (len - len % 64) % 64 == 0;
+}
+
+/// Padded length of a `len`-byte message
+pub fn padded_len(len: u64) -> (n: u64)
+    requires len <= MAX_MESSAGE_LEN,
+    ensures
+        n == spec_padded_len(len as int),
+        n % 64 == 0,
+        len + 9 <= n < len + 73,
+{
+    proof {
+        lemma_padded_len(len as int);
+    }
+    let rem = len % 64;
+    if rem < 56 {
+        len - rem + 64
+    } else {
+        len - rem + 128
+    }
+}
+
+/// Write the final one or two blocks into `out`: the `buffered` bytes of
+/// `tail` not yet compressed, 0x80, zeros, and `bit_len` big-endian in the
+/// last 8 bytes. Returns the number of blocks written.
+pub fn pad_tail(tail: &[u8; 64], buffered: usize, bit_len: u64, out: &mut [u8; 128]) -> (blocks: usize)
+    requires buffered < 64,
+    ensures
+        blocks == 1 || blocks == 2,
+        blocks * 64 == spec_padded_len(buffered as int),
+        forall|k: int| 0 <= k < buffered ==> out[k] == tail[k],
+        out[buffered as int] == 0x80,
+        forall|k: int| buffered < k < blocks * 64 - 8 ==> out[k] == 0,
+        spec_u64_be(out@, blocks * 64 - 8) == bit_len,
+{
+    proof {
+        assert(buffered as int % 64 == buffered as int) by (nonlinear_arith)
+            requires buffered < 64;
+    }
+    let blocks: usize = if buffered < 56 { 1 } else { 2 };
+    let end = blocks * 64;
+
+    // Branches on the length only, never on message bytes
+    let mut i: usize = 0;
+    while i < end - 8
+        invariant
+            buffered < 64,
+            buffered + 9 <= end,
+            end == blocks * 64,
+            blocks == 1 || blocks == 2,
+            i <= end - 8,
+            forall|k: int| 0 <= k < i && k < buffered ==> out[k] == tail[k],
+            forall|k: int| 0 <= k < i && k == buffered ==> out[k] == 0x80,
+            forall|k: int| buffered < k < i ==> out[k] == 0,
+        decreases end - 8 - i,
+    {
+        out[i] = if i < buffered {
+            tail[i]
+        } else if i == buffered {
+            0x80
+        } else {
+            0
+        };
+        i += 1;
+    }
+
+    out[end - 8] = (bit_len >> 56) as u8;
+    out[end - 7] = (bit_len >> 48) as u8;
+    out[end - 6] = (bit_len >> 40) as u8;
+    out[end - 5] = (bit_len >> 32) as u8;
+    out[end - 4] = (bit_len >> 24) as u8;
+    out[end - 3] = (bit_len >> 16) as u8;
+    out[end - 2] = (bit_len >> 8) as u8;
+    out[end - 1] = bit_len as u8;
+    proof {
+        let x = bit_len;
+        assert(((((x >> 56) as u8) as u64) << 56)
+            | ((((x >> 48) as u8) as u64) << 48)
+            | ((((x >> 40) as u8) as u64) << 40)
+            | ((((x >> 32) as u8) as u64) << 32)
+            | ((((x >> 24) as u8) as u64) << 24)
+            | ((((x >> 16) as u8) as u64) << 16)
+            | ((((x >> 8) as u8) as u64) << 8)
+            | ((x as u8) as u64) == x) by (bit_vector);
+    }
+    blocks
+}
+
+// ============================================================================
+// SECURITY PRIMITIVES
+// ============================================================================
+
+/// Constant-time byte comparison
+///
+/// # Security
+/// Timing-safe comparison prevents attackers from learning
+/// partial hash values through timing analysis.
+pub fn constant_time_compare(
+    a: &[u8; SHA256_DIGEST_SIZE],
+    b: &[u8; SHA256_DIGEST_SIZE],
+) -> (equal: bool)
+    ensures equal == (a@ == b@),
+{
+    let mut diff: u8 = 0;
+
+    // XOR all bytes - any difference sets bits in diff
+    // This loop ALWAYS runs exactly SHA256_DIGEST_SIZE iterations
+    let mut i: usize = 0;
+    while i < SHA256_DIGEST_SIZE
+        invariant
+            i <= SHA256_DIGEST_SIZE,
+            (diff == 0) == (forall|k: int| 0 <= k < i ==> a[k] == b[k]),
+        decreases SHA256_DIGEST_SIZE - i,
+    {
+        let x = a[i];
+        let y = b[i];
+        proof {
+            assert((diff | (x ^ y) == 0) == (diff == 0 && x == y)) by (bit_vector);
+        }
+        diff |= x ^ y;
+        i += 1;
+    }
+    proof {
+        if diff == 0 {
+            assert(a@ =~= b@);
+        }
+    }
+
+    // Convert to bool: 0 means equal, non-zero means different
+    diff == 0
+}
+
+} // verus!
+
+/// A SHA-256 digest (32 bytes)
+#[derive(Clone, Copy)]
+pub struct Sha256Digest {
+    bytes: [u8; SHA256_DIGEST_SIZE],
+}
+
+impl Sha256Digest {
+    /// Create a new digest from bytes
+    pub const fn new(bytes: [u8; SHA256_DIGEST_SIZE]) -> Self {
+        Self { bytes }
+    }
+
+    /// Get the underlying bytes
+    pub const fn as_bytes(&self) -> &[u8; SHA256_DIGEST_SIZE] {
+        &self.bytes
+    }
+}
+
+/// Streaming SHA-256 hasher over the verified [`compress`] and [`pad_tail`]
+pub struct Sha256 {
+    state: [u32; 8],
+    /// Input not yet compressed; `block[..buffered]` in use
+    block: [u8; SHA256_BLOCK_SIZE],
+    buffered: usize,
+    /// Bytes hashed so far, modulo 2^64
+    total_len: u64,
+}
+
+impl Sha256 {
+    /// Create a new SHA-256 hasher
+    pub fn new() -> Self {
+        Self {
+            state: H0,
+            block: [0; SHA256_BLOCK_SIZE],
+            buffered: 0,
+            total_len: 0,
+        }
+    }
+
+    /// Update the hash with more data
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let take = (SHA256_BLOCK_SIZE - self.buffered).min(data.len());
+            self.block[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < SHA256_BLOCK_SIZE {
+                return;
+            }
+            compress(&mut self.state, &self.block);
+            self.buffered = 0;
+        }
+
+        while let Some((block, rest)) = data.split_first_chunk::<SHA256_BLOCK_SIZE>() {
+            compress(&mut self.state, block);
+            data = rest;
+        }
+        self.block[..data.len()].copy_from_slice(data);
+        self.buffered = data.len();
+    }
+
+    /// Finalize and return the digest
+    pub fn finalize(mut self) -> Sha256Digest {
+        let mut tail = [0u8; 2 * SHA256_BLOCK_SIZE];
+        let blocks = pad_tail(&self.block, self.buffered, self.total_len.wrapping_mul(8), &mut tail);
+        for block in tail.chunks_exact(SHA256_BLOCK_SIZE).take(blocks) {
+            if let Ok(block) = block.try_into() {
+                compress(&mut self.state, block);
+            }
+        }
+
+        let mut bytes = [0u8; SHA256_DIGEST_SIZE];
+        for (out, word) in bytes.chunks_exact_mut(4).zip(self.state) {
+            out.copy_from_slice(&word.to_be_bytes());
+        }
+        Sha256Digest::new(bytes)
+    }
+
+    /// Compute SHA-256 of data in one call
+    pub fn hash(data: &[u8]) -> Sha256Digest {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bounds-checked indexing
+///
+/// Returns None if index is out of bounds, Some(value) otherwise.
+pub fn safe_index<T: Copy>(slice: &[T], index: usize) -> Option<T> {
+    if index < slice.len() {
+        Some(slice[index])
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// VERIFICATION RESULT TYPES
+// ============================================================================
+
+/// Verification result for display
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VerifyResult {
+    /// Hash matches expected value
+    Valid,
+    /// Hash does not match
+    Invalid,
+    /// Verification not performed
+    NotChecked,
+}
+
+impl VerifyResult {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            VerifyResult::Valid => "VALID",
+            VerifyResult::Invalid => "INVALID",
+            VerifyResult::NotChecked => "NOT CHECKED",
+        }
+    }
+
+    pub const fn is_valid(&self) -> bool {
+        matches!(self, VerifyResult::Valid)
+    }
+}
+
+/// Verify a data buffer against an expected SHA-256 hash
+///
+/// Uses constant-time comparison to prevent timing attacks.
+pub fn verify_sha256(data: &[u8], expected: &Sha256Digest) -> VerifyResult {
+    let actual = Sha256::hash(data);
+
+    if constant_time_compare(actual.as_bytes(), expected.as_bytes()) {
+        VerifyResult::Valid
+    } else {
+        VerifyResult::Invalid
+    }
+}
+
+// ============================================================================
+// UTILITY FUNCTIONS
+// ============================================================================
+
+/// Convert a hex string to bytes (for embedding expected hashes)
+///
+/// Returns None if the string is invalid.
+pub fn hex_to_bytes<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 {
+        return None;
+    }
+
+    let mut result = [0u8; N];
+    let hex_bytes = hex.as_bytes();
+
+    for i in 0..N {
+        let high = hex_digit(hex_bytes[i * 2])?;
+        let low = hex_digit(hex_bytes[i * 2 + 1])?;
+        result[i] = (high << 4) | low;
+    }
+
+    Some(result)
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Format a digest as hex string into a buffer
+pub fn digest_to_hex(digest: &Sha256Digest, out: &mut [u8; 64]) {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+    for (i, byte) in digest.as_bytes().iter().enumerate() {
+        out[i * 2] = HEX_CHARS[(byte >> 4) as usize];
+        out[i * 2 + 1] = HEX_CHARS[(byte & 0x0f) as usize];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vector: SHA-256("")
+    // Expected: e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+    #[test]
+    fn test_sha256_empty() {
+        let digest = Sha256::hash(b"");
+        let expected = hex_to_bytes::<32>(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        ).unwrap();
+        assert!(constant_time_compare(digest.as_bytes(), &expected));
+    }
+
+    // Test vector: SHA-256("abc")
+    // Expected: ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad
+    #[test]
+    fn test_sha256_abc() {
+        let digest = Sha256::hash(b"abc");
+        let expected = hex_to_bytes::<32>(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ).unwrap();
+        assert!(constant_time_compare(digest.as_bytes(), &expected));
+    }
+
+    // Test vector: SHA-256 of the 448-bit FIPS 180-4 message, which pads
+    // into a second block
+    #[test]
+    fn test_sha256_two_blocks() {
+        let digest = Sha256::hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+        let expected = hex_to_bytes::<32>(
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        ).unwrap();
+        assert!(constant_time_compare(digest.as_bytes(), &expected));
+    }
+
+    // Test vector: SHA-256 of one million 'a', fed in uneven pieces
+    #[test]
+    fn test_sha256_million_a() {
+        let mut hasher = Sha256::new();
+        let chunk = [b'a'; 1000];
+        for i in 0..1000 {
+            let split = i % 97;
+            hasher.update(&chunk[..split]);
+            hasher.update(&chunk[split..]);
+        }
+        let expected = hex_to_bytes::<32>(
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        ).unwrap();
+        assert!(constant_time_compare(hasher.finalize().as_bytes(), &expected));
+    }
+
+    #[test]
+    fn test_padded_len_boundaries() {
+        for (len, padded) in [(0, 64), (55, 64), (56, 128), (63, 128), (64, 128), (119, 128), (120, 192)] {
+            assert_eq!(padded_len(len), padded);
+        }
+    }
+
+    #[test]
+    fn test_sha256_matches_sha2_at_every_split() {
+        use sha2::Digest;
+
+        let data: [u8; 200] = core::array::from_fn(|i| (i * 7 + 3) as u8);
+        for len in 0..data.len() {
+            let expected = sha2::Sha256::digest(&data[..len]);
+            assert_eq!(Sha256::hash(&data[..len]).as_bytes()[..], expected[..]);
+
+            let split = len / 3;
+            let mut hasher = Sha256::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..len]);
+            assert_eq!(hasher.finalize().as_bytes()[..], expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_constant_time_compare_any_byte() {
+        let a = [0x5Au8; 32];
+        assert!(constant_time_compare(&a, &a));
+        for i in 0..32 {
+            let mut b = a;
+            b[i] ^= 0x01;
+            assert!(!constant_time_compare(&a, &b));
+        }
+    }
+}
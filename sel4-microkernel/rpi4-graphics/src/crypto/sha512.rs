@@ -0,0 +1,196 @@
+//! # SHA-512
+//!
+//! FIPS 180-4 SHA-512, the hash inside Ed25519 (RFC 8032 §5.1). Only the
+//! verifier uses it, on public data (signature, key and message), so it is
+//! plain Rust checked against the FIPS test vectors rather than a Verus
+//! port like `compress` for SHA-256.
+
+/// SHA-512 digest size in bytes
+pub const SHA512_DIGEST_SIZE: usize = 64;
+
+/// SHA-512 block size in bytes
+pub const SHA512_BLOCK_SIZE: usize = 128;
+
+/// Round constants (FIPS 180-4 §4.2.3)
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+/// Initial hash value (FIPS 180-4 §5.3.5)
+const H0: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+/// Streaming SHA-512 hasher
+pub struct Sha512 {
+    state: [u64; 8],
+    /// Input not yet compressed; `block[..buffered]` in use
+    block: [u8; SHA512_BLOCK_SIZE],
+    buffered: usize,
+    /// Bytes hashed so far (the verifier never hashes 2^64 bytes)
+    total_len: u64,
+}
+
+impl Sha512 {
+    pub fn new() -> Self {
+        Self {
+            state: H0,
+            block: [0; SHA512_BLOCK_SIZE],
+            buffered: 0,
+            total_len: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let take = (SHA512_BLOCK_SIZE - self.buffered).min(data.len());
+            self.block[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < SHA512_BLOCK_SIZE {
+                return;
+            }
+            compress(&mut self.state, &self.block);
+            self.buffered = 0;
+        }
+
+        while let Some((block, rest)) = data.split_first_chunk::<SHA512_BLOCK_SIZE>() {
+            compress(&mut self.state, block);
+            data = rest;
+        }
+        self.block[..data.len()].copy_from_slice(data);
+        self.buffered = data.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; SHA512_DIGEST_SIZE] {
+        // 0x80, zeros, then the 128-bit big-endian bit length; the high 64
+        // bits are the top bits of `total_len * 8`
+        let mut tail = [0u8; 2 * SHA512_BLOCK_SIZE];
+        tail[..self.buffered].copy_from_slice(&self.block[..self.buffered]);
+        tail[self.buffered] = 0x80;
+        let end = if self.buffered < SHA512_BLOCK_SIZE - 16 {
+            SHA512_BLOCK_SIZE
+        } else {
+            2 * SHA512_BLOCK_SIZE
+        };
+        tail[end - 16..end - 8].copy_from_slice(&(self.total_len >> 61).to_be_bytes());
+        tail[end - 8..end].copy_from_slice(&(self.total_len << 3).to_be_bytes());
+        for block in tail[..end].chunks_exact(SHA512_BLOCK_SIZE) {
+            if let Ok(block) = block.try_into() {
+                compress(&mut self.state, block);
+            }
+        }
+
+        let mut digest = [0u8; SHA512_DIGEST_SIZE];
+        for (out, word) in digest.chunks_exact_mut(8).zip(self.state) {
+            out.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    /// SHA-512 of the concatenation of `parts`
+    pub fn hash(parts: &[&[u8]]) -> [u8; SHA512_DIGEST_SIZE] {
+        let mut hasher = Self::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compress one 128-byte block into `state`
+fn compress(state: &mut [u64; 8], block: &[u8; SHA512_BLOCK_SIZE]) {
+    let mut w = [0u64; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(8)) {
+        let mut be = [0u8; 8];
+        be.copy_from_slice(bytes);
+        *word = u64::from_be_bytes(be);
+    }
+    for t in 16..80 {
+        let s0 = w[t - 15].rotate_right(1) ^ w[t - 15].rotate_right(8) ^ (w[t - 15] >> 7);
+        let s1 = w[t - 2].rotate_right(19) ^ w[t - 2].rotate_right(61) ^ (w[t - 2] >> 6);
+        w[t] = w[t - 16].wrapping_add(s0).wrapping_add(w[t - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[t]).wrapping_add(w[t]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (word, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hex_to_bytes;
+
+    fn check(parts: &[&[u8]], expected_hex: &str) {
+        let expected = hex_to_bytes::<64>(expected_hex).unwrap();
+        assert_eq!(Sha512::hash(parts), expected);
+    }
+
+    #[test]
+    fn test_sha512_fips_vectors() {
+        check(
+            &[b"abc"],
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        );
+        check(
+            &[b""],
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        );
+        // 896-bit message: the length no longer fits the first block
+        check(
+            &[b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno",
+              b"ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"],
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+             501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+        );
+    }
+}
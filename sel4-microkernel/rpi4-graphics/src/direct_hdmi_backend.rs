@@ -4,6 +4,7 @@
 //! Uses direct memory access to the framebuffer region mapped by Microkit.
 //! This avoids Mailbox calls - the framebuffer is pre-configured by config.txt.
 
+use crate::vsync::{fallback_frame_delay, Vsync};
 use rpi4_tvdemo::backend::{Color, DisplayBackend};
 
 /// Framebuffer virtual address as mapped by Microkit (tvdemo.system)
@@ -17,6 +18,7 @@ const SCREEN_HEIGHT: u32 = 1080;
 pub struct DirectHdmiBackend {
     width: u32,
     height: u32,
+    vsync: Option<Vsync>,
 }
 
 impl DirectHdmiBackend {
@@ -26,9 +28,36 @@ impl DirectHdmiBackend {
         Self {
             width: SCREEN_WIDTH,
             height: SCREEN_HEIGHT,
+            vsync: None,
         }
     }
 
+    /// Pace frames with the HDMI0 pixel valve's vertical blank interrupt
+    ///
+    /// # Safety
+    /// `pv_base` must be the mapped pixel valve register page
+    /// (see [`crate::PIXELVALVE2_VIRT_BASE`]).
+    pub unsafe fn enable_vsync(&mut self, pv_base: usize) {
+        self.vsync = Some(Vsync::pixel_valve(pv_base));
+    }
+
+    /// Block until the next vertical blank; false (after a fixed delay)
+    /// if vsync is not enabled or timed out
+    pub fn wait_for_vsync(&mut self) -> bool {
+        if let Some(vsync) = self.vsync.as_mut() {
+            if vsync.wait() {
+                return true;
+            }
+        }
+        fallback_frame_delay();
+        false
+    }
+
+    /// Vsync notifier, for servicing its IRQ from the PD's `notified` handler
+    pub fn vsync_mut(&mut self) -> Option<&mut Vsync> {
+        self.vsync.as_mut()
+    }
+
     /// Get raw framebuffer pointer
     #[inline]
     fn fb_ptr(&self) -> *mut u32 {
@@ -0,0 +1,367 @@
+//! # 2D DMA Blit Engine
+//!
+//! Clearing a 1080p screen from the CPU is two million uncached stores,
+//! which dominates frame time on the HDMI path. A legacy BCM2711 DMA channel
+//! in 2D mode does the same work as one transfer: it writes `rows` runs of
+//! `row_bytes`, skipping ahead by a stride after each, so a rectangle of a
+//! framebuffer with any pitch is one control block.
+//!
+//! [`BlitEngine`] owns a channel and a scratch page for its control block
+//! and fill pattern. It fills and copies rectangles between [`DmaWindow`]s,
+//! mappings whose physical address is known: the framebuffer region, and
+//! whatever source regions a PD registers with
+//! [`add_source`](BlitEngine::add_source). Once attached with
+//! [`Framebuffer::attach_blitter`](crate::framebuffer::Framebuffer::attach_blitter),
+//! the framebuffer's `clear`, large `fill_rect`s and large `blit`s from a
+//! registered source go through it; anything the engine refuses (a
+//! misaligned 16bpp rectangle, a source in ordinary PD memory) is drawn by
+//! the CPU as before.
+//!
+//! The channel, the 2D control block and the cache maintenance are the
+//! shared BCM2711 ones from [`rpi4_bcm2711::dma`], which proves the 2D
+//! register packing; this module adds the windows and their bounds.
+//!
+//! ## Verus Verification
+//! - A transfer's last byte lies inside its window
+//!
+//! The engines see the VideoCore bus: RAM below 1 GiB through the uncached
+//! 0xC000_0000 alias. The CPU cleans what it wrote from a cached window
+//! before the channel reads it, and drops stale lines from a cached window
+//! the channel wrote, before returning.
+
+use rpi4_bcm2711::dma::{
+    clean_dcache_range, clean_invalidate_dcache_range, cs, ram_bus_address, ti, ControlBlock, DmaChannel, DmaStatus,
+    MAX_ROWS, MAX_ROW_BYTES, MAX_SKIP,
+};
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+pub open spec fn span_of(pitch: u32, row_bytes: u32, rows: u32) -> int {
+    (rows as int - 1) * pitch as int + row_bytes as int
+}
+
+/// Bytes from the first byte of a `rows` x `row_bytes` rectangle with
+/// rows `pitch` apart to just past its last
+pub fn rect_span(pitch: u32, row_bytes: u32, rows: u32) -> (span: u64)
+    requires
+        rows > 0,
+    ensures
+        span == span_of(pitch, row_bytes, rows),
+{
+    proof {
+        assert((rows as int - 1) * pitch as int <= 0xFFFF_FFFFint * 0xFFFF_FFFFint) by (nonlinear_arith)
+            requires 0 <= rows as int - 1 <= 0xFFFF_FFFF, 0 <= pitch as int <= 0xFFFF_FFFF;
+    }
+    (rows - 1) as u64 * pitch as u64 + row_bytes as u64
+}
+
+/// Does a rectangle starting `offset` bytes into a `window` byte mapping
+/// end inside it?
+pub fn span_inside(offset: usize, span: u64, window: usize) -> (r: bool)
+    ensures
+        r == (offset as int + span as int <= window as int),
+{
+    offset <= window && span <= (window - offset) as u64
+}
+
+} // verus!
+
+/// AXI priority and panic priority: below the display and audio
+/// channels, which are paced by a peripheral and must not starve
+const BLIT_PRIORITY: u32 = cs::priority(4, 8);
+
+/// Rectangles below this many pixels are drawn faster by the CPU than a
+/// transfer takes to set up and wait for
+pub const DMA_MIN_PIXELS: usize = 4096;
+
+/// Scratch page layout: the control block, then the fill pattern
+const CB_OFFSET: usize = 0;
+const PATTERN_OFFSET: usize = 32;
+/// Scratch bytes the engine needs
+pub const SCRATCH_SIZE: usize = 64;
+
+/// Source windows an engine accepts besides the framebuffer and its own
+/// scratch page
+pub const MAX_SOURCES: usize = 4;
+
+/// Status polls before a transfer is given up on (a 1080p clear takes a
+/// few milliseconds)
+const SPIN_LIMIT: u32 = 50_000_000;
+
+/// Why the engine did not do a transfer; the caller draws with the CPU
+/// instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlitError {
+    /// Rectangle runs outside its window, or memory is not DMA-visible
+    OutOfWindow,
+    /// Row or skip too long for the 2D registers
+    TooLarge,
+    /// Address, row length or pitch not 4-byte aligned
+    Unaligned,
+    /// The channel stopped on an AXI or FIFO error
+    Fault,
+    /// The channel did not finish within [`SPIN_LIMIT`] polls
+    Timeout,
+}
+
+/// Memory mapped into the PD whose physical address is known, so the DMA
+/// engine can reach it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmaWindow {
+    pub vaddr: usize,
+    pub paddr: usize,
+    pub size: usize,
+    /// Mapped cached, so needs cleaning and invalidating around transfers
+    pub cached: bool,
+}
+
+impl DmaWindow {
+    /// Offset of `[ptr, ptr + span)` in this window, if all of it is
+    /// inside and below 1 GiB
+    fn offset_of(&self, ptr: usize, span: u64) -> Option<usize> {
+        let offset = ptr.checked_sub(self.vaddr)?;
+        if !span_inside(offset, span, self.size) || self.paddr + self.size > 0x4000_0000 {
+            return None;
+        }
+        Some(offset)
+    }
+
+    fn bus_address(&self, offset: usize) -> u32 {
+        ram_bus_address(self.paddr + offset)
+    }
+}
+
+/// A rectangle of bytes in a window: `rows` runs of `row_bytes`, `pitch`
+/// bytes apart, the first at `ptr`
+#[derive(Clone, Copy, Debug)]
+pub struct Rect2d {
+    pub ptr: usize,
+    pub pitch: u32,
+    pub row_bytes: u32,
+    pub rows: u32,
+}
+
+impl Rect2d {
+    /// Bus address of the first byte and the skip after each row
+    fn place(&self, window: &DmaWindow) -> Result<(u32, u32), BlitError> {
+        if self.row_bytes == 0 || self.rows == 0 || self.row_bytes > MAX_ROW_BYTES || self.pitch < self.row_bytes {
+            return Err(BlitError::TooLarge);
+        }
+        let skip = self.pitch - self.row_bytes;
+        if skip > MAX_SKIP {
+            return Err(BlitError::TooLarge);
+        }
+        if !self.ptr.is_multiple_of(4) || !self.row_bytes.is_multiple_of(4) || !self.pitch.is_multiple_of(4) {
+            return Err(BlitError::Unaligned);
+        }
+        let span = rect_span(self.pitch, self.row_bytes, self.rows);
+        let offset = window.offset_of(self.ptr, span).ok_or(BlitError::OutOfWindow)?;
+        Ok((window.bus_address(offset), skip))
+    }
+
+    /// The first `rows` rows
+    fn take(&self, rows: u32) -> Self {
+        Self { rows: rows.min(self.rows), ..*self }
+    }
+
+    /// Everything after the first `rows` rows
+    fn skip(&self, rows: u32) -> Self {
+        let rows = rows.min(self.rows);
+        Self { ptr: self.ptr + rows as usize * self.pitch as usize, rows: self.rows - rows, ..*self }
+    }
+
+    fn span(&self) -> usize {
+        rect_span(self.pitch, self.row_bytes, self.rows.max(1)) as usize
+    }
+
+    /// 128-bit accesses need 16-byte aligned rows
+    fn wide(&self) -> bool {
+        self.ptr.is_multiple_of(16) && self.row_bytes.is_multiple_of(16) && self.pitch.is_multiple_of(16)
+    }
+}
+
+/// Rectangle fills and copies on a DMA channel
+pub struct BlitEngine {
+    channel: DmaChannel,
+    /// Control block and fill pattern
+    scratch: DmaWindow,
+    /// Memory copies may read from, besides the destination's window
+    sources: [Option<DmaWindow>; MAX_SOURCES],
+}
+
+impl BlitEngine {
+    /// Claim `channel` of the controller mapped at `controller_base`, with
+    /// `scratch` (32-byte aligned, at least [`SCRATCH_SIZE`] bytes) for
+    /// its control block. `None` for a lite channel or an unusable scratch
+    /// page.
+    ///
+    /// # Safety
+    /// `controller_base` must be the mapped DMA register page, nothing else
+    /// (including the GPU firmware) may use `channel`, and `scratch` must
+    /// be mapped and used by nothing else.
+    pub unsafe fn new(controller_base: usize, channel: u8, scratch: DmaWindow) -> Option<Self> {
+        if !scratch.vaddr.is_multiple_of(32) || !scratch.paddr.is_multiple_of(32) {
+            return None;
+        }
+        scratch.offset_of(scratch.vaddr, SCRATCH_SIZE as u64)?;
+        let channel = DmaChannel::new(controller_base, channel, BLIT_PRIORITY)?;
+        Some(Self { channel, scratch, sources: [None; MAX_SOURCES] })
+    }
+
+    /// Let copies read from `window`. Returns false if
+    /// [`MAX_SOURCES`] are already registered.
+    pub fn add_source(&mut self, window: DmaWindow) -> bool {
+        match self.sources.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(window);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The registered source window holding `[ptr, ptr + len)`
+    pub fn source_window(&self, ptr: usize, len: usize) -> Option<DmaWindow> {
+        self.sources
+            .iter()
+            .flatten()
+            .find(|window| window.offset_of(ptr, len as u64).is_some())
+            .copied()
+    }
+
+    /// Fill `dest` in `window` with the 32-bit `word` repeated (one ARGB
+    /// pixel, or two RGB565 pixels)
+    pub fn fill(&mut self, window: &DmaWindow, dest: Rect2d, word: u32) -> Result<(), BlitError> {
+        dest.place(window)?;
+        let pattern = (self.scratch.vaddr + PATTERN_OFFSET) as *mut u32;
+        for i in 0..4 {
+            // Safety: new checked the scratch page holds SCRATCH_SIZE bytes
+            unsafe { pattern.add(i).write_volatile(word) };
+        }
+        let source = self.scratch.bus_address(PATTERN_OFFSET);
+        let width = if dest.wide() { ti::SRC_WIDTH | ti::DEST_WIDTH } else { 0 };
+        let ti = ti::WAIT_RESP | ti::DEST_INC | width | ti::burst(3);
+        self.each_chunk(dest, |engine, chunk| {
+            let (dest_ad, dst_skip) = chunk.place(window)?;
+            engine.run(ControlBlock::rect(ti, source, dest_ad, chunk.row_bytes, chunk.rows, 0, dst_skip), window, &chunk)
+        })
+    }
+
+    /// Copy `src` in `src_window` to `dest` in `dest_window`; both have
+    /// `dest.row_bytes` rows and `dest.rows` of them
+    pub fn copy(&mut self, src_window: &DmaWindow, src: Rect2d, dest_window: &DmaWindow, dest: Rect2d) -> Result<(), BlitError> {
+        if src.row_bytes != dest.row_bytes || src.rows != dest.rows {
+            return Err(BlitError::TooLarge);
+        }
+        src.place(src_window)?;
+        dest.place(dest_window)?;
+        if src_window.cached {
+            clean_dcache_range(src.ptr as *const u8, src.span());
+        }
+        let width = if src.wide() && dest.wide() { ti::SRC_WIDTH | ti::DEST_WIDTH } else { 0 };
+        let ti = ti::WAIT_RESP | ti::SRC_INC | ti::DEST_INC | width | ti::burst(3);
+        let mut done = 0;
+        self.each_chunk(dest, |engine, chunk| {
+            let src_chunk = src.skip(done).take(chunk.rows);
+            done += chunk.rows;
+            let (source_ad, src_skip) = src_chunk.place(src_window)?;
+            let (dest_ad, dst_skip) = chunk.place(dest_window)?;
+            engine.run(ControlBlock::rect(ti, source_ad, dest_ad, chunk.row_bytes, chunk.rows, src_skip, dst_skip), dest_window, &chunk)
+        })
+    }
+
+    /// Split `dest` into transfers of at most [`MAX_ROWS`] rows
+    fn each_chunk(
+        &mut self,
+        dest: Rect2d,
+        mut transfer: impl FnMut(&mut Self, Rect2d) -> Result<(), BlitError>,
+    ) -> Result<(), BlitError> {
+        let mut rest = dest;
+        while rest.rows > 0 {
+            let chunk = rest.take(MAX_ROWS);
+            transfer(self, chunk)?;
+            rest = rest.skip(chunk.rows);
+        }
+        Ok(())
+    }
+
+    /// Write `cb` to the scratch page and run it, leaving no stale lines
+    /// of `dest` behind
+    fn run(&mut self, cb: ControlBlock, window: &DmaWindow, dest: &Rect2d) -> Result<(), BlitError> {
+        let slot = (self.scratch.vaddr + CB_OFFSET) as *mut ControlBlock;
+        // Safety: new checked the scratch page is aligned and big enough
+        unsafe { slot.write_volatile(cb) };
+        if self.scratch.cached {
+            clean_dcache_range(slot as *const u8, SCRATCH_SIZE);
+        } else {
+            barrier();
+        }
+        let result = self.wait(self.scratch.bus_address(CB_OFFSET));
+        if window.cached {
+            clean_invalidate_dcache_range(dest.ptr as *const u8, dest.span());
+        }
+        result
+    }
+
+    /// Run the control block at bus address `cb_bus` to completion
+    fn wait(&mut self, cb_bus: u32) -> Result<(), BlitError> {
+        self.channel.start(cb_bus);
+        for _ in 0..SPIN_LIMIT {
+            match self.channel.status() {
+                DmaStatus::Error => {
+                    self.channel.reset();
+                    return Err(BlitError::Fault);
+                }
+                DmaStatus::Done => return Ok(()),
+                DmaStatus::Active => core::hint::spin_loop(),
+            }
+        }
+        self.channel.reset();
+        Err(BlitError::Timeout)
+    }
+}
+
+/// Make the CPU's uncached stores visible before a channel starts
+fn barrier() {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("dsb sy", options(nostack));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FB: DmaWindow = DmaWindow { vaddr: 0x10_0000, paddr: 0x3e87_6000, size: 0x100_0000, cached: false };
+
+    #[test]
+    fn rect_must_stay_in_its_window() {
+        let pitch = 1280 * 4;
+        let full = Rect2d { ptr: FB.vaddr, pitch, row_bytes: pitch, rows: 720 };
+        assert_eq!(full.place(&FB), Ok((0xFE87_6000, 0)));
+
+        // The last row may end exactly at the window's end, not past it
+        let rows = (FB.size / pitch as usize) as u32;
+        assert!(Rect2d { rows, ..full }.place(&FB).is_ok());
+        assert_eq!(Rect2d { rows: rows + 1, ..full }.place(&FB), Err(BlitError::OutOfWindow));
+        assert_eq!(Rect2d { ptr: FB.vaddr - 4, ..full }.place(&FB), Err(BlitError::OutOfWindow));
+
+        assert_eq!(Rect2d { ptr: FB.vaddr + 2, ..full }.place(&FB), Err(BlitError::Unaligned));
+        assert_eq!(Rect2d { pitch: 0x10000, row_bytes: 0x10000, ..full }.place(&FB), Err(BlitError::TooLarge));
+        assert_eq!(Rect2d { pitch: pitch * 8, row_bytes: 4, ..full }.place(&FB), Err(BlitError::TooLarge));
+    }
+
+    #[test]
+    fn rows_past_the_limit_split() {
+        let tall = Rect2d { ptr: FB.vaddr, pitch: 16, row_bytes: 16, rows: MAX_ROWS + 10 };
+        let first = tall.take(MAX_ROWS);
+        let rest = tall.skip(first.rows);
+        assert_eq!((first.rows, rest.rows), (MAX_ROWS, 10));
+        assert_eq!(rest.ptr, FB.vaddr + MAX_ROWS as usize * 16);
+    }
+}
@@ -0,0 +1,105 @@
+//! Draw Ring Display Backend
+//!
+//! Implements the DisplayBackend trait for a display another PD owns: every
+//! call is queued on a `rpi4-draw-protocol` ring as a clear or rect command,
+//! and that PD validates and draws it. Nothing is shown until [`present`].
+//!
+//! [`present`]: DrawRingBackend::present
+
+use rpi4_draw_protocol::{commands_ptr, header_ptr, DrawCommand, DrawRingHeader};
+use rpi4_tvdemo::backend::{Color, DisplayBackend};
+
+/// Display backend that queues draw commands for the owning PD
+pub struct DrawRingBackend {
+    ring: &'static DrawRingHeader,
+    commands: *mut DrawCommand,
+    width: u16,
+    height: u16,
+    /// Commands dropped because the ring was full
+    dropped: u32,
+}
+
+impl DrawRingBackend {
+    /// Queue on the draw region mapped at `base`, for a `width` x `height`
+    /// display
+    ///
+    /// # Safety
+    /// `base` must be the start of a mapped draw region whose header the
+    /// owning PD has initialized.
+    pub unsafe fn new(base: *mut u8, width: u16, height: u16) -> Self {
+        Self {
+            ring: &*header_ptr(base),
+            commands: commands_ptr(base),
+            width,
+            height,
+            dropped: 0,
+        }
+    }
+
+    /// End the frame: the display shows everything queued before it.
+    /// Returns false if the ring was full.
+    pub fn present(&mut self) -> bool {
+        self.queue(DrawCommand::present())
+    }
+
+    /// Frames the owning PD has presented (wraps)
+    pub fn presented(&self) -> u32 {
+        self.ring.presented()
+    }
+
+    /// Commands dropped because the ring was full (wraps)
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    fn queue(&mut self, command: DrawCommand) -> bool {
+        // Safety: `commands` is this ring's command array (see `new`)
+        let queued = unsafe { self.ring.write_batch(self.commands, &[command]) };
+        if !queued {
+            self.dropped = self.dropped.wrapping_add(1);
+        }
+        queued
+    }
+
+    /// Queue a rect clipped to the screen; false if none of it is on
+    /// screen or the ring is full
+    fn rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) -> bool {
+        let (width, height) = (self.width as u32, self.height as u32);
+        if x >= width || y >= height || w == 0 || h == 0 {
+            return false;
+        }
+        let w = w.min(width - x);
+        let h = h.min(height - y);
+        self.queue(DrawCommand::rect(x as u16, y as u16, w as u16, h as u16, color.to_argb()))
+    }
+}
+
+impl DisplayBackend for DrawRingBackend {
+    fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: Color) -> bool {
+        self.rect(x, y, 1, 1, color)
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.queue(DrawCommand::clear(color.to_argb()));
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) -> bool {
+        self.rect(x, y, w, h, color)
+    }
+
+    fn hline(&mut self, x: u32, y: u32, len: u32, color: Color) {
+        self.rect(x, y, len, 1, color);
+    }
+
+    fn vline(&mut self, x: u32, y: u32, len: u32, color: Color) {
+        self.rect(x, y, 1, len, color);
+    }
+}
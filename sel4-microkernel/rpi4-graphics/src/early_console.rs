@@ -0,0 +1,117 @@
+//! # Early Console
+//!
+//! Boot messages on the screen for boards with no serial console attached.
+//! As soon as a Graphics PD has its framebuffer it can open an
+//! [`EarlyConsole`], draw its own init messages with
+//! [`EarlyConsole::line`], and mirror other PDs' boot logs (the
+//! [`bootlog`](rpi4_pd_error::bootlog) pages they write) with
+//! [`EarlyConsole::mirror`]:
+//!
+//! ```text
+//! BOOT LOG
+//! [GFX] FB 1280x720 @ 0x3e876000
+//! [NET] no interface available (NoDevice)
+//! ```
+//!
+//! The first [`EARLY_CONSOLE_LINES`] lines are shown (fewer on a small
+//! mode), in the 8x8 font at twice its size, errors in red. Later lines
+//! only reach the serial console, and the last row says so.
+
+use crate::font::{draw_string_scaled, CHAR_HEIGHT, CHAR_WIDTH};
+use crate::framebuffer::Framebuffer;
+use crate::graphics::Color;
+use rpi4_pd_error::bootlog::{BootLogReader, Level, BOOT_LOG_LINE_LEN};
+
+/// Lines shown at most
+pub const EARLY_CONSOLE_LINES: usize = 32;
+
+/// Glyph scale
+const SCALE: u32 = 2;
+/// Row pitch in pixels
+const ROW_HEIGHT: u32 = CHAR_HEIGHT * SCALE + 4;
+/// Border around the text
+const MARGIN: u32 = 16;
+
+const BG: Color = Color::BLACK;
+const TITLE: Color = Color::SEL4_GREEN;
+const INFO: Color = Color::LIGHT_GRAY;
+const ERROR: Color = Color::rgb(255, 80, 80);
+
+/// Boot messages drawn top to bottom over a cleared screen
+pub struct EarlyConsole {
+    /// Lines drawn so far
+    shown: usize,
+    /// Lines that fit, below the title and above the overflow row
+    capacity: usize,
+    /// The overflow row has been drawn
+    overflowed: bool,
+}
+
+impl EarlyConsole {
+    /// Clear the screen and draw the title
+    pub fn new(fb: &mut Framebuffer) -> Self {
+        let (_, height) = fb.dimensions();
+        // Title and overflow rows come out of the screen's rows
+        let rows = (height.saturating_sub(2 * MARGIN) / ROW_HEIGHT) as usize;
+        let capacity = rows.saturating_sub(2).min(EARLY_CONSOLE_LINES);
+
+        fb.clear(BG);
+        draw_string_scaled(fb, MARGIN, MARGIN, "BOOT LOG", TITLE, SCALE);
+        Self { shown: 0, capacity, overflowed: false }
+    }
+
+    /// Draw one line as `[source] text`. Returns false once the console is
+    /// full.
+    pub fn line(&mut self, fb: &mut Framebuffer, source: &str, level: Level, text: &str) -> bool {
+        if self.shown >= self.capacity {
+            if !self.overflowed {
+                self.overflowed = true;
+                let y = self.row_y(self.capacity);
+                draw_string_scaled(fb, MARGIN, y, "... MORE ON THE SERIAL CONSOLE", INFO, SCALE);
+            }
+            return false;
+        }
+        let y = self.row_y(self.shown);
+        let x = MARGIN + draw_tag(fb, MARGIN, y, source);
+        let color = match level {
+            Level::Info => INFO,
+            Level::Error => ERROR,
+        };
+        draw_string_scaled(fb, x, y, text, color, SCALE);
+        self.shown += 1;
+        true
+    }
+
+    /// Draw the lines `log` has published since the last call. Returns how
+    /// many were drawn.
+    pub fn mirror(&mut self, fb: &mut Framebuffer, source: &str, log: &mut BootLogReader) -> usize {
+        let mut buf = [0; BOOT_LOG_LINE_LEN];
+        let mut drawn = 0;
+        while let Some((level, text)) = log.poll(&mut buf) {
+            if self.line(fb, source, level, text) {
+                drawn += 1;
+            }
+        }
+        drawn
+    }
+
+    /// Lines drawn so far
+    pub fn shown(&self) -> usize {
+        self.shown
+    }
+
+    /// Top of text row `row`, the title being row -1
+    fn row_y(&self, row: usize) -> u32 {
+        MARGIN + (row as u32 + 1) * ROW_HEIGHT
+    }
+}
+
+/// Draw `[source] ` and return its width in pixels
+fn draw_tag(fb: &mut Framebuffer, x: u32, y: u32, source: &str) -> u32 {
+    let cell = CHAR_WIDTH * SCALE;
+    draw_string_scaled(fb, x, y, "[", TITLE, SCALE);
+    draw_string_scaled(fb, x + cell, y, source, TITLE, SCALE);
+    let end = (source.chars().count() as u32 + 1) * cell;
+    draw_string_scaled(fb, x + end, y, "]", TITLE, SCALE);
+    end + 2 * cell
+}
@@ -0,0 +1,486 @@
+//! # EDID Parser
+//!
+//! Decodes the 128-byte EDID base block that
+//! [`Mailbox::get_edid_block`](crate::mailbox::Mailbox::get_edid_block) reads
+//! from the attached TV over DDC: preferred timing, the other advertised
+//! resolutions, and the monitor name.
+//!
+//! ## Verus Verification
+//! The block comes from whatever is plugged into the HDMI port, so the parser
+//! is verified to be total over arbitrary bytes:
+//! - Every byte access is at a constant offset or a loop index proven < 128
+//! - Mode and name buffers are never written past their capacity
+//! - A parsed [`EdidInfo`] always satisfies `valid()`
+
+// Verus requires explicit arithmetic (e.g., `x = x + 1`), and the range
+// and divisibility checks spelled out, for verification specs
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::manual_range_contains)]
+#![allow(clippy::manual_is_multiple_of)]
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::framebuffer::{write_decimal, DisplayMode};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
+verus! {
+
+/// Size of an EDID block in bytes
+pub const EDID_BLOCK_SIZE: usize = 128;
+
+/// Maximum number of distinct modes kept from one block
+pub const MAX_EDID_MODES: usize = 16;
+
+/// Longest monitor name a display descriptor can carry
+pub const MONITOR_NAME_MAX: usize = 13;
+
+/// Offsets of the four 18-byte descriptors (first = preferred timing)
+pub const DESCRIPTOR_OFFSETS: [usize; 4] = [0x36, 0x48, 0x5A, 0x6C];
+
+/// A resolution/refresh the display advertises
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdidMode {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: u32,
+}
+
+/// Parsed EDID base block
+#[derive(Clone, Copy, Debug)]
+pub struct EdidInfo {
+    /// Three-letter PNP manufacturer ID (e.g. `SAM`)
+    pub manufacturer: [u8; 3],
+    /// Manufacturer product code
+    pub product_code: u16,
+    /// Preferred timing (first detailed timing descriptor)
+    pub preferred: Option<EdidMode>,
+    /// Advertised modes, preferred first, without duplicates
+    pub modes: [EdidMode; MAX_EDID_MODES],
+    pub mode_count: usize,
+    /// Monitor name (ASCII, trailing padding removed)
+    pub name: [u8; MONITOR_NAME_MAX],
+    pub name_len: usize,
+    /// Number of extension blocks that follow (CEA-861 etc.)
+    pub extension_blocks: u8,
+}
+
+/// EDID parse errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdidError {
+    /// Missing the 00 FF FF FF FF FF FF 00 header
+    BadHeader,
+    /// Block bytes do not sum to 0 mod 256
+    BadChecksum,
+}
+
+impl EdidInfo {
+    /// Specification: counts are within their buffers
+    pub open spec fn valid(&self) -> bool {
+        self.mode_count <= MAX_EDID_MODES && self.name_len <= MONITOR_NAME_MAX
+    }
+}
+
+const EMPTY_MODE: EdidMode = EdidMode { width: 0, height: 0, refresh_hz: 0 };
+
+/// Check the fixed 8-byte EDID header
+pub fn header_valid(block: &[u8; 128]) -> (ok: bool)
+    ensures ok ==> block[0] == 0 && block[7] == 0,
+{
+    block[0] == 0x00 && block[1] == 0xFF && block[2] == 0xFF && block[3] == 0xFF
+        && block[4] == 0xFF && block[5] == 0xFF && block[6] == 0xFF && block[7] == 0x00
+}
+
+/// Check that all 128 bytes sum to 0 mod 256
+pub fn checksum_valid(block: &[u8; 128]) -> bool {
+    let mut sum: u32 = 0;
+    let mut i: usize = 0;
+    while i < EDID_BLOCK_SIZE
+        invariant
+            i <= EDID_BLOCK_SIZE,
+            sum <= 255 * i,
+        decreases EDID_BLOCK_SIZE - i,
+    {
+        sum = sum + block[i] as u32;
+        i = i + 1;
+    }
+    sum % 256 == 0
+}
+
+/// Decode a detailed timing descriptor at `off`, or `None` if the slot holds
+/// a display descriptor (pixel clock 0) or a zero-sized mode
+pub fn parse_detailed_timing(block: &[u8; 128], off: usize) -> (mode: Option<EdidMode>)
+    requires off + 18 <= EDID_BLOCK_SIZE,
+    ensures mode.is_some() ==> mode.unwrap().width > 0 && mode.unwrap().height > 0,
+{
+    let clock_10khz = (block[off] as u32) | ((block[off + 1] as u32) << 8);
+    if clock_10khz == 0 {
+        return None;
+    }
+    let h_active = (block[off + 2] as u32) | (((block[off + 4] as u32) >> 4) << 8);
+    let h_blank = (block[off + 3] as u32) | (((block[off + 4] as u32) & 0x0F) << 8);
+    let v_active = (block[off + 5] as u32) | (((block[off + 7] as u32) >> 4) << 8);
+    let v_blank = (block[off + 6] as u32) | (((block[off + 7] as u32) & 0x0F) << 8);
+    if h_active == 0 || v_active == 0 {
+        return None;
+    }
+
+    let h_total = (h_active + h_blank) as u64;
+    let v_total = (v_active + v_blank) as u64;
+    proof {
+        assert(h_total * v_total <= 8190 * 8190) by (nonlinear_arith)
+            requires h_total <= 8190, v_total <= 8190;
+    }
+    let pixels_per_frame = h_total * v_total;
+    // Refresh = pixel clock / pixels per frame, rounded to the nearest Hz
+    let refresh = ((clock_10khz as u64) * 10_000 + pixels_per_frame / 2) / pixels_per_frame;
+
+    Some(EdidMode {
+        width: h_active,
+        height: v_active,
+        refresh_hz: if refresh > 1000 { 1000 } else { refresh as u32 },
+    })
+}
+
+/// Decode a 2-byte standard timing, or `None` for an unused slot
+pub fn parse_standard_timing(b0: u8, b1: u8) -> (mode: Option<EdidMode>)
+    ensures mode.is_some() ==> mode.unwrap().width >= 256 && mode.unwrap().width <= 2288,
+{
+    if b0 == 0x00 || (b0 == 0x01 && b1 == 0x01) {
+        return None;
+    }
+    let width = ((b0 as u32) + 31) * 8;
+    let height = match b1 >> 6 {
+        0 => width * 10 / 16,
+        1 => width * 3 / 4,
+        2 => width * 4 / 5,
+        _ => width * 9 / 16,
+    };
+    Some(EdidMode { width, height, refresh_hz: ((b1 & 0x3F) as u32) + 60 })
+}
+
+/// Append `mode` unless it is already listed or the table is full
+pub fn push_mode(modes: &mut [EdidMode; MAX_EDID_MODES], count: usize, mode: EdidMode) -> (new_count: usize)
+    requires count <= MAX_EDID_MODES,
+    ensures
+        new_count <= MAX_EDID_MODES,
+        new_count == count || new_count == count + 1,
+{
+    let mut i: usize = 0;
+    while i < count
+        invariant
+            i <= count,
+            count <= MAX_EDID_MODES,
+        decreases count - i,
+    {
+        if modes[i].width == mode.width && modes[i].height == mode.height
+            && modes[i].refresh_hz == mode.refresh_hz
+        {
+            return count;
+        }
+        i = i + 1;
+    }
+    if count < MAX_EDID_MODES {
+        modes[count] = mode;
+        count + 1
+    } else {
+        count
+    }
+}
+
+/// Decode a PNP manufacturer letter (1 = 'A' .. 26 = 'Z'); '?' otherwise
+fn pnp_letter(code: u16) -> (c: u8)
+    ensures c == b'?' || (c >= b'A' && c <= b'Z'),
+{
+    if code >= 1 && code <= 26 { (code as u8) + b'A' - 1 } else { b'?' }
+}
+
+/// Copy a monitor-name display descriptor (tag 0xFC) at `off` into `name`,
+/// returning its length with the 0x0A terminator and space padding removed
+pub fn parse_monitor_name(block: &[u8; 128], off: usize, name: &mut [u8; MONITOR_NAME_MAX]) -> (len: usize)
+    requires off + 18 <= EDID_BLOCK_SIZE,
+    ensures len <= MONITOR_NAME_MAX,
+{
+    let mut len: usize = 0;
+    while len < MONITOR_NAME_MAX
+        invariant
+            len <= MONITOR_NAME_MAX,
+            off + 18 <= EDID_BLOCK_SIZE,
+        decreases MONITOR_NAME_MAX - len,
+    {
+        let c = block[off + 5 + len];
+        if c == 0x0A || c == 0x00 {
+            break;
+        }
+        // Keep printable ASCII only; anything else becomes '?'
+        name[len] = if c >= 0x20 && c < 0x7F { c } else { b'?' };
+        len = len + 1;
+    }
+    while len > 0 && name[len - 1] == b' '
+        invariant len <= MONITOR_NAME_MAX,
+        decreases len,
+    {
+        len = len - 1;
+    }
+    len
+}
+
+/// Established timings (bytes 0x23/0x24) worth listing: (byte, bit, mode)
+const ESTABLISHED: [(usize, u8, EdidMode); 4] = [
+    (0x23, 0x20, EdidMode { width: 640, height: 480, refresh_hz: 60 }),
+    (0x23, 0x01, EdidMode { width: 800, height: 600, refresh_hz: 60 }),
+    (0x24, 0x08, EdidMode { width: 1024, height: 768, refresh_hz: 60 }),
+    (0x24, 0x01, EdidMode { width: 1280, height: 1024, refresh_hz: 75 }),
+];
+
+/// Parse an EDID base block
+pub fn parse_edid(block: &[u8; 128]) -> (result: Result<EdidInfo, EdidError>)
+    ensures result.is_ok() ==> result.unwrap().valid(),
+{
+    if !header_valid(block) {
+        return Err(EdidError::BadHeader);
+    }
+    if !checksum_valid(block) {
+        return Err(EdidError::BadChecksum);
+    }
+
+    let id = ((block[8] as u16) << 8) | (block[9] as u16);
+    let mut info = EdidInfo {
+        manufacturer: [
+            pnp_letter((id >> 10) & 0x1F),
+            pnp_letter((id >> 5) & 0x1F),
+            pnp_letter(id & 0x1F),
+        ],
+        product_code: (block[10] as u16) | ((block[11] as u16) << 8),
+        preferred: None,
+        modes: [EMPTY_MODE; MAX_EDID_MODES],
+        mode_count: 0,
+        name: [0; MONITOR_NAME_MAX],
+        name_len: 0,
+        extension_blocks: block[126],
+    };
+
+    // Detailed timings and display descriptors
+    let mut d: usize = 0;
+    while d < 4
+        invariant
+            d <= 4,
+            info.valid(),
+        decreases 4 - d,
+    {
+        let off = DESCRIPTOR_OFFSETS[d];
+        match parse_detailed_timing(block, off) {
+            Some(mode) => {
+                if d == 0 {
+                    info.preferred = Some(mode);
+                }
+                info.mode_count = push_mode(&mut info.modes, info.mode_count, mode);
+            }
+            None => {
+                if block[off] == 0 && block[off + 1] == 0 && block[off + 3] == 0xFC {
+                    info.name_len = parse_monitor_name(block, off, &mut info.name);
+                }
+            }
+        }
+        d = d + 1;
+    }
+
+    // Standard timings (8 two-byte slots at 0x26)
+    let mut s: usize = 0;
+    while s < 8
+        invariant
+            s <= 8,
+            info.valid(),
+        decreases 8 - s,
+    {
+        if let Some(mode) = parse_standard_timing(block[0x26 + 2 * s], block[0x27 + 2 * s]) {
+            info.mode_count = push_mode(&mut info.modes, info.mode_count, mode);
+        }
+        s = s + 1;
+    }
+
+    // Established timings
+    let mut e: usize = 0;
+    while e < 4
+        invariant
+            e <= 4,
+            info.valid(),
+        decreases 4 - e,
+    {
+        let (byte, bit, mode) = ESTABLISHED[e];
+        if block[byte] & bit != 0 {
+            info.mode_count = push_mode(&mut info.modes, info.mode_count, mode);
+        }
+        e = e + 1;
+    }
+
+    Ok(info)
+}
+
+} // verus!
+
+// ============================================================================
+// NON-VERIFIED RUNTIME HELPERS
+// ============================================================================
+
+impl From<EdidError> for PdError {
+    fn from(e: EdidError) -> Self {
+        let kind = match e {
+            EdidError::BadHeader => ErrorKind::InvalidResponse,
+            EdidError::BadChecksum => ErrorKind::Integrity,
+        };
+        PdError::new(ErrorDomain::Display, kind)
+    }
+}
+
+impl EdidInfo {
+    /// Advertised modes, preferred first
+    pub fn modes(&self) -> &[EdidMode] {
+        &self.modes[..self.mode_count.min(MAX_EDID_MODES)]
+    }
+
+    /// Monitor name, or "" if the display did not send one
+    pub fn name(&self) -> &str {
+        let bytes = &self.name[..self.name_len.min(MONITOR_NAME_MAX)];
+        core::str::from_utf8(bytes).unwrap_or("")
+    }
+
+    /// Manufacturer ID as a string (always three ASCII characters)
+    pub fn manufacturer(&self) -> &str {
+        core::str::from_utf8(&self.manufacturer).unwrap_or("???")
+    }
+
+    /// Does the display advertise `width` x `height` at any refresh rate?
+    pub fn supports(&self, width: u32, height: u32) -> bool {
+        self.modes().iter().any(|m| m.width == width && m.height == height)
+    }
+
+    /// First rung of `ladder` whose resolution the display advertises.
+    ///
+    /// Lets a PD skip modes the TV never offered instead of waiting for the
+    /// GPU to refuse them. `None` if no rung matches (e.g. an EDID that only
+    /// lists 4K); callers then fall back to the plain ladder.
+    pub fn pick_mode(&self, ladder: &[DisplayMode]) -> Option<DisplayMode> {
+        ladder.iter().copied().find(|m| self.supports(m.width, m.height))
+    }
+
+    /// The mode to run the panel at, at least `min` and at most `max`
+    /// (at `max`'s depth): its preferred timing if that is in range,
+    /// otherwise the largest advertised mode that is.
+    ///
+    /// `None` if no mode is in range, e.g. a 4K panel that lists nothing
+    /// smaller, or a sink that only offers 480p to a PD laid out for 720p.
+    pub fn native_mode(&self, min: DisplayMode, max: DisplayMode) -> Option<DisplayMode> {
+        let in_range = |m: &EdidMode| m.width >= min.width && m.height >= min.height && max.covers(m.width, m.height);
+        let mode = self.preferred.filter(in_range).or_else(|| {
+            self.modes().iter().copied().filter(in_range).max_by_key(|m| m.width as u64 * m.height as u64)
+        })?;
+        Some(DisplayMode::new(mode.width, mode.height, max.depth))
+    }
+}
+
+impl EdidMode {
+    /// Format as "1920x1080@60" into `buf`
+    pub fn label<'a>(&self, buf: &'a mut [u8; 24]) -> &'a str {
+        let mut len = write_decimal(self.width, buf);
+        buf[len] = b'x';
+        len += 1;
+        len += write_decimal(self.height, &mut buf[len..]);
+        buf[len] = b'@';
+        len += 1;
+        len += write_decimal(self.refresh_hz, &mut buf[len..]);
+        core::str::from_utf8(&buf[..len]).unwrap_or("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Base block of a 1080p TV: "SAM", DTD 1920x1080@60, name "TEST TV",
+    /// standard timing 1280x720@60, established 640x480 / 800x600.
+    fn sample_block() -> [u8; 128] {
+        let mut b = [0u8; 128];
+        b[0..8].copy_from_slice(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+        b[8] = 0x4C; // S=19, A=1, M=13
+        b[9] = 0x2D;
+        b[10] = 0x34;
+        b[11] = 0x12;
+        b[0x23] = 0x21;
+        for slot in b[0x26..0x36].chunks_mut(2) {
+            slot.copy_from_slice(&[0x01, 0x01]);
+        }
+        b[0x26] = (1280 / 8 - 31) as u8;
+        b[0x27] = 0xC0; // 16:9, 60 Hz
+        // 148.5 MHz, 1920+280 x 1080+45
+        b[0x36..0x48].copy_from_slice(&[
+            0x02, 0x3A, 0x80, 0x18, 0x71, 0x38, 0x2D, 0x40, 0x58, 0x2C,
+            0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1E,
+        ]);
+        b[0x48..0x5A].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0xFC, 0x00, b'T', b'E', b'S', b'T', b' ', b'T', b'V', 0x0A,
+            b' ', b' ', b' ', b' ', b' ',
+        ]);
+        b[126] = 1;
+        let sum = b[..127].iter().fold(0u8, |acc, &x| acc.wrapping_add(x));
+        b[127] = 0u8.wrapping_sub(sum);
+        b
+    }
+
+    #[test]
+    fn test_parse_sample() {
+        let info = parse_edid(&sample_block()).unwrap();
+        assert_eq!(info.manufacturer(), "SAM");
+        assert_eq!(info.product_code, 0x1234);
+        assert_eq!(info.name(), "TEST TV");
+        assert_eq!(info.extension_blocks, 1);
+        assert_eq!(
+            info.preferred,
+            Some(EdidMode { width: 1920, height: 1080, refresh_hz: 60 })
+        );
+        assert_eq!(info.modes().len(), 4);
+        assert!(info.supports(1280, 720));
+        assert!(info.supports(800, 600));
+        assert!(!info.supports(1024, 768));
+    }
+
+    #[test]
+    fn test_rejects_bad_header_and_checksum() {
+        let mut b = sample_block();
+        b[127] = b[127].wrapping_add(1);
+        assert_eq!(parse_edid(&b).unwrap_err(), EdidError::BadChecksum);
+        b[1] = 0;
+        assert_eq!(parse_edid(&b).unwrap_err(), EdidError::BadHeader);
+    }
+
+    #[test]
+    fn test_pick_mode() {
+        let info = parse_edid(&sample_block()).unwrap();
+        assert_eq!(info.pick_mode(&crate::framebuffer::FALLBACK_LADDER), Some(DisplayMode::FHD_1080P));
+        assert_eq!(
+            info.pick_mode(crate::framebuffer::ladder_from(DisplayMode::HD_720P)),
+            Some(DisplayMode::HD_720P)
+        );
+    }
+
+    #[test]
+    fn test_native_mode() {
+        let info = parse_edid(&sample_block()).unwrap();
+        let fhd = DisplayMode::FHD_1080P;
+        let hd = DisplayMode::HD_720P;
+        assert_eq!(info.native_mode(hd, fhd), Some(fhd));
+        // Preferred timing too large: the largest mode that fits
+        assert_eq!(info.native_mode(hd, hd), Some(hd));
+        assert_eq!(info.native_mode(DisplayMode::SD_480P, DisplayMode::new(1024, 768, 16)), Some(DisplayMode::new(800, 600, 16)));
+        assert_eq!(info.native_mode(hd, DisplayMode::new(1024, 768, 32)), None);
+    }
+
+    #[test]
+    fn test_mode_label() {
+        let mut buf = [0u8; 24];
+        let mode = EdidMode { width: 1920, height: 1080, refresh_hz: 60 };
+        assert_eq!(mode.label(&mut buf), "1920x1080@60");
+    }
+}
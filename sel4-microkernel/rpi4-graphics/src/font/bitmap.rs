@@ -0,0 +1,368 @@
+//! # Proportional Bitmap Fonts
+//!
+//! Each [`BitmapFont`] stores its characters as 1-bit glyphs cropped to
+//! their ink, with a per-glyph advance and a sorted kerning table, so text
+//! is spaced like the font it was rasterized from rather than on a fixed
+//! grid. Drawing goes through [`TextTarget`], which both [`Framebuffer`]
+//! and a raw pixel pointer ([`RawTarget`]) implement.
+//!
+//! Every face covers printable ASCII; what else it covers is listed in its
+//! [`ranges`](BitmapFont::ranges). Other characters are drawn according to
+//! a [`Fallback`] policy.
+//!
+//! Positions are the top-left of the line box: glyphs sit on a baseline
+//! [`ascent`](BitmapFont::ascent) pixels below `y`.
+
+use super::charset::{fold_to_ascii, Fallback};
+use crate::framebuffer::Framebuffer;
+use crate::graphics::Color;
+
+/// Drawn for characters a face does not cover and cannot fold
+const MISSING: char = '?';
+
+/// One glyph: an ink box placed relative to the pen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyph {
+    /// Start of the glyph's rows in [`BitmapFont::bitmap`]
+    pub offset: u16,
+    /// Ink box size in pixels (0 for blank glyphs such as space)
+    pub width: u8,
+    pub height: u8,
+    /// Ink box position from the pen: right of it, and below the line top
+    pub x_offset: i8,
+    pub y_offset: i8,
+    /// Pen movement after the glyph
+    pub advance: u8,
+}
+
+impl Glyph {
+    pub const fn new(offset: u16, width: u8, height: u8, x_offset: i8, y_offset: i8, advance: u8) -> Self {
+        Self { offset, width, height, x_offset, y_offset, advance }
+    }
+
+    /// Bytes per row of the glyph's bitmap
+    pub const fn row_bytes(&self) -> usize {
+        (self.width as usize).div_ceil(8)
+    }
+}
+
+/// A run of consecutive characters whose glyphs are stored in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphRange {
+    /// First and last character of the run
+    pub first: char,
+    pub last: char,
+    /// Index of the first character's glyph in [`BitmapFont::glyphs`]
+    pub start: u16,
+}
+
+impl GlyphRange {
+    pub const fn new(first: char, last: char, start: u16) -> Self {
+        Self { first, last, start }
+    }
+}
+
+/// Extra pen movement between two characters (usually negative); both are
+/// in the Basic Multilingual Plane
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KernPair {
+    pub left: u16,
+    pub right: u16,
+    pub adjust: i8,
+}
+
+impl KernPair {
+    pub const fn new(left: char, right: char, adjust: i8) -> Self {
+        Self { left: left as u32 as u16, right: right as u32 as u16, adjust }
+    }
+}
+
+/// An embedded proportional font at one pixel size
+pub struct BitmapFont {
+    /// Distance between baselines of consecutive lines
+    pub line_height: u32,
+    /// Baseline position below the top of the line
+    pub ascent: u32,
+    /// Characters covered, sorted and starting with ' ' to '~'
+    pub ranges: &'static [GlyphRange],
+    /// Glyphs of the characters in `ranges`, in order
+    pub glyphs: &'static [Glyph],
+    /// Glyph rows, MSB leftmost, each row padded to a whole byte
+    pub bitmap: &'static [u8],
+    /// Kerning pairs, sorted by (left, right)
+    pub kerning: &'static [KernPair],
+}
+
+impl BitmapFont {
+    /// The face's own glyph for `c`, if it has one
+    pub fn lookup(&self, c: char) -> Option<&Glyph> {
+        let range = self.ranges.iter().find(|range| (range.first..=range.last).contains(&c))?;
+        self.glyphs.get(range.start as usize + (c as usize - range.first as usize))
+    }
+
+    /// Does the face have a glyph for `c`?
+    pub fn covers(&self, c: char) -> bool {
+        self.lookup(c).is_some()
+    }
+
+    /// Glyph for `c`; characters outside the face show as '?'
+    pub fn glyph(&self, c: char) -> &Glyph {
+        self.lookup(c).unwrap_or_else(|| self.missing())
+    }
+
+    /// Kerning adjustment between `left` and `right`
+    pub fn kerning(&self, left: char, right: char) -> i32 {
+        let (Ok(left), Ok(right)) = (u16::try_from(left as u32), u16::try_from(right as u32)) else {
+            return 0;
+        };
+        self.kerning
+            .binary_search_by(|pair| (pair.left, pair.right).cmp(&(left, right)))
+            .map_or(0, |i| self.kerning[i].adjust as i32)
+    }
+
+    /// Width of `text` in pixels: the pen position after its last glyph
+    ///
+    /// Control characters take no space; characters outside the face are
+    /// measured as the default [`Fallback`] draws them.
+    pub fn text_width(&self, text: &str) -> u32 {
+        self.chars_width(text.chars(), Fallback::default())
+    }
+
+    /// Width of `chars` drawn with `fallback` for characters outside the face
+    pub fn chars_width(&self, chars: impl IntoIterator<Item = char>, fallback: Fallback) -> u32 {
+        let mut pen = 0i32;
+        self.layout(chars, fallback, |_, _| {}, &mut pen);
+        pen.max(0) as u32
+    }
+
+    /// Draw `text` with its line box at (`x`, `y`); returns its width
+    ///
+    /// Pixels outside the target are clipped. Characters outside the face
+    /// are drawn by the default [`Fallback`] (folded to ASCII).
+    pub fn draw_text<T: TextTarget + ?Sized>(
+        &self,
+        target: &mut T,
+        x: i32,
+        y: i32,
+        text: &str,
+        color: Color,
+    ) -> u32 {
+        self.draw_chars(target, x, y, text.chars(), color, Fallback::default())
+    }
+
+    /// Draw `chars`, using `fallback` for characters outside the face;
+    /// returns the width drawn. Pair with [`decode_utf8`](super::decode_utf8)
+    /// for text held as bytes.
+    pub fn draw_chars<T: TextTarget + ?Sized>(
+        &self,
+        target: &mut T,
+        x: i32,
+        y: i32,
+        chars: impl IntoIterator<Item = char>,
+        color: Color,
+        fallback: Fallback,
+    ) -> u32 {
+        let mut pen = 0i32;
+        self.layout(chars, fallback, |glyph, pen_x| self.draw_glyph(target, x + pen_x, y, glyph, color), &mut pen);
+        pen.max(0) as u32
+    }
+
+    /// Draw `text` centered horizontally on `center_x`
+    pub fn draw_text_centered<T: TextTarget + ?Sized>(
+        &self,
+        target: &mut T,
+        center_x: i32,
+        y: i32,
+        text: &str,
+        color: Color,
+    ) -> u32 {
+        let x = center_x - (self.text_width(text) / 2) as i32;
+        self.draw_text(target, x, y, text, color)
+    }
+
+    /// The glyph drawn for characters nothing else can stand in for
+    fn missing(&self) -> &Glyph {
+        self.lookup(MISSING).expect("every face covers printable ASCII")
+    }
+
+    /// Walk `chars`, calling `place` with each glyph drawn and its pen
+    /// position
+    fn layout(
+        &self,
+        chars: impl IntoIterator<Item = char>,
+        fallback: Fallback,
+        mut place: impl FnMut(&Glyph, i32),
+        pen: &mut i32,
+    ) {
+        let mut previous = None;
+        let mut emit = |c: char, glyph: &Glyph| {
+            if let Some(previous) = previous {
+                *pen += self.kerning(previous, c);
+            }
+            place(glyph, *pen);
+            *pen += glyph.advance as i32;
+            previous = Some(c);
+        };
+        for c in chars.into_iter().filter(|c| !c.is_control()) {
+            if let Some(glyph) = self.lookup(c) {
+                emit(c, glyph);
+                continue;
+            }
+            match fallback {
+                Fallback::Fold => match fold_to_ascii(c) {
+                    Some(folded) => folded.chars().for_each(|c| emit(c, self.glyph(c))),
+                    None => emit(MISSING, self.missing()),
+                },
+                Fallback::Replace(with) => match self.lookup(with) {
+                    Some(glyph) => emit(with, glyph),
+                    None => emit(MISSING, self.missing()),
+                },
+                Fallback::Skip => {}
+            }
+        }
+    }
+
+    fn draw_glyph<T: TextTarget + ?Sized>(&self, target: &mut T, pen_x: i32, y: i32, glyph: &Glyph, color: Color) {
+        let row_bytes = glyph.row_bytes();
+        let left = pen_x + glyph.x_offset as i32;
+        let top = y + glyph.y_offset as i32;
+        for row in 0..glyph.height as usize {
+            let start = glyph.offset as usize + row * row_bytes;
+            let bits = &self.bitmap[start..start + row_bytes];
+            for col in 0..glyph.width as usize {
+                if bits[col / 8] & (0x80 >> (col % 8)) != 0 {
+                    target.plot(left + col as i32, top + row as i32, color);
+                }
+            }
+        }
+    }
+}
+
+/// Something glyphs can be drawn into
+pub trait TextTarget {
+    /// Set one pixel; coordinates outside the target are ignored
+    fn plot(&mut self, x: i32, y: i32, color: Color);
+}
+
+impl TextTarget for Framebuffer {
+    fn plot(&mut self, x: i32, y: i32, color: Color) {
+        if x >= 0 && y >= 0 {
+            self.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+/// A 32-bit ARGB pixel buffer reached through a raw pointer, for PDs that
+/// draw into their framebuffer mapping directly
+pub struct RawTarget {
+    ptr: *mut u32,
+    pitch: usize,
+    width: usize,
+    height: usize,
+}
+
+impl RawTarget {
+    /// # Safety
+    /// `ptr` must be valid for writes of `height` rows of `pitch` pixels
+    /// for as long as the target is used, and `width <= pitch`.
+    pub unsafe fn new(ptr: *mut u32, pitch: usize, width: usize, height: usize) -> Self {
+        Self { ptr, pitch, width, height }
+    }
+}
+
+impl TextTarget for RawTarget {
+    fn plot(&mut self, x: i32, y: i32, color: Color) {
+        let (x, y) = (x as usize, y as usize);
+        // Negative coordinates wrap to huge values and fail here too
+        if x < self.width && y < self.height {
+            unsafe { self.ptr.add(y * self.pitch + x).write_volatile(color.to_argb()) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::FONTS;
+
+    /// Records plotted pixels
+    struct Canvas {
+        pixels: [[bool; 64]; 32],
+    }
+
+    impl TextTarget for Canvas {
+        fn plot(&mut self, x: i32, y: i32, _color: Color) {
+            if (0..64).contains(&x) && (0..32).contains(&y) {
+                self.pixels[y as usize][x as usize] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn glyphs_stay_inside_bitmap_and_line() {
+        for font in FONTS {
+            assert_eq!((font.ranges[0].first, font.ranges[0].last), (' ', '~'));
+            let mut start = 0;
+            for pair in font.ranges.windows(2) {
+                assert!(pair[0].last < pair[1].first);
+            }
+            for range in font.ranges {
+                assert_eq!(range.start, start);
+                start += (range.last as u32 - range.first as u32 + 1) as u16;
+            }
+            assert_eq!(font.glyphs.len(), start as usize);
+            for glyph in font.glyphs {
+                let end = glyph.offset as usize + glyph.row_bytes() * glyph.height as usize;
+                assert!(end <= font.bitmap.len());
+                assert!(glyph.y_offset >= 0);
+                assert!(glyph.y_offset as u32 + glyph.height as u32 <= font.line_height);
+            }
+            assert!(font.kerning.windows(2).all(|w| (w[0].left, w[0].right) < (w[1].left, w[1].right)));
+        }
+    }
+
+    #[test]
+    fn width_is_proportional_and_kerned() {
+        let font = &crate::font::SANS_16;
+        assert!(font.text_width("i") < font.text_width("W"));
+        assert_eq!(font.text_width(""), 0);
+        assert_eq!(font.text_width("a\nb"), font.text_width("ab"));
+
+        let kern = font.kerning('A', 'V');
+        assert!(kern < 0);
+        let plain = font.glyph('A').advance as u32 + font.glyph('V').advance as u32;
+        assert_eq!(font.text_width("AV") as i32, plain as i32 + kern);
+        assert_eq!(font.kerning('A', '\u{1F600}'), 0);
+    }
+
+    #[test]
+    fn draw_matches_measured_width() {
+        let font = &crate::font::PIXEL_8;
+        let mut canvas = Canvas { pixels: [[false; 64]; 32] };
+        let width = font.draw_text(&mut canvas, 0, 0, "HI", Color::WHITE);
+        assert_eq!(width, font.text_width("HI"));
+        // Nothing drawn past the last advance, or below the line
+        assert!(canvas.pixels.iter().all(|row| row[width as usize..].iter().all(|&p| !p)));
+        assert!(canvas.pixels[8..].iter().all(|row| row.iter().all(|&p| !p)));
+        // Unknown characters fall back to '?'
+        assert_eq!(font.glyph('\u{e9}'), font.glyph('?'));
+    }
+
+    #[test]
+    fn fallback_policy_decides_uncovered_characters() {
+        let pixel = &crate::font::PIXEL_8;
+        let sans = &crate::font::SANS_16;
+        assert!(sans.covers('é') && sans.covers('°') && sans.covers('→') && sans.covers('┼'));
+        assert!(!pixel.covers('é') && pixel.covers('°') && pixel.covers('┼'));
+        assert_eq!(sans.text_width("Café"), sans.chars_width("Café".chars(), Fallback::Skip));
+
+        // 8 px has no accents: folded by default, or replaced, or dropped
+        assert_eq!(pixel.text_width("CAFÉ"), pixel.text_width("CAFE"));
+        assert_eq!(pixel.text_width("Æ"), pixel.text_width("AE"));
+        assert_eq!(pixel.chars_width("É".chars(), Fallback::Replace('*')), pixel.text_width("*"));
+        assert_eq!(pixel.chars_width("É".chars(), Fallback::Replace('中')), pixel.text_width("?"));
+        assert_eq!(pixel.chars_width("CAFÉ".chars(), Fallback::Skip), pixel.text_width("CAF"));
+        // No folding at all: '?'
+        assert_eq!(sans.text_width("中"), sans.text_width("?"));
+    }
+}
@@ -0,0 +1,159 @@
+//! # Character Handling
+//!
+//! What both kinds of font do with text they have no glyph for: UTF-8
+//! decoding of raw names, ASCII folding and the [`Fallback`] policy.
+
+/// What to draw for a character a face does not cover
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fallback {
+    /// Approximate it in ASCII ("é" as "e", "Æ" as "AE", "→" as "->"),
+    /// or draw '?' if there is no approximation
+    #[default]
+    Fold,
+    /// Draw this character instead ('?' if the face lacks it too)
+    Replace(char),
+    /// Leave it out, taking no space
+    Skip,
+}
+
+/// Characters of `bytes` decoded as UTF-8, with each invalid sequence
+/// replaced by U+FFFD, for names that arrive as bytes (file systems,
+/// protocol messages) and may not be valid UTF-8
+pub fn decode_utf8(bytes: &[u8]) -> impl Iterator<Item = char> + '_ {
+    bytes.utf8_chunks().flat_map(|chunk| {
+        let invalid = (!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER);
+        chunk.valid().chars().chain(invalid)
+    })
+}
+
+/// Plain ASCII approximation of `c`, or `None` if there is no sensible one
+///
+/// Covers Latin-1 (accents dropped, ligatures spelled out), common
+/// punctuation, arrows and box drawing. ASCII characters fold to
+/// themselves.
+pub fn fold_to_ascii(c: char) -> Option<&'static str> {
+    if c.is_ascii() {
+        // Every ASCII character as a one-byte string
+        const ASCII: &str = "\0\x01\x02\x03\x04\x05\x06\x07\x08\t\n\x0B\x0C\r\x0E\x0F\
+            \x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1A\x1B\x1C\x1D\x1E\x1F \
+            !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`\
+            abcdefghijklmnopqrstuvwxyz{|}~\x7F";
+        let i = c as usize;
+        return Some(&ASCII[i..i + 1]);
+    }
+    let folded = match c {
+        '\u{A0}' => " ",
+        '¡' => "!",
+        '¢' => "c",
+        '£' => "L",
+        '¥' => "Y",
+        '¦' => "|",
+        '§' => "S",
+        '¨' => "\"",
+        '©' => "(C)",
+        'ª' | '°' | 'º' => "o",
+        '«' => "<<",
+        '\u{AD}' => "",
+        '®' => "(R)",
+        '¯' => "-",
+        '±' => "+-",
+        '²' => "2",
+        '³' => "3",
+        '´' => "'",
+        'µ' => "u",
+        '¶' => "P",
+        '·' => ".",
+        '¸' => ",",
+        '¹' => "1",
+        '»' => ">>",
+        '¼' => "1/4",
+        '½' => "1/2",
+        '¾' => "3/4",
+        '¿' => "?",
+        'À'..='Å' => "A",
+        'Æ' => "AE",
+        'Ç' => "C",
+        'È'..='Ë' => "E",
+        'Ì'..='Ï' => "I",
+        'Ð' => "D",
+        'Ñ' => "N",
+        'Ò'..='Ö' | 'Ø' => "O",
+        '×' => "x",
+        'Ù'..='Ü' => "U",
+        'Ý' => "Y",
+        'Þ' => "Th",
+        'ß' => "ss",
+        'à'..='å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è'..='ë' => "e",
+        'ì'..='ï' => "i",
+        'ð' => "d",
+        'ñ' => "n",
+        'ò'..='ö' | 'ø' => "o",
+        '÷' => "/",
+        'ù'..='ü' => "u",
+        'ý' | 'ÿ' => "y",
+        'þ' => "th",
+        '‐'..='—' | '−' => "-",
+        '‘' | '’' | '′' => "'",
+        '“' | '”' | '″' => "\"",
+        '•' => "*",
+        '…' => "...",
+        '€' => "EUR",
+        '←' => "<-",
+        '↑' => "^",
+        '→' => "->",
+        '↓' => "v",
+        '↔' => "<->",
+        '↕' => "|",
+        '\u{2500}'..='\u{257F}' => fold_box_drawing(c),
+        '\u{2580}'..='\u{259F}' => "#",
+        '\u{FFFD}' => "?",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Box-drawing characters as '-', '|' or '+' by the lines they have
+fn fold_box_drawing(c: char) -> &'static str {
+    match c {
+        '─' | '━' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' | '═' | '╴' | '╶' | '╸' | '╺' | '╼' | '╾' => "-",
+        '│' | '┃' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' | '║' | '╵' | '╷' | '╹' | '╻' | '╽' | '╿' => "|",
+        '╱' => "/",
+        '╲' => "\\",
+        '╳' => "X",
+        _ => "+",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_utf8_lossily() {
+        let mut chars = [' '; 8];
+        let mut n = 0;
+        for c in decode_utf8(b"Caf\xC3\xA9 \xFF!\xE2\x82") {
+            chars[n] = c;
+            n += 1;
+        }
+        assert_eq!(chars[..n], ['C', 'a', 'f', 'é', ' ', '\u{FFFD}', '!', '\u{FFFD}']);
+    }
+
+    #[test]
+    fn folds_to_ascii() {
+        assert_eq!(fold_to_ascii('q'), Some("q"));
+        assert_eq!(fold_to_ascii('~'), Some("~"));
+        assert_eq!(fold_to_ascii('é'), Some("e"));
+        assert_eq!(fold_to_ascii('Æ'), Some("AE"));
+        assert_eq!(fold_to_ascii('→'), Some("->"));
+        assert_eq!(fold_to_ascii('┼'), Some("+"));
+        assert_eq!(fold_to_ascii('║'), Some("|"));
+        assert_eq!(fold_to_ascii('中'), None);
+        // Latin-1 is covered throughout, except the currency sign and not sign
+        let unfolded = ('\u{A0}'..='\u{FF}').filter(|&c| fold_to_ascii(c).is_none());
+        assert!(unfolded.eq(['¤', '¬']));
+    }
+}
@@ -0,0 +1,369 @@
+//! # Bitmap Fonts
+//!
+//! Two kinds of embedded font:
+//!
+//! - The fixed 8x8 cell font below ([`FONT_8X8`], [`draw_string`]), for
+//!   character grids such as [`Terminal`](crate::Terminal)
+//! - Proportional faces ([`BitmapFont`]) at 8, 16 and 24 px, with kerning
+//!   and [`text_width`](BitmapFont::text_width) for layout: [`PIXEL_8`] is
+//!   the 8x8 glyphs trimmed to their ink, [`SANS_16`] and [`SANS_24`] are
+//!   DejaVu Sans
+//!
+//! Text is Unicode throughout. The sans faces cover Latin-1, arrows and box
+//! drawing; the 8x8 glyphs are uppercase ASCII plus [`FONT_8X8_EXTRA`].
+//! Anything else is drawn by [`Fallback`] policy, by default folded to ASCII
+//! ("Café" shows as "CAFE" in the 8x8 font). Names held as bytes go through
+//! [`decode_utf8`].
+//!
+//! The proportional faces are generated from this file and the DejaVu TTF
+//! by `tools/fontgen`; rerun it after changing [`FONT_8X8`] or
+//! [`FONT_8X8_EXTRA`].
+
+mod bitmap;
+mod charset;
+mod pixel8;
+mod sans16;
+mod sans24;
+
+pub use bitmap::{BitmapFont, Glyph, GlyphRange, KernPair, RawTarget, TextTarget};
+pub use charset::{decode_utf8, fold_to_ascii, Fallback};
+pub use pixel8::PIXEL_8;
+pub use sans16::SANS_16;
+pub use sans24::SANS_24;
+
+use crate::framebuffer::Framebuffer;
+use crate::graphics::Color;
+
+/// Every proportional face, smallest first
+pub static FONTS: [&BitmapFont; 3] = [&PIXEL_8, &SANS_16, &SANS_24];
+
+/// The largest proportional face whose lines fit in `line_height` pixels
+/// (the smallest face if none does)
+pub fn for_height(line_height: u32) -> &'static BitmapFont {
+    FONTS
+        .iter()
+        .rev()
+        .find(|font| font.line_height <= line_height)
+        .copied()
+        .unwrap_or(FONTS[0])
+}
+
+/// 8x8 bitmap font (ASCII 32-95: space to underscore)
+/// Each character is 8 bytes, one per row, MSB is leftmost pixel
+/// 64 characters × 8 bytes = 512 bytes
+pub static FONT_8X8: [u8; 512] = [
+    // Space (32)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // ! (33)
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x18, 0x00,
+    // " (34)
+    0x6C, 0x6C, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // # (35)
+    0x6C, 0x6C, 0xFE, 0x6C, 0xFE, 0x6C, 0x6C, 0x00,
+    // $ (36)
+    0x18, 0x3E, 0x60, 0x3C, 0x06, 0x7C, 0x18, 0x00,
+    // % (37)
+    0x00, 0x66, 0xAC, 0xD8, 0x36, 0x6A, 0xCC, 0x00,
+    // & (38)
+    0x38, 0x6C, 0x68, 0x76, 0xDC, 0xCC, 0x76, 0x00,
+    // ' (39)
+    0x18, 0x18, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // ( (40)
+    0x0C, 0x18, 0x30, 0x30, 0x30, 0x18, 0x0C, 0x00,
+    // ) (41)
+    0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x18, 0x30, 0x00,
+    // * (42)
+    0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00,
+    // + (43)
+    0x00, 0x18, 0x18, 0x7E, 0x18, 0x18, 0x00, 0x00,
+    // , (44)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x30,
+    // - (45)
+    0x00, 0x00, 0x00, 0x7E, 0x00, 0x00, 0x00, 0x00,
+    // . (46)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00,
+    // / (47)
+    0x06, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0x80, 0x00,
+    // 0 (48)
+    0x7C, 0xC6, 0xCE, 0xD6, 0xE6, 0xC6, 0x7C, 0x00,
+    // 1 (49)
+    0x18, 0x38, 0x18, 0x18, 0x18, 0x18, 0x7E, 0x00,
+    // 2 (50)
+    0x7C, 0xC6, 0x0C, 0x18, 0x30, 0x66, 0xFE, 0x00,
+    // 3 (51)
+    0x7C, 0xC6, 0x06, 0x3C, 0x06, 0xC6, 0x7C, 0x00,
+    // 4 (52)
+    0x0C, 0x1C, 0x3C, 0x6C, 0xFE, 0x0C, 0x0C, 0x00,
+    // 5 (53)
+    0xFE, 0xC0, 0xFC, 0x06, 0x06, 0xC6, 0x7C, 0x00,
+    // 6 (54)
+    0x7C, 0xC6, 0xC0, 0xFC, 0xC6, 0xC6, 0x7C, 0x00,
+    // 7 (55)
+    0xFE, 0xC6, 0x0C, 0x18, 0x30, 0x30, 0x30, 0x00,
+    // 8 (56)
+    0x7C, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0x7C, 0x00,
+    // 9 (57)
+    0x7C, 0xC6, 0xC6, 0x7E, 0x06, 0xC6, 0x7C, 0x00,
+    // : (58)
+    0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x00,
+    // ; (59)
+    0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x30,
+    // < (60)
+    0x0C, 0x18, 0x30, 0x60, 0x30, 0x18, 0x0C, 0x00,
+    // = (61)
+    0x00, 0x00, 0x7E, 0x00, 0x7E, 0x00, 0x00, 0x00,
+    // > (62)
+    0x30, 0x18, 0x0C, 0x06, 0x0C, 0x18, 0x30, 0x00,
+    // ? (63)
+    0x3C, 0x66, 0x0C, 0x18, 0x18, 0x00, 0x18, 0x00,
+    // @ (64)
+    0x7C, 0xC6, 0xDE, 0xDE, 0xDC, 0xC0, 0x7C, 0x00,
+    // A (65)
+    0x38, 0x6C, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0x00,
+    // B (66)
+    0xFC, 0xC6, 0xC6, 0xFC, 0xC6, 0xC6, 0xFC, 0x00,
+    // C (67)
+    0x7C, 0xC6, 0xC0, 0xC0, 0xC0, 0xC6, 0x7C, 0x00,
+    // D (68)
+    0xF8, 0xCC, 0xC6, 0xC6, 0xC6, 0xCC, 0xF8, 0x00,
+    // E (69)
+    0xFE, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xFE, 0x00,
+    // F (70)
+    0xFE, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0x00,
+    // G (71)
+    0x7C, 0xC6, 0xC0, 0xCE, 0xC6, 0xC6, 0x7C, 0x00,
+    // H (72)
+    0xC6, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xC6, 0x00,
+    // I (73)
+    0x7E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x7E, 0x00,
+    // J (74)
+    0x06, 0x06, 0x06, 0x06, 0xC6, 0xC6, 0x7C, 0x00,
+    // K (75)
+    0xC6, 0xCC, 0xD8, 0xF0, 0xD8, 0xCC, 0xC6, 0x00,
+    // L (76)
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0x00,
+    // M (77)
+    0xC6, 0xEE, 0xFE, 0xD6, 0xC6, 0xC6, 0xC6, 0x00,
+    // N (78)
+    0xC6, 0xE6, 0xF6, 0xDE, 0xCE, 0xC6, 0xC6, 0x00,
+    // O (79)
+    0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0x00,
+    // P (80)
+    0xFC, 0xC6, 0xC6, 0xFC, 0xC0, 0xC0, 0xC0, 0x00,
+    // Q (81)
+    0x7C, 0xC6, 0xC6, 0xC6, 0xD6, 0xDE, 0x7C, 0x06,
+    // R (82)
+    0xFC, 0xC6, 0xC6, 0xFC, 0xD8, 0xCC, 0xC6, 0x00,
+    // S (83)
+    0x7C, 0xC6, 0xC0, 0x7C, 0x06, 0xC6, 0x7C, 0x00,
+    // T (84)
+    0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x00,
+    // U (85)
+    0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0x00,
+    // V (86)
+    0xC6, 0xC6, 0xC6, 0xC6, 0x6C, 0x38, 0x10, 0x00,
+    // W (87)
+    0xC6, 0xC6, 0xC6, 0xD6, 0xFE, 0xEE, 0xC6, 0x00,
+    // X (88)
+    0xC6, 0x6C, 0x38, 0x38, 0x6C, 0xC6, 0xC6, 0x00,
+    // Y (89)
+    0xC3, 0x66, 0x3C, 0x18, 0x18, 0x18, 0x18, 0x00,
+    // Z (90)
+    0xFE, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0xFE, 0x00,
+    // [ (91)
+    0x3C, 0x30, 0x30, 0x30, 0x30, 0x30, 0x3C, 0x00,
+    // \ (92)
+    0xC0, 0x60, 0x30, 0x18, 0x0C, 0x06, 0x02, 0x00,
+    // ] (93)
+    0x3C, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x3C, 0x00,
+    // ^ (94)
+    0x10, 0x38, 0x6C, 0xC6, 0x00, 0x00, 0x00, 0x00,
+    // _ (95)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFE,
+];
+
+/// 8x8 glyphs beyond ASCII, sorted by character: the degree sign, arrows,
+/// light box drawing and a full block. Box drawing fills the cell so lines
+/// join up across neighbours.
+pub static FONT_8X8_EXTRA: [(char, [u8; 8]); 17] = [
+    ('\u{B0}', [0x38, 0x6C, 0x38, 0x00, 0x00, 0x00, 0x00, 0x00]),   // °
+    ('\u{2190}', [0x00, 0x30, 0x60, 0xFE, 0x60, 0x30, 0x00, 0x00]), // ←
+    ('\u{2191}', [0x18, 0x3C, 0x7E, 0x18, 0x18, 0x18, 0x18, 0x00]), // ↑
+    ('\u{2192}', [0x00, 0x18, 0x0C, 0xFE, 0x0C, 0x18, 0x00, 0x00]), // →
+    ('\u{2193}', [0x18, 0x18, 0x18, 0x18, 0x7E, 0x3C, 0x18, 0x00]), // ↓
+    ('\u{2500}', [0x00, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00]), // ─
+    ('\u{2502}', [0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18]), // │
+    ('\u{250C}', [0x00, 0x00, 0x00, 0x1F, 0x1F, 0x18, 0x18, 0x18]), // ┌
+    ('\u{2510}', [0x00, 0x00, 0x00, 0xF8, 0xF8, 0x18, 0x18, 0x18]), // ┐
+    ('\u{2514}', [0x18, 0x18, 0x18, 0x1F, 0x1F, 0x00, 0x00, 0x00]), // └
+    ('\u{2518}', [0x18, 0x18, 0x18, 0xF8, 0xF8, 0x00, 0x00, 0x00]), // ┘
+    ('\u{251C}', [0x18, 0x18, 0x18, 0x1F, 0x1F, 0x18, 0x18, 0x18]), // ├
+    ('\u{2524}', [0x18, 0x18, 0x18, 0xF8, 0xF8, 0x18, 0x18, 0x18]), // ┤
+    ('\u{252C}', [0x00, 0x00, 0x00, 0xFF, 0xFF, 0x18, 0x18, 0x18]), // ┬
+    ('\u{2534}', [0x18, 0x18, 0x18, 0xFF, 0xFF, 0x00, 0x00, 0x00]), // ┴
+    ('\u{253C}', [0x18, 0x18, 0x18, 0xFF, 0xFF, 0x18, 0x18, 0x18]), // ┼
+    ('\u{2588}', [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), // █
+];
+
+/// Font dimensions
+pub const CHAR_WIDTH: u32 = 8;
+pub const CHAR_HEIGHT: u32 = 8;
+
+/// Get the 8-byte bitmap for a character
+/// Returns array of 8 bytes, one per row, MSB is leftmost pixel
+pub fn get_char_bitmap(c: u8) -> [u8; 8] {
+    let ascii = c as usize;
+
+    // Only support ASCII 32-95 (space to underscore) and some extras
+    // Map lowercase to uppercase for simplicity
+    let mapped = if (97..=122).contains(&ascii) {
+        ascii - 32  // lowercase to uppercase
+    } else if (32..=95).contains(&ascii) {
+        ascii
+    } else {
+        32  // default to space
+    };
+
+    let index = (mapped - 32) * 8;
+    let mut result = [0u8; 8];
+    result.copy_from_slice(&FONT_8X8[index..index + 8]);
+    result
+}
+
+/// The 8x8 glyph for `c` itself, if the cell font has one (lowercase
+/// letters use the capitals)
+pub fn cell_glyph(c: char) -> Option<[u8; 8]> {
+    let c = c.to_ascii_uppercase();
+    if (' '..='_').contains(&c) {
+        let index = (c as usize - 32) * 8;
+        let mut rows = [0u8; 8];
+        rows.copy_from_slice(&FONT_8X8[index..index + 8]);
+        return Some(rows);
+    }
+    FONT_8X8_EXTRA
+        .binary_search_by(|(extra, _)| extra.cmp(&c))
+        .ok()
+        .map(|i| FONT_8X8_EXTRA[i].1)
+}
+
+/// Cells the 8x8 font draws for `c`: its glyph, or its ASCII folding
+/// ("Æ" takes two cells), or '?'. Each comes with the character whose
+/// glyph it is. Control characters draw nothing.
+pub(crate) fn for_each_cell(c: char, mut draw: impl FnMut(char, [u8; 8])) {
+    if c.is_control() {
+        return;
+    }
+    if let Some(rows) = cell_glyph(c) {
+        draw(c, rows);
+    } else if let Some(folded) = fold_to_ascii(c) {
+        for cell in folded.chars() {
+            if let Some(rows) = cell_glyph(cell) {
+                draw(cell, rows);
+            }
+        }
+    } else if let Some(rows) = cell_glyph('?') {
+        draw('?', rows);
+    }
+}
+
+/// Draw a single character
+///
+/// Characters without an 8x8 glyph show their ASCII folding, or '?';
+/// a folding of several letters shows just the first, to keep to one cell.
+pub fn draw_char(fb: &mut Framebuffer, x: u32, y: u32, c: char, color: Color) {
+    let mut first = None;
+    for_each_cell(c, |_, rows| {
+        first.get_or_insert(rows);
+    });
+    if let Some(rows) = first {
+        draw_cell(fb, x, y, &rows, color, 1);
+    }
+}
+
+/// Draw a string
+pub fn draw_string(fb: &mut Framebuffer, x: u32, y: u32, s: &str, color: Color) {
+    draw_string_scaled(fb, x, y, s, color, 1);
+}
+
+/// Draw a string with scaling (2x, 3x, etc.)
+pub fn draw_string_scaled(
+    fb: &mut Framebuffer,
+    x: u32,
+    y: u32,
+    s: &str,
+    color: Color,
+    scale: u32,
+) {
+    let (width, _) = fb.dimensions();
+    let mut cursor_x = x;
+
+    for c in s.chars() {
+        for_each_cell(c, |_, rows| {
+            // Stop if we go off screen
+            if cursor_x < width {
+                draw_cell(fb, cursor_x, y, &rows, color, scale);
+                cursor_x += CHAR_WIDTH * scale;
+            }
+        });
+    }
+}
+
+/// Draw a scaled character
+pub fn draw_char_scaled(
+    fb: &mut Framebuffer,
+    x: u32,
+    y: u32,
+    c: char,
+    color: Color,
+    scale: u32,
+) {
+    let mut first = None;
+    for_each_cell(c, |_, rows| {
+        first.get_or_insert(rows);
+    });
+    if let Some(rows) = first {
+        draw_cell(fb, x, y, &rows, color, scale);
+    }
+}
+
+/// Draw one 8x8 cell, each pixel a `scale` x `scale` block
+pub(crate) fn draw_cell(fb: &mut Framebuffer, x: u32, y: u32, rows: &[u8; 8], color: Color, scale: u32) {
+    for (row, byte) in (0..8u32).zip(rows) {
+        for col in 0..8u32 {
+            if (byte >> (7 - col)) & 1 != 0 {
+                if scale == 1 {
+                    fb.put_pixel(x + col, y + row, color);
+                } else {
+                    fb.fill_rect(x + col * scale, y + row * scale, scale, scale, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_font_covers_extras_and_folds_the_rest() {
+        assert!(FONT_8X8_EXTRA.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(cell_glyph('a'), cell_glyph('A'));
+        assert_eq!(cell_glyph('°'), Some(FONT_8X8_EXTRA[0].1));
+        assert_eq!(cell_glyph('é'), None);
+
+        let mut cells = [[0u8; 8]; 4];
+        let mut chars = [' '; 4];
+        let mut n = 0;
+        for c in ['É', 'Æ', '中', '\n'] {
+            for_each_cell(c, |cell, rows| {
+                cells[n] = rows;
+                chars[n] = cell;
+                n += 1;
+            });
+        }
+        let expected = ['E', 'A', 'E', '?'].map(|c| cell_glyph(c).unwrap());
+        assert_eq!(cells[..n], expected);
+        assert_eq!(chars[..n], ['E', 'A', 'E', '?']);
+    }
+}
@@ -0,0 +1,204 @@
+//! Classic 8x8 pixel glyphs, proportionally spaced (uppercase only)
+//!
+//! Generated by `tools/fontgen`; do not edit.
+
+use super::{BitmapFont, Glyph, GlyphRange, KernPair};
+
+pub static PIXEL_8: BitmapFont = BitmapFont {
+    line_height: 8,
+    ascent: 7,
+    ranges: &RANGES,
+    glyphs: &GLYPHS,
+    bitmap: &BITMAP,
+    kerning: &KERNING,
+};
+
+/// Characters covered, and where their glyphs start
+static RANGES: [GlyphRange; 15] = [
+    GlyphRange::new(' ', '~', 0),
+    GlyphRange::new('\u{B0}', '\u{B0}', 95),
+    GlyphRange::new('\u{2190}', '\u{2193}', 96),
+    GlyphRange::new('\u{2500}', '\u{2500}', 100),
+    GlyphRange::new('\u{2502}', '\u{2502}', 101),
+    GlyphRange::new('\u{250C}', '\u{250C}', 102),
+    GlyphRange::new('\u{2510}', '\u{2510}', 103),
+    GlyphRange::new('\u{2514}', '\u{2514}', 104),
+    GlyphRange::new('\u{2518}', '\u{2518}', 105),
+    GlyphRange::new('\u{251C}', '\u{251C}', 106),
+    GlyphRange::new('\u{2524}', '\u{2524}', 107),
+    GlyphRange::new('\u{252C}', '\u{252C}', 108),
+    GlyphRange::new('\u{2534}', '\u{2534}', 109),
+    GlyphRange::new('\u{253C}', '\u{253C}', 110),
+    GlyphRange::new('\u{2588}', '\u{2588}', 111),
+];
+
+/// Ink box and advance of each glyph, in range order
+static GLYPHS: [Glyph; 112] = [
+    Glyph::new(0, 0, 0, 0, 0, 4), // ' '
+    Glyph::new(0, 2, 7, 0, 0, 3), // '!'
+    Glyph::new(7, 5, 3, 0, 0, 6), // '"'
+    Glyph::new(10, 7, 7, 0, 0, 8), // '#'
+    Glyph::new(17, 6, 7, 0, 0, 7), // '$'
+    Glyph::new(24, 7, 6, 0, 1, 8), // '%'
+    Glyph::new(30, 7, 7, 0, 0, 8), // '&'
+    Glyph::new(37, 3, 3, 0, 0, 4), // '\''
+    Glyph::new(40, 4, 7, 0, 0, 5), // '('
+    Glyph::new(47, 4, 7, 0, 0, 5), // ')'
+    Glyph::new(54, 8, 5, 0, 1, 9), // '*'
+    Glyph::new(59, 6, 5, 0, 1, 7), // '+'
+    Glyph::new(64, 3, 3, 0, 5, 4), // ','
+    Glyph::new(67, 6, 1, 0, 3, 7), // '-'
+    Glyph::new(68, 2, 2, 0, 5, 3), // '.'
+    Glyph::new(70, 7, 7, 0, 0, 8), // '/'
+    Glyph::new(77, 7, 7, 0, 0, 8), // '0'
+    Glyph::new(84, 6, 7, 0, 0, 7), // '1'
+    Glyph::new(91, 7, 7, 0, 0, 8), // '2'
+    Glyph::new(98, 7, 7, 0, 0, 8), // '3'
+    Glyph::new(105, 7, 7, 0, 0, 8), // '4'
+    Glyph::new(112, 7, 7, 0, 0, 8), // '5'
+    Glyph::new(119, 7, 7, 0, 0, 8), // '6'
+    Glyph::new(126, 7, 7, 0, 0, 8), // '7'
+    Glyph::new(133, 7, 7, 0, 0, 8), // '8'
+    Glyph::new(140, 7, 7, 0, 0, 8), // '9'
+    Glyph::new(147, 2, 6, 0, 1, 3), // ':'
+    Glyph::new(153, 3, 7, 0, 1, 4), // ';'
+    Glyph::new(160, 5, 7, 0, 0, 6), // '<'
+    Glyph::new(167, 6, 3, 0, 2, 7), // '='
+    Glyph::new(170, 5, 7, 0, 0, 6), // '>'
+    Glyph::new(177, 6, 7, 0, 0, 7), // '?'
+    Glyph::new(184, 7, 7, 0, 0, 8), // '@'
+    Glyph::new(191, 7, 7, 0, 0, 8), // 'A'
+    Glyph::new(198, 7, 7, 0, 0, 8), // 'B'
+    Glyph::new(205, 7, 7, 0, 0, 8), // 'C'
+    Glyph::new(212, 7, 7, 0, 0, 8), // 'D'
+    Glyph::new(219, 7, 7, 0, 0, 8), // 'E'
+    Glyph::new(226, 7, 7, 0, 0, 8), // 'F'
+    Glyph::new(233, 7, 7, 0, 0, 8), // 'G'
+    Glyph::new(240, 7, 7, 0, 0, 8), // 'H'
+    Glyph::new(247, 6, 7, 0, 0, 7), // 'I'
+    Glyph::new(254, 7, 7, 0, 0, 8), // 'J'
+    Glyph::new(261, 7, 7, 0, 0, 8), // 'K'
+    Glyph::new(268, 7, 7, 0, 0, 8), // 'L'
+    Glyph::new(275, 7, 7, 0, 0, 8), // 'M'
+    Glyph::new(282, 7, 7, 0, 0, 8), // 'N'
+    Glyph::new(289, 7, 7, 0, 0, 8), // 'O'
+    Glyph::new(296, 7, 7, 0, 0, 8), // 'P'
+    Glyph::new(303, 7, 8, 0, 0, 8), // 'Q'
+    Glyph::new(311, 7, 7, 0, 0, 8), // 'R'
+    Glyph::new(318, 7, 7, 0, 0, 8), // 'S'
+    Glyph::new(325, 8, 7, 0, 0, 9), // 'T'
+    Glyph::new(332, 7, 7, 0, 0, 8), // 'U'
+    Glyph::new(339, 7, 7, 0, 0, 8), // 'V'
+    Glyph::new(346, 7, 7, 0, 0, 8), // 'W'
+    Glyph::new(353, 7, 7, 0, 0, 8), // 'X'
+    Glyph::new(360, 8, 7, 0, 0, 9), // 'Y'
+    Glyph::new(367, 7, 7, 0, 0, 8), // 'Z'
+    Glyph::new(374, 4, 7, 0, 0, 5), // '['
+    Glyph::new(381, 7, 7, 0, 0, 8), // '\\'
+    Glyph::new(388, 4, 7, 0, 0, 5), // ']'
+    Glyph::new(395, 7, 4, 0, 0, 8), // '^'
+    Glyph::new(399, 7, 1, 0, 7, 8), // '_'
+    Glyph::new(400, 6, 7, 0, 0, 7), // '`'
+    Glyph::new(407, 7, 7, 0, 0, 8), // 'a'
+    Glyph::new(414, 7, 7, 0, 0, 8), // 'b'
+    Glyph::new(421, 7, 7, 0, 0, 8), // 'c'
+    Glyph::new(428, 7, 7, 0, 0, 8), // 'd'
+    Glyph::new(435, 7, 7, 0, 0, 8), // 'e'
+    Glyph::new(442, 7, 7, 0, 0, 8), // 'f'
+    Glyph::new(449, 7, 7, 0, 0, 8), // 'g'
+    Glyph::new(456, 7, 7, 0, 0, 8), // 'h'
+    Glyph::new(463, 6, 7, 0, 0, 7), // 'i'
+    Glyph::new(470, 7, 7, 0, 0, 8), // 'j'
+    Glyph::new(477, 7, 7, 0, 0, 8), // 'k'
+    Glyph::new(484, 7, 7, 0, 0, 8), // 'l'
+    Glyph::new(491, 7, 7, 0, 0, 8), // 'm'
+    Glyph::new(498, 7, 7, 0, 0, 8), // 'n'
+    Glyph::new(505, 7, 7, 0, 0, 8), // 'o'
+    Glyph::new(512, 7, 7, 0, 0, 8), // 'p'
+    Glyph::new(519, 7, 8, 0, 0, 8), // 'q'
+    Glyph::new(527, 7, 7, 0, 0, 8), // 'r'
+    Glyph::new(534, 7, 7, 0, 0, 8), // 's'
+    Glyph::new(541, 8, 7, 0, 0, 9), // 't'
+    Glyph::new(548, 7, 7, 0, 0, 8), // 'u'
+    Glyph::new(555, 7, 7, 0, 0, 8), // 'v'
+    Glyph::new(562, 7, 7, 0, 0, 8), // 'w'
+    Glyph::new(569, 7, 7, 0, 0, 8), // 'x'
+    Glyph::new(576, 8, 7, 0, 0, 9), // 'y'
+    Glyph::new(583, 7, 7, 0, 0, 8), // 'z'
+    Glyph::new(590, 6, 7, 0, 0, 7), // '{'
+    Glyph::new(597, 6, 7, 0, 0, 7), // '|'
+    Glyph::new(604, 6, 7, 0, 0, 7), // '}'
+    Glyph::new(611, 6, 7, 0, 0, 7), // '~'
+    Glyph::new(618, 5, 3, 0, 0, 6), // '\u{B0}' °
+    Glyph::new(621, 7, 5, 0, 1, 8), // '\u{2190}' ←
+    Glyph::new(626, 6, 7, 0, 0, 7), // '\u{2191}' ↑
+    Glyph::new(633, 7, 5, 0, 1, 8), // '\u{2192}' →
+    Glyph::new(638, 6, 7, 0, 0, 7), // '\u{2193}' ↓
+    Glyph::new(645, 8, 8, 0, 0, 8), // '\u{2500}' ─
+    Glyph::new(653, 8, 8, 0, 0, 8), // '\u{2502}' │
+    Glyph::new(661, 8, 8, 0, 0, 8), // '\u{250C}' ┌
+    Glyph::new(669, 8, 8, 0, 0, 8), // '\u{2510}' ┐
+    Glyph::new(677, 8, 8, 0, 0, 8), // '\u{2514}' └
+    Glyph::new(685, 8, 8, 0, 0, 8), // '\u{2518}' ┘
+    Glyph::new(693, 8, 8, 0, 0, 8), // '\u{251C}' ├
+    Glyph::new(701, 8, 8, 0, 0, 8), // '\u{2524}' ┤
+    Glyph::new(709, 8, 8, 0, 0, 8), // '\u{252C}' ┬
+    Glyph::new(717, 8, 8, 0, 0, 8), // '\u{2534}' ┴
+    Glyph::new(725, 8, 8, 0, 0, 8), // '\u{253C}' ┼
+    Glyph::new(733, 8, 8, 0, 0, 8), // '\u{2588}' █
+];
+
+/// Glyph rows, MSB leftmost, each row padded to a whole byte
+static BITMAP: [u8; 741] = [
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0xC0, 0xD8, 0xD8, 0x48, 0x6C, 0x6C, 0xFE, 0x6C, 0xFE, 0x6C,
+    0x6C, 0x30, 0x7C, 0xC0, 0x78, 0x0C, 0xF8, 0x30, 0x66, 0xAC, 0xD8, 0x36, 0x6A, 0xCC, 0x38, 0x6C,
+    0x68, 0x76, 0xDC, 0xCC, 0x76, 0x60, 0x60, 0xC0, 0x30, 0x60, 0xC0, 0xC0, 0xC0, 0x60, 0x30, 0xC0,
+    0x60, 0x30, 0x30, 0x30, 0x60, 0xC0, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x30, 0x30, 0xFC, 0x30, 0x30,
+    0x60, 0x60, 0xC0, 0xFC, 0xC0, 0xC0, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0x80, 0x7C, 0xC6, 0xCE,
+    0xD6, 0xE6, 0xC6, 0x7C, 0x30, 0x70, 0x30, 0x30, 0x30, 0x30, 0xFC, 0x7C, 0xC6, 0x0C, 0x18, 0x30,
+    0x66, 0xFE, 0x7C, 0xC6, 0x06, 0x3C, 0x06, 0xC6, 0x7C, 0x0C, 0x1C, 0x3C, 0x6C, 0xFE, 0x0C, 0x0C,
+    0xFE, 0xC0, 0xFC, 0x06, 0x06, 0xC6, 0x7C, 0x7C, 0xC6, 0xC0, 0xFC, 0xC6, 0xC6, 0x7C, 0xFE, 0xC6,
+    0x0C, 0x18, 0x30, 0x30, 0x30, 0x7C, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0x7C, 0x7C, 0xC6, 0xC6, 0x7E,
+    0x06, 0xC6, 0x7C, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0x60, 0x60, 0x00, 0x00, 0x60, 0x60, 0xC0,
+    0x18, 0x30, 0x60, 0xC0, 0x60, 0x30, 0x18, 0xFC, 0x00, 0xFC, 0xC0, 0x60, 0x30, 0x18, 0x30, 0x60,
+    0xC0, 0x78, 0xCC, 0x18, 0x30, 0x30, 0x00, 0x30, 0x7C, 0xC6, 0xDE, 0xDE, 0xDC, 0xC0, 0x7C, 0x38,
+    0x6C, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xFC, 0xC6, 0xC6, 0xFC, 0xC6, 0xC6, 0xFC, 0x7C, 0xC6, 0xC0,
+    0xC0, 0xC0, 0xC6, 0x7C, 0xF8, 0xCC, 0xC6, 0xC6, 0xC6, 0xCC, 0xF8, 0xFE, 0xC0, 0xC0, 0xFC, 0xC0,
+    0xC0, 0xFE, 0xFE, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0x7C, 0xC6, 0xC0, 0xCE, 0xC6, 0xC6, 0x7C,
+    0xC6, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xC6, 0xFC, 0x30, 0x30, 0x30, 0x30, 0x30, 0xFC, 0x06, 0x06,
+    0x06, 0x06, 0xC6, 0xC6, 0x7C, 0xC6, 0xCC, 0xD8, 0xF0, 0xD8, 0xCC, 0xC6, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xFE, 0xC6, 0xEE, 0xFE, 0xD6, 0xC6, 0xC6, 0xC6, 0xC6, 0xE6, 0xF6, 0xDE, 0xCE, 0xC6,
+    0xC6, 0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0xFC, 0xC6, 0xC6, 0xFC, 0xC0, 0xC0, 0xC0, 0x7C,
+    0xC6, 0xC6, 0xC6, 0xD6, 0xDE, 0x7C, 0x06, 0xFC, 0xC6, 0xC6, 0xFC, 0xD8, 0xCC, 0xC6, 0x7C, 0xC6,
+    0xC0, 0x7C, 0x06, 0xC6, 0x7C, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xC6, 0xC6, 0xC6, 0xC6,
+    0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0x6C, 0x38, 0x10, 0xC6, 0xC6, 0xC6, 0xD6, 0xFE, 0xEE,
+    0xC6, 0xC6, 0x6C, 0x38, 0x38, 0x6C, 0xC6, 0xC6, 0xC3, 0x66, 0x3C, 0x18, 0x18, 0x18, 0x18, 0xFE,
+    0x0C, 0x18, 0x30, 0x60, 0xC0, 0xFE, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF0, 0xC0, 0x60, 0x30,
+    0x18, 0x0C, 0x06, 0x02, 0xF0, 0x30, 0x30, 0x30, 0x30, 0x30, 0xF0, 0x10, 0x38, 0x6C, 0xC6, 0xFE,
+    0x78, 0xCC, 0x18, 0x30, 0x30, 0x00, 0x30, 0x38, 0x6C, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xFC, 0xC6,
+    0xC6, 0xFC, 0xC6, 0xC6, 0xFC, 0x7C, 0xC6, 0xC0, 0xC0, 0xC0, 0xC6, 0x7C, 0xF8, 0xCC, 0xC6, 0xC6,
+    0xC6, 0xCC, 0xF8, 0xFE, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xFE, 0xFE, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0,
+    0xC0, 0x7C, 0xC6, 0xC0, 0xCE, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xC6, 0xFC,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0xFC, 0x06, 0x06, 0x06, 0x06, 0xC6, 0xC6, 0x7C, 0xC6, 0xCC, 0xD8,
+    0xF0, 0xD8, 0xCC, 0xC6, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0xC6, 0xEE, 0xFE, 0xD6, 0xC6,
+    0xC6, 0xC6, 0xC6, 0xE6, 0xF6, 0xDE, 0xCE, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C,
+    0xFC, 0xC6, 0xC6, 0xFC, 0xC0, 0xC0, 0xC0, 0x7C, 0xC6, 0xC6, 0xC6, 0xD6, 0xDE, 0x7C, 0x06, 0xFC,
+    0xC6, 0xC6, 0xFC, 0xD8, 0xCC, 0xC6, 0x7C, 0xC6, 0xC0, 0x7C, 0x06, 0xC6, 0x7C, 0xFF, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0x6C,
+    0x38, 0x10, 0xC6, 0xC6, 0xC6, 0xD6, 0xFE, 0xEE, 0xC6, 0xC6, 0x6C, 0x38, 0x38, 0x6C, 0xC6, 0xC6,
+    0xC3, 0x66, 0x3C, 0x18, 0x18, 0x18, 0x18, 0xFE, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0xFE, 0x78, 0xCC,
+    0x18, 0x30, 0x30, 0x00, 0x30, 0x78, 0xCC, 0x18, 0x30, 0x30, 0x00, 0x30, 0x78, 0xCC, 0x18, 0x30,
+    0x30, 0x00, 0x30, 0x78, 0xCC, 0x18, 0x30, 0x30, 0x00, 0x30, 0x70, 0xD8, 0x70, 0x30, 0x60, 0xFE,
+    0x60, 0x30, 0x30, 0x78, 0xFC, 0x30, 0x30, 0x30, 0x30, 0x18, 0x0C, 0xFE, 0x0C, 0x18, 0x30, 0x30,
+    0x30, 0x30, 0xFC, 0x78, 0x30, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x00, 0x00, 0x1F, 0x1F, 0x18, 0x18, 0x18, 0x00, 0x00, 0x00,
+    0xF8, 0xF8, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1F, 0x1F, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18,
+    0xF8, 0xF8, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18, 0x1F, 0x1F, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0xF8, 0xF8, 0x18, 0x18, 0x18, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0xFF, 0xFF, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18, 0xFF, 0xFF, 0x18, 0x18, 0x18, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+];
+
+/// Kerning pairs, sorted by (left, right)
+static KERNING: [KernPair; 0] = [
+];
@@ -0,0 +1,1048 @@
+//! DejaVu Sans for a 16 pixel line (rasterized at 13.74 px per em)
+//!
+//! Generated by `tools/fontgen`; do not edit.
+
+use super::{BitmapFont, Glyph, GlyphRange, KernPair};
+
+pub static SANS_16: BitmapFont = BitmapFont {
+    line_height: 16,
+    ascent: 13,
+    ranges: &RANGES,
+    glyphs: &GLYPHS,
+    bitmap: &BITMAP,
+    kerning: &KERNING,
+};
+
+/// Characters covered, and where their glyphs start
+static RANGES: [GlyphRange; 5] = [
+    GlyphRange::new(' ', '~', 0),
+    GlyphRange::new('\u{A0}', '\u{FF}', 95),
+    GlyphRange::new('\u{2190}', '\u{2195}', 191),
+    GlyphRange::new('\u{2500}', '\u{257F}', 197),
+    GlyphRange::new('\u{FFFD}', '\u{FFFD}', 325),
+];
+
+/// Ink box and advance of each glyph, in range order
+static GLYPHS: [Glyph; 326] = [
+    Glyph::new(0, 0, 0, 0, 13, 4), // ' '
+    Glyph::new(0, 1, 10, 2, 3, 6), // '!'
+    Glyph::new(10, 4, 4, 1, 3, 6), // '"'
+    Glyph::new(14, 9, 10, 1, 3, 12), // '#'
+    Glyph::new(34, 7, 12, 1, 3, 9), // '$'
+    Glyph::new(46, 11, 10, 1, 3, 13), // '%'
+    Glyph::new(66, 9, 10, 1, 3, 11), // '&'
+    Glyph::new(86, 1, 3, 1, 3, 4), // '\''
+    Glyph::new(89, 3, 12, 1, 3, 5), // '('
+    Glyph::new(101, 3, 12, 1, 3, 5), // ')'
+    Glyph::new(113, 5, 6, 1, 3, 7), // '*'
+    Glyph::new(119, 8, 8, 2, 5, 12), // '+'
+    Glyph::new(127, 2, 4, 1, 11, 4), // ','
+    Glyph::new(131, 3, 1, 1, 9, 5), // '-'
+    Glyph::new(132, 2, 2, 1, 11, 4), // '.'
+    Glyph::new(134, 4, 11, 0, 3, 5), // '/'
+    Glyph::new(145, 7, 10, 1, 3, 9), // '0'
+    Glyph::new(155, 5, 10, 2, 3, 9), // '1'
+    Glyph::new(165, 6, 10, 1, 3, 9), // '2'
+    Glyph::new(175, 7, 10, 1, 3, 9), // '3'
+    Glyph::new(185, 7, 10, 1, 3, 9), // '4'
+    Glyph::new(195, 7, 10, 1, 3, 9), // '5'
+    Glyph::new(205, 7, 10, 1, 3, 9), // '6'
+    Glyph::new(215, 7, 10, 1, 3, 9), // '7'
+    Glyph::new(225, 7, 10, 1, 3, 9), // '8'
+    Glyph::new(235, 7, 10, 1, 3, 9), // '9'
+    Glyph::new(245, 1, 7, 2, 6, 5), // ':'
+    Glyph::new(252, 2, 9, 1, 6, 5), // ';'
+    Glyph::new(261, 9, 7, 1, 5, 12), // '<'
+    Glyph::new(275, 8, 4, 2, 7, 12), // '='
+    Glyph::new(279, 9, 7, 1, 5, 12), // '>'
+    Glyph::new(293, 5, 10, 1, 3, 7), // '?'
+    Glyph::new(303, 12, 12, 1, 3, 14), // '@'
+    Glyph::new(327, 9, 10, 0, 3, 9), // 'A'
+    Glyph::new(347, 7, 10, 1, 3, 9), // 'B'
+    Glyph::new(357, 8, 10, 1, 3, 10), // 'C'
+    Glyph::new(367, 9, 10, 1, 3, 11), // 'D'
+    Glyph::new(387, 7, 10, 1, 3, 9), // 'E'
+    Glyph::new(397, 6, 10, 1, 3, 8), // 'F'
+    Glyph::new(407, 9, 10, 1, 3, 11), // 'G'
+    Glyph::new(427, 8, 10, 1, 3, 10), // 'H'
+    Glyph::new(437, 2, 10, 1, 3, 4), // 'I'
+    Glyph::new(447, 4, 13, -1, 3, 4), // 'J'
+    Glyph::new(460, 8, 10, 1, 3, 9), // 'K'
+    Glyph::new(470, 7, 10, 1, 3, 8), // 'L'
+    Glyph::new(480, 10, 10, 1, 3, 12), // 'M'
+    Glyph::new(500, 8, 10, 1, 3, 10), // 'N'
+    Glyph::new(510, 9, 10, 1, 3, 11), // 'O'
+    Glyph::new(530, 7, 10, 1, 3, 8), // 'P'
+    Glyph::new(540, 9, 12, 1, 3, 11), // 'Q'
+    Glyph::new(564, 8, 10, 1, 3, 10), // 'R'
+    Glyph::new(574, 7, 10, 1, 3, 9), // 'S'
+    Glyph::new(584, 8, 10, 0, 3, 8), // 'T'
+    Glyph::new(594, 8, 10, 1, 3, 10), // 'U'
+    Glyph::new(604, 9, 10, 0, 3, 9), // 'V'
+    Glyph::new(624, 12, 10, 1, 3, 14), // 'W'
+    Glyph::new(644, 8, 10, 1, 3, 9), // 'X'
+    Glyph::new(654, 8, 10, 0, 3, 8), // 'Y'
+    Glyph::new(664, 8, 10, 1, 3, 9), // 'Z'
+    Glyph::new(674, 3, 12, 1, 3, 5), // '['
+    Glyph::new(686, 4, 11, 0, 3, 5), // '\\'
+    Glyph::new(697, 3, 12, 1, 3, 5), // ']'
+    Glyph::new(709, 8, 4, 2, 3, 12), // '^'
+    Glyph::new(713, 7, 1, 0, 15, 7), // '_'
+    Glyph::new(714, 2, 2, 2, 2, 7), // '`'
+    Glyph::new(716, 6, 8, 1, 5, 8), // 'a'
+    Glyph::new(724, 7, 10, 1, 3, 9), // 'b'
+    Glyph::new(734, 6, 8, 1, 5, 8), // 'c'
+    Glyph::new(742, 6, 10, 1, 3, 9), // 'd'
+    Glyph::new(752, 7, 8, 1, 5, 8), // 'e'
+    Glyph::new(760, 4, 10, 1, 3, 5), // 'f'
+    Glyph::new(770, 6, 11, 1, 5, 9), // 'g'
+    Glyph::new(781, 7, 10, 1, 3, 9), // 'h'
+    Glyph::new(791, 2, 10, 1, 3, 4), // 'i'
+    Glyph::new(801, 3, 13, 0, 3, 4), // 'j'
+    Glyph::new(814, 6, 10, 1, 3, 8), // 'k'
+    Glyph::new(824, 2, 10, 1, 3, 4), // 'l'
+    Glyph::new(834, 11, 8, 1, 5, 13), // 'm'
+    Glyph::new(850, 7, 8, 1, 5, 9), // 'n'
+    Glyph::new(858, 7, 8, 1, 5, 8), // 'o'
+    Glyph::new(866, 7, 11, 1, 5, 9), // 'p'
+    Glyph::new(877, 6, 11, 1, 5, 9), // 'q'
+    Glyph::new(888, 4, 8, 1, 5, 6), // 'r'
+    Glyph::new(896, 5, 8, 1, 5, 7), // 's'
+    Glyph::new(904, 4, 9, 1, 4, 5), // 't'
+    Glyph::new(913, 6, 7, 1, 6, 9), // 'u'
+    Glyph::new(920, 6, 7, 1, 6, 8), // 'v'
+    Glyph::new(927, 9, 8, 1, 5, 11), // 'w'
+    Glyph::new(943, 6, 8, 1, 5, 8), // 'x'
+    Glyph::new(951, 6, 10, 1, 6, 8), // 'y'
+    Glyph::new(961, 6, 8, 1, 5, 7), // 'z'
+    Glyph::new(969, 5, 12, 2, 3, 9), // '{'
+    Glyph::new(981, 1, 13, 2, 3, 5), // '|'
+    Glyph::new(994, 5, 12, 2, 3, 9), // '}'
+    Glyph::new(1006, 8, 2, 2, 8, 12), // '~'
+    Glyph::new(1008, 0, 0, 0, 13, 4), // '\u{A0}'
+    Glyph::new(1008, 1, 9, 2, 6, 6), // '\u{A1}' ¡
+    Glyph::new(1017, 6, 9, 1, 5, 9), // '\u{A2}' ¢
+    Glyph::new(1026, 7, 10, 1, 3, 9), // '\u{A3}' £
+    Glyph::new(1036, 7, 7, 1, 5, 9), // '\u{A4}' ¤
+    Glyph::new(1043, 7, 10, 1, 3, 9), // '\u{A5}' ¥
+    Glyph::new(1053, 1, 12, 2, 3, 5), // '\u{A6}' ¦
+    Glyph::new(1065, 5, 11, 1, 3, 7), // '\u{A7}' §
+    Glyph::new(1076, 4, 1, 1, 3, 7), // '\u{A8}' ¨
+    Glyph::new(1077, 10, 10, 2, 3, 14), // '\u{A9}' ©
+    Glyph::new(1097, 5, 7, 1, 3, 6), // '\u{AA}' ª
+    Glyph::new(1104, 6, 6, 1, 6, 8), // '\u{AB}' «
+    Glyph::new(1110, 8, 4, 2, 7, 12), // '\u{AC}' ¬
+    Glyph::new(1114, 3, 1, 1, 9, 5), // '\u{AD}'
+    Glyph::new(1115, 10, 10, 2, 3, 14), // '\u{AE}' ®
+    Glyph::new(1135, 3, 1, 2, 3, 7), // '\u{AF}' ¯
+    Glyph::new(1136, 4, 4, 1, 3, 7), // '\u{B0}' °
+    Glyph::new(1140, 9, 8, 1, 5, 12), // '\u{B1}' ±
+    Glyph::new(1156, 4, 6, 1, 3, 6), // '\u{B2}' ²
+    Glyph::new(1162, 4, 6, 1, 3, 6), // '\u{B3}' ³
+    Glyph::new(1168, 2, 2, 3, 2, 7), // '\u{B4}' ´
+    Glyph::new(1170, 7, 10, 1, 6, 9), // '\u{B5}' µ
+    Glyph::new(1180, 6, 11, 1, 3, 9), // '\u{B6}' ¶
+    Glyph::new(1191, 2, 2, 1, 7, 4), // '\u{B7}' ·
+    Glyph::new(1193, 3, 2, 2, 14, 7), // '\u{B8}' ¸
+    Glyph::new(1195, 3, 6, 1, 3, 6), // '\u{B9}' ¹
+    Glyph::new(1201, 5, 7, 1, 3, 6), // '\u{BA}' º
+    Glyph::new(1208, 6, 6, 1, 6, 8), // '\u{BB}' »
+    Glyph::new(1214, 12, 10, 1, 3, 13), // '\u{BC}' ¼
+    Glyph::new(1234, 11, 10, 1, 3, 13), // '\u{BD}' ½
+    Glyph::new(1254, 12, 10, 1, 3, 13), // '\u{BE}' ¾
+    Glyph::new(1274, 5, 10, 1, 6, 7), // '\u{BF}' ¿
+    Glyph::new(1284, 9, 12, 0, 1, 9), // '\u{C0}' À
+    Glyph::new(1308, 9, 13, 0, 0, 9), // '\u{C1}' Á
+    Glyph::new(1334, 9, 13, 0, 0, 9), // '\u{C2}' Â
+    Glyph::new(1360, 9, 13, 0, 0, 9), // '\u{C3}' Ã
+    Glyph::new(1386, 9, 13, 0, 0, 9), // '\u{C4}' Ä
+    Glyph::new(1412, 9, 13, 0, 0, 9), // '\u{C5}' Å
+    Glyph::new(1438, 13, 10, 0, 3, 13), // '\u{C6}' Æ
+    Glyph::new(1458, 8, 13, 1, 3, 10), // '\u{C7}' Ç
+    Glyph::new(1471, 7, 13, 1, 0, 9), // '\u{C8}' È
+    Glyph::new(1484, 7, 13, 1, 0, 9), // '\u{C9}' É
+    Glyph::new(1497, 7, 13, 1, 0, 9), // '\u{CA}' Ê
+    Glyph::new(1510, 7, 12, 1, 1, 9), // '\u{CB}' Ë
+    Glyph::new(1522, 2, 13, 1, 0, 4), // '\u{CC}' Ì
+    Glyph::new(1535, 2, 13, 1, 0, 4), // '\u{CD}' Í
+    Glyph::new(1548, 4, 13, 0, 0, 4), // '\u{CE}' Î
+    Glyph::new(1561, 4, 13, 0, 0, 4), // '\u{CF}' Ï
+    Glyph::new(1574, 10, 10, 0, 3, 11), // '\u{D0}' Ð
+    Glyph::new(1594, 8, 13, 1, 0, 10), // '\u{D1}' Ñ
+    Glyph::new(1607, 9, 13, 1, 0, 11), // '\u{D2}' Ò
+    Glyph::new(1633, 9, 13, 1, 0, 11), // '\u{D3}' Ó
+    Glyph::new(1659, 9, 13, 1, 0, 11), // '\u{D4}' Ô
+    Glyph::new(1685, 9, 13, 1, 0, 11), // '\u{D5}' Õ
+    Glyph::new(1711, 9, 13, 1, 0, 11), // '\u{D6}' Ö
+    Glyph::new(1737, 7, 7, 2, 5, 12), // '\u{D7}' ×
+    Glyph::new(1744, 9, 10, 1, 3, 11), // '\u{D8}' Ø
+    Glyph::new(1764, 8, 13, 1, 0, 10), // '\u{D9}' Ù
+    Glyph::new(1777, 8, 13, 1, 0, 10), // '\u{DA}' Ú
+    Glyph::new(1790, 8, 13, 1, 0, 10), // '\u{DB}' Û
+    Glyph::new(1803, 8, 13, 1, 0, 10), // '\u{DC}' Ü
+    Glyph::new(1816, 8, 13, 0, 0, 8), // '\u{DD}' Ý
+    Glyph::new(1829, 7, 10, 1, 3, 8), // '\u{DE}' Þ
+    Glyph::new(1839, 7, 10, 1, 3, 9), // '\u{DF}' ß
+    Glyph::new(1849, 6, 11, 1, 2, 8), // '\u{E0}' à
+    Glyph::new(1860, 6, 11, 1, 2, 8), // '\u{E1}' á
+    Glyph::new(1871, 6, 11, 1, 2, 8), // '\u{E2}' â
+    Glyph::new(1882, 6, 11, 1, 2, 8), // '\u{E3}' ã
+    Glyph::new(1893, 6, 10, 1, 3, 8), // '\u{E4}' ä
+    Glyph::new(1903, 6, 12, 1, 1, 8), // '\u{E5}' å
+    Glyph::new(1915, 12, 8, 1, 5, 13), // '\u{E6}' æ
+    Glyph::new(1931, 6, 11, 1, 5, 8), // '\u{E7}' ç
+    Glyph::new(1942, 7, 11, 1, 2, 8), // '\u{E8}' è
+    Glyph::new(1953, 7, 11, 1, 2, 8), // '\u{E9}' é
+    Glyph::new(1964, 7, 11, 1, 2, 8), // '\u{EA}' ê
+    Glyph::new(1975, 7, 10, 1, 3, 8), // '\u{EB}' ë
+    Glyph::new(1985, 3, 11, 0, 2, 4), // '\u{EC}' ì
+    Glyph::new(1996, 3, 11, 1, 2, 4), // '\u{ED}' í
+    Glyph::new(2007, 3, 11, 0, 2, 4), // '\u{EE}' î
+    Glyph::new(2018, 4, 10, 0, 3, 4), // '\u{EF}' ï
+    Glyph::new(2028, 7, 10, 1, 3, 8), // '\u{F0}' ð
+    Glyph::new(2038, 7, 11, 1, 2, 9), // '\u{F1}' ñ
+    Glyph::new(2049, 7, 11, 1, 2, 8), // '\u{F2}' ò
+    Glyph::new(2060, 7, 11, 1, 2, 8), // '\u{F3}' ó
+    Glyph::new(2071, 7, 11, 1, 2, 8), // '\u{F4}' ô
+    Glyph::new(2082, 7, 11, 1, 2, 8), // '\u{F5}' õ
+    Glyph::new(2093, 7, 10, 1, 3, 8), // '\u{F6}' ö
+    Glyph::new(2103, 8, 7, 2, 5, 12), // '\u{F7}' ÷
+    Glyph::new(2110, 7, 8, 1, 5, 8), // '\u{F8}' ø
+    Glyph::new(2118, 6, 11, 1, 2, 9), // '\u{F9}' ù
+    Glyph::new(2129, 6, 11, 1, 2, 9), // '\u{FA}' ú
+    Glyph::new(2140, 6, 11, 1, 2, 9), // '\u{FB}' û
+    Glyph::new(2151, 6, 10, 1, 3, 9), // '\u{FC}' ü
+    Glyph::new(2161, 6, 14, 1, 2, 8), // '\u{FD}' ý
+    Glyph::new(2175, 7, 13, 1, 3, 9), // '\u{FE}' þ
+    Glyph::new(2188, 6, 13, 1, 3, 8), // '\u{FF}' ÿ
+    Glyph::new(2201, 10, 5, 1, 6, 12), // '\u{2190}' ←
+    Glyph::new(2211, 5, 10, 3, 3, 12), // '\u{2191}' ↑
+    Glyph::new(2221, 10, 5, 1, 6, 12), // '\u{2192}' →
+    Glyph::new(2231, 5, 10, 3, 3, 12), // '\u{2193}' ↓
+    Glyph::new(2241, 10, 5, 1, 6, 12), // '\u{2194}' ↔
+    Glyph::new(2251, 5, 10, 3, 3, 12), // '\u{2195}' ↕
+    Glyph::new(2261, 8, 1, 0, 8, 8), // '\u{2500}' ─
+    Glyph::new(2262, 8, 2, 0, 7, 8), // '\u{2501}' ━
+    Glyph::new(2264, 1, 16, 4, 0, 8), // '\u{2502}' │
+    Glyph::new(2280, 2, 16, 3, 0, 8), // '\u{2503}' ┃
+    Glyph::new(2296, 8, 1, 0, 8, 8), // '\u{2504}' ┄
+    Glyph::new(2297, 8, 2, 0, 7, 8), // '\u{2505}' ┅
+    Glyph::new(2299, 1, 14, 4, 1, 8), // '\u{2506}' ┆
+    Glyph::new(2313, 2, 15, 3, 1, 8), // '\u{2507}' ┇
+    Glyph::new(2328, 8, 1, 0, 8, 8), // '\u{2508}' ┈
+    Glyph::new(2329, 8, 2, 0, 7, 8), // '\u{2509}' ┉
+    Glyph::new(2331, 1, 14, 4, 1, 8), // '\u{250A}' ┊
+    Glyph::new(2345, 2, 15, 3, 1, 8), // '\u{250B}' ┋
+    Glyph::new(2360, 4, 8, 4, 8, 8), // '\u{250C}' ┌
+    Glyph::new(2368, 4, 9, 4, 7, 8), // '\u{250D}' ┍
+    Glyph::new(2377, 5, 8, 3, 8, 8), // '\u{250E}' ┎
+    Glyph::new(2385, 5, 9, 3, 7, 8), // '\u{250F}' ┏
+    Glyph::new(2394, 5, 8, 0, 8, 8), // '\u{2510}' ┐
+    Glyph::new(2402, 5, 9, 0, 7, 8), // '\u{2511}' ┑
+    Glyph::new(2411, 5, 8, 0, 8, 8), // '\u{2512}' ┒
+    Glyph::new(2419, 5, 9, 0, 7, 8), // '\u{2513}' ┓
+    Glyph::new(2428, 4, 9, 4, 0, 8), // '\u{2514}' └
+    Glyph::new(2437, 4, 9, 4, 0, 8), // '\u{2515}' ┕
+    Glyph::new(2446, 5, 9, 3, 0, 8), // '\u{2516}' ┖
+    Glyph::new(2455, 5, 9, 3, 0, 8), // '\u{2517}' ┗
+    Glyph::new(2464, 5, 9, 0, 0, 8), // '\u{2518}' ┘
+    Glyph::new(2473, 5, 9, 0, 0, 8), // '\u{2519}' ┙
+    Glyph::new(2482, 5, 9, 0, 0, 8), // '\u{251A}' ┚
+    Glyph::new(2491, 5, 9, 0, 0, 8), // '\u{251B}' ┛
+    Glyph::new(2500, 4, 16, 4, 0, 8), // '\u{251C}' ├
+    Glyph::new(2516, 4, 16, 4, 0, 8), // '\u{251D}' ┝
+    Glyph::new(2532, 5, 16, 3, 0, 8), // '\u{251E}' ┞
+    Glyph::new(2548, 5, 16, 3, 0, 8), // '\u{251F}' ┟
+    Glyph::new(2564, 5, 16, 3, 0, 8), // '\u{2520}' ┠
+    Glyph::new(2580, 5, 16, 3, 0, 8), // '\u{2521}' ┡
+    Glyph::new(2596, 5, 16, 3, 0, 8), // '\u{2522}' ┢
+    Glyph::new(2612, 5, 16, 3, 0, 8), // '\u{2523}' ┣
+    Glyph::new(2628, 5, 16, 0, 0, 8), // '\u{2524}' ┤
+    Glyph::new(2644, 5, 16, 0, 0, 8), // '\u{2525}' ┥
+    Glyph::new(2660, 5, 16, 0, 0, 8), // '\u{2526}' ┦
+    Glyph::new(2676, 5, 16, 0, 0, 8), // '\u{2527}' ┧
+    Glyph::new(2692, 5, 16, 0, 0, 8), // '\u{2528}' ┨
+    Glyph::new(2708, 5, 16, 0, 0, 8), // '\u{2529}' ┩
+    Glyph::new(2724, 5, 16, 0, 0, 8), // '\u{252A}' ┪
+    Glyph::new(2740, 5, 16, 0, 0, 8), // '\u{252B}' ┫
+    Glyph::new(2756, 8, 8, 0, 8, 8), // '\u{252C}' ┬
+    Glyph::new(2764, 8, 9, 0, 7, 8), // '\u{252D}' ┭
+    Glyph::new(2773, 8, 9, 0, 7, 8), // '\u{252E}' ┮
+    Glyph::new(2782, 8, 9, 0, 7, 8), // '\u{252F}' ┯
+    Glyph::new(2791, 8, 8, 0, 8, 8), // '\u{2530}' ┰
+    Glyph::new(2799, 8, 9, 0, 7, 8), // '\u{2531}' ┱
+    Glyph::new(2808, 8, 9, 0, 7, 8), // '\u{2532}' ┲
+    Glyph::new(2817, 8, 9, 0, 7, 8), // '\u{2533}' ┳
+    Glyph::new(2826, 8, 9, 0, 0, 8), // '\u{2534}' ┴
+    Glyph::new(2835, 8, 9, 0, 0, 8), // '\u{2535}' ┵
+    Glyph::new(2844, 8, 9, 0, 0, 8), // '\u{2536}' ┶
+    Glyph::new(2853, 8, 9, 0, 0, 8), // '\u{2537}' ┷
+    Glyph::new(2862, 8, 9, 0, 0, 8), // '\u{2538}' ┸
+    Glyph::new(2871, 8, 9, 0, 0, 8), // '\u{2539}' ┹
+    Glyph::new(2880, 8, 9, 0, 0, 8), // '\u{253A}' ┺
+    Glyph::new(2889, 8, 9, 0, 0, 8), // '\u{253B}' ┻
+    Glyph::new(2898, 8, 16, 0, 0, 8), // '\u{253C}' ┼
+    Glyph::new(2914, 8, 16, 0, 0, 8), // '\u{253D}' ┽
+    Glyph::new(2930, 8, 16, 0, 0, 8), // '\u{253E}' ┾
+    Glyph::new(2946, 8, 16, 0, 0, 8), // '\u{253F}' ┿
+    Glyph::new(2962, 8, 16, 0, 0, 8), // '\u{2540}' ╀
+    Glyph::new(2978, 8, 16, 0, 0, 8), // '\u{2541}' ╁
+    Glyph::new(2994, 8, 16, 0, 0, 8), // '\u{2542}' ╂
+    Glyph::new(3010, 8, 16, 0, 0, 8), // '\u{2543}' ╃
+    Glyph::new(3026, 8, 16, 0, 0, 8), // '\u{2544}' ╄
+    Glyph::new(3042, 8, 16, 0, 0, 8), // '\u{2545}' ╅
+    Glyph::new(3058, 8, 16, 0, 0, 8), // '\u{2546}' ╆
+    Glyph::new(3074, 8, 16, 0, 0, 8), // '\u{2547}' ╇
+    Glyph::new(3090, 8, 16, 0, 0, 8), // '\u{2548}' ╈
+    Glyph::new(3106, 8, 16, 0, 0, 8), // '\u{2549}' ╉
+    Glyph::new(3122, 8, 16, 0, 0, 8), // '\u{254A}' ╊
+    Glyph::new(3138, 8, 16, 0, 0, 8), // '\u{254B}' ╋
+    Glyph::new(3154, 8, 1, 0, 8, 8), // '\u{254C}' ╌
+    Glyph::new(3155, 8, 2, 0, 7, 8), // '\u{254D}' ╍
+    Glyph::new(3157, 1, 13, 4, 2, 8), // '\u{254E}' ╎
+    Glyph::new(3170, 2, 14, 3, 1, 8), // '\u{254F}' ╏
+    Glyph::new(3184, 8, 3, 0, 7, 8), // '\u{2550}' ═
+    Glyph::new(3187, 3, 16, 3, 0, 8), // '\u{2551}' ║
+    Glyph::new(3203, 4, 9, 4, 7, 8), // '\u{2552}' ╒
+    Glyph::new(3212, 5, 8, 3, 8, 8), // '\u{2553}' ╓
+    Glyph::new(3220, 5, 9, 3, 7, 8), // '\u{2554}' ╔
+    Glyph::new(3229, 5, 9, 0, 7, 8), // '\u{2555}' ╕
+    Glyph::new(3238, 6, 8, 0, 8, 8), // '\u{2556}' ╖
+    Glyph::new(3246, 6, 9, 0, 7, 8), // '\u{2557}' ╗
+    Glyph::new(3255, 4, 10, 4, 0, 8), // '\u{2558}' ╘
+    Glyph::new(3265, 5, 9, 3, 0, 8), // '\u{2559}' ╙
+    Glyph::new(3274, 5, 10, 3, 0, 8), // '\u{255A}' ╚
+    Glyph::new(3284, 5, 10, 0, 0, 8), // '\u{255B}' ╛
+    Glyph::new(3294, 6, 9, 0, 0, 8), // '\u{255C}' ╜
+    Glyph::new(3303, 6, 10, 0, 0, 8), // '\u{255D}' ╝
+    Glyph::new(3313, 4, 16, 4, 0, 8), // '\u{255E}' ╞
+    Glyph::new(3329, 5, 16, 3, 0, 8), // '\u{255F}' ╟
+    Glyph::new(3345, 5, 16, 3, 0, 8), // '\u{2560}' ╠
+    Glyph::new(3361, 5, 16, 0, 0, 8), // '\u{2561}' ╡
+    Glyph::new(3377, 6, 16, 0, 0, 8), // '\u{2562}' ╢
+    Glyph::new(3393, 6, 16, 0, 0, 8), // '\u{2563}' ╣
+    Glyph::new(3409, 8, 9, 0, 7, 8), // '\u{2564}' ╤
+    Glyph::new(3418, 8, 8, 0, 8, 8), // '\u{2565}' ╥
+    Glyph::new(3426, 8, 9, 0, 7, 8), // '\u{2566}' ╦
+    Glyph::new(3435, 8, 10, 0, 0, 8), // '\u{2567}' ╧
+    Glyph::new(3445, 8, 9, 0, 0, 8), // '\u{2568}' ╨
+    Glyph::new(3454, 8, 10, 0, 0, 8), // '\u{2569}' ╩
+    Glyph::new(3464, 8, 16, 0, 0, 8), // '\u{256A}' ╪
+    Glyph::new(3480, 8, 16, 0, 0, 8), // '\u{256B}' ╫
+    Glyph::new(3496, 8, 16, 0, 0, 8), // '\u{256C}' ╬
+    Glyph::new(3512, 4, 8, 4, 8, 8), // '\u{256D}' ╭
+    Glyph::new(3520, 5, 8, 0, 8, 8), // '\u{256E}' ╮
+    Glyph::new(3528, 5, 9, 0, 0, 8), // '\u{256F}' ╯
+    Glyph::new(3537, 4, 9, 4, 0, 8), // '\u{2570}' ╰
+    Glyph::new(3546, 9, 16, 0, 0, 8), // '\u{2571}' ╱
+    Glyph::new(3578, 8, 16, 0, 0, 8), // '\u{2572}' ╲
+    Glyph::new(3594, 9, 16, 0, 0, 8), // '\u{2573}' ╳
+    Glyph::new(3626, 4, 1, 0, 8, 8), // '\u{2574}' ╴
+    Glyph::new(3627, 1, 8, 4, 0, 8), // '\u{2575}' ╵
+    Glyph::new(3635, 4, 1, 4, 8, 8), // '\u{2576}' ╶
+    Glyph::new(3636, 1, 7, 4, 9, 8), // '\u{2577}' ╷
+    Glyph::new(3643, 4, 2, 0, 7, 8), // '\u{2578}' ╸
+    Glyph::new(3645, 2, 8, 3, 0, 8), // '\u{2579}' ╹
+    Glyph::new(3653, 4, 2, 4, 7, 8), // '\u{257A}' ╺
+    Glyph::new(3655, 2, 8, 3, 8, 8), // '\u{257B}' ╻
+    Glyph::new(3663, 8, 2, 0, 7, 8), // '\u{257C}' ╼
+    Glyph::new(3665, 2, 16, 3, 0, 8), // '\u{257D}' ╽
+    Glyph::new(3681, 8, 2, 0, 7, 8), // '\u{257E}' ╾
+    Glyph::new(3683, 2, 16, 3, 0, 8), // '\u{257F}' ╿
+    Glyph::new(3699, 14, 13, 0, 1, 14), // '\u{FFFD}' �
+];
+
+/// Glyph rows, MSB leftmost, each row padded to a whole byte
+static BITMAP: [u8; 3725] = [
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x80, 0x80, 0x90, 0x90, 0x90, 0x10, 0x09, 0x00,
+    0x09, 0x00, 0x13, 0x00, 0x7F, 0x80, 0x12, 0x00, 0x12, 0x00, 0xFF, 0x80, 0x24, 0x00, 0x24, 0x00,
+    0x24, 0x00, 0x10, 0x7C, 0xD0, 0x90, 0xD0, 0x78, 0x14, 0x16, 0x94, 0x78, 0x10, 0x10, 0xF0, 0x80,
+    0x91, 0x00, 0x93, 0x00, 0x92, 0x00, 0xF4, 0x00, 0x05, 0xE0, 0x09, 0x20, 0x19, 0x20, 0x11, 0x20,
+    0x21, 0xE0, 0x3C, 0x00, 0x60, 0x00, 0x40, 0x00, 0x60, 0x00, 0x70, 0x00, 0xD9, 0x80, 0x8D, 0x00,
+    0x87, 0x00, 0xC7, 0x00, 0x7D, 0x80, 0x80, 0x80, 0x80, 0x20, 0x40, 0x40, 0xC0, 0xC0, 0x80, 0xC0,
+    0xC0, 0x40, 0x40, 0x60, 0x20, 0x40, 0x40, 0x60, 0x20, 0x20, 0x20, 0x20, 0x20, 0x60, 0x40, 0x40,
+    0x80, 0x20, 0xA8, 0x70, 0x70, 0x28, 0x20, 0x10, 0x10, 0x10, 0xFF, 0x10, 0x10, 0x10, 0x10, 0x40,
+    0x40, 0x80, 0x80, 0xE0, 0x40, 0xC0, 0x10, 0x10, 0x10, 0x30, 0x20, 0x20, 0x60, 0x40, 0x40, 0x40,
+    0x80, 0x78, 0x44, 0xC6, 0x86, 0x86, 0x86, 0x86, 0xC6, 0x44, 0x78, 0xE0, 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x20, 0x20, 0x20, 0xF8, 0xF8, 0x8C, 0x04, 0x04, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0xFC, 0xF8,
+    0x04, 0x04, 0x04, 0x38, 0x0C, 0x06, 0x06, 0x04, 0xF8, 0x1C, 0x1C, 0x2C, 0x2C, 0x4C, 0x8C, 0x8C,
+    0xFE, 0x0C, 0x0C, 0xFC, 0xC0, 0xC0, 0xF0, 0x7C, 0x04, 0x06, 0x04, 0x0C, 0xF8, 0x3C, 0x60, 0xC0,
+    0x98, 0xEC, 0xC6, 0xC6, 0xC6, 0x44, 0x7C, 0xFE, 0x04, 0x0C, 0x08, 0x08, 0x18, 0x10, 0x30, 0x30,
+    0x20, 0x7C, 0xC4, 0xC6, 0xC4, 0x78, 0x4C, 0x86, 0x86, 0xC6, 0x7C, 0x78, 0xC4, 0x84, 0x86, 0xC6,
+    0x7E, 0x36, 0x04, 0x0C, 0x78, 0x80, 0x80, 0x00, 0x00, 0x00, 0x80, 0x80, 0x40, 0x40, 0x00, 0x00,
+    0x00, 0x40, 0x40, 0x80, 0x80, 0x00, 0x80, 0x07, 0x00, 0x3C, 0x00, 0xE0, 0x00, 0x70, 0x00, 0x0E,
+    0x00, 0x01, 0x80, 0xFF, 0x00, 0xFF, 0xFF, 0x40, 0x00, 0x78, 0x00, 0x0E, 0x00, 0x01, 0x80, 0x07,
+    0x00, 0x3C, 0x00, 0xE0, 0x00, 0xF8, 0x88, 0x08, 0x18, 0x30, 0x20, 0x20, 0x00, 0x20, 0x20, 0x07,
+    0x00, 0x38, 0xC0, 0x60, 0x60, 0x44, 0x20, 0x89, 0x90, 0x90, 0x90, 0x90, 0x90, 0x91, 0xA0, 0x8F,
+    0xE0, 0x40, 0x00, 0x20, 0x00, 0x1F, 0x80, 0x0C, 0x00, 0x1C, 0x00, 0x14, 0x00, 0x12, 0x00, 0x32,
+    0x00, 0x23, 0x00, 0x7F, 0x00, 0x61, 0x00, 0x41, 0x80, 0xC0, 0x80, 0xFC, 0xC6, 0xC2, 0xC6, 0xFC,
+    0xC6, 0xC2, 0xC2, 0xC6, 0xFC, 0x3E, 0x61, 0xC0, 0x80, 0x80, 0x80, 0x80, 0xC0, 0x61, 0x3E, 0xFC,
+    0x00, 0xC3, 0x00, 0xC1, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x00, 0xC7,
+    0x00, 0xFC, 0x00, 0xFE, 0xC0, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0xFC, 0xC0, 0xC0,
+    0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x3F, 0x00, 0x61, 0x00, 0xC0, 0x00, 0x80, 0x00, 0x80,
+    0x00, 0x87, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x61, 0x80, 0x3F, 0x00, 0xC1, 0xC1, 0xC1, 0xC1, 0xFF,
+    0xC3, 0xC1, 0xC1, 0xC1, 0xC1, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x60, 0xC0, 0xC2, 0xC4, 0xC8, 0xF0,
+    0xE0, 0xF0, 0xD8, 0xCC, 0xC6, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE,
+    0xE1, 0xC0, 0xE1, 0xC0, 0xE1, 0xC0, 0xD2, 0xC0, 0xD2, 0xC0, 0xD6, 0xC0, 0xCC, 0xC0, 0xCC, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xE1, 0xF1, 0xD1, 0xD9, 0xC9, 0xCD, 0xC7, 0xC7, 0xC3, 0x3E, 0x00,
+    0x63, 0x00, 0xC1, 0x80, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00,
+    0x3E, 0x00, 0xFC, 0xC4, 0xC6, 0xC6, 0xC4, 0xF8, 0xC0, 0xC0, 0xC0, 0xC0, 0x3E, 0x00, 0x63, 0x00,
+    0xC1, 0x80, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00, 0x3E, 0x00,
+    0x06, 0x00, 0x03, 0x00, 0xFC, 0xC6, 0xC6, 0xC6, 0xCC, 0xFC, 0xC4, 0xC6, 0xC2, 0xC3, 0x7C, 0xC0,
+    0x80, 0xC0, 0x78, 0x1C, 0x06, 0x02, 0x86, 0xFC, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC3, 0xC3, 0x42, 0x3E, 0xC0, 0x80, 0x41, 0x80,
+    0x41, 0x00, 0x61, 0x00, 0x23, 0x00, 0x32, 0x00, 0x16, 0x00, 0x14, 0x00, 0x1C, 0x00, 0x0C, 0x00,
+    0x86, 0x10, 0x86, 0x30, 0x8E, 0x30, 0xCA, 0x20, 0x49, 0x20, 0x49, 0x60, 0x59, 0x60, 0x71, 0xC0,
+    0x30, 0xC0, 0x30, 0xC0, 0xC2, 0x46, 0x24, 0x38, 0x18, 0x38, 0x2C, 0x64, 0xC2, 0x83, 0xC1, 0x42,
+    0x26, 0x34, 0x18, 0x18, 0x08, 0x08, 0x08, 0x08, 0xFF, 0x02, 0x04, 0x0C, 0x18, 0x30, 0x20, 0x40,
+    0xC0, 0xFF, 0xE0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xC0, 0xE0, 0x80, 0xC0,
+    0x40, 0x40, 0x60, 0x20, 0x20, 0x20, 0x10, 0x10, 0x10, 0x60, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x20, 0x20, 0x20, 0xE0, 0x18, 0x2C, 0x46, 0x81, 0xFE, 0x80, 0xC0, 0x70, 0xCC, 0x04, 0x3C,
+    0xC4, 0x84, 0x8C, 0x74, 0x80, 0x80, 0x98, 0xEC, 0xC6, 0xC2, 0xC2, 0xC2, 0xC4, 0xFC, 0x38, 0x64,
+    0x80, 0x80, 0x80, 0x80, 0xC0, 0x7C, 0x04, 0x04, 0x34, 0x4C, 0x84, 0x84, 0x84, 0x84, 0xC4, 0x7C,
+    0x38, 0x6C, 0x86, 0xC6, 0xFC, 0x80, 0xC0, 0x7C, 0x70, 0x40, 0xE0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x30, 0x4C, 0x84, 0x84, 0x84, 0x84, 0xCC, 0x74, 0x04, 0x0C, 0x78, 0x80, 0x80, 0x98,
+    0xEC, 0xC4, 0xC6, 0x86, 0x86, 0x86, 0x86, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0x60, 0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x40, 0xC0, 0x80, 0x80,
+    0x84, 0x8C, 0x90, 0xE0, 0xE0, 0x90, 0x88, 0x84, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x18, 0xC0, 0xEF, 0x60, 0xC6, 0x20, 0xC4, 0x20, 0x84, 0x20, 0x84, 0x20, 0x84, 0x20,
+    0x84, 0x20, 0x18, 0xEC, 0xC4, 0xC6, 0x86, 0x86, 0x86, 0x86, 0x30, 0x6C, 0x84, 0x86, 0x86, 0x86,
+    0xC4, 0x78, 0x18, 0xEC, 0xC6, 0xC2, 0xC2, 0xC2, 0xC4, 0xFC, 0x80, 0x80, 0x80, 0x30, 0x4C, 0x84,
+    0x84, 0x84, 0x84, 0xC4, 0x7C, 0x04, 0x04, 0x04, 0x10, 0xE0, 0xC0, 0xC0, 0x80, 0x80, 0x80, 0x80,
+    0x70, 0xC8, 0x80, 0xC0, 0x78, 0x08, 0x08, 0xF8, 0xC0, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0x70, 0x84, 0x84, 0x84, 0x84, 0x84, 0xC4, 0x7C, 0x84, 0x84, 0x4C, 0x48, 0x68, 0x30, 0x30, 0x08,
+    0x00, 0x8C, 0x80, 0x9C, 0x80, 0xD4, 0x80, 0x55, 0x80, 0x73, 0x00, 0x63, 0x00, 0x63, 0x00, 0x84,
+    0xCC, 0x68, 0x30, 0x30, 0x78, 0x4C, 0x84, 0x84, 0xC4, 0x4C, 0x48, 0x78, 0x30, 0x30, 0x30, 0x20,
+    0xC0, 0xF8, 0x0C, 0x18, 0x30, 0x20, 0x40, 0x80, 0xFC, 0x38, 0x20, 0x20, 0x20, 0x20, 0xC0, 0x60,
+    0x20, 0x20, 0x20, 0x20, 0x38, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0xE0, 0x20, 0x20, 0x20, 0x20, 0x38, 0x30, 0x20, 0x20, 0x20, 0x20, 0xE0, 0xFB, 0x0E,
+    0x80, 0x00, 0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x18, 0x7C, 0xC0, 0xC0, 0x80, 0xC0, 0x40,
+    0x3C, 0x10, 0x3C, 0x20, 0x60, 0x60, 0x60, 0xF8, 0x60, 0x60, 0x60, 0xFE, 0x82, 0xFC, 0x44, 0x44,
+    0x44, 0x7C, 0x96, 0x82, 0xC4, 0x44, 0x68, 0xFE, 0x10, 0xFE, 0x10, 0x10, 0x10, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x00, 0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x78, 0x80, 0xC0, 0xE0, 0x98, 0x88, 0xC8,
+    0x30, 0x18, 0x18, 0xF0, 0xD0, 0x1E, 0x00, 0x41, 0x00, 0x1E, 0x80, 0xB0, 0x40, 0xA0, 0x40, 0xA0,
+    0x40, 0xA0, 0x40, 0x1E, 0x80, 0x41, 0x00, 0x1E, 0x00, 0xF0, 0x10, 0xF8, 0x98, 0xF8, 0x00, 0xF0,
+    0x04, 0x6C, 0xD8, 0xD8, 0x6C, 0x04, 0xFF, 0x01, 0x01, 0x01, 0xE0, 0x1E, 0x00, 0x41, 0x00, 0x1C,
+    0x80, 0x92, 0x40, 0x96, 0x40, 0x94, 0x40, 0x92, 0x40, 0x12, 0x80, 0x41, 0x00, 0x1E, 0x00, 0xE0,
+    0x70, 0x80, 0x80, 0x70, 0x08, 0x00, 0x08, 0x00, 0xFF, 0x80, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00,
+    0x00, 0x00, 0xFF, 0x80, 0xE0, 0x10, 0x20, 0x40, 0x80, 0xE0, 0xE0, 0x10, 0x60, 0x10, 0x30, 0xC0,
+    0x40, 0x80, 0x84, 0x84, 0x84, 0x84, 0x84, 0xC6, 0xFE, 0x80, 0x80, 0x80, 0x7C, 0xF4, 0xF4, 0xF4,
+    0x74, 0x34, 0x14, 0x14, 0x14, 0x14, 0x14, 0x40, 0xC0, 0x20, 0xC0, 0xC0, 0x40, 0x40, 0x40, 0x60,
+    0x60, 0xF0, 0x88, 0x88, 0x88, 0xF0, 0x00, 0xF0, 0x90, 0x48, 0x24, 0x24, 0x48, 0x90, 0xC0, 0x80,
+    0x41, 0x00, 0x41, 0x00, 0x42, 0x00, 0x64, 0x20, 0x64, 0x60, 0x08, 0xA0, 0x08, 0x20, 0x11, 0xF0,
+    0x20, 0x20, 0xC0, 0x80, 0x41, 0x00, 0x41, 0x00, 0x42, 0x00, 0x64, 0xC0, 0x64, 0x20, 0x08, 0x20,
+    0x08, 0x40, 0x10, 0x80, 0x21, 0xE0, 0xE0, 0x80, 0x11, 0x00, 0x61, 0x00, 0x12, 0x00, 0x34, 0x20,
+    0xC4, 0x60, 0x08, 0xA0, 0x08, 0x20, 0x11, 0xF0, 0x20, 0x20, 0x30, 0x00, 0x30, 0x30, 0x20, 0x60,
+    0xC0, 0x80, 0xC8, 0x30, 0x08, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x1C, 0x00, 0x14, 0x00, 0x12, 0x00,
+    0x32, 0x00, 0x23, 0x00, 0x7F, 0x00, 0x61, 0x00, 0x41, 0x80, 0xC0, 0x80, 0x04, 0x00, 0x08, 0x00,
+    0x00, 0x00, 0x0C, 0x00, 0x1C, 0x00, 0x14, 0x00, 0x12, 0x00, 0x32, 0x00, 0x23, 0x00, 0x7F, 0x00,
+    0x61, 0x00, 0x41, 0x80, 0xC0, 0x80, 0x08, 0x00, 0x14, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x1C, 0x00,
+    0x14, 0x00, 0x12, 0x00, 0x32, 0x00, 0x23, 0x00, 0x7F, 0x00, 0x61, 0x00, 0x41, 0x80, 0xC0, 0x80,
+    0x12, 0x00, 0x04, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x1C, 0x00, 0x14, 0x00, 0x12, 0x00, 0x32, 0x00,
+    0x23, 0x00, 0x7F, 0x00, 0x61, 0x00, 0x41, 0x80, 0xC0, 0x80, 0x10, 0x00, 0x16, 0x00, 0x00, 0x00,
+    0x0C, 0x00, 0x1C, 0x00, 0x14, 0x00, 0x12, 0x00, 0x32, 0x00, 0x23, 0x00, 0x7F, 0x00, 0x61, 0x00,
+    0x41, 0x80, 0xC0, 0x80, 0x0C, 0x00, 0x14, 0x00, 0x10, 0x00, 0x1C, 0x00, 0x1C, 0x00, 0x14, 0x00,
+    0x12, 0x00, 0x32, 0x00, 0x23, 0x00, 0x7F, 0x00, 0x61, 0x00, 0x41, 0x80, 0xC0, 0x80, 0x0F, 0xF0,
+    0x0A, 0x00, 0x1A, 0x00, 0x12, 0x00, 0x33, 0xF0, 0x23, 0x00, 0x3E, 0x00, 0x62, 0x00, 0x42, 0x00,
+    0xC3, 0xF8, 0x3E, 0x61, 0xC0, 0x80, 0x80, 0x80, 0x80, 0xC0, 0x61, 0x3E, 0x0C, 0x04, 0x18, 0x20,
+    0x10, 0x00, 0xFE, 0xC0, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0x08, 0x10, 0x00, 0xFE,
+    0xC0, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0x10, 0x28, 0x00, 0xFE, 0xC0, 0xC0, 0xC0,
+    0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0x28, 0x00, 0xFE, 0xC0, 0xC0, 0xC0, 0xFC, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xFE, 0x80, 0xC0, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40,
+    0xC0, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0x90, 0x00, 0x60,
+    0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x90, 0x90, 0x00, 0x60, 0x60, 0x60, 0x60,
+    0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x7E, 0x00, 0x61, 0x80, 0x60, 0x80, 0x60, 0xC0, 0xF8, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0x80, 0x61, 0x80, 0x7E, 0x00, 0x10, 0x2C, 0x00, 0xC1, 0xE1, 0xF1,
+    0xD1, 0xD9, 0xC9, 0xCD, 0xC7, 0xC7, 0xC3, 0x10, 0x00, 0x08, 0x00, 0x00, 0x00, 0x3E, 0x00, 0x63,
+    0x00, 0xC1, 0x80, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00, 0x3E,
+    0x00, 0x04, 0x00, 0x08, 0x00, 0x00, 0x00, 0x3E, 0x00, 0x63, 0x00, 0xC1, 0x80, 0x81, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00, 0x3E, 0x00, 0x08, 0x00, 0x14, 0x00, 0x00,
+    0x00, 0x3E, 0x00, 0x63, 0x00, 0xC1, 0x80, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x80, 0xC1,
+    0x80, 0x63, 0x00, 0x3E, 0x00, 0x10, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x3E, 0x00, 0x63, 0x00, 0xC1,
+    0x80, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00, 0x3E, 0x00, 0x04,
+    0x00, 0x14, 0x00, 0x00, 0x00, 0x3E, 0x00, 0x63, 0x00, 0xC1, 0x80, 0x81, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x81, 0x80, 0xC1, 0x80, 0x63, 0x00, 0x3E, 0x00, 0x82, 0x46, 0x2C, 0x18, 0x38, 0x64, 0xC2,
+    0x3E, 0x80, 0x63, 0x00, 0xC3, 0x80, 0x85, 0x80, 0x88, 0x80, 0x98, 0x80, 0x91, 0x80, 0xE1, 0x80,
+    0x63, 0x00, 0xBE, 0x00, 0x10, 0x18, 0x00, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC3, 0xC3, 0x42,
+    0x3E, 0x08, 0x18, 0x00, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC3, 0xC3, 0x42, 0x3E, 0x18, 0x24,
+    0x00, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC1, 0xC3, 0xC3, 0x42, 0x3E, 0x24, 0x24, 0x00, 0xC1, 0xC1,
+    0xC1, 0xC1, 0xC1, 0xC1, 0xC3, 0xC3, 0x42, 0x3E, 0x08, 0x08, 0x00, 0xC1, 0x42, 0x26, 0x34, 0x18,
+    0x18, 0x08, 0x08, 0x08, 0x08, 0xC0, 0xC0, 0xFC, 0xC6, 0xC6, 0xC6, 0xCC, 0xF8, 0xC0, 0xC0, 0x7C,
+    0xC4, 0xCC, 0xD0, 0xD0, 0xD8, 0xC6, 0xC2, 0xC2, 0xFC, 0x40, 0x20, 0x00, 0x70, 0xCC, 0x04, 0x3C,
+    0xC4, 0x84, 0x8C, 0x74, 0x08, 0x10, 0x00, 0x70, 0xCC, 0x04, 0x3C, 0xC4, 0x84, 0x8C, 0x74, 0x30,
+    0x30, 0x00, 0x70, 0xCC, 0x04, 0x3C, 0xC4, 0x84, 0x8C, 0x74, 0x20, 0x58, 0x00, 0x70, 0xCC, 0x04,
+    0x3C, 0xC4, 0x84, 0x8C, 0x74, 0x48, 0x00, 0x70, 0xCC, 0x04, 0x3C, 0xC4, 0x84, 0x8C, 0x74, 0x30,
+    0x48, 0x48, 0x30, 0x70, 0xCC, 0x04, 0x3C, 0xC4, 0x84, 0x8C, 0x74, 0x71, 0xC0, 0xCF, 0x60, 0x04,
+    0x30, 0x3E, 0x30, 0xC7, 0xE0, 0x84, 0x00, 0x8E, 0x00, 0x7B, 0xE0, 0x38, 0x64, 0x80, 0x80, 0x80,
+    0x80, 0xC0, 0x7C, 0x10, 0x08, 0x30, 0x20, 0x30, 0x00, 0x38, 0x6C, 0x86, 0xC6, 0xFC, 0x80, 0xC0,
+    0x7C, 0x08, 0x10, 0x00, 0x38, 0x6C, 0x86, 0xC6, 0xFC, 0x80, 0xC0, 0x7C, 0x10, 0x28, 0x00, 0x38,
+    0x6C, 0x86, 0xC6, 0xFC, 0x80, 0xC0, 0x7C, 0x68, 0x00, 0x38, 0x6C, 0x86, 0xC6, 0xFC, 0x80, 0xC0,
+    0x7C, 0x80, 0x40, 0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x40, 0x00, 0x00,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0xA0, 0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60,
+    0x60, 0x60, 0x90, 0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x3C, 0x70, 0x18, 0x7C,
+    0xC4, 0x86, 0x86, 0x86, 0xC4, 0x78, 0x20, 0x58, 0x00, 0x18, 0xEC, 0xC4, 0xC6, 0x86, 0x86, 0x86,
+    0x86, 0x60, 0x20, 0x00, 0x30, 0x6C, 0x84, 0x86, 0x86, 0x86, 0xC4, 0x78, 0x08, 0x10, 0x00, 0x30,
+    0x6C, 0x84, 0x86, 0x86, 0x86, 0xC4, 0x78, 0x30, 0x28, 0x00, 0x30, 0x6C, 0x84, 0x86, 0x86, 0x86,
+    0xC4, 0x78, 0x20, 0x58, 0x00, 0x30, 0x6C, 0x84, 0x86, 0x86, 0x86, 0xC4, 0x78, 0x68, 0x00, 0x30,
+    0x6C, 0x84, 0x86, 0x86, 0x86, 0xC4, 0x78, 0x10, 0x18, 0x00, 0xFF, 0x00, 0x10, 0x18, 0x32, 0x6C,
+    0x8C, 0x96, 0xB6, 0xE6, 0xC4, 0xF8, 0x60, 0x20, 0x00, 0x00, 0x84, 0x84, 0x84, 0x84, 0x84, 0xC4,
+    0x7C, 0x08, 0x10, 0x00, 0x00, 0x84, 0x84, 0x84, 0x84, 0x84, 0xC4, 0x7C, 0x30, 0x28, 0x00, 0x00,
+    0x84, 0x84, 0x84, 0x84, 0x84, 0xC4, 0x7C, 0x68, 0x00, 0x00, 0x84, 0x84, 0x84, 0x84, 0x84, 0xC4,
+    0x7C, 0x08, 0x10, 0x00, 0x00, 0x84, 0xC4, 0x4C, 0x48, 0x78, 0x30, 0x30, 0x30, 0x20, 0xC0, 0x80,
+    0x80, 0x98, 0xEC, 0xC6, 0xC2, 0xC2, 0xC2, 0xC4, 0xFC, 0x80, 0x80, 0x80, 0x48, 0x00, 0x00, 0x84,
+    0xC4, 0x4C, 0x48, 0x78, 0x30, 0x30, 0x30, 0x20, 0xC0, 0x20, 0x00, 0x40, 0x00, 0xFF, 0xC0, 0xC0,
+    0x00, 0x60, 0x00, 0x30, 0x78, 0xA8, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x01, 0x00, 0x01,
+    0x80, 0xFF, 0xC0, 0x01, 0x80, 0x01, 0x00, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0xE8, 0x78,
+    0x30, 0x21, 0x00, 0x41, 0x80, 0xFF, 0xC0, 0xC1, 0x80, 0x61, 0x00, 0x30, 0x78, 0xA8, 0x20, 0x20,
+    0x20, 0x20, 0xA8, 0x78, 0x30, 0xFF, 0xFF, 0xFF, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xDB, 0xDB, 0xDB, 0x80, 0x80, 0x80, 0x80, 0x00,
+    0x80, 0x80, 0x80, 0x80, 0x00, 0x00, 0x80, 0x80, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xD5, 0xD5, 0xF5, 0x80, 0x80, 0x00, 0x00, 0x80,
+    0x80, 0x00, 0x00, 0x80, 0x80, 0x00, 0x00, 0x80, 0x80, 0xC0, 0xC0, 0xC0, 0x00, 0xC0, 0xC0, 0xC0,
+    0x00, 0xC0, 0xC0, 0xC0, 0x00, 0xC0, 0xC0, 0xC0, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0xF0, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF8, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xF8, 0xF8, 0xE0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0xF8, 0xF8, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0xF8, 0xF8, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x80, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0xF0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0xF8,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xF8, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0xF8, 0xF8, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0xF8, 0xF8, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0xF0, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0x40, 0x40, 0x40,
+    0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0xC0, 0xF8, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0xF8, 0xC0, 0x40, 0x40,
+    0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0xF8, 0xF8, 0xE0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF8, 0xF8, 0xE0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xF8, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xF8, 0x18, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xF8, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0xF8, 0x18, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xF8, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0xF8, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xFF, 0x18, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x1F, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0xFF,
+    0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8,
+    0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1F, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0xFF, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x18, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xFF, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x1F, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0xFF, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0xFF,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1F, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0xFF, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1F, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1F, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xF8, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1F, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, 0x18, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF8, 0xFF, 0x18, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1F, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, 0x1C, 0x18, 0x18, 0x18, 0x18,
+    0x18, 0x18, 0xF7, 0xF7, 0xF7, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00, 0x00, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xFF, 0x00, 0xFF, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0,
+    0xA0, 0xA0, 0xA0, 0xF0, 0x80, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF8, 0xA0, 0xA0, 0xA0,
+    0xA0, 0xA0, 0xA0, 0xA0, 0xF8, 0x80, 0xB8, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xF8, 0x08, 0xF8,
+    0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFC, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0xFC, 0x04,
+    0xF4, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0x80,
+    0xF0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xE0, 0xF8, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0,
+    0xA0, 0xB8, 0x80, 0xF8, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xF8, 0x08, 0xF8, 0x14, 0x14,
+    0x14, 0x14, 0x14, 0x14, 0x14, 0x3C, 0xFC, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x34, 0xE4, 0x04,
+    0xFC, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0x80, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xB8, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0,
+    0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0, 0xB8, 0x80, 0xB8, 0xA0, 0xA0, 0xA0, 0xA0, 0xA0,
+    0xA0, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xF8, 0x08, 0xF8, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x34, 0xF4, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14,
+    0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x34, 0xE4, 0x04, 0xF4, 0x14, 0x14, 0x14, 0x14, 0x14,
+    0x14, 0xFF, 0x00, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0xFF, 0x14, 0x14, 0x14, 0x14, 0x14,
+    0x14, 0x14, 0xFF, 0x00, 0xF7, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x08, 0x08, 0x08, 0x08, 0x08,
+    0x08, 0x18, 0xFF, 0x00, 0xFF, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x3C, 0xFF, 0x14, 0x14,
+    0x14, 0x14, 0x14, 0x14, 0x34, 0xE7, 0x00, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0xFF,
+    0x08, 0xFF, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x3C,
+    0xFF, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0x34, 0xE7,
+    0x00, 0xF7, 0x14, 0x14, 0x14, 0x14, 0x14, 0x14, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0xF0, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x10,
+    0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x70, 0x00, 0x80, 0x01, 0x00, 0x01, 0x00,
+    0x02, 0x00, 0x06, 0x00, 0x04, 0x00, 0x0C, 0x00, 0x08, 0x00, 0x18, 0x00, 0x10, 0x00, 0x30, 0x00,
+    0x20, 0x00, 0x60, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x80, 0x00, 0x80, 0x80, 0x40, 0x40, 0x20, 0x20,
+    0x10, 0x10, 0x08, 0x08, 0x04, 0x04, 0x02, 0x02, 0x01, 0x01, 0x80, 0x80, 0x81, 0x00, 0x41, 0x00,
+    0x42, 0x00, 0x26, 0x00, 0x24, 0x00, 0x1C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00, 0x34, 0x00,
+    0x24, 0x00, 0x62, 0x00, 0x42, 0x00, 0xC1, 0x00, 0x81, 0x00, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0xF0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0xF0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF0, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x0F,
+    0xFF, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xF0, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0x40, 0x40, 0x40,
+    0x40, 0x40, 0x40, 0x03, 0x00, 0x07, 0x80, 0x08, 0x40, 0x1F, 0x20, 0x3F, 0xB0, 0x7F, 0x38, 0xFE,
+    0x7C, 0x7E, 0xF8, 0x3C, 0xF0, 0x1F, 0xE0, 0x0E, 0xC0, 0x04, 0x80, 0x03, 0x00,
+];
+
+/// Kerning pairs, sorted by (left, right)
+static KERNING: [KernPair; 454] = [
+    KernPair::new('-', 'G', 1),
+    KernPair::new('-', 'J', 1),
+    KernPair::new('-', 'Q', 1),
+    KernPair::new('-', 'T', -1),
+    KernPair::new('-', 'V', -1),
+    KernPair::new('-', 'W', -1),
+    KernPair::new('-', 'X', -1),
+    KernPair::new('-', 'Y', -2),
+    KernPair::new('-', '\u{DD}', -2),
+    KernPair::new('A', 'T', -1),
+    KernPair::new('A', 'V', -1),
+    KernPair::new('A', 'W', -1),
+    KernPair::new('A', 'Y', -1),
+    KernPair::new('A', 'v', -1),
+    KernPair::new('A', 'w', -1),
+    KernPair::new('A', 'y', -1),
+    KernPair::new('A', '\u{DD}', -1),
+    KernPair::new('A', '\u{FD}', -1),
+    KernPair::new('A', '\u{FF}', -1),
+    KernPair::new('B', 'Y', -1),
+    KernPair::new('B', '\u{DD}', -1),
+    KernPair::new('D', 'Y', -1),
+    KernPair::new('D', '\u{DD}', -1),
+    KernPair::new('F', '.', -2),
+    KernPair::new('F', ':', -1),
+    KernPair::new('F', 'A', -1),
+    KernPair::new('F', 'a', -1),
+    KernPair::new('F', 'e', -1),
+    KernPair::new('F', 'i', -1),
+    KernPair::new('F', 'r', -1),
+    KernPair::new('F', 'u', -1),
+    KernPair::new('F', 'y', -1),
+    KernPair::new('F', '\u{C0}', -1),
+    KernPair::new('F', '\u{C1}', -1),
+    KernPair::new('F', '\u{C2}', -1),
+    KernPair::new('F', '\u{C3}', -1),
+    KernPair::new('F', '\u{C4}', -1),
+    KernPair::new('F', '\u{E0}', -1),
+    KernPair::new('F', '\u{E1}', -1),
+    KernPair::new('F', '\u{E2}', -1),
+    KernPair::new('F', '\u{E3}', -1),
+    KernPair::new('F', '\u{E4}', -1),
+    KernPair::new('F', '\u{E5}', -1),
+    KernPair::new('F', '\u{E8}', -1),
+    KernPair::new('F', '\u{E9}', -1),
+    KernPair::new('F', '\u{EA}', -1),
+    KernPair::new('F', '\u{EB}', -1),
+    KernPair::new('F', '\u{F9}', -1),
+    KernPair::new('F', '\u{FA}', -1),
+    KernPair::new('F', '\u{FB}', -1),
+    KernPair::new('F', '\u{FC}', -1),
+    KernPair::new('F', '\u{FD}', -1),
+    KernPair::new('F', '\u{FF}', -1),
+    KernPair::new('G', 'Y', -1),
+    KernPair::new('G', '\u{DD}', -1),
+    KernPair::new('K', '-', -1),
+    KernPair::new('K', 'C', -1),
+    KernPair::new('K', 'O', -1),
+    KernPair::new('K', 'T', -1),
+    KernPair::new('K', 'e', -1),
+    KernPair::new('K', 'o', -1),
+    KernPair::new('K', 'u', -1),
+    KernPair::new('K', 'y', -1),
+    KernPair::new('K', '\u{AB}', -1),
+    KernPair::new('K', '\u{C7}', -1),
+    KernPair::new('K', '\u{D2}', -1),
+    KernPair::new('K', '\u{D3}', -1),
+    KernPair::new('K', '\u{D4}', -1),
+    KernPair::new('K', '\u{D5}', -1),
+    KernPair::new('K', '\u{D6}', -1),
+    KernPair::new('K', '\u{E8}', -1),
+    KernPair::new('K', '\u{E9}', -1),
+    KernPair::new('K', '\u{EA}', -1),
+    KernPair::new('K', '\u{EB}', -1),
+    KernPair::new('K', '\u{F2}', -1),
+    KernPair::new('K', '\u{F3}', -1),
+    KernPair::new('K', '\u{F4}', -1),
+    KernPair::new('K', '\u{F5}', -1),
+    KernPair::new('K', '\u{F6}', -1),
+    KernPair::new('K', '\u{F9}', -1),
+    KernPair::new('K', '\u{FA}', -1),
+    KernPair::new('K', '\u{FB}', -1),
+    KernPair::new('K', '\u{FC}', -1),
+    KernPair::new('K', '\u{FD}', -1),
+    KernPair::new('K', '\u{FF}', -1),
+    KernPair::new('L', 'T', -2),
+    KernPair::new('L', 'U', -1),
+    KernPair::new('L', 'V', -2),
+    KernPair::new('L', 'W', -1),
+    KernPair::new('L', 'Y', -2),
+    KernPair::new('L', 'y', -1),
+    KernPair::new('L', '\u{D9}', -1),
+    KernPair::new('L', '\u{DA}', -1),
+    KernPair::new('L', '\u{DB}', -1),
+    KernPair::new('L', '\u{DC}', -1),
+    KernPair::new('L', '\u{DD}', -2),
+    KernPair::new('L', '\u{FD}', -1),
+    KernPair::new('L', '\u{FF}', -1),
+    KernPair::new('O', '.', -1),
+    KernPair::new('O', 'X', -1),
+    KernPair::new('O', 'Y', -1),
+    KernPair::new('O', '\u{DD}', -1),
+    KernPair::new('P', '.', -2),
+    KernPair::new('P', 'A', -1),
+    KernPair::new('P', 'a', -1),
+    KernPair::new('P', '\u{C0}', -1),
+    KernPair::new('P', '\u{C1}', -1),
+    KernPair::new('P', '\u{C2}', -1),
+    KernPair::new('P', '\u{C3}', -1),
+    KernPair::new('P', '\u{C4}', -1),
+    KernPair::new('P', '\u{E0}', -1),
+    KernPair::new('P', '\u{E1}', -1),
+    KernPair::new('P', '\u{E2}', -1),
+    KernPair::new('P', '\u{E3}', -1),
+    KernPair::new('P', '\u{E4}', -1),
+    KernPair::new('P', '\u{E5}', -1),
+    KernPair::new('R', '-', -1),
+    KernPair::new('R', 'A', -1),
+    KernPair::new('R', 'C', -1),
+    KernPair::new('R', 'T', -1),
+    KernPair::new('R', 'V', -1),
+    KernPair::new('R', 'W', -1),
+    KernPair::new('R', 'Y', -1),
+    KernPair::new('R', 'e', -1),
+    KernPair::new('R', 'o', -1),
+    KernPair::new('R', 'u', -1),
+    KernPair::new('R', 'y', -1),
+    KernPair::new('R', '\u{AB}', -1),
+    KernPair::new('R', '\u{C0}', -1),
+    KernPair::new('R', '\u{C1}', -1),
+    KernPair::new('R', '\u{C2}', -1),
+    KernPair::new('R', '\u{C3}', -1),
+    KernPair::new('R', '\u{C4}', -1),
+    KernPair::new('R', '\u{C7}', -1),
+    KernPair::new('R', '\u{DD}', -1),
+    KernPair::new('R', '\u{E8}', -1),
+    KernPair::new('R', '\u{E9}', -1),
+    KernPair::new('R', '\u{EA}', -1),
+    KernPair::new('R', '\u{EB}', -1),
+    KernPair::new('R', '\u{F2}', -1),
+    KernPair::new('R', '\u{F3}', -1),
+    KernPair::new('R', '\u{F4}', -1),
+    KernPair::new('R', '\u{F5}', -1),
+    KernPair::new('R', '\u{F6}', -1),
+    KernPair::new('R', '\u{F9}', -1),
+    KernPair::new('R', '\u{FA}', -1),
+    KernPair::new('R', '\u{FB}', -1),
+    KernPair::new('R', '\u{FC}', -1),
+    KernPair::new('R', '\u{FD}', -1),
+    KernPair::new('R', '\u{FF}', -1),
+    KernPair::new('T', '-', -1),
+    KernPair::new('T', '.', -2),
+    KernPair::new('T', ':', -2),
+    KernPair::new('T', 'A', -1),
+    KernPair::new('T', 'C', -1),
+    KernPair::new('T', 'a', -2),
+    KernPair::new('T', 'c', -2),
+    KernPair::new('T', 'e', -2),
+    KernPair::new('T', 'o', -2),
+    KernPair::new('T', 'r', -2),
+    KernPair::new('T', 's', -2),
+    KernPair::new('T', 'u', -2),
+    KernPair::new('T', 'w', -2),
+    KernPair::new('T', 'y', -2),
+    KernPair::new('T', '\u{AB}', -1),
+    KernPair::new('T', '\u{BB}', -1),
+    KernPair::new('T', '\u{C0}', -1),
+    KernPair::new('T', '\u{C1}', -1),
+    KernPair::new('T', '\u{C2}', -1),
+    KernPair::new('T', '\u{C3}', -1),
+    KernPair::new('T', '\u{C4}', -1),
+    KernPair::new('T', '\u{C7}', -1),
+    KernPair::new('T', '\u{E0}', -2),
+    KernPair::new('T', '\u{E1}', -2),
+    KernPair::new('T', '\u{E2}', -2),
+    KernPair::new('T', '\u{E3}', -2),
+    KernPair::new('T', '\u{E4}', -2),
+    KernPair::new('T', '\u{E5}', -2),
+    KernPair::new('T', '\u{E7}', -2),
+    KernPair::new('T', '\u{E8}', -2),
+    KernPair::new('T', '\u{E9}', -2),
+    KernPair::new('T', '\u{EA}', -2),
+    KernPair::new('T', '\u{EB}', -2),
+    KernPair::new('T', '\u{F2}', -2),
+    KernPair::new('T', '\u{F3}', -2),
+    KernPair::new('T', '\u{F4}', -2),
+    KernPair::new('T', '\u{F5}', -2),
+    KernPair::new('T', '\u{F6}', -2),
+    KernPair::new('T', '\u{F9}', -2),
+    KernPair::new('T', '\u{FA}', -2),
+    KernPair::new('T', '\u{FB}', -2),
+    KernPair::new('T', '\u{FC}', -2),
+    KernPair::new('T', '\u{FD}', -2),
+    KernPair::new('T', '\u{FF}', -2),
+    KernPair::new('V', '-', -1),
+    KernPair::new('V', '.', -2),
+    KernPair::new('V', ':', -1),
+    KernPair::new('V', 'A', -1),
+    KernPair::new('V', 'a', -1),
+    KernPair::new('V', 'e', -1),
+    KernPair::new('V', 'o', -1),
+    KernPair::new('V', 'u', -1),
+    KernPair::new('V', '\u{AB}', -1),
+    KernPair::new('V', '\u{BB}', -1),
+    KernPair::new('V', '\u{C0}', -1),
+    KernPair::new('V', '\u{C1}', -1),
+    KernPair::new('V', '\u{C2}', -1),
+    KernPair::new('V', '\u{C3}', -1),
+    KernPair::new('V', '\u{C4}', -1),
+    KernPair::new('V', '\u{E0}', -1),
+    KernPair::new('V', '\u{E1}', -1),
+    KernPair::new('V', '\u{E2}', -1),
+    KernPair::new('V', '\u{E3}', -1),
+    KernPair::new('V', '\u{E4}', -1),
+    KernPair::new('V', '\u{E5}', -1),
+    KernPair::new('V', '\u{E8}', -1),
+    KernPair::new('V', '\u{E9}', -1),
+    KernPair::new('V', '\u{EA}', -1),
+    KernPair::new('V', '\u{EB}', -1),
+    KernPair::new('V', '\u{F2}', -1),
+    KernPair::new('V', '\u{F3}', -1),
+    KernPair::new('V', '\u{F4}', -1),
+    KernPair::new('V', '\u{F5}', -1),
+    KernPair::new('V', '\u{F6}', -1),
+    KernPair::new('V', '\u{F9}', -1),
+    KernPair::new('V', '\u{FA}', -1),
+    KernPair::new('V', '\u{FB}', -1),
+    KernPair::new('V', '\u{FC}', -1),
+    KernPair::new('W', '-', -1),
+    KernPair::new('W', '.', -2),
+    KernPair::new('W', ':', -1),
+    KernPair::new('W', 'A', -1),
+    KernPair::new('W', 'a', -1),
+    KernPair::new('W', 'e', -1),
+    KernPair::new('W', 'o', -1),
+    KernPair::new('W', 'r', -1),
+    KernPair::new('W', '\u{AB}', -1),
+    KernPair::new('W', '\u{C0}', -1),
+    KernPair::new('W', '\u{C1}', -1),
+    KernPair::new('W', '\u{C2}', -1),
+    KernPair::new('W', '\u{C3}', -1),
+    KernPair::new('W', '\u{C4}', -1),
+    KernPair::new('W', '\u{E0}', -1),
+    KernPair::new('W', '\u{E1}', -1),
+    KernPair::new('W', '\u{E2}', -1),
+    KernPair::new('W', '\u{E3}', -1),
+    KernPair::new('W', '\u{E4}', -1),
+    KernPair::new('W', '\u{E5}', -1),
+    KernPair::new('W', '\u{E8}', -1),
+    KernPair::new('W', '\u{E9}', -1),
+    KernPair::new('W', '\u{EA}', -1),
+    KernPair::new('W', '\u{EB}', -1),
+    KernPair::new('W', '\u{F2}', -1),
+    KernPair::new('W', '\u{F3}', -1),
+    KernPair::new('W', '\u{F4}', -1),
+    KernPair::new('W', '\u{F5}', -1),
+    KernPair::new('W', '\u{F6}', -1),
+    KernPair::new('X', '-', -1),
+    KernPair::new('X', 'C', -1),
+    KernPair::new('X', 'O', -1),
+    KernPair::new('X', 'e', -1),
+    KernPair::new('X', '\u{AB}', -1),
+    KernPair::new('X', '\u{C7}', -1),
+    KernPair::new('X', '\u{D2}', -1),
+    KernPair::new('X', '\u{D3}', -1),
+    KernPair::new('X', '\u{D4}', -1),
+    KernPair::new('X', '\u{D5}', -1),
+    KernPair::new('X', '\u{D6}', -1),
+    KernPair::new('X', '\u{E8}', -1),
+    KernPair::new('X', '\u{E9}', -1),
+    KernPair::new('X', '\u{EA}', -1),
+    KernPair::new('X', '\u{EB}', -1),
+    KernPair::new('Y', '-', -2),
+    KernPair::new('Y', '.', -3),
+    KernPair::new('Y', ':', -2),
+    KernPair::new('Y', 'A', -1),
+    KernPair::new('Y', 'C', -1),
+    KernPair::new('Y', 'O', -1),
+    KernPair::new('Y', 'a', -2),
+    KernPair::new('Y', 'e', -2),
+    KernPair::new('Y', 'o', -2),
+    KernPair::new('Y', 'u', -2),
+    KernPair::new('Y', '\u{AB}', -2),
+    KernPair::new('Y', '\u{BB}', -1),
+    KernPair::new('Y', '\u{C0}', -1),
+    KernPair::new('Y', '\u{C1}', -1),
+    KernPair::new('Y', '\u{C2}', -1),
+    KernPair::new('Y', '\u{C3}', -1),
+    KernPair::new('Y', '\u{C4}', -1),
+    KernPair::new('Y', '\u{C7}', -1),
+    KernPair::new('Y', '\u{D2}', -1),
+    KernPair::new('Y', '\u{D3}', -1),
+    KernPair::new('Y', '\u{D4}', -1),
+    KernPair::new('Y', '\u{D5}', -1),
+    KernPair::new('Y', '\u{D6}', -1),
+    KernPair::new('Y', '\u{E0}', -2),
+    KernPair::new('Y', '\u{E1}', -2),
+    KernPair::new('Y', '\u{E2}', -2),
+    KernPair::new('Y', '\u{E3}', -2),
+    KernPair::new('Y', '\u{E4}', -2),
+    KernPair::new('Y', '\u{E5}', -2),
+    KernPair::new('Y', '\u{E8}', -2),
+    KernPair::new('Y', '\u{E9}', -2),
+    KernPair::new('Y', '\u{EA}', -2),
+    KernPair::new('Y', '\u{EB}', -2),
+    KernPair::new('Y', '\u{F2}', -2),
+    KernPair::new('Y', '\u{F3}', -2),
+    KernPair::new('Y', '\u{F4}', -2),
+    KernPair::new('Y', '\u{F5}', -2),
+    KernPair::new('Y', '\u{F6}', -2),
+    KernPair::new('Y', '\u{F9}', -2),
+    KernPair::new('Y', '\u{FA}', -2),
+    KernPair::new('Y', '\u{FB}', -2),
+    KernPair::new('Y', '\u{FC}', -2),
+    KernPair::new('f', '-', -1),
+    KernPair::new('f', '.', -1),
+    KernPair::new('r', '-', -1),
+    KernPair::new('r', '.', -1),
+    KernPair::new('v', '.', -1),
+    KernPair::new('v', ':', -1),
+    KernPair::new('w', '.', -1),
+    KernPair::new('w', ':', -1),
+    KernPair::new('y', '.', -2),
+    KernPair::new('y', ':', -1),
+    KernPair::new('\u{AB}', 'T', -1),
+    KernPair::new('\u{AB}', 'V', -1),
+    KernPair::new('\u{AB}', 'Y', -1),
+    KernPair::new('\u{AB}', '\u{C6}', 1),
+    KernPair::new('\u{AB}', '\u{DD}', -1),
+    KernPair::new('\u{BB}', 'T', -1),
+    KernPair::new('\u{BB}', 'V', -1),
+    KernPair::new('\u{BB}', 'W', -1),
+    KernPair::new('\u{BB}', 'X', -1),
+    KernPair::new('\u{BB}', 'Y', -2),
+    KernPair::new('\u{BB}', '\u{DD}', -2),
+    KernPair::new('\u{C0}', 'T', -1),
+    KernPair::new('\u{C0}', 'V', -1),
+    KernPair::new('\u{C0}', 'W', -1),
+    KernPair::new('\u{C0}', 'Y', -1),
+    KernPair::new('\u{C0}', 'v', -1),
+    KernPair::new('\u{C0}', 'w', -1),
+    KernPair::new('\u{C0}', 'y', -1),
+    KernPair::new('\u{C0}', '\u{DD}', -1),
+    KernPair::new('\u{C0}', '\u{FD}', -1),
+    KernPair::new('\u{C0}', '\u{FF}', -1),
+    KernPair::new('\u{C1}', 'T', -1),
+    KernPair::new('\u{C1}', 'V', -1),
+    KernPair::new('\u{C1}', 'W', -1),
+    KernPair::new('\u{C1}', 'Y', -1),
+    KernPair::new('\u{C1}', 'v', -1),
+    KernPair::new('\u{C1}', 'w', -1),
+    KernPair::new('\u{C1}', 'y', -1),
+    KernPair::new('\u{C1}', '\u{DD}', -1),
+    KernPair::new('\u{C1}', '\u{FD}', -1),
+    KernPair::new('\u{C1}', '\u{FF}', -1),
+    KernPair::new('\u{C2}', 'T', -1),
+    KernPair::new('\u{C2}', 'V', -1),
+    KernPair::new('\u{C2}', 'W', -1),
+    KernPair::new('\u{C2}', 'Y', -1),
+    KernPair::new('\u{C2}', 'v', -1),
+    KernPair::new('\u{C2}', 'w', -1),
+    KernPair::new('\u{C2}', 'y', -1),
+    KernPair::new('\u{C2}', '\u{DD}', -1),
+    KernPair::new('\u{C2}', '\u{FD}', -1),
+    KernPair::new('\u{C2}', '\u{FF}', -1),
+    KernPair::new('\u{C3}', 'T', -1),
+    KernPair::new('\u{C3}', 'V', -1),
+    KernPair::new('\u{C3}', 'W', -1),
+    KernPair::new('\u{C3}', 'Y', -1),
+    KernPair::new('\u{C3}', 'v', -1),
+    KernPair::new('\u{C3}', 'w', -1),
+    KernPair::new('\u{C3}', 'y', -1),
+    KernPair::new('\u{C3}', '\u{DD}', -1),
+    KernPair::new('\u{C3}', '\u{FD}', -1),
+    KernPair::new('\u{C3}', '\u{FF}', -1),
+    KernPair::new('\u{C4}', 'T', -1),
+    KernPair::new('\u{C4}', 'V', -1),
+    KernPair::new('\u{C4}', 'W', -1),
+    KernPair::new('\u{C4}', 'Y', -1),
+    KernPair::new('\u{C4}', 'v', -1),
+    KernPair::new('\u{C4}', 'w', -1),
+    KernPair::new('\u{C4}', 'y', -1),
+    KernPair::new('\u{C4}', '\u{DD}', -1),
+    KernPair::new('\u{C4}', '\u{FD}', -1),
+    KernPair::new('\u{C4}', '\u{FF}', -1),
+    KernPair::new('\u{D0}', 'Y', -1),
+    KernPair::new('\u{D0}', '\u{DD}', -1),
+    KernPair::new('\u{D2}', '.', -1),
+    KernPair::new('\u{D2}', 'X', -1),
+    KernPair::new('\u{D2}', 'Y', -1),
+    KernPair::new('\u{D2}', '\u{DD}', -1),
+    KernPair::new('\u{D3}', '.', -1),
+    KernPair::new('\u{D3}', 'X', -1),
+    KernPair::new('\u{D3}', 'Y', -1),
+    KernPair::new('\u{D3}', '\u{DD}', -1),
+    KernPair::new('\u{D4}', '.', -1),
+    KernPair::new('\u{D4}', 'X', -1),
+    KernPair::new('\u{D4}', 'Y', -1),
+    KernPair::new('\u{D4}', '\u{DD}', -1),
+    KernPair::new('\u{D5}', '.', -1),
+    KernPair::new('\u{D5}', 'X', -1),
+    KernPair::new('\u{D5}', 'Y', -1),
+    KernPair::new('\u{D5}', '\u{DD}', -1),
+    KernPair::new('\u{D6}', '.', -1),
+    KernPair::new('\u{D6}', 'X', -1),
+    KernPair::new('\u{D6}', 'Y', -1),
+    KernPair::new('\u{D6}', '\u{DD}', -1),
+    KernPair::new('\u{DD}', '-', -2),
+    KernPair::new('\u{DD}', '.', -3),
+    KernPair::new('\u{DD}', ':', -2),
+    KernPair::new('\u{DD}', 'A', -1),
+    KernPair::new('\u{DD}', 'C', -1),
+    KernPair::new('\u{DD}', 'O', -1),
+    KernPair::new('\u{DD}', 'a', -2),
+    KernPair::new('\u{DD}', 'e', -2),
+    KernPair::new('\u{DD}', 'o', -2),
+    KernPair::new('\u{DD}', 'u', -2),
+    KernPair::new('\u{DD}', '\u{AB}', -2),
+    KernPair::new('\u{DD}', '\u{BB}', -1),
+    KernPair::new('\u{DD}', '\u{C0}', -1),
+    KernPair::new('\u{DD}', '\u{C1}', -1),
+    KernPair::new('\u{DD}', '\u{C2}', -1),
+    KernPair::new('\u{DD}', '\u{C3}', -1),
+    KernPair::new('\u{DD}', '\u{C4}', -1),
+    KernPair::new('\u{DD}', '\u{C7}', -1),
+    KernPair::new('\u{DD}', '\u{D2}', -1),
+    KernPair::new('\u{DD}', '\u{D3}', -1),
+    KernPair::new('\u{DD}', '\u{D4}', -1),
+    KernPair::new('\u{DD}', '\u{D5}', -1),
+    KernPair::new('\u{DD}', '\u{D6}', -1),
+    KernPair::new('\u{DD}', '\u{E0}', -2),
+    KernPair::new('\u{DD}', '\u{E1}', -2),
+    KernPair::new('\u{DD}', '\u{E2}', -2),
+    KernPair::new('\u{DD}', '\u{E3}', -2),
+    KernPair::new('\u{DD}', '\u{E4}', -2),
+    KernPair::new('\u{DD}', '\u{E5}', -2),
+    KernPair::new('\u{DD}', '\u{E8}', -2),
+    KernPair::new('\u{DD}', '\u{E9}', -2),
+    KernPair::new('\u{DD}', '\u{EA}', -2),
+    KernPair::new('\u{DD}', '\u{EB}', -2),
+    KernPair::new('\u{DD}', '\u{F2}', -2),
+    KernPair::new('\u{DD}', '\u{F3}', -2),
+    KernPair::new('\u{DD}', '\u{F4}', -2),
+    KernPair::new('\u{DD}', '\u{F5}', -2),
+    KernPair::new('\u{DD}', '\u{F6}', -2),
+    KernPair::new('\u{DD}', '\u{F9}', -2),
+    KernPair::new('\u{DD}', '\u{FA}', -2),
+    KernPair::new('\u{DD}', '\u{FB}', -2),
+    KernPair::new('\u{DD}', '\u{FC}', -2),
+    KernPair::new('\u{DE}', '.', -1),
+    KernPair::new('\u{FD}', '.', -2),
+    KernPair::new('\u{FD}', ':', -1),
+    KernPair::new('\u{FF}', '.', -2),
+    KernPair::new('\u{FF}', ':', -1),
+];
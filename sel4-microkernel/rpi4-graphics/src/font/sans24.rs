@@ -0,0 +1,1494 @@
+//! DejaVu Sans for a 24 pixel line (rasterized at 20.62 px per em)
+//!
+//! Generated by `tools/fontgen`; do not edit.
+
+use super::{BitmapFont, Glyph, GlyphRange, KernPair};
+
+pub static SANS_24: BitmapFont = BitmapFont {
+    line_height: 24,
+    ascent: 19,
+    ranges: &RANGES,
+    glyphs: &GLYPHS,
+    bitmap: &BITMAP,
+    kerning: &KERNING,
+};
+
+/// Characters covered, and where their glyphs start
+static RANGES: [GlyphRange; 5] = [
+    GlyphRange::new(' ', '~', 0),
+    GlyphRange::new('\u{A0}', '\u{FF}', 95),
+    GlyphRange::new('\u{2190}', '\u{2195}', 191),
+    GlyphRange::new('\u{2500}', '\u{257F}', 197),
+    GlyphRange::new('\u{FFFD}', '\u{FFFD}', 325),
+];
+
+/// Ink box and advance of each glyph, in range order
+static GLYPHS: [Glyph; 326] = [
+    Glyph::new(0, 0, 0, 0, 19, 7), // ' '
+    Glyph::new(0, 2, 15, 3, 4, 8), // '!'
+    Glyph::new(15, 5, 6, 2, 4, 9), // '"'
+    Glyph::new(21, 14, 15, 2, 4, 17), // '#'
+    Glyph::new(51, 9, 19, 2, 3, 13), // '$'
+    Glyph::new(89, 17, 15, 1, 4, 20), // '%'
+    Glyph::new(134, 14, 15, 1, 4, 16), // '&'
+    Glyph::new(164, 2, 6, 2, 4, 6), // '\''
+    Glyph::new(170, 4, 19, 2, 3, 8), // '('
+    Glyph::new(189, 4, 19, 2, 3, 8), // ')'
+    Glyph::new(208, 8, 9, 1, 4, 10), // '*'
+    Glyph::new(217, 13, 13, 2, 6, 17), // '+'
+    Glyph::new(243, 3, 5, 2, 16, 7), // ','
+    Glyph::new(248, 5, 1, 1, 13, 7), // '-'
+    Glyph::new(249, 2, 3, 2, 16, 7), // '.'
+    Glyph::new(252, 7, 17, 0, 4, 7), // '/'
+    Glyph::new(269, 11, 15, 1, 4, 13), // '0'
+    Glyph::new(299, 9, 15, 2, 4, 13), // '1'
+    Glyph::new(329, 9, 15, 2, 4, 13), // '2'
+    Glyph::new(359, 9, 15, 2, 4, 13), // '3'
+    Glyph::new(389, 11, 15, 1, 4, 13), // '4'
+    Glyph::new(419, 9, 15, 2, 4, 13), // '5'
+    Glyph::new(449, 11, 15, 1, 4, 13), // '6'
+    Glyph::new(479, 9, 15, 2, 4, 13), // '7'
+    Glyph::new(509, 11, 15, 1, 4, 13), // '8'
+    Glyph::new(539, 11, 15, 1, 4, 13), // '9'
+    Glyph::new(569, 3, 11, 2, 8, 7), // ':'
+    Glyph::new(580, 3, 13, 2, 8, 7), // ';'
+    Glyph::new(593, 13, 11, 2, 7, 17), // '<'
+    Glyph::new(615, 13, 5, 2, 10, 17), // '='
+    Glyph::new(625, 13, 11, 2, 7, 17), // '>'
+    Glyph::new(647, 8, 15, 1, 4, 11), // '?'
+    Glyph::new(662, 18, 19, 1, 4, 21), // '@'
+    Glyph::new(719, 14, 15, 0, 4, 14), // 'A'
+    Glyph::new(749, 11, 15, 2, 4, 14), // 'B'
+    Glyph::new(779, 12, 15, 1, 4, 14), // 'C'
+    Glyph::new(809, 13, 15, 2, 4, 16), // 'D'
+    Glyph::new(839, 10, 15, 2, 4, 13), // 'E'
+    Glyph::new(869, 9, 15, 2, 4, 12), // 'F'
+    Glyph::new(899, 13, 15, 1, 4, 16), // 'G'
+    Glyph::new(929, 11, 15, 2, 4, 16), // 'H'
+    Glyph::new(959, 2, 15, 2, 4, 6), // 'I'
+    Glyph::new(974, 5, 19, -1, 4, 6), // 'J'
+    Glyph::new(993, 11, 15, 2, 4, 14), // 'K'
+    Glyph::new(1023, 9, 15, 2, 4, 11), // 'L'
+    Glyph::new(1053, 14, 15, 2, 4, 18), // 'M'
+    Glyph::new(1083, 11, 15, 2, 4, 15), // 'N'
+    Glyph::new(1113, 14, 15, 1, 4, 16), // 'O'
+    Glyph::new(1143, 10, 15, 2, 4, 12), // 'P'
+    Glyph::new(1173, 14, 18, 1, 4, 16), // 'Q'
+    Glyph::new(1209, 11, 15, 2, 4, 14), // 'R'
+    Glyph::new(1239, 11, 15, 1, 4, 13), // 'S'
+    Glyph::new(1269, 13, 15, 0, 4, 13), // 'T'
+    Glyph::new(1299, 11, 15, 2, 4, 15), // 'U'
+    Glyph::new(1329, 14, 15, 0, 4, 14), // 'V'
+    Glyph::new(1359, 19, 15, 1, 4, 20), // 'W'
+    Glyph::new(1404, 12, 15, 1, 4, 14), // 'X'
+    Glyph::new(1434, 12, 15, 0, 4, 13), // 'Y'
+    Glyph::new(1464, 12, 15, 1, 4, 14), // 'Z'
+    Glyph::new(1494, 4, 19, 2, 3, 8), // '['
+    Glyph::new(1513, 7, 17, 0, 4, 7), // '\\'
+    Glyph::new(1530, 4, 19, 2, 3, 8), // ']'
+    Glyph::new(1549, 11, 6, 3, 4, 17), // '^'
+    Glyph::new(1561, 10, 2, 0, 22, 10), // '_'
+    Glyph::new(1565, 3, 3, 3, 3, 10), // '`'
+    Glyph::new(1568, 10, 12, 1, 7, 13), // 'a'
+    Glyph::new(1592, 10, 16, 2, 3, 13), // 'b'
+    Glyph::new(1624, 9, 12, 1, 7, 11), // 'c'
+    Glyph::new(1648, 10, 16, 1, 3, 13), // 'd'
+    Glyph::new(1680, 11, 12, 1, 7, 13), // 'e'
+    Glyph::new(1704, 8, 16, 0, 3, 7), // 'f'
+    Glyph::new(1720, 10, 16, 1, 7, 13), // 'g'
+    Glyph::new(1752, 9, 16, 2, 3, 13), // 'h'
+    Glyph::new(1784, 2, 16, 2, 3, 6), // 'i'
+    Glyph::new(1800, 4, 20, 0, 3, 6), // 'j'
+    Glyph::new(1820, 9, 16, 2, 3, 12), // 'k'
+    Glyph::new(1852, 2, 16, 2, 3, 6), // 'l'
+    Glyph::new(1868, 16, 12, 2, 7, 20), // 'm'
+    Glyph::new(1892, 9, 12, 2, 7, 13), // 'n'
+    Glyph::new(1916, 10, 12, 1, 7, 13), // 'o'
+    Glyph::new(1940, 10, 16, 2, 7, 13), // 'p'
+    Glyph::new(1972, 10, 16, 1, 7, 13), // 'q'
+    Glyph::new(2004, 6, 12, 2, 7, 8), // 'r'
+    Glyph::new(2016, 9, 12, 1, 7, 11), // 's'
+    Glyph::new(2040, 7, 14, 1, 5, 8), // 't'
+    Glyph::new(2054, 9, 11, 2, 8, 13), // 'u'
+    Glyph::new(2076, 10, 11, 1, 8, 12), // 'v'
+    Glyph::new(2098, 15, 11, 1, 8, 17), // 'w'
+    Glyph::new(2120, 10, 11, 1, 8, 12), // 'x'
+    Glyph::new(2142, 10, 15, 1, 8, 12), // 'y'
+    Glyph::new(2172, 9, 11, 1, 8, 11), // 'z'
+    Glyph::new(2194, 8, 19, 3, 3, 13), // '{'
+    Glyph::new(2213, 1, 21, 3, 3, 7), // '|'
+    Glyph::new(2234, 7, 19, 3, 3, 13), // '}'
+    Glyph::new(2253, 13, 3, 2, 11, 17), // '~'
+    Glyph::new(2259, 0, 0, 0, 19, 7), // '\u{A0}'
+    Glyph::new(2259, 2, 15, 3, 8, 8), // '\u{A1}' ¡
+    Glyph::new(2274, 9, 17, 2, 5, 13), // '\u{A2}' ¢
+    Glyph::new(2308, 10, 15, 1, 4, 13), // '\u{A3}' £
+    Glyph::new(2338, 11, 11, 1, 7, 13), // '\u{A4}' ¤
+    Glyph::new(2360, 11, 15, 1, 4, 13), // '\u{A5}' ¥
+    Glyph::new(2390, 1, 18, 3, 5, 7), // '\u{A6}' ¦
+    Glyph::new(2408, 8, 17, 1, 4, 10), // '\u{A7}' §
+    Glyph::new(2425, 6, 2, 2, 3, 10), // '\u{A8}' ¨
+    Glyph::new(2427, 15, 15, 3, 4, 21), // '\u{A9}' ©
+    Glyph::new(2457, 7, 10, 1, 4, 10), // '\u{AA}' ª
+    Glyph::new(2467, 9, 8, 2, 9, 13), // '\u{AB}' «
+    Glyph::new(2483, 13, 6, 2, 10, 17), // '\u{AC}' ¬
+    Glyph::new(2495, 5, 1, 1, 13, 7), // '\u{AD}'
+    Glyph::new(2496, 15, 15, 3, 4, 21), // '\u{AE}' ®
+    Glyph::new(2526, 6, 1, 2, 4, 10), // '\u{AF}' ¯
+    Glyph::new(2527, 6, 6, 2, 4, 10), // '\u{B0}' °
+    Glyph::new(2533, 13, 13, 2, 6, 17), // '\u{B1}' ±
+    Glyph::new(2559, 6, 8, 1, 4, 8), // '\u{B2}' ²
+    Glyph::new(2567, 6, 8, 1, 4, 8), // '\u{B3}' ³
+    Glyph::new(2575, 4, 3, 4, 3, 10), // '\u{B4}' ´
+    Glyph::new(2578, 11, 15, 2, 8, 13), // '\u{B5}' µ
+    Glyph::new(2608, 9, 17, 2, 4, 13), // '\u{B6}' ¶
+    Glyph::new(2642, 2, 2, 2, 11, 7), // '\u{B7}' ·
+    Glyph::new(2644, 4, 4, 3, 19, 10), // '\u{B8}' ¸
+    Glyph::new(2648, 5, 8, 2, 4, 8), // '\u{B9}' ¹
+    Glyph::new(2656, 8, 10, 1, 4, 10), // '\u{BA}' º
+    Glyph::new(2666, 9, 8, 2, 9, 13), // '\u{BB}' »
+    Glyph::new(2682, 17, 15, 2, 4, 20), // '\u{BC}' ¼
+    Glyph::new(2727, 17, 15, 2, 4, 20), // '\u{BD}' ½
+    Glyph::new(2772, 18, 15, 1, 4, 20), // '\u{BE}' ¾
+    Glyph::new(2817, 8, 15, 1, 8, 11), // '\u{BF}' ¿
+    Glyph::new(2832, 14, 19, 0, 0, 14), // '\u{C0}' À
+    Glyph::new(2870, 14, 19, 0, 0, 14), // '\u{C1}' Á
+    Glyph::new(2908, 14, 19, 0, 0, 14), // '\u{C2}' Â
+    Glyph::new(2946, 14, 19, 0, 0, 14), // '\u{C3}' Ã
+    Glyph::new(2984, 14, 19, 0, 0, 14), // '\u{C4}' Ä
+    Glyph::new(3022, 14, 19, 0, 0, 14), // '\u{C5}' Å
+    Glyph::new(3060, 19, 15, 0, 4, 20), // '\u{C6}' Æ
+    Glyph::new(3105, 12, 19, 1, 4, 14), // '\u{C7}' Ç
+    Glyph::new(3143, 10, 19, 2, 0, 13), // '\u{C8}' È
+    Glyph::new(3181, 10, 19, 2, 0, 13), // '\u{C9}' É
+    Glyph::new(3219, 10, 19, 2, 0, 13), // '\u{CA}' Ê
+    Glyph::new(3257, 10, 19, 2, 0, 13), // '\u{CB}' Ë
+    Glyph::new(3295, 3, 19, 1, 0, 6), // '\u{CC}' Ì
+    Glyph::new(3314, 3, 19, 2, 0, 6), // '\u{CD}' Í
+    Glyph::new(3333, 4, 19, 1, 0, 6), // '\u{CE}' Î
+    Glyph::new(3352, 6, 19, 0, 0, 6), // '\u{CF}' Ï
+    Glyph::new(3371, 15, 15, 0, 4, 16), // '\u{D0}' Ð
+    Glyph::new(3401, 11, 19, 2, 0, 15), // '\u{D1}' Ñ
+    Glyph::new(3439, 14, 19, 1, 0, 16), // '\u{D2}' Ò
+    Glyph::new(3477, 14, 19, 1, 0, 16), // '\u{D3}' Ó
+    Glyph::new(3515, 14, 19, 1, 0, 16), // '\u{D4}' Ô
+    Glyph::new(3553, 14, 19, 1, 0, 16), // '\u{D5}' Õ
+    Glyph::new(3591, 14, 19, 1, 0, 16), // '\u{D6}' Ö
+    Glyph::new(3629, 11, 11, 3, 7, 17), // '\u{D7}' ×
+    Glyph::new(3651, 14, 15, 1, 4, 16), // '\u{D8}' Ø
+    Glyph::new(3681, 11, 19, 2, 0, 15), // '\u{D9}' Ù
+    Glyph::new(3719, 11, 19, 2, 0, 15), // '\u{DA}' Ú
+    Glyph::new(3757, 11, 19, 2, 0, 15), // '\u{DB}' Û
+    Glyph::new(3795, 11, 19, 2, 0, 15), // '\u{DC}' Ü
+    Glyph::new(3833, 12, 19, 0, 0, 13), // '\u{DD}' Ý
+    Glyph::new(3871, 10, 15, 2, 4, 12), // '\u{DE}' Þ
+    Glyph::new(3901, 10, 16, 2, 3, 13), // '\u{DF}' ß
+    Glyph::new(3933, 10, 16, 1, 3, 13), // '\u{E0}' à
+    Glyph::new(3965, 10, 16, 1, 3, 13), // '\u{E1}' á
+    Glyph::new(3997, 10, 16, 1, 3, 13), // '\u{E2}' â
+    Glyph::new(4029, 10, 16, 1, 3, 13), // '\u{E3}' ã
+    Glyph::new(4061, 10, 16, 1, 3, 13), // '\u{E4}' ä
+    Glyph::new(4093, 10, 18, 1, 1, 13), // '\u{E5}' å
+    Glyph::new(4129, 18, 12, 1, 7, 20), // '\u{E6}' æ
+    Glyph::new(4165, 9, 16, 1, 7, 11), // '\u{E7}' ç
+    Glyph::new(4197, 11, 16, 1, 3, 13), // '\u{E8}' è
+    Glyph::new(4229, 11, 16, 1, 3, 13), // '\u{E9}' é
+    Glyph::new(4261, 11, 16, 1, 3, 13), // '\u{EA}' ê
+    Glyph::new(4293, 11, 16, 1, 3, 13), // '\u{EB}' ë
+    Glyph::new(4325, 4, 16, 0, 3, 6), // '\u{EC}' ì
+    Glyph::new(4341, 3, 16, 2, 3, 6), // '\u{ED}' í
+    Glyph::new(4357, 6, 16, 0, 3, 6), // '\u{EE}' î
+    Glyph::new(4373, 6, 16, 0, 3, 6), // '\u{EF}' ï
+    Glyph::new(4389, 10, 16, 1, 3, 13), // '\u{F0}' ð
+    Glyph::new(4421, 9, 16, 2, 3, 13), // '\u{F1}' ñ
+    Glyph::new(4453, 10, 16, 1, 3, 13), // '\u{F2}' ò
+    Glyph::new(4485, 10, 16, 1, 3, 13), // '\u{F3}' ó
+    Glyph::new(4517, 10, 16, 1, 3, 13), // '\u{F4}' ô
+    Glyph::new(4549, 10, 16, 1, 3, 13), // '\u{F5}' õ
+    Glyph::new(4581, 10, 16, 1, 3, 13), // '\u{F6}' ö
+    Glyph::new(4613, 13, 9, 2, 8, 17), // '\u{F7}' ÷
+    Glyph::new(4631, 11, 13, 1, 7, 13), // '\u{F8}' ø
+    Glyph::new(4657, 9, 16, 2, 3, 13), // '\u{F9}' ù
+    Glyph::new(4689, 9, 16, 2, 3, 13), // '\u{FA}' ú
+    Glyph::new(4721, 9, 16, 2, 3, 13), // '\u{FB}' û
+    Glyph::new(4753, 9, 16, 2, 3, 13), // '\u{FC}' ü
+    Glyph::new(4785, 10, 20, 1, 3, 12), // '\u{FD}' ý
+    Glyph::new(4825, 10, 20, 2, 3, 13), // '\u{FE}' þ
+    Glyph::new(4865, 10, 20, 1, 3, 12), // '\u{FF}' ÿ
+    Glyph::new(4905, 15, 7, 1, 9, 17), // '\u{2190}' ←
+    Glyph::new(4919, 8, 15, 5, 4, 17), // '\u{2191}' ↑
+    Glyph::new(4934, 15, 9, 1, 8, 17), // '\u{2192}' →
+    Glyph::new(4952, 8, 15, 5, 4, 17), // '\u{2193}' ↓
+    Glyph::new(4967, 15, 9, 1, 8, 17), // '\u{2194}' ↔
+    Glyph::new(4985, 8, 15, 5, 4, 17), // '\u{2195}' ↕
+    Glyph::new(5000, 13, 2, 0, 11, 12), // '\u{2500}' ─
+    Glyph::new(5004, 13, 4, 0, 10, 12), // '\u{2501}' ━
+    Glyph::new(5012, 2, 24, 5, 0, 12), // '\u{2502}' │
+    Glyph::new(5036, 3, 24, 5, 0, 12), // '\u{2503}' ┃
+    Glyph::new(5060, 11, 2, 1, 11, 12), // '\u{2504}' ┄
+    Glyph::new(5064, 11, 4, 1, 10, 12), // '\u{2505}' ┅
+    Glyph::new(5072, 2, 22, 5, 1, 12), // '\u{2506}' ┆
+    Glyph::new(5094, 3, 22, 5, 1, 12), // '\u{2507}' ┇
+    Glyph::new(5116, 11, 2, 1, 11, 12), // '\u{2508}' ┈
+    Glyph::new(5120, 11, 4, 1, 10, 12), // '\u{2509}' ┉
+    Glyph::new(5128, 2, 22, 5, 1, 12), // '\u{250A}' ┊
+    Glyph::new(5150, 3, 22, 5, 1, 12), // '\u{250B}' ┋
+    Glyph::new(5172, 8, 13, 5, 11, 12), // '\u{250C}' ┌
+    Glyph::new(5185, 8, 14, 5, 10, 12), // '\u{250D}' ┍
+    Glyph::new(5199, 8, 13, 5, 11, 12), // '\u{250E}' ┎
+    Glyph::new(5212, 8, 14, 5, 10, 12), // '\u{250F}' ┏
+    Glyph::new(5226, 7, 13, 0, 11, 12), // '\u{2510}' ┐
+    Glyph::new(5239, 7, 14, 0, 10, 12), // '\u{2511}' ┑
+    Glyph::new(5253, 8, 13, 0, 11, 12), // '\u{2512}' ┒
+    Glyph::new(5266, 8, 14, 0, 10, 12), // '\u{2513}' ┓
+    Glyph::new(5280, 8, 13, 5, 0, 12), // '\u{2514}' └
+    Glyph::new(5293, 8, 14, 5, 0, 12), // '\u{2515}' ┕
+    Glyph::new(5307, 8, 13, 5, 0, 12), // '\u{2516}' ┖
+    Glyph::new(5320, 8, 14, 5, 0, 12), // '\u{2517}' ┗
+    Glyph::new(5334, 7, 13, 0, 0, 12), // '\u{2518}' ┘
+    Glyph::new(5347, 7, 14, 0, 0, 12), // '\u{2519}' ┙
+    Glyph::new(5361, 8, 13, 0, 0, 12), // '\u{251A}' ┚
+    Glyph::new(5374, 8, 14, 0, 0, 12), // '\u{251B}' ┛
+    Glyph::new(5388, 8, 24, 5, 0, 12), // '\u{251C}' ├
+    Glyph::new(5412, 8, 24, 5, 0, 12), // '\u{251D}' ┝
+    Glyph::new(5436, 8, 24, 5, 0, 12), // '\u{251E}' ┞
+    Glyph::new(5460, 8, 24, 5, 0, 12), // '\u{251F}' ┟
+    Glyph::new(5484, 8, 24, 5, 0, 12), // '\u{2520}' ┠
+    Glyph::new(5508, 8, 24, 5, 0, 12), // '\u{2521}' ┡
+    Glyph::new(5532, 8, 24, 5, 0, 12), // '\u{2522}' ┢
+    Glyph::new(5556, 8, 24, 5, 0, 12), // '\u{2523}' ┣
+    Glyph::new(5580, 7, 24, 0, 0, 12), // '\u{2524}' ┤
+    Glyph::new(5604, 7, 24, 0, 0, 12), // '\u{2525}' ┥
+    Glyph::new(5628, 8, 24, 0, 0, 12), // '\u{2526}' ┦
+    Glyph::new(5652, 8, 24, 0, 0, 12), // '\u{2527}' ┧
+    Glyph::new(5676, 8, 24, 0, 0, 12), // '\u{2528}' ┨
+    Glyph::new(5700, 8, 24, 0, 0, 12), // '\u{2529}' ┩
+    Glyph::new(5724, 8, 24, 0, 0, 12), // '\u{252A}' ┪
+    Glyph::new(5748, 8, 24, 0, 0, 12), // '\u{252B}' ┫
+    Glyph::new(5772, 13, 13, 0, 11, 12), // '\u{252C}' ┬
+    Glyph::new(5798, 13, 14, 0, 10, 12), // '\u{252D}' ┭
+    Glyph::new(5826, 13, 14, 0, 10, 12), // '\u{252E}' ┮
+    Glyph::new(5854, 13, 14, 0, 10, 12), // '\u{252F}' ┯
+    Glyph::new(5882, 13, 13, 0, 11, 12), // '\u{2530}' ┰
+    Glyph::new(5908, 13, 14, 0, 10, 12), // '\u{2531}' ┱
+    Glyph::new(5936, 13, 14, 0, 10, 12), // '\u{2532}' ┲
+    Glyph::new(5964, 13, 14, 0, 10, 12), // '\u{2533}' ┳
+    Glyph::new(5992, 13, 13, 0, 0, 12), // '\u{2534}' ┴
+    Glyph::new(6018, 13, 14, 0, 0, 12), // '\u{2535}' ┵
+    Glyph::new(6046, 13, 14, 0, 0, 12), // '\u{2536}' ┶
+    Glyph::new(6074, 13, 14, 0, 0, 12), // '\u{2537}' ┷
+    Glyph::new(6102, 13, 13, 0, 0, 12), // '\u{2538}' ┸
+    Glyph::new(6128, 13, 14, 0, 0, 12), // '\u{2539}' ┹
+    Glyph::new(6156, 13, 14, 0, 0, 12), // '\u{253A}' ┺
+    Glyph::new(6184, 13, 14, 0, 0, 12), // '\u{253B}' ┻
+    Glyph::new(6212, 13, 24, 0, 0, 12), // '\u{253C}' ┼
+    Glyph::new(6260, 13, 24, 0, 0, 12), // '\u{253D}' ┽
+    Glyph::new(6308, 13, 24, 0, 0, 12), // '\u{253E}' ┾
+    Glyph::new(6356, 13, 24, 0, 0, 12), // '\u{253F}' ┿
+    Glyph::new(6404, 13, 24, 0, 0, 12), // '\u{2540}' ╀
+    Glyph::new(6452, 13, 24, 0, 0, 12), // '\u{2541}' ╁
+    Glyph::new(6500, 13, 24, 0, 0, 12), // '\u{2542}' ╂
+    Glyph::new(6548, 13, 24, 0, 0, 12), // '\u{2543}' ╃
+    Glyph::new(6596, 13, 24, 0, 0, 12), // '\u{2544}' ╄
+    Glyph::new(6644, 13, 24, 0, 0, 12), // '\u{2545}' ╅
+    Glyph::new(6692, 13, 24, 0, 0, 12), // '\u{2546}' ╆
+    Glyph::new(6740, 13, 24, 0, 0, 12), // '\u{2547}' ╇
+    Glyph::new(6788, 13, 24, 0, 0, 12), // '\u{2548}' ╈
+    Glyph::new(6836, 13, 24, 0, 0, 12), // '\u{2549}' ╉
+    Glyph::new(6884, 13, 24, 0, 0, 12), // '\u{254A}' ╊
+    Glyph::new(6932, 13, 24, 0, 0, 12), // '\u{254B}' ╋
+    Glyph::new(6980, 11, 2, 1, 11, 12), // '\u{254C}' ╌
+    Glyph::new(6984, 11, 4, 1, 10, 12), // '\u{254D}' ╍
+    Glyph::new(6992, 2, 20, 5, 2, 12), // '\u{254E}' ╎
+    Glyph::new(7012, 3, 20, 5, 2, 12), // '\u{254F}' ╏
+    Glyph::new(7032, 13, 6, 0, 9, 12), // '\u{2550}' ═
+    Glyph::new(7044, 5, 24, 4, 0, 12), // '\u{2551}' ║
+    Glyph::new(7068, 8, 15, 5, 9, 12), // '\u{2552}' ╒
+    Glyph::new(7083, 9, 13, 4, 11, 12), // '\u{2553}' ╓
+    Glyph::new(7109, 9, 15, 4, 9, 12), // '\u{2554}' ╔
+    Glyph::new(7139, 7, 15, 0, 9, 12), // '\u{2555}' ╕
+    Glyph::new(7154, 9, 13, 0, 11, 12), // '\u{2556}' ╖
+    Glyph::new(7180, 9, 15, 0, 9, 12), // '\u{2557}' ╗
+    Glyph::new(7210, 8, 15, 5, 0, 12), // '\u{2558}' ╘
+    Glyph::new(7225, 9, 13, 4, 0, 12), // '\u{2559}' ╙
+    Glyph::new(7251, 9, 15, 4, 0, 12), // '\u{255A}' ╚
+    Glyph::new(7281, 7, 15, 0, 0, 12), // '\u{255B}' ╛
+    Glyph::new(7296, 9, 13, 0, 0, 12), // '\u{255C}' ╜
+    Glyph::new(7322, 9, 15, 0, 0, 12), // '\u{255D}' ╝
+    Glyph::new(7352, 8, 24, 5, 0, 12), // '\u{255E}' ╞
+    Glyph::new(7376, 9, 24, 4, 0, 12), // '\u{255F}' ╟
+    Glyph::new(7424, 9, 24, 4, 0, 12), // '\u{2560}' ╠
+    Glyph::new(7472, 7, 24, 0, 0, 12), // '\u{2561}' ╡
+    Glyph::new(7496, 9, 24, 0, 0, 12), // '\u{2562}' ╢
+    Glyph::new(7544, 9, 24, 0, 0, 12), // '\u{2563}' ╣
+    Glyph::new(7592, 13, 15, 0, 9, 12), // '\u{2564}' ╤
+    Glyph::new(7622, 13, 13, 0, 11, 12), // '\u{2565}' ╥
+    Glyph::new(7648, 13, 15, 0, 9, 12), // '\u{2566}' ╦
+    Glyph::new(7678, 13, 15, 0, 0, 12), // '\u{2567}' ╧
+    Glyph::new(7708, 13, 13, 0, 0, 12), // '\u{2568}' ╨
+    Glyph::new(7734, 13, 15, 0, 0, 12), // '\u{2569}' ╩
+    Glyph::new(7764, 13, 24, 0, 0, 12), // '\u{256A}' ╪
+    Glyph::new(7812, 13, 24, 0, 0, 12), // '\u{256B}' ╫
+    Glyph::new(7860, 13, 24, 0, 0, 12), // '\u{256C}' ╬
+    Glyph::new(7908, 8, 13, 5, 11, 12), // '\u{256D}' ╭
+    Glyph::new(7921, 7, 13, 0, 11, 12), // '\u{256E}' ╮
+    Glyph::new(7934, 7, 13, 0, 0, 12), // '\u{256F}' ╯
+    Glyph::new(7947, 8, 13, 5, 0, 12), // '\u{2570}' ╰
+    Glyph::new(7960, 14, 24, -1, 0, 12), // '\u{2571}' ╱
+    Glyph::new(8008, 14, 24, -1, 0, 12), // '\u{2572}' ╲
+    Glyph::new(8056, 14, 24, -1, 0, 12), // '\u{2573}' ╳
+    Glyph::new(8104, 6, 2, 0, 11, 12), // '\u{2574}' ╴
+    Glyph::new(8106, 2, 12, 5, 0, 12), // '\u{2575}' ╵
+    Glyph::new(8118, 7, 2, 6, 11, 12), // '\u{2576}' ╶
+    Glyph::new(8120, 2, 12, 5, 12, 12), // '\u{2577}' ╷
+    Glyph::new(8132, 6, 4, 0, 10, 12), // '\u{2578}' ╸
+    Glyph::new(8136, 3, 12, 5, 0, 12), // '\u{2579}' ╹
+    Glyph::new(8148, 7, 4, 6, 10, 12), // '\u{257A}' ╺
+    Glyph::new(8152, 3, 12, 5, 12, 12), // '\u{257B}' ╻
+    Glyph::new(8164, 13, 4, 0, 10, 12), // '\u{257C}' ╼
+    Glyph::new(8172, 3, 24, 5, 0, 12), // '\u{257D}' ╽
+    Glyph::new(8196, 13, 4, 0, 10, 12), // '\u{257E}' ╾
+    Glyph::new(8204, 3, 24, 5, 0, 12), // '\u{257F}' ╿
+    Glyph::new(8228, 20, 20, 1, 0, 21), // '\u{FFFD}' �
+];
+
+/// Glyph rows, MSB leftmost, each row padded to a whole byte
+static BITMAP: [u8; 8288] = [
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0x40, 0xC0, 0xC0, 0xC8,
+    0xC8, 0xC8, 0xC8, 0xC8, 0x88, 0x06, 0x20, 0x06, 0x20, 0x04, 0x60, 0x04, 0x60, 0x7F, 0xF8, 0x7F,
+    0xFC, 0x08, 0xC0, 0x08, 0xC0, 0x18, 0xC0, 0xFF, 0xF0, 0xFF, 0xF0, 0x11, 0x80, 0x31, 0x80, 0x31,
+    0x00, 0x23, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00, 0x7F, 0x00, 0xE9, 0x00, 0xC8, 0x00, 0xC8,
+    0x00, 0xC8, 0x00, 0x78, 0x00, 0x3F, 0x00, 0x0B, 0x80, 0x09, 0x80, 0x08, 0x80, 0x09, 0x80, 0xEF,
+    0x80, 0x7E, 0x00, 0x08, 0x00, 0x08, 0x00, 0x08, 0x00, 0x7C, 0x0C, 0x00, 0x46, 0x08, 0x00, 0xC6,
+    0x18, 0x00, 0xC6, 0x30, 0x00, 0xC6, 0x20, 0x00, 0xC6, 0x60, 0x00, 0x6C, 0x40, 0x00, 0x38, 0xCF,
+    0x00, 0x01, 0x9B, 0x80, 0x01, 0x10, 0x80, 0x03, 0x10, 0x80, 0x02, 0x10, 0x80, 0x06, 0x10, 0x80,
+    0x0C, 0x19, 0x80, 0x08, 0x0F, 0x00, 0x1F, 0x80, 0x38, 0x80, 0x30, 0x00, 0x30, 0x00, 0x30, 0x00,
+    0x18, 0x00, 0x3C, 0x00, 0x66, 0x0C, 0x63, 0x08, 0xC1, 0x98, 0xC0, 0xD8, 0xE0, 0xF0, 0x60, 0x70,
+    0x79, 0xF8, 0x1F, 0x9C, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x80, 0x10, 0x30, 0x20, 0x60, 0x60, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0x60, 0x20, 0x30, 0x10, 0x80, 0xC0, 0x40,
+    0x60, 0x20, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x20, 0x60, 0x60, 0xC0, 0x80,
+    0x08, 0x08, 0xCB, 0x3E, 0x1C, 0x7E, 0xC9, 0x08, 0x08, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02,
+    0x00, 0x02, 0x00, 0x03, 0x00, 0xFF, 0xF8, 0x07, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02,
+    0x00, 0x02, 0x00, 0x40, 0xE0, 0xC0, 0xC0, 0x80, 0xF8, 0x40, 0xC0, 0xC0, 0x06, 0x04, 0x0C, 0x0C,
+    0x0C, 0x08, 0x18, 0x18, 0x10, 0x30, 0x30, 0x20, 0x60, 0x60, 0x40, 0xC0, 0xC0, 0x1F, 0x00, 0x3B,
+    0x80, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0xE0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xE0,
+    0x60, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x3B, 0x80, 0x1F, 0x00, 0x7C, 0x00, 0xFC, 0x00, 0x0C,
+    0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x0C,
+    0x00, 0x0C, 0x00, 0x0C, 0x00, 0x7F, 0x80, 0x7F, 0x80, 0xFE, 0x00, 0xE7, 0x00, 0x03, 0x80, 0x01,
+    0x80, 0x01, 0x80, 0x01, 0x80, 0x03, 0x00, 0x07, 0x00, 0x0E, 0x00, 0x1C, 0x00, 0x38, 0x00, 0x70,
+    0x00, 0xE0, 0x00, 0xFF, 0x80, 0xFF, 0x80, 0xFE, 0x00, 0xE7, 0x00, 0x01, 0x80, 0x01, 0x80, 0x01,
+    0x80, 0x03, 0x80, 0x3E, 0x00, 0x3F, 0x00, 0x03, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01,
+    0x80, 0xC7, 0x00, 0xFE, 0x00, 0x03, 0x80, 0x07, 0x80, 0x07, 0x80, 0x0D, 0x80, 0x19, 0x80, 0x11,
+    0x80, 0x31, 0x80, 0x61, 0x80, 0x41, 0x80, 0xC1, 0x80, 0xFF, 0xE0, 0x03, 0x80, 0x01, 0x80, 0x01,
+    0x80, 0x01, 0x80, 0xFF, 0x00, 0xFF, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFC, 0x00, 0xFF,
+    0x00, 0x03, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x01, 0x80, 0x03, 0x80, 0xCF, 0x00, 0xFE,
+    0x00, 0x0F, 0xC0, 0x3C, 0xC0, 0x30, 0x00, 0x60, 0x00, 0x60, 0x00, 0x6F, 0x00, 0xFF, 0xC0, 0xF0,
+    0xC0, 0xE0, 0xE0, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0xC0, 0x39, 0xC0, 0x1F, 0x80, 0xFF,
+    0x80, 0xFF, 0x80, 0x01, 0x80, 0x03, 0x00, 0x03, 0x00, 0x07, 0x00, 0x06, 0x00, 0x06, 0x00, 0x0C,
+    0x00, 0x0C, 0x00, 0x0C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x30, 0x00, 0x30, 0x00, 0x3F, 0x80, 0x71,
+    0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x3F, 0x80, 0x3F, 0x80, 0x60, 0xC0, 0x60,
+    0xC0, 0xC0, 0x60, 0xE0, 0x60, 0x60, 0xC0, 0x71, 0xC0, 0x3F, 0x80, 0x3F, 0x00, 0x73, 0x80, 0x60,
+    0xC0, 0xE0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0x60, 0xE0, 0x61, 0xE0, 0x3F, 0xE0, 0x1E, 0xE0, 0x00,
+    0xC0, 0x00, 0xC0, 0x01, 0x80, 0x67, 0x80, 0x7E, 0x00, 0x40, 0xE0, 0xC0, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x40, 0xE0, 0xE0, 0x40, 0xE0, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0xE0, 0xC0, 0xC0,
+    0x80, 0x00, 0x08, 0x00, 0x78, 0x03, 0xE0, 0x1F, 0x00, 0x7C, 0x00, 0xE0, 0x00, 0x78, 0x00, 0x1F,
+    0x00, 0x03, 0xE0, 0x00, 0x78, 0x00, 0x08, 0xFF, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF,
+    0xF8, 0xC0, 0x00, 0xF0, 0x00, 0x3E, 0x00, 0x07, 0xC0, 0x00, 0xF8, 0x00, 0x38, 0x00, 0xF8, 0x07,
+    0xC0, 0x3E, 0x00, 0xF0, 0x00, 0xC0, 0x00, 0x7E, 0xE7, 0x03, 0x03, 0x03, 0x07, 0x0E, 0x1C, 0x18,
+    0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00, 0x40, 0x00, 0x07, 0xFC, 0x00, 0x0E, 0x06, 0x00, 0x18,
+    0x03, 0x00, 0x30, 0x01, 0x80, 0x61, 0xE8, 0xC0, 0x43, 0xFC, 0xC0, 0x46, 0x1C, 0x40, 0xC6, 0x0C,
+    0x40, 0xC6, 0x0C, 0x40, 0xC6, 0x0C, 0xC0, 0x46, 0x0C, 0x80, 0x43, 0x3F, 0x80, 0x61, 0xEE, 0x00,
+    0x30, 0x00, 0x00, 0x18, 0x00, 0x00, 0x0C, 0x06, 0x00, 0x07, 0xFC, 0x00, 0x00, 0xE0, 0x00, 0x03,
+    0x00, 0x07, 0x80, 0x07, 0x80, 0x04, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18,
+    0x60, 0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18, 0x60, 0x18, 0x60, 0x18, 0xC0, 0x0C, 0xFF, 0x00, 0xFF,
+    0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0xFF, 0x80, 0xFF, 0x80, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x60, 0xC0, 0xE0, 0xC0, 0xC0, 0xFF, 0xC0, 0xFF, 0x00, 0x0F, 0xE0, 0x3C, 0x70, 0x30,
+    0x10, 0x60, 0x00, 0x60, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x60,
+    0x00, 0x60, 0x00, 0x30, 0x10, 0x3C, 0x70, 0x0F, 0xE0, 0xFF, 0x00, 0xFF, 0xC0, 0xC0, 0xE0, 0xC0,
+    0x70, 0xC0, 0x30, 0xC0, 0x30, 0xC0, 0x38, 0xC0, 0x18, 0xC0, 0x38, 0xC0, 0x30, 0xC0, 0x30, 0xC0,
+    0x70, 0xC0, 0xE0, 0xFF, 0xC0, 0xFF, 0x00, 0xFF, 0xC0, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xFF, 0xC0, 0xFF, 0xC0, 0xFF, 0x80, 0xFF, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xFF, 0x00, 0xFF, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0x0F, 0xF0, 0x3C, 0x78, 0x30, 0x08, 0x60, 0x00, 0x60, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0xF8, 0xC0, 0xF8, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18, 0x30, 0x18, 0x3C, 0x78, 0x0F,
+    0xF0, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xFF, 0xE0, 0xFF,
+    0xE0, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0xF0,
+    0xE0, 0xC0, 0xE0, 0xC1, 0xC0, 0xC3, 0x80, 0xC7, 0x00, 0xCE, 0x00, 0xDC, 0x00, 0xF0, 0x00, 0xF0,
+    0x00, 0xD8, 0x00, 0xCC, 0x00, 0xC6, 0x00, 0xC3, 0x00, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0x60, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF, 0x80, 0xE0, 0x1C, 0xF0,
+    0x3C, 0xF0, 0x3C, 0xD0, 0x6C, 0xD8, 0x6C, 0xD8, 0x4C, 0xCC, 0xCC, 0xCC, 0xCC, 0xC4, 0x8C, 0xC7,
+    0x8C, 0xC7, 0x8C, 0xC3, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x60, 0xF0, 0x60, 0xF0,
+    0x60, 0xD8, 0x60, 0xD8, 0x60, 0xCC, 0x60, 0xCC, 0x60, 0xC6, 0x60, 0xC6, 0x60, 0xC3, 0x60, 0xC3,
+    0x60, 0xC1, 0xE0, 0xC1, 0xE0, 0xC0, 0xE0, 0xC0, 0xE0, 0x0F, 0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60,
+    0x18, 0x60, 0x1C, 0xE0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60,
+    0x18, 0x30, 0x38, 0x3C, 0xF0, 0x0F, 0xC0, 0xFE, 0x00, 0xFF, 0x80, 0xC1, 0x80, 0xC1, 0xC0, 0xC0,
+    0xC0, 0xC1, 0xC0, 0xC1, 0x80, 0xFF, 0x80, 0xFE, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0x0F, 0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60, 0x18, 0x60, 0x1C, 0xE0,
+    0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60, 0x18, 0x30, 0x38, 0x3C,
+    0xF0, 0x0F, 0xC0, 0x00, 0xE0, 0x00, 0x70, 0x00, 0x30, 0xFF, 0x00, 0xFF, 0x80, 0xC1, 0x80, 0xC1,
+    0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0xC1, 0x80, 0xFF, 0x00, 0xFF, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0xC0, 0x60, 0x3F, 0xC0, 0x79, 0xC0, 0x60, 0x00, 0xC0, 0x00, 0xE0,
+    0x00, 0x60, 0x00, 0x7E, 0x00, 0x1F, 0x80, 0x01, 0xC0, 0x00, 0xE0, 0x00, 0x60, 0x00, 0x60, 0x00,
+    0xE0, 0xF1, 0xC0, 0x7F, 0x80, 0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06,
+    0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06,
+    0x00, 0x06, 0x00, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xE0, 0xE0, 0x71, 0xC0, 0x3F,
+    0x80, 0xC0, 0x0C, 0x60, 0x18, 0x60, 0x18, 0x30, 0x38, 0x30, 0x30, 0x30, 0x30, 0x18, 0x60, 0x18,
+    0x60, 0x18, 0x60, 0x0C, 0xC0, 0x0C, 0xC0, 0x07, 0xC0, 0x07, 0x80, 0x07, 0x80, 0x03, 0x00, 0xC0,
+    0xC0, 0x60, 0xC0, 0xE0, 0xC0, 0xC1, 0xE0, 0xC0, 0x61, 0xA0, 0xC0, 0x61, 0xA0, 0xC0, 0x61, 0x31,
+    0x80, 0x63, 0x31, 0x80, 0x33, 0x11, 0x80, 0x33, 0x11, 0x80, 0x32, 0x1B, 0x00, 0x36, 0x1B, 0x00,
+    0x1E, 0x0B, 0x00, 0x1E, 0x0F, 0x00, 0x1C, 0x0E, 0x00, 0x1C, 0x0E, 0x00, 0x60, 0x30, 0x70, 0x60,
+    0x30, 0xC0, 0x18, 0xC0, 0x1D, 0x80, 0x0F, 0x00, 0x07, 0x00, 0x07, 0x00, 0x0F, 0x00, 0x19, 0x80,
+    0x19, 0xC0, 0x30, 0xC0, 0x60, 0x60, 0x60, 0x60, 0xC0, 0x30, 0xE0, 0x30, 0x60, 0x70, 0x30, 0x60,
+    0x38, 0xC0, 0x19, 0xC0, 0x0D, 0x80, 0x0F, 0x00, 0x07, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF0, 0xFF, 0xF0, 0x00, 0x60, 0x00, 0xC0,
+    0x01, 0xC0, 0x03, 0x80, 0x03, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x1C, 0x00, 0x38, 0x00, 0x30, 0x00,
+    0x60, 0x00, 0xFF, 0xF0, 0xFF, 0xF0, 0xF0, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xF0, 0xF0, 0xC0, 0xC0, 0x40, 0x60, 0x60, 0x20, 0x30,
+    0x30, 0x10, 0x18, 0x18, 0x08, 0x0C, 0x0C, 0x0C, 0x06, 0x06, 0xF0, 0xF0, 0x30, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0xF0, 0xF0, 0x0E, 0x00, 0x1F,
+    0x00, 0x39, 0x80, 0x70, 0xC0, 0xC0, 0x60, 0x80, 0x20, 0xFF, 0xC0, 0xFF, 0xC0, 0x80, 0xC0, 0x60,
+    0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F, 0xC0, 0x70, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x80, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00,
+    0xC4, 0x00, 0xFF, 0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xE1, 0x80, 0xF3, 0x80, 0xDF, 0x00, 0x06, 0x00, 0x3F, 0x80, 0x70, 0x80, 0x60, 0x00,
+    0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x60, 0x00, 0x79, 0x80, 0x1F, 0x80,
+    0x00, 0x40, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x08, 0xC0, 0x3F, 0xC0, 0x71, 0xC0, 0x60, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0xC0, 0x73, 0xC0, 0x3E, 0xC0,
+    0x04, 0x00, 0x3F, 0x80, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0x40, 0xFF, 0xE0, 0xFF, 0xC0, 0xC0, 0x00,
+    0xC0, 0x00, 0x60, 0x00, 0x38, 0xC0, 0x1F, 0xC0, 0x06, 0x1F, 0x30, 0x30, 0x30, 0xFE, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x08, 0x00, 0x3F, 0xC0, 0x71, 0xC0, 0x60, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x61, 0xC0, 0x3F, 0xC0, 0x1E, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x23, 0x80, 0x3F, 0x00, 0x80, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00,
+    0xC4, 0x00, 0xDF, 0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80,
+    0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x30, 0x30, 0x30, 0x00, 0x00, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x60, 0xE0, 0x80, 0x00, 0xC0, 0x00,
+    0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC3, 0x80, 0xC7, 0x00, 0xCC, 0x00, 0xD8, 0x00, 0xF0, 0x00,
+    0xF0, 0x00, 0xD8, 0x00, 0xCC, 0x00, 0xC6, 0x00, 0xC3, 0x00, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x04, 0x08, 0xDF, 0x3E,
+    0xE3, 0xE7, 0xC1, 0xC3, 0xC1, 0x83, 0xC1, 0x83, 0xC1, 0x83, 0xC1, 0x83, 0xC1, 0x83, 0xC1, 0x83,
+    0xC1, 0x83, 0xC1, 0x83, 0x04, 0x00, 0xDF, 0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80,
+    0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0x04, 0x00, 0x3F, 0x80,
+    0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0xC0,
+    0x73, 0x80, 0x3F, 0x00, 0x04, 0x00, 0xFF, 0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE1, 0x80, 0xF3, 0x80, 0xDF, 0x00, 0xC0, 0x00, 0xC0, 0x00,
+    0xC0, 0x00, 0xC0, 0x00, 0x08, 0x00, 0x3F, 0xC0, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0xC0, 0x73, 0xC0, 0x3E, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x04, 0xDC, 0xE0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0x0C, 0x00, 0x7F, 0x00, 0xE1, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x78, 0x00, 0x3F, 0x00, 0x03, 0x00,
+    0x01, 0x80, 0x01, 0x80, 0xC3, 0x00, 0xFE, 0x00, 0x60, 0x60, 0x60, 0xFE, 0x60, 0x60, 0x60, 0x60,
+    0x60, 0x60, 0x60, 0x60, 0x7C, 0x3E, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80,
+    0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xE7, 0x80, 0x7D, 0x80, 0xC0, 0xC0, 0xC0, 0xC0,
+    0x60, 0xC0, 0x61, 0x80, 0x61, 0x80, 0x33, 0x00, 0x33, 0x00, 0x13, 0x00, 0x1E, 0x00, 0x1E, 0x00,
+    0x0E, 0x00, 0xC3, 0x86, 0xC3, 0x86, 0x43, 0x8C, 0x66, 0xCC, 0x66, 0xCC, 0x64, 0xC8, 0x34, 0x58,
+    0x3C, 0x78, 0x3C, 0x78, 0x38, 0x70, 0x18, 0x30, 0x60, 0xC0, 0x61, 0x80, 0x33, 0x00, 0x1F, 0x00,
+    0x0E, 0x00, 0x0C, 0x00, 0x1E, 0x00, 0x33, 0x00, 0x73, 0x80, 0x61, 0x80, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x60, 0x80, 0x61, 0x80, 0x21, 0x80, 0x33, 0x00, 0x33, 0x00, 0x1A, 0x00, 0x1E, 0x00,
+    0x0E, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x70, 0x00, 0xFF, 0x80, 0x03, 0x80,
+    0x03, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x18, 0x00, 0x38, 0x00, 0x70, 0x00, 0x60, 0x00, 0xE0, 0x00,
+    0xFF, 0x80, 0x06, 0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x30, 0xE0, 0xF0, 0x30, 0x18, 0x18,
+    0x18, 0x18, 0x18, 0x18, 0x0F, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xC0, 0xF0, 0x30, 0x10, 0x10, 0x10,
+    0x10, 0x18, 0x18, 0x0E, 0x1E, 0x18, 0x10, 0x10, 0x10, 0x10, 0x30, 0x30, 0xF0, 0x3C, 0x08, 0xFF,
+    0xF8, 0x81, 0xF0, 0xC0, 0xC0, 0x00, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x04, 0x00, 0x04, 0x00, 0x0C, 0x00, 0x3F, 0x80, 0x74, 0x00, 0xC4, 0x00, 0xC4, 0x00,
+    0xC4, 0x00, 0xC4, 0x00, 0xC4, 0x00, 0xC4, 0x00, 0xE4, 0x00, 0x7D, 0x80, 0x3F, 0x00, 0x04, 0x00,
+    0x04, 0x00, 0x04, 0x00, 0x0F, 0xC0, 0x1C, 0x40, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00,
+    0x18, 0x00, 0x7F, 0x80, 0x7F, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00, 0x7F, 0xC0,
+    0xFF, 0xC0, 0x40, 0x60, 0xEE, 0xE0, 0x7F, 0xC0, 0x31, 0x80, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x31, 0x80, 0x7F, 0xC0, 0xEE, 0xE0, 0xC0, 0x60, 0xC0, 0x60, 0x60, 0x40, 0x60, 0xC0, 0x31, 0x80,
+    0x31, 0x80, 0x1B, 0x00, 0xFB, 0xE0, 0x0E, 0x00, 0x0E, 0x00, 0xFF, 0xE0, 0x0E, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00, 0x00,
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x3E, 0x62, 0x60, 0x60, 0x70, 0x7C, 0xCE, 0xC3,
+    0xC1, 0x61, 0x3B, 0x1E, 0x06, 0x03, 0x03, 0x7E, 0x7C, 0xCC, 0xCC, 0x07, 0xC0, 0x18, 0x30, 0x20,
+    0x18, 0x47, 0xCC, 0xCC, 0x44, 0x98, 0x04, 0x98, 0x02, 0x98, 0x02, 0x98, 0x02, 0x98, 0x06, 0xCC,
+    0x04, 0x47, 0xCC, 0x20, 0x18, 0x18, 0x30, 0x07, 0xC0, 0x7C, 0x06, 0x02, 0x7E, 0xC2, 0xC6, 0xC6,
+    0x7A, 0x00, 0xFE, 0x11, 0x80, 0x33, 0x00, 0x66, 0x00, 0xCC, 0x00, 0xCC, 0x00, 0x66, 0x00, 0x33,
+    0x00, 0x11, 0x80, 0xFF, 0xF8, 0xFF, 0xF8, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0x00, 0x18, 0xF8,
+    0x07, 0xC0, 0x18, 0x30, 0x20, 0x18, 0x4F, 0x8C, 0xCC, 0xC4, 0x8C, 0x64, 0x8C, 0x42, 0x8F, 0x82,
+    0x8C, 0x82, 0x8C, 0xC6, 0xCC, 0x64, 0x40, 0x2C, 0x20, 0x18, 0x18, 0x30, 0x07, 0xC0, 0xFC, 0x78,
+    0xCC, 0x84, 0x84, 0xCC, 0x78, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0xFF, 0xF8, 0xFF,
+    0xF8, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x02, 0x00, 0x00, 0x00, 0xFF, 0xF8, 0xFF, 0xF8, 0xF8,
+    0x0C, 0x0C, 0x08, 0x18, 0x30, 0x40, 0xFC, 0xF8, 0x0C, 0x0C, 0x38, 0x0C, 0x04, 0x0C, 0xFC, 0x30,
+    0x60, 0xC0, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80,
+    0xC1, 0x80, 0xC1, 0x80, 0xE7, 0xC0, 0xFE, 0xE0, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00,
+    0x3F, 0x80, 0x7D, 0x80, 0xFD, 0x80, 0xFD, 0x80, 0xFD, 0x80, 0xFD, 0x80, 0xFD, 0x80, 0x7D, 0x80,
+    0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80, 0x0D, 0x80,
+    0x0D, 0x80, 0xC0, 0xC0, 0x20, 0x10, 0x30, 0xE0, 0xE0, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0xF8,
+    0x7C, 0x46, 0xC3, 0xC3, 0xC3, 0xC2, 0x66, 0x3C, 0x00, 0xFE, 0x8C, 0x00, 0xC6, 0x00, 0x63, 0x00,
+    0x31, 0x80, 0x31, 0x80, 0x63, 0x00, 0xC6, 0x00, 0x8C, 0x00, 0xE0, 0x18, 0x00, 0x20, 0x10, 0x00,
+    0x20, 0x30, 0x00, 0x20, 0x20, 0x00, 0x20, 0x60, 0x00, 0x20, 0xC0, 0x00, 0x20, 0x80, 0x00, 0xF9,
+    0x83, 0x00, 0x01, 0x07, 0x00, 0x03, 0x09, 0x00, 0x06, 0x19, 0x00, 0x04, 0x11, 0x00, 0x0C, 0x3F,
+    0x80, 0x08, 0x03, 0x00, 0x18, 0x01, 0x00, 0xE0, 0x18, 0x00, 0x20, 0x10, 0x00, 0x20, 0x30, 0x00,
+    0x20, 0x20, 0x00, 0x20, 0x60, 0x00, 0x20, 0xC0, 0x00, 0x20, 0x84, 0x00, 0xF9, 0x9F, 0x00, 0x01,
+    0x01, 0x80, 0x03, 0x01, 0x00, 0x06, 0x03, 0x00, 0x04, 0x06, 0x00, 0x0C, 0x0C, 0x00, 0x08, 0x18,
+    0x00, 0x18, 0x1F, 0x80, 0xF8, 0x0C, 0x00, 0x0C, 0x08, 0x00, 0x0C, 0x18, 0x00, 0x38, 0x10, 0x00,
+    0x0C, 0x30, 0x00, 0x04, 0x60, 0x00, 0x0C, 0x40, 0x00, 0xFC, 0xC1, 0x80, 0x00, 0x83, 0x80, 0x01,
+    0x84, 0x80, 0x03, 0x0C, 0x80, 0x02, 0x08, 0x80, 0x06, 0x1F, 0xC0, 0x04, 0x01, 0x80, 0x0C, 0x00,
+    0x80, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x0C, 0x18, 0x38, 0x70, 0x60, 0xE0, 0x60, 0x7F, 0x3E,
+    0x06, 0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x07, 0x80, 0x07, 0x80, 0x04, 0xC0,
+    0x0C, 0xC0, 0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18, 0x60, 0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18,
+    0x60, 0x18, 0x60, 0x18, 0xC0, 0x0C, 0x01, 0x80, 0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00,
+    0x07, 0x80, 0x07, 0x80, 0x04, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18, 0x60,
+    0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18, 0x60, 0x18, 0x60, 0x18, 0xC0, 0x0C, 0x03, 0x00, 0x04, 0x80,
+    0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x07, 0x80, 0x07, 0x80, 0x04, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0,
+    0x18, 0x60, 0x18, 0x60, 0x18, 0x60, 0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18, 0x60, 0x18, 0x60, 0x18,
+    0xC0, 0x0C, 0x0E, 0x40, 0x0B, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x07, 0x80, 0x07, 0x80,
+    0x04, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18, 0x60, 0x3F, 0xF0, 0x3F, 0xF0,
+    0x70, 0x18, 0x60, 0x18, 0x60, 0x18, 0xC0, 0x0C, 0x0C, 0xC0, 0x0C, 0xC0, 0x00, 0x00, 0x00, 0x00,
+    0x03, 0x00, 0x07, 0x80, 0x07, 0x80, 0x04, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60,
+    0x18, 0x60, 0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18, 0x60, 0x18, 0x60, 0x18, 0xC0, 0x0C, 0x07, 0x80,
+    0x04, 0xC0, 0x0C, 0x40, 0x0C, 0xC0, 0x07, 0x80, 0x07, 0x80, 0x07, 0x80, 0x04, 0xC0, 0x0C, 0xC0,
+    0x0C, 0xC0, 0x18, 0x60, 0x18, 0x60, 0x18, 0x60, 0x3F, 0xF0, 0x3F, 0xF0, 0x70, 0x18, 0x60, 0x18,
+    0x60, 0x18, 0xC0, 0x0C, 0x03, 0xFF, 0xE0, 0x03, 0xFF, 0xC0, 0x06, 0x60, 0x00, 0x06, 0x60, 0x00,
+    0x0E, 0x60, 0x00, 0x0C, 0x60, 0x00, 0x0C, 0x7F, 0xC0, 0x18, 0x7F, 0xC0, 0x18, 0x60, 0x00, 0x3F,
+    0xE0, 0x00, 0x3F, 0xE0, 0x00, 0x30, 0x60, 0x00, 0x60, 0x60, 0x00, 0x60, 0x7F, 0xE0, 0xC0, 0x7F,
+    0xE0, 0x0F, 0xE0, 0x3C, 0x70, 0x30, 0x10, 0x60, 0x00, 0x60, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0x60, 0x00, 0x60, 0x00, 0x30, 0x10, 0x3C, 0x70, 0x0F, 0xE0, 0x01,
+    0x80, 0x00, 0x80, 0x01, 0x80, 0x07, 0x00, 0x18, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF,
+    0xC0, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF, 0x80, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0xC0, 0xFF, 0xC0, 0x06, 0x00, 0x0C,
+    0x00, 0x08, 0x00, 0x00, 0x00, 0xFF, 0xC0, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xFF, 0x80, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF,
+    0xC0, 0xFF, 0xC0, 0x1C, 0x00, 0x36, 0x00, 0x20, 0x00, 0x00, 0x00, 0xFF, 0xC0, 0xFF, 0x80, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0xC0, 0xFF, 0xC0, 0x33, 0x00, 0x33, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xFF, 0xC0, 0xFF, 0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF,
+    0x80, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0xC0, 0xFF, 0xC0, 0xC0,
+    0x60, 0x20, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60,
+    0x60, 0x60, 0x60, 0xC0, 0x80, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x70, 0x90, 0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60,
+    0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0xCC, 0xCC, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x3F, 0xC0, 0x3F, 0xF0, 0x30,
+    0x38, 0x30, 0x0C, 0x30, 0x0C, 0x30, 0x0E, 0x38, 0x06, 0xFF, 0x06, 0x30, 0x06, 0x30, 0x0E, 0x30,
+    0x0C, 0x30, 0x0C, 0x30, 0x38, 0x3F, 0xF0, 0x3F, 0xC0, 0x1C, 0x80, 0x37, 0x80, 0x00, 0x00, 0x00,
+    0x00, 0xE0, 0x60, 0xF0, 0x60, 0xF0, 0x60, 0xD8, 0x60, 0xD8, 0x60, 0xCC, 0x60, 0xCC, 0x60, 0xC6,
+    0x60, 0xC6, 0x60, 0xC3, 0x60, 0xC3, 0x60, 0xC1, 0xE0, 0xC1, 0xE0, 0xC0, 0xE0, 0xC0, 0xE0, 0x06,
+    0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0F, 0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60, 0x18, 0x60,
+    0x1C, 0xE0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60, 0x18, 0x30,
+    0x38, 0x3C, 0xF0, 0x0F, 0xC0, 0x01, 0x80, 0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x0F, 0xC0, 0x3C,
+    0xF0, 0x30, 0x38, 0x60, 0x18, 0x60, 0x1C, 0xE0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0,
+    0x0C, 0x60, 0x1C, 0x60, 0x18, 0x30, 0x38, 0x3C, 0xF0, 0x0F, 0xC0, 0x03, 0x80, 0x04, 0x80, 0x00,
+    0x40, 0x00, 0x00, 0x0F, 0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60, 0x18, 0x60, 0x1C, 0xE0, 0x0C, 0xC0,
+    0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60, 0x18, 0x30, 0x38, 0x3C, 0xF0, 0x0F,
+    0xC0, 0x0E, 0x40, 0x09, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x0F, 0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60,
+    0x18, 0x60, 0x1C, 0xE0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60,
+    0x18, 0x30, 0x38, 0x3C, 0xF0, 0x0F, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x0F,
+    0xC0, 0x3C, 0xF0, 0x30, 0x38, 0x60, 0x18, 0x60, 0x1C, 0xE0, 0x0C, 0xC0, 0x0C, 0xC0, 0x0C, 0xC0,
+    0x0C, 0xE0, 0x0C, 0x60, 0x1C, 0x60, 0x18, 0x30, 0x38, 0x3C, 0xF0, 0x0F, 0xC0, 0xC0, 0x60, 0xE0,
+    0xE0, 0x71, 0xC0, 0x3B, 0x80, 0x1F, 0x00, 0x0E, 0x00, 0x1F, 0x00, 0x3B, 0x80, 0x71, 0xC0, 0xE0,
+    0xE0, 0xC0, 0x60, 0x0F, 0xCC, 0x3C, 0xF8, 0x30, 0x38, 0x60, 0x38, 0x60, 0x7C, 0xE0, 0xCC, 0xC1,
+    0x8C, 0xC3, 0x0C, 0xC6, 0x0C, 0xC4, 0x0C, 0x68, 0x1C, 0x78, 0x18, 0x30, 0x38, 0x7C, 0xF0, 0xCF,
+    0xC0, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xE0, 0xE0, 0x71, 0xC0, 0x3F, 0x80, 0x02, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xE0, 0xE0, 0x71, 0xC0, 0x3F, 0x80, 0x0E, 0x00, 0x1B,
+    0x00, 0x11, 0x00, 0x00, 0x00, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xE0, 0xE0, 0x71,
+    0xC0, 0x3F, 0x80, 0x11, 0x00, 0x1B, 0x80, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0, 0x60, 0xC0,
+    0x60, 0xC0, 0x60, 0xE0, 0xE0, 0x71, 0xC0, 0x3F, 0x80, 0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xE0, 0x30, 0x60, 0x70, 0x30, 0x60, 0x38, 0xC0, 0x19, 0xC0, 0x0D, 0x80, 0x0F, 0x00, 0x07,
+    0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xFF, 0x00, 0xC3, 0x80, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1,
+    0xC0, 0xC3, 0x80, 0xFF, 0x00, 0xFC, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x1C, 0x00, 0x7F,
+    0x00, 0xE3, 0x00, 0xC1, 0x80, 0xC3, 0x80, 0xCE, 0x00, 0xCC, 0x00, 0xCC, 0x00, 0xCC, 0x00, 0xC7,
+    0x00, 0xC3, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xD1, 0xC0, 0xDF, 0x80, 0x30, 0x00, 0x18,
+    0x00, 0x0C, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F,
+    0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x03, 0x00, 0x06,
+    0x00, 0x0C, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F,
+    0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x0C, 0x00, 0x16,
+    0x00, 0x33, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F,
+    0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x39, 0x00, 0x2F,
+    0x00, 0x26, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F,
+    0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x33, 0x00, 0x33,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x3F,
+    0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E, 0xC0, 0x1E, 0x00, 0x32,
+    0x00, 0x33, 0x00, 0x33, 0x00, 0x1E, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x7F, 0x00, 0x41, 0x80, 0x00,
+    0x80, 0x00, 0xC0, 0x3F, 0xC0, 0x70, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC1, 0xC0, 0x63, 0xC0, 0x3E,
+    0xC0, 0x0C, 0x04, 0x00, 0x7F, 0x3F, 0x00, 0x41, 0xE1, 0x80, 0x00, 0xC0, 0xC0, 0x00, 0xC0, 0xC0,
+    0x3F, 0xFF, 0xC0, 0x70, 0xFF, 0xC0, 0xC0, 0xC0, 0x00, 0xC0, 0xC0, 0x00, 0xC1, 0xE0, 0x00, 0x63,
+    0x70, 0xC0, 0x3E, 0x3F, 0x80, 0x06, 0x00, 0x3F, 0x80, 0x70, 0x80, 0x60, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x60, 0x00, 0x79, 0x80, 0x1F, 0x80, 0x06, 0x00, 0x02,
+    0x00, 0x02, 0x00, 0x1E, 0x00, 0x18, 0x00, 0x0C, 0x00, 0x04, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0x40, 0xFF, 0xE0, 0xFF, 0xC0, 0xC0, 0x00, 0xC0, 0x00, 0x60,
+    0x00, 0x38, 0xC0, 0x1F, 0xC0, 0x03, 0x00, 0x06, 0x00, 0x04, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0x40, 0xFF, 0xE0, 0xFF, 0xC0, 0xC0, 0x00, 0xC0, 0x00, 0x60,
+    0x00, 0x38, 0xC0, 0x1F, 0xC0, 0x0E, 0x00, 0x0B, 0x00, 0x11, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0x40, 0xFF, 0xE0, 0xFF, 0xC0, 0xC0, 0x00, 0xC0, 0x00, 0x60,
+    0x00, 0x38, 0xC0, 0x1F, 0xC0, 0x11, 0x00, 0x19, 0x80, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0xC0, 0x60, 0xC0, 0xC0, 0x40, 0xFF, 0xE0, 0xFF, 0xC0, 0xC0, 0x00, 0xC0, 0x00, 0x60,
+    0x00, 0x38, 0xC0, 0x1F, 0xC0, 0xC0, 0x60, 0x30, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x20, 0x60, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x70, 0x58, 0xCC, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0xCC, 0xCC, 0x00, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+    0x30, 0x30, 0x30, 0x30, 0x30, 0x10, 0x00, 0x1D, 0x80, 0x1E, 0x00, 0x76, 0x00, 0x03, 0x00, 0x1F,
+    0x80, 0x3B, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x1F, 0x00, 0x39, 0x00, 0x2F, 0x00, 0x66, 0x00, 0x00, 0x00, 0x04, 0x00, 0xDF,
+    0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1,
+    0x80, 0xC1, 0x80, 0xC1, 0x80, 0x18, 0x00, 0x08, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x3F, 0x00, 0x03, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x3F, 0x00, 0x0E, 0x00, 0x1A, 0x00, 0x11, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x3F, 0x00, 0x19, 0x80, 0x3F, 0x00, 0x23, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x3F, 0x00, 0x11, 0x00, 0x33, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x3F,
+    0x80, 0x71, 0x80, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0xC0, 0x73, 0x80, 0x3F, 0x00, 0x07, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF8, 0x00,
+    0x00, 0x00, 0x00, 0x07, 0x00, 0x07, 0x00, 0x04, 0x60, 0x3F, 0xC0, 0x71, 0xC0, 0x61, 0xC0, 0xC3,
+    0xC0, 0xC6, 0xC0, 0xCC, 0x40, 0xC8, 0xC0, 0xF0, 0xC0, 0x70, 0xC0, 0x73, 0x80, 0xFF, 0x00, 0x80,
+    0x00, 0x30, 0x00, 0x10, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC1,
+    0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xE7, 0x80, 0x7D,
+    0x80, 0x06, 0x00, 0x0C, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC1,
+    0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xE7, 0x80, 0x7D,
+    0x80, 0x1C, 0x00, 0x34, 0x00, 0x22, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC1,
+    0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xE7, 0x80, 0x7D,
+    0x80, 0x22, 0x00, 0x66, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x80, 0xC1, 0x80, 0xC1,
+    0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xC1, 0x80, 0xE7, 0x80, 0x7D,
+    0x80, 0x03, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0x80, 0x61, 0x80, 0x21, 0x80, 0x33, 0x00, 0x33, 0x00, 0x1A, 0x00, 0x1E, 0x00, 0x0E, 0x00, 0x0C,
+    0x00, 0x0C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x70, 0x00, 0x80, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0xC4, 0x00, 0xFF, 0x00, 0xE3, 0x80, 0xC1, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xE1, 0x80, 0xF3, 0x80, 0xDF, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0,
+    0x00, 0x33, 0x00, 0x33, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0x60,
+    0x80, 0x61, 0x80, 0x21, 0x80, 0x33, 0x00, 0x33, 0x00, 0x1A, 0x00, 0x1E, 0x00, 0x0E, 0x00, 0x0C,
+    0x00, 0x0C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x70, 0x00, 0x18, 0x00, 0x30, 0x00, 0x60, 0x00, 0xFF,
+    0xFE, 0x60, 0x00, 0x30, 0x00, 0x18, 0x00, 0x18, 0x3C, 0x7E, 0xDF, 0x92, 0x10, 0x10, 0x10, 0x10,
+    0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x10, 0x00, 0x38, 0x00, 0x1C, 0x00, 0x0E, 0xFF, 0xFE,
+    0x00, 0x0E, 0x00, 0x1C, 0x00, 0x38, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+    0x10, 0x10, 0x92, 0xDF, 0x7E, 0x3C, 0x18, 0x00, 0x10, 0x18, 0x38, 0x30, 0x1C, 0x60, 0x0E, 0xFF,
+    0xFE, 0x60, 0x0E, 0x30, 0x1C, 0x18, 0x38, 0x00, 0x10, 0x18, 0x3C, 0x7E, 0xDF, 0x92, 0x10, 0x10,
+    0x10, 0x10, 0x10, 0x92, 0xDF, 0x7E, 0x3C, 0x18, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0xF8, 0xFF, 0xF8,
+    0xFF, 0xF8, 0xFF, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xEE, 0xE0, 0xCE, 0xE0, 0xCE, 0xE0, 0xEE, 0xE0, 0xEE, 0xE0, 0xCC, 0xE0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0x00, 0x00, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0x00, 0x00, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0x9B, 0x60, 0x93, 0x60,
+    0x93, 0x60, 0x9B, 0x60, 0x9B, 0x60, 0x92, 0x60, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0,
+    0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0x00, 0x00, 0xE0, 0xE0, 0xE0, 0xE0, 0x00, 0x00, 0xE0, 0xE0, 0xE0, 0xE0, 0x00, 0x00,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0x7F, 0xFF, 0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF,
+    0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE, 0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0xFF, 0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+    0x07, 0x07, 0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0x7E, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0xFF, 0xFF, 0x7E, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xFF, 0xFF, 0xFF, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE, 0xFE,
+    0xFE, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF, 0x07, 0x07,
+    0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0xFF,
+    0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF,
+    0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFF,
+    0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0xFF, 0xFF, 0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFF,
+    0xFF, 0xFE, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE,
+    0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x07, 0x07, 0x07, 0x07,
+    0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFF,
+    0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+    0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+    0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07,
+    0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0x07, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFE, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFE, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x03, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8,
+    0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0xFF, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF8,
+    0xFF, 0xF8, 0xFF, 0xF0, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8,
+    0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFE, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFE, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x03, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8,
+    0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0xFF, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF8,
+    0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFE, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFE, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFF, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x07, 0xF0,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0xFF, 0xF0,
+    0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00, 0x07, 0x00,
+    0x07, 0x00, 0x07, 0x00, 0xFB, 0xE0, 0xF3, 0xE0, 0xF3, 0xE0, 0xFB, 0xE0, 0xFB, 0xE0, 0xF3, 0xE0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x00, 0x00, 0x00, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0x00, 0x00, 0x00, 0x00,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xF0, 0xFF, 0xF8, 0x00, 0x00, 0x00, 0x00,
+    0xFF, 0xF8, 0xFF, 0xF0, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98,
+    0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x98, 0x7E, 0xFF, 0xC0, 0xC0,
+    0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFF, 0x80, 0xFF, 0x00, 0x98,
+    0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98,
+    0x00, 0x98, 0x00, 0x98, 0x00, 0xFF, 0x00, 0xFF, 0x80, 0x80, 0x00, 0x80, 0x00, 0x9F, 0x80, 0x9F,
+    0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98,
+    0x00, 0x98, 0x00, 0xFE, 0xFE, 0x06, 0x06, 0xFE, 0xFE, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0xFF, 0x80, 0xFF, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xFF, 0x00, 0xFF, 0x80,
+    0x01, 0x80, 0x01, 0x80, 0xF9, 0x80, 0xF9, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xFE, 0xFF, 0xC0, 0xC0, 0xFF, 0x7E, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98,
+    0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0xFF,
+    0x80, 0xFF, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98,
+    0x00, 0x98, 0x00, 0x98, 0x00, 0x9F, 0x00, 0x9F, 0x80, 0x80, 0x00, 0xC0, 0x00, 0xFF, 0x80, 0xFF,
+    0x00, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE, 0x06, 0x06, 0xFE, 0xFE,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xFF, 0x80, 0xFF, 0x00, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xF9, 0x80, 0xF9, 0x80,
+    0x01, 0x80, 0x01, 0x80, 0xFF, 0x80, 0xFF, 0x00, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xFE, 0xFF, 0xC0, 0xC0, 0xFF, 0xFE, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00,
+    0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x9F, 0x80, 0x9F, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00,
+    0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00,
+    0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00,
+    0x98, 0x00, 0x9F, 0x00, 0x9F, 0x80, 0x80, 0x00, 0x80, 0x00, 0x9F, 0x80, 0x9F, 0x00, 0x98, 0x00,
+    0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00, 0x98, 0x00,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFE, 0xFE, 0x06, 0x06, 0xFE, 0xFE, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xF9, 0x80,
+    0xF9, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xF9, 0x80, 0xF9, 0x80, 0x01, 0x80,
+    0x01, 0x80, 0xF9, 0x80, 0xF9, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xFF, 0xF0, 0xFF, 0xF8, 0x00, 0x00, 0x00, 0x00,
+    0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0xFF, 0xF0, 0xFF, 0xF8, 0x00, 0x00, 0x00, 0x00, 0xF9, 0xF8, 0xF9, 0xF0, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0xFF, 0xF0, 0xFF, 0xF8, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0xFF, 0xF8, 0xFF, 0xF0, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xF9, 0xF0, 0xF9, 0xF8, 0x00, 0x00, 0x00, 0x00,
+    0xFF, 0xF8, 0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF0, 0xFF, 0xF8, 0x06, 0x00, 0x06, 0x00, 0xFF, 0xF8,
+    0xFF, 0xF0, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00, 0x06, 0x00,
+    0x06, 0x00, 0x06, 0x00, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xFF, 0xF8, 0xFF, 0xF0, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0xF9, 0xF0, 0xF9, 0xF8, 0x00, 0x00, 0x00, 0x00, 0xF9, 0xF8,
+    0xF9, 0xF0, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80, 0x09, 0x80,
+    0x09, 0x80, 0x09, 0x80, 0x3F, 0x7E, 0x40, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xF8, 0xFC, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06,
+    0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0xFC, 0xF8, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0x60, 0x7F, 0x1E, 0x00, 0x0C, 0x00, 0x08, 0x00, 0x18, 0x00, 0x10,
+    0x00, 0x30, 0x00, 0x20, 0x00, 0x60, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x80, 0x01, 0x80, 0x01, 0x00,
+    0x03, 0x00, 0x06, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x0C, 0x00, 0x18, 0x00, 0x18, 0x00, 0x30, 0x00,
+    0x30, 0x00, 0x60, 0x00, 0x60, 0x00, 0xC0, 0x00, 0xC0, 0x00, 0x60, 0x00, 0x60, 0x00, 0x30, 0x00,
+    0x30, 0x00, 0x18, 0x00, 0x08, 0x00, 0x0C, 0x00, 0x04, 0x00, 0x06, 0x00, 0x02, 0x00, 0x03, 0x00,
+    0x01, 0x00, 0x01, 0x80, 0x00, 0x80, 0x00, 0xC0, 0x00, 0x40, 0x00, 0x60, 0x00, 0x20, 0x00, 0x30,
+    0x00, 0x18, 0x00, 0x18, 0x00, 0x0C, 0x00, 0x0C, 0xC0, 0x0C, 0x60, 0x08, 0x60, 0x18, 0x30, 0x10,
+    0x30, 0x30, 0x18, 0x20, 0x08, 0x60, 0x0C, 0x40, 0x04, 0xC0, 0x06, 0x80, 0x03, 0x80, 0x03, 0x00,
+    0x03, 0x00, 0x07, 0x80, 0x06, 0x80, 0x0C, 0xC0, 0x0C, 0x40, 0x18, 0x60, 0x18, 0x20, 0x30, 0x30,
+    0x30, 0x18, 0x60, 0x18, 0x60, 0x0C, 0xC0, 0x0C, 0xFC, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xFC, 0xFC, 0xFC, 0xFC, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xFE, 0xFE, 0xFE, 0xFC, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0x01, 0xF8, 0xFF, 0xF8, 0xFF, 0xF8, 0x01, 0xF0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xFC, 0x00, 0xFF, 0xF8, 0xFF, 0xF0, 0xFC, 0x00, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xE0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0,
+    0xC0, 0xC0, 0xC0, 0xC0, 0x00, 0x40, 0x00, 0x00, 0xE0, 0x00, 0x01, 0xF0, 0x00, 0x03, 0xF8, 0x00,
+    0x06, 0x04, 0x00, 0x0C, 0x62, 0x00, 0x1F, 0xF3, 0x00, 0x3F, 0xF3, 0x80, 0x7F, 0xF3, 0xC0, 0xFF,
+    0xE7, 0xE0, 0xFF, 0xCF, 0xF0, 0xFF, 0x9F, 0xE0, 0x7F, 0x9F, 0xC0, 0x3F, 0x9F, 0x80, 0x1F, 0x9F,
+    0x00, 0x0F, 0xFE, 0x00, 0x07, 0xBC, 0x00, 0x03, 0x98, 0x00, 0x01, 0x90, 0x00, 0x00, 0xE0, 0x00,
+];
+
+/// Kerning pairs, sorted by (left, right)
+static KERNING: [KernPair; 615] = [
+    KernPair::new('-', 'B', -1),
+    KernPair::new('-', 'G', 1),
+    KernPair::new('-', 'J', 1),
+    KernPair::new('-', 'O', 1),
+    KernPair::new('-', 'Q', 1),
+    KernPair::new('-', 'T', -2),
+    KernPair::new('-', 'V', -1),
+    KernPair::new('-', 'W', -1),
+    KernPair::new('-', 'X', -1),
+    KernPair::new('-', 'Y', -2),
+    KernPair::new('-', 'v', -1),
+    KernPair::new('-', '\u{D2}', 1),
+    KernPair::new('-', '\u{D3}', 1),
+    KernPair::new('-', '\u{D4}', 1),
+    KernPair::new('-', '\u{D5}', 1),
+    KernPair::new('-', '\u{D6}', 1),
+    KernPair::new('-', '\u{DD}', -2),
+    KernPair::new('A', 'A', 1),
+    KernPair::new('A', 'T', -2),
+    KernPair::new('A', 'V', -1),
+    KernPair::new('A', 'W', -1),
+    KernPair::new('A', 'Y', -2),
+    KernPair::new('A', 'f', -1),
+    KernPair::new('A', 'v', -1),
+    KernPair::new('A', 'w', -1),
+    KernPair::new('A', 'y', -1),
+    KernPair::new('A', '\u{AB}', -1),
+    KernPair::new('A', '\u{C0}', 1),
+    KernPair::new('A', '\u{C1}', 1),
+    KernPair::new('A', '\u{C2}', 1),
+    KernPair::new('A', '\u{C3}', 1),
+    KernPair::new('A', '\u{C4}', 1),
+    KernPair::new('A', '\u{DD}', -2),
+    KernPair::new('A', '\u{FD}', -1),
+    KernPair::new('A', '\u{FF}', -1),
+    KernPair::new('B', 'V', -1),
+    KernPair::new('B', 'W', -1),
+    KernPair::new('B', 'Y', -1),
+    KernPair::new('B', '\u{AB}', -1),
+    KernPair::new('B', '\u{DD}', -1),
+    KernPair::new('D', 'Y', -1),
+    KernPair::new('D', '\u{DD}', -1),
+    KernPair::new('F', '.', -3),
+    KernPair::new('F', ':', -2),
+    KernPair::new('F', 'A', -2),
+    KernPair::new('F', 'a', -2),
+    KernPair::new('F', 'e', -1),
+    KernPair::new('F', 'i', -1),
+    KernPair::new('F', 'o', -1),
+    KernPair::new('F', 'r', -1),
+    KernPair::new('F', 'u', -1),
+    KernPair::new('F', 'y', -2),
+    KernPair::new('F', '\u{C0}', -2),
+    KernPair::new('F', '\u{C1}', -2),
+    KernPair::new('F', '\u{C2}', -2),
+    KernPair::new('F', '\u{C3}', -2),
+    KernPair::new('F', '\u{C4}', -2),
+    KernPair::new('F', '\u{E0}', -2),
+    KernPair::new('F', '\u{E1}', -2),
+    KernPair::new('F', '\u{E2}', -2),
+    KernPair::new('F', '\u{E3}', -2),
+    KernPair::new('F', '\u{E4}', -2),
+    KernPair::new('F', '\u{E5}', -2),
+    KernPair::new('F', '\u{E8}', -1),
+    KernPair::new('F', '\u{E9}', -1),
+    KernPair::new('F', '\u{EA}', -1),
+    KernPair::new('F', '\u{EB}', -1),
+    KernPair::new('F', '\u{F2}', -1),
+    KernPair::new('F', '\u{F3}', -1),
+    KernPair::new('F', '\u{F4}', -1),
+    KernPair::new('F', '\u{F5}', -1),
+    KernPair::new('F', '\u{F6}', -1),
+    KernPair::new('F', '\u{F9}', -1),
+    KernPair::new('F', '\u{FA}', -1),
+    KernPair::new('F', '\u{FB}', -1),
+    KernPair::new('F', '\u{FC}', -1),
+    KernPair::new('F', '\u{FD}', -2),
+    KernPair::new('F', '\u{FF}', -2),
+    KernPair::new('G', 'T', -1),
+    KernPair::new('G', 'Y', -1),
+    KernPair::new('G', '\u{DD}', -1),
+    KernPair::new('J', '-', -1),
+    KernPair::new('K', '-', -2),
+    KernPair::new('K', 'C', -1),
+    KernPair::new('K', 'O', -1),
+    KernPair::new('K', 'T', -2),
+    KernPair::new('K', 'U', -1),
+    KernPair::new('K', 'W', -1),
+    KernPair::new('K', 'Y', -1),
+    KernPair::new('K', 'e', -1),
+    KernPair::new('K', 'o', -1),
+    KernPair::new('K', 'u', -1),
+    KernPair::new('K', 'y', -1),
+    KernPair::new('K', '\u{AB}', -1),
+    KernPair::new('K', '\u{C7}', -1),
+    KernPair::new('K', '\u{D2}', -1),
+    KernPair::new('K', '\u{D3}', -1),
+    KernPair::new('K', '\u{D4}', -1),
+    KernPair::new('K', '\u{D5}', -1),
+    KernPair::new('K', '\u{D6}', -1),
+    KernPair::new('K', '\u{D9}', -1),
+    KernPair::new('K', '\u{DA}', -1),
+    KernPair::new('K', '\u{DB}', -1),
+    KernPair::new('K', '\u{DC}', -1),
+    KernPair::new('K', '\u{DD}', -1),
+    KernPair::new('K', '\u{E8}', -1),
+    KernPair::new('K', '\u{E9}', -1),
+    KernPair::new('K', '\u{EA}', -1),
+    KernPair::new('K', '\u{EB}', -1),
+    KernPair::new('K', '\u{F2}', -1),
+    KernPair::new('K', '\u{F3}', -1),
+    KernPair::new('K', '\u{F4}', -1),
+    KernPair::new('K', '\u{F5}', -1),
+    KernPair::new('K', '\u{F6}', -1),
+    KernPair::new('K', '\u{F9}', -1),
+    KernPair::new('K', '\u{FA}', -1),
+    KernPair::new('K', '\u{FB}', -1),
+    KernPair::new('K', '\u{FC}', -1),
+    KernPair::new('K', '\u{FD}', -1),
+    KernPair::new('K', '\u{FF}', -1),
+    KernPair::new('L', 'O', -1),
+    KernPair::new('L', 'T', -3),
+    KernPair::new('L', 'U', -1),
+    KernPair::new('L', 'V', -2),
+    KernPair::new('L', 'W', -2),
+    KernPair::new('L', 'Y', -3),
+    KernPair::new('L', 'y', -2),
+    KernPair::new('L', '\u{D2}', -1),
+    KernPair::new('L', '\u{D3}', -1),
+    KernPair::new('L', '\u{D4}', -1),
+    KernPair::new('L', '\u{D5}', -1),
+    KernPair::new('L', '\u{D6}', -1),
+    KernPair::new('L', '\u{D9}', -1),
+    KernPair::new('L', '\u{DA}', -1),
+    KernPair::new('L', '\u{DB}', -1),
+    KernPair::new('L', '\u{DC}', -1),
+    KernPair::new('L', '\u{DD}', -3),
+    KernPair::new('L', '\u{FD}', -2),
+    KernPair::new('L', '\u{FF}', -2),
+    KernPair::new('O', '-', 1),
+    KernPair::new('O', '.', -1),
+    KernPair::new('O', 'X', -1),
+    KernPair::new('O', 'Y', -1),
+    KernPair::new('O', '\u{DD}', -1),
+    KernPair::new('P', '.', -3),
+    KernPair::new('P', 'A', -1),
+    KernPair::new('P', 'a', -1),
+    KernPair::new('P', 'e', -1),
+    KernPair::new('P', 'o', -1),
+    KernPair::new('P', '\u{C0}', -1),
+    KernPair::new('P', '\u{C1}', -1),
+    KernPair::new('P', '\u{C2}', -1),
+    KernPair::new('P', '\u{C3}', -1),
+    KernPair::new('P', '\u{C4}', -1),
+    KernPair::new('P', '\u{E0}', -1),
+    KernPair::new('P', '\u{E1}', -1),
+    KernPair::new('P', '\u{E2}', -1),
+    KernPair::new('P', '\u{E3}', -1),
+    KernPair::new('P', '\u{E4}', -1),
+    KernPair::new('P', '\u{E5}', -1),
+    KernPair::new('P', '\u{E8}', -1),
+    KernPair::new('P', '\u{E9}', -1),
+    KernPair::new('P', '\u{EA}', -1),
+    KernPair::new('P', '\u{EB}', -1),
+    KernPair::new('P', '\u{F2}', -1),
+    KernPair::new('P', '\u{F3}', -1),
+    KernPair::new('P', '\u{F4}', -1),
+    KernPair::new('P', '\u{F5}', -1),
+    KernPair::new('P', '\u{F6}', -1),
+    KernPair::new('Q', '-', 1),
+    KernPair::new('R', '-', -1),
+    KernPair::new('R', '.', -1),
+    KernPair::new('R', ':', -1),
+    KernPair::new('R', 'A', -1),
+    KernPair::new('R', 'C', -1),
+    KernPair::new('R', 'T', -1),
+    KernPair::new('R', 'V', -1),
+    KernPair::new('R', 'W', -1),
+    KernPair::new('R', 'Y', -1),
+    KernPair::new('R', 'e', -1),
+    KernPair::new('R', 'o', -1),
+    KernPair::new('R', 'u', -1),
+    KernPair::new('R', 'y', -1),
+    KernPair::new('R', '\u{AB}', -1),
+    KernPair::new('R', '\u{C0}', -1),
+    KernPair::new('R', '\u{C1}', -1),
+    KernPair::new('R', '\u{C2}', -1),
+    KernPair::new('R', '\u{C3}', -1),
+    KernPair::new('R', '\u{C4}', -1),
+    KernPair::new('R', '\u{C7}', -1),
+    KernPair::new('R', '\u{DD}', -1),
+    KernPair::new('R', '\u{E8}', -1),
+    KernPair::new('R', '\u{E9}', -1),
+    KernPair::new('R', '\u{EA}', -1),
+    KernPair::new('R', '\u{EB}', -1),
+    KernPair::new('R', '\u{F2}', -1),
+    KernPair::new('R', '\u{F3}', -1),
+    KernPair::new('R', '\u{F4}', -1),
+    KernPair::new('R', '\u{F5}', -1),
+    KernPair::new('R', '\u{F6}', -1),
+    KernPair::new('R', '\u{F9}', -1),
+    KernPair::new('R', '\u{FA}', -1),
+    KernPair::new('R', '\u{FB}', -1),
+    KernPair::new('R', '\u{FC}', -1),
+    KernPair::new('R', '\u{FD}', -1),
+    KernPair::new('R', '\u{FF}', -1),
+    KernPair::new('T', '-', -2),
+    KernPair::new('T', '.', -2),
+    KernPair::new('T', ':', -2),
+    KernPair::new('T', 'A', -2),
+    KernPair::new('T', 'C', -1),
+    KernPair::new('T', 'a', -3),
+    KernPair::new('T', 'c', -4),
+    KernPair::new('T', 'e', -4),
+    KernPair::new('T', 'i', -1),
+    KernPair::new('T', 'o', -4),
+    KernPair::new('T', 'r', -3),
+    KernPair::new('T', 's', -3),
+    KernPair::new('T', 'u', -3),
+    KernPair::new('T', 'w', -3),
+    KernPair::new('T', 'y', -3),
+    KernPair::new('T', '\u{AB}', -2),
+    KernPair::new('T', '\u{BB}', -1),
+    KernPair::new('T', '\u{C0}', -2),
+    KernPair::new('T', '\u{C1}', -2),
+    KernPair::new('T', '\u{C2}', -2),
+    KernPair::new('T', '\u{C3}', -2),
+    KernPair::new('T', '\u{C4}', -2),
+    KernPair::new('T', '\u{C7}', -1),
+    KernPair::new('T', '\u{E0}', -2),
+    KernPair::new('T', '\u{E1}', -3),
+    KernPair::new('T', '\u{E2}', -2),
+    KernPair::new('T', '\u{E3}', -2),
+    KernPair::new('T', '\u{E4}', -2),
+    KernPair::new('T', '\u{E5}', -2),
+    KernPair::new('T', '\u{E7}', -4),
+    KernPair::new('T', '\u{E8}', -3),
+    KernPair::new('T', '\u{E9}', -4),
+    KernPair::new('T', '\u{EA}', -3),
+    KernPair::new('T', '\u{EB}', -3),
+    KernPair::new('T', '\u{F2}', -3),
+    KernPair::new('T', '\u{F3}', -4),
+    KernPair::new('T', '\u{F4}', -3),
+    KernPair::new('T', '\u{F5}', -3),
+    KernPair::new('T', '\u{F6}', -3),
+    KernPair::new('T', '\u{F9}', -3),
+    KernPair::new('T', '\u{FA}', -3),
+    KernPair::new('T', '\u{FB}', -3),
+    KernPair::new('T', '\u{FC}', -3),
+    KernPair::new('T', '\u{FD}', -3),
+    KernPair::new('T', '\u{FF}', -3),
+    KernPair::new('V', '-', -1),
+    KernPair::new('V', '.', -3),
+    KernPair::new('V', ':', -2),
+    KernPair::new('V', 'A', -1),
+    KernPair::new('V', 'a', -2),
+    KernPair::new('V', 'e', -2),
+    KernPair::new('V', 'o', -2),
+    KernPair::new('V', 'u', -1),
+    KernPair::new('V', 'y', -1),
+    KernPair::new('V', '\u{AB}', -2),
+    KernPair::new('V', '\u{BB}', -1),
+    KernPair::new('V', '\u{C0}', -1),
+    KernPair::new('V', '\u{C1}', -1),
+    KernPair::new('V', '\u{C2}', -1),
+    KernPair::new('V', '\u{C3}', -1),
+    KernPair::new('V', '\u{C4}', -1),
+    KernPair::new('V', '\u{E0}', -2),
+    KernPair::new('V', '\u{E1}', -2),
+    KernPair::new('V', '\u{E2}', -2),
+    KernPair::new('V', '\u{E3}', -2),
+    KernPair::new('V', '\u{E4}', -2),
+    KernPair::new('V', '\u{E5}', -2),
+    KernPair::new('V', '\u{E8}', -2),
+    KernPair::new('V', '\u{E9}', -2),
+    KernPair::new('V', '\u{EA}', -2),
+    KernPair::new('V', '\u{EB}', -2),
+    KernPair::new('V', '\u{F2}', -2),
+    KernPair::new('V', '\u{F3}', -2),
+    KernPair::new('V', '\u{F4}', -2),
+    KernPair::new('V', '\u{F5}', -2),
+    KernPair::new('V', '\u{F6}', -2),
+    KernPair::new('V', '\u{F9}', -1),
+    KernPair::new('V', '\u{FA}', -1),
+    KernPair::new('V', '\u{FB}', -1),
+    KernPair::new('V', '\u{FC}', -1),
+    KernPair::new('V', '\u{FD}', -1),
+    KernPair::new('V', '\u{FF}', -1),
+    KernPair::new('W', '-', -1),
+    KernPair::new('W', '.', -2),
+    KernPair::new('W', ':', -1),
+    KernPair::new('W', 'A', -1),
+    KernPair::new('W', 'a', -1),
+    KernPair::new('W', 'e', -1),
+    KernPair::new('W', 'o', -1),
+    KernPair::new('W', 'r', -1),
+    KernPair::new('W', 'u', -1),
+    KernPair::new('W', '\u{AB}', -1),
+    KernPair::new('W', '\u{C0}', -1),
+    KernPair::new('W', '\u{C1}', -1),
+    KernPair::new('W', '\u{C2}', -1),
+    KernPair::new('W', '\u{C3}', -1),
+    KernPair::new('W', '\u{C4}', -1),
+    KernPair::new('W', '\u{E0}', -1),
+    KernPair::new('W', '\u{E1}', -1),
+    KernPair::new('W', '\u{E2}', -1),
+    KernPair::new('W', '\u{E3}', -1),
+    KernPair::new('W', '\u{E4}', -1),
+    KernPair::new('W', '\u{E5}', -1),
+    KernPair::new('W', '\u{E8}', -1),
+    KernPair::new('W', '\u{E9}', -1),
+    KernPair::new('W', '\u{EA}', -1),
+    KernPair::new('W', '\u{EB}', -1),
+    KernPair::new('W', '\u{F2}', -1),
+    KernPair::new('W', '\u{F3}', -1),
+    KernPair::new('W', '\u{F4}', -1),
+    KernPair::new('W', '\u{F5}', -1),
+    KernPair::new('W', '\u{F6}', -1),
+    KernPair::new('W', '\u{F9}', -1),
+    KernPair::new('W', '\u{FA}', -1),
+    KernPair::new('W', '\u{FB}', -1),
+    KernPair::new('W', '\u{FC}', -1),
+    KernPair::new('X', '-', -1),
+    KernPair::new('X', 'C', -1),
+    KernPair::new('X', 'O', -1),
+    KernPair::new('X', 'e', -1),
+    KernPair::new('X', '\u{AB}', -1),
+    KernPair::new('X', '\u{C7}', -1),
+    KernPair::new('X', '\u{D2}', -1),
+    KernPair::new('X', '\u{D3}', -1),
+    KernPair::new('X', '\u{D4}', -1),
+    KernPair::new('X', '\u{D5}', -1),
+    KernPair::new('X', '\u{D6}', -1),
+    KernPair::new('X', '\u{E8}', -1),
+    KernPair::new('X', '\u{E9}', -1),
+    KernPair::new('X', '\u{EA}', -1),
+    KernPair::new('X', '\u{EB}', -1),
+    KernPair::new('Y', '-', -2),
+    KernPair::new('Y', '.', -4),
+    KernPair::new('Y', ':', -3),
+    KernPair::new('Y', 'A', -2),
+    KernPair::new('Y', 'C', -1),
+    KernPair::new('Y', 'O', -1),
+    KernPair::new('Y', 'a', -3),
+    KernPair::new('Y', 'e', -3),
+    KernPair::new('Y', 'i', -1),
+    KernPair::new('Y', 'o', -3),
+    KernPair::new('Y', 'u', -2),
+    KernPair::new('Y', '\u{AB}', -2),
+    KernPair::new('Y', '\u{BB}', -1),
+    KernPair::new('Y', '\u{C0}', -2),
+    KernPair::new('Y', '\u{C1}', -2),
+    KernPair::new('Y', '\u{C2}', -2),
+    KernPair::new('Y', '\u{C3}', -2),
+    KernPair::new('Y', '\u{C4}', -2),
+    KernPair::new('Y', '\u{C7}', -1),
+    KernPair::new('Y', '\u{D2}', -1),
+    KernPair::new('Y', '\u{D3}', -1),
+    KernPair::new('Y', '\u{D4}', -1),
+    KernPair::new('Y', '\u{D5}', -1),
+    KernPair::new('Y', '\u{D6}', -1),
+    KernPair::new('Y', '\u{E0}', -3),
+    KernPair::new('Y', '\u{E1}', -3),
+    KernPair::new('Y', '\u{E2}', -3),
+    KernPair::new('Y', '\u{E3}', -3),
+    KernPair::new('Y', '\u{E4}', -3),
+    KernPair::new('Y', '\u{E5}', -3),
+    KernPair::new('Y', '\u{E8}', -3),
+    KernPair::new('Y', '\u{E9}', -3),
+    KernPair::new('Y', '\u{EA}', -3),
+    KernPair::new('Y', '\u{EB}', -3),
+    KernPair::new('Y', '\u{F2}', -3),
+    KernPair::new('Y', '\u{F3}', -3),
+    KernPair::new('Y', '\u{F4}', -3),
+    KernPair::new('Y', '\u{F5}', -3),
+    KernPair::new('Y', '\u{F6}', -3),
+    KernPair::new('Y', '\u{F9}', -2),
+    KernPair::new('Y', '\u{FA}', -2),
+    KernPair::new('Y', '\u{FB}', -2),
+    KernPair::new('Y', '\u{FC}', -2),
+    KernPair::new('f', '-', -1),
+    KernPair::new('f', '.', -1),
+    KernPair::new('f', ':', -1),
+    KernPair::new('f', '\u{AB}', -1),
+    KernPair::new('k', 'e', -1),
+    KernPair::new('k', 'o', -1),
+    KernPair::new('k', 'u', -1),
+    KernPair::new('k', 'y', -1),
+    KernPair::new('k', '\u{E8}', -1),
+    KernPair::new('k', '\u{E9}', -1),
+    KernPair::new('k', '\u{EA}', -1),
+    KernPair::new('k', '\u{EB}', -1),
+    KernPair::new('k', '\u{F2}', -1),
+    KernPair::new('k', '\u{F3}', -1),
+    KernPair::new('k', '\u{F4}', -1),
+    KernPair::new('k', '\u{F5}', -1),
+    KernPair::new('k', '\u{F6}', -1),
+    KernPair::new('k', '\u{F9}', -1),
+    KernPair::new('k', '\u{FA}', -1),
+    KernPair::new('k', '\u{FB}', -1),
+    KernPair::new('k', '\u{FC}', -1),
+    KernPair::new('k', '\u{FD}', -1),
+    KernPair::new('k', '\u{FF}', -1),
+    KernPair::new('o', 'x', -1),
+    KernPair::new('r', '-', -1),
+    KernPair::new('r', '.', -2),
+    KernPair::new('r', 'x', -1),
+    KernPair::new('r', '\u{AB}', -1),
+    KernPair::new('v', '-', -1),
+    KernPair::new('v', '.', -2),
+    KernPair::new('v', ':', -1),
+    KernPair::new('w', '.', -2),
+    KernPair::new('w', ':', -1),
+    KernPair::new('x', 'e', -1),
+    KernPair::new('x', 'o', -1),
+    KernPair::new('x', '\u{E8}', -1),
+    KernPair::new('x', '\u{E9}', -1),
+    KernPair::new('x', '\u{EA}', -1),
+    KernPair::new('x', '\u{EB}', -1),
+    KernPair::new('x', '\u{F2}', -1),
+    KernPair::new('x', '\u{F3}', -1),
+    KernPair::new('x', '\u{F4}', -1),
+    KernPair::new('x', '\u{F5}', -1),
+    KernPair::new('x', '\u{F6}', -1),
+    KernPair::new('y', '.', -3),
+    KernPair::new('y', ':', -1),
+    KernPair::new('\u{AB}', 'T', -1),
+    KernPair::new('\u{AB}', 'V', -1),
+    KernPair::new('\u{AB}', 'Y', -1),
+    KernPair::new('\u{AB}', '\u{C6}', 2),
+    KernPair::new('\u{AB}', '\u{DD}', -1),
+    KernPair::new('\u{BB}', 'A', -1),
+    KernPair::new('\u{BB}', 'B', -1),
+    KernPair::new('\u{BB}', 'T', -2),
+    KernPair::new('\u{BB}', 'V', -2),
+    KernPair::new('\u{BB}', 'W', -1),
+    KernPair::new('\u{BB}', 'X', -1),
+    KernPair::new('\u{BB}', 'Y', -2),
+    KernPair::new('\u{BB}', '\u{C0}', -1),
+    KernPair::new('\u{BB}', '\u{C1}', -1),
+    KernPair::new('\u{BB}', '\u{C2}', -1),
+    KernPair::new('\u{BB}', '\u{C3}', -1),
+    KernPair::new('\u{BB}', '\u{C4}', -1),
+    KernPair::new('\u{BB}', '\u{DD}', -2),
+    KernPair::new('\u{C0}', 'A', 1),
+    KernPair::new('\u{C0}', 'T', -2),
+    KernPair::new('\u{C0}', 'V', -1),
+    KernPair::new('\u{C0}', 'W', -1),
+    KernPair::new('\u{C0}', 'Y', -2),
+    KernPair::new('\u{C0}', 'f', -1),
+    KernPair::new('\u{C0}', 'v', -1),
+    KernPair::new('\u{C0}', 'w', -1),
+    KernPair::new('\u{C0}', 'y', -1),
+    KernPair::new('\u{C0}', '\u{AB}', -1),
+    KernPair::new('\u{C0}', '\u{C0}', 1),
+    KernPair::new('\u{C0}', '\u{C1}', 1),
+    KernPair::new('\u{C0}', '\u{C2}', 1),
+    KernPair::new('\u{C0}', '\u{C3}', 1),
+    KernPair::new('\u{C0}', '\u{C4}', 1),
+    KernPair::new('\u{C0}', '\u{DD}', -2),
+    KernPair::new('\u{C0}', '\u{FD}', -1),
+    KernPair::new('\u{C0}', '\u{FF}', -1),
+    KernPair::new('\u{C1}', 'A', 1),
+    KernPair::new('\u{C1}', 'T', -2),
+    KernPair::new('\u{C1}', 'V', -1),
+    KernPair::new('\u{C1}', 'W', -1),
+    KernPair::new('\u{C1}', 'Y', -2),
+    KernPair::new('\u{C1}', 'f', -1),
+    KernPair::new('\u{C1}', 'v', -1),
+    KernPair::new('\u{C1}', 'w', -1),
+    KernPair::new('\u{C1}', 'y', -1),
+    KernPair::new('\u{C1}', '\u{AB}', -1),
+    KernPair::new('\u{C1}', '\u{C0}', 1),
+    KernPair::new('\u{C1}', '\u{C1}', 1),
+    KernPair::new('\u{C1}', '\u{C2}', 1),
+    KernPair::new('\u{C1}', '\u{C3}', 1),
+    KernPair::new('\u{C1}', '\u{C4}', 1),
+    KernPair::new('\u{C1}', '\u{DD}', -2),
+    KernPair::new('\u{C1}', '\u{FD}', -1),
+    KernPair::new('\u{C1}', '\u{FF}', -1),
+    KernPair::new('\u{C2}', 'A', 1),
+    KernPair::new('\u{C2}', 'T', -2),
+    KernPair::new('\u{C2}', 'V', -1),
+    KernPair::new('\u{C2}', 'W', -1),
+    KernPair::new('\u{C2}', 'Y', -2),
+    KernPair::new('\u{C2}', 'f', -1),
+    KernPair::new('\u{C2}', 'v', -1),
+    KernPair::new('\u{C2}', 'w', -1),
+    KernPair::new('\u{C2}', 'y', -1),
+    KernPair::new('\u{C2}', '\u{AB}', -1),
+    KernPair::new('\u{C2}', '\u{C0}', 1),
+    KernPair::new('\u{C2}', '\u{C1}', 1),
+    KernPair::new('\u{C2}', '\u{C2}', 1),
+    KernPair::new('\u{C2}', '\u{C3}', 1),
+    KernPair::new('\u{C2}', '\u{C4}', 1),
+    KernPair::new('\u{C2}', '\u{DD}', -2),
+    KernPair::new('\u{C2}', '\u{FD}', -1),
+    KernPair::new('\u{C2}', '\u{FF}', -1),
+    KernPair::new('\u{C3}', 'A', 1),
+    KernPair::new('\u{C3}', 'T', -2),
+    KernPair::new('\u{C3}', 'V', -1),
+    KernPair::new('\u{C3}', 'W', -1),
+    KernPair::new('\u{C3}', 'Y', -2),
+    KernPair::new('\u{C3}', 'f', -1),
+    KernPair::new('\u{C3}', 'v', -1),
+    KernPair::new('\u{C3}', 'w', -1),
+    KernPair::new('\u{C3}', 'y', -1),
+    KernPair::new('\u{C3}', '\u{AB}', -1),
+    KernPair::new('\u{C3}', '\u{C0}', 1),
+    KernPair::new('\u{C3}', '\u{C1}', 1),
+    KernPair::new('\u{C3}', '\u{C2}', 1),
+    KernPair::new('\u{C3}', '\u{C3}', 1),
+    KernPair::new('\u{C3}', '\u{C4}', 1),
+    KernPair::new('\u{C3}', '\u{DD}', -2),
+    KernPair::new('\u{C3}', '\u{FD}', -1),
+    KernPair::new('\u{C3}', '\u{FF}', -1),
+    KernPair::new('\u{C4}', 'A', 1),
+    KernPair::new('\u{C4}', 'T', -2),
+    KernPair::new('\u{C4}', 'V', -1),
+    KernPair::new('\u{C4}', 'W', -1),
+    KernPair::new('\u{C4}', 'Y', -2),
+    KernPair::new('\u{C4}', 'f', -1),
+    KernPair::new('\u{C4}', 'v', -1),
+    KernPair::new('\u{C4}', 'w', -1),
+    KernPair::new('\u{C4}', 'y', -1),
+    KernPair::new('\u{C4}', '\u{AB}', -1),
+    KernPair::new('\u{C4}', '\u{C0}', 1),
+    KernPair::new('\u{C4}', '\u{C1}', 1),
+    KernPair::new('\u{C4}', '\u{C2}', 1),
+    KernPair::new('\u{C4}', '\u{C3}', 1),
+    KernPair::new('\u{C4}', '\u{C4}', 1),
+    KernPair::new('\u{C4}', '\u{DD}', -2),
+    KernPair::new('\u{C4}', '\u{FD}', -1),
+    KernPair::new('\u{C4}', '\u{FF}', -1),
+    KernPair::new('\u{D0}', 'Y', -1),
+    KernPair::new('\u{D0}', '\u{DD}', -1),
+    KernPair::new('\u{D2}', '-', 1),
+    KernPair::new('\u{D2}', '.', -1),
+    KernPair::new('\u{D2}', 'X', -1),
+    KernPair::new('\u{D2}', 'Y', -1),
+    KernPair::new('\u{D2}', '\u{DD}', -1),
+    KernPair::new('\u{D3}', '-', 1),
+    KernPair::new('\u{D3}', '.', -1),
+    KernPair::new('\u{D3}', 'X', -1),
+    KernPair::new('\u{D3}', 'Y', -1),
+    KernPair::new('\u{D3}', '\u{DD}', -1),
+    KernPair::new('\u{D4}', '-', 1),
+    KernPair::new('\u{D4}', '.', -1),
+    KernPair::new('\u{D4}', 'X', -1),
+    KernPair::new('\u{D4}', 'Y', -1),
+    KernPair::new('\u{D4}', '\u{DD}', -1),
+    KernPair::new('\u{D5}', '-', 1),
+    KernPair::new('\u{D5}', '.', -1),
+    KernPair::new('\u{D5}', 'X', -1),
+    KernPair::new('\u{D5}', 'Y', -1),
+    KernPair::new('\u{D5}', '\u{DD}', -1),
+    KernPair::new('\u{D6}', '-', 1),
+    KernPair::new('\u{D6}', '.', -1),
+    KernPair::new('\u{D6}', 'X', -1),
+    KernPair::new('\u{D6}', 'Y', -1),
+    KernPair::new('\u{D6}', '\u{DD}', -1),
+    KernPair::new('\u{DD}', '-', -2),
+    KernPair::new('\u{DD}', '.', -4),
+    KernPair::new('\u{DD}', ':', -3),
+    KernPair::new('\u{DD}', 'A', -2),
+    KernPair::new('\u{DD}', 'C', -1),
+    KernPair::new('\u{DD}', 'O', -1),
+    KernPair::new('\u{DD}', 'a', -3),
+    KernPair::new('\u{DD}', 'e', -3),
+    KernPair::new('\u{DD}', 'i', -1),
+    KernPair::new('\u{DD}', 'o', -3),
+    KernPair::new('\u{DD}', 'u', -2),
+    KernPair::new('\u{DD}', '\u{AB}', -2),
+    KernPair::new('\u{DD}', '\u{BB}', -1),
+    KernPair::new('\u{DD}', '\u{C0}', -2),
+    KernPair::new('\u{DD}', '\u{C1}', -2),
+    KernPair::new('\u{DD}', '\u{C2}', -2),
+    KernPair::new('\u{DD}', '\u{C3}', -2),
+    KernPair::new('\u{DD}', '\u{C4}', -2),
+    KernPair::new('\u{DD}', '\u{C7}', -1),
+    KernPair::new('\u{DD}', '\u{D2}', -1),
+    KernPair::new('\u{DD}', '\u{D3}', -1),
+    KernPair::new('\u{DD}', '\u{D4}', -1),
+    KernPair::new('\u{DD}', '\u{D5}', -1),
+    KernPair::new('\u{DD}', '\u{D6}', -1),
+    KernPair::new('\u{DD}', '\u{E0}', -3),
+    KernPair::new('\u{DD}', '\u{E1}', -3),
+    KernPair::new('\u{DD}', '\u{E2}', -3),
+    KernPair::new('\u{DD}', '\u{E3}', -3),
+    KernPair::new('\u{DD}', '\u{E4}', -3),
+    KernPair::new('\u{DD}', '\u{E5}', -3),
+    KernPair::new('\u{DD}', '\u{E8}', -3),
+    KernPair::new('\u{DD}', '\u{E9}', -3),
+    KernPair::new('\u{DD}', '\u{EA}', -3),
+    KernPair::new('\u{DD}', '\u{EB}', -3),
+    KernPair::new('\u{DD}', '\u{F2}', -3),
+    KernPair::new('\u{DD}', '\u{F3}', -3),
+    KernPair::new('\u{DD}', '\u{F4}', -3),
+    KernPair::new('\u{DD}', '\u{F5}', -3),
+    KernPair::new('\u{DD}', '\u{F6}', -3),
+    KernPair::new('\u{DD}', '\u{F9}', -2),
+    KernPair::new('\u{DD}', '\u{FA}', -2),
+    KernPair::new('\u{DD}', '\u{FB}', -2),
+    KernPair::new('\u{DD}', '\u{FC}', -2),
+    KernPair::new('\u{DE}', '.', -1),
+    KernPair::new('\u{DE}', ':', -1),
+    KernPair::new('\u{F2}', 'x', -1),
+    KernPair::new('\u{F3}', 'x', -1),
+    KernPair::new('\u{F4}', 'x', -1),
+    KernPair::new('\u{F5}', 'x', -1),
+    KernPair::new('\u{F6}', 'x', -1),
+    KernPair::new('\u{FD}', '.', -3),
+    KernPair::new('\u{FD}', ':', -1),
+    KernPair::new('\u{FF}', '.', -3),
+    KernPair::new('\u{FF}', ':', -1),
+];
@@ -0,0 +1,255 @@
+//! # Frame Timer
+//!
+//! Periodic interrupt from the BCM2711 system timer, for event loops that
+//! have no vsync to pace them (no display attached, firmware without SMI
+//! vsync, or a slideshow that only needs a few ticks a second).
+//!
+//! The system timer is a free-running 1 MHz counter (`CLO`/`CHI`) with four
+//! compare registers. The GPU firmware owns C0 and C2, leaving C1 and C3 to
+//! the ARM side: when the low counter word equals C1, bit M1 of `CS` is set
+//! and the interrupt (GIC SPI 65) is raised until the bit is written back,
+//! and likewise for C3 (M3, SPI 67). [`FrameTimer::start`] uses C1;
+//! [`FrameTimer::start_on`] takes either, so two PDs can each tick on
+//! their own channel.
+//!
+//! Route the interrupt to the PD as a Microkit IRQ channel and, from
+//! `notified`, call [`FrameTimer::handle_irq`] before acking the channel.
+//! Between ticks the PD is blocked in the Microkit event loop, so the core
+//! idles in the kernel (which executes WFI) instead of spinning. How much
+//! power that saves has not been measured yet.
+//!
+//! Deadlines advance by whole periods from the previous deadline, so the
+//! tick rate does not drift with interrupt latency. Deadlines that were
+//! missed entirely (e.g. during a long decode) are skipped and counted in
+//! [`FrameTimer::missed`] rather than delivered as a burst of ticks.
+
+/// GIC interrupt ID of system timer compare channel 1 (SPI 65)
+pub const SYSTEM_TIMER_C1_IRQ: usize = 97;
+
+/// GIC interrupt ID of system timer compare channel 3 (SPI 67)
+pub const SYSTEM_TIMER_C3_IRQ: usize = 99;
+
+/// System timer counter frequency
+pub const SYSTEM_TIMER_HZ: u32 = 1_000_000;
+
+/// System timer registers
+mod regs {
+    /// Control/status: one match bit per compare channel (write 1 to clear)
+    pub const CS: usize = 0x00;
+    /// Counter, low 32 bits
+    pub const CLO: usize = 0x04;
+    /// Counter, high 32 bits
+    pub const CHI: usize = 0x08;
+    /// Compare channel 1
+    pub const C1: usize = 0x10;
+    /// Channel 1 match bit in CS
+    pub const CS_M1: u32 = 1 << 1;
+    /// Compare channel 3
+    pub const C3: usize = 0x18;
+    /// Channel 3 match bit in CS
+    pub const CS_M3: u32 = 1 << 3;
+}
+
+/// A system timer compare channel the ARM side may use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compare {
+    C1,
+    C3,
+}
+
+impl Compare {
+    /// Offset of the compare register
+    const fn reg(self) -> usize {
+        match self {
+            Compare::C1 => regs::C1,
+            Compare::C3 => regs::C3,
+        }
+    }
+
+    /// Match bit in CS
+    const fn match_bit(self) -> u32 {
+        match self {
+            Compare::C1 => regs::CS_M1,
+            Compare::C3 => regs::CS_M3,
+        }
+    }
+
+    /// GIC interrupt ID
+    pub const fn irq(self) -> usize {
+        match self {
+            Compare::C1 => SYSTEM_TIMER_C1_IRQ,
+            Compare::C3 => SYSTEM_TIMER_C3_IRQ,
+        }
+    }
+}
+
+/// Has the counter value `now` reached `deadline`?
+///
+/// Wrap-safe as long as the two are less than 2^31 ticks (about 35 minutes)
+/// apart.
+pub const fn deadline_passed(now: u32, deadline: u32) -> bool {
+    (now.wrapping_sub(deadline) as i32) >= 0
+}
+
+/// First deadline after `now` on the `period` grid through `previous`, and
+/// how many grid points were skipped on the way
+pub fn next_deadline(previous: u32, now: u32, period: u32) -> (u32, u32) {
+    let next = previous.wrapping_add(period);
+    if !deadline_passed(now, next) {
+        return (next, 0);
+    }
+    // Whole periods already behind `now`, plus the one that brings us past it
+    let skipped = now.wrapping_sub(next) / period + 1;
+    (next.wrapping_add(skipped.wrapping_mul(period)), skipped)
+}
+
+/// The full 64-bit counter: microseconds since the timer started, which
+/// does not wrap in the life of the board
+///
+/// `CHI` is read on both sides of `CLO`, so a carry between the two reads
+/// is caught and the read retried.
+///
+/// # Safety
+/// `base` must be the mapped system timer register page. Reading the
+/// counter has no side effects, so a read-only mapping is enough.
+pub unsafe fn read_counter(base: usize) -> u64 {
+    let read = |offset: usize| core::ptr::read_volatile((base + offset) as *const u32);
+    loop {
+        let hi = read(regs::CHI);
+        let lo = read(regs::CLO);
+        if read(regs::CHI) == hi {
+            return ((hi as u64) << 32) | lo as u64;
+        }
+    }
+}
+
+/// Periodic tick from a system timer compare channel
+pub struct FrameTimer {
+    /// Virtual address of the system timer registers
+    base: usize,
+    /// Compare channel ticked on
+    compare: Compare,
+    /// Counter ticks between interrupts
+    period: u32,
+    /// Counter value of the pending compare
+    deadline: u32,
+    /// Ticks delivered so far
+    ticks: u32,
+    /// Deadlines skipped because they had already passed
+    missed: u32,
+}
+
+impl FrameTimer {
+    /// Start ticking `hz` times per second (at most [`SYSTEM_TIMER_HZ`]).
+    ///
+    /// # Safety
+    /// `base` must be the mapped system timer register page, and no other
+    /// code may use compare channel 1.
+    pub unsafe fn start(base: usize, hz: u32) -> Self {
+        Self::start_on(base, Compare::C1, hz)
+    }
+
+    /// Start ticking `hz` times per second on `compare`.
+    ///
+    /// # Safety
+    /// `base` must be the mapped system timer register page, and no other
+    /// code may use `compare`.
+    pub unsafe fn start_on(base: usize, compare: Compare, hz: u32) -> Self {
+        let period = SYSTEM_TIMER_HZ / hz.clamp(1, SYSTEM_TIMER_HZ);
+        let mut timer = Self { base, compare, period, deadline: 0, ticks: 0, missed: 0 };
+        timer.write_reg(regs::CS, compare.match_bit());
+        timer.deadline = timer.now();
+        timer.arm();
+        timer
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// Current counter value (microseconds, wraps every ~71 minutes)
+    pub fn now(&self) -> u32 {
+        self.read_reg(regs::CLO)
+    }
+
+    /// Full 64-bit counter value (microseconds, never wraps in practice)
+    pub fn uptime_us(&self) -> u64 {
+        unsafe { read_counter(self.base) }
+    }
+
+    /// Program the compare for the next deadline after the current one.
+    /// Re-checks after writing: a match that slipped by between reading the
+    /// counter and writing the compare would otherwise not fire until the counter
+    /// wraps.
+    fn arm(&mut self) {
+        loop {
+            let (deadline, skipped) = next_deadline(self.deadline, self.now(), self.period);
+            self.deadline = deadline;
+            self.missed = self.missed.wrapping_add(skipped);
+            self.write_reg(self.compare.reg(), deadline);
+            if !deadline_passed(self.now(), deadline) {
+                return;
+            }
+        }
+    }
+
+    /// Service the timer IRQ: returns true (and counts a tick) if the
+    /// channel matched. Call before acking the Microkit IRQ channel, otherwise the
+    /// interrupt fires again immediately.
+    pub fn handle_irq(&mut self) -> bool {
+        let bit = self.compare.match_bit();
+        if self.read_reg(regs::CS) & bit == 0 {
+            return false;
+        }
+        self.write_reg(regs::CS, bit);
+        self.arm();
+        self.ticks = self.ticks.wrapping_add(1);
+        true
+    }
+
+    /// Ticks delivered so far (wraps)
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Deadlines skipped because the PD was busy past them (wraps)
+    pub fn missed(&self) -> u32 {
+        self.missed
+    }
+
+    /// Microseconds between ticks
+    pub fn period_us(&self) -> u32 {
+        self.period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_passed_across_wrap() {
+        assert!(deadline_passed(100, 100));
+        assert!(!deadline_passed(99, 100));
+        assert!(deadline_passed(5, u32::MAX - 5));
+        assert!(!deadline_passed(u32::MAX - 5, 5));
+    }
+
+    #[test]
+    fn test_next_deadline_skips_missed_periods() {
+        // On time: one period after the previous deadline
+        assert_eq!(next_deadline(1000, 1200, 500), (1500, 0));
+        // Late by two and a bit periods: land on the grid past `now`
+        assert_eq!(next_deadline(1000, 2700, 500), (3000, 3));
+        // Exactly on a grid point counts as passed
+        assert_eq!(next_deadline(1000, 2000, 500), (2500, 2));
+        // Across the counter wrap
+        assert_eq!(next_deadline(u32::MAX - 99, 10, 100), (100, 1));
+    }
+}
@@ -2,13 +2,38 @@
 //!
 //! Allocates and manages the framebuffer via the VideoCore mailbox.
 //!
+//! Some HDMI sinks refuse the mode a PD asks for. [`Framebuffer::new_with_fallback`]
+//! walks a ladder of modes (1080p → 720p → 480p, then the same at 16bpp) and
+//! reports which one stuck so the caller can show it.
+//!
+//! A PD that can lay itself out at any size asks the display for its
+//! native mode ([`native_mode`], from its EDID) and switches to it with
+//! [`Framebuffer::reconfigure`], keeping the mode it had if the switch is
+//! refused.
+//!
+//! With a DMA channel to spare, [`Framebuffer::attach_blitter`] hands
+//! `clear`, large `fill_rect`s and large `blit`s to a
+//! [`BlitEngine`](crate::dma::BlitEngine); the CPU draws whatever it
+//! refuses.
+//!
 //! ## Verus Verification
 //! Key properties verified:
 //! - `put_pixel` returns false for out-of-bounds coordinates
 //! - No writes occur outside framebuffer memory
-
+//!
+//! ## Clipping vs. fallible drawing
+//! `fill_rect`, `blit` and the line helpers clip to the screen, which suits
+//! callers whose geometry is already known to fit. The `try_` variants
+//! refuse out-of-range input instead and return a [`DrawError`] naming the
+//! offending parameters, for geometry that comes from elsewhere (IPC,
+//! decoded images, layout maths).
+
+use crate::edid::parse_edid;
 use crate::mailbox::{Mailbox, MailboxError, tags};
+use crate::property::MailboxMessageBuilder;
 use crate::graphics::Color;
+use crate::blend::blend_pixel;
+use crate::dma::{BlitEngine, DmaWindow, Rect2d, DMA_MIN_PIXELS};
 
 // Verus imports disabled for build testing
 // #[allow(unused_imports)]
@@ -27,22 +52,224 @@ pub struct FramebufferInfo {
     pub height: u32,
     /// Bytes per scanline (may be > width * bytes_per_pixel due to alignment)
     pub pitch: u32,
-    /// Bits per pixel (typically 32)
+    /// Bits per pixel (32 = ARGB8888, 16 = RGB565)
     pub depth: u32,
     /// Total size in bytes
     pub size: u32,
 }
 
+impl FramebufferInfo {
+    /// Bytes occupied by one pixel at this depth
+    pub const fn bytes_per_pixel(&self) -> u32 {
+        self.depth / 8
+    }
+}
+
+/// A display mode the VideoCore can be asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayMode {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Bits per pixel (32 or 16)
+    pub depth: u32,
+}
+
+impl DisplayMode {
+    pub const fn new(width: u32, height: u32, depth: u32) -> Self {
+        Self { width, height, depth }
+    }
+
+    pub const FHD_1080P: DisplayMode = DisplayMode::new(1920, 1080, 32);
+    pub const HD_720P: DisplayMode = DisplayMode::new(1280, 720, 32);
+    pub const SD_480P: DisplayMode = DisplayMode::new(720, 480, 32);
+    pub const FHD_1080P_16: DisplayMode = DisplayMode::new(1920, 1080, 16);
+    pub const HD_720P_16: DisplayMode = DisplayMode::new(1280, 720, 16);
+    pub const SD_480P_16: DisplayMode = DisplayMode::new(720, 480, 16);
+
+    /// Whether the mode is at least `width` x `height`
+    pub const fn covers(&self, width: u32, height: u32) -> bool {
+        self.width >= width && self.height >= height
+    }
+
+    /// Render as e.g. `1280x720 32bpp` for status screens (no allocation)
+    pub fn label<'a>(&self, buf: &'a mut [u8; 40]) -> &'a str {
+        let mut len = 0;
+        len += write_decimal(self.width, &mut buf[len..]);
+        buf[len] = b'x';
+        len += 1;
+        len += write_decimal(self.height, &mut buf[len..]);
+        buf[len] = b' ';
+        len += 1;
+        len += write_decimal(self.depth, &mut buf[len..]);
+        buf[len..len + 3].copy_from_slice(b"bpp");
+        len += 3;
+        // Only ASCII digits and literals were written
+        core::str::from_utf8(&buf[..len]).unwrap_or("?")
+    }
+}
+
+/// Write `val` in decimal at the start of `out`, returning the digit count;
+/// only the leading digits are written if `out` is too short
+pub(crate) fn write_decimal(mut val: u32, out: &mut [u8]) -> usize {
+    let mut tmp = [0u8; 10];
+    let mut i = tmp.len();
+    loop {
+        i -= 1;
+        tmp[i] = b'0' + (val % 10) as u8;
+        val /= 10;
+        if val == 0 {
+            break;
+        }
+    }
+    let n = (tmp.len() - i).min(out.len());
+    out[..n].copy_from_slice(&tmp[i..i + n]);
+    n
+}
+
+/// Modes tried by [`Framebuffer::new_with_fallback`], best first.
+///
+/// Resolution drops first (1080p → 720p → 480p) at 32bpp; only when no
+/// 32bpp mode is accepted do we retry the same resolutions at 16bpp.
+pub const FALLBACK_LADDER: [DisplayMode; 6] = [
+    DisplayMode::FHD_1080P,
+    DisplayMode::HD_720P,
+    DisplayMode::SD_480P,
+    DisplayMode::FHD_1080P_16,
+    DisplayMode::HD_720P_16,
+    DisplayMode::SD_480P_16,
+];
+
+/// Ladder for PDs that draw a fixed 1280x720 layout with raw `u32` writes.
+///
+/// Those PDs cannot handle 16bpp or a smaller surface, so the only useful
+/// fallback is a larger 32bpp mode with the layout in its top-left corner.
+pub const FIXED_720P_LADDER: [DisplayMode; 2] = [DisplayMode::HD_720P, DisplayMode::FHD_1080P];
+
+/// The tail of [`FALLBACK_LADDER`] starting at `preferred`.
+///
+/// A PD laid out for 720p passes `DisplayMode::HD_720P` so it never gets
+/// bumped *up* to 1080p on a display that accepts its preferred mode.
+/// Returns the whole ladder if `preferred` is not one of its rungs.
+pub fn ladder_from(preferred: DisplayMode) -> &'static [DisplayMode] {
+    let mut i = 0;
+    while i < FALLBACK_LADDER.len() {
+        if FALLBACK_LADDER[i] == preferred {
+            return &FALLBACK_LADDER[i..];
+        }
+        i += 1;
+    }
+    &FALLBACK_LADDER
+}
+
+/// The attached display's native mode, from its EDID, if it lies between
+/// `min` and `max` (see [`EdidInfo::native_mode`](crate::edid::EdidInfo::native_mode)).
+/// `None` without a readable EDID.
+pub fn native_mode(mailbox: &Mailbox, min: DisplayMode, max: DisplayMode) -> Option<DisplayMode> {
+    let mut block = [0u8; 128];
+    mailbox.get_edid_block(0, &mut block).ok()?;
+    parse_edid(&block).ok()?.native_mode(min, max)
+}
+
+/// Which rung of a fallback ladder was allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeSelection {
+    /// The mode the GPU accepted
+    pub mode: DisplayMode,
+    /// Index into the ladder (0 = preferred mode, no fallback needed)
+    pub rung: usize,
+    /// Number of rungs in the ladder that was tried
+    pub rungs: usize,
+}
+
+impl ModeSelection {
+    /// True if the preferred mode was refused and a lower rung was used
+    pub const fn is_fallback(&self) -> bool {
+        self.rung != 0
+    }
+}
+
+/// Why a fallible drawing call was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawError {
+    /// Pixel lies outside the `width` x `height` framebuffer
+    PixelOutOfBounds { x: u32, y: u32, width: u32, height: u32 },
+    /// Rectangle does not fit inside the `width` x `height` framebuffer
+    RectOutOfBounds { x: u32, y: u32, w: u32, h: u32, width: u32, height: u32 },
+    /// Source pixels are fewer than the `w` x `h` rectangle needs
+    SourceTooShort { needed: usize, len: usize },
+    /// Polygon has more vertices than the filler can hold
+    TooManyVertices { len: usize, max: usize },
+    /// A `width` x `height` surface at `depth` bpp cannot hold a
+    /// `screen_w` x `screen_h` 32bpp [`Screen`](crate::screen::Screen)
+    ScreenMismatch { width: u32, height: u32, depth: u32, screen_w: u32, screen_h: u32 },
+    /// Tile map cells are not `cols` x `rows`
+    MapSizeMismatch { len: usize, cols: u32, rows: u32 },
+    /// Tile index is past the `count` tiles of the sprite sheet
+    TileOutOfRange { tile: u16, count: u32 },
+}
+
+impl core::fmt::Display for DrawError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            DrawError::PixelOutOfBounds { x, y, width, height } => {
+                write!(f, "pixel ({}, {}) outside {}x{} framebuffer", x, y, width, height)
+            }
+            DrawError::RectOutOfBounds { x, y, w, h, width, height } => write!(
+                f,
+                "{}x{} rect at ({}, {}) outside {}x{} framebuffer",
+                w, h, x, y, width, height
+            ),
+            DrawError::SourceTooShort { needed, len } => {
+                write!(f, "source has {} pixels, rect needs {}", len, needed)
+            }
+            DrawError::TooManyVertices { len, max } => {
+                write!(f, "polygon has {} vertices, at most {} can be filled", len, max)
+            }
+            DrawError::ScreenMismatch { width, height, depth, screen_w, screen_h } => write!(
+                f,
+                "{}x{} {}bpp surface cannot hold a {}x{} 32bpp screen",
+                width, height, depth, screen_w, screen_h
+            ),
+            DrawError::MapSizeMismatch { len, cols, rows } => {
+                write!(f, "tile map has {} cells, {}x{} needs {}", len, cols, rows, cols as u64 * rows as u64)
+            }
+            DrawError::TileOutOfRange { tile, count } => {
+                write!(f, "tile {} past the {} tiles of the sheet", tile, count)
+            }
+        }
+    }
+}
+
+impl From<DrawError> for rpi4_pd_error::PdError {
+    fn from(e: DrawError) -> Self {
+        use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+        let detail = match e {
+            DrawError::PixelOutOfBounds { .. } => 1,
+            DrawError::RectOutOfBounds { .. } => 2,
+            DrawError::SourceTooShort { .. } => 3,
+            DrawError::TooManyVertices { .. } => 4,
+            DrawError::ScreenMismatch { .. } => 5,
+            DrawError::MapSizeMismatch { .. } => 6,
+            DrawError::TileOutOfRange { .. } => 7,
+        };
+        PdError::new(ErrorDomain::Display, ErrorKind::InvalidArgument).with_detail(detail)
+    }
+}
+
 /// Framebuffer handle for drawing operations
 pub struct Framebuffer {
     /// Framebuffer info
     info: FramebufferInfo,
     /// Pointer to framebuffer memory
     buffer: *mut u32,
+    /// DMA engine for large fills and copies, once attached
+    blitter: Option<BlitEngine>,
 }
 
 impl Framebuffer {
-    /// Allocate and initialize framebuffer via mailbox
+    /// Allocate and initialize a 32bpp framebuffer via mailbox
     ///
     /// # Safety
     /// The mailbox must be properly initialized and the device memory mapped.
@@ -51,78 +278,89 @@ impl Framebuffer {
         width: u32,
         height: u32,
     ) -> Result<Self, MailboxError> {
-        // Aligned buffer for mailbox communication
-        #[repr(align(16))]
-        struct AlignedBuffer([u32; 36]);
-        let mut buf = AlignedBuffer([0u32; 36]);
-        let buffer = &mut buf.0;
+        Self::with_mode(mailbox, DisplayMode::new(width, height, 32))
+    }
+
+    /// Walk `ladder` and allocate the first mode the GPU accepts
+    ///
+    /// The firmware is free to hand back a different size or depth than
+    /// requested (some HDMI sinks reject modes outright, others get silently
+    /// downgraded), so a rung only counts if the response matches it exactly.
+    ///
+    /// # Safety
+    /// Same requirements as [`Framebuffer::new`].
+    pub unsafe fn new_with_fallback(
+        mailbox: &Mailbox,
+        ladder: &[DisplayMode],
+    ) -> Result<(Self, ModeSelection), MailboxError> {
+        let mut last_err = MailboxError::AllocationFailed;
+
+        for (rung, mode) in ladder.iter().enumerate() {
+            match Self::with_mode(mailbox, *mode) {
+                Ok(fb) => {
+                    let selection = ModeSelection {
+                        mode: *mode,
+                        rung,
+                        rungs: ladder.len(),
+                    };
+                    return Ok((fb, selection));
+                }
+                Err(e) => last_err = e,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    /// Allocate and initialize a framebuffer in an explicit mode
+    ///
+    /// # Safety
+    /// Same requirements as [`Framebuffer::new`].
+    pub unsafe fn with_mode(mailbox: &Mailbox, mode: DisplayMode) -> Result<Self, MailboxError> {
+        if mode.depth != 32 && mode.depth != 16 {
+            return Err(MailboxError::AllocationFailed);
+        }
+        let DisplayMode { width, height, depth } = mode;
 
-        // Build framebuffer allocation request
         // Multiple tags in one message for efficiency
-        buffer[0] = 35 * 4; // Total buffer size
-        buffer[1] = 0; // Request code
-
-        // Set physical display size
-        buffer[2] = tags::SET_PHYSICAL_SIZE;
-        buffer[3] = 8; // Value size
-        buffer[4] = 0; // Request
-        buffer[5] = width;
-        buffer[6] = height;
-
-        // Set virtual buffer size (same as physical for no scrolling)
-        buffer[7] = tags::SET_VIRTUAL_SIZE;
-        buffer[8] = 8;
-        buffer[9] = 0;
-        buffer[10] = width;
-        buffer[11] = height;
-
-        // Set virtual offset (0,0)
-        buffer[12] = tags::SET_VIRTUAL_OFFSET;
-        buffer[13] = 8;
-        buffer[14] = 0;
-        buffer[15] = 0;
-        buffer[16] = 0;
-
-        // Set color depth (32 bits = ARGB)
-        buffer[17] = tags::SET_DEPTH;
-        buffer[18] = 4;
-        buffer[19] = 0;
-        buffer[20] = 32;
-
-        // Set pixel order (RGB, not BGR)
-        buffer[21] = tags::SET_PIXEL_ORDER;
-        buffer[22] = 4;
-        buffer[23] = 0;
-        buffer[24] = 1; // 1 = RGB
-
-        // Allocate buffer
-        buffer[25] = tags::ALLOCATE_BUFFER;
-        buffer[26] = 8;
-        buffer[27] = 0;
-        buffer[28] = 4096; // Alignment
-        buffer[29] = 0; // Will be filled with size
-
-        // Get pitch (bytes per row)
-        buffer[30] = tags::GET_PITCH;
-        buffer[31] = 4;
-        buffer[32] = 0;
-        buffer[33] = 0;
-
-        // End tag
-        buffer[34] = 0;
+        let mut builder = MailboxMessageBuilder::new();
+        let physical = builder.tag(tags::SET_PHYSICAL_SIZE, 2, &[width, height])?;
+        // Virtual size same as physical for no scrolling, at offset (0,0)
+        builder.tag(tags::SET_VIRTUAL_SIZE, 2, &[width, height])?;
+        builder.tag(tags::SET_VIRTUAL_OFFSET, 2, &[0, 0])?;
+        // 32 bits = ARGB, 16 bits = RGB565
+        let set_depth = builder.tag(tags::SET_DEPTH, 1, &[depth])?;
+        // 1 = RGB, not BGR
+        builder.tag(tags::SET_PIXEL_ORDER, 1, &[1])?;
+        // Aligned to 4096; answered with address and size
+        let allocate = builder.tag(tags::ALLOCATE_BUFFER, 2, &[4096])?;
+        let get_pitch = builder.tag(tags::GET_PITCH, 1, &[])?;
+        let mut msg = builder.finish();
 
         // Send to GPU
-        mailbox.call(buffer)?;
+        mailbox.call(&mut msg.words)?;
 
         // Extract results
-        let fb_gpu_addr = buffer[28];
-        let fb_size = buffer[29];
-        let pitch = buffer[33];
+        let [got_width, got_height] = msg.values(physical).ok_or(MailboxError::InvalidResponse)?;
+        let [got_depth] = msg.values(set_depth).ok_or(MailboxError::InvalidResponse)?;
+        let [fb_gpu_addr, fb_size] = msg.values(allocate).ok_or(MailboxError::AllocationFailed)?;
+        let [pitch] = msg.values(get_pitch).ok_or(MailboxError::InvalidResponse)?;
 
         if fb_gpu_addr == 0 || fb_size == 0 {
             return Err(MailboxError::AllocationFailed);
         }
 
+        // The firmware echoes back what it actually set. A mismatch means the
+        // sink refused this mode; the caller's layout would not fit.
+        if got_width != width || got_height != height || got_depth != depth {
+            return Err(MailboxError::ModeRejected);
+        }
+
+        // A pitch smaller than one row means the response is garbage
+        if pitch < width * (depth / 8) {
+            return Err(MailboxError::InvalidResponse);
+        }
+
         // Convert GPU address to ARM physical address
         let fb_phys_addr = crate::gpu_to_arm(fb_gpu_addr);
 
@@ -132,21 +370,68 @@ impl Framebuffer {
         let fb_offset = fb_phys_addr.saturating_sub(crate::FRAMEBUFFER_PHYS_BASE);
         let fb_virt_addr = crate::FRAMEBUFFER_VIRT_BASE + fb_offset;
 
+        // A buffer outside the mapping (a large mode placed elsewhere) would
+        // fault on the first pixel
+        if fb_phys_addr < crate::FRAMEBUFFER_PHYS_BASE
+            || fb_offset + fb_size as usize > crate::FRAMEBUFFER_REGION_SIZE
+        {
+            return Err(MailboxError::AllocationFailed);
+        }
+
         let info = FramebufferInfo {
             base: fb_phys_addr,
             width,
             height,
             pitch,
-            depth: 32,
+            depth,
             size: fb_size,
         };
 
         Ok(Self {
             info,
             buffer: fb_virt_addr as *mut u32,
+            blitter: None,
         })
     }
 
+    /// Switch to a `width` x `height` mode at `depth` bpp
+    ///
+    /// The firmware reallocates the buffer, so pointers from
+    /// [`buffer_ptr`](Self::buffer_ptr) taken before the call are stale
+    /// after it, whatever the outcome. If the new mode is refused the old
+    /// one is asked for again and the error returned; should that be
+    /// refused too, the framebuffer is left 0x0 and drawing on it does
+    /// nothing.
+    ///
+    /// # Safety
+    /// Same requirements as [`Framebuffer::new`].
+    pub unsafe fn reconfigure(
+        &mut self,
+        mailbox: &Mailbox,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Result<(), MailboxError> {
+        let old = DisplayMode::new(self.info.width, self.info.height, self.info.depth);
+        let blitter = self.blitter.take();
+        let err = match Self::with_mode(mailbox, DisplayMode::new(width, height, depth)) {
+            Ok(fb) => {
+                *self = Self { blitter, ..fb };
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        match Self::with_mode(mailbox, old) {
+            Ok(fb) => *self = Self { blitter, ..fb },
+            Err(_) => {
+                self.info.width = 0;
+                self.info.height = 0;
+                self.blitter = blitter;
+            }
+        }
+        Err(err)
+    }
+
     /// Get framebuffer info
     pub fn info(&self) -> &FramebufferInfo {
         &self.info
@@ -172,11 +457,154 @@ impl Framebuffer {
     ///
     /// Use with buffer_ptr() for direct pixel addressing:
     /// `buffer_ptr.add(y * pitch_pixels() + x)`
+    ///
+    /// Direct `u32` writes are only meaningful for 32bpp modes; check
+    /// `info().depth` first if the framebuffer came from a fallback ladder.
     pub fn pitch_pixels(&self) -> usize {
-        (self.info.pitch / 4) as usize
+        (self.info.pitch / self.info.bytes_per_pixel()) as usize
+    }
+
+    /// Route large fills, copies and [`clear`](Self::clear) through
+    /// `engine`. It stays attached across [`reconfigure`](Self::reconfigure).
+    pub fn attach_blitter(&mut self, engine: BlitEngine) {
+        self.blitter = Some(engine);
+    }
+
+    /// Go back to drawing everything with the CPU
+    pub fn detach_blitter(&mut self) -> Option<BlitEngine> {
+        self.blitter.take()
+    }
+
+    /// The mapping the buffer lives in, as the DMA engine sees it
+    fn dma_window(&self) -> DmaWindow {
+        DmaWindow {
+            vaddr: crate::FRAMEBUFFER_VIRT_BASE,
+            paddr: crate::FRAMEBUFFER_PHYS_BASE,
+            size: crate::FRAMEBUFFER_REGION_SIZE,
+            cached: false,
+        }
+    }
+
+    /// Fill the clipped `x_start..x_end` x `y_start..y_end` rectangle with
+    /// the blitter. False if there is none or it refused; the caller then
+    /// draws with the CPU.
+    fn dma_fill(&mut self, x_start: u32, y_start: u32, x_end: u32, y_end: u32, argb: u32) -> bool {
+        let window = self.dma_window();
+        let bpp = self.info.bytes_per_pixel();
+        let word = if self.info.depth == 16 {
+            let pixel = argb_to_rgb565(argb) as u32;
+            (pixel << 16) | pixel
+        } else {
+            argb
+        };
+        let dest = Rect2d {
+            ptr: self.buffer as usize + (y_start * self.info.pitch + x_start * bpp) as usize,
+            pitch: self.info.pitch,
+            row_bytes: (x_end - x_start) * bpp,
+            rows: y_end - y_start,
+        };
+        match self.blitter.as_mut() {
+            Some(engine) => engine.fill(&window, dest, word).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Copy the clipped `x..x_end` x `y..y_end` part of a `w`-wide block of
+    /// ARGB pixels with the blitter. Only a large block, whole rows of
+    /// which are in `pixels`, from a source the blitter can reach, onto a
+    /// 32bpp screen, is taken.
+    fn dma_blit(&mut self, x: u32, y: u32, x_end: u32, y_end: u32, w: u32, pixels: &[u32]) -> bool {
+        let (cols, rows) = (x_end - x, y_end - y);
+        let needed = (rows - 1) as usize * w as usize + cols as usize;
+        if self.info.depth != 32 || (cols as usize * rows as usize) < DMA_MIN_PIXELS || pixels.len() < needed {
+            return false;
+        }
+        let window = self.dma_window();
+        let dest = Rect2d {
+            ptr: self.buffer as usize + (y * self.info.pitch + x * 4) as usize,
+            pitch: self.info.pitch,
+            row_bytes: cols * 4,
+            rows,
+        };
+        let src = Rect2d { ptr: pixels.as_ptr() as usize, pitch: w.saturating_mul(4), row_bytes: cols * 4, rows };
+        let Some(engine) = self.blitter.as_mut() else { return false };
+        let src_window = match engine.source_window(src.ptr, needed * 4) {
+            Some(source) => source,
+            None => window,
+        };
+        engine.copy(&src_window, src, &window, dest).is_ok()
+    }
+
+    /// Write one pixel at a pixel offset, encoded for the current depth
+    ///
+    /// # Safety
+    /// `offset` must be below `pitch_pixels() * height`.
+    #[inline]
+    unsafe fn write_at(&mut self, offset: usize, argb: u32) {
+        if self.info.depth == 16 {
+            (self.buffer as *mut u16).add(offset).write_volatile(argb_to_rgb565(argb));
+        } else {
+            self.buffer.add(offset).write_volatile(argb);
+        }
+    }
+
+    /// Read one pixel at a pixel offset as opaque ARGB
+    ///
+    /// # Safety
+    /// `offset` must be below `pitch_pixels() * height`.
+    #[inline]
+    unsafe fn read_at(&self, offset: usize) -> u32 {
+        if self.info.depth == 16 {
+            rgb565_to_argb((self.buffer as *const u16).add(offset).read_volatile())
+        } else {
+            self.buffer.add(offset).read_volatile() | 0xFF00_0000
+        }
     }
 }
 
+impl Framebuffer {
+    /// 32bpp framebuffer over `mem` with no row padding. The handle does
+    /// not borrow `mem`; callers keep it alive and unaliased while the
+    /// handle is in use.
+    pub(crate) fn over(mem: &mut [u32], width: u32, height: u32) -> Self {
+        assert!(mem.len() >= (width * height) as usize);
+        Self {
+            info: FramebufferInfo {
+                base: 0,
+                width,
+                height,
+                pitch: width * 4,
+                depth: 32,
+                size: width * height * 4,
+            },
+            buffer: mem.as_mut_ptr(),
+            blitter: None,
+        }
+    }
+}
+
+/// Pack an ARGB8888 value into RGB565 (alpha dropped)
+#[inline]
+pub const fn argb_to_rgb565(argb: u32) -> u16 {
+    let r = (argb >> 19) & 0x1F;
+    let g = (argb >> 10) & 0x3F;
+    let b = (argb >> 3) & 0x1F;
+    ((r << 11) | (g << 5) | b) as u16
+}
+
+/// Widen an RGB565 value to opaque ARGB8888, replicating the top bits so
+/// full-scale channels stay full-scale
+#[inline]
+pub const fn rgb565_to_argb(rgb: u16) -> u32 {
+    let r = ((rgb >> 11) & 0x1F) as u32;
+    let g = ((rgb >> 5) & 0x3F) as u32;
+    let b = (rgb & 0x1F) as u32;
+    0xFF00_0000
+        | (((r << 3) | (r >> 2)) << 16)
+        | (((g << 2) | (g >> 4)) << 8)
+        | ((b << 3) | (b >> 2))
+}
+
 impl Framebuffer {
     /// Put a pixel at (x, y) with bounds checking
     ///
@@ -187,12 +615,10 @@ impl Framebuffer {
             return false;
         }
 
-        // Calculate offset (pitch is in bytes, we're working with u32)
-        let pitch_pixels = self.info.pitch / 4;
-        let offset = (y * pitch_pixels + x) as usize;
+        let offset = y as usize * self.pitch_pixels() + x as usize;
 
         unsafe {
-            self.buffer.add(offset).write_volatile(color.to_argb());
+            self.write_at(offset, color.to_argb());
         }
 
         true
@@ -204,19 +630,55 @@ impl Framebuffer {
     /// Caller must ensure x < width and y < height.
     #[inline]
     pub unsafe fn put_pixel_unchecked(&mut self, x: u32, y: u32, color: Color) {
-        let pitch_pixels = self.info.pitch / 4;
-        let offset = (y * pitch_pixels + x) as usize;
-        self.buffer.add(offset).write_volatile(color.to_argb());
+        let offset = y as usize * self.pitch_pixels() + x as usize;
+        self.write_at(offset, color.to_argb());
+    }
+
+    /// Put a pixel at (x, y), refusing out-of-bounds coordinates
+    pub fn try_put_pixel(&mut self, x: u32, y: u32, color: Color) -> Result<(), DrawError> {
+        if self.put_pixel(x, y, color) {
+            Ok(())
+        } else {
+            Err(DrawError::PixelOutOfBounds {
+                x,
+                y,
+                width: self.info.width,
+                height: self.info.height,
+            })
+        }
+    }
+
+    /// Check that a `w` x `h` rectangle at (x, y) lies inside the screen
+    fn check_rect(&self, x: u32, y: u32, w: u32, h: u32) -> Result<(), DrawError> {
+        let fits_x = x.checked_add(w).is_some_and(|end| end <= self.info.width);
+        let fits_y = y.checked_add(h).is_some_and(|end| end <= self.info.height);
+        if fits_x && fits_y {
+            Ok(())
+        } else {
+            Err(DrawError::RectOutOfBounds {
+                x,
+                y,
+                w,
+                h,
+                width: self.info.width,
+                height: self.info.height,
+            })
+        }
     }
 
     /// Fill the entire screen with a color
     pub fn clear(&mut self, color: Color) {
         let argb = color.to_argb();
-        let total_pixels = (self.info.pitch / 4) * self.info.height;
+        // The padding past each row is filled too, as the CPU loop does
+        let (pitch, height) = (self.pitch_pixels() as u32, self.info.height);
+        if height > 0 && self.dma_fill(0, 0, pitch, height, argb) {
+            return;
+        }
+        let total_pixels = self.pitch_pixels() * self.info.height as usize;
 
-        for i in 0..total_pixels as usize {
+        for i in 0..total_pixels {
             unsafe {
-                self.buffer.add(i).write_volatile(argb);
+                self.write_at(i, argb);
             }
         }
     }
@@ -224,40 +686,117 @@ impl Framebuffer {
     /// Fill a rectangle with bounds checking
     pub fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) {
         // Clamp to screen bounds
-        let x_end = (x + w).min(self.info.width);
-        let y_end = (y + h).min(self.info.height);
+        let x_end = x.saturating_add(w).min(self.info.width);
+        let y_end = y.saturating_add(h).min(self.info.height);
         let x_start = x.min(self.info.width);
         let y_start = y.min(self.info.height);
 
         let argb = color.to_argb();
-        let pitch_pixels = self.info.pitch / 4;
+        let pixels = (x_end - x_start) as usize * (y_end - y_start) as usize;
+        if pixels >= DMA_MIN_PIXELS && self.dma_fill(x_start, y_start, x_end, y_end, argb) {
+            return;
+        }
+        let pitch_pixels = self.pitch_pixels();
 
         for py in y_start..y_end {
             for px in x_start..x_end {
-                let offset = (py * pitch_pixels + px) as usize;
+                let offset = py as usize * pitch_pixels + px as usize;
+                unsafe {
+                    self.write_at(offset, argb);
+                }
+            }
+        }
+    }
+
+    /// Blend `color` over a rectangle at `alpha` / 255 coverage, clipped to
+    /// the screen (a translucent panel over whatever is already drawn)
+    pub fn fill_rect_alpha(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color, alpha: u8) {
+        match alpha {
+            0 => return,
+            255 => return self.fill_rect(x, y, w, h, color),
+            _ => {}
+        }
+        let x_end = x.saturating_add(w).min(self.info.width);
+        let y_end = y.saturating_add(h).min(self.info.height);
+        let argb = color.to_argb();
+        let pitch_pixels = self.pitch_pixels();
+
+        for py in y.min(y_end)..y_end {
+            for px in x.min(x_end)..x_end {
+                let offset = py as usize * pitch_pixels + px as usize;
+                unsafe {
+                    let under = self.read_at(offset);
+                    self.write_at(offset, blend_pixel(argb, under, alpha));
+                }
+            }
+        }
+    }
+
+    /// Fill a rectangle that must lie entirely on screen (an empty one is
+    /// fine). Nothing is drawn if it does not fit.
+    pub fn try_fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) -> Result<(), DrawError> {
+        self.check_rect(x, y, w, h)?;
+        self.fill_rect(x, y, w, h, color);
+        Ok(())
+    }
+
+    /// Copy a `w` x `h` block of ARGB pixels (row-major, `w` per row) to
+    /// (x, y), clipped to the screen and to the length of `pixels`
+    pub fn blit(&mut self, x: u32, y: u32, w: u32, h: u32, pixels: &[u32]) {
+        if w == 0 {
+            return;
+        }
+        let x_end = x.saturating_add(w).min(self.info.width);
+        let y_end = y.saturating_add(h).min(self.info.height);
+        if x < x_end && y < y_end && self.dma_blit(x, y, x_end, y_end, w, pixels) {
+            return;
+        }
+        let pitch_pixels = self.pitch_pixels();
+
+        for py in y..y_end {
+            let row = (py - y) as usize * w as usize;
+            for px in x..x_end {
+                let argb = match pixels.get(row + (px - x) as usize) {
+                    Some(&argb) => argb,
+                    None => return,
+                };
+                let offset = py as usize * pitch_pixels + px as usize;
                 unsafe {
-                    self.buffer.add(offset).write_volatile(argb);
+                    self.write_at(offset, argb);
                 }
             }
         }
     }
 
+    /// Copy a `w` x `h` block of ARGB pixels to (x, y). The rectangle must
+    /// lie on screen and `pixels` must hold at least `w * h` entries;
+    /// otherwise nothing is drawn.
+    pub fn try_blit(&mut self, x: u32, y: u32, w: u32, h: u32, pixels: &[u32]) -> Result<(), DrawError> {
+        self.check_rect(x, y, w, h)?;
+        // Fits on screen, so w * h cannot overflow usize
+        let needed = w as usize * h as usize;
+        if pixels.len() < needed {
+            return Err(DrawError::SourceTooShort { needed, len: pixels.len() });
+        }
+        self.blit(x, y, w, h, pixels);
+        Ok(())
+    }
+
     /// Draw a horizontal line
     pub fn hline(&mut self, x: u32, y: u32, len: u32, color: Color) {
         if y >= self.info.height {
             return;
         }
 
-        let x_end = (x + len).min(self.info.width);
+        let x_end = x.saturating_add(len).min(self.info.width);
         let x_start = x.min(self.info.width);
 
         let argb = color.to_argb();
-        let pitch_pixels = self.info.pitch / 4;
-        let row_offset = (y * pitch_pixels) as usize;
+        let row_offset = y as usize * self.pitch_pixels();
 
         for px in x_start..x_end {
             unsafe {
-                self.buffer.add(row_offset + px as usize).write_volatile(argb);
+                self.write_at(row_offset + px as usize, argb);
             }
         }
     }
@@ -268,16 +807,16 @@ impl Framebuffer {
             return;
         }
 
-        let y_end = (y + len).min(self.info.height);
+        let y_end = y.saturating_add(len).min(self.info.height);
         let y_start = y.min(self.info.height);
 
         let argb = color.to_argb();
-        let pitch_pixels = self.info.pitch / 4;
+        let pitch_pixels = self.pitch_pixels();
 
         for py in y_start..y_end {
-            let offset = (py * pitch_pixels + x) as usize;
+            let offset = py as usize * pitch_pixels + x as usize;
             unsafe {
-                self.buffer.add(offset).write_volatile(argb);
+                self.write_at(offset, argb);
             }
         }
     }
@@ -303,3 +842,151 @@ impl Framebuffer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: u32 = 8;
+    const H: u32 = 4;
+
+    /// 8x4 32bpp framebuffer over `mem`, with a pitch of 10 pixels
+    fn fb(mem: &mut [u32; 40]) -> Framebuffer {
+        Framebuffer {
+            info: FramebufferInfo { base: 0, width: W, height: H, pitch: 40, depth: 32, size: 160 },
+            buffer: mem.as_mut_ptr(),
+            blitter: None,
+        }
+    }
+
+    /// Framebuffer handle with `info` and no memory behind it
+    fn info_only(width: u32, height: u32, pitch: u32, depth: u32) -> Framebuffer {
+        Framebuffer {
+            info: FramebufferInfo { base: 0, width, height, pitch, depth, size: pitch * height },
+            buffer: core::ptr::null_mut(),
+            blitter: None,
+        }
+    }
+
+    #[test]
+    fn ladder_from_each_rung_is_the_tail_from_there() {
+        for (i, mode) in FALLBACK_LADDER.iter().enumerate() {
+            let ladder = ladder_from(*mode);
+            assert_eq!(ladder, &FALLBACK_LADDER[i..]);
+            assert_eq!(ladder[0], *mode);
+        }
+        // 720p never goes up to 1080p, and 16bpp comes after every 32bpp
+        assert_eq!(
+            ladder_from(DisplayMode::HD_720P),
+            &[
+                DisplayMode::HD_720P,
+                DisplayMode::SD_480P,
+                DisplayMode::FHD_1080P_16,
+                DisplayMode::HD_720P_16,
+                DisplayMode::SD_480P_16,
+            ]
+        );
+        assert_eq!(ladder_from(DisplayMode::SD_480P_16), &[DisplayMode::SD_480P_16]);
+        // Off the ladder: the whole of it
+        assert_eq!(ladder_from(DisplayMode::new(800, 600, 32)), &FALLBACK_LADDER);
+        assert_eq!(ladder_from(DisplayMode::new(1280, 720, 24)), &FALLBACK_LADDER);
+    }
+
+    #[test]
+    fn fixed_720p_ladder_only_grows() {
+        assert_eq!(FIXED_720P_LADDER, [DisplayMode::HD_720P, DisplayMode::FHD_1080P]);
+        for mode in FIXED_720P_LADDER {
+            assert_eq!(mode.depth, 32);
+            assert!(mode.covers(1280, 720));
+        }
+        assert!(!DisplayMode::SD_480P.covers(1280, 720));
+    }
+
+    #[test]
+    fn mode_labels() {
+        let mut buf = [0u8; 40];
+        assert_eq!(DisplayMode::HD_720P.label(&mut buf), "1280x720 32bpp");
+        assert_eq!(DisplayMode::FHD_1080P.label(&mut buf), "1920x1080 32bpp");
+        assert_eq!(DisplayMode::SD_480P_16.label(&mut buf), "720x480 16bpp");
+        assert_eq!(DisplayMode::new(0, 0, 0).label(&mut buf), "0x0 0bpp");
+        // The widest label still fits the buffer
+        assert_eq!(
+            DisplayMode::new(u32::MAX, u32::MAX, u32::MAX).label(&mut buf),
+            "4294967295x4294967295 4294967295bpp"
+        );
+        let mut digits = [0u8; 3];
+        assert_eq!(write_decimal(12345, &mut digits), 3);
+        assert_eq!(&digits, b"123");
+    }
+
+    #[test]
+    fn pitch_pixels_at_32_and_16_bpp() {
+        let fb = info_only(1280, 720, 5120, 32);
+        assert_eq!(fb.info.bytes_per_pixel(), 4);
+        assert_eq!(fb.pitch_pixels(), 1280);
+        // Rows padded by the firmware
+        assert_eq!(info_only(1366, 768, 5504, 32).pitch_pixels(), 1376);
+
+        let fb = info_only(1280, 720, 2560, 16);
+        assert_eq!(fb.info.bytes_per_pixel(), 2);
+        assert_eq!(fb.pitch_pixels(), 1280);
+        assert_eq!(info_only(1366, 768, 2752, 16).pitch_pixels(), 1376);
+    }
+
+    #[test]
+    fn only_a_lower_rung_is_a_fallback() {
+        let preferred = ModeSelection { mode: DisplayMode::HD_720P, rung: 0, rungs: 5 };
+        let lower = ModeSelection { mode: DisplayMode::SD_480P, rung: 1, rungs: 5 };
+        assert!(!preferred.is_fallback());
+        assert!(lower.is_fallback());
+    }
+
+    #[test]
+    fn try_fill_rect_refuses_instead_of_clamping() {
+        let mut mem = [0u32; 40];
+        let mut fb = fb(&mut mem);
+        let err = fb.try_fill_rect(6, 1, 3, 1, Color::WHITE).unwrap_err();
+        assert_eq!(
+            err,
+            DrawError::RectOutOfBounds { x: 6, y: 1, w: 3, h: 1, width: W, height: H }
+        );
+        assert!(fb.try_fill_rect(u32::MAX, 0, 2, 1, Color::WHITE).is_err());
+        assert!(fb.try_fill_rect(6, 1, 2, 3, Color::WHITE).is_ok());
+        assert!(fb.try_put_pixel(8, 0, Color::WHITE).is_err());
+        assert_eq!(mem[10 + 6], Color::WHITE.to_argb());
+        assert_eq!(mem[10 + 5], 0);
+    }
+
+    #[test]
+    fn fill_rect_alpha_blends_over_existing_pixels() {
+        let mut mem = [0xFF80_8080u32; 40];
+        let mut fb = fb(&mut mem);
+        fb.fill_rect_alpha(6, 3, 5, 5, Color::BLACK, 128);
+        fb.fill_rect_alpha(0, 0, 8, 4, Color::WHITE, 0);
+        // Clipped to the two on-screen pixels; 0x80 halves to 0x40
+        assert_eq!(mem[30 + 5], 0xFF80_8080);
+        assert_eq!(mem[30 + 6], 0xFF40_4040);
+        assert_eq!(mem[30 + 7], 0xFF40_4040);
+        assert_eq!(mem[30 + 8], 0xFF80_8080);
+        assert_eq!(rgb565_to_argb(argb_to_rgb565(0xFFFF_FFFF)), 0xFFFF_FFFF);
+        assert_eq!(rgb565_to_argb(0), 0xFF00_0000);
+    }
+
+    #[test]
+    fn blit_clips_and_try_blit_checks_source() {
+        let mut mem = [0u32; 40];
+        let mut fb = fb(&mut mem);
+        let src = [1, 2, 3, 4, 5, 6];
+        assert_eq!(
+            fb.try_blit(0, 0, 3, 3, &src),
+            Err(DrawError::SourceTooShort { needed: 9, len: 6 })
+        );
+        assert!(fb.try_blit(7, 0, 3, 2, &src).is_err());
+        // Clipped: only the first column lands on screen
+        fb.blit(7, 0, 3, 2, &src);
+        assert!(fb.try_blit(0, 2, 3, 2, &src).is_ok());
+        assert_eq!((mem[7], mem[17]), (1, 4));
+        assert_eq!(&mem[20..23], &[1, 2, 3]);
+        assert_eq!(&mem[30..33], &[4, 5, 6]);
+    }
+}
@@ -0,0 +1,319 @@
+//! # Glyph Cache
+//!
+//! [`draw_string_scaled`](crate::font::draw_string_scaled) draws every set
+//! pixel of a glyph as a `scale` x `scale` block, every time: a line of
+//! 4x title text is a few hundred bounds-checked rectangle fills, redrawn
+//! each frame. A [`GlyphCache`] renders each (character, scale, color) once
+//! into a slot of an off-screen atlas and afterwards copies it to the
+//! screen row by row, as runs of pixels:
+//!
+//! ```text
+//!   atlas (256 x 128, 32 slots of 32 x 32)
+//!   ┌────┬────┬────┬────┬────┬────┬────┬────┐
+//!   │ A4 │ B4 │ ·2 │    │    │    │    │    │   A4 = 'A' at 4x in one color
+//!   ├────┼────┼────┼────┼────┼────┼────┼────┤
+//!   │    │    │    │    │    │    │    │    │
+//!   └────┴────┴────┴────┴────┴────┴────┴────┘
+//! ```
+//!
+//! When all slots are taken, the least recently drawn glyph makes way.
+//! Glyphs above [`MAX_CACHED_SCALE`] do not fit a slot and are drawn
+//! directly, as before.
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - `slot_origin` of any slot leaves a whole slot inside the atlas, so
+//!   rendering into a slot or copying out of it stays in bounds
+//! - `lru_slot` returns a slot, and one drawn no later than any other
+
+// Verus requires explicit arithmetic (e.g., `x = x + 1`) for verification specs
+#![allow(clippy::assign_op_pattern)]
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::font::{draw_cell, for_each_cell, CHAR_WIDTH};
+use crate::framebuffer::Framebuffer;
+use crate::graphics::Color;
+use crate::surface::OffscreenSurface;
+
+verus! {
+
+/// Side of a square slot in pixels
+pub const GLYPH_SLOT: usize = 32;
+
+/// Slots per atlas row
+pub const SLOT_COLS: usize = 8;
+
+/// Slot rows in the atlas
+pub const SLOT_ROWS: usize = 4;
+
+/// Glyphs cached at once
+pub const GLYPH_SLOTS: usize = 32;
+
+/// Atlas width in pixels
+pub const ATLAS_WIDTH: usize = 256;
+
+/// Atlas height in pixels
+pub const ATLAS_HEIGHT: usize = 128;
+
+/// Top-left pixel of `slot` in the atlas
+pub fn slot_origin(slot: usize) -> (r: (usize, usize))
+    requires slot < GLYPH_SLOTS,
+    ensures
+        r.0 + GLYPH_SLOT <= ATLAS_WIDTH,
+        r.1 + GLYPH_SLOT <= ATLAS_HEIGHT,
+{
+    let col = slot % SLOT_COLS;
+    let row = slot / SLOT_COLS;
+    proof {
+        assert(col * 32 + 32 <= 256) by (nonlinear_arith)
+            requires col < 8;
+        assert(row * 32 + 32 <= 128) by (nonlinear_arith)
+            requires row < 4;
+    }
+    (col * GLYPH_SLOT, row * GLYPH_SLOT)
+}
+
+/// The slot with the oldest stamp (the first of them on a tie)
+pub fn lru_slot(stamps: &[u32; GLYPH_SLOTS]) -> (slot: usize)
+    ensures
+        slot < GLYPH_SLOTS,
+        forall|j: int| 0 <= j < GLYPH_SLOTS ==> stamps[slot as int] <= stamps[j],
+{
+    let mut best: usize = 0;
+    let mut i: usize = 1;
+    while i < GLYPH_SLOTS
+        invariant
+            1 <= i <= GLYPH_SLOTS,
+            best < GLYPH_SLOTS,
+            forall|j: int| 0 <= j < i ==> stamps[best as int] <= stamps[j],
+        decreases GLYPH_SLOTS - i,
+    {
+        if stamps[i] < stamps[best] {
+            best = i;
+        }
+        i = i + 1;
+    }
+    best
+}
+
+} // verus!
+
+/// Largest scale that fits a slot
+pub const MAX_CACHED_SCALE: u32 = (GLYPH_SLOT as u32) / CHAR_WIDTH;
+
+/// Atlas pixels outside a glyph's ink: transparent black, which glyphs in
+/// a visible color never are
+const BACKGROUND: u32 = 0x0000_0000;
+
+/// What a slot holds
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct GlyphKey {
+    c: char,
+    scale: u32,
+    color: Color,
+}
+
+/// Pre-rendered 8x8-font glyphs at any scale up to [`MAX_CACHED_SCALE`],
+/// reused least-recently-drawn first
+///
+/// The atlas is 128 KiB, so a PD keeps the cache in a `static`.
+pub struct GlyphCache {
+    atlas: OffscreenSurface<ATLAS_WIDTH, ATLAS_HEIGHT>,
+    keys: [Option<GlyphKey>; GLYPH_SLOTS],
+    /// When each slot was last drawn; 0 for empty slots
+    stamps: [u32; GLYPH_SLOTS],
+    clock: u32,
+    hits: u32,
+    misses: u32,
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphCache {
+    pub const fn new() -> Self {
+        Self {
+            atlas: OffscreenSurface::new(),
+            keys: [None; GLYPH_SLOTS],
+            stamps: [0; GLYPH_SLOTS],
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Draw a string, as [`draw_string_scaled`](crate::font::draw_string_scaled)
+    /// would
+    pub fn draw_string(&mut self, fb: &mut Framebuffer, x: u32, y: u32, s: &str, color: Color, scale: u32) {
+        let (width, _) = fb.dimensions();
+        let mut cursor_x = x;
+
+        for c in s.chars() {
+            for_each_cell(c, |cell, rows| {
+                // Stop if we go off screen
+                if cursor_x < width {
+                    self.draw_glyph(fb, cursor_x, y, GlyphKey { c: cell, scale, color }, &rows);
+                    cursor_x += CHAR_WIDTH * scale;
+                }
+            });
+        }
+    }
+
+    /// Draw a character, as [`draw_char_scaled`](crate::font::draw_char_scaled)
+    /// would
+    pub fn draw_char(&mut self, fb: &mut Framebuffer, x: u32, y: u32, c: char, color: Color, scale: u32) {
+        let mut first = None;
+        for_each_cell(c, |cell, rows| {
+            first.get_or_insert((cell, rows));
+        });
+        if let Some((cell, rows)) = first {
+            self.draw_glyph(fb, x, y, GlyphKey { c: cell, scale, color }, &rows);
+        }
+    }
+
+    /// Forget every cached glyph
+    pub fn clear(&mut self) {
+        self.keys = [None; GLYPH_SLOTS];
+        self.stamps = [0; GLYPH_SLOTS];
+        self.clock = 0;
+    }
+
+    /// Glyphs drawn from the cache, and glyphs that had to be rendered
+    pub const fn stats(&self) -> (u32, u32) {
+        (self.hits, self.misses)
+    }
+
+    fn draw_glyph(&mut self, fb: &mut Framebuffer, x: u32, y: u32, key: GlyphKey, rows: &[u8; 8]) {
+        if key.scale == 0 || key.scale > MAX_CACHED_SCALE || key.color.to_argb() == BACKGROUND {
+            draw_cell(fb, x, y, rows, key.color, key.scale);
+            return;
+        }
+        let slot = self.slot_for(key, rows);
+        self.copy_out(fb, slot, CHAR_WIDTH * key.scale, x, y);
+    }
+
+    /// The slot holding `key`, rendering it into the oldest slot first if
+    /// it is not cached
+    fn slot_for(&mut self, key: GlyphKey, rows: &[u8; 8]) -> usize {
+        if self.clock == u32::MAX {
+            self.clear();
+        }
+        self.clock += 1;
+
+        if let Some(slot) = self.keys.iter().position(|k| *k == Some(key)) {
+            self.stamps[slot] = self.clock;
+            self.hits = self.hits.wrapping_add(1);
+            return slot;
+        }
+
+        let slot = lru_slot(&self.stamps);
+        let (ox, oy) = slot_origin(slot);
+        {
+            let mut canvas = self.atlas.canvas();
+            canvas.fill_rect(ox as u32, oy as u32, GLYPH_SLOT as u32, GLYPH_SLOT as u32, Color::from_argb(BACKGROUND));
+            draw_cell(&mut canvas, ox as u32, oy as u32, rows, key.color, key.scale);
+        }
+
+        self.keys[slot] = Some(key);
+        self.stamps[slot] = self.clock;
+        self.misses = self.misses.wrapping_add(1);
+        slot
+    }
+
+    /// Copy the `size` x `size` glyph in `slot` to (x, y), one run of ink
+    /// at a time so the screen shows through around it
+    fn copy_out(&self, fb: &mut Framebuffer, slot: usize, size: u32, x: u32, y: u32) {
+        let (ox, oy) = slot_origin(slot);
+        let pixels = self.atlas.pixels();
+        for row in 0..size as usize {
+            let start = (oy + row) * ATLAS_WIDTH + ox;
+            let line = &pixels[start..start + size as usize];
+            let dy = y + row as u32;
+            let mut col = 0;
+            while col < line.len() {
+                if line[col] == BACKGROUND {
+                    col += 1;
+                    continue;
+                }
+                let run = line[col..].iter().take_while(|&&px| px != BACKGROUND).count();
+                fb.blit(x + col as u32, dy, run as u32, 1, &line[col..col + run]);
+                col += run;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::{draw_char_scaled, draw_string_scaled};
+
+    const W: u32 = 80;
+    const H: u32 = 40;
+    const N: usize = (W * H) as usize;
+
+    #[test]
+    fn cached_text_matches_direct_drawing() {
+        static mut CACHE: GlyphCache = GlyphCache::new();
+        let cache = unsafe { &mut *core::ptr::addr_of_mut!(CACHE) };
+
+        let mut direct = [0xFF10_2030u32; N];
+        let mut cached = direct;
+        for (scale, y) in [(1, 0), (3, 8), (4, 8)] {
+            draw_string_scaled(&mut Framebuffer::over(&mut direct, W, H), 2, y, "Æ1?", Color::YELLOW, scale);
+            cache.draw_string(&mut Framebuffer::over(&mut cached, W, H), 2, y, "Æ1?", Color::YELLOW, scale);
+        }
+        assert_eq!(direct, cached);
+        // 'A', 'E', '1' and '?' rendered once per scale ('?' at 4x is
+        // off screen)
+        assert_eq!(cache.stats(), (0, 11));
+
+        // Again, and in a new color
+        let mut scratch = [0u32; N];
+        cache.draw_string(&mut Framebuffer::over(&mut scratch, W, H), 2, 0, "AE", Color::YELLOW, 1);
+        cache.draw_char(&mut Framebuffer::over(&mut scratch, W, H), 60, 20, 'E', Color::RED, 1);
+        assert_eq!(cache.stats(), (2, 12));
+
+        // Too big for a slot: drawn directly, the same way
+        draw_char_scaled(&mut Framebuffer::over(&mut direct, W, H), 0, 0, '#', Color::WHITE, 5);
+        cache.draw_char(&mut Framebuffer::over(&mut cached, W, H), 0, 0, '#', Color::WHITE, 5);
+        assert_eq!(direct, cached);
+        assert_eq!(cache.stats(), (2, 12));
+    }
+
+    #[test]
+    fn full_cache_evicts_least_recently_drawn() {
+        static mut CACHE: GlyphCache = GlyphCache::new();
+        let cache = unsafe { &mut *core::ptr::addr_of_mut!(CACHE) };
+        let mut screen = [0u32; N];
+        let mut fb = Framebuffer::over(&mut screen, W, H);
+
+        // Fill every slot, then draw the first glyph again so the second
+        // is now the oldest
+        let glyphs: [char; GLYPH_SLOTS] = core::array::from_fn(|i| (b'0' + i as u8) as char);
+        for &c in &glyphs {
+            cache.draw_char(&mut fb, 0, 0, c, Color::WHITE, 2);
+        }
+        cache.draw_char(&mut fb, 0, 0, glyphs[0], Color::WHITE, 2);
+        assert_eq!(cache.stats(), (1, GLYPH_SLOTS as u32));
+
+        cache.draw_char(&mut fb, 0, 0, 'Z', Color::WHITE, 2);
+        cache.draw_char(&mut fb, 0, 0, glyphs[0], Color::WHITE, 2);
+        assert_eq!(cache.stats(), (2, GLYPH_SLOTS as u32 + 1));
+        cache.draw_char(&mut fb, 0, 0, glyphs[1], Color::WHITE, 2);
+        assert_eq!(cache.stats(), (2, GLYPH_SLOTS as u32 + 2));
+
+        let mut stamps = [5u32; GLYPH_SLOTS];
+        stamps[7] = 2;
+        stamps[9] = 2;
+        assert_eq!(lru_slot(&stamps), 7);
+        assert_eq!(slot_origin(GLYPH_SLOTS - 1), (ATLAS_WIDTH - GLYPH_SLOT, ATLAS_HEIGHT - GLYPH_SLOT));
+    }
+}
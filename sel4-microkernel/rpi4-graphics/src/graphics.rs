@@ -1,18 +1,30 @@
 //! # Graphics Primitives
 //!
-//! Colors, points, and basic drawing operations.
+//! Colors, points, and drawing operations on a [`Framebuffer`]: lines,
+//! circles and ellipses, polygons and rounded rectangles, outlined or
+//! filled, plus the boxes and arrows of the architecture diagrams.
+//!
+//! Shapes take signed coordinates and may lie partly or wholly off screen.
+//! Every pixel and span goes through [`clip_point`] or [`clip_span`] before
+//! it reaches the framebuffer, so off-screen parts cost nothing and never
+//! wrap around.
 //!
 //! ## Verus Verification
 //! Key properties verified:
+//! - `clip_point` only returns on-screen coordinates, unchanged
+//! - `clip_span` returns a non-empty on-screen sub-span of its input, and
+//!   `None` only when nothing of the input is on screen
+//!
+//! Properties documented but not yet under Verus (annotations disabled):
 //! - Color round-trip: `from_argb(to_argb(c)) == c`
 //! - Rectangle containment: correct boundary logic
-//! - All pixel operations bounds-checked
 
-// Verus imports disabled for build testing
-// #[allow(unused_imports)]
-// use verus_builtin::*;
-// #[allow(unused_imports)]
-// use verus_builtin_macros::verus;
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::framebuffer::{DrawError, Framebuffer};
 
 /// ARGB color (Alpha, Red, Green, Blue)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -123,7 +135,69 @@ impl Rect {
     }
 }
 
+verus! {
+
+/// On-screen pixel for (`x`, `y`), or `None` if it lies outside a
+/// `width` x `height` screen
+pub fn clip_point(x: i32, y: i32, width: u32, height: u32) -> (r: Option<(u32, u32)>)
+    ensures
+        r.is_some() ==> r.unwrap().0 < width && r.unwrap().1 < height
+            && r.unwrap().0 as int == x as int && r.unwrap().1 as int == y as int,
+        r.is_none() ==> x < 0 || y < 0 || x as int >= width as int || y as int >= height as int,
+{
+    if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+        None
+    } else {
+        Some((x as u32, y as u32))
+    }
+}
+
+/// The part of the inclusive span `start..=end` inside `0..limit`, as a
+/// half-open `(from, to)`, or `None` if no part of it is
+pub fn clip_span(start: i32, end: i32, limit: u32) -> (r: Option<(u32, u32)>)
+    ensures
+        r.is_some() ==> r.unwrap().0 < r.unwrap().1 && r.unwrap().1 <= limit
+            && start as int <= r.unwrap().0 as int && r.unwrap().1 as int <= end as int + 1,
+        r.is_none() ==> end < start || end < 0 || start as int >= limit as int,
+{
+    if end < start || end < 0 || (start >= 0 && start as u32 >= limit) {
+        return None;
+    }
+    let from: u32 = if start < 0 { 0 } else { start as u32 };
+    let to: u32 = if end as u32 >= limit { limit } else { end as u32 + 1 };
+    Some((from, to))
+}
+
+} // verus!
+
+/// Largest radius the circle and ellipse routines draw; larger radii are
+/// clamped (no screen is that big, and it keeps the arithmetic in range)
+pub const MAX_RADIUS: u32 = 0x7FFF;
+
+/// Most vertices [`fill_polygon`] takes
+pub const MAX_POLYGON_VERTICES: usize = 64;
+
+/// Set one pixel, if it is on screen
+#[inline]
+fn plot(fb: &mut Framebuffer, x: i32, y: i32, color: Color) {
+    let (width, height) = fb.dimensions();
+    if let Some((x, y)) = clip_point(x, y, width, height) {
+        fb.put_pixel(x, y, color);
+    }
+}
+
+/// Fill pixels `x0..=x1` of row `y`, clipped to the screen
+#[inline]
+fn span(fb: &mut Framebuffer, x0: i32, x1: i32, y: i32, color: Color) {
+    let (width, height) = fb.dimensions();
+    if let (Some((y, _)), Some((from, to))) = (clip_span(y, y, height), clip_span(x0, x1, width)) {
+        fb.hline(from, y, to - from, color);
+    }
+}
+
 /// Draw a line using Bresenham's algorithm
+///
+/// A line entirely to one side of the screen is skipped without walking it.
 pub fn draw_line(
     fb: &mut crate::Framebuffer,
     x0: i32,
@@ -132,19 +206,27 @@ pub fn draw_line(
     y1: i32,
     color: Color,
 ) {
-    let dx = (x1 - x0).abs();
-    let dy = -(y1 - y0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
+    let (width, height) = fb.dimensions();
+    let (width, height) = (width as i64, height as i64);
+    let (lo_x, hi_x) = (x0.min(x1) as i64, x0.max(x1) as i64);
+    let (lo_y, hi_y) = (y0.min(y1) as i64, y0.max(y1) as i64);
+    if hi_x < 0 || hi_y < 0 || lo_x >= width || lo_y >= height {
+        return;
+    }
+
+    // i64 so endpoints far apart cannot overflow the error terms
+    let (x1, y1) = (x1 as i64, y1 as i64);
+    let dx = (x1 - x0 as i64).abs();
+    let dy = -(y1 - y0 as i64).abs();
+    let sx = if (x0 as i64) < x1 { 1 } else { -1 };
+    let sy = if (y0 as i64) < y1 { 1 } else { -1 };
     let mut err = dx + dy;
 
-    let mut x = x0;
-    let mut y = y0;
+    let mut x = x0 as i64;
+    let mut y = y0 as i64;
 
     loop {
-        if x >= 0 && y >= 0 {
-            fb.put_pixel(x as u32, y as u32, color);
-        }
+        plot(fb, x as i32, y as i32, color);
 
         if x == x1 && y == y1 {
             break;
@@ -164,6 +246,190 @@ pub fn draw_line(
     }
 }
 
+/// Walk the first-quadrant arc of an `rx` x `ry` ellipse (midpoint
+/// algorithm), calling `f` with each boundary offset (x, y), x and y >= 0,
+/// from (0, ry) to (rx, 0)
+fn ellipse_arc(rx: u32, ry: u32, mut f: impl FnMut(i32, i32)) {
+    let (rx, ry) = (rx.min(MAX_RADIUS) as i64, ry.min(MAX_RADIUS) as i64);
+    if rx == 0 || ry == 0 {
+        // Degenerate: a straight line along the non-zero axis
+        f(rx as i32, ry as i32);
+        f(0, 0);
+        return;
+    }
+    let (a2, b2) = (rx * rx, ry * ry);
+    let (mut x, mut y) = (0i64, ry);
+    let (mut px, mut py) = (0i64, 2 * a2 * y);
+
+    // Region 1: slope shallower than -1, step x
+    let mut p = b2 - a2 * ry + a2 / 4;
+    while px < py {
+        f(x as i32, y as i32);
+        x += 1;
+        px += 2 * b2;
+        if p < 0 {
+            p += b2 + px;
+        } else {
+            y -= 1;
+            py -= 2 * a2;
+            p += b2 + px - py;
+        }
+    }
+
+    // Region 2: slope steeper than -1, step y
+    p = (b2 * (2 * x + 1) * (2 * x + 1)) / 4 + a2 * (y - 1) * (y - 1) - a2 * b2;
+    while y >= 0 {
+        f(x as i32, y as i32);
+        y -= 1;
+        py -= 2 * a2;
+        if p > 0 {
+            p += a2 - py;
+        } else {
+            x += 1;
+            px += 2 * b2;
+            p += a2 - py + px;
+        }
+    }
+}
+
+/// Draw the outline of an ellipse centred on (`cx`, `cy`) with radii `rx`
+/// and `ry` (it spans `2 * rx + 1` pixels across)
+pub fn draw_ellipse(fb: &mut Framebuffer, cx: i32, cy: i32, rx: u32, ry: u32, color: Color) {
+    ellipse_arc(rx, ry, |x, y| {
+        plot(fb, cx + x, cy + y, color);
+        plot(fb, cx - x, cy + y, color);
+        plot(fb, cx + x, cy - y, color);
+        plot(fb, cx - x, cy - y, color);
+    });
+}
+
+/// Fill an ellipse centred on (`cx`, `cy`) with radii `rx` and `ry`
+pub fn fill_ellipse(fb: &mut Framebuffer, cx: i32, cy: i32, rx: u32, ry: u32, color: Color) {
+    ellipse_arc(rx, ry, |x, y| {
+        span(fb, cx - x, cx + x, cy + y, color);
+        span(fb, cx - x, cx + x, cy - y, color);
+    });
+}
+
+/// Draw the outline of a circle centred on (`cx`, `cy`)
+pub fn draw_circle(fb: &mut Framebuffer, cx: i32, cy: i32, radius: u32, color: Color) {
+    draw_ellipse(fb, cx, cy, radius, radius, color);
+}
+
+/// Fill a circle centred on (`cx`, `cy`)
+pub fn fill_circle(fb: &mut Framebuffer, cx: i32, cy: i32, radius: u32, color: Color) {
+    fill_ellipse(fb, cx, cy, radius, radius, color);
+}
+
+/// Draw a closed polygon outline through `points`
+pub fn draw_polygon(fb: &mut Framebuffer, points: &[Point], color: Color) {
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        draw_line(fb, a.x, a.y, b.x, b.y, color);
+    }
+}
+
+/// Fill the polygon through `points` (even-odd rule, so self-intersecting
+/// outlines leave their overlaps open)
+///
+/// Pixels are filled when their top-left corner is inside, so polygons
+/// sharing an edge do not overlap. Refuses more than
+/// [`MAX_POLYGON_VERTICES`] points.
+pub fn fill_polygon(fb: &mut Framebuffer, points: &[Point], color: Color) -> Result<(), DrawError> {
+    if points.len() > MAX_POLYGON_VERTICES {
+        return Err(DrawError::TooManyVertices { len: points.len(), max: MAX_POLYGON_VERTICES });
+    }
+    if points.len() < 3 {
+        return Ok(());
+    }
+    let (_, height) = fb.dimensions();
+    let lo = points.iter().map(|p| p.y).min().unwrap_or(0);
+    let hi = points.iter().map(|p| p.y).max().unwrap_or(0);
+    // Rows whose top edge is inside: lo..hi (the bottom row is excluded)
+    let Some((first, end)) = clip_span(lo, hi - 1, height) else {
+        return Ok(());
+    };
+
+    let mut crossings = [0i32; MAX_POLYGON_VERTICES];
+    for y in first..end {
+        let y = y as i64;
+        let mut n = 0;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let (ay, by) = (a.y as i64, b.y as i64);
+            // Half-open in y, so a vertex is counted once
+            if (ay <= y) == (by <= y) {
+                continue;
+            }
+            let (ax, bx) = (a.x as i64, b.x as i64);
+            // Edge x at this row, rounded up to the first pixel at or right of it
+            let x = ax + ((y - ay) * (bx - ax)).div_euclid(by - ay)
+                + ((y - ay) * (bx - ax)).rem_euclid(by - ay).min(1);
+            crossings[n] = x.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+            n += 1;
+        }
+        let crossings = &mut crossings[..n];
+        crossings.sort_unstable();
+        for pair in crossings.chunks_exact(2) {
+            if pair[0] < pair[1] {
+                span(fb, pair[0], pair[1] - 1, y as i32, color);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Corner radius that fits `rect`: at most half its shorter side
+fn corner_radius(rect: &Rect, radius: u32) -> u32 {
+    radius.min(rect.width / 2).min(rect.height / 2)
+}
+
+/// Draw the outline of `rect` with its corners rounded to `radius`
+pub fn draw_rounded_rect(fb: &mut Framebuffer, rect: Rect, radius: u32, color: Color) {
+    if rect.width == 0 || rect.height == 0 {
+        return;
+    }
+    let r = corner_radius(&rect, radius) as i32;
+    let (left, top) = (rect.x, rect.y);
+    let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+
+    // Straight edges between the corner arcs
+    span(fb, left + r, right - r, top, color);
+    span(fb, left + r, right - r, bottom, color);
+    for y in (top + r)..=(bottom - r) {
+        plot(fb, left, y, color);
+        plot(fb, right, y, color);
+    }
+
+    ellipse_arc(r as u32, r as u32, |x, y| {
+        plot(fb, right - r + x, bottom - r + y, color);
+        plot(fb, left + r - x, bottom - r + y, color);
+        plot(fb, right - r + x, top + r - y, color);
+        plot(fb, left + r - x, top + r - y, color);
+    });
+}
+
+/// Fill `rect` with its corners rounded to `radius`
+pub fn fill_rounded_rect(fb: &mut Framebuffer, rect: Rect, radius: u32, color: Color) {
+    if rect.width == 0 || rect.height == 0 {
+        return;
+    }
+    let r = corner_radius(&rect, radius) as i32;
+    let (left, top) = (rect.x, rect.y);
+    let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+
+    let (_, height) = fb.dimensions();
+    if let Some((from, to)) = clip_span(top + r, bottom - r, height) {
+        for y in from..to {
+            span(fb, left, right, y as i32, color);
+        }
+    }
+    ellipse_arc(r as u32, r as u32, |x, y| {
+        span(fb, left + r - x, right - r + x, top + r - y, color);
+        span(fb, left + r - x, right - r + x, bottom - r + y, color);
+    });
+}
+
 /// Draw a box with label (for architecture diagrams)
 pub fn draw_box(
     fb: &mut crate::Framebuffer,
@@ -234,3 +500,130 @@ pub fn draw_arrow_down(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: u32 = 16;
+    const H: u32 = 12;
+
+    /// Rows of `mem` as strings, '#' for set pixels
+    fn rows(mem: &[u32]) -> [[u8; W as usize]; H as usize] {
+        let mut out = [[b'.'; W as usize]; H as usize];
+        for (i, &px) in mem.iter().enumerate() {
+            if px != 0 {
+                out[i / W as usize][i % W as usize] = b'#';
+            }
+        }
+        out
+    }
+
+    fn count(mem: &[u32]) -> usize {
+        mem.iter().filter(|&&px| px != 0).count()
+    }
+
+    #[test]
+    fn clipping_keeps_only_on_screen_parts() {
+        assert_eq!(clip_point(3, 4, 8, 8), Some((3, 4)));
+        assert_eq!(clip_point(-1, 4, 8, 8), None);
+        assert_eq!(clip_point(3, 8, 8, 8), None);
+        assert_eq!(clip_span(-5, 3, 8), Some((0, 4)));
+        assert_eq!(clip_span(6, i32::MAX, 8), Some((6, 8)));
+        assert_eq!(clip_span(8, 9, 8), None);
+        assert_eq!(clip_span(3, 2, 8), None);
+        assert_eq!(clip_span(i32::MIN, -1, 8), None);
+    }
+
+    #[test]
+    fn far_off_lines_and_shapes_are_clipped() {
+        let mut mem = [0u32; (W * H) as usize];
+        let mut fb = Framebuffer::over(&mut mem, W, H);
+        draw_line(&mut fb, -1_000_000, 5, 1_000_000, 5, Color::WHITE);
+        draw_line(&mut fb, i32::MIN, -3, i32::MAX, -3, Color::WHITE);
+        fill_circle(&mut fb, -40, 5, 10, Color::WHITE);
+        fill_ellipse(&mut fb, 8, 6, u32::MAX, 0, Color::WHITE);
+        let rows = rows(&mem);
+        assert!(rows[5].iter().all(|&p| p == b'#'));
+        assert!(rows[6].iter().all(|&p| p == b'#'));
+        assert_eq!(count(&mem), 2 * W as usize);
+    }
+
+    #[test]
+    fn circle_is_symmetric_and_fill_covers_outline() {
+        let mut outline = [0u32; (W * H) as usize];
+        draw_circle(&mut Framebuffer::over(&mut outline, W, H), 7, 5, 4, Color::WHITE);
+        let mut filled = [0u32; (W * H) as usize];
+        fill_circle(&mut Framebuffer::over(&mut filled, W, H), 7, 5, 4, Color::WHITE);
+
+        let (outline, filled) = (rows(&outline), rows(&filled));
+        for y in 0..H as usize {
+            for x in 0..W as usize {
+                if outline[y][x] == b'#' {
+                    assert_eq!(filled[y][x], b'#');
+                    // Mirror images through the centre
+                    assert_eq!(outline[y][14 - x], b'#');
+                    assert_eq!(outline[10 - y][x], b'#');
+                }
+            }
+        }
+        // Extremes at radius 4, nothing beyond
+        assert_eq!(&filled[5][2..13], b".#########.");
+        assert_eq!(filled[0][7], b'.');
+        assert_eq!((filled[1][7], filled[9][7]), (b'#', b'#'));
+    }
+
+    #[test]
+    fn polygon_fill_is_half_open_and_bounded() {
+        let mut mem = [0u32; (W * H) as usize];
+        let mut fb = Framebuffer::over(&mut mem, W, H);
+        // 4x3 rectangle as a polygon covers exactly 12 pixels
+        let square = [Point::new(2, 1), Point::new(6, 1), Point::new(6, 4), Point::new(2, 4)];
+        fill_polygon(&mut fb, &square, Color::WHITE).unwrap();
+        assert_eq!(count(&mem), 12);
+        assert_eq!(&rows(&mem)[1][1..8], b".####..");
+
+        let many = [Point::ORIGIN; MAX_POLYGON_VERTICES + 1];
+        let mut fb = Framebuffer::over(&mut mem, W, H);
+        assert_eq!(
+            fill_polygon(&mut fb, &many, Color::WHITE),
+            Err(DrawError::TooManyVertices { len: 65, max: MAX_POLYGON_VERTICES })
+        );
+
+        // Triangle: each row narrower than the last
+        let mut mem = [0u32; (W * H) as usize];
+        let triangle = [Point::new(0, 0), Point::new(10, 10), Point::new(0, 10)];
+        fill_polygon(&mut Framebuffer::over(&mut mem, W, H), &triangle, Color::WHITE).unwrap();
+        let rows = rows(&mem);
+        for (y, row) in rows.iter().enumerate().take(10) {
+            assert_eq!(row.iter().filter(|&&p| p == b'#').count(), y);
+        }
+    }
+
+    #[test]
+    fn rounded_rect_trims_corners() {
+        let mut mem = [0u32; (W * H) as usize];
+        let rect = Rect::new(1, 1, 10, 8);
+        fill_rounded_rect(&mut Framebuffer::over(&mut mem, W, H), rect, 3, Color::WHITE);
+        let filled = rows(&mem);
+        assert_eq!(filled[1][1], b'.');
+        assert_eq!(filled[1][4], b'#');
+        assert_eq!(&filled[4][0..12], b".##########.");
+        assert_eq!(filled[8][10], b'.');
+
+        let mut mem = [0u32; (W * H) as usize];
+        draw_rounded_rect(&mut Framebuffer::over(&mut mem, W, H), rect, 3, Color::WHITE);
+        let outline = rows(&mem);
+        assert_eq!(&outline[4][0..12], b".#........#.");
+        // Every outline pixel is inside the filled shape
+        for y in 0..H as usize {
+            for x in 0..W as usize {
+                assert!(outline[y][x] == b'.' || filled[y][x] == b'#');
+            }
+        }
+        // Zero radius is a plain rectangle
+        let mut mem = [0u32; (W * H) as usize];
+        fill_rounded_rect(&mut Framebuffer::over(&mut mem, W, H), rect, 0, Color::WHITE);
+        assert_eq!(count(&mem), 80);
+    }
+}
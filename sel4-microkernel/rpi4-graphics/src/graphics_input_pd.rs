@@ -10,10 +10,59 @@
 //!    - GPIO registers (LED control)
 //!    - Framebuffer memory (graphics output)
 //!    - DMA buffer (mailbox messages)
+//!    - SMI registers (vsync interrupt status)
 //!    - Shared ring buffer (input events - read only)
+//!    - Draw ring (status LCD commands, with `status-lcd`)
 //!
 //! 2. **No Direct Hardware Input**: Cannot access UART directly,
 //!    all input comes through the verified ring buffer protocol.
+//!
+//! The About screen shows the SoC temperature, read over the mailbox each
+//! time the screen is opened.
+//!
+//! ## Frame Pacing
+//!
+//! The firmware raises the SMI interrupt at every vertical blank once asked
+//! to, and a [`FrameClock`] turns every third blank into a frame: the snake
+//! and the screensaver move at 20 Hz, torn-free, whether or not a key is
+//! pressed. Menus still redraw as soon as a key changes them. If the
+//! firmware refuses the vsync tag, the PD renders on input as before.
+//!
+//! ## Sound (`audio` feature)
+//!
+//! With the Audio PD in the system (CONFIG_AUDIO), moving through the menu
+//! clicks and selecting an item beeps, each a protected procedure call
+//! naming a tone. Starting the snake game plays a jingle this PD renders
+//! itself, on a local mixer, and pushes into the audio stream; this PD
+//! maps no audio hardware.
+//!
+//! ## Early Console (`early-console` feature)
+//!
+//! As soon as the framebuffer is mapped the screen shows a boot log instead
+//! of the menu: this PD's own init results and, with `network`, the lines
+//! the Network PD writes to its boot log page (interface and link state),
+//! so a board with no serial console attached still shows why networking
+//! did not come up. New lines are drawn as they arrive; the first key
+//! press opens the menu.
+//!
+//! ## Status LCD (`status-lcd` feature)
+//!
+//! With the SPI LCD's Display PD beside this one (`tvdemo-dual.system`),
+//! the menu, the game and the About screen stay on HDMI while a status
+//! panel goes to the LCD: a block per menu item, outlined when selected and
+//! marked while it runs. Both displays are registered with a
+//! `DisplayRouter` by role and the panel is drawn for the `Status` role;
+//! the LCD is only presented when the router saw it damaged. Its pixels
+//! belong to the Display PD, which validates and draws the commands this
+//! PD queues on the draw ring, so this PD maps no SPI hardware.
+//!
+//! ## Split Render Mode (`split` feature)
+//!
+//! Built with `--features split` this PD becomes the App PD of
+//! `tvdemo-split.system`: it maps no devices at all, renders each frame into
+//! a shared slot and publishes it to the Present PD, which owns the
+//! framebuffer and vsync. A new frame is rendered whenever the Present PD
+//! hands a slot back.
 
 #![no_std]
 #![no_main]
@@ -21,11 +70,34 @@
 extern crate alloc;
 
 use sel4_microkit::{debug_println, protection_domain, Handler, ChannelSet, Channel};
-use core::fmt;
-use core::sync::atomic::Ordering;
+use rpi4_pd_error::PdError;
 use linked_list_allocator::LockedHeap;
 
-use rpi4_graphics::{Mailbox, Framebuffer, MAILBOX_BASE};
+#[cfg(not(feature = "split"))]
+use rpi4_graphics::{
+    vsync::REFRESH_HZ, FrameClock, Framebuffer, Mailbox, Vsync, FIXED_720P_LADDER, MAILBOX_BASE, SMI_VIRT_BASE,
+};
+#[cfg(not(feature = "split"))]
+use core::fmt::Write;
+#[cfg(not(feature = "split"))]
+use verified_microkernel::BoundedString;
+#[cfg(feature = "early-console")]
+use rpi4_graphics::EarlyConsole;
+#[cfg(feature = "early-console")]
+use rpi4_pd_error::bootlog::{Level, BOOT_LOG_LINE_LEN};
+#[cfg(all(feature = "early-console", feature = "network"))]
+use rpi4_pd_error::bootlog::BootLogReader;
+#[cfg(feature = "status-lcd")]
+use rpi4_draw_protocol::DRAW_REGION_VADDR;
+#[cfg(feature = "status-lcd")]
+use rpi4_graphics::{DrawRingBackend, HdmiBackend};
+#[cfg(feature = "status-lcd")]
+use rpi4_tvdemo::{Color, DisplayBackend, DisplayRole, DisplayRouter};
+#[cfg(feature = "split")]
+use rpi4_frame_protocol::{
+    header_ptr as frame_header_ptr, slot_ptr, FrameProducer, HandoffError, APP_FRAME_CHANNEL_ID,
+    FRAME_REGION_VADDR,
+};
 
 // Global allocator for alloc-dependent code
 #[global_allocator]
@@ -36,17 +108,30 @@ const HEAP_SIZE: usize = 64 * 1024;
 static mut HEAP: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
 use rpi4_input::{KeyCode, KeyState};
 use rpi4_input_protocol::{
-    InputRingHeader, InputRingEntry, INPUT_CHANNEL_ID,
+    InputRingEntry, INPUT_CHANNEL_ID,
     header_ptr, entries_ptr,
 };
+#[cfg(feature = "audio")]
+use rpi4_audio_protocol::{
+    stream::stream_ring, AudioRequest, Mixer, StreamRing, AUDIO_CHANNEL_ID, AUDIO_RATE_HZ, AUDIO_STREAM_VADDR,
+    Q8_ONE,
+};
+#[cfg(any(not(feature = "split"), feature = "audio"))]
+use rpi4_pd_error::{ErrorDomain, ErrorKind};
+#[cfg(feature = "audio")]
+use rpi4_pd_error::ResultExt;
+#[cfg(feature = "audio")]
+use sel4_microkit::{with_msg_regs, with_msg_regs_mut, MessageInfo};
+#[cfg(feature = "audio")]
+use verified_microkernel::{rpc::{self, RpcError}, IpcBuffer};
 #[cfg(feature = "network")]
-use rpi4_network_protocol::{ring_flags, NetSharedMemory, NET_CLIENT_CHANNEL_ID, RING_SIZE};
-
-/// Screen dimensions
-const WIDTH: u32 = 1280;
-const HEIGHT: u32 = 720;
+use rpi4_network_protocol::{
+    link_speed, proof, ring_flags, NetSharedMemory, NetStatusSnapshot, NET_CLIENT_CHANNEL_ID,
+};
+use verified_microkernel::{abs_diff_u32, wrap_i32};
 
 /// GPIO virtual address
+#[cfg(not(feature = "split"))]
 const GPIO_BASE: usize = 0x5_0200_0000;
 
 /// Shared ring buffer virtual address
@@ -55,6 +140,16 @@ const RING_BUFFER_VADDR: usize = 0x5_0400_0000;
 /// Input channel for notifications from Input PD
 const INPUT_CHANNEL: Channel = Channel::new(INPUT_CHANNEL_ID);
 
+/// Vsync IRQ channel (must match the SMI `<irq>` id in the .system files)
+#[cfg(not(feature = "split"))]
+const VSYNC_IRQ_CHANNEL_ID: usize = 6;
+#[cfg(not(feature = "split"))]
+const VSYNC_CHANNEL: Channel = Channel::new(VSYNC_IRQ_CHANNEL_ID);
+
+/// Vertical blanks per snake step
+#[cfg(not(feature = "split"))]
+const VSYNC_FRAMES_PER_STEP: u32 = 3;
+
 /// Network shared ring virtual address (must match tvdemo-network.system)
 #[cfg(feature = "network")]
 const NET_RING_VADDR: usize = 0x5_0700_0000;
@@ -63,6 +158,33 @@ const NET_RING_VADDR: usize = 0x5_0700_0000;
 #[cfg(feature = "network")]
 const NET_CHANNEL: Channel = Channel::new(NET_CLIENT_CHANNEL_ID);
 
+/// Network PD boot log page (must match tvdemo-network.system)
+#[cfg(all(feature = "early-console", feature = "network"))]
+const NET_BOOT_LOG_VADDR: usize = 0x5_0B00_0000;
+
+#[cfg(all(feature = "early-console", feature = "split"))]
+compile_error!("early-console draws on the framebuffer, which the split App PD does not own");
+
+#[cfg(all(feature = "status-lcd", feature = "split"))]
+compile_error!("status-lcd routes around the HDMI framebuffer, which the split App PD does not own");
+
+/// Channel to the Display PD's draw ring (must match tvdemo-dual.system;
+/// the protocol's App end, 4, is the Audio PD's channel in this PD)
+#[cfg(feature = "status-lcd")]
+const STATUS_LCD_CHANNEL_ID: usize = 7;
+#[cfg(feature = "status-lcd")]
+const STATUS_LCD_CHANNEL: Channel = Channel::new(STATUS_LCD_CHANNEL_ID);
+
+/// The Display PD's framebuffer
+#[cfg(feature = "status-lcd")]
+const STATUS_LCD_WIDTH: u16 = 320;
+#[cfg(feature = "status-lcd")]
+const STATUS_LCD_HEIGHT: u16 = 240;
+
+/// Channel to the Present PD (frame published / slot released)
+#[cfg(feature = "split")]
+const PRESENT_CHANNEL: Channel = Channel::new(APP_FRAME_CHANNEL_ID);
+
 /// Application state
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum AppState {
@@ -109,54 +231,60 @@ fn u8_to_key_code(code: u8) -> KeyCode {
     }
 }
 
+/// Entries pulled out of the input ring per batch
+const INPUT_BATCH: usize = 16;
+
 /// Input reader from shared ring buffer
+///
+/// Entries are drained in batches of up to [`INPUT_BATCH`], so the read
+/// index is published once per batch rather than once per key.
 struct RingBufferInput {
     ring_base: *mut u8,
+    batch: [InputRingEntry; INPUT_BATCH],
+    batch_len: usize,
+    batch_pos: usize,
 }
 
 impl RingBufferInput {
     const fn new() -> Self {
         Self {
             ring_base: RING_BUFFER_VADDR as *mut u8,
+            batch: [InputRingEntry::EMPTY; INPUT_BATCH],
+            batch_len: 0,
+            batch_pos: 0,
         }
     }
 
+    /// Pull the next batch of entries out of the ring; false if it is empty
+    fn refill(&mut self) -> bool {
+        unsafe {
+            let header = &*header_ptr(self.ring_base);
+            self.batch_len = header.pop_batch(entries_ptr(self.ring_base), &mut self.batch);
+        }
+        self.batch_pos = 0;
+        self.batch_len > 0
+    }
+
     /// Poll for next input event from ring buffer
     ///
     /// ## Verification Properties (Verus)
     /// - Only reads from shared memory region
-    /// - Updates read_idx atomically
-    /// - Returns valid KeyCode/KeyState pairs
+    /// - Updates read_idx atomically, once per batch
+    /// - Returns valid KeyCode/KeyState pairs; non-key entries are skipped
     fn poll(&mut self) -> Option<(KeyCode, KeyState)> {
-        unsafe {
-            let header = &*header_ptr(self.ring_base);
-
-            // Check if data available
-            if !header.has_data() {
+        loop {
+            if self.batch_pos == self.batch_len && !self.refill() {
                 return None;
             }
-
-            // Read entry at current read index
-            let read_idx = header.current_read_idx();
-            let entries = entries_ptr(self.ring_base);
-            let entry = entries.add(read_idx as usize).read_volatile();
-
-            // Memory barrier before advancing
-            core::sync::atomic::fence(Ordering::Acquire);
-
-            // Advance read index
-            header.advance_read();
+            let entry = self.batch[self.batch_pos];
+            self.batch_pos += 1;
 
             // Convert to KeyCode/KeyState
             if entry.is_key_pressed() {
-                let key_code = u8_to_key_code(entry.key_code);
-                Some((key_code, KeyState::Pressed))
+                return Some((u8_to_key_code(entry.key_code), KeyState::Pressed));
             } else if entry.event_type == 1 {
                 // Key released
-                let key_code = u8_to_key_code(entry.key_code);
-                Some((key_code, KeyState::Released))
-            } else {
-                None
+                return Some((u8_to_key_code(entry.key_code), KeyState::Released));
             }
         }
     }
@@ -166,10 +294,13 @@ impl RingBufferInput {
 ///
 /// Reads received packets out of the RX ring published by the Network PD.
 /// The client owns `rx_read_idx`; the Network PD owns `rx_write_idx`,
-/// `mac_address` and `link_up`, so those are read volatilely.
+/// `mac_address` and the status block, so those are read volatilely or
+/// through a status snapshot.
 #[cfg(feature = "network")]
 struct NetClient {
     shared: *mut NetSharedMemory,
+    /// Last consistent status snapshot (link state, counters)
+    status: Option<NetStatusSnapshot>,
 }
 
 #[cfg(feature = "network")]
@@ -177,12 +308,19 @@ impl NetClient {
     const fn new() -> Self {
         Self {
             shared: NET_RING_VADDR as *mut NetSharedMemory,
+            status: None,
         }
     }
 
-    /// Drain the RX ring: consume every valid entry the Network PD queued
-    /// and log a summary of what arrived.
-    fn drain_rx(&mut self) {
+    /// Whether the Network PD last reported carrier
+    fn link_up(&self) -> bool {
+        self.status.is_some_and(|status| status.link.is_up())
+    }
+
+    /// Drain the RX ring, consuming every valid entry the Network PD
+    /// queued, and refresh the status snapshot. Returns whether the link
+    /// changed since the previous snapshot.
+    fn drain_rx(&mut self) -> bool {
         // Safety: NET_RING_VADDR is mapped by tvdemo-network.system
         let shared = unsafe { &mut *self.shared };
 
@@ -192,7 +330,7 @@ impl NetClient {
         unsafe {
             // The Network PD writes rx_write_idx; read it volatilely each iteration
             while shared.rx_read_idx != core::ptr::read_volatile(&shared.rx_write_idx) {
-                let idx = (shared.rx_read_idx as usize) % RING_SIZE;
+                let idx = proof::slot_for(shared.rx_read_idx);
                 let entry = &mut shared.rx_ring[idx];
 
                 let flags = core::ptr::read_volatile(&entry.flags);
@@ -208,22 +346,163 @@ impl NetClient {
                 core::ptr::write_volatile(&mut shared.rx_read_idx, next);
             }
 
-            let link_up = core::ptr::read_volatile(&shared.link_up) != 0;
             let mac = core::ptr::read_volatile(&shared.mac_address);
             debug_println!(
-                "Graphics PD: net rx {} packets ({} bytes), link_up={}, mac={:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-                packets, bytes, link_up,
+                "Graphics PD: net rx {} packets ({} bytes), mac={:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+                packets, bytes,
                 mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
             );
         }
+
+        // A racing update keeps the previous snapshot until the next notify
+        let Some(status) = shared.status.snapshot() else {
+            return false;
+        };
+        let seen = self.status.map_or(0, |prev| prev.link_changes);
+        let changed = status.link_changed_since(seen);
+        if changed {
+            let stats = status.stats;
+            debug_println!(
+                "Graphics PD: link {} ({} Mbps), rx {} / err {} / drop {}, tx {} / err {} / drop {}",
+                if status.link.is_up() { "up" } else { "down" },
+                link_speed::mbps(status.link.speed),
+                stats.rx_packets, stats.rx_errors, stats.rx_dropped,
+                stats.tx_packets, stats.tx_errors, stats.tx_dropped
+            );
+        }
+        self.status = Some(status);
+        changed
     }
 }
 
+/// Menu click: a short square-wave tick
+#[cfg(feature = "audio")]
+const CLICK: AudioRequest = AudioRequest::PlayTone { freq_hz: 1_800, duration_ms: 15, volume: Q8_ONE / 4 };
+
+/// Selection beep: a softer sine
+#[cfg(feature = "audio")]
+const SELECT: AudioRequest = AudioRequest::PlaySine { freq_hz: 880, duration_ms: 120, volume: Q8_ONE / 3 };
+
+/// Snake game jingle: a rising C major arpeggio, one sine per note
+#[cfg(feature = "audio")]
+const JINGLE_NOTES: [u16; 3] = [523, 659, 784];
+#[cfg(feature = "audio")]
+const JINGLE_NOTE_MS: u16 = 50;
+
+/// Samples rendered and pushed at a time
+#[cfg(feature = "audio")]
+const JINGLE_CHUNK: usize = 240;
+
+/// The Audio PD replied with something the protocol does not allow
+#[cfg(feature = "audio")]
+const BAD_AUDIO_REPLY: PdError = PdError::new(ErrorDomain::Audio, ErrorKind::InvalidResponse);
+
+/// Client for the Audio PD
+///
+/// Tones are calls; the jingle is rendered here and written to the audio
+/// stream, which this PD owns the write end of. The whole jingle fits the
+/// stream, so it is pushed in one go rather than fed from a timer this PD
+/// does not have. Sound never holds up the demo: failed calls are logged,
+/// and samples the stream has no room for are dropped.
+#[cfg(feature = "audio")]
+struct AudioClient {
+    stream: StreamRing,
+}
+
+#[cfg(feature = "audio")]
+impl AudioClient {
+    fn new() -> Self {
+        // Safety: AUDIO_STREAM_VADDR is mapped by tvdemo-input.system with
+        // CONFIG_AUDIO, which is what enables this feature
+        Self { stream: unsafe { stream_ring(AUDIO_STREAM_VADDR as *mut u8) } }
+    }
+
+    /// Ask the Audio PD to play `request`
+    fn play(&self, request: AudioRequest) {
+        if let Err(e) = call_audio(request).context("playing sound") {
+            debug_println!("Graphics PD: {}", e);
+        }
+    }
+
+    /// Render the jingle and push it into the stream
+    fn jingle(&self) {
+        let Some(mut mixer) = Mixer::<1>::new(&[], AUDIO_RATE_HZ) else {
+            return;
+        };
+        let note_chunks = (AUDIO_RATE_HZ as usize * JINGLE_NOTE_MS as usize / 1000).div_ceil(JINGLE_CHUNK);
+        let mut chunk = [0i16; JINGLE_CHUNK];
+        for freq_hz in JINGLE_NOTES {
+            if mixer.play_sine(freq_hz, JINGLE_NOTE_MS, Q8_ONE / 3).is_err() {
+                return;
+            }
+            for _ in 0..note_chunks {
+                mixer.mix(&mut chunk);
+                if self.stream.push_batch(&chunk) < chunk.len() {
+                    debug_println!("Graphics PD: Audio stream full, jingle cut short");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Make one protected procedure call to the Audio PD; on success, the
+/// voice it played on
+#[cfg(feature = "audio")]
+fn call_audio(request: AudioRequest) -> Result<u8, PdError> {
+    let mut buf = IpcBuffer::new();
+    let (label, count) =
+        rpc::encode_request(&request, &mut buf).map_err(|e| BAD_AUDIO_REPLY.with_detail(e.detail()))?;
+    with_msg_regs_mut(|mrs| mrs[..count].copy_from_slice(buf.words()));
+    let reply = Channel::new(AUDIO_CHANNEL_ID).pp_call(MessageInfo::new(label, count));
+
+    with_msg_regs(|mrs| buf.load(&mrs[..reply.count().min(mrs.len())]));
+    match rpc::decode_reply(reply.label(), &buf) {
+        Ok(voice) => Ok(voice),
+        Err(RpcError::Remote(code)) => Err(PdError::from_wire(code).unwrap_or(BAD_AUDIO_REPLY)),
+        Err(e) => Err(BAD_AUDIO_REPLY.with_detail(e.detail())),
+    }
+}
+
+/// Boot messages on screen, until the first key press
+#[cfg(feature = "early-console")]
+struct BootConsole {
+    console: EarlyConsole,
+    #[cfg(feature = "network")]
+    net_log: BootLogReader,
+}
+
+/// Status panel on the Display PD's SPI LCD
+#[cfg(feature = "status-lcd")]
+struct StatusLcd {
+    backend: DrawRingBackend,
+    /// The Display PD has set up the draw ring (its first notification)
+    ready: bool,
+    /// Screen and menu selection the panel last showed
+    shown: Option<(AppState, usize)>,
+}
+
 struct GraphicsHandler {
+    #[cfg(not(feature = "split"))]
     framebuffer: Option<Framebuffer>,
+    /// Owns the screen while open; `None` once a key has been pressed
+    #[cfg(feature = "early-console")]
+    console: Option<BootConsole>,
+    /// Vblank pacing; `None` renders on input only
+    #[cfg(not(feature = "split"))]
+    clock: Option<FrameClock>,
+    #[cfg(feature = "split")]
+    frames: FrameProducer<'static>,
     input: RingBufferInput,
     #[cfg(feature = "network")]
     net: NetClient,
+    #[cfg(feature = "audio")]
+    audio: AudioClient,
+    #[cfg(feature = "status-lcd")]
+    status_lcd: StatusLcd,
+    /// SoC temperature when the About screen was opened
+    #[cfg(not(feature = "split"))]
+    soc_temp_milli_c: Option<u32>,
     state: AppState,
     menu_selected: usize,
     snake: Snake,
@@ -304,10 +583,8 @@ impl Snake {
             _ => new_y -= speed,
         }
 
-        if new_x < PLAY_AREA_LEFT { new_x = PLAY_AREA_RIGHT - 1; }
-        if new_x >= PLAY_AREA_RIGHT { new_x = PLAY_AREA_LEFT; }
-        if new_y < PLAY_AREA_TOP { new_y = PLAY_AREA_BOTTOM - 1; }
-        if new_y >= PLAY_AREA_BOTTOM { new_y = PLAY_AREA_TOP; }
+        new_x = wrap_i32(new_x, PLAY_AREA_LEFT, PLAY_AREA_RIGHT);
+        new_y = wrap_i32(new_y, PLAY_AREA_TOP, PLAY_AREA_BOTTOM);
 
         for i in (1..self.length).rev() {
             self.segments[i] = self.segments[i - 1];
@@ -330,7 +607,7 @@ fn hsv_to_rgb(h: u16, s: u8, v: u8) -> u32 {
     let s = s as u32;
     let v = v as u32;
     let c = (v * s) / 255;
-    let x = (c * (60 - ((h % 120) as i32 - 60).unsigned_abs() as u32)) / 60;
+    let x = (c * (60 - abs_diff_u32((h % 120) as u32, 60))) / 60;
     let m = v - c;
     let (r, g, b) = match h / 60 {
         0 => (c, x, 0),
@@ -445,11 +722,8 @@ unsafe fn draw_letter(ptr: *mut u32, pitch: usize, x: usize, y: usize, b: usize,
             draw_block(ptr, pitch, x+b*2, y+b, b, b, color);
             draw_block(ptr, pitch, x, y+b*2, b*3, b, color);
         }
-        '4' => {
-            draw_block(ptr, pitch, x, y, b, b*3, color);
-            draw_block(ptr, pitch, x, y+b*2, b*3, b, color);
-            draw_block(ptr, pitch, x+b*2, y, b, b*5, color);
-        }
+        '0'..='9' => draw_glyph(ptr, pitch, x, y, b, &DIGITS[letter as usize - '0' as usize], color),
+        '.' => draw_block(ptr, pitch, x+b, y+b*4, b, b, color),
         '@' => {
             draw_block(ptr, pitch, x, y, b*3, b, color);
             draw_block(ptr, pitch, x, y+b, b, b*3, color);
@@ -462,6 +736,30 @@ unsafe fn draw_letter(ptr: *mut u32, pitch: usize, x: usize, y: usize, b: usize,
     }
 }
 
+/// Digit glyphs, 3x5 blocks: one row per entry, bit 2 the left column
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+unsafe fn draw_glyph(ptr: *mut u32, pitch: usize, x: usize, y: usize, b: usize, rows: &[u8; 5], color: u32) {
+    for (row, bits) in rows.iter().enumerate() {
+        for col in 0..3 {
+            if bits & (0b100 >> col) != 0 {
+                draw_block(ptr, pitch, x + col*b, y + row*b, b, b, color);
+            }
+        }
+    }
+}
+
 // Simplified rendering functions
 unsafe fn draw_text(ptr: *mut u32, pitch: usize, x: usize, y: usize, text: &str, b: usize, color: u32) {
     let spacing = b * 4;
@@ -473,10 +771,33 @@ unsafe fn draw_text(ptr: *mut u32, pitch: usize, x: usize, y: usize, text: &str,
 impl GraphicsHandler {
     fn new() -> Self {
         Self {
+            #[cfg(not(feature = "split"))]
             framebuffer: None,
+            #[cfg(not(feature = "split"))]
+            clock: None,
+            #[cfg(feature = "early-console")]
+            console: None,
+            #[cfg(feature = "split")]
+            // Safety: FRAME_REGION_VADDR is mapped by tvdemo-split.system
+            frames: FrameProducer::new(unsafe { &*frame_header_ptr(FRAME_REGION_VADDR as *mut u8) }),
             input: RingBufferInput::new(),
             #[cfg(feature = "network")]
             net: NetClient::new(),
+            #[cfg(feature = "audio")]
+            audio: AudioClient::new(),
+            #[cfg(feature = "status-lcd")]
+            status_lcd: StatusLcd {
+                // Safety: DRAW_REGION_VADDR is mapped by tvdemo-dual.system;
+                // nothing is queued before the Display PD has initialized
+                // the header and notified us
+                backend: unsafe {
+                    DrawRingBackend::new(DRAW_REGION_VADDR as *mut u8, STATUS_LCD_WIDTH, STATUS_LCD_HEIGHT)
+                },
+                ready: false,
+                shown: None,
+            },
+            #[cfg(not(feature = "split"))]
+            soc_temp_milli_c: None,
             state: AppState::Menu,
             menu_selected: 0,
             snake: Snake::new(),
@@ -492,6 +813,13 @@ impl GraphicsHandler {
 
         debug_println!("Graphics PD: Key {:?}", key);
 
+        // The first key closes the boot log and shows the menu
+        #[cfg(feature = "early-console")]
+        if self.console.take().is_some() {
+            self.needs_redraw = true;
+            return;
+        }
+
         match self.state {
             AppState::Menu => {
                 match key {
@@ -499,15 +827,24 @@ impl GraphicsHandler {
                         if self.menu_selected > 0 {
                             self.menu_selected -= 1;
                             self.needs_redraw = true;
+                            #[cfg(feature = "audio")]
+                            self.audio.play(CLICK);
                         }
                     }
                     KeyCode::Down => {
                         if self.menu_selected < MENU_ITEM_COUNT - 1 {
                             self.menu_selected += 1;
                             self.needs_redraw = true;
+                            #[cfg(feature = "audio")]
+                            self.audio.play(CLICK);
                         }
                     }
                     KeyCode::Enter | KeyCode::Space => {
+                        #[cfg(feature = "audio")]
+                        match self.menu_selected {
+                            MENU_SNAKE_GAME => self.audio.jingle(),
+                            _ => self.audio.play(SELECT),
+                        }
                         match self.menu_selected {
                             MENU_SNAKE_GAME => {
                                 self.state = AppState::SnakeGame;
@@ -521,6 +858,10 @@ impl GraphicsHandler {
                             }
                             MENU_ABOUT => {
                                 self.state = AppState::About;
+                                #[cfg(not(feature = "split"))]
+                                {
+                                    self.soc_temp_milli_c = read_soc_temperature();
+                                }
                                 self.needs_redraw = true;
                             }
                             _ => {}
@@ -551,6 +892,58 @@ impl GraphicsHandler {
         }
     }
 
+    /// Open the early console on the framebuffer just mapped, with this
+    /// PD's init results and what other PDs have logged so far
+    #[cfg(feature = "early-console")]
+    fn open_console(&mut self) {
+        let Some(fb) = self.framebuffer.as_mut() else {
+            return;
+        };
+        let (width, height) = fb.dimensions();
+        self.console = Some(BootConsole {
+            console: EarlyConsole::new(fb),
+            // Safety: tvdemo-network.system maps the Network PD's boot log
+            // page here, read only
+            #[cfg(feature = "network")]
+            net_log: unsafe { BootLogReader::attach(NET_BOOT_LOG_VADDR as *const u8) },
+        });
+
+        self.console_line(Level::Info, format_args!("framebuffer {}x{}", width, height));
+        match self.clock.as_ref().map(|clock| clock.frame_hz(REFRESH_HZ)) {
+            Some(hz) => self.console_line(Level::Info, format_args!("vsync paced, {} Hz", hz)),
+            None => self.console_line(Level::Info, format_args!("no vsync, rendering on input")),
+        }
+        self.pump_console();
+    }
+
+    /// Show one of this PD's init results on the early console
+    #[cfg(feature = "early-console")]
+    fn console_line(&mut self, level: Level, args: core::fmt::Arguments<'_>) {
+        if let (Some(boot), Some(fb)) = (self.console.as_mut(), self.framebuffer.as_mut()) {
+            // A line too long for the buffer is shown cut
+            let mut line = BoundedString::<BOOT_LOG_LINE_LEN>::new();
+            let _ = line.write_fmt(args);
+            boot.console.line(fb, "GFX", level, &line);
+        }
+    }
+
+    /// Draw the lines other PDs have logged since the last call
+    #[cfg(feature = "early-console")]
+    fn pump_console(&mut self) {
+        #[cfg(feature = "network")]
+        if let (Some(boot), Some(fb)) = (self.console.as_mut(), self.framebuffer.as_mut()) {
+            boot.console.mirror(fb, "NET", &mut boot.net_log);
+        }
+    }
+
+    /// Whether the current screen moves on its own, frame by frame
+    #[cfg(not(feature = "split"))]
+    fn animating(&self) -> bool {
+        matches!(self.state, AppState::SnakeGame | AppState::Screensaver)
+    }
+
+    /// Render one frame straight into the framebuffer
+    #[cfg(not(feature = "split"))]
     fn render(&mut self) {
         let fb = match &self.framebuffer {
             Some(fb) => fb,
@@ -560,6 +953,75 @@ impl GraphicsHandler {
         let ptr = fb.buffer_ptr();
         let pitch = fb.pitch_pixels();
         let (width, height) = fb.dimensions();
+        self.draw(ptr, pitch, width, height);
+        #[cfg(feature = "status-lcd")]
+        self.route_status();
+    }
+
+    /// Draw the status panel for the `Status` display and present the
+    /// displays the router saw damaged
+    #[cfg(feature = "status-lcd")]
+    fn route_status(&mut self) {
+        let shown = (self.state, self.menu_selected);
+        if !self.status_lcd.ready || self.status_lcd.shown == Some(shown) {
+            return;
+        }
+        let Some(fb) = self.framebuffer.as_mut() else {
+            return;
+        };
+        let mut hdmi = HdmiBackend::new(fb);
+        let mut router = DisplayRouter::new();
+        // Two displays with distinct roles on an empty router: cannot fail
+        let _ = router.register("hdmi", &mut hdmi, &[DisplayRole::Content]);
+        let Ok(lcd) = router.register("lcd", &mut self.status_lcd.backend, &[DisplayRole::Status]) else {
+            return;
+        };
+
+        if let Some(mut panel) = router.route(DisplayRole::Status) {
+            draw_status(&mut panel, self.state, self.menu_selected);
+        }
+        // HDMI is scanned out as drawn; the LCD needs a PRESENT
+        let mut lcd_damaged = false;
+        router.flush(|id, _, _| lcd_damaged |= id == lcd);
+        drop(router);
+
+        if lcd_damaged && self.status_lcd.backend.present() {
+            self.status_lcd.shown = Some(shown);
+            STATUS_LCD_CHANNEL.notify();
+        }
+    }
+
+    /// Render one frame into a free shared slot and publish it
+    #[cfg(feature = "split")]
+    fn render(&mut self) {
+        let slot = match self.frames.acquire() {
+            Ok(Some(slot)) => slot,
+            // Present PD not up yet or every slot in flight; it notifies us
+            // once it publishes the geometry or releases a slot
+            Ok(None) | Err(HandoffError::NotReady) => return,
+            Err(e) => {
+                debug_println!("Graphics PD: frame handoff error {:?}", e);
+                return;
+            }
+        };
+        let (width, height) = match self.frames.geometry() {
+            Ok(geometry) => geometry,
+            Err(_) => return,
+        };
+
+        // A slot holds whatever frame was last rendered into it, so
+        // incremental updates don't apply: draw every frame in full
+        self.needs_redraw = true;
+        let ptr = unsafe { slot_ptr(FRAME_REGION_VADDR as *mut u8, slot) };
+        self.draw(ptr, width as usize, width, height);
+
+        match self.frames.publish(slot) {
+            Ok(_) => PRESENT_CHANNEL.notify(),
+            Err(e) => debug_println!("Graphics PD: frame handoff error {:?}", e),
+        }
+    }
+
+    fn draw(&mut self, ptr: *mut u32, pitch: usize, width: u32, height: u32) {
         let bg_color: u32 = 0xFF101030;
         let white: u32 = 0xFFFFFFFF;
         let green: u32 = 0xFF00B050;
@@ -647,7 +1109,25 @@ impl GraphicsHandler {
                         let b = 6usize;
                         draw_text(ptr, pitch, 300, 280, "SEL4 MICROKIT", b, white);
                         draw_text(ptr, pitch, 300, 340, "RPI4", b, white);
+                        #[cfg(not(feature = "split"))]
+                        {
+                            let mut temp = BoundedString::<16>::new();
+                            match self.soc_temp_milli_c {
+                                Some(milli_c) => {
+                                    let _ = write!(temp, "SOC {}.{}C", milli_c / 1000, milli_c % 1000 / 100);
+                                }
+                                None => {
+                                    temp.push_str_truncating("SOC NO TEMP");
+                                }
+                            }
+                            draw_text(ptr, pitch, 700, 340, temp.as_str(), b, gray);
+                        }
                         draw_text(ptr, pitch, 300, 400, "@PRASINCS", b, gray);
+                        #[cfg(feature = "network")]
+                        {
+                            let link = if self.net.link_up() { "LINK UP" } else { "NO LINK" };
+                            draw_text(ptr, pitch, 700, 400, link, b, gray);
+                        }
                         draw_text(ptr, pitch, 300, 480, "PRESS ESC", b, white);
                         self.needs_redraw = false;
                     }
@@ -660,6 +1140,37 @@ impl GraphicsHandler {
     }
 }
 
+/// The status panel: a title bar, then a block per menu item, outlined
+/// while selected and marked while it runs
+#[cfg(feature = "status-lcd")]
+fn draw_status(panel: &mut dyn DisplayBackend, state: AppState, selected: usize) {
+    let bg = Color::from_argb(0xFF101030);
+    let green = Color::from_argb(0xFF00B050);
+    let (width, height) = (panel.width(), panel.height());
+
+    panel.clear(bg);
+    panel.fill_rect(0, 0, width, 32, green);
+
+    let running = match state {
+        AppState::Menu => None,
+        AppState::SnakeGame => Some(MENU_SNAKE_GAME),
+        AppState::Screensaver => Some(MENU_SCREENSAVER),
+        AppState::About => Some(MENU_ABOUT),
+    };
+    let row = (height - 48) / MENU_ITEM_COUNT as u32;
+    for item in 0..MENU_ITEM_COUNT {
+        let y = 40 + item as u32 * row;
+        panel.fill_rect(16, y, width - 32, row - 8, Color::DARK_GRAY);
+        if running == Some(item) {
+            panel.fill_rect(24, y + 8, row - 24, row - 24, green);
+        }
+        if state == AppState::Menu && item == selected {
+            panel.draw_rect(16, y, width - 32, row - 8, Color::WHITE);
+        }
+    }
+}
+
+#[cfg(not(feature = "split"))]
 fn blink_activity_led() {
     debug_println!("Blinking LED...");
     const GPFSEL4: usize = GPIO_BASE + 0x10;
@@ -686,14 +1197,32 @@ fn blink_activity_led() {
     }
 }
 
+/// SoC temperature in millidegrees Celsius, if the firmware answers
+#[cfg(not(feature = "split"))]
+fn read_soc_temperature() -> Option<u32> {
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+    match mailbox.get_temperature() {
+        Ok(milli_c) => Some(milli_c),
+        Err(e) => {
+            debug_println!("Graphics PD: temperature error: {:?}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "split"))]
 fn init_framebuffer() -> Option<Framebuffer> {
     debug_println!("Graphics PD: Initializing framebuffer...");
     let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
 
-    match unsafe { Framebuffer::new(&mailbox, WIDTH, HEIGHT) } {
-        Ok(fb) => {
+    match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok((fb, selection)) => {
             let info = fb.info();
-            debug_println!("Graphics PD: FB {}x{} @ 0x{:08x}", info.width, info.height, info.base);
+            debug_println!(
+                "Graphics PD: FB {}x{} @ 0x{:08x}{}",
+                info.width, info.height, info.base,
+                if selection.is_fallback() { " (fallback)" } else { "" }
+            );
             Some(fb)
         }
         Err(e) => {
@@ -703,6 +1232,24 @@ fn init_framebuffer() -> Option<Framebuffer> {
     }
 }
 
+/// Ask the firmware for vsync interrupts on the SMI IRQ channel
+#[cfg(not(feature = "split"))]
+fn init_frame_clock() -> Option<FrameClock> {
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+    // Safety: the .system files map the SMI registers at SMI_VIRT_BASE
+    match unsafe { Vsync::firmware(&mailbox, SMI_VIRT_BASE) } {
+        Ok(vsync) => {
+            let clock = FrameClock::new(vsync, VSYNC_FRAMES_PER_STEP);
+            debug_println!("Graphics PD: vsync paced, {} Hz", clock.frame_hz(REFRESH_HZ));
+            Some(clock)
+        }
+        Err(e) => {
+            debug_println!("Graphics PD: vsync unavailable ({:?}), rendering on input", e);
+            None
+        }
+    }
+}
+
 #[protection_domain]
 fn init() -> GraphicsHandler {
     // Initialize the heap allocator
@@ -715,27 +1262,37 @@ fn init() -> GraphicsHandler {
     debug_println!("  Graphics Protection Domain (IPC)");
     debug_println!("========================================");
     debug_println!("");
+    #[cfg(feature = "audit")]
+    {
+        rpi4_input_protocol::audit::set_role(rpi4_input_protocol::audit::Role::Graphics);
+        debug_println!("Graphics PD: Security audit, ring accesses checked against graphics_pd_can_access");
+    }
 
-    blink_activity_led();
+    #[cfg(not(feature = "split"))]
+    let handler = {
+        blink_activity_led();
+
+        let mut handler = GraphicsHandler::new();
+        handler.framebuffer = init_framebuffer();
+        if handler.framebuffer.is_some() {
+            handler.clock = init_frame_clock();
+        }
+        #[cfg(feature = "early-console")]
+        handler.open_console();
+        handler
+    };
 
-    let mut handler = GraphicsHandler::new();
-    handler.framebuffer = init_framebuffer();
+    // Split mode: the first frame is rendered once the Present PD
+    // publishes the geometry and notifies us
+    #[cfg(feature = "split")]
+    let handler = GraphicsHandler::new();
 
     debug_println!("Graphics PD: Ready, waiting for input events...");
     handler
 }
 
-#[derive(Debug)]
-pub struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Graphics PD handler error")
-    }
-}
-
 impl Handler for GraphicsHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
         // Check if notification is from Input PD
@@ -748,13 +1305,59 @@ impl Handler for GraphicsHandler {
 
         // Check if notification is from Network PD (received packets)
         #[cfg(feature = "network")]
-        if channels.contains(NET_CHANNEL) {
-            self.net.drain_rx();
+        if channels.contains(NET_CHANNEL) && self.net.drain_rx() && self.state == AppState::About {
+            self.needs_redraw = true;
         }
 
-        // Render frame
-        self.render();
+        // The Display PD set up the draw ring, or presented the panel
+        #[cfg(feature = "status-lcd")]
+        if channels.contains(STATUS_LCD_CHANNEL) {
+            self.status_lcd.ready = true;
+        }
+
+        // Clear the source before acking, or the level IRQ refires at once
+        #[cfg(not(feature = "split"))]
+        let render_now = {
+            let mut frame_due = false;
+            if channels.contains(VSYNC_CHANNEL) {
+                if let Some(clock) = &mut self.clock {
+                    clock.handle_irq(|_| frame_due = true);
+                }
+                VSYNC_CHANNEL.irq_ack().map_err(|_| {
+                    PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking vsync IRQ")
+                })?;
+            }
+            // Paced by vblank, the snake moves on frames only; a key that
+            // changes a menu is drawn at once
+            frame_due || self.clock.is_none() || !self.animating()
+        };
+        // Split mode: the Present PD paces frames by handing slots back
+        #[cfg(feature = "split")]
+        let render_now = true;
+
+        // The early console owns the screen until the first key
+        #[cfg(feature = "early-console")]
+        let render_now = {
+            self.pump_console();
+            render_now && self.console.is_none()
+        };
+
+        // Render frame (split mode: also when the Present PD frees a slot)
+        if render_now {
+            self.render();
+        }
+        #[cfg(feature = "audit")]
+        print_audit_log();
 
         Ok(())
     }
 }
+
+/// Print the access-spec violations logged since the last call
+#[cfg(feature = "audit")]
+fn print_audit_log() {
+    let dropped = rpi4_pd_error::audit::drain(|violation| debug_println!("{}", violation));
+    if dropped > 0 {
+        debug_println!("@audit dropped={}", dropped);
+    }
+}
@@ -3,17 +3,38 @@
 //! Implements the DisplayBackend trait for the HDMI framebuffer
 
 use crate::framebuffer::Framebuffer;
+use crate::vsync::{fallback_frame_delay, Vsync};
 use rpi4_tvdemo::backend::{Color, DisplayBackend};
 
 /// HDMI display backend wrapper
 pub struct HdmiBackend<'a> {
     fb: &'a mut Framebuffer,
+    vsync: Option<&'a mut Vsync>,
 }
 
 impl<'a> HdmiBackend<'a> {
     /// Create a new HDMI backend wrapping a framebuffer
     pub fn new(fb: &'a mut Framebuffer) -> Self {
-        Self { fb }
+        Self { fb, vsync: None }
+    }
+
+    /// Create a backend that paces frames with a vsync notifier
+    pub fn with_vsync(fb: &'a mut Framebuffer, vsync: &'a mut Vsync) -> Self {
+        Self { fb, vsync: Some(vsync) }
+    }
+
+    /// Block until the next vertical blank.
+    ///
+    /// Returns false if there is no vsync source or it timed out; a fixed
+    /// spin delay stands in so callers' frame loops still make progress.
+    pub fn wait_for_vsync(&mut self) -> bool {
+        if let Some(vsync) = self.vsync.as_deref_mut() {
+            if vsync.wait() {
+                return true;
+            }
+        }
+        fallback_frame_delay();
+        false
     }
 }
 
@@ -39,25 +39,56 @@
 #![allow(dead_code)]
 
 pub mod mailbox;
+pub mod property;
 pub mod framebuffer;
+pub mod dma;
+pub mod vsync;
+pub mod frame_timer;
+pub mod time;
+pub mod edid;
 pub mod graphics;
+pub mod blend;
+pub mod compositor;
+pub mod surface;
+pub mod screen;
+pub mod tilemap;
 pub mod font;
+pub mod glyph_cache;
 pub mod truetype;
 pub mod terminal;
+pub mod early_console;
 pub mod tpm;
 pub mod crypto;
 pub mod hdmi_backend;
 pub mod direct_hdmi_backend;
+pub mod draw_ring_backend;
+pub mod profile;
 
 pub use mailbox::{Mailbox, MailboxError};
-pub use framebuffer::{Framebuffer, FramebufferInfo};
+pub use vsync::{FrameClock, Vsync, VsyncSource};
+pub use frame_timer::FrameTimer;
+pub use time::{Duration, Instant};
+pub use edid::{parse_edid, EdidError, EdidInfo, EdidMode};
+pub use framebuffer::{
+    native_mode, DisplayMode, DrawError, Framebuffer, FramebufferInfo, ModeSelection, FALLBACK_LADDER,
+    FIXED_720P_LADDER,
+};
+pub use dma::{BlitEngine, BlitError, DmaWindow};
 pub use graphics::{Color, Point, Rect};
+pub use blend::blend_pixel;
+pub use compositor::{Compositor, Layer};
+pub use surface::{Canvas, OffscreenSurface};
+pub use screen::{Screen, ScreenPoint, ScreenRect};
+pub use tilemap::{SpriteSheet, TileMap, EMPTY_TILE};
 pub use terminal::{Terminal, TtTerminal};
+pub use early_console::EarlyConsole;
+pub use glyph_cache::GlyphCache;
 pub use truetype::{FontRenderer, GlyphMetrics, DEJAVU_MONO, NOTO_DEVANAGARI};
 pub use tpm::{Tpm, TpmError};
-pub use crypto::{Sha256, Sha256Digest, VerifyResult, constant_time_compare, verify_sha256};
+pub use crypto::{Sha256, Sha256Digest, VerifyResult, constant_time_compare, verify_ed25519, verify_sha256};
 pub use hdmi_backend::HdmiBackend;
 pub use direct_hdmi_backend::DirectHdmiBackend;
+pub use draw_ring_backend::DrawRingBackend;
 
 /// BCM2711 peripheral base address (Raspberry Pi 4) - physical
 pub const BCM2711_PERIPH_BASE: usize = 0xFE00_0000;
@@ -76,6 +107,10 @@ pub const FRAMEBUFFER_PHYS_BASE: usize = 0x3e87_6000;
 /// Framebuffer virtual address as mapped by Microkit (graphics.system)
 pub const FRAMEBUFFER_VIRT_BASE: usize = 0x5_0001_0000;
 
+/// Size of the framebuffer mapping every .system file gives a display PD;
+/// a buffer the firmware allocates past its end cannot be drawn on
+pub const FRAMEBUFFER_REGION_SIZE: usize = 0x100_0000;
+
 /// DMA buffer physical address (just before framebuffer)
 /// Used for mailbox communication - GPU needs physical addresses
 pub const DMA_BUFFER_PHYS: usize = 0x3e87_5000;
@@ -83,6 +118,29 @@ pub const DMA_BUFFER_PHYS: usize = 0x3e87_5000;
 /// DMA buffer virtual address as mapped by Microkit
 pub const DMA_BUFFER_VIRT: usize = 0x5_0300_0000;
 
+/// DMA controller register page virtual address (graphics.system), for
+/// the blit engine
+pub const DMA_REGS_VIRT: usize = 0x5_0C00_0000;
+
+/// Blit engine scratch page (control block and fill pattern), physical
+/// address (just before the mailbox buffer)
+pub const BLIT_SCRATCH_PHYS: usize = 0x3e87_4000;
+
+/// Blit engine scratch page virtual address as mapped by Microkit
+pub const BLIT_SCRATCH_VIRT: usize = 0x5_0D00_0000;
+
+/// DMA channel the blit engine claims; the SPI display uses 4 and 5, audio 6
+pub const BLIT_DMA_CHANNEL: u8 = 2;
+
+/// SMI register page virtual address (tvdemo.system), for firmware vsync
+pub const SMI_VIRT_BASE: usize = 0x5_0500_0000;
+
+/// HDMI0 pixel valve register page virtual address, for direct-backend vsync
+pub const PIXELVALVE2_VIRT_BASE: usize = 0x5_0600_0000;
+
+/// System timer register page virtual address, for the frame timer
+pub const SYSTIMER_VIRT_BASE: usize = 0x5_0A00_0000;
+
 /// GPU bus address to ARM physical address translation
 /// The GPU sees memory differently than the ARM cores
 #[inline]
@@ -1,12 +1,17 @@
 //! # VideoCore Mailbox Driver
 //!
 //! Communicates with the VideoCore GPU via the mailbox interface.
-//! Used to allocate and configure the framebuffer.
+//! Used to allocate and configure the framebuffer, and to read and set
+//! the firmware's temperature, clock, voltage and power state through
+//! typed wrappers over [`crate::property`] messages.
 //!
 //! ## Reference
 //! - https://github.com/raspberrypi/firmware/wiki/Mailbox-property-interface
 
 use core::ptr::{read_volatile, write_volatile};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
+use crate::property::{BuildError, PropertyMessage, MAX_VALUE_WORDS};
 
 /// Mailbox register offsets from base
 const MAILBOX_READ: usize = 0x00;
@@ -33,6 +38,7 @@ pub mod tags {
     pub const SET_PIXEL_ORDER: u32 = 0x0004_8006;
     pub const ALLOCATE_BUFFER: u32 = 0x0004_0001;
     pub const GET_PITCH: u32 = 0x0004_0008;
+    pub const SET_VSYNC: u32 = 0x0004_800E;
 
     // Verification tags
     pub const GET_FIRMWARE_REV: u32 = 0x0000_0001;
@@ -41,8 +47,112 @@ pub mod tags {
     pub const GET_BOARD_SERIAL: u32 = 0x0001_0004;
     pub const GET_ARM_MEMORY: u32 = 0x0001_0005;
     pub const GET_VC_MEMORY: u32 = 0x0001_0006;
+
+    // Display tags
+    pub const GET_EDID_BLOCK: u32 = 0x0003_0020;
+
+    // Thermal and power tags
+    pub const GET_TEMPERATURE: u32 = 0x0003_0006;
+    pub const GET_MAX_TEMPERATURE: u32 = 0x0003_000A;
+    pub const GET_THROTTLED: u32 = 0x0003_0046;
+    pub const GET_POWER_STATE: u32 = 0x0002_0001;
+    pub const SET_POWER_STATE: u32 = 0x0002_8001;
+    pub const GET_DOMAIN_STATE: u32 = 0x0003_0030;
+    pub const SET_DOMAIN_STATE: u32 = 0x0003_8030;
+
+    // Clock tags
+    pub const GET_CLOCK_RATE: u32 = 0x0003_0002;
+    pub const SET_CLOCK_RATE: u32 = 0x0003_8002;
+    pub const GET_MAX_CLOCK_RATE: u32 = 0x0003_0004;
+    pub const GET_MIN_CLOCK_RATE: u32 = 0x0003_0007;
+    pub const GET_MEASURED_CLOCK_RATE: u32 = 0x0003_0047;
+
+    // Voltage tags
+    pub const GET_VOLTAGE: u32 = 0x0003_0003;
+    pub const SET_VOLTAGE: u32 = 0x0003_8003;
+    pub const GET_MAX_VOLTAGE: u32 = 0x0003_0005;
+    pub const GET_MIN_VOLTAGE: u32 = 0x0003_0008;
+}
+
+/// Clock ids for [`Mailbox::get_clock_rate`]
+pub mod clock {
+    pub const EMMC: u32 = 1;
+    pub const UART: u32 = 2;
+    pub const ARM: u32 = 3;
+    /// VPU core clock; SPI, I2C and the mini UART divide it down
+    pub const CORE: u32 = 4;
+    /// 3D block: the GPU clock
+    pub const V3D: u32 = 5;
+    pub const H264: u32 = 6;
+    pub const ISP: u32 = 7;
+    pub const SDRAM: u32 = 8;
+    pub const PIXEL: u32 = 9;
+    pub const PWM: u32 = 10;
+    pub const HEVC: u32 = 11;
+    pub const EMMC2: u32 = 12;
+}
+
+/// Voltage ids for [`Mailbox::get_voltage`]
+pub mod voltage {
+    pub const CORE: u32 = 1;
+    pub const SDRAM_C: u32 = 2;
+    pub const SDRAM_P: u32 = 3;
+    pub const SDRAM_I: u32 = 4;
+}
+
+/// Device ids for [`Mailbox::get_power_state`]
+pub mod power {
+    pub const SD_CARD: u32 = 0;
+    pub const UART0: u32 = 1;
+    pub const UART1: u32 = 2;
+    pub const USB_HCD: u32 = 3;
+    pub const I2C0: u32 = 4;
+    pub const I2C1: u32 = 5;
+    pub const I2C2: u32 = 6;
+    pub const SPI: u32 = 7;
+    pub const CCP2TX: u32 = 8;
+}
+
+/// Power domain ids for [`Mailbox::get_domain_state`]: Linux's
+/// `raspberrypi-power` binding ids plus one, as its driver sends them
+pub mod domain {
+    pub const I2C0: u32 = 1;
+    pub const I2C1: u32 = 2;
+    pub const I2C2: u32 = 3;
+    pub const VIDEO_SCALER: u32 = 4;
+    pub const VPU1: u32 = 5;
+    pub const HDMI: u32 = 6;
+    pub const USB: u32 = 7;
+    pub const VEC: u32 = 8;
+    pub const JPEG: u32 = 9;
+    pub const H264: u32 = 10;
+    pub const V3D: u32 = 11;
+    pub const ISP: u32 = 12;
+    pub const ARM: u32 = 23;
 }
 
+/// Power state bits: on, and in a request, wait for the device to settle;
+/// in a response, the second bit means there is no such device
+const POWER_ON: u32 = 1 << 0;
+const POWER_WAIT: u32 = 1 << 1;
+const POWER_NO_DEVICE: u32 = 1 << 1;
+
+/// Flags returned by [`Mailbox::get_throttled`]: the low half is the current
+/// state, the high half is sticky since boot
+pub mod throttled {
+    pub const UNDER_VOLTAGE: u32 = 1 << 0;
+    pub const FREQ_CAPPED: u32 = 1 << 1;
+    pub const THROTTLED: u32 = 1 << 2;
+    pub const SOFT_TEMP_LIMIT: u32 = 1 << 3;
+    pub const UNDER_VOLTAGE_OCCURRED: u32 = 1 << 16;
+    pub const FREQ_CAPPED_OCCURRED: u32 = 1 << 17;
+    pub const THROTTLED_OCCURRED: u32 = 1 << 18;
+    pub const SOFT_TEMP_LIMIT_OCCURRED: u32 = 1 << 19;
+}
+
+/// Size of the mailbox DMA buffer in 32-bit words (one 4KB page)
+const DMA_BUFFER_WORDS: usize = 1024;
+
 /// Mailbox communication errors
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MailboxError {
@@ -54,6 +164,32 @@ pub enum MailboxError {
     Timeout,
     /// Buffer allocation failed
     AllocationFailed,
+    /// GPU answered with a different mode than the one requested
+    ModeRejected,
+    /// The firmware has no device or domain with the id asked for
+    NoSuchDevice,
+}
+
+impl From<MailboxError> for PdError {
+    fn from(e: MailboxError) -> Self {
+        let kind = match e {
+            MailboxError::RequestFailed => ErrorKind::Failed,
+            MailboxError::InvalidResponse => ErrorKind::InvalidResponse,
+            MailboxError::Timeout => ErrorKind::Timeout,
+            MailboxError::AllocationFailed => ErrorKind::Capacity,
+            MailboxError::ModeRejected => ErrorKind::Unsupported,
+            MailboxError::NoSuchDevice => ErrorKind::NotFound,
+        };
+        PdError::new(ErrorDomain::Mailbox, kind).with_detail(e as u16)
+    }
+}
+
+/// A message that does not fit the buffer is a request the firmware never
+/// sees
+impl From<BuildError> for MailboxError {
+    fn from(_: BuildError) -> Self {
+        MailboxError::RequestFailed
+    }
 }
 
 /// Mailbox driver for VideoCore communication
@@ -112,9 +248,16 @@ impl Mailbox {
     /// needs physical addresses for DMA. The buffer is copied to/from
     /// the DMA region.
     ///
+    /// Most messages fit the usual `[u32; 36]`; larger ones (e.g. an EDID
+    /// block) may use any length up to the 4KB DMA page.
+    ///
     /// # Safety
     /// The DMA buffer region must be properly mapped.
-    pub unsafe fn call(&self, buffer: &mut [u32; 36]) -> Result<(), MailboxError> {
+    pub unsafe fn call<const N: usize>(&self, buffer: &mut [u32; N]) -> Result<(), MailboxError> {
+        if N > DMA_BUFFER_WORDS {
+            return Err(MailboxError::RequestFailed);
+        }
+
         // Use the DMA buffer which has a known physical address
         let dma_virt = crate::DMA_BUFFER_VIRT as *mut u32;
         let dma_phys = crate::DMA_BUFFER_PHYS;
@@ -123,7 +266,7 @@ impl Mailbox {
         core::arch::asm!("dsb sy");
 
         // Copy message to DMA buffer
-        for i in 0..36 {
+        for i in 0..N {
             dma_virt.add(i).write_volatile(buffer[i]);
         }
 
@@ -151,7 +294,7 @@ impl Mailbox {
                 core::arch::asm!("dsb sy");
 
                 // Copy response back from DMA buffer
-                for i in 0..36 {
+                for i in 0..N {
                     buffer[i] = dma_virt.add(i).read_volatile();
                 }
 
@@ -166,65 +309,204 @@ impl Mailbox {
     }
 
     /// Get firmware revision
-    pub fn get_firmware_revision(&self, buffer: &mut [u32; 36]) -> Result<u32, MailboxError> {
-        // Clear buffer
-        for i in 0..36 {
-            buffer[i] = 0;
+    pub fn get_firmware_revision(&self) -> Result<u32, MailboxError> {
+        let msg = self.property(tags::GET_FIRMWARE_REV, 1, &[], 1)?;
+        Ok(msg.value(0))
+    }
+
+    /// Get board model
+    pub fn get_board_model(&self) -> Result<u32, MailboxError> {
+        let msg = self.property(tags::GET_BOARD_MODEL, 1, &[], 1)?;
+        Ok(msg.value(0))
+    }
+
+    /// Get board serial number
+    pub fn get_board_serial(&self) -> Result<u64, MailboxError> {
+        let msg = self.property(tags::GET_BOARD_SERIAL, 2, &[], 2)?;
+        Ok((msg.value(1) as u64) << 32 | (msg.value(0) as u64))
+    }
+
+    /// Enable or disable the firmware's vsync notification (the SMI
+    /// interrupt at each vertical blank, see [`crate::vsync`])
+    pub fn set_vsync_notify(&self, enable: bool) -> Result<(), MailboxError> {
+        self.property(tags::SET_VSYNC, 1, &[enable as u32], 0)?;
+        Ok(())
+    }
+
+    /// Send a one-tag message for `tag` with a value buffer of
+    /// `value_words`, starting with `request`, and check the answer is at
+    /// least `min_words` long
+    fn property(
+        &self,
+        tag: u32,
+        value_words: usize,
+        request: &[u32],
+        min_words: usize,
+    ) -> Result<PropertyMessage, MailboxError> {
+        if value_words > MAX_VALUE_WORDS || request.len() > value_words {
+            return Err(MailboxError::RequestFailed);
         }
+        let mut msg = PropertyMessage::new(tag, value_words, request);
 
-        // Build message
-        buffer[0] = 8 * 4; // Buffer size
-        buffer[1] = REQUEST_CODE;
-        buffer[2] = tags::GET_FIRMWARE_REV;
-        buffer[3] = 4; // Value buffer size
-        buffer[4] = 0; // Request
-        buffer[5] = 0; // Value (will be filled by GPU)
-        buffer[6] = 0; // End tag
-        buffer[7] = 0; // Padding
+        unsafe { self.call(&mut msg.words)?; }
 
-        unsafe { self.call(buffer)?; }
+        match msg.response(tag, min_words) {
+            Some(_) => Ok(msg),
+            None => Err(MailboxError::InvalidResponse),
+        }
+    }
 
-        Ok(buffer[5])
+    /// A tag answered with (id, value): send `request`, which starts with
+    /// the id, and return the value for that id
+    fn id_value(&self, tag: u32, request: &[u32]) -> Result<u32, MailboxError> {
+        let msg = self.property(tag, request.len().max(2), request, 2)?;
+        if msg.value(0) != request[0] {
+            return Err(MailboxError::InvalidResponse);
+        }
+        Ok(msg.value(1))
     }
 
-    /// Get board model
-    pub fn get_board_model(&self, buffer: &mut [u32; 36]) -> Result<u32, MailboxError> {
-        for i in 0..36 {
-            buffer[i] = 0;
+    /// SoC temperature in millidegrees Celsius
+    pub fn get_temperature(&self) -> Result<u32, MailboxError> {
+        // Sensor id 0: the SoC
+        self.id_value(tags::GET_TEMPERATURE, &[0])
+    }
+
+    /// Temperature in millidegrees Celsius at which the firmware starts
+    /// throttling
+    pub fn get_max_temperature(&self) -> Result<u32, MailboxError> {
+        self.id_value(tags::GET_MAX_TEMPERATURE, &[0])
+    }
+
+    /// Current rate of clock `id` (see [`clock`]) in Hz
+    pub fn get_clock_rate(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_rate(self.id_value(tags::GET_CLOCK_RATE, &[id])?)
+    }
+
+    /// Highest rate clock `id` may be set to, in Hz
+    pub fn get_max_clock_rate(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_rate(self.id_value(tags::GET_MAX_CLOCK_RATE, &[id])?)
+    }
+
+    /// Lowest rate clock `id` may be set to, in Hz
+    pub fn get_min_clock_rate(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_rate(self.id_value(tags::GET_MIN_CLOCK_RATE, &[id])?)
+    }
+
+    /// Rate of clock `id` as the firmware measures it, in Hz, which may
+    /// differ from the rate set while it throttles
+    pub fn get_measured_clock_rate(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_rate(self.id_value(tags::GET_MEASURED_CLOCK_RATE, &[id])?)
+    }
+
+    /// Set clock `id` to `hz`; returns the rate the firmware chose, which
+    /// it clamps to the clock's range. `skip_turbo` keeps the firmware
+    /// from raising voltages for an ARM rate above the turbo threshold.
+    pub fn set_clock_rate(&self, id: u32, hz: u32, skip_turbo: bool) -> Result<u32, MailboxError> {
+        Self::known_rate(self.id_value(tags::SET_CLOCK_RATE, &[id, hz, skip_turbo as u32])?)
+    }
+
+    /// An unknown clock comes back with rate 0
+    fn known_rate(hz: u32) -> Result<u32, MailboxError> {
+        if hz == 0 {
+            return Err(MailboxError::NoSuchDevice);
         }
+        Ok(hz)
+    }
 
-        buffer[0] = 8 * 4;
-        buffer[1] = REQUEST_CODE;
-        buffer[2] = tags::GET_BOARD_MODEL;
-        buffer[3] = 4;
-        buffer[4] = 0;
-        buffer[5] = 0;
-        buffer[6] = 0;
-        buffer[7] = 0;
+    /// Voltage `id` (see [`voltage`]) in microvolts
+    pub fn get_voltage(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_voltage(self.id_value(tags::GET_VOLTAGE, &[id])?)
+    }
 
-        unsafe { self.call(buffer)?; }
+    pub fn get_max_voltage(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_voltage(self.id_value(tags::GET_MAX_VOLTAGE, &[id])?)
+    }
 
-        Ok(buffer[5])
+    pub fn get_min_voltage(&self, id: u32) -> Result<u32, MailboxError> {
+        Self::known_voltage(self.id_value(tags::GET_MIN_VOLTAGE, &[id])?)
     }
 
-    /// Get board serial number
-    pub fn get_board_serial(&self, buffer: &mut [u32; 36]) -> Result<u64, MailboxError> {
-        for i in 0..36 {
-            buffer[i] = 0;
+    /// Set voltage `id` to `microvolts`; returns the voltage the firmware
+    /// chose
+    pub fn set_voltage(&self, id: u32, microvolts: u32) -> Result<u32, MailboxError> {
+        Self::known_voltage(self.id_value(tags::SET_VOLTAGE, &[id, microvolts])?)
+    }
+
+    /// An unknown voltage comes back as 0x8000_0000
+    fn known_voltage(microvolts: u32) -> Result<u32, MailboxError> {
+        if microvolts == 0x8000_0000 {
+            return Err(MailboxError::NoSuchDevice);
         }
+        Ok(microvolts)
+    }
+
+    /// Whether device `id` (see [`power`]) is powered
+    pub fn get_power_state(&self, id: u32) -> Result<bool, MailboxError> {
+        Self::power_state(self.id_value(tags::GET_POWER_STATE, &[id])?)
+    }
+
+    /// Power device `id` on or off, waiting for it to settle; returns the
+    /// state it is left in
+    pub fn set_power_state(&self, id: u32, on: bool) -> Result<bool, MailboxError> {
+        let state = if on { POWER_ON | POWER_WAIT } else { POWER_WAIT };
+        Self::power_state(self.id_value(tags::SET_POWER_STATE, &[id, state])?)
+    }
+
+    fn power_state(state: u32) -> Result<bool, MailboxError> {
+        if state & POWER_NO_DEVICE != 0 {
+            return Err(MailboxError::NoSuchDevice);
+        }
+        Ok(state & POWER_ON != 0)
+    }
+
+    /// Whether power domain `id` (see [`domain`]) is on
+    pub fn get_domain_state(&self, id: u32) -> Result<bool, MailboxError> {
+        Ok(self.id_value(tags::GET_DOMAIN_STATE, &[id])? & POWER_ON != 0)
+    }
 
-        buffer[0] = 9 * 4;
+    /// Turn power domain `id` on or off; returns the state it is left in
+    pub fn set_domain_state(&self, id: u32, on: bool) -> Result<bool, MailboxError> {
+        Ok(self.id_value(tags::SET_DOMAIN_STATE, &[id, on as u32])? & POWER_ON != 0)
+    }
+
+    /// Firmware under-voltage and throttling state, see [`throttled`]
+    pub fn get_throttled(&self) -> Result<u32, MailboxError> {
+        // 0 leaves the sticky bits set
+        let msg = self.property(tags::GET_THROTTLED, 1, &[0], 1)?;
+        Ok(msg.value(0))
+    }
+
+    /// Read one 128-byte EDID block from the attached HDMI sink.
+    ///
+    /// The firmware fetches the block over DDC. Block 0 is the base block;
+    /// its byte 126 gives the number of extension blocks that follow.
+    pub fn get_edid_block(&self, block: u32, edid: &mut [u8; 128]) -> Result<(), MailboxError> {
+        // 2 header words + 3 tag words + 34 value words (block, status,
+        // 128 EDID bytes) + end tag, padded to a 16-byte multiple.
+        let mut buffer = [0u32; 44];
+
+        buffer[0] = 44 * 4;
         buffer[1] = REQUEST_CODE;
-        buffer[2] = tags::GET_BOARD_SERIAL;
-        buffer[3] = 8;
-        buffer[4] = 0;
-        buffer[5] = 0;
-        buffer[6] = 0;
-        buffer[7] = 0;
-        buffer[8] = 0;
+        buffer[2] = tags::GET_EDID_BLOCK;
+        buffer[3] = 136; // Value buffer size
+        buffer[4] = 4; // Request length (block number)
+        buffer[5] = block;
+        // buffer[6]: status, buffer[7..39]: EDID bytes, buffer[39]: end tag
 
-        unsafe { self.call(buffer)?; }
+        unsafe { self.call(&mut buffer)?; }
 
-        Ok((buffer[6] as u64) << 32 | (buffer[5] as u64))
+        // Tag response bit, then firmware status (non-zero = no sink / DDC error)
+        if buffer[4] & RESPONSE_SUCCESS == 0 || buffer[5] != block {
+            return Err(MailboxError::InvalidResponse);
+        }
+        if buffer[6] != 0 {
+            return Err(MailboxError::RequestFailed);
+        }
+
+        for (i, word) in buffer[7..39].iter().enumerate() {
+            edid[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(())
     }
 }
@@ -9,6 +9,7 @@
 extern crate alloc;
 
 use sel4_microkit::{debug_println, protection_domain, Handler, Channel, ChannelSet};
+use rpi4_pd_error::PdError;
 use linked_list_allocator::LockedHeap;
 
 // Global allocator for fontdue and other alloc-dependent code
@@ -21,6 +22,9 @@ static mut HEAP: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
 
 use rpi4_graphics::{
     Mailbox, Framebuffer, MAILBOX_BASE,
+    BlitEngine, DmaWindow, BLIT_DMA_CHANNEL, BLIT_SCRATCH_PHYS, BLIT_SCRATCH_VIRT, DMA_REGS_VIRT,
+    edid::{parse_edid, EdidInfo},
+    framebuffer::{ladder_from, DisplayMode, ModeSelection},
     graphics::{Color, draw_box, draw_arrow_down},
     font::{draw_string, draw_string_scaled},
     crypto::{Sha256, VerifyResult, constant_time_compare, hex_to_bytes, digest_to_hex},
@@ -41,11 +45,15 @@ const TITLE_COLOR: Color = Color::SEL4_GREEN;
 
 struct GraphicsHandler {
     fb: Option<Framebuffer>,
+    /// Mode the fallback ladder settled on (shown on screen)
+    mode: Option<ModeSelection>,
+    /// Capabilities of the attached display, if it answered EDID
+    edid: Option<EdidInfo>,
 }
 
 impl GraphicsHandler {
     const fn new() -> Self {
-        Self { fb: None }
+        Self { fb: None, mode: None, edid: None }
     }
 
     /// Initialize the framebuffer
@@ -57,36 +65,93 @@ impl GraphicsHandler {
         let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
 
         // Query board info for verification
-        let mut buf = [0u32; 36];
-
-        match mailbox.get_firmware_revision(&mut buf) {
+        match mailbox.get_firmware_revision() {
             Ok(rev) => debug_println!("Firmware revision: 0x{:08x}", rev),
             Err(_) => debug_println!("Failed to get firmware revision"),
         }
 
-        match mailbox.get_board_model(&mut buf) {
+        match mailbox.get_board_model() {
             Ok(model) => debug_println!("Board model: 0x{:08x}", model),
             Err(_) => debug_println!("Failed to get board model"),
         }
 
-        match mailbox.get_board_serial(&mut buf) {
+        match mailbox.get_board_serial() {
             Ok(serial) => debug_println!("Board serial: 0x{:016x}", serial),
             Err(_) => debug_println!("Failed to get board serial"),
         }
 
-        // Allocate framebuffer
-        match unsafe { Framebuffer::new(&mailbox, SCREEN_WIDTH, SCREEN_HEIGHT) } {
-            Ok(fb) => {
+        // Ask the TV what it supports (block 0 is enough for mode selection)
+        let mut block = [0u8; 128];
+        self.edid = match mailbox.get_edid_block(0, &mut block) {
+            Ok(()) => match parse_edid(&block) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    debug_println!("EDID invalid: {:?}", e);
+                    None
+                }
+            },
+            Err(_) => {
+                debug_println!("No EDID from display");
+                None
+            }
+        };
+        if let Some(info) = &self.edid {
+            debug_println!(
+                "EDID: {} \"{}\" product 0x{:04x}, {} modes",
+                info.manufacturer(), info.name(), info.product_code, info.modes().len()
+            );
+            if let Some(p) = info.preferred {
+                debug_println!("EDID preferred: {}x{}@{}", p.width, p.height, p.refresh_hz);
+            }
+        }
+
+        // Allocate framebuffer, stepping down from 720p if the display refuses it.
+        // With EDID we start at the first rung the TV actually advertises.
+        // All drawing below goes through the clipped Framebuffer methods, so a
+        // smaller or 16bpp mode just crops the diagram instead of faulting.
+        let default_mode = DisplayMode::new(SCREEN_WIDTH, SCREEN_HEIGHT, 32);
+        let start = self.edid
+            .and_then(|info| info.pick_mode(ladder_from(default_mode)))
+            .unwrap_or(default_mode);
+        let ladder = ladder_from(start);
+        match unsafe { Framebuffer::new_with_fallback(&mailbox, ladder) } {
+            Ok((fb, selection)) => {
                 let info = fb.info();
                 debug_println!(
-                    "Framebuffer allocated: {}x{} @ 0x{:08x}, pitch={}",
-                    info.width, info.height, info.base, info.pitch
+                    "Framebuffer allocated: {}x{}x{} @ 0x{:08x}, pitch={} (rung {}/{})",
+                    info.width, info.height, info.depth, info.base, info.pitch,
+                    selection.rung + 1, selection.rungs
                 );
+                if selection.is_fallback() {
+                    debug_println!("Preferred mode refused, using fallback");
+                }
                 self.fb = Some(fb);
+                self.mode = Some(selection);
+                self.attach_blitter();
             }
             Err(e) => {
-                debug_println!("Failed to allocate framebuffer: {:?}", e);
+                debug_println!("Failed to allocate framebuffer in any mode: {:?}", e);
+            }
+        }
+    }
+
+    /// Hand full-screen clears and large fills to a DMA channel
+    fn attach_blitter(&mut self) {
+        let Some(fb) = self.fb.as_mut() else { return };
+        let scratch = DmaWindow {
+            vaddr: BLIT_SCRATCH_VIRT,
+            paddr: BLIT_SCRATCH_PHYS,
+            size: 0x1000,
+            cached: false,
+        };
+        // Safety: graphics.system maps the DMA registers and the scratch
+        // page into this PD only, and the firmware leaves the channel free
+        match unsafe { BlitEngine::new(DMA_REGS_VIRT, BLIT_DMA_CHANNEL, scratch) } {
+            Some(engine) => {
+                fb.attach_blitter(engine);
+                debug_println!("Blitter on DMA channel {}", BLIT_DMA_CHANNEL);
             }
+            None => debug_println!("DMA blitter unavailable, drawing with the CPU"),
         }
     }
 
@@ -220,6 +285,30 @@ impl GraphicsHandler {
         draw_string(fb, badge_x + 30, badge_y + 35, "- seL4 (Isabelle)", Color::SEL4_GREEN);
         draw_string(fb, badge_x + 30, badge_y + 50, "- Rust + Verus", Color::SEL4_GREEN);
 
+        // Display mode actually in use (yellow if we had to fall back)
+        if let Some(selection) = self.mode {
+            let mut label_buf = [0u8; 40];
+            let label = selection.mode.label(&mut label_buf);
+            let color = if selection.is_fallback() { Color::YELLOW } else { Color::GRAY };
+            draw_string(fb, 50, SCREEN_HEIGHT - 40, "DISPLAY:", Color::GRAY);
+            draw_string(fb, 50 + 9 * 8, SCREEN_HEIGHT - 40, label, color);
+            if selection.is_fallback() {
+                draw_string(fb, 50, SCREEN_HEIGHT - 28, "(FALLBACK MODE)", Color::YELLOW);
+            }
+        }
+
+        // Attached display as reported by EDID
+        if let Some(info) = &self.edid {
+            let tv = if info.name().is_empty() { info.manufacturer() } else { info.name() };
+            draw_string(fb, 50, SCREEN_HEIGHT - 52, "TV:", Color::GRAY);
+            draw_string(fb, 50 + 9 * 8, SCREEN_HEIGHT - 52, tv, Color::GRAY);
+            if let Some(p) = info.preferred {
+                let mut mode_buf = [0u8; 24];
+                let label = p.label(&mut mode_buf);
+                draw_string(fb, 50 + 9 * 8 + 14 * 8, SCREEN_HEIGHT - 52, label, Color::GRAY);
+            }
+        }
+
         // Footer
         let footer = "seL4 Foundation | sel4.systems";
         let footer_x = (SCREEN_WIDTH - footer.len() as u32 * 8) / 2;
@@ -269,8 +358,8 @@ impl GraphicsHandler {
         line_y += 15;
 
         // Demo 2: Constant-time comparison verification
-        // Known SHA-256("seL4") hash (pre-computed)
-        let expected_hex = "a71c9a6f3e8b2f03e50c0b2c0a3e9f1d8b7c6a5d4e3f2a1b0c9d8e7f6a5b4c3d";
+        // Known SHA-256("seL4") hash (pre-computed with sha256sum)
+        let expected_hex = "b5f7a1157584eb6c24621a39aea7216157afe6c759976faa6fbe5317b2890fd4";
         let expected = hex_to_bytes::<32>(expected_hex);
 
         let (result, color) = match expected {
@@ -278,8 +367,7 @@ impl GraphicsHandler {
                 if constant_time_compare(computed_hash.as_bytes(), &exp) {
                     (VerifyResult::Valid, Color::SEL4_GREEN)
                 } else {
-                    // This is expected - our test hash won't match the fake expected
-                    (VerifyResult::Invalid, Color::YELLOW)
+                    (VerifyResult::Invalid, Color::RED)
                 }
             }
             None => (VerifyResult::NotChecked, Color::GRAY),
@@ -290,7 +378,7 @@ impl GraphicsHandler {
         // Show verification status with appropriate color
         let status_text = match result {
             VerifyResult::Valid => "VERIFIED (hash matches)",
-            VerifyResult::Invalid => "DEMO MODE (test hash)",
+            VerifyResult::Invalid => "MISMATCH",
             VerifyResult::NotChecked => "NOT CHECKED",
         };
         draw_string(fb, panel_x + 180, line_y, status_text, color);
@@ -320,7 +408,7 @@ impl GraphicsHandler {
 
         // Verus verification status
         draw_string(fb, panel_x + 10, line_y, "Verus specs:", TEXT_COLOR);
-        draw_string(fb, panel_x + 110, line_y, "constant_time_compare", Color::SEL4_GREEN);
+        draw_string(fb, panel_x + 110, line_y, "sha256 compress, constant_time_compare", Color::SEL4_GREEN);
 
         debug_println!("Crypto demo complete!");
     }
@@ -361,18 +449,8 @@ impl GraphicsHandler {
     }
 }
 
-/// Error type for the graphics handler
-#[derive(Debug)]
-struct HandlerError;
-
-impl core::fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Graphics handler error")
-    }
-}
-
 impl Handler for GraphicsHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, _channels: ChannelSet) -> Result<(), Self::Error> {
         debug_println!("Received notification");
@@ -0,0 +1,432 @@
+//! # Present Protection Domain
+//!
+//! Scan-out half of the split render architecture (`tvdemo-split.system`).
+//! The App PD renders frames into shared slots without any device access;
+//! this PD owns the display and only moves finished frames onto it.
+//!
+//! ## Responsibilities
+//!
+//! 1. Allocate the framebuffer via the VideoCore mailbox and publish the
+//!    frame geometry in the shared header.
+//! 2. At every [`PRESENT_FRAMES_PER_STEP`]th vertical blank, take the newest
+//!    published frame, copy it to the framebuffer and release the slot.
+//! 3. Notify the App PD whenever a slot comes free so it can render the
+//!    next frame.
+//! 4. Alternatively, draw the App's queued draw commands (clear, rect,
+//!    text, image, present) itself, so the App never writes pixels at all.
+//!    Commands addressed to a scene node are retained instead; on present
+//!    only the parts of the screen the scene changed in are repainted.
+//!
+//! Slot ownership is governed by the verified handoff state machine in
+//! `rpi4-frame-protocol`; this PD never reads a slot it has not taken.
+//! Draw commands are checked against the screen by `rpi4-draw-protocol`
+//! before any of them touches the framebuffer.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError, ResultExt};
+use linked_list_allocator::LockedHeap;
+
+use rpi4_graphics::font::{decode_utf8, Fallback, TextTarget, SANS_16};
+use rpi4_graphics::framebuffer::argb_to_rgb565;
+use rpi4_graphics::{Color, Mailbox, Framebuffer, Vsync, MAILBOX_BASE, SMI_VIRT_BASE, FIXED_720P_LADDER};
+use rpi4_frame_protocol::{
+    frame_geometry, header_ptr, slot_ptr, FrameConsumer, FrameHeader, HandoffError,
+    FRAME_REGION_VADDR, PRESENT_FRAME_CHANNEL_ID,
+};
+use rpi4_draw_protocol::scene::{Content, Node, Rect, Scene};
+use rpi4_draw_protocol::{
+    commands_ptr, image_desc_ptr, image_pixels_ptr, text_pool_ptr, DrawCommand, DrawError,
+    DrawRingHeader, ImageDesc, DISPLAY_DRAW_CHANNEL_ID, DRAW_REGION_VADDR, IMAGE_MAX_SIDE, MAX_TEXT_LEN,
+    OP_CLEAR, OP_IMAGE, OP_MOVE, OP_PRESENT, OP_RECT, OP_REMOVE, OP_RESET, OP_TEXT,
+};
+
+// Global allocator for alloc-dependent code
+#[global_allocator]
+static ALLOCATOR: LockedHeap = LockedHeap::empty();
+
+// 64KB heap
+const HEAP_SIZE: usize = 64 * 1024;
+static mut HEAP: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
+
+/// Channel to the App PD (frame published / slot released)
+const APP_CHANNEL: Channel = Channel::new(PRESENT_FRAME_CHANNEL_ID);
+
+/// Vsync IRQ channel id (must match tvdemo-split.system)
+const VSYNC_IRQ_CHANNEL_ID: usize = 2;
+const VSYNC_CHANNEL: Channel = Channel::new(VSYNC_IRQ_CHANNEL_ID);
+
+/// Vertical blanks per presented frame (20 frames/s at 60 Hz)
+const PRESENT_FRAMES_PER_STEP: u32 = 3;
+
+/// Channel to the App PD's draw ring (commands queued / frame presented)
+const DRAW_CHANNEL: Channel = Channel::new(DISPLAY_DRAW_CHANNEL_ID);
+
+/// Draw commands copied out of the ring at a time
+const DRAW_BATCH: usize = 32;
+/// Batches run per wakeup, so a flooding App cannot hold this PD off vsync;
+/// the rest wait for the next notification or vertical blank
+const DRAW_BATCHES_PER_WAKE: usize = 8;
+
+/// Scene background until the App resets it
+const SCENE_BACKGROUND: u32 = 0xFF00_0000;
+
+struct PresentHandler {
+    framebuffer: Framebuffer,
+    frames: FrameConsumer<'static>,
+    width: usize,
+    height: usize,
+    /// None when the firmware refused vsync; frames are then shown as soon
+    /// as the App publishes them
+    vsync: Option<Vsync>,
+    draw_ring: &'static DrawRingHeader,
+    /// Draw area: the framebuffer, capped to the protocol's u16 coordinates
+    draw_width: u16,
+    draw_height: u16,
+    /// Draw commands refused by validation
+    rejected_draws: u32,
+    /// Nodes the App has drawn retained; repainted where damaged
+    scene: Scene,
+}
+
+impl PresentHandler {
+    /// Show the newest published frame, if any, and hand its slot back
+    fn present(&mut self) -> Result<(), HandoffError> {
+        let slot = match self.frames.take_newest()? {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+
+        let info = *self.framebuffer.info();
+        let dst = self.framebuffer.buffer_ptr();
+        let pitch = self.framebuffer.pitch_pixels();
+
+        unsafe {
+            let src = slot_ptr(FRAME_REGION_VADDR as *mut u8, slot);
+            core::arch::asm!("dsb sy");
+            for y in 0..self.height {
+                let row = src.add(y * self.width);
+                for x in 0..self.width {
+                    let argb = row.add(x).read_volatile();
+                    if info.depth == 16 {
+                        (dst as *mut u16).add(y * pitch + x).write_volatile(argb_to_rgb565(argb));
+                    } else {
+                        dst.add(y * pitch + x).write_volatile(argb);
+                    }
+                }
+            }
+            core::arch::asm!("dsb sy");
+        }
+
+        self.frames.release(slot)?;
+        APP_CHANNEL.notify();
+        Ok(())
+    }
+
+    /// Run the draw commands the App has queued, up to
+    /// [`DRAW_BATCHES_PER_WAKE`] batches
+    fn run_draw_commands(&mut self) {
+        let base = DRAW_REGION_VADDR as *mut u8;
+        let mut batch = [DrawCommand::present(); DRAW_BATCH];
+        for _ in 0..DRAW_BATCHES_PER_WAKE {
+            // Safety: DRAW_REGION_VADDR is mapped by tvdemo-split.system
+            let n = unsafe { self.draw_ring.pop_batch(commands_ptr(base), &mut batch) };
+            if n == 0 {
+                return;
+            }
+            for command in &batch[..n] {
+                if self.execute(command).is_err() {
+                    self.rejected_draws = self.rejected_draws.wrapping_add(1);
+                }
+            }
+        }
+    }
+
+    /// Validate one command against the draw area, then draw it (or, for
+    /// a scene node, retain it)
+    fn execute(&mut self, command: &DrawCommand) -> Result<(), DrawError> {
+        command.validate(self.draw_width, self.draw_height)?;
+        if command.node != 0 && matches!(command.op, OP_RECT | OP_TEXT | OP_IMAGE) {
+            return self.set_node(command);
+        }
+        let (x, y) = (command.x as u32, command.y as u32);
+        let color = Color::from_argb(command.color);
+
+        match command.op {
+            OP_CLEAR => self.framebuffer.clear(color),
+            OP_RECT => self.framebuffer.fill_rect(x, y, command.w as u32, command.h as u32, color),
+            OP_TEXT => {
+                let mut text = [0u8; MAX_TEXT_LEN as usize];
+                let len = copy_text(command, &mut text);
+                SANS_16.draw_chars(
+                    &mut self.framebuffer,
+                    x as i32,
+                    y as i32,
+                    decode_utf8(&text[..len]),
+                    color,
+                    Fallback::default(),
+                );
+            }
+            OP_IMAGE => {
+                let desc = self.image_desc(command)?;
+                let whole = Rect { x: command.x, y: command.y, w: desc.width, h: desc.height };
+                blit_image(&mut self.framebuffer, command.arg, desc.width, (x, y), whole);
+            }
+            OP_MOVE => self.scene.move_node(command.node, command.x, command.y, command.arg)?,
+            OP_REMOVE => self.scene.remove(command.node)?,
+            OP_RESET => self.scene.reset(command.color),
+            OP_PRESENT => {
+                self.repaint_scene();
+                unsafe {
+                    core::arch::asm!("dsb sy");
+                }
+                self.draw_ring.mark_presented();
+                DRAW_CHANNEL.notify();
+            }
+            op => return Err(DrawError::UnknownOp(op)),
+        }
+        Ok(())
+    }
+
+    /// Copy an IMAGE command's descriptor and check it can be drawn where
+    /// the command puts it
+    fn image_desc(&self, command: &DrawCommand) -> Result<ImageDesc, DrawError> {
+        // The descriptor is checked as copied, never re-read
+        let desc = unsafe { image_desc_ptr(DRAW_REGION_VADDR as *mut u8, command.arg).read_volatile() };
+        desc.check_placement(command.x, command.y, self.draw_width, self.draw_height)?;
+        Ok(desc)
+    }
+
+    /// Retain a RECT, TEXT or IMAGE command as what its scene node shows
+    fn set_node(&mut self, command: &DrawCommand) -> Result<(), DrawError> {
+        let (w, h, content) = match command.op {
+            OP_RECT => (command.w, command.h, Content::Rect { color: command.color }),
+            OP_TEXT => {
+                let mut bytes = [0u8; MAX_TEXT_LEN as usize];
+                let len = copy_text(command, &mut bytes);
+                let width = SANS_16.chars_width(decode_utf8(&bytes[..len]), Fallback::default());
+                let content = Content::Text { bytes, len: len as u8, color: command.color };
+                (width.min(u16::MAX as u32) as u16, SANS_16.line_height as u16, content)
+            }
+            OP_IMAGE => {
+                let desc = self.image_desc(command)?;
+                (desc.width, desc.height, Content::Image { slot: command.arg })
+            }
+            op => return Err(DrawError::UnknownOp(op)),
+        };
+        self.scene.set_content(command.node, command.x, command.y, w, h, content)
+    }
+
+    /// Repaint the areas the scene changed in since the last present:
+    /// background, then the nodes over it bottom to top
+    fn repaint_scene(&mut self) {
+        let damage = self.scene.take_damage();
+        let background = Color::from_argb(self.scene.background());
+        for area in damage.rects() {
+            self.framebuffer.fill_rect(area.x as u32, area.y as u32, area.w as u32, area.h as u32, background);
+            let framebuffer = &mut self.framebuffer;
+            self.scene.paint(*area, |node, clip| draw_node(framebuffer, node, clip));
+        }
+    }
+}
+
+/// Copy a validated TEXT command's bytes out of the pool into `text`;
+/// returns their length
+fn copy_text(command: &DrawCommand, text: &mut [u8; MAX_TEXT_LEN as usize]) -> usize {
+    // Copied out first: the App may rewrite the pool meanwhile
+    let len = command.w as usize;
+    unsafe {
+        let src = text_pool_ptr(DRAW_REGION_VADDR as *mut u8).add(command.arg as usize);
+        for (i, byte) in text[..len].iter_mut().enumerate() {
+            *byte = src.add(i).read_volatile();
+        }
+    }
+    len
+}
+
+/// Blit the part inside `clip` of the image in `slot`, `width` pixels
+/// wide, with its top-left corner on the screen at `origin`
+///
+/// `clip` must lie within the image as placed, which was checked against
+/// the copied descriptor.
+fn blit_image(framebuffer: &mut Framebuffer, slot: u16, width: u16, origin: (u32, u32), clip: Rect) {
+    let col = (clip.x as u32 - origin.0) as usize;
+    let span = clip.w as usize;
+    let mut row = [0u32; IMAGE_MAX_SIDE as usize];
+    for py in 0..clip.h as u32 {
+        let src_row = (clip.y as u32 + py - origin.1) as usize;
+        unsafe {
+            let src = image_pixels_ptr(DRAW_REGION_VADDR as *mut u8, slot).add(src_row * width as usize + col);
+            for (px, pixel) in row[..span].iter_mut().enumerate() {
+                *pixel = src.add(px).read_volatile();
+            }
+        }
+        framebuffer.blit(clip.x as u32, clip.y as u32 + py, span as u32, 1, &row[..span]);
+    }
+}
+
+/// Draw the part of scene node `node` inside `clip`
+fn draw_node(framebuffer: &mut Framebuffer, node: &Node, clip: Rect) {
+    let (x, y) = node.origin();
+    match node.content {
+        Content::Rect { color } => {
+            framebuffer.fill_rect(clip.x as u32, clip.y as u32, clip.w as u32, clip.h as u32, Color::from_argb(color));
+        }
+        Content::Text { bytes, len, color } => {
+            SANS_16.draw_chars(
+                &mut ClippedTarget { framebuffer, clip },
+                x as i32,
+                y as i32,
+                decode_utf8(&bytes[..len as usize]),
+                Color::from_argb(color),
+                Fallback::default(),
+            );
+        }
+        Content::Image { slot } => blit_image(framebuffer, slot, node.w, (x, y), clip),
+    }
+}
+
+/// Text target that plots only inside `clip`, so a repaint never spills
+/// glyphs over an area it is not redrawing
+struct ClippedTarget<'a> {
+    framebuffer: &'a mut Framebuffer,
+    clip: Rect,
+}
+
+impl TextTarget for ClippedTarget<'_> {
+    fn plot(&mut self, x: i32, y: i32, color: Color) {
+        let clip = self.clip;
+        if x >= clip.x as i32 && x < clip.x as i32 + clip.w as i32
+            && y >= clip.y as i32 && y < clip.y as i32 + clip.h as i32
+        {
+            self.framebuffer.plot(x, y, color);
+        }
+    }
+}
+
+fn init_framebuffer() -> Option<Framebuffer> {
+    debug_println!("Present PD: Initializing framebuffer...");
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+
+    match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok((fb, selection)) => {
+            let info = fb.info();
+            debug_println!(
+                "Present PD: FB {}x{} @ 0x{:08x}{}",
+                info.width, info.height, info.base,
+                if selection.is_fallback() { " (fallback)" } else { "" }
+            );
+            Some(fb)
+        }
+        Err(e) => {
+            debug_println!("Present PD: FB error: {:?}", e);
+            None
+        }
+    }
+}
+
+fn init_vsync() -> Option<Vsync> {
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+    match unsafe { Vsync::firmware(&mailbox, SMI_VIRT_BASE) } {
+        Ok(vsync) => {
+            debug_println!("Present PD: vsync on IRQ {}", vsync.source().irq());
+            Some(vsync)
+        }
+        Err(e) => {
+            debug_println!("Present PD: vsync unavailable ({:?}), presenting on publish", e);
+            None
+        }
+    }
+}
+
+#[protection_domain]
+fn init() -> PresentHandler {
+    // Initialize the heap allocator
+    unsafe {
+        ALLOCATOR.lock().init(HEAP.as_mut_ptr(), HEAP_SIZE);
+    }
+
+    debug_println!("");
+    debug_println!("========================================");
+    debug_println!("  Present Protection Domain");
+    debug_println!("========================================");
+    debug_println!("");
+
+    let framebuffer = match init_framebuffer() {
+        Some(fb) => fb,
+        None => panic!("Present PD: no framebuffer, cannot present frames"),
+    };
+
+    let (fb_width, fb_height) = framebuffer.dimensions();
+    let (width, height) = match frame_geometry(fb_width, fb_height) {
+        Some(geometry) => geometry,
+        None => panic!("Present PD: framebuffer has no pixels"),
+    };
+
+    // Safety: FRAME_REGION_VADDR is mapped by tvdemo-split.system
+    let header: &'static FrameHeader = unsafe { &*header_ptr(FRAME_REGION_VADDR as *mut u8) };
+    header.reset(width, height);
+    debug_println!("Present PD: frames {}x{}, waking App PD", width, height);
+
+    // Safety: DRAW_REGION_VADDR is mapped by tvdemo-split.system; the App
+    // does not queue commands before the first notification below
+    let draw_ring: &'static DrawRingHeader = unsafe {
+        let ring = rpi4_draw_protocol::header_ptr(DRAW_REGION_VADDR as *mut u8);
+        DrawRingHeader::init(ring);
+        &*ring
+    };
+
+    let handler = PresentHandler {
+        framebuffer,
+        frames: FrameConsumer::new(header),
+        width: width as usize,
+        height: height as usize,
+        vsync: init_vsync(),
+        draw_ring,
+        draw_width: fb_width.min(u16::MAX as u32) as u16,
+        draw_height: fb_height.min(u16::MAX as u32) as u16,
+        rejected_draws: 0,
+        scene: Scene::new(
+            fb_width.min(u16::MAX as u32) as u16,
+            fb_height.min(u16::MAX as u32) as u16,
+            SCENE_BACKGROUND,
+        ),
+    };
+
+    // The App waits for the geometry before rendering its first frame
+    APP_CHANNEL.notify();
+    handler
+}
+
+impl Handler for PresentHandler {
+    type Error = PdError;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        if channels.contains(VSYNC_CHANNEL) {
+            if let Some(vsync) = self.vsync.as_mut() {
+                if vsync.handle_irq() && vsync.frames() % PRESENT_FRAMES_PER_STEP == 0 {
+                    self.present().context("presenting a frame")?;
+                }
+            }
+            VSYNC_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking vsync IRQ")
+            })?;
+            // Pick up commands left over from a busy wakeup
+            self.run_draw_commands();
+        }
+
+        if channels.contains(DRAW_CHANNEL) {
+            self.run_draw_commands();
+        }
+
+        if channels.contains(APP_CHANNEL) && self.vsync.is_none() {
+            self.present().context("presenting a frame")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! # Cycle-Counter Profiling
+//!
+//! `profile_scope!("name")` measures the CPU cycles from the macro to the end
+//! of the enclosing block with the PMU cycle counter (PMCCNTR_EL0) and adds
+//! them to that scope's slot in a fixed table ([`SCOPES`]). Call
+//! [`ScopeTable::end_frame`] once per frame to latch the totals;
+//! [`ScopeTable::top`] then lists the most expensive scopes of the last
+//! frame, which is what the on-screen profiling summaries draw.
+//!
+//! ```ignore
+//! fn render(&mut self) {
+//!     rpi4_graphics::profile_scope!("render");
+//!     // ...
+//! }
+//! ```
+//!
+//! Without the `profile` feature the macro expands to nothing, so scopes can
+//! stay in the render and decode paths of release builds.
+//!
+//! Reading the counter from a PD needs EL0 access to the PMU, which seL4
+//! grants when the kernel is built with `KernelArmExportPMUUser`; call
+//! [`enable_cycle_counter`] once at init. Times are inclusive: a scope nested
+//! inside another is counted in both.
+
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+/// Number of distinct scopes the table can hold
+pub const MAX_SCOPES: usize = 32;
+
+/// Read the PMU cycle counter
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+pub fn read_cycles() -> u64 {
+    let cycles: u64;
+    unsafe {
+        core::arch::asm!("mrs {}, pmccntr_el0", out(reg) cycles, options(nomem, nostack));
+    }
+    cycles
+}
+
+/// Host builds have no PMU; every scope measures zero cycles
+#[cfg(not(target_arch = "aarch64"))]
+#[inline(always)]
+pub fn read_cycles() -> u64 {
+    0
+}
+
+/// Start the 64-bit PMU cycle counter
+///
+/// # Safety
+/// EL0 must have PMU access (seL4 `KernelArmExportPMUUser`), otherwise the
+/// register writes fault.
+#[cfg(target_arch = "aarch64")]
+pub unsafe fn enable_cycle_counter() {
+    // PMCR_EL0.E (bit 0) enables the counters, PMCR_EL0.LC (bit 6) makes the
+    // cycle counter overflow at 64 bits; PMCNTENSET_EL0 bit 31 starts it
+    core::arch::asm!(
+        "mrs {pmcr}, pmcr_el0",
+        "orr {pmcr}, {pmcr}, #0x41",
+        "msr pmcr_el0, {pmcr}",
+        "msr pmcntenset_el0, {cycle}",
+        "isb",
+        pmcr = out(reg) _,
+        cycle = in(reg) 1u64 << 31,
+        options(nostack),
+    );
+}
+
+/// A named, statically allocated scope accumulator
+///
+/// Created by `profile_scope!`; one per call site.
+pub struct Scope {
+    name: &'static str,
+    registered: AtomicBool,
+    cycles: AtomicU64,
+    calls: AtomicU32,
+    frame_cycles: AtomicU64,
+    frame_calls: AtomicU32,
+}
+
+impl Scope {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            registered: AtomicBool::new(false),
+            cycles: AtomicU64::new(0),
+            calls: AtomicU32::new(0),
+            frame_cycles: AtomicU64::new(0),
+            frame_calls: AtomicU32::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// One scope's totals for the last completed frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScopeSample {
+    pub name: &'static str,
+    pub cycles: u64,
+    pub calls: u32,
+}
+
+/// Fixed table of the scopes seen so far
+///
+/// Scopes register themselves on first use; once [`MAX_SCOPES`] are taken,
+/// further scopes are counted in [`ScopeTable::dropped`] and not recorded.
+pub struct ScopeTable {
+    slots: [AtomicPtr<Scope>; MAX_SCOPES],
+    dropped: AtomicU32,
+}
+
+/// The table `profile_scope!` records into
+pub static SCOPES: ScopeTable = ScopeTable::new();
+
+impl ScopeTable {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { AtomicPtr::new(ptr::null_mut()) }; MAX_SCOPES],
+            dropped: AtomicU32::new(0),
+        }
+    }
+
+    /// Add one execution of `scope` that took `cycles`
+    pub fn record(&self, scope: &'static Scope, cycles: u64) {
+        if !scope.registered.swap(true, Ordering::Relaxed) && !self.register(scope) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        scope.cycles.fetch_add(cycles, Ordering::Relaxed);
+        scope.calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn register(&self, scope: &'static Scope) -> bool {
+        let scope = scope as *const Scope as *mut Scope;
+        self.slots.iter().any(|slot| {
+            slot.compare_exchange(ptr::null_mut(), scope, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        })
+    }
+
+    fn scopes(&self) -> impl Iterator<Item = &'static Scope> + '_ {
+        // Safety: only `&'static Scope`s are ever stored in a slot
+        self.slots
+            .iter()
+            .map_while(|slot| unsafe { slot.load(Ordering::Acquire).as_ref() })
+    }
+
+    /// Latch the totals accumulated since the last call as the last frame's
+    /// figures and start counting the next frame
+    pub fn end_frame(&self) {
+        for scope in self.scopes() {
+            let cycles = scope.cycles.swap(0, Ordering::Relaxed);
+            let calls = scope.calls.swap(0, Ordering::Relaxed);
+            scope.frame_cycles.store(cycles, Ordering::Relaxed);
+            scope.frame_calls.store(calls, Ordering::Relaxed);
+        }
+    }
+
+    /// The `N` scopes with the most cycles in the last frame, most expensive
+    /// first; scopes that did not run are left out
+    pub fn top<const N: usize>(&self) -> [Option<ScopeSample>; N] {
+        let mut top = [None; N];
+        for scope in self.scopes() {
+            let sample = ScopeSample {
+                name: scope.name,
+                cycles: scope.frame_cycles.load(Ordering::Relaxed),
+                calls: scope.frame_calls.load(Ordering::Relaxed),
+            };
+            if sample.calls == 0 {
+                continue;
+            }
+
+            // Insertion into the sorted array; the cheapest entry falls off
+            let Some(pos) = top
+                .iter()
+                .position(|t: &Option<ScopeSample>| t.is_none_or(|t| sample.cycles > t.cycles))
+            else {
+                continue;
+            };
+            top[pos..].rotate_right(1);
+            top[pos] = Some(sample);
+        }
+        top
+    }
+
+    /// Scopes that ran but found the table full
+    pub fn dropped(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for ScopeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Measures one execution of a scope; records it when dropped
+pub struct ScopeGuard {
+    table: &'static ScopeTable,
+    scope: &'static Scope,
+    start: u64,
+}
+
+impl ScopeGuard {
+    #[inline(always)]
+    pub fn enter(table: &'static ScopeTable, scope: &'static Scope) -> Self {
+        Self { table, scope, start: read_cycles() }
+    }
+}
+
+impl Drop for ScopeGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.table.record(self.scope, read_cycles().wrapping_sub(self.start));
+    }
+}
+
+/// Count the cycles until the end of the enclosing block under `name`
+#[cfg(feature = "profile")]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:literal) => {
+        let _profile_scope_guard = {
+            static SCOPE: $crate::profile::Scope = $crate::profile::Scope::new($name);
+            $crate::profile::ScopeGuard::enter(&$crate::profile::SCOPES, &SCOPE)
+        };
+    };
+}
+
+/// Profiling is compiled out without the `profile` feature
+#[cfg(not(feature = "profile"))]
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:literal) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_orders_last_frame() {
+        static TABLE: ScopeTable = ScopeTable::new();
+        static DECODE: Scope = Scope::new("decode");
+        static BLIT: Scope = Scope::new("blit");
+        static CLEAR: Scope = Scope::new("clear");
+
+        TABLE.record(&BLIT, 300);
+        TABLE.record(&DECODE, 1000);
+        TABLE.record(&CLEAR, 50);
+        TABLE.record(&BLIT, 200);
+
+        // Nothing is visible until the frame is latched
+        assert_eq!(TABLE.top::<2>(), [None, None]);
+        TABLE.end_frame();
+
+        let top = TABLE.top::<2>();
+        assert_eq!(top[0], Some(ScopeSample { name: "decode", cycles: 1000, calls: 1 }));
+        assert_eq!(top[1], Some(ScopeSample { name: "blit", cycles: 500, calls: 2 }));
+
+        // A scope that did not run in the next frame drops out
+        TABLE.record(&CLEAR, 70);
+        TABLE.end_frame();
+        let top = TABLE.top::<4>();
+        assert_eq!(top[0], Some(ScopeSample { name: "clear", cycles: 70, calls: 1 }));
+        assert_eq!(top[1], None);
+    }
+
+    #[test]
+    fn test_full_table_drops_new_scopes() {
+        static TABLE: ScopeTable = ScopeTable::new();
+        static SCOPES: [Scope; MAX_SCOPES + 1] = [const { Scope::new("s") }; MAX_SCOPES + 1];
+
+        for scope in &SCOPES {
+            TABLE.record(scope, 1);
+        }
+        assert_eq!(TABLE.dropped(), 1);
+        // Recording the dropped scope again does not count it twice
+        TABLE.record(&SCOPES[MAX_SCOPES], 1);
+        assert_eq!(TABLE.dropped(), 1);
+    }
+}
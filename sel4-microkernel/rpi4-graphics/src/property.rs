@@ -0,0 +1,464 @@
+//! # Mailbox Property Messages
+//!
+//! The single-tag property messages [`Mailbox`](crate::Mailbox) sends on
+//! the property channel, and the check of the firmware's answer:
+//!
+//! ```text
+//! word 0     message size in bytes, a multiple of 16
+//! word 1     request code; the firmware's response code
+//! word 2     tag
+//! word 3     value buffer size in bytes
+//! word 4     request length in bytes; response bit 31 | response length
+//! word 5..   value buffer: request words in, response words out
+//! then       end tag (0), zero padding
+//! ```
+//!
+//! The firmware writes its answer over the value buffer, so the buffer is
+//! sized for the longer of request and response.
+//!
+//! Several tags can share one message, each header and value buffer
+//! following the last, with one end tag after them all; the firmware
+//! processes them in order. [`MailboxMessageBuilder`] lays those out,
+//! handing back a [`Tag`] for each to read its answer with.
+//!
+//! ## Verification
+//!
+//! [`PropertyMessage::new`] is proven to lay the message out inside
+//! [`MESSAGE_WORDS`], with a size field that is a multiple of 16 bytes
+//! and covers the value buffer and the end tag, and the request copied to
+//! the start of the value buffer. [`PropertyMessage::response`] is proven
+//! to return a length within the value buffer, whatever the firmware
+//! wrote, so no wrapper reads past it.
+//!
+//! [`MailboxMessageBuilder::tag`] is proven to place each tag after the
+//! last and to refuse one that would leave no room for the end tag, so a
+//! built message never runs past [`MESSAGE_WORDS`]; a [`Tag`] it returns
+//! is proven to lie inside the message, and [`MailboxMessage::response`]
+//! bounds the answer by that tag's value buffer.
+
+// Verus requires explicit arithmetic (e.g., `x = x + 1`), and the
+// divisibility checks spelled out, for verification specs
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::manual_is_multiple_of)]
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// Words in a message buffer, the usual `[u32; 36]`
+pub const MESSAGE_WORDS: usize = 36;
+/// Index of the first value-buffer word
+pub const VALUE_OFFSET: usize = 5;
+/// Largest value buffer: what is left after the headers and the end tag
+pub const MAX_VALUE_WORDS: usize = MESSAGE_WORDS - VALUE_OFFSET - 1;
+
+pub const REQUEST_CODE: u32 = 0x0000_0000;
+/// Response code of a message the firmware processed
+pub const RESPONSE_SUCCESS: u32 = 0x8000_0000;
+/// Set in word 4 once the firmware has answered the tag
+pub const TAG_RESPONSE: u32 = 0x8000_0000;
+
+pub open spec fn message_words_spec(value_words: int) -> int {
+    let used = VALUE_OFFSET + value_words + 1;
+    if used % 4 == 0 { used } else { used + 4 - used % 4 }
+}
+
+/// Words a message with `value_words` of value buffer takes, padded to a
+/// 16-byte multiple
+pub fn message_words(value_words: usize) -> (words: usize)
+    requires
+        value_words <= MAX_VALUE_WORDS,
+    ensures
+        words == message_words_spec(value_words as int),
+        words % 4 == 0,
+        VALUE_OFFSET + value_words + 1 <= words,
+        words <= MESSAGE_WORDS,
+{
+    let used = VALUE_OFFSET + value_words + 1;
+    if used % 4 == 0 {
+        used
+    } else {
+        used + 4 - used % 4
+    }
+}
+
+/// A one-tag property message
+pub struct PropertyMessage {
+    /// The message, as [`Mailbox::call`](crate::Mailbox::call) sends it
+    pub words: [u32; MESSAGE_WORDS],
+    value_words: usize,
+}
+
+impl PropertyMessage {
+    pub open spec fn wf(&self) -> bool {
+        self.value_words <= MAX_VALUE_WORDS
+    }
+
+    /// A request for `tag` with a value buffer of `value_words`, starting
+    /// with `request`
+    pub fn new(tag: u32, value_words: usize, request: &[u32]) -> (msg: Self)
+        requires
+            value_words <= MAX_VALUE_WORDS,
+            request.len() <= value_words,
+        ensures
+            msg.wf(),
+            msg.value_words == value_words,
+            msg.words[0] as int == message_words_spec(value_words as int) * 4,
+            msg.words[2] == tag,
+            msg.words[VALUE_OFFSET + value_words] == 0,
+            forall|i: int| 0 <= i < request.len() ==> msg.words[VALUE_OFFSET + i] == request[i],
+    {
+        let mut words = [0u32; MESSAGE_WORDS];
+        words[0] = (message_words(value_words) * 4) as u32;
+        words[1] = REQUEST_CODE;
+        words[2] = tag;
+        words[3] = (value_words * 4) as u32;
+        words[4] = (request.len() * 4) as u32;
+        let mut i: usize = 0;
+        while i < request.len()
+            invariant
+                i <= request.len(),
+                request.len() <= value_words,
+                value_words <= MAX_VALUE_WORDS,
+                words[0] as int == message_words_spec(value_words as int) * 4,
+                words[2] == tag,
+                words[VALUE_OFFSET + value_words] == 0,
+                forall|j: int| 0 <= j < i ==> words[VALUE_OFFSET + j] == request[j],
+            decreases request.len() - i,
+        {
+            words[VALUE_OFFSET + i] = request[i];
+            i = i + 1;
+        }
+        PropertyMessage { words, value_words }
+    }
+
+    /// Value-buffer word `i`
+    pub fn value(&self, i: usize) -> u32
+        requires
+            self.wf(),
+            i < self.value_words,
+    {
+        self.words[VALUE_OFFSET + i]
+    }
+
+    /// Check the firmware answered `tag`; returns the response length in
+    /// words, at least `min_words` and never past the value buffer
+    pub fn response(&self, tag: u32, min_words: usize) -> (len: Option<usize>)
+        requires
+            self.wf(),
+        ensures
+            len.is_some() ==> min_words <= len.unwrap() && len.unwrap() <= self.value_words,
+    {
+        if self.words[1] != RESPONSE_SUCCESS || self.words[2] != tag {
+            return None;
+        }
+        let status = self.words[4];
+        if status < TAG_RESPONSE {
+            return None;
+        }
+        // A length past the buffer means the firmware had more to say than
+        // fit: the value is truncated
+        let bytes = (status - TAG_RESPONSE) as usize;
+        if bytes > self.value_words * 4 {
+            return None;
+        }
+        let len = if bytes % 4 == 0 { bytes / 4 } else { bytes / 4 + 1 };
+        if len < min_words || len > self.value_words {
+            return None;
+        }
+        Some(len)
+    }
+}
+
+// ============================================================================
+// MULTI-TAG MESSAGES
+// ============================================================================
+
+/// Words of a tag's header: tag, value buffer size, request length
+pub const TAG_HEADER_WORDS: usize = 3;
+/// Index of the first tag
+pub const FIRST_TAG_OFFSET: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// The tag and the end tag would not fit [`MESSAGE_WORDS`]
+    NoRoom,
+    /// The request is longer than the value buffer
+    RequestTooLong,
+}
+
+/// Where a tag sits in a message [`MailboxMessageBuilder`] built
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tag {
+    id: u32,
+    offset: usize,
+    value_words: usize,
+}
+
+impl Tag {
+    pub open spec fn wf(&self) -> bool {
+        FIRST_TAG_OFFSET <= self.offset
+            && self.offset + TAG_HEADER_WORDS + self.value_words < MESSAGE_WORDS
+    }
+
+    pub fn value_words(&self) -> usize {
+        self.value_words
+    }
+}
+
+/// Lays property tags out one after another in a message buffer
+pub struct MailboxMessageBuilder {
+    words: [u32; MESSAGE_WORDS],
+    /// Index of the next tag, which is where the end tag goes
+    len: usize,
+}
+
+impl MailboxMessageBuilder {
+    pub open spec fn wf(&self) -> bool {
+        FIRST_TAG_OFFSET <= self.len && self.len < MESSAGE_WORDS
+    }
+
+    pub fn new() -> (builder: Self)
+        ensures
+            builder.wf(),
+            builder.len == FIRST_TAG_OFFSET,
+    {
+        let mut words = [0u32; MESSAGE_WORDS];
+        words[1] = REQUEST_CODE;
+        MailboxMessageBuilder { words, len: FIRST_TAG_OFFSET }
+    }
+
+    /// Append `tag` with a value buffer of `value_words`, starting with
+    /// `request`
+    pub fn tag(&mut self, tag: u32, value_words: usize, request: &[u32]) -> (placed: Result<Tag, BuildError>)
+        requires
+            old(self).wf(),
+        ensures
+            self.wf(),
+            placed.is_ok() ==> {
+                let t = placed.unwrap();
+                &&& t.wf()
+                &&& t.offset == old(self).len
+                &&& t.value_words == value_words
+                &&& self.len == old(self).len + TAG_HEADER_WORDS + value_words
+            },
+            placed.is_err() ==> self.len == old(self).len,
+    {
+        if request.len() > value_words {
+            return Err(BuildError::RequestTooLong);
+        }
+        // Room left before the end tag's word
+        let room = MESSAGE_WORDS - 1 - self.len;
+        if room < TAG_HEADER_WORDS || value_words > room - TAG_HEADER_WORDS {
+            return Err(BuildError::NoRoom);
+        }
+        let offset = self.len;
+        self.words[offset] = tag;
+        self.words[offset + 1] = (value_words * 4) as u32;
+        self.words[offset + 2] = (request.len() * 4) as u32;
+        let value = offset + TAG_HEADER_WORDS;
+        let mut i: usize = 0;
+        while i < request.len()
+            invariant
+                i <= request.len(),
+                request.len() <= value_words,
+                value + value_words < MESSAGE_WORDS,
+                offset == self.len,
+                self.wf(),
+            decreases request.len() - i,
+        {
+            self.words[value + i] = request[i];
+            i = i + 1;
+        }
+        self.len = value + value_words;
+        Ok(Tag { id: tag, offset, value_words })
+    }
+
+    /// Write the end tag and the size: the message to send
+    pub fn finish(self) -> (msg: MailboxMessage)
+        requires
+            self.wf(),
+        ensures
+            msg.words[0] as int == message_words_spec(self.len - VALUE_OFFSET) * 4,
+            msg.words[self.len as int] == 0,
+    {
+        let mut words = self.words;
+        words[self.len] = 0;
+        // The end tag's word plus padding, by the same rule as one tag
+        let used = self.len + 1;
+        let size = if used % 4 == 0 { used } else { used + 4 - used % 4 };
+        words[0] = (size * 4) as u32;
+        MailboxMessage { words }
+    }
+}
+
+/// A built message: send `words`, then read each tag's answer
+pub struct MailboxMessage {
+    /// The message, as [`Mailbox::call`](crate::Mailbox::call) sends it
+    pub words: [u32; MESSAGE_WORDS],
+}
+
+impl MailboxMessage {
+    /// Check the firmware answered `tag`; returns the response length in
+    /// words, at least `min_words` and never past its value buffer
+    pub fn response(&self, tag: Tag, min_words: usize) -> (len: Option<usize>)
+        requires
+            tag.wf(),
+        ensures
+            len.is_some() ==> min_words <= len.unwrap() && len.unwrap() <= tag.value_words,
+    {
+        // A tag from some other message would not find its id here
+        if self.words[1] != RESPONSE_SUCCESS || self.words[tag.offset] != tag.id {
+            return None;
+        }
+        let status = self.words[tag.offset + 2];
+        if status < TAG_RESPONSE {
+            return None;
+        }
+        let bytes = (status - TAG_RESPONSE) as usize;
+        if bytes > tag.value_words * 4 {
+            return None;
+        }
+        let len = if bytes % 4 == 0 { bytes / 4 } else { bytes / 4 + 1 };
+        if len < min_words || len > tag.value_words {
+            return None;
+        }
+        Some(len)
+    }
+
+    /// Value-buffer word `i` of `tag`
+    pub fn value(&self, tag: Tag, i: usize) -> u32
+        requires
+            tag.wf(),
+            i < tag.value_words,
+    {
+        self.words[tag.offset + TAG_HEADER_WORDS + i]
+    }
+}
+
+} // verus!
+
+impl Default for MailboxMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MailboxMessage {
+    /// The first `K` words of `tag`'s answer, if it answered with at
+    /// least that many
+    pub fn values<const K: usize>(&self, tag: Tag) -> Option<[u32; K]> {
+        if K > tag.value_words {
+            return None;
+        }
+        self.response(tag, K)?;
+        let mut out = [0u32; K];
+        for (i, word) in out.iter_mut().enumerate() {
+            *word = self.value(tag, i);
+        }
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_padded_to_sixteen_bytes() {
+        assert_eq!(message_words(0), 8);
+        assert_eq!(message_words(2), 8);
+        assert_eq!(message_words(3), 12);
+        assert_eq!(message_words(MAX_VALUE_WORDS), MESSAGE_WORDS);
+    }
+
+    #[test]
+    fn request_lays_out_like_the_hand_built_ones() {
+        let msg = PropertyMessage::new(0x0003_0006, 2, &[0]);
+        assert_eq!(&msg.words[..8], &[32, REQUEST_CODE, 0x0003_0006, 8, 4, 0, 0, 0]);
+        let msg = PropertyMessage::new(0x0003_8002, 3, &[3, 1_500_000_000, 1]);
+        assert_eq!(&msg.words[..12], &[48, 0, 0x0003_8002, 12, 12, 3, 1_500_000_000, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn response_is_checked_against_the_value_buffer() {
+        let mut msg = PropertyMessage::new(0x0003_0002, 2, &[3]);
+        // Not answered yet
+        assert_eq!(msg.response(0x0003_0002, 2), None);
+
+        msg.words[1] = RESPONSE_SUCCESS;
+        msg.words[4] = TAG_RESPONSE | 8;
+        msg.words[6] = 1_500_000_000;
+        assert_eq!(msg.response(0x0003_0002, 2), Some(2));
+        assert_eq!(msg.value(1), 1_500_000_000);
+        // Answered some other tag, or too short for the caller
+        assert_eq!(msg.response(0x0003_0004, 2), None);
+        assert_eq!(msg.response(0x0003_0002, 3), None);
+        // The firmware had more than fits the buffer
+        msg.words[4] = TAG_RESPONSE | 12;
+        assert_eq!(msg.response(0x0003_0002, 2), None);
+        msg.words[1] = 0x8000_0001;
+        msg.words[4] = TAG_RESPONSE | 8;
+        assert_eq!(msg.response(0x0003_0002, 2), None);
+    }
+
+    #[test]
+    fn builder_lays_tags_out_back_to_back() {
+        let mut builder = MailboxMessageBuilder::new();
+        let size = builder.tag(0x0004_8003, 2, &[1280, 720]).unwrap();
+        let depth = builder.tag(0x0004_8005, 1, &[32]).unwrap();
+        let pitch = builder.tag(0x0004_0008, 1, &[]).unwrap();
+        let msg = builder.finish();
+        assert_eq!(
+            &msg.words[..16],
+            &[64, REQUEST_CODE, 0x0004_8003, 8, 8, 1280, 720, 0x0004_8005, 4, 4, 32, 0x0004_0008, 4, 0, 0, 0]
+        );
+        assert_eq!((size.value_words(), depth.value_words(), pitch.value_words()), (2, 1, 1));
+        // A single tag comes out as PropertyMessage lays it out
+        let mut builder = MailboxMessageBuilder::new();
+        builder.tag(0x0003_0006, 2, &[0]).unwrap();
+        assert_eq!(builder.finish().words, PropertyMessage::new(0x0003_0006, 2, &[0]).words);
+    }
+
+    #[test]
+    fn builder_refuses_what_does_not_fit() {
+        let mut builder = MailboxMessageBuilder::new();
+        assert_eq!(builder.tag(1, 1, &[1, 2]), Err(BuildError::RequestTooLong));
+        // Two headers, 27 value words and the end tag fill it exactly
+        builder.tag(1, 10, &[]).unwrap();
+        assert_eq!(builder.tag(2, 18, &[]), Err(BuildError::NoRoom));
+        let last = builder.tag(2, 17, &[]).unwrap();
+        assert_eq!(builder.tag(3, 0, &[]), Err(BuildError::NoRoom));
+        let msg = builder.finish();
+        assert_eq!(msg.words[0] as usize, MESSAGE_WORDS * 4);
+        assert_eq!(last.value_words(), 17);
+    }
+
+    #[test]
+    fn answers_are_read_per_tag() {
+        let mut builder = MailboxMessageBuilder::new();
+        let size = builder.tag(0x0004_8003, 2, &[1920, 1080]).unwrap();
+        let alloc = builder.tag(0x0004_0001, 2, &[4096]).unwrap();
+        let mut msg = builder.finish();
+        assert_eq!(msg.values::<2>(size), None);
+
+        msg.words[1] = RESPONSE_SUCCESS;
+        msg.words[4] = TAG_RESPONSE | 8;
+        msg.words[5] = 1280;
+        msg.words[6] = 720;
+        msg.words[9] = TAG_RESPONSE | 8;
+        msg.words[10] = 0x3e40_0000;
+        msg.words[11] = 0x0038_4000;
+        assert_eq!(msg.values::<2>(size), Some([1280, 720]));
+        assert_eq!(msg.values::<2>(alloc), Some([0x3e40_0000, 0x0038_4000]));
+        assert_eq!(msg.values::<3>(alloc), None);
+
+        // A tag from another message does not read this one
+        let mut other = MailboxMessageBuilder::new();
+        let stranger = other.tag(0x0003_0002, 2, &[3]).unwrap();
+        assert_eq!(msg.response(stranger, 0), None);
+    }
+}
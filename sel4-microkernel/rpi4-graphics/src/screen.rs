@@ -0,0 +1,313 @@
+//! # Fixed-Size Screens
+//!
+//! A PD laid out for one resolution (the photo frame's 1280x720, say) knows
+//! its screen size at compile time, yet every helper it calls checks
+//! coordinates again against a width and height read at runtime. A
+//! [`Screen<W, H>`](Screen) makes the size part of the type. It is checked
+//! once, when it is made over a framebuffer or a pixel buffer, to have room
+//! for `W` x `H` pixels at its pitch. After that a [`ScreenPoint<W, H>`] or
+//! [`ScreenRect<W, H>`] is on screen by construction, so drawing with one
+//! needs no further checks. There are three ways to make one:
+//!
+//! - from runtime values, checked once (`new`)
+//! - by clipping to the screen (`ScreenRect::clip`)
+//! - from constants, checked by the compiler (`fixed`)
+//!
+//! ```text
+//! ScreenRect::<1280, 720>::fixed::<0, 0, 1280, 40>()    // the top bar
+//! ScreenRect::<1280, 720>::fixed::<0, 700, 1280, 40>()  // compile error
+//! ```
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - `rect_fits` holds exactly when a rectangle lies inside the screen, for
+//!   coordinates anywhere in `u32`
+//! - `pixel_offset` of a pixel in row `y` is inside that row, so below
+//!   `pitch * H`, the extent a screen is checked to cover when it is made
+
+use core::marker::PhantomData;
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::framebuffer::{DrawError, Framebuffer};
+
+verus! {
+
+/// The `w` x `h` rectangle at (x, y) lies inside a `width` x `height` screen
+pub open spec fn rect_inside(x: u32, y: u32, w: u32, h: u32, width: u32, height: u32) -> bool {
+    x as int + w as int <= width as int && y as int + h as int <= height as int
+}
+
+/// Does the `w` x `h` rectangle at (x, y) fit a `width` x `height` screen?
+pub fn rect_fits(x: u32, y: u32, w: u32, h: u32, width: u32, height: u32) -> (r: bool)
+    ensures
+        r == rect_inside(x, y, w, h, width, height),
+{
+    (x as u64) + (w as u64) <= width as u64 && (y as u64) + (h as u64) <= height as u64
+}
+
+/// Offset of pixel (x, y) in rows of `pitch` pixels: inside row `y`, so
+/// below `pitch * height` for any `height` past `y`
+pub fn pixel_offset(x: u32, y: u32, pitch: usize) -> (o: usize)
+    requires
+        (x as int) < pitch as int,
+        (y as int + 1) * pitch as int <= usize::MAX as int,
+    ensures
+        o == y as int * pitch as int + x as int,
+        o < (y as int + 1) * pitch as int,
+{
+    proof {
+        assert(y as int * pitch as int + pitch as int == (y as int + 1) * pitch as int) by (nonlinear_arith);
+    }
+    y as usize * pitch + x as usize
+}
+
+} // verus!
+
+/// A pixel on a `W` x `H` screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenPoint<const W: u32, const H: u32> {
+    x: u32,
+    y: u32,
+}
+
+impl<const W: u32, const H: u32> ScreenPoint<W, H> {
+    /// The pixel at (x, y), or `None` off the screen
+    pub const fn new(x: u32, y: u32) -> Option<Self> {
+        if x < W && y < H {
+            Some(Self { x, y })
+        } else {
+            None
+        }
+    }
+
+    /// The pixel at constant (X, Y), checked at compile time
+    pub const fn fixed<const X: u32, const Y: u32>() -> Self {
+        const { assert!(X < W && Y < H, "point is off the screen") };
+        Self { x: X, y: Y }
+    }
+
+    pub const fn x(self) -> u32 {
+        self.x
+    }
+
+    pub const fn y(self) -> u32 {
+        self.y
+    }
+}
+
+/// A rectangle inside a `W` x `H` screen (possibly empty)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScreenRect<const W: u32, const H: u32> {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl<const W: u32, const H: u32> ScreenRect<W, H> {
+    /// The whole screen
+    pub const FULL: Self = Self { x: 0, y: 0, w: W, h: H };
+
+    /// The `w` x `h` rectangle at (x, y), refused unless it lies entirely
+    /// on screen
+    pub fn new(x: u32, y: u32, w: u32, h: u32) -> Result<Self, DrawError> {
+        if rect_fits(x, y, w, h, W, H) {
+            Ok(Self { x, y, w, h })
+        } else {
+            Err(DrawError::RectOutOfBounds { x, y, w, h, width: W, height: H })
+        }
+    }
+
+    /// The part of the `w` x `h` rectangle at (x, y) that is on screen
+    pub fn clip(x: u32, y: u32, w: u32, h: u32) -> Self {
+        let (x, y) = (x.min(W), y.min(H));
+        Self { x, y, w: w.min(W - x), h: h.min(H - y) }
+    }
+
+    /// The constant `RW` x `RH` rectangle at (X, Y), checked at compile
+    /// time
+    pub const fn fixed<const X: u32, const Y: u32, const RW: u32, const RH: u32>() -> Self {
+        const {
+            assert!(
+                X as u64 + RW as u64 <= W as u64 && Y as u64 + RH as u64 <= H as u64,
+                "rectangle does not fit the screen"
+            )
+        };
+        Self { x: X, y: Y, w: RW, h: RH }
+    }
+
+    pub const fn x(self) -> u32 {
+        self.x
+    }
+
+    pub const fn y(self) -> u32 {
+        self.y
+    }
+
+    pub const fn width(self) -> u32 {
+        self.w
+    }
+
+    pub const fn height(self) -> u32 {
+        self.h
+    }
+}
+
+/// A `W` x `H` ARGB8888 screen: the top-left `W` x `H` pixels of a
+/// framebuffer or buffer with rows of `pitch` pixels
+pub struct Screen<'a, const W: u32, const H: u32> {
+    buffer: *mut u32,
+    /// At least `W`; `pitch * H` pixels are mapped
+    pitch: usize,
+    _memory: PhantomData<&'a mut [u32]>,
+}
+
+impl<'a, const W: u32, const H: u32> Screen<'a, W, H> {
+    /// The screen in the top-left corner of `fb`, which must be a 32bpp
+    /// framebuffer at least `W` x `H`
+    pub fn new(fb: &'a mut Framebuffer) -> Result<Self, DrawError> {
+        let info = *fb.info();
+        if info.depth != 32 || info.width < W || info.height < H {
+            return Err(DrawError::ScreenMismatch {
+                width: info.width,
+                height: info.height,
+                depth: info.depth,
+                screen_w: W,
+                screen_h: H,
+            });
+        }
+        Ok(Self { buffer: fb.buffer_ptr(), pitch: fb.pitch_pixels(), _memory: PhantomData })
+    }
+
+    /// The screen over `mem`, rows of `pitch` pixels; `mem` must hold `H`
+    /// of them
+    pub fn over(mem: &'a mut [u32], pitch: usize) -> Result<Self, DrawError> {
+        let rows = mem.len().checked_div(pitch).unwrap_or(0);
+        if pitch < W as usize || rows < H as usize {
+            return Err(DrawError::ScreenMismatch {
+                width: pitch.min(u32::MAX as usize) as u32,
+                height: rows.min(u32::MAX as usize) as u32,
+                depth: 32,
+                screen_w: W,
+                screen_h: H,
+            });
+        }
+        Ok(Self { buffer: mem.as_mut_ptr(), pitch, _memory: PhantomData })
+    }
+
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (W, H)
+    }
+
+    /// Pixels from the start of one row to the start of the next
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    /// All `pitch * H` pixels, for helpers that take a pitched buffer
+    pub fn pixels_mut(&mut self) -> &mut [u32] {
+        // Safety: checked to be mapped when the screen was made, and
+        // borrowed for as long as it lives
+        unsafe { core::slice::from_raw_parts_mut(self.buffer, self.pitch * H as usize) }
+    }
+
+    #[inline]
+    fn offset(&self, x: u32, y: u32) -> usize {
+        pixel_offset(x, y, self.pitch)
+    }
+
+    /// The pixel at `p`
+    #[inline]
+    pub fn get(&self, p: ScreenPoint<W, H>) -> u32 {
+        // Safety: on screen, so inside the checked extent
+        unsafe { self.buffer.add(self.offset(p.x, p.y)).read_volatile() }
+    }
+
+    /// Set the pixel at `p`
+    #[inline]
+    pub fn put(&mut self, p: ScreenPoint<W, H>, argb: u32) {
+        // Safety: on screen, so inside the checked extent
+        unsafe { self.buffer.add(self.offset(p.x, p.y)).write_volatile(argb) }
+    }
+
+    /// Fill `rect` with one color
+    pub fn fill(&mut self, rect: ScreenRect<W, H>, argb: u32) {
+        if rect.w == 0 {
+            return;
+        }
+        for y in rect.y..rect.y + rect.h {
+            let row = self.offset(rect.x, y);
+            for i in 0..rect.w as usize {
+                // Safety: the rectangle is on screen
+                unsafe { self.buffer.add(row + i).write_volatile(argb) }
+            }
+        }
+    }
+
+    /// Set every pixel to `color(x, y)`
+    pub fn fill_with(&mut self, mut color: impl FnMut(u32, u32) -> u32) {
+        for y in 0..H {
+            let row = self.offset(0, y);
+            for x in 0..W {
+                // Safety: on screen
+                unsafe { self.buffer.add(row + x as usize).write_volatile(color(x, y)) }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Small<'a> = Screen<'a, 4, 3>;
+
+    #[test]
+    fn screens_need_room_for_every_row() {
+        let mut mem = [0u32; 18];
+        assert!(Small::over(&mut mem, 6).is_ok());
+        assert_eq!(
+            Small::over(&mut mem, 3).err(),
+            Some(DrawError::ScreenMismatch { width: 3, height: 6, depth: 32, screen_w: 4, screen_h: 3 })
+        );
+        assert!(Small::over(&mut mem[..17], 6).is_err());
+        assert!(Small::over(&mut mem, 0).is_err());
+    }
+
+    #[test]
+    fn drawing_stays_inside_the_screen() {
+        // Rows of 6: the last two pixels of each are padding
+        let mut mem = [0u32; 18];
+        let mut screen = Small::over(&mut mem, 6).unwrap();
+        screen.fill(ScreenRect::clip(2, 1, 10, 10), 7);
+        screen.put(ScreenPoint::fixed::<0, 0>(), 1);
+        screen.put(ScreenPoint::new(3, 2).unwrap(), 2);
+        assert_eq!(screen.get(ScreenPoint::fixed::<3, 1>()), 7);
+        assert_eq!(mem, [1, 0, 0, 0, 0, 0, 0, 0, 7, 7, 0, 0, 0, 0, 7, 2, 0, 0]);
+
+        let mut screen = Small::over(&mut mem, 6).unwrap();
+        screen.fill_with(|x, y| y * 10 + x);
+        assert_eq!(&mem[6..12], &[10, 11, 12, 13, 0, 0]);
+    }
+
+    #[test]
+    fn rectangles_are_checked_once() {
+        type Rect43 = ScreenRect<4, 3>;
+        assert_eq!(Rect43::new(1, 1, 3, 2).map(|r| (r.width(), r.height())), Ok((3, 2)));
+        assert_eq!(
+            Rect43::new(u32::MAX, 0, 2, 1),
+            Err(DrawError::RectOutOfBounds { x: u32::MAX, y: 0, w: 2, h: 1, width: 4, height: 3 })
+        );
+        assert_eq!(Rect43::fixed::<0, 2, 4, 1>(), Rect43::new(0, 2, 4, 1).unwrap());
+        assert_eq!(Rect43::clip(9, 9, 1, 1).width(), 0);
+        assert_eq!(Rect43::FULL, Rect43::new(0, 0, 4, 3).unwrap());
+        assert_eq!(ScreenPoint::<4, 3>::new(4, 0), None);
+        assert!(rect_fits(0, 0, 4, 3, 4, 3));
+        assert!(!rect_fits(1, 0, u32::MAX, 3, 4, 3));
+        assert_eq!(pixel_offset(3, 2, 6), 15);
+    }
+}
@@ -0,0 +1,237 @@
+//! # Offscreen Surfaces
+//!
+//! A `W` x `H` ARGB8888 pixel buffer held inline, with no heap, so it can
+//! be a `static`. Draw on it through a [`Canvas`], which is a
+//! [`Framebuffer`] over the surface memory: every shape in
+//! [`graphics`](crate::graphics), every font and the framebuffer's own
+//! rectangles and blits work unchanged. Then copy it, or a region of it
+//! such as one frame of a sprite sheet, to the screen in a single pass,
+//! optionally skipping a color key so sprites keep their outline.
+//!
+//! Drawing a menu offscreen and blitting it means the screen never shows
+//! the half-drawn state in between.
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - `blit_overlap` only returns offsets that land inside both the surface
+//!   and the screen, so a sprite partly or wholly off either never reads or
+//!   writes out of bounds
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::font::TextTarget;
+use crate::framebuffer::Framebuffer;
+use crate::graphics::{Color, Rect};
+
+verus! {
+
+/// Offsets `lo..hi` of a run are inside both the source and the screen
+pub open spec fn overlap_ok(src: i32, dst: i32, len: u32, src_limit: u32, dst_limit: u32, lo: u32, hi: u32) -> bool {
+    lo < hi && hi <= len
+        && 0 <= src as int + lo as int && src as int + hi as int <= src_limit as int
+        && 0 <= dst as int + lo as int && dst as int + hi as int <= dst_limit as int
+}
+
+/// Offsets within a run of `len` pixels, starting at `src` in a source
+/// `src_limit` wide and at `dst` on a screen `dst_limit` wide, that fall
+/// inside both; `None` if none do
+pub fn blit_overlap(src: i32, dst: i32, len: u32, src_limit: u32, dst_limit: u32) -> (r: Option<(u32, u32)>)
+    ensures
+        r.is_some() ==> overlap_ok(src, dst, len, src_limit, dst_limit, r.unwrap().0, r.unwrap().1),
+{
+    let mut lo: i64 = 0;
+    if (src as i64) < 0 && -(src as i64) > lo {
+        lo = -(src as i64);
+    }
+    if (dst as i64) < 0 && -(dst as i64) > lo {
+        lo = -(dst as i64);
+    }
+    let mut hi: i64 = len as i64;
+    if (src_limit as i64) - (src as i64) < hi {
+        hi = (src_limit as i64) - (src as i64);
+    }
+    if (dst_limit as i64) - (dst as i64) < hi {
+        hi = (dst_limit as i64) - (dst as i64);
+    }
+    if lo >= hi {
+        return None;
+    }
+    Some((lo as u32, hi as u32))
+}
+
+} // verus!
+
+/// A `W` x `H` ARGB pixel buffer off screen
+pub struct OffscreenSurface<const W: usize, const H: usize> {
+    pixels: [[u32; W]; H],
+}
+
+impl<const W: usize, const H: usize> Default for OffscreenSurface<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> OffscreenSurface<W, H> {
+    /// Surface of transparent black pixels
+    pub const fn new() -> Self {
+        Self { pixels: [[0; W]; H] }
+    }
+
+    /// Surface dimensions
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (W as u32, H as u32)
+    }
+
+    /// Pixel at (x, y), or `None` off the surface
+    pub fn pixel(&self, x: u32, y: u32) -> Option<u32> {
+        self.pixels.get(y as usize)?.get(x as usize).copied()
+    }
+
+    /// The pixels, row-major with `W` per row
+    pub fn pixels(&self) -> &[u32] {
+        self.pixels.as_flattened()
+    }
+
+    /// The pixels, row-major with `W` per row, for renderers that write
+    /// whole images
+    pub fn pixels_mut(&mut self) -> &mut [u32] {
+        self.pixels.as_flattened_mut()
+    }
+
+    /// Draw on the surface with the framebuffer API
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas {
+            fb: Framebuffer::over(self.pixels.as_flattened_mut(), W as u32, H as u32),
+            _surface: PhantomData,
+        }
+    }
+
+    /// Copy the whole surface to (x, y) on `fb`, clipped to the screen.
+    /// Pixels equal to `key` are skipped, leaving the screen under them.
+    pub fn blit(&self, fb: &mut Framebuffer, x: i32, y: i32, key: Option<Color>) {
+        self.blit_region(fb, Rect::new(0, 0, W as u32, H as u32), x, y, key);
+    }
+
+    /// Copy the `src` part of the surface (a frame of a sprite sheet, say)
+    /// to (x, y) on `fb`, clipped to both. Pixels equal to `key` are
+    /// skipped, leaving the screen under them.
+    pub fn blit_region(&self, fb: &mut Framebuffer, src: Rect, x: i32, y: i32, key: Option<Color>) {
+        let (fb_width, fb_height) = fb.dimensions();
+        let Some((col0, col1)) = blit_overlap(src.x, x, src.width, W as u32, fb_width) else {
+            return;
+        };
+        let Some((row0, row1)) = blit_overlap(src.y, y, src.height, H as u32, fb_height) else {
+            return;
+        };
+        // Offsets are inside both, so these are in range and non-negative
+        let (sx0, sx1) = ((src.x + col0 as i32) as usize, (src.x + col1 as i32) as usize);
+        let dx = (x + col0 as i32) as u32;
+
+        for row in row0..row1 {
+            let line = &self.pixels[(src.y + row as i32) as usize][sx0..sx1];
+            let dy = (y + row as i32) as u32;
+            match key {
+                None => fb.blit(dx, dy, line.len() as u32, 1, line),
+                Some(key) => {
+                    let key = key.to_argb();
+                    for (i, &argb) in line.iter().enumerate() {
+                        if argb != key {
+                            fb.put_pixel(dx + i as u32, dy, Color::from_argb(argb));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Drawing access to an [`OffscreenSurface`]: a [`Framebuffer`] over its
+/// memory, borrowed for as long as the canvas lives
+pub struct Canvas<'a> {
+    fb: Framebuffer,
+    _surface: PhantomData<&'a mut [u32]>,
+}
+
+impl Deref for Canvas<'_> {
+    type Target = Framebuffer;
+
+    fn deref(&self) -> &Framebuffer {
+        &self.fb
+    }
+}
+
+impl DerefMut for Canvas<'_> {
+    fn deref_mut(&mut self) -> &mut Framebuffer {
+        &mut self.fb
+    }
+}
+
+impl TextTarget for Canvas<'_> {
+    fn plot(&mut self, x: i32, y: i32, color: Color) {
+        self.fb.plot(x, y, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::fill_circle;
+
+    const W: u32 = 12;
+    const H: u32 = 8;
+    const N: usize = (W * H) as usize;
+
+    #[test]
+    fn primitives_draw_offscreen_and_key_is_transparent() {
+        let mut sprite = OffscreenSurface::<5, 5>::new();
+        {
+            let mut canvas = sprite.canvas();
+            canvas.clear(Color::BLACK);
+            fill_circle(&mut canvas, 2, 2, 1, Color::WHITE);
+        }
+        assert_eq!(sprite.pixel(2, 2), Some(0xFFFF_FFFF));
+        assert_eq!(sprite.pixel(0, 0), Some(0xFF00_0000));
+        assert_eq!(sprite.pixel(5, 0), None);
+
+        let mut screen = [0xFF11_2233u32; N];
+        let mut fb = Framebuffer::over(&mut screen, W, H);
+        sprite.blit(&mut fb, 3, 2, Some(Color::BLACK));
+        // The circle lands; the black corners keep the screen under them
+        assert_eq!(screen[(4 * W + 5) as usize], 0xFFFF_FFFF);
+        assert_eq!(screen[(2 * W + 3) as usize], 0xFF11_2233);
+        assert_eq!(screen.iter().filter(|&&px| px == 0xFFFF_FFFF).count(), 5);
+    }
+
+    #[test]
+    fn blits_are_clipped_to_surface_and_screen() {
+        let mut sheet = OffscreenSurface::<4, 2>::new();
+        for (i, px) in sheet.pixels_mut().iter_mut().enumerate() {
+            *px = 0xFF00_0000 | i as u32;
+        }
+
+        // Second 2x2 frame of the sheet, hanging off the top-left corner
+        let mut screen = [0u32; N];
+        let mut fb = Framebuffer::over(&mut screen, W, H);
+        sheet.blit_region(&mut fb, Rect::new(2, 0, 2, 2), -1, -1, None);
+        // Hanging off the bottom-right, and a region reaching past the sheet
+        sheet.blit_region(&mut fb, Rect::new(2, 1, 9, 9), W as i32 - 1, H as i32 - 1, None);
+        // Wholly off screen
+        sheet.blit(&mut fb, -4, 0, None);
+        sheet.blit(&mut fb, W as i32, 0, None);
+
+        assert_eq!(screen[0], 0xFF00_0007);
+        assert_eq!(screen[N - 1], 0xFF00_0006);
+        assert_eq!(screen.iter().filter(|&&px| px != 0).count(), 2);
+
+        assert_eq!(blit_overlap(i32::MIN, i32::MAX, u32::MAX, u32::MAX, u32::MAX), None);
+        assert_eq!(blit_overlap(-3, 2, 10, 4, 5), None);
+        assert_eq!(blit_overlap(-3, 0, 10, 6, 5), Some((3, 5)));
+    }
+}
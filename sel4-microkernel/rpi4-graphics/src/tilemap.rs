@@ -0,0 +1,369 @@
+//! # Tile Maps
+//!
+//! Grid games (Tetris wells, Breakout brick walls, Snake boards) and
+//! scrolling screens are a map of small indices into a sheet of `T` x `T`
+//! tiles, drawn through a camera. A [`SpriteSheet<T>`](SpriteSheet) cuts a
+//! pitched pixel buffer (an [`OffscreenSurface`] drawn once at start-up,
+//! say) into tiles; a [`TileMap<T>`](TileMap) holds `cols` x `rows` tile
+//! indices, each checked against the sheet when it is stored. Rendering a
+//! map into a [`ScreenRect`] of a [`Screen`] then needs no per-pixel
+//! checks: the viewport is on screen by construction, every stored index
+//! names a tile in the sheet, and the camera can sit anywhere, including
+//! partly or wholly off the map.
+//!
+//! ```text
+//!   world (map pixels)           screen
+//!   ┌──────────────────┐         ┌──────────────┐
+//!   │   camera ┌─────┐ │  render │  ┌─────┐     │
+//!   │   (x, y) │     │ │ ──────► │  │view-│     │
+//!   │          └─────┘ │         │  │port │     │
+//!   └──────────────────┘         │  └─────┘     │
+//!                                └──────────────┘
+//! ```
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - `cell_index` of a cell on the map is below `cols * rows`
+//! - `tile_pixel` of a pixel inside a tile on the sheet leaves room for the
+//!   rest of that tile row, so a run copied from it stays inside the sheet
+//! - `world_to_tile` only returns a tile on the map and an offset inside it
+
+use crate::framebuffer::DrawError;
+use crate::screen::{pixel_offset, Screen, ScreenRect};
+use crate::surface::OffscreenSurface;
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+/// Map cell that draws nothing, leaving the screen under it
+pub const EMPTY_TILE: u16 = u16::MAX;
+
+verus! {
+
+/// Index of cell (col, row) in a row-major map `cols` wide (`_rows`
+/// only bounds the proof)
+pub fn cell_index(col: u32, row: u32, cols: u32, _rows: u32) -> (i: usize)
+    requires
+        col < cols,
+        row < _rows,
+        cols as int * _rows as int <= usize::MAX as int,
+    ensures
+        i == row as int * cols as int + col as int,
+        i < cols as int * _rows as int,
+{
+    proof {
+        assert(row as int * cols as int + cols as int <= cols as int * _rows as int) by (nonlinear_arith)
+            requires row < _rows;
+    }
+    row as usize * cols as usize + col as usize
+}
+
+/// Offset of pixel (tx, ty) of `tile` in a sheet of `columns` tiles per
+/// row, `size` pixels square, with rows of `pitch` pixels. The rest of
+/// that tile row, `size - tx` pixels, is inside the `_tile_rows` rows of
+/// tiles the sheet holds (`_tile_rows` only bounds the proof).
+pub fn tile_pixel(tile: u32, tx: u32, ty: u32, size: u32, columns: u32, _tile_rows: u32, pitch: usize) -> (o: usize)
+    requires
+        0 < columns,
+        tile < columns as int * _tile_rows as int,
+        tx < size,
+        ty < size,
+        columns as int * size as int <= pitch as int,
+        _tile_rows as int * size as int * pitch as int <= usize::MAX as int,
+    ensures
+        o as int + (size - tx) as int <= _tile_rows as int * size as int * pitch as int,
+{
+    let trow = tile / columns;
+    let tcol = tile % columns;
+    proof {
+        assert(trow < _tile_rows) by (nonlinear_arith)
+            requires trow == tile / columns, tile < columns as int * _tile_rows as int, 0 < columns;
+        assert(tcol < columns);
+        assert((trow as int * size as int + ty as int + 1) <= _tile_rows as int * size as int) by (nonlinear_arith)
+            requires trow < _tile_rows, ty < size;
+        assert(tcol as int * size as int + size as int <= pitch as int) by (nonlinear_arith)
+            requires tcol < columns, columns as int * size as int <= pitch as int;
+        assert((trow as int * size as int + ty as int) * pitch as int + pitch as int
+            <= _tile_rows as int * size as int * pitch as int) by (nonlinear_arith)
+            requires (trow as int * size as int + ty as int + 1) <= _tile_rows as int * size as int;
+    }
+    (trow as usize * size as usize + ty as usize) * pitch + tcol as usize * size as usize + tx as usize
+}
+
+/// The tile (below `limit`) holding world coordinate `world` on an axis of
+/// `size`-pixel tiles, and the offset into it; `None` off the map
+pub fn world_to_tile(world: i64, size: u32, limit: u32) -> (r: Option<(u32, u32)>)
+    requires
+        0 < size,
+    ensures
+        r.is_some() ==> r.unwrap().0 < limit && r.unwrap().1 < size,
+{
+    if world < 0 || world >= limit as i64 * size as i64 {
+        return None;
+    }
+    let tile = world / size as i64;
+    let offset = world % size as i64;
+    proof {
+        assert(tile < limit) by (nonlinear_arith)
+            requires tile == world / size as i64, world < limit as i64 * size as i64, 0 <= world, 0 < size;
+    }
+    Some((tile as u32, offset as u32))
+}
+
+} // verus!
+
+/// A sheet of `T` x `T` tiles, numbered left to right, top to bottom
+#[derive(Clone, Copy)]
+pub struct SpriteSheet<'a, const T: u32> {
+    pixels: &'a [u32],
+    /// Pixels from the start of one row to the start of the next
+    pitch: usize,
+    /// Tiles per row of the sheet
+    columns: u32,
+    /// Rows of tiles
+    tile_rows: u32,
+}
+
+impl<'a, const T: u32> SpriteSheet<'a, T> {
+    /// The tiles in `pixels`, rows of `pitch` pixels. Partial tiles at the
+    /// right and bottom edges are left out.
+    pub fn new(pixels: &'a [u32], pitch: usize) -> Result<Self, DrawError> {
+        const { assert!(T > 0, "tiles must be at least one pixel") };
+        let columns = (pitch / T as usize).min(u32::MAX as usize) as u32;
+        let rows = pixels.len().checked_div(pitch).unwrap_or(0);
+        let tile_rows = (rows / T as usize).min(u32::MAX as usize) as u32;
+        if columns == 0 || tile_rows == 0 {
+            return Err(DrawError::SourceTooShort { needed: T as usize * T as usize, len: pixels.len() });
+        }
+        Ok(Self { pixels, pitch, columns, tile_rows })
+    }
+
+    /// The tiles on an offscreen surface
+    pub fn from_surface<const W: usize, const H: usize>(
+        surface: &'a OffscreenSurface<W, H>,
+    ) -> Result<Self, DrawError> {
+        Self::new(surface.pixels(), W)
+    }
+
+    /// Number of whole tiles on the sheet
+    pub fn tile_count(&self) -> u32 {
+        self.columns.saturating_mul(self.tile_rows)
+    }
+
+    /// Row `ty` of `tile`, from pixel `tx` to the tile's right edge
+    #[inline]
+    fn run(&self, tile: u16, tx: u32, ty: u32) -> &[u32] {
+        let start = tile_pixel(tile as u32, tx, ty, T, self.columns, self.tile_rows, self.pitch);
+        &self.pixels[start..start + (T - tx) as usize]
+    }
+}
+
+/// A `cols` x `rows` grid of tile indices over caller-owned cells
+pub struct TileMap<'a, const T: u32> {
+    /// Row-major; each is [`EMPTY_TILE`] or below `tile_count`
+    cells: &'a mut [u16],
+    cols: u32,
+    rows: u32,
+    /// Tiles the sheet this map was checked against holds
+    tile_count: u32,
+}
+
+impl<'a, const T: u32> TileMap<'a, T> {
+    /// A map over `cells`, which must hold exactly `cols` x `rows` indices,
+    /// each [`EMPTY_TILE`] or a tile on `sheet`
+    pub fn new(cells: &'a mut [u16], cols: u32, rows: u32, sheet: &SpriteSheet<'_, T>) -> Result<Self, DrawError> {
+        if cols as u64 * rows as u64 != cells.len() as u64 {
+            return Err(DrawError::MapSizeMismatch { len: cells.len(), cols, rows });
+        }
+        let tile_count = sheet.tile_count();
+        if let Some(&tile) = cells.iter().find(|&&c| c != EMPTY_TILE && c as u32 >= tile_count) {
+            return Err(DrawError::TileOutOfRange { tile, count: tile_count });
+        }
+        Ok(Self { cells, cols, rows, tile_count })
+    }
+
+    /// Map size in tiles
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.cols, self.rows)
+    }
+
+    /// Map size in world pixels
+    pub fn world_size(&self) -> (u64, u64) {
+        (self.cols as u64 * T as u64, self.rows as u64 * T as u64)
+    }
+
+    /// Tile at (col, row), or `None` off the map
+    pub fn get(&self, col: u32, row: u32) -> Option<u16> {
+        if col < self.cols && row < self.rows {
+            Some(self.cells[cell_index(col, row, self.cols, self.rows)])
+        } else {
+            None
+        }
+    }
+
+    /// Store `tile` at (col, row); refused off the map or past the sheet
+    pub fn set(&mut self, col: u32, row: u32, tile: u16) -> Result<(), DrawError> {
+        if col >= self.cols || row >= self.rows {
+            return Err(DrawError::PixelOutOfBounds { x: col, y: row, width: self.cols, height: self.rows });
+        }
+        if tile != EMPTY_TILE && tile as u32 >= self.tile_count {
+            return Err(DrawError::TileOutOfRange { tile, count: self.tile_count });
+        }
+        self.cells[cell_index(col, row, self.cols, self.rows)] = tile;
+        Ok(())
+    }
+
+    /// Set every cell to `tile` (`EMPTY_TILE` clears the map)
+    pub fn fill(&mut self, tile: u16) -> Result<(), DrawError> {
+        if tile != EMPTY_TILE && tile as u32 >= self.tile_count {
+            return Err(DrawError::TileOutOfRange { tile, count: self.tile_count });
+        }
+        self.cells.fill(tile);
+        Ok(())
+    }
+
+    /// Draw the part of the map the camera at world pixel (cam_x, cam_y)
+    /// sees into `viewport`. Screen pixels over empty cells, off the map or
+    /// equal to `key` in the sheet are left as they were.
+    ///
+    /// `sheet` must hold at least the tiles this map was checked against.
+    pub fn render<const W: u32, const H: u32>(
+        &self,
+        sheet: &SpriteSheet<'_, T>,
+        screen: &mut Screen<'_, W, H>,
+        viewport: ScreenRect<W, H>,
+        cam_x: i32,
+        cam_y: i32,
+        key: Option<u32>,
+    ) -> Result<(), DrawError> {
+        if sheet.tile_count() < self.tile_count {
+            return Err(DrawError::TileOutOfRange {
+                tile: self.tile_count.saturating_sub(1).min(u16::MAX as u32) as u16,
+                count: sheet.tile_count(),
+            });
+        }
+        let pitch = screen.pitch();
+        let pixels = screen.pixels_mut();
+        let (vw, vh) = (viewport.width(), viewport.height());
+
+        for sy in 0..vh {
+            let Some((row, ty)) = world_to_tile(cam_y as i64 + sy as i64, T, self.rows) else {
+                continue;
+            };
+            let line = pixel_offset(viewport.x(), viewport.y() + sy, pitch);
+            let mut sx = 0u32;
+            while sx < vw {
+                let world_x = cam_x as i64 + sx as i64;
+                let Some((col, tx)) = world_to_tile(world_x, T, self.cols) else {
+                    if world_x < 0 {
+                        // Skip to the map's left edge
+                        sx += (-world_x).min((vw - sx) as i64) as u32;
+                        continue;
+                    }
+                    break;
+                };
+                let len = (T - tx).min(vw - sx);
+                let tile = self.cells[cell_index(col, row, self.cols, self.rows)];
+                if tile != EMPTY_TILE {
+                    let src = &sheet.run(tile, tx, ty)[..len as usize];
+                    let dst = &mut pixels[line + sx as usize..line + (sx + len) as usize];
+                    match key {
+                        None => dst.copy_from_slice(src),
+                        Some(key) => {
+                            for (d, &s) in dst.iter_mut().zip(src) {
+                                if s != key {
+                                    *d = s;
+                                }
+                            }
+                        }
+                    }
+                }
+                sx += len;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Four 2x2 tiles in a 4x4 sheet, tile n filled with n + 1
+    fn sheet_pixels() -> [u32; 16] {
+        [
+            1, 1, 2, 2, //
+            1, 1, 2, 2, //
+            3, 3, 4, 4, //
+            3, 3, 4, 4,
+        ]
+    }
+
+    #[test]
+    fn maps_only_hold_tiles_on_the_sheet() {
+        let px = sheet_pixels();
+        let sheet = SpriteSheet::<2>::new(&px, 4).unwrap();
+        assert_eq!(sheet.tile_count(), 4);
+
+        let mut cells = [0, 1, 2, EMPTY_TILE, 4, 0];
+        assert_eq!(
+            TileMap::new(&mut cells, 3, 2, &sheet).err(),
+            Some(DrawError::TileOutOfRange { tile: 4, count: 4 })
+        );
+        cells[4] = 3;
+        assert_eq!(
+            TileMap::new(&mut cells, 2, 2, &sheet).err(),
+            Some(DrawError::MapSizeMismatch { len: 6, cols: 2, rows: 2 })
+        );
+        let mut map = TileMap::new(&mut cells, 3, 2, &sheet).unwrap();
+        assert_eq!(map.get(1, 1), Some(3));
+        assert_eq!(map.get(3, 0), None);
+        assert!(map.set(0, 0, 9).is_err());
+        assert!(map.set(0, 2, 1).is_err());
+        assert_eq!(map.set(2, 1, EMPTY_TILE), Ok(()));
+        assert_eq!(map.get(2, 1), Some(EMPTY_TILE));
+        assert!(SpriteSheet::<2>::new(&px[..7], 4).is_err());
+    }
+
+    #[test]
+    fn camera_scrolls_and_clips_the_map() {
+        let px = sheet_pixels();
+        let sheet = SpriteSheet::<2>::new(&px, 4).unwrap();
+        let mut cells = [0, 1, 2, 3];
+        let map = TileMap::new(&mut cells, 2, 2, &sheet).unwrap();
+
+        // Rows of 6: the viewport is the middle 4 pixels of the top 3 rows
+        let mut mem = [0u32; 6 * 4];
+        let mut screen = Screen::<6, 4>::over(&mut mem, 6).unwrap();
+        let view = ScreenRect::new(1, 0, 4, 3).unwrap();
+        map.render(&sheet, &mut screen, view, -1, 1, None).unwrap();
+        assert_eq!(
+            mem,
+            [
+                0, 0, 1, 1, 2, 0, //
+                0, 0, 3, 3, 4, 0, //
+                0, 0, 3, 3, 4, 0, //
+                0, 0, 0, 0, 0, 0,
+            ]
+        );
+
+        // Wholly off the map draws nothing; keyed pixels are skipped
+        let mut mem = [9u32; 6 * 4];
+        let mut screen = Screen::<6, 4>::over(&mut mem, 6).unwrap();
+        map.render(&sheet, &mut screen, view, 100, -100, None).unwrap();
+        map.render(&sheet, &mut screen, ScreenRect::FULL, 0, 0, Some(4)).unwrap();
+        assert_eq!(&mem[..6], &[1, 1, 2, 2, 9, 9]);
+        assert_eq!(&mem[18..], &[3, 3, 9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn index_helpers_stay_in_bounds() {
+        assert_eq!(cell_index(2, 1, 3, 2), 5);
+        assert_eq!(tile_pixel(3, 1, 1, 2, 2, 2, 4), 15);
+        assert_eq!(world_to_tile(5, 2, 3), Some((2, 1)));
+        assert_eq!(world_to_tile(6, 2, 3), None);
+        assert_eq!(world_to_tile(-1, 2, 3), None);
+    }
+}
@@ -0,0 +1,312 @@
+//! # Time
+//!
+//! [`Instant`] and [`Duration`] over the BCM2711 system timer, for
+//! deadlines that do not depend on how often a PD is woken. Both count
+//! microseconds in a `u64`: the counter starts at power-on and would take
+//! half a million years to wrap, so an instant is read from the full 64-bit
+//! counter ([`read_counter`]) and compared directly, with none of the
+//! wrap-around care the 32-bit compare registers need.
+//!
+//! Arithmetic that could leave the `u64` range is checked (`checked_*`,
+//! returning `None`) or clamps (`saturating_*`); nothing here panics or
+//! wraps. A PD that wants a periodic wake-up takes the Timer PD's tick
+//! notification, or runs a [`FrameTimer`](crate::FrameTimer) itself, and
+//! reads [`Instant::now`] when it is woken to see which of its deadlines
+//! have passed:
+//!
+//! ```text
+//! let due = Instant::now(base).checked_add(Duration::from_secs(5));
+//! ...
+//! if due.is_some_and(|due| Instant::now(base).has_reached(due)) { ... }
+//! ```
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - The checked operations return `None` exactly when the true result is
+//!   outside the `u64` range, and the true result otherwise
+//! - The saturating operations return the true result clamped to the range
+//! - Durations built from `u32` milliseconds or seconds cannot overflow
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::frame_timer::read_counter;
+
+verus! {
+
+/// Microseconds in a millisecond
+pub const MICROS_PER_MILLI: u64 = 1_000;
+
+/// Microseconds in a second
+pub const MICROS_PER_SEC: u64 = 1_000_000;
+
+/// `a + b`, or `None` past `u64::MAX`
+pub fn checked_add_us(a: u64, b: u64) -> (r: Option<u64>)
+    ensures
+        r.is_some() <==> a as int + b as int <= u64::MAX as int,
+        r.is_some() ==> r.unwrap() as int == a as int + b as int,
+{
+    if a > u64::MAX - b {
+        None
+    } else {
+        Some(a + b)
+    }
+}
+
+/// `a - b`, or `None` below zero
+pub fn checked_sub_us(a: u64, b: u64) -> (r: Option<u64>)
+    ensures
+        r.is_some() <==> b <= a,
+        r.is_some() ==> r.unwrap() as int == a as int - b as int,
+{
+    if b > a {
+        None
+    } else {
+        Some(a - b)
+    }
+}
+
+/// `a * n`, or `None` past `u64::MAX`
+pub fn checked_mul_us(a: u64, n: u32) -> (r: Option<u64>)
+    ensures
+        r.is_some() <==> a as int * n as int <= u64::MAX as int,
+        r.is_some() ==> r.unwrap() as int == a as int * n as int,
+{
+    if n == 0 {
+        return Some(0);
+    }
+    if a > u64::MAX / (n as u64) {
+        proof {
+            assert(a as int * n as int > u64::MAX as int) by (nonlinear_arith)
+                requires a as int > u64::MAX as int / n as int, n as int > 0;
+        }
+        None
+    } else {
+        proof {
+            assert(a as int * n as int <= u64::MAX as int) by (nonlinear_arith)
+                requires a as int <= u64::MAX as int / n as int, n as int > 0;
+        }
+        Some(a * n as u64)
+    }
+}
+
+/// `a + b`, clamped to `u64::MAX`
+pub fn saturating_add_us(a: u64, b: u64) -> (r: u64)
+    ensures
+        a as int + b as int <= u64::MAX as int ==> r as int == a as int + b as int,
+        a as int + b as int > u64::MAX as int ==> r == u64::MAX,
+{
+    if a > u64::MAX - b {
+        u64::MAX
+    } else {
+        a + b
+    }
+}
+
+/// `a - b`, clamped to zero
+// Spelled out so the proof sees the branch
+#[allow(clippy::implicit_saturating_sub)]
+pub fn saturating_sub_us(a: u64, b: u64) -> (r: u64)
+    ensures
+        b <= a ==> r as int == a as int - b as int,
+        b > a ==> r == 0,
+{
+    if b > a {
+        0
+    } else {
+        a - b
+    }
+}
+
+/// `ms` milliseconds in microseconds; a `u32` of them always fits
+pub fn millis_to_us(ms: u32) -> (r: u64)
+    ensures
+        r as int == ms as int * 1000,
+{
+    ms as u64 * MICROS_PER_MILLI
+}
+
+/// `s` seconds in microseconds; a `u32` of them always fits
+pub fn secs_to_us(s: u32) -> (r: u64)
+    ensures
+        r as int == s as int * 1_000_000,
+{
+    s as u64 * MICROS_PER_SEC
+}
+
+} // verus!
+
+/// A span of time, in microseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    micros: u64,
+}
+
+impl Duration {
+    pub const ZERO: Self = Self { micros: 0 };
+    pub const MAX: Self = Self { micros: u64::MAX };
+
+    pub const fn from_micros(micros: u64) -> Self {
+        Self { micros }
+    }
+
+    pub fn from_millis(ms: u32) -> Self {
+        Self { micros: millis_to_us(ms) }
+    }
+
+    pub fn from_secs(s: u32) -> Self {
+        Self { micros: secs_to_us(s) }
+    }
+
+    pub const fn as_micros(self) -> u64 {
+        self.micros
+    }
+
+    /// Whole milliseconds, rounded down
+    pub const fn as_millis(self) -> u64 {
+        self.micros / MICROS_PER_MILLI
+    }
+
+    /// Whole seconds, rounded down
+    pub const fn as_secs(self) -> u64 {
+        self.micros / MICROS_PER_SEC
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.micros == 0
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        checked_add_us(self.micros, other.micros).map(Self::from_micros)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        checked_sub_us(self.micros, other.micros).map(Self::from_micros)
+    }
+
+    pub fn checked_mul(self, n: u32) -> Option<Self> {
+        checked_mul_us(self.micros, n).map(Self::from_micros)
+    }
+
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self::from_micros(saturating_add_us(self.micros, other.micros))
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self::from_micros(saturating_sub_us(self.micros, other.micros))
+    }
+
+    /// How many whole `period`s fit in this span (`None` for a zero period)
+    pub fn periods(self, period: Self) -> Option<u64> {
+        self.micros.checked_div(period.micros)
+    }
+}
+
+/// A reading of the system timer: microseconds since it started
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant {
+    micros: u64,
+}
+
+impl Instant {
+    /// When the timer started (power-on)
+    pub const BOOT: Self = Self { micros: 0 };
+
+    pub const fn from_micros(micros: u64) -> Self {
+        Self { micros }
+    }
+
+    /// The current reading of the system timer
+    ///
+    /// # Safety
+    /// `base` must be the mapped system timer register page (read-only is
+    /// enough).
+    pub unsafe fn now(base: usize) -> Self {
+        Self { micros: read_counter(base) }
+    }
+
+    pub const fn as_micros(self) -> u64 {
+        self.micros
+    }
+
+    /// Time since boot
+    pub const fn since_boot(self) -> Duration {
+        Duration::from_micros(self.micros)
+    }
+
+    /// `duration` after this instant, or `None` past the end of time
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        checked_add_us(self.micros, duration.micros).map(Self::from_micros)
+    }
+
+    /// `duration` before this instant, or `None` before boot
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        checked_sub_us(self.micros, duration.micros).map(Self::from_micros)
+    }
+
+    /// `duration` after this instant, clamped to the end of time
+    pub fn saturating_add(self, duration: Duration) -> Self {
+        Self::from_micros(saturating_add_us(self.micros, duration.micros))
+    }
+
+    /// Time from `earlier` to this instant, or `None` if `earlier` is later
+    pub fn checked_duration_since(self, earlier: Self) -> Option<Duration> {
+        checked_sub_us(self.micros, earlier.micros).map(Duration::from_micros)
+    }
+
+    /// Time from `earlier` to this instant, zero if `earlier` is later
+    pub fn saturating_duration_since(self, earlier: Self) -> Duration {
+        Duration::from_micros(saturating_sub_us(self.micros, earlier.micros))
+    }
+
+    /// Is this instant at or past `deadline`?
+    pub fn has_reached(self, deadline: Self) -> bool {
+        self >= deadline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_arithmetic_refuses_overflow() {
+        assert_eq!(checked_add_us(u64::MAX - 1, 1), Some(u64::MAX));
+        assert_eq!(checked_add_us(u64::MAX, 1), None);
+        assert_eq!(checked_sub_us(5, 5), Some(0));
+        assert_eq!(checked_sub_us(5, 6), None);
+        assert_eq!(checked_mul_us(u64::MAX / 3, 3), Some(u64::MAX / 3 * 3));
+        assert_eq!(checked_mul_us(u64::MAX / 3 + 1, 3), None);
+        assert_eq!(checked_mul_us(u64::MAX, 0), Some(0));
+        assert_eq!(saturating_add_us(u64::MAX, 7), u64::MAX);
+        assert_eq!(saturating_sub_us(3, 7), 0);
+    }
+
+    #[test]
+    fn durations_convert_units() {
+        assert_eq!(Duration::from_millis(1500).as_micros(), 1_500_000);
+        assert_eq!(Duration::from_secs(u32::MAX).as_secs(), u32::MAX as u64);
+        assert_eq!(Duration::from_micros(1999).as_millis(), 1);
+        assert_eq!(Duration::from_secs(5).periods(Duration::from_millis(100)), Some(50));
+        assert_eq!(Duration::from_secs(5).periods(Duration::ZERO), None);
+        assert_eq!(Duration::from_secs(2).checked_mul(3), Some(Duration::from_secs(6)));
+        assert_eq!(Duration::MAX.checked_add(Duration::from_micros(1)), None);
+        assert_eq!(Duration::from_millis(1).checked_sub(Duration::from_millis(2)), None);
+    }
+
+    #[test]
+    fn deadlines() {
+        let start = Instant::from_micros(1_000);
+        let due = start.checked_add(Duration::from_millis(100)).unwrap();
+        assert!(!Instant::from_micros(100_999).has_reached(due));
+        assert!(Instant::from_micros(101_000).has_reached(due));
+        assert_eq!(due.checked_duration_since(start), Some(Duration::from_millis(100)));
+        assert_eq!(start.checked_duration_since(due), None);
+        assert_eq!(start.saturating_duration_since(due), Duration::ZERO);
+        assert_eq!(Instant::from_micros(u64::MAX).checked_add(Duration::from_micros(1)), None);
+        assert_eq!(Instant::BOOT.checked_sub(Duration::from_micros(1)), None);
+        assert_eq!(Instant::from_micros(u64::MAX - 1).saturating_add(Duration::MAX).as_micros(), u64::MAX);
+    }
+}
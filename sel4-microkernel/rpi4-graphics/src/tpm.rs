@@ -18,6 +18,8 @@
 //! - ST33KTPM2I3WBZA9 datasheet
 //! - TCG TPM 2.0 Library Specification
 
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
 /// TPM TIS (TPM Interface Specification) register offsets
 pub mod regs {
     pub const TPM_ACCESS: usize = 0x0000;
@@ -95,6 +97,20 @@ pub enum TpmError {
     SelfTestFailed,
 }
 
+impl From<TpmError> for PdError {
+    fn from(e: TpmError) -> Self {
+        let (kind, detail) = match e {
+            TpmError::NotReady => (ErrorKind::NotReady, 0),
+            TpmError::Timeout => (ErrorKind::Timeout, 0),
+            TpmError::InvalidResponse => (ErrorKind::InvalidResponse, 0),
+            // Keep the low bits of the TPM response code for the peer
+            TpmError::CommandFailed(rc) => (ErrorKind::Failed, rc as u16),
+            TpmError::SpiError | TpmError::SelfTestFailed => (ErrorKind::Hardware, 0),
+        };
+        PdError::new(ErrorDomain::Tpm, kind).with_detail(detail)
+    }
+}
+
 /// SPI interface for TPM communication
 pub trait SpiInterface {
     /// Transfer data via SPI (full duplex)
@@ -27,7 +27,9 @@
 
 use sel4_microkit::{debug_println, protection_domain, Handler, Infallible, ChannelSet};
 
-use rpi4_graphics::{Mailbox, Framebuffer, MAILBOX_BASE};
+use rpi4_graphics::{Mailbox, Framebuffer, MAILBOX_BASE, FIXED_720P_LADDER};
+use rpi4_graphics::mailbox::clock;
+use verified_microkernel::{spi_divider, SPI_DEFAULT_CORE_HZ};
 
 /// Mailbox virtual address (page base 0x5_0000_0000 + offset 0x880)
 const MAILBOX_VADDR: usize = 0x5_0000_0000 + 0x880;
@@ -40,6 +42,9 @@ const HEIGHT: u32 = 720;
 const GPIO_BASE: usize = 0x5_0200_0000;
 const SPI_BASE: usize = 0x5_0400_0000;  // SPI0 for TPM
 
+/// SPI clock for the device ID probe: slow enough for any wiring
+const PROBE_SPI_HZ: u32 = 100_000;
+
 /// UART5 (PL011) for debug output - page base + 0xa00 offset
 const UART5_BASE: usize = 0x5_0500_0000 + 0xa00;
 
@@ -253,6 +258,8 @@ impl TestResult {
 /// TPM test state
 struct TpmTest {
     renderer: TextRenderer,
+    /// Core clock SPI0 divides from, as the mailbox reported it
+    core_hz: u32,
     spi_detected: TestResult,
     tpm_vendor_id: u16,
     tpm_device_id: u16,
@@ -264,9 +271,10 @@ struct TpmTest {
 }
 
 impl TpmTest {
-    fn new(fb_ptr: *mut u32, pitch: usize) -> Self {
+    fn new(fb_ptr: *mut u32, pitch: usize, core_hz: u32) -> Self {
         Self {
             renderer: TextRenderer::new(fb_ptr, pitch),
+            core_hz,
             spi_detected: TestResult::Pending,
             tpm_vendor_id: 0,
             tpm_device_id: 0,
@@ -459,8 +467,8 @@ impl TpmTest {
 
             uart5_puts("  Configuring SPI (very slow)...\n");
 
-            // Very slow clock: 250MHz / 2500 = 100kHz
-            spi_clk.write_volatile(2500);
+            // Very slow clock, whatever the core clock is running at
+            spi_clk.write_volatile(spi_divider(self.core_hz, PROBE_SPI_HZ));
 
             // Read current CS register
             let cs_val = spi_cs.read_volatile();
@@ -630,8 +638,8 @@ impl Handler for TpmTestHandler {
 fn init_framebuffer() -> Option<Framebuffer> {
     let mailbox = unsafe { Mailbox::new(MAILBOX_VADDR) };
 
-    match unsafe { Framebuffer::new(&mailbox, WIDTH, HEIGHT) } {
-        Ok(fb) => Some(fb),
+    match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok((fb, _)) => Some(fb),
         Err(_) => None,
     }
 }
@@ -741,16 +749,21 @@ fn init() -> impl Handler {
     // Initialize framebuffer
     let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
 
-    match unsafe { Framebuffer::new(&mailbox, WIDTH, HEIGHT) } {
-        Ok(fb) => {
+    match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok((fb, selection)) => {
             uart5_puts("Framebuffer OK\n");
+            if selection.is_fallback() {
+                uart5_puts("(720p refused, using 1080p)\n");
+            }
             debug_println!("Framebuffer OK");
             let ptr = fb.buffer_ptr();
             let pitch = fb.pitch_pixels();
 
             // Run TPM tests with screen and serial output
             uart5_puts("Running TPM tests...\n\n");
-            let mut test = TpmTest::new(ptr, pitch);
+            let core_hz = mailbox.get_clock_rate(clock::CORE).unwrap_or(SPI_DEFAULT_CORE_HZ);
+            debug_println!("Core clock: {} Hz", core_hz);
+            let mut test = TpmTest::new(ptr, pitch, core_hz);
             test.run_tests();
 
             // Print results to serial
@@ -5,19 +5,27 @@
 //! - Snake Game (interactive)
 //! - Snake Screensaver (automatic)
 //! - Settings and About screens
+//! - Input diagnostics (live per-source counters and raw codes)
 //!
 //! The GPU dynamically allocates the framebuffer via VideoCore mailbox.
+//! Frames are paced by the firmware's vsync interrupt (SMI IRQ channel);
+//! if the firmware refuses to provide it, by a system timer tick instead.
+//! Either way the PD sleeps in the Microkit event loop between frames.
 
 #![no_std]
 #![no_main]
 
 extern crate alloc;
 
-use sel4_microkit::{debug_println, protection_domain, Handler, ChannelSet};
-use core::fmt;
+use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 use linked_list_allocator::LockedHeap;
 
-use rpi4_graphics::{Mailbox, Framebuffer, MAILBOX_BASE};
+use rpi4_graphics::{
+    font, Color, FrameClock, FrameTimer, Mailbox, Framebuffer, OffscreenSurface, Vsync, MAILBOX_BASE,
+    SMI_VIRT_BASE, SYSTIMER_VIRT_BASE, FIXED_720P_LADDER,
+};
+use rpi4_graphics::vsync::REFRESH_HZ;
 
 // Global allocator for alloc-dependent code
 #[global_allocator]
@@ -26,11 +34,8 @@ static ALLOCATOR: LockedHeap = LockedHeap::empty();
 // 64KB heap
 const HEAP_SIZE: usize = 64 * 1024;
 static mut HEAP: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
-use rpi4_input::{InputManager, RemoteOptions, InputEvent, KeyCode, KeyState};
-
-/// Screen dimensions
-const WIDTH: u32 = 1280;
-const HEIGHT: u32 = 720;
+use rpi4_input::{InputManager, InputStats, InputTiming, RemoteOptions, InputEvent, KeyCode, KeyState};
+use verified_microkernel::{abs_diff_u32, wrap_i32};
 
 /// GPIO virtual address
 const GPIO_BASE: usize = 0x5_0200_0000;
@@ -49,18 +54,53 @@ enum AppState {
     Screensaver,
     /// About screen
     About,
+    /// Live input statistics and raw codes
+    InputDiagnostics,
 }
 
 /// Menu item indices
 const MENU_SNAKE_GAME: usize = 0;
 const MENU_SCREENSAVER: usize = 1;
 const MENU_ABOUT: usize = 2;
-const MENU_ITEM_COUNT: usize = 3;
+const MENU_INPUT_DIAG: usize = 3;
+const MENU_ITEM_COUNT: usize = 4;
+
+/// Screen area the menu occupies
+const MENU_LEFT: usize = 200;
+const MENU_TOP: usize = 200;
+const MENU_W: usize = 880;
+const MENU_H: usize = 380;
+
+/// The menu is drawn here and blitted in one pass, so moving the selection
+/// never shows a cleared or half-drawn menu (1.3 MB of BSS)
+static mut MENU_SURFACE: OffscreenSurface<MENU_W, MENU_H> = OffscreenSurface::new();
+
+/// Vsync IRQ channel id (must match tvdemo.system)
+const VSYNC_IRQ_CHANNEL_ID: usize = 1;
+const VSYNC_CHANNEL: Channel = Channel::new(VSYNC_IRQ_CHANNEL_ID);
+
+/// Frame timer IRQ channel id (must match tvdemo.system)
+const TIMER_IRQ_CHANNEL_ID: usize = 2;
+const TIMER_CHANNEL: Channel = Channel::new(TIMER_IRQ_CHANNEL_ID);
+
+/// What paces `TvApp::step`
+enum FramePacer {
+    /// Vertical blanks; the app steps every `VSYNC_FRAMES_PER_STEP`
+    Vsync(FrameClock),
+    /// System timer ticking once per step, when vsync is unavailable
+    Timer(FrameTimer),
+}
 
-struct TvDemoHandler;
+/// Event-loop state: the app is stepped from vsync or timer notifications
+struct TvDemoHandler {
+    app: TvApp,
+    pacer: FramePacer,
+}
 
 impl TvDemoHandler {
-    const fn new() -> Self { Self }
+    fn new(app: TvApp, pacer: FramePacer) -> Self {
+        Self { app, pacer }
+    }
 }
 
 /// Draw a filled block using direct writes for animation performance
@@ -108,24 +148,24 @@ fn init_framebuffer() -> Option<Framebuffer> {
     let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
 
     // Query board info
-    let mut buf = [0u32; 36];
-    match mailbox.get_firmware_revision(&mut buf) {
+    match mailbox.get_firmware_revision() {
         Ok(rev) => debug_println!("Firmware revision: 0x{:08x}", rev),
         Err(_) => debug_println!("Failed to get firmware revision"),
     }
 
-    match mailbox.get_board_model(&mut buf) {
+    match mailbox.get_board_model() {
         Ok(model) => debug_println!("Board model: 0x{:08x}", model),
         Err(_) => debug_println!("Failed to get board model"),
     }
 
-    // Allocate framebuffer
-    match unsafe { Framebuffer::new(&mailbox, WIDTH, HEIGHT) } {
-        Ok(fb) => {
+    // Allocate framebuffer (falls back to 1080p if the display refuses 720p)
+    match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok((fb, selection)) => {
             let info = fb.info();
             debug_println!(
-                "Framebuffer allocated: {}x{} @ phys 0x{:08x}, pitch={}",
-                info.width, info.height, info.base, info.pitch
+                "Framebuffer allocated: {}x{} @ phys 0x{:08x}, pitch={}{}",
+                info.width, info.height, info.base, info.pitch,
+                if selection.is_fallback() { " (fallback)" } else { "" }
             );
             Some(fb)
         }
@@ -218,10 +258,8 @@ impl Snake {
         }
 
         // Wrap around within play area
-        if new_x < PLAY_AREA_LEFT { new_x = PLAY_AREA_RIGHT - 1; }
-        if new_x >= PLAY_AREA_RIGHT { new_x = PLAY_AREA_LEFT; }
-        if new_y < PLAY_AREA_TOP { new_y = PLAY_AREA_BOTTOM - 1; }
-        if new_y >= PLAY_AREA_BOTTOM { new_y = PLAY_AREA_TOP; }
+        new_x = wrap_i32(new_x, PLAY_AREA_LEFT, PLAY_AREA_RIGHT);
+        new_y = wrap_i32(new_y, PLAY_AREA_TOP, PLAY_AREA_BOTTOM);
 
         for i in (1..self.length).rev() {
             self.segments[i] = self.segments[i - 1];
@@ -236,7 +274,7 @@ fn hsv_to_rgb(h: u16, s: u8, v: u8) -> u32 {
     let s = s as u32;
     let v = v as u32;
     let c = (v * s) / 255;
-    let x = (c * (60 - ((h % 120) as i32 - 60).unsigned_abs() as u32)) / 60;
+    let x = (c * (60 - abs_diff_u32((h % 120) as u32, 60))) / 60;
     let m = v - c;
     let (r, g, b) = match h / 60 {
         0 => (c, x, 0),
@@ -336,48 +374,30 @@ unsafe fn draw_static_elements(ptr: *mut u32, pitch: usize, width: usize, height
     }
 }
 
-/// Draw the main menu
-unsafe fn draw_menu(ptr: *mut u32, pitch: usize, width: usize, height: usize, selected: usize) {
-    let bg_color: u32 = 0xFF101030;
+/// Draw the main menu onto `menu`, whose origin is (MENU_LEFT, MENU_TOP)
+unsafe fn draw_menu(menu: &mut OffscreenSurface<MENU_W, MENU_H>, selected: usize) {
     let white: u32 = 0xFFFFFFFF;
     let green: u32 = 0xFF00B050;
     let gray: u32 = 0xFF808080;
 
-    // Clear menu area
-    let menu_top = 200usize;
-    let menu_height = 300usize;
-    for y in menu_top..(menu_top + menu_height) {
-        for x in 200..1080 {
-            ptr.add(y * pitch + x).write_volatile(bg_color);
-        }
-    }
+    let mut canvas = menu.canvas();
+    canvas.clear(Color::from_argb(BG_COLOR));
 
-    // Draw menu box
-    let box_left = 300usize;
-    let box_right = 980usize;
-    let box_top = 220usize;
-    let box_bottom = 480usize;
+    // Menu box
+    let box_left = 100usize;
+    canvas.draw_rect(box_left as u32, 20, 681, 311, Color::from_argb(gray));
 
-    // Box border
-    for x in box_left..box_right {
-        ptr.add(box_top * pitch + x).write_volatile(gray);
-        ptr.add(box_bottom * pitch + x).write_volatile(gray);
-    }
-    for y in box_top..box_bottom {
-        ptr.add(y * pitch + box_left).write_volatile(gray);
-        ptr.add(y * pitch + box_right).write_volatile(gray);
-    }
+    let ptr = canvas.buffer_ptr();
+    let pitch = canvas.pitch_pixels();
 
     // Menu title "SELECT MODE"
-    let title_y = 240usize;
-    let title_x = 500usize;
-    draw_text_select(ptr, pitch, title_x, title_y, white);
+    draw_text_select(ptr, pitch, 300, 40, white);
 
     // Menu items
     let item_height = 50usize;
-    let item_start_y = 300usize;
+    let item_start_y = 100usize;
 
-    let items = ["Snake Game", "Screensaver", "About"];
+    let items = ["Snake Game", "Screensaver", "About", "Input"];
     for (i, _item) in items.iter().enumerate() {
         let y = item_start_y + i * item_height;
         let color = if i == selected { green } else { white };
@@ -392,12 +412,13 @@ unsafe fn draw_menu(ptr: *mut u32, pitch: usize, width: usize, height: usize, se
             0 => draw_text_snake_game(ptr, pitch, box_left + 80, y, color),
             1 => draw_text_screensaver(ptr, pitch, box_left + 80, y, color),
             2 => draw_text_about(ptr, pitch, box_left + 80, y, color),
+            3 => draw_text_input(ptr, pitch, box_left + 80, y, color),
             _ => {}
         }
     }
 
     // Navigation hint at bottom
-    draw_text_hint(ptr, pitch, 400, 520, gray);
+    draw_text_hint(ptr, pitch, 200, 360, gray);
 }
 
 /// Draw "SELECT" text using blocks
@@ -601,6 +622,18 @@ unsafe fn draw_text_about(ptr: *mut u32, pitch: usize, x: usize, y: usize, color
     draw_letter(ptr, pitch, x + spacing*4, y, b, 'T', color);
 }
 
+/// Draw "INPUT" text
+unsafe fn draw_text_input(ptr: *mut u32, pitch: usize, x: usize, y: usize, color: u32) {
+    let b = 6usize;
+    let spacing = b * 4;
+    // I N P U T
+    draw_letter(ptr, pitch, x, y, b, 'I', color);
+    draw_letter(ptr, pitch, x + spacing, y, b, 'N', color);
+    draw_letter(ptr, pitch, x + spacing*2, y, b, 'P', color);
+    draw_letter(ptr, pitch, x + spacing*3, y, b, 'U', color);
+    draw_letter(ptr, pitch, x + spacing*4, y, b, 'T', color);
+}
+
 /// Draw navigation hint - simplified arrow indicators
 unsafe fn draw_text_hint(ptr: *mut u32, pitch: usize, x: usize, y: usize, color: u32) {
     let b = 4usize;
@@ -692,86 +725,173 @@ unsafe fn draw_about_screen(ptr: *mut u32, pitch: usize, _width: usize, _height:
     draw_letter(ptr, pitch, esc2_x + spacing*2, 480, b, 'C', white);
 }
 
-/// Run the main application loop with menu and state machine
-fn run_app(fb: &Framebuffer) {
-    let ptr = fb.buffer_ptr();
-    let pitch = fb.pitch_pixels();
-    let (width, height) = fb.dimensions();
-    let width = width as usize;
-    let height = height as usize;
+/// Draw the input diagnostics screen: a row of counters per source, and
+/// the most recent raw codes beside it
+fn draw_input_diagnostics(fb: &mut Framebuffer, stats: &InputStats) {
+    use alloc::format;
+
+    let white = Color::WHITE;
+    let gray = Color::GRAY;
+    let font = &font::SANS_16;
+    let line = font.line_height as i32 + 10;
+    // Column positions: source, events, errors, last code; recent codes
+    let columns = [120, 300, 420, 540];
+    let (recent_left, recent_code) = (800, 940);
+
+    fb.fill_rect(100, 150, 1080, 450, Color::from_argb(BG_COLOR));
+    font::SANS_24.draw_text(fb, columns[0], 170, "Input diagnostics", Color::SEL4_GREEN);
+
+    let mut y = 240;
+    for (x, heading) in columns.iter().zip(["Source", "Events", "Errors", "Last code"]) {
+        font.draw_text(fb, *x, y, heading, gray);
+    }
+    for (source, counters) in stats.iter() {
+        y += line;
+        let last = match counters.last_code {
+            Some(code) => format!("0x{:04X}", code),
+            None => "--".into(),
+        };
+        let cells = [source.name(), &format!("{}", counters.events), &format!("{}", counters.decode_errors), &last];
+        for (x, cell) in columns.iter().zip(cells) {
+            font.draw_text(fb, *x, y, cell, white);
+        }
+    }
 
-    debug_println!("Starting app with UART input: {}x{}, pitch={}", width, height, pitch);
+    let mut y = 240;
+    font.draw_text(fb, recent_left, y, "Recent", gray);
+    for event in stats.recent() {
+        y += line;
+        font.draw_text(fb, recent_left, y, event.source.name(), white);
+        font.draw_text(fb, recent_code, y, &format!("0x{:04X}", event.code), white);
+    }
 
-    // Initialize input manager with UART at mapped virtual address
-    let mut input = InputManager::new(RemoteOptions::uart_at(UART_VADDR));
+    font.draw_text(fb, columns[0], 540, "Press Esc to return", gray);
+}
 
-    let bg_color: u32 = 0xFF101030;
+/// Background color of every screen
+const BG_COLOR: u32 = 0xFF101030;
+
+/// Snake segment size in pixels
+const SEGMENT_SIZE: usize = 20;
+
+/// Vertical blanks per app step when paced by vsync (20 steps/s at 60 Hz)
+const VSYNC_FRAMES_PER_STEP: u32 = 3;
+
+/// Application state: menu, snake and input, advanced one frame per `step`
+struct TvApp {
+    /// Owned for text rendering; `ptr` is its buffer, for direct writes
+    fb: Framebuffer,
+    ptr: *mut u32,
+    pitch: usize,
+    width: usize,
+    height: usize,
+    input: InputManager,
+    state: AppState,
+    menu_selected: usize,
+    needs_redraw: bool,
+    /// The menu must be redrawn (the rest of the screen is unchanged)
+    menu_dirty: bool,
+    /// Snake state (for game and screensaver)
+    snake: Snake,
+    prev_segments: [Segment; 30],
+    frame: u32,
+    /// Event and error totals last drawn by the diagnostics screen
+    diag_drawn: (u32, u32),
+}
 
-    // Application state
-    let mut state = AppState::Menu;
-    let mut menu_selected: usize = 0;
-    let mut needs_redraw = true;
+impl TvApp {
+    fn new(fb: Framebuffer) -> Self {
+        let ptr = fb.buffer_ptr();
+        let pitch = fb.pitch_pixels();
+        let (width, height) = fb.dimensions();
+        let width = width as usize;
+        let height = height as usize;
 
-    // Snake state (for game and screensaver)
-    let mut snake = Snake::new();
-    let mut prev_segments: [Segment; 30] = [Segment { x: -100, y: -100 }; 30];
-    let mut frame: u32 = 0;
-    let segment_size = 20usize;
+        debug_println!("Starting app with UART input: {}x{}, pitch={}", width, height, pitch);
 
-    // Clear screen once
-    unsafe {
-        core::arch::asm!("dsb sy");
-        for y in 0..height {
-            for x in 0..pitch {
-                ptr.add(y * pitch + x).write_volatile(bg_color);
+        // Clear screen once
+        unsafe {
+            core::arch::asm!("dsb sy");
+            for y in 0..height {
+                for x in 0..pitch {
+                    ptr.add(y * pitch + x).write_volatile(BG_COLOR);
+                }
             }
+            core::arch::asm!("dsb sy");
+        }
+
+        Self {
+            fb,
+            ptr,
+            pitch,
+            width,
+            height,
+            // Initialize input manager with UART at mapped virtual address
+            input: InputManager::new(
+                RemoteOptions::uart_at(UART_VADDR)
+                    .with_timing(InputTiming::at_hz(REFRESH_HZ / VSYNC_FRAMES_PER_STEP)),
+            ),
+            state: AppState::Menu,
+            menu_selected: 0,
+            needs_redraw: true,
+            menu_dirty: false,
+            snake: Snake::new(),
+            prev_segments: [Segment { x: -100, y: -100 }; 30],
+            frame: 0,
+            diag_drawn: (0, 0),
         }
-        core::arch::asm!("dsb sy");
     }
 
-    debug_println!("Entering main loop. Use WASD/arrows to navigate, Enter to select, Q to quit.");
+    /// Poll input and render one frame
+    fn step(&mut self) {
+        let (ptr, pitch, width, height) = (self.ptr, self.pitch, self.width, self.height);
 
-    loop {
-        // Poll for input
-        if let Some(event) = input.poll() {
+        // Poll for input (one input tick per step)
+        self.input.tick();
+        if let Some(event) = self.input.poll() {
             if let InputEvent::Key(key_event) = event {
                 if key_event.state == KeyState::Pressed {
                     debug_println!("Key pressed: {:?}", key_event.key);
 
-                    match state {
+                    match self.state {
                         AppState::Menu => {
                             match key_event.key {
                                 KeyCode::Up => {
-                                    if menu_selected > 0 {
-                                        menu_selected -= 1;
-                                        needs_redraw = true;
+                                    if self.menu_selected > 0 {
+                                        self.menu_selected -= 1;
+                                        self.menu_dirty = true;
                                     }
                                 }
                                 KeyCode::Down => {
-                                    if menu_selected < MENU_ITEM_COUNT - 1 {
-                                        menu_selected += 1;
-                                        needs_redraw = true;
+                                    if self.menu_selected < MENU_ITEM_COUNT - 1 {
+                                        self.menu_selected += 1;
+                                        self.menu_dirty = true;
                                     }
                                 }
                                 KeyCode::Enter | KeyCode::Space => {
-                                    match menu_selected {
+                                    match self.menu_selected {
                                         MENU_SNAKE_GAME => {
-                                            state = AppState::SnakeGame;
-                                            snake = Snake::new();
-                                            needs_redraw = true;
+                                            self.state = AppState::SnakeGame;
+                                            self.snake = Snake::new();
+                                            self.needs_redraw = true;
                                             debug_println!("Starting Snake Game");
                                         }
                                         MENU_SCREENSAVER => {
-                                            state = AppState::Screensaver;
-                                            snake = Snake::new();
-                                            needs_redraw = true;
+                                            self.state = AppState::Screensaver;
+                                            self.snake = Snake::new();
+                                            self.needs_redraw = true;
                                             debug_println!("Starting Screensaver");
                                         }
                                         MENU_ABOUT => {
-                                            state = AppState::About;
-                                            needs_redraw = true;
+                                            self.state = AppState::About;
+                                            self.needs_redraw = true;
                                             debug_println!("Showing About");
                                         }
+                                        MENU_INPUT_DIAG => {
+                                            self.state = AppState::InputDiagnostics;
+                                            self.needs_redraw = true;
+                                            debug_println!("Showing input diagnostics");
+                                        }
                                         _ => {}
                                     }
                                 }
@@ -780,13 +900,13 @@ fn run_app(fb: &Framebuffer) {
                         }
                         AppState::SnakeGame => {
                             match key_event.key {
-                                KeyCode::Up => snake.set_direction(3),
-                                KeyCode::Down => snake.set_direction(1),
-                                KeyCode::Left => snake.set_direction(2),
-                                KeyCode::Right => snake.set_direction(0),
+                                KeyCode::Up => self.snake.set_direction(3),
+                                KeyCode::Down => self.snake.set_direction(1),
+                                KeyCode::Left => self.snake.set_direction(2),
+                                KeyCode::Right => self.snake.set_direction(0),
                                 KeyCode::Escape => {
-                                    state = AppState::Menu;
-                                    needs_redraw = true;
+                                    self.state = AppState::Menu;
+                                    self.needs_redraw = true;
                                     debug_println!("Returning to menu");
                                 }
                                 _ => {}
@@ -794,8 +914,17 @@ fn run_app(fb: &Framebuffer) {
                         }
                         AppState::Screensaver | AppState::About => {
                             if key_event.key == KeyCode::Escape || key_event.key == KeyCode::Enter {
-                                state = AppState::Menu;
-                                needs_redraw = true;
+                                self.state = AppState::Menu;
+                                self.needs_redraw = true;
+                                debug_println!("Returning to menu");
+                            }
+                        }
+                        AppState::InputDiagnostics => {
+                            // Only Escape leaves, so every other key can be
+                            // pressed to see its code
+                            if key_event.key == KeyCode::Escape {
+                                self.state = AppState::Menu;
+                                self.needs_redraw = true;
                                 debug_println!("Returning to menu");
                             }
                         }
@@ -808,82 +937,105 @@ fn run_app(fb: &Framebuffer) {
         unsafe {
             core::arch::asm!("dsb sy");
 
-            match state {
+            match self.state {
                 AppState::Menu => {
-                    if needs_redraw {
+                    if self.needs_redraw {
                         // Clear and draw menu
                         for y in 0..height {
                             for x in 0..pitch {
-                                ptr.add(y * pitch + x).write_volatile(bg_color);
+                                ptr.add(y * pitch + x).write_volatile(BG_COLOR);
                             }
                         }
                         draw_static_elements(ptr, pitch, width, height);
-                        draw_menu(ptr, pitch, width, height, menu_selected);
-                        needs_redraw = false;
+                        self.needs_redraw = false;
+                        self.menu_dirty = true;
+                    }
+                    if self.menu_dirty {
+                        // Only the TV app touches the menu surface
+                        let menu = &mut *core::ptr::addr_of_mut!(MENU_SURFACE);
+                        draw_menu(menu, self.menu_selected);
+                        menu.blit(&mut self.fb, MENU_LEFT as i32, MENU_TOP as i32, None);
+                        self.menu_dirty = false;
                     }
                 }
                 AppState::SnakeGame | AppState::Screensaver => {
-                    if needs_redraw {
+                    if self.needs_redraw {
                         // Clear and draw title
                         for y in 0..height {
                             for x in 0..pitch {
-                                ptr.add(y * pitch + x).write_volatile(bg_color);
+                                ptr.add(y * pitch + x).write_volatile(BG_COLOR);
                             }
                         }
                         draw_static_elements(ptr, pitch, width, height);
-                        needs_redraw = false;
+                        self.needs_redraw = false;
                     }
 
                     // Erase previous snake
-                    for i in 0..snake.length {
-                        let seg = prev_segments[i];
+                    for i in 0..self.snake.length {
+                        let seg = self.prev_segments[i];
                         if seg.x >= PLAY_AREA_LEFT && seg.y >= PLAY_AREA_TOP {
-                            let x = (seg.x as usize).saturating_sub(segment_size / 2);
-                            let y = (seg.y as usize).saturating_sub(segment_size / 2);
-                            if x + segment_size < PLAY_AREA_RIGHT as usize && y + segment_size < PLAY_AREA_BOTTOM as usize {
-                                draw_block(ptr, pitch, x, y, segment_size, segment_size, bg_color);
+                            let x = (seg.x as usize).saturating_sub(SEGMENT_SIZE / 2);
+                            let y = (seg.y as usize).saturating_sub(SEGMENT_SIZE / 2);
+                            if x + SEGMENT_SIZE < PLAY_AREA_RIGHT as usize && y + SEGMENT_SIZE < PLAY_AREA_BOTTOM as usize {
+                                draw_block(ptr, pitch, x, y, SEGMENT_SIZE, SEGMENT_SIZE, BG_COLOR);
                             }
                         }
                     }
 
                     // Save positions
-                    for i in 0..snake.length {
-                        prev_segments[i] = snake.segments[i];
+                    for i in 0..self.snake.length {
+                        self.prev_segments[i] = self.snake.segments[i];
                     }
 
                     // Update snake (auto-turn only in screensaver mode)
-                    if state == AppState::Screensaver {
-                        snake.update();
+                    if self.state == AppState::Screensaver {
+                        self.snake.update();
                     } else {
-                        snake.update_no_auto_turn();
+                        self.snake.update_no_auto_turn();
                     }
 
                     // Draw snake
-                    for i in 0..snake.length {
-                        let seg = snake.segments[i];
+                    for i in 0..self.snake.length {
+                        let seg = self.snake.segments[i];
                         if seg.x >= PLAY_AREA_LEFT && seg.y >= PLAY_AREA_TOP {
-                            let x = (seg.x as usize).saturating_sub(segment_size / 2);
-                            let y = (seg.y as usize).saturating_sub(segment_size / 2);
-                            if x + segment_size < PLAY_AREA_RIGHT as usize && y + segment_size < PLAY_AREA_BOTTOM as usize {
-                                let hue = ((i as u32 * 18 + frame * 4) % 360) as u16;
+                            let x = (seg.x as usize).saturating_sub(SEGMENT_SIZE / 2);
+                            let y = (seg.y as usize).saturating_sub(SEGMENT_SIZE / 2);
+                            if x + SEGMENT_SIZE < PLAY_AREA_RIGHT as usize && y + SEGMENT_SIZE < PLAY_AREA_BOTTOM as usize {
+                                let hue = ((i as u32 * 18 + self.frame * 4) % 360) as u16;
                                 let color = hsv_to_rgb(hue, 255, 255);
-                                draw_block(ptr, pitch, x, y, segment_size, segment_size, color);
+                                draw_block(ptr, pitch, x, y, SEGMENT_SIZE, SEGMENT_SIZE, color);
                             }
                         }
                     }
 
-                    frame = frame.wrapping_add(1);
+                    self.frame = self.frame.wrapping_add(1);
                 }
                 AppState::About => {
-                    if needs_redraw {
+                    if self.needs_redraw {
                         for y in 0..height {
                             for x in 0..pitch {
-                                ptr.add(y * pitch + x).write_volatile(bg_color);
+                                ptr.add(y * pitch + x).write_volatile(BG_COLOR);
                             }
                         }
                         draw_static_elements(ptr, pitch, width, height);
                         draw_about_screen(ptr, pitch, width, height);
-                        needs_redraw = false;
+                        self.needs_redraw = false;
+                    }
+                }
+                AppState::InputDiagnostics => {
+                    // Redraw on entry and whenever a counter moves: IR
+                    // frames and decode errors change the stats without
+                    // producing a key press
+                    let stats = self.input.stats();
+                    let totals = (stats.total_events(), stats.total_errors());
+                    if self.needs_redraw || totals != self.diag_drawn {
+                        if self.needs_redraw {
+                            self.fb.clear(Color::from_argb(BG_COLOR));
+                            draw_static_elements(ptr, pitch, width, height);
+                        }
+                        draw_input_diagnostics(&mut self.fb, &stats);
+                        self.diag_drawn = totals;
+                        self.needs_redraw = false;
                     }
                 }
             }
@@ -892,11 +1044,33 @@ fn run_app(fb: &Framebuffer) {
             core::arch::asm!("isb");
         }
 
-        // Frame delay (shorter for responsive input)
-        for _ in 0..100_000 { core::hint::spin_loop(); }
     }
 }
 
+/// Ask the firmware for vsync interrupts on the SMI IRQ channel
+fn init_vsync() -> Option<Vsync> {
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+    match unsafe { Vsync::firmware(&mailbox, SMI_VIRT_BASE) } {
+        Ok(vsync) => {
+            debug_println!("Vsync: firmware notification on IRQ {}", vsync.source().irq());
+            Some(vsync)
+        }
+        Err(e) => {
+            debug_println!("Vsync unavailable ({:?}), using the system timer", e);
+            None
+        }
+    }
+}
+
+/// Tick the system timer once per app step, as vsync would
+fn init_frame_timer() -> FrameTimer {
+    let hz = REFRESH_HZ / VSYNC_FRAMES_PER_STEP;
+    debug_println!("Frame timer: {} Hz on IRQ {}", hz, rpi4_graphics::frame_timer::SYSTEM_TIMER_C1_IRQ);
+    // Safety: tvdemo.system maps the system timer page here, and nothing
+    // else in this PD uses compare channel 1
+    unsafe { FrameTimer::start(SYSTIMER_VIRT_BASE, hz) }
+}
+
 #[protection_domain]
 fn init() -> TvDemoHandler {
     // Initialize the heap allocator
@@ -914,10 +1088,10 @@ fn init() -> TvDemoHandler {
     blink_activity_led();
 
     // Step 2: Initialize framebuffer via VideoCore mailbox
-    match init_framebuffer() {
+    let app = match init_framebuffer() {
         Some(fb) => {
             debug_println!("Framebuffer ready, starting app...");
-            run_app(&fb);
+            TvApp::new(fb)
         }
         None => {
             debug_println!("ERROR: Could not allocate framebuffer!");
@@ -927,21 +1101,43 @@ fn init() -> TvDemoHandler {
                 blink_activity_led();
             }
         }
-    }
-
-    TvDemoHandler::new()
-}
+    };
 
-#[derive(Debug)]
-pub struct HandlerError;
+    debug_println!("Entering main loop. Use WASD/arrows to navigate, Enter to select, Q to quit.");
 
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Handler error")
-    }
+    // Step 3: Pace frames from the vsync IRQ, or the system timer without it
+    let pacer = match init_vsync() {
+        Some(vsync) => FramePacer::Vsync(FrameClock::new(vsync, VSYNC_FRAMES_PER_STEP)),
+        None => FramePacer::Timer(init_frame_timer()),
+    };
+    TvDemoHandler::new(app, pacer)
 }
 
 impl Handler for TvDemoHandler {
-    type Error = HandlerError;
-    fn notified(&mut self, _channels: ChannelSet) -> Result<(), Self::Error> { Ok(()) }
+    type Error = PdError;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        // Clear the source before acking, or the level IRQ refires at once
+        if channels.contains(VSYNC_CHANNEL) {
+            if let FramePacer::Vsync(clock) = &mut self.pacer {
+                clock.handle_irq(|_| self.app.step());
+            }
+            VSYNC_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking vsync IRQ")
+            })?;
+        }
+        if channels.contains(TIMER_CHANNEL) {
+            if let FramePacer::Timer(timer) = &mut self.pacer {
+                if timer.handle_irq() {
+                    self.app.step();
+                }
+            }
+            TIMER_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking timer IRQ")
+            })?;
+        }
+        // Nothing left to do until the next interrupt: returning blocks the
+        // PD, and the core idles in the kernel
+        Ok(())
+    }
 }
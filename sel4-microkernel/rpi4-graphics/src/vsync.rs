@@ -0,0 +1,241 @@
+//! # Vertical Blank (vsync) Notification
+//!
+//! Paces rendering to the display refresh instead of spin delays. There are
+//! two sources, matching the two HDMI backends:
+//!
+//! - **Firmware** ([`HdmiBackend`](crate::HdmiBackend)): while the VideoCore
+//!   firmware drives the display, it raises the SMI interrupt at every
+//!   vertical blank once asked to via the `SET_VSYNC` property tag.
+//! - **Pixel valve** ([`DirectHdmiBackend`](crate::DirectHdmiBackend)): the
+//!   HDMI0 pixel valve (PV2) raises its own interrupt at the start of the
+//!   vertical front porch.
+//!
+//! Either way the interrupt is routed to the PD as a Microkit IRQ channel
+//! (see `tvdemo.system`). The PD's `notified` handler calls
+//! [`Vsync::handle_irq`] to clear the source, renders a frame, then acks the
+//! channel. Code running outside the event loop can block on
+//! [`Vsync::wait`], which polls the same status bit.
+//!
+//! [`FrameClock`] wraps both for a PD that renders below the refresh rate:
+//! every `divider`th blank runs the PD's frame callback, and
+//! [`FrameClock::wait_for_vsync`] falls back to a fixed delay when no blank
+//! comes.
+//!
+//! ## Reference
+//! - Linux `drivers/gpu/drm/vc4/vc4_regs.h` (pixel valve registers)
+//! - Raspberry Pi `bcm2708_fb` driver (SMI vsync interrupt)
+
+use crate::mailbox::{Mailbox, MailboxError};
+
+/// GIC interrupt ID of the SMI block (SPI 112), used by the firmware for vsync
+pub const SMI_VSYNC_IRQ: usize = 144;
+
+/// GIC interrupt ID of pixel valve 2 / HDMI0 (SPI 101)
+pub const PIXELVALVE2_IRQ: usize = 133;
+
+/// SMI registers
+mod smi {
+    /// Control/status register
+    pub const CS: usize = 0x00;
+    /// Interrupt status bits (INTD, INTT, INTR) - write 0 to clear
+    pub const CS_INTERRUPTS: u32 = (1 << 9) | (1 << 10) | (1 << 11);
+}
+
+/// Pixel valve registers
+mod pv {
+    /// Interrupt enable register
+    pub const INTEN: usize = 0x24;
+    /// Interrupt status register (write 1 to clear)
+    pub const INTSTAT: usize = 0x28;
+    /// Start of vertical front porch - the point the vc4 driver uses as vblank
+    pub const INT_VFP_START: u32 = 1 << 7;
+}
+
+/// Refresh rate of the modes the demos ask for (720p and 1080p at 60 Hz)
+pub const REFRESH_HZ: u32 = 60;
+
+/// Spins before [`Vsync::wait`] gives up (several frames at any refresh rate)
+const WAIT_TIMEOUT_SPINS: u32 = 5_000_000;
+
+/// Spins standing in for one frame when there is no vsync to wait for
+const FALLBACK_FRAME_SPINS: u32 = 100_000;
+
+/// Where vertical blank notifications come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VsyncSource {
+    /// Firmware-driven display; vsync signalled through the SMI interrupt
+    Firmware,
+    /// Directly driven display; vsync from the HDMI0 pixel valve
+    PixelValve,
+}
+
+impl VsyncSource {
+    /// GIC interrupt ID to route to the PD for this source
+    pub const fn irq(&self) -> usize {
+        match self {
+            VsyncSource::Firmware => SMI_VSYNC_IRQ,
+            VsyncSource::PixelValve => PIXELVALVE2_IRQ,
+        }
+    }
+}
+
+/// Vertical blank notifier
+pub struct Vsync {
+    source: VsyncSource,
+    /// Virtual address of the SMI or pixel valve registers
+    base: usize,
+    /// Vertical blanks seen so far
+    frames: u32,
+}
+
+impl Vsync {
+    /// Ask the firmware to signal vsync through the SMI interrupt.
+    ///
+    /// # Safety
+    /// `smi_base` must be the mapped SMI register page.
+    pub unsafe fn firmware(mailbox: &Mailbox, smi_base: usize) -> Result<Self, MailboxError> {
+        mailbox.set_vsync_notify(true)?;
+        let mut vsync = Self { source: VsyncSource::Firmware, base: smi_base, frames: 0 };
+        vsync.clear();
+        Ok(vsync)
+    }
+
+    /// Enable the vertical-front-porch interrupt of a pixel valve.
+    ///
+    /// # Safety
+    /// `pv_base` must be the mapped pixel valve register page, and the
+    /// pixel valve must already be running (set up by the firmware).
+    pub unsafe fn pixel_valve(pv_base: usize) -> Self {
+        let mut vsync = Self { source: VsyncSource::PixelValve, base: pv_base, frames: 0 };
+        vsync.clear();
+        let inten = vsync.read_reg(pv::INTEN);
+        vsync.write_reg(pv::INTEN, inten | pv::INT_VFP_START);
+        vsync
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// Is a vertical blank pending?
+    pub fn pending(&self) -> bool {
+        match self.source {
+            VsyncSource::Firmware => self.read_reg(smi::CS) & smi::CS_INTERRUPTS != 0,
+            VsyncSource::PixelValve => self.read_reg(pv::INTSTAT) & pv::INT_VFP_START != 0,
+        }
+    }
+
+    /// Clear the pending vsync so the (level-triggered) interrupt drops
+    fn clear(&mut self) {
+        match self.source {
+            VsyncSource::Firmware => {
+                let cs = self.read_reg(smi::CS);
+                self.write_reg(smi::CS, cs & !smi::CS_INTERRUPTS);
+            }
+            VsyncSource::PixelValve => self.write_reg(pv::INTSTAT, pv::INT_VFP_START),
+        }
+    }
+
+    /// Service the vsync IRQ: returns true (and counts a frame) if this was a
+    /// vertical blank. Call before acking the Microkit IRQ channel, otherwise
+    /// the interrupt fires again immediately.
+    pub fn handle_irq(&mut self) -> bool {
+        if !self.pending() {
+            return false;
+        }
+        self.clear();
+        self.frames = self.frames.wrapping_add(1);
+        true
+    }
+
+    /// Block until the next vertical blank by polling the status bit.
+    ///
+    /// A blank that already happened is discarded first, so this always waits
+    /// for a fresh one. Returns false if none arrived within the timeout
+    /// (e.g. the display is off); callers should fall back to a fixed delay.
+    pub fn wait(&mut self) -> bool {
+        self.clear();
+        for _ in 0..WAIT_TIMEOUT_SPINS {
+            if self.handle_irq() {
+                return true;
+            }
+            core::hint::spin_loop();
+        }
+        false
+    }
+
+    /// Number of vertical blanks seen (wraps)
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Which source this notifier listens to
+    pub fn source(&self) -> VsyncSource {
+        self.source
+    }
+}
+
+/// Frame callbacks for a PD's event loop, one every `divider` vertical
+/// blanks
+pub struct FrameClock {
+    vsync: Vsync,
+    divider: u32,
+}
+
+impl FrameClock {
+    /// Frames at the refresh rate divided by `divider` (at least 1)
+    pub fn new(vsync: Vsync, divider: u32) -> Self {
+        Self { vsync, divider: divider.max(1) }
+    }
+
+    /// Service the vsync IRQ and, if this blank starts a frame, call
+    /// `on_frame` with the frame number (wraps). Call before acking the
+    /// Microkit IRQ channel, like [`Vsync::handle_irq`]. Returns whether
+    /// `on_frame` ran.
+    pub fn handle_irq(&mut self, on_frame: impl FnOnce(u32)) -> bool {
+        if !self.vsync.handle_irq() {
+            return false;
+        }
+        let blanks = self.vsync.frames();
+        if !blanks.is_multiple_of(self.divider) {
+            return false;
+        }
+        on_frame(blanks / self.divider);
+        true
+    }
+
+    /// Block until the next vertical blank, for drawing outside the event
+    /// loop. Returns false, after a fixed delay standing in for a frame, if
+    /// none came.
+    pub fn wait_for_vsync(&mut self) -> bool {
+        if self.vsync.wait() {
+            return true;
+        }
+        fallback_frame_delay();
+        false
+    }
+
+    /// Frames per second at a display refresh of `refresh_hz`
+    pub fn frame_hz(&self, refresh_hz: u32) -> u32 {
+        refresh_hz / self.divider
+    }
+
+    /// The notifier the frames come from
+    pub fn vsync(&self) -> &Vsync {
+        &self.vsync
+    }
+}
+
+/// Fixed delay used in place of a vertical blank by the backends'
+/// `wait_for_vsync` when no source is attached or it timed out
+pub(crate) fn fallback_frame_delay() {
+    for _ in 0..FALLBACK_FRAME_SPINS {
+        core::hint::spin_loop();
+    }
+}
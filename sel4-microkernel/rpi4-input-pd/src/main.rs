@@ -8,6 +8,7 @@
 //! 1. **Memory Isolation**: This PD only accesses:
 //!    - UART registers at mapped virtual address
 //!    - Shared ring buffer at mapped virtual address
+//!    - System timer registers, read only, to timestamp events
 //!    - No other memory regions
 //!
 //! 2. **IPC Safety**: Ring buffer operations maintain:
@@ -16,30 +17,70 @@
 //!    - Atomic index updates (release/acquire ordering)
 //!
 //! 3. **Input Validation**: Only valid KeyCode values are written
+//!
+//! ## Setting the Clock
+//!
+//! Ctrl-T on the UART console starts typing a time: seconds since 1970 UTC,
+//! optionally `+HHMM` / `-HHMM` for the time zone, then Enter. The bytes
+//! never reach the ring; the time goes to the Timer PD as a `SET_TIME`
+//! call (see `rpi4-clock-protocol`).
 
 #![no_std]
 #![no_main]
 
 use sel4_microkit::{debug_println, protection_domain, Handler, ChannelSet, Channel};
-use core::fmt;
+#[cfg(feature = "uart")]
+use sel4_microkit::{with_msg_regs_mut, MessageInfo};
+#[cfg(feature = "debug-monitor")]
+use sel4_microkit::debug_print;
+use rpi4_pd_error::mem::{Gauge, Unit};
+use rpi4_pd_error::PdError;
 
 use rpi4_input::{KeyCode, KeyState};
 #[cfg(feature = "uart")]
-use rpi4_input::Uart;
+use rpi4_input::{KeyEvent, Uart};
+#[cfg(feature = "uart")]
+use rpi4_clock_protocol::{EntryFeed, SetTime, SetTimeEntry, INPUT_CLOCK_CHANNEL_ID};
 #[cfg(feature = "usb")]
 use rpi4_input::{usb::DmaRegion, UsbKeyboard};
+#[cfg(feature = "debug-monitor")]
+use rpi4_debug_monitor::{Monitor, Region};
 use rpi4_input_protocol::{
     InputRingHeader, InputRingEntry, KeyState as ProtoKeyState,
-    INPUT_CHANNEL_ID, header_ptr, entries_ptr,
+    INPUT_CHANNEL_ID, INPUT_PD_SYSTIMER_BASE, RING_CAPACITY, header_ptr, entries_ptr,
+    timestamp,
 };
+use verified_microkernel::event_loop::{EventLoop, Work};
 
 /// UART virtual address (mapped by Microkit)
 /// Physical: 0xFE215000 (page), mini-UART at +0x40
 #[cfg(feature = "uart")]
 const UART_VADDR: usize = 0x5_0300_0000 + 0x40;
 
+/// Mini-UART register page virtual address (the whole `uart_regs` mapping)
+#[cfg(feature = "debug-monitor")]
+const UART_PAGE_VADDR: usize = 0x5_0300_0000;
+
 /// Shared ring buffer virtual address (mapped by Microkit)
 const RING_BUFFER_VADDR: usize = 0x5_0400_0000;
+/// Entries waiting in the ring after each write, printed as `@mem` lines
+/// (see `rpi4_pd_error::mem`) as the peak grows
+static RING_GAUGE: Gauge = Gauge::new("input_ring", Unit::Slots, (RING_CAPACITY - 1) as usize);
+
+/// Most events forwarded per poll; they go to the ring in one batch
+const EVENT_BATCH: usize = 16;
+
+/// Most polls per notification. A source still producing after that
+/// waits for the next notification.
+const MAX_TURNS: u32 = 4;
+
+/// Shared ring buffer size (`input_ring` memory region)
+#[cfg(feature = "debug-monitor")]
+const RING_BUFFER_SIZE: usize = 0x1000;
+
+/// System timer registers (mapped read-only by Microkit), for event
+/// timestamps. Physical: 0xFE00_3000.
+const SYSTIMER_VADDR: usize = INPUT_PD_SYSTIMER_BASE;
 
 /// DWC2 USB controller MMIO virtual address (mapped by Microkit).
 /// Physical: 0xFE98_0000 (BCM2711 USB OTG core).
@@ -56,16 +97,59 @@ const USB_DMA_PADDR: usize = 0x3e86_0000;
 #[cfg(feature = "usb")]
 const USB_DMA_SIZE: usize = 0x1000;
 
+/// USB controller register window size (`usb_regs` memory region)
+#[cfg(all(feature = "debug-monitor", feature = "usb"))]
+const USB_REGS_SIZE: usize = 0x10000;
+
 /// Graphics PD channel for notifications
 const GRAPHICS_CHANNEL: Channel = Channel::new(INPUT_CHANNEL_ID);
 
+/// Timer PD channel for setting the clock (protected procedure call)
+#[cfg(feature = "uart")]
+const CLOCK_CHANNEL: Channel = Channel::new(INPUT_CLOCK_CHANNEL_ID);
+
+/// Everything this PD maps, and so everything the debug monitor may touch
+#[cfg(all(feature = "debug-monitor", not(feature = "usb")))]
+static DEBUG_REGIONS: [Region; 3] = [
+    Region::read_write("uart_regs", UART_PAGE_VADDR, 0x1000),
+    Region::read_write("input_ring", RING_BUFFER_VADDR, RING_BUFFER_SIZE),
+    Region::read_only("systimer_regs", SYSTIMER_VADDR, 0x1000),
+];
+#[cfg(all(feature = "debug-monitor", feature = "usb"))]
+static DEBUG_REGIONS: [Region; 5] = [
+    Region::read_write("uart_regs", UART_PAGE_VADDR, 0x1000),
+    Region::read_write("input_ring", RING_BUFFER_VADDR, RING_BUFFER_SIZE),
+    Region::read_only("systimer_regs", SYSTIMER_VADDR, 0x1000),
+    Region::read_write("usb_regs", USB_REGS_VADDR, USB_REGS_SIZE),
+    Region::read_write("usb_dma", USB_DMA_VADDR, USB_DMA_SIZE),
+];
+
+/// Debug monitor output goes to the same console as debug_println!
+#[cfg(feature = "debug-monitor")]
+struct DebugConsole;
+
+#[cfg(feature = "debug-monitor")]
+impl core::fmt::Write for DebugConsole {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        debug_print!("{}", s);
+        Ok(())
+    }
+}
+
 /// Input PD handler
 struct InputPdHandler {
     #[cfg(feature = "uart")]
     uart: Uart,
+    /// A time being typed on the console after Ctrl-T
+    #[cfg(feature = "uart")]
+    clock_entry: SetTimeEntry,
     #[cfg(feature = "usb")]
     usb: Option<UsbKeyboard>,
+    #[cfg(feature = "debug-monitor")]
+    monitor: Monitor<'static>,
     ring_base: *mut u8,
+    /// Budgeted dispatch of the Graphics PD channel
+    events: EventLoop<InputPdHandler, PdError, 1>,
 }
 
 impl InputPdHandler {
@@ -100,9 +184,15 @@ impl InputPdHandler {
         Self {
             #[cfg(feature = "uart")]
             uart: Uart::with_base(UART_VADDR),
+            #[cfg(feature = "uart")]
+            clock_entry: SetTimeEntry::new(),
             #[cfg(feature = "usb")]
             usb,
+            // Safety: DEBUG_REGIONS lists exactly this PD's mappings
+            #[cfg(feature = "debug-monitor")]
+            monitor: Monitor::new(&DEBUG_REGIONS),
             ring_base: RING_BUFFER_VADDR as *mut u8,
+            events: EventLoop::new(MAX_TURNS),
         }
     }
 
@@ -116,73 +206,120 @@ impl InputPdHandler {
         debug_println!("Input PD: Ring buffer initialized");
     }
 
-    /// Write an input event to the ring buffer
+    /// Ring entry for a decoded key, stamped with the system timer
+    fn entry_for(key_code: KeyCode, key_state: KeyState) -> InputRingEntry {
+        // Convert key code to u8 (validated mapping)
+        let code_u8 = key_code_to_u8(key_code);
+        let state = match key_state {
+            KeyState::Pressed => ProtoKeyState::Pressed,
+            KeyState::Released => ProtoKeyState::Released,
+        };
+        // Safety: SYSTIMER_VADDR is the mapped system timer page
+        InputRingEntry::key(code_u8, state, 0).at(unsafe { timestamp::now(SYSTIMER_VADDR) })
+    }
+
+    /// Write a batch of input events to the ring buffer
     ///
-    /// Returns true if event was written, false if buffer is full.
+    /// Returns how many were written: as many as fit. Events that did not
+    /// fit are dropped; they still take sequence numbers and are counted in
+    /// the ring header, so the Graphics PD sees the gap.
     ///
     /// ## Verification Properties (Verus)
     /// - Precondition: ring_base points to valid shared memory
-    /// - Postcondition: if returns true, entry was written at correct index
+    /// - Postcondition: the count written never exceeds the free slots
     /// - Invariant: write_idx is always < capacity
-    unsafe fn write_event(&self, key_code: KeyCode, key_state: KeyState) -> bool {
+    unsafe fn write_events(&self, batch: &[InputRingEntry]) -> usize {
         let header = &*header_ptr(self.ring_base);
 
-        // Check if buffer is full
-        if header.is_full() {
-            debug_println!("Input PD: Ring buffer full, dropping event");
-            return false;
+        // Stamps the sequence numbers and publishes the write index once,
+        // after every entry is in place
+        let written = header.push_batch(entries_ptr(self.ring_base), batch);
+        if written < batch.len() {
+            header.record_dropped((batch.len() - written) as u32);
+            debug_println!("Input PD: Ring buffer full, dropping event ({} dropped)", header.dropped());
         }
 
-        // Get current write index
-        let write_idx = header.write_idx.load(core::sync::atomic::Ordering::Acquire);
-
-        // Convert key code to u8 (validated mapping)
-        let code_u8 = key_code_to_u8(key_code);
-        let state = match key_state {
-            KeyState::Pressed => ProtoKeyState::Pressed,
-            KeyState::Released => ProtoKeyState::Released,
-        };
+        if RING_GAUGE.record((RING_CAPACITY - 1 - header.free_slots()) as usize) {
+            debug_println!("{}", RING_GAUGE.reading().line("input"));
+        }
+        written
+    }
 
-        // Write entry at current index
-        let entries = entries_ptr(self.ring_base);
-        let entry = InputRingEntry::key(code_u8, state, 0);
-        entries.add(write_idx as usize).write_volatile(entry);
+    /// Poll all input sources and forward up to `budget` events they
+    /// produced to the ring buffer as one batch, with one notification for
+    /// the lot. `Work::Deferred` if the batch filled, so more may be waiting.
+    fn poll_and_forward(&mut self, budget: u32) -> Result<Work, PdError> {
+        let mut batch = [InputRingEntry::EMPTY; EVENT_BATCH];
+        let limit = (budget as usize).min(EVENT_BATCH);
+        let mut n = 0;
 
-        // Memory barrier before updating index
-        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
+        // USB HID keyboard (real hardware input path). One report can
+        // change several keys; take every event it left queued.
+        #[cfg(feature = "usb")]
+        while n < limit {
+            let Some(event) = self.usb.as_mut().and_then(|usb| usb.poll()) else {
+                break;
+            };
+            batch[n] = Self::entry_for(event.key, event.state);
+            n += 1;
+        }
 
-        // Advance write index
-        header.advance_write();
+        // UART serial input (development / fallback path): drain what the
+        // FIFO holds, so a burst of typing becomes one batch
+        #[cfg(feature = "uart")]
+        while n < limit {
+            let Some(byte) = self.uart.try_read_byte() else {
+                break;
+            };
+            if let Some(event) = self.uart_byte(byte) {
+                batch[n] = Self::entry_for(event.key, event.state);
+                n += 1;
+            }
+        }
+        #[cfg(feature = "debug-monitor")]
+        self.monitor.poll_watches(&mut DebugConsole);
 
-        true
+        // Notify Graphics PD that new input is available
+        if n > 0 && unsafe { self.write_events(&batch[..n]) } > 0 {
+            GRAPHICS_CHANNEL.notify();
+        }
+        Ok(if n == limit { Work::Deferred } else { Work::Done })
     }
 
-    /// Poll all input sources and forward events to the ring buffer.
-    fn poll_and_forward(&mut self) {
-        // USB HID keyboard (real hardware input path). Poll first so the
-        // mutable borrow of `self.usb` is released before `write_event`.
-        #[cfg(feature = "usb")]
-        {
-            let usb_event = self.usb.as_mut().and_then(|usb| usb.poll());
-            if let Some(event) = usb_event {
-                unsafe {
-                    if self.write_event(event.key, event.state) {
-                        GRAPHICS_CHANNEL.notify();
-                    }
-                }
+    /// Route one console byte. With the debug monitor built in, it is
+    /// offered to the monitor first; then to a time being typed after
+    /// Ctrl-T; only what is left is decoded as a key.
+    #[cfg(feature = "uart")]
+    fn uart_byte(&mut self, byte: u8) -> Option<KeyEvent> {
+        #[cfg(feature = "debug-monitor")]
+        let byte = self.monitor.feed(byte, &mut DebugConsole)?;
+        let starting = !self.clock_entry.is_active();
+        match self.clock_entry.feed(byte) {
+            EntryFeed::Pass(byte) => return self.uart.decode(byte),
+            EntryFeed::Held if starting => {
+                debug_println!("Input PD: Set time: seconds since 1970 UTC [+HHMM|-HHMM], Enter (Esc cancels)");
             }
+            EntryFeed::Held => {}
+            EntryFeed::Set(request) => set_clock(request),
+            EntryFeed::Rejected => debug_println!("Input PD: Not a time, clock unchanged"),
+            EntryFeed::Cancelled => debug_println!("Input PD: Time entry cancelled"),
         }
+        None
+    }
+}
 
-        // UART serial input (development / fallback path).
-        #[cfg(feature = "uart")]
-        if let Some(event) = self.uart.poll() {
-            unsafe {
-                if self.write_event(event.key, event.state) {
-                    // Notify Graphics PD that new input is available
-                    GRAPHICS_CHANNEL.notify();
-                }
-            }
-        }
+/// Ask the Timer PD to set the clock to a time typed on the console
+#[cfg(feature = "uart")]
+fn set_clock(request: SetTime) {
+    let words = request.to_words();
+    with_msg_regs_mut(|mrs| mrs[..words.len()].copy_from_slice(&words));
+    let reply = CLOCK_CHANNEL.pp_call(MessageInfo::new(request.label(), words.len()));
+    match reply.label() {
+        0 => debug_println!("Input PD: Clock set"),
+        code => match PdError::from_wire(code as u32) {
+            Some(e) => debug_println!("Input PD: Setting the clock failed: {}", e),
+            None => debug_println!("Input PD: Setting the clock failed: reply 0x{:x}", code),
+        },
     }
 }
 
@@ -234,31 +371,45 @@ fn init() -> InputPdHandler {
     #[cfg(feature = "usb")]
     debug_println!("Input PD: USB controller at 0x{:x}", USB_REGS_VADDR);
     debug_println!("Input PD: Ring buffer at 0x{:x}", RING_BUFFER_VADDR);
+    debug_println!("Input PD: System timer at 0x{:x}", SYSTIMER_VADDR);
+    #[cfg(feature = "debug-monitor")]
+    debug_println!("Input PD: Debug monitor built in, Ctrl-] to enter");
+    #[cfg(feature = "audit")]
+    {
+        rpi4_input_protocol::audit::set_role(rpi4_input_protocol::audit::Role::Input);
+        debug_println!("Input PD: Security audit, ring accesses checked against input_pd_can_access");
+    }
 
-    let handler = unsafe { InputPdHandler::new() };
+    let mut handler = unsafe { InputPdHandler::new() };
+    // A channel id below 63 with a non-zero budget: cannot fail
+    let _ = handler.events.register(INPUT_CHANNEL_ID, EVENT_BATCH as u32, InputPdHandler::poll_and_forward);
 
     // Initialize ring buffer
     unsafe { handler.init_ring_buffer(); }
+    #[cfg(feature = "audit")]
+    print_audit_log();
 
     debug_println!("Input PD: Ready, polling for input...");
     handler
 }
 
-#[derive(Debug)]
-pub struct HandlerError;
+impl Handler for InputPdHandler {
+    type Error = PdError;
 
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Input PD handler error")
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        // Poll the input sources whenever the Graphics PD notifies
+        let result = EventLoop::run(self, |pd| &mut pd.events, channels.iter().map(|channel| channel.index()));
+        #[cfg(feature = "audit")]
+        print_audit_log();
+        result
     }
 }
 
-impl Handler for InputPdHandler {
-    type Error = HandlerError;
-
-    fn notified(&mut self, _channels: ChannelSet) -> Result<(), Self::Error> {
-        // Poll UART on each notification (or timer tick)
-        self.poll_and_forward();
-        Ok(())
+/// Print the access-spec violations logged since the last call
+#[cfg(feature = "audit")]
+fn print_audit_log() {
+    let dropped = rpi4_pd_error::audit::drain(|violation| debug_println!("{}", violation));
+    if dropped > 0 {
+        debug_println!("@audit dropped={}", dropped);
     }
 }
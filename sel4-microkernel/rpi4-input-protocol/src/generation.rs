@@ -80,9 +80,9 @@ impl InputRingHeader {
         let stable = validate_stable_generation(observed)
             .map_err(|observed| OddGeneration { observed })?;
 
-        debug_assert!(self.capacity > 0);
-        debug_assert!(self.current_write_idx() < self.capacity);
-        debug_assert!(self.current_read_idx() < self.capacity);
+        debug_assert!(self.ring.capacity > 0);
+        debug_assert!(self.current_write_idx() < self.ring.capacity);
+        debug_assert!(self.current_read_idx() < self.ring.capacity);
         Ok(Generation(stable))
     }
 
@@ -115,8 +115,8 @@ impl InputRingHeader {
         // Consume the linear token in this reset invocation.
         let _consumed = stopped;
         self.generation_atomic().store(odd, Ordering::Release);
-        self.write_idx.store(0, Ordering::Release);
-        self.read_idx.store(0, Ordering::Release);
+        self.ring.write_idx.store(0, Ordering::Release);
+        self.ring.read_idx.store(0, Ordering::Release);
         self.generation_atomic()
             .store(next_even, Ordering::Release);
 
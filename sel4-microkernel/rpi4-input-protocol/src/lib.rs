@@ -1,23 +1,51 @@
 //! Verified input IPC protocol for the isolated input/graphics products.
 //!
-//! The legacy ABI remains unchanged. Wave 1 adds restart-generation handling
-//! alongside the existing entry and index APIs from this crate root.
+//! Wave 1 adds restart-generation handling alongside the existing entry and
+//! index APIs from this crate root. Entries carry a per-producer sequence
+//! number and the header counts events dropped on a full ring, so a
+//! consumer can detect and report gaps, see [`seq`]. Each entry also
+//! carries the system timer reading at which the event was decoded, see
+//! [`timestamp`].
+//!
+//! The ring is a `verified_microkernel::ring::VerifiedRing` of
+//! [`InputRingEntry`]s ([`InputRing`]); [`InputRingHeader`] adds the
+//! producer's sequence and drop counters after the shared index words.
+//!
+//! The `pow2-capacity` feature shrinks the ring from 254 to 128 entries so
+//! index wrap-around is a mask rather than a compare. Readers use the
+//! capacity stored in the ring header, so both ends need not agree on it.
+//!
+//! Several input PDs can feed the graphics PD through per-source rings and
+//! a merged reader, see [`mpsc`].
+//!
+//! The `audit` feature checks the raw pointer helpers against the PD access
+//! specs at runtime, see [`audit`].
 
 #![no_std]
 #![allow(unused)]
 #![allow(clippy::assign_op_pattern)]
 #![allow(clippy::new_without_default)]
+#![allow(clippy::manual_range_contains)]
+#![allow(clippy::manual_map)]
 
 use core::sync::atomic::{AtomicU32, Ordering};
+use rpi4_pd_error::audit::Access;
+use verified_microkernel::ring::{self, RingHeader, VerifiedRing};
 use verus_builtin_macros::verus;
 
 verus! {
 
 pub const INPUT_CHANNEL_ID: usize = 1;
-pub const RING_CAPACITY: u32 = 1000;
-pub const HEADER_SIZE: usize = 16;
-pub const ENTRY_SIZE: usize = 4;
-pub const ENTRIES_OFFSET: usize = 16;
+/// Input ring capacity: as many entries as fit in the 4 KiB ring region
+#[cfg(not(feature = "pow2-capacity"))]
+pub const RING_CAPACITY: u32 = 254;
+/// Input ring capacity: the largest power of two that fits in the 4 KiB
+/// ring region, so index wrap-around is a mask
+#[cfg(feature = "pow2-capacity")]
+pub const RING_CAPACITY: u32 = 128;
+pub const HEADER_SIZE: usize = 32;
+pub const ENTRY_SIZE: usize = 16;
+pub const ENTRIES_OFFSET: usize = 32;
 
 pub const KEY_CODE_MAX: u8 = 40;
 pub const KEY_UP: u8 = 1;
@@ -64,6 +92,11 @@ pub struct InputRingEntry {
     pub key_code: u8,
     pub key_state: u8,
     pub modifiers: u8,
+    /// Producer's sequence number, stamped by `write_batch`
+    pub seq: u32,
+    /// System timer reading when the event was decoded, 0 if the producer
+    /// has no timer
+    pub timestamp_us: u64,
 }
 
 impl InputRingEntry {
@@ -89,6 +122,8 @@ impl InputRingEntry {
             key_code: code,
             key_state: state,
             modifiers,
+            seq: 0,
+            timestamp_us: 0,
         }
     }
 
@@ -102,6 +137,8 @@ impl InputRingEntry {
             key_code: 0,
             key_state: 0,
             modifiers: 0,
+            seq: 0,
+            timestamp_us: 0,
         }
     }
 
@@ -113,120 +150,6 @@ impl InputRingEntry {
     }
 }
 
-pub struct RingIndices {
-    write_idx: u32,
-    read_idx: u32,
-    capacity: u32,
-}
-
-impl RingIndices {
-    pub open spec fn valid(&self) -> bool {
-        self.capacity > 0
-            && self.capacity <= RING_CAPACITY
-            && self.write_idx < self.capacity
-            && self.read_idx < self.capacity
-    }
-
-    pub open spec fn count(&self) -> u32
-        recommends self.valid()
-    {
-        if self.write_idx >= self.read_idx {
-            self.write_idx - self.read_idx
-        } else {
-            self.capacity - self.read_idx + self.write_idx
-        }
-    }
-
-    pub open spec fn is_empty_spec(&self) -> bool
-        recommends self.valid()
-    {
-        self.write_idx == self.read_idx
-    }
-
-    pub open spec fn is_full_spec(&self) -> bool
-        recommends self.valid()
-    {
-        (self.write_idx + 1) % self.capacity == self.read_idx
-    }
-
-    pub fn new(capacity: u32) -> (indices: Self)
-        requires
-            capacity > 0,
-            capacity <= RING_CAPACITY,
-        ensures
-            indices.valid(),
-            indices.is_empty_spec(),
-            !indices.is_full_spec(),
-    {
-        Self {
-            write_idx: 0,
-            read_idx: 0,
-            capacity,
-        }
-    }
-
-    pub fn is_empty(&self) -> (result: bool)
-        requires self.valid(),
-        ensures result == self.is_empty_spec(),
-    {
-        self.write_idx == self.read_idx
-    }
-
-    pub fn is_full(&self) -> (result: bool)
-        requires self.valid(),
-        ensures result == self.is_full_spec(),
-    {
-        (self.write_idx + 1) % self.capacity == self.read_idx
-    }
-
-    pub fn has_data(&self) -> (result: bool)
-        requires self.valid(),
-        ensures result == !self.is_empty_spec(),
-    {
-        self.write_idx != self.read_idx
-    }
-
-    pub fn write_index(&self) -> (result: u32)
-        requires self.valid(),
-        ensures result == self.write_idx, result < self.capacity,
-    {
-        self.write_idx
-    }
-
-    pub fn read_index(&self) -> (result: u32)
-        requires self.valid(),
-        ensures result == self.read_idx, result < self.capacity,
-    {
-        self.read_idx
-    }
-
-    pub fn advance_write(&mut self)
-        requires
-            old(self).valid(),
-            !old(self).is_full_spec(),
-        ensures
-            self.valid(),
-            self.write_idx == (old(self).write_idx + 1) % old(self).capacity,
-            self.read_idx == old(self).read_idx,
-            self.capacity == old(self).capacity,
-    {
-        self.write_idx = (self.write_idx + 1) % self.capacity;
-    }
-
-    pub fn advance_read(&mut self)
-        requires
-            old(self).valid(),
-            !old(self).is_empty_spec(),
-        ensures
-            self.valid(),
-            self.read_idx == (old(self).read_idx + 1) % old(self).capacity,
-            self.write_idx == old(self).write_idx,
-            self.capacity == old(self).capacity,
-    {
-        self.read_idx = (self.read_idx + 1) % self.capacity;
-    }
-}
-
 pub const RING_BUFFER_VADDR: usize = 0x5_0400_0000;
 pub const RING_BUFFER_SIZE: usize = 0x1000;
 
@@ -256,6 +179,9 @@ pub const INPUT_PD_USB_REGS_BASE: usize = 0x5_0500_0000;
 pub const INPUT_PD_USB_REGS_SIZE: usize = 0x10000;
 pub const INPUT_PD_USB_DMA_BASE: usize = 0x5_0600_0000;
 pub const INPUT_PD_USB_DMA_SIZE: usize = 0x1000;
+/// System timer page, read-only: the counter entries are stamped with
+pub const INPUT_PD_SYSTIMER_BASE: usize = 0x5_0A00_0000;
+pub const INPUT_PD_SYSTIMER_SIZE: usize = 0x1000;
 
 pub open spec fn input_pd_can_access(address: usize) -> bool {
     (address >= INPUT_PD_UART_BASE && address < INPUT_PD_UART_BASE + INPUT_PD_UART_SIZE)
@@ -263,6 +189,8 @@ pub open spec fn input_pd_can_access(address: usize) -> bool {
             && address < INPUT_PD_USB_REGS_BASE + INPUT_PD_USB_REGS_SIZE)
         || (address >= INPUT_PD_USB_DMA_BASE
             && address < INPUT_PD_USB_DMA_BASE + INPUT_PD_USB_DMA_SIZE)
+        || (address >= INPUT_PD_SYSTIMER_BASE
+            && address < INPUT_PD_SYSTIMER_BASE + INPUT_PD_SYSTIMER_SIZE)
         || in_ring_buffer_region(address)
 }
 
@@ -285,67 +213,207 @@ pub open spec fn graphics_pd_can_access(address: usize) -> bool {
         || (address >= GRAPHICS_PD_DMA_BASE
             && address < GRAPHICS_PD_DMA_BASE + GRAPHICS_PD_DMA_SIZE)
         || in_ring_buffer_region(address)
+        || mpsc::in_source_rings_region(address)
+}
+
+/// Runtime check of [`input_pd_can_access`], for audit builds
+pub fn input_pd_may_access(address: usize) -> (r: bool)
+    ensures
+        r == input_pd_can_access(address),
+{
+    (address >= INPUT_PD_UART_BASE && address < INPUT_PD_UART_BASE + INPUT_PD_UART_SIZE)
+        || (address >= INPUT_PD_USB_REGS_BASE
+            && address < INPUT_PD_USB_REGS_BASE + INPUT_PD_USB_REGS_SIZE)
+        || (address >= INPUT_PD_USB_DMA_BASE
+            && address < INPUT_PD_USB_DMA_BASE + INPUT_PD_USB_DMA_SIZE)
+        || (address >= INPUT_PD_SYSTIMER_BASE
+            && address < INPUT_PD_SYSTIMER_BASE + INPUT_PD_SYSTIMER_SIZE)
+        || (address >= RING_BUFFER_VADDR && address < RING_BUFFER_VADDR + RING_BUFFER_SIZE)
+}
+
+/// Runtime check of [`graphics_pd_can_access`], for audit builds
+pub fn graphics_pd_may_access(address: usize) -> (r: bool)
+    ensures
+        r == graphics_pd_can_access(address),
+{
+    (address >= GRAPHICS_PD_MAILBOX_BASE
+        && address < GRAPHICS_PD_MAILBOX_BASE + GRAPHICS_PD_MAILBOX_SIZE)
+        || (address >= GRAPHICS_PD_GPIO_BASE
+            && address < GRAPHICS_PD_GPIO_BASE + GRAPHICS_PD_GPIO_SIZE)
+        || (address >= GRAPHICS_PD_FB_BASE
+            && address < GRAPHICS_PD_FB_BASE + GRAPHICS_PD_FB_SIZE)
+        || (address >= GRAPHICS_PD_DMA_BASE
+            && address < GRAPHICS_PD_DMA_BASE + GRAPHICS_PD_DMA_SIZE)
+        || (address >= RING_BUFFER_VADDR && address < RING_BUFFER_VADDR + RING_BUFFER_SIZE)
+        || (address >= mpsc::SOURCE_RINGS_VADDR
+            && address < mpsc::SOURCE_RINGS_VADDR + mpsc::SOURCE_RINGS_SIZE)
 }
 
 } // verus!
 
-/// Runtime ring-buffer header. The final word remains at offset 0x0c and is
-/// interpreted by `generation` without changing the legacy ABI.
+/// Index model of the input ring (see `verified_microkernel::ring`)
+pub type RingIndices = ring::RingIndices<{ RING_CAPACITY as usize }>;
+
+/// The input ring as a typed [`VerifiedRing`]
+pub type InputRing = VerifiedRing<InputRingEntry, { RING_CAPACITY as usize }>;
+
+/// Runtime ring-buffer header. The word at offset 0x0c is interpreted by
+/// `generation`; `dropped` and `next_seq` belong to the producer (see
+/// [`seq`]).
 #[repr(C, align(16))]
 pub struct InputRingHeader {
-    pub write_idx: AtomicU32,
-    pub read_idx: AtomicU32,
-    pub capacity: u32,
+    pub ring: RingHeader,
     _pad: u32,
+    /// Events the producer could not write, saturating at `u32::MAX`
+    pub dropped: AtomicU32,
+    /// Sequence number the producer's next event takes
+    pub next_seq: AtomicU32,
+    _reserved: [u32; 2],
 }
 
 impl InputRingHeader {
     /// # Safety
     /// `ptr` must be valid, writable, and aligned for `InputRingHeader`.
     pub unsafe fn init(ptr: *mut Self) {
-        (*ptr).write_idx = AtomicU32::new(0);
-        (*ptr).read_idx = AtomicU32::new(0);
-        (*ptr).capacity = RING_CAPACITY;
+        audit::audit_access(ptr, 1, Access::Write);
+        (*ptr).ring = RingHeader::new(RING_CAPACITY);
         (*ptr)._pad = 0;
+        (*ptr).dropped = AtomicU32::new(0);
+        (*ptr).next_seq = AtomicU32::new(0);
+        (*ptr)._reserved = [0; 2];
+    }
+
+    /// The ring with this header and the entries at `entries`
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entry array (see [`entries_ptr`]).
+    pub unsafe fn with_entries(&self, entries: *mut InputRingEntry) -> InputRing {
+        InputRing::new(&self.ring, entries)
     }
 
     pub fn has_data(&self) -> bool {
-        self.current_write_idx() != self.current_read_idx()
+        self.ring.len(RING_CAPACITY) > 0
     }
 
     pub fn is_full(&self) -> bool {
-        ((self.current_write_idx() + 1) % self.capacity) == self.current_read_idx()
+        self.ring.free_slots(RING_CAPACITY) == 0
     }
 
     pub fn current_write_idx(&self) -> u32 {
-        self.write_idx.load(Ordering::Acquire)
+        self.ring.write_idx.load(Ordering::Acquire)
     }
 
     pub fn current_read_idx(&self) -> u32 {
-        self.read_idx.load(Ordering::Acquire)
+        self.ring.read_idx.load(Ordering::Acquire)
     }
 
     pub fn advance_write(&self) {
-        let next = (self.current_write_idx() + 1) % self.capacity;
-        self.write_idx.store(next, Ordering::Release);
+        self.ring.advance_write(RING_CAPACITY);
     }
 
     pub fn advance_read(&self) {
-        let next = (self.current_read_idx() + 1) % self.capacity;
-        self.read_idx.store(next, Ordering::Release);
+        self.ring.advance_read(RING_CAPACITY);
+    }
+
+    /// Entries the writer can still add before the ring is full; a corrupt
+    /// header reads as full
+    pub fn free_slots(&self) -> u32 {
+        self.ring.free_slots(RING_CAPACITY)
+    }
+
+    /// Does the shared header still satisfy the ring invariant (the runtime
+    /// counterpart of `RingIndices::valid`)?
+    pub fn is_consistent(&self) -> bool {
+        self.indices().is_some()
+    }
+
+    /// Snapshot of the shared indices, or `None` if the header is corrupt
+    fn indices(&self) -> Option<(u32, u32, u32)> {
+        self.ring.indices(RING_CAPACITY)
+    }
+
+    /// Copy up to `out.len()` entries out of the ring, oldest first
+    ///
+    /// The indices are loaded once and the read index is published once for
+    /// the whole batch, instead of a fence and store per entry. Returns the
+    /// number of entries copied, never more than were waiting; a corrupt
+    /// header reads as empty.
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entry array (see [`entries_ptr`]).
+    pub unsafe fn pop_batch(&self, entries: *const InputRingEntry, out: &mut [InputRingEntry]) -> usize {
+        audit::audit_access(entries, RING_CAPACITY as usize, Access::Read);
+        self.with_entries(entries as *mut _).pop_batch(out)
+    }
+
+    /// Write as much of `batch` as fits, oldest first
+    ///
+    /// Each entry written is stamped with the next sequence number and the
+    /// write index is published once, after every entry is in place.
+    /// Returns the number written, never more than were free; the rest
+    /// take no numbers, so a producer that gives up on them should pass
+    /// their count to [`record_dropped`](Self::record_dropped).
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entry array (see [`entries_ptr`]).
+    pub unsafe fn push_batch(&self, entries: *mut InputRingEntry, batch: &[InputRingEntry]) -> usize {
+        audit::audit_access(entries, RING_CAPACITY as usize, Access::Write);
+        let seq = self.next_seq.load(Ordering::Relaxed);
+        let n = self
+            .with_entries(entries)
+            .push_batch_with(batch, |i, entry| InputRingEntry { seq: seq::seq_add(seq, i), ..entry });
+        self.next_seq.store(seq::seq_add(seq, n as u32), Ordering::Relaxed);
+        n
+    }
+
+    /// Write all of `batch`, or nothing if fewer slots are free
+    ///
+    /// As [`push_batch`](Self::push_batch), except that a batch that does
+    /// not fit still uses up its numbers and is added to `dropped`, so the
+    /// consumer sees the gap. Returns whether the batch was written.
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entry array (see [`entries_ptr`]).
+    pub unsafe fn write_batch(&self, entries: *mut InputRingEntry, batch: &[InputRingEntry]) -> bool {
+        // Only this producer adds entries, so the free count can only grow
+        // before the push
+        if (self.free_slots() as usize) < batch.len() {
+            self.record_dropped(batch.len().min(u32::MAX as usize) as u32);
+            return false;
+        }
+        self.push_batch(entries, batch);
+        true
+    }
+
+    /// Count `n` events the producer dropped; they use up sequence
+    /// numbers as if written, so the consumer sees the gap
+    pub fn record_dropped(&self, n: u32) {
+        let seq = self.next_seq.load(Ordering::Relaxed);
+        self.next_seq.store(seq::seq_add(seq, n), Ordering::Relaxed);
+        let dropped = self.dropped.load(Ordering::Relaxed);
+        self.dropped.store(seq::dropped_after(dropped, n), Ordering::Release);
+    }
+
+    /// Events the producer has dropped since the ring was initialized
+    pub fn dropped(&self) -> u32 {
+        self.dropped.load(Ordering::Acquire)
     }
 }
 
 /// # Safety
 /// `base` must be a valid shared-memory address with the protocol alignment.
 pub unsafe fn header_ptr(base: *mut u8) -> *mut InputRingHeader {
-    base as *mut InputRingHeader
+    let header = base as *mut InputRingHeader;
+    audit::audit_access(header, 1, Access::Read);
+    header
 }
 
 /// # Safety
 /// `base` must be a valid shared-memory address for the full ring region.
 pub unsafe fn entries_ptr(base: *mut u8) -> *mut InputRingEntry {
-    base.add(ENTRIES_OFFSET) as *mut InputRingEntry
+    let entries = base.add(ENTRIES_OFFSET) as *mut InputRingEntry;
+    audit::audit_access(entries, RING_CAPACITY as usize, Access::Read);
+    entries
 }
 
 pub use self::STATE_PRESSED as KeyStatePressed;
@@ -373,13 +441,35 @@ impl InputRingEntry {
             key_code: code,
             key_state: state as u8,
             modifiers,
+            seq: 0,
+            timestamp_us: 0,
         }
     }
+
+    /// A `None` entry, for filling read buffers
+    pub const EMPTY: Self =
+        Self { event_type: EVENT_NONE, key_code: 0, key_state: 0, modifiers: 0, seq: 0, timestamp_us: 0 };
+
+    /// This entry stamped with the time its event was decoded
+    pub const fn at(self, time: Timestamp) -> Self {
+        Self { timestamp_us: time.micros, ..self }
+    }
+
+    /// When the event was decoded, or `None` if the producer has no timer
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        (self.timestamp_us != 0).then_some(Timestamp::from_micros(self.timestamp_us))
+    }
 }
 
 mod generation_contract;
 mod generation;
+pub mod audit;
+pub mod mpsc;
+pub mod seq;
+pub mod timestamp;
 pub use generation::*;
+pub use seq::SeqTracker;
+pub use timestamp::Timestamp;
 
 #[cfg(test)]
 mod tests {
@@ -404,4 +494,161 @@ mod tests {
         indices.advance_read();
         assert!(indices.is_empty());
     }
+
+    #[test]
+    fn batch_indices_track_count() {
+        let mut indices = RingIndices::new(8);
+        assert_eq!(indices.free(), 7);
+        indices.advance_write_by(6);
+        assert_eq!(indices.len(), 6);
+        indices.advance_read_by(4);
+        indices.advance_write_by(5);
+        assert_eq!(indices.write_index(), 3);
+        assert_eq!(indices.len(), 7);
+        assert!(indices.is_full());
+        indices.advance_read_by(7);
+        assert!(indices.is_empty());
+    }
+
+    #[test]
+    fn header_consistency() {
+        #[repr(C, align(16))]
+        struct Region([u8; HEADER_SIZE]);
+        let mut region = Region([0; HEADER_SIZE]);
+        unsafe {
+            let header = header_ptr(region.0.as_mut_ptr());
+            InputRingHeader::init(header);
+            assert!((*header).is_consistent());
+            (*header).ring.write_idx.store(RING_CAPACITY, Ordering::Relaxed);
+            assert!(!(*header).is_consistent());
+            (*header).ring.write_idx.store(0, Ordering::Relaxed);
+            (*header).ring.capacity = 0;
+            assert!(!(*header).is_consistent());
+        }
+    }
+
+    #[test]
+    fn batch_read_write_wraps() {
+        #[repr(C, align(16))]
+        struct Region([u8; ENTRIES_OFFSET + 8 * ENTRY_SIZE]);
+        let mut region = Region([0; ENTRIES_OFFSET + 8 * ENTRY_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let header = unsafe { header_ptr(base) };
+        let key = |code| InputRingEntry::key(code, KeyState::Pressed, 0);
+        let mut out = [InputRingEntry::key(0, KeyState::Released, 0); 4];
+        unsafe {
+            InputRingHeader::init(header);
+            (*header).ring.capacity = 5;
+            let header = &*header;
+            let entries = entries_ptr(base);
+
+            assert!(header.write_batch(entries, &[key(1), key(2), key(3)]));
+            assert_eq!(header.free_slots(), 1);
+            assert_eq!(header.pop_batch(entries, &mut out[..2]), 2);
+            assert_eq!((out[0].key_code, out[1].key_code), (1, 2));
+
+            // Wraps past the end; a batch that does not fit is not written
+            assert!(header.write_batch(entries, &[key(4), key(5), key(6)]));
+            assert!(!header.write_batch(entries, &[key(7)]));
+            assert_eq!(header.pop_batch(entries, &mut out), 4);
+            let codes = out.map(|e| e.key_code);
+            assert_eq!(codes, [3, 4, 5, 6]);
+            assert!(!header.has_data());
+            assert_eq!(header.free_slots(), 4);
+            assert_eq!(header.pop_batch(entries, &mut out), 0);
+        }
+    }
+
+    #[test]
+    fn push_batch_writes_what_fits() {
+        #[repr(C, align(16))]
+        struct Region([u8; ENTRIES_OFFSET + 4 * ENTRY_SIZE]);
+        let mut region = Region([0; ENTRIES_OFFSET + 4 * ENTRY_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let header = unsafe { header_ptr(base) };
+        let key = |code| InputRingEntry::key(code, KeyState::Pressed, 0);
+        let mut out = [InputRingEntry::EMPTY; 4];
+        unsafe {
+            InputRingHeader::init(header);
+            (*header).ring.capacity = 4;
+            let header = &*header;
+            let entries = entries_ptr(base);
+
+            assert_eq!(header.push_batch(entries, &[key(1), key(2), key(3), key(4), key(5)]), 3);
+            assert_eq!(header.push_batch(entries, &[key(6)]), 0);
+            assert_eq!(header.dropped(), 0);
+            assert_eq!(header.pop_batch(entries, &mut out[..1]), 1);
+            assert_eq!(header.push_batch(entries, &[key(4), key(5)]), 1);
+
+            assert_eq!(header.pop_batch(entries, &mut out), 3);
+            assert_eq!([out[0].key_code, out[1].key_code, out[2].key_code], [2, 3, 4]);
+            // Entries left behind took no numbers
+            assert_eq!([out[0].seq, out[1].seq, out[2].seq], [1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn full_ring_drops_are_counted_and_numbered() {
+        #[repr(C, align(16))]
+        struct Region([u8; ENTRIES_OFFSET + 4 * ENTRY_SIZE]);
+        let mut region = Region([0; ENTRIES_OFFSET + 4 * ENTRY_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let header = unsafe { header_ptr(base) };
+        let key = |code| InputRingEntry::key(code, KeyState::Pressed, 0);
+        let mut out = [InputRingEntry::EMPTY; 4];
+        let mut tracker = SeqTracker::new();
+        unsafe {
+            InputRingHeader::init(header);
+            (*header).ring.capacity = 4;
+            let header = &*header;
+            let entries = entries_ptr(base);
+
+            assert!(header.write_batch(entries, &[key(1), key(2)]));
+            assert!(!header.write_batch(entries, &[key(3), key(4)]));
+            header.record_dropped(1);
+            assert!(header.write_batch(entries, &[key(5).at(Timestamp::from_micros(1_500))]));
+            assert_eq!(header.dropped(), 3);
+
+            assert_eq!(header.pop_batch(entries, &mut out), 3);
+            assert_eq!([out[0].seq, out[1].seq, out[2].seq], [0, 1, 5]);
+            assert_eq!(out[0].timestamp(), None);
+            assert_eq!(out[2].timestamp(), Some(Timestamp::from_micros(1_500)));
+            let gaps = [0, 1, 2].map(|i| tracker.observe(&out[i]));
+            assert_eq!(gaps, [0, 0, 3]);
+        }
+    }
+
+    #[test]
+    fn access_checks_match_regions() {
+        assert!(input_pd_may_access(RING_BUFFER_VADDR));
+        assert!(input_pd_may_access(RING_BUFFER_VADDR + RING_BUFFER_SIZE - 1));
+        assert!(!input_pd_may_access(RING_BUFFER_VADDR + RING_BUFFER_SIZE));
+        assert!(input_pd_may_access(INPUT_PD_USB_DMA_BASE));
+        assert!(!input_pd_may_access(GRAPHICS_PD_FB_BASE));
+        assert!(graphics_pd_may_access(GRAPHICS_PD_FB_BASE + GRAPHICS_PD_FB_SIZE - 1));
+        assert!(graphics_pd_may_access(RING_BUFFER_VADDR));
+        assert!(!graphics_pd_may_access(INPUT_PD_USB_REGS_BASE));
+        assert!(graphics_pd_may_access(mpsc::SOURCE_RINGS_VADDR + mpsc::SOURCE_RINGS_SIZE - 1));
+        assert!(!input_pd_may_access(mpsc::SOURCE_RINGS_VADDR));
+        assert!(input_pd_may_access(INPUT_PD_SYSTIMER_BASE + timestamp::SYSTIMER_CHI));
+        assert!(!graphics_pd_may_access(INPUT_PD_SYSTIMER_BASE));
+    }
+
+    #[test]
+    fn capacity_fits_ring_region() {
+        assert!(ENTRIES_OFFSET + RING_CAPACITY as usize * ENTRY_SIZE <= RING_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn pow2_indices_wrap() {
+        let mut indices = RingIndices::new(4);
+        for _ in 0..3 {
+            indices.advance_write();
+        }
+        assert!(indices.is_full());
+        indices.advance_read();
+        indices.advance_write();
+        assert_eq!(indices.write_index(), 0);
+        assert!(indices.is_full());
+    }
 }
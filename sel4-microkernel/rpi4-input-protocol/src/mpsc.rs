@@ -0,0 +1,326 @@
+//! Several input PDs feeding the graphics PD.
+//!
+//! The input ring is single-producer: one writer owns `write_idx`. Rather
+//! than share one ring between PDs (and make them race on that index), each
+//! source gets a ring of its own, in its own page, and the graphics PD
+//! reads them all through a [`MergedReader`]:
+//!
+//! ```text
+//! ┌──────────┐  source ring 0  ┐
+//! │ UART PD  │ ──────────────► │
+//! └──────────┘                 │  ┌──────────────┐
+//! ┌──────────┐  source ring 1  ├─►│ MergedReader │──► graphics PD
+//! │ Touch PD │ ──────────────► │  └──────────────┘
+//! └──────────┘                 │
+//! ┌──────────┐  source ring 2  │
+//! │ IR PD    │ ──────────────► ┘
+//! └──────────┘
+//! ```
+//!
+//! Each source ring is an ordinary [`InputRingHeader`] ring, so producers
+//! use the existing `push_batch` or `write_batch`. The reader takes entries round-robin,
+//! one per source per turn, so a chatty source cannot starve a quiet one,
+//! and resumes after the last source it served on the next call. Order is
+//! kept within a source, not across sources.
+//!
+//! ## Verification
+//!
+//! The source rings are `MAX_SOURCES` consecutive ring-sized pages. Verus
+//! proves [`source_ring_base`] lands in the caller's own page and that no
+//! two sources' pages overlap, so a producer mapped only its own page
+//! cannot touch another source's ring.
+
+use core::sync::atomic::Ordering;
+
+use rpi4_pd_error::audit::Access;
+use verified_microkernel::{ring_add, ring_len, ring_next};
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::{audit, entries_ptr, header_ptr, InputRingEntry, InputRingHeader, RING_BUFFER_SIZE};
+
+verus! {
+
+/// Most input sources the graphics PD merges
+pub const MAX_SOURCES: u8 = 4;
+
+pub const SOURCE_UART: u8 = 0;
+pub const SOURCE_TOUCH: u8 = 1;
+pub const SOURCE_IR: u8 = 2;
+pub const SOURCE_USB: u8 = 3;
+
+/// Source rings: one `RING_BUFFER_SIZE` page per source, in source order
+pub const SOURCE_RINGS_VADDR: usize = 0x5_0F00_0000;
+pub const SOURCE_RINGS_SIZE: usize = 0x4000;
+
+pub open spec fn in_source_rings_region(addr: usize) -> bool {
+    addr >= SOURCE_RINGS_VADDR && addr < SOURCE_RINGS_VADDR + SOURCE_RINGS_SIZE
+}
+
+/// The page of source `source`'s ring
+pub open spec fn in_source_ring(source: u8, addr: usize) -> bool {
+    addr >= SOURCE_RINGS_VADDR + source as usize * RING_BUFFER_SIZE
+        && addr < SOURCE_RINGS_VADDR + (source as usize + 1) * RING_BUFFER_SIZE
+}
+
+/// Specification: what this protocol grants the producer for `source`
+pub open spec fn source_producer_can_access(source: u8, addr: usize) -> bool {
+    source < MAX_SOURCES && in_source_ring(source, addr)
+}
+
+/// Base of source `source`'s ring
+pub fn source_ring_base(source: u8) -> (base: usize)
+    requires
+        source < MAX_SOURCES,
+    ensures
+        base == SOURCE_RINGS_VADDR + source as usize * RING_BUFFER_SIZE,
+        in_source_ring(source, base),
+        in_source_rings_region(base),
+        in_source_rings_region((base + RING_BUFFER_SIZE - 1) as usize),
+{
+    SOURCE_RINGS_VADDR + source as usize * RING_BUFFER_SIZE
+}
+
+/// Source after `source` among the first `count`, wrapping to 0
+pub fn next_source(source: u8, count: u8) -> (next: u8)
+    requires
+        source < count,
+        count <= MAX_SOURCES,
+    ensures
+        next < count,
+        next == (source + 1) % (count as int),
+{
+    ring_next(source as u32, count as u32) as u8
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: each source's page lies inside the source rings region
+proof fn source_rings_fit_region()
+    ensures
+        forall|s: u8, addr: usize| source_producer_can_access(s, addr) ==> in_source_rings_region(addr)
+{
+}
+
+/// Prove: no address is granted to two sources
+proof fn source_rings_disjoint()
+    ensures
+        forall|s1: u8, s2: u8, addr: usize|
+            s1 != s2 && source_producer_can_access(s1, addr) ==> !source_producer_can_access(s2, addr)
+{
+    assert forall|s1: u8, s2: u8, addr: usize|
+        s1 != s2 && source_producer_can_access(s1, addr) implies !source_producer_can_access(s2, addr) by {
+        if s1 < s2 {
+            assert((s1 as usize + 1) * RING_BUFFER_SIZE <= s2 as usize * RING_BUFFER_SIZE) by (nonlinear_arith)
+                requires s1 < s2;
+        } else {
+            assert((s2 as usize + 1) * RING_BUFFER_SIZE <= s1 as usize * RING_BUFFER_SIZE) by (nonlinear_arith)
+                requires s2 < s1;
+        }
+    }
+}
+
+} // verus!
+
+/// An entry and the source ring it came from
+#[derive(Clone, Copy, Debug)]
+pub struct SourcedEntry {
+    pub source: u8,
+    pub entry: InputRingEntry,
+}
+
+impl SourcedEntry {
+    pub const EMPTY: SourcedEntry = SourcedEntry {
+        source: 0,
+        entry: InputRingEntry::EMPTY,
+    };
+}
+
+/// One source's ring as the reader sees it
+#[derive(Clone, Copy)]
+pub struct SourceRing {
+    header: *const InputRingHeader,
+    entries: *const InputRingEntry,
+}
+
+impl SourceRing {
+    /// The ring laid out at `base`
+    ///
+    /// # Safety
+    /// `base` must be a mapped, initialized ring region (see
+    /// [`InputRingHeader::init`]) that outlives the returned value.
+    pub unsafe fn at(base: *mut u8) -> Self {
+        Self { header: header_ptr(base), entries: entries_ptr(base) }
+    }
+
+    /// Source `source`'s ring at its address in the graphics PD
+    ///
+    /// # Safety
+    /// The source rings must be mapped at [`SOURCE_RINGS_VADDR`] and
+    /// initialized.
+    pub unsafe fn mapped(source: u8) -> Option<Self> {
+        (source < MAX_SOURCES).then(|| Self::at(source_ring_base(source) as *mut u8))
+    }
+}
+
+/// Round-robin reader over up to [`MAX_SOURCES`] source rings
+pub struct MergedReader {
+    /// Source the next call serves first
+    next: u8,
+}
+
+impl MergedReader {
+    pub const fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    /// Copy up to `out.len()` entries out of `sources`, one per source per
+    /// turn, oldest first within each source
+    ///
+    /// Each source's indices are loaded once and its read index published
+    /// once, as in [`InputRingHeader::pop_batch`]. Sources past
+    /// [`MAX_SOURCES`] are ignored; a corrupt header reads as empty.
+    /// Returns the number of entries copied.
+    ///
+    /// # Safety
+    /// Every ring in `sources` must still be mapped, and this must be its
+    /// only reader.
+    pub unsafe fn read(&mut self, sources: &[SourceRing], out: &mut [SourcedEntry]) -> usize {
+        let count = sources.len().min(MAX_SOURCES as usize) as u8;
+        if count == 0 {
+            return 0;
+        }
+        if self.next >= count {
+            self.next = 0;
+        }
+
+        // (read index, capacity, waiting, taken) per source
+        let mut rings = [(0u32, 1u32, 0u32, 0u32); MAX_SOURCES as usize];
+        for (ring, source) in rings.iter_mut().zip(&sources[..count as usize]) {
+            if let Some((write, read, capacity)) = (*source.header).indices() {
+                *ring = (read, capacity, ring_len(write, read, capacity), 0);
+            }
+        }
+
+        let mut n = 0;
+        let mut source = self.next;
+        let mut idle = 0;
+        while n < out.len() && idle < count {
+            let (read, capacity, waiting, taken) = &mut rings[source as usize];
+            if *taken < *waiting {
+                let idx = ring_add(*read, *taken, *capacity);
+                let entries = sources[source as usize].entries;
+                audit::audit_access(entries.add(idx as usize), 1, Access::Read);
+                out[n] = SourcedEntry { source, entry: entries.add(idx as usize).read_volatile() };
+                *taken += 1;
+                n += 1;
+                idle = 0;
+                self.next = next_source(source, count);
+            } else {
+                idle += 1;
+            }
+            source = next_source(source, count);
+        }
+
+        // Entries are copied out before the slots are handed back
+        for (&(read, capacity, _, taken), source) in rings.iter().zip(&sources[..count as usize]) {
+            if taken > 0 {
+                (*source.header).ring.read_idx.store(ring_add(read, taken, capacity), Ordering::Release);
+            }
+        }
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyState, ENTRIES_OFFSET, ENTRY_SIZE};
+
+    const REGION_SIZE: usize = ENTRIES_OFFSET + 8 * ENTRY_SIZE;
+
+    #[repr(C, align(16))]
+    struct Region([u8; REGION_SIZE]);
+
+    impl Region {
+        fn new() -> Self {
+            Region([0; REGION_SIZE])
+        }
+    }
+
+    fn key(code: u8) -> InputRingEntry {
+        InputRingEntry::key(code, KeyState::Pressed, 0)
+    }
+
+    fn ring(region: &mut Region) -> SourceRing {
+        unsafe {
+            let header = header_ptr(region.0.as_mut_ptr());
+            InputRingHeader::init(header);
+            (*header).ring.capacity = 8;
+            SourceRing::at(region.0.as_mut_ptr())
+        }
+    }
+
+    fn write(ring: SourceRing, codes: &[u8]) -> bool {
+        let batch: [InputRingEntry; 8] = core::array::from_fn(|i| key(codes.get(i).copied().unwrap_or(0)));
+        unsafe { (*ring.header).write_batch(ring.entries as *mut _, &batch[..codes.len()]) }
+    }
+
+    #[test]
+    fn sources_are_served_in_turn() {
+        let (mut a, mut b, mut c) = (Region::new(), Region::new(), Region::new());
+        let rings = [ring(&mut a), ring(&mut b), ring(&mut c)];
+        assert!(write(rings[0], &[1, 2, 3, 4]));
+        assert!(write(rings[2], &[31]));
+        let mut reader = MergedReader::new();
+        let mut out = [SourcedEntry::EMPTY; 3];
+
+        // A busy source 0 does not hold back source 2
+        assert_eq!(unsafe { reader.read(&rings, &mut out) }, 3);
+        let got = out.map(|e| (e.source, e.entry.key_code));
+        assert_eq!(got, [(0, 1), (2, 31), (0, 2)]);
+
+        // The next call starts after the last source served
+        assert!(write(rings[1], &[21, 22]));
+        assert_eq!(unsafe { reader.read(&rings, &mut out) }, 3);
+        let got = out.map(|e| (e.source, e.entry.key_code));
+        assert_eq!(got, [(1, 21), (0, 3), (1, 22)]);
+        assert_eq!(unsafe { reader.read(&rings, &mut out) }, 1);
+        assert_eq!((out[0].source, out[0].entry.key_code), (0, 4));
+        assert_eq!(unsafe { reader.read(&rings, &mut out) }, 0);
+
+        // Every slot was handed back
+        for ring in rings {
+            assert_eq!(unsafe { (*ring.header).free_slots() }, 7);
+        }
+    }
+
+    #[test]
+    fn corrupt_sources_read_as_empty() {
+        let (mut a, mut b) = (Region::new(), Region::new());
+        let rings = [ring(&mut a), ring(&mut b)];
+        assert!(write(rings[0], &[1]));
+        assert!(write(rings[1], &[2]));
+        unsafe { (*rings[0].header).ring.write_idx.store(99, Ordering::Relaxed) };
+        let mut out = [SourcedEntry::EMPTY; 4];
+        let mut reader = MergedReader::new();
+        assert_eq!(unsafe { reader.read(&rings, &mut out) }, 1);
+        assert_eq!(out[0].source, 1);
+        assert_eq!(unsafe { reader.read(&[], &mut out) }, 0);
+    }
+
+    #[test]
+    fn source_rings_are_separate_pages() {
+        for source in 0..MAX_SOURCES {
+            let base = source_ring_base(source);
+            assert_eq!(base, SOURCE_RINGS_VADDR + source as usize * RING_BUFFER_SIZE);
+            assert!(base + RING_BUFFER_SIZE <= SOURCE_RINGS_VADDR + SOURCE_RINGS_SIZE);
+        }
+        assert_eq!(next_source(2, 3), 0);
+        assert_eq!(next_source(0, 3), 1);
+    }
+}
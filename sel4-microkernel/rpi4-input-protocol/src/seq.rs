@@ -0,0 +1,156 @@
+//! Sequence numbers and drop accounting for the input ring.
+//!
+//! A producer that finds the ring full used to drop the event and say
+//! nothing. Now every event a producer emits, written or not, takes the
+//! next number from the `next_seq` word in the ring header, and every one
+//! it could not write adds to the header's `dropped` counter. The consumer
+//! feeds what it reads through a [`SeqTracker`], which reports how many
+//! numbers were skipped since the last entry, so it can tell "no input"
+//! from "input lost" and say where.
+//!
+//! Sequence numbers are 32 bits and wrap, so "later" is serial-number
+//! arithmetic (RFC 1982): `a` is newer than `b` when `a - b`, mod 2^32, is
+//! nonzero and below 2^31. At the ring's rate that is weeks of typing
+//! between any two entries a consumer compares.
+//!
+//! ## Verification
+//!
+//! Verus proves that [`seq_add`] by any count below 2^31 gives a newer
+//! number, so the entries of one producer are strictly increasing in that
+//! order however often the counter wraps, and that [`seq_gap`] recovers
+//! exactly the count between two numbers. [`dropped_after`] is proven never
+//! to decrease: the counter saturates at `u32::MAX` rather than wrapping
+//! back to a small count that would hide losses.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::InputRingEntry;
+
+verus! {
+
+/// Half the sequence space: the furthest apart two comparable numbers are
+pub const SEQ_HALF: u32 = 0x8000_0000;
+
+/// `a` comes after `b` in serial-number order
+pub open spec fn seq_newer(a: u32, b: u32) -> bool {
+    a != b && ((a as int - b as int) % 0x1_0000_0000) < SEQ_HALF as int
+}
+
+/// The number `n` after `seq`
+pub fn seq_add(seq: u32, n: u32) -> (r: u32)
+    ensures
+        r as int == (seq as int + n as int) % 0x1_0000_0000,
+        0 < n < SEQ_HALF ==> seq_newer(r, seq),
+{
+    seq.wrapping_add(n)
+}
+
+/// How many numbers after `expected` `got` is: 0 when it is the one
+/// expected, `k` when `k` events in between never arrived
+pub fn seq_gap(expected: u32, got: u32) -> (r: u32)
+    ensures
+        r as int == (got as int - expected as int) % 0x1_0000_0000,
+        (expected as int + r as int) % 0x1_0000_0000 == got as int,
+{
+    got.wrapping_sub(expected)
+}
+
+/// The dropped-event counter after `n` more drops: it only grows, and
+/// stops at `u32::MAX` instead of wrapping
+pub fn dropped_after(count: u32, n: u32) -> (r: u32)
+    ensures
+        r >= count,
+        count as int + n as int <= u32::MAX as int ==> r as int == count as int + n as int,
+        count as int + n as int > u32::MAX as int ==> r == u32::MAX,
+{
+    count.saturating_add(n)
+}
+
+/// Prove: the numbers a producer hands out are strictly increasing, for
+/// any two within half the sequence space of each other
+proof fn seq_strictly_increasing(start: u32, i: u32, j: u32)
+    requires
+        i < j,
+        j - i < SEQ_HALF,
+    ensures
+        seq_newer(
+            ((start as int + j as int) % 0x1_0000_0000) as u32,
+            ((start as int + i as int) % 0x1_0000_0000) as u32,
+        ),
+{
+}
+
+} // verus!
+
+/// Where a consumer is in a producer's sequence
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeqTracker {
+    /// Number the next entry should carry; `None` before the first
+    expected: Option<u32>,
+    /// Entries reported missing so far (saturating)
+    missed: u32,
+}
+
+impl SeqTracker {
+    pub const fn new() -> Self {
+        Self { expected: None, missed: 0 }
+    }
+
+    /// Note an entry read from the ring; returns how many entries were
+    /// lost just before it. The first entry seen only sets the start.
+    pub fn observe(&mut self, entry: &InputRingEntry) -> u32 {
+        let gap = self.expected.map_or(0, |expected| seq_gap(expected, entry.seq));
+        // A number from the past (the producer was reset) restarts the count
+        let gap = if gap >= SEQ_HALF { 0 } else { gap };
+        self.expected = Some(seq_add(entry.seq, 1));
+        self.missed = dropped_after(self.missed, gap);
+        gap
+    }
+
+    /// Entries reported missing since the tracker was made
+    pub fn missed(&self) -> u32 {
+        self.missed
+    }
+}
+
+impl Default for SeqTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyState;
+
+    fn entry(seq: u32) -> InputRingEntry {
+        InputRingEntry { seq, ..InputRingEntry::key(1, KeyState::Pressed, 0) }
+    }
+
+    #[test]
+    fn gaps_are_counted_across_the_wrap() {
+        let mut tracker = SeqTracker::new();
+        assert_eq!(tracker.observe(&entry(u32::MAX - 1)), 0);
+        assert_eq!(tracker.observe(&entry(u32::MAX)), 0);
+        assert_eq!(tracker.observe(&entry(0)), 0);
+        assert_eq!(tracker.observe(&entry(4)), 3);
+        // An old number restarts rather than counting 2^32 - 2 losses
+        assert_eq!(tracker.observe(&entry(2)), 0);
+        assert_eq!(tracker.observe(&entry(3)), 0);
+        assert_eq!(tracker.missed(), 3);
+    }
+
+    #[test]
+    fn counters_never_wrap_back() {
+        assert_eq!(dropped_after(5, 3), 8);
+        assert_eq!(dropped_after(u32::MAX - 1, 3), u32::MAX);
+        assert_eq!(dropped_after(u32::MAX, u32::MAX), u32::MAX);
+        assert_eq!(seq_add(u32::MAX, 2), 1);
+        assert_eq!(seq_gap(u32::MAX, 1), 2);
+        assert_eq!(seq_gap(7, 7), 0);
+    }
+}
@@ -0,0 +1,154 @@
+//! Event timestamps from the BCM2711 system timer.
+//!
+//! The system timer is a free-running 1 MHz counter, 64 bits across `CLO`
+//! and `CHI`, which does not wrap in the life of the board. The Input PD
+//! maps its register page read-only and stamps every entry with the
+//! counter at the moment the event was decoded, so a consumer can measure
+//! the time between two events (double-click, key repeat) or, if it maps
+//! the timer too, how long an event waited in the ring.
+//!
+//! A timestamp of 0 means the producer had no timer to read; see
+//! [`InputRingEntry::timestamp`](crate::InputRingEntry::timestamp).
+//!
+//! ## Verification
+//!
+//! Verus proves that [`Timestamp::checked_since`] returns the exact
+//! difference when the earlier stamp really is earlier and `None`
+//! otherwise, rather than a wrapped `u64` that would read as 584 000
+//! years, and that deltas along a run of ordered stamps add up.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use rpi4_pd_error::audit::Access;
+
+use crate::audit;
+
+verus! {
+
+/// Counter, low 32 bits
+pub const SYSTIMER_CLO: usize = 0x04;
+/// Counter, high 32 bits
+pub const SYSTIMER_CHI: usize = 0x08;
+
+/// A reading of the system timer, in microseconds since it started
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp {
+    pub micros: u64,
+}
+
+impl Timestamp {
+    pub const fn from_micros(micros: u64) -> (t: Self)
+        ensures t.micros == micros,
+    {
+        Self { micros }
+    }
+
+    /// Microseconds from `earlier` to `self`, or `None` if `earlier` is
+    /// in fact later (stamps from another board, or out of order)
+    pub fn checked_since(&self, earlier: Timestamp) -> (r: Option<u64>)
+        ensures
+            earlier.micros <= self.micros ==> r == Some((self.micros - earlier.micros) as u64),
+            earlier.micros > self.micros ==> r.is_none(),
+    {
+        if earlier.micros <= self.micros {
+            Some(self.micros - earlier.micros)
+        } else {
+            None
+        }
+    }
+
+    /// Is `self` no more than `window_us` after `earlier` (and not before
+    /// it)?
+    pub fn within(&self, earlier: Timestamp, window_us: u64) -> (r: bool)
+        ensures
+            r == (earlier.micros <= self.micros && self.micros - earlier.micros <= window_us),
+    {
+        match self.checked_since(earlier) {
+            Some(delta) => delta <= window_us,
+            None => false,
+        }
+    }
+
+    /// `us` microseconds later, or `None` past the end of the counter
+    pub fn checked_add(&self, us: u64) -> (r: Option<Timestamp>)
+        ensures
+            self.micros as int + us as int <= u64::MAX as int
+                ==> r == Some(Timestamp { micros: (self.micros + us) as u64 }),
+            self.micros as int + us as int > u64::MAX as int ==> r.is_none(),
+    {
+        match self.micros.checked_add(us) {
+            Some(micros) => Some(Timestamp { micros }),
+            None => None,
+        }
+    }
+}
+
+/// Prove: the time from `a` to `c` is the time from `a` to `b` plus the
+/// time from `b` to `c`, so per-event deltas sum to the span of a run
+proof fn deltas_add_up(a: Timestamp, b: Timestamp, c: Timestamp)
+    requires
+        a.micros <= b.micros,
+        b.micros <= c.micros,
+    ensures
+        (c.micros - a.micros) == (b.micros - a.micros) + (c.micros - b.micros),
+{
+}
+
+} // verus!
+
+/// Read the system timer
+///
+/// `CHI` is read on both sides of `CLO`, so a carry between the two reads
+/// is caught and the read retried.
+///
+/// # Safety
+/// `base` must be the mapped system timer register page. Reading the
+/// counter has no side effects, so a read-only mapping is enough.
+pub unsafe fn now(base: usize) -> Timestamp {
+    let read = |offset: usize| {
+        let reg = (base + offset) as *const u32;
+        audit::audit_access(reg, 1, Access::Read);
+        core::ptr::read_volatile(reg)
+    };
+    loop {
+        let hi = read(SYSTIMER_CHI);
+        let lo = read(SYSTIMER_CLO);
+        if read(SYSTIMER_CHI) == hi {
+            return Timestamp::from_micros(((hi as u64) << 32) | lo as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_are_checked() {
+        let a = Timestamp::from_micros(1_000);
+        let b = Timestamp::from_micros(251_000);
+        assert_eq!(b.checked_since(a), Some(250_000));
+        assert_eq!(a.checked_since(b), None);
+        assert_eq!(a.checked_since(a), Some(0));
+        assert!(b.within(a, 250_000));
+        assert!(!b.within(a, 249_999));
+        assert!(!a.within(b, u64::MAX));
+        assert_eq!(a.checked_add(250_000), Some(b));
+        assert_eq!(Timestamp::from_micros(u64::MAX).checked_add(1), None);
+    }
+
+    #[test]
+    fn counter_read_joins_both_words() {
+        #[repr(C, align(4))]
+        struct Regs([u32; 4]);
+        let mut regs = Regs([0, 0xffff_fff0, 6, 0]);
+        let base = regs.0.as_mut_ptr() as usize;
+        assert_eq!(unsafe { now(base) }, Timestamp::from_micros((6 << 32) | 0xffff_fff0));
+        regs.0[1] = 0;
+        regs.0[2] = 7;
+        assert_eq!(unsafe { now(base) }, Timestamp::from_micros(7 << 32));
+    }
+}
@@ -0,0 +1,333 @@
+//! GPIO push-button input driver
+//!
+//! Polls a small set of BCM2711 GPIO pins wired to push buttons and turns
+//! level changes into [`KeyEvent`]s, for builds (e.g. a photo frame) that
+//! only have a handful of physical buttons and no keyboard.
+//!
+//! Buttons are normally wired between the pin and ground with the internal
+//! pull-up enabled, so a pressed button reads low ([`ButtonPin::to_ground`]).
+//!
+//! Levels are reported raw, one change per [`poll`](GpioButtons::poll).
+//! Mechanical contacts bounce; [`InputManager`](crate::InputManager)
+//! debounces this source with [`InputTiming::button_debounce`], and
+//! standalone users can run the events through a [`Debouncer`].
+//!
+//! [`InputTiming::button_debounce`]: crate::InputTiming::button_debounce
+//! [`Debouncer`]: crate::Debouncer
+
+use core::ptr::{read_volatile, write_volatile};
+use crate::keyboard::{KeyCode, KeyEvent, KeyModifiers, KeyState};
+
+/// GPIO register block base address (BCM2711)
+/// Physical: 0xFE200000
+/// Must be mapped by Microkit system file
+pub const GPIO_BASE: usize = 0xFE20_0000;
+
+/// Highest GPIO number on the BCM2711
+pub const MAX_GPIO: u8 = 57;
+
+/// Maximum number of buttons one [`GpioButtons`] watches
+pub const MAX_BUTTONS: usize = 8;
+
+/// GPIO register offsets
+const GPFSEL0: usize = 0x00;          // Function Select 0 (pins 0-9)
+const GPLEV0: usize = 0x34;           // Pin Level 0 (pins 0-31)
+const GPIO_PUP_PDN_CNTRL0: usize = 0xE4; // Pull-up/down control 0 (pins 0-15)
+
+/// Internal pull resistor setting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pull {
+    /// No pull resistor (external resistor fitted)
+    None = 0,
+    /// Pull up to 3.3 V
+    Up = 1,
+    /// Pull down to ground
+    Down = 2,
+}
+
+/// One button: which pin, which key it stands for and how it is wired
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ButtonPin {
+    /// BCM GPIO number (0..=57)
+    pub pin: u8,
+    /// Key reported for this button
+    pub key: KeyCode,
+    /// Pull resistor to enable
+    pub pull: Pull,
+    /// Pressed when the pin reads low
+    pub active_low: bool,
+}
+
+impl ButtonPin {
+    /// Button between the pin and ground, using the internal pull-up
+    pub const fn to_ground(pin: u8, key: KeyCode) -> Self {
+        Self { pin, key, pull: Pull::Up, active_low: true }
+    }
+
+    /// Button between the pin and 3.3 V, using the internal pull-down
+    pub const fn to_supply(pin: u8, key: KeyCode) -> Self {
+        Self { pin, key, pull: Pull::Down, active_low: false }
+    }
+}
+
+/// Fixed-capacity set of button pins (at most [`MAX_BUTTONS`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ButtonPins {
+    pins: [Option<ButtonPin>; MAX_BUTTONS],
+    len: usize,
+}
+
+impl ButtonPins {
+    /// No buttons
+    pub const EMPTY: Self = Self { pins: [None; MAX_BUTTONS], len: 0 };
+
+    /// Four buttons on GPIO 5, 6, 13 and 19 (free on the common display
+    /// HATs) for Left, Right, Enter and Escape, wired to ground
+    pub const PHOTO_FRAME: Self = Self::from_slice(&[
+        ButtonPin::to_ground(5, KeyCode::Left),
+        ButtonPin::to_ground(6, KeyCode::Right),
+        ButtonPin::to_ground(13, KeyCode::Enter),
+        ButtonPin::to_ground(19, KeyCode::Escape),
+    ]);
+
+    /// Collect pins from a slice. Pins past [`MAX_BUTTONS`], pins above
+    /// [`MAX_GPIO`] and repeated pins are ignored.
+    pub const fn from_slice(pins: &[ButtonPin]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < pins.len() {
+            set = set.with(pins[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Same set plus `button` (ignored if full, out of range or a repeat)
+    pub const fn with(mut self, button: ButtonPin) -> Self {
+        if self.len == MAX_BUTTONS || button.pin > MAX_GPIO {
+            return self;
+        }
+        let mut i = 0;
+        while i < self.len {
+            if let Some(existing) = self.pins[i] {
+                if existing.pin == button.pin {
+                    return self;
+                }
+            }
+            i += 1;
+        }
+        self.pins[self.len] = Some(button);
+        self.len += 1;
+        self
+    }
+
+    /// Number of buttons
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if there are no buttons
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the buttons
+    pub fn iter(&self) -> impl Iterator<Item = &ButtonPin> {
+        self.pins[..self.len].iter().flatten()
+    }
+}
+
+impl Default for ButtonPins {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// GPIO push-button driver
+pub struct GpioButtons {
+    base: usize,
+    pins: ButtonPins,
+    /// Pressed state last reported, bit i for button i
+    pressed: u8,
+}
+
+impl GpioButtons {
+    /// Create a driver for `pins` with the GPIO block at virtual `base`.
+    /// Call [`init`](Self::init) before polling.
+    pub const fn new(base: usize, pins: ButtonPins) -> Self {
+        Self { base, pins, pressed: 0 }
+    }
+
+    /// Configure every button pin as an input with its pull resistor, and
+    /// take the current levels as the starting state (a button held at
+    /// boot is not reported as a press).
+    pub fn init(&mut self) {
+        for button in self.pins.iter() {
+            let pin = button.pin as usize;
+
+            // Function select: 3 bits per pin, 000 = input
+            let fsel = GPFSEL0 + (pin / 10) * 4;
+            let shift = (pin % 10) * 3;
+            let value = self.read_reg(fsel) & !(0b111 << shift);
+            self.write_reg(fsel, value);
+
+            // BCM2711 pull control: 2 bits per pin, takes effect directly
+            let pull = GPIO_PUP_PDN_CNTRL0 + (pin / 16) * 4;
+            let shift = (pin % 16) * 2;
+            let value = (self.read_reg(pull) & !(0b11 << shift)) | ((button.pull as u32) << shift);
+            self.write_reg(pull, value);
+        }
+        self.pressed = self.sample();
+    }
+
+    /// Report the next button that changed state since the last report.
+    ///
+    /// Reads the pin levels on every call; if several buttons changed, the
+    /// others are reported by the following calls.
+    pub fn poll(&mut self) -> Option<KeyEvent> {
+        let changed = self.sample() ^ self.pressed;
+        if changed == 0 {
+            return None;
+        }
+        let index = changed.trailing_zeros() as usize;
+        let button = self.pins.pins[index]?;
+        self.pressed ^= 1 << index;
+
+        let state = if self.pressed & (1 << index) != 0 {
+            KeyState::Pressed
+        } else {
+            KeyState::Released
+        };
+        Some(KeyEvent { key: button.key, state, modifiers: KeyModifiers::default() })
+    }
+
+    /// Check if any button is currently held
+    pub fn has_input(&self) -> bool {
+        self.pressed != 0
+    }
+
+    /// The buttons being watched
+    pub fn pins(&self) -> &ButtonPins {
+        &self.pins
+    }
+
+    /// Current pressed state of every button, bit i for button i
+    fn sample(&self) -> u8 {
+        let levels = self.read_reg(GPLEV0) as u64 | ((self.read_reg(GPLEV0 + 4) as u64) << 32);
+        pressed_mask(&self.pins, levels)
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { write_volatile((self.base + offset) as *mut u32, value) }
+    }
+}
+
+/// Pressed bitmap for `pins` given the 58 pin levels (bit n = GPIO n high)
+fn pressed_mask(pins: &ButtonPins, levels: u64) -> u8 {
+    let mut mask = 0u8;
+    for (i, button) in pins.iter().enumerate() {
+        let high = levels & (1u64 << button.pin) != 0;
+        if high != button.active_low {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_set_rejects_repeats_and_out_of_range() {
+        let pins = ButtonPins::from_slice(&[
+            ButtonPin::to_ground(5, KeyCode::Left),
+            ButtonPin::to_ground(5, KeyCode::Right),
+            ButtonPin::to_ground(60, KeyCode::Enter),
+            ButtonPin::to_supply(27, KeyCode::Escape),
+        ]);
+        assert_eq!(pins.len(), 2);
+        let keys: [KeyCode; 2] = core::array::from_fn(|i| pins.pins[i].unwrap().key);
+        assert_eq!(keys, [KeyCode::Left, KeyCode::Escape]);
+        assert_eq!(ButtonPins::PHOTO_FRAME.len(), 4);
+    }
+
+    #[test]
+    fn pressed_mask_honours_polarity() {
+        let pins = ButtonPins::from_slice(&[
+            ButtonPin::to_ground(5, KeyCode::Left),
+            ButtonPin::to_supply(40, KeyCode::Right),
+        ]);
+        // Idle: pull-up keeps GPIO5 high, pull-down keeps GPIO40 low
+        assert_eq!(pressed_mask(&pins, 1 << 5), 0b00);
+        assert_eq!(pressed_mask(&pins, 0), 0b01);
+        assert_eq!(pressed_mask(&pins, (1 << 5) | (1 << 40)), 0b10);
+    }
+
+    #[test]
+    fn poll_reports_each_change_once() {
+        // Fake register block: GPLEV0/1 live at 0x34/0x38
+        let mut regs = [0u32; 64];
+        let pins = ButtonPins::from_slice(&[
+            ButtonPin::to_ground(2, KeyCode::Left),
+            ButtonPin::to_ground(3, KeyCode::Right),
+        ]);
+        regs[GPLEV0 / 4] = 0b1100;
+        let mut buttons = GpioButtons::new(regs.as_mut_ptr() as usize, pins);
+        buttons.init();
+        assert_eq!(regs[GPIO_PUP_PDN_CNTRL0 / 4], 0b0101_0000);
+        assert_eq!(buttons.poll(), None);
+
+        // Both pressed: two events, then quiet
+        unsafe { write_volatile(regs.as_mut_ptr().add(GPLEV0 / 4), 0) };
+        let a = buttons.poll().unwrap();
+        let b = buttons.poll().unwrap();
+        assert_eq!((a.key, a.state), (KeyCode::Left, KeyState::Pressed));
+        assert_eq!((b.key, b.state), (KeyCode::Right, KeyState::Pressed));
+        assert_eq!(buttons.poll(), None);
+        assert!(buttons.has_input());
+
+        unsafe { write_volatile(regs.as_mut_ptr().add(GPLEV0 / 4), 0b0100) };
+        let c = buttons.poll().unwrap();
+        assert_eq!((c.key, c.state), (KeyCode::Left, KeyState::Released));
+        assert_eq!(buttons.poll(), None);
+    }
+
+    #[test]
+    fn manager_debounces_button_bounce() {
+        use crate::{InputEvent, InputManager, InputTiming, RemoteOptions};
+
+        let mut regs = [0u32; 64];
+        regs[GPLEV0 / 4] = 1 << 5;
+        let lev = unsafe { regs.as_mut_ptr().add(GPLEV0 / 4) };
+        let timing = InputTiming { button_debounce: 3, ..InputTiming::DEFAULT.without_repeat() };
+        let pins = ButtonPins::from_slice(&[ButtonPin::to_ground(5, KeyCode::Enter)]);
+        let mut input = InputManager::new(
+            RemoteOptions::gpio_buttons_at(regs.as_mut_ptr() as usize, pins).with_timing(timing),
+        );
+        let set_level = |high: bool| unsafe { write_volatile(lev, if high { 1 << 5 } else { 0 }) };
+
+        set_level(false);
+        let press = input.poll();
+        assert!(matches!(press, Some(InputEvent::Key(e)) if e.state == KeyState::Pressed));
+        // Contact bounce right after the press is swallowed
+        set_level(true);
+        assert_eq!(input.poll(), None);
+        set_level(false);
+        assert_eq!(input.poll(), None);
+        for _ in 0..3 {
+            input.tick();
+            assert_eq!(input.poll(), None);
+        }
+
+        set_level(true);
+        let release = input.poll();
+        assert!(matches!(release, Some(InputEvent::Key(e)) if e.state == KeyState::Released));
+    }
+}
@@ -4,8 +4,36 @@
 //! - NEC (most common, used by many TV remotes)
 //! - RC5 (Philips)
 //! - RC6 (Microsoft MCE remotes)
+//! - Samsung (NEC variant with a 4.5 ms leader, used by Samsung TVs)
+//! - Sony SIRC (12, 15 and 20 bit frames)
+//!
+//! [`IrProtocol::Auto`] accepts all of them, classifying each frame by its
+//! leader (SIRC and RC6 leader marks overlap, so those two are told apart
+//! by the leader space); the decoded [`IrEvent`] reports which protocol
+//! was seen.
+//!
+//! Samsung and SIRC remotes resend the whole frame while a button is held,
+//! with no repeat code or toggle bit: a frame that starts soon after an
+//! identical one is reported as a repeat.
+//!
+//! Command codes become [`IrButton`]s through a [`RemoteProfile`] when one
+//! is registered for the frame's protocol and address (a generic 21-key
+//! NEC remote, Samsung and LG TVs are registered from the start), and
+//! through the fallback [`ButtonMap`] otherwise.
 //!
 //! Connects to a GPIO pin via an IR receiver module (e.g., TSOP38238)
+//!
+//! Pulse widths come either from the caller ([`IrRemote::process_edge`]) or
+//! from an attached [`EdgeCapture`], whose system-timer timestamps
+//! [`IrRemote::poll`] turns into durations.
+
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
+use crate::edge_capture::{Edge, EdgeCapture};
+use crate::ir_timing::{
+    samsung_fields, sirc_fields, sirc_frame_length, sirc_step, within_percent, SircState,
+    SircStep, SIRC_SPACE, SIRC_TOLERANCE,
+};
 
 /// Default GPIO pin for IR receiver (active low)
 pub const IR_RECEIVER_PIN: u8 = 4;
@@ -21,10 +49,12 @@ pub enum IrProtocol {
     Rc5,
     /// Philips RC6 protocol (used by Microsoft MCE remotes)
     Rc6,
-    /// Samsung protocol
+    /// Samsung protocol (32-bit NEC variant)
     Samsung,
-    /// Sony SIRC protocol
+    /// Sony SIRC protocol (12, 15 or 20-bit)
     Sony,
+    /// Detect the protocol per frame from its leader
+    Auto,
 }
 
 /// Common IR remote button codes
@@ -203,6 +233,56 @@ impl IrButton {
             IrButton::Red | IrButton::Green | IrButton::Yellow | IrButton::Blue
         )
     }
+
+    /// Convert a `#[repr(u8)]` code back to a button, None for unused codes
+    pub fn from_code(code: u8) -> Option<IrButton> {
+        let button = match code {
+            0x00 => IrButton::Power,
+            0x01 => IrButton::Up,
+            0x02 => IrButton::Down,
+            0x03 => IrButton::Left,
+            0x04 => IrButton::Right,
+            0x05 => IrButton::Ok,
+            0x06 => IrButton::Back,
+            0x07 => IrButton::Menu,
+            0x08 => IrButton::Home,
+            0x10 => IrButton::Num0,
+            0x11 => IrButton::Num1,
+            0x12 => IrButton::Num2,
+            0x13 => IrButton::Num3,
+            0x14 => IrButton::Num4,
+            0x15 => IrButton::Num5,
+            0x16 => IrButton::Num6,
+            0x17 => IrButton::Num7,
+            0x18 => IrButton::Num8,
+            0x19 => IrButton::Num9,
+            0x20 => IrButton::VolumeUp,
+            0x21 => IrButton::VolumeDown,
+            0x22 => IrButton::Mute,
+            0x23 => IrButton::ChannelUp,
+            0x24 => IrButton::ChannelDown,
+            0x30 => IrButton::Play,
+            0x31 => IrButton::Pause,
+            0x32 => IrButton::Stop,
+            0x33 => IrButton::FastForward,
+            0x34 => IrButton::Rewind,
+            0x35 => IrButton::SkipNext,
+            0x36 => IrButton::SkipPrev,
+            0x37 => IrButton::Record,
+            0x40 => IrButton::Red,
+            0x41 => IrButton::Green,
+            0x42 => IrButton::Yellow,
+            0x43 => IrButton::Blue,
+            0x50 => IrButton::Info,
+            0x51 => IrButton::Guide,
+            0x52 => IrButton::Input,
+            0x53 => IrButton::Subtitle,
+            0x54 => IrButton::Audio,
+            0xFF => IrButton::Unknown,
+            _ => return None,
+        };
+        Some(button)
+    }
 }
 
 /// IR remote event
@@ -216,6 +296,8 @@ pub struct IrEvent {
     pub command: u8,
     /// Whether this is a repeat (button held down)
     pub is_repeat: bool,
+    /// Protocol the frame was decoded as (useful with [`IrProtocol::Auto`])
+    pub protocol: IrProtocol,
 }
 
 /// NEC protocol timing constants (in microseconds)
@@ -236,17 +318,173 @@ mod nec_timing {
     pub const TOLERANCE: u32 = 25;
 }
 
-/// Decoder state for NEC protocol
+/// Samsung protocol timing constants (in microseconds). Data bits use the
+/// NEC timings.
+mod samsung_timing {
+    /// Lead pulse duration
+    pub const LEAD_PULSE: u32 = 4500;
+    /// Lead space duration
+    pub const LEAD_SPACE: u32 = 4500;
+    /// Timing tolerance percentage
+    pub const TOLERANCE: u32 = 25;
+    /// Longest time from the end of one frame to the end of the next
+    /// leader for the next to count as a held-button repeat (frames are
+    /// sent every 108 ms and last at least 45 ms)
+    pub const REPEAT_GAP: u32 = 70_000;
+}
+
+/// Sony SIRC timing constants (in microseconds); the pulse widths are in
+/// [`ir_timing`](crate::ir_timing)
+mod sirc_timing {
+    /// Longest time from the end of one frame to the end of the next
+    /// leader for the next to count as a held-button repeat (frames are
+    /// sent every 45 ms and last at least 17 ms)
+    pub const REPEAT_GAP: u32 = 35_000;
+}
+
+/// RC5 protocol timing constants (in microseconds)
+mod rc5_timing {
+    /// Half-bit duration (bi-phase unit)
+    pub const HALF_BIT: u32 = 889;
+    /// Longest run of one level, in units
+    pub const MAX_RUN: u8 = 2;
+    /// Frame length in units (14 bits, 2 halves each)
+    pub const FRAME_UNITS: u8 = 28;
+}
+
+/// RC6 protocol timing constants (in microseconds)
+mod rc6_timing {
+    /// Bi-phase unit (half of a normal bit)
+    pub const UNIT: u32 = 444;
+    /// Leader mark duration (6 units)
+    pub const LEADER_MARK: u32 = 2666;
+    /// Leader space duration (2 units)
+    pub const LEADER_SPACE: u32 = 889;
+    /// Longest run of one level, in units (trailer half plus a normal half)
+    pub const MAX_RUN: u8 = 3;
+    /// Frame length in units after the leader: start bit (2), mode bits (6),
+    /// double-length trailer bit (4), 8-bit address and 8-bit command (32)
+    pub const FRAME_UNITS: u8 = 44;
+    /// First unit of the trailer (toggle) bit
+    pub const TRAILER_UNIT: u8 = 8;
+    /// First unit of the address/command bits
+    pub const DATA_UNIT: u8 = 12;
+}
+
+/// Is `duration` within the NEC-style percentage tolerance of `expected`?
+fn within_tolerance(duration: u32, expected: u32) -> bool {
+    within_percent(duration, expected, nec_timing::TOLERANCE)
+}
+
+/// Round `duration` to a whole number of bi-phase units of `unit` µs, or
+/// `None` if that is zero or more than `max_run` units
+fn biphase_units(duration: u32, unit: u32, max_run: u8) -> Option<u8> {
+    let n = (duration + unit / 2) / unit;
+    if n == 0 || n > max_run as u32 {
+        None
+    } else {
+        Some(n as u8)
+    }
+}
+
+/// Guess the protocol of a frame from its first mark.
+///
+/// NEC opens with a 9 ms mark, Samsung with 4.5 ms and RC6 with a 2.7 ms
+/// leader; RC5 has no leader, so its first mark is one or two 889 µs
+/// half-bits. SIRC's 2.4 ms leader is classified as RC6: only the space
+/// after it tells them apart.
+pub fn classify_leading_mark(duration: u32) -> Option<IrProtocol> {
+    if within_tolerance(duration, nec_timing::LEAD_PULSE) {
+        Some(IrProtocol::Nec)
+    } else if within_percent(duration, samsung_timing::LEAD_PULSE, samsung_timing::TOLERANCE) {
+        Some(IrProtocol::Samsung)
+    } else if within_tolerance(duration, rc6_timing::LEADER_MARK) {
+        Some(IrProtocol::Rc6)
+    } else if biphase_units(duration, rc5_timing::HALF_BIT, rc5_timing::MAX_RUN).is_some() {
+        Some(IrProtocol::Rc5)
+    } else {
+        None
+    }
+}
+
+/// Levels of a bi-phase (Manchester) frame received so far, one bit per
+/// protocol unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct BiphaseFrame {
+    /// Units received
+    units: u8,
+    /// Bit `i` set = IR mark during unit `i`
+    marks: u64,
+}
+
+impl BiphaseFrame {
+    const fn new() -> Self {
+        Self { units: 0, marks: 0 }
+    }
+
+    /// Append `count` units at one level; false if that overruns `total`
+    fn push(&mut self, is_mark: bool, count: u8, total: u8) -> bool {
+        if self.units + count > total {
+            return false;
+        }
+        if is_mark {
+            for i in self.units..self.units + count {
+                self.marks |= 1 << i;
+            }
+        }
+        self.units += count;
+        true
+    }
+
+    fn is_mark(&self, unit: u8) -> bool {
+        self.marks & (1 << unit) != 0
+    }
+
+    /// Has the whole frame arrived? If the last bit's first half is a mark
+    /// its second half is a space that runs into the idle gap (no edge ends
+    /// it), so the frame is complete one unit early.
+    fn complete(&mut self, total: u8) -> bool {
+        if self.units + 1 == total && self.is_mark(total - 2) {
+            self.units = total;
+        }
+        self.units == total
+    }
+
+    /// Level of the first half of the bit at `first` whose halves are `half`
+    /// units long, or `None` if the halves are not opposite levels
+    fn bit(&self, first: u8, half: u8) -> Option<bool> {
+        let a = self.is_mark(first);
+        let b = self.is_mark(first + half);
+        for i in 1..half {
+            if self.is_mark(first + i) != a || self.is_mark(first + half + i) != b {
+                return None;
+            }
+        }
+        if a == b {
+            None
+        } else {
+            Some(a)
+        }
+    }
+}
+
+/// Decoder state
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum DecoderState {
     /// Waiting for lead pulse
     Idle,
-    /// Received lead pulse, waiting for space
-    LeadPulse,
-    /// Receiving data bits
-    Data { bits_received: u8, data: u32 },
+    /// Received an NEC or Samsung lead pulse, waiting for space
+    LeadPulse { protocol: IrProtocol },
+    /// Receiving NEC or Samsung data bits
+    Data { protocol: IrProtocol, bits_received: u8, data: u32 },
     /// Repeat code detected
     Repeat,
+    /// RC6 leader mark seen, waiting for the leader space
+    Rc6Leader,
+    /// Receiving an RC5 or RC6 frame
+    Biphase { protocol: IrProtocol, frame: BiphaseFrame },
+    /// Receiving a SIRC frame (never `SircState::Idle`)
+    Sirc(SircState),
 }
 
 /// IR Remote receiver driver
@@ -256,8 +494,64 @@ pub struct IrRemote {
     state: DecoderState,
     last_edge_time: u32,
     last_command: Option<IrEvent>,
-    /// Custom button mapping (NEC command -> IrButton)
+    /// Toggle bit of the last RC5/RC6 frame (same toggle = button held)
+    last_toggle: Option<bool>,
+    /// Button the next fresh frame will be bound to (learning mode)
+    learning: Option<IrButton>,
+    /// Button mapping for remotes without a profile
     button_map: ButtonMap,
+    /// Button mappings for known remotes, by protocol and address
+    profiles: [Option<RemoteProfile>; MAX_REMOTE_PROFILES],
+    /// Frames abandoned part-way (bad timing, failed checksum)
+    decode_errors: u32,
+    /// Timestamped edges of the receiver pin, if attached
+    capture: Option<EdgeCapture>,
+    /// Microseconds since the last frame was decoded (saturating)
+    since_frame: u32,
+    /// The frame being received began soon enough after the last one to
+    /// be a held-button resend (Samsung, SIRC)
+    follows_last: bool,
+    /// The last pulse was a mark, so the line is now idle (space)
+    after_mark: bool,
+}
+
+/// Magic bytes opening a serialized [`ButtonMap`]
+pub const MAP_MAGIC: [u8; 4] = *b"IRMP";
+
+/// Serialized [`ButtonMap`] format version
+pub const MAP_VERSION: u8 = 1;
+
+/// Serialized [`ButtonMap`] header size (magic, version, reserved, count)
+const MAP_HEADER_SIZE: usize = 8;
+
+/// Largest serialized [`ButtonMap`]: every command code mapped
+pub const MAP_EXPORT_MAX: usize = MAP_HEADER_SIZE + 256 * 2;
+
+/// Why a stored button map could not be loaded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapImportError {
+    /// Data ends before the header or the entries it announces
+    TooShort,
+    /// Data does not start with [`MAP_MAGIC`]
+    BadMagic,
+    /// Written by a newer format version
+    UnsupportedVersion(u8),
+    /// An entry names a button code that does not exist
+    UnknownButton(u8),
+}
+
+impl From<MapImportError> for PdError {
+    fn from(e: MapImportError) -> Self {
+        let error = PdError::new(ErrorDomain::Input, ErrorKind::InvalidArgument);
+        match e {
+            MapImportError::TooShort => error,
+            MapImportError::BadMagic => error.with_detail(1),
+            MapImportError::UnsupportedVersion(version) => {
+                PdError::new(ErrorDomain::Input, ErrorKind::Unsupported).with_detail(version as u16)
+            }
+            MapImportError::UnknownButton(code) => error.with_detail(code as u16),
+        }
+    }
 }
 
 /// Button mapping for NEC protocol
@@ -271,42 +565,72 @@ pub struct ButtonMap {
 
 impl Default for ButtonMap {
     fn default() -> Self {
-        let mut map = [IrButton::Unknown; 256];
-
-        // Common NEC remote mapping (e.g., generic Chinese remotes)
-        // These are typical codes - actual codes vary by remote
-        map[0x45] = IrButton::Power;
-        map[0x46] = IrButton::Menu;
-        map[0x47] = IrButton::Mute;
-        map[0x44] = IrButton::Back;
-        map[0x40] = IrButton::SkipPrev;
-        map[0x43] = IrButton::SkipNext;
-        map[0x07] = IrButton::VolumeDown;
-        map[0x15] = IrButton::VolumeUp;
-        map[0x09] = IrButton::ChannelUp;
-        map[0x19] = IrButton::ChannelDown;
-        map[0x16] = IrButton::Num0;
-        map[0x0C] = IrButton::Num1;
-        map[0x18] = IrButton::Num2;
-        map[0x5E] = IrButton::Num3;
-        map[0x08] = IrButton::Num4;
-        map[0x1C] = IrButton::Num5;
-        map[0x5A] = IrButton::Num6;
-        map[0x42] = IrButton::Num7;
-        map[0x52] = IrButton::Num8;
-        map[0x4A] = IrButton::Num9;
-
-        // Navigation (common across many remotes)
-        map[0x18] = IrButton::Up;
-        map[0x52] = IrButton::Down;
-        map[0x08] = IrButton::Left;
-        map[0x5A] = IrButton::Right;
-        map[0x1C] = IrButton::Ok;
-
-        Self { map }
+        Self::nec_21_key()
     }
 }
 
+/// Generic 21-key NEC remote (the "Car MP3" layout). It has no arrow keys,
+/// so 2/4/6/8 steer and 5 is OK, as on a phone keypad.
+const NEC_21_KEY_CODES: &[(u8, IrButton)] = &[
+    (0x45, IrButton::Power),       // CH-
+    (0x46, IrButton::Menu),        // CH
+    (0x47, IrButton::Mute),        // CH+
+    (0x44, IrButton::Back),        // |<<
+    (0x40, IrButton::SkipPrev),    // >>|
+    (0x43, IrButton::SkipNext),    // >||
+    (0x07, IrButton::VolumeDown),  // -
+    (0x15, IrButton::VolumeUp),    // +
+    (0x09, IrButton::ChannelUp),   // EQ
+    (0x19, IrButton::ChannelDown), // 100+
+    (0x0D, IrButton::Play),        // 200+
+    (0x16, IrButton::Num0),
+    (0x0C, IrButton::Num1),
+    (0x18, IrButton::Up),          // 2
+    (0x5E, IrButton::Num3),
+    (0x08, IrButton::Left),        // 4
+    (0x1C, IrButton::Ok),          // 5
+    (0x5A, IrButton::Right),       // 6
+    (0x42, IrButton::Num7),
+    (0x52, IrButton::Down),        // 8
+    (0x4A, IrButton::Num9),
+];
+
+/// Samsung TV remote
+const SAMSUNG_TV_CODES: &[(u8, IrButton)] = &[
+    (0x02, IrButton::Power),
+    (0x07, IrButton::VolumeUp),
+    (0x0B, IrButton::VolumeDown),
+    (0x0F, IrButton::Mute),
+    (0x12, IrButton::ChannelUp),
+    (0x10, IrButton::ChannelDown),
+    (0x60, IrButton::Up),
+    (0x61, IrButton::Down),
+    (0x65, IrButton::Left),
+    (0x62, IrButton::Right),
+    (0x68, IrButton::Ok),
+    (0x58, IrButton::Back),
+    (0x1A, IrButton::Menu),
+    (0x79, IrButton::Home),
+];
+
+/// LG TV remote
+const LG_TV_CODES: &[(u8, IrButton)] = &[
+    (0x08, IrButton::Power),
+    (0x02, IrButton::VolumeUp),
+    (0x03, IrButton::VolumeDown),
+    (0x09, IrButton::Mute),
+    (0x00, IrButton::ChannelUp),
+    (0x01, IrButton::ChannelDown),
+    (0x40, IrButton::Up),
+    (0x41, IrButton::Down),
+    (0x07, IrButton::Left),
+    (0x06, IrButton::Right),
+    (0x44, IrButton::Ok),
+    (0x28, IrButton::Back),
+    (0x43, IrButton::Menu),
+    (0x42, IrButton::Home),
+];
+
 impl ButtonMap {
     /// Create a new empty button map
     pub fn new() -> Self {
@@ -315,6 +639,17 @@ impl ButtonMap {
         }
     }
 
+    /// Map built from `(command, button)` pairs (later pairs win)
+    pub const fn from_codes(codes: &[(u8, IrButton)]) -> Self {
+        let mut map = [IrButton::Unknown; 256];
+        let mut i = 0;
+        while i < codes.len() {
+            map[codes[i].0 as usize] = codes[i].1;
+            i += 1;
+        }
+        Self { map }
+    }
+
     /// Set a button mapping
     pub fn set(&mut self, command: u8, button: IrButton) {
         self.map[command as usize] = button;
@@ -325,45 +660,169 @@ impl ButtonMap {
         self.map[command as usize]
     }
 
-    /// Create a Samsung TV remote mapping
-    pub fn samsung_tv() -> Self {
+    /// Bind `command` to `button`, dropping any code previously bound to
+    /// that button (so re-learning a button replaces it)
+    pub fn bind(&mut self, command: u8, button: IrButton) {
+        for entry in self.map.iter_mut() {
+            if *entry == button {
+                *entry = IrButton::Unknown;
+            }
+        }
+        self.map[command as usize] = button;
+    }
+
+    /// Number of command codes mapped to a button
+    pub fn mapped_count(&self) -> usize {
+        self.map.iter().filter(|b| **b != IrButton::Unknown).count()
+    }
+
+    /// Serialize the mapped codes into `out` for persistent storage.
+    ///
+    /// Layout: `"IRMP"`, version, reserved byte, entry count (u16 LE), then
+    /// one `(command, button code)` pair per mapped command. Returns the
+    /// number of bytes written, or `None` if `out` is too small
+    /// ([`MAP_EXPORT_MAX`] always fits).
+    pub fn export(&self, out: &mut [u8]) -> Option<usize> {
+        let count = self.mapped_count();
+        let len = MAP_HEADER_SIZE + count * 2;
+        if out.len() < len {
+            return None;
+        }
+
+        out[..4].copy_from_slice(&MAP_MAGIC);
+        out[4] = MAP_VERSION;
+        out[5] = 0;
+        out[6..8].copy_from_slice(&(count as u16).to_le_bytes());
+
+        let mut pos = MAP_HEADER_SIZE;
+        for (command, button) in self.map.iter().enumerate() {
+            if *button != IrButton::Unknown {
+                out[pos] = command as u8;
+                out[pos + 1] = *button as u8;
+                pos += 2;
+            }
+        }
+        Some(len)
+    }
+
+    /// Parse a map written by [`ButtonMap::export`]
+    pub fn import(bytes: &[u8]) -> Result<Self, MapImportError> {
+        if bytes.len() < MAP_HEADER_SIZE {
+            return Err(MapImportError::TooShort);
+        }
+        if bytes[..4] != MAP_MAGIC {
+            return Err(MapImportError::BadMagic);
+        }
+        if bytes[4] != MAP_VERSION {
+            return Err(MapImportError::UnsupportedVersion(bytes[4]));
+        }
+        let count = u16::from_le_bytes([bytes[6], bytes[7]]) as usize;
+        if count > 256 || bytes.len() < MAP_HEADER_SIZE + count * 2 {
+            return Err(MapImportError::TooShort);
+        }
+
         let mut map = Self::new();
-        map.set(0x02, IrButton::Power);
-        map.set(0x07, IrButton::VolumeUp);
-        map.set(0x0B, IrButton::VolumeDown);
-        map.set(0x0F, IrButton::Mute);
-        map.set(0x12, IrButton::ChannelUp);
-        map.set(0x10, IrButton::ChannelDown);
-        map.set(0x60, IrButton::Up);
-        map.set(0x61, IrButton::Down);
-        map.set(0x65, IrButton::Left);
-        map.set(0x62, IrButton::Right);
-        map.set(0x68, IrButton::Ok);
-        map.set(0x58, IrButton::Back);
-        map.set(0x1A, IrButton::Menu);
-        map.set(0x79, IrButton::Home);
-        map
+        for pair in bytes[MAP_HEADER_SIZE..MAP_HEADER_SIZE + count * 2].chunks_exact(2) {
+            match IrButton::from_code(pair[1]) {
+                Some(button) => map.set(pair[0], button),
+                None => return Err(MapImportError::UnknownButton(pair[1])),
+            }
+        }
+        Ok(map)
+    }
+
+    /// Create a generic 21-key NEC remote mapping
+    pub const fn nec_21_key() -> Self {
+        Self::from_codes(NEC_21_KEY_CODES)
+    }
+
+    /// Create a Samsung TV remote mapping
+    pub const fn samsung_tv() -> Self {
+        Self::from_codes(SAMSUNG_TV_CODES)
     }
 
     /// Create an LG TV remote mapping
-    pub fn lg_tv() -> Self {
-        let mut map = Self::new();
-        map.set(0x08, IrButton::Power);
-        map.set(0x02, IrButton::VolumeUp);
-        map.set(0x03, IrButton::VolumeDown);
-        map.set(0x09, IrButton::Mute);
-        map.set(0x00, IrButton::ChannelUp);
-        map.set(0x01, IrButton::ChannelDown);
-        map.set(0x40, IrButton::Up);
-        map.set(0x41, IrButton::Down);
-        map.set(0x07, IrButton::Left);
-        map.set(0x06, IrButton::Right);
-        map.set(0x44, IrButton::Ok);
-        map.set(0x28, IrButton::Back);
-        map.set(0x43, IrButton::Menu);
-        map.set(0x42, IrButton::Home);
-        map
+    pub const fn lg_tv() -> Self {
+        Self::from_codes(LG_TV_CODES)
+    }
+}
+
+/// Most remote profiles an [`IrRemote`] holds
+pub const MAX_REMOTE_PROFILES: usize = 8;
+
+/// A remote's button layout, used for the frames that carry its protocol
+/// and address
+#[derive(Clone, Copy)]
+pub struct RemoteProfile {
+    /// Short name, for logs and settings
+    pub name: &'static str,
+    /// Protocol the remote sends
+    pub protocol: IrProtocol,
+    /// Address (device code) in its frames
+    pub address: u16,
+    /// What its command codes mean
+    pub map: ButtonMap,
+}
+
+impl RemoteProfile {
+    /// Generic 21-key NEC remote, address 0x00
+    pub const NEC_21_KEY: Self = Self {
+        name: "nec-21-key",
+        protocol: IrProtocol::Nec,
+        address: 0x00,
+        map: ButtonMap::nec_21_key(),
+    };
+
+    /// Samsung TV remote, Samsung protocol address 0x07
+    pub const SAMSUNG_TV: Self = Self {
+        name: "samsung-tv",
+        protocol: IrProtocol::Samsung,
+        address: 0x07,
+        map: ButtonMap::samsung_tv(),
+    };
+
+    /// LG TV remote, NEC address 0x04
+    pub const LG_TV: Self = Self {
+        name: "lg-tv",
+        protocol: IrProtocol::Nec,
+        address: 0x04,
+        map: ButtonMap::lg_tv(),
+    };
+
+    /// Profiles every [`IrRemote`] starts with
+    pub const BUILTIN: [Self; 3] = [Self::NEC_21_KEY, Self::SAMSUNG_TV, Self::LG_TV];
+
+    /// Is this the profile for frames of `protocol` from `address`?
+    pub fn matches(&self, protocol: IrProtocol, address: u16) -> bool {
+        self.protocol == protocol && self.address == address
+    }
+}
+
+/// Why a remote profile could not be registered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// Already holding [`MAX_REMOTE_PROFILES`] profiles for other remotes
+    Full,
+}
+
+impl From<ProfileError> for PdError {
+    fn from(e: ProfileError) -> Self {
+        match e {
+            ProfileError::Full => PdError::new(ErrorDomain::Input, ErrorKind::Capacity)
+                .with_detail(MAX_REMOTE_PROFILES as u16),
+        }
+    }
+}
+
+/// Profile slots holding the built-in profiles
+const fn builtin_profiles() -> [Option<RemoteProfile>; MAX_REMOTE_PROFILES] {
+    let mut profiles = [None; MAX_REMOTE_PROFILES];
+    let mut i = 0;
+    while i < RemoteProfile::BUILTIN.len() {
+        profiles[i] = Some(RemoteProfile::BUILTIN[i]);
+        i += 1;
     }
+    profiles
 }
 
 impl IrRemote {
@@ -380,17 +839,118 @@ impl IrRemote {
             state: DecoderState::Idle,
             last_edge_time: 0,
             last_command: None,
+            last_toggle: None,
+            learning: None,
             button_map: ButtonMap {
                 map: [IrButton::Unknown; 256],
             },
+            profiles: builtin_profiles(),
+            decode_errors: 0,
+            capture: None,
+            since_frame: u32::MAX,
+            follows_last: false,
+            after_mark: false,
         }
     }
 
-    /// Set the button mapping
+    /// Set the button mapping for remotes without a profile
     pub fn set_button_map(&mut self, map: ButtonMap) {
         self.button_map = map;
     }
 
+    /// Get the button mapping for remotes without a profile
+    pub fn button_map(&self) -> &ButtonMap {
+        &self.button_map
+    }
+
+    /// Use `profile` for the frames carrying its protocol and address,
+    /// replacing any profile registered for that remote
+    pub fn register_profile(&mut self, profile: RemoteProfile) -> Result<(), ProfileError> {
+        let slot = match self.profile_slot(profile.protocol, profile.address) {
+            Some(slot) => slot,
+            None => self.profiles.iter().position(Option::is_none).ok_or(ProfileError::Full)?,
+        };
+        self.profiles[slot] = Some(profile);
+        Ok(())
+    }
+
+    /// Drop the profile for a remote, whose frames then resolve through
+    /// the fallback [`button_map`](Self::button_map)
+    pub fn remove_profile(&mut self, protocol: IrProtocol, address: u16) -> Option<RemoteProfile> {
+        let slot = self.profile_slot(protocol, address)?;
+        self.profiles[slot].take()
+    }
+
+    /// Registered profiles
+    pub fn profiles(&self) -> impl Iterator<Item = &RemoteProfile> {
+        self.profiles.iter().flatten()
+    }
+
+    /// Profile the frames of `protocol` from `address` resolve through
+    pub fn profile_for(&self, protocol: IrProtocol, address: u16) -> Option<&RemoteProfile> {
+        self.profiles().find(|p| p.matches(protocol, address))
+    }
+
+    /// Use the map of the profile named `name` for remotes without a
+    /// profile of their own (such as clones sending another address).
+    /// Returns false, leaving the fallback map alone, if there is none.
+    pub fn select_profile(&mut self, name: &str) -> bool {
+        let Some(map) = self.profiles().find(|p| p.name == name).map(|p| p.map) else {
+            return false;
+        };
+        self.button_map = map;
+        true
+    }
+
+    fn profile_slot(&self, protocol: IrProtocol, address: u16) -> Option<usize> {
+        self.profiles.iter().position(|p| p.is_some_and(|p| p.matches(protocol, address)))
+    }
+
+    /// Enter learning mode: the next fresh (non-repeat) frame from any
+    /// remote is bound to `button`, and reported as that button.
+    pub fn start_learning(&mut self, button: IrButton) {
+        self.learning = Some(button);
+    }
+
+    /// Leave learning mode without binding anything
+    pub fn cancel_learning(&mut self) {
+        self.learning = None;
+    }
+
+    /// Button waiting to be learned, if in learning mode
+    pub fn learning(&self) -> Option<IrButton> {
+        self.learning
+    }
+
+    /// Serialize the button mapping (see [`ButtonMap::export`])
+    pub fn export_map(&self, out: &mut [u8]) -> Option<usize> {
+        self.button_map.export(out)
+    }
+
+    /// Replace the button mapping with a previously exported one.
+    /// Returns the number of mapped codes; the current map is kept on error.
+    pub fn import_map(&mut self, bytes: &[u8]) -> Result<usize, MapImportError> {
+        let map = ButtonMap::import(bytes)?;
+        self.button_map = map;
+        Ok(map.mapped_count())
+    }
+
+    /// Button for a command from the remote at `protocol` and `address`,
+    /// binding it first (in the map it resolves through) if learning
+    fn resolve_button(&mut self, protocol: IrProtocol, address: u16, command: u8, is_repeat: bool) -> IrButton {
+        let map = match self.profiles.iter_mut().flatten().find(|p| p.matches(protocol, address)) {
+            Some(profile) => &mut profile.map,
+            None => &mut self.button_map,
+        };
+        if let Some(button) = self.learning {
+            if !is_repeat {
+                map.bind(command, button);
+                self.learning = None;
+            }
+        }
+        map.get(command)
+    }
+
     /// Get the GPIO pin used for receiving
     pub fn gpio_pin(&self) -> u8 {
         self.gpio_pin
@@ -401,51 +961,163 @@ impl IrRemote {
         self.protocol
     }
 
+    /// Take edges of the receiver pin from `capture`, which starts
+    /// watching it; [`poll`](Self::poll) then decodes them
+    pub fn attach_capture(&mut self, mut capture: EdgeCapture) {
+        capture.watch(self.gpio_pin);
+        self.capture = Some(capture);
+    }
+
+    /// The attached edge capture, to service from the GPIO interrupt
+    pub fn capture_mut(&mut self) -> Option<&mut EdgeCapture> {
+        self.capture.as_mut()
+    }
+
     /// Poll for IR remote events
+    ///
+    /// Services the attached [`EdgeCapture`] and decodes its queued edges
+    /// until a frame completes, then checks how long the line has been
+    /// quiet (see [`process_idle`](Self::process_idle)). Without a capture
+    /// there is nothing to read; feed edges through
+    /// [`process_edge`](Self::process_edge) instead.
     pub fn poll(&mut self) -> Option<IrEvent> {
-        // TODO: Read GPIO pin and decode IR signal
-        // This requires integration with GPIO driver and timer
-        // for measuring pulse durations
-        None
+        self.capture.as_mut()?.service();
+        while let Some(edge) = self.capture.as_mut().and_then(EdgeCapture::pop) {
+            if let Some(event) = self.process_capture(edge) {
+                return Some(event);
+            }
+        }
+        let now = self.capture.as_ref()?.now();
+        self.process_idle(now.wrapping_sub(self.last_edge_time))
+    }
+
+    /// Process a captured edge of the receiver pin (edges of other pins are
+    /// ignored). The receiver output is active low, so a rising edge ends
+    /// a mark and a falling edge ends a space.
+    pub fn process_capture(&mut self, edge: Edge) -> Option<IrEvent> {
+        if edge.pin != self.gpio_pin {
+            return None;
+        }
+        let duration = edge.time_us.wrapping_sub(self.last_edge_time);
+        self.last_edge_time = edge.time_us;
+        self.process_edge(duration, edge.level)
     }
 
     /// Process a timing edge (for interrupt-driven operation)
     /// `duration` is the time in microseconds since the last edge
     /// `is_mark` is true for IR signal present (typically low from receiver)
     pub fn process_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
+        self.since_frame = self.since_frame.saturating_add(duration);
+        self.after_mark = is_mark;
+        let in_frame = self.state != DecoderState::Idle;
+        let event = self.decode_edge(duration, is_mark);
+        // A frame that was under way and ended without an event was dropped
+        if in_frame && event.is_none() && self.state == DecoderState::Idle {
+            self.decode_errors = self.decode_errors.wrapping_add(1);
+        }
+        if event.is_some() {
+            self.since_frame = 0;
+        }
+        event
+    }
+
+    /// Tell the decoder the line has been quiet for `elapsed` microseconds
+    /// since the last edge
+    ///
+    /// 12 and 15 bit SIRC frames have no end marker: they are complete
+    /// once the line stays idle longer than a bit space, which is only
+    /// seen as an edge when the next frame starts. Call this from a timer
+    /// so the last frame of a burst is not held back; [`poll`](Self::poll)
+    /// does so itself.
+    pub fn process_idle(&mut self, elapsed: u32) -> Option<IrEvent> {
+        let DecoderState::Sirc(SircState::Bits { bits, data }) = self.state else {
+            return None;
+        };
+        // Mid-mark, or not yet longer than a bit space
+        if !self.after_mark || elapsed <= SIRC_SPACE || within_percent(elapsed, SIRC_SPACE, SIRC_TOLERANCE) {
+            return None;
+        }
+        self.state = DecoderState::Idle;
+        if !sirc_frame_length(bits) {
+            self.decode_errors = self.decode_errors.wrapping_add(1);
+            return None;
+        }
+        self.since_frame = 0;
+        Some(self.sirc_event(data))
+    }
+
+    /// Frames abandoned part-way: a pulse out of tolerance, a failed
+    /// checksum, or a repeat code with no frame to repeat
+    pub fn decode_errors(&self) -> u32 {
+        self.decode_errors
+    }
+
+    /// Feed one edge to the configured protocol's decoder
+    fn decode_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
         match self.protocol {
             IrProtocol::Nec | IrProtocol::NecExtended => {
                 self.decode_nec_edge(duration, is_mark)
             }
-            _ => {
-                // Other protocols not yet implemented
-                None
+            IrProtocol::Rc5 => self.decode_rc5_edge(duration, is_mark),
+            IrProtocol::Rc6 => self.decode_rc6_edge(duration, is_mark),
+            IrProtocol::Samsung => self.decode_nec_edge(duration, is_mark),
+            IrProtocol::Sony => self.decode_sirc_edge(duration, is_mark),
+            IrProtocol::Auto => self.decode_auto_edge(duration, is_mark),
+        }
+    }
+
+    /// Protocol of the NEC-family frame a lead pulse of `duration` starts
+    fn nec_family_leader(&self, duration: u32) -> Option<IrProtocol> {
+        match self.protocol {
+            IrProtocol::Samsung => {
+                within_percent(duration, samsung_timing::LEAD_PULSE, samsung_timing::TOLERANCE)
+                    .then_some(IrProtocol::Samsung)
             }
+            IrProtocol::Auto => classify_leading_mark(duration)
+                .filter(|p| matches!(p, IrProtocol::Nec | IrProtocol::Samsung)),
+            _ => within_tolerance(duration, nec_timing::LEAD_PULSE).then_some(IrProtocol::Nec),
         }
     }
 
-    /// Decode NEC protocol edge
+    /// Decode NEC protocol edge (also Samsung, which differs only in its
+    /// leader, its address check and having no repeat code)
     fn decode_nec_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
-        let tolerance = |expected: u32| -> bool {
-            let margin = expected * nec_timing::TOLERANCE / 100;
-            duration >= expected.saturating_sub(margin)
-                && duration <= expected.saturating_add(margin)
-        };
+        let tolerance = |expected: u32| -> bool { within_tolerance(duration, expected) };
 
         match self.state {
             DecoderState::Idle => {
-                // Looking for 9ms lead pulse (mark)
-                if is_mark && tolerance(nec_timing::LEAD_PULSE) {
-                    self.state = DecoderState::LeadPulse;
+                // Looking for the lead pulse (mark): 9 ms NEC, 4.5 ms Samsung
+                if is_mark {
+                    if let Some(protocol) = self.nec_family_leader(duration) {
+                        self.state = DecoderState::LeadPulse { protocol };
+                        self.follows_last = protocol == IrProtocol::Samsung
+                            && self.since_frame <= samsung_timing::REPEAT_GAP;
+                    }
                 }
                 None
             }
 
-            DecoderState::LeadPulse => {
+            DecoderState::LeadPulse { protocol: IrProtocol::Samsung } => {
+                self.state = if !is_mark
+                    && within_percent(duration, samsung_timing::LEAD_SPACE, samsung_timing::TOLERANCE)
+                {
+                    DecoderState::Data {
+                        protocol: IrProtocol::Samsung,
+                        bits_received: 0,
+                        data: 0,
+                    }
+                } else {
+                    DecoderState::Idle
+                };
+                None
+            }
+
+            DecoderState::LeadPulse { .. } => {
                 if !is_mark {
                     if tolerance(nec_timing::LEAD_SPACE) {
                         // Normal command - start receiving data
                         self.state = DecoderState::Data {
+                            protocol: IrProtocol::Nec,
                             bits_received: 0,
                             data: 0,
                         };
@@ -465,7 +1137,7 @@ impl IrRemote {
                 None
             }
 
-            DecoderState::Data { bits_received, data } => {
+            DecoderState::Data { protocol: frame_protocol, bits_received, data } => {
                 if is_mark {
                     // Mark should always be ~562µs in NEC
                     if !tolerance(nec_timing::BIT_PULSE) {
@@ -490,6 +1162,15 @@ impl IrRemote {
                         // Complete message received
                         self.state = DecoderState::Idle;
 
+                        if frame_protocol == IrProtocol::Samsung {
+                            let (address, command) = samsung_fields(new_data)?;
+                            return Some(self.resend_event(
+                                IrProtocol::Samsung,
+                                address as u16,
+                                command,
+                            ));
+                        }
+
                         // Decode NEC format: address (8), ~address (8), command (8), ~command (8)
                         let addr_lo = (new_data & 0xFF) as u8;
                         let addr_hi = ((new_data >> 8) & 0xFF) as u8;
@@ -501,31 +1182,42 @@ impl IrRemote {
                             return None;
                         }
 
-                        let address = match self.protocol {
+                        let extended_address = ((addr_hi as u16) << 8) | (addr_lo as u16);
+                        let (protocol, address) = match self.protocol {
                             IrProtocol::NecExtended => {
                                 // Extended: full 16-bit address
-                                ((addr_hi as u16) << 8) | (addr_lo as u16)
+                                (IrProtocol::NecExtended, extended_address)
+                            }
+                            IrProtocol::Auto => {
+                                // Either form: the address inverse tells them apart
+                                if addr_lo == !addr_hi {
+                                    (IrProtocol::Nec, addr_lo as u16)
+                                } else {
+                                    (IrProtocol::NecExtended, extended_address)
+                                }
                             }
                             _ => {
                                 // Standard: 8-bit address (verify inverse)
                                 if addr_lo != !addr_hi {
                                     return None;
                                 }
-                                addr_lo as u16
+                                (IrProtocol::Nec, addr_lo as u16)
                             }
                         };
 
                         let event = IrEvent {
-                            button: self.button_map.get(cmd),
+                            button: self.resolve_button(protocol, address, cmd, false),
                             address,
                             command: cmd,
                             is_repeat: false,
+                            protocol,
                         };
 
                         self.last_command = Some(event);
                         return Some(event);
                     } else {
                         self.state = DecoderState::Data {
+                            protocol: frame_protocol,
                             bits_received: new_bits,
                             data: new_data,
                         };
@@ -538,9 +1230,261 @@ impl IrRemote {
                 self.state = DecoderState::Idle;
                 None
             }
+
+            // RC5/RC6/SIRC states never occur while decoding NEC
+            _ => {
+                self.state = DecoderState::Idle;
+                None
+            }
+        }
+    }
+
+    /// Decode RC5 protocol edge
+    ///
+    /// RC5 has no leader: a frame opens with start bit S1 = 1, whose first
+    /// half is a space indistinguishable from idle, so the first mark we see
+    /// is already inside the frame.
+    fn decode_rc5_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
+        let frame = match self.state {
+            DecoderState::Biphase { protocol: IrProtocol::Rc5, frame } => frame,
+            _ => {
+                if !is_mark {
+                    self.state = DecoderState::Idle;
+                    return None;
+                }
+                let mut frame = BiphaseFrame::new();
+                frame.push(false, 1, rc5_timing::FRAME_UNITS);
+                frame
+            }
+        };
+        self.continue_biphase(IrProtocol::Rc5, frame, duration, is_mark)
+    }
+
+    /// Decode RC6 (mode 0) protocol edge
+    fn decode_rc6_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
+        match self.state {
+            DecoderState::Biphase { protocol: IrProtocol::Rc6, frame } => {
+                self.continue_biphase(IrProtocol::Rc6, frame, duration, is_mark)
+            }
+            DecoderState::Rc6Leader => {
+                self.state = if !is_mark && within_tolerance(duration, rc6_timing::LEADER_SPACE) {
+                    DecoderState::Biphase {
+                        protocol: IrProtocol::Rc6,
+                        frame: BiphaseFrame::new(),
+                    }
+                } else {
+                    DecoderState::Idle
+                };
+                None
+            }
+            _ => {
+                // Looking for the 6-unit leader mark
+                self.state = if is_mark && within_tolerance(duration, rc6_timing::LEADER_MARK) {
+                    DecoderState::Rc6Leader
+                } else {
+                    DecoderState::Idle
+                };
+                None
+            }
+        }
+    }
+
+    /// Decode Sony SIRC protocol edge
+    fn decode_sirc_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
+        let state = match self.state {
+            DecoderState::Sirc(state) => state,
+            _ => SircState::Idle,
+        };
+        match sirc_step(state, duration, is_mark) {
+            SircStep::Continue(SircState::Idle) | SircStep::Abort => {
+                self.state = DecoderState::Idle;
+                None
+            }
+            SircStep::Continue(next) => {
+                if state == SircState::Idle {
+                    self.follows_last = self.since_frame <= sirc_timing::REPEAT_GAP;
+                }
+                self.state = DecoderState::Sirc(next);
+                None
+            }
+            SircStep::Done { data, .. } => {
+                self.state = DecoderState::Idle;
+                Some(self.sirc_event(data))
+            }
         }
     }
 
+    /// Event for a received SIRC frame
+    fn sirc_event(&mut self, data: u32) -> IrEvent {
+        let (address, command) = sirc_fields(data);
+        self.resend_event(IrProtocol::Sony, address, command)
+    }
+
+    /// Decode an edge when the protocol is detected per frame
+    fn decode_auto_edge(&mut self, duration: u32, is_mark: bool) -> Option<IrEvent> {
+        match self.state {
+            DecoderState::Idle => {
+                if !is_mark {
+                    return None;
+                }
+                // The stop bit closing an NEC or Samsung frame is not an RC5 start
+                let after_pulse_distance = self.last_command.is_some_and(|last| {
+                    matches!(
+                        last.protocol,
+                        IrProtocol::Nec | IrProtocol::NecExtended | IrProtocol::Samsung
+                    )
+                });
+                if after_pulse_distance
+                    && self.since_frame == duration
+                    && within_tolerance(duration, nec_timing::BIT_PULSE)
+                {
+                    return None;
+                }
+                match classify_leading_mark(duration) {
+                    Some(IrProtocol::Rc5) => self.decode_rc5_edge(duration, is_mark),
+                    Some(IrProtocol::Rc6) => {
+                        self.follows_last = self.since_frame <= sirc_timing::REPEAT_GAP;
+                        self.decode_rc6_edge(duration, is_mark)
+                    }
+                    Some(_) => self.decode_nec_edge(duration, is_mark),
+                    None => None,
+                }
+            }
+            // SIRC's leader space is 600 µs against RC6's 889 µs
+            DecoderState::Rc6Leader
+                if !is_mark
+                    && duration < (SIRC_SPACE + rc6_timing::LEADER_SPACE) / 2
+                    && within_percent(duration, SIRC_SPACE, SIRC_TOLERANCE) =>
+            {
+                self.state = DecoderState::Sirc(SircState::Bits { bits: 0, data: 0 });
+                None
+            }
+            DecoderState::Sirc(_) => self.decode_sirc_edge(duration, is_mark),
+            DecoderState::Rc6Leader
+            | DecoderState::Biphase { protocol: IrProtocol::Rc6, .. } => {
+                self.decode_rc6_edge(duration, is_mark)
+            }
+            DecoderState::Biphase { .. } => self.decode_rc5_edge(duration, is_mark),
+            _ => self.decode_nec_edge(duration, is_mark),
+        }
+    }
+
+    /// Feed one edge into a bi-phase frame; decodes it once complete
+    fn continue_biphase(
+        &mut self,
+        protocol: IrProtocol,
+        mut frame: BiphaseFrame,
+        duration: u32,
+        is_mark: bool,
+    ) -> Option<IrEvent> {
+        let (unit, max_run, total) = match protocol {
+            IrProtocol::Rc5 => (rc5_timing::HALF_BIT, rc5_timing::MAX_RUN, rc5_timing::FRAME_UNITS),
+            _ => (rc6_timing::UNIT, rc6_timing::MAX_RUN, rc6_timing::FRAME_UNITS),
+        };
+
+        let accepted = match biphase_units(duration, unit, max_run) {
+            Some(count) => frame.push(is_mark, count, total),
+            None => false,
+        };
+        if !accepted {
+            self.state = DecoderState::Idle;
+            return None;
+        }
+        if !frame.complete(total) {
+            self.state = DecoderState::Biphase { protocol, frame };
+            return None;
+        }
+
+        self.state = DecoderState::Idle;
+        let (address, command, toggle) = match protocol {
+            IrProtocol::Rc5 => Self::rc5_fields(&frame)?,
+            _ => Self::rc6_fields(&frame)?,
+        };
+        Some(self.toggle_event(protocol, address, command, toggle))
+    }
+
+    /// Unpack an RC5 frame: S1, S2, toggle, 5-bit address, 6-bit command
+    /// (MSB first). A zero S2 is RC5X's inverted 7th command bit.
+    fn rc5_fields(frame: &BiphaseFrame) -> Option<(u16, u8, bool)> {
+        let mut bits: u16 = 0;
+        for b in 0..14 {
+            // RC5 sends 1 as space-then-mark
+            let first_is_mark = frame.bit(b * 2, 1)?;
+            bits = (bits << 1) | (!first_is_mark as u16);
+        }
+        if bits >> 13 != 1 {
+            return None;
+        }
+        let field = (bits >> 12) & 1;
+        let toggle = (bits >> 11) & 1 != 0;
+        let address = (bits >> 6) & 0x1F;
+        let command = ((bits & 0x3F) | ((field ^ 1) << 6)) as u8;
+        Some((address, command, toggle))
+    }
+
+    /// Unpack an RC6 mode 0 frame: start bit, 3 mode bits, double-length
+    /// toggle bit, 8-bit address, 8-bit command (MSB first)
+    fn rc6_fields(frame: &BiphaseFrame) -> Option<(u16, u8, bool)> {
+        // RC6 sends 1 as mark-then-space: the first half is the bit value
+        if !frame.bit(0, 1)? {
+            return None;
+        }
+        for mode_bit in 0..3 {
+            if frame.bit(2 + mode_bit * 2, 1)? {
+                return None;
+            }
+        }
+        let toggle = frame.bit(rc6_timing::TRAILER_UNIT, 2)?;
+
+        let mut data: u16 = 0;
+        for b in 0..16 {
+            let bit = frame.bit(rc6_timing::DATA_UNIT + b * 2, 1)?;
+            data = (data << 1) | bit as u16;
+        }
+        Some((data >> 8, (data & 0xFF) as u8, toggle))
+    }
+
+    /// Build the event for a protocol that resends whole frames while a
+    /// button is held: a repeat is the same code again, starting soon
+    /// after the last frame.
+    fn resend_event(&mut self, protocol: IrProtocol, address: u16, command: u8) -> IrEvent {
+        let is_repeat = self.follows_last
+            && self.last_command.is_some_and(|last| {
+                last.protocol == protocol && last.address == address && last.command == command
+            });
+        self.follows_last = false;
+
+        let event = IrEvent {
+            button: self.resolve_button(protocol, address, command, is_repeat),
+            address,
+            command,
+            is_repeat,
+            protocol,
+        };
+        self.last_command = Some(event);
+        event
+    }
+
+    /// Build the event for a toggle-bit protocol. Held buttons resend the
+    /// same frame with the same toggle; a new press flips it.
+    fn toggle_event(&mut self, protocol: IrProtocol, address: u16, command: u8, toggle: bool) -> IrEvent {
+        let is_repeat = self.last_toggle == Some(toggle)
+            && self.last_command.is_some_and(|last| {
+                last.protocol == protocol && last.address == address && last.command == command
+            });
+
+        let event = IrEvent {
+            button: self.resolve_button(protocol, address, command, is_repeat),
+            address,
+            command,
+            is_repeat,
+            protocol,
+        };
+        self.last_command = Some(event);
+        self.last_toggle = Some(toggle);
+        event
+    }
+
     /// Reset the decoder state
     pub fn reset(&mut self) {
         self.state = DecoderState::Idle;
@@ -557,3 +1501,486 @@ impl Default for IrRemote {
         Self::new(IrProtocol::Nec)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit levels (true = mark) of a bi-phase frame under construction
+    struct Levels {
+        units: [bool; 64],
+        len: usize,
+    }
+
+    impl Levels {
+        fn new() -> Self {
+            Self { units: [false; 64], len: 0 }
+        }
+
+        fn push(&mut self, is_mark: bool, count: usize) {
+            for _ in 0..count {
+                self.units[self.len] = is_mark;
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Replay unit levels as edges, with receiver skew (marks stretched,
+    /// spaces shortened)
+    fn replay(ir: &mut IrRemote, levels: &Levels, unit: u32) -> Option<IrEvent> {
+        let mut event = None;
+        let mut i = 0;
+        while i < levels.len {
+            let level = levels.units[i];
+            let start = i;
+            let mut run = 0u32;
+            while i < levels.len && levels.units[i] == level {
+                run += 1;
+                i += 1;
+            }
+            // Leading space is just idle line; trailing space never ends
+            if !level && (start == 0 || i == levels.len) {
+                continue;
+            }
+            let duration = if level { run * unit + 60 } else { run * unit - 60 };
+            if let Some(e) = ir.process_edge(duration, level) {
+                event = Some(e);
+            }
+        }
+        event
+    }
+
+    fn rc5_frame(field: bool, toggle: bool, address: u16, command: u16) -> Levels {
+        let bits = (1u16 << 13) | ((field as u16) << 12) | ((toggle as u16) << 11)
+            | ((address & 0x1F) << 6) | (command & 0x3F);
+        let mut levels = Levels::new();
+        for b in (0..14).rev() {
+            let one = (bits >> b) & 1 != 0;
+            levels.push(!one, 1);
+            levels.push(one, 1);
+        }
+        levels
+    }
+
+    fn rc6_frame(toggle: bool, address: u8, command: u8) -> Levels {
+        let mut levels = Levels::new();
+        levels.push(true, 6); // leader
+        levels.push(false, 2);
+        levels.push(true, 1); // start bit 1
+        levels.push(false, 1);
+        for _ in 0..3 {
+            levels.push(false, 1); // mode bit 0
+            levels.push(true, 1);
+        }
+        levels.push(toggle, 2); // double-length trailer
+        levels.push(!toggle, 2);
+        let data = ((address as u16) << 8) | command as u16;
+        for b in (0..16).rev() {
+            let one = (data >> b) & 1 != 0;
+            levels.push(one, 1);
+            levels.push(!one, 1);
+        }
+        levels
+    }
+
+    fn send_nec(ir: &mut IrRemote, address: u8, command: u8) -> Option<IrEvent> {
+        let data = (address as u32)
+            | ((!address as u32) << 8)
+            | ((command as u32) << 16)
+            | ((!command as u32) << 24);
+        ir.process_edge(9000, true);
+        ir.process_edge(4500, false);
+        let mut event = None;
+        for b in 0..32 {
+            ir.process_edge(562, true);
+            let space = if (data >> b) & 1 != 0 { 1687 } else { 562 };
+            event = ir.process_edge(space, false);
+        }
+        event
+    }
+
+    /// Send a Samsung frame and its stop bit, after `gap` µs of idle line
+    fn send_samsung(ir: &mut IrRemote, gap: u32, address: u8, command: u8) -> Option<IrEvent> {
+        let data = (address as u32)
+            | ((address as u32) << 8)
+            | ((command as u32) << 16)
+            | ((!command as u32) << 24);
+        ir.process_edge(gap, false);
+        ir.process_edge(4500, true);
+        ir.process_edge(4500, false);
+        let mut event = None;
+        for b in 0..32 {
+            ir.process_edge(560, true);
+            let space = if (data >> b) & 1 != 0 { 1690 } else { 560 };
+            event = event.or(ir.process_edge(space, false));
+        }
+        ir.process_edge(560, true);
+        event
+    }
+
+    /// Send a SIRC frame of `bits` bits after `gap` µs of idle line, with
+    /// receiver skew; returns any event, including one for the frame
+    /// before that the gap completes
+    fn send_sirc(ir: &mut IrRemote, gap: u32, bits: u8, data: u32) -> Option<IrEvent> {
+        let mut event = ir.process_edge(gap, false);
+        event = event.or(ir.process_edge(2460, true));
+        for b in 0..bits {
+            event = event.or(ir.process_edge(540, false));
+            let mark = if (data >> b) & 1 != 0 { 1260 } else { 660 };
+            event = event.or(ir.process_edge(mark, true));
+        }
+        event
+    }
+
+    #[test]
+    fn rc5_decodes_frame_and_toggle_repeat() {
+        let mut ir = IrRemote::new(IrProtocol::Rc5);
+        let frame = rc5_frame(true, false, 5, 0x35);
+
+        let e = replay(&mut ir, &frame, 889).expect("rc5 frame");
+        assert_eq!((e.address, e.command, e.protocol), (5, 0x35, IrProtocol::Rc5));
+        assert!(!e.is_repeat);
+
+        // Held button: identical frame, same toggle
+        assert!(replay(&mut ir, &frame, 889).unwrap().is_repeat);
+
+        // New press flips the toggle bit
+        let again = rc5_frame(true, true, 5, 0x35);
+        assert!(!replay(&mut ir, &again, 889).unwrap().is_repeat);
+    }
+
+    #[test]
+    fn counts_abandoned_frames_as_decode_errors() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        assert!(send_nec(&mut ir, 0x00, 0x45).is_some());
+        assert_eq!(ir.decode_errors(), 0);
+
+        // Lead pulse, then a space that fits no NEC timing
+        ir.process_edge(9000, true);
+        ir.process_edge(7000, false);
+        // Half a frame, then a mark far too long for a bit
+        ir.process_edge(9000, true);
+        ir.process_edge(4500, false);
+        ir.process_edge(562, true);
+        ir.process_edge(562, false);
+        ir.process_edge(3000, true);
+        assert_eq!(ir.decode_errors(), 2);
+
+        // Noise while idle is not a frame
+        ir.process_edge(100, false);
+        ir.process_edge(100, true);
+        assert_eq!(ir.decode_errors(), 2);
+    }
+
+    #[test]
+    fn rc5_extended_command_and_last_bit_variants() {
+        let mut ir = IrRemote::new(IrProtocol::Rc5);
+        // Field bit 0 carries the inverted 7th command bit (RC5X); command
+        // ends in 0, so the frame closes with an unterminated space
+        let e = replay(&mut ir, &rc5_frame(false, false, 0x1F, 0x02), 889).unwrap();
+        assert_eq!((e.address, e.command), (0x1F, 0x42));
+
+        // Ends in 1: closing half is a mark
+        let e = replay(&mut ir, &rc5_frame(true, false, 0, 0x01), 889).unwrap();
+        assert_eq!((e.address, e.command), (0, 0x01));
+    }
+
+    #[test]
+    fn rc6_decodes_mode0_frame() {
+        let mut ir = IrRemote::new(IrProtocol::Rc6);
+        let e = replay(&mut ir, &rc6_frame(true, 0x80, 0x0C), 444).expect("rc6 frame");
+        assert_eq!((e.address, e.command, e.protocol), (0x80, 0x0C, IrProtocol::Rc6));
+        assert!(!e.is_repeat);
+
+        let e = replay(&mut ir, &rc6_frame(true, 0x80, 0x0C), 444).unwrap();
+        assert!(e.is_repeat);
+
+        let e = replay(&mut ir, &rc6_frame(false, 0x01, 0xFF), 444).unwrap();
+        assert_eq!((e.address, e.command, e.is_repeat), (0x01, 0xFF, false));
+    }
+
+    #[test]
+    fn rc6_rejects_other_modes() {
+        let mut ir = IrRemote::new(IrProtocol::Rc6);
+        let mut frame = rc6_frame(false, 0x80, 0x0C);
+        // First mode bit = 1 (mark-then-space right after the start bit)
+        frame.units[10] = true;
+        frame.units[11] = false;
+        assert_eq!(replay(&mut ir, &frame, 444), None);
+        // Decoder recovers for the next good frame
+        assert!(replay(&mut ir, &rc6_frame(false, 0x80, 0x0C), 444).is_some());
+    }
+
+    #[test]
+    fn auto_detects_protocol_per_frame() {
+        let mut ir = IrRemote::new(IrProtocol::Auto);
+
+        let e = send_nec(&mut ir, 0x04, 0x45).expect("nec frame");
+        assert_eq!((e.address, e.command, e.protocol), (0x04, 0x45, IrProtocol::Nec));
+
+        let e = replay(&mut ir, &rc5_frame(true, false, 3, 0x10), 889).expect("rc5 frame");
+        assert_eq!((e.address, e.command, e.protocol), (3, 0x10, IrProtocol::Rc5));
+
+        let e = replay(&mut ir, &rc6_frame(false, 0x12, 0x34), 444).expect("rc6 frame");
+        assert_eq!((e.address, e.command, e.protocol), (0x12, 0x34, IrProtocol::Rc6));
+    }
+
+    #[test]
+    fn learning_binds_next_code() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        assert_eq!(send_nec(&mut ir, 0x20, 0x5C).unwrap().button, IrButton::Unknown);
+
+        ir.start_learning(IrButton::Ok);
+        assert_eq!(ir.learning(), Some(IrButton::Ok));
+        let e = send_nec(&mut ir, 0x20, 0x5C).unwrap();
+        assert_eq!(e.button, IrButton::Ok);
+        assert_eq!(ir.learning(), None);
+        assert_eq!(send_nec(&mut ir, 0x20, 0x5C).unwrap().button, IrButton::Ok);
+
+        // Re-learning moves the button to the new code
+        ir.start_learning(IrButton::Ok);
+        send_nec(&mut ir, 0x20, 0x11);
+        assert_eq!(ir.button_map().get(0x11), IrButton::Ok);
+        assert_eq!(ir.button_map().get(0x5C), IrButton::Unknown);
+
+        // RC5 held-button repeats are not learned
+        let mut ir = IrRemote::new(IrProtocol::Rc5);
+        let frame = rc5_frame(true, false, 0, 0x0C);
+        replay(&mut ir, &frame, 889);
+        ir.start_learning(IrButton::Power);
+        assert_eq!(replay(&mut ir, &frame, 889).unwrap().button, IrButton::Unknown);
+        let fresh = rc5_frame(true, true, 0, 0x0C);
+        assert_eq!(replay(&mut ir, &fresh, 889).unwrap().button, IrButton::Power);
+    }
+
+    #[test]
+    fn button_map_export_import_round_trip() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        ir.set_button_map(ButtonMap::samsung_tv());
+        let mut buf = [0u8; MAP_EXPORT_MAX];
+        let len = ir.export_map(&mut buf).unwrap();
+        assert_eq!(len, 8 + 14 * 2);
+        assert_eq!(&buf[..5], b"IRMP\x01");
+
+        let mut other = IrRemote::new(IrProtocol::Nec);
+        assert_eq!(other.import_map(&buf[..len]), Ok(14));
+        for code in 0..=255u8 {
+            assert_eq!(other.button_map().get(code), ButtonMap::samsung_tv().get(code));
+        }
+
+        assert!(ir.export_map(&mut buf[..len - 1]).is_none());
+    }
+
+    #[test]
+    fn button_map_import_rejects_bad_data() {
+        let mut buf = [0u8; MAP_EXPORT_MAX];
+        let len = ButtonMap::lg_tv().export(&mut buf).unwrap();
+
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        assert_eq!(ir.import_map(&buf[..len - 1]), Err(MapImportError::TooShort));
+        assert_eq!(ir.import_map(&buf[..4]), Err(MapImportError::TooShort));
+
+        let mut bad = buf;
+        bad[0] = b'X';
+        assert_eq!(ir.import_map(&bad[..len]), Err(MapImportError::BadMagic));
+
+        let mut bad = buf;
+        bad[4] = 9;
+        assert_eq!(ir.import_map(&bad[..len]), Err(MapImportError::UnsupportedVersion(9)));
+
+        let mut bad = buf;
+        bad[9] = 0x60;
+        assert_eq!(ir.import_map(&bad[..len]), Err(MapImportError::UnknownButton(0x60)));
+
+        // Failed imports leave the existing map alone
+        assert_eq!(ir.button_map().mapped_count(), 0);
+    }
+
+    #[test]
+    fn builtin_profiles_resolve_by_remote() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        assert_eq!(send_nec(&mut ir, 0x00, 0x1C).unwrap().button, IrButton::Ok);
+        assert_eq!(send_nec(&mut ir, 0x04, 0x44).unwrap().button, IrButton::Ok);
+        // No profile for this address, and the fallback map starts empty
+        assert_eq!(send_nec(&mut ir, 0x20, 0x1C).unwrap().button, IrButton::Unknown);
+
+        let mut ir = IrRemote::new(IrProtocol::Samsung);
+        assert_eq!(send_samsung(&mut ir, 20_000, 0x07, 0x68).unwrap().button, IrButton::Ok);
+
+        // A clone remote borrows a profile's map as the fallback
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        assert!(ir.select_profile("lg-tv"));
+        assert!(!ir.select_profile("no-such-remote"));
+        assert_eq!(send_nec(&mut ir, 0x20, 0x44).unwrap().button, IrButton::Ok);
+        assert_eq!(ButtonMap::nec_21_key().mapped_count(), 21);
+    }
+
+    #[test]
+    fn custom_profiles_register_replace_and_remove() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        let custom = RemoteProfile {
+            name: "custom",
+            protocol: IrProtocol::Nec,
+            address: 0x20,
+            map: ButtonMap::from_codes(&[(0x5C, IrButton::Home)]),
+        };
+        assert_eq!(ir.register_profile(custom), Ok(()));
+        assert_eq!(send_nec(&mut ir, 0x20, 0x5C).unwrap().button, IrButton::Home);
+
+        // Same remote again replaces its profile rather than taking a slot
+        let replacement = RemoteProfile { map: ButtonMap::from_codes(&[(0x5C, IrButton::Info)]), ..custom };
+        assert_eq!(ir.register_profile(replacement), Ok(()));
+        assert_eq!(ir.profiles().count(), RemoteProfile::BUILTIN.len() + 1);
+        assert_eq!(send_nec(&mut ir, 0x20, 0x5C).unwrap().button, IrButton::Info);
+
+        // Learning binds into the profile the frame resolves through
+        ir.start_learning(IrButton::Back);
+        send_nec(&mut ir, 0x20, 0x11);
+        assert_eq!(ir.profile_for(IrProtocol::Nec, 0x20).unwrap().map.get(0x11), IrButton::Back);
+        assert_eq!(ir.button_map().mapped_count(), 0);
+
+        assert_eq!(ir.remove_profile(IrProtocol::Nec, 0x20).map(|p| p.name), Some("custom"));
+        assert_eq!(send_nec(&mut ir, 0x20, 0x5C).unwrap().button, IrButton::Unknown);
+        assert!(ir.remove_profile(IrProtocol::Nec, 0x20).is_none());
+
+        for address in 0x100..0x100 + (MAX_REMOTE_PROFILES - RemoteProfile::BUILTIN.len()) as u16 {
+            assert_eq!(ir.register_profile(RemoteProfile { address, ..custom }), Ok(()));
+        }
+        assert_eq!(ir.register_profile(custom), Err(ProfileError::Full));
+        assert_eq!(ir.register_profile(RemoteProfile::LG_TV), Ok(()));
+    }
+
+    #[test]
+    fn decodes_captured_edge_timestamps() {
+        let mut ir = IrRemote::new(IrProtocol::Nec);
+        let data = (0xFFu32 << 8) | (0x45 << 16) | (0xBA << 24);
+        // Start near the top of the timer so the frame straddles its wrap
+        let mut now = u32::MAX - 20_000;
+        let mut edge = |ir: &mut IrRemote, after: u32, level: bool| {
+            now = now.wrapping_add(after);
+            ir.process_capture(Edge { pin: IR_RECEIVER_PIN, level, time_us: now })
+        };
+
+        // Idle line goes low: the long idle "space" is ignored
+        edge(&mut ir, 0, false);
+        edge(&mut ir, 9000, true);
+        edge(&mut ir, 4500, false);
+        // Another pin's edge in between changes nothing
+        assert_eq!(ir.process_capture(Edge { pin: 17, level: true, time_us: 0 }), None);
+        let mut event = None;
+        for b in 0..32 {
+            edge(&mut ir, 562, true);
+            let space = if (data >> b) & 1 != 0 { 1687 } else { 562 };
+            event = edge(&mut ir, space, false);
+        }
+        let event = event.unwrap();
+        assert_eq!((event.address, event.command), (0x00, 0x45));
+        assert_eq!(ir.decode_errors(), 0);
+    }
+
+    #[test]
+    fn classifies_leading_marks() {
+        assert_eq!(classify_leading_mark(9100), Some(IrProtocol::Nec));
+        assert_eq!(classify_leading_mark(2700), Some(IrProtocol::Rc6));
+        assert_eq!(classify_leading_mark(900), Some(IrProtocol::Rc5));
+        assert_eq!(classify_leading_mark(1800), Some(IrProtocol::Rc5));
+        assert_eq!(classify_leading_mark(4400), Some(IrProtocol::Samsung));
+        assert_eq!(classify_leading_mark(6000), None);
+    }
+
+    #[test]
+    fn samsung_decodes_frames_and_held_resends() {
+        let mut ir = IrRemote::new(IrProtocol::Samsung);
+        let e = send_samsung(&mut ir, 100_000, 0x07, 0x02).expect("samsung frame");
+        assert_eq!((e.address, e.command, e.is_repeat), (0x07, 0x02, false));
+        assert_eq!(e.protocol, IrProtocol::Samsung);
+
+        // Held: the whole frame again ~108 ms later
+        let e = send_samsung(&mut ir, 45_000, 0x07, 0x02).expect("resent frame");
+        assert!(e.is_repeat);
+        // Pressed again after a pause
+        let e = send_samsung(&mut ir, 400_000, 0x07, 0x02).expect("new press");
+        assert!(!e.is_repeat);
+
+        // NEC's inverted address and a NEC leader are both refused
+        assert_eq!(send_nec(&mut ir, 0x07, 0x02), None);
+        let errors = ir.decode_errors();
+        let data = 0x07u32 | (0xF8 << 8) | (0x02 << 16) | (0xFD << 24);
+        ir.process_edge(4500, true);
+        ir.process_edge(4500, false);
+        for b in 0..32 {
+            ir.process_edge(560, true);
+            assert_eq!(ir.process_edge(if (data >> b) & 1 != 0 { 1690 } else { 560 }, false), None);
+        }
+        assert_eq!(ir.decode_errors(), errors + 1);
+    }
+
+    #[test]
+    fn sirc_decodes_all_frame_lengths() {
+        let mut ir = IrRemote::new(IrProtocol::Sony);
+
+        // 12 bit: command 0x15, device 1; complete once the gap follows
+        assert_eq!(send_sirc(&mut ir, 100_000, 12, 0x15 | (1 << 7)), None);
+        let e = ir.process_edge(24_000, false).expect("12 bit frame");
+        assert_eq!((e.address, e.command, e.is_repeat), (1, 0x15, false));
+        assert_eq!(e.protocol, IrProtocol::Sony);
+
+        // Held: the same frame again right after is a repeat
+        assert_eq!(send_sirc(&mut ir, 0, 12, 0x15 | (1 << 7)), None);
+        let e = ir.process_edge(24_000, false).expect("resent frame");
+        assert!(e.is_repeat);
+
+        // 15 bit, ended by the line staying idle
+        assert_eq!(send_sirc(&mut ir, 200_000, 15, 0x33 | (0x9A << 7)), None);
+        assert_eq!(ir.process_idle(300), None);
+        let e = ir.process_idle(2_000).expect("15 bit frame");
+        assert_eq!((e.address, e.command, e.is_repeat), (0x9A, 0x33, false));
+        assert_eq!(ir.process_idle(5_000), None);
+
+        // 20 bit frames end on their last mark
+        let e = send_sirc(&mut ir, 200_000, 20, 0x7F | (0x1ABC << 7)).expect("20 bit frame");
+        assert_eq!((e.address, e.command), (0x1ABC, 0x7F));
+        assert_eq!(ir.decode_errors(), 0);
+
+        // 13 bits is no SIRC frame
+        send_sirc(&mut ir, 200_000, 13, 0);
+        assert_eq!(ir.process_edge(24_000, false), None);
+        assert_eq!(ir.decode_errors(), 1);
+    }
+
+    #[test]
+    fn sirc_waits_out_a_long_mark_before_ending_a_frame() {
+        let mut ir = IrRemote::new(IrProtocol::Sony);
+        // 12 bits in, then the space before a 13th
+        send_sirc(&mut ir, 100_000, 12, 0xFFF);
+        ir.process_edge(600, false);
+        // 800 µs into the 1200 µs mark of bit 13 the line is not idle
+        assert_eq!(ir.process_idle(800), None);
+        for b in 12..15 {
+            ir.process_edge(1200, true);
+            if b < 14 {
+                ir.process_edge(600, false);
+            }
+        }
+        let e = ir.process_idle(10_000).expect("15 bit frame");
+        assert_eq!((e.address, e.command), (0xFF, 0x7F));
+    }
+
+    #[test]
+    fn auto_tells_sirc_from_rc6_and_samsung_from_nec() {
+        let mut ir = IrRemote::new(IrProtocol::Auto);
+
+        let e = send_samsung(&mut ir, 100_000, 0x07, 0x60).expect("samsung frame");
+        assert_eq!((e.command, e.protocol), (0x60, IrProtocol::Samsung));
+
+        send_sirc(&mut ir, 100_000, 12, 0x12 | (1 << 7));
+        let e = ir.process_edge(24_000, false).expect("sirc frame");
+        assert_eq!((e.address, e.command, e.protocol), (1, 0x12, IrProtocol::Sony));
+
+        let e = replay(&mut ir, &rc6_frame(false, 0x12, 0x34), 444).expect("rc6 frame");
+        assert_eq!(e.protocol, IrProtocol::Rc6);
+        let e = send_nec(&mut ir, 0x04, 0x45).expect("nec frame");
+        assert_eq!(e.protocol, IrProtocol::Nec);
+        assert_eq!(ir.decode_errors(), 0);
+    }
+}
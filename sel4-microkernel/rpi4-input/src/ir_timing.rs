@@ -0,0 +1,252 @@
+//! IR pulse timing and the verified parts of the frame decoders
+//!
+//! The tolerance window every protocol measures its pulses against, the
+//! Sony SIRC frame state machine, and the Samsung frame check. They are
+//! kept apart from [`IrRemote`](crate::IrRemote) so Verus can check them
+//! without the driver around them:
+//!
+//! - [`within_percent`] accepts exactly `nominal` ± `percent`% and cannot
+//!   overflow
+//! - [`sirc_step`] never holds more than [`SIRC_MAX_BITS`] bits, and only
+//!   completes frames of 12, 15 or 20 bits
+//! - [`samsung_fields`] only accepts frames whose two address bytes agree
+//!   and whose command is followed by its inverse
+
+use verus_builtin_macros::verus;
+
+verus! {
+
+/// SIRC leader mark (4 units of 600 µs)
+pub const SIRC_LEADER_MARK: u32 = 2400;
+/// SIRC mark for a 1 bit (2 units)
+pub const SIRC_ONE_MARK: u32 = 1200;
+/// SIRC mark for a 0 bit (1 unit)
+pub const SIRC_ZERO_MARK: u32 = 600;
+/// SIRC space after the leader and between bits (1 unit)
+pub const SIRC_SPACE: u32 = 600;
+/// SIRC timing tolerance percentage (tighter than NEC's: the 600 µs space
+/// has to stay clear of RC6's 889 µs leader space)
+pub const SIRC_TOLERANCE: u32 = 20;
+/// Longest SIRC frame: 7 command bits, 5 address bits, 8 extended bits
+pub const SIRC_MAX_BITS: u8 = 20;
+
+/// Allowed deviation from `nominal` at `percent`%
+pub open spec fn spec_margin(nominal: u32, percent: u32) -> int {
+    (nominal as int * percent as int) / 100
+}
+
+/// Is `duration` within `percent`% of `nominal`?
+pub fn within_percent(duration: u32, nominal: u32, percent: u32) -> (r: bool)
+    requires
+        nominal <= 1_000_000,
+        percent <= 100,
+    ensures
+        r == (nominal as int - spec_margin(nominal, percent) <= duration as int
+            && duration as int <= nominal as int + spec_margin(nominal, percent)),
+{
+    proof {
+        assert(nominal as int * percent as int <= 100_000_000) by (nonlinear_arith)
+            requires nominal <= 1_000_000, percent <= 100;
+        assert(nominal as int * percent as int / 100 <= nominal as int) by (nonlinear_arith)
+            requires percent <= 100;
+    }
+    let margin = nominal * percent / 100;
+    duration >= nominal - margin && duration <= nominal + margin
+}
+
+/// Frame lengths SIRC remotes send
+pub open spec fn sirc_length_ok(bits: u8) -> bool {
+    bits == 12 || bits == 15 || bits == 20
+}
+
+/// Is a SIRC frame of `bits` bits complete when a gap follows it?
+pub fn sirc_frame_length(bits: u8) -> (r: bool)
+    ensures
+        r == sirc_length_ok(bits),
+{
+    bits == 12 || bits == 15 || bits == 20
+}
+
+/// Where a SIRC frame is between edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SircState {
+    /// Waiting for a leader mark
+    Idle,
+    /// Leader mark seen, waiting for the leader space
+    Leader,
+    /// `bits` bits received into `data`, least significant first
+    Bits { bits: u8, data: u32 },
+}
+
+/// Bit count within bounds
+pub open spec fn sirc_state_ok(state: SircState) -> bool {
+    match state {
+        SircState::Bits { bits, .. } => bits < SIRC_MAX_BITS,
+        _ => true,
+    }
+}
+
+/// What one edge did to a SIRC frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SircStep {
+    /// Frame still under way (or none started)
+    Continue(SircState),
+    /// Frame of `bits` bits received
+    Done { bits: u8, data: u32 },
+    /// Pulse out of tolerance: the frame is dropped
+    Abort,
+}
+
+/// A step keeps the bit count in bounds, and only completes a frame of a
+/// length SIRC uses that was already under way
+pub open spec fn sirc_step_ok(state: SircState, step: SircStep) -> bool {
+    match step {
+        SircStep::Continue(next) => sirc_state_ok(next),
+        SircStep::Done { bits, .. } => sirc_length_ok(bits) && match state {
+            SircState::Bits { .. } => true,
+            _ => false,
+        },
+        SircStep::Abort => true,
+    }
+}
+
+/// Advance a SIRC frame by one pulse of `duration` µs
+///
+/// SIRC has no stop bit, so 12 and 15 bit frames are only known to be over
+/// when a space longer than a bit space follows them; 20 bit frames end on
+/// their last mark.
+pub fn sirc_step(state: SircState, duration: u32, is_mark: bool) -> (r: SircStep)
+    requires
+        sirc_state_ok(state),
+    ensures
+        sirc_step_ok(state, r),
+{
+    match state {
+        SircState::Idle => {
+            if is_mark && within_percent(duration, SIRC_LEADER_MARK, SIRC_TOLERANCE) {
+                SircStep::Continue(SircState::Leader)
+            } else {
+                SircStep::Continue(SircState::Idle)
+            }
+        },
+        SircState::Leader => {
+            if !is_mark && within_percent(duration, SIRC_SPACE, SIRC_TOLERANCE) {
+                SircStep::Continue(SircState::Bits { bits: 0, data: 0 })
+            } else {
+                SircStep::Abort
+            }
+        },
+        SircState::Bits { bits, data } => {
+            if is_mark {
+                let one: u32 = if within_percent(duration, SIRC_ONE_MARK, SIRC_TOLERANCE) {
+                    1
+                } else if within_percent(duration, SIRC_ZERO_MARK, SIRC_TOLERANCE) {
+                    0
+                } else {
+                    return SircStep::Abort;
+                };
+                let data = data | (one << (bits as u32));
+                let bits = bits + 1;
+                if bits == SIRC_MAX_BITS {
+                    SircStep::Done { bits, data }
+                } else {
+                    SircStep::Continue(SircState::Bits { bits, data })
+                }
+            } else if within_percent(duration, SIRC_SPACE, SIRC_TOLERANCE) {
+                SircStep::Continue(state)
+            } else if duration > SIRC_SPACE && sirc_frame_length(bits) {
+                SircStep::Done { bits, data }
+            } else {
+                SircStep::Abort
+            }
+        },
+    }
+}
+
+/// Address and command of a SIRC frame: the 7 command bits come first,
+/// then 5, 8 or 13 address bits (the 20 bit form's 8 extended bits land
+/// above the 5 device bits)
+pub fn sirc_fields(data: u32) -> (r: (u16, u8))
+    ensures
+        r.1 < 128,
+{
+    proof {
+        assert(data & 0x7F <= 0x7F) by (bit_vector);
+    }
+    (((data >> 7) & 0x1FFF) as u16, (data & 0x7F) as u8)
+}
+
+/// Address and command of a 32 bit Samsung frame (address, address
+/// again, command, inverted command, least significant byte first), or
+/// `None` if the redundancy does not check out
+pub fn samsung_fields(data: u32) -> (r: Option<(u8, u8)>)
+    ensures
+        r.is_some() ==> (data & 0xFF) as u8 == ((data >> 8) & 0xFF) as u8
+            && r.unwrap().0 == (data & 0xFF) as u8
+            && r.unwrap().1 == ((data >> 16) & 0xFF) as u8
+            && ((data >> 16) & 0xFF) as u8 == !(((data >> 24) & 0xFF) as u8),
+{
+    let address = (data & 0xFF) as u8;
+    let address_again = ((data >> 8) & 0xFF) as u8;
+    let command = ((data >> 16) & 0xFF) as u8;
+    let command_inv = ((data >> 24) & 0xFF) as u8;
+    if address == address_again && command == !command_inv {
+        Some((address, command))
+    } else {
+        None
+    }
+}
+
+} // verus!
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerance_window_is_inclusive() {
+        assert!(within_percent(480, 600, 20));
+        assert!(within_percent(720, 600, 20));
+        assert!(!within_percent(479, 600, 20));
+        assert!(!within_percent(721, 600, 20));
+        assert!(within_percent(0, 0, 100));
+        assert!(within_percent(2_000_000, 1_000_000, 100));
+    }
+
+    #[test]
+    fn sirc_frames_end_only_at_valid_lengths() {
+        let mut state = SircState::Idle;
+        for (duration, is_mark) in [(2400, true), (600, false)] {
+            match sirc_step(state, duration, is_mark) {
+                SircStep::Continue(next) => state = next,
+                other => panic!("{:?}", other),
+            }
+        }
+        assert_eq!(state, SircState::Bits { bits: 0, data: 0 });
+        for bits in 0..SIRC_MAX_BITS {
+            let gap = sirc_step(state, 20_000, false);
+            if sirc_frame_length(bits) {
+                assert_eq!(gap, SircStep::Done { bits, data: (1 << bits) - 1 });
+            } else {
+                assert_eq!(gap, SircStep::Abort);
+            }
+            match sirc_step(state, 1200, true) {
+                SircStep::Continue(next) => state = next,
+                SircStep::Done { bits, data } => {
+                    assert_eq!((bits, data), (20, 0xF_FFFF));
+                    return;
+                }
+                SircStep::Abort => panic!("bit {} refused", bits),
+            }
+        }
+        panic!("20 bit frame never completed");
+    }
+
+    #[test]
+    fn samsung_fields_check_redundancy() {
+        assert_eq!(samsung_fields(0xFD02_0707), Some((0x07, 0x02)));
+        assert_eq!(samsung_fields(0xFD02_F807), None);
+        assert_eq!(samsung_fields(0xFC02_0707), None);
+        assert_eq!(sirc_fields(0x15 | (0x1ABC << 7)), (0x1ABC, 0x15));
+    }
+}
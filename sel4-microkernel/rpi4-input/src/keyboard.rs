@@ -1,7 +1,12 @@
 //! Keyboard input driver
 //!
 //! Supports USB HID keyboard input for remote control functionality.
-//! Common keycodes are mapped for media/navigation control.
+//! Decodes boot-protocol reports over the full Keyboard/Keypad usage table
+//! (letters, digits, punctuation, function keys, keypad), tracks modifiers
+//! and lock keys, and translates key positions to characters for a
+//! selectable [`KeyboardLayout`] (US QWERTY or French AZERTY).
+
+use verified_microkernel::{ring_add_usize, ring_next_usize};
 
 /// USB HID Keyboard base address (depends on USB controller setup)
 pub const USB_HID_BASE: usize = 0xFE980000;
@@ -15,10 +20,67 @@ pub enum KeyState {
     Released,
 }
 
-/// Common key codes for remote control functionality
+/// USB HID keyboard usage IDs (HID Usage Tables, Keyboard/Keypad page 0x07)
+///
+/// Discriminants are the raw usage IDs, named after the US QWERTY legend
+/// at that key position; [`KeyboardLayout`] maps them to characters.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum KeyCode {
+    // Letters
+    /// A
+    A = 0x04,
+    /// B
+    B = 0x05,
+    /// C
+    C = 0x06,
+    /// D
+    D = 0x07,
+    /// E
+    E = 0x08,
+    /// F
+    F = 0x09,
+    /// G
+    G = 0x0A,
+    /// H
+    H = 0x0B,
+    /// I
+    I = 0x0C,
+    /// J
+    J = 0x0D,
+    /// K
+    K = 0x0E,
+    /// L
+    L = 0x0F,
+    /// M
+    M = 0x10,
+    /// N
+    N = 0x11,
+    /// O
+    O = 0x12,
+    /// P
+    P = 0x13,
+    /// Q
+    Q = 0x14,
+    /// R
+    R = 0x15,
+    /// S
+    S = 0x16,
+    /// T
+    T = 0x17,
+    /// U
+    U = 0x18,
+    /// V
+    V = 0x19,
+    /// W
+    W = 0x1A,
+    /// X
+    X = 0x1B,
+    /// Y
+    Y = 0x1C,
+    /// Z
+    Z = 0x1D,
+
     // Navigation keys
     /// Up arrow
     Up = 0x52,
@@ -57,6 +119,46 @@ pub enum KeyCode {
     /// Key 9
     Num9 = 0x26,
 
+    // Editing and punctuation
+    /// Backspace
+    Backspace = 0x2A,
+    /// Tab
+    Tab = 0x2B,
+    /// - and _
+    Minus = 0x2D,
+    /// = and +
+    Equal = 0x2E,
+    /// [ and {
+    LeftBracket = 0x2F,
+    /// ] and }
+    RightBracket = 0x30,
+    /// \ and |
+    Backslash = 0x31,
+    /// Non-US # and ~ (ISO key left of Enter)
+    NonUsHash = 0x32,
+    /// ; and :
+    Semicolon = 0x33,
+    /// ' and "
+    Apostrophe = 0x34,
+    /// ` and ~
+    Grave = 0x35,
+    /// , and <
+    Comma = 0x36,
+    /// . and >
+    Period = 0x37,
+    /// / and ?
+    Slash = 0x38,
+    /// Non-US \ and | (ISO key right of left shift)
+    NonUsBackslash = 0x64,
+
+    // Lock keys
+    /// Caps Lock
+    CapsLock = 0x39,
+    /// Scroll Lock
+    ScrollLock = 0x47,
+    /// Num Lock
+    NumLock = 0x53,
+
     // Media control keys
     /// Volume Up (F12 as fallback)
     VolumeUp = 0x80,
@@ -82,8 +184,30 @@ pub enum KeyCode {
     F3 = 0x3C,
     /// F4 - Settings
     F4 = 0x3D,
+    /// F5
+    F5 = 0x3E,
+    /// F6
+    F6 = 0x3F,
+    /// F7
+    F7 = 0x40,
+    /// F8
+    F8 = 0x41,
+    /// F9
+    F9 = 0x42,
+    /// F10
+    F10 = 0x43,
+    /// F11
+    F11 = 0x44,
+    /// F12
+    F12 = 0x45,
 
     // Special keys
+    /// Print Screen
+    PrintScreen = 0x46,
+    /// Pause/Break
+    Pause = 0x48,
+    /// Insert
+    Insert = 0x49,
     /// Home
     Home = 0x4A,
     /// End
@@ -92,6 +216,44 @@ pub enum KeyCode {
     PageUp = 0x4B,
     /// Page Down (Channel Down)
     PageDown = 0x4E,
+    /// Delete (forward)
+    Delete = 0x4C,
+    /// Application/Menu key
+    Application = 0x65,
+
+    // Keypad
+    /// Keypad /
+    KpDivide = 0x54,
+    /// Keypad *
+    KpMultiply = 0x55,
+    /// Keypad -
+    KpMinus = 0x56,
+    /// Keypad +
+    KpPlus = 0x57,
+    /// Keypad Enter
+    KpEnter = 0x58,
+    /// Keypad 1 / End
+    Kp1 = 0x59,
+    /// Keypad 2 / Down
+    Kp2 = 0x5A,
+    /// Keypad 3 / Page Down
+    Kp3 = 0x5B,
+    /// Keypad 4 / Left
+    Kp4 = 0x5C,
+    /// Keypad 5
+    Kp5 = 0x5D,
+    /// Keypad 6 / Right
+    Kp6 = 0x5E,
+    /// Keypad 7 / Home
+    Kp7 = 0x5F,
+    /// Keypad 8 / Up
+    Kp8 = 0x60,
+    /// Keypad 9 / Page Up
+    Kp9 = 0x61,
+    /// Keypad 0 / Insert
+    Kp0 = 0x62,
+    /// Keypad . / Delete
+    KpPeriod = 0x63,
 
     /// Unknown key
     Unknown = 0x00,
@@ -133,6 +295,79 @@ impl KeyCode {
             0x4D => KeyCode::End,
             0x4B => KeyCode::PageUp,
             0x4E => KeyCode::PageDown,
+            0x04 => KeyCode::A,
+            0x05 => KeyCode::B,
+            0x06 => KeyCode::C,
+            0x07 => KeyCode::D,
+            0x08 => KeyCode::E,
+            0x09 => KeyCode::F,
+            0x0A => KeyCode::G,
+            0x0B => KeyCode::H,
+            0x0C => KeyCode::I,
+            0x0D => KeyCode::J,
+            0x0E => KeyCode::K,
+            0x0F => KeyCode::L,
+            0x10 => KeyCode::M,
+            0x11 => KeyCode::N,
+            0x12 => KeyCode::O,
+            0x13 => KeyCode::P,
+            0x14 => KeyCode::Q,
+            0x15 => KeyCode::R,
+            0x16 => KeyCode::S,
+            0x17 => KeyCode::T,
+            0x18 => KeyCode::U,
+            0x19 => KeyCode::V,
+            0x1A => KeyCode::W,
+            0x1B => KeyCode::X,
+            0x1C => KeyCode::Y,
+            0x1D => KeyCode::Z,
+            0x2A => KeyCode::Backspace,
+            0x2B => KeyCode::Tab,
+            0x2D => KeyCode::Minus,
+            0x2E => KeyCode::Equal,
+            0x2F => KeyCode::LeftBracket,
+            0x30 => KeyCode::RightBracket,
+            0x31 => KeyCode::Backslash,
+            0x32 => KeyCode::NonUsHash,
+            0x33 => KeyCode::Semicolon,
+            0x34 => KeyCode::Apostrophe,
+            0x35 => KeyCode::Grave,
+            0x36 => KeyCode::Comma,
+            0x37 => KeyCode::Period,
+            0x38 => KeyCode::Slash,
+            0x39 => KeyCode::CapsLock,
+            0x3E => KeyCode::F5,
+            0x3F => KeyCode::F6,
+            0x40 => KeyCode::F7,
+            0x41 => KeyCode::F8,
+            0x42 => KeyCode::F9,
+            0x43 => KeyCode::F10,
+            0x44 => KeyCode::F11,
+            0x45 => KeyCode::F12,
+            0x46 => KeyCode::PrintScreen,
+            0x47 => KeyCode::ScrollLock,
+            0x48 => KeyCode::Pause,
+            0x49 => KeyCode::Insert,
+            0x4C => KeyCode::Delete,
+            0x53 => KeyCode::NumLock,
+            0x54 => KeyCode::KpDivide,
+            0x55 => KeyCode::KpMultiply,
+            0x56 => KeyCode::KpMinus,
+            0x57 => KeyCode::KpPlus,
+            0x58 => KeyCode::KpEnter,
+            0x59 => KeyCode::Kp1,
+            0x5A => KeyCode::Kp2,
+            0x5B => KeyCode::Kp3,
+            0x5C => KeyCode::Kp4,
+            0x5D => KeyCode::Kp5,
+            0x5E => KeyCode::Kp6,
+            0x5F => KeyCode::Kp7,
+            0x60 => KeyCode::Kp8,
+            0x61 => KeyCode::Kp9,
+            0x62 => KeyCode::Kp0,
+            0x63 => KeyCode::KpPeriod,
+            0x64 => KeyCode::NonUsBackslash,
+            0x65 => KeyCode::Application,
             _ => KeyCode::Unknown,
         }
     }
@@ -201,6 +436,30 @@ impl KeyCode {
                 | KeyCode::PrevTrack
         )
     }
+
+    /// Check if this is a letter key (A-Z)
+    pub fn is_letter(&self) -> bool {
+        (KeyCode::A as u8..=KeyCode::Z as u8).contains(&(*self as u8))
+    }
+
+    /// Check if this is a function key (F1-F12)
+    pub fn is_function(&self) -> bool {
+        (KeyCode::F1 as u8..=KeyCode::F12 as u8).contains(&(*self as u8))
+    }
+
+    /// Check if this is a keypad key
+    pub fn is_keypad(&self) -> bool {
+        (KeyCode::KpDivide as u8..=KeyCode::KpPeriod as u8).contains(&(*self as u8))
+    }
+
+    /// Lowercase letter printed on this key position on a US keyboard
+    fn us_letter(&self) -> Option<char> {
+        if self.is_letter() {
+            Some((b'a' + (*self as u8 - KeyCode::A as u8)) as char)
+        } else {
+            None
+        }
+    }
 }
 
 /// Keyboard key event
@@ -223,13 +482,240 @@ pub struct KeyModifiers {
     pub ctrl: bool,
     /// Left or right alt is held
     pub alt: bool,
+    /// Right alt is held (AltGr on international layouts)
+    pub altgr: bool,
+    /// Left or right GUI (Windows/Command) is held
+    pub gui: bool,
+    /// Caps Lock is on
+    pub caps_lock: bool,
+    /// Num Lock is on
+    pub num_lock: bool,
+}
+
+impl KeyModifiers {
+    /// Decode the modifier byte of a boot report. Lock states are not part
+    /// of the report; the [`Keyboard`] tracks them and fills them in.
+    pub const fn from_hid(byte: u8) -> Self {
+        Self {
+            shift: (byte & MOD_SHIFT) != 0,
+            ctrl: (byte & MOD_CTRL) != 0,
+            alt: (byte & MOD_ALT) != 0,
+            altgr: (byte & MOD_RIGHT_ALT) != 0,
+            gui: (byte & MOD_GUI) != 0,
+            caps_lock: false,
+            num_lock: false,
+        }
+    }
+}
+
+// Modifier byte bits (HID 1.11 Appendix B.1): left in the low nibble,
+// right in the high nibble.
+const MOD_CTRL: u8 = 0x11;
+const MOD_SHIFT: u8 = 0x22;
+const MOD_ALT: u8 = 0x44;
+const MOD_GUI: u8 = 0x88;
+const MOD_RIGHT_ALT: u8 = 0x40;
+
+/// First and last of the reserved error usages (ErrorRollOver, POSTFail,
+/// ErrorUndefined). A keyboard fills every key slot with ErrorRollOver
+/// when more keys are down than it can report, or when its matrix cannot
+/// tell real presses from ghosts.
+const HID_ERROR_FIRST: u8 = 0x01;
+const HID_ERROR_LAST: u8 = 0x03;
+
+/// LED output report bits (HID 1.11 Appendix B.1)
+pub const LED_NUM_LOCK: u8 = 0x01;
+/// Caps Lock LED bit of the output report
+pub const LED_CAPS_LOCK: u8 = 0x02;
+
+/// Events one report can produce: six releases plus six presses, with
+/// room for a report that arrives before the previous one was drained
+const EVENT_QUEUE_LEN: usize = 16;
+
+/// Character layout used to translate key positions to text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    /// US QWERTY
+    #[default]
+    Qwerty,
+    /// French AZERTY
+    Azerty,
+}
+
+impl KeyboardLayout {
+    /// Translate a key to the character it types under this layout.
+    ///
+    /// Returns None for keys that do not type text (navigation, function,
+    /// lock keys), for dead keys, and while ctrl, GUI or (left) alt is held,
+    /// since those combinations are shortcuts rather than text.
+    pub fn to_char(self, key: KeyCode, modifiers: KeyModifiers) -> Option<char> {
+        if modifiers.ctrl || modifiers.gui || (modifiers.alt && !modifiers.altgr) {
+            return None;
+        }
+
+        if key.is_keypad() {
+            return keypad_char(key, modifiers.num_lock);
+        }
+        match key {
+            KeyCode::Enter => return Some('\n'),
+            KeyCode::Tab => return Some('\t'),
+            KeyCode::Space => return Some(' '),
+            _ => {}
+        }
+
+        if modifiers.altgr {
+            return match self {
+                KeyboardLayout::Qwerty => None,
+                KeyboardLayout::Azerty => azerty_altgr_char(key),
+            };
+        }
+
+        if let Some(letter) = self.letter(key) {
+            return Some(if modifiers.shift != modifiers.caps_lock {
+                letter.to_ascii_uppercase()
+            } else {
+                letter
+            });
+        }
+
+        let (plain, shifted) = match self {
+            KeyboardLayout::Qwerty => qwerty_symbol(key)?,
+            KeyboardLayout::Azerty => azerty_symbol(key)?,
+        };
+        if modifiers.shift { shifted } else { plain }
+    }
+
+    /// Lowercase letter at this key position, if it is a letter here
+    fn letter(self, key: KeyCode) -> Option<char> {
+        match self {
+            KeyboardLayout::Qwerty => key.us_letter(),
+            KeyboardLayout::Azerty => match key {
+                KeyCode::A => Some('q'),
+                KeyCode::Q => Some('a'),
+                KeyCode::W => Some('z'),
+                KeyCode::Z => Some('w'),
+                KeyCode::Semicolon => Some('m'),
+                KeyCode::M => None,
+                _ => key.us_letter(),
+            },
+        }
+    }
+}
+
+/// Keypad character; the digits and point only type while Num Lock is on
+fn keypad_char(key: KeyCode, num_lock: bool) -> Option<char> {
+    match key {
+        KeyCode::KpDivide => Some('/'),
+        KeyCode::KpMultiply => Some('*'),
+        KeyCode::KpMinus => Some('-'),
+        KeyCode::KpPlus => Some('+'),
+        KeyCode::KpEnter => Some('\n'),
+        _ if !num_lock => None,
+        KeyCode::KpPeriod => Some('.'),
+        KeyCode::Kp0 => Some('0'),
+        _ => Some((b'1' + (key as u8 - KeyCode::Kp1 as u8)) as char),
+    }
+}
+
+/// (unshifted, shifted) symbols of the non-letter keys on US QWERTY
+fn qwerty_symbol(key: KeyCode) -> Option<(Option<char>, Option<char>)> {
+    let pair = match key {
+        KeyCode::Num1 => ('1', '!'),
+        KeyCode::Num2 => ('2', '@'),
+        KeyCode::Num3 => ('3', '#'),
+        KeyCode::Num4 => ('4', '$'),
+        KeyCode::Num5 => ('5', '%'),
+        KeyCode::Num6 => ('6', '^'),
+        KeyCode::Num7 => ('7', '&'),
+        KeyCode::Num8 => ('8', '*'),
+        KeyCode::Num9 => ('9', '('),
+        KeyCode::Num0 => ('0', ')'),
+        KeyCode::Minus => ('-', '_'),
+        KeyCode::Equal => ('=', '+'),
+        KeyCode::LeftBracket => ('[', '{'),
+        KeyCode::RightBracket => (']', '}'),
+        KeyCode::Backslash | KeyCode::NonUsHash => ('\\', '|'),
+        KeyCode::Semicolon => (';', ':'),
+        KeyCode::Apostrophe => ('\'', '"'),
+        KeyCode::Grave => ('`', '~'),
+        KeyCode::Comma => (',', '<'),
+        KeyCode::Period => ('.', '>'),
+        KeyCode::Slash => ('/', '?'),
+        KeyCode::NonUsBackslash => ('<', '>'),
+        _ => return None,
+    };
+    Some((Some(pair.0), Some(pair.1)))
+}
+
+/// (unshifted, shifted) symbols of the non-letter keys on French AZERTY.
+/// The circumflex/diaeresis key is a dead key and types nothing by itself.
+fn azerty_symbol(key: KeyCode) -> Option<(Option<char>, Option<char>)> {
+    let pair = match key {
+        KeyCode::Num1 => (Some('&'), Some('1')),
+        KeyCode::Num2 => (Some('é'), Some('2')),
+        KeyCode::Num3 => (Some('"'), Some('3')),
+        KeyCode::Num4 => (Some('\''), Some('4')),
+        KeyCode::Num5 => (Some('('), Some('5')),
+        KeyCode::Num6 => (Some('-'), Some('6')),
+        KeyCode::Num7 => (Some('è'), Some('7')),
+        KeyCode::Num8 => (Some('_'), Some('8')),
+        KeyCode::Num9 => (Some('ç'), Some('9')),
+        KeyCode::Num0 => (Some('à'), Some('0')),
+        KeyCode::Minus => (Some(')'), Some('°')),
+        KeyCode::Equal => (Some('='), Some('+')),
+        KeyCode::LeftBracket => (None, None),
+        KeyCode::RightBracket => (Some('$'), Some('£')),
+        KeyCode::Backslash | KeyCode::NonUsHash => (Some('*'), Some('µ')),
+        KeyCode::Apostrophe => (Some('ù'), Some('%')),
+        KeyCode::Grave => (Some('²'), None),
+        KeyCode::M => (Some(','), Some('?')),
+        KeyCode::Comma => (Some(';'), Some('.')),
+        KeyCode::Period => (Some(':'), Some('/')),
+        KeyCode::Slash => (Some('!'), Some('§')),
+        KeyCode::NonUsBackslash => (Some('<'), Some('>')),
+        _ => return None,
+    };
+    Some(pair)
+}
+
+/// Third-level (AltGr) symbols on French AZERTY
+fn azerty_altgr_char(key: KeyCode) -> Option<char> {
+    match key {
+        KeyCode::Num2 => Some('~'),
+        KeyCode::Num3 => Some('#'),
+        KeyCode::Num4 => Some('{'),
+        KeyCode::Num5 => Some('['),
+        KeyCode::Num6 => Some('|'),
+        KeyCode::Num7 => Some('`'),
+        KeyCode::Num8 => Some('\\'),
+        KeyCode::Num9 => Some('^'),
+        KeyCode::Num0 => Some('@'),
+        KeyCode::Minus => Some(']'),
+        KeyCode::Equal => Some('}'),
+        KeyCode::RightBracket => Some('¤'),
+        KeyCode::E => Some('€'),
+        _ => None,
+    }
 }
 
 /// Keyboard driver
+///
+/// Decodes 8-byte HID boot-protocol reports into press/release events.
+/// One report can change several keys at once, so events are queued and
+/// drained through [`poll`](Self::poll).
 pub struct Keyboard {
     base: usize,
     modifiers: KeyModifiers,
     last_keys: [u8; 6],
+    layout: KeyboardLayout,
+    /// Pending events, oldest at `head`
+    events: [Option<KeyEvent>; EVENT_QUEUE_LEN],
+    head: usize,
+    len: usize,
+    /// Reports discarded because the keyboard signalled rollover
+    rollover_reports: u32,
+    /// Usage ID of the last key that entered a report
+    last_scancode: u8,
 }
 
 impl Keyboard {
@@ -242,25 +728,33 @@ impl Keyboard {
     pub const fn with_base(base: usize) -> Self {
         Self {
             base,
-            modifiers: KeyModifiers {
-                shift: false,
-                ctrl: false,
-                alt: false,
-            },
+            modifiers: KeyModifiers::from_hid(0),
             last_keys: [0; 6],
+            layout: KeyboardLayout::Qwerty,
+            events: [None; EVENT_QUEUE_LEN],
+            head: 0,
+            len: 0,
+            rollover_reports: 0,
+            last_scancode: 0,
         }
     }
 
     /// Poll for keyboard events.
     ///
     /// [`Keyboard`] is the HID *report decoder*; it has no USB transport of its
-    /// own, so polling it directly yields nothing. The working input path is
-    /// `crate::usb::UsbKeyboard` (behind the `usb` feature), which drives the
-    /// DWC2 host controller and feeds each 8-byte report to
-    /// [`process_hid_report`](Self::process_hid_report).
-    /// This method remains for callers that inject reports another way.
+    /// own. This drains the events decoded from reports passed to
+    /// [`process_hid_report`](Self::process_hid_report), oldest first. The
+    /// working input path is `crate::usb::UsbKeyboard` (behind the `usb`
+    /// feature), which drives the DWC2 host controller and feeds each 8-byte
+    /// report in.
     pub fn poll(&mut self) -> Option<KeyEvent> {
-        None
+        if self.len == 0 {
+            return None;
+        }
+        let event = self.events[self.head].take();
+        self.head = ring_next_usize(self.head, EVENT_QUEUE_LEN);
+        self.len -= 1;
+        event
     }
 
     /// Check if any key is currently pressed
@@ -273,52 +767,113 @@ impl Keyboard {
         self.modifiers
     }
 
-    /// Process a raw HID report (8 bytes)
+    /// Current character layout
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// Select the character layout used by [`to_char`](Self::to_char)
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+    }
+
+    /// Character typed by a key press under the current layout.
+    /// Releases never type anything.
+    pub fn to_char(&self, event: &KeyEvent) -> Option<char> {
+        match event.state {
+            KeyState::Pressed => self.layout.to_char(event.key, event.modifiers),
+            KeyState::Released => None,
+        }
+    }
+
+    /// LED output report matching the lock states, for the transport to
+    /// send back to the keyboard (SET_REPORT)
+    pub fn led_report(&self) -> u8 {
+        let mut leds = 0;
+        if self.modifiers.num_lock {
+            leds |= LED_NUM_LOCK;
+        }
+        if self.modifiers.caps_lock {
+            leds |= LED_CAPS_LOCK;
+        }
+        leds
+    }
+
+    /// Number of reports discarded because the keyboard signalled rollover
+    pub fn rollover_reports(&self) -> u32 {
+        self.rollover_reports
+    }
+
+    /// Usage ID of the last key pressed, including keys with no [`KeyCode`]
+    /// (0 before the first press)
+    pub fn last_scancode(&self) -> u8 {
+        self.last_scancode
+    }
+
+    /// Queue an event, dropping the oldest if the caller stopped draining
+    fn push(&mut self, code: u8, state: KeyState) {
+        if self.len == EVENT_QUEUE_LEN {
+            self.head = ring_next_usize(self.head, EVENT_QUEUE_LEN);
+            self.len -= 1;
+        }
+        let tail = ring_add_usize(self.head, self.len, EVENT_QUEUE_LEN);
+        self.events[tail] = Some(KeyEvent {
+            key: KeyCode::from_scancode(code),
+            state,
+            modifiers: self.modifiers,
+        });
+        self.len += 1;
+    }
+
+    /// Process a raw HID report (8 bytes).
+    ///
+    /// Queues a release for every key that left the report and a press for
+    /// every key that entered it, then returns the oldest pending event
+    /// (the rest are returned by [`poll`](Self::poll)).
+    ///
+    /// A report whose key slots carry an error usage is a rollover/ghosting
+    /// report: the keyboard cannot say which keys are really down, so the
+    /// key state is left as it was until a clean report arrives. Its
+    /// modifier byte is still valid and is applied.
     pub fn process_hid_report(&mut self, report: &[u8; 8]) -> Option<KeyEvent> {
         // Byte 0: Modifier keys
-        let mod_byte = report[0];
-        self.modifiers = KeyModifiers {
-            shift: (mod_byte & 0x22) != 0, // Left or right shift
-            ctrl: (mod_byte & 0x11) != 0,  // Left or right ctrl
-            alt: (mod_byte & 0x44) != 0,   // Left or right alt
-        };
+        let mut modifiers = KeyModifiers::from_hid(report[0]);
+        modifiers.caps_lock = self.modifiers.caps_lock;
+        modifiers.num_lock = self.modifiers.num_lock;
+        self.modifiers = modifiers;
 
         // Byte 1: Reserved
         // Bytes 2-7: Up to 6 keycodes
+        let keys = &report[2..8];
+        if keys.iter().any(|&k| (HID_ERROR_FIRST..=HID_ERROR_LAST).contains(&k)) {
+            self.rollover_reports = self.rollover_reports.wrapping_add(1);
+            return self.poll();
+        }
 
-        // Find new key presses (in current report but not in last)
-        for i in 2..8 {
-            let key = report[i];
-            if key != 0 && !self.last_keys.contains(&key) {
-                // Update last keys
-                self.last_keys.copy_from_slice(&report[2..8]);
-
-                return Some(KeyEvent {
-                    key: KeyCode::from_scancode(key),
-                    state: KeyState::Pressed,
-                    modifiers: self.modifiers,
-                });
+        // Released keys (in last report but not in current)
+        let last_keys = self.last_keys;
+        for &key in &last_keys {
+            if key != 0 && !keys.contains(&key) {
+                self.push(key, KeyState::Released);
             }
         }
 
-        // Find released keys (in last report but not in current)
-        for &key in &self.last_keys {
-            if key != 0 && !report[2..8].contains(&key) {
-                // Update last keys
-                self.last_keys.copy_from_slice(&report[2..8]);
-
-                return Some(KeyEvent {
-                    key: KeyCode::from_scancode(key),
-                    state: KeyState::Released,
-                    modifiers: self.modifiers,
-                });
+        // New key presses (in current report but not in last), ignoring a
+        // usage repeated within the same report
+        for (i, &key) in keys.iter().enumerate() {
+            if key != 0 && !last_keys.contains(&key) && !keys[..i].contains(&key) {
+                match KeyCode::from_scancode(key) {
+                    KeyCode::CapsLock => self.modifiers.caps_lock = !self.modifiers.caps_lock,
+                    KeyCode::NumLock => self.modifiers.num_lock = !self.modifiers.num_lock,
+                    _ => {}
+                }
+                self.last_scancode = key;
+                self.push(key, KeyState::Pressed);
             }
         }
 
-        // Update last keys for next comparison
-        self.last_keys.copy_from_slice(&report[2..8]);
-
-        None
+        self.last_keys.copy_from_slice(keys);
+        self.poll()
     }
 }
 
@@ -327,3 +882,168 @@ impl Default for Keyboard {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(modifiers: u8, keys: &[u8]) -> [u8; 8] {
+        let mut r = [0u8; 8];
+        r[0] = modifiers;
+        r[2..2 + keys.len()].copy_from_slice(keys);
+        r
+    }
+
+    fn drain(kb: &mut Keyboard, first: Option<KeyEvent>) -> ([KeyEvent; 12], usize) {
+        let mut out = [KeyEvent {
+            key: KeyCode::Unknown,
+            state: KeyState::Released,
+            modifiers: KeyModifiers::default(),
+        }; 12];
+        let mut n = 0;
+        let mut next = first;
+        while let Some(event) = next {
+            out[n] = event;
+            n += 1;
+            next = kb.poll();
+        }
+        (out, n)
+    }
+
+    #[test]
+    fn scancodes_round_trip_over_the_usage_table() {
+        for code in 0x04..=0x65u8 {
+            let key = KeyCode::from_scancode(code);
+            assert_eq!(key as u8, code, "usage 0x{:02x}", code);
+        }
+        assert_eq!(KeyCode::from_scancode(0xE0), KeyCode::Unknown);
+        assert!(KeyCode::Q.is_letter() && KeyCode::F12.is_function() && KeyCode::Kp0.is_keypad());
+        assert!(!KeyCode::Num1.is_keypad());
+    }
+
+    #[test]
+    fn report_with_several_changes_yields_every_event() {
+        let mut kb = Keyboard::new();
+        let first = kb.process_hid_report(&report(0, &[0x04, 0x05]));
+        let (events, n) = drain(&mut kb, first);
+        assert_eq!(n, 2);
+        assert_eq!((events[0].key, events[0].state), (KeyCode::A, KeyState::Pressed));
+        assert_eq!((events[1].key, events[1].state), (KeyCode::B, KeyState::Pressed));
+
+        // Roll from A+B to C: two releases, then the press
+        let first = kb.process_hid_report(&report(0, &[0x06]));
+        let (events, n) = drain(&mut kb, first);
+        assert_eq!(n, 3);
+        assert_eq!((events[0].key, events[0].state), (KeyCode::A, KeyState::Released));
+        assert_eq!((events[1].key, events[1].state), (KeyCode::B, KeyState::Released));
+        assert_eq!((events[2].key, events[2].state), (KeyCode::C, KeyState::Pressed));
+        assert!(kb.has_input());
+    }
+
+    #[test]
+    fn last_scancode_keeps_unmapped_usages() {
+        let mut kb = Keyboard::new();
+        assert_eq!(kb.last_scancode(), 0);
+        let event = kb.process_hid_report(&report(0, &[0xE8])).unwrap();
+        assert_eq!(event.key, KeyCode::Unknown);
+        assert_eq!(kb.last_scancode(), 0xE8);
+
+        // Releases leave it alone
+        kb.process_hid_report(&report(0, &[]));
+        assert_eq!(kb.last_scancode(), 0xE8);
+    }
+
+    #[test]
+    fn rollover_report_keeps_key_state() {
+        let mut kb = Keyboard::new();
+        let first = kb.process_hid_report(&report(0, &[0x04, 0x05]));
+        drain(&mut kb, first);
+
+        assert_eq!(kb.process_hid_report(&report(0x02, &[0x01; 6])), None);
+        assert_eq!(kb.rollover_reports(), 1);
+        assert!(kb.modifiers().shift);
+
+        // Clean report again: only the real change comes through
+        let first = kb.process_hid_report(&report(0, &[0x04]));
+        let (events, n) = drain(&mut kb, first);
+        assert_eq!(n, 1);
+        assert_eq!((events[0].key, events[0].state), (KeyCode::B, KeyState::Released));
+    }
+
+    #[test]
+    fn duplicate_usage_in_report_presses_once() {
+        let mut kb = Keyboard::new();
+        let first = kb.process_hid_report(&report(0, &[0x04, 0x04]));
+        let (_, n) = drain(&mut kb, first);
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn modifiers_and_locks_are_tracked() {
+        let mut kb = Keyboard::new();
+        let event = kb.process_hid_report(&report(0x41, &[0x04])).unwrap();
+        assert!(event.modifiers.ctrl && event.modifiers.altgr && event.modifiers.alt);
+        assert!(!event.modifiers.shift);
+
+        kb.process_hid_report(&report(0, &[0x39]));
+        assert!(kb.modifiers().caps_lock);
+        assert_eq!(kb.led_report(), LED_CAPS_LOCK);
+        kb.process_hid_report(&report(0, &[]));
+        kb.process_hid_report(&report(0, &[0x39]));
+        assert!(!kb.modifiers().caps_lock);
+        assert_eq!(kb.led_report(), 0);
+    }
+
+    #[test]
+    fn qwerty_translation() {
+        let none = KeyModifiers::default();
+        let shift = KeyModifiers { shift: true, ..none };
+        let caps = KeyModifiers { caps_lock: true, ..none };
+        let layout = KeyboardLayout::Qwerty;
+
+        assert_eq!(layout.to_char(KeyCode::A, none), Some('a'));
+        assert_eq!(layout.to_char(KeyCode::A, shift), Some('A'));
+        assert_eq!(layout.to_char(KeyCode::A, caps), Some('A'));
+        assert_eq!(layout.to_char(KeyCode::A, KeyModifiers { shift: true, ..caps }), Some('a'));
+        assert_eq!(layout.to_char(KeyCode::Num2, shift), Some('@'));
+        assert_eq!(layout.to_char(KeyCode::Num2, caps), Some('2'));
+        assert_eq!(layout.to_char(KeyCode::Slash, shift), Some('?'));
+        assert_eq!(layout.to_char(KeyCode::C, KeyModifiers { ctrl: true, ..none }), None);
+        assert_eq!(layout.to_char(KeyCode::F5, none), None);
+
+        assert_eq!(layout.to_char(KeyCode::Kp7, none), None);
+        assert_eq!(layout.to_char(KeyCode::Kp7, KeyModifiers { num_lock: true, ..none }), Some('7'));
+        assert_eq!(layout.to_char(KeyCode::KpPlus, none), Some('+'));
+    }
+
+    #[test]
+    fn azerty_translation() {
+        let none = KeyModifiers::default();
+        let shift = KeyModifiers { shift: true, ..none };
+        let altgr = KeyModifiers { alt: true, altgr: true, ..none };
+        let layout = KeyboardLayout::Azerty;
+
+        assert_eq!(layout.to_char(KeyCode::A, none), Some('q'));
+        assert_eq!(layout.to_char(KeyCode::Q, none), Some('a'));
+        assert_eq!(layout.to_char(KeyCode::W, shift), Some('Z'));
+        assert_eq!(layout.to_char(KeyCode::Semicolon, none), Some('m'));
+        assert_eq!(layout.to_char(KeyCode::M, shift), Some('?'));
+        assert_eq!(layout.to_char(KeyCode::Num2, none), Some('é'));
+        assert_eq!(layout.to_char(KeyCode::Num2, shift), Some('2'));
+        assert_eq!(layout.to_char(KeyCode::Num0, altgr), Some('@'));
+        assert_eq!(layout.to_char(KeyCode::E, altgr), Some('€'));
+        assert_eq!(layout.to_char(KeyCode::LeftBracket, none), None);
+        // Left alt alone is a shortcut, not AltGr
+        assert_eq!(layout.to_char(KeyCode::Num0, KeyModifiers { alt: true, ..none }), None);
+    }
+
+    #[test]
+    fn keyboard_translates_presses_only() {
+        let mut kb = Keyboard::new();
+        kb.set_layout(KeyboardLayout::Azerty);
+        let press = kb.process_hid_report(&report(0, &[0x14])).unwrap();
+        assert_eq!(kb.to_char(&press), Some('a'));
+        let release = kb.process_hid_report(&report(0, &[])).unwrap();
+        assert_eq!(kb.to_char(&release), None);
+    }
+}
@@ -2,8 +2,18 @@
 //!
 //! Provides verified drivers for various input devices:
 //! - **Keyboard**: USB HID keyboard for navigation/media control
-//! - **IR Remote**: Infrared remote (NEC, RC5, RC6 protocols)
+//! - **IR Remote**: Infrared remote (NEC, RC5, RC6, Samsung and Sony SIRC
+//!   protocols; timing checks and the SIRC decoder verified in [`ir_timing`])
 //! - **Touch**: Touch event types (actual driver in display crates)
+//! - **GPIO buttons**: Push buttons on BCM2711 GPIO pins
+//! - **Edge capture**: System-timer timestamps of GPIO edges, for the IR
+//!   decoder and other pulse-width inputs (see [`edge_capture`])
+//!
+//! [`InputManager`] also synthesizes key auto-repeat and debounces each
+//! source (see [`repeat`]); call [`InputManager::tick`] from the PD's
+//! periodic event so those timings advance. [`InputManager::stats`] reports
+//! per-source event and decode-error counts with the last raw code (see
+//! [`stats`]).
 //!
 //! # Usage
 //!
@@ -13,6 +23,7 @@
 //! let mut input = InputManager::new(RemoteOptions::all());
 //!
 //! loop {
+//!     input.tick();
 //!     if let Some(event) = input.poll() {
 //!         match event {
 //!             InputEvent::Key(key) => { /* handle keyboard */ }
@@ -28,15 +39,26 @@
 
 pub mod keyboard;
 pub mod ir_remote;
+pub mod ir_timing;
 pub mod touch;
 pub mod uart;
+pub mod gpio_buttons;
+pub mod edge_capture;
+pub mod repeat;
+pub mod stats;
 #[cfg(feature = "usb")]
 pub mod usb;
 
-pub use keyboard::{Keyboard, KeyCode, KeyEvent, KeyState, KeyModifiers};
-pub use ir_remote::{IrRemote, IrButton, IrEvent, IrProtocol, ButtonMap};
+pub use keyboard::{Keyboard, KeyboardLayout, KeyCode, KeyEvent, KeyState, KeyModifiers};
+pub use ir_remote::{
+    IrRemote, IrButton, IrEvent, IrProtocol, ButtonMap, MapImportError, ProfileError, RemoteProfile,
+};
 pub use touch::{TouchEvent, TouchPoint};
 pub use uart::Uart;
+pub use gpio_buttons::{ButtonPin, ButtonPins, GpioButtons, Pull};
+pub use edge_capture::{Edge, EdgeCapture};
+pub use repeat::{Debouncer, InputTiming, KeyRepeat};
+pub use stats::{InputStats, RawEvent, SourceStats};
 #[cfg(feature = "usb")]
 pub use usb::{UsbKeyboard, UsbError, UsbSpeed};
 
@@ -62,6 +84,44 @@ pub enum InputSource {
     Touch,
     /// UART serial input
     Uart,
+    /// Push buttons on GPIO pins
+    Button,
+}
+
+impl InputSource {
+    /// Number of sources
+    pub const COUNT: usize = 5;
+
+    /// Every source, in [`index`](Self::index) order
+    pub const ALL: [InputSource; Self::COUNT] = [
+        InputSource::Keyboard,
+        InputSource::IrRemote,
+        InputSource::Touch,
+        InputSource::Uart,
+        InputSource::Button,
+    ];
+
+    /// Position in [`ALL`](Self::ALL), for per-source tables
+    pub const fn index(self) -> usize {
+        match self {
+            InputSource::Keyboard => 0,
+            InputSource::IrRemote => 1,
+            InputSource::Touch => 2,
+            InputSource::Uart => 3,
+            InputSource::Button => 4,
+        }
+    }
+
+    /// Short name for diagnostics
+    pub const fn name(self) -> &'static str {
+        match self {
+            InputSource::Keyboard => "KEYBOARD",
+            InputSource::IrRemote => "IR",
+            InputSource::Touch => "TOUCH",
+            InputSource::Uart => "UART",
+            InputSource::Button => "BUTTON",
+        }
+    }
 }
 
 /// Remote control options configuration
@@ -79,6 +139,17 @@ pub struct RemoteOptions {
     pub uart_base: usize,
     /// IR protocol to use
     pub ir_protocol: IrProtocol,
+    /// Enable GPIO push-button input (see [`RemoteOptions::gpio_buttons`])
+    pub gpio_buttons_enabled: bool,
+    /// GPIO base address (virtual address mapped by Microkit)
+    pub gpio_base: usize,
+    /// Button pins and the keys they stand for
+    pub button_pins: ButtonPins,
+    /// System timer virtual address; when set, the IR remote captures its
+    /// edges through the GPIO block (see [`RemoteOptions::with_ir_capture`])
+    pub ir_capture: Option<usize>,
+    /// Auto-repeat and debounce timings
+    pub timing: InputTiming,
 }
 
 impl Default for RemoteOptions {
@@ -90,6 +161,11 @@ impl Default for RemoteOptions {
             uart_enabled: false,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 }
@@ -104,6 +180,11 @@ impl RemoteOptions {
             uart_enabled: false,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 
@@ -116,6 +197,11 @@ impl RemoteOptions {
             uart_enabled: false,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 
@@ -128,6 +214,11 @@ impl RemoteOptions {
             uart_enabled: false,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 
@@ -140,6 +231,11 @@ impl RemoteOptions {
             uart_enabled: true,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 
@@ -152,10 +248,38 @@ impl RemoteOptions {
             uart_enabled: true,
             uart_base: base,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
 
-    /// Create options with all inputs enabled
+    /// Create options with only GPIO push buttons enabled
+    pub const fn gpio_buttons(pins: ButtonPins) -> Self {
+        Self::gpio_buttons_at(gpio_buttons::GPIO_BASE, pins)
+    }
+
+    /// Create options with GPIO push buttons at a specific virtual address
+    pub const fn gpio_buttons_at(base: usize, pins: ButtonPins) -> Self {
+        Self {
+            keyboard_enabled: false,
+            ir_remote_enabled: false,
+            touch_enabled: false,
+            uart_enabled: false,
+            uart_base: uart::UART_BASE,
+            ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: true,
+            gpio_base: base,
+            button_pins: pins,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
+        }
+    }
+
+    /// Create options with all inputs enabled (GPIO buttons need a pin
+    /// list, so they stay off; add them with [`with_gpio_buttons`](Self::with_gpio_buttons))
     pub const fn all() -> Self {
         Self {
             keyboard_enabled: true,
@@ -164,8 +288,30 @@ impl RemoteOptions {
             uart_enabled: true,
             uart_base: uart::UART_BASE,
             ir_protocol: IrProtocol::Nec,
+            gpio_buttons_enabled: false,
+            gpio_base: gpio_buttons::GPIO_BASE,
+            button_pins: ButtonPins::EMPTY,
+            ir_capture: None,
+            timing: InputTiming::DEFAULT,
         }
     }
+
+    /// Same options plus GPIO push buttons at virtual `base`
+    pub const fn with_gpio_buttons(self, base: usize, pins: ButtonPins) -> Self {
+        Self { gpio_buttons_enabled: true, gpio_base: base, button_pins: pins, ..self }
+    }
+
+    /// Same options plus timestamped edge capture for the IR remote, with
+    /// the GPIO block at virtual `gpio_base` and the system timer at
+    /// virtual `timer_base`
+    pub const fn with_ir_capture(self, gpio_base: usize, timer_base: usize) -> Self {
+        Self { gpio_base, ir_capture: Some(timer_base), ..self }
+    }
+
+    /// Same options with different auto-repeat and debounce timings
+    pub const fn with_timing(self, timing: InputTiming) -> Self {
+        Self { timing, ..self }
+    }
 }
 
 /// Unified input controller trait
@@ -186,8 +332,18 @@ pub struct InputManager {
     keyboard: Option<Keyboard>,
     ir_remote: Option<IrRemote>,
     uart: Option<Uart>,
+    gpio_buttons: Option<GpioButtons>,
     #[cfg(feature = "usb")]
     usb_keyboard: Option<UsbKeyboard>,
+    /// Ticks counted by [`tick`](Self::tick)
+    ticks: u32,
+    repeat: KeyRepeat,
+    debounce: Debouncer<InputEvent>,
+    /// Last fresh IR frame and when it arrived, for duplicate suppression
+    last_ir: Option<(IrButton, u32)>,
+    /// Events and raw codes per source (decode errors are read from the
+    /// drivers by [`stats`](Self::stats))
+    stats: InputStats,
 }
 
 impl InputManager {
@@ -200,22 +356,46 @@ impl InputManager {
             } else {
                 None
             },
-            ir_remote: if options.ir_remote_enabled {
-                Some(IrRemote::new(options.ir_protocol))
-            } else {
-                None
-            },
+            ir_remote: Self::ir_remote_for(&options),
             uart: if options.uart_enabled {
                 Some(Uart::with_base(options.uart_base))
             } else {
                 None
             },
+            gpio_buttons: Self::gpio_buttons_for(&options),
             // A real USB HID keyboard needs mapped MMIO + DMA regions that only
             // the protection domain knows, so it is attached explicitly via
             // [`attach_usb_keyboard`] rather than constructed from options.
             #[cfg(feature = "usb")]
             usb_keyboard: None,
+            ticks: 0,
+            repeat: KeyRepeat::new(),
+            debounce: Debouncer::new(),
+            last_ir: None,
+            stats: InputStats::new(),
+        }
+    }
+
+    /// IR remote for `options`, with its edge capture attached, if enabled
+    fn ir_remote_for(options: &RemoteOptions) -> Option<IrRemote> {
+        if !options.ir_remote_enabled {
+            return None;
+        }
+        let mut ir = IrRemote::new(options.ir_protocol);
+        if let Some(timer_base) = options.ir_capture {
+            ir.attach_capture(EdgeCapture::new(options.gpio_base, timer_base));
         }
+        Some(ir)
+    }
+
+    /// Configured GPIO buttons for `options`, if enabled
+    fn gpio_buttons_for(options: &RemoteOptions) -> Option<GpioButtons> {
+        if !options.gpio_buttons_enabled || options.button_pins.is_empty() {
+            return None;
+        }
+        let mut buttons = GpioButtons::new(options.gpio_base, options.button_pins);
+        buttons.init();
+        Some(buttons)
     }
 
     /// Attach an initialized DWC2 USB HID keyboard as an input source.
@@ -228,12 +408,50 @@ impl InputManager {
         self.usb_keyboard = Some(keyboard);
     }
 
-    /// Poll all enabled input sources for events
+    /// Advance the input clock by one tick. Call from the PD's periodic
+    /// event (vsync step, timer); repeat and debounce timings count these.
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+    }
+
+    /// Ticks counted so far (wraps)
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Timestamp pending GPIO edges now. Call from the GPIO bank
+    /// interrupt, so pulse widths are measured when the edges happen rather
+    /// than when [`poll`](Self::poll) runs. Returns the edges captured.
+    pub fn service_edges(&mut self) -> usize {
+        self.ir_remote.as_mut().and_then(IrRemote::capture_mut).map_or(0, EdgeCapture::service)
+    }
+
+    /// Poll all enabled input sources for events.
+    ///
+    /// Debounced changes that have settled come first, then new events
+    /// from the sources, then a synthesized repeat of a held key.
     pub fn poll(&mut self) -> Option<InputEvent> {
+        if let Some((source, event)) = self.debounce.poll(self.ticks) {
+            return Some(self.deliver(source, event));
+        }
+
+        while let Some((source, event, code)) = self.poll_sources() {
+            self.stats.record_event(source, code);
+            if let Some(event) = self.filter(source, event) {
+                return Some(self.deliver(source, event));
+            }
+        }
+
+        self.repeat.poll(self.ticks, &self.options.timing).map(InputEvent::Key)
+    }
+
+    /// Next raw event from the sources, in priority order, with its raw
+    /// code (see [`stats`])
+    fn poll_sources(&mut self) -> Option<(InputSource, InputEvent, u32)> {
         // Check UART first (most common for serial console development)
         if let Some(ref mut uart) = self.uart {
             if let Some(event) = uart.poll() {
-                return Some(InputEvent::Key(event));
+                return Some((InputSource::Uart, InputEvent::Key(event), uart.last_byte() as u32));
             }
         }
 
@@ -241,27 +459,104 @@ impl InputManager {
         #[cfg(feature = "usb")]
         if let Some(ref mut usb) = self.usb_keyboard {
             if let Some(event) = usb.poll() {
-                return Some(InputEvent::Key(event));
+                let code = key_code(&event, usb.decoder());
+                return Some((InputSource::Keyboard, InputEvent::Key(event), code));
             }
         }
 
         // Check keyboard
         if let Some(ref mut kb) = self.keyboard {
             if let Some(event) = kb.poll() {
-                return Some(InputEvent::Key(event));
+                let code = key_code(&event, kb);
+                return Some((InputSource::Keyboard, InputEvent::Key(event), code));
+            }
+        }
+
+        // Check GPIO buttons
+        if let Some(ref mut buttons) = self.gpio_buttons {
+            if let Some(event) = buttons.poll() {
+                let event = InputEvent::Key(event);
+                return Some((InputSource::Button, event, stats::event_code(&event)));
             }
         }
 
         // Check IR remote
         if let Some(ref mut ir) = self.ir_remote {
             if let Some(event) = ir.poll() {
-                return Some(InputEvent::Remote(event));
+                return Some((InputSource::IrRemote, InputEvent::Remote(event), stats::ir_code(&event)));
             }
         }
 
         None
     }
 
+    /// Per-source event counts, decode errors and last raw codes
+    ///
+    /// Counts restart when [`set_options`](Self::set_options) rebuilds the
+    /// drivers.
+    pub fn stats(&self) -> InputStats {
+        let mut stats = self.stats;
+        let keyboard_errors = self.keyboard.as_ref().map_or(0, Keyboard::rollover_reports);
+        #[cfg(feature = "usb")]
+        let keyboard_errors = self.usb_keyboard.as_ref().map_or(keyboard_errors, |usb| {
+            keyboard_errors.wrapping_add(usb.decoder().rollover_reports())
+        });
+        stats.set_decode_errors(InputSource::Keyboard, keyboard_errors);
+        stats.set_decode_errors(
+            InputSource::IrRemote,
+            self.ir_remote.as_ref().map_or(0, IrRemote::decode_errors),
+        );
+        stats.set_decode_errors(InputSource::Uart, self.uart.as_ref().map_or(0, Uart::unknown_bytes));
+        stats
+    }
+
+    /// Apply the source's debounce window; `None` drops or defers the event
+    fn filter(&mut self, source: InputSource, event: InputEvent) -> Option<InputEvent> {
+        let window = self.options.timing.debounce_for(source);
+        match event {
+            InputEvent::Key(key) => self.debounce.filter(
+                source,
+                key.key as u16,
+                key.state == KeyState::Pressed,
+                event,
+                self.ticks,
+                window,
+            ),
+            InputEvent::Touch(touch) => self.debounce.filter(
+                source,
+                0,
+                touch != TouchEvent::Up,
+                event,
+                self.ticks,
+                window,
+            ),
+            // Remotes send discrete frames, not press/release: drop a fresh
+            // frame that duplicates the previous one inside the window.
+            // Held-button repeat frames always pass.
+            InputEvent::Remote(ir) if !ir.is_repeat => {
+                let duplicate = matches!(self.last_ir, Some((button, at))
+                    if button == ir.button && !repeat::reached(self.ticks, at.wrapping_add(window)));
+                if duplicate {
+                    return None;
+                }
+                self.last_ir = Some((ir.button, self.ticks));
+                Some(event)
+            }
+            InputEvent::Remote(_) => Some(event),
+        }
+    }
+
+    /// Hand an event to the caller, tracking keyboard and button keys for
+    /// auto-repeat
+    fn deliver(&mut self, source: InputSource, event: InputEvent) -> InputEvent {
+        if let InputEvent::Key(key) = event {
+            if matches!(source, InputSource::Keyboard | InputSource::Button) {
+                self.repeat.track(&key, self.ticks, &self.options.timing);
+            }
+        }
+        event
+    }
+
     /// Get the current options
     pub fn options(&self) -> &RemoteOptions {
         &self.options
@@ -270,6 +565,10 @@ impl InputManager {
     /// Update options and reconfigure inputs
     pub fn set_options(&mut self, options: RemoteOptions) {
         self.options = options;
+        self.repeat.cancel();
+        self.debounce = Debouncer::new();
+        self.last_ir = None;
+        self.stats = InputStats::new();
 
         self.keyboard = if options.keyboard_enabled {
             Some(Keyboard::new())
@@ -277,17 +576,15 @@ impl InputManager {
             None
         };
 
-        self.ir_remote = if options.ir_remote_enabled {
-            Some(IrRemote::new(options.ir_protocol))
-        } else {
-            None
-        };
+        self.ir_remote = Self::ir_remote_for(&options);
 
         self.uart = if options.uart_enabled {
             Some(Uart::with_base(options.uart_base))
         } else {
             None
         };
+
+        self.gpio_buttons = Self::gpio_buttons_for(&options);
     }
 
     /// Get mutable access to keyboard driver (for injecting HID reports)
@@ -305,9 +602,24 @@ impl InputManager {
         self.uart.as_mut()
     }
 
+    /// Get mutable access to the GPIO button driver
+    pub fn gpio_buttons_mut(&mut self) -> Option<&mut GpioButtons> {
+        self.gpio_buttons.as_mut()
+    }
+
     /// Get mutable access to the attached USB HID keyboard, if any
     #[cfg(feature = "usb")]
     pub fn usb_keyboard_mut(&mut self) -> Option<&mut UsbKeyboard> {
         self.usb_keyboard.as_mut()
     }
 }
+
+/// Raw code of a keyboard event: its usage ID, or for keys with no
+/// [`KeyCode`] the usage the decoder last saw pressed
+fn key_code(event: &KeyEvent, decoder: &Keyboard) -> u32 {
+    if event.key == KeyCode::Unknown && event.state == KeyState::Pressed {
+        decoder.last_scancode() as u32
+    } else {
+        event.key as u32
+    }
+}
@@ -0,0 +1,400 @@
+//! Key auto-repeat and debouncing
+//!
+//! Time is counted in *ticks* of whatever periodic event drives the PD
+//! (a vsync step, a timer notification); the PD advances the clock with
+//! [`InputManager::tick`](crate::InputManager::tick) and all timings in
+//! [`InputTiming`] are in those ticks. [`InputTiming::at_hz`] derives
+//! sensible defaults for a given tick rate.
+//!
+//! - [`KeyRepeat`] synthesizes repeated presses for a held key, like the
+//!   typematic repeat of a PC keyboard. Only sources that report releases
+//!   can be repeated: the UART sends one press per character (and the
+//!   terminal repeats on its own), so it is never fed in. Keyboards and
+//!   GPIO buttons are.
+//! - [`Debouncer`] holds back state changes that follow an accepted one
+//!   within the source's debounce window. Once the window has passed, the
+//!   latest held-back state is delivered if it differs from the reported
+//!   one, so a bouncing contact settles on its final state and a genuine
+//!   short tap is delayed rather than lost.
+
+use crate::keyboard::{KeyEvent, KeyState};
+use crate::InputSource;
+
+/// Auto-repeat and debounce timings, in ticks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputTiming {
+    /// Ticks a key must be held before it starts repeating
+    pub repeat_delay: u32,
+    /// Ticks between repeats once repeating (0 disables auto-repeat)
+    pub repeat_rate: u32,
+    /// Debounce window for keyboard (HID/USB) keys (0 disables)
+    pub keyboard_debounce: u32,
+    /// Debounce window for UART input (0 disables)
+    pub uart_debounce: u32,
+    /// Window in which a fresh IR frame for the same button is dropped as
+    /// a duplicate (0 disables)
+    pub ir_debounce: u32,
+    /// Debounce window for touch contact (0 disables)
+    pub touch_debounce: u32,
+    /// Debounce window for GPIO push buttons (0 disables)
+    pub button_debounce: u32,
+}
+
+impl InputTiming {
+    /// Defaults for a 60 Hz tick
+    pub const DEFAULT: Self = Self::at_hz(60);
+
+    /// Defaults for a tick of `hz` per second: 500 ms repeat delay, about
+    /// 15 repeats per second, 100 ms IR duplicate window, 30 ms touch and
+    /// 20 ms button debounce. Keyboards and the UART debounce in hardware
+    /// already.
+    pub const fn at_hz(hz: u32) -> Self {
+        Self {
+            repeat_delay: ticks_for_ms(500, hz),
+            repeat_rate: ticks_for_ms(66, hz),
+            keyboard_debounce: 0,
+            uart_debounce: 0,
+            ir_debounce: ticks_for_ms(100, hz),
+            touch_debounce: ticks_for_ms(30, hz),
+            button_debounce: ticks_for_ms(20, hz),
+        }
+    }
+
+    /// Same timings with auto-repeat turned off
+    pub const fn without_repeat(self) -> Self {
+        Self { repeat_rate: 0, ..self }
+    }
+
+    /// Debounce window for events from `source`
+    pub const fn debounce_for(&self, source: InputSource) -> u32 {
+        match source {
+            InputSource::Keyboard => self.keyboard_debounce,
+            InputSource::Uart => self.uart_debounce,
+            InputSource::IrRemote => self.ir_debounce,
+            InputSource::Touch => self.touch_debounce,
+            InputSource::Button => self.button_debounce,
+        }
+    }
+}
+
+impl Default for InputTiming {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Convert milliseconds to ticks at `hz`, rounding up to at least one tick
+const fn ticks_for_ms(ms: u32, hz: u32) -> u32 {
+    let ticks = (ms * hz).div_ceil(1000);
+    if ticks == 0 { 1 } else { ticks }
+}
+
+/// Has the tick counter reached `deadline`? Correct across wraparound as
+/// long as deadlines are less than 2^31 ticks ahead.
+#[inline]
+pub(crate) const fn reached(now: u32, deadline: u32) -> bool {
+    (now.wrapping_sub(deadline) as i32) >= 0
+}
+
+/// Typematic repeat for the most recently pressed key
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeyRepeat {
+    /// Held key and the tick its next repeat is due
+    held: Option<(KeyEvent, u32)>,
+}
+
+impl KeyRepeat {
+    /// Create an idle repeater
+    pub const fn new() -> Self {
+        Self { held: None }
+    }
+
+    /// Follow a delivered key event: a press (re)starts the delay for that
+    /// key, releasing it stops repeating. Pressing a second key takes over,
+    /// as on a PC keyboard.
+    pub fn track(&mut self, event: &KeyEvent, now: u32, timing: &InputTiming) {
+        match event.state {
+            KeyState::Pressed if timing.repeat_rate > 0 => {
+                self.held = Some((*event, now.wrapping_add(timing.repeat_delay)));
+            }
+            KeyState::Pressed => self.held = None,
+            KeyState::Released => {
+                if matches!(self.held, Some((held, _)) if held.key == event.key) {
+                    self.held = None;
+                }
+            }
+        }
+    }
+
+    /// Next synthesized press, if one is due at `now`.
+    ///
+    /// A caller that falls behind gets one repeat and the schedule restarts
+    /// from `now`, rather than a burst of stale repeats.
+    pub fn poll(&mut self, now: u32, timing: &InputTiming) -> Option<KeyEvent> {
+        let (event, due) = self.held?;
+        if timing.repeat_rate == 0 {
+            self.held = None;
+            return None;
+        }
+        if !reached(now, due) {
+            return None;
+        }
+        let mut next = due.wrapping_add(timing.repeat_rate);
+        if reached(now, next) {
+            next = now.wrapping_add(timing.repeat_rate);
+        }
+        self.held = Some((event, next));
+        Some(event)
+    }
+
+    /// Key currently held for repeat, if any
+    pub fn held(&self) -> Option<KeyEvent> {
+        self.held.map(|(event, _)| event)
+    }
+
+    /// Stop repeating (e.g. when the source is detached)
+    pub fn cancel(&mut self) {
+        self.held = None;
+    }
+}
+
+/// Number of contacts the debouncer tracks at once
+pub const DEBOUNCE_SLOTS: usize = 8;
+
+/// One tracked contact: a key, button or touch contact of one source
+#[derive(Clone, Copy, Debug)]
+struct Contact<T> {
+    source: InputSource,
+    id: u16,
+    /// State last delivered
+    pressed: bool,
+    /// Tick of the last delivered change
+    since: u32,
+    window: u32,
+    /// Latest change held back inside the window
+    pending: Option<(bool, T)>,
+}
+
+/// Per-source debouncer for two-state contacts.
+///
+/// Each contact is identified by its source and a source-specific `id`
+/// (key code, GPIO pin, 0 for the touch contact). `T` is the event that is
+/// delivered for it, so held-back changes can be replayed unchanged.
+pub struct Debouncer<T: Copy> {
+    contacts: [Option<Contact<T>>; DEBOUNCE_SLOTS],
+}
+
+impl<T: Copy> Debouncer<T> {
+    /// Create a debouncer with no tracked contacts
+    pub const fn new() -> Self {
+        Self { contacts: [None; DEBOUNCE_SLOTS] }
+    }
+
+    /// Filter one state change. Returns the event if it should be delivered
+    /// now; `None` means it was held back (or dropped as a bounce).
+    pub fn filter(
+        &mut self,
+        source: InputSource,
+        id: u16,
+        pressed: bool,
+        event: T,
+        now: u32,
+        window: u32,
+    ) -> Option<T> {
+        if window == 0 {
+            return Some(event);
+        }
+
+        let slot = match self.find(source, id) {
+            Some(slot) => slot,
+            None => {
+                let slot = self.free_slot(now);
+                self.contacts[slot] = Some(Contact {
+                    source,
+                    id,
+                    pressed,
+                    since: now,
+                    window,
+                    pending: None,
+                });
+                return Some(event);
+            }
+        };
+
+        let contact = self.contacts[slot].as_mut()?;
+        contact.window = window;
+        if reached(now, contact.since.wrapping_add(window)) {
+            contact.pressed = pressed;
+            contact.since = now;
+            contact.pending = None;
+            Some(event)
+        } else {
+            contact.pending = Some((pressed, event));
+            None
+        }
+    }
+
+    /// Deliver a held-back change whose window has passed, if any. Call
+    /// after every tick until it returns `None`.
+    pub fn poll(&mut self, now: u32) -> Option<(InputSource, T)> {
+        for contact in self.contacts.iter_mut().flatten() {
+            let (pressed, event) = match contact.pending {
+                Some(pending) if reached(now, contact.since.wrapping_add(contact.window)) => pending,
+                _ => continue,
+            };
+            contact.pending = None;
+            if pressed != contact.pressed {
+                contact.pressed = pressed;
+                contact.since = now;
+                return Some((contact.source, event));
+            }
+        }
+        None
+    }
+
+    /// Forget all contacts of `source`
+    pub fn reset(&mut self, source: InputSource) {
+        for slot in self.contacts.iter_mut() {
+            if matches!(slot, Some(contact) if contact.source == source) {
+                *slot = None;
+            }
+        }
+    }
+
+    fn find(&self, source: InputSource, id: u16) -> Option<usize> {
+        self.contacts
+            .iter()
+            .position(|c| matches!(c, Some(c) if c.source == source && c.id == id))
+    }
+
+    /// Pick a slot for a new contact: an empty one, else the settled
+    /// contact that changed longest ago (or, failing that, slot 0)
+    fn free_slot(&self, now: u32) -> usize {
+        let mut best = 0;
+        let mut best_age = 0;
+        for (i, slot) in self.contacts.iter().enumerate() {
+            match slot {
+                None => return i,
+                Some(c) if c.pending.is_none() => {
+                    let age = now.wrapping_sub(c.since);
+                    if age >= best_age {
+                        best = i;
+                        best_age = age;
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        best
+    }
+}
+
+impl<T: Copy> Default for Debouncer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::{KeyCode, KeyModifiers};
+
+    fn key(key: KeyCode, state: KeyState) -> KeyEvent {
+        KeyEvent { key, state, modifiers: KeyModifiers::default() }
+    }
+
+    #[test]
+    fn held_key_repeats_after_delay_at_rate() {
+        let timing = InputTiming { repeat_delay: 10, repeat_rate: 3, ..InputTiming::DEFAULT };
+        let mut repeat = KeyRepeat::new();
+        repeat.track(&key(KeyCode::Down, KeyState::Pressed), 0, &timing);
+
+        let fired: [bool; 17] = core::array::from_fn(|t| repeat.poll(t as u32, &timing).is_some());
+        let ticks: [u32; 3] = [10, 13, 16];
+        for (t, &f) in fired.iter().enumerate() {
+            assert_eq!(f, ticks.contains(&(t as u32)), "tick {}", t);
+        }
+
+        repeat.track(&key(KeyCode::Down, KeyState::Released), 17, &timing);
+        assert_eq!(repeat.poll(40, &timing), None);
+    }
+
+    #[test]
+    fn second_key_takes_over_and_late_poll_does_not_burst() {
+        let timing = InputTiming { repeat_delay: 5, repeat_rate: 2, ..InputTiming::DEFAULT };
+        let mut repeat = KeyRepeat::new();
+        repeat.track(&key(KeyCode::Up, KeyState::Pressed), 0, &timing);
+        repeat.track(&key(KeyCode::Left, KeyState::Pressed), 1, &timing);
+        // Releasing the first key does not stop the second
+        repeat.track(&key(KeyCode::Up, KeyState::Released), 2, &timing);
+
+        assert_eq!(repeat.poll(50, &timing).map(|e| e.key), Some(KeyCode::Left));
+        assert_eq!(repeat.poll(50, &timing), None);
+        assert!(repeat.poll(52, &timing).is_some());
+
+        let off = timing.without_repeat();
+        repeat.track(&key(KeyCode::Up, KeyState::Pressed), 60, &off);
+        assert_eq!(repeat.held(), None);
+    }
+
+    #[test]
+    fn debouncer_swallows_bounce_and_replays_settled_state() {
+        let mut db: Debouncer<u8> = Debouncer::new();
+        let src = InputSource::Touch;
+        assert_eq!(db.filter(src, 0, true, 1, 100, 4), Some(1));
+        // Bounce: release and press again inside the window
+        assert_eq!(db.filter(src, 0, false, 2, 101, 4), None);
+        assert_eq!(db.filter(src, 0, true, 3, 102, 4), None);
+        // Settled back on pressed: nothing to replay
+        assert_eq!(db.poll(104), None);
+
+        // A genuine short tap is delayed, not lost
+        assert_eq!(db.filter(src, 0, false, 4, 110, 4), Some(4));
+        assert_eq!(db.filter(src, 0, true, 5, 111, 4), None);
+        assert_eq!(db.poll(113), None);
+        assert_eq!(db.poll(114), Some((src, 5)));
+
+        // Other sources and a zero window are independent
+        assert_eq!(db.filter(InputSource::Keyboard, 0, false, 6, 115, 4), Some(6));
+        assert_eq!(db.filter(InputSource::Keyboard, 0, true, 7, 115, 0), Some(7));
+    }
+
+    #[test]
+    fn manager_repeats_held_keyboard_key() {
+        use crate::{InputEvent, InputManager, RemoteOptions};
+
+        let timing = InputTiming { repeat_delay: 3, repeat_rate: 2, ..InputTiming::DEFAULT };
+        let mut input = InputManager::new(RemoteOptions::keyboard_only().with_timing(timing));
+        // A is returned straight from the report; B is left for poll()
+        let kb = input.keyboard_mut().unwrap();
+        kb.process_hid_report(&[0, 0, 0x04, 0x05, 0, 0, 0, 0]);
+        assert_eq!(input.poll(), Some(InputEvent::Key(key(KeyCode::B, KeyState::Pressed))));
+
+        let mut repeats = 0;
+        for _ in 0..7 {
+            input.tick();
+            while input.poll().is_some() {
+                repeats += 1;
+            }
+        }
+        // Ticks 3, 5 and 7
+        assert_eq!(repeats, 3);
+
+        input.keyboard_mut().unwrap().process_hid_report(&[0; 8]);
+        while input.poll().is_some() {}
+        for _ in 0..10 {
+            input.tick();
+            assert_eq!(input.poll(), None);
+        }
+    }
+
+    #[test]
+    fn default_timings_scale_with_tick_rate() {
+        let t60 = InputTiming::at_hz(60);
+        assert_eq!((t60.repeat_delay, t60.repeat_rate), (30, 4));
+        let t20 = InputTiming::at_hz(20);
+        assert_eq!((t20.repeat_delay, t20.repeat_rate, t20.touch_debounce), (10, 2, 1));
+        assert_eq!(t20.debounce_for(InputSource::IrRemote), 2);
+    }
+}
@@ -0,0 +1,145 @@
+//! Per-source input statistics
+//!
+//! [`InputManager`](crate::InputManager) counts every event each source
+//! produces, before debouncing, together with the raw code behind it, and
+//! collects the drivers' decode-error counters. Bringing up a new remote or
+//! keyboard is then a matter of watching [`InputStats`]: a button that
+//! produces no event, or only errors, shows up at once, and the raw code
+//! is what a [`ButtonMap`](crate::ButtonMap) entry needs.
+//!
+//! Raw codes per source:
+//!
+//! | Source     | Raw code                                        |
+//! |------------|-------------------------------------------------|
+//! | `Keyboard` | HID usage ID of the last key pressed            |
+//! | `IrRemote` | `address << 8 \| command` of the last frame     |
+//! | `Uart`     | Last byte received                              |
+//! | `Button`   | HID usage ID of the key the button stands for   |
+//! | `Touch`    | `x << 16 \| y` of the last contact              |
+
+use crate::{InputEvent, InputSource, IrEvent, TouchEvent};
+
+/// Raw events kept by [`InputStats::recent`]
+pub const RECENT_EVENTS: usize = 8;
+
+/// Counters for one input source
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SourceStats {
+    /// Events produced by the source, before debouncing (wraps)
+    pub events: u32,
+    /// Input the driver received but could not decode (wraps)
+    pub decode_errors: u32,
+    /// Raw code of the most recent event
+    pub last_code: Option<u32>,
+}
+
+/// One raw event, as kept by [`InputStats::recent`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+    pub source: InputSource,
+    pub code: u32,
+}
+
+/// Counters for every source, plus the last few raw events
+#[derive(Clone, Copy, Debug)]
+pub struct InputStats {
+    sources: [SourceStats; InputSource::COUNT],
+    /// Ring of recent raw events; `next` is the oldest once full
+    recent: [Option<RawEvent>; RECENT_EVENTS],
+    next: usize,
+}
+
+impl InputStats {
+    pub const fn new() -> Self {
+        Self {
+            sources: [SourceStats { events: 0, decode_errors: 0, last_code: None }; InputSource::COUNT],
+            recent: [None; RECENT_EVENTS],
+            next: 0,
+        }
+    }
+
+    /// Counters for `source`
+    pub fn source(&self, source: InputSource) -> &SourceStats {
+        &self.sources[source.index()]
+    }
+
+    /// Every source with its counters, in [`InputSource::ALL`] order
+    pub fn iter(&self) -> impl Iterator<Item = (InputSource, &SourceStats)> {
+        InputSource::ALL.iter().copied().zip(self.sources.iter())
+    }
+
+    /// Events from all sources (wraps)
+    pub fn total_events(&self) -> u32 {
+        self.sources.iter().fold(0, |sum, s| sum.wrapping_add(s.events))
+    }
+
+    /// Decode errors from all sources (wraps)
+    pub fn total_errors(&self) -> u32 {
+        self.sources.iter().fold(0, |sum, s| sum.wrapping_add(s.decode_errors))
+    }
+
+    /// The last [`RECENT_EVENTS`] raw events, newest first
+    pub fn recent(&self) -> impl Iterator<Item = RawEvent> + '_ {
+        (1..=RECENT_EVENTS).filter_map(move |back| {
+            self.recent[(self.next + RECENT_EVENTS - back) % RECENT_EVENTS]
+        })
+    }
+
+    /// Count an event from `source` with raw code `code`
+    pub fn record_event(&mut self, source: InputSource, code: u32) {
+        let stats = &mut self.sources[source.index()];
+        stats.events = stats.events.wrapping_add(1);
+        stats.last_code = Some(code);
+        self.recent[self.next] = Some(RawEvent { source, code });
+        self.next = (self.next + 1) % RECENT_EVENTS;
+    }
+
+    /// Set the decode-error count of `source` from its driver's counter
+    pub fn set_decode_errors(&mut self, source: InputSource, errors: u32) {
+        self.sources[source.index()].decode_errors = errors;
+    }
+}
+
+impl Default for InputStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Raw code of an IR frame: address above the command byte
+pub fn ir_code(event: &IrEvent) -> u32 {
+    ((event.address as u32) << 8) | event.command as u32
+}
+
+/// Raw code an event carries by itself (sources with better raw data, such
+/// as the keyboard's unmapped usages, supply their own)
+pub fn event_code(event: &InputEvent) -> u32 {
+    match event {
+        InputEvent::Key(key) => key.key as u32,
+        InputEvent::Remote(ir) => ir_code(ir),
+        InputEvent::Touch(TouchEvent::Down(p) | TouchEvent::Move(p)) => {
+            ((p.x as u32) << 16) | p.y as u32
+        }
+        InputEvent::Touch(TouchEvent::Up) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_is_newest_first_and_bounded() {
+        let mut stats = InputStats::new();
+        assert_eq!(stats.recent().count(), 0);
+        for code in 0..10 {
+            stats.record_event(InputSource::Uart, code);
+        }
+        let codes: [u32; RECENT_EVENTS] = core::array::from_fn(|i| stats.recent().nth(i).unwrap().code);
+        assert_eq!(codes, [9, 8, 7, 6, 5, 4, 3, 2]);
+        assert_eq!(stats.source(InputSource::Uart).events, 10);
+        assert_eq!(stats.source(InputSource::Uart).last_code, Some(9));
+        assert_eq!(stats.source(InputSource::Keyboard).events, 0);
+        assert_eq!(stats.total_events(), 10);
+    }
+}
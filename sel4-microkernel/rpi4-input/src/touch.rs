@@ -3,6 +3,8 @@
 //! Provides common touch event types that can be used across
 //! different touch controller implementations.
 
+use verified_microkernel::{abs_diff_u32, max_u32};
+
 /// Touch point with screen coordinates
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TouchPoint {
@@ -187,10 +189,10 @@ impl GestureDetector {
 
                 // Long press: held for > 30 frames without moving much
                 const LONG_PRESS_FRAMES: u32 = 30;
-                const TAP_MOVE_THRESHOLD: u16 = 20;
+                const TAP_MOVE_THRESHOLD: u32 = 20;
 
-                let moved = ((end.x as i32 - start.x as i32).abs() as u16 > TAP_MOVE_THRESHOLD)
-                    || ((end.y as i32 - start.y as i32).abs() as u16 > TAP_MOVE_THRESHOLD);
+                let moved = abs_diff_u32(end.x.into(), start.x.into()) > TAP_MOVE_THRESHOLD
+                    || abs_diff_u32(end.y.into(), start.y.into()) > TAP_MOVE_THRESHOLD;
 
                 if !moved && duration > LONG_PRESS_FRAMES {
                     return Some(Gesture::LongPress(start));
@@ -203,12 +205,14 @@ impl GestureDetector {
 
                 // Check for double tap
                 const DOUBLE_TAP_FRAMES: u32 = 20;
-                const DOUBLE_TAP_DISTANCE: u16 = 30;
+                const DOUBLE_TAP_DISTANCE: u32 = 30;
 
                 if let Some(last_tap) = self.last_tap_point {
                     let tap_interval = self.current_frame.wrapping_sub(self.last_tap_frame);
-                    let tap_distance = ((start.x as i32 - last_tap.x as i32).abs() as u16)
-                        .max((start.y as i32 - last_tap.y as i32).abs() as u16);
+                    let tap_distance = max_u32(
+                        abs_diff_u32(start.x.into(), last_tap.x.into()),
+                        abs_diff_u32(start.y.into(), last_tap.y.into()),
+                    );
 
                     if tap_interval < DOUBLE_TAP_FRAMES && tap_distance < DOUBLE_TAP_DISTANCE {
                         self.last_tap_point = None;
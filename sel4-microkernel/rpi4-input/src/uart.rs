@@ -47,6 +47,10 @@ enum EscapeState {
 pub struct Uart {
     base: usize,
     escape_state: EscapeState,
+    /// Last byte fed to the decoder
+    last_byte: u8,
+    /// Bytes and escape sequences that mapped to no key
+    unknown_bytes: u32,
 }
 
 impl Uart {
@@ -60,6 +64,8 @@ impl Uart {
         Self {
             base,
             escape_state: EscapeState::Normal,
+            last_byte: 0,
+            unknown_bytes: 0,
         }
     }
 
@@ -96,7 +102,15 @@ impl Uart {
     /// Returns a KeyEvent when a complete key input is recognized.
     pub fn poll(&mut self) -> Option<KeyEvent> {
         let byte = self.try_read_byte()?;
+        self.decode(byte)
+    }
 
+    /// Feed one already-received byte through the key decoder
+    ///
+    /// Lets a caller that reads the UART itself (e.g. to divert bytes to a
+    /// debug monitor) still turn the remaining bytes into key events.
+    pub fn decode(&mut self, byte: u8) -> Option<KeyEvent> {
+        self.last_byte = byte;
         match self.escape_state {
             EscapeState::Normal => {
                 if byte == 0x1B {  // ESC
@@ -104,7 +118,11 @@ impl Uart {
                     None
                 } else {
                     // Regular ASCII character
-                    self.map_ascii_to_event(byte)
+                    let event = self.map_ascii_to_event(byte);
+                    if event.is_none() {
+                        self.unknown_bytes = self.unknown_bytes.wrapping_add(1);
+                    }
+                    event
                 }
             }
             EscapeState::GotEsc => {
@@ -152,12 +170,23 @@ impl Uart {
                         modifiers: KeyModifiers::default(),
                     })
                 } else {
+                    self.unknown_bytes = self.unknown_bytes.wrapping_add(1);
                     None
                 }
             }
         }
     }
 
+    /// Last byte fed to the decoder (the final byte of an escape sequence)
+    pub fn last_byte(&self) -> u8 {
+        self.last_byte
+    }
+
+    /// Bytes and escape sequences that mapped to no key
+    pub fn unknown_bytes(&self) -> u32 {
+        self.unknown_bytes
+    }
+
     /// Map ASCII byte to KeyEvent
     fn map_ascii_to_event(&self, byte: u8) -> Option<KeyEvent> {
         let key = match byte {
@@ -249,4 +278,17 @@ mod tests {
             Some(KeyCode::Enter)
         );
     }
+
+    #[test]
+    fn test_unknown_bytes_counted() {
+        let mut uart = Uart::new();
+        assert!(uart.decode(b'w').is_some());
+        assert_eq!(uart.decode(b'z'), None);
+        // ESC [ Z: an escape sequence with no key
+        uart.decode(0x1B);
+        uart.decode(b'[');
+        assert_eq!(uart.decode(b'Z'), None);
+        assert_eq!(uart.unknown_bytes(), 2);
+        assert_eq!(uart.last_byte(), b'Z');
+    }
 }
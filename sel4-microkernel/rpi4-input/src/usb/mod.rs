@@ -36,6 +36,7 @@ pub mod dwc2;
 pub mod hid;
 
 use crate::keyboard::{KeyEvent, Keyboard};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 use dwc2::{ChannelParams, Dwc2, TransferResult};
 use hid::{find_boot_keyboard_endpoint, BootKeyboardEndpoint, SetupPacket};
 
@@ -142,6 +143,20 @@ pub enum UsbError {
     DmaTooSmall,
 }
 
+impl From<UsbError> for PdError {
+    fn from(e: UsbError) -> Self {
+        let kind = match e {
+            UsbError::ResetTimeout => ErrorKind::Timeout,
+            UsbError::NotHostMode => ErrorKind::Hardware,
+            UsbError::NoDevice => ErrorKind::NotFound,
+            UsbError::ControlTransferFailed => ErrorKind::Failed,
+            UsbError::NotAKeyboard => ErrorKind::Unsupported,
+            UsbError::DmaTooSmall => ErrorKind::Capacity,
+        };
+        PdError::new(ErrorDomain::Usb, kind).with_detail(e as u16)
+    }
+}
+
 // --- DMA buffer layout (offsets within the provided DmaRegion) ---
 const OFF_SETUP: usize = 0x000; // 8-byte SETUP packet
 const OFF_DATA: usize = 0x040; // control-transfer data stage (descriptors)
@@ -208,6 +223,11 @@ impl UsbKeyboard {
         }
     }
 
+    /// The HID report decoder (layout, rollover count, last scancode)
+    pub fn decoder(&self) -> &Keyboard {
+        &self.decoder
+    }
+
     /// Reset and configure the DWC2 core for host mode.
     ///
     /// Call once after the MMIO/DMA regions are mapped. Enumeration of an
@@ -248,7 +268,8 @@ impl UsbKeyboard {
                 }
                 None
             }
-            State::Running => self.poll_report(),
+            // Events left over from a report that changed several keys first
+            State::Running => self.decoder.poll().or_else(|| self.poll_report()),
         }
     }
 
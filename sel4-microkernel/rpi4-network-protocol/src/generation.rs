@@ -8,6 +8,7 @@ use core::sync::atomic::{AtomicU32, Ordering};
 
 use crate::generation_contract::{reset_plan, validate_stable_generation};
 use crate::RING_SIZE;
+use verified_microkernel::ring_mod;
 
 pub const LEGACY_GENERATION: u32 = 0;
 pub const FIRST_STABLE_GENERATION: u32 = 2;
@@ -158,7 +159,7 @@ impl ResyncedNetworkRing<'_> {
     pub fn advance_write(&self) -> Result<(), GenerationChanged> {
         self.check_generation()?;
         let current = self.header.write_idx.load(Ordering::Acquire);
-        let next = (current + 1) % self.header.capacity;
+        let next = ring_mod(current + 1, self.header.capacity);
         self.header.write_idx.store(next, Ordering::Release);
         Ok(())
     }
@@ -167,7 +168,7 @@ impl ResyncedNetworkRing<'_> {
     pub fn advance_read(&self) -> Result<(), GenerationChanged> {
         self.check_generation()?;
         let current = self.header.read_idx.load(Ordering::Acquire);
-        let next = (current + 1) % self.header.capacity;
+        let next = ring_mod(current + 1, self.header.capacity);
         self.header.read_idx.store(next, Ordering::Release);
         Ok(())
     }
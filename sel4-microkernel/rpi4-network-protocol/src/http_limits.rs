@@ -0,0 +1,449 @@
+//! Admission limits for the control HTTP server.
+//!
+//! The Network PD's buffers are fixed at build time, so one LAN client
+//! must not be able to take all of them. [`Limits`] is asked before each
+//! connection is accepted and before each request is handled:
+//!
+//! - at most [`MAX_CONNECTIONS`] open at once, [`MAX_PER_CLIENT`] of them
+//!   from any one address;
+//! - no request (request line, headers and body) longer than
+//!   [`MAX_REQUEST_LEN`], the size of a connection's receive buffer;
+//! - requests from each address paced by a token bucket: a burst of
+//!   [`REQUEST_BURST`], then [`REQUEST_RATE_PER_S`] a second.
+//!
+//! A refusal carries the HTTP status to answer with ([`Refusal::status`]);
+//! the server sends it and closes the connection. The Network PD's stack
+//! (`rpi4-network`, `net-http` feature) asks before each connection and
+//! request on the control port.
+//!
+//! ## Verification
+//!
+//! [`TokenBucket`] keeps its level in thousandths of a token, so a refill
+//! loses nothing to rounding. [`TokenBucket::refill`] is proven never to
+//! overflow and never to fill past the capacity, however long the wait,
+//! and [`TokenBucket::take`] to remove exactly the tokens it grants and
+//! nothing when it refuses.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+// ============================================================================
+// TOKEN BUCKET
+// ============================================================================
+
+/// Largest bucket, in tokens
+pub const MAX_BURST: u32 = 1000;
+/// Fastest refill, in tokens a second
+pub const MAX_RATE_PER_S: u32 = 1000;
+/// Thousandths in a token
+pub const MILLI: u64 = 1000;
+/// A wait this long fills any bucket that refills at all: the largest
+/// holds `MAX_BURST * MILLI` thousandths, and the slowest earns one a
+/// millisecond
+pub const FULL_REFILL_MS: u64 = MAX_BURST as u64 * MILLI;
+
+pub struct TokenBucket {
+    /// Tokens held, in thousandths
+    level: u64,
+    /// Full level, in thousandths
+    capacity: u64,
+    /// Tokens a second, which is thousandths a millisecond
+    rate: u64,
+    /// Time of the last refill
+    last_ms: u64,
+}
+
+impl TokenBucket {
+    pub open spec fn wf(&self) -> bool {
+        self.level <= self.capacity
+            && self.capacity <= MAX_BURST as u64 * MILLI
+            && self.rate <= MAX_RATE_PER_S as u64
+    }
+
+    /// A full bucket of `burst` tokens refilling at `rate_per_s`
+    pub fn new(burst: u32, rate_per_s: u32, now_ms: u64) -> (bucket: Self)
+        requires
+            burst <= MAX_BURST,
+            rate_per_s <= MAX_RATE_PER_S,
+        ensures
+            bucket.wf(),
+            bucket.level == bucket.capacity,
+            bucket.capacity == burst as u64 * MILLI,
+    {
+        let capacity = burst as u64 * MILLI;
+        TokenBucket { level: capacity, capacity, rate: rate_per_s as u64, last_ms: now_ms }
+    }
+
+    /// Add what the time since the last refill has earned, up to the
+    /// capacity. A clock that reads earlier than the last refill earns
+    /// nothing until it passes it again.
+    pub fn refill(&mut self, now_ms: u64)
+        requires
+            old(self).wf(),
+        ensures
+            self.wf(),
+            self.capacity == old(self).capacity,
+            self.rate == old(self).rate,
+            old(self).level <= self.level,
+    {
+        if now_ms <= self.last_ms {
+            return;
+        }
+        let elapsed = now_ms - self.last_ms;
+        self.last_ms = now_ms;
+        if self.rate == 0 {
+            return;
+        }
+        if elapsed >= FULL_REFILL_MS {
+            self.level = self.capacity;
+            return;
+        }
+        let rate = self.rate;
+        assert(elapsed * rate <= FULL_REFILL_MS * MAX_RATE_PER_S as u64) by (nonlinear_arith)
+            requires
+                elapsed < FULL_REFILL_MS,
+                rate <= MAX_RATE_PER_S as u64,
+        ;
+        let earned = elapsed * rate;
+        let room = self.capacity - self.level;
+        if earned >= room {
+            self.level = self.capacity;
+        } else {
+            self.level += earned;
+        }
+    }
+
+    /// Take `tokens` if the bucket holds them
+    pub fn take(&mut self, tokens: u32) -> (taken: bool)
+        requires
+            old(self).wf(),
+        ensures
+            self.wf(),
+            self.capacity == old(self).capacity,
+            self.rate == old(self).rate,
+            taken == (tokens as u64 * MILLI <= old(self).level),
+            taken ==> self.level == old(self).level - tokens as u64 * MILLI,
+            !taken ==> self.level == old(self).level,
+    {
+        let cost = tokens as u64 * MILLI;
+        if cost <= self.level {
+            self.level -= cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Refill to `now_ms`, then take `tokens`
+    pub fn try_take(&mut self, now_ms: u64, tokens: u32) -> (taken: bool)
+        requires
+            old(self).wf(),
+        ensures
+            self.wf(),
+            self.capacity == old(self).capacity,
+    {
+        self.refill(now_ms);
+        self.take(tokens)
+    }
+
+    pub fn is_full(&self) -> (full: bool)
+        ensures
+            full == (self.level == self.capacity),
+    {
+        self.level == self.capacity
+    }
+}
+
+} // verus!
+
+impl TokenBucket {
+    /// Whole tokens held
+    pub fn tokens(&self) -> u32 {
+        (self.level / MILLI) as u32
+    }
+
+    /// Milliseconds until `tokens` could be taken, rounded up; `None` if
+    /// they never could be
+    pub fn wait_ms(&self, tokens: u32) -> Option<u64> {
+        let cost = tokens as u64 * MILLI;
+        if cost <= self.level {
+            return Some(0);
+        }
+        if cost > self.capacity || self.rate == 0 {
+            return None;
+        }
+        Some((cost - self.level).div_ceil(self.rate))
+    }
+}
+
+// ============================================================================
+// CONNECTIONS
+// ============================================================================
+
+/// Connections open at once: one TCP socket and buffer pair each
+pub const MAX_CONNECTIONS: usize = 4;
+/// Connections open at once from one address
+pub const MAX_PER_CLIENT: u8 = 2;
+/// Longest request: request line, headers and body, the size of a
+/// connection's receive buffer
+pub const MAX_REQUEST_LEN: usize = 2048;
+/// Requests one address may make back to back
+pub const REQUEST_BURST: u32 = 8;
+/// Requests a second one address may keep up
+pub const REQUEST_RATE_PER_S: u32 = 2;
+
+/// Addresses remembered: one per connection, since an address is kept
+/// at least as long as it has one open
+const MAX_CLIENTS: usize = MAX_CONNECTIONS;
+
+/// A client's address: IPv6, or IPv4 mapped into it (`::ffff:a.b.c.d`)
+pub type ClientAddr = [u8; 16];
+
+/// The key for an IPv4 client
+pub fn ipv4_client(addr: [u8; 4]) -> ClientAddr {
+    let mut key = [0u8; 16];
+    key[10] = 0xff;
+    key[11] = 0xff;
+    key[12..].copy_from_slice(&addr);
+    key
+}
+
+/// Why a connection or request was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    /// Every connection is in use
+    Busy,
+    /// This address has [`MAX_PER_CLIENT`] open already
+    ClientLimit,
+    /// This address has used its requests; more in `retry_after_s`
+    RateLimited { retry_after_s: u32 },
+    /// Longer than [`MAX_REQUEST_LEN`]
+    TooLarge,
+}
+
+impl Refusal {
+    /// The HTTP status to answer with
+    pub fn status(self) -> u16 {
+        match self {
+            Refusal::Busy => 503,
+            Refusal::ClientLimit | Refusal::RateLimited { .. } => 429,
+            Refusal::TooLarge => 413,
+        }
+    }
+
+    /// The `Retry-After` value, in seconds, if there is one
+    pub fn retry_after_s(self) -> Option<u32> {
+        match self {
+            Refusal::RateLimited { retry_after_s } => Some(retry_after_s),
+            Refusal::Busy => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Refusal::Busy => write!(f, "all {} connections in use", MAX_CONNECTIONS),
+            Refusal::ClientLimit => write!(f, "{} connections open from this address", MAX_PER_CLIENT),
+            Refusal::RateLimited { retry_after_s } => write!(f, "rate limited for {} s", retry_after_s),
+            Refusal::TooLarge => write!(f, "request over {} bytes", MAX_REQUEST_LEN),
+        }
+    }
+}
+
+struct Client {
+    addr: ClientAddr,
+    open: u8,
+    requests: TokenBucket,
+}
+
+/// Connection and request accounting for the control server
+pub struct Limits {
+    clients: [Option<Client>; MAX_CLIENTS],
+    open: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limits {
+    pub const fn new() -> Self {
+        Self { clients: [const { None }; MAX_CLIENTS], open: 0 }
+    }
+
+    /// Connections open
+    pub fn open_connections(&self) -> usize {
+        self.open
+    }
+
+    /// Ask to accept a connection from `addr`; on `Ok` the caller accepts
+    /// it and calls [`close`](Self::close) when it ends
+    pub fn open(&mut self, addr: &ClientAddr, now_ms: u64) -> Result<(), Refusal> {
+        if self.open >= MAX_CONNECTIONS {
+            return Err(Refusal::Busy);
+        }
+        let slot = match self.find(addr) {
+            Some(slot) => slot,
+            None => self.remember(addr, now_ms).ok_or(Refusal::Busy)?,
+        };
+        let client = self.clients[slot].as_mut().expect("found slots are occupied");
+        if client.open >= MAX_PER_CLIENT {
+            return Err(Refusal::ClientLimit);
+        }
+        client.open += 1;
+        self.open += 1;
+        Ok(())
+    }
+
+    /// A connection from `addr` that [`open`](Self::open) let in has ended
+    pub fn close(&mut self, addr: &ClientAddr) {
+        let Some(slot) = self.find(addr) else {
+            return;
+        };
+        let client = self.clients[slot].as_mut().expect("found slots are occupied");
+        if client.open > 0 {
+            client.open -= 1;
+            self.open -= 1;
+        }
+    }
+
+    /// Ask to handle a request of `len` bytes from `addr`; refused requests
+    /// still count against the rate
+    pub fn request(&mut self, addr: &ClientAddr, len: usize, now_ms: u64) -> Result<(), Refusal> {
+        let Some(slot) = self.find(addr) else {
+            // Only a connection open gets this far
+            return Err(Refusal::Busy);
+        };
+        let client = self.clients[slot].as_mut().expect("found slots are occupied");
+        if !client.requests.try_take(now_ms, 1) {
+            let wait_ms = client.requests.wait_ms(1).unwrap_or(1000);
+            return Err(Refusal::RateLimited { retry_after_s: wait_ms.div_ceil(1000).max(1) as u32 });
+        }
+        if len > MAX_REQUEST_LEN {
+            return Err(Refusal::TooLarge);
+        }
+        Ok(())
+    }
+
+    fn find(&self, addr: &ClientAddr) -> Option<usize> {
+        self.clients
+            .iter()
+            .position(|client| client.as_ref().is_some_and(|client| client.addr == *addr))
+    }
+
+    /// A slot for a new address: an empty one, or one whose address has
+    /// nothing open and a full bucket again. An address with requests
+    /// still owed is kept, so closing and reconnecting does not reset its
+    /// limit.
+    fn remember(&mut self, addr: &ClientAddr, now_ms: u64) -> Option<usize> {
+        let slot = self.clients.iter_mut().position(|client| match client {
+            None => true,
+            Some(client) => {
+                client.requests.refill(now_ms);
+                client.open == 0 && client.requests.is_full()
+            }
+        })?;
+        self.clients[slot] = Some(Client {
+            addr: *addr,
+            open: 0,
+            requests: TokenBucket::new(REQUEST_BURST, REQUEST_RATE_PER_S, now_ms),
+        });
+        Some(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_refills_to_capacity_and_no_further() {
+        let mut bucket = TokenBucket::new(4, 2, 0);
+        for _ in 0..4 {
+            assert!(bucket.try_take(0, 1));
+        }
+        assert!(!bucket.try_take(0, 1));
+        assert_eq!(bucket.wait_ms(1), Some(500));
+        // Half a token is kept, not rounded away
+        assert!(!bucket.try_take(250, 1));
+        assert!(bucket.try_take(500, 1));
+        bucket.refill(u64::MAX);
+        assert_eq!(bucket.tokens(), 4);
+        assert!(bucket.is_full());
+        assert_eq!(bucket.wait_ms(5), None);
+    }
+
+    #[test]
+    fn bucket_ignores_a_clock_going_backwards() {
+        let mut bucket = TokenBucket::new(1, 1, 10_000);
+        assert!(bucket.try_take(10_000, 1));
+        assert!(!bucket.try_take(5_000, 1));
+        // Time before the last refill is not earned twice
+        assert!(!bucket.try_take(10_500, 1));
+        assert!(bucket.try_take(11_000, 1));
+    }
+
+    #[test]
+    fn connections_are_capped_overall_and_per_address() {
+        let mut limits = Limits::new();
+        let a = ipv4_client([10, 0, 2, 2]);
+        let b = ipv4_client([10, 0, 2, 3]);
+        let c = ipv4_client([10, 0, 2, 4]);
+        assert_eq!(limits.open(&a, 0), Ok(()));
+        assert_eq!(limits.open(&a, 0), Ok(()));
+        assert_eq!(limits.open(&a, 0), Err(Refusal::ClientLimit));
+        assert_eq!(limits.open(&b, 0), Ok(()));
+        assert_eq!(limits.open(&b, 0), Ok(()));
+        assert_eq!(limits.open(&c, 0), Err(Refusal::Busy));
+        assert_eq!(limits.open_connections(), MAX_CONNECTIONS);
+
+        limits.close(&a);
+        assert_eq!(limits.open(&c, 0), Ok(()));
+        // Closing what was never opened changes nothing
+        limits.close(&ipv4_client([192, 168, 1, 1]));
+        assert_eq!(limits.open_connections(), MAX_CONNECTIONS);
+    }
+
+    #[test]
+    fn requests_are_rate_limited_and_size_capped() {
+        let mut limits = Limits::new();
+        let a = ipv4_client([10, 0, 2, 2]);
+        limits.open(&a, 0).unwrap();
+        assert_eq!(limits.request(&a, MAX_REQUEST_LEN + 1, 0), Err(Refusal::TooLarge));
+        for _ in 1..REQUEST_BURST {
+            assert_eq!(limits.request(&a, 100, 0), Ok(()));
+        }
+        let refused = limits.request(&a, 100, 0).unwrap_err();
+        assert_eq!(refused, Refusal::RateLimited { retry_after_s: 1 });
+        assert_eq!(refused.status(), 429);
+        assert_eq!(limits.request(&a, 100, 1000 / REQUEST_RATE_PER_S as u64), Ok(()));
+        // No connection, no request
+        assert_eq!(limits.request(&ipv4_client([10, 0, 2, 9]), 100, 0), Err(Refusal::Busy));
+    }
+
+    #[test]
+    fn reconnecting_does_not_reset_the_rate() {
+        let mut limits = Limits::new();
+        let a = ipv4_client([10, 0, 2, 2]);
+        limits.open(&a, 0).unwrap();
+        for _ in 0..REQUEST_BURST {
+            limits.request(&a, 100, 0).unwrap();
+        }
+        limits.close(&a);
+        // Other addresses cannot push it out while it owes requests
+        for last in 3..3 + MAX_CLIENTS as u8 - 1 {
+            limits.open(&ipv4_client([10, 0, 2, last]), 0).unwrap();
+        }
+        assert_eq!(limits.open(&ipv4_client([10, 0, 2, 99]), 0), Err(Refusal::Busy));
+        limits.open(&a, 0).unwrap();
+        assert!(matches!(limits.request(&a, 100, 0), Err(Refusal::RateLimited { .. })));
+    }
+}
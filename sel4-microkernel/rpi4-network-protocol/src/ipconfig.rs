@@ -0,0 +1,573 @@
+//! IPv4 addressing: DHCP or a validated static configuration.
+//!
+//! The interface is configured one of two ways, an [`IpMode`]:
+//!
+//! - [`IpMode::Dhcp`], the default: the stack leases an address.
+//! - [`IpMode::Static`]: address, prefix, gateway and DNS server, entered by
+//!   the user.
+//!
+//! A mode reaches the stack from the settings store, where the settings UI
+//! or the serial shell left it:
+//!
+//! ```text
+//!  serial shell ─┐  IpCommand::parse         save_ip_mode
+//!                ├──────────────────► IpMode ────────────► SettingsStore
+//!  settings UI ──┘                                              │
+//!                                                  load_ip_mode │
+//!                       NetworkStack ◄── IpMode ◄───────────────┘
+//! ```
+//!
+//! The shell's syntax is the text [`IpMode`] displays as, after the `ip`
+//! command word:
+//!
+//! ```text
+//! ip dhcp
+//! ip static 192.168.1.10/24 gw 192.168.1.1 dns 192.168.1.1
+//! ip static 192.168.1.10 netmask 255.255.255.0
+//! ip show
+//! ```
+//!
+//! ## Verification
+//!
+//! Nothing reaches the stack unchecked: every way of building a static
+//! configuration — parsing, loading settings, the legacy [`IpConfig`] wire
+//! struct — ends in [`check_static`], which is proven to accept only a
+//! unicast host address that is neither the network nor the broadcast
+//! address of its subnet, a gateway inside that subnet, and a unicast DNS
+//! server. Netmasks are accepted only if contiguous ([`netmask_to_prefix`]).
+//!
+//! [`IpConfig`]: crate::IpConfig
+
+#![allow(clippy::manual_range_contains)]
+
+use core::fmt;
+use rpi4_settings_protocol::{SaveError, SettingDef, SettingValue, Settings, SettingsError, SettingsStore};
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+// ============================================================================
+// VALIDATION
+// ============================================================================
+
+/// Shortest prefix a static configuration may use
+pub const MIN_PREFIX_LEN: u8 = 1;
+/// Longest prefix a static configuration may use: /31 and /32 leave no
+/// host addresses besides the network and broadcast ones
+pub const MAX_PREFIX_LEN: u8 = 30;
+
+pub open spec fn valid_prefix(prefix_len: u8) -> bool {
+    MIN_PREFIX_LEN <= prefix_len && prefix_len <= MAX_PREFIX_LEN
+}
+
+/// Specification: an address a host may use or talk to — not 0.0.0.0/8,
+/// loopback, multicast or reserved (which includes broadcast)
+pub open spec fn is_unicast(addr: u32) -> bool {
+    let first = addr >> 24u32;
+    first != 0 && first != 127 && first < 224
+}
+
+/// Specification: the host part of `addr` under `netmask` is neither all
+/// zeros (the network address) nor all ones (the broadcast address)
+pub open spec fn is_host_in_subnet(addr: u32, netmask: u32) -> bool {
+    addr & !netmask != 0 && addr & !netmask != !netmask
+}
+
+/// Why a configuration was refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpConfigError {
+    /// Prefix length outside [`MIN_PREFIX_LEN`]..=[`MAX_PREFIX_LEN`]
+    BadPrefix(u8),
+    /// Netmask has a one after a zero
+    NonContiguousNetmask,
+    /// Address is not a unicast address
+    BadAddress,
+    /// Address is the network or broadcast address of its subnet
+    NotAHostAddress,
+    /// Gateway is outside the address's subnet
+    GatewayOutsideSubnet,
+    /// Gateway is the interface's own address
+    GatewayIsAddress,
+    /// DNS server is not a unicast address
+    BadDns,
+    /// Shell input that does not follow the command syntax
+    Syntax,
+}
+
+/// Specification of [`prefix_to_netmask`]
+pub open spec fn prefix_to_netmask_spec(prefix_len: u8) -> u32 {
+    if prefix_len == 0 {
+        0
+    } else {
+        (u32::MAX << ((32 - prefix_len) as u32)) as u32
+    }
+}
+
+/// Netmask with the top `prefix_len` bits set
+pub fn prefix_to_netmask(prefix_len: u8) -> (mask: u32)
+    requires
+        prefix_len <= 32,
+    ensures
+        mask == prefix_to_netmask_spec(prefix_len),
+{
+    if prefix_len == 0 {
+        0
+    } else {
+        u32::MAX << ((32 - prefix_len) as u32)
+    }
+}
+
+/// Check a static configuration before it reaches the stack; addresses in
+/// host order, a gateway or DNS server of 0 meaning none
+pub fn check_static(address: u32, prefix_len: u8, gateway: u32, dns: u32) -> (r: Result<(), IpConfigError>)
+    ensures
+        r.is_ok() ==> valid_prefix(prefix_len),
+        r.is_ok() ==> is_unicast(address),
+        r.is_ok() ==> is_host_in_subnet(address, prefix_to_netmask_spec(prefix_len)),
+        r.is_ok() && gateway != 0 ==> gateway & prefix_to_netmask_spec(prefix_len)
+            == address & prefix_to_netmask_spec(prefix_len) && gateway != address,
+        r.is_ok() && dns != 0 ==> is_unicast(dns),
+{
+    if prefix_len < MIN_PREFIX_LEN || prefix_len > MAX_PREFIX_LEN {
+        return Err(IpConfigError::BadPrefix(prefix_len));
+    }
+    let netmask = prefix_to_netmask(prefix_len);
+    let first = address >> 24u32;
+    if first == 0 || first == 127 || first >= 224 {
+        return Err(IpConfigError::BadAddress);
+    }
+    if address & !netmask == 0 || address & !netmask == !netmask {
+        return Err(IpConfigError::NotAHostAddress);
+    }
+    if gateway != 0 {
+        if gateway & netmask != address & netmask {
+            return Err(IpConfigError::GatewayOutsideSubnet);
+        }
+        if gateway == address {
+            return Err(IpConfigError::GatewayIsAddress);
+        }
+    }
+    if dns != 0 {
+        let first = dns >> 24u32;
+        if first == 0 || first == 127 || first >= 224 {
+            return Err(IpConfigError::BadDns);
+        }
+    }
+    Ok(())
+}
+
+} // verus!
+
+/// Prefix length of `netmask`; `None` unless its ones are contiguous from
+/// the top
+pub fn netmask_to_prefix(netmask: u32) -> Option<u8> {
+    let prefix_len = netmask.leading_ones() as u8;
+    (prefix_to_netmask(prefix_len) == netmask).then_some(prefix_len)
+}
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// A static IPv4 configuration; build one with [`StaticIpv4::new`] so it
+/// is always checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticIpv4 {
+    address: [u8; 4],
+    prefix_len: u8,
+    gateway: Option<[u8; 4]>,
+    dns: Option<[u8; 4]>,
+}
+
+impl StaticIpv4 {
+    /// Check and build a configuration
+    pub fn new(
+        address: [u8; 4],
+        prefix_len: u8,
+        gateway: Option<[u8; 4]>,
+        dns: Option<[u8; 4]>,
+    ) -> Result<Self, IpConfigError> {
+        let host = |addr: Option<[u8; 4]>| addr.map_or(0, u32::from_be_bytes);
+        check_static(u32::from_be_bytes(address), prefix_len, host(gateway), host(dns))?;
+        Ok(Self { address, prefix_len, gateway, dns })
+    }
+
+    pub fn address(&self) -> [u8; 4] {
+        self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn netmask(&self) -> [u8; 4] {
+        prefix_to_netmask(self.prefix_len).to_be_bytes()
+    }
+
+    /// Default route, if the configuration has one
+    pub fn gateway(&self) -> Option<[u8; 4]> {
+        self.gateway
+    }
+
+    pub fn dns(&self) -> Option<[u8; 4]> {
+        self.dns
+    }
+}
+
+/// How the interface gets its address
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IpMode {
+    #[default]
+    Dhcp,
+    Static(StaticIpv4),
+}
+
+impl IpMode {
+    /// Decode the legacy `ConfigureIp` payload
+    pub fn from_wire(config: &crate::IpConfig) -> Result<Self, IpConfigError> {
+        if config.use_dhcp != 0 {
+            return Ok(IpMode::Dhcp);
+        }
+        let prefix_len =
+            netmask_to_prefix(u32::from_be_bytes(config.netmask)).ok_or(IpConfigError::NonContiguousNetmask)?;
+        let optional = |addr: [u8; 4]| (addr != [0; 4]).then_some(addr);
+        StaticIpv4::new(config.ip_addr, prefix_len, optional(config.gateway), optional(config.dns))
+            .map(IpMode::Static)
+    }
+
+    /// Encode as the legacy `ConfigureIp` payload
+    pub fn to_wire(&self) -> crate::IpConfig {
+        match self {
+            IpMode::Dhcp => crate::IpConfig { ip_addr: [0; 4], netmask: [0; 4], gateway: [0; 4], dns: [0; 4], use_dhcp: 1 },
+            IpMode::Static(config) => crate::IpConfig {
+                ip_addr: config.address,
+                netmask: config.netmask(),
+                gateway: config.gateway.unwrap_or([0; 4]),
+                dns: config.dns.unwrap_or([0; 4]),
+                use_dhcp: 0,
+            },
+        }
+    }
+}
+
+// ============================================================================
+// SERIAL SHELL
+// ============================================================================
+
+/// What the shell's `ip` command asked for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpCommand {
+    /// Print the current configuration
+    Show,
+    /// Store and apply a new, already checked configuration
+    Set(IpMode),
+}
+
+impl IpCommand {
+    /// Parse the words after the `ip` command word
+    pub fn parse(args: &str) -> Result<Self, IpConfigError> {
+        let mut words = args.split_ascii_whitespace();
+        let command = match words.next() {
+            None | Some("show") => IpCommand::Show,
+            Some("dhcp") => IpCommand::Set(IpMode::Dhcp),
+            Some("static") => IpCommand::Set(IpMode::Static(parse_static(&mut words)?)),
+            Some(_) => return Err(IpConfigError::Syntax),
+        };
+        match words.next() {
+            None => Ok(command),
+            Some(_) => Err(IpConfigError::Syntax),
+        }
+    }
+}
+
+fn parse_static<'a>(words: &mut impl Iterator<Item = &'a str>) -> Result<StaticIpv4, IpConfigError> {
+    let first = words.next().ok_or(IpConfigError::Syntax)?;
+    let (address, mut prefix_len) = match first.split_once('/') {
+        Some((address, prefix)) => (parse_ipv4(address)?, Some(prefix.parse().map_err(|_| IpConfigError::Syntax)?)),
+        None => (parse_ipv4(first)?, None),
+    };
+    let (mut gateway, mut dns) = (None, None);
+    while let Some(keyword) = words.next() {
+        let value = parse_ipv4(words.next().ok_or(IpConfigError::Syntax)?)?;
+        match keyword {
+            "netmask" if prefix_len.is_none() => {
+                prefix_len = Some(netmask_to_prefix(u32::from_be_bytes(value)).ok_or(IpConfigError::NonContiguousNetmask)?);
+            }
+            "gw" if gateway.is_none() => gateway = Some(value),
+            "dns" if dns.is_none() => dns = Some(value),
+            _ => return Err(IpConfigError::Syntax),
+        }
+    }
+    StaticIpv4::new(address, prefix_len.ok_or(IpConfigError::Syntax)?, gateway, dns)
+}
+
+/// Dotted-quad IPv4 address
+pub fn parse_ipv4(text: &str) -> Result<[u8; 4], IpConfigError> {
+    let mut address = [0u8; 4];
+    let mut octets = text.split('.');
+    for octet in &mut address {
+        let part = octets.next().ok_or(IpConfigError::Syntax)?;
+        // Reject "+1" and "01", which `parse` would take
+        if part.is_empty() || part.len() > 3 || !part.bytes().all(|b| b.is_ascii_digit()) || (part.len() > 1 && part.starts_with('0')) {
+            return Err(IpConfigError::Syntax);
+        }
+        *octet = part.parse().map_err(|_| IpConfigError::Syntax)?;
+    }
+    match octets.next() {
+        None => Ok(address),
+        Some(_) => Err(IpConfigError::Syntax),
+    }
+}
+
+/// Dotted-quad formatting for an address
+pub struct Dotted(pub [u8; 4]);
+
+impl fmt::Display for Dotted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.0;
+        write!(f, "{}.{}.{}.{}", a, b, c, d)
+    }
+}
+
+/// The shell syntax that sets this mode, so `ip show` output can be typed
+/// back in
+impl fmt::Display for IpMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpMode::Dhcp => write!(f, "dhcp"),
+            IpMode::Static(config) => {
+                write!(f, "static {}/{}", Dotted(config.address), config.prefix_len)?;
+                if let Some(gateway) = config.gateway {
+                    write!(f, " gw {}", Dotted(gateway))?;
+                }
+                if let Some(dns) = config.dns {
+                    write!(f, " dns {}", Dotted(dns))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for IpConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpConfigError::BadPrefix(len) => {
+                write!(f, "prefix /{} outside /{}../{}", len, MIN_PREFIX_LEN, MAX_PREFIX_LEN)
+            }
+            IpConfigError::NonContiguousNetmask => write!(f, "netmask is not contiguous"),
+            IpConfigError::BadAddress => write!(f, "address is not unicast"),
+            IpConfigError::NotAHostAddress => write!(f, "address is the subnet's network or broadcast address"),
+            IpConfigError::GatewayOutsideSubnet => write!(f, "gateway is outside the subnet"),
+            IpConfigError::GatewayIsAddress => write!(f, "gateway is the interface address"),
+            IpConfigError::BadDns => write!(f, "DNS server is not unicast"),
+            IpConfigError::Syntax => {
+                write!(f, "usage: ip [show | dhcp | static ADDR/LEN|ADDR netmask MASK [gw ADDR] [dns ADDR]]")
+            }
+        }
+    }
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+
+/// Settings keys; the Network PD's keys are 0x01xx
+pub const KEY_IP_DHCP: u16 = 0x0100;
+pub const KEY_IP_ADDRESS: u16 = 0x0101;
+pub const KEY_IP_PREFIX_LEN: u16 = 0x0102;
+pub const KEY_IP_GATEWAY: u16 = 0x0103;
+pub const KEY_IP_DNS: u16 = 0x0104;
+
+/// The addressing settings; addresses are stored in host order, 0 for
+/// none
+pub const IP_SETTINGS: &[SettingDef] = &[
+    SettingDef { key: KEY_IP_DHCP, name: "ip_dhcp", default: SettingValue::Bool(true) },
+    SettingDef { key: KEY_IP_ADDRESS, name: "ip_address", default: SettingValue::U32(0) },
+    SettingDef { key: KEY_IP_PREFIX_LEN, name: "ip_prefix_len", default: SettingValue::U32(0) },
+    SettingDef { key: KEY_IP_GATEWAY, name: "ip_gateway", default: SettingValue::U32(0) },
+    SettingDef { key: KEY_IP_DNS, name: "ip_dns", default: SettingValue::U32(0) },
+];
+
+impl IpMode {
+    /// Read the mode from settings following [`IP_SETTINGS`]; a stored
+    /// static configuration is checked again, as the blob is only
+    /// integrity-checked
+    pub fn from_settings(settings: &Settings<'_>) -> Result<Self, IpConfigError> {
+        if settings.get_bool(KEY_IP_DHCP).unwrap_or(true) {
+            return Ok(IpMode::Dhcp);
+        }
+        let word = |key| settings.get_u32(key).unwrap_or(0);
+        let optional = |key| Some(word(key)).filter(|&addr| addr != 0).map(u32::to_be_bytes);
+        let prefix_len = u8::try_from(word(KEY_IP_PREFIX_LEN)).map_err(|_| IpConfigError::BadPrefix(u8::MAX))?;
+        StaticIpv4::new(word(KEY_IP_ADDRESS).to_be_bytes(), prefix_len, optional(KEY_IP_GATEWAY), optional(KEY_IP_DNS))
+            .map(IpMode::Static)
+    }
+
+    /// Write the mode into settings following [`IP_SETTINGS`]
+    pub fn store(&self, settings: &mut Settings<'_>) -> Result<(), SettingsError> {
+        let config = match self {
+            IpMode::Dhcp => return settings.set(KEY_IP_DHCP, SettingValue::Bool(true)),
+            IpMode::Static(config) => config,
+        };
+        let host = |addr: Option<[u8; 4]>| SettingValue::U32(addr.map_or(0, u32::from_be_bytes));
+        settings.set(KEY_IP_DHCP, SettingValue::Bool(false))?;
+        settings.set(KEY_IP_ADDRESS, host(Some(config.address)))?;
+        settings.set(KEY_IP_PREFIX_LEN, SettingValue::U32(config.prefix_len as u32))?;
+        settings.set(KEY_IP_GATEWAY, host(config.gateway))?;
+        settings.set(KEY_IP_DNS, host(config.dns))
+    }
+}
+
+/// The stored mode; DHCP if nothing is stored or the stored configuration
+/// fails its checks, so a bad setting cannot take the interface offline
+pub fn load_ip_mode<S: SettingsStore>(store: &mut S) -> IpMode {
+    IpMode::from_settings(&store.load_settings(IP_SETTINGS)).unwrap_or(IpMode::Dhcp)
+}
+
+/// Store `mode`, keeping the static fields of an earlier configuration
+/// when switching to DHCP so switching back needs no re-entry
+pub fn save_ip_mode<S: SettingsStore>(store: &mut S, mode: &IpMode) -> Result<(), SaveError<S::Error>> {
+    let mut settings = store.load_settings(IP_SETTINGS);
+    mode.store(&mut settings).map_err(SaveError::Encode)?;
+    store.save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpi4_settings_protocol::MAX_BLOB_SIZE;
+
+    fn config(text: &str) -> Result<IpMode, IpConfigError> {
+        match IpCommand::parse(text)? {
+            IpCommand::Set(mode) => Ok(mode),
+            IpCommand::Show => panic!("not a set command"),
+        }
+    }
+
+    #[test]
+    fn netmask_and_prefix_agree() {
+        assert_eq!(prefix_to_netmask(0), 0);
+        assert_eq!(prefix_to_netmask(24), 0xFFFF_FF00);
+        assert_eq!(prefix_to_netmask(32), u32::MAX);
+        for prefix_len in 0..=32 {
+            assert_eq!(netmask_to_prefix(prefix_to_netmask(prefix_len)), Some(prefix_len));
+        }
+        assert_eq!(netmask_to_prefix(0xFFFF_00FF), None);
+        assert_eq!(netmask_to_prefix(0x00FF_FFFF), None);
+    }
+
+    #[test]
+    fn static_configuration_is_checked() {
+        let gw = Some([192, 168, 1, 1]);
+        assert!(StaticIpv4::new([192, 168, 1, 10], 24, gw, Some([1, 1, 1, 1])).is_ok());
+        assert!(StaticIpv4::new([10, 0, 2, 15], 8, None, None).is_ok());
+        assert_eq!(StaticIpv4::new([192, 168, 1, 10], 0, None, None), Err(IpConfigError::BadPrefix(0)));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 10], 31, None, None), Err(IpConfigError::BadPrefix(31)));
+        assert_eq!(StaticIpv4::new([127, 0, 0, 2], 8, None, None), Err(IpConfigError::BadAddress));
+        assert_eq!(StaticIpv4::new([224, 0, 0, 5], 24, None, None), Err(IpConfigError::BadAddress));
+        assert_eq!(StaticIpv4::new([0, 1, 2, 3], 24, None, None), Err(IpConfigError::BadAddress));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 0], 24, None, None), Err(IpConfigError::NotAHostAddress));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 255], 24, None, None), Err(IpConfigError::NotAHostAddress));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 10], 24, Some([192, 168, 2, 1]), None), Err(IpConfigError::GatewayOutsideSubnet));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 10], 24, Some([192, 168, 1, 10]), None), Err(IpConfigError::GatewayIsAddress));
+        assert_eq!(StaticIpv4::new([192, 168, 1, 10], 24, gw, Some([255, 255, 255, 255])), Err(IpConfigError::BadDns));
+    }
+
+    #[test]
+    fn shell_commands_parse_and_round_trip() {
+        assert_eq!(IpCommand::parse(""), Ok(IpCommand::Show));
+        assert_eq!(IpCommand::parse("show"), Ok(IpCommand::Show));
+        assert_eq!(config("dhcp"), Ok(IpMode::Dhcp));
+
+        let text = "static 192.168.1.10/24 gw 192.168.1.1 dns 1.1.1.1";
+        let mode = config(text).unwrap();
+        let IpMode::Static(ip) = mode else { panic!("expected static") };
+        assert_eq!(ip.netmask(), [255, 255, 255, 0]);
+        let mut shown = [0u8; 64];
+        let mut out = Buf { bytes: &mut shown, len: 0 };
+        fmt::write(&mut out, format_args!("{}", mode)).unwrap();
+        let len = out.len;
+        assert_eq!(core::str::from_utf8(&shown[..len]).unwrap(), text);
+
+        assert_eq!(config("static 192.168.1.10 netmask 255.255.255.0 gw 192.168.1.1"), config("static 192.168.1.10/24 gw 192.168.1.1"));
+        assert_eq!(config("static 192.168.1.10 netmask 255.0.255.0"), Err(IpConfigError::NonContiguousNetmask));
+        assert_eq!(config("static 192.168.1.10/24 gw 10.0.0.1"), Err(IpConfigError::GatewayOutsideSubnet));
+        // No prefix, repeated or unknown keywords, trailing words, bad octets
+        assert_eq!(config("static 192.168.1.10"), Err(IpConfigError::Syntax));
+        assert_eq!(config("static 192.168.1.10/24 gw 192.168.1.1 gw 192.168.1.2"), Err(IpConfigError::Syntax));
+        assert_eq!(config("static 192.168.1.10/24 router 192.168.1.1"), Err(IpConfigError::Syntax));
+        assert_eq!(config("dhcp now"), Err(IpConfigError::Syntax));
+        assert_eq!(parse_ipv4("192.168.1.256"), Err(IpConfigError::Syntax));
+        assert_eq!(parse_ipv4("192.168.01.1"), Err(IpConfigError::Syntax));
+        assert_eq!(parse_ipv4("192.168.1"), Err(IpConfigError::Syntax));
+        assert_eq!(parse_ipv4("192.168.1.1.1"), Err(IpConfigError::Syntax));
+    }
+
+    #[test]
+    fn legacy_wire_struct_round_trips() {
+        let mode = config("static 10.1.0.5/16 gw 10.1.0.1").unwrap();
+        assert_eq!(IpMode::from_wire(&mode.to_wire()), Ok(mode));
+        assert_eq!(IpMode::from_wire(&IpMode::Dhcp.to_wire()), Ok(IpMode::Dhcp));
+        let mut bad = mode.to_wire();
+        bad.ip_addr = [10, 1, 255, 255];
+        assert_eq!(IpMode::from_wire(&bad), Err(IpConfigError::NotAHostAddress));
+    }
+
+    #[test]
+    fn modes_persist_through_the_settings_store() {
+        struct Nv {
+            blob: [u8; MAX_BLOB_SIZE],
+            len: usize,
+        }
+        impl SettingsStore for Nv {
+            type Error = ();
+            fn read_blob(&mut self, buf: &mut [u8]) -> Result<usize, ()> {
+                buf[..self.len].copy_from_slice(&self.blob[..self.len]);
+                Ok(self.len)
+            }
+            fn write_blob(&mut self, blob: &[u8]) -> Result<(), ()> {
+                self.blob[..blob.len()].copy_from_slice(blob);
+                self.len = blob.len();
+                Ok(())
+            }
+        }
+
+        let mut nv = Nv { blob: [0; MAX_BLOB_SIZE], len: 0 };
+        assert_eq!(load_ip_mode(&mut nv), IpMode::Dhcp);
+
+        let mode = config("static 192.168.1.10/24 gw 192.168.1.1 dns 192.168.1.1").unwrap();
+        save_ip_mode(&mut nv, &mode).unwrap();
+        assert_eq!(load_ip_mode(&mut nv), mode);
+
+        // Back to DHCP keeps the static fields for next time
+        save_ip_mode(&mut nv, &IpMode::Dhcp).unwrap();
+        assert_eq!(load_ip_mode(&mut nv), IpMode::Dhcp);
+        let mut settings = nv.load_settings(IP_SETTINGS);
+        assert_eq!(settings.get_u32(KEY_IP_ADDRESS), Some(0xC0A8_010A));
+
+        // A stored configuration that fails the checks falls back to DHCP
+        settings.set(KEY_IP_DHCP, SettingValue::Bool(false)).unwrap();
+        settings.set(KEY_IP_PREFIX_LEN, SettingValue::U32(32)).unwrap();
+        nv.save_settings(&settings).unwrap();
+        assert_eq!(IpMode::from_settings(&nv.load_settings(IP_SETTINGS)), Err(IpConfigError::BadPrefix(32)));
+        assert_eq!(load_ip_mode(&mut nv), IpMode::Dhcp);
+    }
+
+    struct Buf<'a> {
+        bytes: &'a mut [u8],
+        len: usize,
+    }
+
+    impl fmt::Write for Buf<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+}
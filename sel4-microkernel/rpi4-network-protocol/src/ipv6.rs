@@ -0,0 +1,712 @@
+//! IPv6 neighbour discovery for address autoconfiguration (RFC 4861/4862).
+//!
+//! What a stack needs to configure itself on an IPv6 network without a
+//! DHCPv6 server:
+//!
+//! 1. A link-local address `fe80::/64` from the MAC ([`link_local_address`]),
+//!    checked unique by duplicate address detection ([`Dad`]).
+//! 2. A router solicitation ([`build_router_solicit`]) and the router
+//!    advertisement it brings ([`parse_nd`]).
+//! 3. A global address from the advertised /64 prefix
+//!    ([`slaac_address`]), through DAD again, and a default route via the
+//!    advertising router.
+//!
+//! Packets are whole IPv6 packets, as a raw socket passes them, so the
+//! checks a host owes neighbour discovery (hop limit 255, code 0, the
+//! ICMPv6 checksum over the pseudo-header) are made here rather than
+//! trusted to the caller.
+//!
+//! ## Verification
+//!
+//! Router advertisements come from the network, so nothing in one is
+//! trusted: every option is located by [`parse_option`], proven to return
+//! an option that lies inside the message and ends past where it starts,
+//! so the option walk can neither read past the packet nor loop on a
+//! zero-length option.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+// ============================================================================
+// LAYOUT
+// ============================================================================
+
+pub const IPV6_HEADER_LEN: usize = 40;
+/// Next-header value of ICMPv6
+pub const NEXT_HEADER_ICMPV6: u8 = 58;
+/// Hop limit every neighbour-discovery packet is sent with, and must
+/// arrive with: anything less was forwarded from off-link
+pub const ND_HOP_LIMIT: u8 = 255;
+
+pub const ICMPV6_ROUTER_SOLICIT: u8 = 133;
+pub const ICMPV6_ROUTER_ADVERT: u8 = 134;
+pub const ICMPV6_NEIGHBOR_SOLICIT: u8 = 135;
+pub const ICMPV6_NEIGHBOR_ADVERT: u8 = 136;
+
+/// Router advertisement fields before the options
+pub const RA_FIXED_LEN: usize = 16;
+/// Neighbour solicitation/advertisement fields before the options
+pub const NS_NA_FIXED_LEN: usize = 24;
+
+pub const OPT_SOURCE_LINK_ADDR: u8 = 1;
+pub const OPT_TARGET_LINK_ADDR: u8 = 2;
+pub const OPT_PREFIX_INFO: u8 = 3;
+pub const OPT_MTU: u8 = 5;
+/// Options are sized in units of 8 bytes
+pub const OPTION_UNIT: usize = 8;
+
+/// Why a packet was not taken as neighbour discovery
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdError {
+    /// Shorter than its headers or its payload-length field
+    TooShort,
+    /// Not IPv6, or not ICMPv6 directly after the IPv6 header
+    NotIcmpv6,
+    /// Hop limit below [`ND_HOP_LIMIT`]: forwarded from off-link
+    BadHopLimit(u8),
+    BadCode(u8),
+    BadChecksum,
+    /// ICMPv6 but not a message this module handles (echo, errors, ...)
+    Unhandled(u8),
+    /// Option at this message offset has length 0 or runs past the message
+    BadOption { offset: usize },
+}
+
+/// Big-endian u16 at `offset`
+pub fn read_u16_be(buf: &[u8], offset: usize) -> (value: u16)
+    requires
+        offset + 2 <= buf.len(),
+{
+    ((buf[offset] as u16) << 8) | (buf[offset + 1] as u16)
+}
+
+/// Big-endian u32 at `offset`
+pub fn read_u32_be(buf: &[u8], offset: usize) -> (value: u32)
+    requires
+        offset + 4 <= buf.len(),
+{
+    ((buf[offset] as u32) << 24)
+        | ((buf[offset + 1] as u32) << 16)
+        | ((buf[offset + 2] as u32) << 8)
+        | (buf[offset + 3] as u32)
+}
+
+/// One option, located in the ICMPv6 message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NdOption {
+    pub kind: u8,
+    /// Message offset of the option's type byte
+    pub offset: usize,
+    /// Length in bytes, type and length bytes included
+    pub len: usize,
+}
+
+impl NdOption {
+    /// Message offset just past this option
+    pub fn end(&self) -> (end: usize)
+        requires
+            self.offset + self.len <= usize::MAX,
+        ensures
+            end == self.offset + self.len,
+    {
+        self.offset + self.len
+    }
+}
+
+/// Locate the option at `offset` of `msg`
+///
+/// On success the option lies inside the message and is at least one unit
+/// long, so a loop of calls advances and ends at the message length.
+pub fn parse_option(msg: &[u8], offset: usize) -> (result: Result<NdOption, NdError>)
+    requires
+        offset < msg.len(),
+    ensures
+        match result {
+            Ok(option) => option.offset == offset
+                && option.len >= OPTION_UNIT
+                && option.offset + option.len <= msg.len(),
+            Err(_) => true,
+        },
+{
+    if msg.len() - offset < 2 {
+        return Err(NdError::BadOption { offset });
+    }
+    let kind = msg[offset];
+    let units = msg[offset + 1] as usize;
+    // RFC 4861 4.6: a zero length means the whole packet is discarded
+    if units == 0 {
+        return Err(NdError::BadOption { offset });
+    }
+    let len = units * OPTION_UNIT;
+    if len > msg.len() - offset {
+        return Err(NdError::BadOption { offset });
+    }
+    Ok(NdOption { kind, offset, len })
+}
+
+} // verus!
+
+// ============================================================================
+// ADDRESSES
+// ============================================================================
+
+pub type Ipv6Addr = [u8; 16];
+
+pub const UNSPECIFIED: Ipv6Addr = [0; 16];
+/// `ff02::2`
+pub const ALL_ROUTERS: Ipv6Addr = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+/// Prefix length of link-local and SLAAC addresses
+pub const SLAAC_PREFIX_LEN: u8 = 64;
+
+/// Modified EUI-64 interface identifier of `mac` (RFC 4291 appendix A)
+pub fn interface_id(mac: &[u8; 6]) -> [u8; 8] {
+    [mac[0] ^ 0x02, mac[1], mac[2], 0xff, 0xfe, mac[3], mac[4], mac[5]]
+}
+
+/// `fe80::/64` address of `mac`
+pub fn link_local_address(mac: &[u8; 6]) -> Ipv6Addr {
+    let mut prefix = UNSPECIFIED;
+    prefix[0] = 0xfe;
+    prefix[1] = 0x80;
+    slaac_address(&prefix, mac)
+}
+
+/// Address from the top 64 bits of `prefix` and the interface identifier
+/// of `mac`
+pub fn slaac_address(prefix: &Ipv6Addr, mac: &[u8; 6]) -> Ipv6Addr {
+    let mut address = *prefix;
+    address[8..].copy_from_slice(&interface_id(mac));
+    address
+}
+
+/// Solicited-node multicast group of `address`, `ff02::1:ffXX:XXXX`
+pub fn solicited_node(address: &Ipv6Addr) -> Ipv6Addr {
+    let mut group = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0xff, 0, 0, 0];
+    group[13..].copy_from_slice(&address[13..]);
+    group
+}
+
+/// Ethernet address an IPv6 multicast group is sent to (RFC 2464)
+pub fn multicast_mac(group: &Ipv6Addr) -> [u8; 6] {
+    [0x33, 0x33, group[12], group[13], group[14], group[15]]
+}
+
+/// In `fe80::/10`
+pub fn is_link_local(address: &Ipv6Addr) -> bool {
+    address[0] == 0xfe && address[1] & 0xc0 == 0x80
+}
+
+pub fn is_multicast(address: &Ipv6Addr) -> bool {
+    address[0] == 0xff
+}
+
+// ============================================================================
+// CHECKSUM
+// ============================================================================
+
+/// ICMPv6 checksum of `msg` between `src` and `dst`: the ones'-complement
+/// sum over the pseudo-header and the message. A message with a correct
+/// checksum field sums to 0.
+pub fn icmpv6_checksum(src: &Ipv6Addr, dst: &Ipv6Addr, msg: &[u8]) -> u16 {
+    let mut sum = 0u32;
+    let mut add = |bytes: &[u8]| {
+        for pair in bytes.chunks(2) {
+            let word = (pair[0] as u32) << 8 | pair.get(1).copied().unwrap_or(0) as u32;
+            sum += word;
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+    };
+    add(src);
+    add(dst);
+    add(&(msg.len() as u32).to_be_bytes());
+    add(&[0, 0, 0, NEXT_HEADER_ICMPV6]);
+    add(msg);
+    !(sum as u16)
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+/// Most prefixes kept from one advertisement; the rest are ignored
+pub const MAX_RA_PREFIXES: usize = 4;
+
+/// A prefix information option
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrefixInfo {
+    pub prefix: Ipv6Addr,
+    pub prefix_len: u8,
+    /// L: addresses under the prefix are on this link
+    pub on_link: bool,
+    /// A: hosts may form addresses under the prefix
+    pub autonomous: bool,
+    /// Seconds; `u32::MAX` is forever
+    pub valid_lifetime: u32,
+    pub preferred_lifetime: u32,
+}
+
+impl PrefixInfo {
+    /// A prefix a SLAAC address may be formed from (RFC 4862 5.5.3): the
+    /// A flag, a /64, a live lifetime no shorter than the preferred one,
+    /// and not link-local
+    pub fn is_slaac_usable(&self) -> bool {
+        self.autonomous
+            && self.prefix_len == SLAAC_PREFIX_LEN
+            && self.valid_lifetime > 0
+            && self.preferred_lifetime <= self.valid_lifetime
+            && !is_link_local(&self.prefix)
+            && !is_multicast(&self.prefix)
+    }
+}
+
+/// A router advertisement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouterAdvert {
+    /// The router's link-local address, the default route's next hop
+    pub router: Ipv6Addr,
+    /// Seconds the router is a default router; 0 means it is not one
+    pub router_lifetime: u16,
+    /// M: addresses are available from DHCPv6
+    pub managed: bool,
+    /// O: other configuration is available from DHCPv6
+    pub other: bool,
+    pub reachable_ms: u32,
+    pub retrans_ms: u32,
+    pub mtu: Option<u32>,
+    pub router_mac: Option<[u8; 6]>,
+    prefixes: [PrefixInfo; MAX_RA_PREFIXES],
+    prefix_count: usize,
+}
+
+impl RouterAdvert {
+    pub fn prefixes(&self) -> &[PrefixInfo] {
+        &self.prefixes[..self.prefix_count]
+    }
+
+    /// First prefix a SLAAC address may be formed from
+    pub fn slaac_prefix(&self) -> Option<&PrefixInfo> {
+        self.prefixes().iter().find(|prefix| prefix.is_slaac_usable())
+    }
+}
+
+/// A neighbour-discovery message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdMessage {
+    RouterAdvert(RouterAdvert),
+    /// From `src` (unspecified for a DAD probe) asking who has `target`
+    NeighborSolicit { src: Ipv6Addr, target: Ipv6Addr },
+    /// `target` is in use by the sender
+    NeighborAdvert { target: Ipv6Addr },
+}
+
+const NO_PREFIX: PrefixInfo = PrefixInfo {
+    prefix: UNSPECIFIED,
+    prefix_len: 0,
+    on_link: false,
+    autonomous: false,
+    valid_lifetime: 0,
+    preferred_lifetime: 0,
+};
+
+/// Check an IPv6 packet as neighbour discovery and decode it
+pub fn parse_nd(packet: &[u8]) -> Result<NdMessage, NdError> {
+    if packet.len() < IPV6_HEADER_LEN {
+        return Err(NdError::TooShort);
+    }
+    if packet[0] >> 4 != 6 || packet[6] != NEXT_HEADER_ICMPV6 {
+        return Err(NdError::NotIcmpv6);
+    }
+    let payload_len = read_u16_be(packet, 4) as usize;
+    if payload_len < 4 || payload_len > packet.len() - IPV6_HEADER_LEN {
+        return Err(NdError::TooShort);
+    }
+    let src: Ipv6Addr = address_at(packet, 8);
+    let dst: Ipv6Addr = address_at(packet, 24);
+    let msg = &packet[IPV6_HEADER_LEN..IPV6_HEADER_LEN + payload_len];
+
+    let kind = msg[0];
+    if !(ICMPV6_ROUTER_SOLICIT..=ICMPV6_NEIGHBOR_ADVERT).contains(&kind) {
+        return Err(NdError::Unhandled(kind));
+    }
+    if packet[7] != ND_HOP_LIMIT {
+        return Err(NdError::BadHopLimit(packet[7]));
+    }
+    if msg[1] != 0 {
+        return Err(NdError::BadCode(msg[1]));
+    }
+    if icmpv6_checksum(&src, &dst, msg) != 0 {
+        return Err(NdError::BadChecksum);
+    }
+
+    match kind {
+        ICMPV6_ROUTER_ADVERT => parse_router_advert(&src, msg).map(NdMessage::RouterAdvert),
+        ICMPV6_NEIGHBOR_SOLICIT | ICMPV6_NEIGHBOR_ADVERT => {
+            if msg.len() < NS_NA_FIXED_LEN {
+                return Err(NdError::TooShort);
+            }
+            check_options(msg, NS_NA_FIXED_LEN)?;
+            let target = address_at(msg, 8);
+            if kind == ICMPV6_NEIGHBOR_SOLICIT {
+                Ok(NdMessage::NeighborSolicit { src, target })
+            } else {
+                Ok(NdMessage::NeighborAdvert { target })
+            }
+        }
+        _ => Err(NdError::Unhandled(kind)),
+    }
+}
+
+fn parse_router_advert(src: &Ipv6Addr, msg: &[u8]) -> Result<RouterAdvert, NdError> {
+    if msg.len() < RA_FIXED_LEN {
+        return Err(NdError::TooShort);
+    }
+    // RFC 4861 6.1.2: only a router's link-local address may advertise
+    if !is_link_local(src) {
+        return Err(NdError::NotIcmpv6);
+    }
+    let mut ra = RouterAdvert {
+        router: *src,
+        router_lifetime: read_u16_be(msg, 6),
+        managed: msg[5] & 0x80 != 0,
+        other: msg[5] & 0x40 != 0,
+        reachable_ms: read_u32_be(msg, 8),
+        retrans_ms: read_u32_be(msg, 12),
+        mtu: None,
+        router_mac: None,
+        prefixes: [NO_PREFIX; MAX_RA_PREFIXES],
+        prefix_count: 0,
+    };
+
+    let mut offset = RA_FIXED_LEN;
+    while offset < msg.len() {
+        let option = parse_option(msg, offset)?;
+        let body = &msg[option.offset..option.end()];
+        match option.kind {
+            OPT_SOURCE_LINK_ADDR if body.len() == OPTION_UNIT => {
+                let mut mac = [0u8; 6];
+                mac.copy_from_slice(&body[2..8]);
+                ra.router_mac = Some(mac);
+            }
+            OPT_MTU if body.len() == OPTION_UNIT => ra.mtu = Some(read_u32_be(body, 4)),
+            OPT_PREFIX_INFO if body.len() == 4 * OPTION_UNIT && ra.prefix_count < MAX_RA_PREFIXES => {
+                ra.prefixes[ra.prefix_count] = PrefixInfo {
+                    prefix: address_at(body, 16),
+                    prefix_len: body[2],
+                    on_link: body[3] & 0x80 != 0,
+                    autonomous: body[3] & 0x40 != 0,
+                    valid_lifetime: read_u32_be(body, 4),
+                    preferred_lifetime: read_u32_be(body, 8),
+                };
+                ra.prefix_count += 1;
+            }
+            // Unknown options, and known ones of the wrong size, are skipped
+            _ => {}
+        }
+        offset = option.end();
+    }
+    Ok(ra)
+}
+
+/// Walk the options after `fixed_len` only to check their bounds
+fn check_options(msg: &[u8], fixed_len: usize) -> Result<(), NdError> {
+    let mut offset = fixed_len;
+    while offset < msg.len() {
+        offset = parse_option(msg, offset)?.end();
+    }
+    Ok(())
+}
+
+fn address_at(buf: &[u8], offset: usize) -> Ipv6Addr {
+    let mut address = UNSPECIFIED;
+    address.copy_from_slice(&buf[offset..offset + 16]);
+    address
+}
+
+// ============================================================================
+// BUILDING
+// ============================================================================
+
+/// Length of a router solicitation packet with a source link-layer option
+pub const ROUTER_SOLICIT_LEN: usize = IPV6_HEADER_LEN + 8 + OPTION_UNIT;
+/// Length of a DAD probe
+pub const DAD_PROBE_LEN: usize = IPV6_HEADER_LEN + NS_NA_FIXED_LEN;
+
+/// Build a router solicitation from `src` to all routers into `buf`;
+/// returns the packet length
+///
+/// # Panics
+/// If `buf` is shorter than [`ROUTER_SOLICIT_LEN`].
+pub fn build_router_solicit(buf: &mut [u8], src: &Ipv6Addr, mac: &[u8; 6]) -> usize {
+    let packet = &mut buf[..ROUTER_SOLICIT_LEN];
+    packet.fill(0);
+    let msg = &mut packet[IPV6_HEADER_LEN..];
+    msg[0] = ICMPV6_ROUTER_SOLICIT;
+    msg[8] = OPT_SOURCE_LINK_ADDR;
+    msg[9] = 1;
+    msg[10..16].copy_from_slice(mac);
+    finish_nd(packet, src, &ALL_ROUTERS)
+}
+
+/// Build a DAD probe for `target` into `buf`: a neighbour solicitation from
+/// the unspecified address, which must carry no link-layer option, to the
+/// target's solicited-node group; returns the packet length
+///
+/// # Panics
+/// If `buf` is shorter than [`DAD_PROBE_LEN`].
+pub fn build_dad_probe(buf: &mut [u8], target: &Ipv6Addr) -> usize {
+    let packet = &mut buf[..DAD_PROBE_LEN];
+    packet.fill(0);
+    let msg = &mut packet[IPV6_HEADER_LEN..];
+    msg[0] = ICMPV6_NEIGHBOR_SOLICIT;
+    msg[8..24].copy_from_slice(target);
+    finish_nd(packet, &UNSPECIFIED, &solicited_node(target))
+}
+
+/// Fill in the IPv6 header and ICMPv6 checksum of `packet`
+fn finish_nd(packet: &mut [u8], src: &Ipv6Addr, dst: &Ipv6Addr) -> usize {
+    let len = packet.len();
+    packet[0] = 0x60;
+    packet[4..6].copy_from_slice(&((len - IPV6_HEADER_LEN) as u16).to_be_bytes());
+    packet[6] = NEXT_HEADER_ICMPV6;
+    packet[7] = ND_HOP_LIMIT;
+    packet[8..24].copy_from_slice(src);
+    packet[24..40].copy_from_slice(dst);
+    let checksum = icmpv6_checksum(src, dst, &packet[IPV6_HEADER_LEN..]);
+    packet[IPV6_HEADER_LEN + 2..IPV6_HEADER_LEN + 4].copy_from_slice(&checksum.to_be_bytes());
+    len
+}
+
+// ============================================================================
+// DUPLICATE ADDRESS DETECTION
+// ============================================================================
+
+/// Probes sent before an address is taken as unique (DupAddrDetectTransmits)
+pub const DAD_TRANSMITS: u8 = 1;
+/// Wait after each probe for a defence (RetransTimer)
+pub const DAD_RETRANS_MS: u64 = 1000;
+
+/// What [`Dad::poll`] wants done
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DadStep {
+    /// Send [`build_dad_probe`] for the target now
+    Probe,
+    /// Still tentative; poll again later
+    Wait,
+    /// No one defended the address: it may be assigned
+    Unique,
+    /// Someone else has the address: it must not be used
+    Duplicate,
+}
+
+/// Duplicate address detection for one tentative address
+///
+/// The address must not be assigned to the interface until [`poll`]
+/// returns [`DadStep::Unique`]; every neighbour-discovery message received
+/// meanwhile goes to [`observe`].
+///
+/// [`poll`]: Dad::poll
+/// [`observe`]: Dad::observe
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dad {
+    target: Ipv6Addr,
+    probes_sent: u8,
+    /// When the current probe's wait ends
+    deadline_ms: u64,
+    duplicate: bool,
+}
+
+impl Dad {
+    pub fn new(target: Ipv6Addr) -> Self {
+        Self { target, probes_sent: 0, deadline_ms: 0, duplicate: false }
+    }
+
+    pub fn target(&self) -> &Ipv6Addr {
+        &self.target
+    }
+
+    /// Advance to `now_ms`
+    pub fn poll(&mut self, now_ms: u64) -> DadStep {
+        if self.duplicate {
+            return DadStep::Duplicate;
+        }
+        if self.probes_sent > 0 && now_ms < self.deadline_ms {
+            return DadStep::Wait;
+        }
+        if self.probes_sent < DAD_TRANSMITS {
+            self.probes_sent += 1;
+            self.deadline_ms = now_ms.saturating_add(DAD_RETRANS_MS);
+            return DadStep::Probe;
+        }
+        DadStep::Unique
+    }
+
+    /// Take a received message into account: an advertisement for the
+    /// target is its owner defending it, and another DAD probe for it is a
+    /// node trying to claim it at the same time (RFC 4862 5.4.3)
+    pub fn observe(&mut self, message: &NdMessage) {
+        self.duplicate |= match message {
+            NdMessage::NeighborAdvert { target } => *target == self.target,
+            NdMessage::NeighborSolicit { src, target } => *src == UNSPECIFIED && *target == self.target,
+            NdMessage::RouterAdvert(_) => false,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: [u8; 6] = [0x52, 0x54, 0x00, 0x12, 0x34, 0x56];
+    const ROUTER: Ipv6Addr = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+    const PREFIX: Ipv6Addr = [0xfe, 0xc0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    /// Wrap `msg` in an IPv6 header from `src` to all-nodes, with a valid
+    /// checksum
+    fn packet(src: &Ipv6Addr, msg: &[u8], out: &mut [u8; 256]) -> usize {
+        let len = IPV6_HEADER_LEN + msg.len();
+        out[IPV6_HEADER_LEN..len].copy_from_slice(msg);
+        let mut all_nodes = [0u8; 16];
+        all_nodes[0] = 0xff;
+        all_nodes[1] = 0x02;
+        all_nodes[15] = 1;
+        finish_nd(&mut out[..len], src, &all_nodes)
+    }
+
+    fn router_advert(options: &[u8], out: &mut [u8; 256]) -> usize {
+        let mut msg = [0u8; 128];
+        msg[0] = ICMPV6_ROUTER_ADVERT;
+        msg[4] = 64;
+        msg[5] = 0x40;
+        msg[6..8].copy_from_slice(&1800u16.to_be_bytes());
+        msg[RA_FIXED_LEN..RA_FIXED_LEN + options.len()].copy_from_slice(options);
+        packet(&ROUTER, &msg[..RA_FIXED_LEN + options.len()], out)
+    }
+
+    fn prefix_option(prefix_len: u8, flags: u8, valid: u32, preferred: u32) -> [u8; 32] {
+        let mut option = [0u8; 32];
+        option[0] = OPT_PREFIX_INFO;
+        option[1] = 4;
+        option[2] = prefix_len;
+        option[3] = flags;
+        option[4..8].copy_from_slice(&valid.to_be_bytes());
+        option[8..12].copy_from_slice(&preferred.to_be_bytes());
+        option[16..32].copy_from_slice(&PREFIX);
+        option
+    }
+
+    #[test]
+    fn addresses_from_mac() {
+        let link_local = link_local_address(&MAC);
+        assert_eq!(link_local[..8], [0xfe, 0x80, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(link_local[8..], [0x50, 0x54, 0x00, 0xff, 0xfe, 0x12, 0x34, 0x56]);
+        assert_eq!(slaac_address(&PREFIX, &MAC)[..8], PREFIX[..8]);
+        let group = solicited_node(&link_local);
+        assert_eq!(group[..13], [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0xff]);
+        assert_eq!(group[13..], [0x12, 0x34, 0x56]);
+        assert_eq!(multicast_mac(&group), [0x33, 0x33, 0xff, 0x12, 0x34, 0x56]);
+    }
+
+    #[test]
+    fn router_advert_is_decoded() {
+        let mut options = [0u8; 48];
+        options[..8].copy_from_slice(&[OPT_SOURCE_LINK_ADDR, 1, 0x52, 0x55, 0x0a, 0, 2, 2]);
+        options[8..16].copy_from_slice(&[OPT_MTU, 1, 0, 0, 0, 0, 0x05, 0xdc]);
+        options[16..].copy_from_slice(&prefix_option(64, 0xc0, 86400, 14400));
+        let mut buf = [0u8; 256];
+        let len = router_advert(&options, &mut buf);
+
+        let Ok(NdMessage::RouterAdvert(ra)) = parse_nd(&buf[..len]) else { panic!("not an RA") };
+        assert_eq!(ra.router, ROUTER);
+        assert_eq!(ra.router_lifetime, 1800);
+        assert!(!ra.managed && ra.other);
+        assert_eq!(ra.mtu, Some(1500));
+        assert_eq!(ra.router_mac, Some([0x52, 0x55, 0x0a, 0, 2, 2]));
+        let prefix = ra.slaac_prefix().unwrap();
+        assert_eq!(prefix.prefix, PREFIX);
+        assert!(prefix.on_link && prefix.autonomous);
+    }
+
+    #[test]
+    fn unusable_prefixes_are_not_offered_for_slaac() {
+        let mut buf = [0u8; 256];
+        for option in [
+            prefix_option(48, 0xc0, 86400, 14400),
+            prefix_option(64, 0x80, 86400, 14400),
+            prefix_option(64, 0xc0, 100, 200),
+            prefix_option(64, 0xc0, 0, 0),
+        ] {
+            let len = router_advert(&option, &mut buf);
+            let Ok(NdMessage::RouterAdvert(ra)) = parse_nd(&buf[..len]) else { panic!("not an RA") };
+            assert_eq!(ra.prefixes().len(), 1);
+            assert_eq!(ra.slaac_prefix(), None);
+        }
+    }
+
+    #[test]
+    fn bad_options_and_headers_are_rejected() {
+        let mut buf = [0u8; 256];
+        // Zero-length option
+        let len = router_advert(&[OPT_MTU, 0, 0, 0, 0, 0, 0, 0], &mut buf);
+        assert_eq!(parse_nd(&buf[..len]), Err(NdError::BadOption { offset: RA_FIXED_LEN }));
+        // Option claiming more than the message holds
+        let len = router_advert(&[OPT_PREFIX_INFO, 4, 64, 0xc0, 0, 0, 0, 0], &mut buf);
+        assert_eq!(parse_nd(&buf[..len]), Err(NdError::BadOption { offset: RA_FIXED_LEN }));
+
+        let len = router_advert(&[], &mut buf);
+        let mut forwarded = buf;
+        forwarded[7] = 64;
+        assert_eq!(parse_nd(&forwarded[..len]), Err(NdError::BadHopLimit(64)));
+        let mut corrupt = buf;
+        corrupt[len - 1] ^= 1;
+        assert_eq!(parse_nd(&corrupt[..len]), Err(NdError::BadChecksum));
+        // Payload length beyond the packet, truncated header
+        assert_eq!(parse_nd(&buf[..len - 1]), Err(NdError::TooShort));
+        assert_eq!(parse_nd(&buf[..IPV6_HEADER_LEN - 1]), Err(NdError::TooShort));
+        // Not from a link-local router address
+        let mut msg = [0u8; RA_FIXED_LEN];
+        msg[0] = ICMPV6_ROUTER_ADVERT;
+        let len = packet(&PREFIX, &msg, &mut buf);
+        assert_eq!(parse_nd(&buf[..len]), Err(NdError::NotIcmpv6));
+    }
+
+    #[test]
+    fn built_packets_parse_back() {
+        let mut buf = [0u8; 256];
+        let target = link_local_address(&MAC);
+        let len = build_dad_probe(&mut buf, &target);
+        assert_eq!(len, DAD_PROBE_LEN);
+        assert_eq!(buf[24..40], solicited_node(&target));
+        assert_eq!(parse_nd(&buf[..len]), Ok(NdMessage::NeighborSolicit { src: UNSPECIFIED, target }));
+
+        let len = build_router_solicit(&mut buf, &target, &MAC);
+        assert_eq!(len, ROUTER_SOLICIT_LEN);
+        assert_eq!(buf[24..40], ALL_ROUTERS);
+        assert_eq!(icmpv6_checksum(&target, &ALL_ROUTERS, &buf[IPV6_HEADER_LEN..len]), 0);
+    }
+
+    #[test]
+    fn dad_waits_then_succeeds_or_sees_a_defender() {
+        let target = link_local_address(&MAC);
+        let mut dad = Dad::new(target);
+        assert_eq!(dad.poll(0), DadStep::Probe);
+        assert_eq!(dad.poll(DAD_RETRANS_MS - 1), DadStep::Wait);
+        assert_eq!(dad.poll(DAD_RETRANS_MS), DadStep::Unique);
+
+        let mut dad = Dad::new(target);
+        assert_eq!(dad.poll(0), DadStep::Probe);
+        // A solicitation from a configured host is not a conflict
+        dad.observe(&NdMessage::NeighborSolicit { src: ROUTER, target });
+        dad.observe(&NdMessage::NeighborAdvert { target: ROUTER });
+        assert_eq!(dad.poll(10), DadStep::Wait);
+        dad.observe(&NdMessage::NeighborAdvert { target });
+        assert_eq!(dad.poll(DAD_RETRANS_MS), DadStep::Duplicate);
+
+        let mut dad = Dad::new(target);
+        dad.observe(&NdMessage::NeighborSolicit { src: UNSPECIFIED, target });
+        assert_eq!(dad.poll(0), DadStep::Duplicate);
+    }
+}
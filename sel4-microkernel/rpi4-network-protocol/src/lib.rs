@@ -1,7 +1,8 @@
 //! Shared network IPC protocol between the Network PD and client PDs.
 //!
-//! The existing `NetSharedMemory` layout is retained. Restart-aware generation
-//! and verified SPSC ownership APIs are additive.
+//! The existing `NetSharedMemory` layout is retained. Restart-aware generation,
+//! verified SPSC ownership APIs and the appended link/counter status block are
+//! additive.
 
 #![no_std]
 
@@ -101,6 +102,8 @@ pub struct NetSharedMemory {
     pub mac_address: [u8; 6],
     pub link_up: u8,
     pub _reserved: u8,
+    /// Link state and counters, appended after the legacy fields
+    pub status: NetStatus,
 }
 
 impl NetSharedMemory {
@@ -130,16 +133,17 @@ pub struct WifiConnectRequest {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LinkStatusResponse {
     pub up: u8,
+    /// One of the `status::link_speed` codes
     pub speed: u8,
     pub full_duplex: u8,
     pub _reserved: u8,
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct StatsResponse {
     pub tx_packets: u64,
     pub rx_packets: u64,
@@ -147,6 +151,8 @@ pub struct StatsResponse {
     pub rx_bytes: u64,
     pub tx_errors: u64,
     pub rx_errors: u64,
+    pub tx_dropped: u64,
+    pub rx_dropped: u64,
 }
 
 impl NetRequestHeader {
@@ -206,8 +212,17 @@ mod generation_contract;
 mod generation;
 pub use generation::*;
 
+mod status_contract;
+pub mod status;
+pub use status::{link_speed, NetStatus, NetStatusSnapshot};
+
 pub mod arp;
+pub mod http_limits;
+pub mod ipconfig;
+pub mod ipv6;
+pub mod mdns;
 pub mod proof;
+pub mod weather;
 
 #[cfg(test)]
 mod compatibility_tests {
@@ -219,4 +234,20 @@ mod compatibility_tests {
         assert_eq!(RING_SIZE, 64);
         assert_eq!(ring_flags::VALID, 1);
     }
+
+    #[test]
+    fn status_block_is_appended_after_legacy_fields() {
+        use core::mem::offset_of;
+
+        let entry = core::mem::size_of::<TxRingEntry>();
+        assert_eq!(offset_of!(NetSharedMemory, tx_write_idx), RING_SIZE * entry);
+        assert_eq!(offset_of!(NetSharedMemory, rx_ring), RING_SIZE * entry + 8);
+        let rx_write = 2 * RING_SIZE * entry + 8;
+        assert_eq!(offset_of!(NetSharedMemory, rx_write_idx), rx_write);
+        assert_eq!(offset_of!(NetSharedMemory, mac_address), rx_write + 8);
+        assert_eq!(offset_of!(NetSharedMemory, link_up), rx_write + 14);
+        assert_eq!(offset_of!(NetSharedMemory, status), rx_write + 16);
+        // Still fits the 256 KiB net_ring region in the system descriptions
+        assert!(NetSharedMemory::size() <= 0x4_0000);
+    }
 }
@@ -0,0 +1,890 @@
+//! mDNS responder and DNS-SD advertisement (RFC 6762/6763).
+//!
+//! Lets phones and the host tooling find the device as `<host>.local`,
+//! and find its services by type, without knowing its address:
+//!
+//! ```text
+//! _services._dns-sd._udp.local  PTR  _photoframe._tcp.local
+//! _photoframe._tcp.local        PTR  Photo Frame._photoframe._tcp.local
+//! Photo Frame._photoframe._tcp.local  SRV  0 0 80 photoframe.local
+//!                                     TXT  "path=/"
+//! photoframe.local              A    10.0.2.15
+//! ```
+//!
+//! A [`Responder`] holds the host name and up to [`MAX_SERVICES`]
+//! services. [`Responder::answer`] picks the records a query asks for,
+//! with the additional records DNS-SD suggests alongside them, and
+//! [`Responder::write_reply`] and [`Responder::write_announcement`] build
+//! the response. Sockets, timing and who the reply goes to are the
+//! stack's.
+//!
+//! Known gaps: no probing, so a name already taken on the link is not
+//! noticed; known answers in a query are not parsed, so a record the
+//! asker already has is sent again; and a question's unicast-response bit
+//! is ignored, the answer going to the group as for any other.
+//!
+//! ## Verification
+//!
+//! Queries come from anyone on the link. Names are walked a step at a
+//! time by [`parse_name_step`], proven to return a label that lies inside
+//! the message, or a compression pointer to before the lowest offset the
+//! walk has been at: labels only move forward, and every pointer moves the
+//! lowest offset down, so [`read_name`] ends on any input without a
+//! visited-offsets table.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::ipv6::{read_u16_be, Ipv6Addr};
+
+verus! {
+
+// ============================================================================
+// LAYOUT
+// ============================================================================
+
+pub const MDNS_PORT: u16 = 5353;
+/// DNS message header: id, flags and four section counts
+pub const HEADER_LEN: usize = 12;
+pub const MAX_LABEL_LEN: usize = 63;
+/// Longest name in wire form, length bytes and the root included
+pub const MAX_NAME_LEN: usize = 255;
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+pub const TYPE_ANY: u16 = 255;
+pub const CLASS_IN: u16 = 1;
+pub const CLASS_ANY: u16 = 255;
+/// Top bit of a question's class: unicast response wanted; of a record's:
+/// the record replaces any the receiver has cached under its name
+pub const CLASS_TOP_BIT: u16 = 0x8000;
+
+/// QR: the message is a response
+pub const FLAG_RESPONSE: u16 = 0x8000;
+/// AA: the answers are authoritative, as every mDNS answer is
+pub const FLAG_AUTHORITATIVE: u16 = 0x0400;
+/// The opcode field; mDNS uses only 0, a standard query
+pub const FLAG_OPCODE_MASK: u16 = 0x7800;
+
+/// Why a message was not answered, or a response not built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MdnsError {
+    /// Shorter than its header, or a name or question runs off the end
+    TooShort,
+    /// A response, or a query with an opcode other than 0
+    NotQuery,
+    /// Label at this offset has a reserved length type or runs past the
+    /// message
+    BadLabel { offset: usize },
+    /// Compression pointer at this offset runs past the message or does
+    /// not point back before the name it is in
+    BadPointer { offset: usize },
+    /// Decoded name longer than [`MAX_NAME_LEN`]
+    NameTooLong,
+    /// A configured label that is empty or longer than [`MAX_LABEL_LEN`]
+    BadName,
+    /// A configured TXT entry longer than 255 bytes
+    BadTxt,
+    /// More services than [`MAX_SERVICES`]
+    TooManyServices,
+    /// The response does not fit the buffer
+    NoRoom,
+}
+
+/// The fixed part of a DNS message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub id: u16,
+    pub flags: u16,
+    pub questions: u16,
+    pub answers: u16,
+    pub authorities: u16,
+    pub additionals: u16,
+}
+
+/// Decode the header of `msg`
+pub fn parse_header(msg: &[u8]) -> (result: Result<Header, MdnsError>)
+    ensures
+        result.is_ok() ==> msg.len() >= HEADER_LEN,
+{
+    if msg.len() < HEADER_LEN {
+        return Err(MdnsError::TooShort);
+    }
+    Ok(Header {
+        id: read_u16_be(msg, 0),
+        flags: read_u16_be(msg, 2),
+        questions: read_u16_be(msg, 4),
+        answers: read_u16_be(msg, 6),
+        authorities: read_u16_be(msg, 8),
+        additionals: read_u16_be(msg, 10),
+    })
+}
+
+/// One step of a name, located in the message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameStep {
+    /// A label of `len` bytes after its length byte
+    Label { len: usize },
+    /// The rest of the name is at `target`
+    Pointer { target: usize },
+    /// The root: the name ends here
+    End,
+}
+
+/// Locate the step of a name at `offset` of `msg`, where a compression
+/// pointer must point before `limit`
+///
+/// A walk starts with `limit` at the name's offset and lowers it to each
+/// pointer's target: labels only advance, and each pointer lowers `limit`,
+/// so the walk ends.
+pub fn parse_name_step(msg: &[u8], offset: usize, limit: usize) -> (result: Result<NameStep, MdnsError>)
+    ensures
+        match result {
+            Ok(NameStep::Label { len }) => 1 <= len && len <= MAX_LABEL_LEN
+                && offset + 1 + len <= msg.len(),
+            Ok(NameStep::Pointer { target }) => offset + 2 <= msg.len() && target < limit,
+            Ok(NameStep::End) => offset < msg.len(),
+            Err(_) => true,
+        },
+{
+    if offset >= msg.len() {
+        return Err(MdnsError::TooShort);
+    }
+    let byte = msg[offset];
+    if byte == 0 {
+        Ok(NameStep::End)
+    } else if byte as usize <= MAX_LABEL_LEN {
+        let len = byte as usize;
+        if len > msg.len() - offset - 1 {
+            return Err(MdnsError::BadLabel { offset });
+        }
+        Ok(NameStep::Label { len })
+    } else if byte >= 0xc0 {
+        if msg.len() - offset < 2 {
+            return Err(MdnsError::BadPointer { offset });
+        }
+        let target = ((byte - 0xc0) as usize) * 256 + (msg[offset + 1] as usize);
+        // RFC 1035 4.1.4 only points back to an earlier name; a target at
+        // or past `limit` could loop
+        if target >= limit {
+            return Err(MdnsError::BadPointer { offset });
+        }
+        Ok(NameStep::Pointer { target })
+    } else {
+        // 0x40 and 0x80 length types are reserved (RFC 6891)
+        Err(MdnsError::BadLabel { offset })
+    }
+}
+
+/// Type and class of the question whose name ends at `offset`
+pub fn parse_question_fields(msg: &[u8], offset: usize) -> (result: Result<(u16, u16), MdnsError>)
+    ensures
+        result.is_ok() ==> offset + 4 <= msg.len(),
+{
+    if offset > msg.len() || msg.len() - offset < 4 {
+        return Err(MdnsError::TooShort);
+    }
+    Ok((read_u16_be(msg, offset), read_u16_be(msg, offset + 2)))
+}
+
+} // verus!
+
+// ============================================================================
+// ADDRESSES AND TIMING
+// ============================================================================
+
+/// `224.0.0.251`
+pub const MDNS_GROUP_V4: [u8; 4] = [224, 0, 0, 251];
+/// `ff02::fb`
+pub const MDNS_GROUP_V6: Ipv6Addr = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xfb];
+/// Room for any response: names are written uncompressed, and a full
+/// announcement of two services takes a little over 500 bytes
+pub const MAX_RESPONSE_LEN: usize = 1024;
+/// IP TTL or hop limit every mDNS packet is sent with (RFC 6762 11)
+pub const MDNS_HOP_LIMIT: u8 = 255;
+
+/// Seconds a host record (A, AAAA, SRV) is cached (RFC 6762 10)
+pub const HOST_TTL: u32 = 120;
+/// Seconds any other record is cached
+pub const SERVICE_TTL: u32 = 4500;
+/// Most seconds a record is cached by a legacy resolver, which cannot
+/// hear it change (RFC 6762 6.7)
+pub const LEGACY_TTL: u32 = 10;
+
+/// Unsolicited announcements after an address is configured, and the
+/// wait between them (RFC 6762 8.3)
+pub const ANNOUNCEMENTS: u8 = 2;
+pub const ANNOUNCE_INTERVAL_MS: u64 = 1000;
+
+/// Host name advertised as `<name>.local`
+pub const DEFAULT_HOSTNAME: &str = "photoframe";
+/// Instance name of the services; spaces are fine in an instance label
+pub const DEFAULT_INSTANCE: &str = "Photo Frame";
+/// Service type of the device itself
+pub const PHOTOFRAME_SERVICE: &str = "_photoframe._tcp";
+/// Service type of the HTTP control server
+pub const HTTP_SERVICE: &str = "_http._tcp";
+/// Port of the HTTP control server
+pub const CONTROL_PORT: u16 = 80;
+
+// ============================================================================
+// NAMES
+// ============================================================================
+
+/// A name in uncompressed wire form, ASCII lowercased so names compare
+/// case-insensitively as DNS requires
+#[derive(Clone, Copy)]
+pub struct WireName {
+    bytes: [u8; MAX_NAME_LEN],
+    /// Bytes used, the root included once the name is finished
+    len: usize,
+}
+
+impl WireName {
+    const EMPTY: Self = Self { bytes: [0; MAX_NAME_LEN], len: 0 };
+
+    /// The name of `labels`, under the root; `BadName` for an empty or
+    /// overlong label
+    pub fn from_labels<'a>(labels: impl IntoIterator<Item = &'a str>) -> Result<Self, MdnsError> {
+        let mut name = Self::EMPTY;
+        for label in labels {
+            if label.is_empty() || label.len() > MAX_LABEL_LEN {
+                return Err(MdnsError::BadName);
+            }
+            name.push_label(label.as_bytes())?;
+        }
+        name.push_root()?;
+        Ok(name)
+    }
+
+    /// `<kind>.local`, or `<instance>.<kind>.local` with an instance, for
+    /// a dotted `kind` such as `_http._tcp`
+    fn local(instance: Option<&str>, kind: &str) -> Result<Self, MdnsError> {
+        Self::from_labels(instance.into_iter().chain(kind.split('.')).chain(Some("local")))
+    }
+
+    fn push_label(&mut self, label: &[u8]) -> Result<(), MdnsError> {
+        // Room for the root after it
+        let end = self.len + 1 + label.len();
+        if end >= MAX_NAME_LEN {
+            return Err(MdnsError::NameTooLong);
+        }
+        self.bytes[self.len] = label.len() as u8;
+        for (to, from) in self.bytes[self.len + 1..end].iter_mut().zip(label) {
+            *to = from.to_ascii_lowercase();
+        }
+        self.len = end;
+        Ok(())
+    }
+
+    fn push_root(&mut self) -> Result<(), MdnsError> {
+        if self.len >= MAX_NAME_LEN {
+            return Err(MdnsError::NameTooLong);
+        }
+        self.bytes[self.len] = 0;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl PartialEq for WireName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for WireName {}
+
+impl core::fmt::Debug for WireName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut rest = self.as_bytes();
+        while let Some((&len, tail)) = rest.split_first() {
+            if len == 0 {
+                break;
+            }
+            let (label, tail) = tail.split_at(len as usize);
+            for &byte in label {
+                write!(f, "{}", byte as char)?;
+            }
+            f.write_str(".")?;
+            rest = tail;
+        }
+        Ok(())
+    }
+}
+
+/// Decode the name at `offset` of `msg`, following compression pointers;
+/// returns it and the offset just past it
+pub fn read_name(msg: &[u8], offset: usize) -> Result<(WireName, usize), MdnsError> {
+    let mut name = WireName::EMPTY;
+    let mut at = offset;
+    let mut limit = offset;
+    // Past the first pointer, if any: where the name ends in the message
+    let mut end = None;
+    loop {
+        match parse_name_step(msg, at, limit)? {
+            NameStep::Label { len } => {
+                name.push_label(&msg[at + 1..at + 1 + len])?;
+                at += 1 + len;
+            }
+            NameStep::Pointer { target } => {
+                end.get_or_insert(at + 2);
+                limit = target;
+                at = target;
+            }
+            NameStep::End => {
+                name.push_root()?;
+                return Ok((name, end.unwrap_or(at + 1)));
+            }
+        }
+    }
+}
+
+// ============================================================================
+// RESPONDER
+// ============================================================================
+
+/// Most services one responder advertises
+pub const MAX_SERVICES: usize = 2;
+
+/// A service to advertise
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Service<'a> {
+    /// Instance label, shown to users: "Photo Frame"
+    pub instance: &'a str,
+    /// Service type, dotted: "_photoframe._tcp"
+    pub kind: &'a str,
+    pub port: u16,
+    /// TXT entries, "key=value"
+    pub txt: &'a [&'a str],
+}
+
+#[derive(Clone, Copy)]
+struct Entry<'a> {
+    service: Service<'a>,
+    kind: WireName,
+    instance: WireName,
+}
+
+/// The addresses the host name resolves to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Addresses {
+    pub ipv4: Option<[u8; 4]>,
+    pub ipv6: Option<Ipv6Addr>,
+}
+
+/// A record the responder can send
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Record {
+    A,
+    Aaaa,
+    /// Service `n`'s type, under `_services._dns-sd._udp.local`
+    Enumerate(usize),
+    /// Service `n`'s instance, under its type
+    Ptr(usize),
+    /// Service `n`'s port and host
+    Srv(usize),
+    Txt(usize),
+}
+
+impl Record {
+    const COUNT: usize = 2 + 4 * MAX_SERVICES;
+
+    fn bit(self) -> u16 {
+        let index = match self {
+            Record::A => 0,
+            Record::Aaaa => 1,
+            Record::Enumerate(n) => 2 + 4 * n,
+            Record::Ptr(n) => 3 + 4 * n,
+            Record::Srv(n) => 4 + 4 * n,
+            Record::Txt(n) => 5 + 4 * n,
+        };
+        1 << index
+    }
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Record::A,
+            1 => Record::Aaaa,
+            _ => {
+                let n = (index - 2) / 4;
+                match (index - 2) % 4 {
+                    0 => Record::Enumerate(n),
+                    1 => Record::Ptr(n),
+                    2 => Record::Srv(n),
+                    _ => Record::Txt(n),
+                }
+            }
+        }
+    }
+}
+
+/// A set of records
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecordSet(u16);
+
+impl RecordSet {
+    pub fn insert(&mut self, record: Record) {
+        self.0 |= record.bit();
+    }
+
+    pub fn contains(&self, record: Record) -> bool {
+        self.0 & record.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Record> + '_ {
+        (0..Record::COUNT).map(Record::from_index).filter(|record| self.contains(*record))
+    }
+
+    fn without(self, other: RecordSet) -> RecordSet {
+        RecordSet(self.0 & !other.0)
+    }
+}
+
+/// What to send for one query
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reply {
+    /// The query's id, echoed to a legacy resolver
+    pub id: u16,
+    /// The first question answered, echoed to a legacy resolver
+    pub question: Option<(WireName, u16)>,
+    pub answers: RecordSet,
+    /// Records the asker will want next, and not among the answers
+    pub additionals: RecordSet,
+}
+
+impl Reply {
+    /// Nothing asked for is ours: send nothing
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+/// The names and records of one host
+pub struct Responder<'a> {
+    host: WireName,
+    enumeration: WireName,
+    entries: [Option<Entry<'a>>; MAX_SERVICES],
+}
+
+impl<'a> Responder<'a> {
+    /// A responder for `<hostname>.local` and `services`
+    pub fn new(hostname: &str, services: &[Service<'a>]) -> Result<Self, MdnsError> {
+        if services.len() > MAX_SERVICES {
+            return Err(MdnsError::TooManyServices);
+        }
+        let mut entries = [None; MAX_SERVICES];
+        for (entry, service) in entries.iter_mut().zip(services) {
+            if service.txt.iter().any(|txt| txt.len() > 255) {
+                return Err(MdnsError::BadTxt);
+            }
+            *entry = Some(Entry {
+                service: *service,
+                kind: WireName::local(None, service.kind)?,
+                instance: WireName::local(Some(service.instance), service.kind)?,
+            });
+        }
+        Ok(Self {
+            host: WireName::local(None, hostname)?,
+            enumeration: WireName::local(None, "_services._dns-sd._udp")?,
+            entries,
+        })
+    }
+
+    pub fn host(&self) -> &WireName {
+        &self.host
+    }
+
+    fn services(&self) -> impl Iterator<Item = (usize, &Entry<'a>)> + '_ {
+        self.entries.iter().enumerate().filter_map(|(n, entry)| entry.as_ref().map(|e| (n, e)))
+    }
+
+    /// Every record there is an address for: the announcement
+    pub fn all_records(&self, addrs: &Addresses) -> RecordSet {
+        let mut set = RecordSet::default();
+        self.add_host(&mut set, addrs);
+        for (n, _) in self.services() {
+            for record in [Record::Enumerate(n), Record::Ptr(n), Record::Srv(n), Record::Txt(n)] {
+                set.insert(record);
+            }
+        }
+        set
+    }
+
+    fn add_host(&self, set: &mut RecordSet, addrs: &Addresses) {
+        if addrs.ipv4.is_some() {
+            set.insert(Record::A);
+        }
+        if addrs.ipv6.is_some() {
+            set.insert(Record::Aaaa);
+        }
+    }
+
+    /// The records query `msg` asks for, if any are ours
+    pub fn answer(&self, msg: &[u8], addrs: &Addresses) -> Result<Reply, MdnsError> {
+        let header = parse_header(msg)?;
+        if header.flags & (FLAG_RESPONSE | FLAG_OPCODE_MASK) != 0 {
+            return Err(MdnsError::NotQuery);
+        }
+        let mut reply = Reply {
+            id: header.id,
+            question: None,
+            answers: RecordSet::default(),
+            additionals: RecordSet::default(),
+        };
+        let mut offset = HEADER_LEN;
+        for _ in 0..header.questions {
+            let (name, end) = read_name(msg, offset)?;
+            let (qtype, qclass) = parse_question_fields(msg, end)?;
+            offset = end + 4;
+            if qclass & !CLASS_TOP_BIT != CLASS_IN && qclass & !CLASS_TOP_BIT != CLASS_ANY {
+                continue;
+            }
+            let before = reply.answers;
+            self.match_question(&name, qtype, addrs, &mut reply);
+            if reply.question.is_none() && reply.answers != before {
+                reply.question = Some((name, qtype));
+            }
+        }
+        reply.additionals = reply.additionals.without(reply.answers);
+        Ok(reply)
+    }
+
+    fn match_question(&self, name: &WireName, qtype: u16, addrs: &Addresses, reply: &mut Reply) {
+        let wants = |kind: u16| qtype == kind || qtype == TYPE_ANY;
+        if *name == self.host {
+            if wants(TYPE_A) && addrs.ipv4.is_some() {
+                reply.answers.insert(Record::A);
+            }
+            if wants(TYPE_AAAA) && addrs.ipv6.is_some() {
+                reply.answers.insert(Record::Aaaa);
+            }
+            return;
+        }
+        for (n, entry) in self.services() {
+            // RFC 6763 12: a PTR brings its SRV, TXT and addresses, an SRV
+            // its addresses
+            if *name == self.enumeration && wants(TYPE_PTR) {
+                reply.answers.insert(Record::Enumerate(n));
+            } else if *name == entry.kind && wants(TYPE_PTR) {
+                reply.answers.insert(Record::Ptr(n));
+                reply.additionals.insert(Record::Srv(n));
+                reply.additionals.insert(Record::Txt(n));
+                self.add_host(&mut reply.additionals, addrs);
+            } else if *name == entry.instance {
+                if wants(TYPE_SRV) {
+                    reply.answers.insert(Record::Srv(n));
+                    self.add_host(&mut reply.additionals, addrs);
+                }
+                if wants(TYPE_TXT) {
+                    reply.answers.insert(Record::Txt(n));
+                }
+            }
+        }
+    }
+
+    /// Build the response to `reply` into `buf`; returns its length
+    ///
+    /// `legacy` is for a query from a port other than [`MDNS_PORT`], a
+    /// plain resolver asking once: the response goes back to it alone,
+    /// with its id and question, short TTLs and no cache-flush bits
+    /// (RFC 6762 6.7).
+    pub fn write_reply(
+        &self,
+        buf: &mut [u8],
+        reply: &Reply,
+        addrs: &Addresses,
+        legacy: bool,
+    ) -> Result<usize, MdnsError> {
+        let question = if legacy { reply.question.as_ref() } else { None };
+        let mut w = Writer { buf, len: 0 };
+        w.u16(if legacy { reply.id } else { 0 })?;
+        w.u16(FLAG_RESPONSE | FLAG_AUTHORITATIVE)?;
+        w.u16(question.is_some() as u16)?;
+        w.u16(reply.answers.len() as u16)?;
+        w.u16(0)?;
+        w.u16(reply.additionals.len() as u16)?;
+        if let Some((name, qtype)) = question {
+            w.bytes(name.as_bytes())?;
+            w.u16(*qtype)?;
+            w.u16(CLASS_IN)?;
+        }
+        for record in reply.answers.iter().chain(reply.additionals.iter()) {
+            self.write_record(&mut w, record, addrs, legacy)?;
+        }
+        Ok(w.len)
+    }
+
+    /// Build an unsolicited response with every record into `buf`;
+    /// returns its length
+    pub fn write_announcement(&self, buf: &mut [u8], addrs: &Addresses) -> Result<usize, MdnsError> {
+        let reply = Reply {
+            id: 0,
+            question: None,
+            answers: self.all_records(addrs),
+            additionals: RecordSet::default(),
+        };
+        self.write_reply(buf, &reply, addrs, false)
+    }
+
+    fn write_record(
+        &self,
+        w: &mut Writer<'_>,
+        record: Record,
+        addrs: &Addresses,
+        legacy: bool,
+    ) -> Result<(), MdnsError> {
+        let entry = |n: usize| self.entries[n].as_ref().ok_or(MdnsError::BadName);
+        // Records only this host has are unique; the PTRs are shared with
+        // every other host offering the service
+        let (name, kind, ttl, unique) = match record {
+            Record::A => (&self.host, TYPE_A, HOST_TTL, true),
+            Record::Aaaa => (&self.host, TYPE_AAAA, HOST_TTL, true),
+            Record::Enumerate(_) => (&self.enumeration, TYPE_PTR, SERVICE_TTL, false),
+            Record::Ptr(n) => (&entry(n)?.kind, TYPE_PTR, SERVICE_TTL, false),
+            Record::Srv(n) => (&entry(n)?.instance, TYPE_SRV, HOST_TTL, true),
+            Record::Txt(n) => (&entry(n)?.instance, TYPE_TXT, SERVICE_TTL, true),
+        };
+        w.bytes(name.as_bytes())?;
+        w.u16(kind)?;
+        w.u16(if unique && !legacy { CLASS_IN | CLASS_TOP_BIT } else { CLASS_IN })?;
+        w.u32(if legacy { ttl.min(LEGACY_TTL) } else { ttl })?;
+        let length_at = w.len;
+        w.u16(0)?;
+        match record {
+            Record::A => w.bytes(&addrs.ipv4.ok_or(MdnsError::BadName)?)?,
+            Record::Aaaa => w.bytes(&addrs.ipv6.ok_or(MdnsError::BadName)?)?,
+            Record::Enumerate(n) => w.bytes(entry(n)?.kind.as_bytes())?,
+            Record::Ptr(n) => w.bytes(entry(n)?.instance.as_bytes())?,
+            Record::Srv(n) => {
+                // Priority and weight: one instance, so any will do
+                w.u16(0)?;
+                w.u16(0)?;
+                w.u16(entry(n)?.service.port)?;
+                w.bytes(self.host.as_bytes())?;
+            }
+            Record::Txt(n) => {
+                let txt = entry(n)?.service.txt;
+                // RFC 6763 6.1: no entries is one empty string
+                if txt.is_empty() {
+                    w.bytes(&[0])?;
+                }
+                for item in txt {
+                    w.bytes(&[item.len() as u8])?;
+                    w.bytes(item.as_bytes())?;
+                }
+            }
+        }
+        let rdlength = (w.len - length_at - 2) as u16;
+        w.buf[length_at..length_at + 2].copy_from_slice(&rdlength.to_be_bytes());
+        Ok(())
+    }
+}
+
+struct Writer<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, bytes: &[u8]) -> Result<(), MdnsError> {
+        let end = self.len + bytes.len();
+        if end > self.buf.len() {
+            return Err(MdnsError::NoRoom);
+        }
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn u16(&mut self, value: u16) -> Result<(), MdnsError> {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    fn u32(&mut self, value: u32) -> Result<(), MdnsError> {
+        self.bytes(&value.to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    const TXT: &[&str] = &["path=/"];
+    const SERVICES: [Service<'static>; 2] = [
+        Service { instance: DEFAULT_INSTANCE, kind: PHOTOFRAME_SERVICE, port: CONTROL_PORT, txt: TXT },
+        Service { instance: DEFAULT_INSTANCE, kind: HTTP_SERVICE, port: CONTROL_PORT, txt: &[] },
+    ];
+    const ADDRS: Addresses = Addresses { ipv4: Some([10, 0, 2, 15]), ipv6: None };
+
+    fn responder() -> Responder<'static> {
+        Responder::new(DEFAULT_HOSTNAME, &SERVICES).unwrap()
+    }
+
+    /// A query for `name` (dotted) of `qtype`
+    fn query(name: &str, qtype: u16, out: &mut [u8; 512]) -> usize {
+        out[..HEADER_LEN].copy_from_slice(&[0x12, 0x34, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+        let name = WireName::from_labels(name.split('.')).unwrap();
+        let end = HEADER_LEN + name.as_bytes().len();
+        out[HEADER_LEN..end].copy_from_slice(name.as_bytes());
+        out[end..end + 2].copy_from_slice(&qtype.to_be_bytes());
+        out[end + 2..end + 4].copy_from_slice(&CLASS_IN.to_be_bytes());
+        end + 4
+    }
+
+    #[test]
+    fn names_decode_through_pointers_case_insensitively() {
+        // "local" at 12, "Photoframe" + pointer to it at 19
+        let mut msg = [0u8; 64];
+        msg[12..19].copy_from_slice(b"\x05local\x00");
+        msg[19..30].copy_from_slice(b"\x0aPhotoframe");
+        msg[30..32].copy_from_slice(&[0xc0, 12]);
+        let (name, end) = read_name(&msg, 19).unwrap();
+        assert_eq!(name, WireName::local(None, DEFAULT_HOSTNAME).unwrap());
+        assert_eq!(end, 32);
+    }
+
+    #[test]
+    fn pointer_loops_and_overruns_are_rejected() {
+        let mut msg = [0u8; 32];
+        // Points at itself
+        msg[12..14].copy_from_slice(&[0xc0, 12]);
+        assert_eq!(read_name(&msg, 12), Err(MdnsError::BadPointer { offset: 12 }));
+        // Backwards, into a label that points forward to itself again
+        msg[12..16].copy_from_slice(b"\x01a\xc0\x0c");
+        msg[16..18].copy_from_slice(&[0xc0, 12]);
+        assert_eq!(read_name(&msg, 16), Err(MdnsError::BadPointer { offset: 14 }));
+        // A label past the end, and a reserved length type
+        assert_eq!(read_name(&msg[..14], 12).map(|_| ()), Err(MdnsError::TooShort));
+        assert_eq!(read_name(b"\x05ab", 0), Err(MdnsError::BadLabel { offset: 0 }));
+        assert_eq!(read_name(b"\x40", 0), Err(MdnsError::BadLabel { offset: 0 }));
+        // 128 one-byte labels run past the longest name
+        let long = [[1u8, b'a']; 128];
+        assert_eq!(read_name(long.as_flattened(), 0), Err(MdnsError::NameTooLong));
+    }
+
+    #[test]
+    fn host_query_gets_its_address() {
+        let mut msg = [0u8; 512];
+        let len = query("PhotoFrame.local", TYPE_A, &mut msg);
+        let reply = responder().answer(&msg[..len], &ADDRS).unwrap();
+        assert_eq!(reply.answers.iter().collect::<Vec<_>>(), [Record::A]);
+        assert!(reply.additionals.is_empty());
+
+        // No IPv6 address: an AAAA question is not ours to answer
+        let len = query("photoframe.local", TYPE_AAAA, &mut msg);
+        assert!(responder().answer(&msg[..len], &ADDRS).unwrap().is_empty());
+        let len = query("other.local", TYPE_ANY, &mut msg);
+        assert!(responder().answer(&msg[..len], &ADDRS).unwrap().is_empty());
+    }
+
+    #[test]
+    fn browse_brings_srv_txt_and_address() {
+        let mut msg = [0u8; 512];
+        let len = query("_photoframe._tcp.local", TYPE_PTR, &mut msg);
+        let reply = responder().answer(&msg[..len], &ADDRS).unwrap();
+        assert_eq!(reply.answers.iter().collect::<Vec<_>>(), [Record::Ptr(0)]);
+        assert_eq!(
+            reply.additionals.iter().collect::<Vec<_>>(),
+            [Record::A, Record::Srv(0), Record::Txt(0)]
+        );
+
+        let len = query("_services._dns-sd._udp.local", TYPE_PTR, &mut msg);
+        let reply = responder().answer(&msg[..len], &ADDRS).unwrap();
+        assert_eq!(reply.answers.iter().collect::<Vec<_>>(), [Record::Enumerate(0), Record::Enumerate(1)]);
+    }
+
+    #[test]
+    fn responses_are_not_answered() {
+        let mut msg = [0u8; 512];
+        let len = query("photoframe.local", TYPE_A, &mut msg);
+        msg[2] = 0x84;
+        assert_eq!(responder().answer(&msg[..len], &ADDRS), Err(MdnsError::NotQuery));
+        assert_eq!(responder().answer(&msg[..5], &ADDRS), Err(MdnsError::TooShort));
+    }
+
+    #[test]
+    fn srv_reply_round_trips() {
+        let r = responder();
+        let mut msg = [0u8; 512];
+        let len = query("photo frame._http._tcp.local", TYPE_SRV, &mut msg);
+        let reply = r.answer(&msg[..len], &ADDRS).unwrap();
+        assert_eq!(reply.answers.iter().collect::<Vec<_>>(), [Record::Srv(1)]);
+
+        let mut out = [0u8; 512];
+        let len = r.write_reply(&mut out, &reply, &ADDRS, false).unwrap();
+        let out = &out[..len];
+        let header = parse_header(out).unwrap();
+        assert_eq!((header.id, header.questions, header.answers, header.additionals), (0, 0, 1, 1));
+        assert_eq!(header.flags, FLAG_RESPONSE | FLAG_AUTHORITATIVE);
+
+        let (name, end) = read_name(out, HEADER_LEN).unwrap();
+        assert_eq!(name, WireName::local(Some(DEFAULT_INSTANCE), HTTP_SERVICE).unwrap());
+        assert_eq!(read_u16_be(out, end), TYPE_SRV);
+        assert_eq!(read_u16_be(out, end + 2), CLASS_IN | CLASS_TOP_BIT);
+        let rdlength = read_u16_be(out, end + 8) as usize;
+        let rdata = &out[end + 10..end + 10 + rdlength];
+        assert_eq!(read_u16_be(rdata, 4), CONTROL_PORT);
+        assert_eq!(read_name(rdata, 6).unwrap().0, *r.host());
+
+        // Then the A record, its address last
+        assert_eq!(&out[len - 4..], &[10, 0, 2, 15]);
+    }
+
+    #[test]
+    fn legacy_reply_echoes_question_with_short_ttls() {
+        let r = responder();
+        let mut msg = [0u8; 512];
+        let len = query("photoframe.local", TYPE_A, &mut msg);
+        let reply = r.answer(&msg[..len], &ADDRS).unwrap();
+        let mut out = [0u8; 512];
+        let written = r.write_reply(&mut out, &reply, &ADDRS, true).unwrap();
+        let header = parse_header(&out).unwrap();
+        assert_eq!((header.id, header.questions, header.answers), (0x1234, 1, 1));
+        // The question as asked, then the record under the same name
+        assert_eq!(&out[HEADER_LEN..len], &msg[HEADER_LEN..len]);
+        let (_, end) = read_name(&out, len).unwrap();
+        assert_eq!(read_u16_be(&out, end + 2), CLASS_IN);
+        assert_eq!(&out[end + 4..end + 8], &LEGACY_TTL.to_be_bytes());
+        assert_eq!(written, end + 14);
+    }
+
+    #[test]
+    fn announcement_has_every_record_and_checks_room() {
+        let r = responder();
+        let mut out = [0u8; MAX_RESPONSE_LEN];
+        let len = r.write_announcement(&mut out, &ADDRS).unwrap();
+        assert_eq!(parse_header(&out[..len]).unwrap().answers, 9);
+        assert_eq!(r.write_announcement(&mut out[..len - 1], &ADDRS), Err(MdnsError::NoRoom));
+    }
+
+    #[test]
+    fn configuration_is_checked() {
+        let long = [b'a'; 64];
+        let long = core::str::from_utf8(&long).unwrap();
+        assert_eq!(Responder::new(long, &[]).err(), Some(MdnsError::BadName));
+        assert_eq!(Responder::new("", &[]).err(), Some(MdnsError::BadName));
+        let three = [SERVICES[0]; 3];
+        assert_eq!(Responder::new("host", &three).err(), Some(MdnsError::TooManyServices));
+    }
+}
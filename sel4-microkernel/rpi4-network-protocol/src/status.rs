@@ -0,0 +1,258 @@
+//! Link state and interface counters published by the Network PD.
+//!
+//! The status block is appended after the legacy `NetSharedMemory` fields,
+//! so existing offsets are unchanged. The Network PD is the only writer and
+//! brackets every update with a sequence word (odd while rewriting). Clients
+//! take a [`NetStatusSnapshot`] and retry if an update raced the read.
+//!
+//! A link transition bumps `link_changes` and the Network PD notifies the
+//! client channel; clients compare the counter with the value they last saw.
+
+use core::sync::atomic::{fence, AtomicU32, AtomicU64, Ordering};
+
+use crate::status_contract::{begin_update, end_update, snapshot_stable};
+use crate::{LinkStatusResponse, StatsResponse};
+
+/// Negotiated speed codes carried in `LinkStatusResponse::speed`
+pub mod link_speed {
+    /// Link down, or speed not reported by the driver
+    pub const UNKNOWN: u8 = 0;
+    pub const MBPS_10: u8 = 1;
+    pub const MBPS_100: u8 = 2;
+    pub const MBPS_1000: u8 = 3;
+
+    /// Speed in Mbit/s for a code (0 for unknown codes)
+    pub const fn mbps(code: u8) -> u32 {
+        match code {
+            MBPS_10 => 10,
+            MBPS_100 => 100,
+            MBPS_1000 => 1000,
+            _ => 0,
+        }
+    }
+}
+
+/// Number of 64-bit counters in the status block (see [`StatsResponse`])
+pub const STATUS_COUNTERS: usize = 8;
+
+/// Read attempts before [`NetStatus::snapshot`] gives up on a busy writer
+pub const SNAPSHOT_RETRIES: usize = 4;
+
+impl LinkStatusResponse {
+    pub const fn down() -> Self {
+        Self {
+            up: 0,
+            speed: link_speed::UNKNOWN,
+            full_duplex: 0,
+            _reserved: 0,
+        }
+    }
+
+    pub const fn is_up(&self) -> bool {
+        self.up != 0
+    }
+
+    const fn pack(&self) -> u32 {
+        self.up as u32 | (self.speed as u32) << 8 | (self.full_duplex as u32) << 16
+    }
+
+    const fn unpack(word: u32) -> Self {
+        Self {
+            up: word as u8,
+            speed: (word >> 8) as u8,
+            full_duplex: (word >> 16) as u8,
+            _reserved: 0,
+        }
+    }
+}
+
+impl StatsResponse {
+    /// Counters in block order
+    pub const fn words(&self) -> [u64; STATUS_COUNTERS] {
+        [
+            self.tx_packets,
+            self.rx_packets,
+            self.tx_bytes,
+            self.rx_bytes,
+            self.tx_errors,
+            self.rx_errors,
+            self.tx_dropped,
+            self.rx_dropped,
+        ]
+    }
+
+    pub const fn from_words(w: [u64; STATUS_COUNTERS]) -> Self {
+        Self {
+            tx_packets: w[0],
+            rx_packets: w[1],
+            tx_bytes: w[2],
+            rx_bytes: w[3],
+            tx_errors: w[4],
+            rx_errors: w[5],
+            tx_dropped: w[6],
+            rx_dropped: w[7],
+        }
+    }
+}
+
+/// Consistent copy of the status block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetStatusSnapshot {
+    /// Link transitions published so far (wrapping)
+    pub link_changes: u32,
+    pub link: LinkStatusResponse,
+    pub stats: StatsResponse,
+}
+
+impl NetStatusSnapshot {
+    /// True if the link changed since the client saw `seen` changes
+    pub const fn link_changed_since(&self, seen: u32) -> bool {
+        self.link_changes != seen
+    }
+}
+
+/// Shared status block written by the Network PD
+#[repr(C, align(8))]
+pub struct NetStatus {
+    /// Odd while the Network PD is rewriting the block
+    pub seq: AtomicU32,
+    pub link_changes: AtomicU32,
+    /// Packed `LinkStatusResponse` (up, speed, full_duplex)
+    pub link: AtomicU32,
+    pub _reserved: u32,
+    pub counters: [AtomicU64; STATUS_COUNTERS],
+}
+
+impl NetStatus {
+    /// All-zero block: link down, no counters, as in freshly mapped memory
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const fn new() -> Self {
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        Self {
+            seq: AtomicU32::new(0),
+            link_changes: AtomicU32::new(0),
+            link: AtomicU32::new(0),
+            _reserved: 0,
+            counters: [ZERO; STATUS_COUNTERS],
+        }
+    }
+
+    /// Publish the current link state and counters.
+    ///
+    /// Returns `true` if the link state differs from the last published
+    /// one; the caller then notifies its clients. Single writer only.
+    pub fn publish(&self, link: LinkStatusResponse, stats: &StatsResponse) -> bool {
+        let odd = begin_update(self.seq.load(Ordering::Relaxed));
+        self.seq.store(odd, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let packed = link.pack();
+        let changed = self.link.load(Ordering::Relaxed) != packed;
+        if changed {
+            self.link.store(packed, Ordering::Relaxed);
+            let changes = self.link_changes.load(Ordering::Relaxed);
+            self.link_changes.store(changes.wrapping_add(1), Ordering::Relaxed);
+        }
+        for (slot, value) in self.counters.iter().zip(stats.words()) {
+            slot.store(value, Ordering::Relaxed);
+        }
+
+        self.seq.store(end_update(odd), Ordering::Release);
+        changed
+    }
+
+    /// Take a consistent snapshot, or `None` if every attempt raced an
+    /// update (the caller keeps its previous snapshot).
+    pub fn snapshot(&self) -> Option<NetStatusSnapshot> {
+        for _ in 0..SNAPSHOT_RETRIES {
+            let before = self.seq.load(Ordering::Acquire);
+            let link_changes = self.link_changes.load(Ordering::Relaxed);
+            let link = LinkStatusResponse::unpack(self.link.load(Ordering::Relaxed));
+            let mut words = [0u64; STATUS_COUNTERS];
+            for (word, slot) in words.iter_mut().zip(self.counters.iter()) {
+                *word = slot.load(Ordering::Relaxed);
+            }
+            fence(Ordering::Acquire);
+            let after = self.seq.load(Ordering::Relaxed);
+
+            if snapshot_stable(before, after) {
+                return Some(NetStatusSnapshot {
+                    link_changes,
+                    link,
+                    stats: StatsResponse::from_words(words),
+                });
+            }
+            core::hint::spin_loop();
+        }
+        None
+    }
+}
+
+impl Default for NetStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn up_1000() -> LinkStatusResponse {
+        LinkStatusResponse {
+            up: 1,
+            speed: link_speed::MBPS_1000,
+            full_duplex: 1,
+            _reserved: 0,
+        }
+    }
+
+    #[test]
+    fn fresh_block_reads_link_down() {
+        let status = NetStatus::new();
+        let snap = status.snapshot().unwrap();
+        assert_eq!(snap.link, LinkStatusResponse::down());
+        assert_eq!(snap.stats, StatsResponse::default());
+        assert!(!snap.link_changed_since(0));
+    }
+
+    #[test]
+    fn link_transitions_bump_change_counter() {
+        let status = NetStatus::new();
+        let stats = StatsResponse::default();
+
+        assert!(status.publish(up_1000(), &stats));
+        assert!(!status.publish(up_1000(), &stats));
+        let snap = status.snapshot().unwrap();
+        assert!(snap.link.is_up());
+        assert_eq!(link_speed::mbps(snap.link.speed), 1000);
+        assert_eq!(snap.link_changes, 1);
+
+        assert!(status.publish(LinkStatusResponse::down(), &stats));
+        let snap = status.snapshot().unwrap();
+        assert!(!snap.link.is_up());
+        assert!(snap.link_changed_since(1));
+    }
+
+    #[test]
+    fn counters_round_trip() {
+        let status = NetStatus::new();
+        let stats = StatsResponse::from_words([1, 2, 3, 4, 5, 6, 7, 8]);
+        status.publish(up_1000(), &stats);
+        assert_eq!(status.snapshot().unwrap().stats, stats);
+        assert_eq!(status.seq.load(Ordering::Relaxed) % 2, 0);
+    }
+
+    #[test]
+    fn torn_update_is_not_returned() {
+        let status = NetStatus::new();
+        // Writer stopped mid-update (odd sequence word)
+        status.seq.store(5, Ordering::Relaxed);
+        assert!(status.snapshot().is_none());
+
+        // Its next publish still ends on an even word
+        status.publish(up_1000(), &StatsResponse::default());
+        assert_eq!(status.seq.load(Ordering::Relaxed), 6);
+        assert!(status.snapshot().is_some());
+    }
+}
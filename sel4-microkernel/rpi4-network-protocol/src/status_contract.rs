@@ -0,0 +1,50 @@
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// Sequence word the writer stores before rewriting the status block.
+///
+/// Total over all inputs: a writer that restarted mid-update finds an odd
+/// word and keeps it odd instead of publishing a half-written snapshot.
+pub fn begin_update(seq: u32) -> (odd: u32)
+    ensures
+        odd % 2u32 == 1u32,
+        seq % 2u32 == 1u32 ==> odd == seq,
+        seq % 2u32 == 0u32 ==> odd as int == seq as int + 1,
+{
+    assert(seq % 2u32 == 1u32 ==> (seq | 1u32) == seq) by (bit_vector);
+    assert(seq % 2u32 == 0u32 ==> (seq | 1u32) == seq + 1u32) by (bit_vector);
+    assert((seq | 1u32) % 2u32 == 1u32) by (bit_vector);
+    seq | 1u32
+}
+
+/// Sequence word that publishes the rewritten status block.
+pub fn end_update(odd: u32) -> (even: u32)
+    requires
+        odd % 2u32 == 1u32,
+    ensures
+        even % 2u32 == 0u32,
+        even != odd,
+{
+    if odd == u32::MAX {
+        0
+    } else {
+        odd + 1u32
+    }
+}
+
+/// A snapshot is usable only if no update started or was in flight
+/// between the two sequence reads.
+// Spelled out: vstd has no spec for `u32::is_multiple_of`
+#[allow(clippy::manual_is_multiple_of)]
+pub fn snapshot_stable(before: u32, after: u32) -> (stable: bool)
+    ensures
+        stable <==> (before == after && before % 2u32 == 0u32),
+{
+    before == after && before % 2u32 == 0u32
+}
+
+} // verus!
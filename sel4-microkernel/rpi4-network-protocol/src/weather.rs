@@ -0,0 +1,283 @@
+//! Verified weather report from the Network PD to the display.
+//!
+//! The Network PD fetches the local weather over plain HTTP and publishes
+//! one [`WeatherReport`] on the weather page, which the display maps
+//! read-only:
+//!
+//! ```text
+//! ┌────────────┐  weather_page (rw / r)  ┌─────────────────┐
+//! │ Network PD │ ──────────────────────► │ Photoframe PD   │
+//! └────────────┘                         │ (weather panel) │
+//!                                        └─────────────────┘
+//! ```
+//!
+//! The fetch waits on the TCP stack: until a Network PD joins the system,
+//! the page stays zeroed and the panel stays hidden.
+//!
+//! ## The weather page
+//!
+//! A sequence lock, like the clock page: the writer makes `seq` odd,
+//! writes, and makes it even again; a reader that sees `seq` odd or changed
+//! under it retries, at most [`READ_ATTEMPTS`] times.
+//!
+//! ## Verification
+//!
+//! The page is written by the network-facing PD, so the display trusts
+//! none of it: [`decode_report`] is proven to return only reports with a
+//! temperature within [`MIN_TEMP_C`]..=[`MAX_TEMP_C`] and a known condition
+//! code, and [`WeatherPage::read`] returns nothing it has not passed.
+
+#![allow(clippy::manual_range_contains)]
+
+use core::ptr::{addr_of, addr_of_mut, read_volatile, write_volatile};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+// ============================================================================
+// MEMORY REGIONS
+// ============================================================================
+
+/// Weather page: written by the Network PD, read by the display
+pub const WEATHER_PAGE_VADDR: usize = 0x5_0E00_0000;
+pub const WEATHER_PAGE_SIZE: usize = 0x1000;
+
+pub open spec fn in_weather_page_region(addr: usize) -> bool {
+    addr >= WEATHER_PAGE_VADDR && addr < WEATHER_PAGE_VADDR + WEATHER_PAGE_SIZE
+}
+
+/// Specification: what this protocol grants a reader
+pub open spec fn weather_reader_can_access(addr: usize) -> bool {
+    in_weather_page_region(addr)
+}
+
+// ============================================================================
+// REPORT
+// ============================================================================
+
+/// Coldest temperature a report may carry (the record is -89.2 °C)
+pub const MIN_TEMP_C: i16 = -90;
+/// Hottest temperature a report may carry (the record is 56.7 °C)
+pub const MAX_TEMP_C: i16 = 60;
+
+pub const CONDITION_CLEAR: u8 = 0;
+pub const CONDITION_PARTLY_CLOUDY: u8 = 1;
+pub const CONDITION_CLOUDY: u8 = 2;
+pub const CONDITION_FOG: u8 = 3;
+pub const CONDITION_DRIZZLE: u8 = 4;
+pub const CONDITION_RAIN: u8 = 5;
+pub const CONDITION_SNOW: u8 = 6;
+pub const CONDITION_THUNDERSTORM: u8 = 7;
+/// Highest condition code defined
+pub const MAX_CONDITION_CODE: u8 = CONDITION_THUNDERSTORM;
+
+/// Current conditions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeatherReport {
+    /// Air temperature, whole degrees Celsius
+    pub temp_c: i16,
+    /// One of the `CONDITION_*` codes
+    pub condition_code: u8,
+    /// The last fetch succeeded; when `false` the other fields are zero
+    /// and the display shows nothing
+    pub valid: bool,
+}
+
+/// Specification: a report the display may render
+pub open spec fn well_formed(r: WeatherReport) -> bool {
+    if r.valid {
+        MIN_TEMP_C <= r.temp_c <= MAX_TEMP_C && r.condition_code <= MAX_CONDITION_CODE
+    } else {
+        r.temp_c == 0 && r.condition_code == 0
+    }
+}
+
+/// Decode a report from the words on the weather page; `None` if any of
+/// them is out of range
+pub fn decode_report(temp_c: i32, condition_code: u32, valid: u32) -> (r: Option<WeatherReport>)
+    ensures
+        r.is_some() ==> well_formed(r.unwrap()),
+        r.is_some() ==> (r.unwrap().valid <==> valid == 1),
+        r.is_some() && valid == 1 ==> r.unwrap().temp_c == temp_c && r.unwrap().condition_code == condition_code,
+{
+    if valid == 0 {
+        if temp_c != 0 || condition_code != 0 {
+            return None;
+        }
+        return Some(WeatherReport { temp_c: 0, condition_code: 0, valid: false });
+    }
+    if valid != 1 {
+        return None;
+    }
+    if temp_c < MIN_TEMP_C as i32 || temp_c > MAX_TEMP_C as i32 {
+        return None;
+    }
+    if condition_code > MAX_CONDITION_CODE as u32 {
+        return None;
+    }
+    Some(WeatherReport { temp_c: temp_c as i16, condition_code: condition_code as u8, valid: true })
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: the weather page is all a reader is granted
+proof fn reader_can_access_only_weather_page()
+    ensures
+        forall|addr: usize| weather_reader_can_access(addr) ==> in_weather_page_region(addr)
+{
+}
+
+} // verus!
+
+// ============================================================================
+// WEATHER PAGE
+// ============================================================================
+
+/// Marks a weather page the Network PD has published to ("WTH1")
+pub const WEATHER_MAGIC: u32 = 0x5754_4831;
+
+/// Tries a reader makes before giving up on a page being rewritten
+pub const READ_ATTEMPTS: usize = 4;
+
+impl WeatherReport {
+    /// No current reading
+    pub const UNAVAILABLE: WeatherReport = WeatherReport { temp_c: 0, condition_code: 0, valid: false };
+
+    /// Short upper-case name of the condition, for the display
+    pub fn condition_name(&self) -> &'static str {
+        match self.condition_code {
+            CONDITION_CLEAR => "CLEAR",
+            CONDITION_PARTLY_CLOUDY => "PARTLY CLOUDY",
+            CONDITION_CLOUDY => "CLOUDY",
+            CONDITION_FOG => "FOG",
+            CONDITION_DRIZZLE => "DRIZZLE",
+            CONDITION_RAIN => "RAIN",
+            CONDITION_SNOW => "SNOW",
+            CONDITION_THUNDERSTORM => "THUNDERSTORM",
+            _ => "?",
+        }
+    }
+}
+
+/// Layout of the weather page
+#[repr(C)]
+pub struct WeatherPage {
+    magic: u32,
+    /// Odd while the Network PD is writing
+    seq: AtomicU32,
+    temp_c: i32,
+    condition_code: u32,
+    valid: u32,
+}
+
+impl WeatherPage {
+    /// Publish `report`
+    ///
+    /// # Safety
+    /// `base` must point to the mapped weather page, and the caller must be
+    /// its only writer.
+    pub unsafe fn publish(base: *mut u8, report: &WeatherReport) {
+        let page = base as *mut WeatherPage;
+        let seq = &(*page).seq;
+        let writing = seq.load(Ordering::Relaxed).wrapping_add(1) | 1;
+        seq.store(writing, Ordering::Relaxed);
+        fence(Ordering::Release);
+        write_volatile(addr_of_mut!((*page).magic), WEATHER_MAGIC);
+        write_volatile(addr_of_mut!((*page).temp_c), report.temp_c as i32);
+        write_volatile(addr_of_mut!((*page).condition_code), report.condition_code as u32);
+        write_volatile(addr_of_mut!((*page).valid), report.valid as u32);
+        seq.store(writing.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Read the published report; `None` if nothing has been published,
+    /// [`decode_report`] refuses it, or it was being rewritten on every one
+    /// of [`READ_ATTEMPTS`] tries
+    ///
+    /// # Safety
+    /// `base` must point to the mapped weather page.
+    pub unsafe fn read(base: *const u8) -> Option<WeatherReport> {
+        let page = base as *const WeatherPage;
+        let seq = &(*page).seq;
+        for _ in 0..READ_ATTEMPTS {
+            let before = seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let magic = read_volatile(addr_of!((*page).magic));
+            let temp_c = read_volatile(addr_of!((*page).temp_c));
+            let condition_code = read_volatile(addr_of!((*page).condition_code));
+            let valid = read_volatile(addr_of!((*page).valid));
+            fence(Ordering::Acquire);
+            if seq.load(Ordering::Relaxed) != before {
+                continue;
+            }
+            if magic != WEATHER_MAGIC {
+                return None;
+            }
+            return decode_report(temp_c, condition_code, valid);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_are_range_checked() {
+        let report = WeatherReport { temp_c: 21, condition_code: CONDITION_CLOUDY, valid: true };
+        assert_eq!(decode_report(21, CONDITION_CLOUDY as u32, 1), Some(report));
+        assert_eq!(decode_report(MIN_TEMP_C as i32, 0, 1).map(|r| r.temp_c), Some(MIN_TEMP_C));
+        assert_eq!(decode_report(MAX_TEMP_C as i32, MAX_CONDITION_CODE as u32, 1).map(|r| r.temp_c), Some(MAX_TEMP_C));
+        assert_eq!(decode_report(0, 0, 0), Some(WeatherReport::UNAVAILABLE));
+        // Out of range, unknown conditions, a garbage flag, leftovers
+        // beside an invalid flag
+        assert_eq!(decode_report(MAX_TEMP_C as i32 + 1, 0, 1), None);
+        assert_eq!(decode_report(MIN_TEMP_C as i32 - 1, 0, 1), None);
+        assert_eq!(decode_report(i32::MIN, 0, 1), None);
+        assert_eq!(decode_report(0, MAX_CONDITION_CODE as u32 + 1, 1), None);
+        assert_eq!(decode_report(0, 0, 2), None);
+        assert_eq!(decode_report(21, 0, 0), None);
+        assert_eq!(report.condition_name(), "CLOUDY");
+    }
+
+    #[repr(C, align(8))]
+    struct Page([u8; WEATHER_PAGE_SIZE]);
+
+    #[test]
+    fn weather_page_publishes_and_reads_back() {
+        let mut page = Page([0; WEATHER_PAGE_SIZE]);
+        let base = page.0.as_mut_ptr();
+        unsafe {
+            // Zeroed: nothing published
+            assert_eq!(WeatherPage::read(base), None);
+            let report = WeatherReport { temp_c: -7, condition_code: CONDITION_SNOW, valid: true };
+            WeatherPage::publish(base, &report);
+            assert_eq!(WeatherPage::read(base), Some(report));
+            WeatherPage::publish(base, &WeatherReport::UNAVAILABLE);
+            assert_eq!(WeatherPage::read(base), Some(WeatherReport::UNAVAILABLE));
+
+            // Mid-write on every try
+            let raw = base as *mut WeatherPage;
+            (*raw).seq.store(5, Ordering::Relaxed);
+            assert_eq!(WeatherPage::read(base), None);
+            (*raw).seq.store(6, Ordering::Relaxed);
+
+            // Values no Network PD writes
+            (*raw).valid = 1;
+            (*raw).temp_c = 200;
+            assert_eq!(WeatherPage::read(base), None);
+            (*raw).temp_c = 20;
+            (*raw).condition_code = 99;
+            assert_eq!(WeatherPage::read(base), None);
+        }
+    }
+}
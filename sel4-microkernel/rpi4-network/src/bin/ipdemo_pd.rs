@@ -10,15 +10,25 @@ mod stack;
 #[path = "../time.rs"]
 mod time;
 
-use core::fmt;
-
 use netif::{NetifConfig, NetworkInterface};
+use rpi4_network_protocol::ipconfig::IpMode;
+#[cfg(feature = "net-http")]
+use rpi4_network_protocol::http_limits::{MAX_CONNECTIONS, MAX_REQUEST_LEN};
+#[cfg(feature = "net-mdns")]
+use rpi4_network_protocol::mdns::{self, Responder, Service};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
 use smoltcp::iface::SocketStorage;
 use smoltcp::socket::icmp;
+#[cfg(feature = "net-ipv6")]
+use smoltcp::socket::raw;
+#[cfg(feature = "net-mdns")]
+use smoltcp::socket::udp;
 use stack::{
     DeviceResources, NetworkStack, StackEvent, StackResources, FRAME_CAPACITY,
 };
+#[cfg(feature = "net-http")]
+use stack::HTTP_RESPONSE_CAPACITY;
 
 const VIRTIO_MMIO_VADDR: usize = 0x5_0900_0000;
 const VIRTIO_MMIO_SIZE: usize = 0x4000;
@@ -30,12 +40,59 @@ const NET_IRQ_CHANNEL: Channel = Channel::new(NET_IRQ_CHANNEL_ID);
 
 static mut FRAME_RX: [u8; FRAME_CAPACITY] = [0; FRAME_CAPACITY];
 static mut FRAME_TX: [u8; FRAME_CAPACITY] = [0; FRAME_CAPACITY];
-static mut SOCKET_STORAGE: [SocketStorage<'static>; 2] =
-    [SocketStorage::EMPTY, SocketStorage::EMPTY];
+/// DHCP and ICMP, plus raw ICMPv6 for SLAAC, UDP for mDNS and TCP for
+/// each control server connection
+const SOCKETS: usize = 2
+    + cfg!(feature = "net-ipv6") as usize
+    + cfg!(feature = "net-mdns") as usize
+    + if cfg!(feature = "net-http") { MAX_CONNECTIONS } else { 0 };
+
+static mut SOCKET_STORAGE: [SocketStorage<'static>; SOCKETS] = [SocketStorage::EMPTY; SOCKETS];
 static mut ICMP_RX_METADATA: [icmp::PacketMetadata; 1] = [icmp::PacketMetadata::EMPTY];
 static mut ICMP_TX_METADATA: [icmp::PacketMetadata; 1] = [icmp::PacketMetadata::EMPTY];
 static mut ICMP_RX_PAYLOAD: [u8; 128] = [0; 128];
 static mut ICMP_TX_PAYLOAD: [u8; 128] = [0; 128];
+#[cfg(feature = "net-ipv6")]
+static mut RAW_RX_METADATA: [raw::PacketMetadata; 4] = [raw::PacketMetadata::EMPTY; 4];
+#[cfg(feature = "net-ipv6")]
+static mut RAW_TX_METADATA: [raw::PacketMetadata; 2] = [raw::PacketMetadata::EMPTY; 2];
+#[cfg(feature = "net-ipv6")]
+static mut RAW_RX_PAYLOAD: [u8; 1024] = [0; 1024];
+#[cfg(feature = "net-ipv6")]
+static mut RAW_TX_PAYLOAD: [u8; 256] = [0; 256];
+#[cfg(feature = "net-mdns")]
+static mut UDP_RX_METADATA: [udp::PacketMetadata; 4] = [udp::PacketMetadata::EMPTY; 4];
+#[cfg(feature = "net-mdns")]
+static mut UDP_TX_METADATA: [udp::PacketMetadata; 4] = [udp::PacketMetadata::EMPTY; 4];
+#[cfg(feature = "net-mdns")]
+static mut UDP_RX_PAYLOAD: [u8; 2048] = [0; 2048];
+#[cfg(feature = "net-mdns")]
+static mut UDP_TX_PAYLOAD: [u8; 2048] = [0; 2048];
+#[cfg(feature = "net-http")]
+static mut TCP_RX_PAYLOAD: [u8; MAX_CONNECTIONS * MAX_REQUEST_LEN] =
+    [0; MAX_CONNECTIONS * MAX_REQUEST_LEN];
+#[cfg(feature = "net-http")]
+static mut TCP_TX_PAYLOAD: [u8; MAX_CONNECTIONS * HTTP_RESPONSE_CAPACITY] =
+    [0; MAX_CONNECTIONS * HTTP_RESPONSE_CAPACITY];
+
+/// Advertised over mDNS: the device, and the HTTP control server on
+/// [`mdns::CONTROL_PORT`]. Without `net-http` nothing listens there, and a
+/// browser that follows the record finds the port closed.
+#[cfg(feature = "net-mdns")]
+const SERVICES: [Service<'static>; 2] = [
+    Service {
+        instance: mdns::DEFAULT_INSTANCE,
+        kind: mdns::PHOTOFRAME_SERVICE,
+        port: mdns::CONTROL_PORT,
+        txt: &["path=/"],
+    },
+    Service {
+        instance: mdns::DEFAULT_INSTANCE,
+        kind: mdns::HTTP_SERVICE,
+        port: mdns::CONTROL_PORT,
+        txt: &["path=/"],
+    },
+];
 
 struct IpDemoHandler {
     stack: NetworkStack<'static, NetworkInterface>,
@@ -44,6 +101,9 @@ struct IpDemoHandler {
 impl IpDemoHandler {
     fn log_event(event: StackEvent) {
         match event {
+            StackEvent::StaticConfigured(address) => {
+                debug_println!("STATIC OK {}", address);
+            }
             StackEvent::DhcpConfigured(address) => {
                 debug_println!("DHCP OK {}", address);
             }
@@ -51,6 +111,20 @@ impl IpDemoHandler {
             StackEvent::PingSent => debug_println!("PING SENT 10.0.2.2"),
             StackEvent::PingReply => debug_println!("PING OK"),
             StackEvent::TransmitError => debug_println!("NETWORK TX ERROR"),
+            #[cfg(feature = "net-ipv6")]
+            StackEvent::Ipv6LinkLocal(address) => debug_println!("IPV6 LINK-LOCAL {}", address),
+            #[cfg(feature = "net-ipv6")]
+            StackEvent::Ipv6Configured(address) => debug_println!("SLAAC OK {}", address),
+            #[cfg(feature = "net-ipv6")]
+            StackEvent::Ipv6Duplicate(address) => debug_println!("IPV6 DUPLICATE {}", address),
+            #[cfg(feature = "net-mdns")]
+            StackEvent::MdnsAnnounced(n) => {
+                debug_println!("MDNS ANNOUNCED {}.local ({})", mdns::DEFAULT_HOSTNAME, n)
+            }
+            #[cfg(feature = "net-mdns")]
+            StackEvent::MdnsAnswered(querier) => debug_println!("MDNS ANSWERED {}", querier),
+            #[cfg(feature = "net-http")]
+            StackEvent::HttpAnswered(client, status) => debug_println!("HTTP {} {}", status, client),
         }
     }
 
@@ -98,10 +172,40 @@ fn init() -> IpDemoHandler {
             icmp_rx_payload: &mut *core::ptr::addr_of_mut!(ICMP_RX_PAYLOAD),
             icmp_tx_metadata: &mut *core::ptr::addr_of_mut!(ICMP_TX_METADATA),
             icmp_tx_payload: &mut *core::ptr::addr_of_mut!(ICMP_TX_PAYLOAD),
+            #[cfg(feature = "net-ipv6")]
+            raw_rx_metadata: &mut *core::ptr::addr_of_mut!(RAW_RX_METADATA),
+            #[cfg(feature = "net-ipv6")]
+            raw_rx_payload: &mut *core::ptr::addr_of_mut!(RAW_RX_PAYLOAD),
+            #[cfg(feature = "net-ipv6")]
+            raw_tx_metadata: &mut *core::ptr::addr_of_mut!(RAW_TX_METADATA),
+            #[cfg(feature = "net-ipv6")]
+            raw_tx_payload: &mut *core::ptr::addr_of_mut!(RAW_TX_PAYLOAD),
+            #[cfg(feature = "net-mdns")]
+            mdns_responder: Responder::new(mdns::DEFAULT_HOSTNAME, &SERVICES)
+                .expect("fixed mDNS names are valid"),
+            #[cfg(feature = "net-mdns")]
+            udp_rx_metadata: &mut *core::ptr::addr_of_mut!(UDP_RX_METADATA),
+            #[cfg(feature = "net-mdns")]
+            udp_rx_payload: &mut *core::ptr::addr_of_mut!(UDP_RX_PAYLOAD),
+            #[cfg(feature = "net-mdns")]
+            udp_tx_metadata: &mut *core::ptr::addr_of_mut!(UDP_TX_METADATA),
+            #[cfg(feature = "net-mdns")]
+            udp_tx_payload: &mut *core::ptr::addr_of_mut!(UDP_TX_PAYLOAD),
+            #[cfg(feature = "net-http")]
+            tcp_rx_payload: &mut *core::ptr::addr_of_mut!(TCP_RX_PAYLOAD),
+            #[cfg(feature = "net-http")]
+            tcp_tx_payload: &mut *core::ptr::addr_of_mut!(TCP_TX_PAYLOAD),
         }
     };
 
-    let stack = NetworkStack::new(netif, mac, resources, time::instant());
+    // No settings store in the QEMU product: lease from slirp, as CI expects
+    let stack = NetworkStack::new(
+        netif,
+        mac,
+        IpMode::Dhcp,
+        resources,
+        time::instant(),
+    );
     let mut handler = IpDemoHandler { stack };
     // The first poll emits DHCP DISCOVER; later virtio interrupts drive the
     // offer/ack and ICMP exchange.
@@ -109,23 +213,16 @@ fn init() -> IpDemoHandler {
     handler
 }
 
-#[derive(Debug)]
-struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "ipdemo handler error")
-    }
-}
-
 impl Handler for IpDemoHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
         if channels.contains(NET_IRQ_CHANNEL) {
             self.stack.io_mut().handle_irq();
             self.poll();
-            NET_IRQ_CHANNEL.irq_ack().map_err(|_| HandlerError)?;
+            NET_IRQ_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking network IRQ")
+            })?;
         }
         Ok(())
     }
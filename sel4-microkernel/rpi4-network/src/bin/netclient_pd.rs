@@ -10,7 +10,8 @@
 //! 2. QEMU's slirp backend answers the ARP request; the Network PD pulls
 //!    the reply from the driver into the RX ring and notifies us.
 //! 3. On notification, drains the RX ring and logs each frame — the CI
-//!    boot test greps these lines.
+//!    boot test greps these lines — and logs any link transition the
+//!    Network PD published in the status block.
 //!
 //! Together with the Network PD this covers: client -> TX ring -> driver
 //! -> QEMU -> driver -> RX ring -> client.
@@ -18,13 +19,12 @@
 #![no_std]
 #![no_main]
 
-use core::fmt;
-
 use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
+use rpi4_pd_error::PdError;
 
 use rpi4_network_protocol::arp;
 use rpi4_network_protocol::proof::{consumer_permit, producer_permit, slot_for};
-use rpi4_network_protocol::{ring_flags, NetSharedMemory, NET_CLIENT_CHANNEL_ID};
+use rpi4_network_protocol::{link_speed, ring_flags, NetSharedMemory, NET_CLIENT_CHANNEL_ID};
 
 /// Shared memory with the Network PD (must match netdemo.system)
 const NET_RING_VADDR: usize = 0x5_0700_0000;
@@ -44,6 +44,8 @@ const GATEWAY_IP: [u8; 4] = [10, 0, 2, 2];
 struct NetClientHandler {
     shared: *mut NetSharedMemory,
     frames_seen: u32,
+    /// `status.link_changes` value last logged
+    link_changes_seen: u32,
 }
 
 impl NetClientHandler {
@@ -140,6 +142,32 @@ impl NetClientHandler {
             core::ptr::write_volatile(&mut shared.rx_read_idx, read.wrapping_add(1));
         }
     }
+
+    /// Log the link state and counters if the link changed since last seen.
+    ///
+    /// # Safety
+    /// `self.shared` must point to the Microkit-mapped shared region.
+    unsafe fn check_link(&mut self) {
+        let shared = &*self.shared;
+        let Some(status) = shared.status.snapshot() else {
+            return;
+        };
+        if !status.link_changed_since(self.link_changes_seen) {
+            return;
+        }
+        self.link_changes_seen = status.link_changes;
+
+        let stats = status.stats;
+        debug_println!(
+            "netclient: link {} ({} Mbps), rx {} pkts / {} dropped, tx {} pkts / {} dropped",
+            if status.link.is_up() { "up" } else { "down" },
+            link_speed::mbps(status.link.speed),
+            stats.rx_packets,
+            stats.rx_dropped,
+            stats.tx_packets,
+            stats.tx_dropped
+        );
+    }
 }
 
 #[protection_domain]
@@ -150,6 +178,7 @@ fn init() -> NetClientHandler {
     let mut handler = NetClientHandler {
         shared: NET_RING_VADDR as *mut NetSharedMemory,
         frames_seen: 0,
+        link_changes_seen: 0,
     };
 
     // Safety: NET_RING_VADDR is mapped by netdemo.system. The Network PD
@@ -163,23 +192,15 @@ fn init() -> NetClientHandler {
     handler
 }
 
-#[derive(Debug)]
-pub struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "netclient handler error")
-    }
-}
-
 impl Handler for NetClientHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
         if channels.contains(NET_CHANNEL) {
             // Safety: shared region is mapped by the system description
             unsafe {
                 self.drain_rx();
+                self.check_link();
             }
         }
         Ok(())
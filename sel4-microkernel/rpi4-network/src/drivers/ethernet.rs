@@ -798,7 +798,7 @@ impl NetworkDriver for EthernetDriver {
 
         let in_flight = tx_prod.wrapping_sub(tx_cons);
         if in_flight as usize >= regs::TOTAL_DESC {
-            self.stats.dropped += 1;
+            self.stats.tx_dropped += 1;
             return Err(DriverError::BufferAllocation);
         }
 
@@ -890,7 +890,7 @@ impl NetworkDriver for EthernetDriver {
         } else if len > buffer.len() {
             // Caller's buffer is too small: drop the frame but still
             // recycle the descriptor so the ring keeps flowing.
-            self.stats.dropped += 1;
+            self.stats.rx_dropped += 1;
         } else {
             // Safety: `src` points into the attached, mapped DMA region and
             // `len <= BUF_LENGTH`; `buffer` bounds were checked above.
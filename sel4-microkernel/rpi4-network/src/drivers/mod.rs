@@ -14,6 +14,9 @@ pub mod virtio_net;
 #[cfg(feature = "net-wifi")]
 pub mod wifi;
 
+use rpi4_network_protocol::{link_speed, LinkStatusResponse, StatsResponse};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
 /// A physically-contiguous, uncached memory region used for DMA packet
 /// buffers and (for virtio) virtqueue rings.
 ///
@@ -55,6 +58,22 @@ pub enum DriverError {
     SdioError,
 }
 
+impl From<DriverError> for PdError {
+    fn from(e: DriverError) -> Self {
+        let kind = match e {
+            DriverError::HardwareNotFound => ErrorKind::NotFound,
+            DriverError::InvalidConfig => ErrorKind::InvalidArgument,
+            DriverError::Timeout => ErrorKind::Timeout,
+            DriverError::BufferAllocation => ErrorKind::Capacity,
+            DriverError::DmaNotAttached | DriverError::NoLink => ErrorKind::NotReady,
+            DriverError::InitializationFailed | DriverError::FirmwareError | DriverError::SdioError => {
+                ErrorKind::Hardware
+            }
+        };
+        PdError::new(ErrorDomain::Network, kind).with_detail(e as u16)
+    }
+}
+
 /// MAC address (6 bytes)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MacAddress(pub [u8; 6]);
@@ -109,6 +128,23 @@ impl LinkStatus {
     }
 }
 
+impl From<LinkStatus> for LinkStatusResponse {
+    fn from(status: LinkStatus) -> Self {
+        let speed = match status.speed {
+            Some(LinkSpeed::Speed10) => link_speed::MBPS_10,
+            Some(LinkSpeed::Speed100) => link_speed::MBPS_100,
+            Some(LinkSpeed::Speed1000) => link_speed::MBPS_1000,
+            None => link_speed::UNKNOWN,
+        };
+        Self {
+            up: status.up as u8,
+            speed,
+            full_duplex: status.full_duplex as u8,
+            _reserved: 0,
+        }
+    }
+}
+
 /// Network driver statistics
 #[derive(Debug, Default, Clone, Copy)]
 pub struct DriverStats {
@@ -124,8 +160,25 @@ pub struct DriverStats {
     pub tx_errors: u64,
     /// RX errors
     pub rx_errors: u64,
-    /// Packets dropped
-    pub dropped: u64,
+    /// Frames refused because the TX ring was full
+    pub tx_dropped: u64,
+    /// Received frames dropped because the caller's buffer was too small
+    pub rx_dropped: u64,
+}
+
+impl From<DriverStats> for StatsResponse {
+    fn from(stats: DriverStats) -> Self {
+        Self {
+            tx_packets: stats.tx_packets,
+            rx_packets: stats.rx_packets,
+            tx_bytes: stats.tx_bytes,
+            rx_bytes: stats.rx_bytes,
+            tx_errors: stats.tx_errors,
+            rx_errors: stats.rx_errors,
+            tx_dropped: stats.tx_dropped,
+            rx_dropped: stats.rx_dropped,
+        }
+    }
 }
 
 /// Common trait for network drivers
@@ -382,7 +382,7 @@ impl NetworkDriver for VirtioNetDriver {
         }
         let in_flight = self.tx.avail_idx.wrapping_sub(self.tx.last_used);
         if in_flight >= self.tx.qsize {
-            self.stats.dropped += 1;
+            self.stats.tx_dropped += 1;
             return Err(DriverError::BufferAllocation);
         }
 
@@ -431,7 +431,7 @@ impl NetworkDriver for VirtioNetDriver {
         if total_len > regs::NET_HDR_LEN {
             let len = total_len - regs::NET_HDR_LEN;
             if len > buffer.len() {
-                self.stats.dropped += 1;
+                self.stats.rx_dropped += 1;
             } else {
                 let buf_vaddr = self.dma.vaddr + RX_BUFS_OFF + id * regs::BUF_LENGTH;
                 unsafe {
@@ -39,6 +39,7 @@
 //! - FreeBSD if_bwfm driver
 
 use super::{DriverError, DriverStats, LinkStatus, MacAddress, NetworkDriver};
+use verified_microkernel::BoundedString;
 
 /// WiFi power enable GPIO (active high)
 const WL_ON_GPIO: u32 = 41;
@@ -125,6 +126,19 @@ pub struct WifiNetwork {
     pub security: WifiSecurity,
 }
 
+/// SSID as displayable text
+pub type Ssid = BoundedString<32>;
+
+impl WifiNetwork {
+    /// SSID as text, or None if it is not valid UTF-8 (SSIDs are raw
+    /// octets, so a network may advertise one that cannot be shown as is)
+    pub fn ssid_name(&self) -> Option<Ssid> {
+        let len = self.ssid_len.min(self.ssid.len());
+        let text = core::str::from_utf8(&self.ssid[..len]).ok()?;
+        text.parse().ok()
+    }
+}
+
 /// WiFi driver state
 pub struct WifiDriver {
     /// SDIO controller base address
@@ -20,6 +20,7 @@
 //! | SDIO regs   | 0x5_0600_0000   | 0xFE340000       |
 //! | Net ring    | 0x5_0700_0000   | (allocated)      |
 //! | Net DMA     | 0x5_0800_0000   | 0x3E700000       |
+//! | Boot log    | 0x5_0B00_0000   | (allocated)      |
 //!
 //! # Memory map — QEMU virt (must match netdemo.system)
 //!
@@ -28,6 +29,10 @@
 //! | virtio-mmio | 0x5_0900_0000   | 0x0A000000       |
 //! | Net ring    | 0x5_0700_0000   | (allocated)      |
 //! | Net DMA     | 0x5_0800_0000   | 0x50000000       |
+//! | Boot log    | 0x5_0B00_0000   | (allocated)      |
+//!
+//! Init results and link changes also go to the boot log page, which the
+//! Graphics PD's early console shows on boards without a serial console.
 
 #![no_std]
 #![no_main]
@@ -35,12 +40,17 @@
 mod drivers;
 mod netif;
 
-use core::fmt;
-
 use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
+use rpi4_pd_error::bootlog::{BootLog, Level};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 
+use drivers::LinkStatus;
 use netif::{NetifConfig, NetworkInterface};
-use rpi4_network_protocol::{proof, ring_flags, NetSharedMemory, NET_CLIENT_CHANNEL_ID};
+use rpi4_network_protocol::{
+    link_speed, proof, ring_flags, LinkStatusResponse, NetSharedMemory, StatsResponse,
+    NET_CLIENT_CHANNEL_ID,
+};
+use verified_microkernel::event_loop::{EventLoop, Work};
 
 /// GENET (Ethernet) registers, mapped by Microkit
 #[cfg(feature = "net-ethernet")]
@@ -84,41 +94,57 @@ const GPIO_VADDR: usize = 0x5_0610_0000;
 /// Shared memory with client PDs, mapped by Microkit
 const NET_RING_VADDR: usize = 0x5_0700_0000;
 
+/// Boot log page for the Graphics PD's early console, mapped by Microkit
+const BOOT_LOG_VADDR: usize = 0x5_0B00_0000;
+
 /// IRQ channel id (must match tvdemo-network.system)
 const NET_IRQ_CHANNEL_ID: usize = 1;
 
 const CLIENT_CHANNEL: Channel = Channel::new(NET_CLIENT_CHANNEL_ID);
 const NET_IRQ_CHANNEL: Channel = Channel::new(NET_IRQ_CHANNEL_ID);
 
+/// Most frames moved per event-loop turn, in each direction. A receive
+/// flood gets this many frames before the client's TX ring has a turn.
+const RX_BUDGET: u32 = 32;
+const TX_BUDGET: u32 = 32;
+
+/// Most event-loop turns per notification; what is left carries over to
+/// the next one
+const MAX_TURNS: u32 = 8;
+
 /// Network PD handler
 struct NetworkPdHandler {
     /// Active network interface (driver abstraction)
     netif: NetworkInterface,
     /// Shared memory with the client PD
     shared: *mut NetSharedMemory,
+    boot_log: BootLog,
+    /// Budgeted dispatch of the IRQ and client channels
+    events: EventLoop<NetworkPdHandler, PdError, 2>,
 }
 
 impl NetworkPdHandler {
-    /// Drain the TX ring: transmit every valid entry the client queued.
+    /// Transmit up to `budget` valid entries the client queued. Returns
+    /// `Work::Deferred` if the budget ran out first.
     ///
     /// # Safety
     /// `self.shared` must point to the Microkit-mapped shared memory region.
-    unsafe fn process_tx_ring(&mut self) {
+    unsafe fn process_tx_ring(&mut self, budget: u32) -> Work {
         let shared = &mut *self.shared;
 
-        loop {
+        for _ in 0..budget {
             // The client PD writes tx_write_idx; read it volatilely each iteration
             let write = core::ptr::read_volatile(&shared.tx_write_idx);
             let read = shared.tx_read_idx;
             let next_slot = proof::slot_for(read);
             let flags = core::ptr::read_volatile(&shared.tx_ring[next_slot].flags);
 
-            // Empty ring ends the drain. An unpublished entry inside the
+            // Empty ring ends the turn. An unpublished entry inside the
             // occupied window is a protocol violation by the client; stop
             // rather than touch an entry we do not own.
             let permit = match proof::consumer_permit(write, read, flags) {
                 Ok(permit) => permit,
-                Err(_) => break,
+                Err(_) => return Work::Done,
             };
 
             let entry = &mut shared.tx_ring[permit.slot()];
@@ -134,18 +160,22 @@ impl NetworkPdHandler {
             core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
             core::ptr::write_volatile(&mut shared.tx_read_idx, read.wrapping_add(1));
         }
+
+        // The client may have queued more; the next turn checks
+        Work::Deferred
     }
 
-    /// Pull received packets from the driver into the RX ring and
-    /// notify the client if anything arrived.
+    /// Pull up to `budget` received packets from the driver into the RX
+    /// ring. Returns whether anything arrived, so the caller can notify
+    /// the client, and `Work::Deferred` if the budget ran out first.
     ///
     /// # Safety
     /// `self.shared` must point to the Microkit-mapped shared memory region.
-    unsafe fn process_rx(&mut self) {
+    unsafe fn process_rx(&mut self, budget: u32) -> (bool, Work) {
         let shared = &mut *self.shared;
         let mut received = false;
 
-        loop {
+        for _ in 0..budget {
             let write = shared.rx_write_idx;
             // The client PD writes rx_read_idx; read it volatilely. The
             // permit refuses a full ring and an entry the client has not
@@ -155,12 +185,12 @@ impl NetworkPdHandler {
             let flags = core::ptr::read_volatile(&shared.rx_ring[next_slot].flags);
             let permit = match proof::producer_permit(write, rx_read, flags) {
                 Ok(permit) => permit,
-                Err(_) => break,
+                Err(_) => return (received, Work::Done),
             };
             let entry = &mut shared.rx_ring[permit.slot()];
 
             match self.netif.receive(&mut entry.data) {
-                Ok(0) | Err(_) => break,
+                Ok(0) | Err(_) => return (received, Work::Done),
                 Ok(len) => {
                     core::ptr::write_volatile(&mut entry.length, len as u16);
                     // Publish the entry contents before VALID, and VALID
@@ -174,25 +204,80 @@ impl NetworkPdHandler {
             }
         }
 
-        if received {
-            CLIENT_CHANNEL.notify();
-        }
+        // The driver may hold more frames; the next turn checks
+        (received, Work::Deferred)
     }
 
-    /// Publish interface state (MAC, link) into shared memory for clients.
+    /// Publish interface state (MAC, link, counters) into shared memory
+    /// for clients. Returns whether the link state changed.
     ///
     /// # Safety
     /// `self.shared` must point to the Microkit-mapped shared memory region.
-    unsafe fn publish_state(&mut self) {
+    unsafe fn publish_state(&mut self) -> bool {
         let shared = &mut *self.shared;
 
         if let Ok(mac) = self.netif.mac_address() {
             shared.mac_address = mac.0;
         }
-        shared.link_up = match self.netif.link_status() {
-            Ok(status) if status.up => 1,
-            _ => 0,
+        let link: LinkStatusResponse = self.netif.link_status().unwrap_or(LinkStatus::down()).into();
+        shared.link_up = link.up;
+
+        let stats = self.netif.stats().map(StatsResponse::from).unwrap_or_default();
+        let changed = shared.status.publish(link, &stats);
+        if changed {
+            self.log_link(link);
+        }
+        changed
+    }
+
+    /// IRQ channel turn: move received frames into the RX ring and
+    /// refresh the link state
+    fn serve_irq(&mut self, budget: u32) -> Result<Work, PdError> {
+        self.netif.handle_irq();
+        // Safety: shared region is mapped by the system description
+        let (notify, work) = unsafe {
+            let (received, work) = self.process_rx(budget);
+            let link_changed = self.publish_state();
+            (received || link_changed, work)
         };
+        // One notification covers new frames and link transitions;
+        // clients check `status.link_changes` to tell them apart.
+        if notify {
+            CLIENT_CHANNEL.notify();
+        }
+        // A deferred turn acks again; that only unmasks the IRQ line, so
+        // frames arriving meanwhile still notify this PD
+        NET_IRQ_CHANNEL.irq_ack().map_err(|_| {
+            PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking network IRQ")
+        })?;
+        Ok(work)
+    }
+
+    /// Client channel turn: transmit what the client queued
+    fn serve_client(&mut self, budget: u32) -> Result<Work, PdError> {
+        // Safety: shared region is mapped by the system description
+        let (work, link_changed) = unsafe {
+            let work = self.process_tx_ring(budget);
+            // Refresh the TX counters
+            (work, self.publish_state())
+        };
+        if link_changed {
+            CLIENT_CHANNEL.notify();
+        }
+        Ok(work)
+    }
+
+    /// Log a link transition
+    fn log_link(&mut self, link: LinkStatusResponse) {
+        if link.is_up() {
+            let mbps = link_speed::mbps(link.speed);
+            let duplex = if link.full_duplex != 0 { "full" } else { "half" };
+            debug_println!("Network PD: link up, {} Mbps {} duplex", mbps, duplex);
+            self.boot_log.log(Level::Info, format_args!("link up, {} Mbps {} duplex", mbps, duplex));
+        } else {
+            debug_println!("Network PD: link down");
+            self.boot_log.push(Level::Error, "link down");
+        }
     }
 }
 
@@ -228,15 +313,32 @@ fn init() -> NetworkPdHandler {
         gpio_base: GPIO_VADDR,
     };
 
+    // Safety: BOOT_LOG_VADDR is mapped by the system description, and
+    // this PD is its only writer
+    let mut boot_log = unsafe { BootLog::attach(BOOT_LOG_VADDR as *mut u8) };
+
     let mut netif = NetworkInterface::new();
     match netif.init(&config) {
-        Ok(()) => debug_println!("Network PD: interface initialized"),
-        Err(e) => debug_println!("Network PD: no interface available ({:?})", e),
+        Ok(()) => {
+            debug_println!("Network PD: interface initialized");
+            boot_log.push(Level::Info, "interface initialized");
+        }
+        Err(e) => {
+            debug_println!("Network PD: no interface available ({:?})", e);
+            boot_log.log(Level::Error, format_args!("no interface available ({:?})", e));
+        }
     }
 
+    let mut events = EventLoop::new(MAX_TURNS);
+    // Two distinct channel ids below 63 with non-zero budgets: cannot fail
+    let _ = events.register(NET_IRQ_CHANNEL_ID, RX_BUDGET, NetworkPdHandler::serve_irq);
+    let _ = events.register(NET_CLIENT_CHANNEL_ID, TX_BUDGET, NetworkPdHandler::serve_client);
+
     let mut handler = NetworkPdHandler {
         netif,
         shared: NET_RING_VADDR as *mut NetSharedMemory,
+        boot_log,
+        events,
     };
 
     // Safety: NET_RING_VADDR is mapped by the system description
@@ -248,36 +350,12 @@ fn init() -> NetworkPdHandler {
     handler
 }
 
-#[derive(Debug)]
-pub struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Network PD handler error")
-    }
-}
-
 impl Handler for NetworkPdHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
-        if channels.contains(NET_IRQ_CHANNEL) {
-            self.netif.handle_irq();
-            // Safety: shared region is mapped by the system description
-            unsafe {
-                self.process_rx();
-                self.publish_state();
-            }
-            NET_IRQ_CHANNEL.irq_ack().map_err(|_| HandlerError)?;
-        }
-
-        if channels.contains(CLIENT_CHANNEL) {
-            // Safety: shared region is mapped by the system description
-            unsafe {
-                self.process_tx_ring();
-            }
-        }
-
-        Ok(())
+        // A receive flood gets RX_BUDGET frames a turn, then queues behind
+        // the client's TX ring
+        EventLoop::run(self, |pd| &mut pd.events, channels.iter().map(|channel| channel.index()))
     }
 }
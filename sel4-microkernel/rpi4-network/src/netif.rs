@@ -4,6 +4,7 @@
 //! abstracting over the underlying driver (Ethernet, virtio-net, or WiFi).
 
 use crate::drivers::{DriverStats, LinkStatus, MacAddress};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 
 #[cfg(feature = "net-ethernet")]
 use crate::drivers::ethernet::EthernetDriver;
@@ -31,6 +32,18 @@ pub enum NetifError {
     LinkDown,
 }
 
+impl From<NetifError> for PdError {
+    fn from(e: NetifError) -> Self {
+        let kind = match e {
+            NetifError::NoInterface => ErrorKind::NotFound,
+            NetifError::NotReady | NetifError::LinkDown => ErrorKind::NotReady,
+            NetifError::TransmitFailed | NetifError::ReceiveFailed => ErrorKind::Failed,
+            NetifError::BufferTooSmall => ErrorKind::Capacity,
+        };
+        PdError::new(ErrorDomain::Network, kind).with_detail(e as u16)
+    }
+}
+
 /// Mapped MMIO base addresses for the enabled drivers
 ///
 /// Register bases are *virtual* addresses as mapped by the Microkit
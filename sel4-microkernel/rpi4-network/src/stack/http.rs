@@ -0,0 +1,264 @@
+//! Control HTTP server front end over TCP.
+//!
+//! Listens on [`CONTROL_PORT`] with one TCP socket per connection
+//! [`Limits`] allows, and puts every connection and request to it before
+//! handling them: a connection turned away is answered with the refusal's
+//! status as soon as it is established, and so is a request that is over
+//! [`MAX_REQUEST_LEN`] or over its address's rate. Each connection carries
+//! one request and is closed after the answer.
+//!
+//! Known gaps: there are no pages yet, so an admitted request is answered
+//! `404 Not Found`; a body is not read (the request ends at the blank line
+//! after the headers); and polls come with network interrupts only, so a
+//! client that stops sending keeps its connection until the next packet
+//! after [`READ_TIMEOUT_MS`].
+
+use core::fmt::Write;
+
+use rpi4_network_protocol::http_limits::{
+    ipv4_client, ClientAddr, Limits, Refusal, MAX_CONNECTIONS, MAX_REQUEST_LEN,
+};
+use rpi4_network_protocol::mdns::CONTROL_PORT;
+use smoltcp::iface::{SocketHandle, SocketSet};
+use smoltcp::socket::tcp;
+use smoltcp::time::{Duration, Instant};
+use smoltcp::wire::{IpAddress, IpEndpoint};
+
+use super::StackEvent;
+
+/// Transmit buffer of each connection: the longest answer, with room over
+pub const RESPONSE_CAPACITY: usize = 256;
+/// Time a connection has to finish its request line and headers
+pub const READ_TIMEOUT_MS: u64 = 10_000;
+/// Time an unacknowledged answer is retried before the connection drops
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request being read
+struct Request {
+    peer: ClientAddr,
+    client: IpEndpoint,
+    /// Bytes read so far
+    len: usize,
+    /// Bytes of the `\r\n\r\n` ending the headers matched so far
+    matched: u8,
+    since_ms: u64,
+}
+
+enum Phase {
+    Listening,
+    Reading(Request),
+    /// Answered or abandoned; `Some` holds the address to release from
+    /// [`Limits`] once the socket closes
+    Closing(Option<ClientAddr>),
+}
+
+struct Connection {
+    handle: SocketHandle,
+    phase: Phase,
+}
+
+pub(super) struct Http {
+    connections: [Connection; MAX_CONNECTIONS],
+    limits: Limits,
+}
+
+impl Http {
+    /// Listen on `MAX_CONNECTIONS` sockets; `rx` and `tx` are split evenly
+    /// between them
+    pub(super) fn new<'a>(sockets: &mut SocketSet<'a>, rx: &'a mut [u8], tx: &'a mut [u8]) -> Self {
+        let (rx_len, tx_len) = (rx.len() / MAX_CONNECTIONS, tx.len() / MAX_CONNECTIONS);
+        let mut rx = rx.chunks_mut(rx_len);
+        let mut tx = tx.chunks_mut(tx_len);
+        let connections = core::array::from_fn(|_| {
+            let mut socket = tcp::Socket::new(
+                tcp::SocketBuffer::new(rx.next().expect("one receive buffer per connection")),
+                tcp::SocketBuffer::new(tx.next().expect("one transmit buffer per connection")),
+            );
+            socket.set_timeout(Some(SEND_TIMEOUT));
+            socket
+                .listen(CONTROL_PORT)
+                .expect("fixed control port is valid");
+            Connection {
+                handle: sockets.add(socket),
+                phase: Phase::Listening,
+            }
+        });
+        Self {
+            connections,
+            limits: Limits::new(),
+        }
+    }
+
+    /// Admit new connections, read requests and queue their answers; the
+    /// caller polls the interface afterwards to send them
+    pub(super) fn poll(&mut self, sockets: &mut SocketSet, now: Instant) -> Option<StackEvent> {
+        let now_ms = now.total_millis() as u64;
+        let mut event = None;
+        for connection in &mut self.connections {
+            let socket = sockets.get_mut::<tcp::Socket>(connection.handle);
+            if let Some(answered) = connection.step(socket, &mut self.limits, now_ms) {
+                event = event.or(Some(answered));
+            }
+            connection.reap(socket, &mut self.limits);
+        }
+        event
+    }
+}
+
+impl Connection {
+    fn step(
+        &mut self,
+        socket: &mut tcp::Socket,
+        limits: &mut Limits,
+        now_ms: u64,
+    ) -> Option<StackEvent> {
+        match &mut self.phase {
+            Phase::Listening => {
+                // Answers can only be queued once the handshake is done
+                if socket.is_listening() || !socket.may_send() {
+                    return None;
+                }
+                let client = socket.remote_endpoint()?;
+                let peer = client_key(client.addr);
+                match limits.open(&peer, now_ms) {
+                    Ok(()) => {
+                        self.phase = Phase::Reading(Request {
+                            peer,
+                            client,
+                            len: 0,
+                            matched: 0,
+                            since_ms: now_ms,
+                        });
+                        None
+                    }
+                    Err(refusal) => {
+                        self.phase = Phase::Closing(None);
+                        Some(answer(socket, client, Err(refusal)))
+                    }
+                }
+            }
+            Phase::Reading(request) => {
+                let complete = request.read(socket);
+                if complete || request.len > MAX_REQUEST_LEN {
+                    let verdict = limits.request(&request.peer, request.len, now_ms);
+                    let answered = answer(socket, request.client, verdict);
+                    self.phase = Phase::Closing(Some(request.peer));
+                    return Some(answered);
+                }
+                if now_ms.saturating_sub(request.since_ms) > READ_TIMEOUT_MS {
+                    socket.abort();
+                    self.phase = Phase::Closing(Some(request.peer));
+                } else if !socket.may_recv() {
+                    // The client closed before finishing its headers
+                    socket.close();
+                    self.phase = Phase::Closing(Some(request.peer));
+                }
+                None
+            }
+            Phase::Closing(_) => None,
+        }
+    }
+
+    /// Release a closed connection's address and listen again; also
+    /// recovers a socket reset before it was admitted
+    fn reap(&mut self, socket: &mut tcp::Socket, limits: &mut Limits) {
+        if socket.is_open() {
+            return;
+        }
+        match &self.phase {
+            Phase::Closing(Some(peer)) => limits.close(peer),
+            Phase::Reading(request) => limits.close(&request.peer),
+            Phase::Listening | Phase::Closing(None) => {}
+        }
+        self.phase = Phase::Listening;
+        socket
+            .listen(CONTROL_PORT)
+            .expect("closed sockets can listen");
+    }
+}
+
+impl Request {
+    /// Read what has arrived, stopping at the end of the headers or just
+    /// past [`MAX_REQUEST_LEN`]; true once the headers are complete
+    fn read(&mut self, socket: &mut tcp::Socket) -> bool {
+        let mut chunk = [0u8; 256];
+        while self.len <= MAX_REQUEST_LEN {
+            let Ok(n) = socket.recv_slice(&mut chunk) else {
+                return false;
+            };
+            if n == 0 {
+                return false;
+            }
+            for &byte in &chunk[..n] {
+                self.len += 1;
+                let expected = match self.matched {
+                    0 | 2 => b'\r',
+                    _ => b'\n',
+                };
+                self.matched = if byte == expected {
+                    self.matched + 1
+                } else if byte == b'\r' {
+                    1
+                } else {
+                    0
+                };
+                if self.matched == 4 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// The [`Limits`] key for an address
+fn client_key(address: IpAddress) -> ClientAddr {
+    match address {
+        IpAddress::Ipv4(address) => ipv4_client(address.octets()),
+        #[cfg(feature = "net-ipv6")]
+        IpAddress::Ipv6(address) => address.octets(),
+    }
+}
+
+/// Queue the answer to `verdict` and close the connection after it
+fn answer(
+    socket: &mut tcp::Socket,
+    client: IpEndpoint,
+    verdict: Result<(), Refusal>,
+) -> StackEvent {
+    let (status, retry_after_s) = match verdict {
+        Ok(()) => (404, None),
+        Err(refusal) => (refusal.status(), refusal.retry_after_s()),
+    };
+    let mut out = Reply(socket);
+    // An answer cut short still ends with the connection closing
+    let _ = write!(out, "HTTP/1.1 {} {}\r\n", status, reason(status));
+    if let Some(seconds) = retry_after_s {
+        let _ = write!(out, "Retry-After: {}\r\n", seconds);
+    }
+    let _ = out.write_str("Content-Length: 0\r\nConnection: close\r\n\r\n");
+    socket.close();
+    StackEvent::HttpAnswered(client, status)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        404 => "Not Found",
+        413 => "Content Too Large",
+        429 => "Too Many Requests",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+/// Writes an answer into a socket's transmit buffer
+struct Reply<'s, 'a>(&'s mut tcp::Socket<'a>);
+
+impl Write for Reply<'_, '_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        match self.0.send_slice(s.as_bytes()) {
+            Ok(sent) if sent == s.len() => Ok(()),
+            _ => Err(core::fmt::Error),
+        }
+    }
+}
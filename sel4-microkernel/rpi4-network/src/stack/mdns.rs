@@ -0,0 +1,162 @@
+//! mDNS responder over a UDP socket.
+//!
+//! Binds port 5353, joins the mDNS group, and answers the queries
+//! `rpi4_network_protocol::mdns` finds ours: to the group for an mDNS
+//! querier, straight back for a plain resolver asking from another port.
+//! Each time the interface gains an address, every record is announced
+//! [`ANNOUNCEMENTS`] times, a second apart, so caches pick up the new
+//! address without asking.
+//!
+//! Known gaps: polls come with network interrupts only, so the second
+//! announcement goes out with the first packet after its time rather
+//! than at it; and nothing says goodbye when an address is lost, so
+//! caches keep the old one for up to [`HOST_TTL`](mdns::HOST_TTL)
+//! seconds.
+
+use rpi4_network_protocol::mdns::{
+    self, Addresses, Responder, ANNOUNCEMENTS, ANNOUNCE_INTERVAL_MS, MAX_RESPONSE_LEN, MDNS_PORT,
+};
+use smoltcp::iface::{Interface, SocketHandle, SocketSet};
+use smoltcp::socket::udp;
+use smoltcp::time::Instant;
+use smoltcp::wire::{IpAddress, IpEndpoint, Ipv4Address};
+#[cfg(feature = "net-ipv6")]
+use smoltcp::wire::{IpCidr, Ipv6Address};
+
+use super::StackEvent;
+
+struct Announcing {
+    left: u8,
+    next_ms: u64,
+}
+
+pub(super) struct Mdns<'a> {
+    handle: SocketHandle,
+    responder: Responder<'a>,
+    announcing: Option<Announcing>,
+}
+
+impl<'a> Mdns<'a> {
+    pub(super) fn new(
+        iface: &mut Interface,
+        sockets: &mut SocketSet<'a>,
+        responder: Responder<'a>,
+        rx: udp::PacketBuffer<'a>,
+        tx: udp::PacketBuffer<'a>,
+    ) -> Self {
+        let mut socket = udp::Socket::new(rx, tx);
+        socket.bind(MDNS_PORT).expect("fixed mDNS port is valid");
+        socket.set_hop_limit(Some(mdns::MDNS_HOP_LIMIT));
+        let _ = iface.join_multicast_group(ipv4_group());
+        #[cfg(feature = "net-ipv6")]
+        let _ = iface.join_multicast_group(ipv6_group());
+        Self {
+            handle: sockets.add(socket),
+            responder,
+            announcing: None,
+        }
+    }
+
+    /// The interface gained an address: announce it, starting now
+    pub(super) fn announce(&mut self) {
+        self.announcing = Some(Announcing { left: ANNOUNCEMENTS, next_ms: 0 });
+    }
+
+    /// Answer queued queries and send any announcement due; the caller
+    /// polls the interface afterwards to send them
+    pub(super) fn poll(
+        &mut self,
+        iface: &Interface,
+        sockets: &mut SocketSet,
+        now: Instant,
+    ) -> Option<StackEvent> {
+        let socket = sockets.get_mut::<udp::Socket>(self.handle);
+        let addrs = addresses(iface);
+        let now_ms = now.total_millis() as u64;
+        // Queries too long for the buffer are dropped by the socket
+        let mut query = [0u8; MAX_RESPONSE_LEN];
+        let mut out = [0u8; MAX_RESPONSE_LEN];
+        let mut event = None;
+
+        while let Ok((len, meta)) = socket.recv_slice(&mut query) {
+            let Ok(reply) = self.responder.answer(&query[..len], &addrs) else {
+                continue;
+            };
+            if reply.is_empty() {
+                continue;
+            }
+            let legacy = meta.endpoint.port != MDNS_PORT;
+            let Ok(len) = self.responder.write_reply(&mut out, &reply, &addrs, legacy) else {
+                continue;
+            };
+            let to = if legacy { meta.endpoint } else { group_of(meta.endpoint.addr) };
+            if socket.send_slice(&out[..len], to).is_ok() {
+                event = event.or(Some(StackEvent::MdnsAnswered(meta.endpoint)));
+            }
+        }
+
+        if let Some(announcing) = &mut self.announcing {
+            if now_ms >= announcing.next_ms {
+                let Ok(len) = self.responder.write_announcement(&mut out, &addrs) else {
+                    self.announcing = None;
+                    return event;
+                };
+                let v4 = group_of(IpAddress::Ipv4(ipv4_group()));
+                let mut sent = socket.send_slice(&out[..len], v4).is_ok();
+                #[cfg(feature = "net-ipv6")]
+                if addrs.ipv6.is_some() {
+                    let v6 = group_of(IpAddress::Ipv6(ipv6_group()));
+                    sent |= socket.send_slice(&out[..len], v6).is_ok();
+                }
+                if sent {
+                    announcing.left -= 1;
+                    announcing.next_ms = now_ms.saturating_add(ANNOUNCE_INTERVAL_MS);
+                    event = Some(StackEvent::MdnsAnnounced(ANNOUNCEMENTS - announcing.left));
+                    if announcing.left == 0 {
+                        self.announcing = None;
+                    }
+                }
+            }
+        }
+        event
+    }
+}
+
+/// The addresses the host name resolves to: the IPv4 address, and the
+/// global IPv6 one if there is one, else the link-local
+#[cfg_attr(not(feature = "net-ipv6"), allow(unused_mut))]
+fn addresses(iface: &Interface) -> Addresses {
+    let mut addrs = Addresses {
+        ipv4: iface.ipv4_addr().map(|address| address.octets()),
+        ipv6: None,
+    };
+    #[cfg(feature = "net-ipv6")]
+    for cidr in iface.ip_addrs() {
+        if let IpCidr::Ipv6(cidr) = cidr {
+            let address = cidr.address().octets();
+            if addrs.ipv6.is_none() || !rpi4_network_protocol::ipv6::is_link_local(&address) {
+                addrs.ipv6 = Some(address);
+            }
+        }
+    }
+    addrs
+}
+
+fn ipv4_group() -> Ipv4Address {
+    Ipv4Address::from(mdns::MDNS_GROUP_V4)
+}
+
+#[cfg(feature = "net-ipv6")]
+fn ipv6_group() -> Ipv6Address {
+    Ipv6Address::from(mdns::MDNS_GROUP_V6)
+}
+
+/// The mDNS group of the family of `address`, on the mDNS port
+fn group_of(address: IpAddress) -> IpEndpoint {
+    let group = match address {
+        IpAddress::Ipv4(_) => IpAddress::Ipv4(ipv4_group()),
+        #[cfg(feature = "net-ipv6")]
+        IpAddress::Ipv6(_) => IpAddress::Ipv6(ipv6_group()),
+    };
+    IpEndpoint::new(group, MDNS_PORT)
+}
@@ -1,16 +1,35 @@
 //! Minimal no_std IPv4 stack for the QEMU network milestone.
 
 mod device;
+#[cfg(feature = "net-http")]
+mod http;
+#[cfg(feature = "net-mdns")]
+mod mdns;
+#[cfg(feature = "net-ipv6")]
+mod slaac;
 
 pub use device::{DeviceResources, DriverDevice, FrameIo, FRAME_CAPACITY};
+#[cfg(feature = "net-http")]
+pub use http::RESPONSE_CAPACITY as HTTP_RESPONSE_CAPACITY;
 
+use rpi4_network_protocol::ipconfig::{IpMode, StaticIpv4};
+#[cfg(feature = "net-mdns")]
+use rpi4_network_protocol::mdns::Responder;
 use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet, SocketStorage};
 use smoltcp::phy::Device;
 use smoltcp::socket::{dhcpv4, icmp};
+#[cfg(feature = "net-ipv6")]
+use smoltcp::socket::raw;
+#[cfg(feature = "net-mdns")]
+use smoltcp::socket::udp;
 use smoltcp::time::Instant;
 use smoltcp::wire::{
     EthernetAddress, Icmpv4Packet, Icmpv4Repr, IpAddress, IpCidr, Ipv4Address, Ipv4Cidr,
 };
+#[cfg(any(feature = "net-mdns", feature = "net-http"))]
+use smoltcp::wire::IpEndpoint;
+#[cfg(feature = "net-ipv6")]
+use smoltcp::wire::{Ipv6Address, Ipv6Cidr};
 
 const PING_IDENT: u16 = 0x5341;
 const PING_SEQUENCE: u16 = 1;
@@ -24,23 +43,94 @@ pub struct StackResources<'a> {
     pub icmp_rx_payload: &'a mut [u8],
     pub icmp_tx_metadata: &'a mut [icmp::PacketMetadata],
     pub icmp_tx_payload: &'a mut [u8],
+    /// Neighbour discovery for SLAAC
+    #[cfg(feature = "net-ipv6")]
+    pub raw_rx_metadata: &'a mut [raw::PacketMetadata],
+    #[cfg(feature = "net-ipv6")]
+    pub raw_rx_payload: &'a mut [u8],
+    #[cfg(feature = "net-ipv6")]
+    pub raw_tx_metadata: &'a mut [raw::PacketMetadata],
+    #[cfg(feature = "net-ipv6")]
+    pub raw_tx_payload: &'a mut [u8],
+    /// The names and services answered for over mDNS
+    #[cfg(feature = "net-mdns")]
+    pub mdns_responder: Responder<'a>,
+    #[cfg(feature = "net-mdns")]
+    pub udp_rx_metadata: &'a mut [udp::PacketMetadata],
+    #[cfg(feature = "net-mdns")]
+    pub udp_rx_payload: &'a mut [u8],
+    #[cfg(feature = "net-mdns")]
+    pub udp_tx_metadata: &'a mut [udp::PacketMetadata],
+    #[cfg(feature = "net-mdns")]
+    pub udp_tx_payload: &'a mut [u8],
+    /// Control server receive buffers, split evenly between its
+    /// `MAX_CONNECTIONS` sockets; each needs `MAX_REQUEST_LEN`
+    #[cfg(feature = "net-http")]
+    pub tcp_rx_payload: &'a mut [u8],
+    /// Control server transmit buffers, [`HTTP_RESPONSE_CAPACITY`] each
+    #[cfg(feature = "net-http")]
+    pub tcp_tx_payload: &'a mut [u8],
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StackEvent {
+    /// A static configuration was applied; reported by the first poll
+    StaticConfigured(Ipv4Cidr),
     DhcpConfigured(Ipv4Cidr),
     DhcpDeconfigured,
     PingSent,
     PingReply,
     TransmitError,
+    /// The link-local address passed duplicate address detection
+    #[cfg(feature = "net-ipv6")]
+    Ipv6LinkLocal(Ipv6Address),
+    /// An address formed from a router-advertised prefix passed DAD
+    #[cfg(feature = "net-ipv6")]
+    Ipv6Configured(Ipv6Cidr),
+    /// Another node has this address; IPv6 configuration stops
+    #[cfg(feature = "net-ipv6")]
+    Ipv6Duplicate(Ipv6Address),
+    /// Announcement `n` of this host's records went out
+    #[cfg(feature = "net-mdns")]
+    MdnsAnnounced(u8),
+    /// A query from this endpoint was answered
+    #[cfg(feature = "net-mdns")]
+    MdnsAnswered(IpEndpoint),
+    /// A connection or request from this endpoint to the control server
+    /// was answered with this status
+    #[cfg(feature = "net-http")]
+    HttpAnswered(IpEndpoint, u16),
+}
+
+#[cfg(feature = "net-mdns")]
+impl StackEvent {
+    /// The interface gained an address
+    fn is_configured(&self) -> bool {
+        match self {
+            StackEvent::StaticConfigured(_) | StackEvent::DhcpConfigured(_) => true,
+            #[cfg(feature = "net-ipv6")]
+            StackEvent::Ipv6LinkLocal(_) | StackEvent::Ipv6Configured(_) => true,
+            _ => false,
+        }
+    }
 }
 
 pub struct NetworkStack<'a, D: FrameIo> {
     iface: Interface,
     device: DriverDevice<'a, D>,
     sockets: SocketSet<'a>,
-    dhcp_handle: SocketHandle,
+    /// `None` with a static configuration
+    dhcp_handle: Option<SocketHandle>,
     icmp_handle: SocketHandle,
+    /// Static address not yet reported by [`poll`](Self::poll)
+    static_pending: Option<Ipv4Cidr>,
+    dns_server: Option<Ipv4Address>,
+    #[cfg(feature = "net-ipv6")]
+    slaac: slaac::Slaac,
+    #[cfg(feature = "net-mdns")]
+    mdns: mdns::Mdns<'a>,
+    #[cfg(feature = "net-http")]
+    http: http::Http,
     configured: bool,
     ping_sent: bool,
     ping_reply: bool,
@@ -50,6 +140,7 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
     pub fn new(
         io: D,
         mac: [u8; 6],
+        mode: IpMode,
         resources: StackResources<'a>,
         now: Instant,
     ) -> Self {
@@ -58,10 +149,16 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
         config.random_seed = u64::from_le_bytes([
             mac[0], mac[1], mac[2], mac[3], mac[4], mac[5], 0x53, 0x41,
         ]);
-        let iface = Interface::new(config, &mut device, now);
+        let mut iface = Interface::new(config, &mut device, now);
 
         let mut sockets = SocketSet::new(resources.sockets);
-        let dhcp_handle = sockets.add(dhcpv4::Socket::new());
+        let (dhcp_handle, static_pending, dns_server) = match mode {
+            IpMode::Dhcp => (Some(sockets.add(dhcpv4::Socket::new())), None, None),
+            IpMode::Static(config) => {
+                let address = apply_static(&mut iface, &config);
+                (None, Some(address), config.dns().map(Ipv4Address::from))
+            }
+        };
         let icmp_rx = icmp::PacketBuffer::new(
             resources.icmp_rx_metadata,
             resources.icmp_rx_payload,
@@ -75,6 +172,27 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
             .bind(icmp::Endpoint::Ident(PING_IDENT))
             .expect("fixed ICMP endpoint is valid");
         let icmp_handle = sockets.add(icmp_socket);
+        #[cfg(feature = "net-ipv6")]
+        let slaac = slaac::Slaac::new(
+            &mut sockets,
+            mac,
+            raw::PacketBuffer::new(resources.raw_rx_metadata, resources.raw_rx_payload),
+            raw::PacketBuffer::new(resources.raw_tx_metadata, resources.raw_tx_payload),
+        );
+        #[cfg(feature = "net-mdns")]
+        let mdns = mdns::Mdns::new(
+            &mut iface,
+            &mut sockets,
+            resources.mdns_responder,
+            udp::PacketBuffer::new(resources.udp_rx_metadata, resources.udp_rx_payload),
+            udp::PacketBuffer::new(resources.udp_tx_metadata, resources.udp_tx_payload),
+        );
+        #[cfg(feature = "net-http")]
+        let http = http::Http::new(
+            &mut sockets,
+            resources.tcp_rx_payload,
+            resources.tcp_tx_payload,
+        );
 
         Self {
             iface,
@@ -82,7 +200,15 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
             sockets,
             dhcp_handle,
             icmp_handle,
-            configured: false,
+            static_pending,
+            dns_server,
+            #[cfg(feature = "net-ipv6")]
+            slaac,
+            #[cfg(feature = "net-mdns")]
+            mdns,
+            #[cfg(feature = "net-http")]
+            http,
+            configured: static_pending.is_some(),
             ping_sent: false,
             ping_reply: false,
         }
@@ -92,10 +218,37 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
         self.device.io_mut()
     }
 
+    /// DNS server from the static configuration or the DHCP lease
+    pub fn dns_server(&self) -> Option<Ipv4Address> {
+        self.dns_server
+    }
+
     pub fn poll(&mut self, now: Instant) -> Option<StackEvent> {
         let _ = self.iface.poll(now, &mut self.device, &mut self.sockets);
 
-        let mut event = self.poll_dhcp();
+        let mut event = self
+            .poll_dhcp()
+            .or(self.static_pending.take().map(StackEvent::StaticConfigured));
+        #[cfg(feature = "net-ipv6")]
+        if event.is_none() {
+            event = self.slaac.poll(&mut self.iface, &mut self.sockets, now);
+            let _ = self.iface.poll(now, &mut self.device, &mut self.sockets);
+        }
+        #[cfg(feature = "net-mdns")]
+        {
+            if event.as_ref().is_some_and(StackEvent::is_configured) {
+                self.mdns.announce();
+            }
+            if event.is_none() {
+                event = self.mdns.poll(&self.iface, &mut self.sockets, now);
+                let _ = self.iface.poll(now, &mut self.device, &mut self.sockets);
+            }
+        }
+        #[cfg(feature = "net-http")]
+        if event.is_none() {
+            event = self.http.poll(&mut self.sockets, now);
+            let _ = self.iface.poll(now, &mut self.device, &mut self.sockets);
+        }
         if self.configured && !self.ping_sent && self.queue_ping() {
             self.ping_sent = true;
             event = event.or(Some(StackEvent::PingSent));
@@ -114,16 +267,13 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
     }
 
     fn poll_dhcp(&mut self) -> Option<StackEvent> {
-        match self
-            .sockets
-            .get_mut::<dhcpv4::Socket>(self.dhcp_handle)
-            .poll()
-        {
+        let handle = self.dhcp_handle?;
+        match self.sockets.get_mut::<dhcpv4::Socket>(handle).poll() {
             None => None,
             Some(dhcpv4::Event::Configured(config)) => {
                 let address = config.address;
                 self.iface.update_ip_addrs(|addresses| {
-                    addresses.clear();
+                    addresses.retain(|cidr| !matches!(cidr, IpCidr::Ipv4(_)));
                     addresses
                         .push(IpCidr::Ipv4(address))
                         .expect("one IPv4 address fits fixed storage");
@@ -133,14 +283,18 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
                 } else {
                     self.iface.routes_mut().remove_default_ipv4_route();
                 }
+                self.dns_server = config.dns_servers.first().copied();
                 self.configured = true;
                 self.ping_sent = false;
                 self.ping_reply = false;
                 Some(StackEvent::DhcpConfigured(address))
             }
             Some(dhcpv4::Event::Deconfigured) => {
-                self.iface.update_ip_addrs(|addresses| addresses.clear());
+                self.iface.update_ip_addrs(|addresses| {
+                    addresses.retain(|cidr| !matches!(cidr, IpCidr::Ipv4(_)))
+                });
                 self.iface.routes_mut().remove_default_ipv4_route();
+                self.dns_server = None;
                 self.configured = false;
                 self.ping_sent = false;
                 self.ping_reply = false;
@@ -196,3 +350,20 @@ impl<'a, D: FrameIo> NetworkStack<'a, D> {
         false
     }
 }
+
+/// Give the interface a static configuration, already checked by
+/// [`StaticIpv4::new`]; returns the address
+fn apply_static(iface: &mut Interface, config: &StaticIpv4) -> Ipv4Cidr {
+    let address = Ipv4Cidr::new(Ipv4Address::from(config.address()), config.prefix_len());
+    iface.update_ip_addrs(|addresses| {
+        addresses
+            .push(IpCidr::Ipv4(address))
+            .expect("one IPv4 address fits fixed storage");
+    });
+    if let Some(gateway) = config.gateway() {
+        let _ = iface
+            .routes_mut()
+            .add_default_ipv4_route(Ipv4Address::from(gateway));
+    }
+    address
+}
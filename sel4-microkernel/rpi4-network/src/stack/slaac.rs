@@ -0,0 +1,156 @@
+//! IPv6 link-local and SLAAC address configuration.
+//!
+//! smoltcp answers neighbour solicitations for the addresses it is given
+//! but does not configure any itself. This drives the steps in
+//! `rpi4_network_protocol::ipv6` over a raw ICMPv6 socket, which sees every
+//! neighbour-discovery packet the interface accepts:
+//!
+//! ```text
+//! LinkLocal (DAD) ──► Soliciting (RS, wait for an RA) ──► Global (DAD) ──► Configured
+//!        └──────────────── duplicate ──────────────────────────┴──────────► Failed
+//! ```
+//!
+//! Known gaps: prefix and router lifetimes are not tracked, so a
+//! configured address is kept until reboot; and the solicited-node group
+//! of a tentative address is only joined once the link-local address is
+//! assigned, so the first DAD hears an owner's defence but not a
+//! simultaneous probe.
+
+use rpi4_network_protocol::ipv6::{self, Dad, DadStep, Ipv6Addr, NdMessage};
+use smoltcp::iface::{Interface, SocketHandle, SocketSet};
+use smoltcp::socket::raw;
+use smoltcp::time::Instant;
+use smoltcp::wire::{IpCidr, IpProtocol, IpVersion, Ipv6Address, Ipv6Cidr};
+
+use super::StackEvent;
+
+/// Router solicitations sent before relying on periodic advertisements
+/// (MAX_RTR_SOLICITATIONS)
+const MAX_SOLICITS: u8 = 3;
+/// Wait between router solicitations (RTR_SOLICITATION_INTERVAL)
+const SOLICIT_INTERVAL_MS: u64 = 4000;
+/// Room for the longer of the two packets sent
+const PACKET_LEN: usize = if ipv6::DAD_PROBE_LEN > ipv6::ROUTER_SOLICIT_LEN {
+    ipv6::DAD_PROBE_LEN
+} else {
+    ipv6::ROUTER_SOLICIT_LEN
+};
+
+enum State {
+    /// Checking the link-local address
+    LinkLocal(Dad),
+    /// Link-local assigned; waiting for an advertisement with a usable prefix
+    Soliciting { sent: u8, next_ms: u64 },
+    /// Checking the global address; `router` is the default route, if the
+    /// advertiser offered to be one
+    Global { dad: Dad, router: Option<Ipv6Addr> },
+    Configured,
+    /// An address was a duplicate; IPv6 stays link-local or unconfigured
+    Failed,
+}
+
+pub(super) struct Slaac {
+    handle: SocketHandle,
+    mac: [u8; 6],
+    state: State,
+}
+
+impl Slaac {
+    pub(super) fn new<'a>(
+        sockets: &mut SocketSet<'a>,
+        mac: [u8; 6],
+        rx: raw::PacketBuffer<'a>,
+        tx: raw::PacketBuffer<'a>,
+    ) -> Self {
+        let socket = raw::Socket::new(Some(IpVersion::Ipv6), Some(IpProtocol::Icmpv6), rx, tx);
+        Self {
+            handle: sockets.add(socket),
+            mac,
+            state: State::LinkLocal(Dad::new(ipv6::link_local_address(&mac))),
+        }
+    }
+
+    /// Take in received neighbour discovery and advance; the caller polls
+    /// the interface afterwards to send anything queued
+    pub(super) fn poll(
+        &mut self,
+        iface: &mut Interface,
+        sockets: &mut SocketSet,
+        now: Instant,
+    ) -> Option<StackEvent> {
+        let socket = sockets.get_mut::<raw::Socket>(self.handle);
+        let now_ms = now.total_millis() as u64;
+
+        while let Ok(packet) = socket.recv() {
+            let Ok(message) = ipv6::parse_nd(packet) else {
+                continue;
+            };
+            match &mut self.state {
+                State::LinkLocal(dad) | State::Global { dad, .. } => dad.observe(&message),
+                State::Soliciting { .. } => {
+                    let NdMessage::RouterAdvert(ra) = message else {
+                        continue;
+                    };
+                    if let Some(prefix) = ra.slaac_prefix() {
+                        let address = ipv6::slaac_address(&prefix.prefix, &self.mac);
+                        let router = (ra.router_lifetime > 0).then_some(ra.router);
+                        self.state = State::Global { dad: Dad::new(address), router };
+                    }
+                }
+                State::Configured | State::Failed => {}
+            }
+        }
+
+        let mut buf = [0u8; PACKET_LEN];
+        match &mut self.state {
+            State::LinkLocal(dad) | State::Global { dad, .. } => {
+                let target = *dad.target();
+                match dad.poll(now_ms) {
+                    DadStep::Probe => {
+                        let len = ipv6::build_dad_probe(&mut buf, &target);
+                        let _ = socket.send_slice(&buf[..len]);
+                        None
+                    }
+                    DadStep::Wait => None,
+                    DadStep::Duplicate => {
+                        self.state = State::Failed;
+                        Some(StackEvent::Ipv6Duplicate(Ipv6Address::from(target)))
+                    }
+                    DadStep::Unique => Some(self.assign(iface, target)),
+                }
+            }
+            State::Soliciting { sent, next_ms } => {
+                if *sent < MAX_SOLICITS && now_ms >= *next_ms {
+                    let link_local = ipv6::link_local_address(&self.mac);
+                    let len = ipv6::build_router_solicit(&mut buf, &link_local, &self.mac);
+                    let _ = socket.send_slice(&buf[..len]);
+                    *sent += 1;
+                    *next_ms = now_ms.saturating_add(SOLICIT_INTERVAL_MS);
+                }
+                None
+            }
+            State::Configured | State::Failed => None,
+        }
+    }
+
+    /// Give the interface an address that passed DAD and move on
+    fn assign(&mut self, iface: &mut Interface, address: Ipv6Addr) -> StackEvent {
+        let cidr = Ipv6Cidr::new(Ipv6Address::from(address), ipv6::SLAAC_PREFIX_LEN);
+        iface.update_ip_addrs(|addresses| {
+            let _ = addresses.push(IpCidr::Ipv6(cidr));
+        });
+        match self.state {
+            State::LinkLocal(_) => {
+                self.state = State::Soliciting { sent: 0, next_ms: 0 };
+                StackEvent::Ipv6LinkLocal(cidr.address())
+            }
+            _ => {
+                if let State::Global { router: Some(router), .. } = self.state {
+                    let _ = iface.routes_mut().add_default_ipv6_route(Ipv6Address::from(router));
+                }
+                self.state = State::Configured;
+                StackEvent::Ipv6Configured(cidr)
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Access-spec violations found by security audit builds.
+//!
+//! With their `audit` feature on, the protocol crates check every raw
+//! pointer access in their shared-memory helpers against the PD's
+//! `*_pd_can_access` spec predicate. An access outside the spec does not
+//! fault (the mapping may well exist, which is exactly the drift being
+//! looked for), it is recorded here. The PD drains the log from its event
+//! loop and prints each entry on the console:
+//!
+//! ```text
+//! @audit spec=input_pd_can_access addr=0x504001000 len=4 op=write
+//! ```
+//!
+//! The log holds the first [`AUDIT_LOG_CAPACITY`] undrained violations;
+//! later ones are only counted, since the first hit is the useful one.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// Violations held until the next drain
+pub const AUDIT_LOG_CAPACITY: usize = 16;
+
+/// Direction of an audited access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// One access that fell outside the PD's spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    /// Spec predicate the access failed, e.g. `"display_pd_can_access"`
+    pub spec: &'static str,
+    /// First byte accessed
+    pub address: usize,
+    /// Bytes accessed
+    pub len: usize,
+    pub access: Access,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.access {
+            Access::Read => "read",
+            Access::Write => "write",
+        };
+        write!(
+            f,
+            "@audit spec={} addr={:#x} len={} op={}",
+            self.spec, self.address, self.len, op
+        )
+    }
+}
+
+/// Violation log. Written and drained from the PD's single thread; the
+/// count is atomic only so the log can be a plain `static`.
+struct AuditLog {
+    entries: UnsafeCell<[Option<Violation>; AUDIT_LOG_CAPACITY]>,
+    len: AtomicUsize,
+    dropped: AtomicU32,
+}
+
+// Safety: each PD is single-threaded and never re-enters the log
+unsafe impl Sync for AuditLog {}
+
+static LOG: AuditLog = AuditLog {
+    entries: UnsafeCell::new([None; AUDIT_LOG_CAPACITY]),
+    len: AtomicUsize::new(0),
+    dropped: AtomicU32::new(0),
+};
+
+/// Record a violation
+pub fn report(violation: Violation) {
+    let len = LOG.len.load(Ordering::Relaxed);
+    if len == AUDIT_LOG_CAPACITY {
+        LOG.dropped.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    unsafe { (*LOG.entries.get())[len] = Some(violation) };
+    LOG.len.store(len + 1, Ordering::Relaxed);
+}
+
+/// Record a violation unless `allowed`; returns `allowed`
+pub fn check(allowed: bool, spec: &'static str, address: usize, len: usize, access: Access) -> bool {
+    if !allowed {
+        report(Violation { spec, address, len, access });
+    }
+    allowed
+}
+
+/// Hand every logged violation to `f`, oldest first, and empty the log.
+/// Returns how many violations were dropped because the log was full.
+pub fn drain(mut f: impl FnMut(&Violation)) -> u32 {
+    let len = LOG.len.load(Ordering::Relaxed);
+    let entries = unsafe { &*LOG.entries.get() };
+    entries[..len].iter().flatten().for_each(&mut f);
+    LOG.len.store(0, Ordering::Relaxed);
+    LOG.dropped.swap(0, Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::{format, vec::Vec};
+
+    // The log is process-wide, so everything that touches it is one test
+    #[test]
+    fn log_reports_drains_and_counts_overflow() {
+        drain(|_| {});
+        assert!(check(true, "input_pd_can_access", 0x5_0400_0000, 4, Access::Read));
+        assert!(!check(false, "input_pd_can_access", 0x5_0400_1000, 4, Access::Write));
+
+        let mut seen = Vec::new();
+        assert_eq!(drain(|v| seen.push(*v)), 0);
+        assert_eq!(seen.len(), 1);
+        assert_eq!(
+            format!("{}", seen[0]),
+            "@audit spec=input_pd_can_access addr=0x504001000 len=4 op=write"
+        );
+
+        for i in 0..AUDIT_LOG_CAPACITY + 3 {
+            check(false, "display_pd_can_access", i, 1, Access::Read);
+        }
+        let mut addresses = Vec::new();
+        assert_eq!(drain(|v| addresses.push(v.address)), 3);
+        assert_eq!(addresses, (0..AUDIT_LOG_CAPACITY).collect::<Vec<_>>());
+        assert_eq!(drain(|_| panic!("log should be empty")), 0);
+    }
+}
@@ -0,0 +1,290 @@
+//! Boot log lines shared with the Graphics PD's early console.
+//!
+//! `debug_println!` only reaches the serial console, so a board with just a
+//! screen attached shows nothing about why the TPM or the network failed to
+//! come up. A PD that wants its boot messages on screen writes them to a
+//! page of its own as well, mapped read-only into the Graphics PD, which
+//! draws them as soon as its framebuffer is mapped:
+//!
+//! ```text
+//! [NET] interface initialized
+//! [TPM] ! TPM init failed: timeout
+//! ```
+//!
+//! Each page has one writer, so appending needs no lock. The first
+//! [`BOOT_LOG_LINES`] lines are kept (a boot log's first failure is the
+//! useful one), later ones are only counted. A line is written before the
+//! count that covers it is published, and never rewritten, so a reader
+//! sees whole lines only.
+
+use core::fmt::{self, Write};
+use core::ptr::{addr_of, addr_of_mut, read_volatile, write_volatile};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+/// Size of a boot log page
+pub const BOOT_LOG_PAGE_SIZE: usize = 0x1000;
+
+/// Lines a page keeps
+pub const BOOT_LOG_LINES: usize = 32;
+
+/// Longest line, in bytes; longer ones are cut
+pub const BOOT_LOG_LINE_LEN: usize = 94;
+
+/// Marks a page a writer has attached to ("BLG1")
+pub const BOOT_LOG_MAGIC: u32 = 0x424C_4731;
+
+/// How a line is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    /// Something did not come up
+    Error,
+}
+
+impl Level {
+    const fn to_wire(self) -> u8 {
+        match self {
+            Level::Info => 1,
+            Level::Error => 2,
+        }
+    }
+
+    const fn from_wire(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Level::Info),
+            2 => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+#[repr(C)]
+struct Line {
+    level: u8,
+    len: u8,
+    text: [u8; BOOT_LOG_LINE_LEN],
+}
+
+/// Layout of a boot log page
+#[repr(C)]
+struct BootLogPage {
+    magic: u32,
+    /// Lines written; only ever grows while the writer runs
+    count: AtomicU32,
+    /// Lines refused because the page was full
+    dropped: AtomicU32,
+    _reserved: u32,
+    lines: [Line; BOOT_LOG_LINES],
+}
+
+const _: () = assert!(core::mem::size_of::<BootLogPage>() <= BOOT_LOG_PAGE_SIZE);
+
+/// The writing end of a boot log page
+pub struct BootLog {
+    page: *mut BootLogPage,
+    count: u32,
+}
+
+impl BootLog {
+    /// Start a fresh log on the page at `base`
+    ///
+    /// # Safety
+    /// `base` must point to a mapped, 4-byte aligned boot log page, and the
+    /// caller must be its only writer.
+    pub unsafe fn attach(base: *mut u8) -> Self {
+        let page = base as *mut BootLogPage;
+        (*page).count.store(0, Ordering::Relaxed);
+        (*page).dropped.store(0, Ordering::Relaxed);
+        fence(Ordering::Release);
+        write_volatile(addr_of_mut!((*page).magic), BOOT_LOG_MAGIC);
+        Self { page, count: 0 }
+    }
+
+    /// Append `text`, cut to [`BOOT_LOG_LINE_LEN`] bytes. Returns false if
+    /// the page is full.
+    pub fn push(&mut self, level: Level, text: &str) -> bool {
+        let mut line = LineBuf::new();
+        line.push_str(text);
+        self.push_line(level, &line)
+    }
+
+    /// Append a formatted line, like [`push`](Self::push)
+    pub fn log(&mut self, level: Level, args: fmt::Arguments<'_>) -> bool {
+        let mut line = LineBuf::new();
+        let _ = line.write_fmt(args);
+        self.push_line(level, &line)
+    }
+
+    /// Lines written so far
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn push_line(&mut self, level: Level, line: &LineBuf) -> bool {
+        // Safety: attach's caller guarantees the page and the single writer
+        unsafe {
+            let page = self.page;
+            if self.count as usize >= BOOT_LOG_LINES {
+                let dropped = (*page).dropped.load(Ordering::Relaxed);
+                (*page).dropped.store(dropped.saturating_add(1), Ordering::Relaxed);
+                return false;
+            }
+            let slot = addr_of_mut!((*page).lines[self.count as usize]);
+            write_volatile(addr_of_mut!((*slot).level), level.to_wire());
+            write_volatile(addr_of_mut!((*slot).len), line.len as u8);
+            for (i, &byte) in line.bytes().iter().enumerate() {
+                write_volatile(addr_of_mut!((*slot).text[i]), byte);
+            }
+            self.count += 1;
+            (*page).count.store(self.count, Ordering::Release);
+        }
+        true
+    }
+}
+
+/// The reading end of a boot log page, remembering which lines it has
+/// handed out
+pub struct BootLogReader {
+    page: *const BootLogPage,
+    next: u32,
+}
+
+impl BootLogReader {
+    /// # Safety
+    /// `base` must point to a mapped, 4-byte aligned boot log page.
+    pub unsafe fn attach(base: *const u8) -> Self {
+        Self { page: base as *const BootLogPage, next: 0 }
+    }
+
+    /// Lines published so far; zero until a writer has attached
+    pub fn published(&self) -> usize {
+        // Safety: attach's caller guarantees the page
+        unsafe {
+            let page = self.page;
+            let count = (*page).count.load(Ordering::Acquire);
+            if read_volatile(addr_of!((*page).magic)) != BOOT_LOG_MAGIC {
+                return 0;
+            }
+            (count as usize).min(BOOT_LOG_LINES)
+        }
+    }
+
+    /// Lines the writer could not fit
+    pub fn dropped(&self) -> u32 {
+        // Safety: as for `published`
+        unsafe { (*self.page).dropped.load(Ordering::Relaxed) }
+    }
+
+    /// Copy the next line not yet handed out into `buf`. A line that is
+    /// not valid UTF-8 is cut at the first bad byte.
+    pub fn poll<'a>(&mut self, buf: &'a mut [u8; BOOT_LOG_LINE_LEN]) -> Option<(Level, &'a str)> {
+        if self.next as usize >= self.published() {
+            return None;
+        }
+        // Safety: the line is below the published count, so the writer
+        // has finished it and will not touch it again
+        let (level, len) = unsafe {
+            let slot = addr_of!((*self.page).lines[self.next as usize]);
+            let level = read_volatile(addr_of!((*slot).level));
+            let len = (read_volatile(addr_of!((*slot).len)) as usize).min(BOOT_LOG_LINE_LEN);
+            for (i, byte) in buf[..len].iter_mut().enumerate() {
+                *byte = read_volatile(addr_of!((*slot).text[i]));
+            }
+            (level, len)
+        };
+        self.next += 1;
+        let text = match core::str::from_utf8(&buf[..len]) {
+            Ok(text) => text,
+            // Safety: valid_up_to is a char boundary of valid UTF-8
+            Err(e) => unsafe { core::str::from_utf8_unchecked(&buf[..e.valid_up_to()]) },
+        };
+        Some((Level::from_wire(level).unwrap_or(Level::Info), text))
+    }
+}
+
+/// One line being formatted; text past [`BOOT_LOG_LINE_LEN`] is cut at a
+/// char boundary
+struct LineBuf {
+    bytes: [u8; BOOT_LOG_LINE_LEN],
+    len: usize,
+}
+
+impl LineBuf {
+    const fn new() -> Self {
+        Self { bytes: [0; BOOT_LOG_LINE_LEN], len: 0 }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    fn push_str(&mut self, s: &str) {
+        for c in s.chars() {
+            let n = c.len_utf8();
+            if self.len + n > BOOT_LOG_LINE_LEN {
+                return;
+            }
+            c.encode_utf8(&mut self.bytes[self.len..]);
+            self.len += n;
+        }
+    }
+}
+
+impl Write for LineBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::boxed::Box;
+
+    #[repr(C, align(8))]
+    struct Page([u8; BOOT_LOG_PAGE_SIZE]);
+
+    #[test]
+    fn lines_arrive_once_in_order() {
+        let mut page = Box::new(Page([0xA5; BOOT_LOG_PAGE_SIZE]));
+        let base = page.0.as_mut_ptr();
+        let mut reader = unsafe { BootLogReader::attach(base) };
+        let mut buf = [0; BOOT_LOG_LINE_LEN];
+        // Garbage before the writer attaches is not a log
+        assert_eq!(reader.published(), 0);
+
+        let mut log = unsafe { BootLog::attach(base) };
+        assert!(log.push(Level::Info, "interface initialized"));
+        assert!(log.log(Level::Error, format_args!("TPM init failed: {}", "timeout")));
+        assert_eq!(reader.poll(&mut buf), Some((Level::Info, "interface initialized")));
+        assert_eq!(reader.poll(&mut buf), Some((Level::Error, "TPM init failed: timeout")));
+        assert_eq!(reader.poll(&mut buf), None);
+    }
+
+    #[test]
+    fn full_page_keeps_the_first_lines() {
+        let mut page = Box::new(Page([0; BOOT_LOG_PAGE_SIZE]));
+        let base = page.0.as_mut_ptr();
+        let mut log = unsafe { BootLog::attach(base) };
+        for i in 0..BOOT_LOG_LINES + 3 {
+            assert_eq!(log.log(Level::Info, format_args!("line {}", i)), i < BOOT_LOG_LINES);
+        }
+        let mut reader = unsafe { BootLogReader::attach(base) };
+        let mut buf = [0; BOOT_LOG_LINE_LEN];
+        assert_eq!(reader.poll(&mut buf), Some((Level::Info, "line 0")));
+        assert_eq!(reader.published(), BOOT_LOG_LINES);
+        assert_eq!(reader.dropped(), 3);
+
+        // Cut at a char boundary, never mid-character
+        let mut line = LineBuf::new();
+        line.push_str(&"é".repeat(BOOT_LOG_LINE_LEN));
+        assert_eq!(line.len, BOOT_LOG_LINE_LEN / 2 * 2);
+        assert!(core::str::from_utf8(line.bytes()).is_ok());
+    }
+}
@@ -0,0 +1,374 @@
+//! Shared error type for protection domains.
+//!
+//! Every crate keeps its own precise error enum (`MailboxError`, `TpmRc`,
+//! `HandoffError`, ...) and converts it into a [`PdError`] at the API
+//! boundary. A [`PdError`] is a compact [`ErrorCode`] (which subsystem, what
+//! kind of failure, and a subsystem-specific detail) plus an optional static
+//! context string naming the operation that failed:
+//!
+//! ```text
+//! mailbox: timeout (detail 0x0002) while allocating framebuffer
+//! ```
+//!
+//! The code (but not the context) crosses PD boundaries as a single `u32`,
+//! see [`ErrorCode::to_wire`]. Zero is never a valid code, so a wire value of
+//! zero means success.
+
+#![no_std]
+
+use core::fmt;
+
+pub mod audit;
+pub mod bootlog;
+pub mod mem;
+
+/// Subsystem an error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ErrorDomain {
+    /// seL4/Microkit calls (IRQ ack, notifications, IPC)
+    Kernel = 1,
+    /// VideoCore mailbox
+    Mailbox = 2,
+    /// Framebuffer and display mode setup
+    Display = 3,
+    /// TPM driver and TPM response codes
+    Tpm = 4,
+    /// Input devices and the input ring
+    Input = 5,
+    /// USB host controller and enumeration
+    Usb = 6,
+    /// Network drivers and the network ring
+    Network = 7,
+    /// App/Present frame handoff
+    FrameHandoff = 8,
+    /// Image validation and decoding
+    Image = 9,
+    /// PD application logic
+    App = 10,
+    /// SD card controller and the filesystem on it
+    Storage = 11,
+    /// Wall-clock time and the clock page
+    Clock = 12,
+    /// Audio mixer and the audio calls
+    Audio = 13,
+}
+
+impl ErrorDomain {
+    /// Decode a wire byte
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(ErrorDomain::Kernel),
+            2 => Some(ErrorDomain::Mailbox),
+            3 => Some(ErrorDomain::Display),
+            4 => Some(ErrorDomain::Tpm),
+            5 => Some(ErrorDomain::Input),
+            6 => Some(ErrorDomain::Usb),
+            7 => Some(ErrorDomain::Network),
+            8 => Some(ErrorDomain::FrameHandoff),
+            9 => Some(ErrorDomain::Image),
+            10 => Some(ErrorDomain::App),
+            11 => Some(ErrorDomain::Storage),
+            12 => Some(ErrorDomain::Clock),
+            13 => Some(ErrorDomain::Audio),
+            _ => None,
+        }
+    }
+
+    /// Short lowercase name
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ErrorDomain::Kernel => "kernel",
+            ErrorDomain::Mailbox => "mailbox",
+            ErrorDomain::Display => "display",
+            ErrorDomain::Tpm => "tpm",
+            ErrorDomain::Input => "input",
+            ErrorDomain::Usb => "usb",
+            ErrorDomain::Network => "network",
+            ErrorDomain::FrameHandoff => "frame handoff",
+            ErrorDomain::Image => "image",
+            ErrorDomain::App => "app",
+            ErrorDomain::Storage => "storage",
+            ErrorDomain::Clock => "clock",
+            ErrorDomain::Audio => "audio",
+        }
+    }
+}
+
+/// What went wrong, independent of the subsystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ErrorKind {
+    /// Failure with no more specific kind
+    Failed = 0,
+    /// A caller-supplied value was out of range or malformed
+    InvalidArgument = 1,
+    /// The operation is not allowed in the current state
+    InvalidState = 2,
+    /// The device or peer has not been initialized yet
+    NotReady = 3,
+    /// Waited too long for hardware or a peer
+    Timeout = 4,
+    /// The device or peer answered with something unexpected
+    InvalidResponse = 5,
+    /// A buffer, ring or table is full or too small
+    Capacity = 6,
+    /// The requested device or item does not exist
+    NotFound = 7,
+    /// Not supported by this hardware or build
+    Unsupported = 8,
+    /// The hardware reported a fault
+    Hardware = 9,
+    /// The device refused the operation (authorization, locality, lock)
+    Denied = 10,
+    /// A checksum, signature or measurement did not match
+    Integrity = 11,
+}
+
+impl ErrorKind {
+    /// Decode a wire byte
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(ErrorKind::Failed),
+            1 => Some(ErrorKind::InvalidArgument),
+            2 => Some(ErrorKind::InvalidState),
+            3 => Some(ErrorKind::NotReady),
+            4 => Some(ErrorKind::Timeout),
+            5 => Some(ErrorKind::InvalidResponse),
+            6 => Some(ErrorKind::Capacity),
+            7 => Some(ErrorKind::NotFound),
+            8 => Some(ErrorKind::Unsupported),
+            9 => Some(ErrorKind::Hardware),
+            10 => Some(ErrorKind::Denied),
+            11 => Some(ErrorKind::Integrity),
+            _ => None,
+        }
+    }
+
+    /// Short lowercase name
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Failed => "failed",
+            ErrorKind::InvalidArgument => "invalid argument",
+            ErrorKind::InvalidState => "invalid state",
+            ErrorKind::NotReady => "not ready",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::InvalidResponse => "invalid response",
+            ErrorKind::Capacity => "out of capacity",
+            ErrorKind::NotFound => "not found",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Hardware => "hardware fault",
+            ErrorKind::Denied => "denied",
+            ErrorKind::Integrity => "integrity check failed",
+        }
+    }
+}
+
+/// Compact error code: domain, kind and a 16-bit domain-specific detail
+/// (e.g. the TPM response code or the source enum's variant)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorCode {
+    pub domain: ErrorDomain,
+    pub kind: ErrorKind,
+    pub detail: u16,
+}
+
+impl ErrorCode {
+    /// Create a code with no detail
+    pub const fn new(domain: ErrorDomain, kind: ErrorKind) -> Self {
+        Self { domain, kind, detail: 0 }
+    }
+
+    /// The same code with a domain-specific detail value
+    pub const fn with_detail(self, detail: u16) -> Self {
+        Self { detail, ..self }
+    }
+
+    /// Encode as `domain << 24 | kind << 16 | detail`. Never zero.
+    pub const fn to_wire(self) -> u32 {
+        ((self.domain as u32) << 24) | ((self.kind as u32) << 16) | self.detail as u32
+    }
+
+    /// Decode a wire value; None for zero (success) or unknown domain/kind
+    pub const fn from_wire(wire: u32) -> Option<Self> {
+        let domain = match ErrorDomain::from_u8((wire >> 24) as u8) {
+            Some(domain) => domain,
+            None => return None,
+        };
+        let kind = match ErrorKind::from_u8((wire >> 16) as u8) {
+            Some(kind) => kind,
+            None => return None,
+        };
+        Some(Self { domain, kind, detail: wire as u16 })
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.domain.as_str(), self.kind.as_str())?;
+        if self.detail != 0 {
+            write!(f, " (detail 0x{:04x})", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned across PD and crate APIs: a code plus optional context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdError {
+    code: ErrorCode,
+    context: Option<&'static str>,
+}
+
+/// Result alias for [`PdError`]
+pub type PdResult<T> = Result<T, PdError>;
+
+impl PdError {
+    /// Create an error with no detail or context
+    pub const fn new(domain: ErrorDomain, kind: ErrorKind) -> Self {
+        Self { code: ErrorCode::new(domain, kind), context: None }
+    }
+
+    /// Create an error from a full code
+    pub const fn from_code(code: ErrorCode) -> Self {
+        Self { code, context: None }
+    }
+
+    /// Attach a domain-specific detail value
+    pub const fn with_detail(self, detail: u16) -> Self {
+        Self { code: self.code.with_detail(detail), ..self }
+    }
+
+    /// Name the operation that failed. Replaces any earlier context, so the
+    /// outermost caller's description wins.
+    pub const fn context(self, context: &'static str) -> Self {
+        Self { context: Some(context), ..self }
+    }
+
+    /// The error code
+    pub const fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Subsystem the error came from
+    pub const fn domain(&self) -> ErrorDomain {
+        self.code.domain
+    }
+
+    /// What went wrong
+    pub const fn kind(&self) -> ErrorKind {
+        self.code.kind
+    }
+
+    /// The operation that failed, if recorded
+    pub const fn context_str(&self) -> Option<&'static str> {
+        self.context
+    }
+
+    /// Wire encoding of the code for IPC replies (context is local only)
+    pub const fn to_wire(&self) -> u32 {
+        self.code.to_wire()
+    }
+
+    /// Rebuild an error received over IPC
+    pub const fn from_wire(wire: u32) -> Option<Self> {
+        match ErrorCode::from_wire(wire) {
+            Some(code) => Some(Self::from_code(code)),
+            None => None,
+        }
+    }
+}
+
+impl From<ErrorCode> for PdError {
+    fn from(code: ErrorCode) -> Self {
+        Self::from_code(code)
+    }
+}
+
+impl fmt::Display for PdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code)?;
+        if let Some(context) = self.context {
+            write!(f, " while {}", context)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wire value for an IPC reply: zero on success, the error code otherwise
+pub fn reply_code<T>(result: &PdResult<T>) -> u32 {
+    match result {
+        Ok(_) => 0,
+        Err(e) => e.to_wire(),
+    }
+}
+
+/// Attach context while converting any error into a [`PdError`]
+pub trait ResultExt<T> {
+    /// Convert the error and record which operation failed
+    fn context(self, context: &'static str) -> PdResult<T>;
+}
+
+impl<T, E: Into<PdError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: &'static str) -> PdResult<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::format;
+
+    #[derive(Debug)]
+    enum LocalError {
+        Busy,
+    }
+
+    impl From<LocalError> for PdError {
+        fn from(e: LocalError) -> Self {
+            match e {
+                LocalError::Busy => PdError::new(ErrorDomain::Mailbox, ErrorKind::Timeout).with_detail(2),
+            }
+        }
+    }
+
+    #[test]
+    fn wire_round_trip() {
+        let code = ErrorCode::new(ErrorDomain::Tpm, ErrorKind::Denied).with_detail(0x8E);
+        let wire = code.to_wire();
+        assert_eq!(wire, 0x040A_008E);
+        assert_eq!(ErrorCode::from_wire(wire), Some(code));
+
+        assert_eq!(ErrorCode::from_wire(0), None);
+        assert_eq!(ErrorCode::from_wire(0xFF00_0000), None);
+        assert_eq!(ErrorCode::from_wire(0x01FF_0000), None);
+    }
+
+    #[test]
+    fn every_code_is_nonzero_on_the_wire() {
+        let code = ErrorCode::new(ErrorDomain::Kernel, ErrorKind::Failed);
+        assert_ne!(code.to_wire(), 0);
+        assert_eq!(reply_code(&Err::<(), _>(PdError::from_code(code))), code.to_wire());
+        assert_eq!(reply_code(&Ok::<_, PdError>(())), 0);
+    }
+
+    #[test]
+    fn context_is_attached_on_conversion() {
+        let result: Result<(), LocalError> = Err(LocalError::Busy);
+        let err = result.context("allocating framebuffer").unwrap_err();
+        assert_eq!(err.domain(), ErrorDomain::Mailbox);
+        assert_eq!(err.context_str(), Some("allocating framebuffer"));
+        assert_eq!(
+            format!("{}", err),
+            "mailbox: timeout (detail 0x0002) while allocating framebuffer"
+        );
+
+        // Context stays local; the wire carries only the code
+        let received = PdError::from_wire(err.to_wire()).unwrap();
+        assert_eq!(received.code(), err.code());
+        assert_eq!(received.context_str(), None);
+        assert_eq!(format!("{}", received), "mailbox: timeout (detail 0x0002)");
+    }
+}
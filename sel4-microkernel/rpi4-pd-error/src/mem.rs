@@ -0,0 +1,203 @@
+//! Memory accounting for static buffers, rings and allocators.
+//!
+//! Every region a `.system` file maps, and every heap a PD carves out of
+//! its own image, is sized by guesswork until something measures it. A PD
+//! wraps each of them in a [`Gauge`], records how much is in use where it
+//! fills or drains them, and prints its gauges on the console:
+//!
+//! ```text
+//! @mem pd=photoframe name=decoder_heap unit=bytes used=0 peak=421888 cap=16777216 peak_pct=2
+//! @mem pd=input name=input_ring unit=slots used=0 peak=3 cap=511 peak_pct=0
+//! ```
+//!
+//! A PD prints one line when a gauge's peak first reaches another eighth
+//! of its capacity (see [`Gauge::record`]), so a growing peak is logged a
+//! handful of times rather than on every entry, and may dump all of its
+//! gauges on a timer. The peak after a soak run is the figure to size the
+//! region by.
+//!
+//! Allocators that already count their own usage report through
+//! [`Reading::new`] instead of a gauge.
+
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Steps of capacity a peak is reported at
+pub const REPORT_STEPS: usize = 8;
+
+/// What a gauge counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Bytes,
+    /// Ring entries, table slots
+    Slots,
+}
+
+impl Unit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Unit::Bytes => "bytes",
+            Unit::Slots => "slots",
+        }
+    }
+}
+
+/// Use and high-water mark of one buffer. Updated from the PD's single
+/// thread; the counters are atomic only so a gauge can be a plain `static`.
+pub struct Gauge {
+    name: &'static str,
+    unit: Unit,
+    capacity: usize,
+    used: AtomicUsize,
+    peak: AtomicUsize,
+    /// Highest step of capacity the peak has been reported at
+    reported: AtomicUsize,
+}
+
+impl Gauge {
+    pub const fn new(name: &'static str, unit: Unit, capacity: usize) -> Self {
+        Self {
+            name,
+            unit,
+            capacity,
+            used: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            reported: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record `used` units in use. Returns `true` when this takes the peak
+    /// to another step of [`REPORT_STEPS`] (or past the capacity), when the
+    /// PD should print the gauge.
+    pub fn record(&self, used: usize) -> bool {
+        self.used.store(used, Ordering::Relaxed);
+        if used <= self.peak.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.peak.store(used, Ordering::Relaxed);
+        let step = step_of(used, self.capacity);
+        if step > self.reported.load(Ordering::Relaxed) {
+            self.reported.store(step, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The gauge as it stands
+    pub fn reading(&self) -> Reading {
+        Reading::new(self.name, self.unit, self.used(), self.peak(), self.capacity)
+    }
+}
+
+/// Step of capacity `used` has reached: 1 for anything up to the first
+/// eighth, [`REPORT_STEPS`] at capacity, one more past it
+fn step_of(used: usize, capacity: usize) -> usize {
+    if capacity == 0 || used > capacity {
+        return REPORT_STEPS + 1;
+    }
+    (used.saturating_mul(REPORT_STEPS)).div_ceil(capacity)
+}
+
+/// One buffer's figures at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub name: &'static str,
+    pub unit: Unit,
+    pub used: usize,
+    pub peak: usize,
+    pub capacity: usize,
+}
+
+impl Reading {
+    pub const fn new(name: &'static str, unit: Unit, used: usize, peak: usize, capacity: usize) -> Self {
+        Self { name, unit, used, peak, capacity }
+    }
+
+    /// The peak as a whole percentage of capacity, rounded up so a buffer
+    /// that was touched never reads 0%
+    pub fn peak_percent(&self) -> usize {
+        if self.capacity == 0 {
+            return if self.peak == 0 { 0 } else { 100 };
+        }
+        // u64 so a 16 MB heap cannot overflow a 32-bit usize
+        ((self.peak as u64 * 100).div_ceil(self.capacity as u64)) as usize
+    }
+
+    /// The `@mem` console line for PD `pd`
+    pub fn line(&self, pd: &'static str) -> MemLine<'_> {
+        MemLine { pd, reading: self }
+    }
+}
+
+/// A [`Reading`] as an `@mem` console line
+pub struct MemLine<'a> {
+    pd: &'static str,
+    reading: &'a Reading,
+}
+
+impl fmt::Display for MemLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = self.reading;
+        write!(
+            f,
+            "@mem pd={} name={} unit={} used={} peak={} cap={} peak_pct={}",
+            self.pd,
+            r.name,
+            r.unit.as_str(),
+            r.used,
+            r.peak,
+            r.capacity,
+            r.peak_percent()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::string::ToString;
+
+    #[test]
+    fn peaks_are_reported_once_per_step() {
+        let gauge = Gauge::new("ring", Unit::Slots, 16);
+        // The first entry is a step of its own
+        assert!(gauge.record(1));
+        assert!(!gauge.record(2));
+        // Draining and refilling to the same peak says nothing
+        assert!(!gauge.record(0));
+        assert!(!gauge.record(2));
+        assert!(gauge.record(3));
+        assert!(!gauge.record(4));
+        assert!(gauge.record(16));
+        assert!(gauge.record(17));
+        assert!(!gauge.record(18));
+        assert_eq!((gauge.used(), gauge.peak(), gauge.capacity()), (18, 18, 16));
+    }
+
+    #[test]
+    fn reading_lines() {
+        let gauge = Gauge::new("file_buffer", Unit::Bytes, 4 << 20);
+        gauge.record(421_888);
+        gauge.record(0);
+        assert_eq!(
+            gauge.reading().line("storage").to_string(),
+            "@mem pd=storage name=file_buffer unit=bytes used=0 peak=421888 cap=4194304 peak_pct=11"
+        );
+        assert_eq!(Reading::new("heap", Unit::Bytes, 0, 1, 16 << 20).peak_percent(), 1);
+        assert_eq!(Reading::new("heap", Unit::Bytes, 0, 0, 16 << 20).peak_percent(), 0);
+        assert_eq!(Reading::new("none", Unit::Slots, 0, 0, 0).peak_percent(), 0);
+    }
+}
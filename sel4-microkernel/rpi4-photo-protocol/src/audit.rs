@@ -0,0 +1,56 @@
+//! Audit-build checks of the shared-memory helpers against the PD access specs.
+//!
+//! With the `audit` feature, every raw pointer the command ring, pixel buffer,
+//! telemetry log and thumbnail ring helpers dereference (or hand out) is
+//! checked against the access spec of the PD they run in:
+//! `decoder_pd_can_access` or `display_pd_can_access`, through their
+//! verified runtime mirrors.
+//! Accesses outside the spec are logged to [`rpi4_pd_error::audit`] for the
+//! PD to print, so a `.system` mapping that drifted from the spec shows up on
+//! the console during development.
+//!
+//! Nothing is checked until the PD calls [`set_role`], which keeps host
+//! tests (whose rings live on the stack) quiet. Without the feature the
+//! hooks compile to nothing.
+
+#[cfg(feature = "audit")]
+use core::sync::atomic::{AtomicU8, Ordering};
+use rpi4_pd_error::audit::Access;
+
+/// Which PD's spec the helpers are checked against
+#[cfg(feature = "audit")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Role {
+    Decoder = 1,
+    Display = 2,
+}
+
+#[cfg(feature = "audit")]
+static ROLE: AtomicU8 = AtomicU8::new(0);
+
+/// Start checking accesses against `role`'s spec
+#[cfg(feature = "audit")]
+pub fn set_role(role: Role) {
+    ROLE.store(role as u8, Ordering::Relaxed);
+}
+
+/// Check `count` values of `T` at `ptr` (both ends of the range) against
+/// the current role's spec
+#[inline(always)]
+pub(crate) fn audit_access<T>(ptr: *const T, count: usize, access: Access) {
+    #[cfg(feature = "audit")]
+    {
+        let (spec, may_access): (&'static str, fn(usize) -> bool) = match ROLE.load(Ordering::Relaxed) {
+            1 => ("decoder_pd_can_access", crate::decoder_pd_may_access),
+            2 => ("display_pd_can_access", crate::display_pd_may_access),
+            _ => return,
+        };
+        let len = count * core::mem::size_of::<T>();
+        let first = ptr as usize;
+        let allowed = len == 0 || (may_access(first) && may_access(first + len - 1));
+        rpi4_pd_error::audit::check(allowed, spec, first, len, access);
+    }
+    #[cfg(not(feature = "audit"))]
+    let _ = (ptr, count, access);
+}
@@ -16,7 +16,7 @@
 //! ### Command Ring (4KB) - Display ↔ Input/Timer
 //! ```text
 //! +-------------------+ 0x000
-//! | CommandRingHeader | (16 bytes)
+//! | RingHeader + pad  | (16 bytes)
 //! +-------------------+ 0x010
 //! | PhotoCommand[0]   | (8 bytes each)
 //! | PhotoCommand[1]   |
@@ -27,18 +27,46 @@
 //! ### Pixel Buffer (4MB) - Decoder → Display
 //! ```text
 //! +-------------------+ 0x000
-//! | PixelBufferHeader | (32 bytes)
-//! +-------------------+ 0x020
+//! | PixelBufferHeader | (64 bytes)
+//! +-------------------+ 0x040
 //! | Pixel data        | (up to ~4MB)
 //! | RGBA32 format     |
 //! +-------------------+
 //! ```
+//!
+//! ### Thumbnail Ring (2MB) - Decoder → Display
+//! ```text
+//! +----------------------+ 0x000
+//! | ThumbnailRingHeader  | (16 bytes, padded to 4KB)
+//! +----------------------+ 0x1000
+//! | ThumbnailSlot[0]     | (16-byte header + 192x108 ARGB32,
+//! | ThumbnailSlot[1]     |  0x15000 bytes each, oldest overwritten)
+//! | ...                  |
+//! +----------------------+ 0x1A5000
+//! ```
+//!
+//! ### Telemetry Log (4KB) - Display → Storage
+//! ```text
+//! +--------------------+ 0x000
+//! | TelemetryLogHeader | (16 bytes)
+//! +--------------------+ 0x010
+//! | TelemetryRecord[0] | (32 bytes each, oldest overwritten)
+//! | ...                |
+//! +--------------------+ 0x1000
+//! ```
 
 #![no_std]
 #![allow(unused)]
 #![allow(clippy::assign_op_pattern)]
 #![allow(clippy::new_without_default)]
-
+#![allow(clippy::implicit_saturating_sub)]
+#![allow(clippy::manual_range_contains)]
+#![allow(clippy::manual_swap)]
+#![allow(clippy::manual_map)]
+#![allow(clippy::match_like_matches_macro)]
+
+use verified_microkernel::ring::{self, RingHeader, VerifiedRing};
+use verified_microkernel::{ring_add_usize, ring_mod, ring_next_usize};
 use verus_builtin_macros::verus;
 
 verus! {
@@ -91,6 +119,25 @@ pub const CMD_RESUME: u8 = 4;
 pub const CMD_GOTO: u8 = 5;
 pub const CMD_LOAD_COMPLETE: u8 = 6;
 pub const CMD_LOAD_ERROR: u8 = 7;
+/// Set the playlist order (`flags` is an `ORDER_*` value); reshuffles when
+/// the order is already `ORDER_SHUFFLE`
+pub const CMD_SHUFFLE: u8 = 8;
+/// Toggle the favorite flag of `photo_index`
+pub const CMD_FAVORITE: u8 = 9;
+/// Open (`flags` = `BROWSE_OPEN`) or close (`BROWSE_CLOSE`) the thumbnail
+/// grid
+pub const CMD_BROWSE: u8 = 10;
+/// Close the thumbnail grid on `photo_index`
+pub const CMD_SELECT: u8 = 11;
+
+/// Grid actions for CMD_BROWSE
+pub const BROWSE_CLOSE: u8 = 0;
+pub const BROWSE_OPEN: u8 = 1;
+
+/// Playlist orders for CMD_SHUFFLE
+pub const ORDER_SEQUENTIAL: u8 = 0;
+pub const ORDER_SHUFFLE: u8 = 1;
+pub const ORDER_NEWEST_FIRST: u8 = 2;
 
 /// Specification: is a command type valid?
 pub open spec fn valid_command_type(cmd: u8) -> bool {
@@ -101,7 +148,18 @@ pub open spec fn valid_command_type(cmd: u8) -> bool {
     cmd == CMD_RESUME ||
     cmd == CMD_GOTO ||
     cmd == CMD_LOAD_COMPLETE ||
-    cmd == CMD_LOAD_ERROR
+    cmd == CMD_LOAD_ERROR ||
+    cmd == CMD_SHUFFLE ||
+    cmd == CMD_FAVORITE ||
+    cmd == CMD_BROWSE ||
+    cmd == CMD_SELECT
+}
+
+/// Specification: is a playlist order valid?
+pub open spec fn valid_order(order: u8) -> bool {
+    order == ORDER_SEQUENTIAL ||
+    order == ORDER_SHUFFLE ||
+    order == ORDER_NEWEST_FIRST
 }
 
 /// A photo navigation command.
@@ -121,7 +179,9 @@ pub struct PhotoCommand {
 impl PhotoCommand {
     /// Specification: is this command valid?
     pub open spec fn valid(&self) -> bool {
-        valid_command_type(self.command)
+        valid_command_type(self.command) &&
+        (self.command == CMD_SHUFFLE ==> valid_order(self.flags)) &&
+        (self.command == CMD_BROWSE ==> self.flags <= BROWSE_OPEN)
     }
 
     /// Create a next photo command
@@ -208,6 +268,58 @@ impl PhotoCommand {
         }
     }
 
+    /// Create a playlist order command
+    pub fn shuffle(order: u8) -> (cmd: Self)
+        requires valid_order(order),
+        ensures cmd.valid(), cmd.command == CMD_SHUFFLE, cmd.flags == order
+    {
+        PhotoCommand {
+            command: CMD_SHUFFLE,
+            flags: order,
+            photo_index: 0,
+            _reserved: 0,
+        }
+    }
+
+    /// Create a favorite toggle command
+    pub fn favorite(index: u16) -> (cmd: Self)
+        ensures cmd.valid(), cmd.command == CMD_FAVORITE, cmd.photo_index == index
+    {
+        PhotoCommand {
+            command: CMD_FAVORITE,
+            flags: 0,
+            photo_index: index,
+            _reserved: 0,
+        }
+    }
+
+    /// Create a command opening or closing the thumbnail grid
+    pub fn browse(open: bool) -> (cmd: Self)
+        ensures
+            cmd.valid(),
+            cmd.command == CMD_BROWSE,
+            cmd.flags == (if open { BROWSE_OPEN } else { BROWSE_CLOSE }),
+    {
+        PhotoCommand {
+            command: CMD_BROWSE,
+            flags: if open { BROWSE_OPEN } else { BROWSE_CLOSE },
+            photo_index: 0,
+            _reserved: 0,
+        }
+    }
+
+    /// Create a command picking a photo from the thumbnail grid
+    pub fn select(index: u16) -> (cmd: Self)
+        ensures cmd.valid(), cmd.command == CMD_SELECT, cmd.photo_index == index
+    {
+        PhotoCommand {
+            command: CMD_SELECT,
+            flags: 0,
+            photo_index: index,
+            _reserved: 0,
+        }
+    }
+
     /// Create an empty command
     pub fn empty() -> (cmd: Self)
         ensures cmd.valid(), cmd.command == CMD_NONE
@@ -272,13 +384,25 @@ pub struct PixelBufferHeader {
     pub data_len: u32,
     /// Checksum of pixel data (for integrity verification)
     pub checksum: u32,
-    /// Reserved padding to 32 bytes
-    pub _reserved: [u8; 8],
+    /// Frame of an animated image the buffer holds (0 for stills)
+    pub frame_index: u16,
+    /// Frames in the image (1 for stills)
+    pub frame_count: u16,
+    /// How long the Display shows this frame before asking for the next
+    /// one, in ms (0 for stills)
+    pub frame_delay_ms: u32,
+    /// EXIF orientation (1-8) the Display turns the pixels by; 1 when the
+    /// image has none
+    pub orientation: u8,
+    /// When the photo was taken, packed by `CaptureTime::pack` (0 if unknown)
+    pub taken_at: u32,
+    /// Camera model, NUL-padded ASCII (all zero if unknown)
+    pub camera_model: [u8; EXIF_MODEL_LEN],
 }
 
 impl PixelBufferHeader {
     /// Size of header in bytes
-    pub const SIZE: usize = 32;
+    pub const SIZE: usize = 64;
 
     /// Specification: are dimensions within bounds?
     pub open spec fn valid_dimensions(&self) -> bool {
@@ -301,12 +425,26 @@ impl PixelBufferHeader {
         }
     }
 
+    /// Specification: does the frame index fall inside the animation?
+    pub open spec fn valid_frame(&self) -> bool {
+        self.frame_count > 0 &&
+        self.frame_index < self.frame_count &&
+        self.frame_delay_ms <= MAX_FRAME_DELAY_MS
+    }
+
+    /// Specification: is the EXIF metadata valid?
+    pub open spec fn valid_metadata(&self) -> bool {
+        valid_exif_orientation(self.orientation)
+    }
+
     /// Specification: is this header fully valid?
     pub open spec fn valid(&self) -> bool {
         self.valid_dimensions() &&
         valid_pixel_format(self.format) &&
         valid_buffer_status(self.status) &&
-        self.valid_data_len()
+        self.valid_data_len() &&
+        self.valid_frame() &&
+        self.valid_metadata()
     }
 
     /// Create an empty buffer header
@@ -324,7 +462,12 @@ impl PixelBufferHeader {
             photo_index: 0,
             data_len: 0,
             checksum: 0,
-            _reserved: [0; 8],
+            frame_index: 0,
+            frame_count: 0,
+            frame_delay_ms: 0,
+            orientation: EXIF_ORIENTATION_NORMAL,
+            taken_at: 0,
+            camera_model: [0; EXIF_MODEL_LEN],
         }
     }
 
@@ -342,6 +485,9 @@ impl PixelBufferHeader {
             header.height == height,
             header.format == format,
             header.status == BUFFER_STATUS_LOADING,
+            header.valid_frame(),
+            header.frame_count == 1,
+            header.valid_metadata(),
     {
         let bpp: u32 = if format == PIXEL_FORMAT_RGBA32 { 4 }
                       else if format == PIXEL_FORMAT_RGB24 { 3 }
@@ -355,9 +501,78 @@ impl PixelBufferHeader {
             photo_index,
             data_len: width * height * bpp,
             checksum: 0,
-            _reserved: [0; 8],
+            frame_index: 0,
+            frame_count: 1,
+            frame_delay_ms: 0,
+            orientation: EXIF_ORIENTATION_NORMAL,
+            taken_at: 0,
+            camera_model: [0; EXIF_MODEL_LEN],
         }
     }
+
+    /// The same header for frame `frame_index` of a `frame_count`-frame
+    /// animation, shown for `frame_delay_ms`
+    pub fn with_frame(self, frame_index: u16, frame_count: u16, frame_delay_ms: u32) -> (header: Self)
+        requires
+            frame_index < frame_count,
+            frame_delay_ms <= MAX_FRAME_DELAY_MS,
+        ensures
+            header.valid_frame(),
+            header.width == self.width,
+            header.height == self.height,
+            header.format == self.format,
+            header.status == self.status,
+            header.data_len == self.data_len,
+    {
+        PixelBufferHeader { frame_index, frame_count, frame_delay_ms, ..self }
+    }
+
+    /// The same header carrying the photo's EXIF orientation, capture time
+    /// and camera model
+    pub fn with_metadata(self, orientation: u8, taken_at: u32, camera_model: [u8; EXIF_MODEL_LEN]) -> (header: Self)
+        requires valid_exif_orientation(orientation),
+        ensures
+            header.valid_metadata(),
+            header.orientation == orientation,
+            header.taken_at == taken_at,
+            header.width == self.width,
+            header.height == self.height,
+            header.format == self.format,
+            header.status == self.status,
+            header.data_len == self.data_len,
+            header.frame_index == self.frame_index,
+            header.frame_count == self.frame_count,
+    {
+        PixelBufferHeader { orientation, taken_at, camera_model, ..self }
+    }
+}
+
+// ============================================================================
+// FRAME TIMING
+// ============================================================================
+//
+// Animated images carry a per-frame delay. GIF stores it in centiseconds,
+// and files written for browsers often say 0 or 1 and expect the 100 ms
+// browsers substitute, so the delay is normalized here before it reaches
+// the pixel buffer header.
+
+/// Delay substituted for frames that ask for less than `MIN_FRAME_DELAY_MS`
+pub const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+
+/// Shortest frame delay honored as written
+pub const MIN_FRAME_DELAY_MS: u32 = 20;
+
+/// Longest frame delay a header can carry (the largest GIF delay)
+pub const MAX_FRAME_DELAY_MS: u32 = 655_350;
+
+/// Frame delay in ms for a GIF delay of `centis` hundredths of a second
+pub fn frame_delay_from_centis(centis: u16) -> (ms: u32)
+    ensures
+        MIN_FRAME_DELAY_MS <= ms <= MAX_FRAME_DELAY_MS,
+        centis as u32 * 10 >= MIN_FRAME_DELAY_MS ==> ms == centis as u32 * 10,
+{
+    let ms = centis as u32 * 10;
+    if ms < MIN_FRAME_DELAY_MS { DEFAULT_FRAME_DELAY_MS } else { ms }
 }
 
 // ============================================================================
@@ -405,236 +620,1561 @@ pub open spec fn valid_blit_params(
 }
 
 // ============================================================================
-// COMMAND RING BUFFER
+// SCALED BLIT (Verified)
 // ============================================================================
+//
+// The Display PD letterboxes decoded photos of any size onto its fixed-size
+// framebuffer. Every source and destination index below is proven in range,
+// so bogus dimensions from the (untrusted) Decoder can only get the blit
+// refused, never turned into an out-of-bounds read or write.
+
+/// Largest source or destination dimension `blit_scaled` accepts
+/// (keeps the fixed-point coordinate math well inside u64)
+pub const MAX_BLIT_DIM: u32 = 0xFFFF;
+
+/// Resampling filter for `blit_scaled`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleFilter {
+    /// Nearest neighbor: cheapest, blocky when enlarging
+    Nearest,
+    /// Bilinear: blends the four surrounding source pixels
+    Bilinear,
+}
 
-/// Command ring capacity
-pub const CMD_RING_CAPACITY: u32 = 500;
-
-/// Command entry size
-pub const CMD_ENTRY_SIZE: usize = 8;
+/// Destination rectangle of a scaled blit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlitRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
 
-/// Command ring header size
-pub const CMD_HEADER_SIZE: usize = 16;
+impl BlitRect {
+    /// Specification: non-empty and inside a `dst_w` x `dst_h` surface
+    pub open spec fn within(&self, dst_w: u32, dst_h: u32) -> bool {
+        self.w > 0 && self.h > 0 &&
+        self.w <= MAX_BLIT_DIM && self.h <= MAX_BLIT_DIM &&
+        self.x + self.w <= dst_w &&
+        self.y + self.h <= dst_h
+    }
+}
 
-/// Command ring buffer shared memory size (4KB)
-pub const CMD_RING_SIZE: usize = 0x1000;
+/// Why a scaled blit was refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlitError {
+    /// Source width or height is zero or above `MAX_BLIT_DIM`
+    BadSourceSize,
+    /// Source slice holds fewer than `src_w * src_h` pixels
+    SourceTooShort,
+    /// Destination rectangle is empty or not inside the surface
+    RectOutOfBounds,
+    /// Destination slice holds fewer than `dst_stride * dst_h` pixels
+    DestTooShort,
+}
 
-/// Command ring header
-#[derive(Clone, Copy, Debug)]
-#[repr(C, align(16))]
-pub struct CommandRingHeader {
-    pub write_idx: u32,
-    pub read_idx: u32,
-    pub capacity: u32,
-    pub _pad: u32,
+/// How a stored image is turned to show upright: the EXIF Orientation
+/// tag, values 1-8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// 1: stored upright
+    Normal,
+    /// 2: mirrored left to right
+    MirrorH,
+    /// 3: upside down
+    Rotate180,
+    /// 4: mirrored top to bottom
+    MirrorV,
+    /// 5: mirrored across the main diagonal
+    Transpose,
+    /// 6: needs turning 90° clockwise
+    Rotate90,
+    /// 7: mirrored across the anti-diagonal
+    Transverse,
+    /// 8: needs turning 90° counter-clockwise
+    Rotate270,
 }
 
-impl CommandRingHeader {
-    /// Specification: are indices valid?
-    pub open spec fn valid(&self) -> bool {
-        self.capacity > 0 &&
-        self.capacity <= CMD_RING_CAPACITY &&
-        self.write_idx < self.capacity &&
-        self.read_idx < self.capacity
+impl Orientation {
+    /// Specification: does showing the image upright swap its width and height?
+    pub open spec fn spec_transposed(self) -> bool {
+        self == Orientation::Transpose || self == Orientation::Rotate90 ||
+        self == Orientation::Transverse || self == Orientation::Rotate270
     }
 
-    /// Specification: is buffer empty?
-    pub open spec fn is_empty_spec(&self) -> bool {
-        self.write_idx == self.read_idx
+    /// Decode an EXIF Orientation value; anything outside 1-8 is upright
+    pub fn from_exif(value: u16) -> Self {
+        match value {
+            2 => Orientation::MirrorH,
+            3 => Orientation::Rotate180,
+            4 => Orientation::MirrorV,
+            5 => Orientation::Transpose,
+            6 => Orientation::Rotate90,
+            7 => Orientation::Transverse,
+            8 => Orientation::Rotate270,
+            _ => Orientation::Normal,
+        }
     }
 
-    /// Specification: is buffer full?
-    pub open spec fn is_full_spec(&self) -> bool {
-        (self.write_idx + 1) % self.capacity == self.read_idx
+    /// The EXIF Orientation value
+    pub fn to_exif(self) -> (value: u8)
+        ensures valid_exif_orientation(value),
+    {
+        match self {
+            Orientation::Normal => 1,
+            Orientation::MirrorH => 2,
+            Orientation::Rotate180 => 3,
+            Orientation::MirrorV => 4,
+            Orientation::Transpose => 5,
+            Orientation::Rotate90 => 6,
+            Orientation::Transverse => 7,
+            Orientation::Rotate270 => 8,
+        }
     }
 
-    /// Check if buffer has data
-    pub fn has_data(&self) -> (has: bool)
-        requires self.valid(),
-        ensures has == !self.is_empty_spec(),
+    /// Does showing the image upright swap its width and height?
+    pub fn transposed(self) -> (t: bool)
+        ensures t == self.spec_transposed(),
     {
-        self.write_idx != self.read_idx
+        match self {
+            Orientation::Transpose | Orientation::Rotate90
+            | Orientation::Transverse | Orientation::Rotate270 => true,
+            _ => false,
+        }
     }
 
-    /// Check if buffer is full
-    pub fn is_full(&self) -> (full: bool)
-        requires self.valid(),
-        ensures full == self.is_full_spec(),
+    /// Width and height of a `w` x `h` stored image once shown upright
+    pub fn upright_size(self, w: u32, h: u32) -> (size: (u32, u32))
+        ensures size == (if self.spec_transposed() { (h, w) } else { (w, h) }),
     {
-        (self.write_idx + 1) % self.capacity == self.read_idx
+        if self.transposed() { (h, w) } else { (w, h) }
     }
 }
 
-// ============================================================================
-// MEMORY REGION DEFINITIONS
-// ============================================================================
-
-/// Virtual address for command ring buffer (shared: Input, Timer, Display)
-pub const CMD_RING_VADDR: usize = 0x5_0500_0000;
-
-/// Virtual address for pixel buffer (shared: Decoder, Display)
-pub const PIXEL_BUFFER_VADDR: usize = 0x5_0600_0000;
-
-/// Pixel buffer size (8MB for 1920x1080 RGBA + header)
-pub const PIXEL_BUFFER_SIZE: usize = 0x80_0000;
-
-/// Specification: is address in command ring region?
-pub open spec fn in_cmd_ring_region(addr: usize) -> bool {
-    addr >= CMD_RING_VADDR && addr < CMD_RING_VADDR + CMD_RING_SIZE
+/// Stored pixel shown at (`x`, `y`) of the upright image, for an image
+/// stored `src_w` x `src_h` and turned by `orient`
+pub fn orient_source(x: u32, y: u32, src_w: u32, src_h: u32, orient: Orientation) -> (s: (u32, u32))
+    requires
+        if orient.spec_transposed() { x < src_h && y < src_w } else { x < src_w && y < src_h },
+    ensures
+        s.0 < src_w,
+        s.1 < src_h,
+{
+    match orient {
+        Orientation::Normal => (x, y),
+        Orientation::MirrorH => (src_w - 1 - x, y),
+        Orientation::Rotate180 => (src_w - 1 - x, src_h - 1 - y),
+        Orientation::MirrorV => (x, src_h - 1 - y),
+        Orientation::Transpose => (y, x),
+        Orientation::Rotate90 => (y, src_h - 1 - x),
+        Orientation::Transverse => (src_w - 1 - y, src_h - 1 - x),
+        Orientation::Rotate270 => (src_w - 1 - y, x),
+    }
 }
 
-/// Specification: is address in pixel buffer region?
-pub open spec fn in_pixel_buffer_region(addr: usize) -> bool {
-    addr >= PIXEL_BUFFER_VADDR && addr < PIXEL_BUFFER_VADDR + PIXEL_BUFFER_SIZE
+/// Specification: a scaled blit touches only valid source and destination pixels
+pub open spec fn valid_scaled_blit(
+    src_len: int, src_w: u32, src_h: u32,
+    dst_len: int, dst_stride: u32, dst_h: u32,
+    rect: BlitRect,
+) -> bool {
+    src_w > 0 && src_h > 0 &&
+    src_w <= MAX_BLIT_DIM && src_h <= MAX_BLIT_DIM &&
+    src_w * src_h <= src_len &&
+    rect.within(dst_stride, dst_h) &&
+    dst_stride * dst_h <= dst_len
 }
 
-// ============================================================================
-// PROTECTION DOMAIN ISOLATION SPECIFICATIONS
-// ============================================================================
+/// Row-major index of (`x`, `y`) in a surface `stride` pixels wide
+pub fn surface_index(x: u32, y: u32, stride: u32, rows: u32) -> (idx: usize)
+    requires
+        x < stride,
+        y < rows,
+        stride * rows <= usize::MAX,
+    ensures
+        idx == y * stride + x,
+        idx < stride * rows,
+{
+    proof {
+        assert(y * stride + x < stride * rows) by (nonlinear_arith)
+            requires x < stride, y < rows;
+    }
+    (y as usize) * (stride as usize) + (x as usize)
+}
 
-/// Decoder PD memory regions (untrusted - image parsing)
-/// Has: Pixel buffer (write), Photo data buffer (read)
-/// Missing: Framebuffer, Storage, UART, Network
-pub const DECODER_PD_PHOTO_DATA_BASE: usize = 0x5_0700_0000;
-pub const DECODER_PD_PHOTO_DATA_SIZE: usize = 0x10_0000; // 1MB for photo file data
+/// Source pixel sampled for destination coordinate `d` when `src_len`
+/// pixels are stretched over `dst_len` (pixel centers aligned)
+pub fn nearest_coord(d: u32, dst_len: u32, src_len: u32) -> (s: u32)
+    requires
+        d < dst_len,
+        dst_len <= MAX_BLIT_DIM,
+        0 < src_len <= MAX_BLIT_DIM,
+    ensures s < src_len,
+{
+    // (d + 0.5) * src_len / dst_len in integers
+    let num = (2 * d as u64 + 1) * (src_len as u64);
+    let den = 2 * dst_len as u64;
+    proof {
+        assert(num < den * src_len) by (nonlinear_arith)
+            requires num == (2 * d + 1) * src_len, den == 2 * dst_len, d < dst_len, src_len > 0;
+        assert(num / den < src_len) by (nonlinear_arith)
+            requires num < den * src_len, den > 0;
+    }
+    (num / den) as u32
+}
 
-/// Specification: can Decoder PD access this address?
-pub open spec fn decoder_pd_can_access(addr: usize) -> bool {
-    // Pixel buffer (write decoded pixels)
-    in_pixel_buffer_region(addr) ||
-    // Photo data (read raw file bytes)
-    (addr >= DECODER_PD_PHOTO_DATA_BASE &&
-     addr < DECODER_PD_PHOTO_DATA_BASE + DECODER_PD_PHOTO_DATA_SIZE)
+/// Bilinear sample for destination coordinate `d`: the two neighbouring
+/// source pixels and the 8-bit weight (0..=255) of the second one
+pub fn bilinear_coord(d: u32, dst_len: u32, src_len: u32) -> (r: (u32, u32, u32))
+    requires
+        d < dst_len,
+        dst_len <= MAX_BLIT_DIM,
+        0 < src_len <= MAX_BLIT_DIM,
+    ensures
+        r.0 < src_len,
+        r.1 < src_len,
+        r.2 < 256,
+{
+    // Source position of the destination pixel center in 1/256 pixel units
+    let num = (2 * d as u64 + 1) * (src_len as u64) * 256;
+    let den = 2 * dst_len as u64;
+    proof {
+        assert(num < den * (src_len * 256)) by (nonlinear_arith)
+            requires num == (2 * d + 1) * src_len * 256, den == 2 * dst_len, d < dst_len, src_len > 0;
+        assert(num / den < src_len * 256) by (nonlinear_arith)
+            requires num < den * (src_len * 256), den > 0;
+    }
+    let center = num / den;
+    // Shift by half a source pixel so weights are relative to pixel centers
+    let pos = if center >= 128 { center - 128 } else { 0 };
+    let s0 = (pos / 256) as u32;
+    let s1 = if s0 + 1 < src_len { s0 + 1 } else { s0 };
+    (s0, s1, (pos % 256) as u32)
 }
 
-/// Display PD memory regions
-pub const DISPLAY_PD_FB_BASE: usize = 0x5_0001_0000;
-pub const DISPLAY_PD_FB_SIZE: usize = 0x100_0000;
-pub const DISPLAY_PD_MAILBOX_BASE: usize = 0x5_0000_0000;
-pub const DISPLAY_PD_MAILBOX_SIZE: usize = 0x1000;
+/// Pixel of `src` (stored `src_w` x `src_h`) shown at (`x`, `y`) once
+/// turned upright by `orient`
+fn oriented_pixel(src: &[u32], x: u32, y: u32, src_w: u32, src_h: u32, orient: Orientation) -> u32
+    requires
+        0 < src_w <= MAX_BLIT_DIM,
+        0 < src_h <= MAX_BLIT_DIM,
+        src_w * src_h <= src@.len(),
+        if orient.spec_transposed() { x < src_h && y < src_w } else { x < src_w && y < src_h },
+{
+    let (sx, sy) = orient_source(x, y, src_w, src_h, orient);
+    src[surface_index(sx, sy, src_w, src_h)]
+}
 
-/// Specification: can Display PD access this address?
-pub open spec fn display_pd_can_access(addr: usize) -> bool {
-    // Framebuffer (render)
-    (addr >= DISPLAY_PD_FB_BASE && addr < DISPLAY_PD_FB_BASE + DISPLAY_PD_FB_SIZE) ||
-    // GPU mailbox
-    (addr >= DISPLAY_PD_MAILBOX_BASE && addr < DISPLAY_PD_MAILBOX_BASE + DISPLAY_PD_MAILBOX_SIZE) ||
-    // Pixel buffer (read decoded images)
-    in_pixel_buffer_region(addr) ||
-    // Command ring (receive commands)
-    in_cmd_ring_region(addr)
+/// Blend two 8-bit channels: `a` weighted by `256 - t`, `b` by `t`
+pub fn lerp_channel(a: u32, b: u32, t: u32) -> (c: u32)
+    requires
+        a <= 255,
+        b <= 255,
+        t <= 256,
+    ensures c <= 255,
+{
+    proof {
+        assert(a * (256 - t) + b * t <= 255 * 256) by (nonlinear_arith)
+            requires a <= 255, b <= 255, t <= 256;
+    }
+    (a * (256 - t) + b * t) / 256
 }
 
-// ============================================================================
-// ISOLATION PROOFS
-// ============================================================================
+/// 8-bit channel of an ARGB pixel at bit offset `shift`
+fn argb_channel(p: u32, shift: u32) -> (c: u32)
+    requires shift < 32,
+    ensures c <= 255,
+{
+    let c = (p >> shift) & 0xFF;
+    assert(c <= 255) by (bit_vector)
+        requires c == (p >> shift) & 0xFFu32;
+    c
+}
 
-/// Prove: Decoder PD cannot access framebuffer
-/// This is the key security property - a compromised decoder cannot draw directly
-proof fn decoder_cannot_access_framebuffer()
-    ensures
-        forall|addr: usize|
-            (addr >= DISPLAY_PD_FB_BASE && addr < DISPLAY_PD_FB_BASE + DISPLAY_PD_FB_SIZE)
-            ==> !decoder_pd_can_access(addr)
+/// Blend two ARGB pixels channel by channel
+pub fn lerp_argb(p: u32, q: u32, t: u32) -> (out: u32)
+    requires t <= 256,
 {
-    // Decoder only has access to pixel buffer and photo data regions
-    // Framebuffer is in a completely separate address range
+    let a = lerp_channel(argb_channel(p, 24), argb_channel(q, 24), t);
+    let r = lerp_channel(argb_channel(p, 16), argb_channel(q, 16), t);
+    let g = lerp_channel(argb_channel(p, 8), argb_channel(q, 8), t);
+    let b = lerp_channel(argb_channel(p, 0), argb_channel(q, 0), t);
+    (a << 24) | (r << 16) | (g << 8) | b
 }
 
-/// Prove: Decoder PD cannot access storage
-/// Prevents exfiltration if decoder is compromised
-proof fn decoder_cannot_access_storage()
+/// Largest rectangle with the source's aspect ratio that fits a
+/// `dst_w` x `dst_h` surface, centered (the rest is letterbox/pillarbox)
+pub fn letterbox_rect(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> (rect: Option<BlitRect>)
     ensures
-        forall|addr: usize|
-            // Storage region (hypothetical)
-            (addr >= 0x5_0800_0000 && addr < 0x5_0900_0000)
-            ==> !decoder_pd_can_access(addr)
+        rect.is_some() <==> (
+            0 < src_w <= MAX_BLIT_DIM && 0 < src_h <= MAX_BLIT_DIM &&
+            0 < dst_w <= MAX_BLIT_DIM && 0 < dst_h <= MAX_BLIT_DIM
+        ),
+        rect.is_some() ==> rect.unwrap().within(dst_w, dst_h),
 {
-    // Decoder regions don't overlap with storage
+    if src_w == 0 || src_h == 0 || dst_w == 0 || dst_h == 0
+        || src_w > MAX_BLIT_DIM || src_h > MAX_BLIT_DIM
+        || dst_w > MAX_BLIT_DIM || dst_h > MAX_BLIT_DIM
+    {
+        return None;
+    }
+
+    let wide = (src_w as u64) * (dst_h as u64);
+    let tall = (src_h as u64) * (dst_w as u64);
+    let (w, h) = if wide >= tall {
+        // Source is relatively wider: full width, bars top and bottom
+        let h = tall / (src_w as u64);
+        proof {
+            assert(h <= dst_h) by (nonlinear_arith)
+                requires h == tall / (src_w as u64), tall <= (src_w as u64) * dst_h, src_w > 0;
+        }
+        (dst_w, if h == 0 { 1 } else { h as u32 })
+    } else {
+        // Source is relatively taller: full height, bars left and right
+        let w = wide / (src_h as u64);
+        proof {
+            assert(w <= dst_w) by (nonlinear_arith)
+                requires w == wide / (src_h as u64), wide < (src_h as u64) * dst_w, src_h > 0;
+        }
+        (if w == 0 { 1 } else { w as u32 }, dst_h)
+    };
+
+    Some(BlitRect { x: (dst_w - w) / 2, y: (dst_h - h) / 2, w, h })
 }
 
-/// Prove: Only pixel buffer is shared between Decoder and Display
-proof fn decoder_display_only_share_pixel_buffer()
+/// Scale `src` (`src_w` x `src_h` ARGB32, row-major) into `rect` of `dst`,
+/// a surface `dst_stride` pixels wide and `dst_h` rows tall, turned upright
+/// by `orient` on the way (`rect` is in upright proportions).
+///
+/// Pixels of `dst` outside `rect` are left untouched. Parameters are checked
+/// up front; on error nothing is written.
+#[allow(clippy::too_many_arguments)]
+pub fn blit_scaled(
+    src: &[u32], src_w: u32, src_h: u32, orient: Orientation,
+    dst: &mut [u32], dst_stride: u32, dst_h: u32,
+    rect: BlitRect, filter: ScaleFilter,
+) -> (result: Result<(), BlitError>)
     ensures
-        forall|addr: usize|
-            (decoder_pd_can_access(addr) && display_pd_can_access(addr))
-            ==> in_pixel_buffer_region(addr)
+        result.is_ok() <==> valid_scaled_blit(
+            src@.len() as int, src_w, src_h,
+            old(dst)@.len() as int, dst_stride, dst_h, rect),
+        dst@.len() == old(dst)@.len(),
 {
-    // The only overlapping region is the pixel buffer
-}
+    if src_w == 0 || src_h == 0 || src_w > MAX_BLIT_DIM || src_h > MAX_BLIT_DIM {
+        return Err(BlitError::BadSourceSize);
+    }
+    if (src_w as u64) * (src_h as u64) > src.len() as u64 {
+        return Err(BlitError::SourceTooShort);
+    }
+    if rect.w == 0 || rect.h == 0 || rect.w > MAX_BLIT_DIM || rect.h > MAX_BLIT_DIM
+        || rect.x as u64 + rect.w as u64 > dst_stride as u64
+        || rect.y as u64 + rect.h as u64 > dst_h as u64
+    {
+        return Err(BlitError::RectOutOfBounds);
+    }
+    if (dst_stride as u64) * (dst_h as u64) > dst.len() as u64 {
+        return Err(BlitError::DestTooShort);
+    }
 
-} // verus!
+    // Sample coordinates are in the upright image
+    let (view_w, view_h) = orient.upright_size(src_w, src_h);
+
+    let mut dy: u32 = 0;
+    while dy < rect.h
+        invariant
+            valid_scaled_blit(src@.len() as int, src_w, src_h, dst@.len() as int, dst_stride, dst_h, rect),
+            dst@.len() == old(dst)@.len(),
+            (view_w, view_h) == (if orient.spec_transposed() { (src_h, src_w) } else { (src_w, src_h) }),
+            dy <= rect.h,
+        decreases rect.h - dy,
+    {
+        let (y0, y1, ty) = match filter {
+            ScaleFilter::Nearest => (nearest_coord(dy, rect.h, view_h), 0, 0),
+            ScaleFilter::Bilinear => bilinear_coord(dy, rect.h, view_h),
+        };
+
+        let mut dx: u32 = 0;
+        while dx < rect.w
+            invariant
+                valid_scaled_blit(src@.len() as int, src_w, src_h, dst@.len() as int, dst_stride, dst_h, rect),
+                dst@.len() == old(dst)@.len(),
+                (view_w, view_h) == (if orient.spec_transposed() { (src_h, src_w) } else { (src_w, src_h) }),
+                dy < rect.h,
+                dx <= rect.w,
+                y0 < view_h,
+                y1 < view_h,
+                ty < 256,
+            decreases rect.w - dx,
+        {
+            let pixel = match filter {
+                ScaleFilter::Nearest => {
+                    let x0 = nearest_coord(dx, rect.w, view_w);
+                    oriented_pixel(src, x0, y0, src_w, src_h, orient)
+                }
+                ScaleFilter::Bilinear => {
+                    let (x0, x1, tx) = bilinear_coord(dx, rect.w, view_w);
+                    let top = lerp_argb(
+                        oriented_pixel(src, x0, y0, src_w, src_h, orient),
+                        oriented_pixel(src, x1, y0, src_w, src_h, orient),
+                        tx,
+                    );
+                    let bottom = lerp_argb(
+                        oriented_pixel(src, x0, y1, src_w, src_h, orient),
+                        oriented_pixel(src, x1, y1, src_w, src_h, orient),
+                        tx,
+                    );
+                    lerp_argb(top, bottom, ty)
+                }
+            };
+            dst[surface_index(rect.x + dx, rect.y + dy, dst_stride, dst_h)] = pixel;
+            dx = dx + 1;
+        }
+        dy = dy + 1;
+    }
+    Ok(())
+}
 
 // ============================================================================
-// NON-VERIFIED RUNTIME HELPERS
+// SLIDESHOW TRANSITIONS (Verified)
 // ============================================================================
+//
+// Between two photos the Display PD draws a few frames that mix the outgoing
+// photo with the incoming one. Both are screen-sized buffers; every index a
+// transition effect derives (slid, wiped or zoomed) is proven inside them.
+
+/// Slideshow transition effect between two screen-sized photos
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionEffect {
+    /// Blend the whole screen from the old photo to the new one
+    Crossfade,
+    /// The new photo pushes the old one out to the left
+    SlideLeft,
+    /// The new photo pushes the old one out to the right
+    SlideRight,
+    /// An edge sweeping left to right uncovers the new photo
+    Wipe,
+    /// The new photo fades in zoomed to 125%, panning and zooming out to rest
+    KenBurns,
+}
 
-use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+/// Transition progress (in 1/256ths) at which only the new photo shows
+pub const TRANSITION_END: u32 = 256;
 
-/// Runtime command ring header with atomics
-#[repr(C, align(16))]
-pub struct AtomicCommandRingHeader {
-    pub write_idx: AtomicU32,
-    pub read_idx: AtomicU32,
-    pub capacity: u32,
-    pub _pad: u32,
+/// Specification: a transition frame reads and writes only valid pixels
+pub open spec fn valid_transition(
+    from_len: int, to_len: int, dst_len: int,
+    width: u32, height: u32,
+) -> bool {
+    0 < width <= MAX_BLIT_DIM && 0 < height <= MAX_BLIT_DIM &&
+    width * height <= from_len &&
+    width * height <= to_len &&
+    width * height <= dst_len
 }
 
-impl AtomicCommandRingHeader {
-    /// Initialize at memory location
-    ///
-    /// # Safety
-    /// Pointer must be valid and properly aligned
-    pub unsafe fn init(ptr: *mut Self) {
-        (*ptr).write_idx = AtomicU32::new(0);
-        (*ptr).read_idx = AtomicU32::new(0);
-        (*ptr).capacity = CMD_RING_CAPACITY;
-        (*ptr)._pad = 0;
+/// How far a slide or wipe edge has moved across `len` pixels at progress `t`
+pub fn transition_offset(len: u32, t: u32) -> (offset: u32)
+    requires
+        len <= MAX_BLIT_DIM,
+        t <= TRANSITION_END,
+    ensures offset <= len,
+{
+    let moved = (len as u64) * (t as u64) / 256;
+    proof {
+        assert(moved <= len) by (nonlinear_arith)
+            requires moved == (len as u64) * (t as u64) / 256, t <= 256;
     }
+    moved as u32
+}
 
-    pub fn has_data(&self) -> bool {
-        let write = self.write_idx.load(Ordering::Acquire);
-        let read = self.read_idx.load(Ordering::Acquire);
-        write != read
+/// Pixel shown at column `x` of a slide whose edge has moved `offset` of
+/// `width`: whether it comes from the new photo, and its column there
+pub fn slide_source(x: u32, offset: u32, width: u32, leftwards: bool) -> (r: (bool, u32))
+    requires
+        x < width,
+        offset <= width,
+    ensures r.1 < width,
+{
+    if leftwards {
+        // The old photo has moved `offset` left; the new one follows it in
+        if x < width - offset { (false, x + offset) } else { (true, x - (width - offset)) }
+    } else {
+        if x < offset { (true, x + (width - offset)) } else { (false, x - offset) }
     }
+}
 
-    pub fn is_full(&self) -> bool {
-        let write = self.write_idx.load(Ordering::Acquire);
-        let read = self.read_idx.load(Ordering::Acquire);
-        ((write + 1) % self.capacity) == read
+/// Visible window (length, origin) along a `len`-pixel axis of a Ken Burns
+/// frame at progress `t`: 1/1.25 of the axis at the far end to start with,
+/// growing to all of it as it pans back to the origin
+pub fn ken_burns_window(len: u32, t: u32) -> (r: (u32, u32))
+    requires
+        0 < len <= MAX_BLIT_DIM,
+        t <= TRANSITION_END,
+    ensures
+        0 < r.0,
+        r.0 + r.1 <= len,
+{
+    // Zoom in 1/1024ths, from 1280 (125%) down to 1024
+    let zoom = 1280 - t as u64;
+    let fit = (len as u64) * 1024 / zoom;
+    proof {
+        assert(fit <= len) by (nonlinear_arith)
+            requires fit == (len as u64) * 1024 / zoom, zoom >= 1024;
     }
-
-    pub fn advance_write(&self) {
-        let next = (self.write_idx.load(Ordering::Acquire) + 1) % self.capacity;
-        self.write_idx.store(next, Ordering::Release);
+    let window = if fit == 0 { 1 } else { fit as u32 };
+    let slack = (len - window) as u64;
+    let origin = slack * ((TRANSITION_END - t) as u64) / 256;
+    proof {
+        assert(origin <= slack) by (nonlinear_arith)
+            requires origin == slack * ((TRANSITION_END - t) as u64) / 256, t <= 256;
     }
+    (window, origin as u32)
+}
 
-    pub fn advance_read(&self) {
-        let next = (self.read_idx.load(Ordering::Acquire) + 1) % self.capacity;
-        self.read_idx.store(next, Ordering::Release);
+/// Column or row of the new photo sampled for screen coordinate `d` when a
+/// `window`-long part of a `len`-pixel axis starting at `origin` is stretched
+/// over the whole axis
+pub fn zoom_coord(d: u32, len: u32, window: u32, origin: u32) -> (s: u32)
+    requires
+        d < len,
+        len <= MAX_BLIT_DIM,
+        0 < window,
+        origin + window <= len,
+    ensures s < len,
+{
+    let step = (d as u64) * (window as u64) / (len as u64);
+    proof {
+        assert(step < window) by (nonlinear_arith)
+            requires step == (d as u64) * (window as u64) / (len as u64), d < len, window > 0;
     }
+    origin + step as u32
+}
 
-    pub fn current_write_idx(&self) -> u32 {
-        self.write_idx.load(Ordering::Acquire)
+/// Draw frame `t` (clamped to `TRANSITION_END`) of `effect` from `from` to
+/// `to` into `dst`. All three are `width` x `height` ARGB32, row-major.
+///
+/// Parameters are checked up front; on error nothing is written.
+pub fn transition_frame(
+    from: &[u32], to: &[u32], dst: &mut [u32],
+    width: u32, height: u32,
+    effect: TransitionEffect, t: u32,
+) -> (result: Result<(), BlitError>)
+    ensures
+        result.is_ok() <==> valid_transition(
+            from@.len() as int, to@.len() as int, old(dst)@.len() as int, width, height),
+        dst@.len() == old(dst)@.len(),
+{
+    if width == 0 || height == 0 || width > MAX_BLIT_DIM || height > MAX_BLIT_DIM {
+        return Err(BlitError::BadSourceSize);
+    }
+    let pixels = (width as u64) * (height as u64);
+    if pixels > from.len() as u64 || pixels > to.len() as u64 {
+        return Err(BlitError::SourceTooShort);
+    }
+    if pixels > dst.len() as u64 {
+        return Err(BlitError::DestTooShort);
     }
 
-    pub fn current_read_idx(&self) -> u32 {
-        self.read_idx.load(Ordering::Acquire)
+    let t = if t > TRANSITION_END { TRANSITION_END } else { t };
+    let offset = transition_offset(width, t);
+    let leftwards = matches!(effect, TransitionEffect::SlideLeft);
+    let (win_w, org_x) = ken_burns_window(width, t);
+    let (win_h, org_y) = ken_burns_window(height, t);
+
+    let mut y: u32 = 0;
+    while y < height
+        invariant
+            valid_transition(from@.len() as int, to@.len() as int, dst@.len() as int, width, height),
+            dst@.len() == old(dst)@.len(),
+            y <= height,
+            t <= TRANSITION_END,
+            offset <= width,
+            0 < win_w, win_w + org_x <= width,
+            0 < win_h, win_h + org_y <= height,
+        decreases height - y,
+    {
+        let zy = zoom_coord(y, height, win_h, org_y);
+
+        let mut x: u32 = 0;
+        while x < width
+            invariant
+                valid_transition(from@.len() as int, to@.len() as int, dst@.len() as int, width, height),
+                dst@.len() == old(dst)@.len(),
+                y < height,
+                x <= width,
+                zy < height,
+                t <= TRANSITION_END,
+                offset <= width,
+                0 < win_w, win_w + org_x <= width,
+            decreases width - x,
+        {
+            let i = surface_index(x, y, width, height);
+            let pixel = match effect {
+                TransitionEffect::Crossfade => lerp_argb(from[i], to[i], t),
+                TransitionEffect::SlideLeft | TransitionEffect::SlideRight => {
+                    let (new, sx) = slide_source(x, offset, width, leftwards);
+                    let j = surface_index(sx, y, width, height);
+                    if new { to[j] } else { from[j] }
+                }
+                TransitionEffect::Wipe => if x < offset { to[i] } else { from[i] },
+                TransitionEffect::KenBurns => {
+                    let zx = zoom_coord(x, width, win_w, org_x);
+                    lerp_argb(from[i], to[surface_index(zx, zy, width, height)], t)
+                }
+            };
+            dst[i] = pixel;
+            x = x + 1;
+        }
+        y = y + 1;
     }
+    Ok(())
 }
 
-/// Runtime pixel buffer header with atomics
-#[repr(C, align(32))]
-pub struct AtomicPixelBufferHeader {
-    pub width: AtomicU32,
-    pub height: AtomicU32,
-    pub format: AtomicU8,
-    pub status: AtomicU8,
-    pub photo_index: u16,
-    pub data_len: AtomicU32,
+// ============================================================================
+// PLAYLIST SHUFFLE (Verified)
+// ============================================================================
+//
+// The slideshow shuffles its play order with Fisher-Yates. Both swap slots
+// are proven inside the shuffled prefix, so a shuffle can neither index
+// past the order array nor disturb the entries after `len`.
+
+/// Next state of the xorshift32 generator the shuffle draws from (a
+/// nonzero state never reaches 0)
+pub fn xorshift32(x: u32) -> u32 {
+    let x = x ^ (x << 13u32);
+    let x = x ^ (x >> 17u32);
+    x ^ (x << 5u32)
+}
+
+/// Slot to swap slot `i` with in a Fisher-Yates pass, from a random word
+pub fn shuffle_pick(i: usize, random: u32) -> (j: usize)
+    requires i < usize::MAX,
+    ensures j <= i,
+{
+    random as usize % (i + 1)
+}
+
+/// Shuffle `order[..len]` in place, drawing from the xorshift32 generator
+/// seeded with `state`; returns the generator's new state
+pub fn fisher_yates(order: &mut [u16], len: usize, state: u32) -> (next: u32)
+    requires len <= old(order)@.len(),
+    ensures
+        order@.len() == old(order)@.len(),
+        forall|k: int| len <= k < order@.len() ==> order@[k] == old(order)@[k],
+{
+    let mut state = state;
+    let mut i = len;
+    while i > 1
+        invariant
+            i <= len,
+            len <= order@.len(),
+            order@.len() == old(order)@.len(),
+            forall|k: int| len <= k < order@.len() ==> order@[k] == old(order)@[k],
+        decreases i,
+    {
+        i = i - 1;
+        state = xorshift32(state);
+        let j = shuffle_pick(i, state);
+        let tmp = order[i];
+        order[i] = order[j];
+        order[j] = tmp;
+    }
+    state
+}
+
+// ============================================================================
+// EXIF METADATA (Verified)
+// ============================================================================
+//
+// Photos from cameras and phones carry an EXIF block: a small TIFF file in
+// the JPEG's APP1 segment whose IFDs (tag directories) point at each other
+// and at their values by offset from the TIFF header. Those offsets come
+// straight from the untrusted file, so every IFD entry and value the
+// Decoder PD reads is located by the helpers below, proven to lie inside
+// the photo data it was handed.
+
+/// EXIF orientation of an upright image (also used for photos without one)
+pub const EXIF_ORIENTATION_NORMAL: u8 = 1;
+
+/// Camera model bytes carried in the pixel buffer header (longer models
+/// are cut short)
+pub const EXIF_MODEL_LEN: usize = 28;
+
+/// Bytes in one IFD entry: tag, type, count, then the value or its offset
+pub const IFD_ENTRY_SIZE: usize = 12;
+
+/// Most entries read from one IFD; any after them are ignored
+pub const EXIF_MAX_IFD_ENTRIES: u16 = 128;
+
+/// Specification: is this an EXIF orientation (1-8)?
+pub open spec fn valid_exif_orientation(orientation: u8) -> bool {
+    1 <= orientation && orientation <= 8
+}
+
+/// Start of the `len` bytes at `offset` from a TIFF header at `base` in a
+/// `buf_len`-byte buffer, or `None` if any of them fall outside it
+pub fn tiff_range(base: usize, offset: u32, len: usize, buf_len: usize) -> (start: Option<usize>)
+    ensures
+        start.is_some() <==> base + offset + len <= buf_len,
+        start.is_some() ==> start.unwrap() == base + offset && start.unwrap() + len <= buf_len,
+{
+    if base > buf_len {
+        return None;
+    }
+    let room = buf_len - base;
+    let offset = offset as usize;
+    if offset > room || len > room - offset { None } else { Some(base + offset) }
+}
+
+/// Start of entry `index` of the IFD at TIFF offset `ifd`, or `None` if the
+/// entry runs past the buffer
+pub fn ifd_entry(base: usize, ifd: u32, index: u16, buf_len: usize) -> (entry: Option<usize>)
+    requires index < EXIF_MAX_IFD_ENTRIES,
+    ensures entry.is_some() ==> entry.unwrap() + IFD_ENTRY_SIZE <= buf_len,
+{
+    // Entries follow the 2-byte entry count
+    let skip = 2 + IFD_ENTRY_SIZE * (index as usize);
+    match tiff_range(base, ifd, skip + IFD_ENTRY_SIZE, buf_len) {
+        Some(start) => Some(start + skip),
+        None => None,
+    }
+}
+
+/// Start of the `len`-byte value of the IFD entry at `entry`: in the entry
+/// itself when it fits in 4 bytes, else at TIFF offset `value_offset`
+pub fn ifd_value(base: usize, entry: usize, value_offset: u32, len: usize, buf_len: usize) -> (start: Option<usize>)
+    requires entry + IFD_ENTRY_SIZE <= buf_len,
+    ensures start.is_some() ==> start.unwrap() + len <= buf_len,
+{
+    if len <= 4 { Some(entry + 8) } else { tiff_range(base, value_offset, len, buf_len) }
+}
+
+/// When a photo was taken, to the minute
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaptureTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl CaptureTime {
+    /// Pack for `PixelBufferHeader::taken_at`: the year in the top 12 bits,
+    /// then month (4), day (5), hour (5) and minute (6)
+    pub fn pack(self) -> u32 {
+        (((self.year as u32) & 0xFFF) << 20u32)
+            | (((self.month as u32) & 0xF) << 16u32)
+            | (((self.day as u32) & 0x1F) << 11u32)
+            | (((self.hour as u32) & 0x1F) << 6u32)
+            | ((self.minute as u32) & 0x3F)
+    }
+
+    /// Unpack a `taken_at`; `None` for 0 (unknown)
+    pub fn unpack(packed: u32) -> Option<Self> {
+        if packed == 0 {
+            return None;
+        }
+        Some(CaptureTime {
+            year: (packed >> 20u32) as u16,
+            month: ((packed >> 16u32) & 0xF) as u8,
+            day: ((packed >> 11u32) & 0x1F) as u8,
+            hour: ((packed >> 6u32) & 0x1F) as u8,
+            minute: (packed & 0x3F) as u8,
+        })
+    }
+}
+
+// ============================================================================
+// DECODER OUTPUT INDEXING (Verified)
+// ============================================================================
+//
+// The Decoder PD's PNG path inflates attacker-controlled zlib data. Every
+// index it derives from that data - sliding-window positions, back-reference
+// sources, scanline strides and output pixel slots - goes through one of the
+// helpers below, so a hostile stream can at worst produce wrong pixels, never
+// an out-of-range write.
+
+/// DEFLATE sliding window size (RFC 1951: back-references reach ≤ 32 KiB)
+pub const INFLATE_WINDOW_SIZE: usize = 32768;
+
+/// Widest image the decoder computes scanline strides for
+pub const MAX_DECODE_WIDTH: u32 = 0xFFFF;
+
+/// Specification: bytes per pixel for a pixel buffer format
+pub open spec fn format_bpp_spec(fmt: u8) -> u32 {
+    if fmt == PIXEL_FORMAT_RGBA32 { 4 }
+    else if fmt == PIXEL_FORMAT_RGB24 { 3 }
+    else { 2 }
+}
+
+/// Bytes per pixel for a pixel buffer format
+pub fn format_bytes_per_pixel(fmt: u8) -> (bpp: u32)
+    requires valid_pixel_format(fmt),
+    ensures
+        bpp == format_bpp_spec(fmt),
+        2 <= bpp <= 4,
+{
+    if fmt == PIXEL_FORMAT_RGBA32 { 4 }
+    else if fmt == PIXEL_FORMAT_RGB24 { 3 }
+    else { 2 }
+}
+
+/// Advance a sliding-window cursor by one byte, wrapping at the window end
+pub fn window_next(pos: usize) -> (next: usize)
+    requires pos < INFLATE_WINDOW_SIZE,
+    ensures
+        next < INFLATE_WINDOW_SIZE,
+        next == (pos + 1) % INFLATE_WINDOW_SIZE,
+{
+    ring_next_usize(pos, INFLATE_WINDOW_SIZE)
+}
+
+/// Resolve a back-reference `distance` bytes behind the window cursor.
+///
+/// `produced` is the number of bytes emitted so far; a reference reaching
+/// before the start of the stream (or past the window) is rejected.
+pub fn window_back_ref(pos: usize, distance: usize, produced: usize) -> (src: Option<usize>)
+    requires pos < INFLATE_WINDOW_SIZE,
+    ensures
+        src.is_some() <==> (distance >= 1 && distance <= INFLATE_WINDOW_SIZE && distance <= produced),
+        src.is_some() ==> src.unwrap() < INFLATE_WINDOW_SIZE,
+        src.is_some() ==> src.unwrap() == (pos + INFLATE_WINDOW_SIZE - distance) % (INFLATE_WINDOW_SIZE as int),
+{
+    if distance == 0 || distance > INFLATE_WINDOW_SIZE || distance > produced {
+        None
+    } else {
+        Some(ring_add_usize(pos, INFLATE_WINDOW_SIZE - distance, INFLATE_WINDOW_SIZE))
+    }
+}
+
+/// Unfiltered scanline length in bytes (`width * bpp`) for a pixel format
+pub fn scanline_bytes(width: u32, fmt: u8) -> (len: Option<usize>)
+    requires valid_pixel_format(fmt),
+    ensures
+        len.is_some() <==> (width > 0 && width <= MAX_DECODE_WIDTH),
+        len.is_some() ==> len.unwrap() == width * format_bpp_spec(fmt),
+        len.is_some() ==> len.unwrap() <= MAX_DECODE_WIDTH * 4,
+{
+    if width == 0 || width > MAX_DECODE_WIDTH {
+        None
+    } else if fmt == PIXEL_FORMAT_RGBA32 {
+        Some((width * 4) as usize)
+    } else if fmt == PIXEL_FORMAT_RGB24 {
+        Some((width * 3) as usize)
+    } else {
+        Some((width * 2) as usize)
+    }
+}
+
+/// Output slot for pixel `x` of a row starting at `row_base`, or `None` if
+/// it would land outside a buffer of `buf_len` pixels
+pub fn row_pixel_index(row_base: usize, x: usize, buf_len: usize) -> (idx: Option<usize>)
+    ensures
+        idx.is_some() <==> row_base + x < buf_len,
+        idx.is_some() ==> idx.unwrap() == row_base + x,
+{
+    if row_base >= buf_len || x >= buf_len - row_base {
+        None
+    } else {
+        Some(row_base + x)
+    }
+}
+
+/// Start of the next output row, or `None` if it would overflow
+pub fn next_row_base(row_base: usize, width: usize) -> (next: Option<usize>)
+    ensures
+        next.is_some() <==> row_base + width <= usize::MAX,
+        next.is_some() ==> next.unwrap() == row_base + width,
+{
+    if width > usize::MAX - row_base {
+        None
+    } else {
+        Some(row_base + width)
+    }
+}
+
+// ============================================================================
+// GIF LZW TABLE INDEXING (Verified)
+// ============================================================================
+//
+// The Decoder PD's GIF path rebuilds an LZW string table from
+// attacker-controlled codes. Every code it looks up, every entry it adds and
+// every byte it stacks while expanding a string goes through the helpers
+// below, so a hostile stream can at worst produce wrong pixels, never an
+// access outside the 4096-entry table.
+
+/// LZW string table entries (GIF codes are at most 12 bits)
+pub const LZW_TABLE_SIZE: usize = 4096;
+
+/// Widest LZW code in a GIF stream, in bits
+pub const LZW_MAX_CODE_BITS: u32 = 12;
+
+/// Largest LZW minimum code size a GIF image may declare
+pub const LZW_MAX_MIN_CODE_SIZE: u8 = 8;
+
+/// Table slot holding `code`, or `None` if the table (`next_code` entries
+/// so far) has no such entry yet
+pub fn lzw_lookup(code: u16, next_code: u16) -> (slot: Option<usize>)
+    requires next_code as usize <= LZW_TABLE_SIZE,
+    ensures
+        slot.is_some() <==> code < next_code,
+        slot.is_some() ==> slot.unwrap() == code && slot.unwrap() < LZW_TABLE_SIZE,
+{
+    if code < next_code { Some(code as usize) } else { None }
+}
+
+/// Slot for the next table entry, or `None` once all 4096 are in use (GIF
+/// decoders then stop adding entries until the next clear code)
+pub fn lzw_add(next_code: u16) -> (slot: Option<usize>)
+    ensures
+        slot.is_some() <==> (next_code as usize) < LZW_TABLE_SIZE,
+        slot.is_some() ==> slot.unwrap() == next_code,
+{
+    if (next_code as usize) < LZW_TABLE_SIZE { Some(next_code as usize) } else { None }
+}
+
+/// Code width for reading the next code, once the table holds `next_code`
+/// entries and codes were `bits` wide: one bit wider when the table has
+/// outgrown them, never past 12
+pub fn lzw_code_bits(next_code: u16, bits: u32) -> (next_bits: u32)
+    requires 0 < bits <= LZW_MAX_CODE_BITS,
+    ensures
+        bits <= next_bits <= LZW_MAX_CODE_BITS,
+        next_bits <= bits + 1,
+{
+    if bits < LZW_MAX_CODE_BITS && next_code as u32 == (1u32 << bits) { bits + 1 } else { bits }
+}
+
+/// Stack slot for the `depth`th byte of an expanded string, or `None` if
+/// the string is longer than any the table can hold
+pub fn lzw_stack_slot(depth: usize) -> (slot: Option<usize>)
+    ensures
+        slot.is_some() <==> depth < LZW_TABLE_SIZE,
+        slot.is_some() ==> slot.unwrap() == depth,
+{
+    if depth < LZW_TABLE_SIZE { Some(depth) } else { None }
+}
+
+// ============================================================================
+// COMMAND RING BUFFER
+// ============================================================================
+//
+// A `verified_microkernel::ring` ring of `PhotoCommand`s: its index model
+// is `CommandRingHeader`, and the runtime `AtomicCommandRingHeader` moves
+// commands through a `CommandRing` view.
+
+/// Command ring capacity
+#[cfg(not(feature = "pow2-capacity"))]
+pub const CMD_RING_CAPACITY: u32 = 500;
+
+/// Command ring capacity: the largest power of two that fits in the ring
+/// region, so index wrap-around is a mask
+#[cfg(feature = "pow2-capacity")]
+pub const CMD_RING_CAPACITY: u32 = 256;
+
+/// Command entry size
+pub const CMD_ENTRY_SIZE: usize = 8;
+
+/// Command ring header size
+pub const CMD_HEADER_SIZE: usize = 16;
+
+/// Command ring buffer shared memory size (4KB)
+pub const CMD_RING_SIZE: usize = 0x1000;
+
+// ============================================================================
+// TELEMETRY LOG
+// ============================================================================
+//
+// Periodic health samples (SoC temperature, firmware throttle flags, frame
+// statistics) for long-running reliability studies. Unlike the command ring
+// this is a log, not a queue: the writer never waits, the oldest record is
+// overwritten, and `written` counts every record ever appended, so a reader
+// (the Storage PD persisting it, or a debug dump) can tell which records it
+// has not seen yet and whether it fell behind.
+
+/// Telemetry log shared memory size (4KB)
+pub const TELEMETRY_LOG_SIZE: usize = 0x1000;
+
+/// Telemetry log header size
+pub const TELEMETRY_HEADER_SIZE: usize = 16;
+
+/// Telemetry record size
+pub const TELEMETRY_RECORD_SIZE: usize = 32;
+
+/// Records the log region holds
+pub const TELEMETRY_CAPACITY: u32 = 127;
+
+/// Header magic ("TLM1"); anything else means the region holds no log yet
+pub const TELEMETRY_MAGIC: u32 = 0x314D_4C54;
+
+/// One telemetry sample
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct TelemetryRecord {
+    /// Position in the log (assigned on append)
+    pub seq: u32,
+    /// Seconds since the PD started
+    pub uptime_s: u32,
+    /// SoC temperature in millidegrees Celsius
+    pub temp_milli_c: i32,
+    /// Firmware throttle flags (mailbox GET_THROTTLED)
+    pub throttled: u32,
+    /// Frames drawn since the PD started
+    pub frames: u32,
+    /// Timer deadlines skipped because the PD was busy
+    pub missed_ticks: u32,
+    /// Photo on screen when the sample was taken
+    pub photo_index: u32,
+    pub _reserved: u32,
+}
+
+/// Telemetry log header
+#[derive(Clone, Copy, Debug)]
+#[repr(C, align(16))]
+pub struct TelemetryLogHeader {
+    pub magic: u32,
+    pub capacity: u32,
+    /// Records appended since the log was created (the next `seq`)
+    pub written: u32,
+    pub _pad: u32,
+}
+
+impl TelemetryLogHeader {
+    /// Specification: does the header describe a log?
+    pub open spec fn valid(&self) -> bool {
+        self.magic == TELEMETRY_MAGIC &&
+        self.capacity > 0 &&
+        self.capacity <= TELEMETRY_CAPACITY
+    }
+
+    /// Specification: number of records still in the log
+    pub open spec fn retained_spec(&self) -> u32 {
+        if self.written < self.capacity { self.written } else { self.capacity }
+    }
+
+    /// An empty log
+    pub fn new() -> (header: Self)
+        ensures
+            header.valid(),
+            header.written == 0,
+    {
+        TelemetryLogHeader { magic: TELEMETRY_MAGIC, capacity: TELEMETRY_CAPACITY, written: 0, _pad: 0 }
+    }
+
+    /// Does the header describe a log (e.g. one kept across a PD restart)?
+    pub fn is_valid(&self) -> (valid: bool)
+        ensures valid == self.valid(),
+    {
+        self.magic == TELEMETRY_MAGIC && self.capacity > 0 && self.capacity <= TELEMETRY_CAPACITY
+    }
+
+    /// Number of records still in the log
+    pub fn retained(&self) -> (n: u32)
+        requires self.valid(),
+        ensures
+            n == self.retained_spec(),
+            n <= self.capacity,
+    {
+        if self.written < self.capacity { self.written } else { self.capacity }
+    }
+
+    /// Slot holding record `seq`
+    pub fn slot(&self, seq: u32) -> (slot: u32)
+        requires self.valid(),
+        ensures
+            slot == seq % self.capacity,
+            slot < self.capacity,
+    {
+        ring_mod(seq, self.capacity)
+    }
+}
+
+// ============================================================================
+// THUMBNAIL RING (Verified)
+// ============================================================================
+//
+// The browse screen shows a grid of downscaled photos. The Decoder PD
+// decodes each photo once more, scales it into the next slot of this ring
+// and stamps the slot with the photo's index; the Display PD looks photos
+// up by index and draws whichever slots hold them. Like the telemetry log
+// it is written round and round, the oldest thumbnail overwritten, and a
+// page of the grid fits in it at once. Every slot and pixel offset below is
+// proven inside the region.
+
+/// Thumbnail size: 16:9 like the screen
+pub const THUMB_W: u32 = 192;
+pub const THUMB_H: u32 = 108;
+
+/// Pixels in one thumbnail
+pub const THUMB_PIXELS: usize = (THUMB_W * THUMB_H) as usize;
+
+/// Thumbnail ring shared memory size (2MB)
+pub const THUMB_RING_SIZE: usize = 0x20_0000;
+
+/// Ring header size, padded so the slots start on a page
+pub const THUMB_HEADER_SIZE: usize = 0x1000;
+
+/// Slot header size; the pixels follow it
+pub const THUMB_SLOT_HEADER_SIZE: usize = 16;
+
+/// Bytes per slot: header and pixels, rounded up to a page
+pub const THUMB_SLOT_SIZE: usize = 0x1_5000;
+
+/// Slots in the ring (one full page of the grid)
+pub const THUMB_SLOTS: u32 = 20;
+
+/// Header magic ("THM1")
+pub const THUMB_MAGIC: u32 = 0x314D_4854;
+
+/// Slot states
+pub const THUMB_EMPTY: u8 = 0;
+pub const THUMB_READY: u8 = 1;
+/// The photo was refused by the decode pipeline; the grid shows a
+/// placeholder instead of decoding it again
+pub const THUMB_FAILED: u8 = 2;
+
+/// Grid layout: columns and rows per page
+pub const GRID_COLS: u32 = 5;
+pub const GRID_ROWS: u32 = 4;
+
+/// Photos per page of the grid
+pub const GRID_PAGE: u32 = 20;
+
+/// Thumbnail ring header
+#[derive(Clone, Copy, Debug)]
+#[repr(C, align(16))]
+pub struct ThumbnailRingHeader {
+    pub magic: u32,
+    pub slots: u32,
+    /// Thumbnails written since the ring was created
+    pub written: u32,
+    pub _pad: u32,
+}
+
+impl ThumbnailRingHeader {
+    /// Specification: does the header describe a ring?
+    pub open spec fn valid(&self) -> bool {
+        self.magic == THUMB_MAGIC && self.slots == THUMB_SLOTS
+    }
+
+    /// An empty ring
+    pub fn new() -> (header: Self)
+        ensures
+            header.valid(),
+            header.written == 0,
+    {
+        ThumbnailRingHeader { magic: THUMB_MAGIC, slots: THUMB_SLOTS, written: 0, _pad: 0 }
+    }
+
+    /// Does the header describe a ring?
+    pub fn is_valid(&self) -> (valid: bool)
+        ensures valid == self.valid(),
+    {
+        self.magic == THUMB_MAGIC && self.slots == THUMB_SLOTS
+    }
+
+    /// Slot the `seq`th thumbnail goes in
+    pub fn slot(&self, seq: u32) -> (slot: u32)
+        ensures
+            slot == seq % THUMB_SLOTS,
+            slot < THUMB_SLOTS,
+    {
+        ring_mod(seq, THUMB_SLOTS)
+    }
+}
+
+/// Header of one thumbnail slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C, align(16))]
+pub struct ThumbnailSlot {
+    /// Photo the slot holds
+    pub photo_index: u16,
+    /// `THUMB_EMPTY`, `THUMB_READY` or `THUMB_FAILED`
+    pub status: u8,
+    pub _pad: u8,
+    /// Part of the slot's `THUMB_W` x `THUMB_H` pixels the photo covers
+    /// (letterboxed to its aspect ratio and centered; the margins are
+    /// background)
+    pub width: u16,
+    pub height: u16,
+    /// Ring position it was written at
+    pub seq: u32,
+    pub _reserved: u32,
+}
+
+impl ThumbnailSlot {
+    /// Specification: is the slot's picture inside the slot?
+    pub open spec fn valid(&self) -> bool {
+        self.status == THUMB_EMPTY ||
+        self.status == THUMB_FAILED ||
+        (self.status == THUMB_READY &&
+         0 < self.width <= THUMB_W && 0 < self.height <= THUMB_H)
+    }
+
+    /// An unused slot
+    pub fn empty() -> (slot: Self)
+        ensures slot.valid(), slot.status == THUMB_EMPTY,
+    {
+        ThumbnailSlot { photo_index: 0, status: THUMB_EMPTY, _pad: 0, width: 0, height: 0, seq: 0, _reserved: 0 }
+    }
+}
+
+/// Byte offset of slot `slot`'s header in the ring region
+pub fn thumb_slot_offset(slot: u32) -> (off: usize)
+    requires slot < THUMB_SLOTS,
+    ensures
+        off == THUMB_HEADER_SIZE + slot * THUMB_SLOT_SIZE,
+        off + THUMB_SLOT_SIZE <= THUMB_RING_SIZE,
+        off % 16 == 0,
+{
+    proof {
+        assert(THUMB_HEADER_SIZE + slot * THUMB_SLOT_SIZE + THUMB_SLOT_SIZE <= THUMB_RING_SIZE) by (nonlinear_arith)
+            requires slot < THUMB_SLOTS;
+        assert((THUMB_HEADER_SIZE + slot * THUMB_SLOT_SIZE) % 16 == 0) by (nonlinear_arith);
+    }
+    THUMB_HEADER_SIZE + (slot as usize) * THUMB_SLOT_SIZE
+}
+
+/// Byte offset of pixel (`x`, `y`) of slot `slot` in the ring region
+pub fn thumb_pixel_offset(slot: u32, x: u32, y: u32) -> (off: usize)
+    requires
+        slot < THUMB_SLOTS,
+        x < THUMB_W,
+        y < THUMB_H,
+    ensures
+        off + 4 <= THUMB_RING_SIZE,
+        off % 4 == 0,
+{
+    let pixel = surface_index(x, y, THUMB_W, THUMB_H);
+    proof {
+        assert(THUMB_SLOT_HEADER_SIZE + (pixel + 1) * 4 <= THUMB_SLOT_SIZE) by (nonlinear_arith)
+            requires pixel < THUMB_W * THUMB_H;
+    }
+    thumb_slot_offset(slot) + THUMB_SLOT_HEADER_SIZE + pixel * 4
+}
+
+/// First photo on the grid page showing `selected`
+pub fn grid_page_start(selected: u32) -> (start: u32)
+    ensures
+        start <= selected,
+        selected - start < GRID_PAGE,
+        start % GRID_PAGE == 0,
+{
+    selected - selected % GRID_PAGE
+}
+
+/// Column and row of `selected` on its page
+pub fn grid_cell(selected: u32) -> (cell: (u32, u32))
+    ensures
+        cell.0 < GRID_COLS,
+        cell.1 < GRID_ROWS,
+{
+    let on_page = selected % GRID_PAGE;
+    (on_page % GRID_COLS, on_page / GRID_COLS)
+}
+
+/// Arrow-key moves through the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridMove {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The selection after moving from `selected` through a grid of `count`
+/// photos. Left and right run on across rows and pages, up and down move a
+/// row (onto the next page at the bottom); moves off either end stay put,
+/// and down into the ragged last row lands on the last photo.
+pub fn grid_move(selected: u32, count: u32, dir: GridMove) -> (next: u32)
+    requires selected < count,
+    ensures next < count,
+{
+    match dir {
+        GridMove::Left => if selected > 0 { selected - 1 } else { selected },
+        GridMove::Right => if selected + 1 < count { selected + 1 } else { selected },
+        GridMove::Up => if selected >= GRID_COLS { selected - GRID_COLS } else { selected },
+        GridMove::Down => {
+            if count - selected > GRID_COLS {
+                selected + GRID_COLS
+            } else if selected / GRID_COLS < (count - 1) / GRID_COLS {
+                count - 1
+            } else {
+                selected
+            }
+        }
+    }
+}
+
+// ============================================================================
+// MEMORY REGION DEFINITIONS
+// ============================================================================
+
+/// Virtual address for command ring buffer (shared: Input, Timer, Display)
+pub const CMD_RING_VADDR: usize = 0x5_0500_0000;
+
+/// Virtual address for pixel buffer (shared: Decoder, Display)
+pub const PIXEL_BUFFER_VADDR: usize = 0x5_0600_0000;
+
+/// Pixel buffer size (8MB for 1920x1080 RGBA + header)
+pub const PIXEL_BUFFER_SIZE: usize = 0x80_0000;
+
+/// Virtual address for the telemetry log (shared: Display, Storage)
+pub const TELEMETRY_LOG_VADDR: usize = 0x5_0B00_0000;
+
+/// Virtual address for the thumbnail ring (shared: Decoder, Display)
+pub const THUMB_RING_VADDR: usize = 0x5_0C00_0000;
+
+/// Specification: is address in command ring region?
+pub open spec fn in_cmd_ring_region(addr: usize) -> bool {
+    addr >= CMD_RING_VADDR && addr < CMD_RING_VADDR + CMD_RING_SIZE
+}
+
+/// Specification: is address in pixel buffer region?
+pub open spec fn in_pixel_buffer_region(addr: usize) -> bool {
+    addr >= PIXEL_BUFFER_VADDR && addr < PIXEL_BUFFER_VADDR + PIXEL_BUFFER_SIZE
+}
+
+/// Specification: is address in telemetry log region?
+pub open spec fn in_telemetry_log_region(addr: usize) -> bool {
+    addr >= TELEMETRY_LOG_VADDR && addr < TELEMETRY_LOG_VADDR + TELEMETRY_LOG_SIZE
+}
+
+/// Specification: is address in thumbnail ring region?
+pub open spec fn in_thumb_ring_region(addr: usize) -> bool {
+    addr >= THUMB_RING_VADDR && addr < THUMB_RING_VADDR + THUMB_RING_SIZE
+}
+
+// ============================================================================
+// PROTECTION DOMAIN ISOLATION SPECIFICATIONS
+// ============================================================================
+
+/// Decoder PD memory regions (untrusted - image parsing)
+/// Has: Pixel buffer (write), Thumbnail ring (write), Photo data buffer (read)
+/// Missing: Framebuffer, Storage, UART, Network
+/// The photo data buffer is the Storage PD's file buffer (`FILE_BUFFER_VADDR`
+/// in `rpi4-storage-protocol`)
+pub const DECODER_PD_PHOTO_DATA_BASE: usize = 0x5_0700_0000;
+pub const DECODER_PD_PHOTO_DATA_SIZE: usize = 0x10_0000; // 1MB for photo file data
+
+/// Specification: can Decoder PD access this address?
+pub open spec fn decoder_pd_can_access(addr: usize) -> bool {
+    // Pixel buffer (write decoded pixels)
+    in_pixel_buffer_region(addr) ||
+    // Thumbnail ring (write downscaled photos)
+    in_thumb_ring_region(addr) ||
+    // Photo data (read raw file bytes)
+    (addr >= DECODER_PD_PHOTO_DATA_BASE &&
+     addr < DECODER_PD_PHOTO_DATA_BASE + DECODER_PD_PHOTO_DATA_SIZE)
+}
+
+/// Display PD memory regions
+pub const DISPLAY_PD_FB_BASE: usize = 0x5_0001_0000;
+pub const DISPLAY_PD_FB_SIZE: usize = 0x100_0000;
+pub const DISPLAY_PD_MAILBOX_BASE: usize = 0x5_0000_0000;
+pub const DISPLAY_PD_MAILBOX_SIZE: usize = 0x1000;
+
+/// Specification: can Display PD access this address?
+pub open spec fn display_pd_can_access(addr: usize) -> bool {
+    // Framebuffer (render)
+    (addr >= DISPLAY_PD_FB_BASE && addr < DISPLAY_PD_FB_BASE + DISPLAY_PD_FB_SIZE) ||
+    // GPU mailbox
+    (addr >= DISPLAY_PD_MAILBOX_BASE && addr < DISPLAY_PD_MAILBOX_BASE + DISPLAY_PD_MAILBOX_SIZE) ||
+    // Pixel buffer (read decoded images)
+    in_pixel_buffer_region(addr) ||
+    // Thumbnail ring (read the browse grid)
+    in_thumb_ring_region(addr) ||
+    // Command ring (receive commands)
+    in_cmd_ring_region(addr) ||
+    // Telemetry log (append samples)
+    in_telemetry_log_region(addr)
+}
+
+/// Runtime check of `decoder_pd_can_access`, for audit builds
+pub fn decoder_pd_may_access(addr: usize) -> (r: bool)
+    ensures r == decoder_pd_can_access(addr),
+{
+    (addr >= PIXEL_BUFFER_VADDR && addr < PIXEL_BUFFER_VADDR + PIXEL_BUFFER_SIZE) ||
+    (addr >= THUMB_RING_VADDR && addr < THUMB_RING_VADDR + THUMB_RING_SIZE) ||
+    (addr >= DECODER_PD_PHOTO_DATA_BASE &&
+     addr < DECODER_PD_PHOTO_DATA_BASE + DECODER_PD_PHOTO_DATA_SIZE)
+}
+
+/// Runtime check of `display_pd_can_access`, for audit builds
+pub fn display_pd_may_access(addr: usize) -> (r: bool)
+    ensures r == display_pd_can_access(addr),
+{
+    (addr >= DISPLAY_PD_FB_BASE && addr < DISPLAY_PD_FB_BASE + DISPLAY_PD_FB_SIZE) ||
+    (addr >= DISPLAY_PD_MAILBOX_BASE && addr < DISPLAY_PD_MAILBOX_BASE + DISPLAY_PD_MAILBOX_SIZE) ||
+    (addr >= PIXEL_BUFFER_VADDR && addr < PIXEL_BUFFER_VADDR + PIXEL_BUFFER_SIZE) ||
+    (addr >= THUMB_RING_VADDR && addr < THUMB_RING_VADDR + THUMB_RING_SIZE) ||
+    (addr >= CMD_RING_VADDR && addr < CMD_RING_VADDR + CMD_RING_SIZE) ||
+    (addr >= TELEMETRY_LOG_VADDR && addr < TELEMETRY_LOG_VADDR + TELEMETRY_LOG_SIZE)
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: Decoder PD cannot access framebuffer
+/// This is the key security property - a compromised decoder cannot draw directly
+proof fn decoder_cannot_access_framebuffer()
+    ensures
+        forall|addr: usize|
+            (addr >= DISPLAY_PD_FB_BASE && addr < DISPLAY_PD_FB_BASE + DISPLAY_PD_FB_SIZE)
+            ==> !decoder_pd_can_access(addr)
+{
+    // Decoder only has access to pixel buffer and photo data regions
+    // Framebuffer is in a completely separate address range
+}
+
+/// Prove: Decoder PD cannot access storage
+/// Prevents exfiltration if decoder is compromised
+proof fn decoder_cannot_access_storage()
+    ensures
+        forall|addr: usize|
+            // 16MB slot holding the Storage PD's EMMC2 window (`STORAGE_PD_EMMC_BASE`)
+            (addr >= 0x5_0800_0000 && addr < 0x5_0900_0000)
+            ==> !decoder_pd_can_access(addr)
+{
+    // Decoder regions don't overlap with storage
+}
+
+/// Prove: Only decoded pixels are shared between Decoder and Display
+proof fn decoder_display_only_share_decoded_pixels()
+    ensures
+        forall|addr: usize|
+            (decoder_pd_can_access(addr) && display_pd_can_access(addr))
+            ==> (in_pixel_buffer_region(addr) || in_thumb_ring_region(addr))
+{
+    // The only overlapping regions are the pixel buffer and thumbnail ring
+}
+
+} // verus!
+
+// ============================================================================
+// NON-VERIFIED RUNTIME HELPERS
+// ============================================================================
+
+use core::sync::atomic::{AtomicU16, AtomicU32, AtomicU8, Ordering};
+use rpi4_pd_error::audit::Access;
+
+pub mod audit;
+use audit::audit_access;
+
+/// Index model of the command ring (see `verified_microkernel::ring`)
+pub type CommandRingHeader = ring::RingIndices<{ CMD_RING_CAPACITY as usize }>;
+
+/// The command ring as a typed [`VerifiedRing`]
+pub type CommandRing = VerifiedRing<PhotoCommand, { CMD_RING_CAPACITY as usize }>;
+
+/// Runtime command ring header with atomics
+#[repr(C, align(16))]
+pub struct AtomicCommandRingHeader {
+    pub ring: RingHeader,
+    pub _pad: u32,
+}
+
+impl AtomicCommandRingHeader {
+    /// Initialize at memory location
+    ///
+    /// # Safety
+    /// Pointer must be valid and properly aligned
+    pub unsafe fn init(ptr: *mut Self) {
+        audit_access(ptr, 1, Access::Write);
+        (*ptr).ring = RingHeader::new(CMD_RING_CAPACITY);
+        (*ptr)._pad = 0;
+    }
+
+    /// The ring with this header and the commands at `entries`
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entries (see [`cmd_entries_ptr`])
+    pub unsafe fn with_entries(&self, entries: *mut PhotoCommand) -> CommandRing {
+        CommandRing::new(&self.ring, entries)
+    }
+
+    pub fn has_data(&self) -> bool {
+        self.ring.len(CMD_RING_CAPACITY) > 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.ring.free_slots(CMD_RING_CAPACITY) == 0
+    }
+
+    pub fn advance_write(&self) {
+        self.ring.advance_write(CMD_RING_CAPACITY);
+    }
+
+    pub fn advance_read(&self) {
+        self.ring.advance_read(CMD_RING_CAPACITY);
+    }
+
+    pub fn current_write_idx(&self) -> u32 {
+        self.ring.write_idx.load(Ordering::Acquire)
+    }
+
+    pub fn current_read_idx(&self) -> u32 {
+        self.ring.read_idx.load(Ordering::Acquire)
+    }
+
+    /// Copy up to `out.len()` commands out of the ring, oldest first
+    ///
+    /// The read index is published once for the whole batch. Returns the
+    /// number of commands copied, never more than were queued; a corrupt
+    /// header reads as empty.
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entries (see [`cmd_entries_ptr`])
+    pub unsafe fn pop_batch(&self, entries: *const PhotoCommand, out: &mut [PhotoCommand]) -> usize {
+        audit_access(entries, CMD_RING_CAPACITY as usize, Access::Read);
+        self.with_entries(entries as *mut _).pop_batch(out)
+    }
+
+    /// Queue as much of `batch` as fits, oldest first
+    ///
+    /// The write index is published once, after every command is in place.
+    /// Returns the number queued, never more than were free; the caller
+    /// keeps the rest for after the reader has caught up.
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entries (see [`cmd_entries_ptr`])
+    pub unsafe fn push_batch(&self, entries: *mut PhotoCommand, batch: &[PhotoCommand]) -> usize {
+        audit_access(entries, CMD_RING_CAPACITY as usize, Access::Write);
+        self.with_entries(entries).push_batch(batch)
+    }
+
+    /// Queue all of `batch`, or nothing if fewer slots are free
+    ///
+    /// # Safety
+    /// `entries` must point at this ring's entries (see [`cmd_entries_ptr`])
+    pub unsafe fn write_batch(&self, entries: *mut PhotoCommand, batch: &[PhotoCommand]) -> bool {
+        // Only the producer adds commands, so the free count can only grow
+        // before the push
+        if (self.ring.free_slots(CMD_RING_CAPACITY) as usize) < batch.len() {
+            return false;
+        }
+        self.push_batch(entries, batch);
+        true
+    }
+}
+
+/// Runtime pixel buffer header with atomics
+#[repr(C, align(32))]
+pub struct AtomicPixelBufferHeader {
+    pub width: AtomicU32,
+    pub height: AtomicU32,
+    pub format: AtomicU8,
+    pub status: AtomicU8,
+    pub photo_index: u16,
+    pub data_len: AtomicU32,
     pub checksum: AtomicU32,
-    pub _reserved: [u8; 8],
+    pub frame_index: AtomicU16,
+    pub frame_count: AtomicU16,
+    pub frame_delay_ms: AtomicU32,
+    pub orientation: AtomicU8,
+    pub taken_at: AtomicU32,
+    pub camera_model: [AtomicU8; EXIF_MODEL_LEN],
 }
 
 impl AtomicPixelBufferHeader {
@@ -643,6 +2183,7 @@ impl AtomicPixelBufferHeader {
     /// # Safety
     /// Pointer must be valid and properly aligned
     pub unsafe fn init(ptr: *mut Self) {
+        audit_access(ptr, 1, Access::Write);
         (*ptr).width = AtomicU32::new(0);
         (*ptr).height = AtomicU32::new(0);
         (*ptr).format = AtomicU8::new(PIXEL_FORMAT_RGBA32);
@@ -650,7 +2191,12 @@ impl AtomicPixelBufferHeader {
         (*ptr).photo_index = 0;
         (*ptr).data_len = AtomicU32::new(0);
         (*ptr).checksum = AtomicU32::new(0);
-        (*ptr)._reserved = [0; 8];
+        (*ptr).frame_index = AtomicU16::new(0);
+        (*ptr).frame_count = AtomicU16::new(1);
+        (*ptr).frame_delay_ms = AtomicU32::new(0);
+        (*ptr).orientation = AtomicU8::new(EXIF_ORIENTATION_NORMAL);
+        (*ptr).taken_at = AtomicU32::new(0);
+        (*ptr).camera_model = core::array::from_fn(|_| AtomicU8::new(0));
     }
 
     pub fn is_ready(&self) -> bool {
@@ -696,6 +2242,48 @@ impl AtomicPixelBufferHeader {
         };
         self.data_len.store(width * height * bpp, Ordering::Release);
     }
+
+    /// Mark the buffer as frame `index` of `count`, due to be replaced after
+    /// `delay_ms` (set before `set_ready`)
+    pub fn set_frame(&self, index: u16, count: u16, delay_ms: u32) {
+        self.frame_index.store(index, Ordering::Release);
+        self.frame_count.store(count, Ordering::Release);
+        self.frame_delay_ms.store(delay_ms, Ordering::Release);
+    }
+
+    /// (frame index, frame count, delay in ms) of the buffered frame
+    pub fn get_frame(&self) -> (u16, u16, u32) {
+        (
+            self.frame_index.load(Ordering::Acquire),
+            self.frame_count.load(Ordering::Acquire),
+            self.frame_delay_ms.load(Ordering::Acquire),
+        )
+    }
+
+    /// Record the photo's EXIF orientation, packed capture time and camera
+    /// model (set before `set_ready`); an orientation outside 1-8 is stored
+    /// as upright
+    pub fn set_metadata(&self, orientation: u8, taken_at: u32, camera_model: &[u8; EXIF_MODEL_LEN]) {
+        let orientation = if (1..=8).contains(&orientation) { orientation } else { EXIF_ORIENTATION_NORMAL };
+        self.orientation.store(orientation, Ordering::Release);
+        self.taken_at.store(taken_at, Ordering::Release);
+        for (slot, byte) in self.camera_model.iter().zip(camera_model) {
+            slot.store(*byte, Ordering::Release);
+        }
+    }
+
+    /// (orientation, packed capture time, camera model) of the buffered photo
+    pub fn get_metadata(&self) -> (Orientation, u32, [u8; EXIF_MODEL_LEN]) {
+        let mut model = [0; EXIF_MODEL_LEN];
+        for (byte, slot) in model.iter_mut().zip(&self.camera_model) {
+            *byte = slot.load(Ordering::Acquire);
+        }
+        (
+            Orientation::from_exif(self.orientation.load(Ordering::Acquire) as u16),
+            self.taken_at.load(Ordering::Acquire),
+            model,
+        )
+    }
 }
 
 /// Get command ring header pointer
@@ -703,7 +2291,9 @@ impl AtomicPixelBufferHeader {
 /// # Safety
 /// Base must be valid command ring memory
 pub unsafe fn cmd_ring_header_ptr(base: *mut u8) -> *mut AtomicCommandRingHeader {
-    base as *mut AtomicCommandRingHeader
+    let header = base as *mut AtomicCommandRingHeader;
+    audit_access(header, 1, Access::Read);
+    header
 }
 
 /// Get command entries pointer
@@ -711,7 +2301,9 @@ pub unsafe fn cmd_ring_header_ptr(base: *mut u8) -> *mut AtomicCommandRingHeader
 /// # Safety
 /// Base must be valid command ring memory
 pub unsafe fn cmd_entries_ptr(base: *mut u8) -> *mut PhotoCommand {
-    base.add(CMD_HEADER_SIZE) as *mut PhotoCommand
+    let entries = base.add(CMD_HEADER_SIZE) as *mut PhotoCommand;
+    audit_access(entries, CMD_RING_CAPACITY as usize, Access::Read);
+    entries
 }
 
 /// Get pixel buffer header pointer
@@ -719,7 +2311,9 @@ pub unsafe fn cmd_entries_ptr(base: *mut u8) -> *mut PhotoCommand {
 /// # Safety
 /// Base must be valid pixel buffer memory
 pub unsafe fn pixel_header_ptr(base: *mut u8) -> *mut AtomicPixelBufferHeader {
-    base as *mut AtomicPixelBufferHeader
+    let header = base as *mut AtomicPixelBufferHeader;
+    audit_access(header, 1, Access::Read);
+    header
 }
 
 /// Get pixel data pointer (after header)
@@ -727,7 +2321,197 @@ pub unsafe fn pixel_header_ptr(base: *mut u8) -> *mut AtomicPixelBufferHeader {
 /// # Safety
 /// Base must be valid pixel buffer memory
 pub unsafe fn pixel_data_ptr(base: *mut u8) -> *mut u8 {
-    base.add(PixelBufferHeader::SIZE)
+    let data = base.add(PixelBufferHeader::SIZE);
+    audit_access(data, PIXEL_BUFFER_SIZE - PixelBufferHeader::SIZE, Access::Read);
+    data
+}
+
+/// Telemetry log in its shared memory region
+///
+/// The Display PD appends; readers only ever look at records whose `seq`
+/// is below `written`, which is published after the record itself.
+pub struct TelemetryLog {
+    header: *mut TelemetryLogHeader,
+    records: *mut TelemetryRecord,
+}
+
+impl TelemetryLog {
+    /// Use the log at `base`, keeping its records if the header is intact
+    /// (e.g. after a PD restart) and starting an empty log otherwise.
+    /// Returns the log and whether existing records were kept.
+    ///
+    /// # Safety
+    /// `base` must point to `TELEMETRY_LOG_SIZE` bytes, 16-byte aligned,
+    /// that no other writer uses.
+    pub unsafe fn attach(base: *mut u8) -> (Self, bool) {
+        let header = base as *mut TelemetryLogHeader;
+        audit_access(base, TELEMETRY_LOG_SIZE, Access::Write);
+        let resumed = core::ptr::read_volatile(header).is_valid();
+        if !resumed {
+            core::ptr::write_volatile(header, TelemetryLogHeader::new());
+        }
+        let log = Self {
+            header,
+            records: base.add(TELEMETRY_HEADER_SIZE) as *mut TelemetryRecord,
+        };
+        (log, resumed)
+    }
+
+    fn header(&self) -> TelemetryLogHeader {
+        audit_access(self.header, 1, Access::Read);
+        unsafe { core::ptr::read_volatile(self.header) }
+    }
+
+    /// Records appended since the log was created
+    pub fn written(&self) -> u32 {
+        self.header().written
+    }
+
+    /// Is the header still intact? Only a stray write can make it invalid
+    /// once the log is attached.
+    pub fn is_valid(&self) -> bool {
+        self.header().is_valid()
+    }
+
+    /// Sequence numbers of the records still in the log
+    pub fn retained(&self) -> core::ops::Range<u32> {
+        let header = self.header();
+        header.written - header.retained()..header.written
+    }
+
+    /// Append `record`, overwriting the oldest one when full; returns the
+    /// sequence number it was given
+    pub fn append(&mut self, mut record: TelemetryRecord) -> u32 {
+        let mut header = self.header();
+        record.seq = header.written;
+        audit_access(self.records.wrapping_add(header.slot(record.seq) as usize), 1, Access::Write);
+        unsafe {
+            core::ptr::write_volatile(self.records.add(header.slot(record.seq) as usize), record);
+        }
+        // The record must be visible before the count that covers it
+        core::sync::atomic::fence(Ordering::Release);
+        header.written = header.written.wrapping_add(1);
+        unsafe { core::ptr::write_volatile(self.header, header) };
+        record.seq
+    }
+
+    /// Record `seq`, if it is still in the log
+    pub fn get(&self, seq: u32) -> Option<TelemetryRecord> {
+        let header = self.header();
+        if !(header.written - header.retained()..header.written).contains(&seq) {
+            return None;
+        }
+        core::sync::atomic::fence(Ordering::Acquire);
+        audit_access(self.records.wrapping_add(header.slot(seq) as usize), 1, Access::Read);
+        Some(unsafe { core::ptr::read_volatile(self.records.add(header.slot(seq) as usize)) })
+    }
+}
+
+/// Thumbnail ring in its shared memory region
+///
+/// The Decoder PD writes; a slot's pixels are only trusted once its header
+/// says `THUMB_READY`, which is written after them. While a slot is being
+/// rewritten its header says `THUMB_EMPTY`.
+pub struct ThumbnailRing {
+    base: *mut u8,
+}
+
+impl ThumbnailRing {
+    /// Start an empty ring at `base`: thumbnails are cheap to make again, so
+    /// none are kept across a PD restart
+    ///
+    /// # Safety
+    /// `base` must point to `THUMB_RING_SIZE` bytes, 16-byte aligned, that
+    /// no other writer uses.
+    pub unsafe fn attach(base: *mut u8) -> Self {
+        audit_access(base, THUMB_RING_SIZE, Access::Write);
+        let ring = Self { base };
+        for slot in 0..THUMB_SLOTS {
+            ring.write_slot(slot, ThumbnailSlot::empty());
+        }
+        core::ptr::write_volatile(base as *mut ThumbnailRingHeader, ThumbnailRingHeader::new());
+        ring
+    }
+
+    fn header(&self) -> ThumbnailRingHeader {
+        audit_access(self.base, THUMB_HEADER_SIZE, Access::Read);
+        unsafe { core::ptr::read_volatile(self.base as *const ThumbnailRingHeader) }
+    }
+
+    fn slot_ptr(&self, slot: u32) -> *mut ThumbnailSlot {
+        self.base.wrapping_add(thumb_slot_offset(slot)) as *mut ThumbnailSlot
+    }
+
+    fn write_slot(&self, slot: u32, header: ThumbnailSlot) {
+        audit_access(self.slot_ptr(slot), 1, Access::Write);
+        unsafe { core::ptr::write_volatile(self.slot_ptr(slot), header) };
+    }
+
+    /// Header of slot `slot`
+    pub fn slot(&self, slot: u32) -> ThumbnailSlot {
+        audit_access(self.slot_ptr(slot), 1, Access::Read);
+        unsafe { core::ptr::read_volatile(self.slot_ptr(slot)) }
+    }
+
+    /// Thumbnails written since the ring was attached
+    pub fn written(&self) -> u32 {
+        self.header().written
+    }
+
+    /// Is the header still intact? Only a stray write can make it invalid
+    /// once the ring is attached.
+    pub fn is_valid(&self) -> bool {
+        self.header().is_valid()
+    }
+
+    /// The slot holding photo `index`, if one does; the newest wins
+    pub fn find(&self, index: u16) -> Option<(u32, ThumbnailSlot)> {
+        let header = self.header();
+        let held = header.written.min(THUMB_SLOTS);
+        (1..=held)
+            .map(|back| header.slot(header.written.wrapping_sub(back)))
+            .map(|slot| (slot, self.slot(slot)))
+            .find(|(_, thumb)| thumb.status != THUMB_EMPTY && thumb.photo_index == index)
+    }
+
+    /// The pixels of slot `slot`, `THUMB_W` x `THUMB_H` row-major
+    pub fn pixels(&self, slot: u32) -> &[u32] {
+        let start = thumb_pixel_offset(slot, 0, 0);
+        audit_access(self.base.wrapping_add(start) as *const u32, THUMB_PIXELS, Access::Read);
+        unsafe { core::slice::from_raw_parts(self.base.add(start) as *const u32, THUMB_PIXELS) }
+    }
+
+    /// Take the next slot for photo `index`, overwriting the oldest
+    /// thumbnail; draw into the pixels, then `publish` the slot
+    pub fn begin(&mut self, index: u16) -> (u32, &mut [u32]) {
+        let slot = self.header().slot(self.written());
+        self.write_slot(slot, ThumbnailSlot { photo_index: index, ..ThumbnailSlot::empty() });
+        let start = thumb_pixel_offset(slot, 0, 0);
+        audit_access(self.base.wrapping_add(start) as *const u32, THUMB_PIXELS, Access::Write);
+        let pixels = unsafe { core::slice::from_raw_parts_mut(self.base.add(start) as *mut u32, THUMB_PIXELS) };
+        (slot, pixels)
+    }
+
+    /// Mark the slot from `begin` ready with a `width` x `height` picture,
+    /// or failed if `width` is 0
+    pub fn publish(&mut self, slot: u32, index: u16, width: u32, height: u32) {
+        let mut header = self.header();
+        let status = if width == 0 || height == 0 { THUMB_FAILED } else { THUMB_READY };
+        let thumb = ThumbnailSlot {
+            photo_index: index,
+            status,
+            width: width.min(THUMB_W) as u16,
+            height: height.min(THUMB_H) as u16,
+            seq: header.written,
+            ..ThumbnailSlot::empty()
+        };
+        // The pixels must be visible before the header that vouches for them
+        core::sync::atomic::fence(Ordering::Release);
+        self.write_slot(slot, thumb);
+        header.written = header.written.wrapping_add(1);
+        audit_access(self.base, THUMB_HEADER_SIZE, Access::Write);
+        unsafe { core::ptr::write_volatile(self.base as *mut ThumbnailRingHeader, header) };
+    }
 }
 
 // ============================================================================
@@ -754,11 +2538,248 @@ mod tests {
         assert_eq!(core::mem::size_of::<PhotoCommand>(), CMD_ENTRY_SIZE);
     }
 
+    #[test]
+    fn test_access_checks_match_regions() {
+        assert!(decoder_pd_may_access(PIXEL_BUFFER_VADDR));
+        assert!(decoder_pd_may_access(DECODER_PD_PHOTO_DATA_BASE + DECODER_PD_PHOTO_DATA_SIZE - 1));
+        assert!(!decoder_pd_may_access(DISPLAY_PD_FB_BASE));
+        assert!(!decoder_pd_may_access(TELEMETRY_LOG_VADDR));
+        assert!(display_pd_may_access(CMD_RING_VADDR + CMD_RING_SIZE - 1));
+        assert!(display_pd_may_access(TELEMETRY_LOG_VADDR));
+        assert!(!display_pd_may_access(TELEMETRY_LOG_VADDR + TELEMETRY_LOG_SIZE));
+        assert!(!display_pd_may_access(DECODER_PD_PHOTO_DATA_BASE));
+        assert!(decoder_pd_may_access(THUMB_RING_VADDR + THUMB_RING_SIZE - 1));
+        assert!(display_pd_may_access(THUMB_RING_VADDR));
+        assert!(!display_pd_may_access(THUMB_RING_VADDR + THUMB_RING_SIZE));
+    }
+
+    #[test]
+    fn test_command_ring_fits_region() {
+        assert!(CMD_HEADER_SIZE + CMD_RING_CAPACITY as usize * CMD_ENTRY_SIZE <= CMD_RING_SIZE);
+        assert!(CommandRing::region_size(CMD_HEADER_SIZE) <= CMD_RING_SIZE);
+        assert_eq!(core::mem::size_of::<AtomicCommandRingHeader>(), CMD_HEADER_SIZE);
+    }
+
+    #[test]
+    fn test_command_ring_batches() {
+        #[repr(C, align(16))]
+        struct Region([u8; CMD_HEADER_SIZE + 4 * CMD_ENTRY_SIZE]);
+        let mut region = Region([0; CMD_HEADER_SIZE + 4 * CMD_ENTRY_SIZE]);
+        let base = region.0.as_mut_ptr();
+        let mut out = [PhotoCommand::empty(); 4];
+        unsafe {
+            let header = cmd_ring_header_ptr(base);
+            AtomicCommandRingHeader::init(header);
+            (*header).ring.capacity = 4;
+            let header = &*header;
+            let entries = cmd_entries_ptr(base);
+
+            assert!(header.write_batch(entries, &[PhotoCommand::next(), PhotoCommand::goto(7)]));
+            assert_eq!(header.pop_batch(entries, &mut out[..1]), 1);
+            assert!(header.write_batch(entries, &[PhotoCommand::prev(), PhotoCommand::pause()]));
+            assert!(header.is_full());
+            assert!(!header.write_batch(entries, &[PhotoCommand::resume()]));
+            assert_eq!(header.push_batch(entries, &[PhotoCommand::resume()]), 0);
+
+            assert_eq!(header.pop_batch(entries, &mut out), 3);
+            assert_eq!(out[0].photo_index, 7);
+            assert_eq!(out[1].command, CMD_PREV);
+            assert_eq!(out[2].command, CMD_PAUSE);
+            assert!(!header.has_data());
+
+            // A partial push queues what fits and reports it
+            let batch = [PhotoCommand::next(), PhotoCommand::prev(), PhotoCommand::pause(), PhotoCommand::resume()];
+            assert_eq!(header.push_batch(entries, &batch), 3);
+            assert_eq!(header.pop_batch(entries, &mut out), 3);
+            assert_eq!(out[2].command, CMD_PAUSE);
+        }
+    }
+
+    #[test]
+    fn test_telemetry_log_fits_region() {
+        assert_eq!(core::mem::size_of::<TelemetryLogHeader>(), TELEMETRY_HEADER_SIZE);
+        assert_eq!(core::mem::size_of::<TelemetryRecord>(), TELEMETRY_RECORD_SIZE);
+        assert!(
+            TELEMETRY_HEADER_SIZE + TELEMETRY_CAPACITY as usize * TELEMETRY_RECORD_SIZE <= TELEMETRY_LOG_SIZE
+        );
+    }
+
+    #[test]
+    fn test_telemetry_log_overwrites_oldest() {
+        #[repr(C, align(16))]
+        struct Region([u8; TELEMETRY_LOG_SIZE]);
+        let mut region = Region([0; TELEMETRY_LOG_SIZE]);
+        let sample = |uptime_s| TelemetryRecord {
+            seq: 0, uptime_s, temp_milli_c: 48_500, throttled: 0, frames: 0,
+            missed_ticks: 0, photo_index: 0, _reserved: 0,
+        };
+        unsafe {
+            let (mut log, resumed) = TelemetryLog::attach(region.0.as_mut_ptr());
+            assert!(!resumed);
+            assert_eq!(log.retained(), 0..0);
+            for i in 0..TELEMETRY_CAPACITY + 3 {
+                assert_eq!(log.append(sample(i * 60)), i);
+            }
+            assert_eq!(log.retained(), 3..TELEMETRY_CAPACITY + 3);
+            assert_eq!(log.get(2), None);
+            assert_eq!(log.get(3).map(|r| r.uptime_s), Some(180));
+            assert_eq!(log.get(TELEMETRY_CAPACITY + 2).map(|r| r.seq), Some(TELEMETRY_CAPACITY + 2));
+
+            // A restarted writer keeps the records
+            let (log, resumed) = TelemetryLog::attach(region.0.as_mut_ptr());
+            assert!(resumed);
+            assert_eq!(log.written(), TELEMETRY_CAPACITY + 3);
+
+            // A stray write over the header shows up as an invalid log
+            assert!(log.is_valid());
+            region.0[0] ^= 0xFF;
+            assert!(!log.is_valid());
+        }
+    }
+
+    #[test]
+    fn test_thumbnail_ring_fits_region() {
+        assert_eq!(core::mem::size_of::<ThumbnailRingHeader>(), 16);
+        assert_eq!(core::mem::size_of::<ThumbnailSlot>(), THUMB_SLOT_HEADER_SIZE);
+        const { assert!(THUMB_SLOT_HEADER_SIZE + THUMB_PIXELS * 4 <= THUMB_SLOT_SIZE) };
+        assert_eq!(thumb_slot_offset(THUMB_SLOTS - 1) + THUMB_SLOT_SIZE, 0x1A_5000);
+        assert!(thumb_pixel_offset(THUMB_SLOTS - 1, THUMB_W - 1, THUMB_H - 1) + 4 <= THUMB_RING_SIZE);
+        // A page of the grid fits in the ring
+        assert_eq!(GRID_COLS * GRID_ROWS, GRID_PAGE);
+        assert_eq!(GRID_PAGE, THUMB_SLOTS);
+    }
+
+    #[test]
+    fn test_thumbnail_ring_overwrites_oldest() {
+        #[repr(C, align(16))]
+        struct Region([u8; THUMB_RING_SIZE]);
+        static mut REGION: Region = Region([0; THUMB_RING_SIZE]);
+        unsafe {
+            let base = core::ptr::addr_of_mut!(REGION.0) as *mut u8;
+            let mut ring = ThumbnailRing::attach(base);
+            assert_eq!(ring.find(0), None);
+
+            for index in 0..THUMB_SLOTS as u16 + 2 {
+                let (slot, pixels) = ring.begin(index);
+                pixels[0] = index as u32;
+                // A slot being drawn is not found
+                assert_eq!(ring.find(index), None);
+                ring.publish(slot, index, THUMB_W, 81);
+            }
+            assert_eq!(ring.written(), THUMB_SLOTS + 2);
+            // The first two were overwritten
+            assert_eq!(ring.find(1), None);
+            let (slot, thumb) = ring.find(2).unwrap();
+            assert_eq!((thumb.status, thumb.width, thumb.height, thumb.seq), (THUMB_READY, 192, 81, 2));
+            assert_eq!(ring.pixels(slot)[0], 2);
+            let (slot, _) = ring.find(THUMB_SLOTS as u16 + 1).unwrap();
+            assert_eq!(slot, 1);
+
+            // Refused photos are kept as failed
+            let (slot, _) = ring.begin(99);
+            ring.publish(slot, 99, 0, 0);
+            assert_eq!(ring.find(99).map(|(_, thumb)| thumb.status), Some(THUMB_FAILED));
+
+            assert!(ring.is_valid());
+            (*core::ptr::addr_of_mut!(REGION.0))[0] ^= 0xFF;
+            assert!(!ring.is_valid());
+        }
+    }
+
+    #[test]
+    fn test_grid_navigation() {
+        // 23 photos: a full page, then 3 on the second
+        assert_eq!(grid_move(0, 23, GridMove::Left), 0);
+        assert_eq!(grid_move(4, 23, GridMove::Right), 5);
+        assert_eq!(grid_move(22, 23, GridMove::Right), 22);
+        assert_eq!(grid_move(3, 23, GridMove::Up), 3);
+        assert_eq!(grid_move(8, 23, GridMove::Up), 3);
+        assert_eq!(grid_move(17, 23, GridMove::Down), 22);
+        assert_eq!(grid_move(14, 23, GridMove::Down), 19);
+        // Bottom of the first page runs onto the next
+        assert_eq!(grid_move(16, 23, GridMove::Down), 21);
+        // Down into the ragged last row lands on the last photo
+        assert_eq!(grid_move(18, 23, GridMove::Down), 22);
+        assert_eq!(grid_move(21, 23, GridMove::Down), 21);
+
+        assert_eq!(grid_page_start(19), 0);
+        assert_eq!(grid_page_start(21), 20);
+        assert_eq!(grid_cell(7), (2, 1));
+        assert_eq!(grid_cell(21), (1, 0));
+    }
+
     #[test]
     fn test_header_size() {
         assert_eq!(core::mem::size_of::<PixelBufferHeader>(), PixelBufferHeader::SIZE);
     }
 
+    #[test]
+    fn test_frame_fields() {
+        assert_eq!(core::mem::size_of::<AtomicPixelBufferHeader>(), PixelBufferHeader::SIZE);
+        let header = PixelBufferHeader::new(64, 48, PIXEL_FORMAT_RGBA32, 3).with_frame(2, 5, 80);
+        assert_eq!((header.frame_index, header.frame_count, header.frame_delay_ms), (2, 5, 80));
+        assert_eq!(header.data_len, 64 * 48 * 4);
+
+        assert_eq!(frame_delay_from_centis(0), DEFAULT_FRAME_DELAY_MS);
+        assert_eq!(frame_delay_from_centis(1), DEFAULT_FRAME_DELAY_MS);
+        assert_eq!(frame_delay_from_centis(2), 20);
+        assert_eq!(frame_delay_from_centis(u16::MAX), MAX_FRAME_DELAY_MS);
+    }
+
+    #[test]
+    fn test_metadata_fields() {
+        let mut model = [0u8; EXIF_MODEL_LEN];
+        model[..5].copy_from_slice(b"PiCam");
+        let taken = CaptureTime { year: 2024, month: 7, day: 14, hour: 18, minute: 5 };
+        let header = PixelBufferHeader::new(64, 48, PIXEL_FORMAT_RGBA32, 3).with_metadata(6, taken.pack(), model);
+        assert_eq!(header.orientation, 6);
+        assert_eq!(CaptureTime::unpack(header.taken_at), Some(taken));
+        assert_eq!(&header.camera_model[..5], b"PiCam");
+        assert_eq!(CaptureTime::unpack(0), None);
+        assert_eq!(PixelBufferHeader::empty().orientation, EXIF_ORIENTATION_NORMAL);
+
+        for value in 1..=8u16 {
+            assert_eq!(Orientation::from_exif(value).to_exif() as u16, value);
+        }
+        assert_eq!(Orientation::from_exif(0), Orientation::Normal);
+        assert_eq!(Orientation::from_exif(9), Orientation::Normal);
+    }
+
+    #[test]
+    fn test_tiff_offsets_stay_in_buffer() {
+        // TIFF header at 10 in a 100-byte buffer
+        assert_eq!(tiff_range(10, 8, 2, 100), Some(18));
+        assert_eq!(tiff_range(10, 88, 2, 100), Some(98));
+        assert_eq!(tiff_range(10, 89, 2, 100), None);
+        assert_eq!(tiff_range(10, u32::MAX, 2, 100), None);
+        assert_eq!(tiff_range(101, 0, 0, 100), None);
+        assert_eq!(tiff_range(10, 0, usize::MAX, 100), None);
+
+        // IFD at 8: count, then 12-byte entries
+        assert_eq!(ifd_entry(10, 8, 0, 100), Some(20));
+        assert_eq!(ifd_entry(10, 8, 5, 100), Some(80));
+        assert_eq!(ifd_entry(10, 8, 6, 100), None);
+
+        // Short values sit in the entry, long ones at their offset
+        assert_eq!(ifd_value(10, 20, 0xFFFF_FFFF, 4, 100), Some(28));
+        assert_eq!(ifd_value(10, 20, 40, 20, 100), Some(50));
+        assert_eq!(ifd_value(10, 20, 80, 20, 100), None);
+    }
+
+    #[test]
+    fn test_lzw_table_indexing() {
+        assert_eq!(lzw_lookup(5, 6), Some(5));
+        assert_eq!(lzw_lookup(6, 6), None);
+        assert_eq!(lzw_add(4095), Some(4095));
+        assert_eq!(lzw_add(4096), None);
+        // Codes widen when the table outgrows them, and stop at 12 bits
+        assert_eq!(lzw_code_bits(63, 6), 6);
+        assert_eq!(lzw_code_bits(64, 6), 7);
+        assert_eq!(lzw_code_bits(4096, 12), 12);
+        assert_eq!(lzw_stack_slot(LZW_TABLE_SIZE - 1), Some(LZW_TABLE_SIZE - 1));
+        assert_eq!(lzw_stack_slot(LZW_TABLE_SIZE), None);
+    }
+
     #[test]
     fn test_commands() {
         let next = PhotoCommand::next();
@@ -767,5 +2788,206 @@ mod tests {
         let goto = PhotoCommand::goto(42);
         assert_eq!(goto.command, CMD_GOTO);
         assert_eq!(goto.photo_index, 42);
+
+        let shuffle = PhotoCommand::shuffle(ORDER_NEWEST_FIRST);
+        assert_eq!((shuffle.command, shuffle.flags), (CMD_SHUFFLE, ORDER_NEWEST_FIRST));
+        let favorite = PhotoCommand::favorite(3);
+        assert_eq!((favorite.command, favorite.photo_index), (CMD_FAVORITE, 3));
+        let browse = PhotoCommand::browse(true);
+        assert_eq!((browse.command, browse.flags), (CMD_BROWSE, BROWSE_OPEN));
+        assert_eq!(PhotoCommand::browse(false).flags, BROWSE_CLOSE);
+        let select = PhotoCommand::select(7);
+        assert_eq!((select.command, select.photo_index), (CMD_SELECT, 7));
+    }
+
+    #[test]
+    fn test_fisher_yates_permutes_prefix_only() {
+        let mut order = [0u16; 12];
+        for (i, slot) in order.iter_mut().enumerate() {
+            *slot = i as u16;
+        }
+        let state = fisher_yates(&mut order, 10, 0x1234_5678);
+        assert_ne!(state, 0x1234_5678);
+        assert_ne!(order[..10], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let mut sorted = order;
+        sorted[..10].sort_unstable();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(order[10..], [10, 11]);
+        assert_eq!(shuffle_pick(0, u32::MAX), 0);
+    }
+
+    #[test]
+    fn test_window_back_ref() {
+        assert_eq!(window_back_ref(10, 3, 100), Some(7));
+        // Wraps around the start of the window
+        assert_eq!(window_back_ref(2, 5, 100), Some(INFLATE_WINDOW_SIZE - 3));
+        // Reaching before the first byte, zero, and too-far distances fail
+        assert_eq!(window_back_ref(10, 11, 10), None);
+        assert_eq!(window_back_ref(10, 0, 100), None);
+        assert_eq!(window_back_ref(10, INFLATE_WINDOW_SIZE + 1, usize::MAX), None);
+        assert_eq!(window_next(INFLATE_WINDOW_SIZE - 1), 0);
+    }
+
+    #[test]
+    fn test_decoder_row_indexing() {
+        assert_eq!(scanline_bytes(320, PIXEL_FORMAT_RGB24), Some(960));
+        assert_eq!(scanline_bytes(320, PIXEL_FORMAT_RGBA32), Some(1280));
+        assert_eq!(scanline_bytes(0, PIXEL_FORMAT_RGB24), None);
+        assert_eq!(scanline_bytes(MAX_DECODE_WIDTH + 1, PIXEL_FORMAT_RGB24), None);
+
+        assert_eq!(row_pixel_index(8, 3, 12), Some(11));
+        assert_eq!(row_pixel_index(8, 4, 12), None);
+        assert_eq!(row_pixel_index(20, 0, 12), None);
+        assert_eq!(next_row_base(usize::MAX - 1, 2), None);
+    }
+
+    #[test]
+    fn test_letterbox_rect() {
+        // 4:3 photo on a 16:9 screen: full height, pillarboxed
+        assert_eq!(letterbox_rect(320, 240, 1280, 720), Some(BlitRect { x: 160, y: 0, w: 960, h: 720 }));
+        // Same aspect: fills the screen
+        assert_eq!(letterbox_rect(1920, 1080, 1280, 720), Some(BlitRect { x: 0, y: 0, w: 1280, h: 720 }));
+        // Very wide panorama: full width, letterboxed
+        assert_eq!(letterbox_rect(4000, 100, 1280, 720), Some(BlitRect { x: 0, y: 344, w: 1280, h: 32 }));
+        assert_eq!(letterbox_rect(0, 240, 1280, 720), None);
+        assert_eq!(letterbox_rect(MAX_BLIT_DIM + 1, 240, 1280, 720), None);
+    }
+
+    #[test]
+    fn test_blit_scaled_nearest() {
+        let src = [1u32, 2, 3, 4];
+        let mut dst = [0u32; 6 * 5];
+        let rect = BlitRect { x: 1, y: 1, w: 4, h: 4 };
+        assert_eq!(blit_scaled(&src, 2, 2, Orientation::Normal, &mut dst, 6, 5, rect, ScaleFilter::Nearest), Ok(()));
+        let expected_rows = [
+            [0, 0, 0, 0, 0, 0],
+            [0, 1, 1, 2, 2, 0],
+            [0, 1, 1, 2, 2, 0],
+            [0, 3, 3, 4, 4, 0],
+            [0, 3, 3, 4, 4, 0],
+        ];
+        for (y, row) in expected_rows.iter().enumerate() {
+            assert_eq!(&dst[y * 6..y * 6 + 6], row);
+        }
+    }
+
+    #[test]
+    fn test_blit_scaled_bilinear() {
+        // Black to white, stretched 2 -> 4: endpoints kept, midpoints blended
+        let src = [0xFF00_0000u32, 0xFFFF_FFFF];
+        let mut dst = [0u32; 4];
+        let rect = BlitRect { x: 0, y: 0, w: 4, h: 1 };
+        assert_eq!(blit_scaled(&src, 2, 1, Orientation::Normal, &mut dst, 4, 1, rect, ScaleFilter::Bilinear), Ok(()));
+        assert_eq!(dst, [0xFF00_0000, 0xFF3F_3F3F, 0xFFBF_BFBF, 0xFFFF_FFFF]);
+
+        // Downscaling a flat image stays flat
+        let flat = [0xFF12_3456u32; 9];
+        let mut small = [0u32; 4];
+        let rect = BlitRect { x: 0, y: 0, w: 2, h: 2 };
+        assert_eq!(blit_scaled(&flat, 3, 3, Orientation::Normal, &mut small, 2, 2, rect, ScaleFilter::Bilinear), Ok(()));
+        assert_eq!(small, [0xFF12_3456; 4]);
+    }
+
+    #[test]
+    fn test_blit_scaled_oriented() {
+        // 3x2 stored: 1 2 3 / 4 5 6
+        let src = [1u32, 2, 3, 4, 5, 6];
+        let upright = |orient: Orientation| {
+            let (w, h) = orient.upright_size(3, 2);
+            let mut dst = [0u32; 6];
+            let rect = BlitRect { x: 0, y: 0, w, h };
+            assert_eq!(blit_scaled(&src, 3, 2, orient, &mut dst, w, h, rect, ScaleFilter::Nearest), Ok(()));
+            dst
+        };
+        assert_eq!(upright(Orientation::Normal), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(upright(Orientation::MirrorH), [3, 2, 1, 6, 5, 4]);
+        assert_eq!(upright(Orientation::Rotate180), [6, 5, 4, 3, 2, 1]);
+        assert_eq!(upright(Orientation::MirrorV), [4, 5, 6, 1, 2, 3]);
+        // Turned a quarter: 2 wide, 3 tall
+        assert_eq!(upright(Orientation::Transpose), [1, 4, 2, 5, 3, 6]);
+        assert_eq!(upright(Orientation::Rotate90), [4, 1, 5, 2, 6, 3]);
+        assert_eq!(upright(Orientation::Transverse), [6, 3, 5, 2, 4, 1]);
+        assert_eq!(upright(Orientation::Rotate270), [3, 6, 2, 5, 1, 4]);
+    }
+
+    #[test]
+    fn test_blit_scaled_rejects_bad_params() {
+        let src = [0u32; 4];
+        let mut dst = [7u32; 16];
+        let rect = BlitRect { x: 0, y: 0, w: 4, h: 4 };
+        let f = ScaleFilter::Nearest;
+        assert_eq!(blit_scaled(&src, 0, 2, Orientation::Normal, &mut dst, 4, 4, rect, f), Err(BlitError::BadSourceSize));
+        // Decoder claims a bigger image than it delivered
+        assert_eq!(blit_scaled(&src, 4, 4, Orientation::Normal, &mut dst, 4, 4, rect, f), Err(BlitError::SourceTooShort));
+        let off_screen = BlitRect { x: 1, y: 0, w: 4, h: 4 };
+        assert_eq!(blit_scaled(&src, 2, 2, Orientation::Normal, &mut dst, 4, 4, off_screen, f), Err(BlitError::RectOutOfBounds));
+        assert_eq!(blit_scaled(&src, 2, 2, Orientation::Normal, &mut dst, 4, 5, rect, f), Err(BlitError::DestTooShort));
+        // Nothing written on error
+        assert_eq!(dst, [7; 16]);
+    }
+
+    #[test]
+    fn test_transition_endpoints() {
+        let from = [0xFF00_0000u32; 12];
+        let to = [0xFFFF_FFFFu32; 12];
+        let mut dst = [0u32; 12];
+        for effect in [
+            TransitionEffect::Crossfade,
+            TransitionEffect::SlideLeft,
+            TransitionEffect::SlideRight,
+            TransitionEffect::Wipe,
+            TransitionEffect::KenBurns,
+        ] {
+            transition_frame(&from, &to, &mut dst, 4, 3, effect, 0).unwrap();
+            assert_eq!(dst, from, "{:?} starts on the old photo", effect);
+            transition_frame(&from, &to, &mut dst, 4, 3, effect, TRANSITION_END + 9).unwrap();
+            assert_eq!(dst, to, "{:?} ends on the new photo", effect);
+        }
+    }
+
+    #[test]
+    fn test_transition_midpoints() {
+        // One row, columns numbered: old photo 0..4, new photo 10..14
+        let from = [0, 1, 2, 3];
+        let to = [10, 11, 12, 13];
+        let mut dst = [0u32; 4];
+        let half = TRANSITION_END / 2;
+        transition_frame(&from, &to, &mut dst, 4, 1, TransitionEffect::SlideLeft, half).unwrap();
+        assert_eq!(dst, [2, 3, 10, 11]);
+        transition_frame(&from, &to, &mut dst, 4, 1, TransitionEffect::SlideRight, half).unwrap();
+        assert_eq!(dst, [12, 13, 0, 1]);
+        transition_frame(&from, &to, &mut dst, 4, 1, TransitionEffect::Wipe, half).unwrap();
+        assert_eq!(dst, [10, 11, 2, 3]);
+
+        let (from, to) = ([0xFF00_0000u32; 1], [0xFF00_00FEu32; 1]);
+        let mut dst = [0u32; 1];
+        transition_frame(&from, &to, &mut dst, 1, 1, TransitionEffect::Crossfade, half).unwrap();
+        assert_eq!(dst, [0xFF00_007F]);
+    }
+
+    #[test]
+    fn test_ken_burns_window_stays_inside() {
+        for len in [1, 2, 5, 720, 1280, MAX_BLIT_DIM] {
+            for t in 0..=TRANSITION_END {
+                let (window, origin) = ken_burns_window(len, t);
+                assert!(window > 0 && window + origin <= len);
+                assert!(zoom_coord(len - 1, len, window, origin) < len);
+            }
+            assert_eq!(ken_burns_window(len, TRANSITION_END), (len, 0));
+        }
+        // Zoomed in at the start: 1280 columns show 1024 of the photo
+        assert_eq!(ken_burns_window(1280, 0), (1024, 256));
+    }
+
+    #[test]
+    fn test_transition_rejects_bad_params() {
+        let small = [0u32; 4];
+        let mut dst = [7u32; 6];
+        let f = TransitionEffect::Crossfade;
+        assert_eq!(transition_frame(&small, &small, &mut dst, 0, 2, f, 0), Err(BlitError::BadSourceSize));
+        assert_eq!(transition_frame(&small, &[0; 6], &mut dst, 3, 2, f, 0), Err(BlitError::SourceTooShort));
+        assert_eq!(transition_frame(&[0; 6], &small, &mut dst, 3, 2, f, 0), Err(BlitError::SourceTooShort));
+        assert_eq!(transition_frame(&[0; 8], &[0; 8], &mut dst, 4, 2, f, 0), Err(BlitError::DestTooShort));
+        assert_eq!(dst, [7; 6]);
     }
 }
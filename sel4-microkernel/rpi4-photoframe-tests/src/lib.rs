@@ -16,11 +16,48 @@ extern crate alloc;
 #[path = "../../rpi4-photoframe/src/bounded_alloc.rs"]
 pub mod bounded_alloc;
 
+#[path = "../../rpi4-photoframe/src/clock.rs"]
+pub mod clock;
+
+#[path = "../../rpi4-photoframe/src/debug_overlay.rs"]
+pub mod debug_overlay;
+
 #[path = "../../rpi4-photoframe/src/decoder.rs"]
 pub mod decoder;
 
+#[path = "../../rpi4-photoframe/src/exif.rs"]
+pub mod exif;
+
+#[path = "../../rpi4-photoframe/src/gif.rs"]
+pub mod gif;
+
+#[path = "../../rpi4-photoframe/src/health.rs"]
+pub mod health;
+
+#[path = "../../rpi4-photoframe/src/inflate.rs"]
+pub mod inflate;
+
+#[path = "../../rpi4-photoframe/src/png.rs"]
+pub mod png;
+
 #[path = "../../rpi4-photoframe/src/validate.rs"]
 pub mod validate;
 
 #[path = "../../rpi4-photoframe/src/secure_decode.rs"]
 pub mod secure_decode;
+
+#[path = "../../rpi4-photoframe/src/soak.rs"]
+pub mod soak;
+
+#[path = "../../rpi4-photoframe/src/transitions.rs"]
+pub mod transitions;
+
+#[path = "../../rpi4-photoframe/src/playlist.rs"]
+pub mod playlist;
+
+#[path = "../../rpi4-photoframe/src/weather.rs"]
+pub mod weather;
+
+// Not pulled in: the host-only harness that runs the Input, Timer, Decoder
+// and Display PD logic on threads over the real shared-memory protocols.
+pub mod system_sim;
@@ -0,0 +1,832 @@
+//! Host simulation of the multi-PD photo frame.
+//!
+//! Runs the Input, Timer, Decoder and Display PD logic as one thread each,
+//! wired together the way a `.system` file wires the PDs:
+//!
+//! ```text
+//!  keys / UART ┌──────────┐ input_ring ┌────────────┐ cmd_ring ┌────────────┐
+//!  ──────────► │ Input PD │ ─────────► │ Display PD │ ───────► │ Decoder PD │
+//!              └────┬─────┘  notify 1  │            │ notify 4 │            │
+//!          SET_TIME │                  │            │ ◄─────── │            │
+//!          (PPC) 1  ▼                  │            │ pixel    └────────────┘
+//!  tick IRQ    ┌──────────┐ clock_page │            │ buffer,
+//!  ──────────► │ Timer PD │ ─────────► │            │ notify 2
+//!              └──────────┘  notify 3  └────────────┘
+//! ```
+//!
+//! Shared memory regions are zeroed, page-aligned heap allocations
+//! ([`Region`]), and every access to them goes through the same verified
+//! protocol helpers the PDs use: the input ring, the clock page, the command
+//! ring and the pixel buffer. Notifications and protected procedure calls
+//! are messages on each PD's event queue, delivered in order on the channel
+//! number the receiving end would see.
+//!
+//! The Photoframe PD still decodes in-process; the Decoder/Display split
+//! here is the one `rpi4-photo-protocol` is written for, so end-to-end flows
+//! (a photo decoded and drawn, navigation, the slideshow, setting the clock)
+//! can be tested across the real protocol boundaries without seL4 or a
+//! board. Time is virtual: the system timer only moves when the test fires
+//! a tick with [`SystemSim::tick`].
+
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+use std::ptr::{addr_of, addr_of_mut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rpi4_clock_protocol::{
+    decode_request, source_for_channel, ClockPage, ClockTime, EntryFeed, SetTimeEntry, CLOCK_PAGE_SIZE, TICK_HZ,
+    TIMER_UART_CHANNEL_ID,
+};
+use rpi4_input_protocol::{
+    entries_ptr, header_ptr, InputRingEntry, InputRingHeader, KeyState, SeqTracker, Timestamp, KEY_DOWN, KEY_ESCAPE,
+    KEY_LEFT, KEY_RIGHT, KEY_SPACE, KEY_UP, RING_BUFFER_SIZE,
+};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+use rpi4_photo_protocol::{
+    blit_scaled, cmd_entries_ptr, cmd_ring_header_ptr, compute_checksum, letterbox_rect, pixel_data_ptr,
+    pixel_header_ptr, AtomicCommandRingHeader, AtomicPixelBufferHeader, PhotoCommand, ScaleFilter, BUFFER_STATUS_ERROR,
+    BUFFER_STATUS_READY, CMD_GOTO, CMD_NEXT, CMD_PREV, CMD_RING_SIZE, DECODER_CHANNEL_ID,
+    DISPLAY_TO_DECODER_CHANNEL_ID, INPUT_CHANNEL_ID, MAX_PHOTO_HEIGHT, MAX_PHOTO_WIDTH, MAX_PIXELS, PIXEL_BUFFER_SIZE,
+    PIXEL_FORMAT_RGBA32, TIMER_CHANNEL_ID,
+};
+
+use crate::bounded_alloc::HeapControl;
+use crate::playlist::Playlist;
+use crate::secure_decode::secure_decode_into;
+
+/// Size of the simulated screen
+pub const SCREEN_W: u32 = 640;
+pub const SCREEN_H: u32 = 360;
+
+/// Seconds a photo stays up before the slideshow moves on
+pub const SLIDESHOW_INTERVAL_S: u32 = 5;
+
+/// Microseconds the system timer moves per tick
+pub const TICK_US: u64 = 1_000_000 / TICK_HZ as u64;
+
+/// How long [`SystemSim::wait_for`] waits for the screen to change
+const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reply to a call on a channel the Timer PD takes no calls on
+const WRONG_CHANNEL: PdError = PdError::new(ErrorDomain::Clock, ErrorKind::Denied);
+
+/// Reply to a SET_TIME call with an invalid time or offset
+const INVALID_REQUEST: PdError = PdError::new(ErrorDomain::Clock, ErrorKind::InvalidArgument);
+
+// ============================================================================
+// SHARED MEMORY
+// ============================================================================
+
+/// A shared memory region: zeroed, page-aligned heap memory, as Microkit
+/// maps a `memory_region` into each PD that names it
+pub struct Region {
+    base: *mut u8,
+    layout: Layout,
+}
+
+// Safety: the region is only reached through `base`, and the protocols
+// decide which PD may touch which bytes when, exactly as with real shared
+// memory
+unsafe impl Send for Region {}
+unsafe impl Sync for Region {}
+
+impl Region {
+    pub fn new(size: usize) -> Self {
+        let layout = Layout::from_size_align(size, 0x1000).expect("region layout");
+        // Safety: size is non-zero for every region the sim makes
+        let base = unsafe { alloc_zeroed(layout) };
+        assert!(!base.is_null(), "out of memory for a {size:#x} byte region");
+        Self { base, layout }
+    }
+
+    /// Where the region is mapped
+    pub fn base(&self) -> *mut u8 {
+        self.base
+    }
+}
+
+impl Drop for Region {
+    fn drop(&mut self) {
+        // Safety: allocated in `new` with this layout
+        unsafe { dealloc(self.base, self.layout) };
+    }
+}
+
+/// The regions of the simulated system
+struct Regions {
+    input_ring: Region,
+    cmd_ring: Region,
+    pixel_buffer: Region,
+    clock_page: Region,
+    /// Stands in for the system timer registers: one counter, in
+    /// microseconds, that only the Timer PD moves
+    systimer: Region,
+}
+
+impl Regions {
+    /// Allocate the regions and set up the ring and buffer headers, as
+    /// the PDs' `init`s do before any notification is delivered
+    fn new() -> Self {
+        let regions = Self {
+            input_ring: Region::new(RING_BUFFER_SIZE),
+            cmd_ring: Region::new(CMD_RING_SIZE),
+            pixel_buffer: Region::new(PIXEL_BUFFER_SIZE),
+            clock_page: Region::new(CLOCK_PAGE_SIZE),
+            systimer: Region::new(0x1000),
+        };
+        // Safety: each base is a fresh region of the protocol's size
+        unsafe {
+            InputRingHeader::init(header_ptr(regions.input_ring.base()));
+            AtomicCommandRingHeader::init(cmd_ring_header_ptr(regions.cmd_ring.base()));
+            AtomicPixelBufferHeader::init(pixel_header_ptr(regions.pixel_buffer.base()));
+        }
+        regions
+    }
+
+    fn now_us(&self) -> u64 {
+        self.counter().load(Ordering::Acquire)
+    }
+
+    fn counter(&self) -> &AtomicU64 {
+        // Safety: the region is page-aligned and its first word is only
+        // ever used as this counter
+        unsafe { &*(self.systimer.base() as *const AtomicU64) }
+    }
+}
+
+// ============================================================================
+// CHANNELS
+// ============================================================================
+
+/// A device event, delivered to the PD that owns the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Device {
+    /// A key pressed and released on the keyboard (an input-protocol key code)
+    Key(u8),
+    /// A byte typed on the UART console
+    Uart(u8),
+    /// The system timer compare matched
+    TimerIrq,
+}
+
+/// What a PD thread wakes up for
+enum Event {
+    /// A notification, on the receiver's channel number
+    Notified(usize),
+    /// A protected procedure call, on the receiver's channel number
+    Protected {
+        channel: usize,
+        label: u64,
+        words: [u64; 2],
+        reply: Sender<u64>,
+    },
+    Device(Device),
+    /// Reply once every earlier event has been handled
+    Barrier(Sender<()>),
+    Stop,
+}
+
+/// One end of a channel: the peer's event queue, and the channel number
+/// the peer sees
+#[derive(Clone)]
+struct Channel {
+    peer: Sender<Event>,
+    id: usize,
+}
+
+impl Channel {
+    fn notify(&self) {
+        // A stopped peer drops notifications, as a faulted PD would
+        let _ = self.peer.send(Event::Notified(self.id));
+    }
+
+    /// Call the peer and wait for its reply label; a stopped peer replies
+    /// with an error
+    fn pp_call(&self, label: u64, words: [u64; 2]) -> u64 {
+        let (reply, answer) = channel();
+        if self
+            .peer
+            .send(Event::Protected {
+                channel: self.id,
+                label,
+                words,
+                reply,
+            })
+            .is_err()
+        {
+            return WRONG_CHANNEL.to_wire() as u64;
+        }
+        answer.recv().unwrap_or(WRONG_CHANNEL.to_wire() as u64)
+    }
+}
+
+/// The Microkit entry points a simulated PD implements
+trait Pd: Send + 'static {
+    fn notified(&mut self, channel: usize);
+
+    fn protected(&mut self, _channel: usize, _label: u64, _words: [u64; 2]) -> u64 {
+        WRONG_CHANNEL.to_wire() as u64
+    }
+
+    fn device(&mut self, _event: Device) {}
+}
+
+/// Run `pd` on its own thread until it is told to stop
+fn spawn<P: Pd>(name: &str, mut pd: P, events: Receiver<Event>) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(move || {
+            for event in events {
+                match event {
+                    Event::Notified(channel) => pd.notified(channel),
+                    Event::Protected {
+                        channel,
+                        label,
+                        words,
+                        reply,
+                    } => {
+                        let _ = reply.send(pd.protected(channel, label, words));
+                    }
+                    Event::Device(event) => pd.device(event),
+                    Event::Barrier(done) => {
+                        let _ = done.send(());
+                    }
+                    Event::Stop => break,
+                }
+            }
+        })
+        .expect("spawn PD thread")
+}
+
+// ============================================================================
+// INPUT PD
+// ============================================================================
+
+/// Writes key events to the input ring; turns a time typed on the UART into
+/// a SET_TIME call to the Timer PD
+struct InputPd {
+    regions: Arc<Regions>,
+    display: Channel,
+    clock: Channel,
+    entry: SetTimeEntry,
+}
+
+impl Pd for InputPd {
+    fn notified(&mut self, _channel: usize) {}
+
+    fn device(&mut self, event: Device) {
+        match event {
+            Device::Key(code) => {
+                let at = Timestamp::from_micros(self.regions.now_us());
+                let batch = [
+                    InputRingEntry::key(code, KeyState::Pressed, 0).at(at),
+                    InputRingEntry::key(code, KeyState::Released, 0).at(at),
+                ];
+                let base = self.regions.input_ring.base();
+                // Safety: base is the input ring, set up by `Regions::new`,
+                // and this PD is its only producer
+                unsafe { (*header_ptr(base)).write_batch(entries_ptr(base), &batch) };
+                self.display.notify();
+            }
+            Device::Uart(byte) => {
+                if let EntryFeed::Set(request) = self.entry.feed(byte) {
+                    self.clock.pp_call(request.label(), request.to_words());
+                }
+            }
+            Device::TimerIrq => {}
+        }
+    }
+}
+
+// ============================================================================
+// TIMER PD
+// ============================================================================
+
+/// Moves the system timer, ticks the Display PD and publishes the clock page
+struct TimerPd {
+    regions: Arc<Regions>,
+    display: Channel,
+    time: ClockTime,
+}
+
+impl TimerPd {
+    fn publish(&self) {
+        // Safety: the clock page region, and this PD is its only writer
+        unsafe { ClockPage::publish(self.regions.clock_page.base(), &self.time) };
+    }
+}
+
+impl Pd for TimerPd {
+    fn notified(&mut self, _channel: usize) {}
+
+    fn protected(&mut self, channel: usize, label: u64, words: [u64; 2]) -> u64 {
+        let Some(source) = source_for_channel(channel) else {
+            return WRONG_CHANNEL.to_wire() as u64;
+        };
+        let Some(request) = decode_request(label, words[0], words[1]) else {
+            return INVALID_REQUEST.with_detail(label as u16).to_wire() as u64;
+        };
+        self.time = ClockTime {
+            epoch_s: request.epoch_s,
+            mono_us: self.regions.now_us(),
+            utc_offset_min: request.utc_offset_min.unwrap_or(self.time.utc_offset_min),
+            source,
+        };
+        self.publish();
+        0
+    }
+
+    fn device(&mut self, event: Device) {
+        if event == Device::TimerIrq {
+            self.regions.counter().fetch_add(TICK_US, Ordering::AcqRel);
+            self.display.notify();
+        }
+    }
+}
+
+// ============================================================================
+// DECODER PD
+// ============================================================================
+
+/// Decodes the photo the Display PD asks for into the pixel buffer
+///
+/// Holds the encoded photos itself, standing in for the file chunks the
+/// Storage PD would hand over.
+struct DecoderPd {
+    regions: Arc<Regions>,
+    display: Channel,
+    photos: Vec<Vec<u8>>,
+    heap: &'static (dyn HeapControl + Sync),
+    /// Photo asked for while the Display PD still held the last one
+    pending: Option<u16>,
+}
+
+impl DecoderPd {
+    fn header(&self) -> &AtomicPixelBufferHeader {
+        // Safety: the pixel buffer region, set up by `Regions::new`
+        unsafe { &*pixel_header_ptr(self.regions.pixel_buffer.base()) }
+    }
+
+    /// Decode photo `index` into the pixel buffer and mark it ready, or
+    /// mark the buffer as failed
+    fn load(&self, index: u16) {
+        let base = self.regions.pixel_buffer.base();
+        let header = self.header();
+        header.set_loading();
+        // Safety: the Display PD leaves the pixels alone until the buffer
+        // is ready again, and the data area holds MAX_PIXELS pixels
+        let pixels = unsafe { std::slice::from_raw_parts_mut(pixel_data_ptr(base) as *mut u32, MAX_PIXELS as usize) };
+        // Safety: as above, the Display PD reads the index only once the
+        // status says the buffer is ready or failed
+        unsafe { addr_of_mut!((*pixel_header_ptr(base)).photo_index).write(index) };
+
+        let Some(result) = self
+            .photos
+            .get(index as usize)
+            .and_then(|data| secure_decode_into(data, pixels, self.heap).ok())
+        else {
+            header.set_error();
+            return;
+        };
+        let len = (result.width * result.height) as usize;
+        // Safety: the decode just wrote these `len` pixels
+        let bytes = unsafe { std::slice::from_raw_parts(pixels.as_ptr() as *const u8, len * 4) };
+        header.set_dimensions(result.width, result.height, PIXEL_FORMAT_RGBA32);
+        header.set_frame(0, result.frame_count, result.frame_delay_ms);
+        header.checksum.store(compute_checksum(bytes), Ordering::Release);
+        header.set_ready();
+    }
+}
+
+impl Pd for DecoderPd {
+    fn notified(&mut self, channel: usize) {
+        if channel != DISPLAY_TO_DECODER_CHANNEL_ID {
+            return;
+        }
+        let base = self.regions.cmd_ring.base();
+        let mut batch = [PhotoCommand::empty(); 8];
+        loop {
+            // Safety: the command ring region, set up by `Regions::new`,
+            // and this PD is its only consumer
+            let n = unsafe { (*cmd_ring_header_ptr(base)).pop_batch(cmd_entries_ptr(base), &mut batch) };
+            if n == 0 {
+                break;
+            }
+            // Only the latest photo asked for is worth decoding
+            for cmd in &batch[..n] {
+                if cmd.command == CMD_GOTO {
+                    self.pending = Some(cmd.photo_index);
+                }
+            }
+        }
+        // A buffer the Display PD has not taken yet is left alone; it
+        // notifies again once it has
+        if self.header().is_ready() {
+            return;
+        }
+        if let Some(index) = self.pending.take() {
+            self.load(index);
+            self.display.notify();
+        }
+    }
+}
+
+// ============================================================================
+// DISPLAY PD
+// ============================================================================
+
+/// What the Display PD has put on the screen, for tests to look at
+#[derive(Clone, Debug, Default)]
+pub struct Screen {
+    /// Photo on screen
+    pub photo: Option<usize>,
+    /// Photos drawn so far
+    pub presented: u32,
+    /// Photos the Decoder PD failed on
+    pub load_errors: u32,
+    /// Pixel buffers refused by the Display PD's checks
+    pub rejected: u32,
+    pub paused: bool,
+    /// Ticks seen
+    pub ticks: u32,
+    /// Wall-clock seconds at the last tick, once the time is set
+    pub wall_clock: Option<u64>,
+    /// Input events lost in the ring
+    pub missed_events: u32,
+    /// The framebuffer, `SCREEN_W` x `SCREEN_H` ARGB32
+    pub framebuffer: Vec<u32>,
+}
+
+/// The Display PD's [`Screen`] and a way to wait for it to change
+struct ScreenWatch {
+    screen: Mutex<Screen>,
+    changed: Condvar,
+}
+
+impl ScreenWatch {
+    fn update(&self, f: impl FnOnce(&mut Screen)) {
+        f(&mut self.screen.lock().unwrap());
+        self.changed.notify_all();
+    }
+}
+
+/// Turns keys into playlist commands, has photos decoded and draws them,
+/// and advances the slideshow on the tick
+struct DisplayPd {
+    regions: Arc<Regions>,
+    decoder: Channel,
+    watch: Arc<ScreenWatch>,
+    playlist: Playlist,
+    paused: bool,
+    /// Photo asked of the Decoder PD; any other in the buffer is stale
+    requested: Option<u16>,
+    /// System timer reading the slideshow moves on at
+    next_slide_us: Option<u64>,
+    seq: SeqTracker,
+    framebuffer: Vec<u32>,
+}
+
+impl DisplayPd {
+    /// Ask the Decoder PD for `photo`
+    fn show(&mut self, photo: usize) {
+        let index = photo as u16;
+        self.requested = Some(index);
+        self.next_slide_us = None;
+        let base = self.regions.cmd_ring.base();
+        // Safety: the command ring region, and this PD is its only producer
+        unsafe { (*cmd_ring_header_ptr(base)).write_batch(cmd_entries_ptr(base), &[PhotoCommand::goto(index)]) };
+        self.decoder.notify();
+    }
+
+    fn handle_command(&mut self, cmd: PhotoCommand) {
+        match cmd.command {
+            CMD_NEXT => {
+                let photo = self.playlist.next();
+                self.show(photo);
+            }
+            CMD_PREV => {
+                let photo = self.playlist.prev();
+                self.show(photo);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, code: u8) {
+        match code {
+            KEY_RIGHT | KEY_DOWN => self.handle_command(PhotoCommand::next()),
+            KEY_LEFT | KEY_UP => self.handle_command(PhotoCommand::prev()),
+            KEY_SPACE => {
+                self.paused = !self.paused;
+                let paused = self.paused;
+                self.watch.update(|screen| screen.paused = paused);
+            }
+            KEY_ESCAPE => {
+                self.playlist.goto(0);
+                self.paused = false;
+                self.watch.update(|screen| screen.paused = false);
+                self.show(0);
+            }
+            _ => {}
+        }
+    }
+
+    fn drain_input(&mut self) {
+        let base = self.regions.input_ring.base();
+        let mut batch = [InputRingEntry::EMPTY; 16];
+        loop {
+            // Safety: the input ring region, and this PD is its only consumer
+            let n = unsafe { (*header_ptr(base)).pop_batch(entries_ptr(base), &mut batch) };
+            if n == 0 {
+                break;
+            }
+            for entry in &batch[..n] {
+                self.seq.observe(entry);
+                if entry.is_key_pressed() {
+                    self.handle_key(entry.key_code);
+                }
+            }
+        }
+        let missed = self.seq.missed();
+        self.watch.update(|screen| screen.missed_events = missed);
+    }
+
+    /// Take the pixel buffer: draw a photo that passes the checks, skip
+    /// one that failed to decode, and hand the buffer back
+    fn present(&mut self) {
+        let base = self.regions.pixel_buffer.base();
+        // Safety: the pixel buffer region, set up by `Regions::new`
+        let header = unsafe { &*pixel_header_ptr(base) };
+        let status = header.status.load(Ordering::Acquire);
+        if status != BUFFER_STATUS_READY && status != BUFFER_STATUS_ERROR {
+            return;
+        }
+        // Safety: the status is ready or failed, so the Decoder PD is done
+        // writing the index
+        let index = unsafe { addr_of!((*pixel_header_ptr(base)).photo_index).read() };
+        if Some(index) == self.requested {
+            if status == BUFFER_STATUS_READY {
+                self.draw(header);
+            } else {
+                self.skip_failed(index as usize);
+            }
+        }
+        header.set_empty();
+        // The Decoder PD may be holding a newer request for the buffer
+        self.decoder.notify();
+    }
+
+    /// Check the buffered photo against the protocol's limits and its
+    /// checksum, then letterbox it onto the framebuffer
+    fn draw(&mut self, header: &AtomicPixelBufferHeader) {
+        let (width, height) = header.get_dimensions();
+        let format = header.format.load(Ordering::Acquire);
+        let data_len = header.data_len.load(Ordering::Acquire) as usize;
+        let (frame, frames, _) = header.get_frame();
+        let sane = (1..=MAX_PHOTO_WIDTH).contains(&width)
+            && (1..=MAX_PHOTO_HEIGHT).contains(&height)
+            && format == PIXEL_FORMAT_RGBA32
+            && data_len == (width * height * 4) as usize
+            && frame < frames;
+        // Safety: the dimensions were just checked to fit the data area
+        let bytes = sane.then(|| unsafe {
+            std::slice::from_raw_parts(pixel_data_ptr(self.regions.pixel_buffer.base()) as *const u8, data_len)
+        });
+        let Some(bytes) = bytes.filter(|bytes| compute_checksum(bytes) == header.checksum.load(Ordering::Acquire))
+        else {
+            self.watch.update(|screen| screen.rejected += 1);
+            return;
+        };
+        // Safety: the data area is 4-byte aligned and `bytes` is whole pixels
+        let src = unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const u32, bytes.len() / 4) };
+        let (orientation, _, _) = header.get_metadata();
+
+        self.framebuffer.fill(0xFF00_0000);
+        let drawn = letterbox_rect(width, height, SCREEN_W, SCREEN_H).is_some_and(|rect| {
+            blit_scaled(
+                src,
+                width,
+                height,
+                orientation,
+                &mut self.framebuffer,
+                SCREEN_W,
+                SCREEN_H,
+                rect,
+                ScaleFilter::Bilinear,
+            )
+            .is_ok()
+        });
+        if !drawn {
+            self.watch.update(|screen| screen.rejected += 1);
+            return;
+        }
+
+        let photo = self.playlist.current();
+        let dwell_us = self.playlist.dwell(photo, SLIDESHOW_INTERVAL_S) as u64 * 1_000_000;
+        self.next_slide_us = Some(self.regions.now_us() + dwell_us);
+        let framebuffer = &self.framebuffer;
+        self.watch.update(|screen| {
+            screen.photo = Some(photo);
+            screen.presented += 1;
+            screen.framebuffer.clone_from(framebuffer);
+        });
+    }
+
+    /// Leave a photo that fails to decode out of the slideshow and move on
+    fn skip_failed(&mut self, photo: usize) {
+        self.watch.update(|screen| screen.load_errors += 1);
+        self.playlist.set_skip(photo, true);
+        let next = self.playlist.next();
+        if next != photo {
+            self.show(next);
+        }
+    }
+
+    fn tick(&mut self) {
+        let now = self.regions.now_us();
+        // Safety: the clock page region; the seqlock makes a torn read
+        // come back as `None`
+        let wall_clock = unsafe { ClockPage::read(self.regions.clock_page.base()) }.and_then(|time| time.epoch_at(now));
+        self.watch.update(|screen| {
+            screen.ticks += 1;
+            screen.wall_clock = wall_clock;
+        });
+        if !self.paused && self.next_slide_us.is_some_and(|due| now >= due) {
+            self.handle_command(PhotoCommand::next());
+        }
+    }
+}
+
+impl Pd for DisplayPd {
+    fn notified(&mut self, channel: usize) {
+        match channel {
+            INPUT_CHANNEL_ID => self.drain_input(),
+            DECODER_CHANNEL_ID => self.present(),
+            TIMER_CHANNEL_ID => self.tick(),
+            _ => {}
+        }
+    }
+}
+
+// ============================================================================
+// THE SYSTEM
+// ============================================================================
+
+/// The Input, Timer, Decoder and Display PDs, running
+///
+/// Dropping the simulation stops every PD thread.
+pub struct SystemSim {
+    input: Sender<Event>,
+    timer: Sender<Event>,
+    queues: Vec<Sender<Event>>,
+    threads: Vec<JoinHandle<()>>,
+    watch: Arc<ScreenWatch>,
+}
+
+impl SystemSim {
+    /// Start the system with `photos` (encoded files) in the slideshow;
+    /// the Decoder PD decodes on `heap`. The Display PD asks for the
+    /// first photo straight away.
+    pub fn boot(photos: Vec<Vec<u8>>, heap: &'static (dyn HeapControl + Sync)) -> Self {
+        let regions = Arc::new(Regions::new());
+        let watch = Arc::new(ScreenWatch {
+            screen: Mutex::new(Screen::default()),
+            changed: Condvar::new(),
+        });
+        let (input, input_events) = channel();
+        let (timer, timer_events) = channel();
+        let (decoder, decoder_events) = channel();
+        let (display, display_events) = channel();
+
+        let time = ClockTime::unset(0);
+        // Safety: the Timer PD has not started, so nothing else writes the page
+        unsafe { ClockPage::publish(regions.clock_page.base(), &time) };
+
+        let mut display_pd = DisplayPd {
+            regions: regions.clone(),
+            decoder: Channel {
+                peer: decoder.clone(),
+                id: DISPLAY_TO_DECODER_CHANNEL_ID,
+            },
+            watch: watch.clone(),
+            playlist: Playlist::new(photos.len(), 0),
+            paused: false,
+            requested: None,
+            next_slide_us: None,
+            seq: SeqTracker::new(),
+            framebuffer: vec![0; (SCREEN_W * SCREEN_H) as usize],
+        };
+        if !photos.is_empty() {
+            display_pd.show(display_pd.playlist.current());
+        }
+
+        let threads = vec![
+            spawn(
+                "input",
+                InputPd {
+                    regions: regions.clone(),
+                    display: Channel {
+                        peer: display.clone(),
+                        id: INPUT_CHANNEL_ID,
+                    },
+                    clock: Channel {
+                        peer: timer.clone(),
+                        id: TIMER_UART_CHANNEL_ID,
+                    },
+                    entry: SetTimeEntry::new(),
+                },
+                input_events,
+            ),
+            spawn(
+                "timer",
+                TimerPd {
+                    regions: regions.clone(),
+                    display: Channel {
+                        peer: display.clone(),
+                        id: TIMER_CHANNEL_ID,
+                    },
+                    time,
+                },
+                timer_events,
+            ),
+            spawn(
+                "decoder",
+                DecoderPd {
+                    regions,
+                    display: Channel {
+                        peer: display.clone(),
+                        id: DECODER_CHANNEL_ID,
+                    },
+                    photos,
+                    heap,
+                    pending: None,
+                },
+                decoder_events,
+            ),
+            spawn("display", display_pd, display_events),
+        ];
+        let queues = vec![input.clone(), timer.clone(), decoder, display];
+        Self {
+            input,
+            timer,
+            queues,
+            threads,
+            watch,
+        }
+    }
+
+    /// Press and release a key on the keyboard
+    pub fn press(&self, code: u8) {
+        let _ = self.input.send(Event::Device(Device::Key(code)));
+    }
+
+    /// Type `text` on the UART console, and wait for the Input PD to have
+    /// handled it, calls included
+    pub fn type_uart(&self, text: &[u8]) {
+        for &byte in text {
+            let _ = self.input.send(Event::Device(Device::Uart(byte)));
+        }
+        let (done, handled) = channel();
+        if self.input.send(Event::Barrier(done)).is_ok() {
+            let _ = handled.recv();
+        }
+    }
+
+    /// Fire `n` timer ticks, moving the system timer on `n * TICK_US`
+    pub fn tick(&self, n: u32) {
+        for _ in 0..n {
+            let _ = self.timer.send(Event::Device(Device::TimerIrq));
+        }
+    }
+
+    /// What is on the screen now
+    pub fn screen(&self) -> Screen {
+        self.watch.screen.lock().unwrap().clone()
+    }
+
+    /// Wait until the screen satisfies `done` and return it; `None` if it
+    /// still does not after a generous timeout
+    pub fn wait_for(&self, done: impl Fn(&Screen) -> bool) -> Option<Screen> {
+        let deadline = Instant::now() + WAIT_TIMEOUT;
+        let mut screen = self.watch.screen.lock().unwrap();
+        while !done(&screen) {
+            let left = deadline.checked_duration_since(Instant::now())?;
+            screen = self.watch.changed.wait_timeout(screen, left).unwrap().0;
+        }
+        Some(screen.clone())
+    }
+}
+
+impl Drop for SystemSim {
+    fn drop(&mut self) {
+        for queue in &self.queues {
+            let _ = queue.send(Event::Stop);
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
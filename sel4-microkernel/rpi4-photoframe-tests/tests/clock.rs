@@ -0,0 +1,67 @@
+//! Tests for the clock overlay: time and date text, corner placement
+//! around the info bars, and the night window.
+
+use rpi4_clock_protocol::local_time;
+use rpi4_photoframe_tests::clock::{clock_origin, Corner, DateText, NightMode, TimeText, CLOCK_MARGIN};
+
+const SCREEN: (u32, u32) = (1280, 720);
+
+#[test]
+fn formats_time_and_date() {
+    // 2026-10-16 07:05:09 UTC, a Friday
+    let t = local_time(1_792_134_309, 0).unwrap();
+    assert_eq!(format!("{}", TimeText(&t)), "07:05");
+    assert_eq!(format!("{}", DateText(&t)), "FRI 16 OCT 2026");
+    // Nine and a half hours east is the afternoon
+    let t = local_time(1_792_134_309, 9 * 60 + 30).unwrap();
+    assert_eq!(format!("{}", TimeText(&t)), "16:35");
+    // Just before midnight on New Year's Eve, five hours west
+    let t = local_time(1_798_761_599 + 5 * 3600, -5 * 60).unwrap();
+    assert_eq!(format!("{} {}", TimeText(&t), DateText(&t)), "23:59 THU 31 DEC 2026");
+}
+
+#[test]
+fn corners_keep_clear_of_the_bars() {
+    let size = (200, 60);
+    assert_eq!(clock_origin(Corner::TopLeft, SCREEN, size, 0, 0), (CLOCK_MARGIN, CLOCK_MARGIN));
+    assert_eq!(clock_origin(Corner::BottomRight, SCREEN, size, 0, 0), (1280 - 20 - 200, 720 - 20 - 60));
+    // With the info shown: below the top bar, above the bottom one
+    assert_eq!(clock_origin(Corner::TopRight, SCREEN, size, 40, 66), (1060, 60));
+    assert_eq!(clock_origin(Corner::BottomLeft, SCREEN, size, 40, 66), (20, 720 - 66 - 20 - 60));
+    // Too big for the screen: pinned to the edge, never wrapped round
+    assert_eq!(clock_origin(Corner::BottomRight, (100, 50), size, 0, 0), (0, 0));
+}
+
+#[test]
+fn clock_key_cycles_through_every_corner_and_hidden() {
+    let mut corner = Some(Corner::TopRight);
+    let mut seen = Vec::new();
+    for _ in 0..5 {
+        corner = Corner::cycle(corner);
+        seen.push(corner);
+    }
+    assert_eq!(
+        seen,
+        [Some(Corner::BottomRight), Some(Corner::BottomLeft), Some(Corner::TopLeft), None, Some(Corner::TopRight)]
+    );
+}
+
+#[test]
+fn night_runs_over_midnight() {
+    let night = NightMode::DEFAULT;
+    let at = |h: i64, m: i64| local_time((h * 3600 + m * 60) as u64 + 86_400, 0).unwrap();
+    assert!(night.is_night(&at(22, 0)));
+    assert!(night.is_night(&at(3, 30)));
+    assert!(night.is_night(&at(6, 59)));
+    assert!(!night.is_night(&at(7, 0)));
+    assert!(!night.is_night(&at(12, 0)));
+    assert!(!night.is_night(&at(21, 59)));
+    assert_eq!(night.shade() >> 24, night.dim_alpha as u32);
+    assert_eq!(night.shade() & 0x00FF_FFFF, 0);
+
+    // An empty window never dims; minutes past a day wrap rather than panic
+    let never = NightMode { start_min: 0, end_min: 0, ..night };
+    assert!(!never.is_night(&at(0, 0)));
+    let odd = NightMode { start_min: 22 * 60 + 1440, end_min: 7 * 60 + 1440, ..night };
+    assert!(odd.is_night(&at(23, 0)));
+}
@@ -0,0 +1,94 @@
+//! Tests for the debug overlay: the toggle chord, the frame-rate meter and
+//! the readout and memory gauge text.
+
+use rpi4_input_protocol::{KEY_NUM0, KEY_NUM9, KEY_RIGHT};
+use rpi4_pd_error::mem::{Reading, Unit};
+use rpi4_photoframe_tests::debug_overlay::{
+    key_name, Chord, DebugStats, FpsMeter, MemText, CHORD_WINDOW_TICKS, DEBUG_LINES,
+};
+
+#[test]
+fn chord_needs_both_keys_together() {
+    let mut chord = Chord::new();
+    // Hold 9, press 0
+    assert!(!chord.key(KEY_NUM9, true, 100));
+    assert!(chord.key(KEY_NUM0, true, 101));
+    // Either order
+    assert!(!chord.key(KEY_NUM0, true, 200));
+    assert!(chord.key(KEY_NUM9, true, 201));
+    // 9 let go first
+    assert!(!chord.key(KEY_NUM9, true, 300));
+    assert!(!chord.key(KEY_NUM9, false, 301));
+    assert!(!chord.key(KEY_NUM0, true, 302));
+    // Another key in between
+    let mut chord = Chord::new();
+    assert!(!chord.key(KEY_NUM9, true, 400));
+    assert!(!chord.key(KEY_RIGHT, true, 401));
+    assert!(!chord.key(KEY_NUM0, true, 402));
+}
+
+#[test]
+fn chord_without_releases_needs_quick_presses() {
+    // The UART console sends presses only
+    let mut chord = Chord::new();
+    assert!(!chord.key(KEY_NUM9, true, 0));
+    assert!(!chord.key(KEY_NUM0, true, CHORD_WINDOW_TICKS + 1));
+    assert!(chord.key(KEY_NUM9, true, CHORD_WINDOW_TICKS + 1 + CHORD_WINDOW_TICKS));
+    // The same key twice is not a chord
+    assert!(!chord.key(KEY_NUM0, true, 1000));
+    assert!(!chord.key(KEY_NUM0, true, 1001));
+}
+
+#[test]
+fn fps_over_whole_seconds() {
+    let mut fps = FpsMeter::new();
+    fps.sample(0, 0, 10);
+    fps.sample(5, 3, 10);
+    assert_eq!(fps.tenths(), None);
+    fps.sample(10, 10, 10);
+    assert_eq!(fps.tenths(), Some(100));
+    // A late tick stretches the second rather than inflating the figure
+    fps.sample(25, 13, 10);
+    assert_eq!(fps.tenths(), Some(20));
+    // Counters wrap
+    let mut fps = FpsMeter::new();
+    fps.sample(u32::MAX - 4, u32::MAX, 10);
+    fps.sample(5, 4, 10);
+    assert_eq!(fps.tenths(), Some(50));
+}
+
+#[test]
+fn readout_lines() {
+    let stats = DebugStats {
+        fps_tenths: Some(98),
+        frames: 1234,
+        missed_ticks: 0,
+        last_key: Some((KEY_RIGHT, 3)),
+        input_pd_s: Some(3),
+        timer_pd_up: true,
+    };
+    let lines: Vec<String> = (0..DEBUG_LINES).map(|n| stats.line(n).unwrap().to_string()).collect();
+    assert_eq!(lines, ["FPS 9.8  FRAMES 1234  MISSED 0", "LAST KEY RIGHT 3S AGO", "INPUT PD 3S AGO  TIMER PD UP"]);
+    assert!(stats.line(DEBUG_LINES).is_none());
+
+    let idle = DebugStats::default();
+    assert_eq!(idle.line(0).unwrap().to_string(), "FPS --  FRAMES 0  MISSED 0");
+    assert_eq!(idle.line(1).unwrap().to_string(), "LAST KEY NONE");
+    assert_eq!(idle.line(2).unwrap().to_string(), "INPUT PD SILENT  TIMER PD NO PAGE");
+}
+
+#[test]
+fn memory_gauge_lines() {
+    let heap = Reading::new("decoder_heap", Unit::Bytes, 0, 412 * 1024, 16 << 20);
+    assert_eq!(MemText(&heap).to_string(), "DECODER HEAP 0/16384 KB  PEAK 412 KB (3%)");
+    let ring = Reading::new("input_ring", Unit::Slots, 0, 3, 511);
+    assert_eq!(MemText(&ring).to_string(), "INPUT RING 0/511  PEAK 3 (1%)");
+}
+
+#[test]
+fn key_names() {
+    assert_eq!(key_name(KEY_NUM0), "0");
+    assert_eq!(key_name(KEY_NUM9), "9");
+    assert_eq!(key_name(KEY_RIGHT), "RIGHT");
+    assert_eq!(key_name(200), "?");
+}
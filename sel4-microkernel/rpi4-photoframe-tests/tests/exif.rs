@@ -0,0 +1,197 @@
+//! Tests for the EXIF reader: orientation, capture time and camera model
+//! from both byte orders, and hostile offsets that point outside the block.
+
+use rpi4_photo_protocol::{CaptureTime, Orientation, PixelBufferHeader, PIXEL_FORMAT_RGBA32};
+use rpi4_photoframe_tests::exif::{read_exif, ExifInfo};
+
+/// One IFD entry: tag, type, count and the 4-byte value field (already in
+/// the file's byte order)
+struct Field {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    value: [u8; 4],
+}
+
+/// TIFF writer for one byte order
+struct Tiff {
+    big_endian: bool,
+    bytes: Vec<u8>,
+}
+
+impl Tiff {
+    fn new(big_endian: bool) -> Self {
+        let mut tiff = Tiff { big_endian, bytes: Vec::new() };
+        tiff.bytes.extend_from_slice(if big_endian { b"MM" } else { b"II" });
+        tiff.u16(42);
+        tiff.u32(8);
+        tiff
+    }
+
+    fn u16(&mut self, v: u16) {
+        let b = if self.big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        self.bytes.extend_from_slice(&b);
+    }
+
+    fn u32(&mut self, v: u32) {
+        let b = if self.big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        self.bytes.extend_from_slice(&b);
+    }
+
+    fn short(&self, tag: u16, v: u16) -> Field {
+        let mut value = [0; 4];
+        let b = if self.big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        value[..2].copy_from_slice(&b);
+        Field { tag, kind: 3, count: 1, value }
+    }
+
+    fn long(&self, tag: u16, v: u32) -> Field {
+        let value = if self.big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+        Field { tag, kind: 4, count: 1, value }
+    }
+
+    /// A string of `count` bytes (NUL included) at TIFF offset `offset`
+    fn ascii_at(&self, tag: u16, count: u32, offset: u32) -> Field {
+        Field { kind: 2, count, ..self.long(tag, offset) }
+    }
+
+    /// An IFD at the current end, followed by a zero next-IFD link
+    fn ifd(&mut self, fields: &[Field]) {
+        self.u16(fields.len() as u16);
+        for f in fields {
+            self.u16(f.tag);
+            self.u16(f.kind);
+            self.u32(f.count);
+            self.bytes.extend_from_slice(&f.value);
+        }
+        self.u32(0);
+    }
+}
+
+/// A JPEG prefix (SOI, APP1 with `tiff`, then SOS) as far as the reader looks
+fn jpeg(tiff: &[u8]) -> Vec<u8> {
+    let mut data = vec![0xFF, 0xD8];
+    // An APP0 segment first, as most cameras write
+    data.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00]);
+    data.extend_from_slice(&[0xFF, 0xE1]);
+    data.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    data.extend_from_slice(b"Exif\0\0");
+    data.extend_from_slice(tiff);
+    data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02]);
+    data
+}
+
+/// A camera's EXIF: IFD0 with orientation, model, date and the Exif IFD
+/// link, then the Exif IFD with the original date
+fn camera_exif(big_endian: bool, orientation: u16) -> Vec<u8> {
+    let mut tiff = Tiff::new(big_endian);
+    // IFD0 at 8 (4 entries), Exif IFD after it (1 entry), then the strings
+    let ifd0_len = 2 + 4 * 12 + 4;
+    let exif_ifd = 8 + ifd0_len as u32;
+    let strings = exif_ifd + 2 + 12 + 4;
+    let model = b"PiCam Module 3\0";
+    let date = b"2023:01:02 03:04:05\0";
+    let original = b"2022:11:30 23:59:00\0";
+    let fields = [
+        tiff.short(0x0112, orientation),
+        tiff.ascii_at(0x0110, model.len() as u32, strings),
+        tiff.ascii_at(0x0132, date.len() as u32, strings + model.len() as u32),
+        tiff.long(0x8769, exif_ifd),
+    ];
+    tiff.ifd(&fields);
+    let original_field = tiff.ascii_at(0x9003, original.len() as u32, strings + (model.len() + date.len()) as u32);
+    tiff.ifd(&[original_field]);
+    tiff.bytes.extend_from_slice(model);
+    tiff.bytes.extend_from_slice(date);
+    tiff.bytes.extend_from_slice(original);
+    jpeg(&tiff.bytes)
+}
+
+#[test]
+fn reads_orientation_model_and_original_date() {
+    for big_endian in [false, true] {
+        let info = read_exif(&camera_exif(big_endian, 6));
+        assert_eq!(info.orientation, Orientation::Rotate90);
+        assert_eq!(info.model(), "PiCam Module 3");
+        // DateTimeOriginal wins over DateTime
+        assert_eq!(info.taken, Some(CaptureTime { year: 2022, month: 11, day: 30, hour: 23, minute: 59 }));
+    }
+}
+
+#[test]
+fn falls_back_to_date_time() {
+    let mut tiff = Tiff::new(false);
+    let date = b"2021:06:07 08:09:10\0";
+    let field = tiff.ascii_at(0x0132, date.len() as u32, 8 + 2 + 12 + 4);
+    tiff.ifd(&[field]);
+    tiff.bytes.extend_from_slice(date);
+    let info = read_exif(&jpeg(&tiff.bytes));
+    assert_eq!(info.taken, Some(CaptureTime { year: 2021, month: 6, day: 7, hour: 8, minute: 9 }));
+    assert_eq!(info.orientation, Orientation::Normal);
+    assert_eq!(info.model(), "");
+}
+
+#[test]
+fn no_exif_is_upright_with_nothing_known() {
+    assert_eq!(read_exif(&[]), ExifInfo::NONE);
+    assert_eq!(read_exif(b"\x89PNG\r\n\x1a\n"), ExifInfo::NONE);
+    // JPEG whose first segment is the image data
+    assert_eq!(read_exif(&[0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02]), ExifInfo::NONE);
+    // "Unknown" dates and orientations outside 1-8 are ignored
+    let mut tiff = Tiff::new(true);
+    let date = b"0000:00:00 00:00:00\0";
+    let fields = [tiff.short(0x0112, 9), tiff.ascii_at(0x0132, date.len() as u32, 8 + 2 + 2 * 12 + 4)];
+    tiff.ifd(&fields);
+    tiff.bytes.extend_from_slice(date);
+    assert_eq!(read_exif(&jpeg(&tiff.bytes)), ExifInfo::NONE);
+}
+
+#[test]
+fn offsets_outside_the_segment_are_ignored() {
+    let mut tiff = Tiff::new(false);
+    let fields = [
+        tiff.short(0x0112, 3),
+        // Model pointing far past the end, and one wrapping round u32
+        tiff.ascii_at(0x0110, 64, 0x7FFF_FFF0),
+        tiff.ascii_at(0x0132, 20, u32::MAX),
+        // Exif IFD link past the end
+        tiff.long(0x8769, 0xFFFF_0000),
+    ];
+    tiff.ifd(&fields);
+    let info = read_exif(&jpeg(&tiff.bytes));
+    assert_eq!(info.orientation, Orientation::Rotate180);
+    assert_eq!(info.model(), "");
+    assert_eq!(info.taken, None);
+
+    // An entry count larger than the segment holds: the entries that fit
+    // are read, the walk stops at the first that does not
+    let mut tiff = Tiff::new(false);
+    tiff.u16(0xFFFF);
+    let orientation = tiff.short(0x0112, 8);
+    tiff.u16(orientation.tag);
+    tiff.u16(orientation.kind);
+    tiff.u32(orientation.count);
+    tiff.bytes.extend_from_slice(&orientation.value);
+    assert_eq!(read_exif(&jpeg(&tiff.bytes)).orientation, Orientation::Rotate270);
+}
+
+#[test]
+fn truncated_segments_are_ignored() {
+    let full = camera_exif(false, 6);
+    // Cut anywhere before the end of the APP1 segment (the last 4 bytes
+    // are the SOS marker): the segment length promises more than is there,
+    // so nothing is read
+    for len in 0..full.len() - 4 {
+        assert_eq!(read_exif(&full[..len]), ExifInfo::NONE);
+    }
+}
+
+#[test]
+fn metadata_round_trips_through_the_pixel_buffer_header() {
+    let info = read_exif(&camera_exif(true, 8));
+    let header = info.stamp(PixelBufferHeader::new(64, 48, PIXEL_FORMAT_RGBA32, 2));
+    assert_eq!(header.orientation, 8);
+    assert_eq!((header.width, header.height), (64, 48));
+    assert_eq!(ExifInfo::from_header(&header), info);
+    assert_eq!(ExifInfo::from_header(&PixelBufferHeader::empty()), ExifInfo::NONE);
+}
@@ -0,0 +1,47 @@
+//! Tests for the health panel: a line per registered client under the
+//! watchdog header.
+
+use rpi4_photoframe_tests::health::{health_line, health_rows, HEALTH_LINES};
+use rpi4_watchdog_protocol::{Heartbeats, MAX_CLIENTS};
+
+const MS: u64 = 1000;
+
+fn text(line: Option<impl std::fmt::Display>) -> String {
+    line.map(|l| l.to_string()).unwrap_or_default()
+}
+
+#[test]
+fn no_page_is_one_line() {
+    assert_eq!(health_rows(None), 1);
+    assert_eq!(text(health_line(None, 0, 0)), "WATCHDOG NO PAGE");
+    assert!(health_line(None, 1, 0).is_none());
+}
+
+#[test]
+fn one_line_per_registered_client() {
+    let mut beats = Heartbeats::new();
+    beats.register(0, "TIMER", 2_000, 0).unwrap();
+    beats.register(2, "PHOTO", 10_000, 0).unwrap();
+    beats.ping(0, 1_000 * MS);
+    beats.ping(0, 1_700 * MS);
+    beats.fed();
+    let liveness = beats.liveness();
+
+    assert_eq!(health_rows(Some(liveness)), 3);
+    assert!(health_rows(Some(liveness)) <= HEALTH_LINES);
+    let now = 2_050 * MS;
+    assert_eq!(text(health_line(Some(liveness), 0, now)), "WATCHDOG FEEDS 1");
+    assert_eq!(text(health_line(Some(liveness), 1, now)), "TIMER OK  PING 0.3S AGO  2 PINGS  WINDOW 2S");
+    assert_eq!(text(health_line(Some(liveness), 2, now)), "PHOTO WAITING  NO PING  0 PINGS  WINDOW 10S");
+    assert!(health_line(Some(liveness), 3, now).is_none());
+}
+
+#[test]
+fn a_late_client_says_so() {
+    let mut beats = Heartbeats::new();
+    beats.register(1, "TIMER", 2_000, 0).unwrap();
+    assert_eq!(beats.check(2_001 * MS), Some(1));
+    let line = text(health_line(Some(beats.liveness()), 1, 2_001 * MS));
+    assert!(line.starts_with("TIMER LATE"), "{}", line);
+    assert_eq!(HEALTH_LINES, 1 + MAX_CLIENTS);
+}
@@ -2,7 +2,7 @@
 //! sample images, plus the rejection paths the security design relies on.
 
 use rpi4_photoframe_tests::bounded_alloc::BoundedBumpAllocator;
-use rpi4_photoframe_tests::secure_decode::{secure_decode_into, SecureDecodeError};
+use rpi4_photoframe_tests::secure_decode::{secure_decode_animated, secure_decode_into, SecureDecodeError};
 use rpi4_photoframe_tests::validate::ImageType;
 
 /// Path to the committed sample images (independent of the test's cwd).
@@ -53,6 +53,67 @@ fn bmp_and_qoi_decode_identically() {
     assert_eq!(a, b, "QOI and BMP decodes diverged");
 }
 
+#[test]
+fn png_through_secure_pipeline() {
+    // 8-bit RGB, all five filter types, IDAT split across two chunks:
+    // exercises the native inflate path rather than zune-png.
+    // PNG allocates, so each PNG test gets its own pool: a shared one could be
+    // reset by a concurrently running test mid-decode.
+    static PNG_HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let data = photo("sample_gradient.png");
+    let mut out = vec![0u32; 320 * 240];
+    let res = secure_decode_into(&data, &mut out, &PNG_HEAP).expect("png decode");
+    assert_eq!((res.width, res.height), (320, 240));
+    assert!(matches!(res.format, ImageType::Png));
+    assert_eq!(out[108 * 320 + 160], 0xFFFA_D228);
+    // Native path holds only the inflate window and two scanlines.
+    assert!(res.heap_peak < 64 * 1024, "heap peak {}", res.heap_peak);
+}
+
+#[test]
+fn gif_animation_through_secure_pipeline() {
+    // 160x120 spinner: a full background frame, then eight 16x16 dots that
+    // restore to the previous frame, every other one interlaced.
+    static GIF_HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let data = photo("sample_spinner.gif");
+    let mut out = vec![0u32; 160 * 120];
+    let (res, animation) = secure_decode_animated(&data, &mut out, &GIF_HEAP).expect("gif decode");
+    assert_eq!((res.width, res.height), (160, 120));
+    assert!(matches!(res.format, ImageType::Gif));
+    assert_eq!((res.frame_count, res.frame_delay_ms), (9, 100));
+    assert_eq!(out[0], 0xFF18_2040);
+    assert_eq!(out[60 * 160 + 116], 0xFF5A_82C8);
+
+    // The first dot lands right of centre, then is disposed of again
+    let mut gif = animation.expect("animated");
+    let frame = gif.next_frame(&mut out).unwrap();
+    assert_eq!((frame.index, frame.delay_ms), (1, 100));
+    assert_eq!(out[60 * 160 + 116], 0xFFFA_D228);
+    gif.next_frame(&mut out).unwrap();
+    assert_eq!(out[60 * 160 + 116], 0xFF5A_82C8);
+}
+
+#[test]
+fn png_and_qoi_decode_identically() {
+    static PNG_HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let mut a = vec![0u32; 320 * 240];
+    let mut b = vec![0u32; 320 * 240];
+    secure_decode_into(&photo("sample_gradient.qoi"), &mut a, &HEAP).unwrap();
+    secure_decode_into(&photo("sample_gradient.png"), &mut b, &PNG_HEAP).unwrap();
+    assert_eq!(a, b, "QOI and PNG decodes diverged");
+}
+
+#[test]
+fn rejects_corrupt_png_stream() {
+    let mut data = photo("sample_gradient.png");
+    // Flip bits inside the first IDAT payload (signature 8 + IHDR 25 + IDAT header 8).
+    data[8 + 25 + 8 + 100] ^= 0x5A;
+    static PNG_HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let mut out = vec![0u32; 320 * 240];
+    let err = secure_decode_into(&data, &mut out, &PNG_HEAP).unwrap_err();
+    assert!(matches!(err, SecureDecodeError::Decode(_)), "{err:?}");
+}
+
 #[test]
 fn rejects_bad_magic() {
     let junk = [0u8; 64];
@@ -0,0 +1,103 @@
+//! Tests for the slideshow playlist: play orders, skip flags, dwell
+//! overrides and favorites.
+
+use rpi4_photoframe_tests::playlist::{Order, Playlist, MAX_PLAYLIST};
+
+/// The photos visited by `n` calls to `next`
+fn walk(playlist: &mut Playlist, n: usize) -> Vec<usize> {
+    (0..n).map(|_| playlist.next()).collect()
+}
+
+#[test]
+fn sequential_wraps_both_ways() {
+    let mut playlist = Playlist::new(4, 7);
+    assert_eq!(playlist.current(), 0);
+    assert_eq!(walk(&mut playlist, 5), [1, 2, 3, 0, 1]);
+    assert_eq!(playlist.prev(), 0);
+    assert_eq!(playlist.prev(), 3);
+}
+
+#[test]
+fn newest_first_reverses() {
+    let mut playlist = Playlist::new(4, 7);
+    playlist.set_order(Order::NewestFirst);
+    // Stays on photo 0, now last in the order
+    assert_eq!(playlist.current(), 0);
+    assert_eq!(walk(&mut playlist, 4), [3, 2, 1, 0]);
+}
+
+#[test]
+fn shuffle_is_a_permutation_that_keeps_the_current_photo() {
+    let mut playlist = Playlist::new(20, 0xDEAD_BEEF);
+    assert!(playlist.goto(5));
+    playlist.set_order(Order::Shuffle);
+    assert_eq!(playlist.current(), 5);
+
+    let mut seen = walk(&mut playlist, 20);
+    assert_eq!(seen.last(), Some(&5));
+    seen.sort_unstable();
+    assert_eq!(seen, (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn skipped_photos_are_passed_over() {
+    let mut playlist = Playlist::new(4, 1);
+    playlist.set_skip(1, true);
+    playlist.set_skip(2, true);
+    assert!(playlist.is_skipped(2));
+    assert_eq!(walk(&mut playlist, 3), [3, 0, 3]);
+    assert_eq!(playlist.prev(), 0);
+
+    // Still reachable directly
+    assert!(playlist.goto(2));
+    assert_eq!(playlist.current(), 2);
+}
+
+#[test]
+fn everything_else_skipped_stays_put() {
+    let mut playlist = Playlist::new(3, 1);
+    playlist.set_skip(1, true);
+    playlist.set_skip(2, true);
+    assert_eq!(playlist.next(), 0);
+    assert_eq!(playlist.prev(), 0);
+}
+
+#[test]
+fn dwell_overrides_the_interval() {
+    let mut playlist = Playlist::new(3, 1);
+    assert_eq!(playlist.dwell(1, 300), 300);
+    playlist.set_dwell(1, Some(600));
+    assert_eq!(playlist.dwell(1, 300), 600);
+    playlist.set_dwell(1, None);
+    assert_eq!(playlist.dwell(1, 300), 300);
+    // Out of range: the default, and setting is ignored
+    playlist.set_dwell(9, Some(1));
+    assert_eq!(playlist.dwell(9, 300), 300);
+}
+
+#[test]
+fn favorites_toggle() {
+    let mut playlist = Playlist::new(3, 1);
+    assert!(!playlist.is_favorite(2));
+    assert!(playlist.toggle_favorite(2));
+    assert!(playlist.is_favorite(2));
+    assert!(!playlist.toggle_favorite(2));
+    assert!(!playlist.toggle_favorite(3));
+}
+
+#[test]
+fn order_wire_round_trips_and_cycles() {
+    for order in [Order::Sequential, Order::Shuffle, Order::NewestFirst] {
+        assert_eq!(Order::from_wire(order.to_wire()), Some(order));
+        assert_ne!(order.cycle(), order);
+    }
+    assert_eq!(Order::from_wire(3), None);
+}
+
+#[test]
+fn length_is_capped() {
+    let playlist = Playlist::new(MAX_PLAYLIST + 10, 1);
+    assert_eq!(playlist.len(), MAX_PLAYLIST);
+    let empty = Playlist::new(0, 1);
+    assert!(empty.is_empty());
+}
@@ -0,0 +1,59 @@
+//! Tests for the soak-mode replay and invariant bookkeeping.
+
+use rpi4_photoframe_tests::soak::{
+    arm_guards, check_guards, Checker, HeapReading, Replay, ScriptStep, Violation,
+    HEAP_LOW_WATERMARK, SOAK_SCRIPT,
+};
+
+static SCRIPT: &[ScriptStep] = &[ScriptStep { ticks: 2, key: 4 }, ScriptStep { ticks: 1, key: 5 }];
+
+#[test]
+fn replay_loops_with_recorded_delays() {
+    let mut replay = Replay::new(SCRIPT);
+    let keys: Vec<_> = (0..6).map(|_| replay.tick()).collect();
+    assert_eq!(keys, [None, Some(4), Some(5), None, Some(4), Some(5)]);
+    assert_eq!(replay.loops(), 2);
+}
+
+#[test]
+fn default_script_is_replayable() {
+    assert!(!SOAK_SCRIPT.is_empty());
+    assert!(SOAK_SCRIPT.iter().all(|step| step.ticks > 0));
+}
+
+#[test]
+fn guard_words_detect_overwrite() {
+    let mut guard = [0u32; 8];
+    arm_guards(&mut guard);
+    assert_eq!(check_guards(&guard), None);
+    guard[5] = 0xFF00_FF00;
+    assert_eq!(check_guards(&guard), Some((5, 0xFF00_FF00)));
+    assert_eq!(check_guards(&[]), None);
+}
+
+#[test]
+fn violations_are_logged_at_powers_of_two() {
+    let mut checker = Checker::new();
+    let logged: Vec<_> = (1..=9).filter(|_| checker.record(Violation::ScratchGuard)).collect();
+    assert_eq!(logged.len(), 4); // 1st, 2nd, 4th, 8th
+    assert_eq!(checker.count(Violation::ScratchGuard), 9);
+    assert_eq!(checker.count(Violation::InputRing), 0);
+    assert_eq!(checker.total(), 9);
+}
+
+#[test]
+fn heap_watermark_reports_new_lows_only() {
+    let capacity = 4 * HEAP_LOW_WATERMARK;
+    let reading = |peak| HeapReading { used: 0, peak, capacity, oom: false };
+    let mut checker = Checker::new();
+    assert_eq!(checker.heap_free_min(), None);
+
+    assert_eq!(checker.check_heap(reading(HEAP_LOW_WATERMARK)), None);
+    let low = capacity - HEAP_LOW_WATERMARK / 2;
+    assert_eq!(checker.check_heap(reading(low)), Some(Violation::HeapWatermark));
+    assert_eq!(checker.check_heap(reading(low)), None);
+    assert_eq!(checker.heap_free_min(), Some(HEAP_LOW_WATERMARK / 2));
+
+    let oom = HeapReading { oom: true, ..reading(0) };
+    assert_eq!(checker.check_heap(oom), Some(Violation::Heap));
+}
@@ -0,0 +1,141 @@
+//! End-to-end flows through the simulated multi-PD system: photos loaded
+//! across the Decoder/Display boundary, navigation from the keyboard, the
+//! slideshow on the tick, and the clock set from the UART console.
+
+use rpi4_input_protocol::{KEY_ESCAPE, KEY_LEFT, KEY_RIGHT, KEY_SPACE};
+use rpi4_photoframe_tests::bounded_alloc::BoundedBumpAllocator;
+use rpi4_photoframe_tests::system_sim::{Screen, SystemSim, SCREEN_H, SCREEN_W, SLIDESHOW_INTERVAL_S, TICK_US};
+
+/// Path to the committed sample images (independent of the test's cwd).
+fn photo(name: &str) -> Vec<u8> {
+    let path = format!("{}/../rpi4-photoframe/photos/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("read {path}: {e}"))
+}
+
+/// The QOI, PNG and GIF samples, in that order
+fn library() -> Vec<Vec<u8>> {
+    vec![
+        photo("sample_gradient.qoi"),
+        photo("sample_gradient.png"),
+        photo("sample_spinner.gif"),
+    ]
+}
+
+/// Wait for photo `index` to be drawn for the `presented`th time overall
+fn shown(sim: &SystemSim, index: usize, presented: u32) -> Screen {
+    sim.wait_for(|s| s.presented >= presented && s.photo == Some(index))
+        .unwrap_or_else(|| panic!("photo {index} never drawn: {:?}", sim.screen().photo))
+}
+
+/// Ticks in one slideshow interval
+const SLIDE_TICKS: u32 = (SLIDESHOW_INTERVAL_S as u64 * 1_000_000 / TICK_US) as u32;
+
+#[test]
+fn boot_loads_and_draws_the_first_photo() {
+    static HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let sim = SystemSim::boot(library(), &HEAP);
+    let screen = shown(&sim, 0, 1);
+    assert_eq!((screen.load_errors, screen.rejected), (0, 0));
+
+    // 320x240 letterboxed onto 640x360: 480x360, pillarboxed 80 px a side
+    assert_eq!(screen.framebuffer.len(), (SCREEN_W * SCREEN_H) as usize);
+    assert_eq!(screen.framebuffer[180 * SCREEN_W as usize + 40], 0xFF00_0000);
+    assert_eq!(screen.framebuffer[180 * SCREEN_W as usize + 600], 0xFF00_0000);
+    // The sample's filled circle, near (160,108) in the source, in (250,210,40)
+    assert_eq!(screen.framebuffer[162 * SCREEN_W as usize + 320], 0xFFFA_D228);
+}
+
+#[test]
+fn arrow_keys_navigate_through_the_decoder() {
+    static HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let sim = SystemSim::boot(library(), &HEAP);
+    shown(&sim, 0, 1);
+
+    sim.press(KEY_RIGHT);
+    let png = shown(&sim, 1, 2);
+    sim.press(KEY_RIGHT);
+    let gif = shown(&sim, 2, 3);
+    assert_ne!(png.framebuffer, gif.framebuffer);
+
+    // Back round the start of the playlist to the last photo
+    sim.press(KEY_RIGHT);
+    shown(&sim, 0, 4);
+    sim.press(KEY_LEFT);
+    shown(&sim, 2, 5);
+
+    // Escape goes home; the QOI and PNG samples hold the same pixels
+    sim.press(KEY_ESCAPE);
+    let qoi = shown(&sim, 0, 6);
+    assert_eq!(qoi.framebuffer, png.framebuffer);
+    assert_eq!((qoi.load_errors, qoi.rejected, qoi.missed_events), (0, 0, 0));
+}
+
+#[test]
+fn slideshow_advances_on_the_tick_unless_paused() {
+    static HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let sim = SystemSim::boot(library(), &HEAP);
+    shown(&sim, 0, 1);
+
+    // One tick short of the interval stays put
+    sim.tick(SLIDE_TICKS - 1);
+    let screen = sim.wait_for(|s| s.ticks == SLIDE_TICKS - 1).unwrap();
+    assert_eq!((screen.photo, screen.presented), (Some(0), 1));
+    sim.tick(1);
+    shown(&sim, 1, 2);
+
+    // Paused, a whole interval goes by on the same photo
+    sim.press(KEY_SPACE);
+    sim.wait_for(|s| s.paused).unwrap();
+    sim.tick(SLIDE_TICKS);
+    let screen = sim.wait_for(|s| s.ticks == 2 * SLIDE_TICKS).unwrap();
+    assert_eq!((screen.photo, screen.presented), (Some(1), 2));
+
+    // Resumed, the overdue slide moves on at the next tick
+    sim.press(KEY_SPACE);
+    sim.wait_for(|s| !s.paused).unwrap();
+    sim.tick(1);
+    shown(&sim, 2, 3);
+}
+
+#[test]
+fn photos_that_fail_to_decode_are_skipped() {
+    static HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let mut corrupt = photo("sample_gradient.png");
+    corrupt[8 + 25 + 8 + 100] ^= 0x5A;
+    let mut photos = library();
+    photos.insert(1, corrupt);
+    let sim = SystemSim::boot(photos, &HEAP);
+    shown(&sim, 0, 1);
+
+    // Photo 1 fails in the Decoder PD; the Display PD drops it and moves on
+    sim.press(KEY_RIGHT);
+    let screen = shown(&sim, 2, 2);
+    assert_eq!(screen.load_errors, 1);
+
+    // ... and leaves it out from then on
+    sim.press(KEY_LEFT);
+    let screen = shown(&sim, 0, 3);
+    assert_eq!(screen.load_errors, 1);
+}
+
+#[test]
+fn uart_time_entry_sets_the_clock_page() {
+    static HEAP: BoundedBumpAllocator<{ 512 * 1024 }> = BoundedBumpAllocator::new();
+    let sim = SystemSim::boot(library(), &HEAP);
+    sim.tick(1);
+    let screen = sim.wait_for(|s| s.ticks == 1).unwrap();
+    assert_eq!(screen.wall_clock, None);
+
+    // Ctrl-T, the time, Enter: a SET_TIME call from the Input PD to the Timer PD
+    sim.type_uart(b"\x141792134309+0200\r");
+    // Ten ticks later the Display PD reads a second on from the page
+    sim.tick(10);
+    let screen = sim.wait_for(|s| s.ticks == 11).unwrap();
+    assert_eq!(screen.wall_clock, Some(1_792_134_310));
+
+    // A time the Timer PD refuses leaves the clock as it was
+    sim.type_uart(b"\x14999999999999999\r");
+    sim.tick(10);
+    let screen = sim.wait_for(|s| s.ticks == 21).unwrap();
+    assert_eq!(screen.wall_clock, Some(1_792_134_311));
+}
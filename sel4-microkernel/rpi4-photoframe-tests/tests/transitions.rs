@@ -0,0 +1,48 @@
+//! Tests for the slideshow transition sequencing.
+
+use rpi4_photoframe_tests::transitions::{effect_for, Effect, Transition, EFFECTS};
+
+const W: u32 = 8;
+const H: u32 = 4;
+const N: usize = (W * H) as usize;
+
+#[test]
+fn transition_lands_on_the_new_photo() {
+    let from = [0xFF00_0000u32; N];
+    let to = [0xFFFF_FFFFu32; N];
+    let mut dst = [0u32; N];
+    for effect in EFFECTS {
+        let mut transition = Transition::new(effect, 4);
+        let mut frames = 1;
+        while transition.step(&from, &to, &mut dst, W, H).unwrap() {
+            assert_ne!(dst, to, "{:?} finished early", effect);
+            frames += 1;
+        }
+        assert_eq!(frames, 4);
+        assert!(transition.is_done());
+        assert_eq!(dst, to, "{:?} ends on the new photo", effect);
+        // Nothing more to draw
+        assert!(!transition.step(&from, &to, &mut dst, W, H).unwrap());
+    }
+}
+
+#[test]
+fn refused_frame_ends_the_transition() {
+    let short = [0u32; N - 1];
+    let to = [0u32; N];
+    let mut dst = [7u32; N];
+    let mut transition = Transition::new(Effect::Crossfade, 4);
+    assert!(transition.step(&short, &to, &mut dst, W, H).is_err());
+    assert!(transition.is_done());
+    assert_eq!(dst, [7; N]);
+}
+
+#[test]
+fn effects_cycle_and_slides_follow_direction() {
+    let forwards: Vec<_> = (0..EFFECTS.len() as u32).map(|n| effect_for(n, false)).collect();
+    assert_eq!(forwards, EFFECTS);
+    assert_eq!(effect_for(EFFECTS.len() as u32, false), EFFECTS[0]);
+    assert_eq!(effect_for(1, true), Effect::SlideRight);
+    assert_eq!(effect_for(4, true), Effect::SlideLeft);
+    assert_eq!(effect_for(0, true), Effect::Crossfade);
+}
@@ -0,0 +1,32 @@
+//! Tests for the weather panel: its corner and text, and that only valid
+//! reports are drawn.
+
+use rpi4_network_protocol::weather::{decode_report, WeatherReport, CONDITION_CLOUDY, MIN_TEMP_C};
+use rpi4_photoframe_tests::clock::Corner;
+use rpi4_photoframe_tests::weather::{shown, weather_corner, TempText};
+
+#[test]
+fn panel_sits_across_from_the_clock() {
+    assert_eq!(weather_corner(Some(Corner::TopRight)), Corner::TopLeft);
+    assert_eq!(weather_corner(Some(Corner::BottomRight)), Corner::TopLeft);
+    assert_eq!(weather_corner(Some(Corner::TopLeft)), Corner::TopRight);
+    assert_eq!(weather_corner(Some(Corner::BottomLeft)), Corner::TopRight);
+    assert_eq!(weather_corner(None), Corner::TopRight);
+}
+
+#[test]
+fn only_valid_reports_are_shown() {
+    let report = decode_report(21, CONDITION_CLOUDY as u32, 1);
+    assert_eq!(shown(report), report);
+    assert_eq!(shown(Some(WeatherReport::UNAVAILABLE)), None);
+    assert_eq!(shown(None), None);
+}
+
+#[test]
+fn temperature_text() {
+    let report = decode_report(21, CONDITION_CLOUDY as u32, 1).unwrap();
+    assert_eq!(TempText(&report).to_string(), "21°C");
+    assert_eq!(report.condition_name(), "CLOUDY");
+    let cold = decode_report(MIN_TEMP_C as i32, 0, 1).unwrap();
+    assert_eq!(TempText(&cold).to_string(), "-90°C");
+}
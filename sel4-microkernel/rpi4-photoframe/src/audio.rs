@@ -0,0 +1,42 @@
+//! Slideshow chime, played by the Audio PD
+//!
+//! The Audio PD owns the sound output and the clips it plays; this PD
+//! names the chime by its index in the Audio PD's sample bank, so it never
+//! hands the Audio PD memory to read. Sound is a garnish: a call that
+//! fails is logged and the slideshow carries on silently.
+
+use sel4_microkit::{debug_println, with_msg_regs, with_msg_regs_mut, Channel, MessageInfo};
+use rpi4_audio_protocol::{clips, AudioRequest, AUDIO_CHANNEL_ID, Q8_ONE};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError, ResultExt};
+use verified_microkernel::rpc::{self, RpcError};
+use verified_microkernel::IpcBuffer;
+
+/// The Audio PD replied with something the protocol does not allow
+const BAD_REPLY: PdError = PdError::new(ErrorDomain::Audio, ErrorKind::InvalidResponse);
+
+/// Chime volume, Q8: half scale leaves headroom for anything else playing
+const CHIME_VOLUME: u16 = Q8_ONE / 2;
+
+/// Play the slide-change chime
+pub fn chime() {
+    let request = AudioRequest::PlaySample { sample: clips::CHIME, volume: CHIME_VOLUME, looped: false };
+    if let Err(e) = call(request).context("playing chime") {
+        debug_println!("Photoframe PD: {}", e);
+    }
+}
+
+/// Make one protected procedure call; on success, the voice the Audio PD
+/// played on
+fn call(request: AudioRequest) -> Result<u8, PdError> {
+    let mut buf = IpcBuffer::new();
+    let (label, count) = rpc::encode_request(&request, &mut buf).map_err(|e| BAD_REPLY.with_detail(e.detail()))?;
+    with_msg_regs_mut(|mrs| mrs[..count].copy_from_slice(buf.words()));
+    let reply = Channel::new(AUDIO_CHANNEL_ID).pp_call(MessageInfo::new(label, count));
+
+    with_msg_regs(|mrs| buf.load(&mrs[..reply.count().min(mrs.len())]));
+    match rpc::decode_reply(reply.label(), &buf) {
+        Ok(voice) => Ok(voice),
+        Err(RpcError::Remote(code)) => Err(PdError::from_wire(code).unwrap_or(BAD_REPLY)),
+        Err(e) => Err(BAD_REPLY.with_detail(e.detail())),
+    }
+}
@@ -0,0 +1,127 @@
+//! # Clock Overlay
+//!
+//! The wall-clock time and date drawn over the photo, and the night-mode
+//! dimming that goes with them. The time comes from the Timer PD's clock
+//! page (`rpi4-clock-protocol`); this module only decides the text, the
+//! corner it goes in and when the screen is dimmed, so all of it can be
+//! tested on a host.
+//!
+//! ```text
+//!   ┌──────────────────────────── 40 px info bar ─┐
+//!   │ TopLeft                            TopRight │  ← below the bar while
+//!   │                                             │    the info is shown
+//!   │ BottomLeft                      BottomRight │  ← above the status
+//!   └─────────────────────────── hint bar, status ┘    lines and hints
+//! ```
+
+use core::fmt;
+use rpi4_clock_protocol::{in_daily_window, LocalTime, MINUTES_PER_DAY};
+
+/// Clear space between the clock and the screen edge (or an info bar)
+pub const CLOCK_MARGIN: u32 = 20;
+
+/// Clock text by day
+pub const DAY_COLOR: u32 = 0xFFFF_FFFF;
+/// Clock text at night: dim red, which keeps a dark room dark
+pub const NIGHT_COLOR: u32 = 0xFF90_4040;
+
+/// Screen corner the clock sits in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// Next placement for the clock key: round the corners clockwise from
+    /// the top right, then hidden, then back to the top right
+    pub fn cycle(corner: Option<Corner>) -> Option<Corner> {
+        match corner {
+            Some(Corner::TopRight) => Some(Corner::BottomRight),
+            Some(Corner::BottomRight) => Some(Corner::BottomLeft),
+            Some(Corner::BottomLeft) => Some(Corner::TopLeft),
+            Some(Corner::TopLeft) => None,
+            None => Some(Corner::TopRight),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "top left",
+            Corner::TopRight => "top right",
+            Corner::BottomLeft => "bottom left",
+            Corner::BottomRight => "bottom right",
+        }
+    }
+}
+
+/// Top-left corner of a `w` x `h` clock in `corner` of a `screen_w` x
+/// `screen_h` screen, `top` pixels clear of the top edge and `bottom` of
+/// the bottom one (the info bars, when they are shown). A clock too big for
+/// the space left is pinned to the left / top edge rather than pushed off.
+pub fn clock_origin(corner: Corner, screen: (u32, u32), size: (u32, u32), top: u32, bottom: u32) -> (u32, u32) {
+    let (screen_w, screen_h) = screen;
+    let (w, h) = size;
+    let left = CLOCK_MARGIN;
+    let right = screen_w.saturating_sub(CLOCK_MARGIN + w);
+    let upper = top + CLOCK_MARGIN;
+    let lower = screen_h.saturating_sub(bottom + CLOCK_MARGIN + h);
+    match corner {
+        Corner::TopLeft => (left, upper),
+        Corner::TopRight => (right, upper),
+        Corner::BottomLeft => (left, lower),
+        Corner::BottomRight => (right, lower),
+    }
+}
+
+/// The daily window the screen is dimmed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NightMode {
+    /// Local minute of the day dimming starts
+    pub start_min: u32,
+    /// Local minute of the day it ends; before `start_min` for a window
+    /// over midnight, equal to it for none at all
+    pub end_min: u32,
+    /// Opacity of the black drawn over the whole screen (255 = black)
+    pub dim_alpha: u8,
+}
+
+impl NightMode {
+    /// 22:00 to 07:00, the photo still visible underneath
+    pub const DEFAULT: NightMode = NightMode { start_min: 22 * 60, end_min: 7 * 60, dim_alpha: 0xB0 };
+
+    /// Is it night at `time`?
+    pub fn is_night(&self, time: &LocalTime) -> bool {
+        in_daily_window(
+            time.minute_of_day % MINUTES_PER_DAY,
+            self.start_min % MINUTES_PER_DAY,
+            self.end_min % MINUTES_PER_DAY,
+        )
+    }
+
+    /// ARGB drawn over the screen at night
+    pub fn shade(&self) -> u32 {
+        (self.dim_alpha as u32) << 24
+    }
+}
+
+/// `HH:MM`, 24-hour
+pub struct TimeText<'a>(pub &'a LocalTime);
+
+impl fmt::Display for TimeText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0.hour(), self.0.minute())
+    }
+}
+
+/// `FRI 16 OCT 2026`
+pub struct DateText<'a>(pub &'a LocalTime);
+
+impl fmt::Display for DateText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = self.0;
+        write!(f, "{} {} {} {}", t.weekday_name(), t.date.day, t.month_name(), t.date.year)
+    }
+}
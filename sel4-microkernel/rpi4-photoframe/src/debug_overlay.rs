@@ -0,0 +1,231 @@
+//! # Debug Overlay
+//!
+//! Developer readouts drawn on the overlay layer, on top of whatever the
+//! screen shows (slideshow, grid or info bars), toggled by a key chord:
+//! hold 9 and press 0.
+//!
+//! ```text
+//!   FPS 9.8  FRAMES 1234  MISSED 0
+//!   LAST KEY RIGHT 3S AGO
+//!   INPUT PD 3S AGO  TIMER PD UP
+//!   DECODER HEAP 0/16384 KB  PEAK 412 KB (3%)
+//!   INPUT RING 0/511  PEAK 3 (1%)
+//!   ...
+//! ```
+//!
+//! Under the fixed lines, one per memory gauge (`rpi4_pd_error::mem`), the
+//! same figures the `@mem` console lines carry.
+//!
+//! The PD gathers its readings into a [`DebugStats`] for every redraw; this
+//! module only tracks the chord and the frame rate and lays out the text,
+//! so the host test crate can exercise it.
+
+use core::fmt;
+use rpi4_pd_error::mem::{Reading, Unit};
+use rpi4_input_protocol::{
+    KEY_DOWN, KEY_END, KEY_ENTER, KEY_ESCAPE, KEY_HOME, KEY_LEFT, KEY_MUTE, KEY_NUM0, KEY_NUM9,
+    KEY_PAGEDOWN, KEY_PAGEUP, KEY_RIGHT, KEY_SPACE, KEY_UP, KEY_VOLUMEDOWN, KEY_VOLUMEUP,
+};
+
+/// The two ring key codes of the toggle chord, held together in either
+/// order
+pub const CHORD_KEYS: [u8; 2] = [KEY_NUM9, KEY_NUM0];
+
+/// Ticks the second chord key may follow the first by. Sources that send
+/// no releases (the UART console) see two quick presses as the chord.
+pub const CHORD_WINDOW_TICKS: u32 = 10;
+
+/// Lines of text in the overlay above the memory gauges
+pub const DEBUG_LINES: usize = 3;
+
+/// Key chord detector, fed every key event from the input ring
+pub struct Chord {
+    /// The chord key held down, and the tick it went down on
+    held: Option<(u8, u32)>,
+}
+
+impl Chord {
+    pub const fn new() -> Self {
+        Self { held: None }
+    }
+
+    /// Feed a key event; `true` when it completes the chord, in which case
+    /// the key should not also act on its own
+    pub fn key(&mut self, code: u8, pressed: bool, tick: u32) -> bool {
+        if !CHORD_KEYS.contains(&code) {
+            // Another key in between breaks the chord
+            if pressed {
+                self.held = None;
+            }
+            return false;
+        }
+        if !pressed {
+            if self.held.is_some_and(|(held, _)| held == code) {
+                self.held = None;
+            }
+            return false;
+        }
+        match self.held {
+            Some((held, at)) if held != code && tick.wrapping_sub(at) <= CHORD_WINDOW_TICKS => {
+                self.held = None;
+                true
+            }
+            _ => {
+                self.held = Some((code, tick));
+                false
+            }
+        }
+    }
+}
+
+impl Default for Chord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Frames per second, measured over whole seconds of ticks
+pub struct FpsMeter {
+    /// Tick and frame count the current measurement started at
+    start: Option<(u32, u32)>,
+    /// Last measurement, in tenths of a frame per second
+    tenths: Option<u32>,
+}
+
+impl FpsMeter {
+    pub const fn new() -> Self {
+        Self { start: None, tenths: None }
+    }
+
+    /// Record the frames drawn so far at `tick`; a new figure is taken once
+    /// `ticks_per_s` ticks have passed
+    pub fn sample(&mut self, tick: u32, frames: u32, ticks_per_s: u32) {
+        let Some((start_tick, start_frames)) = self.start else {
+            self.start = Some((tick, frames));
+            return;
+        };
+        let elapsed = tick.wrapping_sub(start_tick);
+        if elapsed == 0 || elapsed < ticks_per_s {
+            return;
+        }
+        let drawn = frames.wrapping_sub(start_frames) as u64;
+        let tenths = drawn * 10 * ticks_per_s as u64 / elapsed as u64;
+        self.tenths = Some(tenths.min(u32::MAX as u64) as u32);
+        self.start = Some((tick, frames));
+    }
+
+    /// Frames per second in tenths; `None` until a second has passed
+    pub fn tenths(&self) -> Option<u32> {
+        self.tenths
+    }
+}
+
+impl Default for FpsMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Short name of ring key code `code`
+pub fn key_name(code: u8) -> &'static str {
+    const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    match code {
+        KEY_UP => "UP",
+        KEY_DOWN => "DOWN",
+        KEY_LEFT => "LEFT",
+        KEY_RIGHT => "RIGHT",
+        KEY_ENTER => "ENTER",
+        KEY_ESCAPE => "ESC",
+        KEY_SPACE => "SPACE",
+        KEY_NUM0..=KEY_NUM9 => DIGITS[(code - KEY_NUM0) as usize],
+        KEY_HOME => "HOME",
+        KEY_END => "END",
+        KEY_PAGEUP => "PGUP",
+        KEY_PAGEDOWN => "PGDN",
+        KEY_VOLUMEUP => "VOL+",
+        KEY_VOLUMEDOWN => "VOL-",
+        KEY_MUTE => "MUTE",
+        _ => "?",
+    }
+}
+
+/// One redraw's worth of readings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DebugStats {
+    /// Frames per second in tenths, once measured
+    pub fps_tenths: Option<u32>,
+    /// Frames drawn since start
+    pub frames: u32,
+    /// Timer ticks handled late
+    pub missed_ticks: u32,
+    /// Ring key code of the last key pressed, and seconds since
+    pub last_key: Option<(u8, u32)>,
+    /// Seconds since the Input PD last notified
+    pub input_pd_s: Option<u32>,
+    /// The Timer PD has published the clock page
+    pub timer_pd_up: bool,
+}
+
+impl DebugStats {
+    /// Line `n` of the overlay, `n < DEBUG_LINES`
+    pub fn line(&self, n: usize) -> Option<DebugLine<'_>> {
+        (n < DEBUG_LINES).then_some(DebugLine { stats: self, n })
+    }
+}
+
+/// One line of the overlay, formatted on demand
+pub struct DebugLine<'a> {
+    stats: &'a DebugStats,
+    n: usize,
+}
+
+impl fmt::Display for DebugLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.stats;
+        match self.n {
+            0 => {
+                match s.fps_tenths {
+                    Some(t) => write!(f, "FPS {}.{}", t / 10, t % 10)?,
+                    None => f.write_str("FPS --")?,
+                }
+                write!(f, "  FRAMES {}  MISSED {}", s.frames, s.missed_ticks)
+            }
+            1 => match s.last_key {
+                Some((code, age)) => write!(f, "LAST KEY {} {}S AGO", key_name(code), age),
+                None => f.write_str("LAST KEY NONE"),
+            },
+            _ => {
+                match s.input_pd_s {
+                    Some(age) => write!(f, "INPUT PD {}S AGO", age)?,
+                    None => f.write_str("INPUT PD SILENT")?,
+                }
+                f.write_str(if s.timer_pd_up { "  TIMER PD UP" } else { "  TIMER PD NO PAGE" })
+            }
+        }
+    }
+}
+
+/// A memory gauge as an overlay line, in the overlay font's upper case:
+/// `DECODER HEAP 0/16384 KB  PEAK 412 KB (3%)`
+pub struct MemText<'a>(pub &'a Reading);
+
+impl fmt::Display for MemText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = self.0;
+        for c in r.name.chars() {
+            let c = if c == '_' { ' ' } else { c.to_ascii_uppercase() };
+            fmt::Write::write_char(f, c)?;
+        }
+        match r.unit {
+            Unit::Bytes => write!(
+                f,
+                " {}/{} KB  PEAK {} KB ({}%)",
+                r.used.div_ceil(1024),
+                r.capacity / 1024,
+                r.peak.div_ceil(1024),
+                r.peak_percent()
+            ),
+            Unit::Slots => write!(f, " {}/{}  PEAK {} ({}%)", r.used, r.capacity, r.peak, r.peak_percent()),
+        }
+    }
+}
@@ -4,6 +4,9 @@
 //! - **BMP** - Uncompressed bitmap (tinybmp)
 //! - **QOI** - Quite OK Image format (inline implementation)
 //!
+//! PNG (native inflate for 8-bit RGB/RGBA, see [`crate::png`]), GIF (see
+//! [`crate::gif`]) and JPEG decode against the bounded heap instead.
+//!
 //! ## Security Note
 //!
 //! All decoders operate on untrusted input. In the full 3-PD architecture,
@@ -18,7 +21,7 @@
 //! - No heap allocation needed
 
 /// Error types for decoding
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DecodeError {
     InvalidFormat,
     InvalidHeader,
@@ -159,14 +162,24 @@ pub fn decode_jpeg(data: &[u8], output: &mut [u32]) -> Result<(u32, u32), Decode
 }
 
 // ============================================================================
-// PNG DECODER (zune-png, allocation-based)
+// PNG DECODER (native for 8-bit RGB/RGBA, zune-png otherwise)
 // ============================================================================
 
 /// Decode a PNG image to ARGB32 pixels.
 ///
-/// See [`decode_jpeg`] for the allocation/security contract; the same applies
-/// here (PNG inflate uses the bounded global heap).
+/// 8-bit RGB/RGBA non-interlaced images take the native streaming path
+/// ([`crate::png`]), which needs only an inflate window and two scanlines.
+/// Everything else falls back to zune-png. See [`decode_jpeg`] for the
+/// allocation/security contract; both paths use the bounded global heap.
 pub fn decode_png(data: &[u8], output: &mut [u32]) -> Result<(u32, u32), DecodeError> {
+    if let Some(header) = crate::png::PngHeader::parse(data)? {
+        return crate::png::decode_native(data, &header, output);
+    }
+    decode_png_zune(data, output)
+}
+
+/// zune-png path for the PNG variants the native decoder does not handle.
+fn decode_png_zune(data: &[u8], output: &mut [u32]) -> Result<(u32, u32), DecodeError> {
     use zune_png::PngDecoder;
 
     let mut decoder = PngDecoder::new(data);
@@ -203,6 +216,24 @@ pub fn decode_png(data: &[u8], output: &mut [u32]) -> Result<(u32, u32), DecodeE
     Ok((width, height))
 }
 
+// ============================================================================
+// GIF DECODER (native LZW, animated)
+// ============================================================================
+
+/// Open a GIF and draw its first frame into `output` (ARGB32, the size of
+/// the GIF's logical screen).
+///
+/// The returned decoder draws the remaining frames into the same buffer.
+/// See [`decode_jpeg`] for the allocation/security contract.
+pub fn decode_gif<'a>(
+    data: &'a [u8],
+    output: &mut [u32],
+) -> Result<(crate::gif::GifDecoder<'a>, crate::gif::GifFrame), DecodeError> {
+    let mut decoder = crate::gif::GifDecoder::open(data)?;
+    let frame = decoder.next_frame(output)?;
+    Ok((decoder, frame))
+}
+
 // ============================================================================
 // QOI DECODER (inline, no dependencies, no allocation)
 // ============================================================================
@@ -0,0 +1,277 @@
+//! # EXIF Metadata
+//!
+//! Reads the three things the slideshow wants from a JPEG's EXIF block:
+//! which way up the photo goes, when it was taken and what took it.
+//!
+//! ```text
+//!   SOI ─► APP1 "Exif\0\0" ─► TIFF header (II/MM, 42) ─► IFD0 ──────────► Orientation 0x0112
+//!                                                         │                Model       0x0110
+//!                                                         │                DateTime    0x0132
+//!                                                         └─► Exif IFD ─► DateTimeOriginal 0x9003
+//! ```
+//!
+//! The EXIF block is as untrusted as the pixels. Every IFD entry and value
+//! is located through the verified `tiff_range` / `ifd_entry` / `ifd_value`
+//! in `rpi4_photo_protocol`, proven to stay inside the APP1 segment, and
+//! the walk is bounded: at most `EXIF_MAX_IFD_ENTRIES` entries from IFD0 and
+//! from the one Exif sub-IFD, with no following of IFD chains. A missing or
+//! malformed block just leaves the photo upright with no metadata; it never
+//! fails the decode.
+
+use rpi4_photo_protocol::{
+    ifd_entry, ifd_value, tiff_range, CaptureTime, Orientation, PixelBufferHeader, EXIF_MAX_IFD_ENTRIES,
+    EXIF_MODEL_LEN,
+};
+
+/// JPEG markers walked to find the EXIF segment
+const SOI: u8 = 0xD8;
+const APP1: u8 = 0xE1;
+const SOS: u8 = 0xDA;
+const EOI: u8 = 0xD9;
+
+/// APP1 payload prefix of an EXIF segment
+const EXIF_PREFIX: &[u8; 6] = b"Exif\0\0";
+
+/// TIFF tags read
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+/// TIFF field types read
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+
+/// "YYYY:MM:DD HH:MM" of an EXIF date (the seconds are not kept)
+const DATE_TIME_LEN: usize = 16;
+
+/// What a photo's EXIF block says about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExifInfo {
+    /// How to turn the pixels to show them upright
+    pub orientation: Orientation,
+    /// When it was taken: DateTimeOriginal, else DateTime
+    pub taken: Option<CaptureTime>,
+    /// Camera model, NUL-padded
+    pub model: [u8; EXIF_MODEL_LEN],
+}
+
+impl ExifInfo {
+    /// A photo without EXIF: upright, nothing known
+    pub const NONE: ExifInfo = ExifInfo {
+        orientation: Orientation::Normal,
+        taken: None,
+        model: [0; EXIF_MODEL_LEN],
+    };
+
+    /// The camera model, or "" if unknown or not text
+    pub fn model(&self) -> &str {
+        model_str(&self.model)
+    }
+
+    /// Copy into the header handed to the Display side
+    pub fn stamp(&self, header: PixelBufferHeader) -> PixelBufferHeader {
+        header.with_metadata(self.orientation.to_exif(), self.taken.map_or(0, CaptureTime::pack), self.model)
+    }
+
+    /// Read back from a header stamped by [`ExifInfo::stamp`]
+    pub fn from_header(header: &PixelBufferHeader) -> Self {
+        ExifInfo {
+            orientation: Orientation::from_exif(header.orientation as u16),
+            taken: CaptureTime::unpack(header.taken_at),
+            model: header.camera_model,
+        }
+    }
+}
+
+/// A NUL-padded model field as text, trailing spaces dropped
+pub fn model_str(model: &[u8; EXIF_MODEL_LEN]) -> &str {
+    let len = model.iter().position(|&b| b == 0).unwrap_or(EXIF_MODEL_LEN);
+    core::str::from_utf8(&model[..len]).map_or("", str::trim_end)
+}
+
+/// Read the EXIF block of a JPEG. Anything that is not a JPEG, has no EXIF
+/// or has a malformed one gets [`ExifInfo::NONE`] for whatever could not be
+/// read.
+pub fn read_exif(data: &[u8]) -> ExifInfo {
+    let mut info = ExifInfo::NONE;
+    let Some((tiff, end)) = find_exif(data) else {
+        return info;
+    };
+    // The segment, so no offset can reach past it into the image data
+    let Some(tiff) = Tiff::new(&data[..end], tiff) else {
+        return info;
+    };
+
+    let mut exif_ifd = None;
+    let mut date_time = None;
+    tiff.for_each_entry(tiff.first_ifd, |entry| match entry.tag {
+        TAG_ORIENTATION if entry.kind == TYPE_SHORT => {
+            info.orientation = Orientation::from_exif(tiff.u16_at(entry.at + 8));
+        }
+        TAG_MODEL if entry.kind == TYPE_ASCII => {
+            if let Some(text) = tiff.ascii(&entry) {
+                let len = text.len().min(EXIF_MODEL_LEN);
+                info.model[..len].copy_from_slice(&text[..len]);
+            }
+        }
+        TAG_DATE_TIME if entry.kind == TYPE_ASCII => {
+            date_time = tiff.ascii(&entry).and_then(parse_date_time);
+        }
+        TAG_EXIF_IFD if entry.kind == TYPE_LONG => exif_ifd = Some(entry.value),
+        _ => {}
+    });
+
+    let mut original = None;
+    if let Some(ifd) = exif_ifd {
+        tiff.for_each_entry(ifd, |entry| {
+            if entry.tag == TAG_DATE_TIME_ORIGINAL && entry.kind == TYPE_ASCII {
+                original = tiff.ascii(&entry).and_then(parse_date_time);
+            }
+        });
+    }
+    info.taken = original.or(date_time);
+    info
+}
+
+/// Where the TIFF header of the EXIF segment starts and where the segment
+/// ends, if the JPEG has one before its image data
+fn find_exif(data: &[u8]) -> Option<(usize, usize)> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != SOI {
+        return None;
+    }
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            // Fill byte
+            pos += 1;
+            continue;
+        }
+        if marker == SOS || marker == EOI {
+            return None;
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        if length < 2 || pos + 2 + length > data.len() {
+            return None;
+        }
+        let body = pos + 4;
+        let end = pos + 2 + length;
+        if marker == APP1 && end - body >= EXIF_PREFIX.len() && data[body..body + EXIF_PREFIX.len()] == EXIF_PREFIX[..] {
+            return Some((body + EXIF_PREFIX.len(), end));
+        }
+        pos = end;
+    }
+    None
+}
+
+/// One IFD entry
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    /// The value, when it fits in 4 bytes, or its offset
+    value: u32,
+    /// Where the entry starts in the segment
+    at: usize,
+}
+
+/// The TIFF file inside an EXIF segment
+struct Tiff<'a> {
+    /// The segment, up to its last byte
+    data: &'a [u8],
+    /// Where the TIFF header starts in `data`; offsets count from here
+    base: usize,
+    big_endian: bool,
+    /// Offset of IFD0
+    first_ifd: u32,
+}
+
+impl<'a> Tiff<'a> {
+    /// Check the TIFF header at `base`
+    fn new(data: &'a [u8], base: usize) -> Option<Self> {
+        let header = tiff_range(base, 0, 8, data.len())?;
+        let big_endian = match &data[header..header + 2] {
+            b"II" => false,
+            b"MM" => true,
+            _ => return None,
+        };
+        let mut tiff = Tiff { data, base, big_endian, first_ifd: 0 };
+        if tiff.u16_at(header + 2) != 42 {
+            return None;
+        }
+        tiff.first_ifd = tiff.u32_at(header + 4);
+        Some(tiff)
+    }
+
+    /// The u16 at `pos`; callers have checked `pos + 2` is in range
+    fn u16_at(&self, pos: usize) -> u16 {
+        let bytes = [self.data[pos], self.data[pos + 1]];
+        if self.big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) }
+    }
+
+    /// The u32 at `pos`; callers have checked `pos + 4` is in range
+    fn u32_at(&self, pos: usize) -> u32 {
+        let bytes = [self.data[pos], self.data[pos + 1], self.data[pos + 2], self.data[pos + 3]];
+        if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) }
+    }
+
+    /// Call `f` with each entry of the IFD at `ifd`, up to
+    /// `EXIF_MAX_IFD_ENTRIES`; stops at the first entry past the segment
+    fn for_each_entry(&self, ifd: u32, mut f: impl FnMut(Entry)) {
+        let Some(count_at) = tiff_range(self.base, ifd, 2, self.data.len()) else {
+            return;
+        };
+        let count = self.u16_at(count_at).min(EXIF_MAX_IFD_ENTRIES);
+        for index in 0..count {
+            let Some(at) = ifd_entry(self.base, ifd, index, self.data.len()) else {
+                return;
+            };
+            f(Entry {
+                tag: self.u16_at(at),
+                kind: self.u16_at(at + 2),
+                count: self.u32_at(at + 4),
+                value: self.u32_at(at + 8),
+                at,
+            });
+        }
+    }
+
+    /// An ASCII entry's text, without its NUL terminator
+    fn ascii(&self, entry: &Entry) -> Option<&'a [u8]> {
+        let len = entry.count as usize;
+        let start = ifd_value(self.base, entry.at, entry.value, len, self.data.len())?;
+        let text = &self.data[start..start + len];
+        let end = text.iter().position(|&b| b == 0).unwrap_or(len);
+        Some(&text[..end])
+    }
+}
+
+/// Parse "YYYY:MM:DD HH:MM[:SS]"; `None` for the all-zero "unknown" date
+/// or anything out of range
+fn parse_date_time(text: &[u8]) -> Option<CaptureTime> {
+    if text.len() < DATE_TIME_LEN || text[4] != b':' || text[7] != b':' || text[10] != b' ' || text[13] != b':' {
+        return None;
+    }
+    let number = |range: core::ops::Range<usize>| {
+        text[range].iter().try_fold(0u16, |n, &b| b.is_ascii_digit().then(|| n * 10 + (b - b'0') as u16))
+    };
+    let time = CaptureTime {
+        year: number(0..4)?,
+        month: number(5..7)? as u8,
+        day: number(8..10)? as u8,
+        hour: number(11..13)? as u8,
+        minute: number(14..16)? as u8,
+    };
+    let valid = (1..4096).contains(&time.year)
+        && (1..=12).contains(&time.month)
+        && (1..=31).contains(&time.day)
+        && time.hour < 24
+        && time.minute < 60;
+    valid.then_some(time)
+}
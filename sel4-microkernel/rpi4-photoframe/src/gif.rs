@@ -0,0 +1,702 @@
+//! # GIF Decoder (animated, LZW)
+//!
+//! Plays GIF87a/GIF89a images frame by frame onto a canvas the size of the
+//! GIF's logical screen:
+//!
+//! ```text
+//!   blocks ──► Graphic Control ──► image descriptor ──► LZW (4096 entries) ──► frame rect ──► canvas
+//!              (delay, disposal,                        sub-block bit stream    (clipped,      ARGB32
+//!               transparency)                                                   interlace)
+//! ```
+//!
+//! Each frame is drawn over what the previous one left behind, after that
+//! frame's disposal method has been applied: left in place, cleared to the
+//! background color, or restored to what was under it. After the last frame
+//! the animation starts over from a cleared canvas.
+//!
+//! Every LZW table access and string-stack push goes through the verified
+//! helpers in `rpi4_photo_protocol`, and canvas slots through its
+//! `surface_index`; frame rectangles are clipped to the canvas first. The
+//! heap holds the LZW table and, only for GIFs that use "restore to
+//! previous", one canvas-sized save buffer; see [`GifDecoder::working_memory`].
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rpi4_photo_protocol::{
+    frame_delay_from_centis, lzw_add, lzw_code_bits, lzw_lookup, lzw_stack_slot, surface_index,
+    LZW_MAX_MIN_CODE_SIZE, LZW_TABLE_SIZE,
+};
+
+use crate::decoder::DecodeError;
+
+/// GIF signatures
+const SIGNATURE_87A: &[u8; 6] = b"GIF87a";
+const SIGNATURE_89A: &[u8; 6] = b"GIF89a";
+
+/// Block introducers
+const EXTENSION: u8 = 0x21;
+const IMAGE_DESCRIPTOR: u8 = 0x2C;
+const TRAILER: u8 = 0x3B;
+
+/// Extension label of the Graphic Control Extension
+const GRAPHIC_CONTROL: u8 = 0xF9;
+
+/// Canvas color where no frame has drawn and no background color is given
+const BLANK: u32 = 0xFF00_0000;
+
+/// Rows of each interlace pass: (first row, step)
+const INTERLACE_PASSES: [(u32, u32); 4] = [(0, 8), (4, 8), (2, 4), (1, 2)];
+
+/// What happens to a frame's rectangle before the next frame is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Disposal {
+    /// Leave the frame in place (also "unspecified")
+    Keep,
+    /// Clear the rectangle to the background color
+    Background,
+    /// Put back what was under the frame
+    Previous,
+}
+
+impl Disposal {
+    fn from_packed(packed: u8) -> Self {
+        match (packed >> 2) & 0x07 {
+            2 => Disposal::Background,
+            3 => Disposal::Previous,
+            _ => Disposal::Keep,
+        }
+    }
+}
+
+/// Graphic Control Extension fields for the next image
+#[derive(Debug, Clone, Copy)]
+struct GraphicControl {
+    disposal: Disposal,
+    delay_centis: u16,
+    transparent: Option<u8>,
+}
+
+impl GraphicControl {
+    const NONE: Self = Self { disposal: Disposal::Keep, delay_centis: 0, transparent: None };
+}
+
+/// A frame rectangle, clipped to the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+/// A frame that was just drawn onto the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GifFrame {
+    /// Position in the animation, from 0
+    pub index: u16,
+    /// How long to show it, in ms (normalized, see `frame_delay_from_centis`)
+    pub delay_ms: u32,
+}
+
+/// Walk a chain of data sub-blocks starting at `pos`; returns the offset
+/// just past its terminator
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *data.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            return Some(pos);
+        }
+        pos = pos.checked_add(len).filter(|&end| end <= data.len())?;
+    }
+}
+
+/// Color table of `1 << (bits + 1)` RGB entries at `pos`; returns it and
+/// the offset past it
+fn color_table(data: &[u8], pos: usize, bits: u8) -> Option<(&[u8], usize)> {
+    let len = 3 * (1usize << ((bits & 0x07) + 1));
+    let end = pos.checked_add(len)?;
+    Some((data.get(pos..end)?, end))
+}
+
+/// ARGB32 color of palette entry `index`; out-of-range entries are black
+fn palette_color(palette: &[u8], index: u8) -> u32 {
+    let o = index as usize * 3;
+    match palette.get(o..o + 3) {
+        Some(rgb) => 0xFF00_0000 | ((rgb[0] as u32) << 16) | ((rgb[1] as u32) << 8) | (rgb[2] as u32),
+        None => BLANK,
+    }
+}
+
+// ============================================================================
+// LZW
+// ============================================================================
+
+/// Image data sub-blocks read as one little-endian bit stream
+struct CodeReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Bytes left in the current sub-block
+    block_left: usize,
+    /// Reached the terminating empty sub-block
+    done: bool,
+    bits: u32,
+    nbits: u32,
+}
+
+impl<'a> CodeReader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos, block_left: 0, done: false, bits: 0, nbits: 0 }
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        if self.block_left == 0 {
+            if self.done {
+                return None;
+            }
+            self.block_left = *self.data.get(self.pos)? as usize;
+            self.pos += 1;
+            if self.block_left == 0 {
+                self.done = true;
+                return None;
+            }
+        }
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        self.block_left -= 1;
+        Some(b)
+    }
+
+    /// Next `width`-bit code, or `None` at the end of the data
+    fn next_code(&mut self, width: u32) -> Option<u16> {
+        while self.nbits < width {
+            self.bits |= (self.next_byte()? as u32) << self.nbits;
+            self.nbits += 8;
+        }
+        let code = (self.bits & ((1 << width) - 1)) as u16;
+        self.bits >>= width;
+        self.nbits -= width;
+        Some(code)
+    }
+}
+
+/// LZW string table: entry `c` is entry `prefix[c]` followed by `suffix[c]`
+struct LzwTable {
+    prefix: [u16; LZW_TABLE_SIZE],
+    suffix: [u8; LZW_TABLE_SIZE],
+    /// First byte of each entry's string
+    first: [u8; LZW_TABLE_SIZE],
+    /// A string being expanded, last byte first
+    stack: [u8; LZW_TABLE_SIZE],
+}
+
+impl LzwTable {
+    /// Expand entry `slot` (a literal or a string entry, `clear` being the
+    /// first non-literal code) onto the stack; returns its length
+    fn expand(&mut self, mut slot: usize, clear: u16) -> Result<usize, DecodeError> {
+        let mut depth = 0;
+        loop {
+            let top = lzw_stack_slot(depth).ok_or(DecodeError::CorruptedData)?;
+            self.stack[top] = self.suffix[slot];
+            depth += 1;
+            if slot < clear as usize {
+                return Ok(depth);
+            }
+            slot = lzw_lookup(self.prefix[slot], slot as u16).ok_or(DecodeError::CorruptedData)?;
+        }
+    }
+
+    /// Decode the LZW stream at `pos` (minimum code size byte first),
+    /// passing each palette index to `emit`; returns the offset past the
+    /// image data
+    fn decode(&mut self, data: &[u8], pos: usize, mut emit: impl FnMut(u8)) -> Result<usize, DecodeError> {
+        let min_size = *data.get(pos).ok_or(DecodeError::CorruptedData)?;
+        if min_size == 0 || min_size > LZW_MAX_MIN_CODE_SIZE {
+            return Err(DecodeError::CorruptedData);
+        }
+        let end = skip_sub_blocks(data, pos + 1).ok_or(DecodeError::CorruptedData)?;
+
+        let clear: u16 = 1 << min_size;
+        let end_of_info = clear + 1;
+        for literal in 0..clear {
+            self.suffix[literal as usize] = literal as u8;
+            self.first[literal as usize] = literal as u8;
+        }
+
+        let mut reader = CodeReader::new(&data[..end], pos + 1);
+        let mut width = min_size as u32 + 1;
+        let mut next_code = clear + 2;
+        let mut prev: Option<u16> = None;
+
+        // A stream cut short before its end code keeps what it decoded;
+        // encoders that drop the end code are common
+        while let Some(code) = reader.next_code(width) {
+            if code == clear {
+                width = min_size as u32 + 1;
+                next_code = clear + 2;
+                prev = None;
+                continue;
+            }
+            if code == end_of_info {
+                break;
+            }
+
+            let Some(prev_code) = prev else {
+                // First code after a clear must be a literal
+                if code >= clear {
+                    return Err(DecodeError::CorruptedData);
+                }
+                emit(code as u8);
+                prev = Some(code);
+                continue;
+            };
+
+            let (len, first) = match lzw_lookup(code, next_code) {
+                Some(slot) => {
+                    let len = self.expand(slot, clear)?;
+                    (len, self.first[slot])
+                }
+                // The one code not yet in the table: the previous string
+                // followed by its own first byte
+                None if code == next_code => {
+                    let prev_slot = lzw_lookup(prev_code, next_code).ok_or(DecodeError::CorruptedData)?;
+                    let first = self.first[prev_slot];
+                    let len = self.expand(prev_slot, clear)?;
+                    emit_reversed(&self.stack[..len], &mut emit);
+                    emit(first);
+                    (0, first)
+                }
+                None => return Err(DecodeError::CorruptedData),
+            };
+            emit_reversed(&self.stack[..len], &mut emit);
+
+            if let Some(slot) = lzw_add(next_code) {
+                let prev_slot = lzw_lookup(prev_code, next_code).ok_or(DecodeError::CorruptedData)?;
+                self.prefix[slot] = prev_code;
+                self.suffix[slot] = first;
+                self.first[slot] = self.first[prev_slot];
+                next_code += 1;
+                width = lzw_code_bits(next_code, width);
+            }
+            prev = Some(code);
+        }
+        Ok(end)
+    }
+}
+
+/// Pass the stacked string to `emit` in stream order
+fn emit_reversed(stack: &[u8], emit: &mut impl FnMut(u8)) {
+    for &b in stack.iter().rev() {
+        emit(b);
+    }
+}
+
+// ============================================================================
+// FRAMES
+// ============================================================================
+
+/// Walks a GIF's frames, compositing each onto a caller-owned canvas.
+///
+/// The canvas is `width() * height()` ARGB32 pixels and must be passed
+/// unchanged between [`next_frame`](Self::next_frame) calls: frames draw
+/// over what their predecessors left behind.
+pub struct GifDecoder<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    frame_count: u16,
+    global_palette: &'a [u8],
+    background: u32,
+    /// Offset of the first block after the header
+    first_block: usize,
+    /// Offset of the next block to read
+    pos: usize,
+    next_index: u16,
+    /// Disposal still owed by the frame on the canvas
+    pending: Option<(Disposal, Rect)>,
+    table: Box<LzwTable>,
+    /// Canvas under the current frame, for `Disposal::Previous`; empty when
+    /// no frame in the file asks for it
+    saved: Vec<u32>,
+}
+
+impl<'a> GifDecoder<'a> {
+    /// Parse the header and walk the block structure once, counting frames.
+    ///
+    /// Allocates [`working_memory`](Self::working_memory) bytes from the
+    /// (bounded) global heap; call only through [`crate::secure_decode`].
+    pub fn open(data: &'a [u8]) -> Result<Self, DecodeError> {
+        let (width, height, global_palette, background, first_block) = Self::parse_header(data)?;
+        let (frame_count, restores) = Self::scan(data, first_block)?;
+
+        let saved = if restores { vec![0u32; width as usize * height as usize] } else { Vec::new() };
+        let table = Box::new(LzwTable {
+            prefix: [0; LZW_TABLE_SIZE],
+            suffix: [0; LZW_TABLE_SIZE],
+            first: [0; LZW_TABLE_SIZE],
+            stack: [0; LZW_TABLE_SIZE],
+        });
+        Ok(Self {
+            data,
+            width,
+            height,
+            frame_count,
+            global_palette,
+            background,
+            first_block,
+            pos: first_block,
+            next_index: 0,
+            pending: None,
+            table,
+            saved,
+        })
+    }
+
+    /// Heap bytes [`open`](Self::open) allocates for a `width` x `height`
+    /// GIF, at most (the save buffer is only allocated when needed)
+    pub fn working_memory(width: u32, height: u32) -> usize {
+        core::mem::size_of::<LzwTable>() + (width as usize) * (height as usize) * 4
+    }
+
+    /// Logical screen width
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Logical screen height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Frames in the animation (1 for a still GIF)
+    pub fn frame_count(&self) -> u16 {
+        self.frame_count
+    }
+
+    /// Logical screen size, global palette, background color and the
+    /// offset of the first block
+    #[allow(clippy::type_complexity)]
+    fn parse_header(data: &'a [u8]) -> Result<(u32, u32, &'a [u8], u32, usize), DecodeError> {
+        if data.len() < 13 || (&data[0..6] != SIGNATURE_87A && &data[0..6] != SIGNATURE_89A) {
+            return Err(DecodeError::InvalidFormat);
+        }
+        let width = u16::from_le_bytes([data[6], data[7]]) as u32;
+        let height = u16::from_le_bytes([data[8], data[9]]) as u32;
+        if width == 0 || height == 0 {
+            return Err(DecodeError::InvalidDimensions);
+        }
+        let (packed, bg_index) = (data[10], data[11]);
+        if packed & 0x80 == 0 {
+            return Ok((width, height, &[], BLANK, 13));
+        }
+        let (palette, end) = color_table(data, 13, packed).ok_or(DecodeError::InvalidHeader)?;
+        Ok((width, height, palette, palette_color(palette, bg_index), end))
+    }
+
+    /// Count the frames and note whether any restores to previous, checking
+    /// the block structure is intact
+    fn scan(data: &[u8], mut pos: usize) -> Result<(u16, bool), DecodeError> {
+        let mut frames: u16 = 0;
+        let mut restores = false;
+        loop {
+            match *data.get(pos).ok_or(DecodeError::CorruptedData)? {
+                EXTENSION => {
+                    let label = *data.get(pos + 1).ok_or(DecodeError::CorruptedData)?;
+                    if label == GRAPHIC_CONTROL && data.get(pos + 2) == Some(&4) {
+                        let packed = *data.get(pos + 3).ok_or(DecodeError::CorruptedData)?;
+                        restores |= Disposal::from_packed(packed) == Disposal::Previous;
+                    }
+                    pos = skip_sub_blocks(data, pos + 2).ok_or(DecodeError::CorruptedData)?;
+                }
+                IMAGE_DESCRIPTOR => {
+                    let packed = *data.get(pos + 9).ok_or(DecodeError::CorruptedData)?;
+                    pos += 10;
+                    if packed & 0x80 != 0 {
+                        pos = color_table(data, pos, packed).ok_or(DecodeError::CorruptedData)?.1;
+                    }
+                    // LZW minimum code size, then the data sub-blocks
+                    pos = skip_sub_blocks(data, pos + 1).ok_or(DecodeError::CorruptedData)?;
+                    frames = frames.checked_add(1).ok_or(DecodeError::CorruptedData)?;
+                }
+                TRAILER => break,
+                _ => return Err(DecodeError::CorruptedData),
+            }
+        }
+        if frames == 0 {
+            return Err(DecodeError::CorruptedData);
+        }
+        Ok((frames, restores))
+    }
+
+    /// Clear the whole canvas to the background color
+    fn clear_canvas(&self, canvas: &mut [u32]) {
+        canvas.fill(self.background);
+    }
+
+    /// Settle the previous frame's disposal on the canvas
+    fn dispose(&mut self, canvas: &mut [u32]) {
+        let Some((disposal, rect)) = self.pending.take() else {
+            return;
+        };
+        if disposal == Disposal::Keep {
+            return;
+        }
+        for y in rect.y..rect.y + rect.h {
+            for x in rect.x..rect.x + rect.w {
+                let idx = surface_index(x, y, self.width, self.height);
+                canvas[idx] = match disposal {
+                    Disposal::Previous => self.saved[idx],
+                    _ => self.background,
+                };
+            }
+        }
+    }
+
+    /// Draw the next frame onto `canvas`, starting over after the last one.
+    pub fn next_frame(&mut self, canvas: &mut [u32]) -> Result<GifFrame, DecodeError> {
+        let pixel_count = self.width as usize * self.height as usize;
+        if canvas.len() < pixel_count {
+            return Err(DecodeError::BufferTooSmall);
+        }
+        let canvas = &mut canvas[..pixel_count];
+
+        if self.next_index == 0 {
+            self.clear_canvas(canvas);
+            self.pending = None;
+        } else {
+            self.dispose(canvas);
+        }
+
+        let data = self.data;
+        let mut control = GraphicControl::NONE;
+        loop {
+            match *data.get(self.pos).ok_or(DecodeError::CorruptedData)? {
+                EXTENSION => {
+                    if data.get(self.pos + 1) == Some(&GRAPHIC_CONTROL) && data.get(self.pos + 2) == Some(&4) {
+                        let gce = data.get(self.pos + 3..self.pos + 7).ok_or(DecodeError::CorruptedData)?;
+                        control = GraphicControl {
+                            disposal: Disposal::from_packed(gce[0]),
+                            delay_centis: u16::from_le_bytes([gce[1], gce[2]]),
+                            transparent: (gce[0] & 0x01 != 0).then_some(gce[3]),
+                        };
+                    }
+                    self.pos = skip_sub_blocks(data, self.pos + 2).ok_or(DecodeError::CorruptedData)?;
+                }
+                IMAGE_DESCRIPTOR => break,
+                TRAILER => {
+                    // Loop: the next call draws frame 0 on a cleared canvas
+                    self.pos = self.first_block;
+                    self.next_index = 0;
+                    self.clear_canvas(canvas);
+                    self.pending = None;
+                    control = GraphicControl::NONE;
+                }
+                _ => return Err(DecodeError::CorruptedData),
+            }
+        }
+
+        let desc = data.get(self.pos + 1..self.pos + 10).ok_or(DecodeError::CorruptedData)?;
+        let left = u16::from_le_bytes([desc[0], desc[1]]) as u32;
+        let top = u16::from_le_bytes([desc[2], desc[3]]) as u32;
+        let frame_w = u16::from_le_bytes([desc[4], desc[5]]) as u32;
+        let frame_h = u16::from_le_bytes([desc[6], desc[7]]) as u32;
+        let packed = desc[8];
+        let interlaced = packed & 0x40 != 0;
+        self.pos += 10;
+
+        let palette = if packed & 0x80 != 0 {
+            let (palette, end) = color_table(data, self.pos, packed).ok_or(DecodeError::CorruptedData)?;
+            self.pos = end;
+            palette
+        } else {
+            self.global_palette
+        };
+
+        // The part of the frame that lands on the canvas
+        let rect = Rect {
+            x: left.min(self.width),
+            y: top.min(self.height),
+            w: frame_w.min(self.width.saturating_sub(left)),
+            h: frame_h.min(self.height.saturating_sub(top)),
+        };
+        if control.disposal == Disposal::Previous {
+            self.saved.copy_from_slice(canvas);
+        }
+
+        let (width, height) = (self.width, self.height);
+        let mut col: u32 = 0;
+        let mut pass = 0;
+        let mut row: u32 = if interlaced { INTERLACE_PASSES[0].0 } else { 0 };
+        let emit = |index: u8| {
+            if row >= frame_h {
+                return;
+            }
+            if col < rect.w && row < rect.h && control.transparent != Some(index) {
+                canvas[surface_index(left + col, top + row, width, height)] = palette_color(palette, index);
+            }
+            col += 1;
+            if col == frame_w {
+                col = 0;
+                if !interlaced {
+                    row += 1;
+                    return;
+                }
+                row += INTERLACE_PASSES[pass].1;
+                while row >= frame_h && pass + 1 < INTERLACE_PASSES.len() {
+                    pass += 1;
+                    row = INTERLACE_PASSES[pass].0;
+                }
+            }
+        };
+        self.pos = self.table.decode(data, self.pos, emit)?;
+
+        self.pending = Some((control.disposal, rect));
+        let frame = GifFrame {
+            index: self.next_index,
+            delay_ms: frame_delay_from_centis(control.delay_centis),
+        };
+        self.next_index += 1;
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pack `codes` of `width` bits LSB-first into one sub-block chain
+    fn pack(codes: &[(u16, u32)]) -> Vec<u8> {
+        let (mut bytes, mut acc, mut n) = (Vec::new(), 0u32, 0u32);
+        for &(code, width) in codes {
+            acc |= (code as u32) << n;
+            n += width;
+            while n >= 8 {
+                bytes.push(acc as u8);
+                acc >>= 8;
+                n -= 8;
+            }
+        }
+        if n > 0 {
+            bytes.push(acc as u8);
+        }
+        let mut out = vec![bytes.len() as u8];
+        out.extend_from_slice(&bytes);
+        out.push(0);
+        out
+    }
+
+    /// 4x2 GIF with a 4-color global palette and the given frames
+    /// (GCE bytes, descriptor fields, LZW data)
+    fn gif(frames: &[([u8; 4], [u16; 4], u8, Vec<u8>)]) -> Vec<u8> {
+        let mut g = SIGNATURE_89A.to_vec();
+        g.extend_from_slice(&[4, 0, 2, 0, 0x81, 0, 0]);
+        // black, red, green, blue
+        g.extend_from_slice(&[0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255]);
+        for (gce, [x, y, w, h], flags, lzw) in frames {
+            g.extend_from_slice(&[EXTENSION, GRAPHIC_CONTROL, 4]);
+            g.extend_from_slice(gce);
+            g.push(0);
+            g.push(IMAGE_DESCRIPTOR);
+            for v in [x, y, w, h] {
+                g.extend_from_slice(&v.to_le_bytes());
+            }
+            g.push(*flags);
+            g.push(2);
+            g.extend_from_slice(lzw);
+        }
+        g.push(TRAILER);
+        g
+    }
+
+    const RED: u32 = 0xFFFF_0000;
+    const GREEN: u32 = 0xFF00_FF00;
+    const BLUE: u32 = 0xFF00_00FF;
+    const BLACK: u32 = 0xFF00_0000;
+
+    #[test]
+    fn test_lzw_repeats_and_kwkwk() {
+        // clear, 1, 6 (the code being defined: 1 1), 6, 7 (1 1 1), end;
+        // codes widen to 4 bits once entry 7 is in the table
+        let lzw = pack(&[(4, 3), (1, 3), (6, 3), (6, 3), (7, 4), (5, 4)]);
+        let data = gif(&[([0, 5, 0, 0], [0, 0, 4, 2], 0, lzw)]);
+        let mut dec = GifDecoder::open(&data).unwrap();
+        assert_eq!(dec.frame_count(), 1);
+        let mut canvas = [0u32; 8];
+        assert_eq!(dec.next_frame(&mut canvas), Ok(GifFrame { index: 0, delay_ms: 50 }));
+        assert_eq!(canvas, [RED; 8]);
+    }
+
+    #[test]
+    fn test_disposal_and_transparency() {
+        let fill = |c: u16| pack(&[(4, 3), (c, 3), (6, 3), (7, 3), (6, 4), (5, 4)]);
+        let data = gif(&[
+            // Full red frame, restore-to-background afterwards
+            ([2 << 2, 10, 0, 0], [0, 0, 4, 2], 0, fill(1)),
+            // 2x1 green at (1,0), restore-to-previous afterwards
+            ([3 << 2, 10, 0, 0], [1, 0, 2, 1], 0, pack(&[(4, 3), (2, 3), (2, 3), (5, 3)])),
+            // 2x2 blue at (2,0) with green transparent
+            ([1 | (1 << 2), 10, 0, 2], [2, 0, 2, 2], 0, pack(&[(4, 3), (2, 3), (3, 3), (3, 3), (2, 4), (5, 4)])),
+        ]);
+        let mut dec = GifDecoder::open(&data).unwrap();
+        assert_eq!(dec.frame_count(), 3);
+        let mut canvas = [0u32; 8];
+
+        dec.next_frame(&mut canvas).unwrap();
+        assert_eq!(canvas, [RED; 8]);
+
+        // Frame 0 cleared to the background (palette entry 0) first
+        dec.next_frame(&mut canvas).unwrap();
+        assert_eq!(canvas, [BLACK, GREEN, GREEN, BLACK, BLACK, BLACK, BLACK, BLACK]);
+
+        // Frame 1 restored away; transparent pixels show what is beneath
+        let frame = dec.next_frame(&mut canvas).unwrap();
+        assert_eq!(frame.index, 2);
+        assert_eq!(canvas, [BLACK, BLACK, BLACK, BLUE, BLACK, BLACK, BLUE, BLACK]);
+
+        // Loops back to frame 0 on a cleared canvas
+        assert_eq!(dec.next_frame(&mut canvas).unwrap().index, 0);
+        assert_eq!(canvas, [RED; 8]);
+    }
+
+    #[test]
+    fn test_interlaced_rows() {
+        // 1x4 interlaced: rows arrive in the order 0, 2, 1, 3
+        let mut data = gif(&[([0, 0, 0, 0], [0, 0, 1, 4], 0x40, pack(&[(4, 3), (1, 3), (2, 3), (3, 3), (0, 4), (5, 4)]))]);
+        // Make the logical screen 1x4
+        data[6..10].copy_from_slice(&[1, 0, 4, 0]);
+        let mut dec = GifDecoder::open(&data).unwrap();
+        let mut canvas = [0u32; 4];
+        dec.next_frame(&mut canvas).unwrap();
+        assert_eq!(canvas, [RED, BLUE, GREEN, BLACK]);
+    }
+
+    #[test]
+    fn test_frame_clipped_to_canvas() {
+        // 4x2 frame placed at (2,1): only its top-left 2x1 lands on the canvas
+        let fill = pack(&[(4, 3), (3, 3), (6, 3), (7, 3), (6, 4), (5, 4)]);
+        let data = gif(&[([0, 0, 0, 0], [2, 1, 4, 2], 0, fill)]);
+        let mut dec = GifDecoder::open(&data).unwrap();
+        let mut canvas = [0u32; 8];
+        dec.next_frame(&mut canvas).unwrap();
+        assert_eq!(canvas, [BLACK, BLACK, BLACK, BLACK, BLACK, BLACK, BLUE, BLUE]);
+    }
+
+    #[test]
+    fn test_rejects_bad_streams() {
+        // Code past the table
+        let data = gif(&[([0, 0, 0, 0], [0, 0, 4, 2], 0, pack(&[(4, 3), (1, 3), (7, 3)]))]);
+        let mut canvas = [0u32; 8];
+        assert_eq!(GifDecoder::open(&data).unwrap().next_frame(&mut canvas), Err(DecodeError::CorruptedData));
+
+        // Truncated block structure
+        let mut data = gif(&[([0, 0, 0, 0], [0, 0, 4, 2], 0, pack(&[(4, 3), (1, 3), (5, 3)]))]);
+        data.truncate(data.len() - 3);
+        assert_eq!(GifDecoder::open(&data).err(), Some(DecodeError::CorruptedData));
+
+        // No frames at all
+        let data = gif(&[]);
+        assert_eq!(GifDecoder::open(&data).err(), Some(DecodeError::CorruptedData));
+    }
+}
@@ -0,0 +1,61 @@
+//! # Health Panel
+//!
+//! What the Watchdog PD last saw of the PDs it watches, from the liveness
+//! page (`rpi4_watchdog_protocol`), as lines of the debug overlay between
+//! the fixed readouts and the memory gauges:
+//!
+//! ```text
+//!   WATCHDOG FEEDS 1234
+//!   TIMER OK  PING 0.3S AGO  12 PINGS  WINDOW 2S
+//!   PHOTO OK  PING 0.9S AGO  11 PINGS  WINDOW 10S
+//! ```
+//!
+//! One line per registered client, under a header that says whether the
+//! Watchdog PD has published at all. This module only lays out the text,
+//! so the host test crate can exercise it.
+
+use core::fmt;
+use rpi4_watchdog_protocol::{ClientHealth, Liveness, MAX_CLIENTS};
+
+/// Most lines the panel takes: the header and one per client slot
+pub const HEALTH_LINES: usize = 1 + MAX_CLIENTS;
+
+/// Lines the panel takes for `liveness`
+pub fn health_rows(liveness: Option<&Liveness>) -> usize {
+    1 + liveness.map_or(0, |l| l.registered().count())
+}
+
+/// Line `n` of the panel at system timer reading `now_us`, `n <
+/// health_rows(liveness)`
+pub fn health_line(liveness: Option<&Liveness>, n: usize, now_us: u64) -> Option<HealthLine<'_>> {
+    match (n, liveness) {
+        (0, _) => Some(HealthLine::Header(liveness)),
+        (n, Some(l)) => l.registered().nth(n - 1).map(|client| HealthLine::Client(client, now_us)),
+        (_, None) => None,
+    }
+}
+
+/// One line of the panel, formatted on demand
+pub enum HealthLine<'a> {
+    Header(Option<&'a Liveness>),
+    Client(&'a ClientHealth, u64),
+}
+
+impl fmt::Display for HealthLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthLine::Header(Some(l)) => write!(f, "WATCHDOG FEEDS {}", l.feeds),
+            HealthLine::Header(None) => f.write_str("WATCHDOG NO PAGE"),
+            HealthLine::Client(client, now_us) => {
+                let age_ms = client.ping_age_ms(*now_us);
+                write!(f, "{} {}  ", client.name(), client.state.name())?;
+                if client.pings == 0 {
+                    f.write_str("NO PING")?;
+                } else {
+                    write!(f, "PING {}.{}S AGO", age_ms / 1000, age_ms % 1000 / 100)?;
+                }
+                write!(f, "  {} PINGS  WINDOW {}S", client.pings, client.window_ms / 1000)
+            }
+        }
+    }
+}
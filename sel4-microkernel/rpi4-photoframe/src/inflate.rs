@@ -0,0 +1,515 @@
+//! # No-std zlib / DEFLATE Decompressor
+//!
+//! Streaming inflate (RFC 1950 + RFC 1951) for the PNG decode path. It owns no
+//! buffers: the caller lends it a 32 KiB sliding window and receives every
+//! decompressed byte through a [`ByteSink`], so the full inflated image never
+//! has to exist in memory.
+//!
+//! ## Security Note
+//!
+//! The input is attacker-controlled. All window arithmetic goes through the
+//! verified helpers in `rpi4_photo_protocol` ([`window_next`],
+//! [`window_back_ref`]), so a malicious back-reference is rejected rather than
+//! reading outside the window or before the start of the stream. Huffman
+//! tables are built into fixed-size arrays and over-subscribed code sets are
+//! refused, following the structure of zlib's reference `puff.c`.
+
+use rpi4_photo_protocol::{window_back_ref, window_next, INFLATE_WINDOW_SIZE};
+
+/// Inflate failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InflateError {
+    /// Input ended before the final block / checksum
+    UnexpectedEof,
+    /// zlib header is malformed or uses a preset dictionary
+    BadZlibHeader,
+    /// Reserved block type (3)
+    BadBlockType,
+    /// Stored block LEN/NLEN mismatch
+    BadStoredLength,
+    /// Code lengths do not describe a usable Huffman code
+    BadHuffmanTable,
+    /// Bit pattern does not decode to a valid symbol
+    BadSymbol,
+    /// Back-reference before the start of output or beyond the window
+    BadDistance,
+    /// Adler-32 of the output does not match the trailer
+    ChecksumMismatch,
+    /// The sink refused more output
+    OutputOverflow,
+    /// The sink rejected the data itself (e.g. bad PNG filter byte)
+    InvalidOutput,
+}
+
+/// Source of compressed bytes.
+pub trait ByteSource {
+    /// Next input byte, or `None` at end of input.
+    fn next_byte(&mut self) -> Option<u8>;
+}
+
+/// Destination for decompressed bytes.
+pub trait ByteSink {
+    /// Accept one decompressed byte.
+    fn push(&mut self, byte: u8) -> Result<(), InflateError>;
+}
+
+/// A contiguous compressed buffer.
+pub struct SliceSource<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SliceSource<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+}
+
+impl ByteSource for SliceSource<'_> {
+    fn next_byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+}
+
+// ============================================================================
+// BIT READER
+// ============================================================================
+
+/// LSB-first bit reader (DEFLATE bit order).
+struct BitReader<'s, S: ByteSource> {
+    src: &'s mut S,
+    bitbuf: u32,
+    bitcnt: u32,
+}
+
+impl<'s, S: ByteSource> BitReader<'s, S> {
+    fn new(src: &'s mut S) -> Self {
+        Self { src, bitbuf: 0, bitcnt: 0 }
+    }
+
+    /// Read `need` (≤ 16) bits.
+    fn bits(&mut self, need: u32) -> Result<u32, InflateError> {
+        while self.bitcnt < need {
+            let b = self.src.next_byte().ok_or(InflateError::UnexpectedEof)?;
+            self.bitbuf |= (b as u32) << self.bitcnt;
+            self.bitcnt += 8;
+        }
+        let val = self.bitbuf & ((1u32 << need) - 1);
+        self.bitbuf >>= need;
+        self.bitcnt -= need;
+        Ok(val)
+    }
+
+    /// Discard the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.bitbuf = 0;
+        self.bitcnt = 0;
+    }
+
+    fn byte(&mut self) -> Result<u8, InflateError> {
+        self.src.next_byte().ok_or(InflateError::UnexpectedEof)
+    }
+}
+
+// ============================================================================
+// HUFFMAN TABLES
+// ============================================================================
+
+const MAX_BITS: usize = 15;
+const MAX_LIT_CODES: usize = 286;
+const MAX_DIST_CODES: usize = 30;
+const FIXED_LIT_CODES: usize = 288;
+
+/// Canonical Huffman decoding table (symbols sorted by code length).
+struct Huffman {
+    count: [u16; MAX_BITS + 1],
+    symbol: [u16; FIXED_LIT_CODES],
+}
+
+impl Huffman {
+    const fn empty() -> Self {
+        Self { count: [0; MAX_BITS + 1], symbol: [0; FIXED_LIT_CODES] }
+    }
+
+    /// Build from per-symbol code lengths. Returns the number of unused codes
+    /// (0 = complete code); over-subscribed sets are an error.
+    fn build(&mut self, lengths: &[u8]) -> Result<i32, InflateError> {
+        if lengths.len() > FIXED_LIT_CODES {
+            return Err(InflateError::BadHuffmanTable);
+        }
+        self.count = [0; MAX_BITS + 1];
+        for &len in lengths {
+            if len as usize > MAX_BITS {
+                return Err(InflateError::BadHuffmanTable);
+            }
+            self.count[len as usize] += 1;
+        }
+        if self.count[0] as usize == lengths.len() {
+            return Ok(0);
+        }
+
+        let mut left: i32 = 1;
+        for len in 1..=MAX_BITS {
+            left <<= 1;
+            left -= self.count[len] as i32;
+            if left < 0 {
+                return Err(InflateError::BadHuffmanTable);
+            }
+        }
+
+        let mut offs = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offs[len + 1] = offs[len] + self.count[len];
+        }
+        for (sym, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                let slot = &mut offs[len as usize];
+                self.symbol[*slot as usize] = sym as u16;
+                *slot += 1;
+            }
+        }
+        Ok(left)
+    }
+
+    /// Decode one symbol, one bit at a time.
+    fn decode<S: ByteSource>(&self, br: &mut BitReader<'_, S>) -> Result<u16, InflateError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..=MAX_BITS {
+            code |= br.bits(1)? as i32;
+            let count = self.count[len] as i32;
+            if code - count < first {
+                let slot = (index + (code - first)) as usize;
+                return self.symbol.get(slot).copied().ok_or(InflateError::BadSymbol);
+            }
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+        Err(InflateError::BadSymbol)
+    }
+}
+
+/// Base lengths for length codes 257..285
+const LEN_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+/// Extra bits for length codes 257..285
+const LEN_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances for distance codes 0..29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+/// Extra bits for distance codes 0..29
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+/// Order in which code-length code lengths are transmitted
+const CLEN_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+// ============================================================================
+// INFLATER
+// ============================================================================
+
+/// Streaming inflater over a caller-provided sliding window.
+pub struct Inflater<'w, K: ByteSink> {
+    window: &'w mut [u8],
+    pos: usize,
+    produced: usize,
+    adler_a: u32,
+    adler_b: u32,
+    sink: K,
+}
+
+impl<'w, K: ByteSink> Inflater<'w, K> {
+    /// Create an inflater. `window` must be exactly [`INFLATE_WINDOW_SIZE`]
+    /// bytes; its previous contents are irrelevant.
+    pub fn new(window: &'w mut [u8], sink: K) -> Result<Self, InflateError> {
+        if window.len() != INFLATE_WINDOW_SIZE {
+            return Err(InflateError::OutputOverflow);
+        }
+        Ok(Self { window, pos: 0, produced: 0, adler_a: 1, adler_b: 0, sink })
+    }
+
+    /// Total decompressed bytes emitted.
+    pub fn produced(&self) -> usize {
+        self.produced
+    }
+
+    /// Consume the inflater, returning the sink.
+    pub fn into_sink(self) -> K {
+        self.sink
+    }
+
+    /// Inflate a complete zlib stream (header, DEFLATE blocks, Adler-32).
+    pub fn inflate_zlib<S: ByteSource>(&mut self, src: &mut S) -> Result<(), InflateError> {
+        let mut br = BitReader::new(src);
+        let cmf = br.byte()?;
+        let flg = br.byte()?;
+        // CM = 8 (deflate), CINFO ≤ 7 (window ≤ 32K), FCHECK, no FDICT
+        if cmf & 0x0F != 8
+            || cmf >> 4 > 7
+            || !((cmf as u16) << 8 | flg as u16).is_multiple_of(31)
+            || flg & 0x20 != 0
+        {
+            return Err(InflateError::BadZlibHeader);
+        }
+
+        self.inflate_blocks(&mut br)?;
+
+        br.align();
+        let mut expected: u32 = 0;
+        for _ in 0..4 {
+            expected = (expected << 8) | br.byte()? as u32;
+        }
+        if expected != (self.adler_b << 16) | self.adler_a {
+            return Err(InflateError::ChecksumMismatch);
+        }
+        Ok(())
+    }
+
+    /// Inflate raw DEFLATE blocks until the final block.
+    fn inflate_blocks<S: ByteSource>(&mut self, br: &mut BitReader<'_, S>) -> Result<(), InflateError> {
+        let mut lit = Huffman::empty();
+        let mut dist = Huffman::empty();
+        loop {
+            let last = br.bits(1)?;
+            match br.bits(2)? {
+                0 => self.stored(br)?,
+                1 => {
+                    build_fixed(&mut lit, &mut dist)?;
+                    self.codes(br, &lit, &dist)?;
+                }
+                2 => {
+                    build_dynamic(br, &mut lit, &mut dist)?;
+                    self.codes(br, &lit, &dist)?;
+                }
+                _ => return Err(InflateError::BadBlockType),
+            }
+            if last == 1 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn stored<S: ByteSource>(&mut self, br: &mut BitReader<'_, S>) -> Result<(), InflateError> {
+        br.align();
+        let len = br.byte()? as u16 | (br.byte()? as u16) << 8;
+        let nlen = br.byte()? as u16 | (br.byte()? as u16) << 8;
+        if len != !nlen {
+            return Err(InflateError::BadStoredLength);
+        }
+        for _ in 0..len {
+            let b = br.byte()?;
+            self.emit(b)?;
+        }
+        Ok(())
+    }
+
+    fn codes<S: ByteSource>(
+        &mut self,
+        br: &mut BitReader<'_, S>,
+        lit: &Huffman,
+        dist: &Huffman,
+    ) -> Result<(), InflateError> {
+        loop {
+            let sym = lit.decode(br)? as usize;
+            if sym < 256 {
+                self.emit(sym as u8)?;
+            } else if sym == 256 {
+                return Ok(());
+            } else {
+                let li = sym - 257;
+                if li >= LEN_BASE.len() {
+                    return Err(InflateError::BadSymbol);
+                }
+                let len = LEN_BASE[li] as usize + br.bits(LEN_EXTRA[li] as u32)? as usize;
+
+                let di = dist.decode(br)? as usize;
+                if di >= DIST_BASE.len() {
+                    return Err(InflateError::BadSymbol);
+                }
+                let distance = DIST_BASE[di] as usize + br.bits(DIST_EXTRA[di] as u32)? as usize;
+
+                let mut from = window_back_ref(self.pos, distance, self.produced)
+                    .ok_or(InflateError::BadDistance)?;
+                for _ in 0..len {
+                    let b = self.window[from];
+                    self.emit(b)?;
+                    from = window_next(from);
+                }
+            }
+        }
+    }
+
+    /// Record one output byte in the window and checksum, then hand it on.
+    fn emit(&mut self, b: u8) -> Result<(), InflateError> {
+        self.window[self.pos] = b;
+        self.pos = window_next(self.pos);
+        self.produced = self.produced.saturating_add(1);
+        self.adler_a = (self.adler_a + b as u32) % 65521;
+        self.adler_b = (self.adler_b + self.adler_a) % 65521;
+        self.sink.push(b)
+    }
+}
+
+fn build_fixed(lit: &mut Huffman, dist: &mut Huffman) -> Result<(), InflateError> {
+    let mut lengths = [0u8; FIXED_LIT_CODES];
+    for (sym, len) in lengths.iter_mut().enumerate() {
+        *len = match sym {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    lit.build(&lengths)?;
+    dist.build(&[5u8; MAX_DIST_CODES])?;
+    Ok(())
+}
+
+fn build_dynamic<S: ByteSource>(
+    br: &mut BitReader<'_, S>,
+    lit: &mut Huffman,
+    dist: &mut Huffman,
+) -> Result<(), InflateError> {
+    let nlen = br.bits(5)? as usize + 257;
+    let ndist = br.bits(5)? as usize + 1;
+    let ncode = br.bits(4)? as usize + 4;
+    if nlen > MAX_LIT_CODES || ndist > MAX_DIST_CODES {
+        return Err(InflateError::BadHuffmanTable);
+    }
+
+    let mut lengths = [0u8; MAX_LIT_CODES + MAX_DIST_CODES];
+    for &idx in CLEN_ORDER.iter().take(ncode) {
+        lengths[idx] = br.bits(3)? as u8;
+    }
+    let mut clen = Huffman::empty();
+    if clen.build(&lengths[..19])? != 0 {
+        return Err(InflateError::BadHuffmanTable);
+    }
+
+    let total = nlen + ndist;
+    let mut i = 0;
+    while i < total {
+        let sym = clen.decode(br)?;
+        if sym < 16 {
+            lengths[i] = sym as u8;
+            i += 1;
+            continue;
+        }
+        let (value, repeat) = match sym {
+            16 => {
+                if i == 0 {
+                    return Err(InflateError::BadHuffmanTable);
+                }
+                (lengths[i - 1], 3 + br.bits(2)? as usize)
+            }
+            17 => (0, 3 + br.bits(3)? as usize),
+            _ => (0, 11 + br.bits(7)? as usize),
+        };
+        if i + repeat > total {
+            return Err(InflateError::BadHuffmanTable);
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+
+    // The end-of-block code must be present.
+    if lengths[256] == 0 {
+        return Err(InflateError::BadHuffmanTable);
+    }
+
+    // Incomplete literal/length codes are only allowed for a single code.
+    let left = lit.build(&lengths[..nlen])?;
+    if left > 0 && nlen as i32 - lit.count[0] as i32 != 1 {
+        return Err(InflateError::BadHuffmanTable);
+    }
+    let left = dist.build(&lengths[nlen..total])?;
+    if left > 0 && ndist as i32 - dist.count[0] as i32 != 1 {
+        return Err(InflateError::BadHuffmanTable);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    struct VecSink(Vec<u8>);
+
+    impl ByteSink for VecSink {
+        fn push(&mut self, byte: u8) -> Result<(), InflateError> {
+            self.0.push(byte);
+            Ok(())
+        }
+    }
+
+    fn inflate(data: &[u8]) -> Result<Vec<u8>, InflateError> {
+        let mut window = alloc::vec![0u8; INFLATE_WINDOW_SIZE];
+        let mut inf = Inflater::new(&mut window, VecSink(Vec::new()))?;
+        inf.inflate_zlib(&mut SliceSource::new(data))?;
+        Ok(inf.into_sink().0)
+    }
+
+    #[test]
+    fn test_stored_block() {
+        // zlib.compress(b"hello", 0)
+        let data = [
+            0x78, 0x01, 0x01, 0x05, 0x00, 0xfa, 0xff, b'h', b'e', b'l', b'l', b'o',
+            0x06, 0x2c, 0x02, 0x15,
+        ];
+        assert_eq!(inflate(&data).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_fixed_huffman_with_back_reference() {
+        // zlib.compress(b"abcabcabcabcabc", 9)
+        let data = [
+            0x78, 0xda, 0x4b, 0x4c, 0x4a, 0x4e, 0x44, 0x42, 0x00, 0x2d, 0xf5, 0x05, 0xbf,
+        ];
+        assert_eq!(inflate(&data).unwrap(), b"abcabcabcabcabc");
+    }
+
+    #[test]
+    fn test_rejects_bad_checksum() {
+        let data = [
+            0x78, 0x01, 0x01, 0x05, 0x00, 0xfa, 0xff, b'h', b'e', b'l', b'l', b'o',
+            0x06, 0x2c, 0x02, 0x16,
+        ];
+        assert_eq!(inflate(&data), Err(InflateError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_rejects_distance_before_start() {
+        // Fixed block: length code 257 (len 3), distance code 0 (dist 1),
+        // with no prior output.
+        // Bits: BFINAL=1, BTYPE=01, then 7-bit code 0000001 (sym 257),
+        // then 5-bit distance 00000.
+        let data = [0x78, 0x01, 0x03, 0x02, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(inflate(&data), Err(InflateError::BadDistance));
+    }
+
+    #[test]
+    fn test_rejects_truncated_stream() {
+        let data = [0x78, 0x01, 0x01, 0x05, 0x00, 0xfa, 0xff, b'h', b'e'];
+        assert_eq!(inflate(&data), Err(InflateError::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_rejects_preset_dictionary() {
+        // FDICT set (0x78 0xbb satisfies FCHECK)
+        assert_eq!(inflate(&[0x78, 0xbb, 0, 0, 0, 0]), Err(InflateError::BadZlibHeader));
+    }
+}
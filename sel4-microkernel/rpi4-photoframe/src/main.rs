@@ -1,7 +1,9 @@
 //! # Secure Photo Frame Protection Domain
 //!
 //! A photo frame demo demonstrating seL4 isolation principles.
-//! Photos are embedded at compile time and displayed with slideshow functionality.
+//! Photos are embedded at compile time and displayed with slideshow functionality;
+//! with the `sdcard` feature, the photos on the SD card follow them, fetched
+//! from the Storage PD.
 //!
 //! ## Security Model
 //!
@@ -9,6 +11,10 @@
 //! - **Input PD**: UART input handling (isolated from display)
 //! - **Photoframe PD**: Photo decoding + display (this PD)
 //!
+//! A Timer PD keeps the wall-clock time for the clock overlay, and a
+//! Network PD will publish the weather for the weather panel; this PD only
+//! reads them, from pages mapped read-only.
+//!
 //! The full 3-PD architecture would separate decoder from display,
 //! providing defense-in-depth against malicious image files.
 //!
@@ -17,7 +23,33 @@
 //! - Slideshow with configurable interval
 //! - Manual navigation (next/prev)
 //! - Pause/resume
-//! - Photo info overlay
+//! - Playlist orders (in order, shuffle, newest first), per-photo dwell
+//!   times, favorites, and photos that fail to decode skipped thereafter
+//! - Photo info overlay, composited over the photo so it can be toggled
+//!   without decoding the photo again
+//! - Animated GIF playback, each frame shown for its own delay (drawn on
+//!   the first tick after it)
+//! - Transitions between photos (crossfade, slide, wipe, Ken Burns), drawn
+//!   by a verified kernel that cannot index past either photo, at 30 Hz on
+//!   the display's vertical blank when the firmware signals it
+//! - Clock and date in a corner of the screen (cycled with "4"), with the
+//!   screen dimmed overnight
+//! - Weather panel (temperature and conditions) in the other top corner,
+//!   once a Network PD publishes reports
+//! - Debug overlay (hold "9", press "0"): frame rate, last key, when the
+//!   Input and Timer PDs were last heard from, and each buffer's use and
+//!   high-water mark
+//! - Memory accounting: every buffer's high-water mark on the console as
+//!   `@mem` lines, to size the `.system` regions by
+//! - Cycle-count profiling screen (`profile` feature, toggled with "0")
+//! - Self-driving soak test with per-frame invariant checks (`soak` feature)
+//! - SD card photos, read through the Storage PD (`sdcard` feature), and
+//!   only Ed25519-signed ones with the `signed-photos` feature
+//! - A chime on each slide change, played by the Audio PD (`audio` feature)
+//! - A heartbeat to the Watchdog PD, and its view of every PD it watches on
+//!   the debug overlay (`watchdog` feature)
+//! - The display's native mode from its EDID, 720p up to 1080p, with
+//!   everything laid out at the size it gives
 
 #![no_std]
 #![no_main]
@@ -25,17 +57,45 @@
 extern crate alloc;
 
 mod decoder;
+mod exif;
+mod gif;
+mod inflate;
+mod png;
 mod bounded_alloc;
+#[cfg(feature = "audio")]
+mod audio;
+mod clock;
+mod debug_overlay;
+#[cfg(feature = "watchdog")]
+mod health;
 mod validate;
 mod secure_decode;
+mod playlist;
+#[cfg(feature = "sdcard")]
+mod sdcard;
+#[cfg(feature = "soak")]
+mod soak;
+mod transitions;
+mod weather;
 
 use sel4_microkit::{debug_println, protection_domain, Handler, ChannelSet, Channel};
-use core::fmt;
+use rpi4_pd_error::mem::{Gauge, Reading, Unit};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
 use core::cell::UnsafeCell;
-use core::sync::atomic::Ordering;
 
 use bounded_alloc::BoundedBumpAllocator;
-use secure_decode::{secure_decode_into, SecureDecodeError};
+use clock::{clock_origin, Corner, DateText, NightMode, TimeText, CLOCK_MARGIN, DAY_COLOR, NIGHT_COLOR};
+use debug_overlay::{Chord, DebugStats, FpsMeter, MemText, DEBUG_LINES};
+use exif::ExifInfo;
+use gif::GifDecoder;
+#[cfg(feature = "watchdog")]
+use health::{health_line, health_rows};
+use playlist::{Order, Playlist};
+use secure_decode::{secure_decode_animated, secure_decode_into, SecureDecodeError};
+#[cfg(feature = "sdcard")]
+use sdcard::SdCard;
+use transitions::{effect_for, effect_name, frames_at, Transition, TRANSITION_FRAMES};
+use weather::{weather_corner, TempText};
 
 // ============================================================================
 // BOUNDED GLOBAL ALLOCATOR
@@ -48,7 +108,7 @@ use secure_decode::{secure_decode_into, SecureDecodeError};
 // pipeline reports OutOfMemory instead of letting the heap grow unbounded.
 //
 // Memory cost: this reserves DECODER_HEAP_SIZE of zero-initialized BSS in the
-// PD image. 16 MB comfortably covers a full-screen (1280x720) JPEG/PNG decode
+// PD image. 16 MB comfortably covers a full-screen (1920x1080) JPEG/PNG decode
 // plus the decoder's internal working buffers. A production build would back
 // this with a dedicated `memory_region` in the .system file rather than BSS.
 
@@ -58,16 +118,80 @@ const DECODER_HEAP_SIZE: usize = 16 * 1024 * 1024;
 #[global_allocator]
 static DECODER_HEAP: BoundedBumpAllocator<DECODER_HEAP_SIZE> = BoundedBumpAllocator::new();
 
-use rpi4_graphics::{Mailbox, Framebuffer, MAILBOX_BASE};
+// Memory accounting: use and high-water mark of each buffer, printed as
+// `@mem` lines and shown on the debug overlay, to size them by. The decoder
+// heap counts for itself.
+
+/// Input ring entries waiting, seen before each batch is drained
+static INPUT_RING_GAUGE: Gauge = Gauge::new("input_ring", Unit::Slots, (RING_CAPACITY - 1) as usize);
+/// Bytes of the decode scratch buffer the largest picture filled
+static SCRATCH_GAUGE: Gauge = Gauge::new("pixel_scratch", Unit::Bytes, SCRATCH_PIXELS * 4);
+/// Thumbnail ring slots written
+static THUMB_GAUGE: Gauge = Gauge::new("thumb_ring", Unit::Slots, THUMB_SLOTS as usize);
+/// Telemetry records held
+static TELEMETRY_GAUGE: Gauge = Gauge::new("telemetry_log", Unit::Slots, TELEMETRY_CAPACITY as usize);
+
+/// Gauges on the `@mem` lines and the debug overlay, the heap first
+const MEM_GAUGES: usize = 5;
+
+fn mem_readings() -> [Reading; MEM_GAUGES] {
+    [
+        Reading::new("decoder_heap", Unit::Bytes, DECODER_HEAP.used(), DECODER_HEAP.peak(), DECODER_HEAP_SIZE),
+        INPUT_RING_GAUGE.reading(),
+        SCRATCH_GAUGE.reading(),
+        THUMB_GAUGE.reading(),
+        TELEMETRY_GAUGE.reading(),
+    ]
+}
+
+/// Record `used` on `gauge`, printing it when its peak reaches another step
+fn record_mem(gauge: &Gauge, used: usize) {
+    if gauge.record(used) {
+        debug_println!("{}", gauge.reading().line("photoframe"));
+    }
+}
+
+/// Print every gauge as an `@mem` line
+fn print_mem_stats() {
+    for reading in mem_readings() {
+        debug_println!("{}", reading.line("photoframe"));
+    }
+}
+
+use rpi4_graphics::{
+    native_mode, profile_scope, vsync::REFRESH_HZ, Color, Compositor, DisplayMode, Duration, FrameClock, Instant,
+    Layer, Mailbox, Framebuffer, ModeSelection, Vsync, MAILBOX_BASE, SMI_VIRT_BASE, SYSTIMER_VIRT_BASE,
+    FIXED_720P_LADDER,
+};
+#[cfg(feature = "profile")]
+use rpi4_graphics::font::RawTarget;
+use rpi4_graphics::font::{BitmapFont, TextTarget, PIXEL_8, SANS_16, SANS_24};
+use rpi4_clock_protocol::{CivilDate, ClockPage, LocalTime, CLIENT_TICK_CHANNEL_ID, CLOCK_PAGE_VADDR, TICK_HZ};
+use rpi4_network_protocol::weather::{WeatherPage, WeatherReport, WEATHER_PAGE_VADDR};
+#[cfg(feature = "watchdog")]
+use rpi4_watchdog_protocol::{Liveness, LivenessPage, CLIENT_WATCHDOG_CHANNEL_ID, LIVENESS_PAGE_VADDR, PING_INTERVAL_MS};
+#[cfg(feature = "profile")]
+use rpi4_graphics::profile::{self, ScopeSample};
+use rpi4_photo_protocol::{
+    blit_scaled, grid_cell, grid_move, grid_page_start, letterbox_rect, BlitError, BlitRect, GridMove,
+    Orientation, PhotoCommand, PixelBufferHeader, ScaleFilter, TelemetryLog, TelemetryRecord, ThumbnailRing,
+    BROWSE_OPEN, CMD_BROWSE, CMD_FAVORITE, CMD_NEXT, CMD_PREV, CMD_SELECT, CMD_SHUFFLE, GRID_COLS, GRID_PAGE,
+    GRID_ROWS, PIXEL_FORMAT_RGBA32, TELEMETRY_CAPACITY, TELEMETRY_LOG_VADDR, THUMB_H, THUMB_READY,
+    THUMB_RING_VADDR, THUMB_SLOTS, THUMB_W,
+};
+use verified_microkernel::{abs_diff_u32, max_i32, min_u32, BoundedString};
 use rpi4_input::{KeyCode, KeyState};
 use rpi4_input_protocol::{
-    InputRingHeader, InputRingEntry, INPUT_CHANNEL_ID,
-    header_ptr, entries_ptr,
+    InputRingEntry, SeqTracker, INPUT_CHANNEL_ID, RING_CAPACITY,
+    header_ptr, entries_ptr, timestamp,
 };
 
-/// Screen dimensions
-const WIDTH: u32 = 1280;
-const HEIGHT: u32 = 720;
+/// Largest mode the photo frame runs at: the screen-sized buffers below
+/// are this big, whatever the display's native mode
+const MAX_MODE: DisplayMode = DisplayMode::FHD_1080P;
+
+/// Smallest mode the info bars' layout fits
+const MIN_MODE: DisplayMode = DisplayMode::HD_720P;
 
 /// GPIO virtual address
 const GPIO_BASE: usize = 0x5_0200_0000;
@@ -78,8 +202,38 @@ const RING_BUFFER_VADDR: usize = 0x5_0400_0000;
 /// Input channel for notifications from Input PD
 const INPUT_CHANNEL: Channel = Channel::new(INPUT_CHANNEL_ID);
 
-/// Slideshow interval in frames (at ~60fps, 300 = 5 seconds)
-const SLIDESHOW_INTERVAL: u32 = 300;
+/// Tick notifications from the Timer PD (must match photoframe.system);
+/// the PD sleeps between ticks
+const TIMER_CHANNEL: Channel = Channel::new(CLIENT_TICK_CHANNEL_ID);
+
+/// Vsync IRQ from the SMI (must match photoframe.system)
+const VSYNC_CHANNEL: Channel = Channel::new(6);
+
+/// Vertical blanks per transition frame (30 Hz at 60 Hz refresh)
+const VSYNC_FRAMES_PER_STEP: u32 = 2;
+
+/// Time between ticks
+const TICK_PERIOD: Duration = Duration::from_micros(1_000_000 / TICK_HZ as u64);
+
+/// Slideshow interval in seconds
+const SLIDESHOW_INTERVAL_S: u32 = 5;
+
+/// Heartbeat to the Watchdog PD (must match photoframe.system)
+#[cfg(feature = "watchdog")]
+const WATCHDOG_CHANNEL: Channel = Channel::new(CLIENT_WATCHDOG_CHANNEL_ID);
+
+/// Health panel lines on the debug overlay, at most
+#[cfg(feature = "watchdog")]
+const HEALTH_LINES: usize = health::HEALTH_LINES;
+#[cfg(not(feature = "watchdog"))]
+const HEALTH_LINES: usize = 0;
+
+/// Seconds between telemetry samples
+const TELEMETRY_INTERVAL_S: u32 = 60;
+
+/// Seconds between soak status lines
+#[cfg(feature = "soak")]
+const SOAK_STATUS_INTERVAL_S: u32 = 60;
 
 // ============================================================================
 // EMBEDDED PHOTO DATA
@@ -88,16 +242,25 @@ const SLIDESHOW_INTERVAL: u32 = 300;
 /// Where a photo's pixels come from.
 #[derive(Clone, Copy)]
 enum PhotoSource {
-    /// Procedurally generated test pattern (no decoding).
-    Generated(fn(u32, u32) -> u32),
-    /// Encoded image bytes (BMP/QOI/PNG/JPEG) run through the secure pipeline.
+    /// Procedurally generated test pattern (no decoding), the colour of
+    /// pixel (x, y) on a `width` x `height` screen.
+    Generated(fn(u32, u32, u32, u32) -> u32),
+    /// Encoded image bytes (BMP/QOI/PNG/GIF/JPEG) run through the secure pipeline.
     Encoded(&'static [u8]),
 }
 
+/// Short on-screen text (photo counter, heap figures)
+type Caption = BoundedString<16>;
+
+/// A line of the debug overlay
+type DebugText = BoundedString<64>;
+
 /// Photo metadata
 struct Photo {
     name: &'static str,
     source: PhotoSource,
+    /// Seconds to show the photo for, instead of the slideshow interval
+    dwell_s: Option<u32>,
 }
 
 // Real encoded images embedded at compile time. These are decoded at runtime
@@ -105,33 +268,137 @@ struct Photo {
 // the exact path a dropped-in JPEG/PNG would take.
 //
 // To add your own: drop a file in `photos/` and `include_bytes!` it below. JPEG
-// and PNG decode through zune-jpeg / zune-png under the bounded heap; BMP and QOI
-// decode allocation-free.
+// decodes through zune-jpeg under the bounded heap; 8-bit RGB/RGBA PNG through
+// the native streaming inflate (window + two scanlines), other PNGs through
+// zune-png; BMP and QOI decode allocation-free. Animated GIFs keep their LZW
+// table on the bounded heap while they play.
 const SAMPLE_QOI: &[u8] = include_bytes!("../photos/sample_gradient.qoi");
 const SAMPLE_BMP: &[u8] = include_bytes!("../photos/sample_gradient.bmp");
+const SAMPLE_PNG: &[u8] = include_bytes!("../photos/sample_gradient.png");
+const SAMPLE_GIF: &[u8] = include_bytes!("../photos/sample_spinner.gif");
 
 /// Decoded-pixel scratch buffer (ARGB32), sized to the display. Encoded photos
-/// are decoded here, then blitted (centered) to the framebuffer. Wrapped in an
+/// are decoded here, then scaled (letterboxed) to the framebuffer; an animated
+/// GIF keeps compositing its frames here while it plays. Wrapped in an
 /// `UnsafeCell` because the PD is single-threaded and the seL4 event loop never
 /// re-enters `render` concurrently.
-struct PixelScratch(UnsafeCell<[u32; (WIDTH * HEIGHT) as usize]>);
+///
+/// A few guard words follow the pixels; soak builds fill them with a pattern
+/// and check it after every frame, to catch writes past the end.
+struct PixelScratch(UnsafeCell<[u32; SCRATCH_PIXELS + SCRATCH_GUARD_WORDS]>);
 unsafe impl Sync for PixelScratch {}
 static PIXEL_SCRATCH: PixelScratch =
-    PixelScratch(UnsafeCell::new([0; (WIDTH * HEIGHT) as usize]));
+    PixelScratch(UnsafeCell::new([0; SCRATCH_PIXELS + SCRATCH_GUARD_WORDS]));
+
+impl PixelScratch {
+    /// The pixel area
+    ///
+    /// # Safety
+    /// No other reference into the buffer may be live.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn pixels(&self) -> &mut [u32] {
+        &mut (&mut *self.0.get())[..SCRATCH_PIXELS]
+    }
+
+    /// The guard words after the pixels
+    ///
+    /// # Safety
+    /// No other reference into the buffer may be live.
+    #[cfg(feature = "soak")]
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn guard(&self) -> &mut [u32] {
+        &mut (&mut *self.0.get())[SCRATCH_PIXELS..]
+    }
+}
 
-/// Test pattern generators for demo photos
-fn pattern_gradient(x: u32, y: u32) -> u32 {
+/// Pixels in the decode scratch buffer
+const SCRATCH_PIXELS: usize = (MAX_MODE.width * MAX_MODE.height) as usize;
+
+/// Memory for one screen-sized compositor layer. The photo is rendered
+/// into the background layer once per photo; the info overlay lives in
+/// the OSD layer over it, so toggling or updating the overlay recomposes
+/// the bars without decoding the photo again. Sized for [`MAX_MODE`], so
+/// 8 MB of BSS each; a smaller mode uses the front of it.
+struct LayerMemory(UnsafeCell<[u32; SCRATCH_PIXELS]>);
+unsafe impl Sync for LayerMemory {}
+static PHOTO_LAYER: LayerMemory = LayerMemory(UnsafeCell::new([0; SCRATCH_PIXELS]));
+static OSD_LAYER: LayerMemory = LayerMemory(UnsafeCell::new([0; SCRATCH_PIXELS]));
+
+/// The outgoing and incoming photo of a slideshow transition, screen-sized
+/// like the layers (another 16 MB of BSS between them)
+static TRANSITION_FROM: LayerMemory = LayerMemory(UnsafeCell::new([0; SCRATCH_PIXELS]));
+static TRANSITION_TO: LayerMemory = LayerMemory(UnsafeCell::new([0; SCRATCH_PIXELS]));
+
+impl LayerMemory {
+    /// A `width` x `height` layer over this memory; `None` past
+    /// [`MAX_MODE`]
+    ///
+    /// # Safety
+    /// Call at most once per buffer: the layer holds the only reference.
+    unsafe fn layer(&'static self, (width, height): (u32, u32)) -> Option<Layer<'static>> {
+        Layer::new(&mut *self.0.get(), width, height)
+    }
+
+    /// The raw pixels, for a buffer that is not a layer
+    ///
+    /// # Safety
+    /// Call at most once per buffer: the slice is the only reference.
+    unsafe fn pixels(&'static self) -> &'static mut [u32] {
+        &mut *self.0.get()
+    }
+}
+
+/// Translucent black behind overlay text (half coverage)
+const OVERLAY_SHADE: u32 = 0x8000_0000;
+
+/// Debug overlay box, darker than the info bars so it reads over anything
+const DEBUG_SHADE: u32 = 0xC000_0000;
+/// Debug overlay text
+const DEBUG_COLOR: u32 = 0xFF80_FF80;
+
+/// Guard words after the decode scratch buffer
+const SCRATCH_GUARD_WORDS: usize = 16;
+
+/// Browse grid: `GRID_COLS` x `GRID_ROWS` cells between the top and bottom
+/// bars, a thumbnail centered in each
+const GRID_TOP: u32 = 48;
+/// Height of the hint bar under the grid, with a margin
+const GRID_BOTTOM: u32 = 38;
+
+/// Browse grid colors: behind the cells, a thumbnail not made yet, one
+/// whose photo failed to decode, and the selection frame
+const GRID_BACKGROUND: u32 = 0xFF10_1010;
+const THUMB_PENDING: u32 = 0xFF30_3030;
+const THUMB_BROKEN: u32 = 0xFF60_2020;
+const GRID_SELECTION: u32 = 0xFFFF_D700;
+
+/// Width of the selection frame around a thumbnail
+const SELECTION_FRAME: u32 = 4;
+
+/// Top left corner of the thumbnail in grid cell (`col`, `row`) on a
+/// `width` x `height` screen
+fn grid_thumb_origin(col: u32, row: u32, (width, height): (u32, u32)) -> (u32, u32) {
+    let cell_w = width / GRID_COLS;
+    let cell_h = (height - GRID_TOP - GRID_BOTTOM) / GRID_ROWS;
+    (
+        col * cell_w + (cell_w - THUMB_W) / 2,
+        GRID_TOP + row * cell_h + (cell_h - THUMB_H) / 2,
+    )
+}
+
+/// Test pattern generators for demo photos, scaled to the screen
+fn pattern_gradient(x: u32, y: u32, width: u32, height: u32) -> u32 {
     // Smooth gradient
-    let r = (x * 255 / WIDTH) as u8;
-    let g = (y * 255 / HEIGHT) as u8;
-    let b = ((x + y) * 128 / (WIDTH + HEIGHT)) as u8;
+    let r = (x * 255 / width) as u8;
+    let g = (y * 255 / height) as u8;
+    let b = ((x + y) * 128 / (width + height)) as u8;
     0xFF000000 | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
 }
 
-fn pattern_circles(x: u32, y: u32) -> u32 {
+fn pattern_circles(x: u32, y: u32, width: u32, height: u32) -> u32 {
     // Concentric circles
-    let cx = WIDTH / 2;
-    let cy = HEIGHT / 2;
+    let cx = width / 2;
+    let cy = height / 2;
     let dx = (x as i32 - cx as i32).unsigned_abs();
     let dy = (y as i32 - cy as i32).unsigned_abs();
     // Integer sqrt: `f32::sqrt` is a std-only method, unavailable in no_std.
@@ -144,7 +411,7 @@ fn pattern_circles(x: u32, y: u32) -> u32 {
     }
 }
 
-fn pattern_checkerboard(x: u32, y: u32) -> u32 {
+fn pattern_checkerboard(x: u32, y: u32, _width: u32, _height: u32) -> u32 {
     // Checkerboard pattern
     let tile_size = 80;
     let tx = (x / tile_size) % 2;
@@ -156,19 +423,19 @@ fn pattern_checkerboard(x: u32, y: u32) -> u32 {
     }
 }
 
-fn pattern_sunset(x: u32, y: u32) -> u32 {
+fn pattern_sunset(x: u32, y: u32, width: u32, height: u32) -> u32 {
     // Sunset gradient
-    let t = y * 255 / HEIGHT;
+    let t = y * 255 / height;
     let r = 255u8;
-    let g = (180 - (t as i32 * 140 / 255).min(180)).max(0) as u8;
-    let b = (100 - (t as i32 * 100 / 255).min(100)).max(0) as u8;
+    let g = (180 - min_u32(t * 140 / 255, 180)) as u8;
+    let b = (100 - min_u32(t * 100 / 255, 100)) as u8;
 
     // Sun
-    let sun_x = WIDTH / 2;
-    let sun_y = HEIGHT / 3;
+    let sun_x = width / 2;
+    let sun_y = height / 3;
     let sun_r = 80u32;
-    let dx = (x as i32 - sun_x as i32).unsigned_abs();
-    let dy = (y as i32 - sun_y as i32).unsigned_abs();
+    let dx = abs_diff_u32(x, sun_x);
+    let dy = abs_diff_u32(y, sun_y);
     if dx * dx + dy * dy < sun_r * sun_r {
         0xFFFFFF00 // Yellow sun
     } else {
@@ -176,43 +443,43 @@ fn pattern_sunset(x: u32, y: u32) -> u32 {
     }
 }
 
-fn pattern_mountains(x: u32, y: u32) -> u32 {
+fn pattern_mountains(x: u32, y: u32, width: u32, height: u32) -> u32 {
     // Simple mountain silhouette
     let sky_color = 0xFF4080C0;
     let mountain_color = 0xFF203020;
     let snow_color = 0xFFE0E0F0;
 
     // Mountain peaks using simple triangle functions
-    let peak1_x = WIDTH / 4;
-    let peak1_h = HEIGHT / 2;
-    let peak2_x = WIDTH * 2 / 3;
-    let peak2_h = HEIGHT * 2 / 5;
+    let peak1_x = width / 4;
+    let peak1_h = height / 2;
+    let peak2_x = width * 2 / 3;
+    let peak2_h = height * 2 / 5;
 
     // Calculate mountain heights at this x
     let m1_height = if x < peak1_x {
         (x * peak1_h / peak1_x) as i32
-    } else if x < WIDTH / 2 {
-        ((WIDTH / 2 - x) * peak1_h / (WIDTH / 4)) as i32
+    } else if x < width / 2 {
+        ((width / 2 - x) * peak1_h / (width / 4)) as i32
     } else {
         0
     };
 
-    let m2_height = if x > WIDTH / 3 && x < WIDTH {
+    let m2_height = if x > width / 3 && x < width {
         let rel_x = if x < peak2_x {
-            (x - WIDTH / 3) * peak2_h / (peak2_x - WIDTH / 3)
+            (x - width / 3) * peak2_h / (peak2_x - width / 3)
         } else {
-            (WIDTH - x) * peak2_h / (WIDTH - peak2_x)
+            (width - x) * peak2_h / (width - peak2_x)
         };
         rel_x as i32
     } else {
         0
     };
 
-    let mountain_line = HEIGHT as i32 - m1_height.max(m2_height);
+    let mountain_line = height as i32 - max_i32(m1_height, m2_height);
 
     if (y as i32) > mountain_line {
         mountain_color
-    } else if (y as i32) > mountain_line - 30 && m1_height > (HEIGHT / 3) as i32 {
+    } else if (y as i32) > mountain_line - 30 && m1_height > (height / 3) as i32 {
         snow_color
     } else {
         sky_color
@@ -222,13 +489,15 @@ fn pattern_mountains(x: u32, y: u32) -> u32 {
 /// Collection of demo photos: a mix of procedural patterns and real encoded
 /// images decoded through the secure pipeline.
 const PHOTOS: &[Photo] = &[
-    Photo { name: "GRADIENT", source: PhotoSource::Generated(pattern_gradient) },
-    Photo { name: "QOI PHOTO", source: PhotoSource::Encoded(SAMPLE_QOI) },
-    Photo { name: "BMP PHOTO", source: PhotoSource::Encoded(SAMPLE_BMP) },
-    Photo { name: "CIRCLES", source: PhotoSource::Generated(pattern_circles) },
-    Photo { name: "CHECKERBOARD", source: PhotoSource::Generated(pattern_checkerboard) },
-    Photo { name: "SUNSET", source: PhotoSource::Generated(pattern_sunset) },
-    Photo { name: "MOUNTAINS", source: PhotoSource::Generated(pattern_mountains) },
+    Photo { name: "GRADIENT", source: PhotoSource::Generated(pattern_gradient), dwell_s: None },
+    Photo { name: "QOI PHOTO", source: PhotoSource::Encoded(SAMPLE_QOI), dwell_s: None },
+    Photo { name: "BMP PHOTO", source: PhotoSource::Encoded(SAMPLE_BMP), dwell_s: None },
+    Photo { name: "PNG PHOTO", source: PhotoSource::Encoded(SAMPLE_PNG), dwell_s: None },
+    Photo { name: "GIF ANIMATION", source: PhotoSource::Encoded(SAMPLE_GIF), dwell_s: Some(10) },
+    Photo { name: "CIRCLES", source: PhotoSource::Generated(pattern_circles), dwell_s: None },
+    Photo { name: "CHECKERBOARD", source: PhotoSource::Generated(pattern_checkerboard), dwell_s: None },
+    Photo { name: "SUNSET", source: PhotoSource::Generated(pattern_sunset), dwell_s: None },
+    Photo { name: "MOUNTAINS", source: PhotoSource::Generated(pattern_mountains), dwell_s: None },
 ];
 
 // ============================================================================
@@ -245,42 +514,110 @@ fn u8_to_key_code(code: u8) -> KeyCode {
         5 => KeyCode::Enter,
         6 => KeyCode::Escape,
         7 => KeyCode::Space,
+        10 => KeyCode::Num0,
+        11 => KeyCode::Num1,
+        12 => KeyCode::Num2,
+        13 => KeyCode::Num3,
+        14 => KeyCode::Num4,
         _ => KeyCode::Unknown,
     }
 }
 
+/// Convert KeyCode back to its ring key code (for `@in` lines)
+#[cfg(feature = "soak")]
+fn key_code_to_u8(key: KeyCode) -> u8 {
+    match key {
+        KeyCode::Up => 1,
+        KeyCode::Down => 2,
+        KeyCode::Left => 3,
+        KeyCode::Right => 4,
+        KeyCode::Enter => 5,
+        KeyCode::Escape => 6,
+        KeyCode::Space => 7,
+        KeyCode::Num0 => 10,
+        KeyCode::Num1 => 11,
+        KeyCode::Num2 => 12,
+        KeyCode::Num3 => 13,
+        KeyCode::Num4 => 14,
+        _ => 0,
+    }
+}
+
+/// Entries pulled out of the input ring per batch
+const INPUT_BATCH: usize = 16;
+
 /// Input reader from shared ring buffer
+///
+/// Entries are drained in batches of up to [`INPUT_BATCH`], so the read
+/// index is published once per batch rather than once per key. Gaps in the
+/// Input PD's sequence numbers (events it dropped on a full ring) are
+/// reported as they are found.
 struct RingBufferInput {
     ring_base: *mut u8,
+    batch: [InputRingEntry; INPUT_BATCH],
+    batch_len: usize,
+    batch_pos: usize,
+    seq: SeqTracker,
+    /// Longest an event has waited between the Input PD decoding it and
+    /// this PD reading it, in microseconds
+    worst_latency_us: u64,
 }
 
 impl RingBufferInput {
     const fn new() -> Self {
         Self {
             ring_base: RING_BUFFER_VADDR as *mut u8,
+            batch: [InputRingEntry::EMPTY; INPUT_BATCH],
+            batch_len: 0,
+            batch_pos: 0,
+            seq: SeqTracker::new(),
+            worst_latency_us: 0,
         }
     }
 
-    fn poll(&mut self) -> Option<(KeyCode, KeyState)> {
-        unsafe {
-            let header = &*header_ptr(self.ring_base);
-
-            if !header.has_data() {
-                return None;
+    /// Next key event, press or release; other events are skipped
+    fn poll(&mut self) -> Option<InputRingEntry> {
+        loop {
+            if self.batch_pos == self.batch_len {
+                self.batch_len = unsafe {
+                    let header = &*header_ptr(self.ring_base);
+                    record_mem(&INPUT_RING_GAUGE, (RING_CAPACITY - 1 - header.free_slots()) as usize);
+                    header.pop_batch(entries_ptr(self.ring_base), &mut self.batch)
+                };
+                self.batch_pos = 0;
+                if self.batch_len == 0 {
+                    return None;
+                }
             }
+            let entry = self.batch[self.batch_pos];
+            self.batch_pos += 1;
+
+            let lost = self.seq.observe(&entry);
+            if lost > 0 {
+                // Safety: ring_base is the mapped input ring
+                let dropped = unsafe { (*header_ptr(self.ring_base)).dropped() };
+                debug_println!("Input: {} events lost before #{} ({} dropped so far)", lost, entry.seq, dropped);
+            }
+            self.note_latency(&entry);
 
-            let read_idx = header.current_read_idx();
-            let entries = entries_ptr(self.ring_base);
-            let entry = entries.add(read_idx as usize).read_volatile();
-
-            core::sync::atomic::fence(Ordering::Acquire);
-            header.advance_read();
+            if entry.event_type == 1 {
+                return Some(entry);
+            }
+        }
+    }
 
-            if entry.event_type == 1 && entry.key_state == 1 {
-                let key_code = u8_to_key_code(entry.key_code);
-                Some((key_code, KeyState::Pressed))
-            } else {
-                None
+    /// Compare the entry's timestamp with the system timer and report a
+    /// new worst latency; unstamped entries are skipped
+    fn note_latency(&mut self, entry: &InputRingEntry) {
+        let Some(stamp) = entry.timestamp() else {
+            return;
+        };
+        // Safety: SYSTIMER_VIRT_BASE is this PD's system timer mapping
+        let now = unsafe { timestamp::now(SYSTIMER_VIRT_BASE) };
+        if let Some(latency) = now.checked_since(stamp) {
+            if latency > self.worst_latency_us {
+                self.worst_latency_us = latency;
+                debug_println!("Input: #{} waited {} us in the ring (worst so far)", entry.seq, latency);
             }
         }
     }
@@ -290,116 +627,53 @@ impl RingBufferInput {
 // DRAWING HELPERS
 // ============================================================================
 
-/// Draw a filled rectangle
+/// Draw a filled rectangle, clipped to a `width` x `height` screen
+#[cfg(feature = "profile")]
 #[inline]
-unsafe fn fill_rect(fb: *mut u32, pitch: usize, x: usize, y: usize, w: usize, h: usize, color: u32) {
+#[allow(clippy::too_many_arguments)]
+unsafe fn fill_rect(fb: *mut u32, pitch: usize, (width, height): (usize, usize), x: usize, y: usize, w: usize, h: usize, color: u32) {
     for dy in 0..h {
         for dx in 0..w {
             let px = x + dx;
             let py = y + dy;
-            if px < WIDTH as usize && py < HEIGHT as usize {
+            if px < width && py < height {
                 fb.add(py * pitch + px).write_volatile(color);
             }
         }
     }
 }
 
-/// 8x8 bitmap font for status display
-const FONT_8X8: [[u8; 8]; 64] = [
-    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // Space (32)
-    [0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x18, 0x00], // !
-    [0x6C, 0x6C, 0x24, 0x00, 0x00, 0x00, 0x00, 0x00], // "
-    [0x6C, 0xFE, 0x6C, 0x6C, 0xFE, 0x6C, 0x00, 0x00], // #
-    [0x18, 0x7E, 0x58, 0x7C, 0x1A, 0x7E, 0x18, 0x00], // $
-    [0x62, 0x64, 0x08, 0x10, 0x26, 0x46, 0x00, 0x00], // %
-    [0x38, 0x6C, 0x38, 0x76, 0xDC, 0xCC, 0x76, 0x00], // &
-    [0x18, 0x18, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00], // '
-    [0x0C, 0x18, 0x30, 0x30, 0x30, 0x18, 0x0C, 0x00], // (
-    [0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x18, 0x30, 0x00], // )
-    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00], // *
-    [0x00, 0x18, 0x18, 0x7E, 0x18, 0x18, 0x00, 0x00], // +
-    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x30], // ,
-    [0x00, 0x00, 0x00, 0x7E, 0x00, 0x00, 0x00, 0x00], // -
-    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00], // .
-    [0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x00], // /
-    [0x7C, 0xC6, 0xCE, 0xD6, 0xE6, 0xC6, 0x7C, 0x00], // 0
-    [0x18, 0x38, 0x18, 0x18, 0x18, 0x18, 0x7E, 0x00], // 1
-    [0x7C, 0xC6, 0x06, 0x1C, 0x70, 0xC0, 0xFE, 0x00], // 2
-    [0x7C, 0xC6, 0x06, 0x3C, 0x06, 0xC6, 0x7C, 0x00], // 3
-    [0x1C, 0x3C, 0x6C, 0xCC, 0xFE, 0x0C, 0x1E, 0x00], // 4
-    [0xFE, 0xC0, 0xFC, 0x06, 0x06, 0xC6, 0x7C, 0x00], // 5
-    [0x38, 0x60, 0xC0, 0xFC, 0xC6, 0xC6, 0x7C, 0x00], // 6
-    [0xFE, 0xC6, 0x0C, 0x18, 0x30, 0x30, 0x30, 0x00], // 7
-    [0x7C, 0xC6, 0xC6, 0x7C, 0xC6, 0xC6, 0x7C, 0x00], // 8
-    [0x7C, 0xC6, 0xC6, 0x7E, 0x06, 0x0C, 0x78, 0x00], // 9
-    [0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x00], // :
-    [0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x30], // ;
-    [0x0C, 0x18, 0x30, 0x60, 0x30, 0x18, 0x0C, 0x00], // <
-    [0x00, 0x00, 0x7E, 0x00, 0x7E, 0x00, 0x00, 0x00], // =
-    [0x30, 0x18, 0x0C, 0x06, 0x0C, 0x18, 0x30, 0x00], // >
-    [0x7C, 0xC6, 0x0C, 0x18, 0x18, 0x00, 0x18, 0x00], // ?
-    [0x7C, 0xC6, 0xDE, 0xDE, 0xDE, 0xC0, 0x7C, 0x00], // @
-    [0x38, 0x6C, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0x00], // A
-    [0xFC, 0xC6, 0xC6, 0xFC, 0xC6, 0xC6, 0xFC, 0x00], // B
-    [0x7C, 0xC6, 0xC0, 0xC0, 0xC0, 0xC6, 0x7C, 0x00], // C
-    [0xF8, 0xCC, 0xC6, 0xC6, 0xC6, 0xCC, 0xF8, 0x00], // D
-    [0xFE, 0xC0, 0xC0, 0xF8, 0xC0, 0xC0, 0xFE, 0x00], // E
-    [0xFE, 0xC0, 0xC0, 0xF8, 0xC0, 0xC0, 0xC0, 0x00], // F
-    [0x7C, 0xC6, 0xC0, 0xCE, 0xC6, 0xC6, 0x7E, 0x00], // G
-    [0xC6, 0xC6, 0xC6, 0xFE, 0xC6, 0xC6, 0xC6, 0x00], // H
-    [0x7E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x7E, 0x00], // I
-    [0x1E, 0x06, 0x06, 0x06, 0xC6, 0xC6, 0x7C, 0x00], // J
-    [0xC6, 0xCC, 0xD8, 0xF0, 0xD8, 0xCC, 0xC6, 0x00], // K
-    [0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xC0, 0xFE, 0x00], // L
-    [0xC6, 0xEE, 0xFE, 0xD6, 0xC6, 0xC6, 0xC6, 0x00], // M
-    [0xC6, 0xE6, 0xF6, 0xDE, 0xCE, 0xC6, 0xC6, 0x00], // N
-    [0x7C, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0x00], // O
-    [0xFC, 0xC6, 0xC6, 0xFC, 0xC0, 0xC0, 0xC0, 0x00], // P
-    [0x7C, 0xC6, 0xC6, 0xC6, 0xD6, 0xDE, 0x7C, 0x06], // Q
-    [0xFC, 0xC6, 0xC6, 0xFC, 0xD8, 0xCC, 0xC6, 0x00], // R
-    [0x7C, 0xC6, 0xC0, 0x7C, 0x06, 0xC6, 0x7C, 0x00], // S
-    [0xFF, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x00], // T
-    [0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0xC6, 0x7C, 0x00], // U
-    [0xC6, 0xC6, 0xC6, 0xC6, 0x6C, 0x38, 0x10, 0x00], // V
-    [0xC6, 0xC6, 0xC6, 0xD6, 0xFE, 0xEE, 0xC6, 0x00], // W
-    [0xC6, 0xC6, 0x6C, 0x38, 0x6C, 0xC6, 0xC6, 0x00], // X
-    [0xC3, 0xC3, 0x66, 0x3C, 0x18, 0x18, 0x18, 0x00], // Y
-    [0xFE, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFE, 0x00], // Z
-    [0x3C, 0x30, 0x30, 0x30, 0x30, 0x30, 0x3C, 0x00], // [
-    [0x80, 0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x00], // \
-    [0x3C, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x3C, 0x00], // ]
-    [0x10, 0x38, 0x6C, 0xC6, 0x00, 0x00, 0x00, 0x00], // ^
-    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF], // _
-];
+/// A buffer the photo is drawn into: the photo layer, or one of the
+/// transition buffers, at the screen's size
+type PhotoScreen<'a> = Layer<'a>;
 
-/// Draw a character at position (x, y) with scale
-unsafe fn draw_char(fb: *mut u32, pitch: usize, x: usize, y: usize, ch: char, scale: usize, color: u32) {
-    let idx = if ch >= ' ' && ch <= '_' {
-        (ch as usize) - 32
-    } else if ch >= 'a' && ch <= 'z' {
-        (ch as usize) - 'a' as usize + 33 // Map to uppercase
-    } else {
-        0 // Space for unknown
-    };
+/// The front of `pixels` as a `width` x `height` [`PhotoScreen`]. Every
+/// photo buffer is `SCRATCH_PIXELS` long, which holds [`MAX_MODE`], so this
+/// only fails on a bug.
+fn photo_screen(pixels: &mut [u32], (width, height): (u32, u32)) -> Option<PhotoScreen<'_>> {
+    Layer::new(pixels, width, height)
+}
 
-    let glyph = &FONT_8X8[idx];
-    for row in 0..8 {
-        for col in 0..8 {
-            if (glyph[row] >> (7 - col)) & 1 != 0 {
-                fill_rect(fb, pitch, x + col * scale, y + row * scale, scale, scale, color);
-            }
-        }
-    }
+/// Fill the screen with the letterbox background
+fn clear_photo(screen: &mut PhotoScreen<'_>) {
+    profile_scope!("clear");
+    screen.fill(0xFF101018);
 }
 
-/// Draw text string at position
-unsafe fn draw_text(fb: *mut u32, pitch: usize, x: usize, y: usize, text: &str, scale: usize, color: u32) {
-    let char_width = 8 * scale + scale; // 8 pixels + 1 spacing
-    for (i, ch) in text.chars().enumerate() {
-        draw_char(fb, pitch, x + i * char_width, y, ch, scale, color);
+/// Fill the screen with a generated pattern
+fn fill_pattern(screen: &mut PhotoScreen<'_>, gen: fn(u32, u32, u32, u32) -> u32) {
+    let (width, height) = screen.dimensions();
+    for (i, pixel) in screen.pixels_mut().iter_mut().enumerate() {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        *pixel = gen(x, y, width, height);
     }
 }
 
+/// Draw `text` in `font` with its line box at (x, y); returns its width
+fn draw_text(target: &mut impl TextTarget, x: usize, y: usize, text: &str, font: &BitmapFont, color: u32) -> usize {
+    font.draw_text(target, x as i32, y as i32, text, Color::from_argb(color)) as usize
+}
+
 // ============================================================================
 // PHOTO FRAME STATE
 // ============================================================================
@@ -413,11 +687,44 @@ enum PhotoStatus {
     Decoded {
         format: validate::ImageType,
         heap_peak_kb: u32,
+        /// Frames in the image (1 for stills)
+        frames: u16,
     },
     /// The secure pipeline rejected or failed to decode the image.
     Failed(&'static str),
 }
 
+/// Soak test state: the replayed session and the invariant checks
+#[cfg(feature = "soak")]
+struct SoakState {
+    replay: soak::Replay,
+    checker: soak::Checker,
+    /// Tick of the last key from the Input PD, for `@in` lines
+    last_key_tick: u32,
+    /// Guard words past the drawn part of the framebuffer (empty when the
+    /// buffer has no room past it)
+    fb_guard: *mut u32,
+    fb_guard_len: usize,
+}
+
+/// An animated GIF being played on the photo layer
+struct Animation {
+    /// Draws the next frame into the scratch buffer; holds the LZW table on
+    /// the decoder heap, so it is dropped before the heap is reset
+    decoder: GifDecoder<'static>,
+    /// When the next frame is due; it is drawn on the first tick at or
+    /// after it
+    due: Instant,
+}
+
+/// Screen-sized buffers a transition mixes
+struct TransitionBuffers {
+    /// The photo on screen when the photo changed
+    from: &'static mut [u32],
+    /// The photo changed to
+    to: &'static mut [u32],
+}
+
 /// Photoframe application state
 enum AppMode {
     Slideshow,
@@ -426,12 +733,92 @@ enum AppMode {
 
 struct PhotoFrameHandler {
     framebuffer: Option<Framebuffer>,
+    /// Photo and overlay layers; `None` until init has built them
+    layers: Option<Compositor<'static>>,
+    /// Display mode the framebuffer settled on: the display's native mode,
+    /// or the rung of the fallback ladder it fell back to
+    display_mode: Option<ModeSelection>,
+    /// Width and height everything is laid out at, the framebuffer's
+    screen: (u32, u32),
     input: RingBufferInput,
+    /// Play order and per-photo settings
+    playlist: Playlist,
+    /// The playlist's current photo
     current_photo: usize,
     mode: AppMode,
+    /// Ticks handled since start
     frame_counter: u32,
-    slideshow_timer: u32,
+    /// When the slideshow moves on; `None` starts the photo's dwell afresh
+    /// on the next tick
+    next_slide: Option<Instant>,
+    /// When the last tick was handled
+    last_tick: Option<Instant>,
+    /// Ticks that came and went while this PD was busy
+    missed_ticks: u32,
+    /// Frames drawn to the framebuffer since start
+    frames_drawn: u32,
+    /// Health samples for reliability studies; `None` until init attaches it
+    telemetry: Option<TelemetryLog>,
+    /// Thumbnails for the browse grid; `None` until init attaches the ring
+    thumbs: Option<ThumbnailRing>,
+    /// The photo selected in the browse grid, while it is open
+    browse: Option<u32>,
     show_info: bool,
+    /// Outcome of drawing the current photo, for the overlay
+    photo_status: PhotoStatus,
+    /// What the decode handed over for the current photo: its size and the
+    /// EXIF orientation, capture time and camera model
+    photo_header: PixelBufferHeader,
+    /// The current photo's remaining frames, when it is animated
+    animation: Option<Animation>,
+    /// `None` until init has claimed them
+    transition_buffers: Option<TransitionBuffers>,
+    /// Transition to the current photo, while it plays
+    transition: Option<Transition>,
+    /// Vblank pacing for transitions; `None` steps them on the tick
+    clock: Option<FrameClock>,
+    /// A paced vblank frame came since the last tick
+    vblank_since_tick: bool,
+    /// Photo changes so far, to cycle through the effects
+    photo_changes: u32,
+    /// The last change stepped back through the photos
+    backwards: bool,
+    /// Where the clock is drawn; `None` hides it
+    clock_corner: Option<Corner>,
+    /// When the screen is dimmed
+    night: NightMode,
+    /// Date and minute the clock last showed, `None` while the time is
+    /// unset
+    clock_shown: Option<(CivilDate, u32)>,
+    /// Weather report the panel last showed, `None` while there is none
+    weather_shown: Option<WeatherReport>,
+    /// Draw the developer readouts over everything else
+    show_debug: bool,
+    /// Watches for the key chord that toggles them
+    chord: Chord,
+    fps: FpsMeter,
+    /// Ring code of the last key pressed, and the tick it came on
+    last_key: Option<(u8, u32)>,
+    /// Tick the Input PD last notified on
+    input_seen: Option<u32>,
+    /// Draw the profiling screen over the photo
+    #[cfg(feature = "profile")]
+    show_profile: bool,
+    /// Photos on the SD card; `None` without a card or Storage PD
+    #[cfg(feature = "sdcard")]
+    sd_card: Option<SdCard>,
+    /// Index and name of the SD card photo last loaded
+    #[cfg(feature = "sdcard")]
+    sd_loaded: Option<(usize, Caption)>,
+    #[cfg(feature = "soak")]
+    soak: SoakState,
+    /// When the Watchdog PD is next pinged
+    #[cfg(feature = "watchdog")]
+    next_ping: Instant,
+    /// The photo layer must be redrawn (implies `needs_redraw`)
+    needs_photo: bool,
+    /// The animation's next frame is due (implies `needs_redraw`)
+    needs_frame: bool,
     needs_redraw: bool,
 }
 
@@ -439,35 +826,203 @@ impl PhotoFrameHandler {
     fn new() -> Self {
         Self {
             framebuffer: None,
+            layers: None,
+            display_mode: None,
+            screen: (MIN_MODE.width, MIN_MODE.height),
             input: RingBufferInput::new(),
+            playlist: Playlist::new(PHOTOS.len(), 0),
             current_photo: 0,
             mode: AppMode::Slideshow,
             frame_counter: 0,
-            slideshow_timer: 0,
+            next_slide: None,
+            last_tick: None,
+            missed_ticks: 0,
+            frames_drawn: 0,
+            telemetry: None,
+            thumbs: None,
+            browse: None,
             show_info: true,
+            photo_status: PhotoStatus::Generated,
+            photo_header: PixelBufferHeader::empty(),
+            animation: None,
+            transition_buffers: None,
+            transition: None,
+            clock: None,
+            vblank_since_tick: false,
+            photo_changes: 0,
+            backwards: false,
+            clock_corner: Some(Corner::TopRight),
+            night: NightMode::DEFAULT,
+            clock_shown: None,
+            weather_shown: None,
+            show_debug: false,
+            chord: Chord::new(),
+            fps: FpsMeter::new(),
+            last_key: None,
+            input_seen: None,
+            #[cfg(feature = "profile")]
+            show_profile: false,
+            #[cfg(feature = "sdcard")]
+            sd_card: None,
+            #[cfg(feature = "sdcard")]
+            sd_loaded: None,
+            #[cfg(feature = "watchdog")]
+            next_ping: Instant::BOOT,
+            #[cfg(feature = "soak")]
+            soak: SoakState {
+                replay: soak::Replay::new(soak::SOAK_SCRIPT),
+                checker: soak::Checker::new(),
+                last_key_tick: 0,
+                fb_guard: core::ptr::null_mut(),
+                fb_guard_len: 0,
+            },
+            needs_photo: true,
+            needs_frame: false,
             needs_redraw: true,
         }
     }
 
+    /// Photos in the slideshow: the embedded ones, then any on the SD card
+    fn photo_count(&self) -> usize {
+        #[cfg(feature = "sdcard")]
+        if let Some(card) = &self.sd_card {
+            return PHOTOS.len() + card.photo_count();
+        }
+        PHOTOS.len()
+    }
+
+    /// Name of the current photo
+    fn photo_name(&self) -> &str {
+        self.name_of(self.current_photo)
+    }
+
+    /// Name of photo `index`; an SD card photo is only named once it has
+    /// been loaded
+    fn name_of(&self, index: usize) -> &str {
+        if let Some(photo) = PHOTOS.get(index) {
+            return photo.name;
+        }
+        #[cfg(feature = "sdcard")]
+        if let Some((loaded, name)) = &self.sd_loaded {
+            if *loaded == index {
+                return name;
+            }
+        }
+        "SD CARD"
+    }
+
+    /// Start the playlist over with every photo, once the SD card's have
+    /// been counted
+    fn load_playlist(&mut self, seed: u32) {
+        self.playlist = Playlist::new(self.photo_count(), seed);
+        for (index, photo) in PHOTOS.iter().enumerate() {
+            if let Some(seconds) = photo.dwell_s {
+                self.playlist.set_dwell(index, Some(seconds));
+            }
+        }
+        self.current_photo = self.playlist.current();
+    }
+
     fn next_photo(&mut self) {
-        self.current_photo = (self.current_photo + 1) % PHOTOS.len();
+        self.current_photo = self.playlist.next();
+        self.backwards = false;
+        self.needs_photo = true;
         self.needs_redraw = true;
-        self.slideshow_timer = 0;
-        debug_println!("Photo {}/{}: {}", self.current_photo + 1, PHOTOS.len(), PHOTOS[self.current_photo].name);
+        self.next_slide = None;
+        #[cfg(feature = "audio")]
+        audio::chime();
+        debug_println!("Photo {}/{}: {}", self.current_photo + 1, self.photo_count(), self.photo_name());
     }
 
     fn prev_photo(&mut self) {
-        if self.current_photo == 0 {
-            self.current_photo = PHOTOS.len() - 1;
+        self.current_photo = self.playlist.prev();
+        self.backwards = true;
+        self.needs_photo = true;
+        self.needs_redraw = true;
+        self.next_slide = None;
+        #[cfg(feature = "audio")]
+        audio::chime();
+        debug_println!("Photo {}/{}: {}", self.current_photo + 1, self.photo_count(), self.photo_name());
+    }
+
+    /// Apply a playlist command
+    ///
+    /// Pause, resume and jumps still come straight from the keys; this PD
+    /// has no command ring to receive them on yet.
+    fn handle_command(&mut self, cmd: PhotoCommand) {
+        match cmd.command {
+            CMD_NEXT => self.next_photo(),
+            CMD_PREV => self.prev_photo(),
+            CMD_SHUFFLE => match Order::from_wire(cmd.flags) {
+                Some(order) => {
+                    self.playlist.set_order(order);
+                    debug_println!("Playlist: {}", order.name());
+                    self.needs_redraw = true;
+                }
+                None => debug_println!("Photoframe PD: unknown playlist order {}", cmd.flags),
+            },
+            CMD_FAVORITE => {
+                let photo = cmd.photo_index as usize;
+                let favorite = self.playlist.toggle_favorite(photo);
+                debug_println!("Photo {}: favorite {}", photo + 1, if favorite { "on" } else { "off" });
+                self.needs_redraw = true;
+            }
+            CMD_BROWSE => self.set_browse(cmd.flags == BROWSE_OPEN),
+            CMD_SELECT => {
+                let photo = cmd.photo_index as usize;
+                if self.playlist.goto(photo) {
+                    self.current_photo = photo;
+                    self.backwards = false;
+                    self.next_slide = None;
+                    self.set_browse(false);
+                    debug_println!("Photo {}/{}: {}", photo + 1, self.photo_count(), self.photo_name());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Open or close the browse grid
+    ///
+    /// Thumbnails are decoded on the heap an animation lives on, so opening
+    /// the grid stops the current photo's; closing it draws the photo
+    /// again, through a transition from the grid.
+    fn set_browse(&mut self, open: bool) {
+        if open == self.browse.is_some() {
+            return;
+        }
+        if open {
+            self.animation = None;
+            self.transition = None;
+            self.browse = Some(self.current_photo as u32);
         } else {
-            self.current_photo -= 1;
+            self.browse = None;
+            self.needs_photo = true;
         }
         self.needs_redraw = true;
-        self.slideshow_timer = 0;
-        debug_println!("Photo {}/{}: {}", self.current_photo + 1, PHOTOS.len(), PHOTOS[self.current_photo].name);
+    }
+
+    /// Keys while the browse grid is open: the arrows move the selection,
+    /// Enter shows the selected photo, Escape or 3 closes the grid
+    fn browse_input(&mut self, selected: u32, key: KeyCode) {
+        let dir = match key {
+            KeyCode::Left => GridMove::Left,
+            KeyCode::Right => GridMove::Right,
+            KeyCode::Up => GridMove::Up,
+            KeyCode::Down => GridMove::Down,
+            KeyCode::Enter => return self.handle_command(PhotoCommand::select(selected as u16)),
+            KeyCode::Escape | KeyCode::Num3 => return self.handle_command(PhotoCommand::browse(false)),
+            _ => return,
+        };
+        self.browse = Some(grid_move(selected, self.playlist.len() as u32, dir));
+        self.needs_redraw = true;
     }
 
     fn handle_input(&mut self, key: KeyCode, _state: KeyState) {
+        if let Some(selected) = self.browse {
+            self.browse_input(selected, key);
+            return;
+        }
         match key {
             KeyCode::Right | KeyCode::Down => {
                 self.next_photo();
@@ -484,7 +1039,7 @@ impl PhotoFrameHandler {
                     }
                     AppMode::Paused => {
                         self.mode = AppMode::Slideshow;
-                        self.slideshow_timer = 0;
+                        self.next_slide = None;
                         debug_println!("Slideshow resumed");
                     }
                 }
@@ -495,202 +1050,918 @@ impl PhotoFrameHandler {
                 self.show_info = !self.show_info;
                 self.needs_redraw = true;
             }
+            KeyCode::Num1 => {
+                let order = self.playlist.order().cycle();
+                self.handle_command(PhotoCommand::shuffle(order.to_wire()));
+            }
+            KeyCode::Num2 => {
+                self.handle_command(PhotoCommand::favorite(self.current_photo as u16));
+            }
+            KeyCode::Num3 => {
+                self.handle_command(PhotoCommand::browse(true));
+            }
+            KeyCode::Num4 => {
+                self.clock_corner = Corner::cycle(self.clock_corner);
+                debug_println!("Clock: {}", self.clock_corner.map_or("hidden", Corner::name));
+                self.needs_redraw = true;
+            }
+            #[cfg(feature = "profile")]
+            KeyCode::Num0 => {
+                self.show_profile = !self.show_profile;
+                self.needs_redraw = true;
+            }
             KeyCode::Escape => {
                 // Return to first photo
+                self.playlist.goto(0);
                 self.current_photo = 0;
+                self.backwards = true;
                 self.mode = AppMode::Slideshow;
-                self.slideshow_timer = 0;
+                self.next_slide = None;
+                self.needs_photo = true;
                 self.needs_redraw = true;
             }
             _ => {}
         }
     }
 
+    /// The system timer
+    fn now(&self) -> Instant {
+        // Safety: SYSTIMER_VIRT_BASE is this PD's system timer mapping
+        unsafe { Instant::now(SYSTIMER_VIRT_BASE) }
+    }
+
     fn update(&mut self) {
         self.frame_counter = self.frame_counter.wrapping_add(1);
+        let now = self.now();
+
+        // A gap of more than one period means ticks were skipped
+        if let Some(skipped) = self
+            .last_tick
+            .and_then(|last| now.saturating_duration_since(last).periods(TICK_PERIOD))
+        {
+            let skipped = u32::try_from(skipped.saturating_sub(1)).unwrap_or(u32::MAX);
+            self.missed_ticks = self.missed_ticks.saturating_add(skipped);
+        }
+        self.last_tick = Some(now);
+
+        // Ping from the tick, so a PD stuck in a decode or a draw stops
+        // pinging and the Watchdog PD notices
+        #[cfg(feature = "watchdog")]
+        if now.has_reached(self.next_ping) {
+            WATCHDOG_CHANNEL.notify();
+            self.next_ping = now.saturating_add(Duration::from_millis(PING_INTERVAL_MS));
+        }
 
-        // Handle slideshow timing
-        if matches!(self.mode, AppMode::Slideshow) {
-            self.slideshow_timer += 1;
-            if self.slideshow_timer >= SLIDESHOW_INTERVAL {
+        // Handle slideshow timing; it holds while the grid is open
+        if matches!(self.mode, AppMode::Slideshow) && self.browse.is_none() {
+            let dwell = Duration::from_secs(self.playlist.dwell(self.current_photo, SLIDESHOW_INTERVAL_S));
+            let due = *self.next_slide.get_or_insert(now.saturating_add(dwell));
+            if now.has_reached(due) {
                 self.next_photo();
             }
         }
+
+        // A transition draws a frame every tick, unless vblanks are pacing
+        // it; if they stop coming, the tick takes over
+        let paced = core::mem::take(&mut self.vblank_since_tick);
+        if self.transition.is_some() && !paced {
+            self.needs_redraw = true;
+        }
+
+        // The grid makes one thumbnail a tick until its page is complete
+        if let Some(selected) = self.browse {
+            if self.missing_thumbnail(selected).is_some() {
+                self.needs_redraw = true;
+            }
+        }
+
+        // Animations keep playing while the slideshow is paused
+        if self.animation.as_ref().is_some_and(|animation| now.has_reached(animation.due)) {
+            self.needs_frame = true;
+            self.needs_redraw = true;
+        }
+
+        // The clock, and the night shade with it, changes once a minute
+        let shown = self.local_time().map(|t| (t.date, t.minute_of_day));
+        if shown != self.clock_shown {
+            self.clock_shown = shown;
+            self.needs_redraw = true;
+        }
+
+        // The Network PD republishes the weather whenever it fetches it
+        let weather = self.weather();
+        if weather != self.weather_shown {
+            self.weather_shown = weather;
+            self.needs_redraw = true;
+        }
+
+        // The debug readouts are refreshed once a second
+        self.fps.sample(self.frame_counter, self.frames_drawn, TICK_HZ);
+        if self.show_debug && self.frame_counter.is_multiple_of(TICK_HZ) {
+            self.needs_redraw = true;
+        }
+
+        if self.frame_counter.is_multiple_of(TELEMETRY_INTERVAL_S * TICK_HZ) {
+            self.sample_telemetry();
+            print_mem_stats();
+        }
     }
 
-    fn render(&mut self) {
-        let fb = match &self.framebuffer {
-            Some(fb) => fb,
-            None => return,
-        };
+    /// The local time from the Timer PD's clock page; `None` until it has
+    /// been set
+    fn local_time(&self) -> Option<LocalTime> {
+        let now_us = self.now().as_micros();
+        // Safety: photoframe.system maps the clock page here, read-only
+        let time = unsafe { ClockPage::read(CLOCK_PAGE_VADDR as *const u8) }?;
+        time.local_at(now_us)
+    }
+
+    /// The current weather from the Network PD's weather page; `None`
+    /// until a valid report has been published
+    fn weather(&self) -> Option<WeatherReport> {
+        // Safety: photoframe.system maps the weather page here, read-only
+        weather::shown(unsafe { WeatherPage::read(WEATHER_PAGE_VADDR as *const u8) })
+    }
 
-        if !self.needs_redraw {
+    /// Append a health sample to the telemetry log and echo it on the
+    /// console, where `serial-debug telemetry export` picks it up
+    fn sample_telemetry(&mut self) {
+        let Some(log) = self.telemetry.as_mut() else {
             return;
+        };
+        let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+        // A failed query is logged as an impossible reading rather than
+        // dropping the sample: the gap itself is worth seeing
+        let temp_milli_c = mailbox.get_temperature().map_or(i32::MIN, |t| t as i32);
+        let throttled = mailbox.get_throttled().unwrap_or(u32::MAX);
+        let record = TelemetryRecord {
+            seq: 0,
+            uptime_s: self.frame_counter / TICK_HZ,
+            temp_milli_c,
+            throttled,
+            frames: self.frames_drawn,
+            missed_ticks: self.missed_ticks,
+            photo_index: self.current_photo as u32,
+            _reserved: 0,
+        };
+        let seq = log.append(record);
+        print_telemetry(&TelemetryRecord { seq, ..record });
+        record_mem(&TELEMETRY_GAUGE, log.retained().len());
+    }
+
+    /// Fill the guard words past the scratch buffer and past the drawn part
+    /// of the framebuffer
+    #[cfg(feature = "soak")]
+    fn arm_soak(&mut self) {
+        unsafe { soak::arm_guards(PIXEL_SCRATCH.guard()) };
+
+        // Nothing draws below the screen's last row, so whatever the
+        // firmware allocated past it is free for sentinels
+        if let Some(fb) = &self.framebuffer {
+            let drawn = fb.pitch_pixels() * self.screen.1 as usize;
+            let total = fb.info().size as usize / 4;
+            self.soak.fb_guard_len = total.saturating_sub(drawn).min(soak::FB_GUARD_WORDS);
+            self.soak.fb_guard = unsafe { fb.buffer_ptr().add(drawn) };
+            unsafe { soak::arm_guards(self.fb_guard()) };
+        }
+    }
+
+    /// The framebuffer guard words
+    #[cfg(feature = "soak")]
+    unsafe fn fb_guard(&self) -> &'static mut [u32] {
+        if self.soak.fb_guard_len == 0 {
+            return &mut [];
         }
+        core::slice::from_raw_parts_mut(self.soak.fb_guard, self.soak.fb_guard_len)
+    }
+
+    /// Echo a key from the Input PD as an `@in` line, the format a
+    /// replayed session is written in
+    #[cfg(feature = "soak")]
+    fn echo_key(&mut self, key: KeyCode) {
+        let dt = self.frame_counter.wrapping_sub(self.soak.last_key_tick);
+        self.soak.last_key_tick = self.frame_counter;
+        debug_println!("@in dt={} key={}", dt, key_code_to_u8(key));
+    }
 
-        let ptr = fb.buffer_ptr();
-        let pitch = fb.pitch_pixels();
+    /// Send the replayed key due on this tick, and print a status line
+    /// every `SOAK_STATUS_INTERVAL_S`
+    #[cfg(feature = "soak")]
+    fn soak_tick(&mut self) {
+        if let Some(code) = self.soak.replay.tick() {
+            self.handle_input(u8_to_key_code(code), KeyState::Pressed);
+        }
+        if self.frame_counter.is_multiple_of(SOAK_STATUS_INTERVAL_S * TICK_HZ) {
+            let checker = &self.soak.checker;
+            debug_println!(
+                "@soak t={} loops={} checks={} frames={} violations={} heap_free_min_kb={}",
+                self.frame_counter / TICK_HZ, self.soak.replay.loops(), checker.frames(),
+                self.frames_drawn, checker.total(),
+                checker.heap_free_min().map_or(0, |free| free / 1024)
+            );
+        }
+    }
+
+    /// Check the runtime invariants after a frame and log violations
+    #[cfg(feature = "soak")]
+    fn check_invariants(&mut self) {
+        use soak::Violation;
+
+        self.soak.checker.begin_frame();
+
+        // Safety: RING_BUFFER_VADDR is the mapped input ring
+        let ring = unsafe { &*header_ptr(RING_BUFFER_VADDR as *mut u8) };
+        if !ring.is_consistent() {
+            self.report_violation(Violation::InputRing, ring.current_write_idx());
+        }
+        if self.telemetry.as_ref().is_some_and(|log| !log.is_valid()) {
+            self.report_violation(Violation::TelemetryLog, 0);
+        }
+        if let Some((_, word)) = soak::check_guards(unsafe { PIXEL_SCRATCH.guard() }) {
+            self.report_violation(Violation::ScratchGuard, word);
+        }
+        if let Some((_, word)) = soak::check_guards(unsafe { self.fb_guard() }) {
+            self.report_violation(Violation::FramebufferGuard, word);
+        }
+        let heap = soak::HeapReading {
+            used: DECODER_HEAP.used(),
+            peak: DECODER_HEAP.peak(),
+            capacity: DECODER_HEAP.capacity(),
+            oom: DECODER_HEAP.oom_occurred(),
+        };
+        if let Some(violation) = self.soak.checker.check_heap(heap) {
+            self.report_violation(violation, heap.peak as u32);
+        }
+    }
+
+    /// Count a violation and log it (rate-limited by the checker)
+    #[cfg(feature = "soak")]
+    fn report_violation(&mut self, violation: soak::Violation, value: u32) {
+        let checker = &mut self.soak.checker;
+        if checker.record(violation) {
+            debug_println!(
+                "@soak violation={} t={} check={} count={} value=0x{:x}",
+                violation.name(), self.frame_counter / TICK_HZ, checker.frames(),
+                checker.count(violation), value
+            );
+        }
+    }
+
+    fn render(&mut self) {
+        if self.framebuffer.is_none() || !self.needs_redraw {
+            return;
+        }
+        let Some(mut layers) = self.layers.take() else {
+            return;
+        };
 
         unsafe {
             core::arch::asm!("dsb sy");
+            if let Some(selected) = self.browse {
+                // The photo waits for the grid to close
+                if let Some(index) = self.missing_thumbnail(selected) {
+                    self.make_thumbnail(index);
+                }
+                self.draw_grid(layers.background(), selected);
+            } else if self.needs_photo {
+                let photo = layers.background().pixels_mut();
+                self.photo_status = self.change_photo(photo);
+                self.needs_photo = false;
+                // A photo the pipeline refused is refused every time
+                if let PhotoStatus::Failed(_) = self.photo_status {
+                    self.playlist.set_skip(self.current_photo, true);
+                }
+            } else if self.transition.is_some() {
+                // GIF frames wait until the transition has landed on the
+                // first one
+                self.step_transition(layers.background().pixels_mut());
+            } else if self.needs_frame {
+                if let Some(mut photo) = photo_screen(layers.background().pixels_mut(), self.screen) {
+                    self.draw_next_frame(&mut photo);
+                }
+            }
+            self.needs_frame = false;
+        }
+        self.draw_overlay(layers.osd());
+
+        if let Some(fb) = self.framebuffer.as_mut() {
+            // The profiling screen is drawn straight onto the framebuffer,
+            // so the layers have to cover all of it again every frame
+            #[cfg(feature = "profile")]
+            layers.invalidate();
+            {
+                profile_scope!("present");
+                layers.present(fb);
+            }
 
-            let photo = &PHOTOS[self.current_photo];
-
-            // Draw the photo. Generated patterns fill the screen directly;
-            // encoded images are run through the secure decode pipeline into the
-            // scratch buffer and then blitted centered.
-            let photo_status = match photo.source {
-                PhotoSource::Generated(gen) => {
-                    for y in 0..HEIGHT {
-                        for x in 0..WIDTH {
-                            let color = gen(x, y);
-                            ptr.add(y as usize * pitch + x as usize).write_volatile(color);
-                        }
+            // Latch this frame's scope totals before drawing them, so the
+            // profiling screen itself is not part of the figures
+            #[cfg(feature = "profile")]
+            {
+                profile::SCOPES.end_frame();
+                if self.show_profile {
+                    let (width, height) = fb.dimensions();
+                    unsafe {
+                        draw_profile_screen(
+                            fb.buffer_ptr(),
+                            fb.pitch_pixels(),
+                            (width as usize, height as usize),
+                            &profile::SCOPES.top::<PROFILE_ROWS>(),
+                        );
                     }
-                    PhotoStatus::Generated
                 }
-                PhotoSource::Encoded(bytes) => {
-                    // Fill the background first so margins around a smaller,
-                    // centered image are clean rather than stale pixels.
-                    for y in 0..HEIGHT as usize {
-                        for x in 0..WIDTH as usize {
-                            ptr.add(y * pitch + x).write_volatile(0xFF101018);
-                        }
-                    }
+            }
+        }
+        self.layers = Some(layers);
+
+        unsafe {
+            core::arch::asm!("dsb sy");
+            core::arch::asm!("isb");
+        }
+
+        self.frames_drawn = self.frames_drawn.wrapping_add(1);
+        self.needs_redraw = false;
+    }
+
+    /// Draw the current photo onto the photo layer, through a transition
+    /// from whatever is on screen (once something has been)
+    unsafe fn change_photo(&mut self, photo: &mut [u32]) -> PhotoStatus {
+        let mut buffers = match self.transition_buffers.take() {
+            Some(buffers) if self.frames_drawn > 0 => buffers,
+            unused => {
+                self.transition_buffers = unused;
+                self.transition = None;
+                return match photo_screen(photo, self.screen) {
+                    Some(mut screen) => self.draw_photo(&mut screen),
+                    None => PhotoStatus::Failed("PHOTO BUFFER TOO SMALL"),
+                };
+            }
+        };
+
+        // Mid-transition, the outgoing photo is the mix on screen
+        buffers.from[..photo.len()].copy_from_slice(photo);
+        let status = match photo_screen(&mut buffers.to[..], self.screen) {
+            Some(mut screen) => self.draw_photo(&mut screen),
+            None => PhotoStatus::Failed("PHOTO BUFFER TOO SMALL"),
+        };
+        self.transition_buffers = Some(buffers);
+
+        let effect = effect_for(self.photo_changes, self.backwards);
+        self.photo_changes = self.photo_changes.wrapping_add(1);
+        debug_println!("Photoframe PD: transition {}", effect_name(effect));
+        self.transition = Some(Transition::new(effect, self.transition_frames()));
+        self.step_transition(photo);
+        status
+    }
+
+    /// Frames a transition lasts at the rate it is stepped
+    fn transition_frames(&self) -> u32 {
+        match &self.clock {
+            Some(clock) => frames_at(clock.frame_hz(REFRESH_HZ)),
+            None => TRANSITION_FRAMES,
+        }
+    }
 
-                    // SECURE PIPELINE: validate -> budget -> bounded decode.
-                    let scratch = &mut *PIXEL_SCRATCH.0.get();
-                    match secure_decode_into(bytes, scratch, &DECODER_HEAP) {
-                        Ok(res) => {
-                            blit_centered(ptr, pitch, scratch, res.width, res.height);
-                            debug_println!(
-                                "Photoframe PD: decoded {} {}x{} heap_peak={}KB",
-                                image_type_str(res.format),
-                                res.width,
-                                res.height,
-                                res.heap_peak / 1024
-                            );
-                            PhotoStatus::Decoded {
-                                format: res.format,
-                                heap_peak_kb: (res.heap_peak / 1024) as u32,
-                            }
+    /// A paced vertical blank: the transition's next frame is due
+    fn vblank_frame(&mut self) {
+        self.vblank_since_tick = true;
+        if self.transition.is_some() {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Draw the transition's next frame onto the photo layer
+    fn step_transition(&mut self, photo: &mut [u32]) {
+        profile_scope!("transition");
+        let (Some(transition), Some(buffers)) = (self.transition.as_mut(), self.transition_buffers.as_ref()) else {
+            return;
+        };
+        let (width, height) = self.screen;
+        match transition.step(&buffers.from[..], &buffers.to[..], photo, width, height) {
+            Ok(true) => {}
+            Ok(false) => self.transition = None,
+            Err(e) => {
+                // Cut straight to the new photo
+                debug_println!("Photoframe PD: transition refused: {:?}", e);
+                photo.copy_from_slice(&buffers.to[..photo.len()]);
+                self.transition = None;
+            }
+        }
+    }
+
+    /// Draw the current photo onto `screen`
+    unsafe fn draw_photo(&mut self, screen: &mut PhotoScreen<'_>) -> PhotoStatus {
+        profile_scope!("render");
+
+        // The previous photo's animation lives on the heap the decode below
+        // resets
+        self.animation = None;
+        self.photo_header = PixelBufferHeader::empty();
+        let Some(source) = self.photo_source(self.current_photo) else {
+            clear_photo(screen);
+            return PhotoStatus::Failed("SD CARD READ FAILED");
+        };
+
+        // Generated patterns fill the screen directly; encoded images are
+        // run through the secure decode pipeline into the scratch buffer and
+        // then scaled to fit the screen.
+        match source {
+            PhotoSource::Generated(gen) => {
+                profile_scope!("pattern");
+                fill_pattern(screen, gen);
+                PhotoStatus::Generated
+            }
+            PhotoSource::Encoded(bytes) => {
+                // Fill the background first so the letterbox margins are
+                // clean rather than stale pixels.
+                clear_photo(screen);
+
+                // EXIF first: it only reads the bytes, through the
+                // verified offset helpers, and never fails the photo
+                let exif = {
+                    profile_scope!("exif");
+                    exif::read_exif(bytes)
+                };
+
+                // SECURE PIPELINE: validate -> budget -> bounded decode.
+                let scratch = PIXEL_SCRATCH.pixels();
+                let decoded = {
+                    profile_scope!("decode");
+                    secure_decode_animated(bytes, scratch, &DECODER_HEAP)
+                };
+                match decoded {
+                    Ok((res, animation)) => {
+                        record_mem(&SCRATCH_GAUGE, res.width as usize * res.height as usize * 4);
+                        let header =
+                            PixelBufferHeader::new(res.width, res.height, PIXEL_FORMAT_RGBA32, self.current_photo as u16);
+                        self.photo_header = exif.stamp(header);
+                        let orientation = Orientation::from_exif(self.photo_header.orientation as u16);
+                        let blit = {
+                            profile_scope!("blit");
+                            blit_letterboxed(screen, scratch, res.width, res.height, orientation)
+                        };
+                        if let Err(e) = blit {
+                            debug_println!("Photoframe PD: blit refused: {:?}", e);
                         }
-                        Err(e) => {
-                            let reason = secure_error_str(&e);
-                            debug_println!("Photoframe PD: secure decode rejected: {}", reason);
-                            PhotoStatus::Failed(reason)
+                        debug_println!(
+                            "Photoframe PD: decoded {} {}x{} heap_peak={}KB",
+                            image_type_str(res.format),
+                            res.width,
+                            res.height,
+                            res.heap_peak / 1024
+                        );
+                        let due = self.now().saturating_add(Duration::from_millis(res.frame_delay_ms));
+                        self.animation = animation.map(|decoder| Animation { decoder, due });
+                        PhotoStatus::Decoded {
+                            format: res.format,
+                            heap_peak_kb: (res.heap_peak / 1024) as u32,
+                            frames: res.frame_count,
                         }
                     }
+                    Err(e) => {
+                        let reason = secure_error_str(&e);
+                        debug_println!("Photoframe PD: secure decode rejected: {}", reason);
+                        PhotoStatus::Failed(reason)
+                    }
                 }
-            };
+            }
+        }
+    }
 
-            // Draw info overlay if enabled
-            if self.show_info {
-                // Semi-transparent black bar at top
-                let bar_height = 40usize;
-                for y in 0..bar_height {
-                    for x in 0..WIDTH as usize {
-                        let bg = ptr.add(y * pitch + x).read_volatile();
-                        // Darken by 50%
-                        let r = ((bg >> 16) & 0xFF) / 2;
-                        let g = ((bg >> 8) & 0xFF) / 2;
-                        let b = (bg & 0xFF) / 2;
-                        ptr.add(y * pitch + x).write_volatile(0xFF000000 | (r << 16) | (g << 8) | b);
-                    }
+    /// Where photo `index` comes from; an SD card photo is copied into the
+    /// file buffer first
+    fn photo_source(&mut self, index: usize) -> Option<PhotoSource> {
+        match PHOTOS.get(index) {
+            Some(photo) => Some(photo.source),
+            None => self.load_sd_photo(index).map(PhotoSource::Encoded),
+        }
+    }
+
+    /// Copy photo `photo` off the SD card into the file buffer
+    #[cfg(feature = "sdcard")]
+    fn load_sd_photo(&mut self, photo: usize) -> Option<&'static [u8]> {
+        let card = self.sd_card.as_ref()?;
+        let index = (photo - PHOTOS.len()) as u32;
+        match card.load(index) {
+            Ok(loaded) => {
+                let name = Caption::format(format_args!("{}", loaded.name)).unwrap_or_default();
+                debug_println!("Photoframe PD: loaded {} from SD card ({} bytes)", name, loaded.bytes.len());
+                self.sd_loaded = Some((photo, name));
+                Some(loaded.bytes)
+            }
+            Err(e) => {
+                debug_println!("Photoframe PD: SD card photo {}: {}", index, e);
+                None
+            }
+        }
+    }
+
+    /// Without the Storage PD every photo is embedded
+    #[cfg(not(feature = "sdcard"))]
+    fn load_sd_photo(&mut self, _photo: usize) -> Option<&'static [u8]> {
+        None
+    }
+
+    /// First photo on the selected photo's grid page without a thumbnail
+    fn missing_thumbnail(&self, selected: u32) -> Option<u32> {
+        let ring = self.thumbs.as_ref()?;
+        let start = grid_page_start(selected);
+        let end = (start + GRID_PAGE).min(self.playlist.len() as u32);
+        (start..end).find(|&index| ring.find(index as u16).is_none())
+    }
+
+    /// Make photo `index`'s thumbnail in the next ring slot
+    ///
+    /// Patterns are sampled at thumbnail size; encoded photos go through
+    /// the secure pipeline like any other decode and are scaled down. A
+    /// photo that fails gets a failed slot, so the grid shows it broken
+    /// rather than decoding it again every tick.
+    unsafe fn make_thumbnail(&mut self, index: u32) {
+        profile_scope!("thumbnail");
+        let source = self.photo_source(index as usize);
+        let Some(ring) = self.thumbs.as_mut() else {
+            return;
+        };
+        let (slot, pixels) = ring.begin(index as u16);
+        pixels.fill(GRID_BACKGROUND);
+        let (screen_w, screen_h) = self.screen;
+        let (width, height) = match source {
+            Some(PhotoSource::Generated(gen)) => {
+                for (i, pixel) in pixels.iter_mut().enumerate() {
+                    let (x, y) = (i as u32 % THUMB_W, i as u32 / THUMB_W);
+                    *pixel = gen(x * screen_w / THUMB_W, y * screen_h / THUMB_H, screen_w, screen_h);
                 }
+                (THUMB_W, THUMB_H)
+            }
+            Some(PhotoSource::Encoded(bytes)) => decode_thumbnail(bytes, pixels).unwrap_or((0, 0)),
+            None => (0, 0),
+        };
+        ring.publish(slot, index as u16, width, height);
+        record_mem(&THUMB_GAUGE, ring.written().min(THUMB_SLOTS) as usize);
+    }
 
-                // Draw photo name
-                draw_text(ptr, pitch, 20, 12, photo.name, 2, 0xFFFFFFFF);
+    /// Draw the selected photo's grid page on the photo layer: the
+    /// thumbnails made so far and placeholders for the rest
+    fn draw_grid(&self, photo: &mut Layer, selected: u32) {
+        profile_scope!("grid");
+        photo.fill(GRID_BACKGROUND);
+        let (width, height) = photo.dimensions();
+        let start = grid_page_start(selected);
+        let end = (start + GRID_PAGE).min(self.playlist.len() as u32);
+        for index in start..end {
+            let (col, row) = grid_cell(index);
+            let (x, y) = grid_thumb_origin(col, row, (width, height));
+            let thumb = self.thumbs.as_ref().and_then(|ring| Some((ring, ring.find(index as u16)?)));
+            match thumb {
+                Some((ring, (slot, header))) if header.status == THUMB_READY => {
+                    // 1:1 through the verified blit, which checks the cell
+                    // is on the layer
+                    let rect = BlitRect { x, y, w: THUMB_W, h: THUMB_H };
+                    let blit = blit_scaled(
+                        ring.pixels(slot),
+                        THUMB_W,
+                        THUMB_H,
+                        Orientation::Normal,
+                        photo.pixels_mut(),
+                        width,
+                        height,
+                        rect,
+                        ScaleFilter::Nearest,
+                    );
+                    if let Err(e) = blit {
+                        debug_println!("Photoframe PD: thumbnail blit refused: {:?}", e);
+                    }
+                }
+                Some(_) => photo.fill_rect(x, y, THUMB_W, THUMB_H, THUMB_BROKEN),
+                None => photo.fill_rect(x, y, THUMB_W, THUMB_H, THUMB_PENDING),
+            }
+        }
+    }
 
-                // Draw photo counter
-                let mut counter_buf = [0u8; 8];
-                let counter_str = format_counter(self.current_photo + 1, PHOTOS.len(), &mut counter_buf);
-                draw_text(ptr, pitch, WIDTH as usize - 120, 12, counter_str, 2, 0xFFFFFFFF);
+    /// Draw the animation's next frame onto `screen`, which already holds
+    /// the previous frame
+    ///
+    /// A frame that fails to decode stops the animation on the last good
+    /// frame.
+    unsafe fn draw_next_frame(&mut self, screen: &mut PhotoScreen<'_>) {
+        profile_scope!("frame");
+        let now = self.now();
+        let Some(animation) = self.animation.as_mut() else {
+            return;
+        };
 
-                // Draw status indicator
-                let status = match self.mode {
-                    AppMode::Slideshow => ">",
-                    AppMode::Paused => "||",
+        let scratch = PIXEL_SCRATCH.pixels();
+        let frame = {
+            profile_scope!("decode");
+            animation.decoder.next_frame(scratch)
+        };
+        match frame {
+            Ok(frame) => {
+                animation.due = now.saturating_add(Duration::from_millis(frame.delay_ms));
+                let (width, height) = (animation.decoder.width(), animation.decoder.height());
+                let orientation = Orientation::from_exif(self.photo_header.orientation as u16);
+                let blit = {
+                    profile_scope!("blit");
+                    blit_letterboxed(screen, scratch, width, height, orientation)
                 };
-                draw_text(ptr, pitch, WIDTH as usize / 2 - 20, 12, status, 2, 0xFF00FF00);
-
-                // Draw controls hint at bottom
-                let hint_y = HEIGHT as usize - 30;
-                // Darken bottom bar
-                for y in hint_y..HEIGHT as usize {
-                    for x in 0..WIDTH as usize {
-                        let bg = ptr.add(y * pitch + x).read_volatile();
-                        let r = ((bg >> 16) & 0xFF) / 2;
-                        let g = ((bg >> 8) & 0xFF) / 2;
-                        let b = (bg & 0xFF) / 2;
-                        ptr.add(y * pitch + x).write_volatile(0xFF000000 | (r << 16) | (g << 8) | b);
-                    }
+                if let Err(e) = blit {
+                    debug_println!("Photoframe PD: blit refused: {:?}", e);
                 }
-                draw_text(ptr, pitch, 20, hint_y + 5, "ARROWS:NAV  SPACE:PAUSE  ENTER:INFO", 1, 0xFFCCCCCC);
-
-                // SEL4 SECURE badge
-                draw_text(ptr, pitch, WIDTH as usize - 180, hint_y + 5, "SEL4 SECURE", 1, 0xFF00B050);
-
-                // Secure-decode status line for encoded photos (just above the
-                // bottom hint bar). Tells the on-screen story: which decoder ran
-                // and how much of the bounded heap it touched.
-                let status_y = hint_y - 22;
-                match photo_status {
-                    PhotoStatus::Decoded { format, heap_peak_kb } => {
-                        let mut num = [0u8; 12];
-                        let kb = format_u32(heap_peak_kb, &mut num);
-                        draw_text(ptr, pitch, 20, status_y, "SECURE DECODE:", 1, 0xFF80FF80);
-                        draw_text(ptr, pitch, 180, status_y, image_type_str(format), 1, 0xFFFFFFFF);
-                        draw_text(ptr, pitch, 260, status_y, "OK  HEAP PEAK", 1, 0xFFCCCCCC);
-                        draw_text(ptr, pitch, 420, status_y, kb, 1, 0xFFFFFFFF);
-                        draw_text(ptr, pitch, 480, status_y, "KB", 1, 0xFFCCCCCC);
-                    }
-                    PhotoStatus::Failed(reason) => {
-                        draw_text(ptr, pitch, 20, status_y, "SECURE DECODE: REJECTED", 1, 0xFFFF6060);
-                        draw_text(ptr, pitch, 320, status_y, reason, 1, 0xFFFF6060);
-                    }
-                    PhotoStatus::Generated => {
-                        draw_text(ptr, pitch, 20, status_y, "PROCEDURAL PATTERN", 1, 0xFF8080FF);
-                    }
+            }
+            Err(e) => {
+                debug_println!("Photoframe PD: GIF frame failed, animation stopped: {:?}", e);
+                self.animation = None;
+            }
+        }
+    }
+
+    /// Redraw the info overlay on the OSD layer; the photo under it is left
+    /// alone
+    fn draw_overlay(&self, osd: &mut Layer) {
+        profile_scope!("overlay");
+        osd.clear();
+        let now = self.local_time();
+        let night = now.is_some_and(|t| self.night.is_night(&t));
+        if night {
+            osd.fill(self.night.shade());
+        }
+        if let Some(selected) = self.browse {
+            self.draw_grid_overlay(osd, selected);
+        } else {
+            self.draw_info(osd, now, night);
+        }
+        if self.show_debug {
+            self.draw_debug(osd);
+        }
+    }
+
+    /// The clock and, unless they are hidden, the info bars
+    fn draw_info(&self, osd: &mut Layer, now: Option<LocalTime>, night: bool) {
+        if let (Some(corner), Some(now)) = (self.clock_corner, now) {
+            self.draw_clock(osd, corner, &now, night);
+        }
+        if let Some(report) = self.weather_shown {
+            self.draw_weather(osd, &report, night);
+        }
+        if !self.show_info {
+            return;
+        }
+        let (width, height) = osd.dimensions();
+        // Half-transparent bar at top
+        osd.fill_rect(0, 0, width, 40, OVERLAY_SHADE);
+
+        // Draw photo name
+        let name_w = draw_text(osd, 20, 12, self.photo_name(), &SANS_16, 0xFFFFFFFF);
+        if self.playlist.is_favorite(self.current_photo) {
+            draw_text(osd, 20 + name_w, 12, "  FAV", &SANS_16, 0xFFFFD700);
+        }
+
+        // Draw photo counter, right-aligned
+        let counter = Caption::format(format_args!("{}/{}", self.current_photo + 1, self.photo_count()))
+            .unwrap_or_default();
+        let counter_x = width as usize - 20 - SANS_16.text_width(&counter) as usize;
+        draw_text(osd, counter_x, 12, &counter, &SANS_16, 0xFFFFFFFF);
+
+        // Draw status indicator, centered
+        let status = match self.mode {
+            AppMode::Slideshow => ">",
+            AppMode::Paused => "||",
+        };
+        let status_x = (width - SANS_16.text_width(status)) as usize / 2;
+        draw_text(osd, status_x, 12, status, &SANS_16, 0xFF00FF00);
+
+        // Draw controls hint at bottom
+        let hint_y = height as usize - 30;
+        // Half-transparent bar at bottom
+        osd.fill_rect(0, hint_y as u32, width, height - hint_y as u32, OVERLAY_SHADE);
+        draw_text(osd, 20, hint_y + 5, "ARROWS:NAV  SPACE:PAUSE  ENTER:INFO  1:ORDER  2:FAV  3:GRID  4:CLOCK", &PIXEL_8, 0xFFCCCCCC);
+
+        // Playlist order
+        draw_text(osd, 540, hint_y + 5, self.playlist.order().name(), &PIXEL_8, 0xFF80C0FF);
+
+        // Active display mode (yellow when the preferred mode was refused)
+        if let Some(selection) = self.display_mode {
+            let mut label_buf = [0u8; 40];
+            let label = selection.mode.label(&mut label_buf);
+            let color = if selection.is_fallback() { 0xFFFFFF00 } else { 0xFFCCCCCC };
+            draw_text(osd, 760, hint_y + 5, label, &PIXEL_8, color);
+        }
+
+        // SEL4 SECURE badge
+        let badge_x = width as usize - 20 - PIXEL_8.text_width("SEL4 SECURE") as usize;
+        draw_text(osd, badge_x, hint_y + 5, "SEL4 SECURE", &PIXEL_8, 0xFF00B050);
+
+        // Secure-decode status line for encoded photos (just above the
+        // bottom hint bar). Tells the on-screen story: which decoder ran
+        // and how much of the bounded heap it touched.
+        let status_y = hint_y - 22;
+        match self.photo_status {
+            PhotoStatus::Decoded { format, heap_peak_kb, frames } => {
+                let kb = Caption::format(format_args!("{}", heap_peak_kb)).unwrap_or_default();
+                // Runs of text laid out one after another
+                let mut x = 20;
+                for (text, color) in [
+                    ("SECURE DECODE: ", 0xFF80FF80),
+                    (image_type_str(format), 0xFFFFFFFF),
+                    (" OK  HEAP PEAK ", 0xFFCCCCCC),
+                    (kb.as_str(), 0xFFFFFFFF),
+                    (" KB", 0xFFCCCCCC),
+                ] {
+                    x += draw_text(osd, x, status_y, text, &PIXEL_8, color);
+                }
+                if frames > 1 {
+                    let count = Caption::format(format_args!("  {} FRAMES", frames)).unwrap_or_default();
+                    draw_text(osd, x, status_y, &count, &PIXEL_8, 0xFFCCCCCC);
                 }
             }
+            PhotoStatus::Failed(reason) => {
+                let x = 20 + draw_text(osd, 20, status_y, "SECURE DECODE: REJECTED  ", &PIXEL_8, 0xFFFF6060);
+                draw_text(osd, x, status_y, reason, &PIXEL_8, 0xFFFF6060);
+            }
+            PhotoStatus::Generated => {
+                draw_text(osd, 20, status_y, "PROCEDURAL PATTERN", &PIXEL_8, 0xFF8080FF);
+            }
+        }
 
-            core::arch::asm!("dsb sy");
-            core::arch::asm!("isb");
+        // EXIF camera model and capture time, on the line above
+        let exif = ExifInfo::from_header(&self.photo_header);
+        let mut x = 20;
+        if !exif.model().is_empty() {
+            x += draw_text(osd, x, status_y - 14, exif.model(), &PIXEL_8, 0xFFCCCCCC) + 16;
         }
+        if let Some(t) = exif.taken {
+            let taken = Caption::format(format_args!(
+                "{:04}-{:02}-{:02} {:02}:{:02}",
+                t.year, t.month, t.day, t.hour, t.minute
+            ))
+            .unwrap_or_default();
+            draw_text(osd, x, status_y - 14, &taken, &PIXEL_8, 0xFFCCCCCC);
+        }
+    }
 
-        self.needs_redraw = false;
+    /// Readings for the debug overlay
+    fn debug_stats(&self) -> DebugStats {
+        let seconds_since = |tick: u32| self.frame_counter.wrapping_sub(tick) / TICK_HZ;
+        DebugStats {
+            fps_tenths: self.fps.tenths(),
+            frames: self.frames_drawn,
+            missed_ticks: self.missed_ticks,
+            last_key: self.last_key.map(|(code, tick)| (code, seconds_since(tick))),
+            input_pd_s: self.input_seen.map(seconds_since),
+            // Safety: photoframe.system maps the clock page here, read-only
+            timer_pd_up: unsafe { ClockPage::read(CLOCK_PAGE_VADDR as *const u8) }.is_some(),
+        }
     }
-}
 
-/// Format photo counter as "N/M"
-fn format_counter(current: usize, total: usize, buf: &mut [u8; 8]) -> &str {
-    let c = (current % 10) as u8 + b'0';
-    let t = (total % 10) as u8 + b'0';
-    buf[0] = c;
-    buf[1] = b'/';
-    buf[2] = t;
-    // Safety: we know these are valid ASCII
-    unsafe { core::str::from_utf8_unchecked(&buf[0..3]) }
-}
+    /// What the Watchdog PD last published
+    #[cfg(feature = "watchdog")]
+    fn liveness(&self) -> Option<Liveness> {
+        // Safety: photoframe.system maps the liveness page here, read-only
+        unsafe { LivenessPage::read(LIVENESS_PAGE_VADDR as *const u8) }
+    }
 
-/// Format a u32 as decimal into `buf`, returning the written slice as &str.
-fn format_u32(mut val: u32, buf: &mut [u8; 12]) -> &str {
-    if val == 0 {
-        buf[0] = b'0';
-        return unsafe { core::str::from_utf8_unchecked(&buf[0..1]) };
+    /// The developer readouts, the health panel, then the memory gauges, in
+    /// a box under the top bar
+    fn draw_debug(&self, osd: &mut Layer) {
+        let stats = self.debug_stats();
+        let mem = mem_readings();
+        #[cfg(feature = "watchdog")]
+        let (liveness, now_us) = (self.liveness(), self.now().as_micros());
+        #[cfg(feature = "watchdog")]
+        let (health, health_text) = (health_rows(liveness.as_ref()), |n| {
+            health_line(liveness.as_ref(), n, now_us).map(|line| DebugText::format(format_args!("{}", line)))
+        });
+        #[cfg(not(feature = "watchdog"))]
+        let (health, health_text) = (0, |_| None);
+        let all: [DebugText; DEBUG_LINES + HEALTH_LINES + MEM_GAUGES] = core::array::from_fn(|n| {
+            let text = if n < DEBUG_LINES {
+                stats.line(n).map(|line| DebugText::format(format_args!("{}", line)))
+            } else if n < DEBUG_LINES + health {
+                health_text(n - DEBUG_LINES)
+            } else {
+                mem.get(n - DEBUG_LINES - health).map(|r| DebugText::format(format_args!("{}", MemText(r))))
+            };
+            text.and_then(Result::ok).unwrap_or_default()
+        });
+        let lines = &all[..DEBUG_LINES + health + MEM_GAUGES];
+        let pad = 8;
+        let line_h = 14;
+        let w = lines.iter().map(|line| PIXEL_8.text_width(line)).max().unwrap_or(0) + 2 * pad;
+        let h = lines.len() as u32 * line_h + 2 * pad;
+        let (x, y) = (CLOCK_MARGIN, 40 + CLOCK_MARGIN);
+        osd.fill_rect(x, y, w, h, DEBUG_SHADE);
+        for (n, line) in lines.iter().enumerate() {
+            let line_y = y + pad + n as u32 * line_h;
+            draw_text(osd, (x + pad) as usize, line_y as usize, line, &PIXEL_8, DEBUG_COLOR);
+        }
+    }
+
+    /// The time, and the date under it, in `corner`, clear of the info
+    /// bars while they are shown
+    fn draw_clock(&self, osd: &mut Layer, corner: Corner, now: &LocalTime, night: bool) {
+        let time = Caption::format(format_args!("{}", TimeText(now))).unwrap_or_default();
+        let date = Caption::format(format_args!("{}", DateText(now))).unwrap_or_default();
+        let pad = 8;
+        let w = SANS_24.text_width(&time).max(SANS_16.text_width(&date)) + 2 * pad;
+        let h = SANS_24.line_height + SANS_16.line_height + 2 * pad;
+        // The top bar, and at the bottom the hint bar with the status and
+        // EXIF lines above it
+        let (top, bottom) = if self.show_info { (40, 30 + 36) } else { (0, 0) };
+        let (x, y) = clock_origin(corner, osd.dimensions(), (w, h), top, bottom);
+
+        let color = if night { NIGHT_COLOR } else { DAY_COLOR };
+        // By night the whole screen is already shaded
+        if !night {
+            osd.fill_rect(x, y, w, h, OVERLAY_SHADE);
+        }
+        let (x, y) = ((x + pad) as usize, (y + pad) as usize);
+        draw_text(osd, x, y, &time, &SANS_24, color);
+        draw_text(osd, x, y + SANS_24.line_height as usize, &date, &SANS_16, color);
     }
-    // Write digits back-to-front, then shift to the front.
-    let mut tmp = [0u8; 12];
-    let mut i = 12;
-    while val > 0 {
-        i -= 1;
-        tmp[i] = b'0' + (val % 10) as u8;
-        val /= 10;
+
+    /// The temperature, and the conditions under it, in the top corner
+    /// across from the clock
+    fn draw_weather(&self, osd: &mut Layer, report: &WeatherReport, night: bool) {
+        let temp = Caption::format(format_args!("{}", TempText(report))).unwrap_or_default();
+        let condition = report.condition_name();
+        let pad = 8;
+        let w = SANS_24.text_width(&temp).max(SANS_16.text_width(condition)) + 2 * pad;
+        let h = SANS_24.line_height + SANS_16.line_height + 2 * pad;
+        let top = if self.show_info { 40 } else { 0 };
+        let (x, y) = clock_origin(weather_corner(self.clock_corner), osd.dimensions(), (w, h), top, 0);
+
+        let color = if night { NIGHT_COLOR } else { DAY_COLOR };
+        if !night {
+            osd.fill_rect(x, y, w, h, OVERLAY_SHADE);
+        }
+        let (x, y) = ((x + pad) as usize, (y + pad) as usize);
+        draw_text(osd, x, y, &temp, &SANS_24, color);
+        draw_text(osd, x, y + SANS_24.line_height as usize, condition, &SANS_16, color);
+    }
+
+    /// The browse grid's overlay: the selected photo's name and page, and
+    /// a frame around its thumbnail
+    fn draw_grid_overlay(&self, osd: &mut Layer, selected: u32) {
+        let (width, height) = osd.dimensions();
+        osd.fill_rect(0, 0, width, 40, OVERLAY_SHADE);
+        let name_w = draw_text(osd, 20, 12, self.name_of(selected as usize), &SANS_16, 0xFFFFFFFF);
+        if self.playlist.is_favorite(selected as usize) {
+            draw_text(osd, 20 + name_w, 12, "  FAV", &SANS_16, 0xFFFFD700);
+        }
+
+        let pages = (self.playlist.len() as u32).div_ceil(GRID_PAGE).max(1);
+        let page = Caption::format(format_args!("PAGE {}/{}", selected / GRID_PAGE + 1, pages)).unwrap_or_default();
+        let page_x = width as usize - 20 - SANS_16.text_width(&page) as usize;
+        draw_text(osd, page_x, 12, &page, &SANS_16, 0xFFFFFFFF);
+
+        let (col, row) = grid_cell(selected);
+        let (x, y) = grid_thumb_origin(col, row, (width, height));
+        let (frame_x, frame_w) = (x - SELECTION_FRAME, THUMB_W + 2 * SELECTION_FRAME);
+        osd.fill_rect(frame_x, y - SELECTION_FRAME, frame_w, SELECTION_FRAME, GRID_SELECTION);
+        osd.fill_rect(frame_x, y + THUMB_H, frame_w, SELECTION_FRAME, GRID_SELECTION);
+        osd.fill_rect(frame_x, y, SELECTION_FRAME, THUMB_H, GRID_SELECTION);
+        osd.fill_rect(x + THUMB_W, y, SELECTION_FRAME, THUMB_H, GRID_SELECTION);
+
+        let hint_y = height - 30;
+        osd.fill_rect(0, hint_y, width, height - hint_y, OVERLAY_SHADE);
+        draw_text(osd, 20, hint_y as usize + 5, "ARROWS:SELECT  ENTER:SHOW  ESC/3:CLOSE", &PIXEL_8, 0xFFCCCCCC);
+    }
+}
+
+/// Scopes listed on the profiling screen
+#[cfg(feature = "profile")]
+const PROFILE_ROWS: usize = 8;
+
+/// Draw the profiling screen: the most expensive scopes of the frame just
+/// rendered, with cycle counts, call counts and a bar relative to the top one.
+#[cfg(feature = "profile")]
+unsafe fn draw_profile_screen(fb: *mut u32, pitch: usize, screen: (usize, usize), top: &[Option<ScopeSample>]) {
+    const PANEL_W: usize = 440;
+    const ROW_H: usize = 20;
+    const BAR_W: usize = 120;
+    let x0 = screen.0 - PANEL_W - 20;
+    let y0 = 60;
+    let panel_h = (top.len() + 1) * ROW_H + 16;
+
+    fill_rect(fb, pitch, screen, x0, y0, PANEL_W, panel_h, 0xFF000000);
+    let mut target = RawTarget::new(fb, pitch, screen.0, screen.1);
+    draw_text(&mut target, x0 + 8, y0 + 8, "SCOPE", &PIXEL_8, 0xFF80FF80);
+    draw_text(&mut target, x0 + 150, y0 + 8, "KCYCLES", &PIXEL_8, 0xFF80FF80);
+    draw_text(&mut target, x0 + 240, y0 + 8, "CALLS", &PIXEL_8, 0xFF80FF80);
+
+    let max = top.first().copied().flatten().map_or(1, |s| s.cycles.max(1));
+    for (row, sample) in top.iter().enumerate() {
+        let Some(sample) = sample else { break };
+        let y = y0 + 8 + (row + 1) * ROW_H;
+        let kcycles = Caption::format(format_args!("{}", sample.cycles / 1000)).unwrap_or_default();
+        let calls = Caption::format(format_args!("{}", sample.calls)).unwrap_or_default();
+        let bar = ((sample.cycles as u128 * BAR_W as u128) / max as u128) as usize;
+
+        draw_text(&mut target, x0 + 8, y, sample.name, &PIXEL_8, 0xFFFFFFFF);
+        draw_text(&mut target, x0 + 150, y, &kcycles, &PIXEL_8, 0xFFFFFFFF);
+        draw_text(&mut target, x0 + 240, y, &calls, &PIXEL_8, 0xFFCCCCCC);
+        fill_rect(fb, pitch, screen, x0 + PANEL_W - BAR_W - 12, y, bar.max(1), 8, 0xFFFFB000);
     }
-    let len = 12 - i;
-    buf[..len].copy_from_slice(&tmp[i..]);
-    unsafe { core::str::from_utf8_unchecked(&buf[..len]) }
 }
 
 /// Human-readable name for a validated image format.
@@ -700,6 +1971,7 @@ fn image_type_str(t: validate::ImageType) -> &'static str {
         validate::ImageType::Png => "PNG",
         validate::ImageType::Bmp => "BMP",
         validate::ImageType::Qoi => "QOI",
+        validate::ImageType::Gif => "GIF",
         validate::ImageType::Unknown => "UNKNOWN",
     }
 }
@@ -715,29 +1987,47 @@ fn secure_error_str(e: &SecureDecodeError) -> &'static str {
     }
 }
 
-/// Blit a decoded image from the scratch buffer to the framebuffer, centered.
+/// Scale a decoded image from the scratch buffer to fit the screen (aspect
+/// ratio preserved), turned upright by its EXIF `orientation`, and blit it
+/// centered; the margins keep the background.
 ///
-/// Bounds are clamped to the display so an image reported larger than the
-/// screen (or a dimension mismatch) can never write outside the framebuffer.
+/// All indexing goes through the verified `blit_scaled`, which refuses a
+/// source shorter than `src_w * src_h` pixels or a rectangle off the screen,
+/// so a bogus size from the decoder can never read or write out of bounds.
 /// `src` is laid out as `src_w * src_h` ARGB32 pixels, row-major.
-unsafe fn blit_centered(fb: *mut u32, pitch: usize, src: &[u32], src_w: u32, src_h: u32) {
-    let copy_w = src_w.min(WIDTH) as usize;
-    let copy_h = src_h.min(HEIGHT) as usize;
-    let off_x = ((WIDTH.saturating_sub(src_w)) / 2) as usize;
-    let off_y = ((HEIGHT.saturating_sub(src_h)) / 2) as usize;
-    let stride = src_w as usize;
+fn blit_letterboxed(
+    screen: &mut PhotoScreen<'_>,
+    src: &[u32],
+    src_w: u32,
+    src_h: u32,
+    orientation: Orientation,
+) -> Result<(), BlitError> {
+    let (width, height) = screen.dimensions();
+    let (upright_w, upright_h) = orientation.upright_size(src_w, src_h);
+    let rect = letterbox_rect(upright_w, upright_h, width, height).ok_or(BlitError::BadSourceSize)?;
+    blit_scaled(src, src_w, src_h, orientation, screen.pixels_mut(), width, height, rect, ScaleFilter::Bilinear)
+}
 
-    for y in 0..copy_h {
-        let src_row = y * stride;
-        let dst_row = (off_y + y) * pitch + off_x;
-        for x in 0..copy_w {
-            // Defensive bound: never read past the scratch slice.
-            let si = src_row + x;
-            if si < src.len() {
-                fb.add(dst_row + x).write_volatile(src[si]);
-            }
+/// Decode `bytes` through the secure pipeline and scale the picture into
+/// `thumb`, upright and letterboxed; its size in the thumbnail, or `None`
+/// if the pipeline refused it
+unsafe fn decode_thumbnail(bytes: &[u8], thumb: &mut [u32]) -> Option<(u32, u32)> {
+    let orientation = exif::read_exif(bytes).orientation;
+    let scratch = PIXEL_SCRATCH.pixels();
+    let res = match secure_decode_into(bytes, scratch, &DECODER_HEAP) {
+        Ok(res) => {
+            record_mem(&SCRATCH_GAUGE, res.width as usize * res.height as usize * 4);
+            res
         }
-    }
+        Err(e) => {
+            debug_println!("Photoframe PD: thumbnail decode rejected: {}", secure_error_str(&e));
+            return None;
+        }
+    };
+    let (upright_w, upright_h) = orientation.upright_size(res.width, res.height);
+    let rect = letterbox_rect(upright_w, upright_h, THUMB_W, THUMB_H)?;
+    blit_scaled(scratch, res.width, res.height, orientation, thumb, THUMB_W, THUMB_H, rect, ScaleFilter::Bilinear).ok()?;
+    Some((rect.w, rect.h))
 }
 
 // ============================================================================
@@ -774,18 +2064,73 @@ fn blink_activity_led() {
 // FRAMEBUFFER INIT
 // ============================================================================
 
-fn init_framebuffer() -> Option<Framebuffer> {
+/// One telemetry record as a console line (parsed by serial-debug)
+fn print_telemetry(record: &TelemetryRecord) {
+    debug_println!(
+        "@tlm seq={} t={} temp_mc={} throttled=0x{:x} frames={} missed={} photo={}",
+        record.seq, record.uptime_s, record.temp_milli_c, record.throttled,
+        record.frames, record.missed_ticks, record.photo_index
+    );
+}
+
+/// Allocate the framebuffer, then switch it to the display's native mode.
+/// Rendering below assumes a 32bpp surface between `MIN_MODE` and
+/// `MAX_MODE`, so only modes in that range are tried.
+fn init_framebuffer() -> Option<(Framebuffer, ModeSelection)> {
     debug_println!("Photoframe PD: Initializing framebuffer...");
     let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
 
-    match unsafe { Framebuffer::new(&mailbox, WIDTH, HEIGHT) } {
-        Ok(fb) => {
-            let info = fb.info();
-            debug_println!("Photoframe PD: FB {}x{} @ 0x{:08x}", info.width, info.height, info.base);
-            Some(fb)
-        }
+    let (mut fb, mut selection) = match unsafe { Framebuffer::new_with_fallback(&mailbox, &FIXED_720P_LADDER) } {
+        Ok(allocated) => allocated,
         Err(e) => {
             debug_println!("Photoframe PD: FB error: {:?}", e);
+            return None;
+        }
+    };
+
+    // 720p is what every sink takes; a panel that says it is something
+    // else in range gets its own mode, and counts as the preferred rung
+    if let Some(native) = native_mode(&mailbox, MIN_MODE, MAX_MODE) {
+        let switched = native == selection.mode
+            || match unsafe { fb.reconfigure(&mailbox, native.width, native.height, native.depth) } {
+                Ok(()) => true,
+                Err(e) => {
+                    debug_println!("Photoframe PD: native {}x{} refused: {:?}", native.width, native.height, e);
+                    false
+                }
+            };
+        let rungs = selection.rungs + 1;
+        selection = if switched {
+            ModeSelection { mode: native, rung: 0, rungs }
+        } else {
+            ModeSelection { rung: selection.rung + 1, rungs, ..selection }
+        };
+    }
+    let info = fb.info();
+    if info.width == 0 {
+        debug_println!("Photoframe PD: FB lost switching modes");
+        return None;
+    }
+    debug_println!(
+        "Photoframe PD: FB {}x{} @ 0x{:08x}{}",
+        info.width, info.height, info.base,
+        if selection.is_fallback() { " (fallback)" } else { "" }
+    );
+    Some((fb, selection))
+}
+
+/// Ask the firmware for vsync interrupts, to pace transitions by
+fn init_frame_clock() -> Option<FrameClock> {
+    let mailbox = unsafe { Mailbox::new(MAILBOX_BASE) };
+    // Safety: photoframe.system maps the SMI registers at SMI_VIRT_BASE
+    match unsafe { Vsync::firmware(&mailbox, SMI_VIRT_BASE) } {
+        Ok(vsync) => {
+            let clock = FrameClock::new(vsync, VSYNC_FRAMES_PER_STEP);
+            debug_println!("Photoframe PD: transitions vsync paced, {} Hz", clock.frame_hz(REFRESH_HZ));
+            Some(clock)
+        }
+        Err(e) => {
+            debug_println!("Photoframe PD: vsync unavailable ({:?}), transitions on the tick", e);
             None
         }
     }
@@ -803,52 +2148,170 @@ fn init() -> PhotoFrameHandler {
     debug_println!("========================================");
     debug_println!("");
     debug_println!("Security: Input isolated from display");
-    debug_println!("Photos: {} ({} procedural, BMP+QOI decoded securely)", PHOTOS.len(), PHOTOS.len() - 2);
+    debug_println!("Photos: {} ({} procedural, BMP+QOI+PNG+GIF decoded securely)", PHOTOS.len(), PHOTOS.len() - 4);
     debug_println!("Decoder heap: {} MB bounded (BoundedBumpAllocator)", DECODER_HEAP_SIZE / (1024 * 1024));
     debug_println!("Pipeline: validate -> budget -> bounded decode");
     debug_println!("");
 
+    // Audit builds check the ring and log helpers against the specs of the
+    // PDs this one stands in for: the graphics side of the input ring and
+    // the display side of the photo protocol
+    #[cfg(feature = "audit")]
+    {
+        rpi4_input_protocol::audit::set_role(rpi4_input_protocol::audit::Role::Graphics);
+        rpi4_photo_protocol::audit::set_role(rpi4_photo_protocol::audit::Role::Display);
+        debug_println!("Security audit: protocol accesses checked against PD specs");
+    }
+
     blink_activity_led();
 
+    // Safety: profile builds run on a kernel with KernelArmExportPMUUser
+    #[cfg(feature = "profile")]
+    unsafe {
+        profile::enable_cycle_counter();
+    }
+
     let mut handler = PhotoFrameHandler::new();
-    handler.framebuffer = init_framebuffer();
+    #[cfg(feature = "sdcard")]
+    {
+        handler.sd_card = match SdCard::connect() {
+            Ok(card) => {
+                debug_println!("Photoframe PD: SD card: {} photos", card.photo_count());
+                Some(card)
+            }
+            Err(e) => {
+                debug_println!("Photoframe PD: no SD card photos: {}", e);
+                None
+            }
+        };
+    }
+    if let Some((fb, selection)) = init_framebuffer() {
+        handler.screen = fb.dimensions();
+        handler.framebuffer = Some(fb);
+        handler.display_mode = Some(selection);
+        handler.clock = init_frame_clock();
+    }
+    // Safety: init runs once, and these are the only references to the
+    // layer buffers
+    handler.layers = unsafe {
+        let size = handler.screen;
+        PHOTO_LAYER.layer(size).zip(OSD_LAYER.layer(size)).and_then(|(photo, osd)| Compositor::new(photo, osd))
+    };
+
+    // Safety: as for the layers, these are the only references
+    handler.transition_buffers = unsafe {
+        Some(TransitionBuffers { from: TRANSITION_FROM.pixels(), to: TRANSITION_TO.pixels() })
+    };
+
+    // Every photo is known once the SD card has been counted; the boot time
+    // in microseconds seeds the shuffle
+    let seed = handler.now().as_micros() as u32;
+    handler.load_playlist(seed);
+
+    // Safety: photoframe.system maps the 4KB telemetry log here, and this
+    // PD is its only writer. A log kept across a PD restart is replayed so
+    // a console capture has the whole history.
+    let (log, resumed) = unsafe { TelemetryLog::attach(TELEMETRY_LOG_VADDR as *mut u8) };
+    if resumed {
+        debug_println!("Photoframe PD: Telemetry log resumed, {} records", log.retained().len());
+        for seq in log.retained() {
+            if let Some(record) = log.get(seq) {
+                print_telemetry(&record);
+            }
+        }
+    }
+    TELEMETRY_GAUGE.record(log.retained().len());
+    handler.telemetry = Some(log);
+
+    // Safety: photoframe.system maps the 2MB thumbnail ring here, and
+    // nothing else uses it
+    handler.thumbs = Some(unsafe { ThumbnailRing::attach(THUMB_RING_VADDR as *mut u8) });
+
+    #[cfg(feature = "soak")]
+    {
+        handler.arm_soak();
+        debug_println!(
+            "Photoframe PD: Soak mode, replaying a {}-key session; {} framebuffer guard words",
+            soak::SOAK_SCRIPT.len(), handler.soak.fb_guard_len
+        );
+    }
 
     // Initial render
     handler.render();
+    #[cfg(feature = "audit")]
+    print_audit_log();
 
     debug_println!("Photoframe PD: Ready");
-    debug_println!("Controls: Arrows=Navigate, Space=Pause, Enter=Info");
+    debug_println!("Controls: Arrows=Navigate, Space=Pause, Enter=Info, 1=Playlist order, 2=Favorite, 3=Grid, 4=Clock, 9+0=Debug");
+    #[cfg(feature = "profile")]
+    debug_println!("Profiling: 0=Toggle profiling screen");
     handler
 }
 
-#[derive(Debug)]
-pub struct HandlerError;
-
-impl fmt::Display for HandlerError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Photoframe PD handler error")
+/// Print the access-spec violations logged since the last call
+#[cfg(feature = "audit")]
+fn print_audit_log() {
+    let dropped = rpi4_pd_error::audit::drain(|violation| debug_println!("{}", violation));
+    if dropped > 0 {
+        debug_println!("@audit dropped={}", dropped);
     }
 }
 
 impl Handler for PhotoFrameHandler {
-    type Error = HandlerError;
+    type Error = PdError;
 
     fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
         // Process input from Input PD
         if channels.contains(INPUT_CHANNEL) {
-            while let Some((key, state)) = self.input.poll() {
-                if state == KeyState::Pressed {
-                    self.handle_input(key, state);
+            self.input_seen = Some(self.frame_counter);
+            while let Some(entry) = self.input.poll() {
+                let pressed = entry.key_state == 1;
+                if self.chord.key(entry.key_code, pressed, self.frame_counter) {
+                    self.show_debug = !self.show_debug;
+                    debug_println!("Debug overlay {}", if self.show_debug { "on" } else { "off" });
+                    self.needs_redraw = true;
+                    continue;
+                }
+                if pressed {
+                    self.last_key = Some((entry.key_code, self.frame_counter));
+                    let key = u8_to_key_code(entry.key_code);
+                    #[cfg(feature = "soak")]
+                    self.echo_key(key);
+                    self.handle_input(key, KeyState::Pressed);
                 }
             }
         }
 
-        // Update state
-        self.update();
+        // Clear the source before acking, or the level IRQ refires at once
+        if channels.contains(VSYNC_CHANNEL) {
+            let mut frame_due = false;
+            if let Some(clock) = &mut self.clock {
+                clock.handle_irq(|_| frame_due = true);
+            }
+            VSYNC_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking vsync IRQ")
+            })?;
+            if frame_due {
+                self.vblank_frame();
+            }
+        }
+
+        // Advance the slideshow on the Timer PD's tick
+        if channels.contains(TIMER_CHANNEL) {
+            self.update();
+            #[cfg(feature = "soak")]
+            self.soak_tick();
+        }
 
         // Render if needed
         self.render();
+        #[cfg(feature = "soak")]
+        self.check_invariants();
+        #[cfg(feature = "audit")]
+        print_audit_log();
 
+        // Returning blocks the PD until the next tick, key or vblank; the core
+        // idles in the kernel meanwhile
         Ok(())
     }
 }
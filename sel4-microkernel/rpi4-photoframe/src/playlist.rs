@@ -0,0 +1,238 @@
+//! Slideshow playlist: the order photos are shown in, and per-photo
+//! settings.
+//!
+//! Photos are identified by their index in the slideshow (the embedded
+//! photos, then any on the SD card). The playlist keeps a play order over
+//! those indices and a position in it; `next`/`prev` step through the order,
+//! passing over photos flagged to skip. Each photo can also carry a dwell
+//! time that overrides the slideshow interval, and a favorite flag for the
+//! overlay.
+//!
+//! Shuffling is the verified `fisher_yates` pass in `rpi4-photo-protocol`,
+//! proven to swap only inside the playlist's part of the order array.
+
+use rpi4_photo_protocol::{fisher_yates, ORDER_NEWEST_FIRST, ORDER_SEQUENTIAL, ORDER_SHUFFLE};
+
+/// Most photos a playlist holds; later ones are left out of the slideshow
+pub const MAX_PLAYLIST: usize = 512;
+
+/// Order the slideshow plays photos in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Embedded photos, then the SD card's in directory order
+    Sequential,
+    /// A random permutation, drawn again on every shuffle command
+    Shuffle,
+    /// Sequential reversed: FAT appends new files to the directory, so the
+    /// photos copied to the card last come first
+    NewestFirst,
+}
+
+impl Order {
+    /// Decode a CMD_SHUFFLE order
+    pub fn from_wire(order: u8) -> Option<Self> {
+        match order {
+            ORDER_SEQUENTIAL => Some(Order::Sequential),
+            ORDER_SHUFFLE => Some(Order::Shuffle),
+            ORDER_NEWEST_FIRST => Some(Order::NewestFirst),
+            _ => None,
+        }
+    }
+
+    /// Encode for a CMD_SHUFFLE
+    pub fn to_wire(self) -> u8 {
+        match self {
+            Order::Sequential => ORDER_SEQUENTIAL,
+            Order::Shuffle => ORDER_SHUFFLE,
+            Order::NewestFirst => ORDER_NEWEST_FIRST,
+        }
+    }
+
+    /// The order after this one, for a key that cycles through them
+    pub fn cycle(self) -> Self {
+        match self {
+            Order::Sequential => Order::Shuffle,
+            Order::Shuffle => Order::NewestFirst,
+            Order::NewestFirst => Order::Sequential,
+        }
+    }
+
+    /// Console / overlay name
+    pub fn name(self) -> &'static str {
+        match self {
+            Order::Sequential => "IN ORDER",
+            Order::Shuffle => "SHUFFLE",
+            Order::NewestFirst => "NEWEST FIRST",
+        }
+    }
+}
+
+/// Per-photo settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Entry {
+    /// Seconds to show the photo for, instead of the slideshow interval
+    dwell: Option<u32>,
+    /// Left out when stepping through the playlist
+    skip: bool,
+    favorite: bool,
+}
+
+/// Play order and per-photo settings for up to [`MAX_PLAYLIST`] photos
+pub struct Playlist {
+    len: usize,
+    order: Order,
+    /// Photo indices in play order; `sequence[..len]` is a permutation of
+    /// `0..len`
+    sequence: [u16; MAX_PLAYLIST],
+    /// Indexed by photo
+    entries: [Entry; MAX_PLAYLIST],
+    /// Index into `sequence` of the current photo
+    position: usize,
+    /// xorshift32 state for shuffling; never 0
+    rng: u32,
+}
+
+impl Playlist {
+    /// A sequential playlist of `photos` photos (at most [`MAX_PLAYLIST`]),
+    /// starting at the first; `seed` seeds the shuffle
+    pub fn new(photos: usize, seed: u32) -> Self {
+        let mut playlist = Self {
+            len: photos.min(MAX_PLAYLIST),
+            order: Order::Sequential,
+            sequence: [0; MAX_PLAYLIST],
+            entries: [Entry::default(); MAX_PLAYLIST],
+            position: 0,
+            rng: seed | 1,
+        };
+        playlist.rebuild();
+        playlist
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    /// The current photo
+    pub fn current(&self) -> usize {
+        self.sequence[self.position] as usize
+    }
+
+    /// Play photos in `order`, staying on the current photo; a shuffle
+    /// playlist is reshuffled
+    pub fn set_order(&mut self, order: Order) {
+        let current = self.current();
+        self.order = order;
+        self.rebuild();
+        self.goto(current);
+    }
+
+    /// Make `photo` current; false if it is not in the playlist
+    pub fn goto(&mut self, photo: usize) -> bool {
+        match self.sequence[..self.len].iter().position(|&p| p as usize == photo) {
+            Some(position) => {
+                self.position = position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Step to the next photo not flagged to skip and return it; stays put
+    /// when every other photo is skipped
+    pub fn next(&mut self) -> usize {
+        self.step(1)
+    }
+
+    /// Step to the previous photo not flagged to skip and return it
+    pub fn prev(&mut self) -> usize {
+        self.step(self.len.saturating_sub(1))
+    }
+
+    /// Seconds to show `photo` for: its dwell override, or `default`
+    pub fn dwell(&self, photo: usize, default: u32) -> u32 {
+        self.entry(photo).and_then(|e| e.dwell).unwrap_or(default)
+    }
+
+    /// Show `photo` for `seconds` instead of the slideshow interval (`None`
+    /// restores the interval)
+    pub fn set_dwell(&mut self, photo: usize, seconds: Option<u32>) {
+        if let Some(entry) = self.entry_mut(photo) {
+            entry.dwell = seconds;
+        }
+    }
+
+    pub fn is_skipped(&self, photo: usize) -> bool {
+        self.entry(photo).is_some_and(|e| e.skip)
+    }
+
+    /// Leave `photo` out when stepping through the playlist; it can still
+    /// be reached with `goto`
+    pub fn set_skip(&mut self, photo: usize, skip: bool) {
+        if let Some(entry) = self.entry_mut(photo) {
+            entry.skip = skip;
+        }
+    }
+
+    pub fn is_favorite(&self, photo: usize) -> bool {
+        self.entry(photo).is_some_and(|e| e.favorite)
+    }
+
+    /// Flip the favorite flag of `photo`; returns the new flag
+    pub fn toggle_favorite(&mut self, photo: usize) -> bool {
+        match self.entry_mut(photo) {
+            Some(entry) => {
+                entry.favorite = !entry.favorite;
+                entry.favorite
+            }
+            None => false,
+        }
+    }
+
+    fn entry(&self, photo: usize) -> Option<&Entry> {
+        self.entries[..self.len].get(photo)
+    }
+
+    fn entry_mut(&mut self, photo: usize) -> Option<&mut Entry> {
+        self.entries[..self.len].get_mut(photo)
+    }
+
+    /// Move `stride` places round the play order until a photo not flagged
+    /// to skip, giving up after one lap
+    fn step(&mut self, stride: usize) -> usize {
+        if self.len == 0 {
+            return 0;
+        }
+        let mut position = self.position;
+        for _ in 0..self.len {
+            position = (position + stride) % self.len;
+            if !self.entries[self.sequence[position] as usize].skip {
+                self.position = position;
+                break;
+            }
+        }
+        self.current()
+    }
+
+    /// Lay out `sequence[..len]` for the current order
+    fn rebuild(&mut self) {
+        let len = self.len;
+        for (i, slot) in self.sequence[..len].iter_mut().enumerate() {
+            *slot = match self.order {
+                Order::NewestFirst => (len - 1 - i) as u16,
+                Order::Sequential | Order::Shuffle => i as u16,
+            };
+        }
+        if self.order == Order::Shuffle {
+            self.rng = fisher_yates(&mut self.sequence, len, self.rng);
+        }
+        self.position = 0;
+    }
+}
@@ -0,0 +1,353 @@
+//! # Native PNG Decoder (8-bit RGB / RGBA, non-interlaced)
+//!
+//! Decodes the common truecolor PNG variants without a third-party decoder:
+//!
+//! ```text
+//!   IDAT chunks ──► IdatSource ──► inflate::Inflater ──► ScanlineSink ──► ARGB32
+//!   (walked in place)              (32 KiB window)       (2 scanlines)
+//! ```
+//!
+//! Nothing proportional to the image is allocated: the heap holds the inflate
+//! window and two scanlines, the pixels land directly in the caller's output.
+//! Scanline strides and output slots come from the verified helpers in
+//! `rpi4_photo_protocol`, and the source color type maps onto the same
+//! `PIXEL_FORMAT_*` values the Decoder → Display pixel buffer uses.
+//!
+//! Other PNG variants (grayscale, palette, 16-bit, Adam7) are left to
+//! [`crate::decoder::decode_png`]'s zune-png fallback.
+
+use alloc::vec;
+
+use rpi4_photo_protocol::{
+    format_bytes_per_pixel, next_row_base, row_pixel_index, scanline_bytes,
+    INFLATE_WINDOW_SIZE, PIXEL_FORMAT_RGB24, PIXEL_FORMAT_RGBA32,
+};
+
+use crate::decoder::DecodeError;
+use crate::inflate::{ByteSink, ByteSource, InflateError, Inflater};
+
+/// PNG signature bytes
+const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// IHDR color types
+const COLOR_RGB: u8 = 2;
+const COLOR_RGBA: u8 = 6;
+
+/// Parsed IHDR for a natively supported PNG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PngHeader {
+    pub width: u32,
+    pub height: u32,
+    /// Source pixel layout as a `PIXEL_FORMAT_*` value (RGB24 or RGBA32)
+    pub format: u8,
+}
+
+impl PngHeader {
+    /// Parse the IHDR chunk. Returns `Ok(None)` for well-formed PNGs this
+    /// decoder does not handle natively.
+    pub fn parse(data: &[u8]) -> Result<Option<Self>, DecodeError> {
+        if data.len() < 33 || &data[0..8] != SIGNATURE {
+            return Err(DecodeError::InvalidFormat);
+        }
+        if data[8..12] != [0, 0, 0, 13] || &data[12..16] != b"IHDR" {
+            return Err(DecodeError::InvalidHeader);
+        }
+        let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+        let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+        let (bit_depth, color_type, interlace) = (data[24], data[25], data[28]);
+
+        if width == 0 || height == 0 {
+            return Err(DecodeError::InvalidDimensions);
+        }
+
+        let format = match color_type {
+            COLOR_RGB => PIXEL_FORMAT_RGB24,
+            COLOR_RGBA => PIXEL_FORMAT_RGBA32,
+            _ => return Ok(None),
+        };
+        if bit_depth != 8 || interlace != 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self { width, height, format }))
+    }
+
+    /// Heap bytes [`decode_native`] allocates for this image.
+    pub fn working_memory(&self) -> usize {
+        let row = scanline_bytes(self.width, self.format).unwrap_or(usize::MAX / 4);
+        INFLATE_WINDOW_SIZE + 2 * row
+    }
+}
+
+// ============================================================================
+// IDAT STREAM
+// ============================================================================
+
+/// Presents the concatenated IDAT payloads as one byte stream, walking the
+/// chunk list in place.
+struct IdatSource<'a> {
+    data: &'a [u8],
+    pos: usize,
+    chunk_end: usize,
+    next_chunk: usize,
+    in_idat: bool,
+}
+
+impl<'a> IdatSource<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        // First chunk after the signature (IHDR is skipped like any other).
+        Self { data, pos: 8, chunk_end: 8, next_chunk: 8, in_idat: false }
+    }
+}
+
+impl ByteSource for IdatSource<'_> {
+    fn next_byte(&mut self) -> Option<u8> {
+        while self.pos >= self.chunk_end {
+            let hdr = self.data.get(self.next_chunk..self.next_chunk.checked_add(8)?)?;
+            let len = u32::from_be_bytes([hdr[0], hdr[1], hdr[2], hdr[3]]) as usize;
+            let start = self.next_chunk + 8;
+            let end = start.checked_add(len)?;
+            if end > self.data.len() {
+                return None;
+            }
+            // Skip the 4-byte CRC; zlib's Adler-32 covers the payload.
+            self.next_chunk = end.checked_add(4)?;
+
+            if &hdr[4..8] == b"IDAT" {
+                self.in_idat = true;
+                self.pos = start;
+                self.chunk_end = end;
+            } else if self.in_idat || &hdr[4..8] == b"IEND" {
+                // IDAT chunks must be consecutive.
+                return None;
+            }
+        }
+        let b = self.data[self.pos];
+        self.pos += 1;
+        Some(b)
+    }
+}
+
+// ============================================================================
+// SCANLINE UNFILTERING
+// ============================================================================
+
+/// Reverses PNG per-scanline filtering and packs finished rows into ARGB32.
+struct ScanlineSink<'o> {
+    prev: &'o mut [u8],
+    cur: &'o mut [u8],
+    output: &'o mut [u32],
+    bpp: usize,
+    width: usize,
+    rows_left: u32,
+    row_base: usize,
+    filter: u8,
+    /// Next byte index in `cur`; `None` while waiting for the filter byte
+    col: Option<usize>,
+}
+
+impl ByteSink for ScanlineSink<'_> {
+    fn push(&mut self, x: u8) -> Result<(), InflateError> {
+        if self.rows_left == 0 {
+            return Err(InflateError::OutputOverflow);
+        }
+        let col = match self.col {
+            None => {
+                if x > 4 {
+                    return Err(InflateError::InvalidOutput);
+                }
+                self.filter = x;
+                self.col = Some(0);
+                return Ok(());
+            }
+            Some(col) => col,
+        };
+
+        let a = if col >= self.bpp { self.cur[col - self.bpp] } else { 0 };
+        let b = self.prev[col];
+        let c = if col >= self.bpp { self.prev[col - self.bpp] } else { 0 };
+        self.cur[col] = x.wrapping_add(match self.filter {
+            0 => 0,
+            1 => a,
+            2 => b,
+            3 => ((a as u16 + b as u16) / 2) as u8,
+            _ => paeth(a, b, c),
+        });
+
+        if col + 1 < self.cur.len() {
+            self.col = Some(col + 1);
+            return Ok(());
+        }
+        self.finish_row()
+    }
+}
+
+impl ScanlineSink<'_> {
+    fn finish_row(&mut self) -> Result<(), InflateError> {
+        for (x, px) in self.cur.chunks_exact(self.bpp).enumerate() {
+            let idx = row_pixel_index(self.row_base, x, self.output.len())
+                .ok_or(InflateError::OutputOverflow)?;
+            // Alpha is forced opaque; the framebuffer does not blend.
+            self.output[idx] = 0xFF00_0000
+                | ((px[0] as u32) << 16)
+                | ((px[1] as u32) << 8)
+                | (px[2] as u32);
+        }
+        self.row_base = next_row_base(self.row_base, self.width).ok_or(InflateError::OutputOverflow)?;
+        self.rows_left -= 1;
+        core::mem::swap(&mut self.prev, &mut self.cur);
+        self.col = None;
+        Ok(())
+    }
+}
+
+/// PNG Paeth predictor
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// ============================================================================
+// DECODE
+// ============================================================================
+
+/// Decode a natively supported PNG into ARGB32 `output`.
+///
+/// Allocates [`PngHeader::working_memory`] bytes from the (bounded) global
+/// heap; call only through [`crate::secure_decode`].
+pub fn decode_native(data: &[u8], header: &PngHeader, output: &mut [u32]) -> Result<(u32, u32), DecodeError> {
+    let row_len = scanline_bytes(header.width, header.format).ok_or(DecodeError::InvalidDimensions)?;
+    let pixel_count = (header.width as usize)
+        .checked_mul(header.height as usize)
+        .ok_or(DecodeError::InvalidDimensions)?;
+    if output.len() < pixel_count {
+        return Err(DecodeError::BufferTooSmall);
+    }
+
+    let mut window = vec![0u8; INFLATE_WINDOW_SIZE];
+    let mut rows = vec![0u8; 2 * row_len];
+    let (prev, cur) = rows.split_at_mut(row_len);
+
+    let sink = ScanlineSink {
+        prev,
+        cur,
+        output: &mut output[..pixel_count],
+        bpp: format_bytes_per_pixel(header.format) as usize,
+        width: header.width as usize,
+        rows_left: header.height,
+        row_base: 0,
+        filter: 0,
+        col: None,
+    };
+
+    let mut inflater = Inflater::new(&mut window, sink).map_err(|_| DecodeError::CorruptedData)?;
+    inflater
+        .inflate_zlib(&mut IdatSource::new(data))
+        .map_err(|_| DecodeError::CorruptedData)?;
+
+    if inflater.into_sink().rows_left != 0 {
+        return Err(DecodeError::CorruptedData);
+    }
+    Ok((header.width, header.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Build a PNG whose zlib stream uses a single stored block.
+    fn stored_png(width: u32, height: u32, color_type: u8, raw: &[u8]) -> Vec<u8> {
+        let mut z = vec![0x78, 0x01, 0x01];
+        let len = raw.len() as u16;
+        z.extend_from_slice(&len.to_le_bytes());
+        z.extend_from_slice(&(!len).to_le_bytes());
+        z.extend_from_slice(raw);
+        let (mut a, mut b) = (1u32, 0u32);
+        for &byte in raw {
+            a = (a + byte as u32) % 65521;
+            b = (b + a) % 65521;
+        }
+        z.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+        let mut png = SIGNATURE.to_vec();
+        let mut chunk = |ty: &[u8], body: &[u8]| {
+            png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            png.extend_from_slice(ty);
+            png.extend_from_slice(body);
+            png.extend_from_slice(&[0; 4]);
+        };
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        chunk(b"IHDR", &ihdr);
+        chunk(b"tEXt", b"k\0v");
+        chunk(b"IDAT", &z);
+        chunk(b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn test_rgba_with_filters() {
+        // 2x2 RGBA: row 0 Sub-filtered, row 1 Up-filtered.
+        let raw = [
+            1, 10, 20, 30, 255, 5, 5, 5, 0,
+            2, 1, 1, 1, 0, 0, 0, 0, 0,
+        ];
+        let png = stored_png(2, 2, COLOR_RGBA, &raw);
+        let header = PngHeader::parse(&png).unwrap().unwrap();
+        assert_eq!(header.format, PIXEL_FORMAT_RGBA32);
+
+        let mut out = [0u32; 4];
+        assert_eq!(decode_native(&png, &header, &mut out), Ok((2, 2)));
+        assert_eq!(out, [0xFF0A141E, 0xFF0F1923, 0xFF0B151F, 0xFF0F1923]);
+    }
+
+    #[test]
+    fn test_rgb_paeth_and_average() {
+        let raw = [
+            3, 100, 0, 0, 50, 0, 0,
+            4, 0, 10, 0, 0, 0, 0,
+        ];
+        let png = stored_png(2, 2, COLOR_RGB, &raw);
+        let header = PngHeader::parse(&png).unwrap().unwrap();
+        assert_eq!(header.format, PIXEL_FORMAT_RGB24);
+
+        let mut out = [0u32; 4];
+        decode_native(&png, &header, &mut out).unwrap();
+        // Average: (100,0,0), (50+50,0,0); Paeth predicts from above, then left.
+        assert_eq!(out, [0xFF640000, 0xFF640000, 0xFF640A00, 0xFF640A00]);
+    }
+
+    #[test]
+    fn test_rejects_bad_filter_and_short_data() {
+        let png = stored_png(1, 1, COLOR_RGB, &[5, 1, 2, 3]);
+        let header = PngHeader::parse(&png).unwrap().unwrap();
+        let mut out = [0u32; 1];
+        assert_eq!(decode_native(&png, &header, &mut out), Err(DecodeError::CorruptedData));
+
+        // One row of a two-row image
+        let png = stored_png(1, 2, COLOR_RGB, &[0, 1, 2, 3]);
+        let header = PngHeader::parse(&png).unwrap().unwrap();
+        let mut out = [0u32; 2];
+        assert_eq!(decode_native(&png, &header, &mut out), Err(DecodeError::CorruptedData));
+    }
+
+    #[test]
+    fn test_other_variants_not_native() {
+        let mut png = stored_png(1, 1, 0, &[0, 7]);
+        assert_eq!(PngHeader::parse(&png), Ok(None));
+        png[24] = 16; // 16-bit RGB
+        png[25] = COLOR_RGB;
+        assert_eq!(PngHeader::parse(&png), Ok(None));
+    }
+}
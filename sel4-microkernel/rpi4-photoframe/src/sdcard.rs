@@ -0,0 +1,128 @@
+//! SD card photos, served by the Storage PD
+//!
+//! The Storage PD owns the card; this PD only sees the file buffer, mapped
+//! read-only. A photo is fetched with one `OPEN` and then one `READ` per
+//! chunk, each reply checked against the window `chunk_window` gives for
+//! that chunk before the bytes are trusted to be there. The bytes then go
+//! through the same secure decode pipeline as the embedded photos.
+//!
+//! With the `signed-photos` feature a photo must also carry an Ed25519
+//! signature trailer (`rpi4_graphics::crypto::ed25519`) made with the key
+//! whose public half was in `PHOTO_SIGNING_KEY` at build time. Unsigned or
+//! forged photos never reach the decoder.
+
+use sel4_microkit::{with_msg_regs, with_msg_regs_mut, Channel, MessageInfo};
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError, ResultExt};
+use rpi4_storage_protocol::{
+    chunk_count, chunk_window, FileName, StorageRequest, FILE_BUFFER_SIZE, FILE_BUFFER_VADDR,
+    REPLY_WORDS, STORAGE_CHANNEL_ID,
+};
+#[cfg(feature = "signed-photos")]
+use rpi4_graphics::crypto::{
+    ed25519::{verify_signed, ED25519_PUBLIC_KEY_SIZE},
+    VerifyResult,
+};
+
+/// The Storage PD replied with something the protocol does not allow
+const BAD_REPLY: PdError = PdError::new(ErrorDomain::Storage, ErrorKind::InvalidResponse);
+
+/// The photo is unsigned (detail 1) or its signature does not verify
+/// (detail 2)
+#[cfg(feature = "signed-photos")]
+const NOT_SIGNED: PdError = PdError::new(ErrorDomain::Image, ErrorKind::Integrity);
+
+/// Public key SD card photos must be signed with: `PHOTO_SIGNING_KEY`, 64
+/// hex digits, at build time
+#[cfg(feature = "signed-photos")]
+const PHOTO_SIGNING_KEY: [u8; ED25519_PUBLIC_KEY_SIZE] = match option_env!("PHOTO_SIGNING_KEY") {
+    Some(hex) => parse_key(hex),
+    None => panic!("the signed-photos feature needs PHOTO_SIGNING_KEY (public key, 64 hex digits)"),
+};
+
+/// A photo copied into the file buffer
+pub struct SdPhoto {
+    pub name: FileName,
+    /// Valid until the next `load`
+    pub bytes: &'static [u8],
+}
+
+/// Connection to the Storage PD
+pub struct SdCard {
+    photos: u32,
+}
+
+impl SdCard {
+    /// Ask the Storage PD how many photos the card holds
+    pub fn connect() -> Result<Self, PdError> {
+        let [photos, _, _] = call(StorageRequest::Count).context("counting SD card photos")?;
+        Ok(Self { photos: photos as u32 })
+    }
+
+    pub fn photo_count(&self) -> usize {
+        self.photos as usize
+    }
+
+    /// Copy photo `index` into the file buffer
+    pub fn load(&self, index: u32) -> Result<SdPhoto, PdError> {
+        let [size, name_lo, name_hi] = call(StorageRequest::Open { index }).context("opening SD card photo")?;
+        let size = size as usize;
+        if size > FILE_BUFFER_SIZE {
+            return Err(BAD_REPLY.with_detail(1));
+        }
+        for chunk in 0..chunk_count(size) {
+            let [len, _, _] = call(StorageRequest::Read { chunk }).context("reading SD card photo")?;
+            match chunk_window(chunk, size) {
+                Some(window) if window.len as u64 == len => {}
+                _ => return Err(BAD_REPLY.with_detail(2)),
+            }
+        }
+        // Safety: photoframe.system maps the file buffer here, read-only,
+        // and the Storage PD only writes it while serving our READ calls
+        let bytes = unsafe { core::slice::from_raw_parts(FILE_BUFFER_VADDR as *const u8, size) };
+        #[cfg(feature = "signed-photos")]
+        let bytes = match verify_signed(bytes, &PHOTO_SIGNING_KEY) {
+            (VerifyResult::Valid, payload) => payload,
+            (VerifyResult::NotChecked, _) => return Err(NOT_SIGNED.with_detail(1)),
+            (VerifyResult::Invalid, _) => return Err(NOT_SIGNED.with_detail(2)),
+        };
+        Ok(SdPhoto { name: FileName::from_words([name_lo, name_hi]), bytes })
+    }
+}
+
+/// Decode 64 hex digits at compile time
+#[cfg(feature = "signed-photos")]
+const fn parse_key(hex: &str) -> [u8; ED25519_PUBLIC_KEY_SIZE] {
+    const fn digit(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => panic!("PHOTO_SIGNING_KEY is not hex"),
+        }
+    }
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 2 * ED25519_PUBLIC_KEY_SIZE, "PHOTO_SIGNING_KEY is not 64 hex digits");
+    let mut key = [0u8; ED25519_PUBLIC_KEY_SIZE];
+    let mut i = 0;
+    while i < key.len() {
+        key[i] = (digit(hex[2 * i]) << 4) | digit(hex[2 * i + 1]);
+        i += 1;
+    }
+    key
+}
+
+/// Make one protected procedure call; on success, the reply's message
+/// registers
+fn call(request: StorageRequest) -> Result<[u64; REPLY_WORDS], PdError> {
+    with_msg_regs_mut(|mrs| mrs[0] = request.arg());
+    let reply = Channel::new(STORAGE_CHANNEL_ID).pp_call(MessageInfo::new(request.label(), 1));
+    match reply.label() {
+        0 if reply.count() == REPLY_WORDS => {
+            let mut words = [0; REPLY_WORDS];
+            with_msg_regs(|mrs| words.copy_from_slice(&mrs[..REPLY_WORDS]));
+            Ok(words)
+        }
+        0 => Err(BAD_REPLY),
+        code => Err(PdError::from_wire(code as u32).unwrap_or(BAD_REPLY)),
+    }
+}
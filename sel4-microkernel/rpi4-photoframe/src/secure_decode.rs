@@ -25,6 +25,10 @@
 //!   ARGB32 pixels in caller-owned `output`
 //! ```
 //!
+//! Animated GIFs come back from [`secure_decode_animated`] with frame 0 in
+//! `output` and a [`GifDecoder`] for the rest. Its LZW table lives on the
+//! bounded heap, so it must be dropped before the next photo resets the heap.
+//!
 //! The heap referenced here is the process-wide `#[global_allocator]`, a
 //! [`crate::bounded_alloc::BoundedBumpAllocator`] with a fixed pool. Even a
 //! fully-compromised decoder cannot allocate past that cap, and — under seL4 —
@@ -32,6 +36,7 @@
 
 use crate::bounded_alloc::HeapControl;
 use crate::decoder::{self, DecodeError};
+use crate::gif::GifDecoder;
 use crate::validate::{self, ImageType, ValidatedImage, ValidationError};
 
 /// Outcome of a successful secure decode.
@@ -44,6 +49,10 @@ pub struct SecureDecodeResult {
     pub heap_used: usize,
     /// Peak heap bytes during decode.
     pub heap_peak: usize,
+    /// Frames in the image (1 for stills).
+    pub frame_count: u16,
+    /// How long to show the decoded frame in ms (0 for stills).
+    pub frame_delay_ms: u32,
 }
 
 /// Why a secure decode was rejected or failed.
@@ -77,11 +86,26 @@ impl From<DecodeError> for SecureDecodeError {
 /// `heap` is the bounded global allocator (passed as `&dyn HeapControl` so this
 /// stays independent of the heap's compile-time size). `output` must be large
 /// enough to hold `width * height` pixels.
+///
+/// Animated images decode to their first frame only.
 pub fn secure_decode_into(
     data: &[u8],
     output: &mut [u32],
     heap: &dyn HeapControl,
 ) -> Result<SecureDecodeResult, SecureDecodeError> {
+    secure_decode_animated(data, output, heap).map(|(result, _)| result)
+}
+
+/// Like [`secure_decode_into`], but also returns the decoder for the
+/// remaining frames when `data` is an animated GIF.
+///
+/// The decoder draws each further frame into the same `output` with
+/// [`GifDecoder::next_frame`]; frames composite onto the previous ones.
+pub fn secure_decode_animated<'a>(
+    data: &'a [u8],
+    output: &mut [u32],
+    heap: &dyn HeapControl,
+) -> Result<(SecureDecodeResult, Option<GifDecoder<'a>>), SecureDecodeError> {
     // ---- 1. Validate header (no allocation yet) ----------------------------
     let info: ValidatedImage = validate::validate_auto(data)?;
 
@@ -106,11 +130,21 @@ pub fn secure_decode_into(
     heap.reset();
 
     // ---- 4. Decode against the bounded allocator ---------------------------
+    let mut frames = (1, 0);
+    let mut animation = None;
     let decode_result = match info.format {
         ImageType::Jpeg => decoder::decode_jpeg(data, output),
         ImageType::Png => decoder::decode_png(data, output),
         ImageType::Bmp => decoder::decode_bmp(data, output),
         ImageType::Qoi => decoder::decode_qoi(data, output),
+        ImageType::Gif => decoder::decode_gif(data, output).map(|(gif, frame)| {
+            let size = (gif.width(), gif.height());
+            if gif.frame_count() > 1 {
+                frames = (gif.frame_count(), frame.delay_ms);
+                animation = Some(gif);
+            }
+            size
+        }),
         ImageType::Unknown => Err(DecodeError::UnsupportedFormat),
     };
 
@@ -126,11 +160,14 @@ pub fn secure_decode_into(
 
     let (width, height) = decode_result?;
 
-    Ok(SecureDecodeResult {
+    let result = SecureDecodeResult {
         width,
         height,
         format: info.format,
         heap_used: heap.used(),
         heap_peak: heap.peak(),
-    })
+        frame_count: frames.0,
+        frame_delay_ms: frames.1,
+    };
+    Ok((result, animation))
 }
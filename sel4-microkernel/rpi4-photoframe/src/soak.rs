@@ -0,0 +1,256 @@
+//! # Soak Test Mode
+//!
+//! Built with `--features soak`, the photo frame drives itself for as long
+//! as it is left running: a recorded key session is replayed in a loop
+//! (navigating, pausing, toggling the overlay, cycling every photo) while
+//! runtime invariants are checked after every frame:
+//!
+//! - **Input ring**: the shared header still satisfies the ring invariant
+//!   (capacity in range, both indices below it)
+//! - **Telemetry log**: its header is still valid
+//! - **Guard words**: sentinels past the decode scratch buffer and past the
+//!   drawn part of the framebuffer still hold their pattern
+//! - **Decoder heap**: usage within capacity, no allocation failure, and
+//!   free space above a low watermark
+//!
+//! This module holds the target-independent logic (replay, guards,
+//! violation bookkeeping); the PD feeds it readings and prints what it
+//! returns, so the host test crate can exercise it directly.
+//!
+//! ## Recording a Session
+//!
+//! Soak builds echo every key that arrives from the Input PD as
+//!
+//! ```text
+//! @in dt=12 key=4
+//! ```
+//!
+//! where `dt` is the number of timer ticks since the previous key and `key`
+//! the ring key code. A capture of those lines maps one-to-one onto
+//! [`ScriptStep`]s, so a session played by hand can replace [`SOAK_SCRIPT`].
+
+use rpi4_input_protocol::{KEY_DOWN, KEY_ENTER, KEY_ESCAPE, KEY_LEFT, KEY_RIGHT, KEY_SPACE, KEY_UP};
+
+/// Pattern written into guard words
+pub const GUARD_PATTERN: u32 = 0x50AC_6A2D;
+
+/// Guard words after the drawn part of the framebuffer
+pub const FB_GUARD_WORDS: usize = 16;
+
+/// Free decoder heap below which a decode counts as a violation
+pub const HEAP_LOW_WATERMARK: usize = 1024 * 1024;
+
+/// One replayed key: sent `ticks` timer ticks after the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptStep {
+    pub ticks: u16,
+    /// Ring key code (`rpi4_input_protocol::KEY_*`)
+    pub key: u8,
+}
+
+const fn step(ticks: u16, key: u8) -> ScriptStep {
+    ScriptStep { ticks, key }
+}
+
+/// Session replayed by default, at 10 ticks per second: browse forwards
+/// and backwards through every photo, pause and resume, toggle the overlay,
+/// then let the slideshow run on its own for a while before starting over
+pub const SOAK_SCRIPT: &[ScriptStep] = &[
+    step(20, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(15, KEY_RIGHT),
+    step(10, KEY_ENTER),
+    step(10, KEY_LEFT),
+    step(10, KEY_UP),
+    step(10, KEY_DOWN),
+    step(10, KEY_ENTER),
+    step(20, KEY_SPACE),
+    step(30, KEY_RIGHT),
+    step(30, KEY_SPACE),
+    step(5, KEY_RIGHT),
+    step(5, KEY_RIGHT),
+    step(5, KEY_RIGHT),
+    step(5, KEY_LEFT),
+    step(5, KEY_LEFT),
+    step(20, KEY_ESCAPE),
+    // Unattended slideshow: a few automatic advances
+    step(300, KEY_ENTER),
+    step(10, KEY_ENTER),
+];
+
+/// Replays a script in a loop, one timer tick at a time
+pub struct Replay {
+    script: &'static [ScriptStep],
+    /// Next step to send
+    pos: usize,
+    /// Ticks since the previous step was sent
+    waited: u16,
+    /// Times the whole script has been played
+    loops: u32,
+}
+
+impl Replay {
+    pub const fn new(script: &'static [ScriptStep]) -> Self {
+        Self { script, pos: 0, waited: 0, loops: 0 }
+    }
+
+    /// Advance one tick; returns the key due now, if any
+    pub fn tick(&mut self) -> Option<u8> {
+        let step = self.script.get(self.pos)?;
+        self.waited = self.waited.saturating_add(1);
+        if self.waited < step.ticks {
+            return None;
+        }
+        self.waited = 0;
+        self.pos += 1;
+        if self.pos == self.script.len() {
+            self.pos = 0;
+            self.loops = self.loops.wrapping_add(1);
+        }
+        Some(step.key)
+    }
+
+    /// Times the whole script has been played
+    pub fn loops(&self) -> u32 {
+        self.loops
+    }
+}
+
+/// Fill guard words with [`GUARD_PATTERN`]
+pub fn arm_guards(words: &mut [u32]) {
+    words.fill(GUARD_PATTERN);
+}
+
+/// First guard word that no longer holds the pattern, as (index, value)
+pub fn check_guards(words: &[u32]) -> Option<(usize, u32)> {
+    words
+        .iter()
+        .enumerate()
+        .find(|(_, &word)| word != GUARD_PATTERN)
+        .map(|(i, &word)| (i, word))
+}
+
+/// Invariant that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// Input ring header no longer satisfies the ring invariant
+    InputRing,
+    /// Telemetry log header is no longer valid
+    TelemetryLog,
+    /// A guard word past the decode scratch buffer was overwritten
+    ScratchGuard,
+    /// A guard word past the drawn framebuffer area was overwritten
+    FramebufferGuard,
+    /// Decoder heap usage beyond its capacity, or an allocation failed
+    Heap,
+    /// Decoder heap free space fell below [`HEAP_LOW_WATERMARK`]
+    HeapWatermark,
+}
+
+impl Violation {
+    /// Every kind, in counter order
+    pub const ALL: [Violation; 6] = [
+        Violation::InputRing,
+        Violation::TelemetryLog,
+        Violation::ScratchGuard,
+        Violation::FramebufferGuard,
+        Violation::Heap,
+        Violation::HeapWatermark,
+    ];
+
+    /// Name used in `@soak` console lines
+    pub fn name(self) -> &'static str {
+        match self {
+            Violation::InputRing => "input_ring",
+            Violation::TelemetryLog => "telemetry_log",
+            Violation::ScratchGuard => "scratch_guard",
+            Violation::FramebufferGuard => "fb_guard",
+            Violation::Heap => "heap",
+            Violation::HeapWatermark => "heap_watermark",
+        }
+    }
+}
+
+/// Decoder heap figures after a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapReading {
+    pub used: usize,
+    pub peak: usize,
+    pub capacity: usize,
+    pub oom: bool,
+}
+
+/// Violation counters and the heap watermark over a soak run
+pub struct Checker {
+    counts: [u32; Violation::ALL.len()],
+    /// Frames checked
+    frames: u32,
+    /// Lowest free decoder heap seen
+    heap_free_min: usize,
+}
+
+impl Checker {
+    pub const fn new() -> Self {
+        Self { counts: [0; Violation::ALL.len()], frames: 0, heap_free_min: usize::MAX }
+    }
+
+    /// Count `violation`; returns whether it should be logged
+    ///
+    /// The 1st, 2nd, 4th, 8th, ... occurrence of each kind is logged, so a
+    /// persistent fault cannot flood the console over a long run.
+    pub fn record(&mut self, violation: Violation) -> bool {
+        let count = &mut self.counts[violation as usize];
+        *count = count.saturating_add(1);
+        count.is_power_of_two()
+    }
+
+    /// Start checking a new frame
+    pub fn begin_frame(&mut self) {
+        self.frames = self.frames.wrapping_add(1);
+    }
+
+    /// Check the decoder heap and update the watermark
+    ///
+    /// The peak only ever grows, so falling below [`HEAP_LOW_WATERMARK`] is
+    /// reported when a new low is reached rather than on every frame after.
+    pub fn check_heap(&mut self, heap: HeapReading) -> Option<Violation> {
+        if heap.oom || heap.used > heap.capacity || heap.peak > heap.capacity {
+            return Some(Violation::Heap);
+        }
+        let free = heap.capacity - heap.peak;
+        let new_low = free < self.heap_free_min;
+        self.heap_free_min = self.heap_free_min.min(free);
+        (new_low && free < HEAP_LOW_WATERMARK).then_some(Violation::HeapWatermark)
+    }
+
+    /// Occurrences of `violation` so far
+    pub fn count(&self, violation: Violation) -> u32 {
+        self.counts[violation as usize]
+    }
+
+    /// Occurrences of every kind so far
+    pub fn total(&self) -> u32 {
+        self.counts.iter().fold(0, |sum, &n| sum.saturating_add(n))
+    }
+
+    /// Frames checked so far
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Lowest free decoder heap seen, if any frame has been checked
+    pub fn heap_free_min(&self) -> Option<usize> {
+        (self.heap_free_min != usize::MAX).then_some(self.heap_free_min)
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
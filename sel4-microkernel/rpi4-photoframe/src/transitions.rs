@@ -0,0 +1,109 @@
+//! Slideshow transitions between the previous and the next photo.
+//!
+//! When the photo changes, the outgoing photo is copied out of the photo
+//! layer and the incoming one is drawn into a second screen-sized buffer.
+//! A [`Transition`] then draws one mixed frame per timer tick (or per
+//! paced vertical blank, see [`frames_at`]) into the photo layer, ending on
+//! the new photo exactly, after which the layer holds it as if it had been
+//! drawn there directly.
+//!
+//! The per-pixel work is the verified `transition_frame` kernel in
+//! `rpi4-photo-protocol`: every effect (including the Ken Burns zoom,
+//! which samples the new photo at computed coordinates) is proven to index
+//! only inside the two source buffers and the destination.
+
+use rpi4_clock_protocol::TICK_HZ;
+use rpi4_photo_protocol::{transition_frame, BlitError, TRANSITION_END};
+
+pub use rpi4_photo_protocol::TransitionEffect as Effect;
+
+/// Frames a transition lasts by default (at the 10 Hz slideshow tick,
+/// 0.6 s)
+pub const TRANSITION_FRAMES: u32 = 6;
+
+/// Frames a transition lasts drawn at `frame_hz` rather than the tick, so
+/// it takes the same 0.6 s
+pub fn frames_at(frame_hz: u32) -> u32 {
+    (TRANSITION_FRAMES * frame_hz / TICK_HZ).max(1)
+}
+
+/// Effects in the order the slideshow cycles through them
+pub const EFFECTS: [Effect; 5] = [
+    Effect::Crossfade,
+    Effect::SlideLeft,
+    Effect::Wipe,
+    Effect::KenBurns,
+    Effect::SlideRight,
+];
+
+/// Effect for the `n`th photo change. Stepping back through the photos
+/// swaps the slide directions, so the new photo comes in from the side
+/// the user moved towards.
+pub fn effect_for(n: u32, backwards: bool) -> Effect {
+    let effect = EFFECTS[n as usize % EFFECTS.len()];
+    match (effect, backwards) {
+        (Effect::SlideLeft, true) => Effect::SlideRight,
+        (Effect::SlideRight, true) => Effect::SlideLeft,
+        _ => effect,
+    }
+}
+
+/// Console / overlay name of an effect
+pub fn effect_name(effect: Effect) -> &'static str {
+    match effect {
+        Effect::Crossfade => "CROSSFADE",
+        Effect::SlideLeft => "SLIDE LEFT",
+        Effect::SlideRight => "SLIDE RIGHT",
+        Effect::Wipe => "WIPE",
+        Effect::KenBurns => "KEN BURNS",
+    }
+}
+
+/// A transition in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    effect: Effect,
+    /// Frames drawn so far
+    frame: u32,
+    frames: u32,
+}
+
+impl Transition {
+    /// A transition over `frames` frames (at least one)
+    pub fn new(effect: Effect, frames: u32) -> Self {
+        Self { effect, frame: 0, frames: frames.max(1) }
+    }
+
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+
+    /// Has the final frame (the new photo alone) been drawn?
+    pub fn is_done(&self) -> bool {
+        self.frame == self.frames
+    }
+
+    /// Progress of the next frame, in the kernel's 1/256ths
+    fn next_progress(&self) -> u32 {
+        (self.frame + 1) * TRANSITION_END / self.frames
+    }
+
+    /// Draw the next frame from `from` to `to` into `dst`, all three
+    /// `width` x `height`. Returns whether more frames follow; a refused
+    /// frame ends the transition without touching `dst`.
+    pub fn step(
+        &mut self,
+        from: &[u32],
+        to: &[u32],
+        dst: &mut [u32],
+        width: u32,
+        height: u32,
+    ) -> Result<bool, BlitError> {
+        if self.is_done() {
+            return Ok(false);
+        }
+        let drawn = transition_frame(from, to, dst, width, height, self.effect, self.next_progress());
+        self.frame = if drawn.is_ok() { self.frame + 1 } else { self.frames };
+        drawn.map(|()| !self.is_done())
+    }
+}
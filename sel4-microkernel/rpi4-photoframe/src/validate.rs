@@ -49,6 +49,7 @@ pub enum ImageType {
     Png,
     Bmp,
     Qoi,
+    Gif,
     Unknown,
 }
 
@@ -226,6 +227,9 @@ pub fn validate_png(data: &[u8]) -> Result<ValidatedImage, ValidationError> {
     let output_size = (width as usize) * (height as usize) * 4;
     let decode_overhead = if interlace != 0 {
         output_size // Interlaced needs extra buffer
+    } else if bit_depth == 8 && matches!(color_type, 2 | 6) {
+        // Native streaming path: 32 KiB window + two RGBA-sized scanlines
+        32 * 1024 + 2 * (width as usize) * 4
     } else {
         1024 * 1024 // ~1MB for zlib state
     };
@@ -321,6 +325,51 @@ pub fn validate_qoi(data: &[u8]) -> Result<ValidatedImage, ValidationError> {
     })
 }
 
+// ============================================================================
+// GIF VALIDATION
+// ============================================================================
+
+/// GIF signatures (the version is part of the magic)
+const GIF_SIGNATURES: [&[u8]; 2] = [b"GIF87a", b"GIF89a"];
+
+/// Validate GIF header (logical screen descriptor).
+///
+/// Frames are composited onto a canvas the size of the logical screen, so
+/// that is the size validated; frame rectangles are clipped to it.
+pub fn validate_gif(data: &[u8]) -> Result<ValidatedImage, ValidationError> {
+    if data.len() < 13 {
+        return Err(ValidationError::TooSmall);
+    }
+
+    if !GIF_SIGNATURES.contains(&&data[0..6]) {
+        return Err(ValidationError::InvalidMagic);
+    }
+
+    let width = u16::from_le_bytes([data[6], data[7]]) as u32;
+    let height = u16::from_le_bytes([data[8], data[9]]) as u32;
+
+    if width == 0 || height == 0 {
+        return Err(ValidationError::ZeroDimension);
+    }
+    if width > MAX_WIDTH || height > MAX_HEIGHT {
+        return Err(ValidationError::TooLarge);
+    }
+    if (width as u64) * (height as u64) > MAX_PIXELS {
+        return Err(ValidationError::TooManyPixels);
+    }
+
+    // Estimate memory: output + LZW table + restore-to-previous save buffer
+    let output_size = (width as usize) * (height as usize) * 4;
+    let estimated_memory = output_size + crate::gif::GifDecoder::working_memory(width, height);
+
+    Ok(ValidatedImage {
+        width,
+        height,
+        format: ImageType::Gif,
+        estimated_memory,
+    })
+}
+
 // ============================================================================
 // AUTO-DETECT AND VALIDATE
 // ============================================================================
@@ -344,6 +393,9 @@ pub fn validate_auto(data: &[u8]) -> Result<ValidatedImage, ValidationError> {
     if &data[0..4] == b"qoif" {
         return validate_qoi(data);
     }
+    if &data[0..4] == b"GIF8" {
+        return validate_gif(data);
+    }
 
     Err(ValidationError::InvalidMagic)
 }
@@ -408,6 +460,23 @@ mod tests {
         assert_eq!(info.height, 50);
     }
 
+    #[test]
+    fn test_gif_validation() {
+        let mut data = b"GIF89a".to_vec();
+        data.extend_from_slice(&[0x40, 0x01, 0xF0, 0x00, 0x00, 0x00, 0x00]); // 320x240
+        let info = validate_auto(&data).unwrap();
+        assert_eq!((info.width, info.height, info.format), (320, 240, ImageType::Gif));
+        // Canvas, LZW table and save buffer
+        assert!(info.estimated_memory > 2 * info.output_size());
+
+        data[4] = b'8'; // "GIF88a"
+        assert_eq!(validate_gif(&data).err(), Some(ValidationError::InvalidMagic));
+        data[4] = b'7';
+        data[6] = 0;
+        data[7] = 0;
+        assert_eq!(validate_gif(&data).err(), Some(ValidationError::ZeroDimension));
+    }
+
     #[test]
     fn test_oversized_rejection() {
         // JPEG with dimensions exceeding MAX (padded past the length guard).
@@ -0,0 +1,41 @@
+//! # Weather Panel
+//!
+//! The current temperature and conditions, drawn in the top corner the
+//! clock is not in. The report comes from the Network PD's weather page
+//! (`rpi4_network_protocol::weather`), already range-checked by the time
+//! this PD sees it; this module only picks the corner and the text, so the
+//! host test crate can exercise it.
+//!
+//! ```text
+//!   ┌──────────────────────────── 40 px info bar ─┐
+//!   │  21°C                              12:34    │
+//!   │  CLOUDY                   FRI 16 OCT 2026   │
+//! ```
+
+use crate::clock::Corner;
+use core::fmt;
+use rpi4_network_protocol::weather::WeatherReport;
+
+/// Top corner for the panel: across from the clock, or where the clock
+/// would be when it is hidden. The clock's bottom corners leave both top
+/// ones free; the panel takes the one on the other side.
+pub fn weather_corner(clock: Option<Corner>) -> Corner {
+    match clock {
+        Some(Corner::TopRight | Corner::BottomRight) => Corner::TopLeft,
+        Some(Corner::TopLeft | Corner::BottomLeft) | None => Corner::TopRight,
+    }
+}
+
+/// The report to draw: only one the Network PD marked valid
+pub fn shown(report: Option<WeatherReport>) -> Option<WeatherReport> {
+    report.filter(|r| r.valid)
+}
+
+/// `21°C`
+pub struct TempText<'a>(pub &'a WeatherReport);
+
+impl fmt::Display for TempText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\u{B0}C", self.0.temp_c)
+    }
+}
@@ -0,0 +1,747 @@
+//! Verified persistent settings blob format.
+//!
+//! One format for every place settings are persisted: a file kept by the
+//! Storage PD and a TPM NV index. Both backends hand this crate an opaque
+//! byte blob (see [`SettingsStore`]); parsing, defaults and integrity
+//! checking live here, once.
+//!
+//! ## Layout
+//!
+//! All integers are little-endian.
+//!
+//! ```text
+//! Offset  Size  Field
+//! 0x00    4     magic        "SET1"
+//! 0x04    2     version      format version (1)
+//! 0x06    2     count        number of entries
+//! 0x08    4     payload_len  bytes of entries after the header
+//! 0x0C    4     crc32        CRC-32 (IEEE) of bytes 0x00..0x0C, then the payload
+//! 0x10    ...   entries
+//!
+//! Entry:
+//! 0x00    2     key
+//! 0x02    1     type         1 = u32, 2 = i32, 3 = bool, 4 = bytes
+//! 0x03    1     len          value length in bytes
+//! 0x04    len   value
+//! ```
+//!
+//! Entries carry their own length, so a reader skips keys it does not know
+//! (settings written by newer firmware) and a schema key with no entry, or
+//! with an entry of the wrong type, takes its default. A blob is accepted or
+//! rejected as a whole: a bad magic, unknown version, length mismatch or
+//! CRC failure means none of it is used.
+//!
+//! ## Verification
+//!
+//! Every read goes through [`read_u16_le`] / [`read_u32_le`] or a bounds
+//! check proven in [`parse_header`] and [`parse_entry`]: the header's
+//! payload must lie inside the blob, and every entry's value inside the
+//! payload, so the parser can never read past the blob length whatever the
+//! bytes say.
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::new_without_default)]
+
+use verus_builtin_macros::verus;
+
+verus! {
+
+pub const SETTINGS_MAGIC: u32 = 0x3154_4553; // "SET1"
+pub const SETTINGS_VERSION: u16 = 1;
+
+pub const HEADER_SIZE: usize = 16;
+pub const ENTRY_HEADER_SIZE: usize = 4;
+/// Bytes of the header covered by the CRC (everything before the CRC)
+pub const CRC_COVERED_HEADER: usize = 12;
+
+/// Largest blob: fits one TPM NV index and one Storage-PD sector pair
+pub const MAX_BLOB_SIZE: usize = 1024;
+/// Largest byte-string value
+pub const MAX_VALUE_LEN: usize = 64;
+/// Most keys a schema may define
+pub const MAX_SETTINGS: usize = 32;
+
+pub const TYPE_U32: u8 = 1;
+pub const TYPE_I32: u8 = 2;
+pub const TYPE_BOOL: u8 = 3;
+pub const TYPE_BYTES: u8 = 4;
+
+pub open spec fn valid_type(kind: u8) -> bool {
+    kind == TYPE_U32 || kind == TYPE_I32 || kind == TYPE_BOOL || kind == TYPE_BYTES
+}
+
+/// Why a blob was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsError {
+    /// Shorter than the header
+    TooShort,
+    BadMagic,
+    UnsupportedVersion(u16),
+    /// The header claims more payload than the blob holds
+    Truncated,
+    BadCrc { stored: u32, computed: u32 },
+    /// Entry at this payload offset runs past the payload or has an
+    /// impossible length for its type
+    BadEntry { offset: usize },
+    /// The header's entry count disagrees with the payload
+    CountMismatch,
+    /// Encoding would not fit the output buffer or [`MAX_BLOB_SIZE`]
+    TooLarge,
+    /// No such key in the schema
+    UnknownKey(u16),
+    /// Value type differs from the schema's
+    WrongType(u16),
+}
+
+/// Little-endian u16 at `offset`
+pub fn read_u16_le(buf: &[u8], offset: usize) -> (value: u16)
+    requires
+        offset + 2 <= buf.len(),
+{
+    (buf[offset] as u16) | ((buf[offset + 1] as u16) << 8)
+}
+
+/// Little-endian u32 at `offset`
+pub fn read_u32_le(buf: &[u8], offset: usize) -> (value: u32)
+    requires
+        offset + 4 <= buf.len(),
+{
+    (buf[offset] as u32)
+        | ((buf[offset + 1] as u32) << 8)
+        | ((buf[offset + 2] as u32) << 16)
+        | ((buf[offset + 3] as u32) << 24)
+}
+
+/// Length a value of `kind` must have, or `None` for variable-length bytes
+pub fn fixed_len(kind: u8) -> (len: Option<usize>)
+    ensures
+        kind == TYPE_U32 || kind == TYPE_I32 ==> len == Some(4usize),
+        kind == TYPE_BOOL ==> len == Some(1usize),
+{
+    if kind == TYPE_U32 || kind == TYPE_I32 {
+        Some(4)
+    } else if kind == TYPE_BOOL {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Decoded blob header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SettingsHeader {
+    pub version: u16,
+    pub count: u16,
+    pub payload_len: u32,
+    pub crc: u32,
+}
+
+/// Parse and bounds-check the header of `blob`
+///
+/// On success the payload `[HEADER_SIZE, HEADER_SIZE + payload_len)` lies
+/// inside the blob. The CRC is not checked here (see [`check_blob`]).
+pub fn parse_header(blob: &[u8]) -> (result: Result<SettingsHeader, SettingsError>)
+    ensures
+        match result {
+            Ok(header) => HEADER_SIZE + header.payload_len <= blob.len()
+                && header.version == SETTINGS_VERSION,
+            Err(_) => true,
+        },
+{
+    if blob.len() < HEADER_SIZE {
+        return Err(SettingsError::TooShort);
+    }
+    if read_u32_le(blob, 0) != SETTINGS_MAGIC {
+        return Err(SettingsError::BadMagic);
+    }
+    let version = read_u16_le(blob, 4);
+    if version != SETTINGS_VERSION {
+        return Err(SettingsError::UnsupportedVersion(version));
+    }
+    let count = read_u16_le(blob, 6);
+    let payload_len = read_u32_le(blob, 8);
+    if payload_len as usize > blob.len() - HEADER_SIZE {
+        return Err(SettingsError::Truncated);
+    }
+    let crc = read_u32_le(blob, 12);
+    Ok(SettingsHeader { version, count, payload_len, crc })
+}
+
+/// One entry's header, with the value located in the payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawEntry {
+    pub key: u16,
+    pub kind: u8,
+    /// Payload offset of the value
+    pub value_offset: usize,
+    pub value_len: usize,
+}
+
+impl RawEntry {
+    /// Payload offset just past this entry
+    pub fn end(&self) -> (end: usize)
+        requires
+            self.value_offset + self.value_len <= usize::MAX,
+        ensures
+            end == self.value_offset + self.value_len,
+    {
+        self.value_offset + self.value_len
+    }
+}
+
+/// Parse the entry at `offset` of `payload`
+///
+/// On success the entry's value lies inside the payload, past `offset`, so
+/// a loop of calls advances and ends at the payload length.
+pub fn parse_entry(payload: &[u8], offset: usize) -> (result: Result<RawEntry, SettingsError>)
+    requires
+        offset < payload.len(),
+    ensures
+        match result {
+            Ok(entry) => entry.value_offset == offset + ENTRY_HEADER_SIZE
+                && entry.value_offset + entry.value_len <= payload.len(),
+            Err(_) => true,
+        },
+{
+    if payload.len() - offset < ENTRY_HEADER_SIZE {
+        return Err(SettingsError::BadEntry { offset });
+    }
+    let key = read_u16_le(payload, offset);
+    let kind = payload[offset + 2];
+    let value_len = payload[offset + 3] as usize;
+    let value_offset = offset + ENTRY_HEADER_SIZE;
+    if value_len > payload.len() - value_offset {
+        return Err(SettingsError::BadEntry { offset });
+    }
+    // Known types must have their exact size; unknown ones are skipped
+    match fixed_len(kind) {
+        Some(len) => {
+            if value_len != len {
+                return Err(SettingsError::BadEntry { offset });
+            }
+        }
+        None => {
+            if kind == TYPE_BYTES && value_len > MAX_VALUE_LEN {
+                return Err(SettingsError::BadEntry { offset });
+            }
+        }
+    }
+    Ok(RawEntry { key, kind, value_offset, value_len })
+}
+
+} // verus!
+
+// ============================================================================
+// CRC-32
+// ============================================================================
+
+/// CRC-32 (IEEE 802.3, reflected, as in zlib and Ethernet)
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32(u32);
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+impl Crc32 {
+    pub const fn new() -> Self {
+        Self(0xFFFF_FFFF)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = CRC32_TABLE[((self.0 ^ byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32 of `bytes`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
+
+/// CRC a blob should carry: the header fields before the CRC, then the
+/// payload
+fn blob_crc(header_prefix: &[u8], payload: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(header_prefix);
+    crc.update(payload);
+    crc.finish()
+}
+
+/// Check a blob's header and CRC; returns the header and the payload
+pub fn check_blob(blob: &[u8]) -> Result<(SettingsHeader, &[u8]), SettingsError> {
+    let header = parse_header(blob)?;
+    let payload = &blob[HEADER_SIZE..HEADER_SIZE + header.payload_len as usize];
+    let computed = blob_crc(&blob[..CRC_COVERED_HEADER], payload);
+    if computed != header.crc {
+        return Err(SettingsError::BadCrc { stored: header.crc, computed });
+    }
+    Ok((header, payload))
+}
+
+// ============================================================================
+// TYPED SETTINGS
+// ============================================================================
+
+/// A byte-string value of at most [`MAX_VALUE_LEN`] bytes
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SettingBytes {
+    len: u8,
+    data: [u8; MAX_VALUE_LEN],
+}
+
+impl SettingBytes {
+    /// `None` if `bytes` is longer than [`MAX_VALUE_LEN`]
+    pub const fn new(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > MAX_VALUE_LEN {
+            return None;
+        }
+        let mut data = [0; MAX_VALUE_LEN];
+        let mut i = 0;
+        while i < bytes.len() {
+            data[i] = bytes[i];
+            i += 1;
+        }
+        Some(Self { len: bytes.len() as u8, data })
+    }
+
+    pub const fn empty() -> Self {
+        Self { len: 0, data: [0; MAX_VALUE_LEN] }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+}
+
+impl core::fmt::Debug for SettingBytes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+/// A setting's value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingValue {
+    U32(u32),
+    I32(i32),
+    Bool(bool),
+    Bytes(SettingBytes),
+}
+
+impl SettingValue {
+    /// Wire type tag
+    pub fn kind(&self) -> u8 {
+        match self {
+            SettingValue::U32(_) => TYPE_U32,
+            SettingValue::I32(_) => TYPE_I32,
+            SettingValue::Bool(_) => TYPE_BOOL,
+            SettingValue::Bytes(_) => TYPE_BYTES,
+        }
+    }
+
+    /// Decode a value of `kind`; `None` for unknown types or a bool that is
+    /// neither 0 nor 1
+    fn decode(kind: u8, value: &[u8]) -> Option<Self> {
+        match (kind, value) {
+            (TYPE_U32, &[a, b, c, d]) => Some(SettingValue::U32(u32::from_le_bytes([a, b, c, d]))),
+            (TYPE_I32, &[a, b, c, d]) => Some(SettingValue::I32(i32::from_le_bytes([a, b, c, d]))),
+            (TYPE_BOOL, &[0]) => Some(SettingValue::Bool(false)),
+            (TYPE_BOOL, &[1]) => Some(SettingValue::Bool(true)),
+            (TYPE_BYTES, bytes) => SettingBytes::new(bytes).map(SettingValue::Bytes),
+            _ => None,
+        }
+    }
+
+    /// Encoded value bytes into `out`; returns the length
+    fn encode(&self, out: &mut [u8; MAX_VALUE_LEN]) -> usize {
+        match self {
+            SettingValue::U32(v) => {
+                out[..4].copy_from_slice(&v.to_le_bytes());
+                4
+            }
+            SettingValue::I32(v) => {
+                out[..4].copy_from_slice(&v.to_le_bytes());
+                4
+            }
+            SettingValue::Bool(v) => {
+                out[0] = *v as u8;
+                1
+            }
+            SettingValue::Bytes(bytes) => {
+                let bytes = bytes.as_slice();
+                out[..bytes.len()].copy_from_slice(bytes);
+                bytes.len()
+            }
+        }
+    }
+}
+
+/// One setting a component knows about, and its value when the blob has
+/// none
+#[derive(Clone, Copy, Debug)]
+pub struct SettingDef {
+    pub key: u16,
+    pub name: &'static str,
+    pub default: SettingValue,
+}
+
+/// What loading a blob found
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Schema keys read from the blob
+    pub loaded: u16,
+    /// Schema keys left at their default (missing or of the wrong type)
+    pub defaulted: u16,
+    /// Entries for keys this schema does not define
+    pub skipped: u16,
+}
+
+/// Settings of one schema, each either loaded or defaulted
+#[derive(Clone, Copy, Debug)]
+pub struct Settings<'s> {
+    schema: &'s [SettingDef],
+    values: [SettingValue; MAX_SETTINGS],
+}
+
+impl<'s> Settings<'s> {
+    /// Every setting at its default
+    ///
+    /// # Panics
+    /// If the schema has more than [`MAX_SETTINGS`] keys.
+    pub fn defaults(schema: &'s [SettingDef]) -> Self {
+        assert!(schema.len() <= MAX_SETTINGS, "settings schema too large");
+        let mut values = [SettingValue::Bool(false); MAX_SETTINGS];
+        for (value, def) in values.iter_mut().zip(schema) {
+            *value = def.default;
+        }
+        Self { schema, values }
+    }
+
+    /// Load a blob: check it, then take every schema key it holds with the
+    /// right type and default the rest
+    pub fn load(schema: &'s [SettingDef], blob: &[u8]) -> Result<(Self, LoadReport), SettingsError> {
+        let (header, payload) = check_blob(blob)?;
+        let mut settings = Self::defaults(schema);
+        let mut found = [false; MAX_SETTINGS];
+        let mut report = LoadReport::default();
+
+        let mut offset = 0;
+        let mut entries = 0u16;
+        while offset < payload.len() {
+            let entry = parse_entry(payload, offset)?;
+            let value = &payload[entry.value_offset..entry.end()];
+            match settings.index_of(entry.key) {
+                Some(i) => match SettingValue::decode(entry.kind, value) {
+                    Some(v) if v.kind() == schema[i].default.kind() && !found[i] => {
+                        settings.values[i] = v;
+                        found[i] = true;
+                        report.loaded += 1;
+                    }
+                    _ => {}
+                },
+                None => report.skipped = report.skipped.saturating_add(1),
+            }
+            entries = entries.checked_add(1).ok_or(SettingsError::CountMismatch)?;
+            offset = entry.end();
+        }
+        if entries != header.count {
+            return Err(SettingsError::CountMismatch);
+        }
+        report.defaulted = schema.len() as u16 - report.loaded;
+        Ok((settings, report))
+    }
+
+    /// [`load`](Self::load), falling back to all defaults if the blob is
+    /// rejected (blank NV index, corrupted file)
+    pub fn load_or_defaults(schema: &'s [SettingDef], blob: &[u8]) -> Self {
+        Self::load(schema, blob).map_or_else(|_| Self::defaults(schema), |(settings, _)| settings)
+    }
+
+    fn index_of(&self, key: u16) -> Option<usize> {
+        self.schema.iter().position(|def| def.key == key)
+    }
+
+    /// The schema these settings follow
+    pub fn schema(&self) -> &'s [SettingDef] {
+        self.schema
+    }
+
+    pub fn get(&self, key: u16) -> Option<SettingValue> {
+        self.index_of(key).map(|i| self.values[i])
+    }
+
+    pub fn get_u32(&self, key: u16) -> Option<u32> {
+        match self.get(key)? {
+            SettingValue::U32(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn get_i32(&self, key: u16) -> Option<i32> {
+        match self.get(key)? {
+            SettingValue::I32(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn get_bool(&self, key: u16) -> Option<bool> {
+        match self.get(key)? {
+            SettingValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn get_bytes(&self, key: u16) -> Option<SettingBytes> {
+        match self.get(key)? {
+            SettingValue::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Change a setting; the value must have the schema's type
+    pub fn set(&mut self, key: u16, value: SettingValue) -> Result<(), SettingsError> {
+        let i = self.index_of(key).ok_or(SettingsError::UnknownKey(key))?;
+        if value.kind() != self.schema[i].default.kind() {
+            return Err(SettingsError::WrongType(key));
+        }
+        self.values[i] = value;
+        Ok(())
+    }
+
+    /// Encode every setting into `out`; returns the blob length
+    pub fn encode(&self, out: &mut [u8]) -> Result<usize, SettingsError> {
+        let limit = out.len().min(MAX_BLOB_SIZE);
+        let mut len = HEADER_SIZE;
+        let mut value = [0u8; MAX_VALUE_LEN];
+        for (def, setting) in self.schema.iter().zip(&self.values) {
+            let value_len = setting.encode(&mut value);
+            let end = len + ENTRY_HEADER_SIZE + value_len;
+            if end > limit {
+                return Err(SettingsError::TooLarge);
+            }
+            out[len..len + 2].copy_from_slice(&def.key.to_le_bytes());
+            out[len + 2] = setting.kind();
+            out[len + 3] = value_len as u8;
+            out[len + ENTRY_HEADER_SIZE..end].copy_from_slice(&value[..value_len]);
+            len = end;
+        }
+
+        out[0..4].copy_from_slice(&SETTINGS_MAGIC.to_le_bytes());
+        out[4..6].copy_from_slice(&SETTINGS_VERSION.to_le_bytes());
+        out[6..8].copy_from_slice(&(self.schema.len() as u16).to_le_bytes());
+        out[8..12].copy_from_slice(&((len - HEADER_SIZE) as u32).to_le_bytes());
+        let crc = blob_crc(&out[..CRC_COVERED_HEADER], &out[HEADER_SIZE..len]);
+        out[12..16].copy_from_slice(&crc.to_le_bytes());
+        Ok(len)
+    }
+}
+
+// ============================================================================
+// PERSISTENCE BACKENDS
+// ============================================================================
+
+/// Where a settings blob is kept: a Storage-PD file or a TPM NV index
+///
+/// Backends move opaque bytes only; the format above is the same for all
+/// of them, so settings can migrate between backends unchanged.
+pub trait SettingsStore {
+    type Error;
+
+    /// Read the stored blob into `buf`; returns its length (0 if nothing
+    /// has been stored yet)
+    fn read_blob(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Replace the stored blob
+    fn write_blob(&mut self, blob: &[u8]) -> Result<(), Self::Error>;
+
+    /// Load settings from this store, defaulting everything if the store is
+    /// empty, unreadable or holds a rejected blob
+    fn load_settings<'s>(&mut self, schema: &'s [SettingDef]) -> Settings<'s> {
+        let mut buf = [0u8; MAX_BLOB_SIZE];
+        match self.read_blob(&mut buf) {
+            Ok(len) => Settings::load_or_defaults(schema, &buf[..len.min(MAX_BLOB_SIZE)]),
+            Err(_) => Settings::defaults(schema),
+        }
+    }
+
+    /// Encode and store `settings`
+    fn save_settings(&mut self, settings: &Settings<'_>) -> Result<(), SaveError<Self::Error>> {
+        let mut buf = [0u8; MAX_BLOB_SIZE];
+        let len = settings.encode(&mut buf).map_err(SaveError::Encode)?;
+        self.write_blob(&buf[..len]).map_err(SaveError::Store)
+    }
+}
+
+/// Why [`SettingsStore::save_settings`] failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaveError<E> {
+    Encode(SettingsError),
+    Store(E),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_BRIGHTNESS: u16 = 1;
+    const KEY_TZ_OFFSET: u16 = 2;
+    const KEY_AUTO_DIM: u16 = 3;
+    const KEY_HOSTNAME: u16 = 4;
+
+    const SCHEMA: &[SettingDef] = &[
+        SettingDef { key: KEY_BRIGHTNESS, name: "brightness", default: SettingValue::U32(200) },
+        SettingDef { key: KEY_TZ_OFFSET, name: "tz_offset_min", default: SettingValue::I32(0) },
+        SettingDef { key: KEY_AUTO_DIM, name: "auto_dim", default: SettingValue::Bool(true) },
+        SettingDef {
+            key: KEY_HOSTNAME,
+            name: "hostname",
+            default: SettingValue::Bytes(match SettingBytes::new(b"sel4-frame") {
+                Some(bytes) => bytes,
+                None => SettingBytes::empty(),
+            }),
+        },
+    ];
+
+    fn sample_blob() -> ([u8; MAX_BLOB_SIZE], usize) {
+        let mut settings = Settings::defaults(SCHEMA);
+        settings.set(KEY_BRIGHTNESS, SettingValue::U32(64)).unwrap();
+        settings.set(KEY_TZ_OFFSET, SettingValue::I32(-300)).unwrap();
+        let mut blob = [0u8; MAX_BLOB_SIZE];
+        let len = settings.encode(&mut blob).unwrap();
+        (blob, len)
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (blob, len) = sample_blob();
+        let (settings, report) = Settings::load(SCHEMA, &blob[..len]).unwrap();
+        assert_eq!(report, LoadReport { loaded: 4, defaulted: 0, skipped: 0 });
+        assert_eq!(settings.get_u32(KEY_BRIGHTNESS), Some(64));
+        assert_eq!(settings.get_i32(KEY_TZ_OFFSET), Some(-300));
+        assert_eq!(settings.get_bool(KEY_AUTO_DIM), Some(true));
+        assert_eq!(settings.get_bytes(KEY_HOSTNAME).unwrap().as_slice(), b"sel4-frame");
+    }
+
+    #[test]
+    fn test_missing_and_unknown_keys() {
+        // Written by a schema with a key this one lacks, and without two of ours
+        const OLD: &[SettingDef] = &[
+            SettingDef { key: KEY_BRIGHTNESS, name: "brightness", default: SettingValue::U32(10) },
+            SettingDef { key: 99, name: "retired", default: SettingValue::U32(7) },
+            SettingDef { key: KEY_AUTO_DIM, name: "auto_dim", default: SettingValue::U32(1) },
+        ];
+        let mut blob = [0u8; MAX_BLOB_SIZE];
+        let len = Settings::defaults(OLD).encode(&mut blob).unwrap();
+
+        let (settings, report) = Settings::load(SCHEMA, &blob[..len]).unwrap();
+        // auto_dim was stored as a u32, so ours stays at its default
+        assert_eq!(report, LoadReport { loaded: 1, defaulted: 3, skipped: 1 });
+        assert_eq!(settings.get_u32(KEY_BRIGHTNESS), Some(10));
+        assert_eq!(settings.get_bool(KEY_AUTO_DIM), Some(true));
+        assert_eq!(settings.get_i32(KEY_TZ_OFFSET), Some(0));
+    }
+
+    #[test]
+    fn test_corruption_is_rejected() {
+        let (blob, len) = sample_blob();
+        for i in 0..len {
+            let mut bad = blob;
+            bad[i] ^= 0x10;
+            assert!(Settings::load(SCHEMA, &bad[..len]).is_err(), "flip at {}", i);
+        }
+        assert_eq!(
+            Settings::load_or_defaults(SCHEMA, &[0xFF; 64]).get_u32(KEY_BRIGHTNESS),
+            Some(200)
+        );
+    }
+
+    #[test]
+    fn test_truncation_never_reads_past_blob() {
+        let (blob, len) = sample_blob();
+        for cut in 0..len {
+            assert!(Settings::load(SCHEMA, &blob[..cut]).is_err());
+        }
+        // A header that claims a longer payload than the blob holds
+        let mut bad = blob;
+        bad[8..12].copy_from_slice(&(MAX_BLOB_SIZE as u32).to_le_bytes());
+        assert_eq!(parse_header(&bad[..len]), Err(SettingsError::Truncated));
+    }
+
+    #[test]
+    fn test_entry_past_payload() {
+        let payload = [KEY_BRIGHTNESS as u8, 0, TYPE_BYTES, 10, 1, 2, 3];
+        assert_eq!(parse_entry(&payload, 0), Err(SettingsError::BadEntry { offset: 0 }));
+        let payload = [KEY_BRIGHTNESS as u8, 0, TYPE_U32, 2, 1, 2];
+        assert_eq!(parse_entry(&payload, 0), Err(SettingsError::BadEntry { offset: 0 }));
+    }
+
+    #[test]
+    fn test_set_checks_schema() {
+        let mut settings = Settings::defaults(SCHEMA);
+        assert_eq!(settings.set(KEY_BRIGHTNESS, SettingValue::Bool(true)), Err(SettingsError::WrongType(KEY_BRIGHTNESS)));
+        assert_eq!(settings.set(42, SettingValue::U32(1)), Err(SettingsError::UnknownKey(42)));
+        let mut small = [0u8; HEADER_SIZE + 4];
+        assert_eq!(settings.encode(&mut small), Err(SettingsError::TooLarge));
+    }
+
+    #[test]
+    fn test_store_falls_back_to_defaults() {
+        struct Nv {
+            blob: [u8; MAX_BLOB_SIZE],
+            len: usize,
+        }
+        impl SettingsStore for Nv {
+            type Error = ();
+            fn read_blob(&mut self, buf: &mut [u8]) -> Result<usize, ()> {
+                buf[..self.len].copy_from_slice(&self.blob[..self.len]);
+                Ok(self.len)
+            }
+            fn write_blob(&mut self, blob: &[u8]) -> Result<(), ()> {
+                self.blob[..blob.len()].copy_from_slice(blob);
+                self.len = blob.len();
+                Ok(())
+            }
+        }
+
+        let mut nv = Nv { blob: [0; MAX_BLOB_SIZE], len: 0 };
+        let mut settings = nv.load_settings(SCHEMA);
+        assert_eq!(settings.get_u32(KEY_BRIGHTNESS), Some(200));
+        settings.set(KEY_BRIGHTNESS, SettingValue::U32(33)).unwrap();
+        nv.save_settings(&settings).unwrap();
+        assert_eq!(nv.load_settings(SCHEMA).get_u32(KEY_BRIGHTNESS), Some(33));
+    }
+}
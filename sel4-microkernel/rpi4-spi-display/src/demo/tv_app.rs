@@ -4,7 +4,7 @@
 
 use crate::display::{Framebuffer, Rgb565};
 use crate::input::{InputEvent, KeyCode, KeyState, IrButton};
-use crate::touch::TouchEvent;
+use crate::touch::{Gesture, GestureRecognizer, SwipeDirection, TouchEvent, TouchPoint};
 
 use super::animation::{AnimationPlayer, AnimationType};
 use super::menu::{Menu, MenuItem, MenuStyle};
@@ -71,6 +71,8 @@ pub struct TvDemo {
     overlay_timer: u16,
     /// Dark theme enabled
     dark_theme: bool,
+    /// Touch gesture recognizer
+    gestures: GestureRecognizer,
 }
 
 impl TvDemo {
@@ -86,6 +88,7 @@ impl TvDemo {
             show_overlay: false,
             overlay_timer: 0,
             dark_theme: true,
+            gestures: GestureRecognizer::new(),
         };
 
         demo.setup_menus();
@@ -196,57 +199,80 @@ impl TvDemo {
 
     /// Handle touch input
     fn handle_touch(&mut self, event: TouchEvent) {
-        match event {
-            TouchEvent::Down(point) => {
-                // Show overlay when touched during playback
-                if self.state == DemoState::Playing {
-                    self.show_overlay = true;
-                    self.overlay_timer = 180; // 3 seconds at 60fps
-                }
+        // Show overlay when touched during playback
+        if let TouchEvent::Down(_) = event {
+            if self.state == DemoState::Playing {
+                self.show_overlay = true;
+                self.overlay_timer = 180; // 3 seconds at 60fps
+            }
+        }
+
+        if let Some(gesture) = self.gestures.feed(event) {
+            self.handle_gesture(gesture);
+        }
+    }
 
-                // Simple touch zones for menu navigation
-                match self.state {
-                    DemoState::Menu | DemoState::Settings => {
-                        // Calculate which menu item was touched
-                        let menu = self.current_menu();
-                        if let Some(menu) = menu {
-                            let item_height = 32u16;
-                            let start_y = 40u16;
-                            let item_idx = (point.y.saturating_sub(start_y)) / item_height;
-
-                            if (item_idx as usize) < menu.item_count() {
-                                // Navigate to item and select
-                                while menu.selected_index() != item_idx as usize {
-                                    if menu.selected_index() < item_idx as usize {
-                                        menu.move_down();
-                                    } else {
-                                        menu.move_up();
-                                    }
-                                }
-                            }
-                        }
+    /// Handle a recognized touch gesture
+    ///
+    /// Menus: swipe up/down moves the highlight, swipe right or double-tap
+    /// selects, swipe left or long-press goes back, and a tap highlights the
+    /// touched item. Playback: tap zones and swipes left/right switch
+    /// animations, swipe down stops.
+    fn handle_gesture(&mut self, gesture: Gesture) {
+        match self.state {
+            DemoState::Menu | DemoState::Settings => match gesture {
+                Gesture::Swipe { direction, .. } => match direction {
+                    SwipeDirection::Up => self.handle_menu_key(KeyCode::Up),
+                    SwipeDirection::Down => self.handle_menu_key(KeyCode::Down),
+                    SwipeDirection::Right => self.handle_menu_key(KeyCode::Enter),
+                    SwipeDirection::Left => self.handle_menu_key(KeyCode::Escape),
+                },
+                Gesture::Tap(point) => self.highlight_touched_item(point),
+                Gesture::DoubleTap(point) => {
+                    self.highlight_touched_item(point);
+                    self.select_current_item();
+                }
+                Gesture::LongPress(_) => self.go_back(),
+                Gesture::Drag { .. } | Gesture::DragEnd(_) => {}
+            },
+            DemoState::Playing | DemoState::Paused => match gesture {
+                Gesture::Tap(point) => {
+                    // Touch center to toggle play/pause
+                    if point.x > 100 && point.x < 220 && point.y > 80 && point.y < 160 {
+                        self.toggle_playback();
+                    }
+                    // Touch left side for previous
+                    else if point.x < 80 {
+                        self.player.prev();
                     }
-                    DemoState::Playing | DemoState::Paused => {
-                        // Touch center to toggle play/pause
-                        if point.x > 100 && point.x < 220 && point.y > 80 && point.y < 160 {
-                            self.toggle_playback();
-                        }
-                        // Touch left side for previous
-                        else if point.x < 80 {
-                            self.player.prev();
-                        }
-                        // Touch right side for next
-                        else if point.x > 240 {
-                            self.player.next();
-                        }
+                    // Touch right side for next
+                    else if point.x > 240 {
+                        self.player.next();
+                    }
+                }
+                Gesture::Swipe { direction: SwipeDirection::Left, .. } => self.player.next(),
+                Gesture::Swipe { direction: SwipeDirection::Right, .. } => self.player.prev(),
+                Gesture::Swipe { direction: SwipeDirection::Down, .. } => self.stop_playback(),
+                _ => {}
+            },
+        }
+    }
+
+    /// Move the menu highlight to the item under a touch point
+    fn highlight_touched_item(&mut self, point: TouchPoint) {
+        if let Some(menu) = self.current_menu() {
+            let item_height = 32u16;
+            let start_y = 40u16;
+            let item_idx = (point.y.saturating_sub(start_y)) / item_height;
+
+            if (item_idx as usize) < menu.item_count() {
+                while menu.selected_index() != item_idx as usize {
+                    if menu.selected_index() < item_idx as usize {
+                        menu.move_down();
+                    } else {
+                        menu.move_up();
                     }
                 }
-            }
-            TouchEvent::Up => {
-                // Could trigger selection on release
-            }
-            TouchEvent::Move(_) => {
-                // Could implement swipe gestures
             }
         }
     }
@@ -412,6 +438,11 @@ impl TvDemo {
 
     /// Update application state (call each frame)
     pub fn update(&mut self) {
+        // Long-presses fire while the finger is still down
+        if let Some(gesture) = self.gestures.tick() {
+            self.handle_gesture(gesture);
+        }
+
         // Update overlay timer
         if self.overlay_timer > 0 {
             self.overlay_timer -= 1;
@@ -0,0 +1,139 @@
+//! RGBA8888 → RGB565 Color Conversion with Verus Verification
+//!
+//! Decoders hand out 8 bits per channel; the panel takes 5-6-5. Truncating
+//! each channel throws away the low bits, which shows as visible bands in
+//! smooth gradients (skies, skin). This module converts with optional
+//! ordered dithering, which spreads the rounding error over a 4×4 Bayer
+//! pattern so the eye averages neighbouring pixels back to the original
+//! shade, and optional gamma correction through a 256-entry lookup table
+//! for panels whose response does not match the sRGB content.
+//!
+//! ```text
+//! RGBA8888 ──► gamma LUT ──► + Bayer offset ──► truncate ──► RGB565
+//!              (optional)     (optional)        5/6/5 bits
+//! ```
+//!
+//! Alpha is ignored: pixels are taken as opaque. Composite translucent
+//! images before converting.
+//!
+//! # Verification Properties
+//!
+//! The Bayer threshold, channel dithering and gamma tables are the
+//! verified ones in `verified-microkernel` (host-tested there):
+//!
+//! - Every Bayer and gamma table index is in bounds, whatever the pixel
+//!   coordinates or channel values
+//! - Dithered channels stay within 5 or 6 bits, so they never spill into
+//!   the neighbouring channel of the packed pixel
+//!
+//! Here, row and image conversion never read or write past either buffer.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::framebuffer::Rgb565;
+use verified_microkernel::rgb565::rgb888_to_rgb565_dithered;
+
+pub use verified_microkernel::rgb565::{bayer_threshold, dither_channel, GammaLut, BAYER_4X4};
+
+/// Bytes per RGBA8888 pixel
+pub const RGBA_BYTES: usize = 4;
+
+/// How to round channels down to 5-6-5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    /// Truncate, as [`Rgb565::from_rgb`] does
+    None,
+    /// 4×4 ordered (Bayer) dithering
+    Ordered,
+}
+
+/// RGBA8888 → RGB565 converter
+#[derive(Clone, Copy)]
+pub struct ColorConverter {
+    dither: Dither,
+    gamma: Option<GammaLut>,
+}
+
+impl ColorConverter {
+    /// Plain truncation, no gamma correction
+    pub const fn new() -> Self {
+        Self { dither: Dither::None, gamma: None }
+    }
+
+    /// Ordered dithering, no gamma correction: the choice for photos
+    pub const fn dithered() -> Self {
+        Self { dither: Dither::Ordered, gamma: None }
+    }
+
+    pub const fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    pub const fn with_gamma(mut self, gamma: GammaLut) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Convert one pixel at (x, y); the position picks the dither threshold
+    #[verus_verify]
+    pub fn convert(&self, r: u8, g: u8, b: u8, x: usize, y: usize) -> Rgb565 {
+        let (r, g, b) = match self.gamma {
+            Some(ref lut) => (lut.apply(r), lut.apply(g), lut.apply(b)),
+            None => (r, g, b),
+        };
+        match self.dither {
+            Dither::None => Rgb565::from_rgb(r, g, b),
+            Dither::Ordered => Rgb565(rgb888_to_rgb565_dithered(r, g, b, bayer_threshold(x, y))),
+        }
+    }
+
+    /// Convert one row of RGBA8888 bytes starting at column `x0` of row `y`
+    ///
+    /// Converts as many whole pixels as both buffers hold and returns that
+    /// count.
+    #[verus_verify]
+    pub fn convert_row(&self, src: &[u8], x0: usize, y: usize, out: &mut [Rgb565]) -> usize
+        ensures
+            result <= out.len(),
+            result * RGBA_BYTES <= src.len(),
+    {
+        let count = out.len().min(src.len() / RGBA_BYTES);
+        for (i, (pixel, rgba)) in out[..count].iter_mut().zip(src.chunks_exact(RGBA_BYTES)).enumerate() {
+            *pixel = self.convert(rgba[0], rgba[1], rgba[2], x0.wrapping_add(i), y);
+        }
+        count
+    }
+
+    /// Convert a `width`-pixel-wide RGBA8888 image, row by row
+    ///
+    /// Returns the number of pixels converted: as many whole pixels as both
+    /// buffers hold.
+    #[verus_verify]
+    pub fn convert_image(&self, src: &[u8], width: usize, out: &mut [Rgb565]) -> usize
+        requires
+            width > 0,
+        ensures
+            result <= out.len(),
+            result * RGBA_BYTES <= src.len(),
+    {
+        let total = out.len().min(src.len() / RGBA_BYTES);
+        let mut done = 0;
+        let mut y = 0;
+        while done < total {
+            let n = width.min(total - done);
+            let start = done * RGBA_BYTES;
+            self.convert_row(&src[start..start + n * RGBA_BYTES], 0, y, &mut out[done..done + n]);
+            done += n;
+            y += 1;
+        }
+        total
+    }
+}
+
+impl Default for ColorConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
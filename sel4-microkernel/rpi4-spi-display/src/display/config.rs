@@ -0,0 +1,120 @@
+//! Run-time Controller Selection
+//!
+//! One Display PD image drives any of the supported panels: the system
+//! configuration names a [`ControllerKind`] and [`AnyController`] dispatches
+//! to the matching driver.
+
+use super::controller::{DisplayController, DisplayError, PixelFormat, Rotation};
+use super::ili9341::Ili9341;
+use super::ili9488::Ili9488;
+use super::st7789::{St7789, St7789Panel};
+
+/// Which panel is wired up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerKind {
+    /// 2.8" 320×240 ILI9341
+    Ili9341,
+    /// 1.3"/1.54" 240×240 ST7789
+    St7789_240x240,
+    /// 2.0"/2.4" 240×320 ST7789
+    St7789_240x320,
+    /// 3.5" 480×320 ILI9488
+    Ili9488,
+}
+
+impl ControllerKind {
+    /// Decode the configuration value; `None` for an unknown panel
+    pub const fn from_config(value: usize) -> Option<Self> {
+        match value {
+            0 => Some(Self::Ili9341),
+            1 => Some(Self::St7789_240x240),
+            2 => Some(Self::St7789_240x320),
+            3 => Some(Self::Ili9488),
+            _ => None,
+        }
+    }
+
+    /// Configuration value for this panel
+    pub const fn config_value(self) -> usize {
+        match self {
+            Self::Ili9341 => 0,
+            Self::St7789_240x240 => 1,
+            Self::St7789_240x320 => 2,
+            Self::Ili9488 => 3,
+        }
+    }
+}
+
+/// Any supported controller, chosen at run time
+pub enum AnyController {
+    Ili9341(Ili9341),
+    St7789(St7789),
+    Ili9488(Ili9488),
+}
+
+impl AnyController {
+    /// Create the driver for `kind`; `None` if the panel geometry does not
+    /// fit its controller
+    pub fn new(kind: ControllerKind) -> Option<Self> {
+        match kind {
+            ControllerKind::Ili9341 => Some(Self::Ili9341(Ili9341::new())),
+            ControllerKind::St7789_240x240 => St7789::new(St7789Panel::PANEL_240X240).map(Self::St7789),
+            ControllerKind::St7789_240x320 => St7789::new(St7789Panel::PANEL_240X320).map(Self::St7789),
+            ControllerKind::Ili9488 => Some(Self::Ili9488(Ili9488::new())),
+        }
+    }
+
+    fn inner(&self) -> &dyn DisplayController {
+        match self {
+            Self::Ili9341(c) => c,
+            Self::St7789(c) => c,
+            Self::Ili9488(c) => c,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn DisplayController {
+        match self {
+            Self::Ili9341(c) => c,
+            Self::St7789(c) => c,
+            Self::Ili9488(c) => c,
+        }
+    }
+}
+
+impl DisplayController for AnyController {
+    fn init(&mut self) -> Result<(), DisplayError> {
+        self.inner_mut().init()
+    }
+
+    fn width(&self) -> u16 {
+        self.inner().width()
+    }
+
+    fn height(&self) -> u16 {
+        self.inner().height()
+    }
+
+    fn rotation(&self) -> Rotation {
+        self.inner().rotation()
+    }
+
+    fn set_rotation(&mut self, rotation: Rotation) -> Result<(), DisplayError> {
+        self.inner_mut().set_rotation(rotation)
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        self.inner().pixel_format()
+    }
+
+    fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DisplayError> {
+        self.inner_mut().set_window(x0, y0, x1, y1)
+    }
+
+    fn write_pixels(&mut self, data: &[u16]) -> Result<(), DisplayError> {
+        self.inner_mut().write_pixels(data)
+    }
+
+    fn start_ram_write(&mut self) -> Result<(), DisplayError> {
+        self.inner_mut().start_ram_write()
+    }
+}
@@ -0,0 +1,98 @@
+//! Display Controller Interface
+//!
+//! Common interface over the SPI LCD controllers (ILI9341, ST7789), so the
+//! [`Display`](super::Display) front end and the demo do not depend on which
+//! panel is wired up.
+//!
+//! Coordinates are in the rotated orientation: after
+//! [`set_rotation`](DisplayController::set_rotation) with a quarter turn,
+//! `width()` and `height()` swap.
+
+/// Display errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayError {
+    NotInitialized,
+    SpiError,
+    InvalidCoordinates,
+}
+
+/// Panel orientation, clockwise from the controller's native scan direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    /// Does this orientation swap width and height?
+    pub const fn is_transposed(self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+}
+
+/// Pixel format sent over the wire (COLMOD / PIXFMT argument)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 16 bits per pixel, two bytes per pixel
+    Rgb565,
+    /// 18 bits per pixel, three bytes per pixel
+    Rgb666,
+}
+
+impl PixelFormat {
+    /// Interface pixel format register value (DPI and DBI fields)
+    pub const fn colmod(self) -> u8 {
+        match self {
+            PixelFormat::Rgb565 => 0x55,
+            PixelFormat::Rgb666 => 0x66,
+        }
+    }
+
+    /// Bytes on the wire per pixel
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Rgb666 => 3,
+        }
+    }
+}
+
+/// An SPI LCD controller
+pub trait DisplayController {
+    /// Run the power-up and configuration sequence
+    fn init(&mut self) -> Result<(), DisplayError>;
+
+    /// Visible width in the current rotation
+    fn width(&self) -> u16;
+
+    /// Visible height in the current rotation
+    fn height(&self) -> u16;
+
+    /// Orientation currently programmed into the controller
+    fn rotation(&self) -> Rotation;
+
+    /// Change the orientation (memory access control register)
+    fn set_rotation(&mut self, rotation: Rotation) -> Result<(), DisplayError>;
+
+    /// Pixel format the controller expects for `write_pixels`
+    fn pixel_format(&self) -> PixelFormat;
+
+    /// Select the inclusive rectangle that following pixel writes fill;
+    /// `InvalidCoordinates` if it is empty or leaves the panel
+    fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DisplayError>;
+
+    /// Write RGB565 pixels to the current window
+    fn write_pixels(&mut self, data: &[u16]) -> Result<(), DisplayError>;
+
+    /// Send the memory-write command and leave DC high, so pixel bytes in
+    /// [`pixel_format`](Self::pixel_format) can follow from the SPI DMA
+    /// engine
+    fn start_ram_write(&mut self) -> Result<(), DisplayError>;
+}
+
+/// Is the inclusive window non-empty and on a `width` x `height` panel?
+pub fn window_fits(x0: u16, y0: u16, x1: u16, y1: u16, width: u16, height: u16) -> bool {
+    x0 <= x1 && x1 < width && y0 <= y1 && y1 < height
+}
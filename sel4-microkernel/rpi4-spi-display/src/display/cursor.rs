@@ -0,0 +1,296 @@
+//! Software Cursor with Save-Under
+//!
+//! Draws a small sprite (an arrow, a crosshair, or an expanding touch
+//! ripple) over the framebuffer at the last touch or focus position. The
+//! pixels under the sprite are copied aside before it is drawn and put
+//! back when it moves or hides, so the application never has to redraw
+//! what the cursor covered.
+//!
+//! The save-under is only right while nothing else draws beneath the
+//! cursor. Hide it before drawing in its area and show it again after, or
+//! call [`Cursor::invalidate`] after redrawing the whole screen.
+//!
+//! The crosshair marks the exact calibrated point, which makes a
+//! miscalibrated touch panel easy to spot.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::framebuffer::{Framebuffer, Rgb565};
+use super::ili9341::{WIDTH, HEIGHT};
+
+/// Cursor sprites are `CURSOR_SIZE` x `CURSOR_SIZE` pixels
+pub const CURSOR_SIZE: u16 = 16;
+
+/// Pixels in the save-under buffer
+const CURSOR_PIXELS: usize = (CURSOR_SIZE as usize) * (CURSOR_SIZE as usize);
+
+/// Frames a touch ripple takes to expand before it disappears
+pub const RIPPLE_FRAMES: u8 = 4;
+
+/// What the cursor looks like
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorShape {
+    /// Pointer with its tip at the position
+    Arrow,
+    /// Cross centred on the position
+    Crosshair,
+    /// Ring expanding from the position over [`RIPPLE_FRAMES`] ticks, then
+    /// gone
+    Ripple,
+}
+
+/// Two-color 16x16 bitmap; bit 15 of each row is the leftmost pixel
+struct Sprite {
+    outline: [u16; CURSOR_SIZE as usize],
+    fill: [u16; CURSOR_SIZE as usize],
+    /// Offset of the cursor position within the sprite
+    hotspot: (u16, u16),
+}
+
+const ARROW: Sprite = Sprite {
+    outline: [
+        0b1000_0000_0000_0000,
+        0b1100_0000_0000_0000,
+        0b1010_0000_0000_0000,
+        0b1001_0000_0000_0000,
+        0b1000_1000_0000_0000,
+        0b1000_0100_0000_0000,
+        0b1000_0010_0000_0000,
+        0b1000_1111_0000_0000,
+        0b1001_0000_0000_0000,
+        0b1010_0000_0000_0000,
+        0b1100_0000_0000_0000,
+        0b1000_0000_0000_0000,
+        0, 0, 0, 0,
+    ],
+    fill: [
+        0,
+        0,
+        0b0100_0000_0000_0000,
+        0b0110_0000_0000_0000,
+        0b0111_0000_0000_0000,
+        0b0111_1000_0000_0000,
+        0b0111_1100_0000_0000,
+        0b0111_0000_0000_0000,
+        0b0110_0000_0000_0000,
+        0b0100_0000_0000_0000,
+        0, 0, 0, 0, 0, 0,
+    ],
+    hotspot: (0, 0),
+};
+
+const CROSSHAIR: Sprite = Sprite {
+    outline: [0; CURSOR_SIZE as usize],
+    fill: [
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b1111_1111_1111_1110,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0b0000_0001_0000_0000,
+        0,
+    ],
+    hotspot: (7, 7),
+};
+
+/// Centre of the ripple within the sprite
+const RIPPLE_CENTRE: i32 = 7;
+
+/// Is sprite pixel (col, row) on the ripple ring for `frame`?
+fn on_ripple(frame: u8, col: u16, row: u16) -> bool {
+    let radius = 1 + 2 * frame as i32;
+    let (dx, dy) = (col as i32 - RIPPLE_CENTRE, row as i32 - RIPPLE_CENTRE);
+    (dx * dx + dy * dy - radius * radius).abs() <= radius
+}
+
+/// Part of a sprite row or column starting at `origin` that lands on a
+/// screen `limit` pixels across: (offset into the sprite, screen
+/// coordinate, length), or `None` if none of it does
+#[verus_verify]
+fn clip_span(origin: i32, limit: u16) -> Option<(u16, u16, u16)>
+    ensures
+        result.is_some() ==> (result.unwrap().0 as u32) + (result.unwrap().2 as u32) <= CURSOR_SIZE as u32,
+        result.is_some() ==> (result.unwrap().1 as u32) + (result.unwrap().2 as u32) <= limit as u32,
+{
+    let start = if origin < 0 { 0 } else { origin as i64 };
+    let end = (origin as i64 + CURSOR_SIZE as i64).min(limit as i64);
+    if start >= end {
+        return None;
+    }
+    Some(((start - origin as i64) as u16, start as u16, (end - start) as u16))
+}
+
+/// Area of the screen the cursor covers, clipped to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Covered {
+    x: u16,
+    y: u16,
+    w: u16,
+    h: u16,
+}
+
+/// A cursor drawn over a [`Framebuffer`]
+pub struct Cursor {
+    shape: CursorShape,
+    x: u16,
+    y: u16,
+    fill: Rgb565,
+    outline: Rgb565,
+    /// Where the cursor is drawn, if it is
+    covered: Option<Covered>,
+    /// What was on screen under `covered`, row-major with `w` per row
+    saved: [Rgb565; CURSOR_PIXELS],
+    /// Ripple frame showing
+    frame: u8,
+}
+
+impl Cursor {
+    /// Hidden cursor: white with a black outline
+    pub const fn new(shape: CursorShape) -> Self {
+        Self {
+            shape,
+            x: 0,
+            y: 0,
+            fill: Rgb565::WHITE,
+            outline: Rgb565::BLACK,
+            covered: None,
+            saved: [Rgb565::BLACK; CURSOR_PIXELS],
+            frame: 0,
+        }
+    }
+
+    /// Current position
+    pub fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    /// Is the cursor on screen?
+    pub fn is_visible(&self) -> bool {
+        self.covered.is_some()
+    }
+
+    /// Change the colors, redrawing the cursor if it is showing
+    pub fn set_colors(&mut self, fb: &mut Framebuffer, fill: Rgb565, outline: Rgb565) {
+        self.fill = fill;
+        self.outline = outline;
+        self.refresh(fb);
+    }
+
+    /// Change the shape, redrawing the cursor if it is showing
+    pub fn set_shape(&mut self, fb: &mut Framebuffer, shape: CursorShape) {
+        self.shape = shape;
+        self.frame = 0;
+        self.refresh(fb);
+    }
+
+    /// Move the cursor to (x, y) and show it; a ripple starts again from
+    /// its smallest ring
+    pub fn show_at(&mut self, fb: &mut Framebuffer, x: u16, y: u16) {
+        self.hide(fb);
+        self.x = x;
+        self.y = y;
+        self.frame = 0;
+        self.draw(fb);
+    }
+
+    /// Take the cursor off screen, restoring what was under it
+    pub fn hide(&mut self, fb: &mut Framebuffer) {
+        let Some(area) = self.covered.take() else {
+            return;
+        };
+        for row in 0..area.h {
+            for col in 0..area.w {
+                let saved = self.saved[(row as usize) * (area.w as usize) + (col as usize)];
+                fb.set_pixel(area.x + col, area.y + row, saved);
+            }
+        }
+    }
+
+    /// Forget what was under the cursor without restoring it, after the
+    /// screen under it was redrawn; call [`show_at`](Self::show_at) to
+    /// draw it again
+    pub fn invalidate(&mut self) {
+        self.covered = None;
+    }
+
+    /// Advance a ripple by one frame, hiding it after the last. Returns
+    /// whether the screen changed.
+    pub fn tick(&mut self, fb: &mut Framebuffer) -> bool {
+        if self.shape != CursorShape::Ripple || self.covered.is_none() {
+            return false;
+        }
+        self.hide(fb);
+        self.frame += 1;
+        if self.frame < RIPPLE_FRAMES {
+            self.draw(fb);
+        }
+        true
+    }
+
+    /// Redraw in place if showing, e.g. after a shape or color change
+    fn refresh(&mut self, fb: &mut Framebuffer) {
+        if self.covered.is_some() {
+            self.hide(fb);
+            self.draw(fb);
+        }
+    }
+
+    /// Color of sprite pixel (col, row), or `None` where it is transparent
+    fn sprite_pixel(&self, col: u16, row: u16) -> Option<Rgb565> {
+        let sprite = match self.shape {
+            CursorShape::Arrow => &ARROW,
+            CursorShape::Crosshair => &CROSSHAIR,
+            CursorShape::Ripple => {
+                return on_ripple(self.frame, col, row).then_some(self.fill);
+            }
+        };
+        let bit = 0x8000 >> col;
+        if sprite.outline[row as usize] & bit != 0 {
+            Some(self.outline)
+        } else if sprite.fill[row as usize] & bit != 0 {
+            Some(self.fill)
+        } else {
+            None
+        }
+    }
+
+    fn hotspot(&self) -> (u16, u16) {
+        match self.shape {
+            CursorShape::Arrow => ARROW.hotspot,
+            CursorShape::Crosshair => CROSSHAIR.hotspot,
+            CursorShape::Ripple => (RIPPLE_CENTRE as u16, RIPPLE_CENTRE as u16),
+        }
+    }
+
+    /// Save what is under the sprite, then draw it
+    fn draw(&mut self, fb: &mut Framebuffer) {
+        let (hx, hy) = self.hotspot();
+        let Some((col, x, w)) = clip_span(self.x as i32 - hx as i32, WIDTH) else {
+            return;
+        };
+        let Some((row, y, h)) = clip_span(self.y as i32 - hy as i32, HEIGHT) else {
+            return;
+        };
+
+        for dy in 0..h {
+            for dx in 0..w {
+                let under = fb.get_pixel(x + dx, y + dy).unwrap_or(Rgb565::BLACK);
+                self.saved[(dy as usize) * (w as usize) + (dx as usize)] = under;
+                if let Some(color) = self.sprite_pixel(col + dx, row + dy) {
+                    fb.set_pixel(x + dx, y + dy, color);
+                }
+            }
+        }
+        self.covered = Some(Covered { x, y, w, h });
+    }
+}
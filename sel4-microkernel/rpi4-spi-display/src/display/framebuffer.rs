@@ -1,6 +1,11 @@
 //! RGB565 Framebuffer with Verified Operations
 //!
 //! Provides a bounds-checked framebuffer for the 320×240 display.
+//!
+//! The verified operations take their bounds as preconditions (or report a
+//! bare `bool`), which suits callers that have already proven the geometry
+//! fits. The `try_` variants check at run time and return a [`DrawError`]
+//! carrying the offending parameters.
 
 use verus_builtin::*;
 use verus_builtin_macros::*;
@@ -28,6 +33,25 @@ impl Rgb565 {
     }
 }
 
+/// Why a fallible drawing call was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawError {
+    /// Rectangle does not fit on the 320×240 display
+    RectOutOfBounds { x: u16, y: u16, w: u16, h: u16 },
+    /// Source pixels are fewer than the `w` x `h` rectangle needs
+    SourceTooShort { needed: usize, len: usize },
+}
+
+/// Does a `w` x `h` rectangle at (x, y) fit on the display?
+/// Computed in u32 so `x + w` cannot wrap.
+#[verus_verify]
+pub fn rect_fits(x: u16, y: u16, w: u16, h: u16) -> bool
+    ensures
+        result == ((x as u32) + (w as u32) <= WIDTH as u32 && (y as u32) + (h as u32) <= HEIGHT as u32),
+{
+    (x as u32) + (w as u32) <= WIDTH as u32 && (y as u32) + (h as u32) <= HEIGHT as u32
+}
+
 /// Framebuffer for 320×240 RGB565 display
 pub struct Framebuffer {
     buffer: [u16; (WIDTH as usize) * (HEIGHT as usize)],
@@ -98,4 +122,42 @@ impl Framebuffer {
         }
         true
     }
+    /// Fill a rectangle, reporting why it was refused
+    pub fn try_fill_rect(&mut self, x: u16, y: u16, w: u16, h: u16, color: Rgb565) -> Result<(), DrawError> {
+        if !rect_fits(x, y, w, h) {
+            return Err(DrawError::RectOutOfBounds { x, y, w, h });
+        }
+        self.fill_rect(x, y, w, h, color);
+        Ok(())
+    }
+
+    /// Copy a `w` x `h` block of pixels (row-major, `w` per row) to (x, y)
+    #[verus_verify]
+    pub fn blit(&mut self, x: u16, y: u16, w: u16, h: u16, pixels: &[Rgb565])
+        requires
+            x + w <= WIDTH,
+            y + h <= HEIGHT,
+            pixels.len() >= (w as usize) * (h as usize),
+    {
+        for row in 0..h {
+            for col in 0..w {
+                let src = (row as usize) * (w as usize) + (col as usize);
+                let idx = ((y + row) as usize) * (WIDTH as usize) + ((x + col) as usize);
+                self.buffer[idx] = pixels[src].0;
+            }
+        }
+    }
+
+    /// Copy a block of pixels, checking the rectangle and source length
+    pub fn try_blit(&mut self, x: u16, y: u16, w: u16, h: u16, pixels: &[Rgb565]) -> Result<(), DrawError> {
+        if !rect_fits(x, y, w, h) {
+            return Err(DrawError::RectOutOfBounds { x, y, w, h });
+        }
+        let needed = (w as usize) * (h as usize);
+        if pixels.len() < needed {
+            return Err(DrawError::SourceTooShort { needed, len: pixels.len() });
+        }
+        self.blit(x, y, w, h, pixels);
+        Ok(())
+    }
 }
@@ -6,6 +6,9 @@
 use verus_builtin::*;
 use verus_builtin_macros::*;
 
+use super::controller::{window_fits, DisplayController, PixelFormat, Rotation};
+pub use super::controller::DisplayError;
+
 /// ILI9341 commands
 #[allow(dead_code)]
 mod cmd {
@@ -29,12 +32,26 @@ pub const HEIGHT: u16 = 240;
 pub struct Ili9341 {
     // SPI and GPIO handles would go here
     initialized: bool,
+    rotation: Rotation,
 }
 
 impl Ili9341 {
     /// Create a new ILI9341 driver instance
+    ///
+    /// The panel starts in landscape (`Rotation::Deg90` from the
+    /// controller's 240×320 portrait scan), matching `WIDTH` x `HEIGHT`.
     pub const fn new() -> Self {
-        Self { initialized: false }
+        Self { initialized: false, rotation: Rotation::Deg90 }
+    }
+
+    /// MADCTL value for a rotation (the panel is wired BGR)
+    fn madctl(rotation: Rotation) -> u8 {
+        match rotation {
+            Rotation::Deg0 => 0x48,
+            Rotation::Deg90 => 0x28,
+            Rotation::Deg180 => 0x88,
+            Rotation::Deg270 => 0xE8,
+        }
     }
 
     /// Initialize the display
@@ -84,10 +101,53 @@ impl Ili9341 {
     }
 }
 
-/// Display errors
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DisplayError {
-    NotInitialized,
-    SpiError,
-    InvalidCoordinates,
+impl DisplayController for Ili9341 {
+    fn init(&mut self) -> Result<(), DisplayError> {
+        Ili9341::init(self)
+    }
+
+    fn width(&self) -> u16 {
+        if self.rotation.is_transposed() { WIDTH } else { HEIGHT }
+    }
+
+    fn height(&self) -> u16 {
+        if self.rotation.is_transposed() { HEIGHT } else { WIDTH }
+    }
+
+    fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    fn set_rotation(&mut self, rotation: Rotation) -> Result<(), DisplayError> {
+        // TODO: Send MADCTL with Self::madctl(rotation)
+        self.rotation = rotation;
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Rgb565
+    }
+
+    fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        if !window_fits(x0, y0, x1, y1, self.width(), self.height()) {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+        // TODO: Send CASET and PASET commands
+        Ok(())
+    }
+
+    fn write_pixels(&mut self, data: &[u16]) -> Result<(), DisplayError> {
+        Ili9341::write_pixels(self, data)
+    }
+
+    fn start_ram_write(&mut self) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        // TODO: DC low, send RAMWR; DC high
+        Ok(())
+    }
 }
@@ -0,0 +1,168 @@
+//! ILI9488 LCD Controller Driver
+//!
+//! Driver for the ILI9488 TFT controller on 3.5" 480×320 SPI panels.
+//!
+//! Over a 4-wire SPI interface the ILI9488 only accepts 18-bit color, so
+//! RGB565 pixels are widened with [`pack_rgb666`] as they are sent.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::controller::{window_fits, DisplayController, DisplayError, PixelFormat, Rotation};
+use super::rgb666::{pack_rgb666, packed_len, RGB666_BYTES};
+
+/// ILI9488 commands
+#[allow(dead_code)]
+mod cmd {
+    pub const NOP: u8 = 0x00;
+    pub const SWRESET: u8 = 0x01;
+    pub const SLPOUT: u8 = 0x11;
+    pub const NORON: u8 = 0x13;    // Normal display mode on
+    pub const DISPOFF: u8 = 0x28;
+    pub const DISPON: u8 = 0x29;
+    pub const CASET: u8 = 0x2A;    // Column address set
+    pub const PASET: u8 = 0x2B;    // Page address set
+    pub const RAMWR: u8 = 0x2C;    // Memory write
+    pub const MADCTL: u8 = 0x36;   // Memory access control
+    pub const PIXFMT: u8 = 0x3A;   // Interface pixel format
+}
+
+/// Panel width in the native portrait scan
+pub const NATIVE_WIDTH: u16 = 320;
+/// Panel height in the native portrait scan
+pub const NATIVE_HEIGHT: u16 = 480;
+
+/// Pixels converted per SPI burst in `write_pixels`
+const BURST_PIXELS: usize = 32;
+
+/// Encode an inclusive CASET/PASET range: start and end, big-endian
+#[verus_verify]
+fn address_range(start: u16, end: u16) -> [u8; 4]
+    requires
+        start <= end,
+        end < NATIVE_HEIGHT,
+{
+    [(start >> 8) as u8, start as u8, (end >> 8) as u8, end as u8]
+}
+
+/// ILI9488 driver
+pub struct Ili9488 {
+    // SPI and GPIO handles would go here
+    initialized: bool,
+    rotation: Rotation,
+}
+
+impl Ili9488 {
+    /// Create a new ILI9488 driver instance
+    ///
+    /// The panel starts in landscape (`Rotation::Deg90`), 480×320.
+    pub const fn new() -> Self {
+        Self { initialized: false, rotation: Rotation::Deg90 }
+    }
+
+    /// MADCTL value for a rotation (the panel is wired BGR)
+    fn madctl(rotation: Rotation) -> u8 {
+        match rotation {
+            Rotation::Deg0 => 0x48,
+            Rotation::Deg90 => 0x28,
+            Rotation::Deg180 => 0x88,
+            Rotation::Deg270 => 0xE8,
+        }
+    }
+
+    /// Send a command byte followed by its arguments
+    fn command(&mut self, command: u8, args: &[u8]) -> Result<(), DisplayError> {
+        // TODO: DC low, write `command`; DC high, write `args` (SPI0 CS0)
+        Ok(())
+    }
+
+    /// Send pixel bytes after RAMWR
+    fn data(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        // TODO: DC high, write `bytes` (SPI0 CS0)
+        Ok(())
+    }
+
+    /// Wait for the controller after reset or sleep-out
+    fn delay_ms(&mut self, ms: u32) {
+        // TODO: Busy-wait on the system timer
+    }
+}
+
+impl Default for Ili9488 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayController for Ili9488 {
+    fn init(&mut self) -> Result<(), DisplayError> {
+        // TODO: Hardware reset (RST low, delay, RST high) before SWRESET
+        self.command(cmd::SWRESET, &[])?;
+        self.delay_ms(120);
+        self.command(cmd::SLPOUT, &[])?;
+        self.delay_ms(120);
+        self.command(cmd::PIXFMT, &[PixelFormat::Rgb666.colmod()])?;
+        self.command(cmd::MADCTL, &[Self::madctl(self.rotation)])?;
+        self.command(cmd::NORON, &[])?;
+        self.command(cmd::DISPON, &[])?;
+        self.delay_ms(20);
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn width(&self) -> u16 {
+        if self.rotation.is_transposed() { NATIVE_HEIGHT } else { NATIVE_WIDTH }
+    }
+
+    fn height(&self) -> u16 {
+        if self.rotation.is_transposed() { NATIVE_WIDTH } else { NATIVE_HEIGHT }
+    }
+
+    fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    fn set_rotation(&mut self, rotation: Rotation) -> Result<(), DisplayError> {
+        if self.initialized {
+            self.command(cmd::MADCTL, &[Self::madctl(rotation)])?;
+        }
+        self.rotation = rotation;
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Rgb666
+    }
+
+    fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        if !window_fits(x0, y0, x1, y1, self.width(), self.height()) {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+        // Both axes are under 480 in every rotation
+        self.command(cmd::CASET, &address_range(x0, x1))?;
+        self.command(cmd::PASET, &address_range(y0, y1))
+    }
+
+    fn write_pixels(&mut self, data: &[u16]) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        self.command(cmd::RAMWR, &[])?;
+        let mut burst = [0u8; BURST_PIXELS * RGB666_BYTES];
+        for pixels in data.chunks(BURST_PIXELS) {
+            let packed = pack_rgb666(pixels, &mut burst);
+            self.data(&burst[..packed_len(packed)])?;
+        }
+        Ok(())
+    }
+
+    fn start_ram_write(&mut self) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        self.command(cmd::RAMWR, &[])
+    }
+}
@@ -1,29 +1,50 @@
 //! Display drivers and graphics primitives
 //!
-//! Provides verified drivers for ILI9341-based displays and
-//! a framebuffer with bounds-checked drawing operations.
+//! Provides verified drivers for ILI9341-, ST7789- and ILI9488-based
+//! displays behind the [`DisplayController`] trait, and a framebuffer with
+//! bounds-checked drawing operations.
 
+pub mod controller;
+pub mod config;
 pub mod ili9341;
+pub mod ili9488;
+pub mod st7789;
+pub mod rgb666;
+pub mod color;
 pub mod framebuffer;
+pub mod cursor;
 
+pub use controller::{DisplayController, DisplayError, PixelFormat, Rotation};
+pub use config::{AnyController, ControllerKind};
 pub use ili9341::Ili9341;
-pub use framebuffer::{Framebuffer, Rgb565};
+pub use ili9488::Ili9488;
+pub use st7789::{St7789, St7789Panel};
+pub use framebuffer::{DrawError, Framebuffer, Rgb565};
+pub use color::{ColorConverter, Dither, GammaLut};
+pub use cursor::{Cursor, CursorShape};
+
+use crate::hal::{BusGrant, ChipSelect, SpiDmaEngine};
+use rgb666::pack_rgb666;
 
 /// High-level display interface
-pub struct Display {
-    controller: Ili9341,
+///
+/// The framebuffer is always 320×240; on a smaller panel (e.g. a 240×240
+/// ST7789) only the top-left part that fits is shown, and on a larger one
+/// (the 480×320 ILI9488) it fills the top-left corner.
+pub struct Display<C: DisplayController = Ili9341> {
+    controller: C,
     framebuffer: Framebuffer,
     dirty: bool,
 }
 
-impl Display {
+impl<C: DisplayController> Display<C> {
     /// Display width in pixels
     pub const WIDTH: u16 = 320;
     /// Display height in pixels
     pub const HEIGHT: u16 = 240;
 
     /// Create a new display instance
-    pub fn new(controller: Ili9341) -> Self {
+    pub fn new(controller: C) -> Self {
         Self {
             controller,
             framebuffer: Framebuffer::new(),
@@ -31,6 +52,11 @@ impl Display {
         }
     }
 
+    /// Get the display controller
+    pub fn controller_mut(&mut self) -> &mut C {
+        &mut self.controller
+    }
+
     /// Get mutable access to the framebuffer
     pub fn framebuffer_mut(&mut self) -> &mut Framebuffer {
         self.dirty = true;
@@ -45,9 +71,68 @@ impl Display {
         }
     }
 
+    /// Start pushing the framebuffer to the panel with the SPI DMA engine
+    ///
+    /// Sends the whole visible part of the framebuffer, converted to the
+    /// controller's wire format. Returns `Ok(true)` once the write is
+    /// started (completion arrives as the engine's IRQ), `Ok(false)` if
+    /// nothing changed or the previous flush is still in flight. `grant`
+    /// must stay held until the write completes.
+    pub fn refresh_dma(&mut self, engine: &mut SpiDmaEngine, grant: &BusGrant) -> Result<bool, DisplayError> {
+        if !self.dirty || engine.is_busy() {
+            return Ok(false);
+        }
+        let w = self.controller.width().min(Self::WIDTH);
+        let h = self.controller.height().min(Self::HEIGHT);
+        let format = self.controller.pixel_format();
+        let row_bytes = w as usize * format.bytes_per_pixel();
+        let len = row_bytes * h as usize;
+
+        let buffer = engine.buffer_mut().ok_or(DisplayError::SpiError)?;
+        if len > buffer.len() {
+            return Err(DisplayError::SpiError);
+        }
+        let pixels = self.framebuffer.as_slice();
+        for (row, out) in buffer[..len].chunks_exact_mut(row_bytes).enumerate() {
+            let start = row * Self::WIDTH as usize;
+            let line = &pixels[start..start + w as usize];
+            match format {
+                PixelFormat::Rgb565 => {
+                    for (bytes, &pixel) in out.chunks_exact_mut(2).zip(line) {
+                        bytes.copy_from_slice(&pixel.to_be_bytes());
+                    }
+                }
+                PixelFormat::Rgb666 => {
+                    pack_rgb666(line, out);
+                }
+            }
+        }
+
+        self.controller.set_window(0, 0, w - 1, h - 1)?;
+        self.controller.start_ram_write()?;
+        engine.start_write(grant, ChipSelect::Cs0, len).map_err(|_| DisplayError::SpiError)?;
+        self.dirty = false;
+        Ok(true)
+    }
+
     /// Clear the display to a solid color
     pub fn clear(&mut self, color: Rgb565) {
         self.framebuffer.clear(color);
         self.dirty = true;
     }
+
+    /// Fill a rectangle, refusing one that does not fit on screen
+    pub fn try_fill_rect(&mut self, x: u16, y: u16, w: u16, h: u16, color: Rgb565) -> Result<(), DrawError> {
+        self.framebuffer.try_fill_rect(x, y, w, h, color)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Copy a block of pixels, refusing an off-screen rectangle or a short
+    /// source
+    pub fn try_blit(&mut self, x: u16, y: u16, w: u16, h: u16, pixels: &[Rgb565]) -> Result<(), DrawError> {
+        self.framebuffer.try_blit(x, y, w, h, pixels)?;
+        self.dirty = true;
+        Ok(())
+    }
 }
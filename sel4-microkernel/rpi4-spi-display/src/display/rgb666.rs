@@ -0,0 +1,79 @@
+//! RGB666 Pixel Packing
+//!
+//! Controllers that only accept 18-bit color over SPI (ILI9488) take three
+//! bytes per pixel, one per channel, with the six color bits in the top of
+//! each byte. The framebuffer stays RGB565, so pixels are widened on the way
+//! out: green already has six bits, red and blue repeat their top bit.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+/// Wire bytes per RGB666 pixel
+pub const RGB666_BYTES: usize = 3;
+
+/// Widen a 5-bit channel to 6 bits, copying the top bit into the bottom so
+/// full scale stays full scale
+#[verus_verify]
+fn expand5(c: u16) -> u8
+    requires
+        c < 32,
+    ensures
+        result < 64,
+        result >> 1 == c,
+{
+    ((c << 1) | (c >> 4)) as u8
+}
+
+/// One RGB565 pixel as its three RGB666 wire bytes (red, green, blue)
+#[verus_verify]
+pub fn rgb565_to_rgb666(pixel: u16) -> [u8; RGB666_BYTES]
+    ensures
+        result[0] & 0x03 == 0,
+        result[1] & 0x03 == 0,
+        result[2] & 0x03 == 0,
+{
+    let r = expand5((pixel >> 11) & 0x1F);
+    let g = ((pixel >> 5) & 0x3F) as u8;
+    let b = expand5(pixel & 0x1F);
+    [r << 2, g << 2, b << 2]
+}
+
+/// RGB666 wire bytes back to RGB565; drops the low bit of red and blue
+#[verus_verify]
+pub fn rgb666_to_rgb565(bytes: [u8; RGB666_BYTES]) -> u16
+    ensures
+        result >> 11 == bytes[0] >> 3,
+{
+    let r = (bytes[0] >> 3) as u16;
+    let g = (bytes[1] >> 2) as u16;
+    let b = (bytes[2] >> 3) as u16;
+    (r << 11) | (g << 5) | b
+}
+
+/// Wire bytes needed for `pixels` pixels
+#[verus_verify]
+pub const fn packed_len(pixels: usize) -> usize
+    requires
+        pixels <= usize::MAX / RGB666_BYTES,
+    ensures
+        result == pixels * RGB666_BYTES,
+{
+    pixels * RGB666_BYTES
+}
+
+/// Pack as many whole pixels as fit into `out`
+///
+/// Returns the number of pixels packed; `packed_len` of it is the number of
+/// bytes written. A trailing partial pixel's worth of `out` is left alone.
+#[verus_verify]
+pub fn pack_rgb666(pixels: &[u16], out: &mut [u8]) -> usize
+    ensures
+        result <= pixels.len(),
+        result * RGB666_BYTES <= out.len(),
+{
+    let count = pixels.len().min(out.len() / RGB666_BYTES);
+    for (chunk, &pixel) in out.chunks_exact_mut(RGB666_BYTES).zip(&pixels[..count]) {
+        chunk.copy_from_slice(&rgb565_to_rgb666(pixel));
+    }
+    count
+}
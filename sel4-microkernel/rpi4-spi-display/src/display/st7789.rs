@@ -0,0 +1,248 @@
+//! ST7789 LCD Controller Driver
+//!
+//! Driver for the Sitronix ST7789 TFT controller used on many cheap
+//! 240×240 and 240×320 SPI panels.
+//!
+//! The controller has 240×320 pixels of RAM. Panels smaller than that are
+//! glued to part of it, so window addresses carry a per-panel offset which
+//! also depends on the rotation (mirroring moves the visible part to the
+//! other end of the RAM).
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::controller::{window_fits, DisplayController, DisplayError, PixelFormat, Rotation};
+
+/// ST7789 commands
+#[allow(dead_code)]
+mod cmd {
+    pub const NOP: u8 = 0x00;
+    pub const SWRESET: u8 = 0x01;
+    pub const SLPOUT: u8 = 0x11;
+    pub const NORON: u8 = 0x13;    // Normal display mode on
+    pub const INVOFF: u8 = 0x20;
+    pub const INVON: u8 = 0x21;
+    pub const DISPOFF: u8 = 0x28;
+    pub const DISPON: u8 = 0x29;
+    pub const CASET: u8 = 0x2A;    // Column address set
+    pub const RASET: u8 = 0x2B;    // Row address set
+    pub const RAMWR: u8 = 0x2C;    // Memory write
+    pub const MADCTL: u8 = 0x36;   // Memory data access control
+    pub const COLMOD: u8 = 0x3A;   // Interface pixel format
+}
+
+/// MADCTL bits
+mod madctl {
+    pub const MY: u8 = 0x80;       // Row address order (mirror Y)
+    pub const MX: u8 = 0x40;       // Column address order (mirror X)
+    pub const MV: u8 = 0x20;       // Row/column exchange
+    pub const BGR: u8 = 0x08;      // BGR subpixel order
+}
+
+/// Controller RAM width (columns)
+pub const RAM_WIDTH: u16 = 240;
+/// Controller RAM height (rows)
+pub const RAM_HEIGHT: u16 = 320;
+
+/// Where a panel sits in the controller RAM, in the native orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct St7789Panel {
+    /// Visible columns
+    pub width: u16,
+    /// Visible rows
+    pub height: u16,
+    /// First visible RAM column
+    pub col_offset: u16,
+    /// First visible RAM row
+    pub row_offset: u16,
+    /// Panel needs display inversion on to show true colors (most IPS ones)
+    pub inverted: bool,
+    /// Panel subpixels are wired BGR
+    pub bgr: bool,
+}
+
+impl St7789Panel {
+    /// 1.3"/1.54" 240×240 IPS panels
+    pub const PANEL_240X240: Self = Self {
+        width: 240,
+        height: 240,
+        col_offset: 0,
+        row_offset: 0,
+        inverted: true,
+        bgr: false,
+    };
+
+    /// 2.0"/2.4" 240×320 panels
+    pub const PANEL_240X320: Self = Self {
+        width: 240,
+        height: 320,
+        col_offset: 0,
+        row_offset: 0,
+        inverted: true,
+        bgr: false,
+    };
+
+    /// Does the panel fit in the controller RAM?
+    pub const fn fits_ram(&self) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.col_offset as u32 + self.width as u32 <= RAM_WIDTH as u32
+            && self.row_offset as u32 + self.height as u32 <= RAM_HEIGHT as u32
+    }
+}
+
+/// Encode an inclusive CASET/RASET range: start and end, big-endian
+#[verus_verify]
+fn address_range(start: u16, end: u16, offset: u16) -> [u8; 4]
+    requires
+        start <= end,
+        end + offset < RAM_HEIGHT,
+{
+    let s = start + offset;
+    let e = end + offset;
+    [(s >> 8) as u8, s as u8, (e >> 8) as u8, e as u8]
+}
+
+/// ST7789 driver
+pub struct St7789 {
+    // SPI and GPIO handles would go here
+    panel: St7789Panel,
+    rotation: Rotation,
+    format: PixelFormat,
+    initialized: bool,
+}
+
+impl St7789 {
+    /// Create a driver for `panel`; `None` if the panel does not fit in the
+    /// controller RAM
+    pub const fn new(panel: St7789Panel) -> Option<Self> {
+        if !panel.fits_ram() {
+            return None;
+        }
+        Some(Self {
+            panel,
+            rotation: Rotation::Deg0,
+            format: PixelFormat::Rgb565,
+            initialized: false,
+        })
+    }
+
+    /// Panel geometry this driver was created for
+    pub fn panel(&self) -> St7789Panel {
+        self.panel
+    }
+
+    /// Choose the wire pixel format; takes effect at the next `init`
+    pub fn set_pixel_format(&mut self, format: PixelFormat) {
+        self.format = format;
+    }
+
+    /// MADCTL value for a rotation
+    fn madctl(&self, rotation: Rotation) -> u8 {
+        let scan = match rotation {
+            Rotation::Deg0 => 0,
+            Rotation::Deg90 => madctl::MX | madctl::MV,
+            Rotation::Deg180 => madctl::MX | madctl::MY,
+            Rotation::Deg270 => madctl::MY | madctl::MV,
+        };
+        if self.panel.bgr { scan | madctl::BGR } else { scan }
+    }
+
+    /// RAM offset of the visible area as (x, y) in the current rotation.
+    /// Mirroring an axis measures that axis from the other end of the RAM.
+    fn window_offset(&self) -> (u16, u16) {
+        let p = self.panel;
+        let col_mirrored = RAM_WIDTH - p.width - p.col_offset;
+        let row_mirrored = RAM_HEIGHT - p.height - p.row_offset;
+        match self.rotation {
+            Rotation::Deg0 => (p.col_offset, p.row_offset),
+            Rotation::Deg90 => (p.row_offset, col_mirrored),
+            Rotation::Deg180 => (col_mirrored, row_mirrored),
+            Rotation::Deg270 => (row_mirrored, p.col_offset),
+        }
+    }
+
+    /// Send a command byte followed by its arguments
+    fn command(&mut self, command: u8, args: &[u8]) -> Result<(), DisplayError> {
+        // TODO: DC low, write `command`; DC high, write `args` (SPI0 CS0)
+        Ok(())
+    }
+
+    /// Wait for the controller after reset or sleep-out
+    fn delay_ms(&mut self, ms: u32) {
+        // TODO: Busy-wait on the system timer
+    }
+}
+
+impl DisplayController for St7789 {
+    fn init(&mut self) -> Result<(), DisplayError> {
+        // TODO: Hardware reset (RST low, delay, RST high) before SWRESET
+        self.command(cmd::SWRESET, &[])?;
+        self.delay_ms(150);
+        self.command(cmd::SLPOUT, &[])?;
+        self.delay_ms(10);
+        self.command(cmd::COLMOD, &[self.format.colmod()])?;
+        self.command(cmd::MADCTL, &[self.madctl(self.rotation)])?;
+        let invert = if self.panel.inverted { cmd::INVON } else { cmd::INVOFF };
+        self.command(invert, &[])?;
+        self.command(cmd::NORON, &[])?;
+        self.delay_ms(10);
+        self.command(cmd::DISPON, &[])?;
+        self.delay_ms(10);
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn width(&self) -> u16 {
+        if self.rotation.is_transposed() { self.panel.height } else { self.panel.width }
+    }
+
+    fn height(&self) -> u16 {
+        if self.rotation.is_transposed() { self.panel.width } else { self.panel.height }
+    }
+
+    fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    fn set_rotation(&mut self, rotation: Rotation) -> Result<(), DisplayError> {
+        if self.initialized {
+            self.command(cmd::MADCTL, &[self.madctl(rotation)])?;
+        }
+        self.rotation = rotation;
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        if !window_fits(x0, y0, x1, y1, self.width(), self.height()) {
+            return Err(DisplayError::InvalidCoordinates);
+        }
+        // The offsets keep the window inside the 240×320 RAM: see fits_ram
+        let (dx, dy) = self.window_offset();
+        self.command(cmd::CASET, &address_range(x0, x1, dx))?;
+        self.command(cmd::RASET, &address_range(y0, y1, dy))
+    }
+
+    fn write_pixels(&mut self, data: &[u16]) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        // TODO: Send RAMWR, then the pixels big-endian (RGB565) or expanded
+        // to three bytes each (RGB666)
+        Ok(())
+    }
+
+    fn start_ram_write(&mut self) -> Result<(), DisplayError> {
+        if !self.initialized {
+            return Err(DisplayError::NotInitialized);
+        }
+        self.command(cmd::RAMWR, &[])
+    }
+}
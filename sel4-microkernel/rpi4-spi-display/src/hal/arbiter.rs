@@ -0,0 +1,21 @@
+//! SPI0 Bus Arbitration
+//!
+//! The panel (CE0), the touch controller (CE1) and, on boards that fit
+//! one, the TPM share SPI0. Every driver takes the bus from one
+//! [`SpiArbiter`] before touching it and gives it back when its transfer is
+//! done; transfers that need the bus take the [`BusGrant`] as proof.
+//!
+//! The arbiter itself is the verified one in `verified-microkernel`:
+//!
+//! - At most one client owns the bus, and only its grant is live
+//! - Clients that find the bus busy are queued once each and served in
+//!   arrival order
+//! - An owner holding the bus past its limit is revoked by
+//!   [`SpiArbiter::expire`], so a stuck transfer cannot starve the others
+//!
+//! Hold limits are in the ticks the PD passes to `acquire` and `expire`.
+
+pub use verified_microkernel::{
+    BusError, BusGrant, Expired, SpiArbiter, SPI_BUS_CLIENTS, SPI_CLIENT_DISPLAY, SPI_CLIENT_SD,
+    SPI_CLIENT_TOUCH, SPI_CLIENT_TPM,
+};
@@ -0,0 +1,208 @@
+//! Backlight Brightness Control with Verus Verification
+//!
+//! Dims the panel backlight through PWM0 on GPIO18: [`Backlight::set_brightness`]
+//! for an immediate change, [`Backlight::fade_to`] (and the fade-in/out
+//! helpers) for a ramp advanced one step per [`Backlight::tick`], and
+//! [`AutoDim`] for a night-time and idle schedule.
+//!
+//! The PD calls [`Backlight::tick`] from its refresh timer, so fade lengths
+//! are in refresh ticks.
+//!
+//! # Verification Properties
+//!
+//! - Every level along a fade lies between its start and end, and the last
+//!   step lands exactly on the end
+//! - The night window is empty when it starts and ends at the same minute
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::gpio::{Gpio, Pin, PinMode};
+use super::pwm::{duty_for, Pwm};
+
+/// Full brightness
+pub const MAX_BRIGHTNESS: u8 = 255;
+
+/// Minutes in a day; times of day are minutes since midnight
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Brightness at `step` of `steps` along a fade from `from` to `to`
+#[verus_verify]
+pub fn fade_level(from: u8, to: u8, step: u16, steps: u16) -> (level: u8)
+    requires
+        0 < steps,
+        step <= steps,
+    ensures
+        from <= to ==> from <= level && level <= to,
+        to < from ==> to <= level && level <= from,
+        step == 0 ==> level == from,
+        step == steps ==> level == to,
+{
+    if from <= to {
+        from + ((to - from) as u32 * step as u32 / steps as u32) as u8
+    } else {
+        from - ((from - to) as u32 * step as u32 / steps as u32) as u8
+    }
+}
+
+/// Is `minute` inside `[start, end)`? A window whose end comes before its
+/// start runs past midnight.
+#[verus_verify]
+pub fn in_window(minute: u16, start: u16, end: u16) -> (inside: bool)
+    ensures
+        start == end ==> !inside,
+{
+    if start <= end {
+        start <= minute && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// A fade in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fade {
+    from: u8,
+    to: u8,
+    step: u16,
+    steps: u16,
+}
+
+/// When to dim the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoDim {
+    /// Start of the night window (minutes since midnight)
+    pub night_start: u16,
+    /// End of the night window; before `night_start` to span midnight
+    pub night_end: u16,
+    /// Brightness outside the night window
+    pub day_level: u8,
+    /// Brightness inside the night window
+    pub night_level: u8,
+    /// Ticks without input before dimming to `idle_level` (0: never)
+    pub idle_timeout: u32,
+    /// Brightness once idle; never brighter than the schedule
+    pub idle_level: u8,
+}
+
+impl AutoDim {
+    /// Full brightness by day, dim from 22:00 to 07:00, no idle dimming
+    pub const fn new() -> Self {
+        Self {
+            night_start: 22 * 60,
+            night_end: 7 * 60,
+            day_level: MAX_BRIGHTNESS,
+            night_level: 32,
+            idle_timeout: 0,
+            idle_level: 0,
+        }
+    }
+
+    /// Brightness for the time of day (if the caller knows it) after
+    /// `idle_ticks` ticks without input
+    pub fn target(&self, minute_of_day: Option<u16>, idle_ticks: u32) -> u8 {
+        let scheduled = match minute_of_day {
+            Some(minute) if in_window(minute % MINUTES_PER_DAY, self.night_start, self.night_end) => {
+                self.night_level
+            }
+            _ => self.day_level,
+        };
+        if self.idle_timeout != 0 && idle_ticks >= self.idle_timeout {
+            scheduled.min(self.idle_level)
+        } else {
+            scheduled
+        }
+    }
+}
+
+impl Default for AutoDim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Panel backlight on PWM0
+pub struct Backlight {
+    pwm: Pwm,
+    /// Brightness currently driven
+    level: u8,
+    fade: Option<Fade>,
+}
+
+impl Backlight {
+    /// Route GPIO18 to PWM0 and start with the backlight off
+    pub fn new(pwm: Pwm, gpio: &mut Gpio) -> Self {
+        gpio.set_mode(Pin::Backlight, PinMode::Alt5);
+        let mut backlight = Self { pwm, level: 0, fade: None };
+        backlight.apply(0);
+        backlight
+    }
+
+    /// Drive `level` without touching any fade in progress
+    fn apply(&mut self, level: u8) {
+        self.level = level;
+        let duty = duty_for(level, self.pwm.range());
+        self.pwm.set_duty(duty);
+    }
+
+    /// Brightness currently driven
+    pub fn brightness(&self) -> u8 {
+        self.level
+    }
+
+    /// Brightness the backlight is heading for (the current one when not
+    /// fading)
+    pub fn target(&self) -> u8 {
+        self.fade.map_or(self.level, |fade| fade.to)
+    }
+
+    /// Is a fade in progress?
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    /// Change brightness now, cancelling any fade
+    pub fn set_brightness(&mut self, level: u8) {
+        self.fade = None;
+        self.apply(level);
+    }
+
+    /// Ramp from the current brightness to `level` over `steps` ticks
+    /// (immediately if `steps` is 0)
+    pub fn fade_to(&mut self, level: u8, steps: u16) {
+        if steps == 0 || level == self.level {
+            self.set_brightness(level);
+            return;
+        }
+        self.fade = Some(Fade { from: self.level, to: level, step: 0, steps });
+    }
+
+    /// Ramp up to full brightness over `steps` ticks
+    pub fn fade_in(&mut self, steps: u16) {
+        self.fade_to(MAX_BRIGHTNESS, steps);
+    }
+
+    /// Ramp down to off over `steps` ticks
+    pub fn fade_out(&mut self, steps: u16) {
+        self.fade_to(0, steps);
+    }
+
+    /// Advance a fade by one step; returns true while it is still running
+    pub fn tick(&mut self) -> bool {
+        let Some(mut fade) = self.fade else {
+            return false;
+        };
+        fade.step += 1;
+        self.apply(fade_level(fade.from, fade.to, fade.step, fade.steps));
+        self.fade = (fade.step < fade.steps).then_some(fade);
+        self.fade.is_some()
+    }
+
+    /// Fade towards what `schedule` asks for, if that has changed
+    pub fn auto_dim(&mut self, schedule: &AutoDim, minute_of_day: Option<u16>, idle_ticks: u32, steps: u16) {
+        let level = schedule.target(minute_of_day, idle_ticks);
+        if level != self.target() {
+            self.fade_to(level, steps);
+        }
+    }
+}
@@ -8,7 +8,7 @@
 //! |------|----------------|-----------|
 //! | 25   | DC (Data/Cmd)  | Output    |
 //! | 24   | RST (Reset)    | Output    |
-//! | 18   | BL (Backlight) | Output    |
+//! | 18   | BL (Backlight) | PWM0      |
 //! | 17   | T_IRQ (Touch)  | Input     |
 //! | 4    | IR_IN (Remote) | Input     |
 
@@ -18,6 +18,9 @@ use verus_builtin_macros::*;
 /// BCM2711 GPIO base address
 pub const GPIO_BASE: usize = 0xFE200000;
 
+/// GPIO bank 0 interrupt (GIC SPI 113): edge and level events on GPIO 0-27
+pub const GPIO_BANK0_IRQ: usize = 145;
+
 /// GPIO register offsets
 #[allow(dead_code)]
 mod regs {
@@ -108,23 +111,57 @@ impl Gpio {
         requires
             (pin as u8) < 54,
     {
-        let pin_num = pin as u8;
-        // TODO: Read from GPLEV0/GPLEV1
-        false
+        // Every `Pin` is in bank 0
+        self.read_reg(regs::GPLEV0) & bank0_bit(pin) != 0
     }
 
     /// Enable falling edge detection on a pin (for touch IRQ)
+    ///
+    /// Detected edges latch in GPEDS0 and raise [`GPIO_BANK0_IRQ`] until
+    /// cleared with [`check_edge_detect`](Self::check_edge_detect).
     pub fn enable_falling_edge_detect(&mut self, pin: Pin) {
-        // TODO: Configure GPFEN0
+        let enabled = self.read_reg(regs::GPFEN0);
+        self.write_reg(regs::GPFEN0, enabled | bank0_bit(pin));
+    }
+
+    /// Stop detecting falling edges on a pin; an edge already latched stays
+    /// until cleared
+    pub fn disable_falling_edge_detect(&mut self, pin: Pin) {
+        let enabled = self.read_reg(regs::GPFEN0);
+        self.write_reg(regs::GPFEN0, enabled & !bank0_bit(pin));
     }
 
     /// Check and clear edge detect status
     pub fn check_edge_detect(&mut self, pin: Pin) -> bool {
-        // TODO: Check and clear GPEDS0
-        false
+        let bit = bank0_bit(pin);
+        let detected = self.read_reg(regs::GPEDS0) & bit != 0;
+        if detected {
+            // Write-one-to-clear; the other pins' events are left alone
+            self.write_reg(regs::GPEDS0, bit);
+        }
+        detected
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
     }
 }
 
+/// A bank 0 pin's bit in the GPLEV0, GPFEN0 and GPEDS0 registers
+#[verus_verify]
+fn bank0_bit(pin: Pin) -> (bit: u32)
+    ensures
+        bit != 0,
+{
+    1u32 << (pin as u32)
+}
+
 /// Display control helper functions
 impl Gpio {
     /// Set DC pin for command mode (low)
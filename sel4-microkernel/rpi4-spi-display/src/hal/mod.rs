@@ -3,9 +3,23 @@
 //! Provides verified drivers for:
 //! - SPI0 peripheral
 //! - GPIO pin control
+//! - The SPI0 DMA write engine, on the shared BCM2711 DMA channels
+//!   (`rpi4_bcm2711::dma`, re-exported as [`dma`])
+//! - PWM backlight brightness, fades and auto-dim
+//! - SPI0 bus arbitration between the panel, touch controller and TPM
 
 pub mod gpio;
 pub mod spi;
+pub mod arbiter;
+pub use rpi4_bcm2711::dma;
+pub mod spi_dma;
+pub mod pwm;
+pub mod backlight;
 
-pub use gpio::{Gpio, Pin, PinMode};
-pub use spi::{Spi, SpiConfig, ChipSelect};
+pub use gpio::{Gpio, Pin, PinMode, GPIO_BANK0_IRQ};
+pub use spi::{Spi, SpiClock, SpiConfig, SpiError, ChipSelect};
+pub use arbiter::{BusError, BusGrant, Expired, SpiArbiter};
+pub use dma::{ControlBlock, DmaChannel, DmaStatus};
+pub use spi_dma::{DmaEvent, DmaState, SpiDmaEngine};
+pub use pwm::Pwm;
+pub use backlight::{AutoDim, Backlight};
@@ -0,0 +1,105 @@
+//! BCM2711 PWM Driver with Verus Verification
+//!
+//! Drives PWM0 channel 1, which GPIO18 carries in alternate function 5, for
+//! the panel backlight. The PWM block is clocked from the clock manager's
+//! PWM clock, fed by the 54 MHz crystal oscillator. The register layout and
+//! the clock manager are the shared ones in `rpi4_bcm2711`.
+//!
+//! The channel runs in mark-space mode: each period of `range` clock ticks
+//! is high for `duty` ticks, then low. With [`PWM_CLOCK_DIVISOR`] and
+//! [`BACKLIGHT_RANGE`] the period is about 26 kHz, above audible whine and
+//! far above visible flicker.
+//!
+//! # Verification Properties
+//!
+//! - The duty programmed into the channel never exceeds its range
+//! - Brightness 0 is fully off and brightness 255 is fully on
+
+use rpi4_bcm2711::clock::{start_clock, Clock, Source};
+use rpi4_bcm2711::pwm::{ctl, regs};
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+/// PWM clock divisor: 54 MHz / 8 = 6.75 MHz
+pub const PWM_CLOCK_DIVISOR: u32 = 8;
+
+/// Clock ticks per PWM period for the backlight (6.75 MHz / 256 ≈ 26 kHz)
+pub const BACKLIGHT_RANGE: u32 = 256;
+
+/// Largest range [`duty_for`] accepts
+pub const MAX_RANGE: u32 = 0x10000;
+
+/// Duty ticks for an 8-bit brightness in a period of `range` ticks
+#[verus_verify]
+pub fn duty_for(brightness: u8, range: u32) -> (duty: u32)
+    requires
+        0 < range,
+        range <= MAX_RANGE,
+    ensures
+        duty <= range,
+        brightness == 0 ==> duty == 0,
+        brightness == 255 ==> duty == range,
+{
+    (brightness as u32) * range / 255
+}
+
+/// PWM0 channel 1
+pub struct Pwm {
+    /// Virtual address of the PWM0 registers
+    base: usize,
+    /// Clock ticks per period
+    range: u32,
+    /// Duty currently programmed
+    duty: u32,
+}
+
+impl Pwm {
+    /// Start the PWM clock and channel 1, initially off
+    ///
+    /// GPIO18 must be switched to [`PinMode::Alt5`](super::PinMode::Alt5)
+    /// for the output to reach the pin.
+    ///
+    /// # Safety
+    /// `pwm_base` and `cm_base` must be the mapped PWM0 and clock manager
+    /// register pages, and nothing else may use PWM0 or the PWM clock.
+    #[verus_verify]
+    pub unsafe fn new(pwm_base: usize, cm_base: usize, range: u32) -> Self
+        requires
+            0 < range,
+            range <= MAX_RANGE,
+    {
+        let pwm = Self { base: pwm_base, range, duty: 0 };
+        pwm.write_reg(regs::CTL, 0);
+        start_clock(cm_base, Clock::Pwm, Source::Osc, PWM_CLOCK_DIVISOR << 12);
+        pwm.write_reg(regs::RNG1, range);
+        pwm.write_reg(regs::DAT1, 0);
+        pwm.write_reg(regs::CTL, ctl::PWEN1 | ctl::MSEN1);
+        pwm
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// Clock ticks per period
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    /// Duty currently programmed
+    pub fn duty(&self) -> u32 {
+        self.duty
+    }
+
+    /// Program the high time; anything past the range is clamped to it
+    #[verus_verify]
+    pub fn set_duty(&mut self, duty: u32)
+        ensures
+            self.duty <= self.range,
+            self.range == old(self).range,
+    {
+        self.duty = if duty > self.range { self.range } else { duty };
+        self.write_reg(regs::DAT1, self.duty);
+    }
+}
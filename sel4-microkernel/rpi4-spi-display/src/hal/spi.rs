@@ -11,10 +11,26 @@
 //! - All transfers complete with correct byte count
 //! - Chip select is always properly managed
 //! - Clock configuration is within valid range
+//!
+//! # Clocking
+//!
+//! Each chip select gets its own SCLK, negotiated from the device's
+//! [`SpiConfig`] against the core clock (ask the mailbox for
+//! `clock::CORE`; PDs without it mapped use [`SPI_DEFAULT_CORE_HZ`]) and
+//! programmed whenever that device is selected. A driver that sees a
+//! corrupt transfer calls [`Spi::report_error`] to halve its clock, down to
+//! the slowest rate the device is specified for.
+//!
+//! Transfers take a [`BusGrant`] from the [`SpiArbiter`](super::arbiter::SpiArbiter):
+//! the bus is shared, and only its current owner may drive it.
 
 use verus_builtin::*;
 use verus_builtin_macros::*;
 
+pub use verified_microkernel::{SpiClock, SPI_DEFAULT_CORE_HZ};
+
+use super::arbiter::BusGrant;
+
 /// BCM2711 SPI0 base address
 pub const SPI0_BASE: usize = 0xFE204000;
 
@@ -29,6 +45,14 @@ mod regs {
     pub const DC: usize = 0x14;    // DMA DREQ Controls
 }
 
+/// CS register bits
+mod cs {
+    pub const CPHA: u32 = 1 << 2;
+    pub const CPOL: u32 = 1 << 3;
+    pub const CLEAR_TX: u32 = 1 << 4;
+    pub const CLEAR_RX: u32 = 1 << 5;
+}
+
 /// Chip select lines
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ChipSelect {
@@ -36,33 +60,74 @@ pub enum ChipSelect {
     Cs1 = 1,  // GPIO7 - Touch
 }
 
-/// SPI configuration
+/// Per-device SPI configuration
 #[derive(Clone, Copy)]
 pub struct SpiConfig {
-    /// Clock divider (core_clk / divider = SPI clock)
-    pub clock_divider: u16,
+    /// Fastest SCLK the device is run at
+    pub max_hz: u32,
+    /// Slowest SCLK to fall back to after transfer errors
+    pub min_hz: u32,
     /// SPI mode (0-3)
     pub mode: u8,
 }
 
 impl SpiConfig {
-    /// 32 MHz SPI clock (for display)
+    /// ILI9341 pixel writes: well past the datasheet's 10 MHz, which the
+    /// panel handles on short wires; 32 MHz is the floor for a usable
+    /// frame rate
     pub const DISPLAY: Self = Self {
-        clock_divider: 8,   // 250 MHz / 8 = 31.25 MHz
+        max_hz: 64_000_000,
+        min_hz: 32_000_000,
         mode: 0,
     };
 
-    /// 2 MHz SPI clock (for touch)
+    /// XPT2046 touch controller (2.5 MHz maximum at 2.7 V)
     pub const TOUCH: Self = Self {
-        clock_divider: 128, // 250 MHz / 128 = ~2 MHz
+        max_hz: 2_000_000,
+        min_hz: 500_000,
+        mode: 0,
+    };
+
+    /// SLB 9670 TPM: rated for 43 MHz, but not reliable above 22.5 MHz
+    /// over the GPIO header
+    pub const TPM: Self = Self {
+        max_hz: 22_500_000,
+        min_hz: 1_000_000,
         mode: 0,
     };
+
+    /// The device's clock off a `core_hz` core clock
+    pub fn negotiate(&self, core_hz: u32) -> SpiClock {
+        SpiClock::negotiate(core_hz, self.max_hz, self.min_hz.min(self.max_hz).max(1))
+    }
+
+    /// CS register mode bits
+    fn mode_bits(&self) -> u32 {
+        let mut bits = 0;
+        if self.mode & 1 != 0 {
+            bits |= cs::CPHA;
+        }
+        if self.mode & 2 != 0 {
+            bits |= cs::CPOL;
+        }
+        bits
+    }
+}
+
+/// A configured device on one chip select
+#[derive(Clone, Copy)]
+struct Device {
+    clock: SpiClock,
+    mode_bits: u32,
 }
 
 /// SPI driver state
 pub struct Spi {
     base: usize,
     initialized: bool,
+    core_hz: u32,
+    /// Indexed by chip select
+    devices: [Option<Device>; 2],
 }
 
 impl Spi {
@@ -71,9 +136,16 @@ impl Spi {
         Self {
             base,
             initialized: false,
+            core_hz: SPI_DEFAULT_CORE_HZ,
+            devices: [None; 2],
         }
     }
 
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
     /// Check if SPI is initialized
     #[verus_verify]
     pub fn is_initialized(&self) -> (result: bool)
@@ -83,23 +155,52 @@ impl Spi {
         self.initialized
     }
 
-    /// Initialize the SPI peripheral
-    pub fn init(&mut self, config: &SpiConfig) {
-        // TODO: Configure SPI registers
-        // 1. Set clock divider
-        // 2. Configure mode (CPOL, CPHA)
-        // 3. Clear FIFOs
-        // 4. Enable SPI
+    /// Initialize the SPI peripheral; `core_hz` is the core clock the
+    /// device clocks are divided from
+    pub fn init(&mut self, core_hz: u32) {
+        self.write_reg(regs::CS, cs::CLEAR_TX | cs::CLEAR_RX);
+        self.core_hz = core_hz;
+        self.devices = [None; 2];
         self.initialized = true;
     }
 
+    /// Set up the device on `chip`, at the fastest clock its config allows
+    pub fn configure(&mut self, chip: ChipSelect, config: &SpiConfig) {
+        self.devices[chip as usize] = Some(Device {
+            clock: config.negotiate(self.core_hz),
+            mode_bits: config.mode_bits(),
+        });
+    }
+
+    /// The clock of the device on `chip`, if configured
+    pub fn clock(&self, chip: ChipSelect) -> Option<SpiClock> {
+        self.devices[chip as usize].map(|device| device.clock)
+    }
+
+    /// A transfer to `chip` came back corrupt (bad CRC or readback): halve
+    /// its clock. False once it is already as slow as the device allows.
+    pub fn report_error(&mut self, chip: ChipSelect) -> bool {
+        match &mut self.devices[chip as usize] {
+            Some(device) => device.clock.slow_down(),
+            None => false,
+        }
+    }
+
+    /// Program the clock and mode of the device on `chip`
+    fn select(&self, chip: ChipSelect) -> Result<(), SpiError> {
+        let device = self.devices[chip as usize].ok_or(SpiError::NotInitialized)?;
+        self.write_reg(regs::CLK, device.clock.divider());
+        self.write_reg(regs::CS, chip as u32 | device.mode_bits);
+        Ok(())
+    }
+
     /// Transfer data over SPI
     ///
     /// # Verification
     ///
     /// Ensures that exactly `tx.len()` bytes are sent and received.
     #[verus_verify]
-    pub fn transfer(&mut self, cs: ChipSelect, tx: &[u8], rx: &mut [u8]) -> (result: Result<(), SpiError>)
+    pub fn transfer(&mut self, _grant: &BusGrant, cs: ChipSelect, tx: &[u8], rx: &mut [u8]) -> (result: Result<(), SpiError>)
         requires
             self.initialized,
             tx.len() == rx.len(),
@@ -110,6 +211,7 @@ impl Spi {
         if tx.len() != rx.len() {
             return Err(SpiError::LengthMismatch);
         }
+        self.select(cs)?;
 
         // TODO: Implement SPI transfer
         // 1. Assert CS
@@ -124,22 +226,24 @@ impl Spi {
 
     /// Write-only transfer (ignore received data)
     #[verus_verify]
-    pub fn write(&mut self, cs: ChipSelect, data: &[u8]) -> (result: Result<(), SpiError>)
+    pub fn write(&mut self, _grant: &BusGrant, cs: ChipSelect, data: &[u8]) -> (result: Result<(), SpiError>)
         requires
             self.initialized,
             data.len() <= 65535,
     {
+        self.select(cs)?;
         // TODO: Implement write-only transfer
         Ok(())
     }
 
     /// Read-only transfer (send zeros)
     #[verus_verify]
-    pub fn read(&mut self, cs: ChipSelect, buffer: &mut [u8]) -> (result: Result<(), SpiError>)
+    pub fn read(&mut self, _grant: &BusGrant, cs: ChipSelect, buffer: &mut [u8]) -> (result: Result<(), SpiError>)
         requires
             self.initialized,
             buffer.len() <= 65535,
     {
+        self.select(cs)?;
         // TODO: Implement read-only transfer
         Ok(())
     }
@@ -152,4 +256,8 @@ pub enum SpiError {
     LengthMismatch,
     Timeout,
     FifoOverrun,
+    /// A DMA write is already in flight
+    Busy,
+    /// DMA length is zero, too long, or not a whole number of FIFO words
+    Unaligned,
 }
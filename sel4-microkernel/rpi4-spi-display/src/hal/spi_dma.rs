@@ -0,0 +1,302 @@
+//! SPI0 DMA Transfer Engine with Verus Verification
+//!
+//! Pushes large writes (a full-frame flush) through SPI0 with two DMA
+//! channels instead of the CPU feeding the FIFO byte by byte:
+//!
+//! - TX channel: DMA buffer → SPI FIFO, paced by the SPI TX DREQ
+//! - RX channel: SPI FIFO → nowhere, paced by the SPI RX DREQ; it finishes
+//!   last, so its interrupt signals completion
+//!
+//! SPI0 counts DMA transfers in `DLEN`, which is 16 bits, so a write is sent
+//! as a series of chunks of at most [`MAX_CHUNK`] bytes. Chip select stays
+//! asserted (`TA` set) across chunks, so the panel sees one continuous write;
+//! the next chunk is started from [`SpiDmaEngine::handle_irq`].
+//!
+//! # Buffer Layout
+//!
+//! The engine owns one physically contiguous region: two control blocks at
+//! offset 0, data from [`DATA_OFFSET`]. Fill the data area through
+//! [`SpiDmaEngine::buffer_mut`], then call [`SpiDmaEngine::start_write`].
+//!
+//! Writes go out at the panel's [`SpiConfig::DISPLAY`] clock; after a
+//! failed write the PD calls [`SpiDmaEngine::slow_down`] so the next one is
+//! sent slower.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::dma::{self, clean_dcache_range, ControlBlock, DmaChannel, DmaStatus};
+use super::arbiter::BusGrant;
+use super::spi::{ChipSelect, SpiClock, SpiConfig, SpiError, SPI0_BASE, SPI_DEFAULT_CORE_HZ};
+
+/// DMA channel feeding the SPI TX FIFO
+pub const TX_CHANNEL: u8 = 4;
+
+/// DMA channel draining the SPI RX FIFO (its IRQ signals completion)
+pub const RX_CHANNEL: u8 = 5;
+
+/// AXI priority and panic priority of both channels (both mid-range)
+const DMA_PRIORITY: u32 = dma::cs::priority(8, 15);
+
+/// GIC interrupt ID signalling the end of a chunk
+pub const SPI_DMA_IRQ: usize = dma::DMA_CHANNEL0_IRQ + RX_CHANNEL as usize;
+
+/// Offset of the data area in the DMA buffer
+pub const DATA_OFFSET: usize = 0x1000;
+
+/// Largest write the data area holds: the 320×240 framebuffer as RGB666,
+/// rounded up
+pub const DATA_CAPACITY: usize = 0x40000;
+
+/// Size of the DMA buffer region (control blocks + data)
+pub const BUFFER_SIZE: usize = DATA_OFFSET + DATA_CAPACITY;
+
+/// Largest chunk `DLEN` can describe, rounded down to whole FIFO words
+pub const MAX_CHUNK: usize = 0xFFFC;
+
+/// SPI register offsets used for DMA
+mod regs {
+    pub const CS: usize = 0x00;
+    pub const FIFO: usize = 0x04;
+    pub const CLK: usize = 0x08;
+    pub const DLEN: usize = 0x0C;
+    pub const DC: usize = 0x14;
+}
+
+/// SPI CS bits
+mod cs {
+    pub const CLEAR_TX: u32 = 1 << 4;
+    pub const CLEAR_RX: u32 = 1 << 5;
+    pub const TA: u32 = 1 << 7;
+    pub const DMAEN: u32 = 1 << 8;
+    pub const DONE: u32 = 1 << 16;
+}
+
+/// DREQ/panic thresholds (the hardware reset value: TX DREQ below 32
+/// bytes, RX DREQ above 32 bytes)
+const DC_THRESHOLDS: u32 = 0x3020_1020;
+
+/// Bytes to send in the chunk starting at `offset` of a `len`-byte write
+#[verus_verify]
+pub fn chunk_len(offset: usize, len: usize) -> usize
+    requires
+        offset < len,
+    ensures
+        0 < result,
+        result <= MAX_CHUNK,
+        offset + result <= len,
+{
+    let remaining = len - offset;
+    if remaining < MAX_CHUNK { remaining } else { MAX_CHUNK }
+}
+
+/// Where a write is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaState {
+    /// No write in flight
+    Idle,
+    /// Chunk at `offset` of a `len`-byte write is on the wire
+    Busy { offset: usize, len: usize },
+}
+
+/// What an interrupt meant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaEvent {
+    /// Not ours (or the chunk is still running)
+    None,
+    /// A chunk finished and the next one was started
+    Progress,
+    /// The whole write is on the panel; chip select is released
+    Complete,
+    /// A channel reported an error; the write was abandoned
+    Failed,
+}
+
+/// SPI0 write engine on DMA channels [`TX_CHANNEL`] and [`RX_CHANNEL`]
+pub struct SpiDmaEngine {
+    /// Virtual address of the SPI0 registers
+    spi_base: usize,
+    tx: DmaChannel,
+    rx: DmaChannel,
+    /// Virtual and physical address of the DMA buffer
+    buffer: *mut u8,
+    buffer_phys: usize,
+    chip: ChipSelect,
+    /// SCLK for the panel
+    clock: SpiClock,
+    state: DmaState,
+}
+
+impl SpiDmaEngine {
+    /// Set up the engine
+    ///
+    /// # Safety
+    /// `spi_base` and `dma_base` must be the mapped SPI0 and DMA register
+    /// pages; `buffer` must map [`BUFFER_SIZE`] bytes of physically
+    /// contiguous RAM at `buffer_phys`. The SPI driver must not touch SPI0
+    /// while a write is in flight.
+    #[verus_verify]
+    pub unsafe fn new(spi_base: usize, dma_base: usize, buffer: *mut u8, buffer_phys: usize) -> Self
+        requires
+            buffer_phys % 32 == 0,
+            buffer_phys + BUFFER_SIZE <= 0x4000_0000,
+    {
+        Self {
+            spi_base,
+            tx: DmaChannel::new(dma_base, TX_CHANNEL, DMA_PRIORITY).expect("TX_CHANNEL is a full channel"),
+            rx: DmaChannel::new(dma_base, RX_CHANNEL, DMA_PRIORITY).expect("RX_CHANNEL is a full channel"),
+            buffer,
+            buffer_phys,
+            chip: ChipSelect::Cs0,
+            clock: SpiConfig::DISPLAY.negotiate(SPI_DEFAULT_CORE_HZ),
+            state: DmaState::Idle,
+        }
+    }
+
+    #[inline]
+    fn read_reg(&self, offset: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.spi_base + offset) as *const u32) }
+    }
+
+    #[inline]
+    fn write_reg(&self, offset: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.spi_base + offset) as *mut u32, value) }
+    }
+
+    /// Is a write in flight?
+    pub fn is_busy(&self) -> bool {
+        self.state != DmaState::Idle
+    }
+
+    /// Current state
+    pub fn state(&self) -> DmaState {
+        self.state
+    }
+
+    /// Renegotiate the panel clock for the core clock the mailbox reports
+    pub fn set_core_clock(&mut self, core_hz: u32) {
+        self.clock = SpiConfig::DISPLAY.negotiate(core_hz);
+    }
+
+    /// The panel clock the next write goes out at
+    pub fn clock(&self) -> SpiClock {
+        self.clock
+    }
+
+    /// Halve the panel clock after a failed write; false once it is at
+    /// [`SpiConfig::DISPLAY`]'s floor
+    pub fn slow_down(&mut self) -> bool {
+        self.clock.slow_down()
+    }
+
+    /// The data area, to fill before [`start_write`](Self::start_write).
+    /// `None` while a write is in flight.
+    pub fn buffer_mut(&mut self) -> Option<&mut [u8]> {
+        if self.is_busy() {
+            return None;
+        }
+        Some(unsafe { core::slice::from_raw_parts_mut(self.buffer.add(DATA_OFFSET), DATA_CAPACITY) })
+    }
+
+    /// Send the first `len` bytes of the data area to `chip`
+    ///
+    /// Returns once the first chunk is started; completion is reported by
+    /// [`handle_irq`](Self::handle_irq). `len` must be a whole number of
+    /// FIFO words. Keep holding the bus `grant` came with until then.
+    #[verus_verify]
+    pub fn start_write(&mut self, _grant: &BusGrant, chip: ChipSelect, len: usize) -> (result: Result<(), SpiError>)
+        ensures
+            result.is_ok() ==> self.is_busy(),
+    {
+        if self.is_busy() {
+            return Err(SpiError::Busy);
+        }
+        if len == 0 || len > DATA_CAPACITY || len % 4 != 0 {
+            return Err(SpiError::Unaligned);
+        }
+
+        clean_dcache_range(unsafe { self.buffer.add(DATA_OFFSET) }, len);
+
+        self.chip = chip;
+        self.write_reg(regs::DC, DC_THRESHOLDS);
+        self.write_reg(regs::CLK, self.clock.divider());
+        self.write_reg(regs::CS, chip as u32 | cs::CLEAR_TX | cs::CLEAR_RX);
+        self.start_chunk(0, len);
+        self.write_reg(regs::CS, chip as u32 | cs::TA | cs::DMAEN);
+        Ok(())
+    }
+
+    /// Program the control blocks and both channels for one chunk
+    fn start_chunk(&mut self, offset: usize, len: usize) {
+        let count = chunk_len(offset, len);
+        let fifo = dma::peripheral_bus_address(SPI0_BASE + regs::FIFO);
+        let data = dma::ram_bus_address(self.buffer_phys + DATA_OFFSET + offset);
+
+        let tx_cb = ControlBlock::new(
+            dma::ti::WAIT_RESP | dma::ti::SRC_INC | dma::ti::DEST_DREQ | dma::ti::permap(dma::dreq::SPI0_TX),
+            data,
+            fifo,
+            count as u32,
+            0,
+        );
+        let rx_cb = ControlBlock::new(
+            dma::ti::INTEN | dma::ti::SRC_DREQ | dma::ti::DEST_IGNORE | dma::ti::permap(dma::dreq::SPI0_RX),
+            fifo,
+            0,
+            count as u32,
+            0,
+        );
+        let cbs = self.buffer as *mut ControlBlock;
+        unsafe {
+            cbs.write_volatile(tx_cb);
+            cbs.add(1).write_volatile(rx_cb);
+        }
+        clean_dcache_range(self.buffer, 2 * core::mem::size_of::<ControlBlock>());
+
+        let cb_bus = dma::ram_bus_address(self.buffer_phys);
+        self.write_reg(regs::DLEN, count as u32);
+        // Drain first, so the RX FIFO never fills and stalls the clock
+        self.rx.start(cb_bus + core::mem::size_of::<ControlBlock>() as u32);
+        self.tx.start(cb_bus);
+        self.state = DmaState::Busy { offset, len };
+    }
+
+    /// Service the RX channel IRQ. Call before acking the Microkit IRQ
+    /// channel.
+    pub fn handle_irq(&mut self) -> DmaEvent {
+        if !self.rx.take_interrupt() {
+            return DmaEvent::None;
+        }
+        let DmaState::Busy { offset, len } = self.state else {
+            return DmaEvent::None;
+        };
+
+        if self.tx.status() == DmaStatus::Error || self.rx.status() == DmaStatus::Error {
+            self.abort();
+            return DmaEvent::Failed;
+        }
+
+        let next = offset + chunk_len(offset, len);
+        if next < len {
+            self.start_chunk(next, len);
+            return DmaEvent::Progress;
+        }
+
+        // The RX channel only finishes once the last byte has been clocked
+        // in, but wait for DONE before dropping chip select
+        while self.read_reg(regs::CS) & cs::DONE == 0 {
+            core::hint::spin_loop();
+        }
+        self.write_reg(regs::CS, self.chip as u32);
+        self.state = DmaState::Idle;
+        DmaEvent::Complete
+    }
+
+    /// Stop both channels and release chip select
+    pub fn abort(&mut self) {
+        self.tx.reset();
+        self.rx.reset();
+        self.write_reg(regs::CS, self.chip as u32 | cs::CLEAR_TX | cs::CLEAR_RX);
+        self.state = DmaState::Idle;
+    }
+}
@@ -57,8 +57,11 @@ pub mod input;
 pub mod demo;
 
 // Re-export main types
-pub use display::{Display, Framebuffer, Rgb565};
-pub use touch::{TouchEvent, TouchPoint};
+pub use display::{
+    AnyController, ControllerKind, Display, DisplayController, DrawError, Framebuffer,
+    Rgb565, Rotation, Ili9341, Ili9488, St7789, St7789Panel,
+};
+pub use touch::{Gesture, GestureRecognizer, SwipeDirection, TouchEvent, TouchPoint};
 pub use input::{
     InputEvent, InputManager, InputSource, RemoteOptions,
     KeyCode, KeyEvent, KeyState, Keyboard,
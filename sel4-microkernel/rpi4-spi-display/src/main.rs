@@ -1,66 +1,455 @@
 //! SPI Display Protection Domain
 //!
 //! This is the main entry point for the verified SPI display system.
+//!
+//! With the `draw-ring` feature an App PD can draw on the panel without
+//! mapping it: it queues clear and rect commands on a shared draw ring,
+//! which this PD checks against the screen before drawing, and each
+//! `PRESENT` starts a flush.
 
 #![no_std]
 #![no_main]
 
-use sel4_microkit::{protection_domain, Handler, MessageInfo};
+use core::convert::Infallible;
+
+use sel4_microkit::{protection_domain, Channel, ChannelSet, Handler, MessageInfo};
+
+use rpi4_spi_display::display::{
+    AnyController, ControllerKind, Cursor, CursorShape, Display, DisplayController, Rgb565,
+};
+use rpi4_spi_display::hal::pwm::{BACKLIGHT_RANGE, Pwm};
+use rpi4_spi_display::hal::arbiter::{SPI_BUS_CLIENTS, SPI_CLIENT_DISPLAY, SPI_CLIENT_TOUCH};
+use rpi4_spi_display::hal::{
+    AutoDim, Backlight, BusGrant, DmaEvent, Gpio, Pin, PinMode, SpiArbiter, SpiDmaEngine,
+};
+use rpi4_spi_display::touch::{PenAction, PenIrq, TouchController, TouchEvent, Xpt2046};
+use verified_microkernel::event_loop::{EventLoop, Work};
+#[cfg(feature = "draw-ring")]
+use rpi4_draw_protocol::{
+    commands_ptr, header_ptr, DrawCommand, DrawError, DrawRingHeader, DISPLAY_DRAW_CHANNEL_ID,
+    DRAW_REGION_VADDR, OP_CLEAR, OP_PRESENT, OP_RECT,
+};
+
+/// SPI0 registers (see spi-display.system)
+const SPI0_VADDR: usize = 0x5_0000_0000;
+/// GPIO registers
+const GPIO_VADDR: usize = 0x5_0001_0000;
+/// DMA controller registers
+const DMA_VADDR: usize = 0x5_0002_0000;
+/// SPI DMA buffer: control blocks and one packed frame
+const DMA_BUFFER_VADDR: usize = 0x5_0100_0000;
+/// Physical address of the SPI DMA buffer (fixed in the system description)
+const DMA_BUFFER_PHYS: usize = 0x3E70_0000;
+/// PWM0 registers (backlight)
+const PWM_VADDR: usize = 0x5_0003_0000;
+/// Clock manager registers (PWM clock)
+const CM_VADDR: usize = 0x5_0004_0000;
+
+/// Refresh ticks for the power-on fade and for auto-dim changes
+const BACKLIGHT_FADE_TICKS: u16 = 30;
+
+/// Channel of the GPIO bank 0 IRQ: the touch controller's PENIRQ edge
+const TOUCH_CHANNEL: usize = 0;
+
+/// Channel of the refresh timer tick
+const REFRESH_CHANNEL: usize = 1;
+
+/// Channel of the SPI DMA completion IRQ
+const DMA_CHANNEL: usize = 2;
+
+/// Channel of the App PD's draw ring (commands queued / frame presented)
+#[cfg(feature = "draw-ring")]
+const DRAW_CHANNEL: usize = DISPLAY_DRAW_CHANNEL_ID;
+
+/// Draw commands copied out of the ring, and run, per turn
+#[cfg(feature = "draw-ring")]
+const DRAW_BATCH: usize = 32;
+
+/// Turns the draw ring may take in one notification: enough to drain a
+/// full ring (255 commands), so a frame's `PRESENT` is never left waiting
+/// for a notification that may not come
+#[cfg(feature = "draw-ring")]
+const DRAW_TURNS: u32 = 8;
 
-use rpi4_spi_display::display::{Display, Rgb565};
-use rpi4_spi_display::touch::TouchController;
+/// Each channel's handler serves one interrupt or tick a turn and never
+/// defers, so every channel that fired gets its turn in one notification
+const EVENT_BUDGET: u32 = 1;
+#[cfg(not(feature = "draw-ring"))]
+const EVENT_CHANNELS: usize = 3;
+#[cfg(not(feature = "draw-ring"))]
+const MAX_TURNS: u32 = 3;
+#[cfg(feature = "draw-ring")]
+const EVENT_CHANNELS: usize = 4;
+#[cfg(feature = "draw-ring")]
+const MAX_TURNS: u32 = 3 + DRAW_TURNS;
+
+/// Refresh ticks each SPI0 client may hold the bus: a frame flush gets
+/// two, everything else one
+const BUS_MAX_HOLD: [u64; SPI_BUS_CLIENTS] = [2, 1, 1, 1];
 
 /// Display Protection Domain state
 struct DisplayPd {
-    display: Option<Display>,
-    touch: Option<TouchController>,
+    display: Option<Display<AnyController>>,
+    touch: Option<Xpt2046>,
+    /// Ripple at the last touch, for feedback and checking calibration
+    cursor: Cursor,
+    dma: Option<SpiDmaEngine>,
+    /// Owner of SPI0: the panel flush, touch sampling
+    bus: SpiArbiter,
+    /// Held from the start of a flush until its DMA completes
+    flush_grant: Option<BusGrant>,
+    /// A touch sample is due but the bus was busy
+    touch_pending: bool,
+    /// Samples the touch controller only while the pen is down
+    pen: PenIrq,
+    /// Refresh ticks since boot, the arbiter's clock
+    ticks: u64,
+    gpio: Gpio,
+    backlight: Option<Backlight>,
+    auto_dim: AutoDim,
+    /// Refresh ticks since the last touch
+    idle_ticks: u32,
+    /// Frames pushed to the panel
+    frames: u32,
+    /// Flushes abandoned after a DMA error
+    dma_errors: u32,
+    /// App PD draw ring, set up in init
+    #[cfg(feature = "draw-ring")]
+    draw_ring: Option<&'static DrawRingHeader>,
+    /// Draw commands refused by validation
+    #[cfg(feature = "draw-ring")]
+    rejected_draws: u32,
+    /// Budgeted dispatch of the touch, refresh, DMA and draw channels
+    events: EventLoop<DisplayPd, Infallible, EVENT_CHANNELS>,
 }
 
 impl DisplayPd {
-    const fn new() -> Self {
+    fn new() -> Self {
         Self {
             display: None,
             touch: None,
+            cursor: Cursor::new(CursorShape::Ripple),
+            dma: None,
+            bus: SpiArbiter::new(BUS_MAX_HOLD),
+            flush_grant: None,
+            touch_pending: false,
+            pen: PenIrq::new(),
+            ticks: 0,
+            gpio: Gpio::new(GPIO_VADDR),
+            backlight: None,
+            auto_dim: AutoDim::new(),
+            idle_ticks: 0,
+            frames: 0,
+            dma_errors: 0,
+            #[cfg(feature = "draw-ring")]
+            draw_ring: None,
+            #[cfg(feature = "draw-ring")]
+            rejected_draws: 0,
+            events: EventLoop::new(MAX_TURNS),
+        }
+    }
+
+    /// Pen down (PENIRQ falling edge). The edge is cleared before the ack,
+    /// so the line has dropped by then
+    fn touch_irq(&mut self, _budget: u32) -> Result<Work, Infallible> {
+        self.pen_down();
+        Channel::new(TOUCH_CHANNEL).irq_ack().ok();
+        Ok(Work::Done)
+    }
+
+    /// Refresh timer: advance the backlight and start a flush unless the
+    /// last one is still on the wire
+    fn refresh_tick(&mut self, _budget: u32) -> Result<Work, Infallible> {
+        self.ticks += 1;
+        self.expire_bus();
+        // Touch bursts: one sample a tick while the pen is down
+        if self.pen.sampling() {
+            self.poll_touch();
+            self.idle_ticks = 0;
+        }
+        self.idle_ticks = self.idle_ticks.saturating_add(1);
+        if let Some(ref mut backlight) = self.backlight {
+            // No wall clock in this PD yet, so only idle dimming applies;
+            // the night window needs a time of day
+            backlight.auto_dim(&self.auto_dim, None, self.idle_ticks, BACKLIGHT_FADE_TICKS);
+            backlight.tick();
         }
+        if let Some(ref mut display) = self.display {
+            if self.cursor.is_visible() {
+                self.cursor.tick(display.framebuffer_mut());
+            }
+        }
+        self.flush();
+        Ok(Work::Done)
+    }
+
+    /// SPI DMA chunk finished
+    fn dma_irq(&mut self, _budget: u32) -> Result<Work, Infallible> {
+        let event = match self.dma {
+            Some(ref mut dma) => dma.handle_irq(),
+            None => DmaEvent::None,
+        };
+        match event {
+            DmaEvent::Complete => {
+                self.frames = self.frames.wrapping_add(1);
+                self.end_flush();
+            }
+            DmaEvent::Failed => {
+                self.dma_errors = self.dma_errors.wrapping_add(1);
+                // Send the next frame slower; at the floor the errors just
+                // keep counting
+                if let Some(ref mut dma) = self.dma {
+                    dma.slow_down();
+                }
+                self.end_flush();
+            }
+            DmaEvent::None | DmaEvent::Progress => {}
+        }
+        // Without refresh ticks, a frame presented while this flush was on
+        // the wire would wait for the App's next one
+        #[cfg(feature = "draw-ring")]
+        if matches!(event, DmaEvent::Complete | DmaEvent::Failed) {
+            self.flush();
+        }
+        Channel::new(DMA_CHANNEL).irq_ack().ok();
+        Ok(Work::Done)
+    }
+
+    /// The App queued draw commands: run up to `budget` of them, and
+    /// start a flush once a frame is presented
+    #[cfg(feature = "draw-ring")]
+    fn draw_commands(&mut self, budget: u32) -> Result<Work, Infallible> {
+        let Some(ring) = self.draw_ring else {
+            return Ok(Work::Done);
+        };
+        let base = DRAW_REGION_VADDR as *mut u8;
+        let mut batch = [DrawCommand::present(); DRAW_BATCH];
+        let batch = &mut batch[..(budget as usize).min(DRAW_BATCH)];
+        // Safety: DRAW_REGION_VADDR is mapped by tvdemo-dual.system
+        let n = unsafe { ring.pop_batch(commands_ptr(base), batch) };
+        let mut presented = false;
+        for command in &batch[..n] {
+            match self.execute(command) {
+                Ok(true) => {
+                    ring.mark_presented();
+                    presented = true;
+                }
+                Ok(false) => {}
+                Err(_) => self.rejected_draws = self.rejected_draws.wrapping_add(1),
+            }
+        }
+        if presented {
+            self.flush();
+            Channel::new(DRAW_CHANNEL).notify();
+        }
+        Ok(if ring.has_data() { Work::Deferred } else { Work::Done })
+    }
+
+    /// Validate one command against the panel, then draw it; true for a
+    /// `PRESENT`. Scene nodes are not kept here, so a command addressed to
+    /// one is refused.
+    #[cfg(feature = "draw-ring")]
+    fn execute(&mut self, command: &DrawCommand) -> Result<bool, DrawError> {
+        type Panel = Display<AnyController>;
+        command.validate(Panel::WIDTH, Panel::HEIGHT)?;
+        if command.node != 0 {
+            return Err(DrawError::BadNode(command.node));
+        }
+        let argb = command.color;
+        let color = Rgb565::from_rgb((argb >> 16) as u8, (argb >> 8) as u8, argb as u8);
+        match (command.op, self.display.as_mut()) {
+            (OP_PRESENT, _) => return Ok(true),
+            // No panel: drop the drawing, still let frames complete
+            (OP_CLEAR | OP_RECT, None) => {}
+            (OP_CLEAR, Some(display)) => display.clear(color),
+            (OP_RECT, Some(display)) => display
+                .try_fill_rect(command.x, command.y, command.w, command.h, color)
+                .map_err(|_| DrawError::OutOfBounds)?,
+            (op, _) => return Err(DrawError::UnknownOp(op)),
+        }
+        Ok(false)
+    }
+
+    /// Sample the touch controller, once it has the bus
+    fn poll_touch(&mut self) {
+        let grant = match self.bus.acquire(SPI_CLIENT_TOUCH, self.ticks) {
+            Ok(grant) => grant,
+            Err(_) => {
+                // Queued: sampled when the bus is handed on
+                self.touch_pending = true;
+                return;
+            }
+        };
+        self.touch_pending = false;
+        let mut touched = false;
+        if let Some(ref mut touch) = self.touch {
+            // TODO: Forward touch events to the application
+            if let Some(TouchEvent::Down(p) | TouchEvent::Move(p)) = touch.poll_event() {
+                touched = true;
+                if let Some(ref mut display) = self.display {
+                    self.cursor.show_at(display.framebuffer_mut(), p.x, p.y);
+                }
+            }
+        }
+        let pen_low = self.gpio.touch_irq_active();
+        let next = self.bus.release(grant, self.ticks).ok().flatten();
+        if self.pen.sampled(touched, pen_low) == PenAction::Rearm {
+            self.arm_pen_irq();
+        }
+        self.hand_on(next);
+    }
+
+    /// A PENIRQ falling edge: start sampling until the pen lifts
+    fn pen_down(&mut self) {
+        if !self.gpio.check_edge_detect(Pin::TouchIrq) || !self.pen.pen_down() {
+            // Another bank 0 pin's event, or a burst already running
+            return;
+        }
+        // Conversions toggle PENIRQ: no more edges until the burst ends
+        self.gpio.disable_falling_edge_detect(Pin::TouchIrq);
+        self.idle_ticks = 0;
+        self.poll_touch();
+    }
+
+    /// Listen for the next pen-down edge
+    ///
+    /// Clear any stale event, then arm: an edge from here on latches and
+    /// raises the interrupt, so a press right after the burst is not lost.
+    fn arm_pen_irq(&mut self) {
+        self.gpio.check_edge_detect(Pin::TouchIrq);
+        self.gpio.enable_falling_edge_detect(Pin::TouchIrq);
+    }
+
+    /// Start a flush if the panel needs one and the bus is free
+    fn flush(&mut self) {
+        let (Some(display), Some(dma)) = (&mut self.display, &mut self.dma) else {
+            return;
+        };
+        if self.flush_grant.is_some() {
+            return;
+        }
+        // Queued behind touch: the next refresh tick retries
+        let Ok(grant) = self.bus.acquire(SPI_CLIENT_DISPLAY, self.ticks) else {
+            return;
+        };
+        let result = display.refresh_dma(dma, &grant);
+        match result {
+            Ok(true) => self.flush_grant = Some(grant),
+            result => {
+                if result.is_err() {
+                    self.dma_errors = self.dma_errors.wrapping_add(1);
+                }
+                let next = self.bus.release(grant, self.ticks).ok().flatten();
+                self.hand_on(next);
+            }
+        }
+    }
+
+    /// The flush is over (done or abandoned): give the bus back
+    fn end_flush(&mut self) {
+        if let Some(grant) = self.flush_grant.take() {
+            let next = self.bus.release(grant, self.ticks).ok().flatten();
+            self.hand_on(next);
+        }
+    }
+
+    /// Serve the client whose turn it now is; the display retries on its
+    /// own every refresh tick
+    fn hand_on(&mut self, next: Option<u8>) {
+        if next == Some(SPI_CLIENT_TOUCH) && self.touch_pending {
+            self.poll_touch();
+        }
+    }
+
+    /// Revoke the bus from a client holding it too long
+    fn expire_bus(&mut self) {
+        let Some(expired) = self.bus.expire(self.ticks) else {
+            return;
+        };
+        if expired.client == SPI_CLIENT_DISPLAY {
+            // The flush is stuck: stop the DMA so it no longer drives SPI0
+            if let Some(ref mut dma) = self.dma {
+                dma.abort();
+            }
+            self.flush_grant = None;
+            self.dma_errors = self.dma_errors.wrapping_add(1);
+        }
+        self.hand_on(expired.next);
     }
 }
 
+/// Panel attached to this board
+///
+/// `display_controller` is patched into the image when the system is built
+/// (see `ControllerKind::from_config`), so one image serves every supported
+/// panel. Unknown values fall back to the ILI9341.
+fn configured_controller() -> ControllerKind {
+    let value = *sel4_microkit::var!(display_controller: usize = 0);
+    ControllerKind::from_config(value).unwrap_or(ControllerKind::Ili9341)
+}
+
 #[protection_domain]
 fn init() -> impl Handler {
+    let mut pd = DisplayPd::new();
+
     // TODO: Initialize SPI peripheral
-    // TODO: Initialize GPIO for DC/RST/BL pins
-    // TODO: Initialize ILI9341 display
+    // TODO: Initialize GPIO for DC/RST pins
+    // SAFETY: the system description maps PWM0 and the clock manager here,
+    // and nothing else in the system drives PWM0
+    let pwm = unsafe { Pwm::new(PWM_VADDR, CM_VADDR, BACKLIGHT_RANGE) };
+    pd.backlight = Some(Backlight::new(pwm, &mut pd.gpio));
+    // SAFETY: the system description maps these regions at these addresses,
+    // and DMA channels 4 and 5 are not used by the GPU firmware
+    pd.dma = Some(unsafe {
+        SpiDmaEngine::new(SPI0_VADDR, DMA_VADDR, DMA_BUFFER_VADDR as *mut u8, DMA_BUFFER_PHYS)
+    });
+    pd.display = AnyController::new(configured_controller()).and_then(|mut controller| {
+        controller.init().ok()?;
+        Some(Display::new(controller))
+    });
     // TODO: Initialize XPT2046 touch controller
     // TODO: Run touch calibration if needed
+    // Touch is sampled from the first pen-down interrupt on
+    pd.gpio.set_mode(Pin::TouchIrq, PinMode::Input);
+    pd.arm_pen_irq();
+
+    // Distinct channel ids below 63 with non-zero budgets: cannot fail
+    let _ = pd.events.register(TOUCH_CHANNEL, EVENT_BUDGET, DisplayPd::touch_irq);
+    let _ = pd.events.register(REFRESH_CHANNEL, EVENT_BUDGET, DisplayPd::refresh_tick);
+    let _ = pd.events.register(DMA_CHANNEL, EVENT_BUDGET, DisplayPd::dma_irq);
+    #[cfg(feature = "draw-ring")]
+    let _ = pd.events.register(DRAW_CHANNEL, DRAW_BATCH as u32, DisplayPd::draw_commands);
+
+    // The App does not queue commands before the notification below
+    #[cfg(feature = "draw-ring")]
+    {
+        // Safety: DRAW_REGION_VADDR is mapped by tvdemo-dual.system
+        pd.draw_ring = Some(unsafe {
+            let ring = header_ptr(DRAW_REGION_VADDR as *mut u8);
+            DrawRingHeader::init(ring);
+            &*ring
+        });
+        Channel::new(DRAW_CHANNEL).notify();
+    }
+
+    // Bring the backlight up once the panel shows something
+    if let Some(ref mut backlight) = pd.backlight {
+        backlight.fade_in(BACKLIGHT_FADE_TICKS);
+    }
 
-    DisplayPd::new()
+    pd
 }
 
 impl Handler for DisplayPd {
-    type Error = core::convert::Infallible;
-
-    fn notified(&mut self, channel: sel4_microkit::Channel) -> Result<(), Self::Error> {
-        match channel.index() {
-            // Touch interrupt
-            0 => {
-                if let Some(ref mut touch) = self.touch {
-                    // TODO: Read touch event and process
-                }
-            }
-            // Refresh timer
-            1 => {
-                if let Some(ref mut display) = self.display {
-                    // TODO: Refresh dirty regions
-                }
-            }
-            _ => {}
-        }
-        Ok(())
+    type Error = Infallible;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        EventLoop::run(self, |pd| &mut pd.events, channels.iter().map(|channel| channel.index()))
     }
 
     fn protected(
         &mut self,
-        channel: sel4_microkit::Channel,
+        channel: Channel,
         msg: MessageInfo,
     ) -> Result<MessageInfo, Self::Error> {
         // Handle IPC from other protection domains
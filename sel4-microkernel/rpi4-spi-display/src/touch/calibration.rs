@@ -0,0 +1,208 @@
+//! Touch Calibration
+//!
+//! Three-point calibration for resistive panels. Touching three known
+//! targets yields an affine transform from raw ADC readings to display
+//! coordinates:
+//!
+//! ```text
+//! x = (a·raw_x + b·raw_y + c) / divisor
+//! y = (d·raw_x + e·raw_y + f) / divisor
+//! ```
+//!
+//! Unlike the per-axis min/max [`Calibration`](super::xpt2046::Calibration),
+//! this also corrects a panel that is rotated or skewed against the LCD.
+//! The coefficients serialize to [`CALIBRATION_BYTES`] bytes so a calibration
+//! can be stored and restored at boot instead of repeated.
+//!
+//! Results are not clamped here: the
+//! [`TransformChain`](super::transform::TransformChain) decides whether a
+//! point off the panel is pulled onto the edge or rejected.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+/// Number of points the calibration routine asks for
+pub const CALIBRATION_POINTS: usize = 3;
+
+/// Targets shown during calibration: near three corners, away from the
+/// panel edges where resistive readings are least linear
+pub const CALIBRATION_TARGETS: [(u16, u16); CALIBRATION_POINTS] = [
+    (32, 24),   // top left
+    (288, 120), // right middle
+    (160, 216), // bottom middle
+];
+
+/// Size of a serialized calibration
+pub const CALIBRATION_BYTES: usize = 4 + 7 * 8;
+
+/// Leading bytes of a serialized calibration
+const CALIBRATION_MAGIC: [u8; 4] = *b"XPC1";
+
+/// One calibration touch: where the target was and what the ADC read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalibrationSample {
+    pub screen_x: u16,
+    pub screen_y: u16,
+    pub raw_x: u16,
+    pub raw_y: u16,
+}
+
+/// Why a calibration could not be computed or restored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// The three touches are in a line, so they fix no transform
+    Degenerate,
+    /// Not all targets have been touched yet
+    Incomplete,
+    /// Stored bytes are not a calibration (wrong magic or zero divisor)
+    InvalidData,
+}
+
+/// Affine transform from raw ADC readings to display coordinates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AffineCalibration {
+    a: i64,
+    b: i64,
+    c: i64,
+    d: i64,
+    e: i64,
+    f: i64,
+    /// Common denominator; never zero
+    divisor: i64,
+}
+
+impl AffineCalibration {
+    /// Solve the transform from three samples
+    ///
+    /// Uses the determinant form of the three-point solution, so the only
+    /// division happens when the transform is applied.
+    pub fn from_samples(samples: &[CalibrationSample; CALIBRATION_POINTS]) -> Result<Self, CalibrationError> {
+        let [p0, p1, p2] = samples.map(|s| {
+            (s.raw_x as i64, s.raw_y as i64, s.screen_x as i64, s.screen_y as i64)
+        });
+        let (rx0, ry0, sx0, sy0) = p0;
+        let (rx1, ry1, sx1, sy1) = p1;
+        let (rx2, ry2, sx2, sy2) = p2;
+
+        let divisor = (rx0 - rx2) * (ry1 - ry2) - (rx1 - rx2) * (ry0 - ry2);
+        if divisor == 0 {
+            return Err(CalibrationError::Degenerate);
+        }
+
+        let a = (sx0 - sx2) * (ry1 - ry2) - (sx1 - sx2) * (ry0 - ry2);
+        let b = (rx0 - rx2) * (sx1 - sx2) - (sx0 - sx2) * (rx1 - rx2);
+        let d = (sy0 - sy2) * (ry1 - ry2) - (sy1 - sy2) * (ry0 - ry2);
+        let e = (rx0 - rx2) * (sy1 - sy2) - (sy0 - sy2) * (rx1 - rx2);
+
+        // The offsets make the first sample map exactly onto its target
+        Ok(Self {
+            a,
+            b,
+            c: sx0 * divisor - a * rx0 - b * ry0,
+            d,
+            e,
+            f: sy0 * divisor - d * rx0 - e * ry0,
+            divisor,
+        })
+    }
+
+    /// Map a raw reading into display coordinates, which may lie off the
+    /// display
+    ///
+    /// Computed in i128: coefficients restored from storage are arbitrary
+    /// i64 values and a raw reading is at most 16 bits, so no term overflows.
+    #[verus_verify]
+    pub fn project(&self, raw_x: u16, raw_y: u16) -> (i128, i128)
+        requires
+            self.divisor != 0,
+    {
+        let rx = raw_x as i128;
+        let ry = raw_y as i128;
+        let div = self.divisor as i128;
+
+        let x = (self.a as i128 * rx + self.b as i128 * ry + self.c as i128) / div;
+        let y = (self.d as i128 * rx + self.e as i128 * ry + self.f as i128) / div;
+
+        (x, y)
+    }
+
+    /// Serialize for persistent storage (little-endian coefficients)
+    pub fn to_bytes(&self) -> [u8; CALIBRATION_BYTES] {
+        let mut out = [0u8; CALIBRATION_BYTES];
+        out[..4].copy_from_slice(&CALIBRATION_MAGIC);
+        let words = [self.a, self.b, self.c, self.d, self.e, self.f, self.divisor];
+        for (chunk, word) in out[4..].chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    /// Restore a calibration saved with [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8; CALIBRATION_BYTES]) -> Result<Self, CalibrationError> {
+        if bytes[..4] != CALIBRATION_MAGIC {
+            return Err(CalibrationError::InvalidData);
+        }
+        let mut words = [0i64; 7];
+        for (word, chunk) in words.iter_mut().zip(bytes[4..].chunks_exact(8)) {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(chunk);
+            *word = i64::from_le_bytes(le);
+        }
+        let [a, b, c, d, e, f, divisor] = words;
+        if divisor == 0 {
+            return Err(CalibrationError::InvalidData);
+        }
+        Ok(Self { a, b, c, d, e, f, divisor })
+    }
+}
+
+/// Calibration routine: walks the user through [`CALIBRATION_TARGETS`]
+///
+/// Draw a crosshair at [`current_target`](Self::current_target), pass the
+/// raw reading of the next touch to [`record`](Self::record), and call
+/// [`finish`](Self::finish) once every target has been touched.
+pub struct Calibrator {
+    samples: [CalibrationSample; CALIBRATION_POINTS],
+    count: usize,
+}
+
+impl Calibrator {
+    pub const fn new() -> Self {
+        Self {
+            samples: [CalibrationSample { screen_x: 0, screen_y: 0, raw_x: 0, raw_y: 0 }; CALIBRATION_POINTS],
+            count: 0,
+        }
+    }
+
+    /// Target the user should touch next, or `None` when all are done
+    pub fn current_target(&self) -> Option<(u16, u16)> {
+        CALIBRATION_TARGETS.get(self.count).copied()
+    }
+
+    /// Record the raw reading for the current target; ignored once done
+    pub fn record(&mut self, raw_x: u16, raw_y: u16) {
+        if let Some((screen_x, screen_y)) = self.current_target() {
+            self.samples[self.count] = CalibrationSample { screen_x, screen_y, raw_x, raw_y };
+            self.count += 1;
+        }
+    }
+
+    /// Solve the transform from the recorded touches
+    pub fn finish(&self) -> Result<AffineCalibration, CalibrationError> {
+        if self.count < CALIBRATION_POINTS {
+            return Err(CalibrationError::Incomplete);
+        }
+        AffineCalibration::from_samples(&self.samples)
+    }
+
+    /// Start over from the first target
+    pub fn restart(&mut self) {
+        self.count = 0;
+    }
+}
+
+impl Default for Calibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
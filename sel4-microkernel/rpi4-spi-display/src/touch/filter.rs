@@ -0,0 +1,263 @@
+//! Touch Edge Rejection and Pressure Curves
+//!
+//! Resistive overlays report spurious touches where the panel meets the
+//! bezel: a palm resting on the frame, or the overlay flexing against it.
+//! [`TouchFilter`] sits between the controller's calibrated readings and the
+//! `Down`/`Move`/`Up` events and:
+//!
+//! - maps the panel's touch resistance onto a pressure of 0..=255 through a
+//!   [`PressureCurve`], so `TouchPoint::pressure` reads higher for a
+//!   firmer press whatever the panel
+//! - starts a touch only at the curve's press threshold and keeps it down
+//!   to the lower release threshold, so a touch hovering at the threshold
+//!   does not flicker up and down
+//! - ignores touches that start in the [`EdgeMargins`] until they lift,
+//!   while a touch that starts inside may still drag out to the edge
+//!
+//! Margins are checked against the screen size when they are set, so a
+//! filter can never end up rejecting the whole panel.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::TouchPoint;
+
+/// Margin on each edge a new filter starts with (pixels)
+pub const DEFAULT_EDGE_MARGIN: u16 = 4;
+
+/// Most knee points a [`PressureCurve`] has
+pub const MAX_CURVE_POINTS: usize = 4;
+
+/// Why filter parameters were refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterError {
+    /// The margins leave no active area on the screen
+    MarginsTooWide,
+    /// A curve needs 2 to [`MAX_CURVE_POINTS`] points
+    CurvePointCount,
+    /// Curve resistances must rise and pressures must not
+    CurveNotMonotonic,
+    /// The release threshold is above the press threshold
+    ReleaseAbovePress,
+}
+
+/// Strips along each screen edge where new touches are ignored (pixels)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdgeMargins {
+    pub left: u16,
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+}
+
+impl EdgeMargins {
+    /// No margins: the whole screen is active
+    pub const NONE: Self = Self::uniform(0);
+
+    /// The same margin on every edge
+    pub const fn uniform(margin: u16) -> Self {
+        Self { left: margin, top: margin, right: margin, bottom: margin }
+    }
+
+    /// Do the margins leave some of a `width` x `height` screen active?
+    pub const fn fits(&self, width: u16, height: u16) -> bool {
+        (self.left as u32 + self.right as u32) < width as u32
+            && (self.top as u32 + self.bottom as u32) < height as u32
+    }
+
+    /// Is (x, y) inside the active area of a `width` x `height` screen?
+    #[verus_verify]
+    pub fn contains(&self, x: u16, y: u16, width: u16, height: u16) -> bool
+        ensures
+            result ==> x >= self.left && y >= self.top,
+            result ==> (x as u32) + (self.right as u32) < width as u32,
+            result ==> (y as u32) + (self.bottom as u32) < height as u32,
+    {
+        x >= self.left
+            && y >= self.top
+            && (x as u32) + (self.right as u32) < width as u32
+            && (y as u32) + (self.bottom as u32) < height as u32
+    }
+}
+
+/// Map a resistance between two knees of a curve onto the pressures there
+#[verus_verify]
+fn interpolate(resistance: u16, r0: u16, p0: u8, r1: u16, p1: u8) -> u8
+    requires
+        r0 < r1,
+        p0 >= p1,
+        r0 <= resistance,
+        resistance <= r1,
+    ensures
+        p1 <= result,
+        result <= p0,
+{
+    let span = (r1 - r0) as u32;
+    let drop = (p0 - p1) as u32;
+    let fall = (resistance - r0) as u32 * drop / span;
+    p0 - fall as u8
+}
+
+/// Piecewise-linear map from touch resistance (the controller's reading,
+/// lower for a firmer press) to pressure 0..=255, with the thresholds a
+/// touch must reach to start and stay down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PressureCurve {
+    /// (resistance, pressure) knees, resistance rising and pressure falling
+    points: [(u16, u8); MAX_CURVE_POINTS],
+    len: usize,
+    press: u8,
+    release: u8,
+}
+
+impl PressureCurve {
+    /// Curve for an XPT2046 on a typical 2.8" overlay: full pressure at a
+    /// resistance of 100 or less, none from the 1000 the driver already
+    /// treats as no touch. A starting point; tune per panel.
+    pub const DEFAULT: Self = Self {
+        points: [(100, 255), (400, 128), (1000, 0), (1000, 0)],
+        len: 3,
+        press: 48,
+        release: 24,
+    };
+
+    /// Curve through `points` ((resistance, pressure), resistance rising,
+    /// pressure not rising). A touch starts at `press` and ends below
+    /// `release`.
+    pub fn new(points: &[(u16, u8)], press: u8, release: u8) -> Result<Self, FilterError> {
+        if points.len() < 2 || points.len() > MAX_CURVE_POINTS {
+            return Err(FilterError::CurvePointCount);
+        }
+        if points.windows(2).any(|pair| pair[0].0 >= pair[1].0 || pair[0].1 < pair[1].1) {
+            return Err(FilterError::CurveNotMonotonic);
+        }
+        if release > press {
+            return Err(FilterError::ReleaseAbovePress);
+        }
+        let mut knees = [points[points.len() - 1]; MAX_CURVE_POINTS];
+        knees[..points.len()].copy_from_slice(points);
+        Ok(Self { points: knees, len: points.len(), press, release })
+    }
+
+    /// Pressure for a touch resistance; flat beyond the first and last knees
+    pub fn pressure(&self, resistance: u16) -> u8 {
+        let knees = &self.points[..self.len];
+        let (first, last) = (knees[0], knees[self.len - 1]);
+        if resistance <= first.0 {
+            return first.1;
+        }
+        if resistance >= last.0 {
+            return last.1;
+        }
+        knees
+            .windows(2)
+            .find(|pair| resistance <= pair[1].0)
+            .map_or(last.1, |pair| interpolate(resistance, pair[0].0, pair[0].1, pair[1].0, pair[1].1))
+    }
+
+    /// Pressure a touch must reach to start
+    pub fn press_threshold(&self) -> u8 {
+        self.press
+    }
+
+    /// Pressure below which a touch ends
+    pub fn release_threshold(&self) -> u8 {
+        self.release
+    }
+}
+
+impl Default for PressureCurve {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Where the current contact stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Contact {
+    /// Nothing on the panel (or too light to count)
+    Idle,
+    /// Touch accepted; reported until it lifts
+    Active,
+    /// Touch began in a margin; ignored until it lifts
+    Rejected,
+}
+
+/// Edge rejection and pressure thresholds for one panel
+#[derive(Clone, Copy, Debug)]
+pub struct TouchFilter {
+    width: u16,
+    height: u16,
+    margins: EdgeMargins,
+    curve: PressureCurve,
+    contact: Contact,
+}
+
+impl TouchFilter {
+    /// Filter for a `width` x `height` screen with
+    /// [`DEFAULT_EDGE_MARGIN`] on each edge (none if the screen is too
+    /// small for it) and the default pressure curve
+    pub const fn new(width: u16, height: u16) -> Self {
+        let margins = EdgeMargins::uniform(DEFAULT_EDGE_MARGIN);
+        Self {
+            width,
+            height,
+            margins: if margins.fits(width, height) { margins } else { EdgeMargins::NONE },
+            curve: PressureCurve::DEFAULT,
+            contact: Contact::Idle,
+        }
+    }
+
+    /// Ignore touches starting within `margins` of the edges; refused,
+    /// keeping the old margins, if they leave no active area
+    pub fn set_margins(&mut self, margins: EdgeMargins) -> Result<(), FilterError> {
+        if !margins.fits(self.width, self.height) {
+            return Err(FilterError::MarginsTooWide);
+        }
+        self.margins = margins;
+        Ok(())
+    }
+
+    /// Current margins
+    pub fn margins(&self) -> EdgeMargins {
+        self.margins
+    }
+
+    /// Use `curve` for pressure and the press/release thresholds
+    pub fn set_curve(&mut self, curve: PressureCurve) {
+        self.curve = curve;
+    }
+
+    /// Current pressure curve
+    pub fn curve(&self) -> &PressureCurve {
+        &self.curve
+    }
+
+    /// Filter one reading (`None` when the controller sees no touch). The
+    /// point's `pressure` goes in as the raw resistance and comes out
+    /// mapped through the curve; `None` means no touch to report.
+    pub fn process(&mut self, reading: Option<TouchPoint>) -> Option<TouchPoint> {
+        let Some(point) = reading else {
+            self.contact = Contact::Idle;
+            return None;
+        };
+        let pressure = self.curve.pressure(point.pressure);
+        let point = TouchPoint { pressure: pressure as u16, ..point };
+
+        self.contact = match self.contact {
+            Contact::Idle if pressure < self.curve.press => Contact::Idle,
+            Contact::Idle if self.margins.contains(point.x, point.y, self.width, self.height) => {
+                Contact::Active
+            }
+            Contact::Idle => Contact::Rejected,
+            _ if pressure < self.curve.release => Contact::Idle,
+            contact => contact,
+        };
+        (self.contact == Contact::Active).then_some(point)
+    }
+
+    /// Is a touch being ignored because it began in a margin?
+    pub fn rejecting(&self) -> bool {
+        self.contact == Contact::Rejected
+    }
+}
@@ -0,0 +1,233 @@
+//! Touch Gesture Recognition
+//!
+//! Turns the raw `Down`/`Move`/`Up` stream from a [`TouchController`] into
+//! taps, double-taps, long-presses, swipes and drags.
+//!
+//! Time is counted in frames: call [`GestureRecognizer::tick`] once per frame
+//! (the demo runs at 60fps) and pass every touch event to
+//! [`GestureRecognizer::feed`]. Long-presses are reported from `tick`, since
+//! they fire while the finger is still down.
+//!
+//! A double-tap is reported as a `Tap` followed by a `DoubleTap`, so a single
+//! tap is never delayed waiting for a possible second one.
+//!
+//! Every stored point goes through [`clamp_point`], so the recognizer's
+//! coordinates always lie on the 320×240 panel and displacements between
+//! them fit in an `i16`. The per-axis geometry (clamping, travel, and the
+//! tap and swipe thresholds) is the verified one in `verified-microkernel`,
+//! host-tested there.
+//!
+//! [`TouchController`]: super::TouchController
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use super::{TouchEvent, TouchPoint};
+use verified_microkernel::gesture::{axis_delta, axis_distance, clamp_axis, swipe_axis, StrokeAxis};
+
+pub use verified_microkernel::gesture::{SWIPE_MIN_DISTANCE, TAP_SLOP};
+
+/// Panel width in touch coordinates
+const SCREEN_WIDTH: u16 = 320;
+/// Panel height in touch coordinates
+const SCREEN_HEIGHT: u16 = 240;
+
+/// Longest press (frames) reported as a tap
+pub const TAP_MAX_FRAMES: u32 = 15;
+/// Longest gap (frames) between two taps of a double-tap
+pub const DOUBLE_TAP_FRAMES: u32 = 18;
+/// Press duration (frames) that triggers a long-press
+pub const LONG_PRESS_FRAMES: u32 = 36;
+/// Longest gesture (frames) that still counts as a swipe
+pub const SWIPE_MAX_FRAMES: u32 = 30;
+
+/// Swipe direction, by the axis with the larger travel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Recognized gesture
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    /// Short press and release without moving
+    Tap(TouchPoint),
+    /// Second tap close in time and place to the previous one
+    DoubleTap(TouchPoint),
+    /// Finger held still for [`LONG_PRESS_FRAMES`]
+    LongPress(TouchPoint),
+    /// Fast stroke that ended with the finger lifted
+    Swipe { direction: SwipeDirection, distance: u16 },
+    /// Finger moved while down; `dx`/`dy` are relative to the previous report
+    Drag { at: TouchPoint, dx: i16, dy: i16 },
+    /// Drag finished with the finger lifted
+    DragEnd(TouchPoint),
+}
+
+/// Where the recognizer is within a gesture
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// No finger on the panel
+    Idle,
+    /// Finger down and still within the tap slop
+    Pressed { start: TouchPoint, since: u32 },
+    /// Long-press already reported; waiting for the finger to lift
+    Held,
+    /// Finger moved past the slop
+    Dragging { start: TouchPoint, last: TouchPoint, since: u32 },
+}
+
+/// Gesture recognizer state machine
+pub struct GestureRecognizer {
+    state: State,
+    /// Frame counter advanced by `tick`
+    now: u32,
+    /// Last tap, for double-tap detection
+    last_tap: Option<(TouchPoint, u32)>,
+}
+
+/// Clamp a point onto the panel
+#[verus_verify]
+pub fn clamp_point(point: TouchPoint) -> TouchPoint
+    ensures
+        result.x < SCREEN_WIDTH,
+        result.y < SCREEN_HEIGHT,
+        result.pressure == point.pressure,
+{
+    TouchPoint {
+        x: clamp_axis(point.x, SCREEN_WIDTH),
+        y: clamp_axis(point.y, SCREEN_HEIGHT),
+        pressure: point.pressure,
+    }
+}
+
+/// Are two points within the tap slop of each other?
+fn within_slop(a: TouchPoint, b: TouchPoint) -> bool {
+    verified_microkernel::gesture::within_slop(axis_distance(a.x, b.x), axis_distance(a.y, b.y))
+}
+
+impl GestureRecognizer {
+    /// Create an idle recognizer
+    pub const fn new() -> Self {
+        Self {
+            state: State::Idle,
+            now: 0,
+            last_tap: None,
+        }
+    }
+
+    /// Forget any gesture in progress
+    pub fn reset(&mut self) {
+        self.state = State::Idle;
+        self.last_tap = None;
+    }
+
+    /// Advance one frame; reports a long-press once the hold time is reached
+    pub fn tick(&mut self) -> Option<Gesture> {
+        self.now = self.now.wrapping_add(1);
+
+        match self.state {
+            State::Pressed { start, since }
+                if self.now.wrapping_sub(since) >= LONG_PRESS_FRAMES =>
+            {
+                self.state = State::Held;
+                self.last_tap = None;
+                Some(Gesture::LongPress(start))
+            }
+            _ => None,
+        }
+    }
+
+    /// Process one touch event
+    pub fn feed(&mut self, event: TouchEvent) -> Option<Gesture> {
+        match event {
+            TouchEvent::Down(point) => {
+                self.state = State::Pressed { start: clamp_point(point), since: self.now };
+                None
+            }
+            TouchEvent::Move(point) => self.on_move(clamp_point(point)),
+            TouchEvent::Up => self.on_up(),
+        }
+    }
+
+    fn on_move(&mut self, point: TouchPoint) -> Option<Gesture> {
+        let (start, last, since) = match self.state {
+            State::Pressed { start, since } => {
+                if within_slop(start, point) {
+                    return None;
+                }
+                (start, start, since)
+            }
+            State::Dragging { start, last, since } => (start, last, since),
+            // Moves after a long-press or without a Down are ignored
+            State::Idle | State::Held => return None,
+        };
+
+        self.state = State::Dragging { start, last: point, since };
+        Some(Gesture::Drag {
+            at: point,
+            dx: axis_delta(last.x, point.x),
+            dy: axis_delta(last.y, point.y),
+        })
+    }
+
+    fn on_up(&mut self) -> Option<Gesture> {
+        let state = self.state;
+        self.state = State::Idle;
+
+        match state {
+            State::Pressed { start, since } => {
+                if self.now.wrapping_sub(since) > TAP_MAX_FRAMES {
+                    return None;
+                }
+                self.on_tap(start)
+            }
+            State::Dragging { start, last, since } => {
+                self.last_tap = None;
+                let fast = self.now.wrapping_sub(since) <= SWIPE_MAX_FRAMES;
+                match swipe_direction(start, last) {
+                    Some((direction, distance)) if fast => {
+                        Some(Gesture::Swipe { direction, distance })
+                    }
+                    _ => Some(Gesture::DragEnd(last)),
+                }
+            }
+            State::Idle | State::Held => None,
+        }
+    }
+
+    fn on_tap(&mut self, point: TouchPoint) -> Option<Gesture> {
+        if let Some((prev, at)) = self.last_tap {
+            if self.now.wrapping_sub(at) <= DOUBLE_TAP_FRAMES && within_slop(prev, point) {
+                self.last_tap = None;
+                return Some(Gesture::DoubleTap(point));
+            }
+        }
+        self.last_tap = Some((point, self.now));
+        Some(Gesture::Tap(point))
+    }
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Direction and length of a stroke, if it is long enough to be a swipe
+fn swipe_direction(start: TouchPoint, end: TouchPoint) -> Option<(SwipeDirection, u16)> {
+    let dx = axis_distance(start.x, end.x);
+    let dy = axis_distance(start.y, end.y);
+
+    let (axis, distance) = swipe_axis(dx, dy)?;
+    let direction = match axis {
+        StrokeAxis::X if end.x > start.x => SwipeDirection::Right,
+        StrokeAxis::X => SwipeDirection::Left,
+        StrokeAxis::Y if end.y > start.y => SwipeDirection::Down,
+        StrokeAxis::Y => SwipeDirection::Up,
+    };
+    Some((direction, distance))
+}
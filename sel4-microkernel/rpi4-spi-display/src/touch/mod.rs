@@ -3,11 +3,21 @@
 //! Provides verified drivers for resistive touch controllers.
 
 pub mod xpt2046;
+pub mod calibration;
+pub mod gesture;
+pub mod filter;
+pub mod transform;
+pub mod penirq;
 
 pub use xpt2046::Xpt2046;
+pub use calibration::{AffineCalibration, CalibrationError, Calibrator};
+pub use gesture::{Gesture, GestureRecognizer, SwipeDirection};
+pub use filter::{EdgeMargins, FilterError, PressureCurve, TouchFilter};
+pub use transform::{RawMapping, Rejection, Scaling, TransformChain, TransformError};
+pub use penirq::{PenAction, PenIrq, PenState, RELEASE_SAMPLES};
 
 /// Touch point with screen coordinates
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TouchPoint {
     /// X coordinate (0-319)
     pub x: u16,
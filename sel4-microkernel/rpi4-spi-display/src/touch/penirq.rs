@@ -0,0 +1,130 @@
+//! PENIRQ-Driven Touch Sampling
+//!
+//! The XPT2046 pulls PENIRQ (T_IRQ, GPIO17) low while the panel is pressed.
+//! Rather than reading X, Y and pressure over SPI on every refresh tick, the
+//! Display PD arms the GPIO falling-edge detect on that pin and samples only
+//! between a pen-down interrupt and the pen lifting:
+//!
+//! ```text
+//!          PENIRQ falling edge (GPIO bank 0 IRQ)
+//!   Idle ────────────────────────────────────────► Burst ── one sample per
+//!    ▲  edge detect armed                            │      refresh tick,
+//!    │                                               │      edge detect off
+//!    └── clear the event, re-arm ◄───────────────────┘
+//!        RELEASE_SAMPLES samples in a row with no touch and PENIRQ high
+//! ```
+//!
+//! The edge detect is off during a burst: the controller stops driving
+//! PENIRQ while it converts, so every sample would otherwise end in another
+//! edge and another interrupt. A burst only ends once the pen has read as up
+//! several times in a row, so one noisy sample does not cut a drag in two.
+//!
+//! # Verification Properties
+//!
+//! - A burst never ends on a sample that saw a touch or PENIRQ low
+//! - A burst ends after at most `RELEASE_SAMPLES` samples with the pen up
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+/// Samples in a row that must see the pen up before a burst ends
+pub const RELEASE_SAMPLES: u8 = 2;
+
+/// Where the sampler is between pen-down interrupts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PenState {
+    /// Waiting for a pen-down interrupt; the edge detect is armed
+    Idle,
+    /// Sampling every refresh tick; `released` samples in a row have seen
+    /// the pen up
+    Burst { released: u8 },
+}
+
+/// What the PD does after a sample
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PenAction {
+    /// Sample again on the next refresh tick
+    Continue,
+    /// The pen is up: clear the edge event and re-arm the edge detect
+    Rearm,
+}
+
+/// Pen-down interrupt to pen-up sampling state
+pub struct PenIrq {
+    state: PenState,
+    /// Pen-down interrupts that started a burst
+    bursts: u32,
+    /// Samples taken across all bursts
+    samples: u32,
+}
+
+impl PenIrq {
+    /// Idle, waiting for the first pen-down interrupt
+    pub const fn new() -> Self {
+        Self { state: PenState::Idle, bursts: 0, samples: 0 }
+    }
+
+    pub fn state(&self) -> PenState {
+        self.state
+    }
+
+    /// Should the touch controller be sampled on this refresh tick?
+    pub fn sampling(&self) -> bool {
+        matches!(self.state, PenState::Burst { .. })
+    }
+
+    /// A pen-down interrupt: start a burst. `false` if one is already
+    /// running, when there is nothing to start.
+    pub fn pen_down(&mut self) -> bool {
+        if self.sampling() {
+            return false;
+        }
+        self.state = PenState::Burst { released: 0 };
+        self.bursts = self.bursts.wrapping_add(1);
+        true
+    }
+
+    /// Record a burst sample: whether the controller read a touch and
+    /// whether PENIRQ was still low
+    #[verus_verify]
+    pub fn sampled(&mut self, touched: bool, pen_low: bool) -> (action: PenAction)
+        ensures
+            touched ==> action == PenAction::Continue,
+            pen_low ==> action == PenAction::Continue,
+    {
+        let PenState::Burst { released } = self.state else {
+            // Not in a burst (a sample queued behind the bus that landed
+            // after the pen lifted): nothing to end
+            return PenAction::Continue;
+        };
+        self.samples = self.samples.wrapping_add(1);
+        if touched || pen_low {
+            self.state = PenState::Burst { released: 0 };
+            return PenAction::Continue;
+        }
+        let released = released + 1;
+        if released >= RELEASE_SAMPLES {
+            self.state = PenState::Idle;
+            PenAction::Rearm
+        } else {
+            self.state = PenState::Burst { released };
+            PenAction::Continue
+        }
+    }
+
+    /// Pen-down interrupts that started a burst
+    pub fn bursts(&self) -> u32 {
+        self.bursts
+    }
+
+    /// Samples taken, against one per refresh tick when polling
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+}
+
+impl Default for PenIrq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,269 @@
+//! Touch Coordinate Transform Chain
+//!
+//! Every step from a raw XPT2046 sample to a UI coordinate, in one place:
+//!
+//! ```text
+//! raw ADC ──calibration──▶ touch frame ──rotation──▶ screen ──scaling──▶ UI
+//! ```
+//!
+//! - **calibration** (per-axis min/max or three-point affine) maps the raw
+//!   reading into the touch frame: the 320×240 orientation the panel was
+//!   calibrated in. A reading more than [`EDGE_SLACK`] pixels off the panel
+//!   is rejected as noise; anything closer is pulled onto the edge.
+//! - **rotation** turns the touch frame with the display, so a touch still
+//!   lands on what is drawn under it after the panel is rotated.
+//! - **scaling** maps screen pixels back onto the UI surface (the
+//!   framebuffer), which may be shown offset or enlarged on a bigger panel.
+//!   Touches outside the area the UI is shown in are rejected.
+//!
+//! [`TransformChain::map`] is verified to give either a [`Rejection`] or a
+//! point strictly inside the UI surface, whatever the raw sample.
+
+use verus_builtin::*;
+use verus_builtin_macros::*;
+
+use crate::display::Rotation;
+
+use super::calibration::AffineCalibration;
+use super::xpt2046::Calibration;
+
+/// Width of the touch frame (the orientation calibration targets are in)
+pub const TOUCH_WIDTH: u16 = 320;
+/// Height of the touch frame
+pub const TOUCH_HEIGHT: u16 = 240;
+
+/// How far (pixels) a calibrated reading may fall off the panel and still
+/// be pulled onto the edge instead of rejected
+pub const EDGE_SLACK: u16 = 24;
+
+/// Why a raw sample produced no UI coordinate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// The min/max calibration has an empty raw range on some axis
+    BadCalibration,
+    /// Calibration puts the sample well outside the panel
+    OffPanel,
+    /// On the screen, but outside the area the UI is shown in
+    OutsideUi,
+}
+
+/// Why transform parameters were refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransformError {
+    /// A scale factor or UI dimension is zero
+    ZeroExtent,
+}
+
+/// How raw readings are calibrated into the touch frame
+#[derive(Clone, Copy)]
+pub enum RawMapping {
+    /// Per-axis linear map between the min/max readings
+    MinMax(Calibration),
+    /// Three-point calibration, which also corrects rotation and skew
+    Affine(AffineCalibration),
+}
+
+/// Where the UI surface is shown on the screen
+///
+/// A UI pixel (x, y) covers screen pixels from
+/// `(origin_x + x·num/den, origin_y + y·num/den)`; `num`/`den` is the
+/// enlargement (2/1 doubles the UI on a panel twice its size).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scaling {
+    origin_x: u16,
+    origin_y: u16,
+    num: u16,
+    den: u16,
+}
+
+impl Scaling {
+    /// The UI drawn 1:1 from the top-left corner
+    pub const IDENTITY: Self = Self { origin_x: 0, origin_y: 0, num: 1, den: 1 };
+
+    /// UI shown at (`origin_x`, `origin_y`), enlarged by `num`/`den`
+    pub const fn new(origin_x: u16, origin_y: u16, num: u16, den: u16) -> Result<Self, TransformError> {
+        if num == 0 || den == 0 {
+            return Err(TransformError::ZeroExtent);
+        }
+        Ok(Self { origin_x, origin_y, num, den })
+    }
+}
+
+impl Default for Scaling {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Linear map of one raw axis between its min and max readings, unclamped
+#[verus_verify]
+fn project_axis(raw: u16, min: u16, max: u16, extent: u16) -> Option<i128>
+    ensures
+        result.is_some() ==> max > min,
+{
+    if max <= min {
+        return None;
+    }
+    let offset = raw as i128 - min as i128;
+    let range = (max - min) as i128;
+    Some(offset * (extent as i128 - 1) / range)
+}
+
+/// Pull a calibrated coordinate onto one axis of the touch frame, or
+/// reject it when it lies more than [`EDGE_SLACK`] off the panel
+#[verus_verify]
+fn settle_axis(value: i128, extent: u16) -> Option<u16>
+    requires
+        extent > 0,
+    ensures
+        result.is_some() ==> result.unwrap() < extent,
+{
+    if value < -(EDGE_SLACK as i128) || value >= extent as i128 + EDGE_SLACK as i128 {
+        None
+    } else if value < 0 {
+        Some(0)
+    } else if value >= extent as i128 {
+        Some(extent - 1)
+    } else {
+        Some(value as u16)
+    }
+}
+
+/// Turn a touch-frame point clockwise with the display
+#[verus_verify]
+fn rotate(x: u16, y: u16, width: u16, height: u16, rotation: Rotation) -> (u16, u16)
+    requires
+        x < width,
+        y < height,
+    ensures
+        (rotation == Rotation::Deg0 || rotation == Rotation::Deg180) ==> result.0 < width && result.1 < height,
+        (rotation == Rotation::Deg90 || rotation == Rotation::Deg270) ==> result.0 < height && result.1 < width,
+{
+    match rotation {
+        Rotation::Deg0 => (x, y),
+        Rotation::Deg90 => (height - 1 - y, x),
+        Rotation::Deg180 => (width - 1 - x, height - 1 - y),
+        Rotation::Deg270 => (y, width - 1 - x),
+    }
+}
+
+/// Map one screen axis back onto the UI surface
+#[verus_verify]
+fn unscale_axis(screen: u16, origin: u16, num: u16, den: u16, ui_extent: u16) -> Option<u16>
+    requires
+        num > 0,
+    ensures
+        result.is_some() ==> result.unwrap() < ui_extent,
+{
+    if screen < origin {
+        return None;
+    }
+    let ui = (screen - origin) as u32 * den as u32 / num as u32;
+    if ui >= ui_extent as u32 {
+        None
+    } else {
+        Some(ui as u16)
+    }
+}
+
+/// Calibration, rotation and scaling from raw touch samples to UI pixels
+#[derive(Clone, Copy)]
+pub struct TransformChain {
+    mapping: RawMapping,
+    rotation: Rotation,
+    scaling: Scaling,
+    /// UI surface size; never zero
+    ui_width: u16,
+    ui_height: u16,
+}
+
+impl TransformChain {
+    /// Chain for a `ui_width` x `ui_height` UI drawn 1:1 on an unrotated
+    /// panel, with the default min/max calibration
+    pub const fn new(ui_width: u16, ui_height: u16) -> Result<Self, TransformError> {
+        if ui_width == 0 || ui_height == 0 {
+            return Err(TransformError::ZeroExtent);
+        }
+        Ok(Self {
+            mapping: RawMapping::MinMax(Calibration::DEFAULT),
+            rotation: Rotation::Deg0,
+            scaling: Scaling::IDENTITY,
+            ui_width,
+            ui_height,
+        })
+    }
+
+    /// Calibrate raw readings with `mapping`
+    pub fn set_mapping(&mut self, mapping: RawMapping) {
+        self.mapping = mapping;
+    }
+
+    /// Current calibration
+    pub fn mapping(&self) -> RawMapping {
+        self.mapping
+    }
+
+    /// Rotate touches with the display; `rotation` is relative to the
+    /// orientation the panel was calibrated in
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Current rotation
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Show the UI on the screen by `scaling`
+    pub fn set_scaling(&mut self, scaling: Scaling) {
+        self.scaling = scaling;
+    }
+
+    /// Current scaling
+    pub fn scaling(&self) -> Scaling {
+        self.scaling
+    }
+
+    /// UI surface size (width, height)
+    pub fn ui_size(&self) -> (u16, u16) {
+        (self.ui_width, self.ui_height)
+    }
+
+    /// Calibrate a raw reading into the touch frame
+    fn calibrate(&self, raw_x: u16, raw_y: u16) -> Result<(u16, u16), Rejection> {
+        let (x, y) = match self.mapping {
+            RawMapping::MinMax(cal) => (
+                project_axis(raw_x, cal.x_min, cal.x_max, TOUCH_WIDTH).ok_or(Rejection::BadCalibration)?,
+                project_axis(raw_y, cal.y_min, cal.y_max, TOUCH_HEIGHT).ok_or(Rejection::BadCalibration)?,
+            ),
+            RawMapping::Affine(affine) => affine.project(raw_x, raw_y),
+        };
+        match (settle_axis(x, TOUCH_WIDTH), settle_axis(y, TOUCH_HEIGHT)) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(Rejection::OffPanel),
+        }
+    }
+
+    /// Map a raw sample through calibration, rotation and scaling
+    #[verus_verify]
+    pub fn map(&self, raw_x: u16, raw_y: u16) -> Result<(u16, u16), Rejection>
+        requires
+            self.ui_width > 0,
+            self.ui_height > 0,
+            self.scaling.num > 0,
+        ensures
+            result.is_ok() ==> result.unwrap().0 < self.ui_width,
+            result.is_ok() ==> result.unwrap().1 < self.ui_height,
+    {
+        let (x, y) = self.calibrate(raw_x, raw_y)?;
+        let (sx, sy) = rotate(x, y, TOUCH_WIDTH, TOUCH_HEIGHT, self.rotation);
+        let s = self.scaling;
+        match (
+            unscale_axis(sx, s.origin_x, s.num, s.den, self.ui_width),
+            unscale_axis(sy, s.origin_y, s.num, s.den, self.ui_height),
+        ) {
+            (Some(ux), Some(uy)) => Ok((ux, uy)),
+            _ => Err(Rejection::OutsideUi),
+        }
+    }
+}
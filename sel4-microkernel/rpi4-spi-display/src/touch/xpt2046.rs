@@ -6,6 +6,11 @@
 use verus_builtin::*;
 use verus_builtin_macros::*;
 
+use crate::display::Rotation;
+
+use super::calibration::AffineCalibration;
+use super::filter::{EdgeMargins, FilterError, PressureCurve, TouchFilter};
+use super::transform::{RawMapping, Scaling, TransformChain, TOUCH_HEIGHT, TOUCH_WIDTH};
 use super::{TouchController, TouchEvent, TouchPoint};
 
 /// XPT2046 control byte commands
@@ -26,39 +31,42 @@ pub struct Calibration {
     pub y_max: u16,
 }
 
+impl Calibration {
+    /// Typical values for a 320x240 display
+    pub const DEFAULT: Self = Self {
+        x_min: 200,
+        x_max: 3800,
+        y_min: 200,
+        y_max: 3800,
+    };
+}
+
 impl Default for Calibration {
     fn default() -> Self {
-        // Typical values for 320x240 display
-        Self {
-            x_min: 200,
-            x_max: 3800,
-            y_min: 200,
-            y_max: 3800,
-        }
+        Self::DEFAULT
     }
 }
 
 /// XPT2046 driver state
 pub struct Xpt2046 {
-    calibration: Calibration,
+    /// Raw sample to screen coordinate mapping
+    transform: TransformChain,
+    /// Edge rejection and pressure thresholds for reported touches
+    filter: TouchFilter,
     last_point: Option<TouchPoint>,
     was_touched: bool,
 }
 
 impl Xpt2046 {
-    /// Screen dimensions for coordinate mapping
-    const SCREEN_WIDTH: u16 = 320;
-    const SCREEN_HEIGHT: u16 = 240;
-
     /// Create a new XPT2046 driver
     pub const fn new() -> Self {
+        let transform = match TransformChain::new(TOUCH_WIDTH, TOUCH_HEIGHT) {
+            Ok(transform) => transform,
+            Err(_) => panic!("touch frame has zero size"),
+        };
         Self {
-            calibration: Calibration {
-                x_min: 200,
-                x_max: 3800,
-                y_min: 200,
-                y_max: 3800,
-            },
+            transform,
+            filter: TouchFilter::new(TOUCH_WIDTH, TOUCH_HEIGHT),
             last_point: None,
             was_touched: false,
         }
@@ -66,7 +74,53 @@ impl Xpt2046 {
 
     /// Set calibration data
     pub fn set_calibration(&mut self, cal: Calibration) {
-        self.calibration = cal;
+        self.transform.set_mapping(RawMapping::MinMax(cal));
+    }
+
+    /// Use a three-point calibration (e.g. restored from storage) instead of
+    /// the min/max calibration; `None` goes back to the default min/max
+    pub fn set_affine_calibration(&mut self, cal: Option<AffineCalibration>) {
+        let mapping = match cal {
+            Some(affine) => RawMapping::Affine(affine),
+            None => RawMapping::MinMax(Calibration::DEFAULT),
+        };
+        self.transform.set_mapping(mapping);
+    }
+
+    /// Current three-point calibration, for saving
+    pub fn affine_calibration(&self) -> Option<AffineCalibration> {
+        match self.transform.mapping() {
+            RawMapping::Affine(affine) => Some(affine),
+            RawMapping::MinMax(_) => None,
+        }
+    }
+
+    /// Rotate touches with the display, relative to the orientation the
+    /// panel was calibrated in
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.transform.set_rotation(rotation);
+    }
+
+    /// Map touches onto a UI shown on the screen by `scaling`
+    pub fn set_scaling(&mut self, scaling: Scaling) {
+        self.transform.set_scaling(scaling);
+    }
+
+    /// Raw sample to screen coordinate mapping in use
+    pub fn transform(&self) -> &TransformChain {
+        &self.transform
+    }
+
+    /// Ignore touches that start within `margins` of the screen edges;
+    /// refused if they leave no active area
+    pub fn set_edge_margins(&mut self, margins: EdgeMargins) -> Result<(), FilterError> {
+        self.filter.set_margins(margins)
+    }
+
+    /// Map touch resistance to pressure, and start and end touches, by
+    /// `curve`
+    pub fn set_pressure_curve(&mut self, curve: PressureCurve) {
+        self.filter.set_curve(curve);
     }
 
     /// Read raw 12-bit ADC value for a channel
@@ -79,34 +133,15 @@ impl Xpt2046 {
         0
     }
 
-    /// Map raw ADC value to screen coordinate
-    #[verus_verify]
-    fn map_coordinate(&self, raw: u16, min: u16, max: u16, screen_max: u16) -> u16
-        requires
-            max > min,
-        ensures
-            result <= screen_max,
-    {
-        if raw <= min {
-            return 0;
-        }
-        if raw >= max {
-            return screen_max;
-        }
-
-        let range = (max - min) as u32;
-        let offset = (raw - min) as u32;
-        let mapped = (offset * (screen_max as u32)) / range;
-
-        if mapped > screen_max as u32 {
-            screen_max
-        } else {
-            mapped as u16
-        }
+    /// Read the uncalibrated ADC position of the current touch, if any
+    ///
+    /// This is what a [`Calibrator`](super::calibration::Calibrator) records.
+    pub fn read_raw_point(&mut self) -> Option<(u16, u16)> {
+        self.read_raw_touch().map(|(raw_x, raw_y, _)| (raw_x, raw_y))
     }
 
-    /// Read touch point with calibration applied
-    fn read_calibrated(&mut self) -> Option<TouchPoint> {
+    /// Read raw X, Y and pressure; `None` when the panel is not pressed
+    fn read_raw_touch(&mut self) -> Option<(u16, u16, u16)> {
         let raw_x = self.read_raw(cmd::READ_X);
         let raw_y = self.read_raw(cmd::READ_Y);
         let z1 = self.read_raw(cmd::READ_Z1);
@@ -124,19 +159,15 @@ impl Xpt2046 {
             return None;
         }
 
-        let x = self.map_coordinate(
-            raw_x,
-            self.calibration.x_min,
-            self.calibration.x_max,
-            Self::SCREEN_WIDTH - 1,
-        );
-        let y = self.map_coordinate(
-            raw_y,
-            self.calibration.y_min,
-            self.calibration.y_max,
-            Self::SCREEN_HEIGHT - 1,
-        );
+        Some((raw_x, raw_y, pressure))
+    }
 
+    /// Read touch point mapped through the transform chain
+    ///
+    /// A sample the chain rejects reads as no touch.
+    fn read_calibrated(&mut self) -> Option<TouchPoint> {
+        let (raw_x, raw_y, pressure) = self.read_raw_touch()?;
+        let (x, y) = self.transform.map(raw_x, raw_y).ok()?;
         Some(TouchPoint { x, y, pressure })
     }
 }
@@ -148,11 +179,13 @@ impl TouchController for Xpt2046 {
     }
 
     fn read_point(&mut self) -> Option<TouchPoint> {
-        self.read_calibrated()
+        let reading = self.read_calibrated();
+        self.filter.process(reading)
     }
 
     fn poll_event(&mut self) -> Option<TouchEvent> {
-        let point = self.read_calibrated();
+        let reading = self.read_calibrated();
+        let point = self.filter.process(reading);
 
         match (self.was_touched, point) {
             (false, Some(p)) => {
@@ -0,0 +1,187 @@
+//! # Storage Protection Domain
+//!
+//! Owns the SD card: the EMMC2 controller registers are mapped into this PD
+//! and no other. Photos are served from the root directory of the card's
+//! FAT32 volume, read-only, over the protected procedure calls defined in
+//! `rpi4-storage-protocol`:
+//!
+//! ```text
+//! ┌──────────────┐  COUNT / OPEN / READ (PPC)  ┌──────────────┐
+//! │ Photoframe   │ ──────────────────────────► │ Storage PD   │
+//! │ PD (decoder) │                             │              │
+//! │              │ ◄── file_buffer (r / rw) ── │              │
+//! └──────────────┘                             └──────┬───────┘
+//!                                                     │ EMMC2
+//!                                                     ▼
+//!                                                  SD card
+//! ```
+//!
+//! ## Security Properties
+//!
+//! 1. **Isolated Access**: only this PD can reach the card controller; the
+//!    decoder sees file bytes in the file buffer and nothing else
+//! 2. **Read-only**: the driver has no write path, so a compromised decoder
+//!    cannot modify the card through this PD
+//! 3. **Verified Chunking**: every copy into the file buffer lands in the
+//!    window `chunk_window` proves to lie inside both the file and the buffer
+//! 4. **Untrusted Card**: the FAT32 reader bounds-checks every cluster it
+//!    follows, so a corrupt card produces an error reply, not a hang
+
+#![no_std]
+#![no_main]
+
+use sel4_microkit::{debug_println, protection_domain, with_msg_regs, with_msg_regs_mut, Channel, Handler, MessageInfo};
+use rpi4_pd_error::mem::{Gauge, Unit};
+use rpi4_pd_error::{reply_code, ErrorDomain, ErrorKind, PdError, PdResult, ResultExt};
+use rpi4_storage::{DirEntry, File, Sdhci, StorageError, Volume};
+use rpi4_storage_protocol::{
+    chunk_window, decode_request, FileName, StorageRequest, FILE_BUFFER_SIZE, FILE_BUFFER_VADDR,
+    REPLY_WORDS, STORAGE_CHANNEL_ID, STORAGE_PD_EMMC_BASE,
+};
+
+/// Unknown call label (the detail carries the label)
+const INVALID_REQUEST: PdError = PdError::new(ErrorDomain::Storage, ErrorKind::InvalidArgument);
+
+/// Call on a channel other than the client's
+const WRONG_CHANNEL: PdError = PdError::new(ErrorDomain::Storage, ErrorKind::Denied);
+
+/// No card was mounted at boot or on the last `COUNT`
+const NOT_MOUNTED: PdError = PdError::new(ErrorDomain::Storage, ErrorKind::NotReady);
+
+/// `READ` before a successful `OPEN`
+const NOTHING_OPEN: PdError = PdError::new(ErrorDomain::Storage, ErrorKind::InvalidState);
+
+/// Bytes of the file buffer the largest photo opened needs, printed as
+/// `@mem` lines (see `rpi4_pd_error::mem`) as the peak grows
+static FILE_BUFFER_GAUGE: Gauge = Gauge::new("file_buffer", Unit::Bytes, FILE_BUFFER_SIZE);
+
+/// The file buffer shared with the client
+///
+/// # Safety
+/// Only the PD's single thread may hold the returned slice.
+unsafe fn file_buffer() -> &'static mut [u8] {
+    core::slice::from_raw_parts_mut(FILE_BUFFER_VADDR as *mut u8, FILE_BUFFER_SIZE)
+}
+
+/// Files the decoder is offered
+fn is_photo(entry: &DirEntry) -> bool {
+    FileName(entry.name).is_photo()
+}
+
+struct StoragePd {
+    card: Sdhci,
+    /// `None` until the card has been brought up and mounted
+    volume: Option<Volume>,
+    /// The photo `OPEN` selected
+    open: Option<File>,
+}
+
+impl StoragePd {
+    /// (Re)initialize the card and mount its FAT32 volume
+    fn mount(&mut self) -> PdResult<()> {
+        self.volume = None;
+        self.open = None;
+        self.card.init().context("initializing SD card")?;
+        let volume = Volume::mount(&mut self.card).context("mounting FAT32 volume")?;
+        debug_println!("Storage PD: FAT32 volume mounted, {} clusters", volume.cluster_count());
+        self.volume = Some(volume);
+        Ok(())
+    }
+
+    /// Serve one call; on success, the reply's message registers
+    fn handle(&mut self, request: StorageRequest) -> PdResult<[u64; REPLY_WORDS]> {
+        match request {
+            StorageRequest::Count => {
+                // A card inserted after boot is picked up here
+                if self.volume.is_none() {
+                    self.mount()?;
+                }
+                let volume = self.volume.as_mut().ok_or(NOT_MOUNTED)?;
+                let count = volume.count_files(&mut self.card, is_photo).context("listing photos")?;
+                Ok([count as u64, 0, 0])
+            }
+            StorageRequest::Open { index } => {
+                self.open = None;
+                let volume = self.volume.as_mut().ok_or(NOT_MOUNTED)?;
+                let entry = volume.nth_file(&mut self.card, index, is_photo).context("opening photo")?;
+                // Photos too large are counted too: they are what a bigger
+                // buffer would have served
+                if FILE_BUFFER_GAUGE.record(entry.size as usize) {
+                    debug_println!("{}", FILE_BUFFER_GAUGE.reading().line("storage"));
+                }
+                if entry.size as usize > FILE_BUFFER_SIZE {
+                    return Err(StorageError::TooLarge).context("opening photo");
+                }
+                self.open = Some(File::new(entry));
+                let [name_lo, name_hi] = FileName(entry.name).to_words();
+                Ok([entry.size as u64, name_lo, name_hi])
+            }
+            StorageRequest::Read { chunk } => {
+                let volume = self.volume.as_mut().ok_or(NOT_MOUNTED)?;
+                let file = self.open.as_mut().ok_or(NOTHING_OPEN)?;
+                let window = chunk_window(chunk, file.size() as usize)
+                    .ok_or(StorageError::OutOfRange)
+                    .context("reading photo")?;
+                let buffer = unsafe { &mut file_buffer()[window.offset..window.offset + window.len] };
+                volume
+                    .read_at(&mut self.card, file, window.offset as u32, buffer)
+                    .context("reading photo")?;
+                Ok([window.len as u64, 0, 0])
+            }
+        }
+    }
+}
+
+impl Handler for StoragePd {
+    type Error = PdError;
+
+    fn protected(&mut self, channel: Channel, msg_info: MessageInfo) -> Result<MessageInfo, Self::Error> {
+        let label = msg_info.label();
+        let arg = with_msg_regs(|mrs| mrs[0]);
+        let result = if channel.index() != STORAGE_CHANNEL_ID {
+            Err(WRONG_CHANNEL)
+        } else {
+            match decode_request(label, arg) {
+                Some(request) => self.handle(request),
+                None => Err(INVALID_REQUEST.with_detail(label as u16)),
+            }
+        };
+
+        // The reply label is the PdError wire code, or 0 with the results
+        // in the message registers
+        let words = match &result {
+            Ok(words) => {
+                with_msg_regs_mut(|mrs| mrs[..REPLY_WORDS].copy_from_slice(words));
+                REPLY_WORDS
+            }
+            Err(e) => {
+                debug_println!("Storage PD: {}", e);
+                0
+            }
+        };
+        Ok(MessageInfo::new(reply_code(&result) as u64, words))
+    }
+}
+
+#[protection_domain]
+fn init() -> StoragePd {
+    debug_println!("");
+    debug_println!("========================================");
+    debug_println!("  Storage Protection Domain Starting");
+    debug_println!("========================================");
+    debug_println!("");
+    debug_println!("Storage PD: EMMC2 at 0x{:x}", STORAGE_PD_EMMC_BASE);
+    debug_println!("Storage PD: File buffer at 0x{:x} ({} KB)", FILE_BUFFER_VADDR, FILE_BUFFER_SIZE / 1024);
+
+    let mut pd = StoragePd {
+        card: unsafe { Sdhci::new(STORAGE_PD_EMMC_BASE) },
+        volume: None,
+        open: None,
+    };
+    // No card is not fatal: the PD answers NotReady and retries on COUNT
+    match pd.mount() {
+        Ok(()) => debug_println!("Storage PD: Ready, serving photos read-only"),
+        Err(e) => debug_println!("Storage PD: {}, serving nothing until a card is found", e),
+    }
+    pd
+}
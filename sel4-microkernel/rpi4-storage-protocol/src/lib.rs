@@ -0,0 +1,349 @@
+//! Verified IPC protocol between the Storage PD and its clients.
+//!
+//! The Storage PD owns the SD card controller (EMMC2) and the read-only
+//! FAT32 volume on it. A client (the PD that decodes photos) never touches
+//! the card: it asks for files by index over a protected procedure call and
+//! the Storage PD copies them, one chunk per call, into a file buffer that
+//! the client maps read-only.
+//!
+//! ## Calls
+//!
+//! The call label is the operation, message register 0 its argument. The
+//! reply label is zero on success or a `PdError` wire code; on success the
+//! reply carries [`REPLY_WORDS`] message registers.
+//!
+//! ```text
+//! Label  Operation  MR0 in   MR0 out        MR1-MR2 out
+//! 1      COUNT      -        photo count    -
+//! 2      OPEN       index    file size      8.3 file name
+//! 3      READ       chunk    bytes copied   -
+//! ```
+//!
+//! `READ` copies chunk `n` of the open file, [`CHUNK_SIZE`] bytes (fewer
+//! for the last), to offset `n * CHUNK_SIZE` of the file buffer, so after
+//! reading chunks `0..chunk_count(size)` the buffer holds the whole file.
+//!
+//! ## Verification
+//!
+//! [`chunk_window`] is the only place a chunk's position in the file buffer
+//! is computed, and is proven to stay inside both the file and the buffer;
+//! the isolation proofs at the end show the file buffer is all a client can
+//! reach and that it never overlaps the controller registers.
+
+#![no_std]
+#![allow(unused)]
+#![allow(clippy::new_without_default)]
+
+use core::fmt;
+use verus_builtin_macros::verus;
+use verified_microkernel::div_round_up_usize;
+
+verus! {
+
+/// Client → Storage PD protected procedure channel
+pub const STORAGE_CHANNEL_ID: usize = 3;
+
+// ============================================================================
+// MEMORY REGIONS
+// ============================================================================
+
+/// File buffer: written by the Storage PD, read by the client
+pub const FILE_BUFFER_VADDR: usize = 0x5_0700_0000;
+/// File buffer size (1MB); larger files are refused on `OPEN`
+pub const FILE_BUFFER_SIZE: usize = 0x10_0000;
+
+/// EMMC2 controller registers, mapped into the Storage PD only
+pub const STORAGE_PD_EMMC_BASE: usize = 0x5_0800_0000;
+pub const STORAGE_PD_EMMC_SIZE: usize = 0x1000;
+
+/// Bytes copied per `READ`
+pub const CHUNK_SIZE: usize = 0x1000;
+/// Chunks in a full file buffer
+pub const MAX_CHUNKS: u32 = 256;
+
+pub open spec fn in_file_buffer_region(addr: usize) -> bool {
+    addr >= FILE_BUFFER_VADDR && addr < FILE_BUFFER_VADDR + FILE_BUFFER_SIZE
+}
+
+pub open spec fn in_emmc_region(addr: usize) -> bool {
+    addr >= STORAGE_PD_EMMC_BASE && addr < STORAGE_PD_EMMC_BASE + STORAGE_PD_EMMC_SIZE
+}
+
+/// Specification: can the Storage PD access this address?
+pub open spec fn storage_pd_can_access(addr: usize) -> bool {
+    in_emmc_region(addr) || in_file_buffer_region(addr)
+}
+
+/// Specification: what this protocol grants a client
+pub open spec fn client_pd_can_access(addr: usize) -> bool {
+    in_file_buffer_region(addr)
+}
+
+// ============================================================================
+// OPERATIONS
+// ============================================================================
+
+pub const OP_COUNT: u64 = 1;
+pub const OP_OPEN: u64 = 2;
+pub const OP_READ: u64 = 3;
+
+/// Message registers in a successful reply
+pub const REPLY_WORDS: usize = 3;
+
+/// A decoded call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageRequest {
+    /// How many photos are on the card
+    Count,
+    /// Select the `index`th photo for reading
+    Open { index: u32 },
+    /// Copy chunk `chunk` of the open photo into the file buffer
+    Read { chunk: u32 },
+}
+
+/// Decode a call from its label and first message register. Arguments
+/// wider than 32 bits are refused rather than truncated.
+pub fn decode_request(label: u64, arg: u64) -> (r: Option<StorageRequest>)
+    ensures
+        r.is_some() ==> (label == OP_COUNT || label == OP_OPEN || label == OP_READ),
+        label == OP_COUNT ==> r == Some(StorageRequest::Count),
+{
+    if label == OP_COUNT {
+        Some(StorageRequest::Count)
+    } else if arg > u32::MAX as u64 {
+        None
+    } else if label == OP_OPEN {
+        Some(StorageRequest::Open { index: arg as u32 })
+    } else if label == OP_READ {
+        Some(StorageRequest::Read { chunk: arg as u32 })
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// CHUNK ARITHMETIC (Verified)
+// ============================================================================
+
+/// Where a chunk lands in the file buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkWindow {
+    /// Byte offset in both the file and the file buffer
+    pub offset: usize,
+    /// Bytes in this chunk (1..=CHUNK_SIZE)
+    pub len: usize,
+}
+
+/// Position of chunk `chunk` of a `file_size`-byte file, or `None` when the
+/// file does not fit the buffer or the chunk is past its end
+pub fn chunk_window(chunk: u32, file_size: usize) -> (w: Option<ChunkWindow>)
+    ensures
+        w.is_some() <==> (file_size <= FILE_BUFFER_SIZE && (chunk as int) * CHUNK_SIZE < file_size),
+        w.is_some() ==> w.unwrap().offset == (chunk as int) * CHUNK_SIZE,
+        w.is_some() ==> 0 < w.unwrap().len <= CHUNK_SIZE,
+        w.is_some() ==> w.unwrap().offset + w.unwrap().len <= file_size,
+        w.is_some() ==> w.unwrap().offset + w.unwrap().len <= FILE_BUFFER_SIZE,
+{
+    if file_size > FILE_BUFFER_SIZE || chunk >= MAX_CHUNKS {
+        proof {
+            assert(chunk >= MAX_CHUNKS ==> (chunk as int) * CHUNK_SIZE >= FILE_BUFFER_SIZE) by (nonlinear_arith);
+        }
+        return None;
+    }
+    proof {
+        assert((chunk as int) * CHUNK_SIZE < FILE_BUFFER_SIZE) by (nonlinear_arith)
+            requires chunk < MAX_CHUNKS;
+    }
+    let offset = chunk as usize * CHUNK_SIZE;
+    if offset >= file_size {
+        return None;
+    }
+    let rest = file_size - offset;
+    let len = if rest < CHUNK_SIZE { rest } else { CHUNK_SIZE };
+    Some(ChunkWindow { offset, len })
+}
+
+/// Chunks needed to read a `file_size`-byte file
+pub fn chunk_count(file_size: usize) -> (n: u32)
+    requires
+        file_size <= FILE_BUFFER_SIZE,
+    ensures
+        n <= MAX_CHUNKS,
+        (n as int) * CHUNK_SIZE >= file_size,
+        n == 0 || ((n - 1) as int) * CHUNK_SIZE < file_size,
+{
+    let n = div_round_up_usize(file_size, CHUNK_SIZE);
+    proof {
+        assert(n <= MAX_CHUNKS) by (nonlinear_arith)
+            requires (n - 1) * CHUNK_SIZE < file_size, file_size <= FILE_BUFFER_SIZE;
+    }
+    n as u32
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: a chunk window inside the buffer only covers file buffer addresses
+proof fn chunk_writes_stay_in_file_buffer()
+    ensures
+        forall|offset: usize, len: usize, i: usize|
+            offset + len <= FILE_BUFFER_SIZE && i < len
+            ==> in_file_buffer_region((FILE_BUFFER_VADDR + offset + i) as usize)
+{
+}
+
+/// Prove: a client cannot reach the SD card controller
+proof fn client_cannot_access_emmc()
+    ensures
+        forall|addr: usize| in_emmc_region(addr) ==> !client_pd_can_access(addr)
+{
+}
+
+/// Prove: the file buffer is the only region the Storage PD shares
+proof fn storage_and_client_share_only_file_buffer()
+    ensures
+        forall|addr: usize|
+            (storage_pd_can_access(addr) && client_pd_can_access(addr))
+            ==> in_file_buffer_region(addr)
+{
+}
+
+} // verus!
+
+// ============================================================================
+// FILE NAMES
+// ============================================================================
+
+/// File extensions the Storage PD lists as photos
+pub const PHOTO_EXTENSIONS: [&[u8; 3]; 5] = [b"BMP", b"QOI", b"PNG", b"GIF", b"JPG"];
+
+/// 8.3 short name as stored in a FAT directory entry: 8 name and 3
+/// extension bytes, each space padded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FileName(pub [u8; 11]);
+
+impl FileName {
+    pub fn base(&self) -> &[u8] {
+        trim_padding(&self.0[..8])
+    }
+
+    pub fn extension(&self) -> &[u8] {
+        trim_padding(&self.0[8..])
+    }
+
+    /// Does the extension name a format the decoder understands?
+    pub fn is_photo(&self) -> bool {
+        PHOTO_EXTENSIONS.iter().any(|ext| self.0[8..] == ext[..])
+    }
+
+    /// Pack into message registers MR1-MR2
+    pub fn to_words(&self) -> [u64; 2] {
+        let mut lo = [0u8; 8];
+        let mut hi = [0u8; 8];
+        lo.copy_from_slice(&self.0[..8]);
+        hi[..3].copy_from_slice(&self.0[8..]);
+        [u64::from_le_bytes(lo), u64::from_le_bytes(hi)]
+    }
+
+    pub fn from_words(words: [u64; 2]) -> Self {
+        let mut name = [0u8; 11];
+        name[..8].copy_from_slice(&words[0].to_le_bytes());
+        name[8..].copy_from_slice(&words[1].to_le_bytes()[..3]);
+        Self(name)
+    }
+}
+
+fn trim_padding(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().rposition(|&b| b != b' ').map_or(0, |last| last + 1);
+    &bytes[..len]
+}
+
+/// `NAME.EXT`, with anything but printable ASCII shown as `?` (the name
+/// came from the card)
+impl fmt::Display for FileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = |b: u8| if b.is_ascii_graphic() { b as char } else { '?' };
+        for &b in self.base() {
+            write!(f, "{}", printable(b))?;
+        }
+        if !self.extension().is_empty() {
+            write!(f, ".")?;
+            for &b in self.extension() {
+                write!(f, "{}", printable(b))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StorageRequest {
+    /// Call label
+    pub fn label(&self) -> u64 {
+        match self {
+            StorageRequest::Count => OP_COUNT,
+            StorageRequest::Open { .. } => OP_OPEN,
+            StorageRequest::Read { .. } => OP_READ,
+        }
+    }
+
+    /// Value for message register 0
+    pub fn arg(&self) -> u64 {
+        match *self {
+            StorageRequest::Count => 0,
+            StorageRequest::Open { index } => index as u64,
+            StorageRequest::Read { chunk } => chunk as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn requests_round_trip() {
+        for request in [
+            StorageRequest::Count,
+            StorageRequest::Open { index: 7 },
+            StorageRequest::Read { chunk: u32::MAX },
+        ] {
+            assert_eq!(decode_request(request.label(), request.arg()), Some(request));
+        }
+        assert_eq!(decode_request(OP_OPEN, 1 << 32), None);
+        assert_eq!(decode_request(0, 0), None);
+        assert_eq!(decode_request(OP_READ + 1, 0), None);
+    }
+
+    #[test]
+    fn chunks_cover_the_file_exactly() {
+        let size = 3 * CHUNK_SIZE + 100;
+        assert_eq!(chunk_count(size), 4);
+        assert_eq!(chunk_window(0, size), Some(ChunkWindow { offset: 0, len: CHUNK_SIZE }));
+        assert_eq!(chunk_window(3, size), Some(ChunkWindow { offset: 3 * CHUNK_SIZE, len: 100 }));
+        assert_eq!(chunk_window(4, size), None);
+
+        assert_eq!(chunk_count(0), 0);
+        assert_eq!(chunk_window(0, 0), None);
+        assert_eq!(chunk_count(FILE_BUFFER_SIZE), MAX_CHUNKS);
+        assert_eq!(chunk_window(MAX_CHUNKS - 1, FILE_BUFFER_SIZE).map(|w| w.offset + w.len), Some(FILE_BUFFER_SIZE));
+        assert_eq!(chunk_window(MAX_CHUNKS, FILE_BUFFER_SIZE), None);
+        assert_eq!(chunk_window(u32::MAX, FILE_BUFFER_SIZE), None);
+        assert_eq!(chunk_window(0, FILE_BUFFER_SIZE + 1), None);
+    }
+
+    #[test]
+    fn file_names_pack_and_print() {
+        let name = FileName(*b"IMG_0001JPG");
+        assert!(name.is_photo());
+        assert_eq!(FileName::from_words(name.to_words()), name);
+        assert_eq!(format!("{}", name), "IMG_0001.JPG");
+
+        let short = FileName(*b"A       BMP");
+        assert_eq!(format!("{}", short), "A.BMP");
+        assert!(!FileName(*b"README  TXT").is_photo());
+        assert_eq!(format!("{}", FileName(*b"NOEXT\x01     ")), "NOEXT?");
+    }
+}
@@ -0,0 +1,548 @@
+//! Read-only FAT32.
+//!
+//! Mounts the first FAT32 partition of an MBR-partitioned card (type 0x0B
+//! or 0x0C), or a card formatted without a partition table. Only the root
+//! directory is listed, by 8.3 short name: long-name entries, deleted
+//! entries, subdirectories and the volume label are skipped.
+//!
+//! Nothing on the card is trusted. The boot sector must describe a volume
+//! whose FAT has an entry for every cluster, every cluster number is
+//! checked before it is turned into a block address, and every walk along
+//! a cluster chain is bounded by the cluster count, so a looping chain ends
+//! in [`StorageError::Corrupt`].
+
+use crate::{BlockDevice, StorageError, BLOCK_SIZE};
+
+/// `0x55 0xAA` at the end of a boot sector or MBR
+const BOOT_SIGNATURE: u16 = 0xAA55;
+/// Offset of the MBR partition table
+const MBR_PARTITIONS: usize = 446;
+const MBR_PARTITION_SIZE: usize = 16;
+const PARTITION_FAT32_CHS: u8 = 0x0B;
+const PARTITION_FAT32_LBA: u8 = 0x0C;
+
+const DIR_ENTRY_SIZE: usize = 32;
+const ENTRY_END: u8 = 0x00;
+const ENTRY_DELETED: u8 = 0xE5;
+/// A first name byte of 0x05 stands for a real 0xE5
+const ENTRY_KANJI_E5: u8 = 0x05;
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_DIRECTORY: u8 = 0x10;
+
+const FAT_ENTRY_SIZE: u32 = 4;
+const FAT_ENTRY_MASK: u32 = 0x0FFF_FFFF;
+/// Entries at or above this end a chain
+const FAT_END_OF_CHAIN: u32 = 0x0FFF_FFF8;
+/// Cluster numbers start at 2
+const FIRST_CLUSTER: u32 = 2;
+
+fn le16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn le32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+/// A file in the root directory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DirEntry {
+    /// 8.3 name, space padded
+    pub name: [u8; 11],
+    pub first_cluster: u32,
+    /// Size in bytes
+    pub size: u32,
+}
+
+impl DirEntry {
+    fn parse(raw: &[u8]) -> Self {
+        let mut name = [0u8; 11];
+        name.copy_from_slice(&raw[..11]);
+        if name[0] == ENTRY_KANJI_E5 {
+            name[0] = ENTRY_DELETED;
+        }
+        let first_cluster = ((le16(raw, 20) as u32) << 16) | le16(raw, 26) as u32;
+        Self { name, first_cluster, size: le32(raw, 28) }
+    }
+}
+
+/// An open file. Remembers the last cluster reached, so reading a file
+/// front to back follows each link of its chain once.
+#[derive(Clone, Copy, Debug)]
+pub struct File {
+    entry: DirEntry,
+    /// Position in the chain of `cluster`
+    cluster_index: u32,
+    cluster: u32,
+}
+
+impl File {
+    pub fn new(entry: DirEntry) -> Self {
+        Self { entry, cluster_index: 0, cluster: entry.first_cluster }
+    }
+
+    pub fn entry(&self) -> &DirEntry {
+        &self.entry
+    }
+
+    pub fn size(&self) -> u32 {
+        self.entry.size
+    }
+}
+
+/// A mounted FAT32 volume
+pub struct Volume {
+    /// Block address of the first FAT
+    fat_start: u32,
+    /// Block address of cluster 2
+    data_start: u32,
+    sectors_per_cluster: u32,
+    cluster_count: u32,
+    root_cluster: u32,
+    /// Block address of `fat_block`, if it holds one
+    fat_cached: Option<u32>,
+    fat_block: [u8; BLOCK_SIZE],
+}
+
+impl Volume {
+    /// Find and mount the FAT32 volume on `dev`
+    pub fn mount(dev: &mut impl BlockDevice) -> Result<Self, StorageError> {
+        let mut block = [0u8; BLOCK_SIZE];
+        dev.read_block(0, &mut block)?;
+        if looks_like_boot_sector(&block) {
+            return Self::from_boot_sector(0, &block);
+        }
+        let start = find_partition(&block)?;
+        dev.read_block(start, &mut block)?;
+        Self::from_boot_sector(start, &block)
+    }
+
+    /// Check the boot sector of a volume starting at block `start`
+    fn from_boot_sector(start: u32, bs: &[u8; BLOCK_SIZE]) -> Result<Self, StorageError> {
+        let sectors_per_cluster = bs[13] as u32;
+        let reserved = le16(bs, 14) as u32;
+        let fats = bs[16] as u32;
+        let total = match le16(bs, 19) {
+            0 => le32(bs, 32),
+            small => small as u32,
+        };
+        let fat_size = le32(bs, 36);
+        let root_cluster = le32(bs, 44);
+
+        let fat32 = le16(bs, 510) == BOOT_SIGNATURE
+            && le16(bs, 11) as usize == BLOCK_SIZE
+            && sectors_per_cluster.is_power_of_two()
+            && sectors_per_cluster <= 128
+            && reserved != 0
+            && (fats == 1 || fats == 2)
+            // FAT12/16 keep a fixed root directory and a 16-bit FAT size
+            && le16(bs, 17) == 0
+            && le16(bs, 22) == 0
+            && fat_size != 0;
+        if !fat32 {
+            return Err(StorageError::NotFat32);
+        }
+
+        let metadata = reserved as u64 + fats as u64 * fat_size as u64;
+        if metadata >= total as u64 || start as u64 + total as u64 > u32::MAX as u64 {
+            return Err(StorageError::NotFat32);
+        }
+        let cluster_count = (total - metadata as u32) / sectors_per_cluster;
+        // Every cluster needs a FAT entry, so FAT lookups stay in the FAT
+        let fat_entries = fat_size as u64 * (BLOCK_SIZE as u64 / FAT_ENTRY_SIZE as u64);
+        if cluster_count == 0 || (cluster_count + FIRST_CLUSTER) as u64 > fat_entries {
+            return Err(StorageError::NotFat32);
+        }
+
+        let volume = Self {
+            fat_start: start + reserved,
+            data_start: start + metadata as u32,
+            sectors_per_cluster,
+            cluster_count,
+            root_cluster,
+            fat_cached: None,
+            fat_block: [0; BLOCK_SIZE],
+        };
+        if !volume.is_cluster(root_cluster) {
+            return Err(StorageError::NotFat32);
+        }
+        Ok(volume)
+    }
+
+    /// Data clusters on the volume
+    pub fn cluster_count(&self) -> u32 {
+        self.cluster_count
+    }
+
+    fn is_cluster(&self, cluster: u32) -> bool {
+        cluster >= FIRST_CLUSTER && cluster - FIRST_CLUSTER < self.cluster_count
+    }
+
+    /// Block address of the first block of `cluster`
+    fn cluster_lba(&self, cluster: u32) -> Result<u32, StorageError> {
+        if !self.is_cluster(cluster) {
+            return Err(StorageError::Corrupt);
+        }
+        Ok(self.data_start + (cluster - FIRST_CLUSTER) * self.sectors_per_cluster)
+    }
+
+    /// The cluster after `cluster` in its chain, `None` at the end
+    fn next_cluster(&mut self, dev: &mut impl BlockDevice, cluster: u32) -> Result<Option<u32>, StorageError> {
+        if !self.is_cluster(cluster) {
+            return Err(StorageError::Corrupt);
+        }
+        let offset = cluster * FAT_ENTRY_SIZE;
+        let lba = self.fat_start + offset / BLOCK_SIZE as u32;
+        if self.fat_cached != Some(lba) {
+            self.fat_cached = None;
+            dev.read_block(lba, &mut self.fat_block)?;
+            self.fat_cached = Some(lba);
+        }
+        let next = le32(&self.fat_block, offset as usize % BLOCK_SIZE) & FAT_ENTRY_MASK;
+        if next >= FAT_END_OF_CHAIN {
+            Ok(None)
+        } else if self.is_cluster(next) {
+            Ok(Some(next))
+        } else {
+            // Free, reserved or bad cluster in the middle of a chain
+            Err(StorageError::Corrupt)
+        }
+    }
+
+    /// Call `f` on each file in the root directory, in directory order,
+    /// until it returns false
+    pub fn for_each_file(
+        &mut self,
+        dev: &mut impl BlockDevice,
+        mut f: impl FnMut(&DirEntry) -> bool,
+    ) -> Result<(), StorageError> {
+        let mut block = [0u8; BLOCK_SIZE];
+        let mut cluster = self.root_cluster;
+        for _ in 0..self.cluster_count {
+            let lba = self.cluster_lba(cluster)?;
+            for sector in 0..self.sectors_per_cluster {
+                dev.read_block(lba + sector, &mut block)?;
+                for raw in block.chunks_exact(DIR_ENTRY_SIZE) {
+                    match raw[0] {
+                        ENTRY_END => return Ok(()),
+                        ENTRY_DELETED => continue,
+                        _ => {}
+                    }
+                    // Long-name entries carry the volume label bit too
+                    if raw[11] & (ATTR_VOLUME_ID | ATTR_DIRECTORY) != 0 {
+                        continue;
+                    }
+                    if !f(&DirEntry::parse(raw)) {
+                        return Ok(());
+                    }
+                }
+            }
+            match self.next_cluster(dev, cluster)? {
+                Some(next) => cluster = next,
+                None => return Ok(()),
+            }
+        }
+        // Longer than the volume: the chain loops
+        Err(StorageError::Corrupt)
+    }
+
+    /// Number of root directory files `filter` accepts
+    pub fn count_files(
+        &mut self,
+        dev: &mut impl BlockDevice,
+        filter: impl Fn(&DirEntry) -> bool,
+    ) -> Result<u32, StorageError> {
+        let mut count = 0;
+        self.for_each_file(dev, |entry| {
+            if filter(entry) {
+                count += 1;
+            }
+            true
+        })?;
+        Ok(count)
+    }
+
+    /// The `n`th root directory file `filter` accepts
+    pub fn nth_file(
+        &mut self,
+        dev: &mut impl BlockDevice,
+        n: u32,
+        filter: impl Fn(&DirEntry) -> bool,
+    ) -> Result<DirEntry, StorageError> {
+        let mut seen = 0;
+        let mut found = None;
+        self.for_each_file(dev, |entry| {
+            if filter(entry) {
+                if seen == n {
+                    found = Some(*entry);
+                    return false;
+                }
+                seen += 1;
+            }
+            true
+        })?;
+        found.ok_or(StorageError::NotFound)
+    }
+
+    /// Fill `buf` from `file`, starting `offset` bytes in. The whole range
+    /// must lie inside the file.
+    pub fn read_at(
+        &mut self,
+        dev: &mut impl BlockDevice,
+        file: &mut File,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<(), StorageError> {
+        if offset as u64 + buf.len() as u64 > file.size() as u64 {
+            return Err(StorageError::OutOfRange);
+        }
+        let cluster_bytes = self.sectors_per_cluster * BLOCK_SIZE as u32;
+        let mut block = [0u8; BLOCK_SIZE];
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u32;
+            let cluster = self.seek(dev, file, pos / cluster_bytes)?;
+            let in_cluster = pos % cluster_bytes;
+            dev.read_block(self.cluster_lba(cluster)? + in_cluster / BLOCK_SIZE as u32, &mut block)?;
+            let in_block = in_cluster as usize % BLOCK_SIZE;
+            let n = (BLOCK_SIZE - in_block).min(buf.len() - done);
+            buf[done..done + n].copy_from_slice(&block[in_block..in_block + n]);
+            done += n;
+        }
+        Ok(())
+    }
+
+    /// The `index`th cluster of `file`'s chain
+    fn seek(&mut self, dev: &mut impl BlockDevice, file: &mut File, index: u32) -> Result<u32, StorageError> {
+        if index >= self.cluster_count {
+            return Err(StorageError::Corrupt);
+        }
+        if index < file.cluster_index {
+            file.cluster_index = 0;
+            file.cluster = file.entry.first_cluster;
+        }
+        while file.cluster_index < index {
+            // The chain ending before the file does is corruption too
+            file.cluster = self.next_cluster(dev, file.cluster)?.ok_or(StorageError::Corrupt)?;
+            file.cluster_index += 1;
+        }
+        Ok(file.cluster)
+    }
+}
+
+/// Is block 0 itself a FAT boot sector (a card with no partition table)?
+fn looks_like_boot_sector(block: &[u8; BLOCK_SIZE]) -> bool {
+    matches!(block[0], 0xEB | 0xE9)
+        && le16(block, 11) as usize == BLOCK_SIZE
+        && block[13].is_power_of_two()
+        && le16(block, 14) != 0
+}
+
+/// Start of the first FAT32 partition in an MBR
+fn find_partition(mbr: &[u8; BLOCK_SIZE]) -> Result<u32, StorageError> {
+    if le16(mbr, 510) != BOOT_SIGNATURE {
+        return Err(StorageError::NotFat32);
+    }
+    (0..4)
+        .map(|i| &mbr[MBR_PARTITIONS + i * MBR_PARTITION_SIZE..][..MBR_PARTITION_SIZE])
+        .find(|entry| matches!(entry[4], PARTITION_FAT32_CHS | PARTITION_FAT32_LBA) && le32(entry, 8) != 0)
+        .map(|entry| le32(entry, 8))
+        .ok_or(StorageError::NotFat32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::collections::BTreeMap;
+    use std::vec::Vec;
+
+    /// Sparse card image: unwritten blocks read as zeros
+    #[derive(Default)]
+    struct Card(BTreeMap<u32, [u8; BLOCK_SIZE]>);
+
+    impl BlockDevice for Card {
+        fn read_block(&mut self, lba: u32, buf: &mut [u8; BLOCK_SIZE]) -> Result<(), StorageError> {
+            *buf = self.0.get(&lba).copied().unwrap_or([0; BLOCK_SIZE]);
+            Ok(())
+        }
+    }
+
+    impl Card {
+        fn write(&mut self, lba: u32, offset: usize, bytes: &[u8]) {
+            let block = self.0.entry(lba).or_insert([0; BLOCK_SIZE]);
+            block[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+    }
+
+    const PART: u32 = 64;
+    // One block per cluster, 4 reserved blocks and two one-block FATs
+    const FAT: u32 = PART + 4;
+    const DATA: u32 = PART + 6;
+
+    fn boot_sector(card: &mut Card, lba: u32) {
+        card.write(lba, 0, &[0xEB, 0x58, 0x90]);
+        card.write(lba, 11, &512u16.to_le_bytes());
+        card.write(lba, 13, &[1]);
+        card.write(lba, 14, &4u16.to_le_bytes());
+        card.write(lba, 16, &[2]);
+        card.write(lba, 32, &106u32.to_le_bytes());
+        card.write(lba, 36, &1u32.to_le_bytes());
+        card.write(lba, 44, &2u32.to_le_bytes());
+        card.write(lba, 510, &[0x55, 0xAA]);
+    }
+
+    fn link(card: &mut Card, cluster: u32, next: u32) {
+        card.write(FAT, cluster as usize * 4, &next.to_le_bytes());
+    }
+
+    fn dir_entry(card: &mut Card, cluster: u32, slot: usize, name: &[u8; 11], attr: u8, first: u32, size: u32) {
+        let lba = DATA + cluster - 2;
+        let offset = slot * DIR_ENTRY_SIZE;
+        card.write(lba, offset, name);
+        card.write(lba, offset + 11, &[attr]);
+        card.write(lba, offset + 20, &((first >> 16) as u16).to_le_bytes());
+        card.write(lba, offset + 26, &(first as u16).to_le_bytes());
+        card.write(lba, offset + 28, &size.to_le_bytes());
+    }
+
+    fn pattern(i: usize) -> u8 {
+        (i * 7 % 251) as u8
+    }
+
+    /// Partitioned card: the root directory spans clusters 2 and 9, and
+    /// SUNSET.JPG is fragmented over clusters 4, 7 and 5
+    fn photo_card() -> Card {
+        let mut card = Card::default();
+        card.write(0, MBR_PARTITIONS + 4, &[PARTITION_FAT32_LBA]);
+        card.write(0, MBR_PARTITIONS + 8, &PART.to_le_bytes());
+        card.write(0, 510, &[0x55, 0xAA]);
+        boot_sector(&mut card, PART);
+
+        dir_entry(&mut card, 2, 0, b"PHOTOS     ", ATTR_VOLUME_ID, 0, 0);
+        dir_entry(&mut card, 2, 1, b"Bs\0u\0n\0s\0e\0", 0x0F, 0, 0);
+        dir_entry(&mut card, 2, 2, b"SUNSET  JPG", 0x20, 4, 1200);
+        dir_entry(&mut card, 2, 3, b"\xE5LD     JPG", 0x20, 3, 10);
+        dir_entry(&mut card, 2, 4, b"ALBUM      ", ATTR_DIRECTORY, 3, 0);
+        dir_entry(&mut card, 2, 5, b"README  TXT", 0x20, 6, 10);
+        for slot in 6..16 {
+            dir_entry(&mut card, 2, slot, b"\xE5UNUSED    ", 0, 0, 0);
+        }
+        dir_entry(&mut card, 9, 0, b"CAT     PNG", 0x20, 8, 5);
+        link(&mut card, 2, 9);
+        link(&mut card, 9, 0x0FFF_FFFF);
+
+        let bytes: Vec<u8> = (0..1200).map(pattern).collect();
+        for (i, cluster) in [4u32, 7, 5].into_iter().enumerate() {
+            let end = (i * BLOCK_SIZE + BLOCK_SIZE).min(bytes.len());
+            card.write(DATA + cluster - 2, 0, &bytes[i * BLOCK_SIZE..end]);
+        }
+        link(&mut card, 4, 7);
+        link(&mut card, 7, 5);
+        link(&mut card, 5, 0x0FFF_FFF8);
+        card
+    }
+
+    fn names(volume: &mut Volume, card: &mut Card) -> Vec<[u8; 11]> {
+        let mut names = Vec::new();
+        volume.for_each_file(card, |entry| {
+            names.push(entry.name);
+            true
+        }).unwrap();
+        names
+    }
+
+    #[test]
+    fn lists_only_root_directory_files() {
+        let mut card = photo_card();
+        let mut volume = Volume::mount(&mut card).unwrap();
+        assert_eq!(volume.cluster_count(), 100);
+        assert_eq!(names(&mut volume, &mut card), [*b"SUNSET  JPG", *b"README  TXT", *b"CAT     PNG"]);
+
+        let not_text = |entry: &DirEntry| &entry.name[8..] != b"TXT";
+        assert_eq!(volume.count_files(&mut card, not_text), Ok(2));
+        assert_eq!(volume.nth_file(&mut card, 1, not_text).map(|e| e.name), Ok(*b"CAT     PNG"));
+        assert_eq!(volume.nth_file(&mut card, 2, not_text), Err(StorageError::NotFound));
+    }
+
+    #[test]
+    fn reads_follow_the_cluster_chain() {
+        let mut card = photo_card();
+        let mut volume = Volume::mount(&mut card).unwrap();
+        let entry = volume.nth_file(&mut card, 0, |_| true).unwrap();
+        let mut file = File::new(entry);
+
+        let mut whole = [0u8; 1200];
+        volume.read_at(&mut card, &mut file, 0, &mut whole).unwrap();
+        assert!(whole.iter().enumerate().all(|(i, &b)| b == pattern(i)));
+
+        // Straddling a cluster boundary, after seeking back to the start
+        let mut part = [0u8; 100];
+        volume.read_at(&mut card, &mut file, 1000, &mut part).unwrap();
+        volume.read_at(&mut card, &mut file, 480, &mut part).unwrap();
+        assert!(part.iter().enumerate().all(|(i, &b)| b == pattern(480 + i)));
+
+        assert_eq!(volume.read_at(&mut card, &mut file, 1150, &mut part), Err(StorageError::OutOfRange));
+        assert_eq!(volume.read_at(&mut card, &mut file, u32::MAX, &mut part), Err(StorageError::OutOfRange));
+    }
+
+    #[test]
+    fn corrupt_chains_are_refused() {
+        // Root directory chained to itself, with no end marker in it
+        let mut card = photo_card();
+        for slot in 0..BLOCK_SIZE / DIR_ENTRY_SIZE {
+            card.write(DATA + 7, slot * DIR_ENTRY_SIZE, &[ENTRY_DELETED]);
+        }
+        link(&mut card, 9, 2);
+        let mut volume = Volume::mount(&mut card).unwrap();
+        assert_eq!(volume.for_each_file(&mut card, |_| true), Err(StorageError::Corrupt));
+
+        // A free cluster and one past the volume in the middle of a file
+        for next in [0, 500] {
+            let mut card = photo_card();
+            link(&mut card, 7, next);
+            let mut volume = Volume::mount(&mut card).unwrap();
+            let mut file = File::new(volume.nth_file(&mut card, 0, |_| true).unwrap());
+            let mut buf = [0u8; 1200];
+            assert_eq!(volume.read_at(&mut card, &mut file, 0, &mut buf), Err(StorageError::Corrupt));
+        }
+
+        // A file claiming a first cluster outside the volume
+        let mut card = photo_card();
+        let mut volume = Volume::mount(&mut card).unwrap();
+        let mut file = File::new(DirEntry { name: *b"EVIL    BMP", first_cluster: 0xFFFF, size: 10 });
+        assert_eq!(volume.read_at(&mut card, &mut file, 0, &mut [0u8; 10]), Err(StorageError::Corrupt));
+    }
+
+    #[test]
+    fn bad_boot_sectors_are_refused() {
+        // No FAT32 partition
+        let mut card = photo_card();
+        card.write(0, MBR_PARTITIONS + 4, &[0x83]);
+        assert_eq!(Volume::mount(&mut card).err(), Some(StorageError::NotFat32));
+
+        // 4K sectors
+        let mut card = photo_card();
+        card.write(PART, 11, &4096u16.to_le_bytes());
+        assert_eq!(Volume::mount(&mut card).err(), Some(StorageError::NotFat32));
+
+        // More clusters than the FAT has entries for
+        let mut card = photo_card();
+        card.write(PART, 32, &1000u32.to_le_bytes());
+        assert_eq!(Volume::mount(&mut card).err(), Some(StorageError::NotFat32));
+
+        // Root directory outside the volume
+        let mut card = photo_card();
+        card.write(PART, 44, &200u32.to_le_bytes());
+        assert_eq!(Volume::mount(&mut card).err(), Some(StorageError::NotFat32));
+    }
+
+    #[test]
+    fn mounts_a_card_without_partition_table() {
+        let mut card = Card::default();
+        boot_sector(&mut card, 0);
+        let volume = Volume::mount(&mut card).unwrap();
+        assert_eq!((volume.fat_start, volume.data_start), (4, 6));
+        assert_eq!(volume.cluster_count(), 100);
+    }
+}
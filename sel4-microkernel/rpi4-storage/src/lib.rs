@@ -0,0 +1,70 @@
+//! # Raspberry Pi 4 SD Card Storage
+//!
+//! Drivers behind the Storage PD:
+//! - **SDHCI**: the BCM2711 EMMC2 controller the SD card slot is wired to,
+//!   in polled single-block (PIO) mode (see [`sdhci`])
+//! - **FAT32**: a read-only FAT32 reader over any [`BlockDevice`], listing
+//!   the root directory and reading files by cluster chain (see [`fat32`])
+//!
+//! Everything read from the card is untrusted: the FAT32 code checks every
+//! cluster number against the volume before following it and bounds every
+//! chain walk, so a corrupt or hostile card image yields [`StorageError`]
+//! rather than a hang or an out-of-volume read.
+
+#![no_std]
+
+pub mod fat32;
+pub mod sdhci;
+
+pub use fat32::{DirEntry, File, Volume};
+pub use sdhci::Sdhci;
+
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
+/// Bytes per block, both on the card and in the filesystem
+pub const BLOCK_SIZE: usize = 512;
+
+/// A device read in 512-byte blocks
+pub trait BlockDevice {
+    /// Read block `lba` into `buf`
+    fn read_block(&mut self, lba: u32, buf: &mut [u8; BLOCK_SIZE]) -> Result<(), StorageError>;
+}
+
+/// Errors from the SD card and the filesystem on it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageError {
+    /// No card in the slot, or it never answered the reset sequence
+    NoCard,
+    /// The controller or card did not finish within the poll budget
+    Timeout,
+    /// The controller flagged a command or data error (CRC, end bit, index)
+    Controller,
+    /// The card is not an SD card this driver can talk to (pre-v2, voltage)
+    UnsupportedCard,
+    /// No FAT32 partition, or its boot sector is invalid
+    NotFat32,
+    /// A FAT or directory entry points outside the volume, or a cluster
+    /// chain loops
+    Corrupt,
+    /// No file with that index
+    NotFound,
+    /// The file does not fit the buffer it is read into
+    TooLarge,
+    /// Read past the end of the file
+    OutOfRange,
+}
+
+impl From<StorageError> for PdError {
+    fn from(e: StorageError) -> Self {
+        let kind = match e {
+            StorageError::NoCard | StorageError::NotFound => ErrorKind::NotFound,
+            StorageError::Timeout => ErrorKind::Timeout,
+            StorageError::Controller => ErrorKind::Hardware,
+            StorageError::UnsupportedCard | StorageError::NotFat32 => ErrorKind::Unsupported,
+            StorageError::Corrupt => ErrorKind::Integrity,
+            StorageError::TooLarge => ErrorKind::Capacity,
+            StorageError::OutOfRange => ErrorKind::InvalidArgument,
+        };
+        PdError::new(ErrorDomain::Storage, kind).with_detail(e as u16)
+    }
+}
@@ -0,0 +1,340 @@
+//! BCM2711 EMMC2 SD host controller.
+//!
+//! The Raspberry Pi 4's SD card slot hangs off EMMC2, an SDHCI 3.0
+//! compatible controller. The driver brings the card up by the SD
+//! physical-layer sequence (CMD0, CMD8, ACMD41, CMD2, CMD3, CMD7) at
+//! 400 kHz, then reads 512-byte blocks with CMD17 at 25 MHz, moving the data
+//! through the data port register (no DMA, no interrupts). Only SD v2 cards
+//! (SDSC up to 2 GB and SDHC/SDXC) are supported; the card stays in 1-bit,
+//! 3.3 V default-speed mode, which the firmware has already set up.
+//!
+//! Every wait polls a status bit with a fixed budget and gives up with
+//! [`StorageError::Timeout`], so a missing or wedged card cannot hang the
+//! Storage PD.
+
+use core::ptr::{read_volatile, write_volatile};
+
+use crate::{BlockDevice, StorageError, BLOCK_SIZE};
+
+/// EMMC2 physical base address (ARM low-peripheral mapping)
+pub const EMMC2_BASE: usize = 0xFE34_0000;
+
+/// Register offsets
+mod reg {
+    pub const BLKSIZECNT: usize = 0x04;
+    pub const ARG1: usize = 0x08;
+    pub const CMDTM: usize = 0x0C;
+    pub const RESP0: usize = 0x10;
+    pub const DATA: usize = 0x20;
+    pub const STATUS: usize = 0x24;
+    pub const CONTROL0: usize = 0x28;
+    pub const CONTROL1: usize = 0x2C;
+    pub const INTERRUPT: usize = 0x30;
+    pub const IRPT_MASK: usize = 0x34;
+    pub const IRPT_EN: usize = 0x38;
+    pub const CAPABILITIES: usize = 0x40;
+}
+
+// STATUS (present state)
+const STATUS_CMD_INHIBIT: u32 = 1 << 0;
+const STATUS_DAT_INHIBIT: u32 = 1 << 1;
+const STATUS_CARD_INSERTED: u32 = 1 << 16;
+
+/// CONTROL0: SD bus power on at 3.3 V
+const CONTROL0_POWER_3V3: u32 = 0x0F << 8;
+
+// CONTROL1 (clock control, timeout control, software reset)
+const C1_CLK_INTLEN: u32 = 1 << 0;
+const C1_CLK_STABLE: u32 = 1 << 1;
+const C1_CLK_EN: u32 = 1 << 2;
+const C1_CLK_DIVIDER: u32 = 0xFFC0;
+const C1_DATA_TIMEOUT_MAX: u32 = 0xE << 16;
+const C1_SRST_HC: u32 = 1 << 24;
+const C1_SRST_CMD: u32 = 1 << 25;
+const C1_SRST_DATA: u32 = 1 << 26;
+
+// INTERRUPT (write 1 to clear)
+const INT_CMD_DONE: u32 = 1 << 0;
+const INT_DATA_DONE: u32 = 1 << 1;
+const INT_READ_RDY: u32 = 1 << 5;
+const INT_ERR: u32 = 1 << 15;
+const INT_ALL: u32 = 0xFFFF_FFFF;
+
+// CMDTM: command index 29:24, data present 21, index check 20, CRC check
+// 19, response type 17:16; transfer mode in the low half
+const CMD_RSP_136: u32 = 1 << 16;
+const CMD_RSP_48: u32 = 2 << 16;
+const CMD_RSP_48_BUSY: u32 = 3 << 16;
+const CMD_CRC_CHECK: u32 = 1 << 19;
+const CMD_INDEX_CHECK: u32 = 1 << 20;
+const CMD_DATA: u32 = 1 << 21;
+const TM_READ: u32 = 1 << 4;
+
+// Commands
+const GO_IDLE_STATE: u8 = 0;
+const ALL_SEND_CID: u8 = 2;
+const SEND_RELATIVE_ADDR: u8 = 3;
+const SELECT_CARD: u8 = 7;
+const SEND_IF_COND: u8 = 8;
+const SET_BLOCKLEN: u8 = 16;
+const READ_SINGLE_BLOCK: u8 = 17;
+const APP_CMD: u8 = 55;
+const SD_SEND_OP_COND: u8 = 41;
+
+/// CMD8 argument: 2.7-3.6 V plus a check pattern the card echoes
+const IF_COND_3V3: u32 = 0x1AA;
+/// ACMD41 argument: host supports high capacity, 3.2-3.4 V window
+const OP_COND_HCS_3V3: u32 = (1 << 30) | 0x0030_0000;
+const OCR_POWERED_UP: u32 = 1 << 31;
+const OCR_HIGH_CAPACITY: u32 = 1 << 30;
+
+/// Card identification clock
+const ID_CLOCK_HZ: u32 = 400_000;
+/// Default-speed transfer clock
+const TRANSFER_CLOCK_HZ: u32 = 25_000_000;
+/// Base clock when the capabilities register does not report one (the
+/// firmware's EMMC2 clock)
+const DEFAULT_BASE_CLOCK_HZ: u32 = 100_000_000;
+
+/// Spin-loop iteration budget for polling a hardware bit before giving up
+const POLL_BUDGET: u32 = 1_000_000;
+/// ACMD41 attempts while the card powers up (about a second)
+const OP_COND_ATTEMPTS: u32 = 1000;
+
+/// Response a command expects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Response {
+    None,
+    /// 48-bit card status
+    R1,
+    /// R1, then the card holds DAT0 low while busy
+    R1b,
+    /// 136-bit CID/CSD
+    R2,
+    /// 48-bit OCR, no CRC
+    R3,
+    /// 48-bit published RCA
+    R6,
+    /// 48-bit interface condition echo
+    R7,
+}
+
+/// CMDTM value for command `index`
+fn command_word(index: u8, response: Response, read: bool) -> u32 {
+    let rsp = match response {
+        Response::None => 0,
+        Response::R2 => CMD_RSP_136 | CMD_CRC_CHECK,
+        Response::R3 => CMD_RSP_48,
+        Response::R1b => CMD_RSP_48_BUSY | CMD_CRC_CHECK | CMD_INDEX_CHECK,
+        Response::R1 | Response::R6 | Response::R7 => CMD_RSP_48 | CMD_CRC_CHECK | CMD_INDEX_CHECK,
+    };
+    let data = if read { CMD_DATA | TM_READ } else { 0 };
+    ((index as u32) << 24) | rsp | data
+}
+
+/// SDHCI 3.0 10-bit divided clock: the SD clock is `base / (2 * divider)`,
+/// or `base` itself for divider 0. Returns the smallest divider that does
+/// not exceed `target_hz`.
+pub fn clock_divider(base_hz: u32, target_hz: u32) -> u32 {
+    if target_hz >= base_hz {
+        return 0;
+    }
+    let divider = base_hz.div_ceil(2 * target_hz.max(1));
+    divider.min(0x3FF)
+}
+
+/// CONTROL1 divider field for `divider`: low 8 bits in 15:8, high 2 in 7:6
+fn divider_bits(divider: u32) -> u32 {
+    ((divider & 0xFF) << 8) | (((divider >> 8) & 0x3) << 6)
+}
+
+/// EMMC2 controller bound to a mapped MMIO base, and the card in its slot
+pub struct Sdhci {
+    base: usize,
+    /// Relative card address, already shifted into the argument's top half
+    rca: u32,
+    /// SDHC/SDXC cards are addressed in blocks, SDSC cards in bytes
+    high_capacity: bool,
+}
+
+impl Sdhci {
+    /// Create a driver over the register window at `base` (a
+    /// Microkit-mapped virtual address)
+    ///
+    /// # Safety
+    /// `base` must be the mapped EMMC2 register window.
+    pub const unsafe fn new(base: usize) -> Self {
+        Self { base, rca: 0, high_capacity: false }
+    }
+
+    #[inline]
+    fn read(&self, off: usize) -> u32 {
+        unsafe { read_volatile((self.base + off) as *const u32) }
+    }
+
+    #[inline]
+    fn write(&self, off: usize, val: u32) {
+        unsafe { write_volatile((self.base + off) as *mut u32, val) }
+    }
+
+    /// Poll until `done` holds for a register value
+    fn poll(&self, off: usize, done: impl Fn(u32) -> bool) -> Result<u32, StorageError> {
+        for _ in 0..POLL_BUDGET {
+            let value = self.read(off);
+            if done(value) {
+                return Ok(value);
+            }
+        }
+        Err(StorageError::Timeout)
+    }
+
+    /// Reset the controller and bring the card to the transfer state
+    pub fn init(&mut self) -> Result<(), StorageError> {
+        self.write(reg::CONTROL1, C1_SRST_HC);
+        self.poll(reg::CONTROL1, |c1| c1 & C1_SRST_HC == 0)?;
+        if self.read(reg::STATUS) & STATUS_CARD_INSERTED == 0 {
+            return Err(StorageError::NoCard);
+        }
+        self.write(reg::CONTROL0, CONTROL0_POWER_3V3);
+        self.set_clock(ID_CLOCK_HZ)?;
+
+        // Latch every status bit, signal none: the driver polls
+        self.write(reg::IRPT_MASK, INT_ALL);
+        self.write(reg::IRPT_EN, 0);
+
+        self.command(GO_IDLE_STATE, 0, Response::None)?;
+        // Pre-v2 cards do not know CMD8
+        match self.command(SEND_IF_COND, IF_COND_3V3, Response::R7) {
+            Ok(echo) if echo & 0xFFF == IF_COND_3V3 => {}
+            _ => return Err(StorageError::UnsupportedCard),
+        }
+
+        let mut ocr = 0;
+        for _ in 0..OP_COND_ATTEMPTS {
+            ocr = self.app_command(SD_SEND_OP_COND, OP_COND_HCS_3V3, Response::R3)?;
+            if ocr & OCR_POWERED_UP != 0 {
+                break;
+            }
+            spin(10_000);
+        }
+        if ocr & OCR_POWERED_UP == 0 {
+            return Err(StorageError::NoCard);
+        }
+        self.high_capacity = ocr & OCR_HIGH_CAPACITY != 0;
+
+        self.command(ALL_SEND_CID, 0, Response::R2)?;
+        self.rca = self.command(SEND_RELATIVE_ADDR, 0, Response::R6)? & 0xFFFF_0000;
+        self.command(SELECT_CARD, self.rca, Response::R1b)?;
+        if !self.high_capacity {
+            self.command(SET_BLOCKLEN, BLOCK_SIZE as u32, Response::R1)?;
+        }
+        self.set_clock(TRANSFER_CLOCK_HZ)
+    }
+
+    /// Run the SD clock at the fastest rate not above `target_hz`
+    fn set_clock(&self, target_hz: u32) -> Result<(), StorageError> {
+        let base_mhz = (self.read(reg::CAPABILITIES) >> 8) & 0xFF;
+        let base_hz = if base_mhz == 0 { DEFAULT_BASE_CLOCK_HZ } else { base_mhz * 1_000_000 };
+        let divider = divider_bits(clock_divider(base_hz, target_hz));
+
+        let c1 = self.read(reg::CONTROL1) & !(C1_CLK_EN | C1_CLK_DIVIDER);
+        self.write(reg::CONTROL1, c1);
+        self.write(reg::CONTROL1, c1 | divider | C1_CLK_INTLEN | C1_DATA_TIMEOUT_MAX);
+        self.poll(reg::CONTROL1, |c1| c1 & C1_CLK_STABLE != 0)?;
+        self.write(reg::CONTROL1, self.read(reg::CONTROL1) | C1_CLK_EN);
+        spin(10_000);
+        Ok(())
+    }
+
+    /// Wait for `mask` in INTERRUPT and acknowledge it. A controller error
+    /// resets the command and data lines so the next command can run.
+    fn wait_interrupt(&self, mask: u32) -> Result<(), StorageError> {
+        let irq = self.poll(reg::INTERRUPT, |irq| irq & (mask | INT_ERR) != 0)?;
+        if irq & INT_ERR != 0 {
+            self.write(reg::INTERRUPT, irq);
+            self.write(reg::CONTROL1, self.read(reg::CONTROL1) | C1_SRST_CMD | C1_SRST_DATA);
+            self.poll(reg::CONTROL1, |c1| c1 & (C1_SRST_CMD | C1_SRST_DATA) == 0)?;
+            return Err(StorageError::Controller);
+        }
+        self.write(reg::INTERRUPT, mask);
+        Ok(())
+    }
+
+    /// Issue a command and return the first response word
+    fn issue(&self, word: u32, arg: u32, uses_data_line: bool) -> Result<u32, StorageError> {
+        let inhibit = if uses_data_line { STATUS_CMD_INHIBIT | STATUS_DAT_INHIBIT } else { STATUS_CMD_INHIBIT };
+        self.poll(reg::STATUS, |status| status & inhibit == 0)?;
+        self.write(reg::INTERRUPT, INT_ALL);
+        self.write(reg::ARG1, arg);
+        self.write(reg::CMDTM, word);
+        self.wait_interrupt(INT_CMD_DONE)?;
+        Ok(self.read(reg::RESP0))
+    }
+
+    fn command(&self, index: u8, arg: u32, response: Response) -> Result<u32, StorageError> {
+        let busy = response == Response::R1b;
+        let resp = self.issue(command_word(index, response, false), arg, busy)?;
+        if busy {
+            self.wait_interrupt(INT_DATA_DONE)?;
+        }
+        Ok(resp)
+    }
+
+    fn app_command(&self, index: u8, arg: u32, response: Response) -> Result<u32, StorageError> {
+        self.command(APP_CMD, self.rca, Response::R1)?;
+        self.command(index, arg, response)
+    }
+}
+
+impl BlockDevice for Sdhci {
+    fn read_block(&mut self, lba: u32, buf: &mut [u8; BLOCK_SIZE]) -> Result<(), StorageError> {
+        let arg = if self.high_capacity {
+            lba
+        } else {
+            lba.checked_mul(BLOCK_SIZE as u32).ok_or(StorageError::OutOfRange)?
+        };
+        self.write(reg::BLKSIZECNT, (1 << 16) | BLOCK_SIZE as u32);
+        self.issue(command_word(READ_SINGLE_BLOCK, Response::R1, true), arg, true)?;
+        self.wait_interrupt(INT_READ_RDY)?;
+        for word in buf.chunks_exact_mut(4) {
+            word.copy_from_slice(&self.read(reg::DATA).to_le_bytes());
+        }
+        self.wait_interrupt(INT_DATA_DONE)
+    }
+}
+
+/// Busy-wait for approximately `iterations` loop passes
+#[inline]
+fn spin(iterations: u32) {
+    for _ in 0..iterations {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_never_exceeds_target() {
+        assert_eq!(clock_divider(100_000_000, 400_000), 125);
+        assert_eq!(clock_divider(100_000_000, 25_000_000), 2);
+        assert_eq!(clock_divider(100_000_000, 30_000_000), 2);
+        assert_eq!(clock_divider(50_000_000, 50_000_000), 0);
+        // Slowest the 10-bit divider goes
+        assert_eq!(clock_divider(200_000_000, 1), 0x3FF);
+
+        assert_eq!(divider_bits(125), 125 << 8);
+        assert_eq!(divider_bits(0x3FF), 0xFFC0);
+    }
+
+    #[test]
+    fn command_words() {
+        assert_eq!(command_word(GO_IDLE_STATE, Response::None, false), 0);
+        assert_eq!(command_word(SEND_IF_COND, Response::R7, false), 0x081A_0000);
+        assert_eq!(command_word(SD_SEND_OP_COND, Response::R3, false), 0x2902_0000);
+        assert_eq!(command_word(ALL_SEND_CID, Response::R2, false), 0x0209_0000);
+        assert_eq!(command_word(SELECT_CARD, Response::R1b, false), 0x071B_0000);
+        assert_eq!(command_word(READ_SINGLE_BLOCK, Response::R1, true), 0x113A_0010);
+    }
+}
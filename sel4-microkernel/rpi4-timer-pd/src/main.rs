@@ -0,0 +1,187 @@
+//! # Timer Protection Domain
+//!
+//! Keeps the wall-clock time and the tick. The system timer only counts
+//! microseconds since power-on; this PD pairs a reading of it with the time
+//! a setter reports and publishes the pair on the clock page defined in
+//! `rpi4-clock-protocol`, which readers map read-only and extrapolate from.
+//! It also runs compare channel 1 and notifies the Photoframe PD on every
+//! tick, so no client programs the timer or takes its interrupt:
+//!
+//! ```text
+//! ┌──────────┐  SET_TIME (PPC)  ┌──────────┐  clock_page (rw / r)  ┌─────────────┐
+//! │ Input PD │ ───────────────► │ Timer PD │ ────────────────────► │ Photoframe  │
+//! └──────────┘                  └────┬─────┘ ──── tick (notify) ─► │ PD          │
+//!                                    │ CLO/CHI, C1 (rw), IRQ 97    └─────────────┘
+//!                                    ▼
+//!                               system timer
+//! ```
+//!
+//! The local time zone comes from the `utc_offset_min` variable, patched
+//! into the image when the system is built, until a setter sends another.
+//! A tick only wakes the client; it reads the counter itself to see what is
+//! due (`rpi4_graphics::Instant`), so ticks late behind a higher-priority
+//! PD are skipped rather than queued.
+//!
+//! With the `watchdog` feature, every [`TICK_HZ`]th tick also pings the
+//! Watchdog PD, so a Timer PD that stops ticking gets the board reset.
+//!
+//! ## Security Properties
+//!
+//! 1. **One Writer**: only this PD maps the clock page writable; readers
+//!    cannot change the time another PD shows
+//! 2. **Source by Channel**: a setter's source is the channel its call came
+//!    in on, never an argument, so the console cannot pass for the network
+//! 3. **Checked Calls**: `decode_request` refuses times and offsets outside
+//!    the range the verified calendar is proven for
+//! 4. **No Callbacks**: this PD cannot call anyone, and its only
+//!    notifications are the tick and the heartbeat, which carry no data; a
+//!    compromised Timer PD can show a wrong time, wake the Photoframe PD too
+//!    often or not at all, or have the Watchdog PD reset the board by
+//!    falling silent, and nothing more
+
+#![no_std]
+#![no_main]
+
+use sel4_microkit::{debug_println, protection_domain, with_msg_regs, Channel, ChannelSet, Handler, MessageInfo};
+use rpi4_clock_protocol::{
+    decode_request, local_time, source_for_channel, ClockPage, ClockTime, SetTime, TimeSource,
+    CLOCK_PAGE_VADDR, MAX_UTC_OFFSET_MIN, TICK_HZ, TIMER_IRQ_CHANNEL_ID, TIMER_PD_SYSTIMER_BASE,
+    TIMER_TICK_CHANNEL_ID,
+};
+use rpi4_graphics::frame_timer::read_counter;
+use rpi4_graphics::FrameTimer;
+use rpi4_pd_error::{reply_code, ErrorDomain, ErrorKind, PdError, PdResult};
+#[cfg(feature = "watchdog")]
+use rpi4_watchdog_protocol::CLIENT_WATCHDOG_CHANNEL_ID;
+
+/// Unknown call label or out-of-range time (the detail carries the label)
+const INVALID_REQUEST: PdError = PdError::new(ErrorDomain::Clock, ErrorKind::InvalidArgument);
+
+/// Call on a channel that speaks for no time source
+const WRONG_CHANNEL: PdError = PdError::new(ErrorDomain::Clock, ErrorKind::Denied);
+
+/// System timer compare 1 IRQ (must match photoframe.system)
+const IRQ_CHANNEL: Channel = Channel::new(TIMER_IRQ_CHANNEL_ID);
+
+/// Tick notification to the Photoframe PD (must match photoframe.system)
+const TICK_CHANNEL: Channel = Channel::new(TIMER_TICK_CHANNEL_ID);
+
+/// Heartbeat to the Watchdog PD (must match photoframe.system)
+#[cfg(feature = "watchdog")]
+const WATCHDOG_CHANNEL: Channel = Channel::new(CLIENT_WATCHDOG_CHANNEL_ID);
+
+struct TimerPd {
+    /// What the clock page holds
+    time: ClockTime,
+    /// The tick; this PD is the only user of compare channel 1
+    ticker: FrameTimer,
+}
+
+impl TimerPd {
+    /// Microseconds since the system timer started
+    fn now_us(&self) -> u64 {
+        // Safety: photoframe.system maps the system timer registers here
+        unsafe { read_counter(TIMER_PD_SYSTIMER_BASE) }
+    }
+
+    fn publish(&self) {
+        // Safety: the clock page is mapped here, and this PD is its only
+        // writer
+        unsafe { ClockPage::publish(CLOCK_PAGE_VADDR as *mut u8, &self.time) };
+    }
+
+    /// Set the clock from `source`
+    fn set(&mut self, request: SetTime, source: TimeSource) -> PdResult<()> {
+        self.time = ClockTime {
+            epoch_s: request.epoch_s,
+            mono_us: self.now_us(),
+            utc_offset_min: request.utc_offset_min.unwrap_or(self.time.utc_offset_min),
+            source,
+        };
+        self.publish();
+        match local_time(self.time.epoch_s, self.time.utc_offset_min) {
+            Some(local) => debug_println!(
+                "Timer PD: Time set from {}: {} (UTC{:+} min)",
+                source.name(), local, self.time.utc_offset_min
+            ),
+            None => debug_println!("Timer PD: Time set from {}: {} s", source.name(), self.time.epoch_s),
+        }
+        Ok(())
+    }
+}
+
+impl Handler for TimerPd {
+    type Error = PdError;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        // Clear the match before acking, or the level IRQ refires at once
+        if channels.contains(IRQ_CHANNEL) {
+            if self.ticker.handle_irq() {
+                TICK_CHANNEL.notify();
+                // Once a second, as PING_INTERVAL_MS asks
+                #[cfg(feature = "watchdog")]
+                if self.ticker.ticks() % TICK_HZ == 0 {
+                    WATCHDOG_CHANNEL.notify();
+                }
+            }
+            IRQ_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking timer IRQ")
+            })?;
+        }
+        Ok(())
+    }
+
+    fn protected(&mut self, channel: Channel, msg_info: MessageInfo) -> Result<MessageInfo, Self::Error> {
+        let label = msg_info.label();
+        let [epoch_s, offset] = with_msg_regs(|mrs| [mrs[0], mrs[1]]);
+        let result = match source_for_channel(channel.index()) {
+            None => Err(WRONG_CHANNEL),
+            Some(source) => match decode_request(label, epoch_s, offset) {
+                Some(request) => self.set(request, source),
+                None => Err(INVALID_REQUEST.with_detail(label as u16)),
+            },
+        };
+        if let Err(e) = &result {
+            debug_println!("Timer PD: {}", e);
+        }
+        Ok(MessageInfo::new(reply_code(&result) as u64, 0))
+    }
+}
+
+/// Local time zone, in minutes east of UTC
+///
+/// `utc_offset_min` is patched into the image when the system is built.
+/// Offsets no time zone uses fall back to UTC.
+fn configured_offset() -> i16 {
+    let value = *sel4_microkit::var!(utc_offset_min: i64 = 0);
+    match i16::try_from(value) {
+        Ok(offset) if offset.unsigned_abs() <= MAX_UTC_OFFSET_MIN as u16 => offset,
+        _ => {
+            debug_println!("Timer PD: UTC offset {} min out of range, using UTC", value);
+            0
+        }
+    }
+}
+
+#[protection_domain]
+fn init() -> TimerPd {
+    debug_println!("");
+    debug_println!("========================================");
+    debug_println!("  Timer Protection Domain Starting");
+    debug_println!("========================================");
+    debug_println!("");
+    debug_println!("Timer PD: System timer at 0x{:x}", TIMER_PD_SYSTIMER_BASE);
+    debug_println!("Timer PD: Clock page at 0x{:x}", CLOCK_PAGE_VADDR);
+
+    // Safety: photoframe.system maps the system timer page here; the only
+    // other PD given it writable is the Watchdog PD, which uses compare
+    // channel 3, so compare channel 1 is this PD's
+    let ticker = unsafe { FrameTimer::start(TIMER_PD_SYSTIMER_BASE, TICK_HZ) };
+    let pd = TimerPd { time: ClockTime::unset(configured_offset()), ticker };
+    // Readers see the time zone, and that there is no time yet, until a
+    // setter calls
+    pd.publish();
+    debug_println!("Timer PD: Ticking at {} Hz", TICK_HZ);
+    debug_println!("Timer PD: Ready, time unset (UTC{:+} min); Ctrl-T on the console sets it", pd.time.utc_offset_min);
+    pd
+}
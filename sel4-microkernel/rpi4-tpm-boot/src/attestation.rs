@@ -25,6 +25,12 @@ use crate::boot_chain::BootChain;
 /// Size of attestation nonce
 pub const NONCE_SIZE: usize = 32;
 
+/// TPM_GENERATED_VALUE, opening every structure the TPM signs
+pub const TPM_GENERATED_VALUE: u32 = 0xFF544347;
+
+/// TPM_ST_ATTEST_QUOTE
+pub const TPM_ST_ATTEST_QUOTE: u16 = 0x8018;
+
 /// Maximum size of quote data
 pub const MAX_QUOTE_SIZE: usize = 1024;
 
@@ -107,6 +113,109 @@ pub struct AttestedData {
     pub pcr_digest: Sha256Digest,
 }
 
+impl AttestedData {
+    /// Parse the TPMS_ATTEST of a SHA-256 quote, as TPM2_Quote returns
+    /// it. A nonce shorter than [`NONCE_SIZE`] is zero-padded in
+    /// `extra_data`; the signer name, nonce or selection not fitting
+    /// these fields is an error.
+    pub fn parse(attest: &[u8]) -> TpmResult<Self> {
+        let mut reader = Reader {
+            bytes: attest,
+            pos: 0,
+        };
+        let magic = reader.u32()?;
+        let attest_type = reader.u16()?;
+        if attest_type != TPM_ST_ATTEST_QUOTE {
+            return Err(TpmRc::BadTag);
+        }
+
+        let mut qualified_signer = [0u8; 34];
+        let name = reader.tpm2b()?;
+        qualified_signer
+            .get_mut(..name.len())
+            .ok_or(TpmRc::Failure)?
+            .copy_from_slice(name);
+        let mut extra_data = [0u8; NONCE_SIZE];
+        let nonce = reader.tpm2b()?;
+        extra_data
+            .get_mut(..nonce.len())
+            .ok_or(TpmRc::Failure)?
+            .copy_from_slice(nonce);
+
+        let clock_info = ClockInfo {
+            clock: reader.u64()?,
+            reset_count: reader.u32()?,
+            restart_count: reader.u32()?,
+            safe: reader.take(1)?[0] != 0,
+        };
+        let firmware_version = reader.u64()?;
+
+        // TPML_PCR_SELECTION: exactly one SHA-256 bank
+        if reader.u32()? != 1 || reader.u16()? != crate::slb9670::TPM2_ALG_SHA256 {
+            return Err(TpmRc::Failure);
+        }
+        let select = reader.take(1)?[0] as usize;
+        let mut bitmap = 0u32;
+        for (i, &byte) in reader.take(select)?.iter().enumerate() {
+            if i >= 3 {
+                if byte != 0 {
+                    return Err(TpmRc::Failure);
+                }
+                continue;
+            }
+            bitmap |= (byte as u32) << (8 * i);
+        }
+
+        let digest = reader.tpm2b()?;
+        let pcr_digest = Sha256Digest::new(digest.try_into().map_err(|_| TpmRc::Failure)?);
+
+        Ok(Self {
+            magic,
+            attest_type,
+            qualified_signer,
+            extra_data,
+            clock_info,
+            firmware_version,
+            pcr_select: PcrSelection::from_bitmap(bitmap),
+            pcr_digest,
+        })
+    }
+}
+
+/// Big-endian cursor over a TPM structure
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> TpmResult<&'a [u8]> {
+        let end = self.pos.checked_add(n).ok_or(TpmRc::Failure)?;
+        let taken = self.bytes.get(self.pos..end).ok_or(TpmRc::Failure)?;
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> TpmResult<u16> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> TpmResult<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> TpmResult<u64> {
+        Ok(((self.u32()? as u64) << 32) | self.u32()? as u64)
+    }
+
+    fn tpm2b(&mut self) -> TpmResult<&'a [u8]> {
+        let n = self.u16()? as usize;
+        self.take(n)
+    }
+}
+
 /// TPM clock information
 #[derive(Clone, Copy, Debug)]
 pub struct ClockInfo {
@@ -335,7 +444,7 @@ impl AttestationVerifier {
         }
 
         // 2. Verify magic value
-        if response.quote.attested.magic != 0xFF544347 {
+        if response.quote.attested.magic != TPM_GENERATED_VALUE {
             return VerificationResult::InvalidQuote;
         }
 
@@ -515,4 +624,49 @@ mod tests {
         let result = verifier.verify(&request, &response);
         assert_eq!(result, VerificationResult::NonceMismatch);
     }
+
+    #[test]
+    fn test_parse_quote_attest() {
+        extern crate std;
+        use std::vec::Vec;
+
+        let mut attest = Vec::new();
+        attest.extend_from_slice(&TPM_GENERATED_VALUE.to_be_bytes());
+        attest.extend_from_slice(&TPM_ST_ATTEST_QUOTE.to_be_bytes());
+        attest.extend_from_slice(&34u16.to_be_bytes());
+        attest.extend_from_slice(&[0x00, 0x0B]);
+        attest.extend_from_slice(&[0xAA; 32]);
+        attest.extend_from_slice(&8u16.to_be_bytes());
+        attest.extend_from_slice(&[0x42; 8]);
+        attest.extend_from_slice(&1000u64.to_be_bytes()); // clock
+        attest.extend_from_slice(&3u32.to_be_bytes()); // resetCount
+        attest.extend_from_slice(&1u32.to_be_bytes()); // restartCount
+        attest.push(1); // safe
+        attest.extend_from_slice(&0x0001_0002_0003_0004u64.to_be_bytes());
+        attest.extend_from_slice(&1u32.to_be_bytes());
+        attest.extend_from_slice(&0x000Bu16.to_be_bytes());
+        attest.push(3);
+        attest.extend_from_slice(&[0x9F, 0, 0]); // PCRs 0-4 and 7
+        attest.extend_from_slice(&32u16.to_be_bytes());
+        attest.extend_from_slice(&[0x5C; 32]);
+
+        let parsed = AttestedData::parse(&attest).unwrap();
+        assert_eq!(parsed.magic, TPM_GENERATED_VALUE);
+        assert_eq!(parsed.qualified_signer[2..], [0xAA; 32]);
+        assert_eq!(parsed.extra_data[..8], [0x42; 8]);
+        assert_eq!(parsed.extra_data[8..], [0; 24]);
+        assert_eq!(parsed.clock_info.clock, 1000);
+        assert_eq!(parsed.clock_info.reset_count, 3);
+        assert!(parsed.clock_info.safe);
+        assert_eq!(parsed.firmware_version, 0x0001_0002_0003_0004);
+        assert_eq!(parsed.pcr_select.bitmap(), 0x9F);
+        assert_eq!(parsed.pcr_digest, Sha256Digest::new([0x5C; 32]));
+
+        assert_eq!(
+            AttestedData::parse(&attest[..attest.len() - 1]).err(),
+            Some(TpmRc::Failure)
+        );
+        attest[5] = 0x17; // TPM_ST_ATTEST_CERTIFY
+        assert_eq!(AttestedData::parse(&attest).err(), Some(TpmRc::BadTag));
+    }
 }
@@ -5,17 +5,20 @@
 //! ## Verification Properties
 //!
 //! 1. **Measurement Integrity**: Each boot stage measurement is correctly
-//!    computed as SHA-256 hash of the component.
+//!    computed as SHA-256 hash of the component, and as its hash in every
+//!    other selected bank ([`BankSelection`]).
 //!
 //! 2. **PCR Extension Correctness**: PCR values are computed as:
-//!    `PCR_new = SHA-256(PCR_old || measurement)`
+//!    `PCR_new = H(PCR_old || measurement)`, with `H` the bank's hash
 //!
 //! 3. **Chain Ordering**: Boot stages are measured in correct sequence.
 //!
 //! 4. **No Skipping**: Every boot stage must be measured before proceeding.
 
-use crate::{Sha256Digest, BootStage, TpmResult, TpmRc};
-use sha2::{Sha256, Digest};
+use crate::pcr::{BankSelection, DigestValues, PcrBanks};
+use crate::{Sha256Digest, BootStage, HashAlg, PcrDigest, TpmResult, TpmRc};
+use sha2::{Sha256, Sha384, Digest};
+use sm3::Sm3;
 
 // ============================================================================
 // VERIFIED BOOT MEASUREMENT TYPES
@@ -77,6 +80,8 @@ pub struct BootChain {
     count: usize,
     /// Current PCR values (simulated)
     pcr_values: [Sha256Digest; 24],
+    /// Current PCR values in every selected bank, SHA-256 included
+    banks: PcrBanks,
     /// Expected PCR values for verification
     expected_pcrs: [Option<Sha256Digest>; 24],
     /// Whether the chain is sealed (no more measurements)
@@ -84,17 +89,29 @@ pub struct BootChain {
 }
 
 impl BootChain {
-    /// Create a new empty boot chain
+    /// Create a new empty boot chain measuring into the SHA-256 bank
     pub const fn new() -> Self {
+        Self::with_banks(BankSelection::sha256())
+    }
+
+    /// Create a new empty boot chain measuring into `banks`
+    pub const fn with_banks(banks: BankSelection) -> Self {
         Self {
             measurements: [None; MAX_MEASUREMENTS],
             count: 0,
             pcr_values: [Sha256Digest::zero(); 24],
+            banks: PcrBanks::new(banks),
             expected_pcrs: [None; 24],
             sealed: false,
         }
     }
 
+    /// Banks the chain measures into
+    #[inline]
+    pub fn banks(&self) -> BankSelection {
+        self.banks.banks()
+    }
+
     /// Get number of measurements
     #[inline]
     pub fn count(&self) -> usize {
@@ -116,6 +133,11 @@ impl BootChain {
         }
     }
 
+    /// Get PCR value in the `alg` bank; None if the bank is not selected
+    pub fn bank_value(&self, alg: HashAlg, index: u8) -> Option<&PcrDigest> {
+        self.banks.read(alg, index)
+    }
+
     /// Set expected PCR value for verification
     pub fn set_expected_pcr(&mut self, index: u8, digest: Sha256Digest) -> bool {
         if index < 24 {
@@ -128,11 +150,27 @@ impl BootChain {
 
     /// Add a measurement to the chain
     ///
+    /// With banks other than SHA-256 selected, use
+    /// [`Self::add_measurement_values`]: the SHA-256 digest alone cannot
+    /// extend them.
+    pub fn add_measurement(&mut self, measurement: BootMeasurement) -> TpmResult<()> {
+        self.add_measurement_values(measurement, &DigestValues::from(measurement.digest))
+    }
+
+    /// Add a measurement to the chain, with its digest in every selected
+    /// bank
+    ///
     /// # Verification Properties (enforced at runtime)
     /// - PCR index must be valid (0-23)
     /// - Chain must not be sealed
     /// - Measurement count must not exceed maximum
-    pub fn add_measurement(&mut self, measurement: BootMeasurement) -> TpmResult<()> {
+    /// - `values` holds a digest for exactly the selected banks, its
+    ///   SHA-256 one being the measurement's
+    pub fn add_measurement_values(
+        &mut self,
+        measurement: BootMeasurement,
+        values: &DigestValues,
+    ) -> TpmResult<()> {
         // Check preconditions
         if self.sealed {
             return Err(TpmRc::BadSequence);
@@ -147,6 +185,13 @@ impl BootChain {
             return Err(TpmRc::BadParam);
         }
 
+        let sha256 = values.get(HashAlg::Sha256).and_then(PcrDigest::to_sha256);
+        if sha256 != Some(measurement.digest) {
+            return Err(TpmRc::BadParam);
+        }
+        // Checks the banks before changing any
+        self.banks.extend(pcr_index, values)?;
+
         // Extend PCR: PCR_new = SHA-256(PCR_old || measurement)
         let new_pcr = extend_pcr(&self.pcr_values[pcr_index as usize], &measurement.digest);
         self.pcr_values[pcr_index as usize] = new_pcr;
@@ -158,7 +203,7 @@ impl BootChain {
         Ok(())
     }
 
-    /// Measure and add a component
+    /// Measure and add a component, in every selected bank
     pub fn measure_component(
         &mut self,
         stage: BootStage,
@@ -166,8 +211,9 @@ impl BootChain {
         data: &[u8],
     ) -> TpmResult<Sha256Digest> {
         let measurement = BootMeasurement::from_component(stage, component_id, data);
+        let values = DigestValues::measure(self.banks(), data);
         let digest = measurement.digest;
-        self.add_measurement(measurement)?;
+        self.add_measurement_values(measurement, &values)?;
         Ok(digest)
     }
 
@@ -326,6 +372,39 @@ pub fn extend_pcr(current: &Sha256Digest, measurement: &Sha256Digest) -> Sha256D
     Sha256Digest::new(bytes)
 }
 
+/// Hash `parts`, in order, with `alg`
+fn hash_parts(alg: HashAlg, parts: &[&[u8]]) -> PcrDigest {
+    fn hash<D: Digest>(parts: &[&[u8]], out: &mut [u8]) {
+        let mut hasher = D::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        out.copy_from_slice(&hasher.finalize());
+    }
+
+    let mut digest = PcrDigest::zero(alg);
+    let out = &mut digest.bytes[..alg.digest_len()];
+    match alg {
+        HashAlg::Sha256 => hash::<Sha256>(parts, out),
+        HashAlg::Sha384 => hash::<Sha384>(parts, out),
+        HashAlg::Sm3_256 => hash::<Sm3>(parts, out),
+    }
+    digest
+}
+
+/// Compute the `alg` hash of data
+pub fn compute_digest(alg: HashAlg, data: &[u8]) -> PcrDigest {
+    hash_parts(alg, &[data])
+}
+
+/// Extend a PCR value in any bank with a measurement of the same
+/// algorithm
+///
+/// PCR_new = H(PCR_old || measurement), with `H` the bank's hash
+pub fn extend_digest(current: &PcrDigest, measurement: &PcrDigest) -> PcrDigest {
+    hash_parts(current.alg(), &[current.as_bytes(), measurement.as_bytes()])
+}
+
 /// Constant-time comparison of digests (timing attack resistant)
 pub fn constant_time_compare(a: &Sha256Digest, b: &Sha256Digest) -> bool {
     let mut diff: u8 = 0;
@@ -479,6 +558,51 @@ mod tests {
         assert!(chain.add_measurement(measurement).is_err());
     }
 
+    #[test]
+    fn test_digest_algorithms() {
+        // FIPS 180-2 and GB/T 32905 test vectors for "abc"
+        assert_eq!(
+            compute_digest(HashAlg::Sha256, b"abc").as_bytes(),
+            hex_literal::hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            compute_digest(HashAlg::Sha384, b"abc").as_bytes(),
+            hex_literal::hex!(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed"
+                "8086072ba1e7cc2358baeca134c825a7"
+            )
+        );
+        assert_eq!(
+            compute_digest(HashAlg::Sm3_256, b"abc").as_bytes(),
+            hex_literal::hex!("66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0")
+        );
+
+        // The SHA-256 bank extends exactly as before
+        let measurement = compute_sha256(b"measurement");
+        let extended = extend_digest(&PcrDigest::zero(HashAlg::Sha256), &measurement.into());
+        assert_eq!(extended.to_sha256(), Some(extend_pcr(&Sha256Digest::zero(), &measurement)));
+    }
+
+    #[test]
+    fn test_boot_chain_banks() {
+        let banks = BankSelection::sha256().with(HashAlg::Sha384);
+        let mut chain = BootChain::with_banks(banks);
+        let digest = chain.measure_component(BootStage::Kernel, 1, b"sel4.elf").unwrap();
+
+        let kernel = BootStage::Kernel.pcr_index();
+        let sha384 = compute_digest(HashAlg::Sha384, b"sel4.elf");
+        let expected = extend_digest(&PcrDigest::zero(HashAlg::Sha384), &sha384);
+        assert_eq!(chain.bank_value(HashAlg::Sha384, kernel), Some(&expected));
+        assert_eq!(chain.bank_value(HashAlg::Sha256, kernel).and_then(PcrDigest::to_sha256).as_ref(), chain.pcr_value(kernel));
+        assert_eq!(chain.bank_value(HashAlg::Sm3_256, kernel), None);
+
+        // A SHA-256 digest alone would leave the SHA-384 bank behind
+        let measurement = BootMeasurement::new(BootStage::Kernel, digest, 1, 8);
+        assert_eq!(chain.add_measurement(measurement), Err(TpmRc::BadParam));
+        assert_eq!(chain.count(), 1);
+        assert!(chain.replay_and_verify());
+    }
+
     #[test]
     fn test_constant_time_compare() {
         let a = compute_sha256(b"test");
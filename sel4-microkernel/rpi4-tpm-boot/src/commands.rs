@@ -11,13 +11,18 @@
 //! the TPM stack joins the verified set; host tests + the update-capsule
 //! parser set the pattern.)
 
-use crate::pcr::PcrReadResult;
+use crate::pcr::{BankReadResult, DigestValues, PcrReadResult};
 use crate::pcr::PcrSelection;
 use crate::slb9670::{
-    MAX_PCR_INDEX, TPM2_ALG_SHA256, TPM2_CC_GET_RANDOM, TPM2_CC_PCR_EXTEND, TPM2_CC_PCR_READ,
-    TPM2_CC_QUOTE, TPM2_CC_SELF_TEST, TPM2_CC_STARTUP, TPM2_ST_NO_SESSIONS, TPM2_ST_SESSIONS,
+    MAX_PCR_INDEX, TPM2_ALG_SHA256, TPM2_CC_CREATE, TPM2_CC_CREATE_PRIMARY, TPM2_CC_EVICT_CONTROL,
+    TPM2_CC_FLUSH_CONTEXT, TPM2_CC_GET_RANDOM, TPM2_CC_LOAD, TPM2_CC_NV_DEFINE_SPACE,
+    TPM2_CC_NV_INCREMENT, TPM2_CC_NV_READ, TPM2_CC_NV_READ_PUBLIC, TPM2_CC_NV_UNDEFINE_SPACE,
+    TPM2_CC_NV_WRITE, TPM2_CC_PCR_EXTEND, TPM2_CC_PCR_READ, TPM2_CC_POLICY_PCR, TPM2_CC_QUOTE,
+    TPM2_CC_READ_PUBLIC, TPM2_CC_SELF_TEST, TPM2_CC_START_AUTH_SESSION, TPM2_CC_STARTUP, TPM2_ALG_AES,
+    TPM2_ALG_CFB, TPM2_RH_NULL, TPM2_RH_OWNER, TPM2_SE_HMAC, TPM2_SE_POLICY, TPM2_ST_NO_SESSIONS,
+    TPM2_ST_SESSIONS,
 };
-use crate::{Sha256Digest, TpmRc, TpmResult};
+use crate::{HashAlg, PcrDigest, Sha256Digest, TpmRc, TpmResult, HASH_ALG_COUNT};
 
 /// TPM_RS_PW: the built-in password authorization session handle.
 pub const TPM_RS_PW: u32 = 0x4000_0009;
@@ -33,7 +38,14 @@ const PW_AUTH_LEN: u32 = 9;
 
 /// Writes the 9-byte empty password authorization area at `off`.
 fn write_pw_auth(buf: &mut [u8], off: usize) {
-    buf[off..off + 4].copy_from_slice(&TPM_RS_PW.to_be_bytes());
+    write_session_auth(buf, off, TPM_RS_PW);
+}
+
+/// Writes a 9-byte authorization area for `session` at `off`: no nonce,
+/// no HMAC, and continueSession clear, so a policy session is flushed
+/// once the command succeeds.
+fn write_session_auth(buf: &mut [u8], off: usize, session: u32) {
+    buf[off..off + 4].copy_from_slice(&session.to_be_bytes());
     buf[off + 4..off + 6].copy_from_slice(&0u16.to_be_bytes()); // nonce size
     buf[off + 6] = 0; // session attributes
     buf[off + 7..off + 9].copy_from_slice(&0u16.to_be_bytes()); // hmac size
@@ -93,19 +105,58 @@ pub fn build_pcr_extend(pcr_index: u8, digest: &Sha256Digest) -> TpmResult<[u8;
     Ok(cmd)
 }
 
-/// Exact size of a single-bank SHA-256 TPM2_PCR_Read command:
+/// Largest TPM2_PCR_Extend: the single-digest layout's first 31 bytes,
+/// then hashAlg(2) + digest for every supported bank.
+pub const PCR_EXTEND_CMD_MAX_LEN: usize = 31 + 2 * HASH_ALG_COUNT + 32 + 48 + 32;
+
+/// Build TPM2_PCR_Extend with the digest for each bank in `values`.
+/// Returns the command length.
+pub fn build_pcr_extend_values(
+    pcr_index: u8,
+    values: &DigestValues,
+    out: &mut [u8; PCR_EXTEND_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    if pcr_index > MAX_PCR_INDEX || values.digests().is_empty() {
+        return Err(TpmRc::BadParam);
+    }
+
+    let len = 31 + values.digests().iter().map(|d| 2 + d.as_bytes().len()).sum::<usize>();
+    out[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    out[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+    out[6..10].copy_from_slice(&TPM2_CC_PCR_EXTEND.to_be_bytes());
+    out[10..14].copy_from_slice(&(pcr_index as u32).to_be_bytes());
+    out[14..18].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(out, 18);
+    // TPML_DIGEST_VALUES: one TPMT_HA per bank
+    out[27..31].copy_from_slice(&(values.digests().len() as u32).to_be_bytes());
+    let mut off = 31;
+    for digest in values.digests() {
+        let bytes = digest.as_bytes();
+        out[off..off + 2].copy_from_slice(&digest.alg().alg_id().to_be_bytes());
+        out[off + 2..off + 2 + bytes.len()].copy_from_slice(bytes);
+        off += 2 + bytes.len();
+    }
+    Ok(len)
+}
+
+/// Exact size of a single-bank TPM2_PCR_Read command:
 /// header(10) + count(4) + hashAlg(2) + sizeofSelect(1) + select(3).
 pub const PCR_READ_CMD_LEN: usize = 20;
 
 /// Build TPM2_PCR_Read for the SHA-256 bank.
 pub fn build_pcr_read(selection: PcrSelection) -> [u8; PCR_READ_CMD_LEN] {
+    build_pcr_read_bank(HashAlg::Sha256, selection)
+}
+
+/// Build TPM2_PCR_Read for the `alg` bank.
+pub fn build_pcr_read_bank(alg: HashAlg, selection: PcrSelection) -> [u8; PCR_READ_CMD_LEN] {
     let mut cmd = [0u8; PCR_READ_CMD_LEN];
     cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
     cmd[2..6].copy_from_slice(&(PCR_READ_CMD_LEN as u32).to_be_bytes());
     cmd[6..10].copy_from_slice(&TPM2_CC_PCR_READ.to_be_bytes());
     // TPML_PCR_SELECTION with one entry
     cmd[10..14].copy_from_slice(&1u32.to_be_bytes());
-    cmd[14..16].copy_from_slice(&TPM2_ALG_SHA256.to_be_bytes());
+    cmd[14..16].copy_from_slice(&alg.alg_id().to_be_bytes());
     cmd[16] = 3; // sizeofSelect: 3 bytes cover PCR 0-23
     let bitmap = selection.bitmap();
     cmd[17] = (bitmap & 0xFF) as u8;
@@ -178,6 +229,482 @@ pub fn build_quote(
     Ok(len)
 }
 
+// ============================================================================
+// KEY PROVISIONING
+// ============================================================================
+
+/// TPMT_PUBLIC of the storage root key: a restricted decryption ECC P-256
+/// key with an AES-128-CFB symmetric wrapper, the parent the attestation
+/// key is created under.
+pub const SRK_TEMPLATE: [u8; 26] = [
+    0x00, 0x23, // type: TPM_ALG_ECC
+    0x00, 0x0B, // nameAlg: SHA-256
+    // fixedTPM | fixedParent | sensitiveDataOrigin | userWithAuth | noDA
+    // | restricted | decrypt
+    0x00, 0x03, 0x04, 0x72, // objectAttributes
+    0x00, 0x00, // authPolicy: empty
+    0x00, 0x06, 0x00, 0x80, 0x00, 0x43, // symmetric: AES-128-CFB
+    0x00, 0x10, // scheme: TPM_ALG_NULL
+    0x00, 0x03, // curveID: NIST P-256
+    0x00, 0x10, // kdf: TPM_ALG_NULL
+    0x00, 0x00, 0x00, 0x00, // unique: empty x and y
+];
+
+/// TPMT_PUBLIC of the attestation key: a restricted ECDSA P-256 signing
+/// key, so it only signs structures the TPM generated itself (quotes).
+pub const AK_TEMPLATE: [u8; 24] = [
+    0x00, 0x23, // type: TPM_ALG_ECC
+    0x00, 0x0B, // nameAlg: SHA-256
+    // fixedTPM | fixedParent | sensitiveDataOrigin | userWithAuth
+    // | restricted | sign
+    0x00, 0x05, 0x00, 0x72, // objectAttributes
+    0x00, 0x00, // authPolicy: empty
+    0x00, 0x10, // symmetric: TPM_ALG_NULL
+    0x00, 0x18, 0x00, 0x0B, // scheme: ECDSA with SHA-256
+    0x00, 0x03, // curveID: NIST P-256
+    0x00, 0x10, // kdf: TPM_ALG_NULL
+    0x00, 0x00, 0x00, 0x00, // unique: empty x and y
+];
+
+/// Largest public template [`build_create_primary`] and [`build_create`]
+/// accept.
+pub const TEMPLATE_MAX_LEN: usize = 64;
+
+/// Maximum size of a TPM2_CreatePrimary or TPM2_Create command:
+/// header(10) + parentHandle(4) + authSize(4) + password auth(9)
+/// + TPM2B_SENSITIVE_CREATE(6) + TPM2B_PUBLIC(2+template)
+/// + TPM2B outsideInfo(2) + TPML_PCR_SELECTION creationPCR(4).
+pub const CREATE_CMD_MAX_LEN: usize = 41 + TEMPLATE_MAX_LEN;
+
+/// Largest TPM2B_PRIVATE or TPM2B_PUBLIC contents [`build_load`] accepts.
+pub const KEY_BLOB_MAX_LEN: usize = 256;
+
+/// Maximum size of a TPM2_Load command: header(10) + parentHandle(4)
+/// + authSize(4) + password auth(9) + TPM2B_PRIVATE + TPM2B_PUBLIC.
+pub const LOAD_CMD_MAX_LEN: usize = 27 + 2 * (2 + KEY_BLOB_MAX_LEN);
+
+/// Exact size of a TPM2_EvictControl command: header(10) + auth(4)
+/// + objectHandle(4) + authSize(4) + password auth(9)
+/// + persistentHandle(4).
+pub const EVICT_CONTROL_CMD_LEN: usize = 35;
+
+/// TPM2_CreatePrimary and TPM2_Create share their layout: a parent, an
+/// empty sensitive area, the template, no outside info and no creation
+/// PCRs.
+fn build_create_object(
+    command_code: u32,
+    parent: u32,
+    template: &[u8],
+    out: &mut [u8; CREATE_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    if template.len() > TEMPLATE_MAX_LEN {
+        return Err(TpmRc::BadParam);
+    }
+    let len = 41 + template.len();
+
+    out[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    out[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+    out[6..10].copy_from_slice(&command_code.to_be_bytes());
+    out[10..14].copy_from_slice(&parent.to_be_bytes());
+    out[14..18].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(out, 18);
+
+    // TPM2B_SENSITIVE_CREATE: empty userAuth and data
+    out[27..29].copy_from_slice(&4u16.to_be_bytes());
+    out[29..33].fill(0);
+    // TPM2B_PUBLIC
+    let mut off = 33;
+    out[off..off + 2].copy_from_slice(&(template.len() as u16).to_be_bytes());
+    off += 2;
+    out[off..off + template.len()].copy_from_slice(template);
+    off += template.len();
+    // Empty outsideInfo, empty creationPCR selection
+    out[off..off + 6].fill(0);
+    off += 6;
+
+    debug_assert_eq!(off, len);
+    Ok(len)
+}
+
+/// Build TPM2_CreatePrimary of `template` in `hierarchy`. Returns the
+/// command length written.
+pub fn build_create_primary(
+    hierarchy: u32,
+    template: &[u8],
+    out: &mut [u8; CREATE_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    build_create_object(TPM2_CC_CREATE_PRIMARY, hierarchy, template, out)
+}
+
+/// Build TPM2_Create of `template` under the loaded key `parent`.
+/// Returns the command length written.
+pub fn build_create(
+    parent: u32,
+    template: &[u8],
+    out: &mut [u8; CREATE_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    build_create_object(TPM2_CC_CREATE, parent, template, out)
+}
+
+/// Build TPM2_Load of a key's private and public areas, as TPM2_Create
+/// returned them, under `parent`. Returns the command length written.
+pub fn build_load(
+    parent: u32,
+    private: &[u8],
+    public: &[u8],
+    out: &mut [u8; LOAD_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    if private.len() > KEY_BLOB_MAX_LEN || public.len() > KEY_BLOB_MAX_LEN {
+        return Err(TpmRc::BadParam);
+    }
+    let len = 31 + private.len() + public.len();
+
+    out[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    out[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+    out[6..10].copy_from_slice(&TPM2_CC_LOAD.to_be_bytes());
+    out[10..14].copy_from_slice(&parent.to_be_bytes());
+    out[14..18].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(out, 18);
+
+    let mut off = 27;
+    for blob in [private, public] {
+        out[off..off + 2].copy_from_slice(&(blob.len() as u16).to_be_bytes());
+        off += 2;
+        out[off..off + blob.len()].copy_from_slice(blob);
+        off += blob.len();
+    }
+
+    debug_assert_eq!(off, len);
+    Ok(len)
+}
+
+/// Build TPM2_EvictControl making the loaded `object_handle` persistent
+/// at `persistent_handle`, authorized by the owner hierarchy.
+pub fn build_evict_control(
+    object_handle: u32,
+    persistent_handle: u32,
+) -> [u8; EVICT_CONTROL_CMD_LEN] {
+    let mut cmd = [0u8; EVICT_CONTROL_CMD_LEN];
+    cmd[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&(EVICT_CONTROL_CMD_LEN as u32).to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_EVICT_CONTROL.to_be_bytes());
+    cmd[10..14].copy_from_slice(&TPM2_RH_OWNER.to_be_bytes());
+    cmd[14..18].copy_from_slice(&object_handle.to_be_bytes());
+    cmd[18..22].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(&mut cmd, 22);
+    cmd[31..35].copy_from_slice(&persistent_handle.to_be_bytes());
+    cmd
+}
+
+/// Build TPM2_FlushContext, unloading a transient object.
+pub fn build_flush_context(handle: u32) -> [u8; 14] {
+    let mut cmd = [0u8; 14];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&14u32.to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_FLUSH_CONTEXT.to_be_bytes());
+    cmd[10..14].copy_from_slice(&handle.to_be_bytes());
+    cmd
+}
+
+/// Build TPM2_ReadPublic of a loaded or persistent object.
+pub fn build_read_public(handle: u32) -> [u8; 14] {
+    let mut cmd = [0u8; 14];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&14u32.to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_READ_PUBLIC.to_be_bytes());
+    cmd[10..14].copy_from_slice(&handle.to_be_bytes());
+    cmd
+}
+
+// ============================================================================
+// NV COUNTERS
+// ============================================================================
+
+/// TPMA_NV_WRITTEN: set once an index has been written (for a counter,
+/// incremented) at least once
+pub const TPMA_NV_WRITTEN: u32 = 1 << 29;
+
+/// Attributes of a counter index: TPM_NT_COUNTER, read and incremented with
+/// the index's own (empty) auth, exempt from dictionary-attack lockout
+pub const NV_COUNTER_ATTRIBUTES: u32 = (1 << 4) // TPM_NT_COUNTER
+    | (1 << 2) // TPMA_NV_AUTHWRITE
+    | (1 << 18) // TPMA_NV_AUTHREAD
+    | (1 << 25); // TPMA_NV_NO_DA
+
+/// Exact size of a TPM2_NV_DefineSpace command for a counter:
+/// header(10) + authHandle(4) + authSize(4) + password auth(9)
+/// + TPM2B_AUTH(2) + TPM2B_NV_PUBLIC(2+14).
+pub const NV_DEFINE_CMD_LEN: usize = 45;
+
+/// Exact size of a TPM2_NV_Increment command: header(10) + authHandle(4)
+/// + nvIndex(4) + authSize(4) + password auth(9).
+pub const NV_INCREMENT_CMD_LEN: usize = 31;
+
+/// Exact size of a TPM2_NV_Read command: TPM2_NV_Increment's layout
+/// + size(2) + offset(2).
+pub const NV_READ_CMD_LEN: usize = 35;
+
+/// Largest authPolicy [`build_nv_define_space`] writes: one SHA-256 digest.
+pub const NV_DEFINE_CMD_MAX_LEN: usize = NV_DEFINE_CMD_LEN + 32;
+
+/// Build TPM2_NV_DefineSpace of a `size`-byte index at `nv_index` with an
+/// empty auth and `auth_policy`, if any, authorized by the owner
+/// hierarchy. Returns the command length written.
+pub fn build_nv_define_space(
+    nv_index: u32,
+    attributes: u32,
+    size: u16,
+    auth_policy: Option<&Sha256Digest>,
+    out: &mut [u8; NV_DEFINE_CMD_MAX_LEN],
+) -> usize {
+    let policy: &[u8] = match auth_policy {
+        Some(digest) => &digest.bytes,
+        None => &[],
+    };
+    let len = NV_DEFINE_CMD_LEN + policy.len();
+
+    out[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    out[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+    out[6..10].copy_from_slice(&TPM2_CC_NV_DEFINE_SPACE.to_be_bytes());
+    out[10..14].copy_from_slice(&TPM2_RH_OWNER.to_be_bytes());
+    out[14..18].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(out, 18);
+    // Empty TPM2B_AUTH at 27..29, then TPM2B_NV_PUBLIC
+    out[27..29].fill(0);
+    out[29..31].copy_from_slice(&(14 + policy.len() as u16).to_be_bytes());
+    out[31..35].copy_from_slice(&nv_index.to_be_bytes());
+    out[35..37].copy_from_slice(&TPM2_ALG_SHA256.to_be_bytes());
+    out[37..41].copy_from_slice(&attributes.to_be_bytes());
+    out[41..43].copy_from_slice(&(policy.len() as u16).to_be_bytes());
+    out[43..43 + policy.len()].copy_from_slice(policy);
+    out[43 + policy.len()..len].copy_from_slice(&size.to_be_bytes());
+    len
+}
+
+/// Build TPM2_NV_DefineSpace of an 8-byte counter at `nv_index`, with
+/// [`NV_COUNTER_ATTRIBUTES`] and an empty auth, authorized by the owner
+/// hierarchy.
+pub fn build_nv_define_counter(nv_index: u32) -> [u8; NV_DEFINE_CMD_LEN] {
+    let mut out = [0u8; NV_DEFINE_CMD_MAX_LEN];
+    build_nv_define_space(nv_index, NV_COUNTER_ATTRIBUTES, 8, None, &mut out);
+    let mut cmd = [0u8; NV_DEFINE_CMD_LEN];
+    cmd.copy_from_slice(&out[..NV_DEFINE_CMD_LEN]);
+    cmd
+}
+
+/// TPM2_NV_Increment, TPM2_NV_Read and TPM2_NV_Write all authorize with
+/// the index itself, through `session`
+fn write_nv_auth(cmd: &mut [u8], command_code: u32, nv_index: u32, session: u32) {
+    cmd[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    let len = cmd.len() as u32;
+    cmd[2..6].copy_from_slice(&len.to_be_bytes());
+    cmd[6..10].copy_from_slice(&command_code.to_be_bytes());
+    cmd[10..14].copy_from_slice(&nv_index.to_be_bytes());
+    cmd[14..18].copy_from_slice(&nv_index.to_be_bytes());
+    cmd[18..22].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_session_auth(cmd, 22, session);
+}
+
+/// Build TPM2_NV_Increment of the counter at `nv_index`.
+pub fn build_nv_increment(nv_index: u32) -> [u8; NV_INCREMENT_CMD_LEN] {
+    let mut cmd = [0u8; NV_INCREMENT_CMD_LEN];
+    write_nv_auth(&mut cmd, TPM2_CC_NV_INCREMENT, nv_index, TPM_RS_PW);
+    cmd
+}
+
+/// Build TPM2_NV_Read of the 8-byte counter at `nv_index`.
+pub fn build_nv_read_counter(nv_index: u32) -> [u8; NV_READ_CMD_LEN] {
+    build_nv_read(nv_index, TPM_RS_PW, 8, 0)
+}
+
+/// Build TPM2_NV_ReadPublic of `nv_index`.
+pub fn build_nv_read_public(nv_index: u32) -> [u8; 14] {
+    let mut cmd = [0u8; 14];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&14u32.to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_NV_READ_PUBLIC.to_be_bytes());
+    cmd[10..14].copy_from_slice(&nv_index.to_be_bytes());
+    cmd
+}
+
+// ============================================================================
+// NV STORAGE
+// ============================================================================
+
+/// TPMA_NV_POLICYWRITE: the index is written under its authPolicy
+pub const TPMA_NV_POLICYWRITE: u32 = 1 << 3;
+
+/// TPMA_NV_POLICYREAD: the index is read under its authPolicy
+pub const TPMA_NV_POLICYREAD: u32 = 1 << 19;
+
+/// Attributes of an ordinary data index read and written with its own
+/// (empty) auth, exempt from dictionary-attack lockout
+pub const NV_DATA_ATTRIBUTES: u32 = (1 << 2) // TPMA_NV_AUTHWRITE
+    | (1 << 18) // TPMA_NV_AUTHREAD
+    | (1 << 25); // TPMA_NV_NO_DA
+
+/// Attributes of an ordinary data index only read or written under its
+/// authPolicy
+pub const NV_SEALED_ATTRIBUTES: u32 = TPMA_NV_POLICYWRITE | TPMA_NV_POLICYREAD | (1 << 25);
+
+/// Most bytes one TPM2_NV_Read or TPM2_NV_Write moves; well under the
+/// MAX_NV_BUFFER_SIZE of every part this crate supports.
+pub const NV_BUFFER_MAX: usize = 256;
+
+/// Maximum size of a TPM2_NV_Write command: header(10) + authHandle(4)
+/// + nvIndex(4) + authSize(4) + auth(9) + TPM2B_MAX_NV_BUFFER(2+data)
+/// + offset(2).
+pub const NV_WRITE_CMD_MAX_LEN: usize = 35 + NV_BUFFER_MAX;
+
+/// Exact size of a TPM2_NV_UndefineSpace command: header(10)
+/// + authHandle(4) + nvIndex(4) + authSize(4) + password auth(9).
+pub const NV_UNDEFINE_CMD_LEN: usize = 31;
+
+/// Bytes of nonceCaller [`build_start_policy_session`] sends: the
+/// shortest a SHA-256 session accepts.
+pub const POLICY_NONCE_LEN: usize = 16;
+
+/// Exact size of a TPM2_StartAuthSession command for an unbound,
+/// unsalted SHA-256 policy session: header(10) + tpmKey(4) + bind(4)
+/// + TPM2B nonceCaller(2+16) + empty encryptedSalt(2) + sessionType(1)
+/// + TPMT_SYM_DEF null(2) + authHash(2).
+pub const START_AUTH_SESSION_CMD_LEN: usize = 43;
+
+/// Exact size of a TPM2_PolicyPCR command: header(10) + policySession(4)
+/// + empty pcrDigest(2) + TPML_PCR_SELECTION(10).
+pub const POLICY_PCR_CMD_LEN: usize = 26;
+
+/// The TPML_PCR_SELECTION of `selection` in the SHA-256 bank, as every
+/// command here sends it.
+pub fn marshal_pcr_selection(selection: PcrSelection) -> [u8; 10] {
+    let mut out = [0u8; 10];
+    out[0..4].copy_from_slice(&1u32.to_be_bytes());
+    out[4..6].copy_from_slice(&TPM2_ALG_SHA256.to_be_bytes());
+    out[6] = 3; // sizeofSelect: 3 bytes cover PCR 0-23
+    let bitmap = selection.bitmap();
+    out[7] = (bitmap & 0xFF) as u8;
+    out[8] = ((bitmap >> 8) & 0xFF) as u8;
+    out[9] = ((bitmap >> 16) & 0xFF) as u8;
+    out
+}
+
+/// Build TPM2_NV_Read of `size` bytes from `offset` of `nv_index`,
+/// authorized through `session`.
+pub fn build_nv_read(nv_index: u32, session: u32, size: u16, offset: u16) -> [u8; NV_READ_CMD_LEN] {
+    let mut cmd = [0u8; NV_READ_CMD_LEN];
+    write_nv_auth(&mut cmd, TPM2_CC_NV_READ, nv_index, session);
+    cmd[31..33].copy_from_slice(&size.to_be_bytes());
+    cmd[33..35].copy_from_slice(&offset.to_be_bytes());
+    cmd
+}
+
+/// Build TPM2_NV_Write of `data` at `offset` of `nv_index`, authorized
+/// through `session`. Returns the command length written.
+pub fn build_nv_write(
+    nv_index: u32,
+    session: u32,
+    offset: u16,
+    data: &[u8],
+    out: &mut [u8; NV_WRITE_CMD_MAX_LEN],
+) -> TpmResult<usize> {
+    if data.len() > NV_BUFFER_MAX {
+        return Err(TpmRc::BadParam);
+    }
+    let len = 35 + data.len();
+    write_nv_auth(&mut out[..len], TPM2_CC_NV_WRITE, nv_index, session);
+    out[31..33].copy_from_slice(&(data.len() as u16).to_be_bytes());
+    out[33..33 + data.len()].copy_from_slice(data);
+    out[33 + data.len()..len].copy_from_slice(&offset.to_be_bytes());
+    Ok(len)
+}
+
+/// Build TPM2_NV_UndefineSpace of `nv_index`, authorized by the owner
+/// hierarchy.
+pub fn build_nv_undefine_space(nv_index: u32) -> [u8; NV_UNDEFINE_CMD_LEN] {
+    let mut cmd = [0u8; NV_UNDEFINE_CMD_LEN];
+    cmd[0..2].copy_from_slice(&TPM2_ST_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&(NV_UNDEFINE_CMD_LEN as u32).to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_NV_UNDEFINE_SPACE.to_be_bytes());
+    cmd[10..14].copy_from_slice(&TPM2_RH_OWNER.to_be_bytes());
+    cmd[14..18].copy_from_slice(&nv_index.to_be_bytes());
+    cmd[18..22].copy_from_slice(&PW_AUTH_LEN.to_be_bytes());
+    write_pw_auth(&mut cmd, 22);
+    cmd
+}
+
+/// Build TPM2_StartAuthSession of a policy session with no salt, no
+/// bind and no parameter encryption.
+pub fn build_start_policy_session(nonce_caller: &[u8; POLICY_NONCE_LEN]) -> [u8; START_AUTH_SESSION_CMD_LEN] {
+    let mut cmd = [0u8; START_AUTH_SESSION_CMD_LEN];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&(START_AUTH_SESSION_CMD_LEN as u32).to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_START_AUTH_SESSION.to_be_bytes());
+    cmd[10..14].copy_from_slice(&TPM2_RH_NULL.to_be_bytes()); // tpmKey
+    cmd[14..18].copy_from_slice(&TPM2_RH_NULL.to_be_bytes()); // bind
+    cmd[18..20].copy_from_slice(&(POLICY_NONCE_LEN as u16).to_be_bytes());
+    cmd[20..36].copy_from_slice(nonce_caller);
+    // Empty encryptedSalt at 36..38
+    cmd[38] = TPM2_SE_POLICY;
+    cmd[39..41].copy_from_slice(&TPM2_ALG_NULL.to_be_bytes());
+    cmd[41..43].copy_from_slice(&TPM2_ALG_SHA256.to_be_bytes());
+    cmd
+}
+
+/// Bytes of nonceCaller [`build_start_hmac_session`] sends: a full
+/// SHA-256 digest.
+pub const HMAC_NONCE_LEN: usize = 32;
+
+/// Bytes in the TPMS_ECC_POINT of a P-256 point: two TPM2B coordinates.
+pub const ECC_POINT_LEN: usize = 68;
+
+/// Exact size of a TPM2_StartAuthSession command for an ECC-salted,
+/// unbound SHA-256 HMAC session with AES-128-CFB parameter encryption:
+/// header(10) + tpmKey(4) + bind(4) + TPM2B nonceCaller(2+32)
+/// + TPM2B encryptedSalt(2+68) + sessionType(1) + TPMT_SYM_DEF(6)
+/// + authHash(2).
+pub const START_HMAC_SESSION_CMD_LEN: usize = 131;
+
+/// Build TPM2_StartAuthSession of an HMAC session salted through the
+/// loaded ECC key `tpm_key`; `encrypted_salt` is the caller's ephemeral
+/// public point.
+pub fn build_start_hmac_session(
+    tpm_key: u32,
+    nonce_caller: &[u8; HMAC_NONCE_LEN],
+    encrypted_salt: &[u8; ECC_POINT_LEN],
+) -> [u8; START_HMAC_SESSION_CMD_LEN] {
+    let mut cmd = [0u8; START_HMAC_SESSION_CMD_LEN];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&(START_HMAC_SESSION_CMD_LEN as u32).to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_START_AUTH_SESSION.to_be_bytes());
+    cmd[10..14].copy_from_slice(&tpm_key.to_be_bytes());
+    cmd[14..18].copy_from_slice(&TPM2_RH_NULL.to_be_bytes()); // bind
+    cmd[18..20].copy_from_slice(&(HMAC_NONCE_LEN as u16).to_be_bytes());
+    cmd[20..52].copy_from_slice(nonce_caller);
+    cmd[52..54].copy_from_slice(&(ECC_POINT_LEN as u16).to_be_bytes());
+    cmd[54..122].copy_from_slice(encrypted_salt);
+    cmd[122] = TPM2_SE_HMAC;
+    // TPMT_SYM_DEF: AES-128 in CFB mode
+    cmd[123..125].copy_from_slice(&TPM2_ALG_AES.to_be_bytes());
+    cmd[125..127].copy_from_slice(&128u16.to_be_bytes());
+    cmd[127..129].copy_from_slice(&TPM2_ALG_CFB.to_be_bytes());
+    cmd[129..131].copy_from_slice(&TPM2_ALG_SHA256.to_be_bytes());
+    cmd
+}
+
+/// Build TPM2_PolicyPCR, binding `session` to the current values of the
+/// `selection` PCRs (an empty pcrDigest lets the TPM take them as they
+/// are).
+pub fn build_policy_pcr(session: u32, selection: PcrSelection) -> [u8; POLICY_PCR_CMD_LEN] {
+    let mut cmd = [0u8; POLICY_PCR_CMD_LEN];
+    cmd[0..2].copy_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+    cmd[2..6].copy_from_slice(&(POLICY_PCR_CMD_LEN as u32).to_be_bytes());
+    cmd[6..10].copy_from_slice(&TPM2_CC_POLICY_PCR.to_be_bytes());
+    cmd[10..14].copy_from_slice(&session.to_be_bytes());
+    // Empty pcrDigest at 14..16
+    cmd[16..26].copy_from_slice(&marshal_pcr_selection(selection));
+    cmd
+}
+
 // ============================================================================
 // RESPONSE PARSERS
 // ============================================================================
@@ -229,6 +756,17 @@ pub fn parse_get_random(resp: &[u8]) -> TpmResult<&[u8]> {
 /// Parse a single-bank SHA-256 TPM2_PCR_Read response into
 /// `(pcr index, digest)` pairs, using the selection the TPM echoes back.
 pub fn parse_pcr_read(resp: &[u8]) -> TpmResult<PcrReadResult> {
+    let bank = parse_pcr_read_bank(resp, HashAlg::Sha256)?;
+    let mut result = PcrReadResult::new();
+    for (index, digest) in bank.values() {
+        result.add(*index, digest.to_sha256().ok_or(TpmRc::Failure)?);
+    }
+    Ok(result)
+}
+
+/// Parse a TPM2_PCR_Read response for the `alg` bank into
+/// `(pcr index, digest)` pairs, using the selection the TPM echoes back.
+pub fn parse_pcr_read_bank(resp: &[u8], alg: HashAlg) -> TpmResult<BankReadResult> {
     check_response(resp)?;
     let body = &resp[RESPONSE_HEADER_LEN..];
 
@@ -238,7 +776,7 @@ pub fn parse_pcr_read(resp: &[u8]) -> TpmResult<PcrReadResult> {
     }
     let selection_count = u32::from_be_bytes([body[4], body[5], body[6], body[7]]) as usize;
     if selection_count != 1 {
-        // This crate only ever requests the SHA-256 bank.
+        // This crate only ever requests one bank at a time.
         return Err(TpmRc::Failure);
     }
 
@@ -247,10 +785,10 @@ pub fn parse_pcr_read(resp: &[u8]) -> TpmResult<PcrReadResult> {
     if body.len() < off + 3 {
         return Err(TpmRc::Failure);
     }
-    let alg = u16::from_be_bytes([body[off], body[off + 1]]);
+    let bank_alg = u16::from_be_bytes([body[off], body[off + 1]]);
     let size_of_select = body[off + 2] as usize;
     off += 3;
-    if alg != TPM2_ALG_SHA256 || size_of_select > 3 || body.len() < off + size_of_select {
+    if HashAlg::from_alg_id(bank_alg) != Some(alg) || size_of_select > 3 || body.len() < off + size_of_select {
         return Err(TpmRc::Failure);
     }
     let mut bitmap: u32 = 0;
@@ -271,7 +809,7 @@ pub fn parse_pcr_read(resp: &[u8]) -> TpmResult<PcrReadResult> {
         return Err(TpmRc::Failure);
     }
 
-    let mut result = PcrReadResult::new();
+    let mut result = BankReadResult::new(alg);
     let mut indices = selection.iter();
     for _ in 0..digest_count {
         if body.len() < off + 2 {
@@ -279,15 +817,14 @@ pub fn parse_pcr_read(resp: &[u8]) -> TpmResult<PcrReadResult> {
         }
         let dlen = u16::from_be_bytes([body[off], body[off + 1]]) as usize;
         off += 2;
-        if dlen != 32 || body.len() < off + dlen {
+        if dlen != alg.digest_len() || body.len() < off + dlen {
             return Err(TpmRc::Failure);
         }
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&body[off..off + 32]);
-        off += 32;
+        let digest = PcrDigest::from_bytes(alg, &body[off..off + dlen]).ok_or(TpmRc::Failure)?;
+        off += dlen;
         // Digests are returned in ascending PCR order of the selection.
         let index = indices.next().ok_or(TpmRc::Failure)?;
-        result.add(index, Sha256Digest::new(bytes));
+        result.add(index, digest)?;
     }
     Ok(result)
 }
@@ -334,3 +871,131 @@ pub fn parse_quote(resp: &[u8]) -> TpmResult<QuoteResponse<'_>> {
     }
     Ok(QuoteResponse { attest, signature })
 }
+
+/// The contents of the TPM2B at `off` in `buf`, and the offset after it.
+pub(crate) fn tpm2b(buf: &[u8], off: usize) -> TpmResult<(&[u8], usize)> {
+    let start = off.checked_add(2).ok_or(TpmRc::Failure)?;
+    if buf.len() < start {
+        return Err(TpmRc::Failure);
+    }
+    let end = start + u16::from_be_bytes([buf[off], buf[off + 1]]) as usize;
+    if buf.len() < end {
+        return Err(TpmRc::Failure);
+    }
+    Ok((&buf[start..end], end))
+}
+
+/// The parameter area of a response with sessions, from the
+/// parameterSize that follows any response handles. The session
+/// acknowledgement trails it.
+fn parameter_area(body: &[u8]) -> TpmResult<&[u8]> {
+    if body.len() < 4 {
+        return Err(TpmRc::Failure);
+    }
+    let size = u32::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize;
+    body.get(4..4 + size).ok_or(TpmRc::Failure)
+}
+
+/// The single response handle of a response with sessions, and what
+/// follows it.
+fn response_handle(resp: &[u8]) -> TpmResult<(u32, &[u8])> {
+    let body = &resp[RESPONSE_HEADER_LEN..];
+    if body.len() < 4 {
+        return Err(TpmRc::Failure);
+    }
+    let handle = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+    Ok((handle, &body[4..]))
+}
+
+/// A primary key TPM2_CreatePrimary loaded: its transient handle and
+/// TPMT_PUBLIC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatedPrimary<'a> {
+    pub handle: u32,
+    pub public: &'a [u8],
+}
+
+/// Parse a TPM2_CreatePrimary response (password session).
+pub fn parse_create_primary(resp: &[u8]) -> TpmResult<CreatedPrimary<'_>> {
+    check_response(resp)?;
+    let (handle, rest) = response_handle(resp)?;
+    let (public, _) = tpm2b(parameter_area(rest)?, 0)?;
+    Ok(CreatedPrimary { handle, public })
+}
+
+/// A key TPM2_Create made but did not load: its wrapped TPM2B_PRIVATE
+/// and its TPMT_PUBLIC contents, ready for [`build_load`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatedKey<'a> {
+    pub private: &'a [u8],
+    pub public: &'a [u8],
+}
+
+/// Parse a TPM2_Create response (password session).
+pub fn parse_create(resp: &[u8]) -> TpmResult<CreatedKey<'_>> {
+    check_response(resp)?;
+    let params = parameter_area(&resp[RESPONSE_HEADER_LEN..])?;
+    let (private, off) = tpm2b(params, 0)?;
+    let (public, _) = tpm2b(params, off)?;
+    Ok(CreatedKey { private, public })
+}
+
+/// Parse a TPM2_Load response (password session); returns the loaded
+/// object's transient handle.
+pub fn parse_load(resp: &[u8]) -> TpmResult<u32> {
+    check_response(resp)?;
+    let (handle, rest) = response_handle(resp)?;
+    // The name must be there, though nothing here needs it
+    tpm2b(parameter_area(rest)?, 0)?;
+    Ok(handle)
+}
+
+/// Parse a TPM2_ReadPublic response; returns the object's TPMT_PUBLIC.
+pub fn parse_read_public(resp: &[u8]) -> TpmResult<&[u8]> {
+    check_response(resp)?;
+    let (public, _) = tpm2b(&resp[RESPONSE_HEADER_LEN..], 0)?;
+    Ok(public)
+}
+
+/// Parse a TPM2_NV_Read response; returns the data read.
+pub fn parse_nv_read(resp: &[u8]) -> TpmResult<&[u8]> {
+    check_response(resp)?;
+    let (data, _) = tpm2b(parameter_area(&resp[RESPONSE_HEADER_LEN..])?, 0)?;
+    Ok(data)
+}
+
+/// Parse a TPM2_NV_Read response (password session) of an 8-byte counter.
+pub fn parse_nv_read_counter(resp: &[u8]) -> TpmResult<u64> {
+    let bytes: [u8; 8] = parse_nv_read(resp)?.try_into().map_err(|_| TpmRc::Failure)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Parse a TPM2_StartAuthSession response; returns the session handle
+/// and nonceTPM.
+pub fn parse_start_auth_session(resp: &[u8]) -> TpmResult<(u32, &[u8])> {
+    check_response(resp)?;
+    let (handle, rest) = response_handle(resp)?;
+    let (nonce_tpm, _) = tpm2b(rest, 0)?;
+    Ok((handle, nonce_tpm))
+}
+
+/// Parse a TPM2_NV_ReadPublic response; returns the index's attributes.
+pub fn parse_nv_read_public(resp: &[u8]) -> TpmResult<u32> {
+    check_response(resp)?;
+    let (public, _) = tpm2b(&resp[RESPONSE_HEADER_LEN..], 0)?;
+    // nvIndex(4) + nameAlg(2), then the attributes
+    let attributes = public.get(6..10).ok_or(TpmRc::Failure)?;
+    Ok(u32::from_be_bytes([attributes[0], attributes[1], attributes[2], attributes[3]]))
+}
+
+/// Bytes in the Name of a SHA-256 NV index: nameAlg(2) + digest(32).
+pub const NV_NAME_LEN: usize = 34;
+
+/// Parse a TPM2_NV_ReadPublic response; returns the index's Name, which
+/// an HMAC session's cpHash covers. It changes once the index is written.
+pub fn parse_nv_name(resp: &[u8]) -> TpmResult<[u8; NV_NAME_LEN]> {
+    check_response(resp)?;
+    let (_, off) = tpm2b(&resp[RESPONSE_HEADER_LEN..], 0)?;
+    let (name, _) = tpm2b(&resp[RESPONSE_HEADER_LEN..], off)?;
+    name.try_into().map_err(|_| TpmRc::Failure)
+}
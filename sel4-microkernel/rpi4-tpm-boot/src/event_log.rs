@@ -0,0 +1,472 @@
+//! # TCG Event Log
+//!
+//! [`BootChain`] keeps its measurements to itself. A verifier checking a
+//! quote off-device needs them as a log it can replay: every extend, in
+//! order, with the PCR it went to and what was measured. [`TcgEventLog`]
+//! records them in the crypto-agile format of the TCG PC Client Platform
+//! Firmware Profile, the one `tpm2_eventlog` and Keylime read:
+//!
+//! ```text
+//! TCG_PCClientPCREvent   PCR 0, EV_NO_ACTION, SHA-1 sized zero digest,
+//!                        TCG_EfiSpecIdEvent ("Spec ID Event03", SHA-256 only)
+//! TCG_PCR_EVENT2         PCR, event type, 1 digest (TPM_ALG_SHA256), event data
+//! TCG_PCR_EVENT2         ...
+//! ```
+//!
+//! All fields are little-endian. [`replay_log`] parses a log (this one or any
+//! other crypto-agile log carrying SHA-256) back into PCR values, which is
+//! what the verifier compares against the quote.
+//!
+//! A [`BootMeasurement`]'s event data is its component ID and size, both
+//! `u32`, so the log names what was measured without the PD keeping file
+//! names.
+
+use crate::boot_chain::{extend_pcr, BootChain, BootMeasurement};
+use crate::{BootStage, Sha256Digest, TpmRc, TpmResult};
+
+// ============================================================================
+// FORMAT CONSTANTS
+// ============================================================================
+
+/// Bytes a log holds
+pub const EVENT_LOG_SIZE: usize = 4096;
+
+/// Longest event data a log accepts
+pub const MAX_EVENT_DATA: usize = 256;
+
+/// Bytes of the log handed out per IPC read
+pub const EVENT_LOG_CHUNK: usize = 512;
+
+/// TCG algorithm ID of SHA-256
+pub const TPM_ALG_SHA256: u16 = 0x000B;
+
+/// Signature opening the Spec ID event
+const SPEC_ID_SIGNATURE: &[u8; 16] = b"Spec ID Event03\0";
+
+/// Spec ID event: signature, platform class, version 2.0 errata 0, 64-bit
+/// UINTN, one algorithm, no vendor info
+const SPEC_ID_EVENT_SIZE: usize = 16 + 4 + 4 + 4 + 4 + 1;
+
+/// The header event: PCR, type, SHA-1 sized digest, size, Spec ID event
+const HEADER_SIZE: usize = 4 + 4 + 20 + 4 + SPEC_ID_EVENT_SIZE;
+
+/// Bytes of a `TCG_PCR_EVENT2` before its event data
+const EVENT2_FIXED_SIZE: usize = 4 + 4 + 4 + 2 + 32 + 4;
+
+/// Algorithms a parsed log may list
+const MAX_ALGORITHMS: usize = 8;
+
+/// TCG event types used by this log
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TcgEventType {
+    /// Firmware measured by the boot ROM
+    PostCode = 0x0000_0001,
+    /// Informational; not extended into any PCR
+    NoAction = 0x0000_0003,
+    /// End of a boot phase
+    Separator = 0x0000_0004,
+    /// A runtime measurement tagged by its sender
+    EventTag = 0x0000_0006,
+    /// Code loaded by the bootloader
+    Ipl = 0x0000_000D,
+    /// Platform configuration
+    PlatformConfigFlags = 0x0000_000A,
+}
+
+impl TcgEventType {
+    /// The event type a boot stage's measurements are logged as
+    pub const fn for_stage(stage: BootStage) -> Self {
+        match stage {
+            BootStage::Firmware => TcgEventType::PostCode,
+            BootStage::Kernel | BootStage::ProtectionDomains => TcgEventType::Ipl,
+            BootStage::System | BootStage::SecureBootPolicy => TcgEventType::PlatformConfigFlags,
+            BootStage::Runtime => TcgEventType::EventTag,
+        }
+    }
+}
+
+/// Why a log did not parse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventLogError {
+    /// The log ends inside the event starting at this offset
+    Truncated { offset: usize },
+    /// The first event is not a Spec ID event
+    BadHeader,
+    /// The log carries no SHA-256 digests
+    NoSha256,
+    /// An event at this offset names a PCR above 23
+    BadPcr { offset: usize },
+    /// An event at this offset uses an algorithm the header did not list
+    UnknownAlgorithm { offset: usize },
+}
+
+// ============================================================================
+// EVENT LOG BUILDER
+// ============================================================================
+
+/// The header event, built once
+const HEADER: [u8; HEADER_SIZE] = header();
+
+const fn header() -> [u8; HEADER_SIZE] {
+    let mut out = [0u8; HEADER_SIZE];
+    // PCR 0, EV_NO_ACTION, 20 zero bytes of digest
+    out[4] = TcgEventType::NoAction as u32 as u8;
+    let size = (SPEC_ID_EVENT_SIZE as u32).to_le_bytes();
+    out[28] = size[0];
+    out[29] = size[1];
+    out[30] = size[2];
+    out[31] = size[3];
+
+    let mut i = 0;
+    while i < 16 {
+        out[32 + i] = SPEC_ID_SIGNATURE[i];
+        i += 1;
+    }
+    // platformClass 0 (client), then version minor 0, major 2, errata 0,
+    // uintnSize 2 (64-bit)
+    out[52] = 0;
+    out[53] = 2;
+    out[54] = 0;
+    out[55] = 2;
+    // numberOfAlgorithms 1: SHA-256, 32 bytes
+    out[56] = 1;
+    let alg = TPM_ALG_SHA256.to_le_bytes();
+    out[60] = alg[0];
+    out[61] = alg[1];
+    out[62] = 32;
+    // vendorInfoSize 0
+    out
+}
+
+/// A crypto-agile TCG event log with SHA-256 digests
+#[derive(Clone)]
+pub struct TcgEventLog {
+    buf: [u8; EVENT_LOG_SIZE],
+    len: usize,
+    /// Events after the header
+    events: usize,
+}
+
+impl TcgEventLog {
+    /// A log holding only its header
+    pub const fn new() -> Self {
+        let mut buf = [0u8; EVENT_LOG_SIZE];
+        let mut i = 0;
+        while i < HEADER_SIZE {
+            buf[i] = HEADER[i];
+            i += 1;
+        }
+        Self { buf, len: HEADER_SIZE, events: 0 }
+    }
+
+    /// A log of every measurement in `chain`, in order
+    pub fn from_chain(chain: &BootChain) -> TpmResult<Self> {
+        let mut log = Self::new();
+        for measurement in chain.measurements().iter().flatten() {
+            log.record_measurement(measurement)?;
+        }
+        Ok(log)
+    }
+
+    /// Append an extend of `pcr` by `digest`
+    ///
+    /// Fails with [`TpmRc::BadParam`] for a PCR above 23 or data longer
+    /// than [`MAX_EVENT_DATA`], and with [`TpmRc::Failure`] once the log is
+    /// full; nothing is written either way.
+    pub fn record(&mut self, pcr: u8, event_type: TcgEventType, digest: &Sha256Digest, data: &[u8]) -> TpmResult<()> {
+        if pcr > 23 || data.len() > MAX_EVENT_DATA {
+            return Err(TpmRc::BadParam);
+        }
+        if !self.can_record(data.len()) {
+            return Err(TpmRc::Failure);
+        }
+        let size = EVENT2_FIXED_SIZE + data.len();
+        let mut at = self.len;
+        for field in [&(pcr as u32).to_le_bytes()[..], &(event_type as u32).to_le_bytes(), &1u32.to_le_bytes()] {
+            self.buf[at..at + field.len()].copy_from_slice(field);
+            at += field.len();
+        }
+        self.buf[at..at + 2].copy_from_slice(&TPM_ALG_SHA256.to_le_bytes());
+        self.buf[at + 2..at + 34].copy_from_slice(&digest.bytes);
+        self.buf[at + 34..at + 38].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        self.buf[at + 38..at + 38 + data.len()].copy_from_slice(data);
+        self.len += size;
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Would an event with `data_len` bytes of data fit?
+    pub fn can_record(&self, data_len: usize) -> bool {
+        data_len <= MAX_EVENT_DATA && EVENT2_FIXED_SIZE + data_len <= EVENT_LOG_SIZE - self.len
+    }
+
+    /// Append a boot measurement, with its component ID and size as the
+    /// event data
+    pub fn record_measurement(&mut self, measurement: &BootMeasurement) -> TpmResult<()> {
+        let mut data = [0u8; 8];
+        data[..4].copy_from_slice(&measurement.component_id.to_le_bytes());
+        data[4..].copy_from_slice(&measurement.component_size.to_le_bytes());
+        self.record(
+            measurement.stage.pcr_index(),
+            TcgEventType::for_stage(measurement.stage),
+            &measurement.digest,
+            &data,
+        )
+    }
+
+    /// The serialized log
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Serialized length in bytes, header included
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// No events recorded past the header
+    pub fn is_empty(&self) -> bool {
+        self.events == 0
+    }
+
+    /// Events recorded past the header
+    pub fn event_count(&self) -> usize {
+        self.events
+    }
+
+    /// Copy the log from `offset` into `out`, for export in chunks.
+    /// Returns the bytes copied, 0 at or past the end.
+    pub fn read_at(&self, offset: usize, out: &mut [u8]) -> usize {
+        let rest = self.as_bytes().get(offset..).unwrap_or(&[]);
+        let n = rest.len().min(out.len());
+        out[..n].copy_from_slice(&rest[..n]);
+        n
+    }
+}
+
+impl Default for TcgEventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// PARSING AND REPLAY
+// ============================================================================
+
+/// One event of a parsed log, with its SHA-256 digest
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogEvent<'a> {
+    pub pcr: u8,
+    pub event_type: u32,
+    pub digest: Sha256Digest,
+    pub data: &'a [u8],
+}
+
+/// Little-endian cursor over a log
+struct Reader<'a> {
+    log: &'a [u8],
+    at: usize,
+    /// Start of the event being read, for errors
+    event: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], EventLogError> {
+        let bytes = self.log.get(self.at..).and_then(|rest| rest.get(..n)).ok_or(EventLogError::Truncated { offset: self.event })?;
+        self.at += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, EventLogError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, EventLogError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, EventLogError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+/// Iterator over the events of a crypto-agile log, after its header
+pub struct Events<'a> {
+    reader: Reader<'a>,
+    /// Algorithms and digest sizes the header lists
+    algorithms: [(u16, u16); MAX_ALGORITHMS],
+    algorithm_count: usize,
+    failed: bool,
+}
+
+impl<'a> Events<'a> {
+    /// Read the header of `log`; it must list SHA-256
+    pub fn new(log: &'a [u8]) -> Result<Self, EventLogError> {
+        let mut reader = Reader { log, at: 0, event: 0 };
+        let pcr = reader.u32()?;
+        let event_type = reader.u32()?;
+        reader.take(20)?;
+        let size = reader.u32()? as usize;
+        let spec = reader.take(size)?;
+        if pcr != 0 || event_type != TcgEventType::NoAction as u32 || spec.len() < SPEC_ID_EVENT_SIZE || &spec[..16] != SPEC_ID_SIGNATURE {
+            return Err(EventLogError::BadHeader);
+        }
+
+        let mut spec_reader = Reader { log: spec, at: 24, event: 0 };
+        let count = spec_reader.u32().map_err(|_| EventLogError::BadHeader)? as usize;
+        if count > MAX_ALGORITHMS {
+            return Err(EventLogError::BadHeader);
+        }
+        let mut algorithms = [(0, 0); MAX_ALGORITHMS];
+        for slot in algorithms.iter_mut().take(count) {
+            let id = spec_reader.u16().map_err(|_| EventLogError::BadHeader)?;
+            let size = spec_reader.u16().map_err(|_| EventLogError::BadHeader)?;
+            *slot = (id, size);
+        }
+        if !algorithms[..count].contains(&(TPM_ALG_SHA256, 32)) {
+            return Err(EventLogError::NoSha256);
+        }
+        Ok(Self { reader, algorithms, algorithm_count: count, failed: false })
+    }
+
+    fn digest_size(&self, algorithm: u16) -> Option<usize> {
+        self.algorithms[..self.algorithm_count]
+            .iter()
+            .find(|(id, _)| *id == algorithm)
+            .map(|(_, size)| *size as usize)
+    }
+
+    fn next_event(&mut self) -> Result<LogEvent<'a>, EventLogError> {
+        let offset = self.reader.at;
+        self.reader.event = offset;
+        let pcr = self.reader.u32()?;
+        let event_type = self.reader.u32()?;
+        let count = self.reader.u32()?;
+        let mut sha256 = None;
+        for _ in 0..count {
+            let algorithm = self.reader.u16()?;
+            let size = self.digest_size(algorithm).ok_or(EventLogError::UnknownAlgorithm { offset })?;
+            let digest = self.reader.take(size)?;
+            if algorithm == TPM_ALG_SHA256 {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(digest);
+                sha256 = Some(Sha256Digest::new(bytes));
+            }
+        }
+        let size = self.reader.u32()? as usize;
+        let data = self.reader.take(size)?;
+        if pcr > 23 {
+            return Err(EventLogError::BadPcr { offset });
+        }
+        // An event without a SHA-256 digest extended nothing in that bank
+        Ok(LogEvent { pcr: pcr as u8, event_type, digest: sha256.unwrap_or(Sha256Digest::zero()), data })
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Result<LogEvent<'a>, EventLogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.reader.at >= self.reader.log.len() {
+            return None;
+        }
+        let event = self.next_event();
+        self.failed = event.is_err();
+        Some(event)
+    }
+}
+
+/// PCR values the log's SHA-256 bank ends at, extending from zero;
+/// `EV_NO_ACTION` events extend nothing
+pub fn replay_log(log: &[u8]) -> Result<[Sha256Digest; 24], EventLogError> {
+    let mut pcrs = [Sha256Digest::zero(); 24];
+    for event in Events::new(log)? {
+        let event = event?;
+        if event.event_type != TcgEventType::NoAction as u32 {
+            let pcr = &mut pcrs[event.pcr as usize];
+            *pcr = extend_pcr(pcr, &event.digest);
+        }
+    }
+    Ok(pcrs)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boot_chain::compute_sha256;
+
+    #[test]
+    fn test_replay_matches_chain() {
+        let mut chain = BootChain::new();
+        chain.measure_component(BootStage::Firmware, 0, b"start4.elf").unwrap();
+        chain.measure_component(BootStage::Kernel, 1, b"sel4.elf").unwrap();
+        chain.measure_component(BootStage::ProtectionDomains, 2, b"graphics_pd.elf").unwrap();
+        chain.measure_component(BootStage::ProtectionDomains, 3, b"tpm_pd.elf").unwrap();
+
+        let log = TcgEventLog::from_chain(&chain).unwrap();
+        assert_eq!(log.event_count(), 4);
+        let pcrs = replay_log(log.as_bytes()).unwrap();
+        for (i, pcr) in pcrs.iter().enumerate() {
+            assert_eq!(pcr, chain.pcr_value(i as u8).unwrap());
+        }
+
+        let kernel = Events::new(log.as_bytes()).unwrap().nth(1).unwrap().unwrap();
+        assert_eq!(kernel.pcr, 1);
+        assert_eq!(kernel.event_type, TcgEventType::Ipl as u32);
+        assert_eq!(kernel.digest, compute_sha256(b"sel4.elf"));
+        assert_eq!(kernel.data, &[1, 0, 0, 0, 8, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let log = TcgEventLog::new();
+        let bytes = log.as_bytes();
+        assert_eq!(bytes.len(), HEADER_SIZE);
+        assert_eq!(&bytes[4..8], &3u32.to_le_bytes());
+        assert_eq!(&bytes[28..32], &(SPEC_ID_EVENT_SIZE as u32).to_le_bytes());
+        assert_eq!(&bytes[32..48], SPEC_ID_SIGNATURE);
+        assert_eq!(&bytes[56..64], &[1, 0, 0, 0, 0x0B, 0, 32, 0]);
+        assert_eq!(replay_log(bytes).unwrap(), [Sha256Digest::zero(); 24]);
+    }
+
+    #[test]
+    fn test_chunked_read_and_errors() {
+        let mut log = TcgEventLog::new();
+        let digest = compute_sha256(b"runtime");
+        log.record(4, TcgEventType::EventTag, &digest, b"settings").unwrap();
+        assert_eq!(log.record(24, TcgEventType::EventTag, &digest, &[]), Err(TpmRc::BadParam));
+
+        // Reassembling the chunks gives back the log
+        let mut copy = [0u8; EVENT_LOG_SIZE];
+        let mut offset = 0;
+        let mut chunk = [0u8; 16];
+        loop {
+            let n = log.read_at(offset, &mut chunk);
+            if n == 0 {
+                break;
+            }
+            copy[offset..offset + n].copy_from_slice(&chunk[..n]);
+            offset += n;
+        }
+        assert_eq!(&copy[..offset], log.as_bytes());
+
+        let cut = &log.as_bytes()[..log.len() - 1];
+        assert_eq!(replay_log(cut), Err(EventLogError::Truncated { offset: HEADER_SIZE }));
+        assert_eq!(replay_log(&log.as_bytes()[..10]), Err(EventLogError::Truncated { offset: 0 }));
+
+        // Full logs refuse further events without writing a partial one
+        while log.record(4, TcgEventType::EventTag, &digest, &[0; MAX_EVENT_DATA]).is_ok() {}
+        let len = log.len();
+        assert_eq!(log.record(4, TcgEventType::EventTag, &digest, &[0; MAX_EVENT_DATA]), Err(TpmRc::Failure));
+        assert_eq!(log.len(), len);
+        assert!(replay_log(log.as_bytes()).is_ok());
+    }
+}
@@ -0,0 +1,280 @@
+//! # Loader Handoff Measurement
+//!
+//! Nothing measures the seL4 kernel or the Microkit loader that starts it:
+//! by the time a PD runs, the loader has copied the kernel and the user
+//! image out of `loader.img` and jumped to the kernel, and PCR 0 and 1 hold
+//! whatever a PD chose to put there. The TPM PD is the highest-priority PD,
+//! so its `init` runs before any other PD; it maps `loader.img` where the
+//! firmware loaded it and measures it with [`LoaderImage`] before
+//! returning.
+//!
+//! A Microkit loader image is the loader's code followed by the header the
+//! Microkit tool appends, a table of regions, and their data:
+//!
+//! ```text
+//! ┌─────────────┬────────┬──────────────┬──────────────────────────┐
+//! │ loader code │ header │ region table │ region data (kernel, UI) │
+//! └─────────────┴────────┴──────────────┴──────────────────────────┘
+//!  ─────────── PCR 0 ─────────────────── ──── PCR 1: kernel ────
+//! ```
+//!
+//! PCR 0 gets the loader: code, header and region table in one
+//! measurement. PCR 1 gets each kernel region, that is each region loaded
+//! outside the user image's physical range. Digests are taken over the
+//! bytes in the image rather than in memory after loading, so a verifier
+//! computes the same values from the `loader.img` it built.
+
+use crate::boot_chain::BootMeasurement;
+use crate::BootStage;
+
+/// Magic number opening the header of a 64-bit Microkit loader image
+pub const LOADER_MAGIC: u64 = 0x5e14_dead_14de_5ead;
+
+/// Bytes in the header: eleven 64-bit words
+pub const LOADER_HEADER_SIZE: usize = 11 * 8;
+
+/// Bytes in one region table entry: four 64-bit words
+pub const LOADER_REGION_SIZE: usize = 4 * 8;
+
+/// Regions an image may describe
+pub const MAX_LOADER_REGIONS: usize = 64;
+
+/// Component ID of the loader measurement
+pub const LOADER_COMPONENT: u32 = 0;
+
+/// Component ID of the first kernel region; the rest follow by region index
+pub const KERNEL_COMPONENT_BASE: u32 = 0x100;
+
+/// Why a loader image could not be measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandoffError {
+    /// No header with a region table that fits the image
+    NoHeader,
+    /// The image loads nothing outside the user image
+    NoKernel,
+}
+
+/// One entry of the region table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoaderRegion {
+    /// Physical address the loader copies the region to
+    pub load_addr: u64,
+    /// Bytes in the region
+    pub size: u64,
+    /// Offset of its data from the end of the region table
+    pub offset: u64,
+    /// Region type as the loader defines it
+    pub kind: u64,
+}
+
+/// A Microkit loader image, its header and region table checked against
+/// its length
+pub struct LoaderImage<'a> {
+    image: &'a [u8],
+    /// Offset of the header
+    header: usize,
+    num_regions: usize,
+    /// Physical range of the user image (monitor and PDs)
+    ui_start: u64,
+    ui_end: u64,
+}
+
+impl<'a> LoaderImage<'a> {
+    /// Find the header in `image`: the first 8-byte aligned copy of
+    /// [`LOADER_MAGIC`] followed by a table of at least one region, every
+    /// region lying inside `image`. The loader's own copy of the magic, in
+    /// its code, has no such table after it.
+    pub fn parse(image: &'a [u8]) -> Result<Self, HandoffError> {
+        let mut header = 0;
+        while header + LOADER_HEADER_SIZE <= image.len() {
+            if word(image, header) == Some(LOADER_MAGIC) {
+                if let Some(parsed) = Self::at(image, header) {
+                    return Ok(parsed);
+                }
+            }
+            header += 8;
+        }
+        Err(HandoffError::NoHeader)
+    }
+
+    /// The image with its header at `header`, if the table checks out
+    fn at(image: &'a [u8], header: usize) -> Option<Self> {
+        let num_regions = word(image, header + 10 * 8)?;
+        if num_regions == 0 || num_regions > MAX_LOADER_REGIONS as u64 {
+            return None;
+        }
+        let parsed = Self {
+            image,
+            header,
+            num_regions: num_regions as usize,
+            ui_start: word(image, header + 4 * 8)?,
+            ui_end: word(image, header + 5 * 8)?,
+        };
+        if parsed.table_end() > image.len() {
+            return None;
+        }
+        for region in parsed.regions() {
+            parsed.data_range(&region)?;
+        }
+        Some(parsed)
+    }
+
+    /// Regions in the table
+    pub fn num_regions(&self) -> usize {
+        self.num_regions
+    }
+
+    /// Region `index` of the table
+    pub fn region(&self, index: usize) -> Option<LoaderRegion> {
+        if index >= self.num_regions {
+            return None;
+        }
+        let entry = self.header + LOADER_HEADER_SIZE + index * LOADER_REGION_SIZE;
+        Some(LoaderRegion {
+            load_addr: word(self.image, entry)?,
+            size: word(self.image, entry + 8)?,
+            offset: word(self.image, entry + 16)?,
+            kind: word(self.image, entry + 24)?,
+        })
+    }
+
+    /// Every region, in table order
+    pub fn regions(&self) -> impl Iterator<Item = LoaderRegion> + '_ {
+        (0..self.num_regions).filter_map(|index| self.region(index))
+    }
+
+    /// Does `region` belong to the kernel rather than the user image?
+    pub fn is_kernel(&self, region: &LoaderRegion) -> bool {
+        region.load_addr < self.ui_start || region.load_addr >= self.ui_end
+    }
+
+    /// The loader's code, header and region table
+    pub fn loader_bytes(&self) -> &'a [u8] {
+        &self.image[..self.table_end()]
+    }
+
+    /// `region`'s data in the image
+    pub fn region_data(&self, region: &LoaderRegion) -> &'a [u8] {
+        // Every region was checked by `parse`
+        self.data_range(region).map_or(&[], |(start, end)| &self.image[start..end])
+    }
+
+    /// Measurements of the loader (PCR 0) and then of each kernel region
+    /// (PCR 1), in table order
+    pub fn measurements(&self) -> Result<impl Iterator<Item = BootMeasurement> + '_, HandoffError> {
+        if !self.regions().any(|region| self.is_kernel(&region)) {
+            return Err(HandoffError::NoKernel);
+        }
+        let loader = BootMeasurement::from_component(BootStage::Firmware, LOADER_COMPONENT, self.loader_bytes());
+        let kernel = (0..self.num_regions).filter_map(move |index| {
+            let region = self.region(index)?;
+            if !self.is_kernel(&region) {
+                return None;
+            }
+            Some(BootMeasurement::from_component(
+                BootStage::Kernel,
+                KERNEL_COMPONENT_BASE + index as u32,
+                self.region_data(&region),
+            ))
+        });
+        Ok(core::iter::once(loader).chain(kernel))
+    }
+
+    /// End of the region table, where region data offsets count from
+    fn table_end(&self) -> usize {
+        self.header + LOADER_HEADER_SIZE + self.num_regions * LOADER_REGION_SIZE
+    }
+
+    /// Byte range of `region`'s data, if it lies inside the image
+    fn data_range(&self, region: &LoaderRegion) -> Option<(usize, usize)> {
+        let start = (self.table_end() as u64).checked_add(region.offset)?;
+        let end = start.checked_add(region.size)?;
+        if end > self.image.len() as u64 {
+            return None;
+        }
+        Some((start as usize, end as usize))
+    }
+}
+
+/// Little-endian 64-bit word at `offset`
+fn word(bytes: &[u8], offset: usize) -> Option<u64> {
+    let raw = bytes.get(offset..offset.checked_add(8)?)?;
+    let mut word = [0u8; 8];
+    word.copy_from_slice(raw);
+    Some(u64::from_le_bytes(word))
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boot_chain::compute_sha256;
+
+    const UI_START: u64 = 0x4000_0000;
+
+    /// 64 bytes of "code" (holding a stray magic), a header, two regions
+    /// (the kernel, then the user image) and their data
+    fn image() -> [u8; 256] {
+        let mut image = [0xAAu8; 256];
+        image[8..16].copy_from_slice(&LOADER_MAGIC.to_le_bytes());
+        let mut put = |offset: usize, value: u64| image[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        let header = 64;
+        put(header, LOADER_MAGIC);
+        for i in 1..10 {
+            put(header + i * 8, 0);
+        }
+        put(header + 4 * 8, UI_START);
+        put(header + 5 * 8, UI_START + 0x1000);
+        put(header + 10 * 8, 2);
+        // Data starts at 64 + 88 + 64 = 216
+        let table = header + LOADER_HEADER_SIZE;
+        for (i, region) in [[0x8000_0000, 24, 0, 1], [UI_START, 16, 24, 1]].iter().enumerate() {
+            for (j, &value) in region.iter().enumerate() {
+                put(table + i * LOADER_REGION_SIZE + j * 8, value);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_measures_loader_and_kernel() {
+        let image = image();
+        let loader = LoaderImage::parse(&image).unwrap();
+        assert_eq!(loader.num_regions(), 2);
+        assert_eq!(loader.loader_bytes().len(), 216);
+
+        let kernel = loader.region(0).unwrap();
+        assert!(loader.is_kernel(&kernel));
+        assert!(!loader.is_kernel(&loader.region(1).unwrap()));
+        assert_eq!(loader.region_data(&kernel), &image[216..240]);
+
+        let mut measurements = loader.measurements().unwrap();
+        let first = measurements.next().unwrap();
+        assert_eq!(first.stage, BootStage::Firmware);
+        assert_eq!(first.digest, compute_sha256(&image[..216]));
+        let second = measurements.next().unwrap();
+        assert_eq!(second.stage, BootStage::Kernel);
+        assert_eq!(second.component_id, KERNEL_COMPONENT_BASE);
+        assert_eq!(second.digest, compute_sha256(&image[216..240]));
+        assert!(measurements.next().is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_images() {
+        // Region data running past the end
+        let image = image();
+        assert_eq!(LoaderImage::parse(&image[..250]).err(), Some(HandoffError::NoHeader));
+        // No magic at all, as in a region the kernel cleared
+        assert_eq!(LoaderImage::parse(&[0u8; 256]).err(), Some(HandoffError::NoHeader));
+
+        // Everything loads into the user image
+        let mut image = image;
+        let entry = 64 + LOADER_HEADER_SIZE;
+        image[entry..entry + 8].copy_from_slice(&UI_START.to_le_bytes());
+        let loader = LoaderImage::parse(&image).unwrap();
+        assert_eq!(loader.measurements().err(), Some(HandoffError::NoKernel));
+    }
+}
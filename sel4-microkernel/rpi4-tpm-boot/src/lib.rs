@@ -156,6 +156,11 @@ macro_rules! verus_stub_impl {
 
 pub mod slb9670;
 pub mod boot_chain;
+pub mod event_log;
+pub mod handoff;
+pub mod rollback;
+pub mod nv;
+pub mod session;
 pub mod commands;
 pub mod pcr;
 pub mod attestation;
@@ -165,10 +170,17 @@ pub mod transport;
 // Re-exports
 pub use slb9670::*;
 pub use boot_chain::*;
+pub use event_log::{replay_log, EventLogError, LogEvent, TcgEventLog, TcgEventType};
+pub use handoff::{HandoffError, LoaderImage, LoaderRegion};
+pub use rollback::{check_rollback, RollbackPolicy, RollbackStatus};
+pub use nv::{NvSettingsStore, NvSlot};
+pub use session::{ecc_salt, EccSalt, HmacSession, SessionUse};
 pub use pcr::*;
 pub use attestation::*;
 pub use transport::{MockExchange, MockTransport, Tpm, TpmTransport};
 
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+
 // ============================================================================
 // CORE TYPES
 // ============================================================================
@@ -210,6 +222,133 @@ impl core::fmt::Debug for Sha256Digest {
     }
 }
 
+/// Hash algorithm of a PCR bank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashAlg {
+    /// SHA-256: always measured, since the event log, PCR policies and
+    /// quotes are SHA-256
+    Sha256 = 0,
+    /// SHA-384
+    Sha384 = 1,
+    /// SM3-256 (GB/T 32905), on TPMs certified for China
+    Sm3_256 = 2,
+}
+
+/// Number of [`HashAlg`] variants
+pub const HASH_ALG_COUNT: usize = 3;
+
+/// Longest digest of any [`HashAlg`] (SHA-384)
+pub const MAX_DIGEST_LEN: usize = 48;
+
+impl HashAlg {
+    /// Every supported algorithm, in discriminant order
+    pub const ALL: [HashAlg; HASH_ALG_COUNT] = [HashAlg::Sha256, HashAlg::Sha384, HashAlg::Sm3_256];
+
+    /// TPM_ALG_ID of the algorithm
+    pub const fn alg_id(&self) -> u16 {
+        match self {
+            HashAlg::Sha256 => slb9670::TPM2_ALG_SHA256,
+            HashAlg::Sha384 => slb9670::TPM2_ALG_SHA384,
+            HashAlg::Sm3_256 => slb9670::TPM2_ALG_SM3_256,
+        }
+    }
+
+    /// Algorithm with TPM_ALG_ID `id`, if supported
+    pub const fn from_alg_id(id: u16) -> Option<Self> {
+        match id {
+            slb9670::TPM2_ALG_SHA256 => Some(HashAlg::Sha256),
+            slb9670::TPM2_ALG_SHA384 => Some(HashAlg::Sha384),
+            slb9670::TPM2_ALG_SM3_256 => Some(HashAlg::Sm3_256),
+            _ => None,
+        }
+    }
+
+    /// Digest size in bytes
+    pub const fn digest_len(&self) -> usize {
+        match self {
+            HashAlg::Sha256 | HashAlg::Sm3_256 => 32,
+            HashAlg::Sha384 => 48,
+        }
+    }
+}
+
+/// Digest in any PCR bank: the algorithm and its `digest_len` bytes
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PcrDigest {
+    alg: HashAlg,
+    /// Zero past `alg.digest_len()`
+    bytes: [u8; MAX_DIGEST_LEN],
+}
+
+impl PcrDigest {
+    /// Zero digest of `alg` (used for PCR initial state)
+    pub const fn zero(alg: HashAlg) -> Self {
+        Self { alg, bytes: [0u8; MAX_DIGEST_LEN] }
+    }
+
+    /// Digest of `alg` from its bytes; None unless `bytes` is exactly
+    /// `alg.digest_len()` long
+    pub fn from_bytes(alg: HashAlg, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != alg.digest_len() {
+            return None;
+        }
+        let mut digest = Self::zero(alg);
+        digest.bytes[..bytes.len()].copy_from_slice(bytes);
+        Some(digest)
+    }
+
+    /// Algorithm of the digest
+    pub const fn alg(&self) -> HashAlg {
+        self.alg
+    }
+
+    /// The digest's bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.alg.digest_len()]
+    }
+
+    /// Check if this is the zero digest
+    pub fn is_zero(&self) -> bool {
+        self.bytes == [0u8; MAX_DIGEST_LEN]
+    }
+
+    /// The digest as a [`Sha256Digest`], if it is one
+    pub fn to_sha256(&self) -> Option<Sha256Digest> {
+        match self.alg {
+            HashAlg::Sha256 => {
+                let mut bytes = [0u8; 32];
+                bytes.copy_from_slice(self.as_bytes());
+                Some(Sha256Digest::new(bytes))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Sha256Digest> for PcrDigest {
+    fn from(digest: Sha256Digest) -> Self {
+        let mut pcr_digest = Self::zero(HashAlg::Sha256);
+        pcr_digest.bytes[..32].copy_from_slice(&digest.bytes);
+        pcr_digest
+    }
+}
+
+impl core::fmt::Debug for PcrDigest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = self.as_bytes();
+        write!(f, "PcrDigest({:?}, ", self.alg)?;
+        for byte in &bytes[..4] {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, "...")?;
+        for byte in &bytes[bytes.len() - 4..] {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
 /// TPM response codes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -245,6 +384,21 @@ impl From<u32> for TpmRc {
     }
 }
 
+impl From<TpmRc> for PdError {
+    fn from(rc: TpmRc) -> Self {
+        let kind = match rc {
+            TpmRc::Success | TpmRc::Failure => ErrorKind::Failed,
+            TpmRc::BadTag | TpmRc::BadParam => ErrorKind::InvalidArgument,
+            TpmRc::BadSequence => ErrorKind::InvalidState,
+            TpmRc::AuthFail | TpmRc::Locality | TpmRc::NvLocked => ErrorKind::Denied,
+            TpmRc::Disabled | TpmRc::Retry => ErrorKind::NotReady,
+            TpmRc::Unknown => ErrorKind::InvalidResponse,
+        };
+        // The detail is the TPM_RC value itself
+        PdError::new(ErrorDomain::Tpm, kind).with_detail(rc as u16)
+    }
+}
+
 /// Boot stage identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
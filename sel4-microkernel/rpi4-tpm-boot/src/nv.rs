@@ -0,0 +1,487 @@
+//! # NV Storage
+//!
+//! An [`NvSlot`] is an ordinary TPM NV index holding data a PD keeps across
+//! boots, such as a settings blob of `rpi4-settings-protocol`
+//! ([`NvSettingsStore`]). Reads and writes move at most [`NV_BUFFER_MAX`]
+//! bytes per command.
+//!
+//! A slot may be sealed to a set of PCRs. It is then defined with the
+//! policy digest of TPM2_PolicyPCR over their values at that moment
+//! ([`policy_pcr_digest`]), and read or written only through a policy
+//! session that has just run TPM2_PolicyPCR. Once a different loader,
+//! kernel or system image has been measured, the TPM refuses both.
+//!
+//! That is also the cost of sealing: an update that changes a sealed PCR
+//! locks its own slot. The owner has to undefine it ([`NvSlot::undefine`]);
+//! the next write defines it again under the new measurements, and what it
+//! held is lost, so readers fall back to their defaults.
+//!
+//! The data itself crosses the SPI bus in the clear unless it is read and
+//! written through an [`HmacSession`] ([`NvSlot::read_encrypted`],
+//! [`NvSettingsStore::encrypted`]).
+
+use rpi4_settings_protocol::SettingsStore;
+
+use crate::commands::{
+    NV_BUFFER_MAX, NV_DATA_ATTRIBUTES, NV_SEALED_ATTRIBUTES, POLICY_NONCE_LEN, TPMA_NV_WRITTEN,
+    TPM_RS_PW,
+};
+use crate::pcr::{policy_pcr_digest, PcrSelection};
+use crate::session::HmacSession;
+use crate::transport::{Tpm, TpmTransport};
+use crate::{TpmRc, TpmResult};
+
+/// NV index of the settings slot, in the owner range after the
+/// anti-rollback counter
+pub const SETTINGS_NV_INDEX: u32 = 0x0150_0002;
+
+/// TPMA_NV bits a slot's definition fixes: TPM_NT and how it is
+/// authorized. The TPM manages the rest (written, locks) itself.
+const DEFINED_ATTRIBUTES: u32 = (0xF << 4) | NV_DATA_ATTRIBUTES | NV_SEALED_ATTRIBUTES;
+
+/// An ordinary NV index of `size` bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NvSlot {
+    pub index: u32,
+    pub size: u16,
+    /// PCRs the slot is sealed to, if any
+    pub seal: Option<PcrSelection>,
+}
+
+impl NvSlot {
+    /// A slot read and written with its own (empty) auth
+    pub const fn new(index: u32, size: u16) -> Self {
+        Self {
+            index,
+            size,
+            seal: None,
+        }
+    }
+
+    /// A slot sealed to the values `pcrs` hold when it is defined
+    pub const fn sealed(index: u32, size: u16, pcrs: PcrSelection) -> Self {
+        Self {
+            index,
+            size,
+            seal: Some(pcrs),
+        }
+    }
+
+    /// The TPMA_NV attributes the slot is defined with
+    pub fn attributes(&self) -> u32 {
+        match self.seal {
+            Some(_) => NV_SEALED_ATTRIBUTES,
+            None => NV_DATA_ATTRIBUTES,
+        }
+    }
+
+    /// Define the slot unless its index exists. Returns whether it was
+    /// defined here. Errors if the index is something else: a counter, or
+    /// an index authorized another way.
+    pub fn define<T>(&self, tpm: &mut Tpm<T>) -> TpmResult<bool>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        if let Ok(attributes) = tpm.nv_read_public(self.index) {
+            if attributes & DEFINED_ATTRIBUTES != self.attributes() {
+                return Err(TpmRc::Failure);
+            }
+            return Ok(false);
+        }
+        let policy = match self.seal {
+            Some(pcrs) => {
+                let values = tpm.pcr_read(pcrs)?;
+                // The TPM may answer with fewer PCRs than asked for
+                if values.count != pcrs.count() {
+                    return Err(TpmRc::Failure);
+                }
+                Some(policy_pcr_digest(pcrs, &values))
+            }
+            None => None,
+        };
+        tpm.nv_define_space(self.index, self.attributes(), self.size, policy.as_ref())?;
+        Ok(true)
+    }
+
+    /// Read the slot into `buf`, up to its size. Returns the bytes read:
+    /// 0 if the slot has never been written.
+    pub fn read<T>(&self, tpm: &mut Tpm<T>, buf: &mut [u8]) -> TpmResult<usize>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        self.read_with(tpm, None, buf)
+    }
+
+    /// [`read`](Self::read) with the data encrypted on the bus under
+    /// `hmac`
+    pub fn read_encrypted<T>(
+        &self,
+        tpm: &mut Tpm<T>,
+        hmac: &mut HmacSession,
+        buf: &mut [u8],
+    ) -> TpmResult<usize>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        self.read_with(tpm, Some(hmac), buf)
+    }
+
+    fn read_with<T>(
+        &self,
+        tpm: &mut Tpm<T>,
+        mut hmac: Option<&mut HmacSession>,
+        buf: &mut [u8],
+    ) -> TpmResult<usize>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        if tpm.nv_read_public(self.index)? & TPMA_NV_WRITTEN == 0 {
+            return Ok(0);
+        }
+        let len = buf.len().min(self.size as usize);
+        let mut offset = 0;
+        while offset < len {
+            let n = (len - offset).min(NV_BUFFER_MAX);
+            let session = self.authorize(tpm)?;
+            let result = match hmac.as_deref_mut() {
+                Some(hmac) => {
+                    tpm.nv_read_encrypted(hmac, self.index, session, offset as u16, n as u16)
+                }
+                None => tpm.nv_read(self.index, session, offset as u16, n as u16),
+            }
+            .map(|data| buf[offset..offset + n].copy_from_slice(data));
+            release(tpm, session, result)?;
+            offset += n;
+        }
+        Ok(len)
+    }
+
+    /// Write `data` at the start of the slot
+    pub fn write<T>(&self, tpm: &mut Tpm<T>, data: &[u8]) -> TpmResult<()>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        self.write_with(tpm, None, data)
+    }
+
+    /// [`write`](Self::write) with the data encrypted on the bus under
+    /// `hmac`
+    pub fn write_encrypted<T>(
+        &self,
+        tpm: &mut Tpm<T>,
+        hmac: &mut HmacSession,
+        data: &[u8],
+    ) -> TpmResult<()>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        self.write_with(tpm, Some(hmac), data)
+    }
+
+    fn write_with<T>(
+        &self,
+        tpm: &mut Tpm<T>,
+        mut hmac: Option<&mut HmacSession>,
+        data: &[u8],
+    ) -> TpmResult<()>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        if data.len() > self.size as usize {
+            return Err(TpmRc::BadParam);
+        }
+        for (i, chunk) in data.chunks(NV_BUFFER_MAX).enumerate() {
+            let session = self.authorize(tpm)?;
+            let offset = (i * NV_BUFFER_MAX) as u16;
+            let result = match hmac.as_deref_mut() {
+                Some(hmac) => tpm.nv_write_encrypted(hmac, self.index, session, offset, chunk),
+                None => tpm.nv_write(self.index, session, offset, chunk),
+            };
+            release(tpm, session, result)?;
+        }
+        Ok(())
+    }
+
+    /// Delete the slot and its data, with the owner's authorization
+    pub fn undefine<T>(&self, tpm: &mut Tpm<T>) -> TpmResult<()>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        tpm.nv_undefine_space(self.index)
+    }
+
+    /// The session for one read or write: the password session, or for a
+    /// sealed slot a fresh policy session that has run TPM2_PolicyPCR
+    fn authorize<T>(&self, tpm: &mut Tpm<T>) -> TpmResult<u32>
+    where
+        T: TpmTransport,
+        T::Error: Into<TpmRc>,
+    {
+        let Some(pcrs) = self.seal else {
+            return Ok(TPM_RS_PW);
+        };
+        let mut nonce = [0u8; POLICY_NONCE_LEN];
+        tpm.get_random(&mut nonce)?;
+        let session = tpm.start_policy_session(&nonce)?;
+        let result = tpm.policy_pcr(session, pcrs);
+        release(tpm, session, result)?;
+        Ok(session)
+    }
+}
+
+/// Pass `result` on, flushing `session` if it failed: a policy session only
+/// goes away by itself once a command it authorized succeeds
+fn release<T, R>(tpm: &mut Tpm<T>, session: u32, result: TpmResult<R>) -> TpmResult<R>
+where
+    T: TpmTransport,
+    T::Error: Into<TpmRc>,
+{
+    if result.is_err() && session != TPM_RS_PW {
+        let _ = tpm.flush_context(session);
+    }
+    result
+}
+
+/// A settings blob kept in an [`NvSlot`], which is defined on first use
+pub struct NvSettingsStore<'a, T> {
+    tpm: &'a mut Tpm<T>,
+    slot: NvSlot,
+    hmac: Option<&'a mut HmacSession>,
+}
+
+impl<'a, T> NvSettingsStore<'a, T> {
+    pub fn new(tpm: &'a mut Tpm<T>, slot: NvSlot) -> Self {
+        Self {
+            tpm,
+            slot,
+            hmac: None,
+        }
+    }
+
+    /// A store whose blob is encrypted on the bus under `hmac`
+    pub fn encrypted(tpm: &'a mut Tpm<T>, slot: NvSlot, hmac: &'a mut HmacSession) -> Self {
+        Self {
+            tpm,
+            slot,
+            hmac: Some(hmac),
+        }
+    }
+}
+
+impl<T> SettingsStore for NvSettingsStore<'_, T>
+where
+    T: TpmTransport,
+    T::Error: Into<TpmRc>,
+{
+    type Error = TpmRc;
+
+    fn read_blob(&mut self, buf: &mut [u8]) -> Result<usize, TpmRc> {
+        self.slot.define(self.tpm)?;
+        self.slot.read_with(self.tpm, self.hmac.as_deref_mut(), buf)
+    }
+
+    fn write_blob(&mut self, blob: &[u8]) -> Result<(), TpmRc> {
+        self.slot.define(self.tpm)?;
+        self.slot
+            .write_with(self.tpm, self.hmac.as_deref_mut(), blob)
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::commands;
+    use crate::pcr::PcrReadResult;
+    use crate::transport::{MockExchange, MockTransport};
+    use crate::Sha256Digest;
+    use std::vec;
+    use std::vec::Vec;
+
+    const INDEX: u32 = SETTINGS_NV_INDEX;
+    const SESSION: u32 = 0x0300_0000;
+
+    fn response(tag: u16, rc: u32, body: &[u8]) -> Vec<u8> {
+        let mut r = Vec::new();
+        r.extend_from_slice(&tag.to_be_bytes());
+        r.extend_from_slice(&((10 + body.len()) as u32).to_be_bytes());
+        r.extend_from_slice(&rc.to_be_bytes());
+        r.extend_from_slice(body);
+        r
+    }
+
+    /// Success with an empty parameter area and one session acknowledged
+    fn ok() -> Vec<u8> {
+        response(0x8002, 0, &[0, 0, 0, 0, 0, 0, 0, 0, 0])
+    }
+
+    /// TPM2_NV_ReadPublic response with `attributes`
+    fn nv_public(attributes: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&14u16.to_be_bytes());
+        body.extend_from_slice(&INDEX.to_be_bytes());
+        body.extend_from_slice(&0x000Bu16.to_be_bytes());
+        body.extend_from_slice(&attributes.to_be_bytes());
+        body.extend_from_slice(&[0, 0, 0, 0]);
+        body.extend_from_slice(&[0, 0]); // nvName
+        response(0x8001, 0, &body)
+    }
+
+    /// The exchanges opening a policy session bound to PCR 0
+    fn policy_session(script: &mut Vec<(Vec<u8>, Vec<u8>)>) {
+        let nonce = [0x5Au8; POLICY_NONCE_LEN];
+        let mut random = vec![0, POLICY_NONCE_LEN as u8];
+        random.extend_from_slice(&nonce);
+        let mut started = SESSION.to_be_bytes().to_vec();
+        started.extend_from_slice(&random);
+        script.push((
+            commands::build_get_random(16).to_vec(),
+            response(0x8001, 0, &random),
+        ));
+        script.push((
+            commands::build_start_policy_session(&nonce).to_vec(),
+            response(0x8001, 0, &started),
+        ));
+        script.push((
+            commands::build_policy_pcr(SESSION, PcrSelection::single(0)).to_vec(),
+            response(0x8001, 0, &[]),
+        ));
+    }
+
+    fn run<R>(
+        script: &[(Vec<u8>, Vec<u8>)],
+        f: impl FnOnce(&mut Tpm<MockTransport<'_>>) -> R,
+    ) -> R {
+        let exchanges: Vec<MockExchange<'_>> = script
+            .iter()
+            .map(|(cmd, resp)| MockExchange { cmd, resp })
+            .collect();
+        let mut tpm = Tpm::new(MockTransport::new(&exchanges));
+        let result = f(&mut tpm);
+        assert!(tpm.transport().finished());
+        result
+    }
+
+    #[test]
+    fn test_sealed_slot_defines_writes_and_reads_in_chunks() {
+        let slot = NvSlot::sealed(INDEX, 512, PcrSelection::single(0));
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let mut script = Vec::new();
+
+        // Defined under the policy of PCR 0 as it reads now
+        script.push((
+            commands::build_nv_read_public(INDEX).to_vec(),
+            response(0x8001, 0x18B, &[]),
+        ));
+        let mut pcr0 = vec![
+            0, 0, 0, 1, 0, 0, 0, 1, 0x00, 0x0B, 3, 1, 0, 0, 0, 0, 0, 1, 0, 32,
+        ];
+        pcr0.extend_from_slice(&[0x11; 32]);
+        script.push((
+            commands::build_pcr_read(PcrSelection::single(0)).to_vec(),
+            response(0x8001, 0, &pcr0),
+        ));
+        let mut values = PcrReadResult::new();
+        values.add(0, Sha256Digest::new([0x11; 32]));
+        let policy = policy_pcr_digest(PcrSelection::single(0), &values);
+        let mut define = [0u8; commands::NV_DEFINE_CMD_MAX_LEN];
+        let len = commands::build_nv_define_space(
+            INDEX,
+            NV_SEALED_ATTRIBUTES,
+            512,
+            Some(&policy),
+            &mut define,
+        );
+        script.push((define[..len].to_vec(), ok()));
+
+        // Written in two chunks, each under its own session
+        for (i, chunk) in data.chunks(NV_BUFFER_MAX).enumerate() {
+            policy_session(&mut script);
+            let mut write = [0u8; commands::NV_WRITE_CMD_MAX_LEN];
+            let offset = (i * NV_BUFFER_MAX) as u16;
+            let len = commands::build_nv_write(INDEX, SESSION, offset, chunk, &mut write).unwrap();
+            script.push((write[..len].to_vec(), ok()));
+        }
+
+        // And read back the same way
+        script.push((
+            commands::build_nv_read_public(INDEX).to_vec(),
+            nv_public(NV_SEALED_ATTRIBUTES | TPMA_NV_WRITTEN),
+        ));
+        for (i, chunk) in data.chunks(NV_BUFFER_MAX).enumerate() {
+            policy_session(&mut script);
+            let read = commands::build_nv_read(
+                INDEX,
+                SESSION,
+                chunk.len() as u16,
+                (i * NV_BUFFER_MAX) as u16,
+            );
+            let mut body = ((2 + chunk.len()) as u32).to_be_bytes().to_vec();
+            body.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            body.extend_from_slice(chunk);
+            body.extend_from_slice(&[0, 0, 0, 0, 0]);
+            script.push((read.to_vec(), response(0x8002, 0, &body)));
+        }
+
+        let mut buf = [0u8; 300];
+        let n = run(&script, |tpm| {
+            assert_eq!(slot.define(tpm), Ok(true));
+            slot.write(tpm, &data).unwrap();
+            slot.read(tpm, &mut buf).unwrap()
+        });
+        assert_eq!(n, 300);
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    fn test_refusals() {
+        // Never written: nothing to read
+        let slot = NvSlot::new(INDEX, 64);
+        let script = [(
+            commands::build_nv_read_public(INDEX).to_vec(),
+            nv_public(NV_DATA_ATTRIBUTES),
+        )];
+        assert_eq!(run(&script, |tpm| slot.read(tpm, &mut [0u8; 64])), Ok(0));
+
+        // A sealed slot is not reused as an unsealed one, nor a counter
+        let script = [(
+            commands::build_nv_read_public(INDEX).to_vec(),
+            nv_public(NV_SEALED_ATTRIBUTES),
+        )];
+        assert_eq!(run(&script, |tpm| slot.define(tpm)), Err(TpmRc::Failure));
+        let counter = nv_public(commands::NV_COUNTER_ATTRIBUTES);
+        let script = [(commands::build_nv_read_public(INDEX).to_vec(), counter)];
+        assert_eq!(run(&script, |tpm| slot.define(tpm)), Err(TpmRc::Failure));
+
+        // PCRs that moved fail the policy, and the session is flushed
+        let sealed = NvSlot::sealed(INDEX, 64, PcrSelection::single(0));
+        let mut script = Vec::new();
+        script.push((
+            commands::build_nv_read_public(INDEX).to_vec(),
+            nv_public(NV_SEALED_ATTRIBUTES | TPMA_NV_WRITTEN),
+        ));
+        policy_session(&mut script);
+        script.push((
+            commands::build_nv_read(INDEX, SESSION, 64, 0).to_vec(),
+            response(0x8002, 0x99D, &[]), // TPM_RC_POLICY_FAIL
+        ));
+        script.push((
+            commands::build_flush_context(SESSION).to_vec(),
+            response(0x8001, 0, &[]),
+        ));
+        assert!(run(&script, |tpm| sealed.read(tpm, &mut [0u8; 64])).is_err());
+    }
+}
@@ -25,8 +25,19 @@
 //! - PCR extension is monotonic (values only increase in entropy)
 //! - PCR banks are correctly tracked per algorithm
 //! - Policy evaluation is sound
+//!
+//! ## PCR Banks
+//!
+//! A TPM keeps one bank of PCRs per hash algorithm it has allocated, and
+//! TPM2_PCR_Extend takes a digest for each bank. [`PcrBank`] shadows the
+//! SHA-256 bank, the one PCR policies and quotes use. [`PcrBanks`] shadows
+//! every bank in a [`BankSelection`] chosen at init (SHA-384 and SM3 next
+//! to SHA-256), and only extends with a [`DigestValues`] holding a digest
+//! for each of them: a bank the TPM has allocated but nobody extends can
+//! later be extended to any value.
 
-use crate::{Sha256Digest, TpmResult, TpmRc};
+use crate::boot_chain::{compute_digest, extend_digest};
+use crate::{HashAlg, PcrDigest, Sha256Digest, TpmResult, TpmRc, HASH_ALG_COUNT};
 
 #[cfg(feature = "verus")]
 use verus_builtin_macros::verus;
@@ -282,6 +293,181 @@ impl Default for PcrBank {
     }
 }
 
+// ============================================================================
+// PCR BANKS (ANY ALGORITHM)
+// ============================================================================
+
+/// Hash algorithms of the PCR banks to measure into, chosen at init
+///
+/// Must name every bank the TPM has allocated (TPM2_PCR_Allocate, a
+/// platform firmware job). SHA-256 is always selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BankSelection {
+    /// Bitmap of selected algorithms, by `HashAlg` discriminant
+    bitmap: u8,
+}
+
+impl BankSelection {
+    /// The SHA-256 bank only
+    pub const fn sha256() -> Self {
+        Self { bitmap: 1 << HashAlg::Sha256 as u8 }
+    }
+
+    /// This selection and the `alg` bank
+    pub const fn with(self, alg: HashAlg) -> Self {
+        Self { bitmap: self.bitmap | 1 << alg as u8 }
+    }
+
+    /// Check if the `alg` bank is selected
+    #[inline]
+    pub const fn contains(&self, alg: HashAlg) -> bool {
+        (self.bitmap & (1 << alg as u8)) != 0
+    }
+
+    /// Count selected banks
+    pub fn count(&self) -> usize {
+        self.bitmap.count_ones() as usize
+    }
+
+    /// Iterate over selected algorithms
+    pub fn iter(&self) -> impl Iterator<Item = HashAlg> {
+        let selection = *self;
+        HashAlg::ALL.into_iter().filter(move |alg| selection.contains(*alg))
+    }
+}
+
+/// One measurement's digest in each of several banks: the
+/// TPML_DIGEST_VALUES of TPM2_PCR_Extend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DigestValues {
+    /// Digests, at most one per algorithm
+    digests: [PcrDigest; HASH_ALG_COUNT],
+    /// Number of digests
+    count: usize,
+}
+
+impl DigestValues {
+    /// No digests
+    pub const fn new() -> Self {
+        Self {
+            digests: [PcrDigest::zero(HashAlg::Sha256); HASH_ALG_COUNT],
+            count: 0,
+        }
+    }
+
+    /// Hash `data` for every bank in `banks`
+    pub fn measure(banks: BankSelection, data: &[u8]) -> Self {
+        let mut values = Self::new();
+        for alg in banks.iter() {
+            values.digests[values.count] = compute_digest(alg, data);
+            values.count += 1;
+        }
+        values
+    }
+
+    /// Add a digest; BadParam if one of its algorithm is already here
+    pub fn push(&mut self, digest: PcrDigest) -> TpmResult<()> {
+        if self.get(digest.alg()).is_some() {
+            return Err(TpmRc::BadParam);
+        }
+        // One digest per algorithm, so there is room
+        self.digests[self.count] = digest;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// The digest for the `alg` bank
+    pub fn get(&self, alg: HashAlg) -> Option<&PcrDigest> {
+        self.digests().iter().find(|digest| digest.alg() == alg)
+    }
+
+    /// Get digests as slice
+    pub fn digests(&self) -> &[PcrDigest] {
+        &self.digests[..self.count]
+    }
+
+    /// Check if there is a digest for exactly the banks in `banks`
+    pub fn covers(&self, banks: BankSelection) -> bool {
+        self.count == banks.count() && self.digests().iter().all(|digest| banks.contains(digest.alg()))
+    }
+}
+
+impl From<Sha256Digest> for DigestValues {
+    fn from(digest: Sha256Digest) -> Self {
+        let mut values = Self::new();
+        values.digests[0] = digest.into();
+        values.count = 1;
+        values
+    }
+}
+
+impl Default for DigestValues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// PCR values of every selected bank, for all 24 PCRs
+#[derive(Clone)]
+pub struct PcrBanks {
+    /// Banks tracked
+    banks: BankSelection,
+    /// PCR values, indexed by `HashAlg` discriminant; unselected banks
+    /// stay zero
+    values: [[PcrDigest; PCR_COUNT]; HASH_ALG_COUNT],
+}
+
+impl PcrBanks {
+    /// Create the `banks` PCR banks with all zeros
+    pub const fn new(banks: BankSelection) -> Self {
+        Self {
+            banks,
+            values: [
+                [PcrDigest::zero(HashAlg::Sha256); PCR_COUNT],
+                [PcrDigest::zero(HashAlg::Sha384); PCR_COUNT],
+                [PcrDigest::zero(HashAlg::Sm3_256); PCR_COUNT],
+            ],
+        }
+    }
+
+    /// Banks tracked
+    #[inline]
+    pub fn banks(&self) -> BankSelection {
+        self.banks
+    }
+
+    /// Read a PCR value in the `alg` bank
+    ///
+    /// Returns None if the bank is not selected or index is invalid.
+    pub fn read(&self, alg: HashAlg, index: u8) -> Option<&PcrDigest> {
+        let index = VerifiedPcrIndex::new(index)?;
+        if !self.banks.contains(alg) {
+            return None;
+        }
+        Some(&self.values[alg as usize][index.as_usize()])
+    }
+
+    /// Extend a PCR in every bank with that bank's digest in `values`
+    ///
+    /// # Verification Properties
+    /// - Index must be valid (0-23)
+    /// - `values` must cover exactly the selected banks; otherwise no
+    ///   bank changes
+    pub fn extend(&mut self, index: u8, values: &DigestValues) -> TpmResult<()> {
+        let index = VerifiedPcrIndex::new(index).ok_or(TpmRc::BadParam)?;
+        if !values.covers(self.banks) {
+            return Err(TpmRc::BadParam);
+        }
+
+        for digest in values.digests() {
+            let pcr = &mut self.values[digest.alg() as usize][index.as_usize()];
+            *pcr = extend_digest(pcr, digest);
+        }
+
+        Ok(())
+    }
+}
+
 // ============================================================================
 // PCR READ RESULT
 // ============================================================================
@@ -324,6 +510,50 @@ impl Default for PcrReadResult {
     }
 }
 
+/// Result of reading multiple PCRs from one bank
+#[derive(Clone)]
+pub struct BankReadResult {
+    /// Bank read
+    alg: HashAlg,
+    /// PCR values read
+    values: [(u8, PcrDigest); PCR_COUNT],
+    /// Number of values
+    count: usize,
+}
+
+impl BankReadResult {
+    /// Create empty result for the `alg` bank
+    pub const fn new(alg: HashAlg) -> Self {
+        Self {
+            alg,
+            values: [(0, PcrDigest::zero(alg)); PCR_COUNT],
+            count: 0,
+        }
+    }
+
+    /// Bank read
+    pub const fn alg(&self) -> HashAlg {
+        self.alg
+    }
+
+    /// Add a PCR value to result; BadParam if it is not from this bank
+    pub fn add(&mut self, index: u8, value: PcrDigest) -> TpmResult<()> {
+        if value.alg() != self.alg {
+            return Err(TpmRc::BadParam);
+        }
+        if self.count < PCR_COUNT {
+            self.values[self.count] = (index, value);
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    /// Get values as slice
+    pub fn values(&self) -> &[(u8, PcrDigest)] {
+        &self.values[..self.count]
+    }
+}
+
 // ============================================================================
 // PCR POLICY
 // ============================================================================
@@ -437,6 +667,26 @@ pub fn compute_pcr_composite(values: &PcrReadResult) -> Sha256Digest {
     Sha256Digest::new(bytes)
 }
 
+/// Policy digest TPM2_PolicyPCR leaves in a fresh policy session when the
+/// `selection` PCRs hold `values`: the authPolicy of an object sealed to
+/// those values
+///
+/// policyDigest = SHA-256(0^32 || TPM_CC_PolicyPCR || TPML_PCR_SELECTION || composite)
+pub fn policy_pcr_digest(selection: PcrSelection, values: &PcrReadResult) -> Sha256Digest {
+    use sha2::{Sha256, Digest};
+
+    let mut hasher = Sha256::new();
+    hasher.update([0u8; 32]);
+    hasher.update(crate::slb9670::TPM2_CC_POLICY_PCR.to_be_bytes());
+    hasher.update(crate::commands::marshal_pcr_selection(selection));
+    hasher.update(compute_pcr_composite(values).bytes);
+
+    let result = hasher.finalize();
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&result);
+    Sha256Digest::new(bytes)
+}
+
 // ============================================================================
 // VERIFIED PCR INDEX (with Verus specs when enabled)
 // ============================================================================
@@ -569,6 +819,44 @@ mod tests {
         assert_eq!(idx.as_usize(), 5);
     }
 
+    #[test]
+    fn test_pcr_banks() {
+        let banks = BankSelection::sha256().with(HashAlg::Sm3_256);
+        assert!(banks.contains(HashAlg::Sm3_256) && !banks.contains(HashAlg::Sha384));
+        assert_eq!(banks.iter().collect::<Vec<_>>(), [HashAlg::Sha256, HashAlg::Sm3_256]);
+
+        let mut shadow = PcrBanks::new(banks);
+        let values = DigestValues::measure(banks, b"test measurement");
+        shadow.extend(4, &values).unwrap();
+
+        // The SHA-256 bank matches the SHA-256-only shadow
+        let mut bank = PcrBank::new();
+        bank.extend(4, &crate::boot_chain::compute_sha256(b"test measurement")).unwrap();
+        assert_eq!(shadow.read(HashAlg::Sha256, 4).and_then(PcrDigest::to_sha256).as_ref(), bank.read(4));
+        assert!(!shadow.read(HashAlg::Sm3_256, 4).unwrap().is_zero());
+        assert!(shadow.read(HashAlg::Sha384, 4).is_none());
+        assert!(shadow.read(HashAlg::Sm3_256, 24).is_none());
+    }
+
+    #[test]
+    fn test_pcr_banks_need_every_bank() {
+        let banks = BankSelection::sha256().with(HashAlg::Sha384);
+        let mut shadow = PcrBanks::new(banks);
+        let digest = crate::boot_chain::compute_sha256(b"test");
+
+        // Missing SHA-384, or an extra bank, changes nothing
+        assert_eq!(shadow.extend(0, &DigestValues::from(digest)), Err(TpmRc::BadParam));
+        let all = BankSelection::sha256().with(HashAlg::Sha384).with(HashAlg::Sm3_256);
+        assert_eq!(shadow.extend(0, &DigestValues::measure(all, b"test")), Err(TpmRc::BadParam));
+        assert!(shadow.read(HashAlg::Sha256, 0).unwrap().is_zero());
+
+        let mut values = DigestValues::from(digest);
+        assert_eq!(values.push(digest.into()), Err(TpmRc::BadParam));
+        values.push(crate::boot_chain::compute_digest(HashAlg::Sha384, b"test")).unwrap();
+        assert_eq!(shadow.extend(24, &values), Err(TpmRc::BadParam));
+        assert!(shadow.extend(0, &values).is_ok());
+    }
+
     #[test]
     fn test_pcr_reset() {
         let mut bank = PcrBank::new();
@@ -0,0 +1,274 @@
+//! # Anti-Rollback Counter
+//!
+//! A TPM NV counter at [`ROLLBACK_NV_INDEX`] holds the lowest security
+//! version the board may boot. The TPM PD runs [`check_rollback`] from
+//! `init`, after the handoff measurements:
+//!
+//! 1. Read the counter. An index not yet defined is defined; a counter
+//!    never incremented holds no floor and reads as 0.
+//! 2. Compare it with the running image's version. A downgrade stops here
+//!    and the counter is not touched.
+//! 3. Otherwise advance the counter towards the version, one NV_Increment
+//!    at a time, reading it back after each, at most
+//!    [`ROLLBACK_MAX_STEPS`] times per boot.
+//!
+//! The comparison and the ordering of the updates are verified in
+//! [`RollbackGuard`]: the counter only moves after an accepted comparison,
+//! one step at a time, and never past the running version. The outcome is
+//! extended into PCR 7, so a verifier sees a flagged downgrade in a quote.
+//!
+//! The TPM starts a counter's first increment at the highest value any
+//! counter on it has held rather than at 1. On a TPM whose NV has held other
+//! counters the first check therefore fails, and the counter keeps that
+//! value as its floor.
+
+use verified_microkernel::{RollbackGuard, ROLLBACK_MAX_STEPS};
+
+use crate::boot_chain::BootMeasurement;
+use crate::commands::{NV_COUNTER_ATTRIBUTES, TPMA_NV_WRITTEN};
+use crate::transport::{Tpm, TpmTransport};
+use crate::{BootStage, TpmRc, TpmResult};
+
+/// NV index of the counter, in the owner range
+pub const ROLLBACK_NV_INDEX: u32 = 0x0150_0001;
+
+/// Component ID of the rollback check's PCR 7 measurement
+pub const ROLLBACK_COMPONENT: u32 = 0x200;
+
+/// TPM_NT_COUNTER in the TPMA_NV_TPM_NT field (bits 7:4)
+const NT_COUNTER: u32 = 1;
+
+/// What to do with an image older than the counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollbackPolicy {
+    /// Refuse to serve TPM requests for the rest of the boot
+    Enforce,
+    /// Keep serving, and report the downgrade for a display to show
+    Flag,
+}
+
+/// Outcome of [`check_rollback`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollbackStatus {
+    /// The image may boot; `counter` is the floor after this boot's
+    /// increments
+    Accepted { version: u64, counter: u64 },
+    /// The image is older than `counter`, which was left as it was
+    Downgrade { version: u64, counter: u64 },
+}
+
+impl RollbackStatus {
+    pub fn is_downgrade(&self) -> bool {
+        matches!(self, RollbackStatus::Downgrade { .. })
+    }
+
+    /// The running image's version
+    pub fn version(&self) -> u64 {
+        match *self {
+            RollbackStatus::Accepted { version, .. }
+            | RollbackStatus::Downgrade { version, .. } => version,
+        }
+    }
+
+    /// The counter as the check left it
+    pub fn counter(&self) -> u64 {
+        match *self {
+            RollbackStatus::Accepted { counter, .. }
+            | RollbackStatus::Downgrade { counter, .. } => counter,
+        }
+    }
+
+    /// The outcome as a PCR 7 measurement: version and counter (big-endian)
+    /// and 1 for a downgrade
+    pub fn measurement(&self) -> BootMeasurement {
+        let mut data = [0u8; 17];
+        data[0..8].copy_from_slice(&self.version().to_be_bytes());
+        data[8..16].copy_from_slice(&self.counter().to_be_bytes());
+        data[16] = self.is_downgrade() as u8;
+        BootMeasurement::from_component(BootStage::SecureBootPolicy, ROLLBACK_COMPONENT, &data)
+    }
+}
+
+/// Check the running image's `version` against the counter at `nv_index`,
+/// then advance the counter if the image is accepted. Errors if the index
+/// is not a counter, or if the counter read back after an increment did
+/// not move by exactly one.
+pub fn check_rollback<T>(tpm: &mut Tpm<T>, nv_index: u32, version: u64) -> TpmResult<RollbackStatus>
+where
+    T: TpmTransport,
+    T::Error: Into<TpmRc>,
+{
+    let attributes = match tpm.nv_read_public(nv_index) {
+        Ok(attributes) => attributes,
+        Err(_) => {
+            tpm.nv_define_counter(nv_index)?;
+            NV_COUNTER_ATTRIBUTES
+        }
+    };
+    // Anything else at the index could be rewritten at will
+    if (attributes >> 4) & 0xF != NT_COUNTER {
+        return Err(TpmRc::Failure);
+    }
+    let counter = if attributes & TPMA_NV_WRITTEN != 0 {
+        tpm.nv_read_counter(nv_index)?
+    } else {
+        0
+    };
+
+    let Some(mut guard) = RollbackGuard::check(counter, version) else {
+        return Ok(RollbackStatus::Downgrade { version, counter });
+    };
+    while guard.needs_increment() {
+        tpm.nv_increment(nv_index)?;
+        let now = tpm.nv_read_counter(nv_index)?;
+        if !guard.record_increment(now) {
+            return Err(TpmRc::Failure);
+        }
+    }
+    Ok(RollbackStatus::Accepted {
+        version,
+        counter: guard.counter(),
+    })
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::commands;
+    use crate::transport::{MockExchange, MockTransport};
+    use std::vec::Vec;
+
+    const INDEX: u32 = ROLLBACK_NV_INDEX;
+
+    fn response(tag: u16, rc: u32, body: &[u8]) -> Vec<u8> {
+        let mut r = Vec::new();
+        r.extend_from_slice(&tag.to_be_bytes());
+        r.extend_from_slice(&((10 + body.len()) as u32).to_be_bytes());
+        r.extend_from_slice(&rc.to_be_bytes());
+        r.extend_from_slice(body);
+        r
+    }
+
+    /// TPM2_NV_ReadPublic response with `attributes`
+    fn nv_public(attributes: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&14u16.to_be_bytes());
+        body.extend_from_slice(&INDEX.to_be_bytes());
+        body.extend_from_slice(&0x000Bu16.to_be_bytes());
+        body.extend_from_slice(&attributes.to_be_bytes());
+        body.extend_from_slice(&[0, 0, 0, 8]);
+        body.extend_from_slice(&[0, 0]); // nvName
+        response(0x8001, 0, &body)
+    }
+
+    /// TPM2_NV_Read response holding `counter`
+    fn nv_value(counter: u64) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&10u32.to_be_bytes());
+        body.extend_from_slice(&8u16.to_be_bytes());
+        body.extend_from_slice(&counter.to_be_bytes());
+        body.extend_from_slice(&[0, 0, 0, 0, 0]);
+        response(0x8002, 0, &body)
+    }
+
+    #[test]
+    fn test_defines_and_advances_a_new_counter() {
+        let read_public = commands::build_nv_read_public(INDEX);
+        let no_index = response(0x8001, 0x18B, &[]); // TPM_RC_HANDLE
+        let define = commands::build_nv_define_counter(INDEX);
+        let ok = response(0x8002, 0, &[0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let increment = commands::build_nv_increment(INDEX);
+        let read = commands::build_nv_read_counter(INDEX);
+        let (one, two) = (nv_value(1), nv_value(2));
+
+        let script = [
+            MockExchange {
+                cmd: &read_public,
+                resp: &no_index,
+            },
+            MockExchange {
+                cmd: &define,
+                resp: &ok,
+            },
+            MockExchange {
+                cmd: &increment,
+                resp: &ok,
+            },
+            MockExchange {
+                cmd: &read,
+                resp: &one,
+            },
+            MockExchange {
+                cmd: &increment,
+                resp: &ok,
+            },
+            MockExchange {
+                cmd: &read,
+                resp: &two,
+            },
+        ];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        let status = check_rollback(&mut tpm, INDEX, 2).unwrap();
+        assert_eq!(
+            status,
+            RollbackStatus::Accepted {
+                version: 2,
+                counter: 2
+            }
+        );
+        assert!(tpm.transport().finished());
+    }
+
+    #[test]
+    fn test_downgrade_leaves_the_counter_alone() {
+        let read_public = commands::build_nv_read_public(INDEX);
+        let written = nv_public(NV_COUNTER_ATTRIBUTES | TPMA_NV_WRITTEN);
+        let read = commands::build_nv_read_counter(INDEX);
+        let five = nv_value(5);
+
+        let script = [
+            MockExchange {
+                cmd: &read_public,
+                resp: &written,
+            },
+            MockExchange {
+                cmd: &read,
+                resp: &five,
+            },
+        ];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        let status = check_rollback(&mut tpm, INDEX, 3).unwrap();
+        assert_eq!(
+            status,
+            RollbackStatus::Downgrade {
+                version: 3,
+                counter: 5
+            }
+        );
+        assert!(tpm.transport().finished());
+        assert_ne!(
+            status.measurement().digest,
+            RollbackStatus::Accepted {
+                version: 3,
+                counter: 5
+            }
+            .measurement()
+            .digest
+        );
+
+        // An ordinary index is not trusted as a counter
+        let ordinary = nv_public(TPMA_NV_WRITTEN);
+        let script = [MockExchange {
+            cmd: &read_public,
+            resp: &ordinary,
+        }];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        assert_eq!(check_rollback(&mut tpm, INDEX, 3), Err(TpmRc::Failure));
+    }
+}
@@ -0,0 +1,628 @@
+//! # HMAC Sessions and Parameter Encryption
+//!
+//! The command builders in [`crate::commands`] authorize with the password
+//! session, so authorization values and data travel over the SPI lines in
+//! the clear. An [`HmacSession`] reframes such a command before it is sent:
+//!
+//! - the password session is replaced by an HMAC over the command, keyed
+//!   with the session key and the entity's authValue, so the authValue is
+//!   never sent ([`SessionUse::auth_value`]). A command already authorized
+//!   by a policy session keeps it, and the HMAC session rides along second
+//!   for encryption only;
+//! - with [`SessionUse::decrypt`], the first command parameter (a TPM2B)
+//!   is encrypted with AES-128-CFB, and with [`SessionUse::encrypt`] the
+//!   TPM encrypts the first response parameter;
+//! - the response HMAC is checked before the response is decrypted, so a
+//!   response altered on the bus is refused with `TpmRc::AuthFail`.
+//!
+//! Sessions are unbound and salted. The salt is agreed by ECDH between an
+//! ephemeral P-256 key and a loaded ECC decryption key of the TPM, such as
+//! the storage root key ([`ecc_salt`]); it is the only secret the session
+//! key derives from, since nonces cross the bus in the clear. The ephemeral
+//! key therefore has to come from an entropy source off the bus: a value
+//! fetched with TPM2_GetRandom could be read by the same sniffer.
+//!
+//! Key derivations follow TPM 2.0 Part 1, section 11.4.10 (KDFa, KDFe),
+//! section 19.6 (session key, HMACs) and section 21.3 (CFB keys).
+
+use aes::Aes128;
+use cfb_mode::cipher::{AsyncStreamCipher, KeyIvInit};
+use cfb_mode::{Decryptor, Encryptor};
+use hmac::{Hmac, Mac};
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::elliptic_curve::{Field, PrimeField};
+use p256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256};
+
+use crate::commands::{
+    check_response, tpm2b, ECC_POINT_LEN, HMAC_NONCE_LEN, RESPONSE_HEADER_LEN, TPM_RS_PW,
+};
+use crate::slb9670::{TPM2_ALG_NULL, TPM2_ST_SESSIONS};
+use crate::{TpmRc, TpmResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// TPMA_SESSION_CONTINUESESSION: keep the session after the command
+pub const TPMA_SESSION_CONTINUE: u8 = 0x01;
+
+/// TPMA_SESSION_DECRYPT: the first command parameter is encrypted
+pub const TPMA_SESSION_DECRYPT: u8 = 0x20;
+
+/// TPMA_SESSION_ENCRYPT: the TPM encrypts the first response parameter
+pub const TPMA_SESSION_ENCRYPT: u8 = 0x40;
+
+/// Bytes of one HMAC session's authorization area: sessionHandle(4)
+/// + TPM2B nonceCaller(2+32) + sessionAttributes(1) + TPM2B hmac(2+32)
+pub const HMAC_AUTH_LEN: usize = 73;
+
+/// Bytes of the password or policy session area the builders write
+const PLAIN_AUTH_LEN: usize = 9;
+
+/// Largest command [`HmacSession::protect`] frames
+pub const SESSION_CMD_MAX_LEN: usize = 1024;
+
+/// TPM_ALG_ECC, the type of a key that can salt a session
+const TPM2_ALG_ECC: u16 = 0x0023;
+
+/// TPM_ECC_NIST_P256
+const TPM2_ECC_NIST_P256: u16 = 0x0003;
+
+// ============================================================================
+// KEY DERIVATION
+// ============================================================================
+
+/// KDFa with HMAC-SHA-256: fill `out` from `key`, a label (the trailing
+/// zero octet is added here) and two contexts
+pub fn kdfa(key: &[u8], label: &[u8], context_u: &[u8], context_v: &[u8], out: &mut [u8]) {
+    let bits = (out.len() * 8) as u32;
+    for (i, block) in out.chunks_mut(32).enumerate() {
+        let mut mac = hmac_sha256(key);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        mac.update(label);
+        mac.update(&[0]);
+        mac.update(context_u);
+        mac.update(context_v);
+        mac.update(&bits.to_be_bytes());
+        block.copy_from_slice(&mac.finalize().into_bytes()[..block.len()]);
+    }
+}
+
+/// KDFe with SHA-256, for one digest's worth of output: derive a salt
+/// from the ECDH shared secret `z`
+pub fn kdfe(z: &[u8], label: &[u8], party_u: &[u8], party_v: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(1u32.to_be_bytes());
+    hasher.update(z);
+    hasher.update(label);
+    hasher.update([0]);
+    hasher.update(party_u);
+    hasher.update(party_v);
+    hasher.finalize().into()
+}
+
+fn hmac_sha256(key: &[u8]) -> HmacSha256 {
+    // HMAC takes keys of any length
+    HmacSha256::new_from_slice(key).unwrap_or_else(|_| unreachable!())
+}
+
+// ============================================================================
+// SALT
+// ============================================================================
+
+/// A salt agreed with a TPM key: the ephemeral public point to send as
+/// TPM2_StartAuthSession's encryptedSalt, and the salt itself
+#[derive(Clone, Copy)]
+pub struct EccSalt {
+    pub encrypted_salt: [u8; ECC_POINT_LEN],
+    pub salt: [u8; 32],
+}
+
+/// Agree a salt with the ECC P-256 key whose TPMT_PUBLIC is `public`,
+/// using `ephemeral` as the private scalar. Errors if the key is not a
+/// P-256 key, its point is not on the curve, or `ephemeral` is not a
+/// valid nonzero scalar.
+pub fn ecc_salt(public: &[u8], ephemeral: &[u8; 32]) -> TpmResult<EccSalt> {
+    let (x, y) = ecc_public_point(public)?;
+    let point =
+        EncodedPoint::from_affine_coordinates(&FieldBytes::from(x), &FieldBytes::from(y), false);
+    let tpm_point: Option<AffinePoint> = AffinePoint::from_encoded_point(&point).into();
+    let tpm_point = tpm_point.ok_or(TpmRc::BadParam)?;
+    let scalar: Option<Scalar> = Scalar::from_repr(FieldBytes::from(*ephemeral)).into();
+    let scalar = scalar
+        .filter(|s| !bool::from(s.is_zero()))
+        .ok_or(TpmRc::BadParam)?;
+
+    let ours = (ProjectivePoint::GENERATOR * scalar)
+        .to_affine()
+        .to_encoded_point(false);
+    let shared = (ProjectivePoint::from(tpm_point) * scalar)
+        .to_affine()
+        .to_encoded_point(false);
+    let (Some(our_x), Some(our_y), Some(z)) = (ours.x(), ours.y(), shared.x()) else {
+        return Err(TpmRc::Failure);
+    };
+
+    let mut encrypted_salt = [0u8; ECC_POINT_LEN];
+    encrypted_salt[0..2].copy_from_slice(&32u16.to_be_bytes());
+    encrypted_salt[2..34].copy_from_slice(our_x);
+    encrypted_salt[34..36].copy_from_slice(&32u16.to_be_bytes());
+    encrypted_salt[36..68].copy_from_slice(our_y);
+    Ok(EccSalt {
+        encrypted_salt,
+        salt: kdfe(z, b"SECRET", our_x, &x),
+    })
+}
+
+/// The unique point of an ECC P-256 key's TPMT_PUBLIC
+fn ecc_public_point(public: &[u8]) -> TpmResult<([u8; 32], [u8; 32])> {
+    let u16_at = |off: usize| -> TpmResult<u16> {
+        let bytes = public.get(off..off + 2).ok_or(TpmRc::Failure)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    if u16_at(0)? != TPM2_ALG_ECC {
+        return Err(TpmRc::BadParam);
+    }
+    // type(2) + nameAlg(2) + objectAttributes(4), then authPolicy
+    let (_, mut off) = tpm2b(public, 8)?;
+    // symmetric: an algorithm, with key bits and mode unless null
+    off += if u16_at(off)? == TPM2_ALG_NULL { 2 } else { 6 };
+    // scheme: an algorithm, with its hash unless null
+    off += if u16_at(off)? == TPM2_ALG_NULL { 2 } else { 4 };
+    if u16_at(off)? != TPM2_ECC_NIST_P256 {
+        return Err(TpmRc::BadParam);
+    }
+    off += 2;
+    // kdf, like the scheme
+    off += if u16_at(off)? == TPM2_ALG_NULL { 2 } else { 4 };
+    let (x, off) = tpm2b(public, off)?;
+    let (y, _) = tpm2b(public, off)?;
+    let x: [u8; 32] = x.try_into().map_err(|_| TpmRc::Failure)?;
+    let y: [u8; 32] = y.try_into().map_err(|_| TpmRc::Failure)?;
+    Ok((x, y))
+}
+
+// ============================================================================
+// HMAC SESSION
+// ============================================================================
+
+/// How one command uses an [`HmacSession`]
+#[derive(Clone, Copy, Debug)]
+pub struct SessionUse<'a> {
+    /// Names of the command's handles, in order
+    pub names: &'a [&'a [u8]],
+    /// authValue of the entity the session authorizes (empty everywhere in
+    /// this crate); ignored when a policy session authorizes
+    pub auth_value: &'a [u8],
+    /// Encrypt the first command parameter, a TPM2B
+    pub decrypt: bool,
+    /// Have the TPM encrypt the first response parameter, a TPM2B
+    pub encrypt: bool,
+}
+
+/// The command [`HmacSession::protect`] framed last
+#[derive(Clone, Copy)]
+struct Pending {
+    command_code: u32,
+    attributes: u8,
+    auth_value: [u8; 32],
+    auth_len: usize,
+    /// Place of this session in the authorization area
+    position: usize,
+}
+
+/// A salted, unbound HMAC session with AES-128-CFB parameter encryption
+pub struct HmacSession {
+    handle: u32,
+    session_key: [u8; 32],
+    nonce_caller: [u8; HMAC_NONCE_LEN],
+    nonce_tpm: [u8; HMAC_NONCE_LEN],
+    pending: Option<Pending>,
+}
+
+impl HmacSession {
+    /// The session TPM2_StartAuthSession started as `handle`, from the
+    /// salt and both nonces
+    pub fn new(
+        handle: u32,
+        salt: &[u8; 32],
+        nonce_caller: &[u8; HMAC_NONCE_LEN],
+        nonce_tpm: &[u8],
+    ) -> TpmResult<Self> {
+        let nonce_tpm: [u8; HMAC_NONCE_LEN] = nonce_tpm.try_into().map_err(|_| TpmRc::Failure)?;
+        // Unbound, so the key derives from the salt alone
+        let mut session_key = [0u8; 32];
+        kdfa(salt, b"ATH", &nonce_tpm, nonce_caller, &mut session_key);
+        Ok(Self {
+            handle,
+            session_key,
+            nonce_caller: *nonce_caller,
+            nonce_tpm,
+            pending: None,
+        })
+    }
+
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    /// Reframe `cmd`, built for the password session (or a policy
+    /// session), for this session into `out`. Returns the length written.
+    pub fn protect(
+        &mut self,
+        cmd: &[u8],
+        usage: &SessionUse<'_>,
+        out: &mut [u8],
+    ) -> TpmResult<usize> {
+        let auth_at = RESPONSE_HEADER_LEN + 4 * usage.names.len();
+        let params_at = auth_at + 4 + PLAIN_AUTH_LEN;
+        if cmd.len() < params_at
+            || u16::from_be_bytes([cmd[0], cmd[1]]) != TPM2_ST_SESSIONS
+            || cmd[auth_at..auth_at + 4] != (PLAIN_AUTH_LEN as u32).to_be_bytes()
+            || usage.auth_value.len() > 32
+        {
+            return Err(TpmRc::BadParam);
+        }
+        let first_session = u32::from_be_bytes([
+            cmd[auth_at + 4],
+            cmd[auth_at + 5],
+            cmd[auth_at + 6],
+            cmd[auth_at + 7],
+        ]);
+        // A policy session stays first; the password session is replaced
+        let kept = if first_session == TPM_RS_PW {
+            &[][..]
+        } else {
+            &cmd[auth_at + 4..params_at]
+        };
+        let params = &cmd[params_at..];
+        let auth_len = kept.len() + HMAC_AUTH_LEN;
+        let len = auth_at + 4 + auth_len + params.len();
+        if out.len() < len {
+            return Err(TpmRc::BadParam);
+        }
+
+        let mut pending = Pending {
+            command_code: u32::from_be_bytes([cmd[6], cmd[7], cmd[8], cmd[9]]),
+            attributes: TPMA_SESSION_CONTINUE,
+            auth_value: [0u8; 32],
+            auth_len: 0,
+            position: if kept.is_empty() { 0 } else { 1 },
+        };
+        if usage.decrypt {
+            pending.attributes |= TPMA_SESSION_DECRYPT;
+        }
+        if usage.encrypt {
+            pending.attributes |= TPMA_SESSION_ENCRYPT;
+        }
+        if kept.is_empty() {
+            pending.auth_value[..usage.auth_value.len()].copy_from_slice(usage.auth_value);
+            pending.auth_len = usage.auth_value.len();
+        }
+        self.roll_nonce();
+
+        out[..auth_at].copy_from_slice(&cmd[..auth_at]);
+        out[2..6].copy_from_slice(&(len as u32).to_be_bytes());
+        out[auth_at..auth_at + 4].copy_from_slice(&(auth_len as u32).to_be_bytes());
+        let session_at = auth_at + 4 + kept.len();
+        out[auth_at + 4..session_at].copy_from_slice(kept);
+        let params_at = session_at + HMAC_AUTH_LEN;
+        out[params_at..len].copy_from_slice(params);
+        if usage.decrypt {
+            let key = self.cfb_key(&pending, &self.nonce_caller, &self.nonce_tpm);
+            let data = first_parameter(&mut out[params_at..len])?;
+            Encryptor::<Aes128>::new(key[..16].into(), key[16..].into()).encrypt(data);
+        }
+
+        // cpHash covers the parameters as sent
+        let mut cp_hash = Sha256::new();
+        cp_hash.update(pending.command_code.to_be_bytes());
+        for name in usage.names {
+            cp_hash.update(name);
+        }
+        cp_hash.update(&out[params_at..len]);
+        let mut mac = self.hmac(&pending);
+        mac.update(&cp_hash.finalize());
+        mac.update(&self.nonce_caller);
+        mac.update(&self.nonce_tpm);
+        mac.update(&[pending.attributes]);
+
+        out[session_at..session_at + 4].copy_from_slice(&self.handle.to_be_bytes());
+        out[session_at + 4..session_at + 6].copy_from_slice(&(HMAC_NONCE_LEN as u16).to_be_bytes());
+        out[session_at + 6..session_at + 38].copy_from_slice(&self.nonce_caller);
+        out[session_at + 38] = pending.attributes;
+        out[session_at + 39..session_at + 41].copy_from_slice(&32u16.to_be_bytes());
+        out[session_at + 41..session_at + 73].copy_from_slice(&mac.finalize().into_bytes());
+
+        self.pending = Some(pending);
+        Ok(len)
+    }
+
+    /// Check the response to the command [`protect`](Self::protect)
+    /// framed last, which carries `response_handles` handles, and decrypt
+    /// its first parameter in place if it was encrypted. Errors with
+    /// `TpmRc::AuthFail` if the response HMAC does not match.
+    pub fn unprotect(&mut self, resp: &mut [u8], response_handles: usize) -> TpmResult<()> {
+        let pending = self.pending.take().ok_or(TpmRc::BadSequence)?;
+        check_response(resp)?;
+        if u16::from_be_bytes([resp[0], resp[1]]) != TPM2_ST_SESSIONS {
+            return Err(TpmRc::Failure);
+        }
+        let size_at = RESPONSE_HEADER_LEN + 4 * response_handles;
+        let size = resp.get(size_at..size_at + 4).ok_or(TpmRc::Failure)?;
+        let params_at = size_at + 4;
+        let params_end =
+            params_at + u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize;
+        if params_end > resp.len() {
+            return Err(TpmRc::Failure);
+        }
+
+        // One acknowledgement per command session, ours at `position`
+        let mut off = params_end;
+        for _ in 0..pending.position {
+            let (_, after_nonce) = tpm2b(resp, off)?;
+            let (_, after_hmac) = tpm2b(resp, after_nonce + 1)?;
+            off = after_hmac;
+        }
+        let (nonce, after_nonce) = tpm2b(resp, off)?;
+        let nonce_tpm: [u8; HMAC_NONCE_LEN] = nonce.try_into().map_err(|_| TpmRc::Failure)?;
+        let attributes = *resp.get(after_nonce).ok_or(TpmRc::Failure)?;
+        let (hmac, _) = tpm2b(resp, after_nonce + 1)?;
+
+        let mut rp_hash = Sha256::new();
+        rp_hash.update(0u32.to_be_bytes()); // responseCode
+        rp_hash.update(pending.command_code.to_be_bytes());
+        rp_hash.update(&resp[params_at..params_end]);
+        let mut mac = self.hmac(&pending);
+        mac.update(&rp_hash.finalize());
+        mac.update(&nonce_tpm);
+        mac.update(&self.nonce_caller);
+        mac.update(&[attributes]);
+        mac.verify_slice(hmac).map_err(|_| TpmRc::AuthFail)?;
+        self.nonce_tpm = nonce_tpm;
+
+        if pending.attributes & TPMA_SESSION_ENCRYPT != 0 {
+            let key = self.cfb_key(&pending, &self.nonce_tpm, &self.nonce_caller);
+            let data = first_parameter(&mut resp[params_at..params_end])?;
+            Decryptor::<Aes128>::new(key[..16].into(), key[16..].into()).decrypt(data);
+        }
+        Ok(())
+    }
+
+    /// The session key followed, when this session authorizes, by the
+    /// entity's authValue: the key of the HMACs and of parameter
+    /// encryption
+    fn key(&self, pending: &Pending) -> ([u8; 64], usize) {
+        let mut key = [0u8; 64];
+        key[..32].copy_from_slice(&self.session_key);
+        key[32..32 + pending.auth_len].copy_from_slice(&pending.auth_value[..pending.auth_len]);
+        (key, 32 + pending.auth_len)
+    }
+
+    fn hmac(&self, pending: &Pending) -> HmacSha256 {
+        let (key, len) = self.key(pending);
+        hmac_sha256(&key[..len])
+    }
+
+    /// AES-128 key and IV for one direction of parameter encryption
+    fn cfb_key(&self, pending: &Pending, newer: &[u8], older: &[u8]) -> [u8; 32] {
+        let (key, len) = self.key(pending);
+        let mut out = [0u8; 32];
+        kdfa(&key[..len], b"CFB", newer, older, &mut out);
+        out
+    }
+
+    /// A fresh nonceCaller for the next command. It only has to differ
+    /// from command to command; deriving it from the session key keeps it
+    /// unpredictable without another round trip for random bytes.
+    fn roll_nonce(&mut self) {
+        let mut next = [0u8; HMAC_NONCE_LEN];
+        kdfa(
+            &self.session_key,
+            b"NONCE",
+            &self.nonce_tpm,
+            &self.nonce_caller,
+            &mut next,
+        );
+        self.nonce_caller = next;
+    }
+}
+
+/// The contents of the TPM2B opening `params`
+fn first_parameter(params: &mut [u8]) -> TpmResult<&mut [u8]> {
+    let (data, end) = tpm2b(params, 0)?;
+    let start = end - data.len();
+    Ok(&mut params[start..end])
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::commands;
+    use std::vec::Vec;
+
+    const HANDLE: u32 = 0x0200_0000;
+    const INDEX: u32 = 0x0150_0002;
+
+    fn session(salt: &[u8; 32]) -> HmacSession {
+        HmacSession::new(HANDLE, salt, &[0x11; 32], &[0x22; 32]).unwrap()
+    }
+
+    /// What the TPM does with a command `protect` framed: check its HMAC
+    /// and decrypt its first parameter. Returns the parameters, or None if
+    /// the HMAC does not match.
+    fn tpm_receives(tpm: &HmacSession, cmd: &[u8], names: &[&[u8]]) -> Option<Vec<u8>> {
+        let auth_at = 10 + 4 * names.len();
+        let session_at = auth_at + 4;
+        let params_at = session_at + HMAC_AUTH_LEN;
+        let nonce: [u8; 32] = cmd[session_at + 6..session_at + 38].try_into().unwrap();
+        let attributes = cmd[session_at + 38];
+
+        let mut cp_hash = Sha256::new();
+        cp_hash.update(&cmd[6..10]);
+        for name in names {
+            cp_hash.update(name);
+        }
+        cp_hash.update(&cmd[params_at..]);
+        let mut mac = hmac_sha256(&tpm.session_key);
+        mac.update(&cp_hash.finalize());
+        mac.update(&nonce);
+        mac.update(&tpm.nonce_tpm);
+        mac.update(&[attributes]);
+        mac.verify_slice(&cmd[session_at + 41..session_at + 73])
+            .ok()?;
+
+        let mut params = cmd[params_at..].to_vec();
+        let mut key = [0u8; 32];
+        kdfa(&tpm.session_key, b"CFB", &nonce, &tpm.nonce_tpm, &mut key);
+        Decryptor::<Aes128>::new(key[..16].into(), key[16..].into())
+            .decrypt(first_parameter(&mut params).ok()?);
+        Some(params)
+    }
+
+    /// The TPM's answer to NV_Read: `data` encrypted under a new nonceTPM,
+    /// with the response HMAC
+    fn tpm_answers(
+        tpm: &HmacSession,
+        nonce_caller: &[u8; 32],
+        nonce_tpm: &[u8; 32],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut key = [0u8; 32];
+        kdfa(&tpm.session_key, b"CFB", nonce_tpm, nonce_caller, &mut key);
+        let mut params = (data.len() as u16).to_be_bytes().to_vec();
+        params.extend_from_slice(data);
+        Encryptor::<Aes128>::new(key[..16].into(), key[16..].into()).encrypt(&mut params[2..]);
+
+        let attributes = TPMA_SESSION_CONTINUE | TPMA_SESSION_ENCRYPT;
+        let mut rp_hash = Sha256::new();
+        rp_hash.update(0u32.to_be_bytes());
+        rp_hash.update(crate::slb9670::TPM2_CC_NV_READ.to_be_bytes());
+        rp_hash.update(&params);
+        let mut mac = hmac_sha256(&tpm.session_key);
+        mac.update(&rp_hash.finalize());
+        mac.update(nonce_tpm);
+        mac.update(nonce_caller);
+        mac.update(&[attributes]);
+
+        let mut body = (params.len() as u32).to_be_bytes().to_vec();
+        body.extend_from_slice(&params);
+        body.extend_from_slice(&32u16.to_be_bytes());
+        body.extend_from_slice(nonce_tpm);
+        body.push(attributes);
+        body.extend_from_slice(&32u16.to_be_bytes());
+        body.extend_from_slice(&mac.finalize().into_bytes());
+        let mut resp = 0x8002u16.to_be_bytes().to_vec();
+        resp.extend_from_slice(&((10 + body.len()) as u32).to_be_bytes());
+        resp.extend_from_slice(&0u32.to_be_bytes());
+        resp.extend_from_slice(&body);
+        resp
+    }
+
+    #[test]
+    fn test_salt_matches_the_tpm_side() {
+        // The TPM's key, and the public area it would report
+        let tpm_secret = Scalar::from_repr(FieldBytes::from([7u8; 32])).unwrap();
+        let tpm_point = (ProjectivePoint::GENERATOR * tpm_secret)
+            .to_affine()
+            .to_encoded_point(false);
+        let mut public = Vec::from(&commands::SRK_TEMPLATE[..22]);
+        for coordinate in [tpm_point.x().unwrap(), tpm_point.y().unwrap()] {
+            public.extend_from_slice(&32u16.to_be_bytes());
+            public.extend_from_slice(coordinate);
+        }
+
+        let salt = ecc_salt(&public, &[9u8; 32]).unwrap();
+        // The TPM recovers the same salt from the point alone
+        let ours = EncodedPoint::from_affine_coordinates(
+            salt.encrypted_salt[2..34].into(),
+            salt.encrypted_salt[36..68].into(),
+            false,
+        );
+        let ours = AffinePoint::from_encoded_point(&ours).unwrap();
+        let z = (ProjectivePoint::from(ours) * tpm_secret)
+            .to_affine()
+            .to_encoded_point(false);
+        let expected = kdfe(
+            z.x().unwrap(),
+            b"SECRET",
+            &salt.encrypted_salt[2..34],
+            tpm_point.x().unwrap(),
+        );
+        assert_eq!(salt.salt, expected);
+
+        assert_eq!(ecc_salt(&public, &[0u8; 32]).err(), Some(TpmRc::BadParam));
+        assert_eq!(
+            ecc_salt(&commands::AK_TEMPLATE, &[9u8; 32]).err(),
+            Some(TpmRc::Failure)
+        );
+    }
+
+    #[test]
+    fn test_protected_write_hides_the_data() {
+        let mut ours = session(&[0x33; 32]);
+        let tpm = session(&[0x33; 32]);
+        let secret = *b"brightness=42;interval=30";
+        let mut cmd = [0u8; commands::NV_WRITE_CMD_MAX_LEN];
+        let len = commands::build_nv_write(INDEX, TPM_RS_PW, 0, &secret, &mut cmd).unwrap();
+        let name = [0xAAu8; commands::NV_NAME_LEN];
+        let usage = SessionUse {
+            names: &[&name, &name],
+            auth_value: &[],
+            decrypt: true,
+            encrypt: false,
+        };
+
+        let mut framed = [0u8; SESSION_CMD_MAX_LEN];
+        let n = ours.protect(&cmd[..len], &usage, &mut framed).unwrap();
+        assert_eq!(n, len - PLAIN_AUTH_LEN + HMAC_AUTH_LEN);
+        assert!(!framed[..n].windows(secret.len()).any(|w| w == secret));
+
+        let params = tpm_receives(&tpm, &framed[..n], usage.names).unwrap();
+        assert_eq!(&params[2..2 + secret.len()], &secret);
+        // A different Name is a different command
+        assert!(tpm_receives(&tpm, &framed[..n], &[&name, &[0xBB; 34]]).is_none());
+    }
+
+    #[test]
+    fn test_protected_read_checks_and_decrypts_the_response() {
+        let mut ours = session(&[0x44; 32]);
+        let tpm = session(&[0x44; 32]);
+        let cmd = commands::build_nv_read(INDEX, TPM_RS_PW, 8, 0);
+        let name = [0xAAu8; commands::NV_NAME_LEN];
+        let usage = SessionUse {
+            names: &[&name, &name],
+            auth_value: &[],
+            decrypt: false,
+            encrypt: true,
+        };
+
+        let mut framed = [0u8; SESSION_CMD_MAX_LEN];
+        ours.protect(&cmd, &usage, &mut framed).unwrap();
+        let nonce_caller: [u8; 32] = framed[22 + 6..22 + 38].try_into().unwrap();
+        let data = *b"sealed!!";
+        let resp = tpm_answers(&tpm, &nonce_caller, &[0x55; 32], &data);
+        assert!(!resp.windows(data.len()).any(|w| w == data));
+
+        // A session in the same state refuses the response altered on the bus
+        let mut other = session(&[0x44; 32]);
+        other
+            .protect(&cmd, &usage, &mut [0u8; SESSION_CMD_MAX_LEN])
+            .unwrap();
+        let mut tampered = resp.clone();
+        tampered[16] ^= 1;
+        assert_eq!(other.unprotect(&mut tampered, 0), Err(TpmRc::AuthFail));
+
+        let mut resp = resp;
+        ours.unprotect(&mut resp, 0).unwrap();
+        assert_eq!(commands::parse_nv_read(&resp).unwrap(), &data);
+        assert_eq!(ours.nonce_tpm, [0x55; 32]);
+        // Each command answers exactly one response
+        assert_eq!(ours.unprotect(&mut resp, 0), Err(TpmRc::BadSequence));
+    }
+}
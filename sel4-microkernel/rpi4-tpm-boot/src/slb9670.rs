@@ -84,6 +84,20 @@ pub const TPM2_CC_PCR_READ: u32 = 0x0000017E;
 pub const TPM2_CC_GET_RANDOM: u32 = 0x0000017B;
 pub const TPM2_CC_QUOTE: u32 = 0x00000158;
 pub const TPM2_CC_GET_CAPABILITY: u32 = 0x0000017A;
+pub const TPM2_CC_CREATE_PRIMARY: u32 = 0x00000131;
+pub const TPM2_CC_CREATE: u32 = 0x00000153;
+pub const TPM2_CC_LOAD: u32 = 0x00000157;
+pub const TPM2_CC_EVICT_CONTROL: u32 = 0x00000120;
+pub const TPM2_CC_FLUSH_CONTEXT: u32 = 0x00000165;
+pub const TPM2_CC_READ_PUBLIC: u32 = 0x00000173;
+pub const TPM2_CC_NV_DEFINE_SPACE: u32 = 0x0000012A;
+pub const TPM2_CC_NV_INCREMENT: u32 = 0x00000134;
+pub const TPM2_CC_NV_READ: u32 = 0x0000014E;
+pub const TPM2_CC_NV_READ_PUBLIC: u32 = 0x00000169;
+pub const TPM2_CC_NV_WRITE: u32 = 0x00000137;
+pub const TPM2_CC_NV_UNDEFINE_SPACE: u32 = 0x00000122;
+pub const TPM2_CC_START_AUTH_SESSION: u32 = 0x00000176;
+pub const TPM2_CC_POLICY_PCR: u32 = 0x0000017F;
 
 // TPM 2.0 Startup Types
 pub const TPM2_SU_CLEAR: u16 = 0x0000;
@@ -91,7 +105,19 @@ pub const TPM2_SU_STATE: u16 = 0x0001;
 
 // TPM 2.0 Algorithm IDs
 pub const TPM2_ALG_SHA256: u16 = 0x000B;
+pub const TPM2_ALG_SHA384: u16 = 0x000C;
+pub const TPM2_ALG_SM3_256: u16 = 0x0012;
 pub const TPM2_ALG_NULL: u16 = 0x0010;
+pub const TPM2_ALG_AES: u16 = 0x0006;
+pub const TPM2_ALG_CFB: u16 = 0x0043;
+
+// TPM 2.0 Hierarchies
+pub const TPM2_RH_OWNER: u32 = 0x40000001;
+pub const TPM2_RH_NULL: u32 = 0x40000007;
+
+// TPM 2.0 Session Types
+pub const TPM2_SE_HMAC: u8 = 0x00;
+pub const TPM2_SE_POLICY: u8 = 0x01;
 
 // TPM 2.0 Structure Tags
 pub const TPM2_ST_NO_SESSIONS: u16 = 0x8001;
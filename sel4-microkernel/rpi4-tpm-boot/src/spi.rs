@@ -21,10 +21,27 @@
 //! The Infineon SLB 9670 uses:
 //! - SPI Mode 0 (CPOL=0, CPHA=0)
 //! - MSB first
-//! - Maximum 43 MHz clock (we use conservative 10 MHz)
+//! - Maximum 43 MHz clock, but only up to [`TPM_MAX_HZ`] is reliable over
+//!   the GPIO header (we default to a conservative 10 MHz)
 //! - CS active low
+//!
+//! ## Clocking
+//!
+//! The SPI clock is divided from the VPU core clock, which the firmware
+//! may change; pass the rate the mailbox reports (`clock::CORE`) to
+//! [`Spi::set_core_clock`]. A register that reads back inconsistently is
+//! taken as a signal-integrity problem, and [`TpmSpi::tis_read_stable`]
+//! halves the clock and retries, down to [`TPM_MIN_HZ`].
+//!
+//! ## Shared Bus
+//!
+//! SPI0 may also carry a display and touch controller. The TIS accessors
+//! take a [`BusGrant`] for [`SPI_CLIENT_TPM`] from the bus's
+//! [`SpiArbiter`](verified_microkernel::SpiArbiter), so a TPM transaction
+//! never interleaves with another device's.
 
 use crate::{TpmResult, TpmRc};
+use verified_microkernel::{BusGrant, SpiClock, SPI_CLIENT_TPM, SPI_DEFAULT_CORE_HZ};
 
 // ============================================================================
 // BCM2711 SPI REGISTERS
@@ -132,6 +149,13 @@ pub mod gpio_func {
 // SPI CONFIGURATION
 // ============================================================================
 
+/// Fastest TPM clock: the SLB 9670 is rated for 43 MHz, but header wiring
+/// is not reliable above this
+pub const TPM_MAX_HZ: u32 = 22_500_000;
+
+/// Slowest clock to fall back to after readback errors
+pub const TPM_MIN_HZ: u32 = 1_000_000;
+
 /// SPI clock speed options
 #[derive(Clone, Copy, Debug)]
 pub enum SpiSpeed {
@@ -139,20 +163,20 @@ pub enum SpiSpeed {
     Slow,
     /// 10 MHz (recommended for TPM)
     Medium,
-    /// 25 MHz (fast)
+    /// 20 MHz (fast)
     Fast,
-    /// 43 MHz (maximum for SLB9670)
+    /// [`TPM_MAX_HZ`]
     Maximum,
 }
 
 impl SpiSpeed {
-    /// Get clock divider for 500 MHz core clock
-    pub fn divider(&self) -> u32 {
+    /// Clock ceiling; the divider is negotiated against the core clock
+    pub fn max_hz(&self) -> u32 {
         match self {
-            SpiSpeed::Slow => 500,    // 1 MHz
-            SpiSpeed::Medium => 50,   // 10 MHz
-            SpiSpeed::Fast => 20,     // 25 MHz
-            SpiSpeed::Maximum => 12,  // ~42 MHz
+            SpiSpeed::Slow => TPM_MIN_HZ,
+            SpiSpeed::Medium => 10_000_000,
+            SpiSpeed::Fast => 20_000_000,
+            SpiSpeed::Maximum => TPM_MAX_HZ,
         }
     }
 }
@@ -193,6 +217,10 @@ pub struct Spi {
     chip_select: ChipSelect,
     /// Configured speed
     speed: SpiSpeed,
+    /// Core clock the SPI clock is divided from
+    core_hz: u32,
+    /// Clock for `speed`, slowed after readback errors
+    clock: SpiClock,
     /// Initialized flag
     initialized: bool,
 }
@@ -210,6 +238,8 @@ impl Spi {
             gpio_base,
             chip_select,
             speed: SpiSpeed::Medium,
+            core_hz: SPI_DEFAULT_CORE_HZ,
+            clock: SpiClock::SLOWEST,
             initialized: false,
         }
     }
@@ -269,7 +299,8 @@ impl Spi {
         core::ptr::write_volatile(cs_reg, cs_bits::CLEAR_TX | cs_bits::CLEAR_RX);
 
         // Set clock divider
-        core::ptr::write_volatile(clk_reg, self.speed.divider());
+        self.clock = SpiClock::negotiate(self.core_hz, self.speed.max_hz(), TPM_MIN_HZ);
+        core::ptr::write_volatile(clk_reg, self.clock.divider());
 
         // Configure CS register:
         // - SPI Mode 0 (CPOL=0, CPHA=0)
@@ -284,10 +315,41 @@ impl Spi {
     /// Set SPI speed
     pub fn set_speed(&mut self, speed: SpiSpeed) {
         self.speed = speed;
+        self.renegotiate();
+    }
+
+    /// Divide from `core_hz`, the core clock the mailbox reports
+    pub fn set_core_clock(&mut self, core_hz: u32) {
+        self.core_hz = core_hz;
+        self.renegotiate();
+    }
+
+    /// Current SPI clock in Hz
+    pub fn clock_hz(&self) -> u32 {
+        self.clock.hz()
+    }
+
+    /// Halve the clock after a transfer error; false once at [`TPM_MIN_HZ`]
+    pub fn slow_down(&mut self) -> bool {
+        let slowed = self.clock.slow_down();
+        if slowed {
+            self.write_clock();
+        }
+        slowed
+    }
+
+    /// Recompute the clock for the current speed and core clock, dropping
+    /// any slow-down
+    fn renegotiate(&mut self) {
+        self.clock = SpiClock::negotiate(self.core_hz, self.speed.max_hz(), TPM_MIN_HZ);
+        self.write_clock();
+    }
+
+    fn write_clock(&self) {
         if self.initialized {
             unsafe {
                 let clk_reg = (self.spi_base + spi_reg::CLK) as *mut u32;
-                core::ptr::write_volatile(clk_reg, speed.divider());
+                core::ptr::write_volatile(clk_reg, self.clock.divider());
             }
         }
     }
@@ -469,7 +531,8 @@ impl TpmSpi {
     ///
     /// # Safety
     /// SPI must be initialized.
-    pub unsafe fn tis_read(&self, address: u32, buf: &mut [u8]) -> TpmResult<()> {
+    pub unsafe fn tis_read(&self, grant: &BusGrant, address: u32, buf: &mut [u8]) -> TpmResult<()> {
+        check_grant(grant)?;
         if buf.is_empty() || buf.len() > 64 {
             return Err(TpmRc::BadParam);
         }
@@ -495,11 +558,36 @@ impl TpmSpi {
         Ok(())
     }
 
+    /// Read a TIS register until two reads agree, slowing the clock after
+    /// each disagreement
+    ///
+    /// Only for registers whose reads have no side effects: not the data
+    /// FIFO. Fails with `TpmRc::Failure` if the reads still disagree at
+    /// [`TPM_MIN_HZ`].
+    ///
+    /// # Safety
+    /// SPI must be initialized.
+    pub unsafe fn tis_read_stable(&mut self, grant: &BusGrant, address: u32, buf: &mut [u8]) -> TpmResult<()> {
+        let mut check = [0u8; 64];
+        let check = check.get_mut(..buf.len()).ok_or(TpmRc::BadParam)?;
+        loop {
+            self.tis_read(grant, address, buf)?;
+            self.tis_read(grant, address, check)?;
+            if buf == check {
+                return Ok(());
+            }
+            if !self.spi.slow_down() {
+                return Err(TpmRc::Failure);
+            }
+        }
+    }
+
     /// Write to TPM TIS register
     ///
     /// # Safety
     /// SPI must be initialized.
-    pub unsafe fn tis_write(&self, address: u32, buf: &[u8]) -> TpmResult<()> {
+    pub unsafe fn tis_write(&self, grant: &BusGrant, address: u32, buf: &[u8]) -> TpmResult<()> {
+        check_grant(grant)?;
         if buf.is_empty() || buf.len() > 64 {
             return Err(TpmRc::BadParam);
         }
@@ -521,3 +609,11 @@ impl TpmSpi {
         Ok(())
     }
 }
+
+/// The bus must have been granted to the TPM, not another device
+fn check_grant(grant: &BusGrant) -> TpmResult<()> {
+    if grant.client() != SPI_CLIENT_TPM {
+        return Err(TpmRc::BadParam);
+    }
+    Ok(())
+}
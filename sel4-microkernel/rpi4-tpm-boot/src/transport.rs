@@ -14,10 +14,11 @@
 //! `AttestationBackend` (measure/seal/counter/quote), not by
 //! stretching this trait.
 
-use crate::commands::{self, QuoteResponse};
-use crate::pcr::{PcrReadResult, PcrSelection};
-use crate::slb9670::{Slb9670Tpm, TPM2_SU_CLEAR};
-use crate::{Sha256Digest, TpmRc, TpmResult};
+use crate::commands::{self, CreatedKey, QuoteResponse};
+use crate::pcr::{BankReadResult, DigestValues, PcrReadResult, PcrSelection};
+use crate::session::{EccSalt, HmacSession, SessionUse, SESSION_CMD_MAX_LEN};
+use crate::slb9670::{Slb9670Tpm, TPM2_RH_OWNER, TPM2_SU_CLEAR};
+use crate::{HashAlg, Sha256Digest, TpmRc, TpmResult};
 
 /// A channel that carries TPM 2.0 command streams to a TPM and returns
 /// its response streams (workplan IC-3 — this signature is a fixed
@@ -112,6 +113,16 @@ where
         Ok(())
     }
 
+    /// TPM2_PCR_Extend with a digest for each bank in `values`. Every
+    /// bank the TPM has allocated needs one; those left out keep their
+    /// value.
+    pub fn pcr_extend_values(&mut self, pcr_index: u8, values: &DigestValues) -> TpmResult<()> {
+        let mut cmd = [0u8; commands::PCR_EXTEND_CMD_MAX_LEN];
+        let len = commands::build_pcr_extend_values(pcr_index, values, &mut cmd)?;
+        self.exchange_checked(&cmd[..len])?;
+        Ok(())
+    }
+
     /// TPM2_PCR_Read of the SHA-256 bank.
     pub fn pcr_read(&mut self, selection: PcrSelection) -> TpmResult<PcrReadResult> {
         let cmd = commands::build_pcr_read(selection);
@@ -119,6 +130,13 @@ where
         commands::parse_pcr_read(&self.resp[..n])
     }
 
+    /// TPM2_PCR_Read of the `alg` bank.
+    pub fn pcr_read_bank(&mut self, alg: HashAlg, selection: PcrSelection) -> TpmResult<BankReadResult> {
+        let cmd = commands::build_pcr_read_bank(alg, selection);
+        let n = self.exchange_checked(&cmd)?;
+        commands::parse_pcr_read_bank(&self.resp[..n], alg)
+    }
+
     /// TPM2_GetRandom into `buf` (at most 32 bytes, the TPM's per-call
     /// limit for common parts).
     pub fn get_random(&mut self, buf: &mut [u8]) -> TpmResult<usize> {
@@ -150,6 +168,264 @@ where
         let n = self.exchange_checked(&cmd[..len])?;
         commands::parse_quote(&self.resp[..n])
     }
+
+    /// TPM2_CreatePrimary of `template` in `hierarchy`. Returns the
+    /// transient handle of the loaded key.
+    pub fn create_primary(&mut self, hierarchy: u32, template: &[u8]) -> TpmResult<u32> {
+        let mut cmd = [0u8; commands::CREATE_CMD_MAX_LEN];
+        let len = commands::build_create_primary(hierarchy, template, &mut cmd)?;
+        let n = self.exchange_checked(&cmd[..len])?;
+        Ok(commands::parse_create_primary(&self.resp[..n])?.handle)
+    }
+
+    /// TPM2_Create of `template` under `parent`. The key is not loaded;
+    /// the borrows are valid until the next command.
+    pub fn create(&mut self, parent: u32, template: &[u8]) -> TpmResult<CreatedKey<'_>> {
+        let mut cmd = [0u8; commands::CREATE_CMD_MAX_LEN];
+        let len = commands::build_create(parent, template, &mut cmd)?;
+        let n = self.exchange_checked(&cmd[..len])?;
+        commands::parse_create(&self.resp[..n])
+    }
+
+    /// TPM2_Load of a key [`Tpm::create`] made. Returns its transient
+    /// handle.
+    pub fn load(&mut self, parent: u32, private: &[u8], public: &[u8]) -> TpmResult<u32> {
+        let mut cmd = [0u8; commands::LOAD_CMD_MAX_LEN];
+        let len = commands::build_load(parent, private, public, &mut cmd)?;
+        let n = self.exchange_checked(&cmd[..len])?;
+        commands::parse_load(&self.resp[..n])
+    }
+
+    /// TPM2_EvictControl: persist a loaded key at `persistent_handle`.
+    pub fn evict_control(&mut self, object_handle: u32, persistent_handle: u32) -> TpmResult<()> {
+        let cmd = commands::build_evict_control(object_handle, persistent_handle);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// TPM2_FlushContext of a transient object.
+    pub fn flush_context(&mut self, handle: u32) -> TpmResult<()> {
+        let cmd = commands::build_flush_context(handle);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// TPM2_ReadPublic. Returns the object's TPMT_PUBLIC, valid until the
+    /// next command.
+    pub fn read_public(&mut self, handle: u32) -> TpmResult<&[u8]> {
+        let cmd = commands::build_read_public(handle);
+        let n = self.exchange_checked(&cmd)?;
+        commands::parse_read_public(&self.resp[..n])
+    }
+
+    /// TPM2_NV_DefineSpace of an 8-byte counter at `nv_index`.
+    pub fn nv_define_counter(&mut self, nv_index: u32) -> TpmResult<()> {
+        let cmd = commands::build_nv_define_counter(nv_index);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// TPM2_NV_Increment of the counter at `nv_index`.
+    pub fn nv_increment(&mut self, nv_index: u32) -> TpmResult<()> {
+        let cmd = commands::build_nv_increment(nv_index);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// TPM2_NV_Read of the counter at `nv_index`.
+    pub fn nv_read_counter(&mut self, nv_index: u32) -> TpmResult<u64> {
+        let cmd = commands::build_nv_read_counter(nv_index);
+        let n = self.exchange_checked(&cmd)?;
+        commands::parse_nv_read_counter(&self.resp[..n])
+    }
+
+    /// TPM2_NV_ReadPublic. Returns the index's attributes.
+    pub fn nv_read_public(&mut self, nv_index: u32) -> TpmResult<u32> {
+        let cmd = commands::build_nv_read_public(nv_index);
+        let n = self.exchange_checked(&cmd)?;
+        commands::parse_nv_read_public(&self.resp[..n])
+    }
+
+    /// TPM2_NV_DefineSpace of a `size`-byte index, authorized by the
+    /// owner hierarchy, with `auth_policy` if it is read or written under
+    /// a policy.
+    pub fn nv_define_space(
+        &mut self,
+        nv_index: u32,
+        attributes: u32,
+        size: u16,
+        auth_policy: Option<&Sha256Digest>,
+    ) -> TpmResult<()> {
+        let mut cmd = [0u8; commands::NV_DEFINE_CMD_MAX_LEN];
+        let len = commands::build_nv_define_space(nv_index, attributes, size, auth_policy, &mut cmd);
+        self.exchange_checked(&cmd[..len])?;
+        Ok(())
+    }
+
+    /// TPM2_NV_UndefineSpace, authorized by the owner hierarchy.
+    pub fn nv_undefine_space(&mut self, nv_index: u32) -> TpmResult<()> {
+        let cmd = commands::build_nv_undefine_space(nv_index);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// TPM2_NV_Read of `size` bytes from `offset`, authorized through
+    /// `session`. The data borrows this `Tpm`'s response buffer.
+    pub fn nv_read(&mut self, nv_index: u32, session: u32, offset: u16, size: u16) -> TpmResult<&[u8]> {
+        let cmd = commands::build_nv_read(nv_index, session, size, offset);
+        let n = self.exchange_checked(&cmd)?;
+        let data = commands::parse_nv_read(&self.resp[..n])?;
+        if data.len() != size as usize {
+            return Err(TpmRc::Failure);
+        }
+        Ok(data)
+    }
+
+    /// TPM2_NV_Write of `data` at `offset`, authorized through `session`.
+    pub fn nv_write(&mut self, nv_index: u32, session: u32, offset: u16, data: &[u8]) -> TpmResult<()> {
+        let mut cmd = [0u8; commands::NV_WRITE_CMD_MAX_LEN];
+        let len = commands::build_nv_write(nv_index, session, offset, data, &mut cmd)?;
+        self.exchange_checked(&cmd[..len])?;
+        Ok(())
+    }
+
+    /// TPM2_StartAuthSession of a policy session. Returns its handle.
+    pub fn start_policy_session(&mut self, nonce_caller: &[u8; commands::POLICY_NONCE_LEN]) -> TpmResult<u32> {
+        let cmd = commands::build_start_policy_session(nonce_caller);
+        let n = self.exchange_checked(&cmd)?;
+        Ok(commands::parse_start_auth_session(&self.resp[..n])?.0)
+    }
+
+    /// TPM2_StartAuthSession of an HMAC session with parameter
+    /// encryption, salted through the loaded ECC key `salt_key` with
+    /// `salt` ([`crate::session::ecc_salt`] of that key's public area).
+    pub fn start_hmac_session(
+        &mut self,
+        salt_key: u32,
+        salt: &EccSalt,
+        nonce_caller: &[u8; commands::HMAC_NONCE_LEN],
+    ) -> TpmResult<HmacSession> {
+        let cmd = commands::build_start_hmac_session(salt_key, nonce_caller, &salt.encrypted_salt);
+        let n = self.exchange_checked(&cmd)?;
+        let (handle, nonce_tpm) = commands::parse_start_auth_session(&self.resp[..n])?;
+        HmacSession::new(handle, &salt.salt, nonce_caller, nonce_tpm)
+    }
+
+    /// TPM2_NV_ReadPublic. Returns the index's Name.
+    pub fn nv_name(&mut self, nv_index: u32) -> TpmResult<[u8; commands::NV_NAME_LEN]> {
+        let cmd = commands::build_nv_read_public(nv_index);
+        let n = self.exchange_checked(&cmd)?;
+        commands::parse_nv_name(&self.resp[..n])
+    }
+
+    /// TPM2_NV_Read like [`Tpm::nv_read`], with the data encrypted on the
+    /// bus under `hmac`. The HMAC session also authorizes the read when
+    /// `session` is the password session.
+    pub fn nv_read_encrypted(
+        &mut self,
+        hmac: &mut HmacSession,
+        nv_index: u32,
+        session: u32,
+        offset: u16,
+        size: u16,
+    ) -> TpmResult<&[u8]> {
+        let name = self.nv_name(nv_index)?;
+        let cmd = commands::build_nv_read(nv_index, session, size, offset);
+        let usage = SessionUse {
+            names: &[&name, &name],
+            auth_value: &[],
+            decrypt: false,
+            encrypt: true,
+        };
+        let n = self.exchange_protected(hmac, &cmd, &usage, 0)?;
+        let data = commands::parse_nv_read(&self.resp[..n])?;
+        if data.len() != size as usize {
+            return Err(TpmRc::Failure);
+        }
+        Ok(data)
+    }
+
+    /// TPM2_NV_Write like [`Tpm::nv_write`], with the data encrypted on
+    /// the bus under `hmac`.
+    pub fn nv_write_encrypted(
+        &mut self,
+        hmac: &mut HmacSession,
+        nv_index: u32,
+        session: u32,
+        offset: u16,
+        data: &[u8],
+    ) -> TpmResult<()> {
+        let name = self.nv_name(nv_index)?;
+        let mut cmd = [0u8; commands::NV_WRITE_CMD_MAX_LEN];
+        let len = commands::build_nv_write(nv_index, session, offset, data, &mut cmd)?;
+        let usage = SessionUse {
+            names: &[&name, &name],
+            auth_value: &[],
+            decrypt: true,
+            encrypt: false,
+        };
+        self.exchange_protected(hmac, &cmd[..len], &usage, 0)?;
+        Ok(())
+    }
+
+    /// Exchange `cmd` framed by `hmac`, and check and decrypt the response
+    /// in place.
+    fn exchange_protected(
+        &mut self,
+        hmac: &mut HmacSession,
+        cmd: &[u8],
+        usage: &SessionUse<'_>,
+        response_handles: usize,
+    ) -> TpmResult<usize> {
+        let mut framed = [0u8; SESSION_CMD_MAX_LEN];
+        let len = hmac.protect(cmd, usage, &mut framed)?;
+        let n = self.exchange_checked(&framed[..len])?;
+        hmac.unprotect(&mut self.resp[..n], response_handles)?;
+        Ok(n)
+    }
+
+    /// TPM2_PolicyPCR: bind `session` to the current values of the
+    /// `selection` PCRs.
+    pub fn policy_pcr(&mut self, session: u32, selection: PcrSelection) -> TpmResult<()> {
+        let cmd = commands::build_policy_pcr(session, selection);
+        self.exchange_checked(&cmd)?;
+        Ok(())
+    }
+
+    /// Make sure an attestation key ([`commands::AK_TEMPLATE`]) lives at
+    /// `persistent_handle`. A key already there is kept, so the key a
+    /// verifier enrolled survives reboots; otherwise one is created
+    /// under a fresh storage root key and made persistent, and the
+    /// transient objects are flushed.
+    pub fn provision_ak(&mut self, persistent_handle: u32) -> TpmResult<()> {
+        if self.read_public(persistent_handle).is_ok() {
+            return Ok(());
+        }
+        let srk = self.create_primary(TPM2_RH_OWNER, &commands::SRK_TEMPLATE)?;
+        let result = self.persist_ak(srk, persistent_handle);
+        // The SRK only wraps the AK; free its slot whatever happened
+        let flushed = self.flush_context(srk);
+        result.and(flushed)
+    }
+
+    /// Create, load and persist the AK under `srk`.
+    fn persist_ak(&mut self, srk: u32, persistent_handle: u32) -> TpmResult<()> {
+        let mut private = [0u8; commands::KEY_BLOB_MAX_LEN];
+        let mut public = [0u8; commands::KEY_BLOB_MAX_LEN];
+        let (private_len, public_len) = {
+            let key = self.create(srk, &commands::AK_TEMPLATE)?;
+            if key.private.len() > private.len() || key.public.len() > public.len() {
+                return Err(TpmRc::Failure);
+            }
+            private[..key.private.len()].copy_from_slice(key.private);
+            public[..key.public.len()].copy_from_slice(key.public);
+            (key.private.len(), key.public.len())
+        };
+        let ak = self.load(srk, &private[..private_len], &public[..public_len])?;
+        let result = self.evict_control(ak, persistent_handle);
+        let flushed = self.flush_context(ak);
+        result.and(flushed)
+    }
 }
 
 // ============================================================================
@@ -242,6 +518,7 @@ mod tests {
 
     use super::*;
     use std::vec::Vec;
+    use crate::PcrDigest;
 
     /// Build a canned response stream: header with the given tag/rc and
     /// the body appended, size filled in.
@@ -370,6 +647,66 @@ mod tests {
         assert_eq!(values[1], (7, Sha256Digest::new([0x77; 32])));
     }
 
+    #[test]
+    fn pcr_extend_values_covers_each_bank() {
+        let digest = Sha256Digest::new([0xAB; 32]);
+        let mut cmd = [0u8; commands::PCR_EXTEND_CMD_MAX_LEN];
+
+        // One SHA-256 digest is the single-digest command
+        let len = commands::build_pcr_extend_values(3, &DigestValues::from(digest), &mut cmd).unwrap();
+        assert_eq!(&cmd[..len], &commands::build_pcr_extend(3, &digest).unwrap()[..]);
+
+        let mut values = DigestValues::from(digest);
+        values.push(PcrDigest::from_bytes(HashAlg::Sha384, &[0xCD; 48]).unwrap()).unwrap();
+        let len = commands::build_pcr_extend_values(3, &values, &mut cmd).unwrap();
+        assert_eq!(len, 65 + 2 + 48);
+        assert_eq!(&cmd[2..6], &(len as u32).to_be_bytes());
+        assert_eq!(&cmd[27..31], &2u32.to_be_bytes()); // digest count
+        assert_eq!(&cmd[65..67], &0x000Cu16.to_be_bytes()); // SHA-384
+        assert_eq!(&cmd[67..len], &[0xCD; 48]);
+
+        let resp = response(0x8002, 0, &[0, 0, 0, 0, 0]);
+        let script = [MockExchange {
+            cmd: &cmd[..len],
+            resp: &resp,
+        }];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        assert_eq!(tpm.pcr_extend_values(24, &values), Err(TpmRc::BadParam));
+        assert_eq!(tpm.pcr_extend_values(3, &DigestValues::new()), Err(TpmRc::BadParam));
+        tpm.pcr_extend_values(3, &values).unwrap();
+        assert!(tpm.transport().finished());
+    }
+
+    #[test]
+    fn pcr_read_bank_roundtrip_via_mock() {
+        let selection = PcrSelection::single(1);
+        let cmd = commands::build_pcr_read_bank(HashAlg::Sha384, selection);
+        assert_eq!(&cmd[14..16], &0x000Cu16.to_be_bytes());
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&1u32.to_be_bytes()); // pcrUpdateCounter
+        body.extend_from_slice(&1u32.to_be_bytes()); // selection count
+        body.extend_from_slice(&0x000Cu16.to_be_bytes()); // SHA-384
+        body.push(3); // sizeofSelect
+        body.extend_from_slice(&[0x02, 0, 0]); // PCR 1
+        body.extend_from_slice(&1u32.to_be_bytes()); // digest count
+        body.extend_from_slice(&48u16.to_be_bytes());
+        body.extend_from_slice(&[0x38; 48]);
+        let resp = response(0x8001, 0, &body);
+
+        // A SHA-384 answer is no SHA-256 read
+        assert_eq!(commands::parse_pcr_read(&resp).err(), Some(TpmRc::Failure));
+
+        let script = [MockExchange {
+            cmd: &cmd,
+            resp: &resp,
+        }];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        let result = tpm.pcr_read_bank(HashAlg::Sha384, selection).unwrap();
+        assert_eq!(result.alg(), HashAlg::Sha384);
+        assert_eq!(result.values(), &[(1, PcrDigest::from_bytes(HashAlg::Sha384, &[0x38; 48]).unwrap())]);
+    }
+
     #[test]
     fn quote_roundtrip_via_mock() {
         let selection = PcrSelection::boot_pcrs();
@@ -433,4 +770,116 @@ mod tests {
         let r = response(0x8001, 0, &body);
         assert_eq!(commands::parse_get_random(&r), Err(TpmRc::Failure));
     }
+
+    /// A password-session response body: `handle` if any, then
+    /// parameterSize, `params` and the session acknowledgement.
+    fn session_body(handle: Option<u32>, params: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        if let Some(handle) = handle {
+            body.extend_from_slice(&handle.to_be_bytes());
+        }
+        body.extend_from_slice(&(params.len() as u32).to_be_bytes());
+        body.extend_from_slice(params);
+        body.extend_from_slice(&[0, 0, 0, 0, 0]);
+        body
+    }
+
+    fn tpm2b(bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        out.extend_from_slice(bytes);
+        out
+    }
+
+    #[test]
+    fn provision_ak_creates_and_persists_a_key() {
+        const AK: u32 = 0x8101_0001;
+        const SRK: u32 = 0x8000_0000;
+        const TRANSIENT_AK: u32 = 0x8000_0001;
+        let private = [0x5A; 48];
+
+        let read_public = commands::build_read_public(AK);
+        let no_key = response(0x8001, 0x18B, &[]); // TPM_RC_HANDLE
+        let mut create_primary = [0u8; commands::CREATE_CMD_MAX_LEN];
+        let len = commands::build_create_primary(
+            TPM2_RH_OWNER,
+            &commands::SRK_TEMPLATE,
+            &mut create_primary,
+        )
+        .unwrap();
+        assert_eq!(len, 67);
+        let srk_created = response(
+            0x8002,
+            0,
+            &session_body(Some(SRK), &tpm2b(&commands::SRK_TEMPLATE)),
+        );
+        let mut create = [0u8; commands::CREATE_CMD_MAX_LEN];
+        let create_len = commands::build_create(SRK, &commands::AK_TEMPLATE, &mut create).unwrap();
+        let mut params = tpm2b(&private);
+        params.extend_from_slice(&tpm2b(&commands::AK_TEMPLATE));
+        let ak_created = response(0x8002, 0, &session_body(None, &params));
+        let mut load = [0u8; commands::LOAD_CMD_MAX_LEN];
+        let load_len =
+            commands::build_load(SRK, &private, &commands::AK_TEMPLATE, &mut load).unwrap();
+        let ak_loaded = response(
+            0x8002,
+            0,
+            &session_body(Some(TRANSIENT_AK), &tpm2b(&[0x00, 0x0B])),
+        );
+        let evict = commands::build_evict_control(TRANSIENT_AK, AK);
+        let ok_sessions = response(0x8002, 0, &session_body(None, &[]));
+        let flush_ak = commands::build_flush_context(TRANSIENT_AK);
+        let flush_srk = commands::build_flush_context(SRK);
+        let ok = response(0x8001, 0, &[]);
+
+        let script = [
+            MockExchange {
+                cmd: &read_public,
+                resp: &no_key,
+            },
+            MockExchange {
+                cmd: &create_primary[..len],
+                resp: &srk_created,
+            },
+            MockExchange {
+                cmd: &create[..create_len],
+                resp: &ak_created,
+            },
+            MockExchange {
+                cmd: &load[..load_len],
+                resp: &ak_loaded,
+            },
+            MockExchange {
+                cmd: &evict,
+                resp: &ok_sessions,
+            },
+            MockExchange {
+                cmd: &flush_ak,
+                resp: &ok,
+            },
+            MockExchange {
+                cmd: &flush_srk,
+                resp: &ok,
+            },
+        ];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        tpm.provision_ak(AK).unwrap();
+        assert!(tpm.transport().finished());
+    }
+
+    #[test]
+    fn provision_ak_keeps_an_existing_key() {
+        let read_public = commands::build_read_public(0x8101_0001);
+        let mut body = tpm2b(&commands::AK_TEMPLATE);
+        body.extend_from_slice(&tpm2b(&[0x00, 0x0B])); // name
+        body.extend_from_slice(&tpm2b(&[0x00, 0x0B])); // qualifiedName
+        let resp = response(0x8001, 0, &body);
+        let script = [MockExchange {
+            cmd: &read_public,
+            resp: &resp,
+        }];
+        let mut tpm = Tpm::new(MockTransport::new(&script));
+        tpm.provision_ak(0x8101_0001).unwrap();
+        assert!(tpm.transport().finished());
+    }
 }
@@ -29,19 +29,41 @@
 //!
 //! 1. **Isolated Access**: Only TPM PD can access TPM hardware registers
 //! 2. **Capability-Based**: Other PDs request measurements via IPC
-//! 3. **Verified Protocol**: IPC protocol is formally verified
-//! 4. **Attestation**: Supports remote attestation for system state verification
+//! 3. **Verified Protocol**: requests and replies are encoded with
+//!    `verified_microkernel::rpc`, whose bounds are verified and checked at
+//!    compile time
+//! 4. **Attestation**: `Quote` signs the boot PCRs and a verifier's nonce
+//!    with an attestation key the PD provisions in the TPM on first use
+//! 5. **Measured Handoff**: `init` hashes the Microkit loader into PCR 0 and
+//!    the kernel into PCR 1 before any other PD runs
+//! 6. **Anti-Rollback**: `init` checks [`SECURITY_VERSION`] against a TPM NV
+//!    counter and, under [`ROLLBACK_POLICY`], refuses to serve an older image
+//! 7. **Sealed Settings**: a settings blob kept in TPM NV RAM that only
+//!    reads or writes while PCR 0 and 1 hold the measurements it was
+//!    sealed under ([`SETTINGS_SLOT`])
 
 #![no_std]
 #![no_main]
 
-use sel4_microkit::{protection_domain, Channel, Handler, Infallible, MessageInfo};
+use sel4_microkit::{
+    protection_domain, with_msg_regs, with_msg_regs_mut, Channel, Handler, Infallible, MessageInfo,
+};
+use rpi4_pd_error::{reply_code, ErrorDomain, ErrorKind, PdError, PdResult, ResultExt};
 use rpi4_tpm_boot::{
-    Slb9670Tpm, BootChain, BootStage, Sha256Digest, TpmResult, TpmRc,
-    boot_chain::compute_sha256,
-    pcr::{PcrBank, PcrSelection},
+    Slb9670Tpm, Tpm, BootChain, BootMeasurement, BootStage, Sha256Digest, TpmResult, TpmRc,
+    attestation::{AttestationKey, AttestedData, MAX_SIGNATURE_SIZE},
+    event_log::{TcgEventLog, TcgEventType, EVENT_LOG_CHUNK},
+    handoff::LoaderImage,
+    nv::{NvSettingsStore, NvSlot, SETTINGS_NV_INDEX},
+    pcr::{PcrBank, PcrSelection, PCR_FIRMWARE, PCR_KERNEL},
+    rollback::{self, RollbackPolicy, RollbackStatus, ROLLBACK_NV_INDEX},
     spi::{Spi, ChipSelect, SpiSpeed, SPI0_BASE, GPIO_BASE},
 };
+use rpi4_settings_protocol::{check_blob, SettingsStore};
+use verified_microkernel::{
+    rpc::{self, Message, MessageWriter, RpcError},
+    rpc_requests, IpcBuffer,
+};
 
 // ============================================================================
 // MEMORY MAP (from Microkit system description)
@@ -53,55 +75,123 @@ const SPI_VADDR: usize = 0x5_0100_0000;
 /// GPIO registers virtual address
 const GPIO_VADDR: usize = 0x5_0200_0000;
 
+/// `loader.img` as the firmware loaded it (read-only)
+const LOADER_IMAGE_VADDR: usize = 0x5_0500_0000;
+
+/// Size of the loader image mapping
+const LOADER_IMAGE_SIZE: usize = 0x100_0000;
+
+// ============================================================================
+// ANTI-ROLLBACK
+// ============================================================================
+
+/// Security version of this system image. Bump it for a release that
+/// older images must not be able to replace.
+const SECURITY_VERSION: u64 = 1;
+
+/// What `init` does when the image is older than the TPM's counter
+const ROLLBACK_POLICY: RollbackPolicy = RollbackPolicy::Enforce;
+
+// ============================================================================
+// SEALED SETTINGS
+// ============================================================================
+
+/// Largest settings blob the PD stores: one message's worth
+const SETTINGS_MAX: usize = 512;
+
+/// Where settings blobs are kept: sealed to the loader and kernel
+/// measurements, which `init` takes before any client can run. The other
+/// boot PCRs take client extends at run time, so sealing to them would
+/// lock the slot as soon as a client measured something.
+const SETTINGS_SLOT: NvSlot = NvSlot::sealed(
+    SETTINGS_NV_INDEX,
+    SETTINGS_MAX as u16,
+    PcrSelection::from_bitmap((1 << PCR_FIRMWARE) | (1 << PCR_KERNEL)),
+);
+
 // ============================================================================
 // IPC PROTOCOL
 // ============================================================================
 
-/// IPC commands for TPM operations
-#[repr(u64)]
-#[derive(Clone, Copy, Debug)]
-enum TpmCommand {
-    /// Initialize TPM
-    Init = 0,
-    /// Extend PCR with measurement
-    PcrExtend = 1,
-    /// Read PCR value
-    PcrRead = 2,
-    /// Get random bytes
-    GetRandom = 3,
-    /// Measure component (hash and extend)
-    Measure = 4,
-    /// Request attestation quote
-    Quote = 5,
-    /// Get boot verification status
-    GetStatus = 6,
+rpc_requests! {
+    /// IPC requests for TPM operations; the label is the operation
+    enum TpmRequest {
+        /// Initialize TPM
+        Init = 0,
+        /// Extend PCR `pcr` with a SHA-256 measurement
+        PcrExtend { pcr: u8, digest: [u8; 32] } = 1,
+        /// Read PCR `pcr`; replies with its 32-byte value
+        PcrRead { pcr: u8 } = 2,
+        /// Get up to 32 random bytes; replies with the count and the bytes
+        GetRandom { count: u8 } = 3,
+        /// Measure component (hash and extend)
+        Measure { stage: u8, component_id: u32, len: u32 } = 4,
+        /// Quote the boot PCRs over `nonce`; replies with the lengths of
+        /// the TPMS_ATTEST and TPMT_SIGNATURE, then the two structures
+        Quote { nonce: [u8; 32] } = 5,
+        /// Get boot verification status; replies with the verification
+        /// flag and the measurement count
+        GetStatus = 6,
+        /// Get the TCG event log's size; replies with its length in bytes
+        /// and the number of events after the header
+        EventLogInfo = 7,
+        /// Read the TCG event log from byte `offset`; replies with the
+        /// count of bytes read and up to `EVENT_LOG_CHUNK` of them
+        EventLogRead { offset: u32 } = 8,
+        /// Get the attestation key's TPMT_PUBLIC, for a verifier to check
+        /// quote signatures with; replies with its length and the bytes
+        AkPublic = 9,
+        /// Get the anti-rollback check's outcome; replies with whether the
+        /// image was accepted, its security version and the NV counter
+        RollbackCheck = 10,
+        /// Read the sealed settings blob; replies with its length (0 if
+        /// none has been stored) and up to `SETTINGS_MAX` bytes
+        SettingsRead = 11,
+        /// Replace the sealed settings blob with the first `len` bytes of
+        /// `blob`, which must be a valid settings blob
+        SettingsWrite { len: u16, blob: [u8; SETTINGS_MAX] } = 12,
+        /// Delete the settings slot, so the next `SettingsWrite` seals it
+        /// under the current measurements (after an update)
+        SettingsReset = 13,
+    }
 }
 
-impl TryFrom<u64> for TpmCommand {
-    type Error = ();
-
-    fn try_from(value: u64) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(TpmCommand::Init),
-            1 => Ok(TpmCommand::PcrExtend),
-            2 => Ok(TpmCommand::PcrRead),
-            3 => Ok(TpmCommand::GetRandom),
-            4 => Ok(TpmCommand::Measure),
-            5 => Ok(TpmCommand::Quote),
-            6 => Ok(TpmCommand::GetStatus),
-            _ => Err(()),
-        }
-    }
+/// Largest TPMS_ATTEST a `Quote` reply carries
+const QUOTE_ATTEST_MAX: usize = 256;
+
+/// Largest TPMT_PUBLIC an `AkPublic` reply carries
+const AK_PUBLIC_MAX: usize = 512;
+
+/// Unknown label or malformed request (the detail is the [`RpcError`]'s)
+const INVALID_COMMAND: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::InvalidArgument);
+
+/// Reply did not fit the message registers
+const REPLY_OVERFLOW: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::Capacity);
+
+/// Command needs the TPM but `Init` has not succeeded yet
+const NOT_INITIALIZED: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::NotReady);
+
+/// The image failed the anti-rollback check under [`RollbackPolicy::Enforce`]
+const ROLLBACK_REFUSED: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::Denied);
+
+/// The anti-rollback check could not be run
+const ROLLBACK_UNCHECKED: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::Failed);
+
+/// Settings need the loader and kernel measured first, or the slot would be
+/// sealed to unmeasured PCRs
+const HANDOFF_UNMEASURED: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::InvalidState);
+
+/// `SettingsWrite` of something that is not a settings blob
+const INVALID_SETTINGS: PdError = PdError::new(ErrorDomain::Tpm, ErrorKind::Integrity);
+
+/// A request that did not decode
+fn invalid_command(e: RpcError) -> PdError {
+    INVALID_COMMAND.with_detail(e.detail())
 }
 
-/// IPC response status
-#[repr(u64)]
-enum TpmResponse {
-    Success = 0,
-    Error = 1,
-    NotInitialized = 2,
-    InvalidCommand = 3,
-    InvalidParameter = 4,
+/// Encode a successful reply's fields
+fn put<T: Message>(reply: &mut MessageWriter<'_>, value: &T) -> PdResult<()> {
+    reply.put(value).map_err(|_| REPLY_OVERFLOW)
 }
 
 // ============================================================================
@@ -111,9 +201,17 @@ enum TpmResponse {
 /// TPM Protection Domain handler
 struct TpmPd {
     /// TPM driver
-    tpm: Option<Slb9670Tpm>,
+    tpm: Option<Tpm<Slb9670Tpm>>,
+    /// Attestation key known to be at `AttestationKey::standard_aik()`
+    ak_provisioned: bool,
+    /// Outcome of the anti-rollback check, once it has run
+    rollback: Option<RollbackStatus>,
+    /// The loader and kernel are measured into PCR 0 and 1
+    handoff_measured: bool,
     /// Boot measurement chain
     boot_chain: BootChain,
+    /// Every extend, in order, for a verifier to replay
+    event_log: TcgEventLog,
     /// Local PCR shadow (for software emulation/verification)
     pcr_bank: PcrBank,
     /// Initialization status
@@ -126,7 +224,11 @@ impl TpmPd {
     const fn new() -> Self {
         Self {
             tpm: None,
+            ak_provisioned: false,
+            rollback: None,
+            handoff_measured: false,
             boot_chain: BootChain::new(),
+            event_log: TcgEventLog::new(),
             pcr_bank: PcrBank::new(),
             initialized: false,
             debug_enabled: true,
@@ -146,7 +248,7 @@ impl TpmPd {
         //     return Err(TpmRc::Failure);
         // }
 
-        self.tpm = Some(tpm);
+        self.tpm = Some(Tpm::new(tpm));
 
         // Initialize TPM
         if let Some(ref mut tpm) = self.tpm {
@@ -167,24 +269,120 @@ impl TpmPd {
         data: &[u8],
     ) -> TpmResult<Sha256Digest> {
         // Compute hash
-        let digest = compute_sha256(data);
+        let measurement = BootMeasurement::from_component(stage, component_id, data);
+        let digest = measurement.digest;
+        self.record(measurement)?;
 
-        // Extend local PCR bank
-        let pcr_index = stage.pcr_index();
-        self.pcr_bank.extend(pcr_index, &digest)?;
+        self.debug_print("TPM PD: Measured component for PCR ");
+        // Would print PCR index
+
+        Ok(digest)
+    }
 
-        // Extend TPM hardware PCR
+    /// Measure the Microkit loader into PCR 0 and the kernel into PCR 1,
+    /// from the loader image mapped at [`LOADER_IMAGE_VADDR`]. Runs from
+    /// `init`, before any lower-priority PD has run.
+    fn measure_handoff(&mut self) -> TpmResult<()> {
+        // SAFETY: the system description maps LOADER_IMAGE_SIZE bytes
+        // read-only here, and nothing writes them
+        let image = unsafe { core::slice::from_raw_parts(LOADER_IMAGE_VADDR as *const u8, LOADER_IMAGE_SIZE) };
+        let Ok(loader) = LoaderImage::parse(image) else {
+            self.debug_print("TPM PD: no Microkit loader image to measure\n");
+            return Err(TpmRc::Failure);
+        };
+        let measurements = loader.measurements().map_err(|_| TpmRc::Failure)?;
+        for measurement in measurements {
+            self.record(measurement)?;
+        }
+        self.handoff_measured = true;
+        self.debug_print("TPM PD: Measured loader and kernel into PCR 0/1\n");
+        Ok(())
+    }
+
+    /// Check [`SECURITY_VERSION`] against the TPM's anti-rollback counter,
+    /// advancing the counter if the image is accepted, and measure the
+    /// outcome into PCR 7. Runs from `init`, after the handoff measurements.
+    fn check_rollback(&mut self) -> TpmResult<()> {
+        let tpm = self.tpm.as_mut().ok_or(TpmRc::BadSequence)?;
+        let status = rollback::check_rollback(tpm, ROLLBACK_NV_INDEX, SECURITY_VERSION)?;
+        self.rollback = Some(status);
+        if status.is_downgrade() {
+            self.debug_print("TPM PD: ROLLBACK: image is older than the TPM counter\n");
+        }
+        self.record(status.measurement())
+    }
+
+    /// Extend `measurement` into its PCR, then log it and add it to the
+    /// boot chain
+    fn record(&mut self, measurement: BootMeasurement) -> TpmResult<()> {
+        // Refuse before extending (the event data is the 8-byte ID and
+        // size): a PCR the log cannot explain is worse than a failed
+        // measurement
+        if !self.event_log.can_record(8) {
+            return Err(TpmRc::Failure);
+        }
+        self.extend_pcr_unlogged(measurement.stage.pcr_index(), &measurement.digest)?;
+        self.event_log.record_measurement(&measurement)?;
+        self.boot_chain.add_measurement(measurement)
+    }
+
+    /// Extend a PCR on behalf of a client, logging it as an `EV_EVENT_TAG`
+    /// with no data
+    fn extend_pcr(&mut self, pcr_index: u8, digest: &Sha256Digest) -> TpmResult<()> {
+        if !self.event_log.can_record(0) {
+            return Err(TpmRc::Failure);
+        }
+        self.extend_pcr_unlogged(pcr_index, digest)?;
+        self.event_log.record(pcr_index, TcgEventType::EventTag, digest, &[])
+    }
+
+    /// Extend the local PCR bank and, when present, the TPM's PCR
+    fn extend_pcr_unlogged(&mut self, pcr_index: u8, digest: &Sha256Digest) -> TpmResult<()> {
+        self.pcr_bank.extend(pcr_index, digest)?;
         if let Some(ref mut tpm) = self.tpm {
-            tpm.pcr_extend(pcr_index, &digest)?;
+            tpm.pcr_extend(pcr_index, digest)?;
         }
+        Ok(())
+    }
 
-        // Record in boot chain
-        self.boot_chain.measure_component(stage, component_id, data)?;
+    /// The TPM, with the attestation key provisioned on first use
+    fn attestation_tpm(&mut self) -> TpmResult<&mut Tpm<Slb9670Tpm>> {
+        let tpm = self.tpm.as_mut().ok_or(TpmRc::BadSequence)?;
+        if !self.ak_provisioned {
+            tpm.provision_ak(AttestationKey::standard_aik())?;
+            self.ak_provisioned = true;
+            self.debug_print("TPM PD: Attestation key ready\n");
+        }
+        Ok(tpm)
+    }
 
-        self.debug_print("TPM PD: Measured component for PCR ");
-        // Would print PCR index
+    /// Quote the boot PCRs over `nonce` into `attest` and `signature`;
+    /// returns the length of each
+    fn quote(
+        &mut self,
+        nonce: &[u8; 32],
+        attest: &mut [u8; QUOTE_ATTEST_MAX],
+        signature: &mut [u8; MAX_SIGNATURE_SIZE],
+    ) -> TpmResult<(usize, usize)> {
+        let tpm = self.attestation_tpm()?;
+        let quote = tpm.quote(AttestationKey::standard_aik(), nonce, PcrSelection::boot_pcrs())?;
+        // The TPM must have signed what was asked for
+        if AttestedData::parse(quote.attest)?.extra_data != *nonce {
+            return Err(TpmRc::Failure);
+        }
+        attest.get_mut(..quote.attest.len()).ok_or(TpmRc::Failure)?.copy_from_slice(quote.attest);
+        signature.get_mut(..quote.signature.len()).ok_or(TpmRc::Failure)?.copy_from_slice(quote.signature);
+        Ok((quote.attest.len(), quote.signature.len()))
+    }
 
-        Ok(digest)
+    /// The settings store in [`SETTINGS_SLOT`], once the PCRs it is
+    /// sealed to have been measured
+    fn settings(&mut self) -> PdResult<NvSettingsStore<'_, Slb9670Tpm>> {
+        if !self.handoff_measured {
+            return Err(HANDOFF_UNMEASURED);
+        }
+        let tpm = self.tpm.as_mut().ok_or(NOT_INITIALIZED)?;
+        Ok(NvSettingsStore::new(tpm, SETTINGS_SLOT))
     }
 
     /// Get boot verification status
@@ -201,71 +399,125 @@ impl TpmPd {
         }
     }
 
-    /// Handle IPC message
+    /// Fail with [`NOT_INITIALIZED`] until `Init` has succeeded, and with
+    /// [`ROLLBACK_REFUSED`] when [`ROLLBACK_POLICY`] enforces a rollback
+    /// check the image did not pass
+    fn require_initialized(&self) -> PdResult<()> {
+        if !self.initialized {
+            return Err(NOT_INITIALIZED);
+        }
+        let accepted = matches!(self.rollback, Some(RollbackStatus::Accepted { .. }));
+        if ROLLBACK_POLICY == RollbackPolicy::Enforce && !accepted {
+            return Err(ROLLBACK_REFUSED);
+        }
+        Ok(())
+    }
+
+    /// Handle IPC message: decode the request from the message registers
+    /// and leave the reply in them. The reply label is the [`PdError`]
+    /// wire code of the failure, or 0 with the reply's fields.
     fn handle_message(&mut self, channel: Channel, msg: MessageInfo) -> MessageInfo {
-        let label = msg.label();
+        let mut buf = IpcBuffer::new();
+        with_msg_regs(|mrs| buf.load(&mrs[..msg.count().min(mrs.len())]));
 
-        let cmd = match TpmCommand::try_from(label) {
-            Ok(cmd) => cmd,
-            Err(_) => {
-                return MessageInfo::new(TpmResponse::InvalidCommand as u64, 0);
-            }
-        };
+        let result = rpc::decode_request(msg.label(), &buf)
+            .map_err(invalid_command)
+            .and_then(|request| self.handle(request, &mut MessageWriter::new(&mut buf)));
 
-        match cmd {
-            TpmCommand::Init => {
-                match self.init_tpm() {
-                    Ok(()) => MessageInfo::new(TpmResponse::Success as u64, 0),
-                    Err(_) => MessageInfo::new(TpmResponse::Error as u64, 0),
-                }
-            }
+        let count = if result.is_ok() { buf.len() } else { 0 };
+        with_msg_regs_mut(|mrs| mrs[..count].copy_from_slice(&buf.words()[..count]));
+        MessageInfo::new(reply_code(&result) as u64, count)
+    }
 
-            TpmCommand::PcrExtend => {
-                if !self.initialized {
-                    return MessageInfo::new(TpmResponse::NotInitialized as u64, 0);
-                }
-                // PCR index and digest would be passed in message registers
-                // For now, return success placeholder
-                MessageInfo::new(TpmResponse::Success as u64, 0)
+    /// Serve one request, writing its reply fields to `reply`
+    fn handle(&mut self, request: TpmRequest, reply: &mut MessageWriter<'_>) -> PdResult<()> {
+        match request {
+            TpmRequest::Init => self.init_tpm().context("initializing TPM"),
+
+            TpmRequest::PcrExtend { pcr, digest } => {
+                self.require_initialized()?;
+                self.extend_pcr(pcr, &Sha256Digest::new(digest)).context("extending PCR")
             }
 
-            TpmCommand::PcrRead => {
-                if !self.initialized {
-                    return MessageInfo::new(TpmResponse::NotInitialized as u64, 0);
-                }
-                // PCR index in message, return digest in response
-                MessageInfo::new(TpmResponse::Success as u64, 0)
+            TpmRequest::PcrRead { pcr } => {
+                self.require_initialized()?;
+                // The local shadow bank sees every extend the TPM does
+                let value = self.pcr_bank.read(pcr).ok_or(TpmRc::BadParam).context("reading PCR")?;
+                put(reply, &value.bytes)
             }
 
-            TpmCommand::GetRandom => {
-                if !self.initialized {
-                    return MessageInfo::new(TpmResponse::NotInitialized as u64, 0);
-                }
-                // Would get random bytes from TPM
-                MessageInfo::new(TpmResponse::Success as u64, 0)
+            TpmRequest::GetRandom { count } => {
+                self.require_initialized()?;
+                let tpm = self.tpm.as_mut().ok_or(NOT_INITIALIZED)?;
+                let mut bytes = [0u8; 32];
+                let want = bytes.get_mut(..count as usize).ok_or(TpmRc::BadParam).context("getting random bytes")?;
+                tpm.get_random(want).context("getting random bytes")?;
+                put(reply, &(count, bytes))
             }
 
-            TpmCommand::Measure => {
-                if !self.initialized {
-                    return MessageInfo::new(TpmResponse::NotInitialized as u64, 0);
-                }
+            TpmRequest::Measure { .. } => {
                 // Would measure component from shared memory
-                MessageInfo::new(TpmResponse::Success as u64, 0)
+                self.require_initialized()
             }
 
-            TpmCommand::Quote => {
-                if !self.initialized {
-                    return MessageInfo::new(TpmResponse::NotInitialized as u64, 0);
-                }
-                // Would generate attestation quote
-                MessageInfo::new(TpmResponse::Success as u64, 0)
+            TpmRequest::Quote { nonce } => {
+                self.require_initialized()?;
+                let mut attest = [0u8; QUOTE_ATTEST_MAX];
+                let mut signature = [0u8; MAX_SIGNATURE_SIZE];
+                let (attest_len, signature_len) =
+                    self.quote(&nonce, &mut attest, &mut signature).context("quoting boot PCRs")?;
+                put(reply, &((attest_len as u32, signature_len as u32), attest, signature))
             }
 
-            TpmCommand::GetStatus => {
+            TpmRequest::GetStatus => {
                 let (verified, count) = self.get_status();
-                let status = if verified { 1u64 } else { 0u64 };
-                // Return verification status and measurement count
-                MessageInfo::new(status, count)
+                put(reply, &(verified, count as u64))
+            }
+
+            TpmRequest::EventLogInfo => {
+                put(reply, &(self.event_log.len() as u32, self.event_log.event_count() as u32))
+            }
+
+            TpmRequest::EventLogRead { offset } => {
+                let mut chunk = [0u8; EVENT_LOG_CHUNK];
+                let n = self.event_log.read_at(offset as usize, &mut chunk);
+                put(reply, &(n as u32, chunk))
+            }
+
+            TpmRequest::AkPublic => {
+                self.require_initialized()?;
+                let public = self
+                    .attestation_tpm()
+                    .and_then(|tpm| tpm.read_public(AttestationKey::standard_aik()))
+                    .context("reading attestation key")?;
+                let mut bytes = [0u8; AK_PUBLIC_MAX];
+                bytes.get_mut(..public.len()).ok_or(REPLY_OVERFLOW)?.copy_from_slice(public);
+                put(reply, &(public.len() as u32, bytes))
+            }
+
+            TpmRequest::RollbackCheck => {
+                let status = self.rollback.ok_or(ROLLBACK_UNCHECKED)?;
+                put(reply, &(!status.is_downgrade(), status.version(), status.counter()))
+            }
+
+            TpmRequest::SettingsRead => {
+                self.require_initialized()?;
+                let mut blob = [0u8; SETTINGS_MAX];
+                let len = self.settings()?.read_blob(&mut blob).context("reading settings")?;
+                put(reply, &(len as u32, blob))
+            }
+
+            TpmRequest::SettingsWrite { len, blob } => {
+                self.require_initialized()?;
+                let blob = blob.get(..len as usize).ok_or(INVALID_COMMAND)?;
+                check_blob(blob).map_err(|_| INVALID_SETTINGS)?;
+                self.settings()?.write_blob(blob).context("writing settings")
+            }
+
+            TpmRequest::SettingsReset => {
+                self.require_initialized()?;
+                let tpm = self.tpm.as_mut().ok_or(NOT_INITIALIZED)?;
+                SETTINGS_SLOT.undefine(tpm).context("resetting settings")
             }
         }
     }
@@ -316,5 +568,14 @@ fn init() -> TpmPd {
     // Note: In production, this might be done on first use instead
     let _ = pd.init_tpm();
 
+    // Measure the loader and kernel before returning: this PD has the
+    // highest priority, so no other PD has run yet
+    let _ = pd.measure_handoff();
+
+    // Then compare the image's security version with the TPM's counter;
+    // under RollbackPolicy::Enforce a failed or skipped check leaves the
+    // PD refusing requests
+    let _ = pd.check_rollback();
+
     pd
 }
@@ -3,6 +3,7 @@
 //! Provides various animations that can be played on any display backend.
 
 use crate::backend::{DisplayBackend, Color};
+use verified_microkernel::{abs_diff_u32, clamp_i32};
 
 /// Animation trait for playable content
 pub trait Animation {
@@ -95,11 +96,11 @@ impl Animation for BouncingBall {
 
         if self.x - r <= 0 || self.x + r >= w {
             self.vx = -self.vx;
-            self.x = self.x.clamp(r, w - r);
+            self.x = clamp_i32(self.x, r, w - r);
         }
         if self.y - r <= 0 || self.y + r >= h {
             self.vy = -self.vy;
-            self.y = self.y.clamp(r, h - r);
+            self.y = clamp_i32(self.y, r, h - r);
         }
     }
 
@@ -186,7 +187,7 @@ impl ColorCycle {
         let v = v as u16;
 
         let c = (v * s) / 255;
-        let x = (c * (60 - ((h % 120) as i16 - 60).unsigned_abs() as u16)) / 60;
+        let x = (c * (60 - abs_diff_u32((h % 120) as u32, 60) as u16)) / 60;
         let m = v - c;
 
         let (r, g, b) = match h / 60 {
@@ -22,6 +22,18 @@
 //!     demo.render(&mut display);
 //! }
 //! ```
+//!
+//! IR remotes work without setup when they are a generic 21-key NEC remote
+//! or a Samsung or LG TV remote: [`IrRemote`] recognizes them by address
+//! and applies their [`RemoteProfile`]. Other remotes need a profile
+//! registered through [`InputManager::ir_remote_mut`], or one of the
+//! built-in maps selected with [`IrRemote::select_profile`].
+//!
+//! With the HDMI framebuffer and the SPI LCD attached together, a
+//! [`DisplayRouter`] drives both: backends are registered by
+//! [`DisplayRole`] (photos on HDMI, status and controls on the LCD), and
+//! each display's damage is tracked separately so only what changed is
+//! pushed to it.
 
 #![no_std]
 #![allow(dead_code)]
@@ -29,17 +41,19 @@
 pub mod backend;
 pub mod animation;
 pub mod menu;
+pub mod router;
 pub mod tv_app;
 
 pub use backend::{DisplayBackend, Color, ScaledDisplay};
 pub use animation::{Animation, AnimationPlayer, AnimationType, BouncingBall, ColorCycle, Spinner};
-pub use menu::{Menu, MenuItem, MenuStyle};
+pub use menu::{ClipRect, Menu, MenuItem, MenuStyle, Ticker};
+pub use router::{DamageRect, DisplayId, DisplayRole, DisplayRouter, Routed, RouteError, MAX_DISPLAYS};
 pub use tv_app::{TvDemo, DemoState, Screen};
 
 // Re-export input types for convenience
 pub use rpi4_input::{
     InputEvent, InputManager, InputSource, RemoteOptions,
     KeyCode, KeyEvent, KeyState, KeyModifiers, Keyboard,
-    IrButton, IrEvent, IrProtocol, IrRemote,
+    IrButton, IrEvent, IrProtocol, IrRemote, ButtonMap, RemoteProfile,
     TouchEvent, TouchPoint,
 };
@@ -1,18 +1,285 @@
 //! Menu system for TV demo
 //!
-//! Provides a navigable menu with highlight selection.
+//! Provides a navigable menu with highlight selection, and a [`Ticker`]
+//! that scrolls a line too long for its space back and forth instead of
+//! cutting it off. The selected menu item scrolls through one when its
+//! label does not fit.
+//!
+//! Text is drawn as one placeholder block per character,
+//! [`GLYPH_ADVANCE`] pixels apart. Every block goes through [`clip_cell`]
+//! (verified to stay inside the clip rectangle) before it is drawn.
 
 use crate::backend::{DisplayBackend, Color};
+use verified_microkernel::BoundedString;
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
 
 /// Maximum number of menu items
 pub const MAX_MENU_ITEMS: usize = 10;
 
+/// Maximum length of a menu label or title, in bytes
+pub const MAX_LABEL_LEN: usize = 31;
+
+/// Menu label or title text; longer text is cut at a character boundary
+pub type MenuLabel = BoundedString<MAX_LABEL_LEN>;
+
+/// Horizontal distance between placeholder glyphs, in pixels
+pub const GLYPH_ADVANCE: u32 = 8;
+
+/// Height of a placeholder glyph, in pixels
+pub const GLYPH_HEIGHT: u32 = 8;
+
+/// Maximum length of ticker text, in bytes
+pub const MAX_TICKER_LEN: usize = 127;
+
+/// Ticker text; longer text is cut at a character boundary
+pub type TickerText = BoundedString<MAX_TICKER_LEN>;
+
+/// Pixels a ticker scrolls per frame unless configured otherwise
+pub const DEFAULT_TICKER_SPEED: u32 = 1;
+
+/// Frames a ticker holds at each end unless configured otherwise (1s at
+/// 60fps)
+pub const DEFAULT_TICKER_PAUSE: u32 = 60;
+
+verus! {
+
+/// The part of glyph cell `cell_x..cell_x + cell_w` (in text pixels) that
+/// shows through a `clip_w` wide window scrolled `offset` pixels into the
+/// text, as `(x, w)` relative to the window's left edge, or `None` if none
+/// of it does
+pub fn clip_cell(cell_x: u32, cell_w: u32, offset: u32, clip_w: u32) -> (r: Option<(u32, u32)>)
+    ensures
+        r.is_some() ==> 0 < r.unwrap().1 && r.unwrap().1 <= cell_w
+            && r.unwrap().0 as int + r.unwrap().1 as int <= clip_w as int,
+{
+    let start = cell_x as u64;
+    let end = cell_x as u64 + cell_w as u64;
+    let window_start = offset as u64;
+    let window_end = offset as u64 + clip_w as u64;
+    if cell_w == 0 || clip_w == 0 || end <= window_start || start >= window_end {
+        return None;
+    }
+    let from = if start > window_start { start } else { window_start };
+    let to = if end < window_end { end } else { window_end };
+    Some(((from - window_start) as u32, (to - from) as u32))
+}
+
+/// Furthest a `text_w` wide line scrolls in a `clip_w` wide window: far
+/// enough that its end meets the right edge, and not at all if it fits
+// Spelled out so the proof sees the branch
+#[allow(clippy::implicit_saturating_sub)]
+pub fn max_scroll(text_w: u32, clip_w: u32) -> (r: u32)
+    ensures
+        r <= text_w,
+        r as int + clip_w as int >= text_w as int,
+        text_w <= clip_w ==> r == 0,
+{
+    if text_w > clip_w {
+        text_w - clip_w
+    } else {
+        0
+    }
+}
+
+/// Scroll `speed` pixels on from `offset`, stopping at `limit`
+pub fn step_scroll(offset: u32, speed: u32, limit: u32) -> (r: u32)
+    requires
+        offset <= limit,
+    ensures
+        offset <= r && r <= limit,
+{
+    if limit - offset < speed {
+        limit
+    } else {
+        offset + speed
+    }
+}
+
+} // verus!
+
+/// Draw `text` as placeholder glyphs inside `clip`, scrolled `offset`
+/// pixels to the left; spaces leave a gap
+fn draw_text_line<D: DisplayBackend>(display: &mut D, text: &str, clip: ClipRect, offset: u32, color: Color) {
+    let glyph_h = GLYPH_HEIGHT.min(clip.height);
+    let text_y = clip.y + (clip.height - glyph_h) / 2;
+    for (i, c) in text.chars().enumerate() {
+        let cell_x = (i as u32).saturating_mul(GLYPH_ADVANCE);
+        if cell_x >= offset.saturating_add(clip.width) {
+            break;
+        }
+        if c == ' ' {
+            continue;
+        }
+        // One pixel short of the advance, so neighbouring glyphs stay apart
+        if let Some((x, w)) = clip_cell(cell_x, GLYPH_ADVANCE - 1, offset, clip.width) {
+            display.fill_rect(clip.x + x, text_y, w, glyph_h, color);
+        }
+    }
+}
+
+/// Width of `text` drawn as placeholder glyphs
+fn text_width(text: &str) -> u32 {
+    (text.chars().count() as u32).saturating_mul(GLYPH_ADVANCE)
+}
+
+/// Screen area a ticker draws in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClipRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+}
+
+/// Where a ticker is in its scroll cycle
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum TickerPhase {
+    /// Holding at the start, for this many more frames
+    HoldStart(u32),
+    /// Moving towards the end
+    Scrolling,
+    /// Holding at the end, for this many more frames
+    HoldEnd(u32),
+}
+
+/// A line of text that scrolls horizontally when it is wider than its clip
+/// rectangle
+///
+/// Text that fits is drawn still. Longer text holds at its start for the
+/// pause, scrolls by the speed each [`tick`](Self::tick) until its end
+/// meets the right edge, holds there for the pause and jumps back.
+#[derive(Clone, Copy)]
+pub struct Ticker {
+    text: TickerText,
+    clip: ClipRect,
+    /// Pixels per frame; never zero
+    speed: u32,
+    /// Frames held at each end
+    pause: u32,
+    /// Pixels scrolled; never more than `max_scroll` of the text
+    offset: u32,
+    phase: TickerPhase,
+}
+
+impl Ticker {
+    /// Empty ticker drawing in `clip`
+    pub const fn new(clip: ClipRect) -> Self {
+        Self {
+            text: TickerText::new(),
+            clip,
+            speed: DEFAULT_TICKER_SPEED,
+            pause: DEFAULT_TICKER_PAUSE,
+            offset: 0,
+            phase: TickerPhase::HoldStart(DEFAULT_TICKER_PAUSE),
+        }
+    }
+
+    /// Ticker showing `text` in `clip`
+    pub fn with_text(clip: ClipRect, text: &str) -> Self {
+        let mut ticker = Self::new(clip);
+        ticker.set_text(text);
+        ticker
+    }
+
+    /// Show `text`, scrolling from the start; unchanged text keeps its place
+    pub fn set_text(&mut self, text: &str) {
+        let text = TickerText::from_str_truncating(text);
+        if self.text != text {
+            self.text = text;
+            self.restart();
+        }
+    }
+
+    /// Current text
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Draw in `clip` from now on, scrolling from the start
+    pub fn set_clip(&mut self, clip: ClipRect) {
+        if self.clip != clip {
+            self.clip = clip;
+            self.restart();
+        }
+    }
+
+    /// Current clip rectangle
+    pub fn clip(&self) -> ClipRect {
+        self.clip
+    }
+
+    /// Scroll `pixels_per_frame` pixels each tick (at least 1)
+    pub fn set_speed(&mut self, pixels_per_frame: u32) {
+        self.speed = pixels_per_frame.max(1);
+    }
+
+    /// Hold `frames` frames at each end
+    pub fn set_pause(&mut self, frames: u32) {
+        self.pause = frames;
+    }
+
+    /// Does the text need to scroll to be read?
+    pub fn scrolls(&self) -> bool {
+        text_width(self.text.as_str()) > self.clip.width
+    }
+
+    /// Pixels the text is currently scrolled to the left
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Go back to the start and hold there
+    pub fn restart(&mut self) {
+        self.offset = 0;
+        self.phase = TickerPhase::HoldStart(self.pause);
+    }
+
+    /// Advance one frame
+    pub fn tick(&mut self) {
+        let limit = max_scroll(text_width(self.text.as_str()), self.clip.width);
+        if limit == 0 {
+            self.offset = 0;
+            return;
+        }
+        self.phase = match self.phase {
+            TickerPhase::HoldStart(0) => TickerPhase::Scrolling,
+            TickerPhase::HoldStart(frames) => TickerPhase::HoldStart(frames - 1),
+            TickerPhase::Scrolling => {
+                self.offset = step_scroll(self.offset.min(limit), self.speed, limit);
+                if self.offset == limit {
+                    TickerPhase::HoldEnd(self.pause)
+                } else {
+                    TickerPhase::Scrolling
+                }
+            }
+            TickerPhase::HoldEnd(0) => {
+                self.offset = 0;
+                TickerPhase::HoldStart(self.pause)
+            }
+            TickerPhase::HoldEnd(frames) => TickerPhase::HoldEnd(frames - 1),
+        };
+    }
+
+    /// Draw the visible part of the text; the caller paints the background
+    pub fn render<D: DisplayBackend>(&self, display: &mut D, color: Color) {
+        draw_text_line(display, self.text.as_str(), self.clip, self.offset, color);
+    }
+}
+
 /// Menu item with label and optional icon
 #[derive(Clone, Copy)]
 pub struct MenuItem {
-    /// Display label (null-terminated, max 32 chars)
-    label: [u8; 32],
-    label_len: usize,
+    /// Display label
+    label: MenuLabel,
     /// Whether this item is enabled
     pub enabled: bool,
     /// Item identifier for action handling
@@ -23,8 +290,7 @@ impl MenuItem {
     /// Create a new menu item
     pub const fn new(id: u8) -> Self {
         Self {
-            label: [0; 32],
-            label_len: 0,
+            label: MenuLabel::new(),
             enabled: true,
             id,
         }
@@ -39,15 +305,12 @@ impl MenuItem {
 
     /// Set the label text
     pub fn set_label(&mut self, text: &str) {
-        let bytes = text.as_bytes();
-        let len = bytes.len().min(31);
-        self.label[..len].copy_from_slice(&bytes[..len]);
-        self.label_len = len;
+        self.label = MenuLabel::from_str_truncating(text);
     }
 
     /// Get the label as a string slice
     pub fn label(&self) -> &str {
-        core::str::from_utf8(&self.label[..self.label_len]).unwrap_or("")
+        self.label.as_str()
     }
 }
 
@@ -115,8 +378,9 @@ pub struct Menu {
     selected: usize,
     style: MenuStyle,
     /// Title of the menu
-    title: [u8; 32],
-    title_len: usize,
+    title: MenuLabel,
+    /// Scrolls the selected item's label when it does not fit
+    ticker: Ticker,
     /// Screen dimensions
     width: u32,
     height: u32,
@@ -130,8 +394,8 @@ impl Menu {
             item_count: 0,
             selected: 0,
             style: MenuStyle::dark(),
-            title: [0; 32],
-            title_len: 0,
+            title: MenuLabel::new(),
+            ticker: Ticker::new(ClipRect::new(0, 0, 0, 0)),
             width,
             height,
         }
@@ -146,15 +410,12 @@ impl Menu {
 
     /// Set the menu title
     pub fn set_title(&mut self, text: &str) {
-        let bytes = text.as_bytes();
-        let len = bytes.len().min(31);
-        self.title[..len].copy_from_slice(&bytes[..len]);
-        self.title_len = len;
+        self.title = MenuLabel::from_str_truncating(text);
     }
 
     /// Get the menu title
     pub fn title(&self) -> &str {
-        core::str::from_utf8(&self.title[..self.title_len]).unwrap_or("")
+        self.title.as_str()
     }
 
     /// Add an item to the menu
@@ -162,6 +423,7 @@ impl Menu {
         if self.item_count < MAX_MENU_ITEMS {
             self.items[self.item_count] = item;
             self.item_count += 1;
+            self.sync_ticker();
             true
         } else {
             false
@@ -172,11 +434,34 @@ impl Menu {
     pub fn clear(&mut self) {
         self.item_count = 0;
         self.selected = 0;
+        self.sync_ticker();
     }
 
     /// Set the menu style
     pub fn set_style(&mut self, style: MenuStyle) {
         self.style = style;
+        self.sync_ticker();
+    }
+
+    /// Advance the selected label's scrolling (call each frame)
+    pub fn tick(&mut self) {
+        self.ticker.tick();
+    }
+
+    /// Where item `index`'s label is drawn
+    fn label_clip(&self, index: usize) -> ClipRect {
+        let style = &self.style;
+        let y = style.padding_top + (index as u32) * style.item_height;
+        let width = self.width.saturating_sub(style.padding_left + 10);
+        ClipRect::new(style.padding_left, y, width, style.item_height)
+    }
+
+    /// Point the ticker at the selected item's label
+    fn sync_ticker(&mut self) {
+        let clip = self.label_clip(self.selected);
+        let label = self.selected_item().map_or(MenuLabel::new(), |item| item.label);
+        self.ticker.set_clip(clip);
+        self.ticker.set_text(label.as_str());
     }
 
     /// Get the currently selected item
@@ -212,6 +497,7 @@ impl Menu {
             }
         }
         self.selected = new_sel;
+        self.sync_ticker();
     }
 
     /// Move selection down
@@ -229,6 +515,7 @@ impl Menu {
             }
         }
         self.selected = new_sel;
+        self.sync_ticker();
     }
 
     /// Render the menu to a display
@@ -239,7 +526,7 @@ impl Menu {
         display.clear(style.bg_color);
 
         // Draw title bar (if present)
-        if self.title_len > 0 {
+        if !self.title.is_empty() {
             display.fill_rect(0, 0, self.width, 30, Color::rgb(40, 40, 60));
         }
 
@@ -270,15 +557,11 @@ impl Menu {
                 style.text_color
             };
 
-            let text_y = y + (style.item_height - 8) / 2;
-            let text_width = (item.label_len as u32) * 8;
-            display.fill_rect(
-                style.padding_left,
-                text_y,
-                text_width.min(self.width - style.padding_left - 10),
-                8,
-                text_color,
-            );
+            if is_selected {
+                self.ticker.render(display, text_color);
+            } else {
+                draw_text_line(display, item.label(), self.label_clip(i), 0, text_color);
+            }
         }
     }
 
@@ -287,3 +570,199 @@ impl Menu {
         self.item_count
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend that records the rectangles drawn on it
+    struct Recorder {
+        rects: [(u32, u32, u32, u32); 32],
+        count: usize,
+    }
+
+    impl Recorder {
+        const fn new() -> Self {
+            Self {
+                rects: [(0, 0, 0, 0); 32],
+                count: 0,
+            }
+        }
+
+        fn rects(&self) -> &[(u32, u32, u32, u32)] {
+            &self.rects[..self.count]
+        }
+    }
+
+    impl DisplayBackend for Recorder {
+        fn width(&self) -> u32 {
+            320
+        }
+
+        fn height(&self) -> u32 {
+            240
+        }
+
+        fn set_pixel(&mut self, _x: u32, _y: u32, _color: Color) -> bool {
+            true
+        }
+
+        fn clear(&mut self, _color: Color) {}
+
+        fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, _color: Color) -> bool {
+            self.rects[self.count] = (x, y, w, h);
+            self.count += 1;
+            true
+        }
+    }
+
+    /// Tick until the ticker has scrolled all the way and holds at the end
+    fn scroll_to_end(ticker: &mut Ticker) {
+        for _ in 0..10_000 {
+            ticker.tick();
+            if ticker.phase == TickerPhase::HoldEnd(ticker.pause) {
+                return;
+            }
+        }
+        panic!("ticker never reached its end");
+    }
+
+    #[test]
+    fn clip_cell_inside_straddling_and_outside() {
+        // Wholly inside the window
+        assert_eq!(clip_cell(8, 7, 0, 40), Some((8, 7)));
+        // Cut by the right edge
+        assert_eq!(clip_cell(36, 7, 0, 40), Some((36, 4)));
+        // Cut by the left edge once scrolled
+        assert_eq!(clip_cell(8, 7, 10, 40), Some((0, 5)));
+        // Scrolled past, not yet reached, or empty
+        assert_eq!(clip_cell(0, 7, 7, 40), None);
+        assert_eq!(clip_cell(40, 7, 0, 40), None);
+        assert_eq!(clip_cell(8, 0, 0, 40), None);
+        assert_eq!(clip_cell(8, 7, 0, 0), None);
+        // No overflow at the far end of the range
+        assert_eq!(clip_cell(u32::MAX - 3, 7, u32::MAX - 3, 2), Some((0, 2)));
+    }
+
+    #[test]
+    fn max_scroll_narrower_equal_and_wider() {
+        assert_eq!(max_scroll(24, 40), 0);
+        assert_eq!(max_scroll(40, 40), 0);
+        assert_eq!(max_scroll(64, 40), 24);
+        assert_eq!(max_scroll(0, 0), 0);
+        assert_eq!(max_scroll(u32::MAX, 0), u32::MAX);
+    }
+
+    #[test]
+    fn step_scroll_stops_at_the_limit() {
+        assert_eq!(step_scroll(0, 3, 10), 3);
+        assert_eq!(step_scroll(9, 3, 10), 10);
+        assert_eq!(step_scroll(10, 3, 10), 10);
+        assert_eq!(step_scroll(u32::MAX - 1, u32::MAX, u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn narrower_text_stays_still() {
+        // 3 glyphs, 24 pixels, in a 40 pixel clip
+        let mut ticker = Ticker::with_text(ClipRect::new(10, 0, 40, 8), "abc");
+        assert!(!ticker.scrolls());
+        for _ in 0..200 {
+            ticker.tick();
+            assert_eq!(ticker.offset(), 0);
+        }
+        let mut display = Recorder::new();
+        ticker.render(&mut display, Color::WHITE);
+        assert_eq!(
+            display.rects(),
+            [(10, 0, 7, 8), (18, 0, 7, 8), (26, 0, 7, 8)]
+        );
+    }
+
+    #[test]
+    fn text_as_wide_as_the_clip_stays_still() {
+        // 5 glyphs, 40 pixels, in a 40 pixel clip
+        let mut ticker = Ticker::with_text(ClipRect::new(0, 0, 40, 8), "abcde");
+        assert!(!ticker.scrolls());
+        for _ in 0..200 {
+            ticker.tick();
+            assert_eq!(ticker.offset(), 0);
+        }
+        let mut display = Recorder::new();
+        ticker.render(&mut display, Color::WHITE);
+        assert_eq!(display.count, 5);
+        assert_eq!(display.rects()[4], (32, 0, 7, 8));
+    }
+
+    #[test]
+    fn wider_text_holds_scrolls_and_jumps_back() {
+        // 8 glyphs, 64 pixels, in a 40 pixel clip: 24 pixels to scroll
+        let mut ticker = Ticker::with_text(ClipRect::new(0, 0, 40, 8), "abcdefgh");
+        ticker.set_speed(5);
+        ticker.set_pause(2);
+        ticker.restart();
+        assert!(ticker.scrolls());
+        // Holds at the start for the pause
+        for _ in 0..3 {
+            ticker.tick();
+            assert_eq!(ticker.offset(), 0);
+        }
+        ticker.tick();
+        assert_eq!(ticker.offset(), 5);
+        scroll_to_end(&mut ticker);
+        // The last step is cut short at the end
+        assert_eq!(ticker.offset(), 24);
+        // Holds at the end, then jumps back to the start
+        for _ in 0..2 {
+            ticker.tick();
+            assert_eq!(ticker.offset(), 24);
+        }
+        ticker.tick();
+        assert_eq!(ticker.offset(), 0);
+    }
+
+    #[test]
+    fn scrolled_text_is_clipped_to_its_rectangle() {
+        let clip = ClipRect::new(100, 50, 40, 8);
+        let mut ticker = Ticker::with_text(clip, "abcdefgh");
+        ticker.set_speed(3);
+        ticker.set_pause(0);
+        ticker.restart();
+        for _ in 0..20 {
+            ticker.tick();
+            let mut display = Recorder::new();
+            ticker.render(&mut display, Color::WHITE);
+            assert!(display.count > 0);
+            for &(x, y, w, h) in display.rects() {
+                assert!(w > 0 && h > 0);
+                assert!(x >= clip.x && x + w <= clip.x + clip.width);
+                assert!(y >= clip.y && y + h <= clip.y + clip.height);
+            }
+        }
+        // At the end the last glyph meets the right edge, and the first
+        // three are scrolled out
+        scroll_to_end(&mut ticker);
+        let mut display = Recorder::new();
+        ticker.render(&mut display, Color::WHITE);
+        assert_eq!(display.rects().last(), Some(&(132, 50, 7, 8)));
+        assert_eq!(display.rects()[0], (100, 50, 7, 8));
+        assert_eq!(display.count, 5);
+    }
+
+    #[test]
+    fn new_text_or_clip_restarts_the_scroll() {
+        let mut ticker = Ticker::with_text(ClipRect::new(0, 0, 40, 8), "abcdefgh");
+        ticker.set_pause(0);
+        ticker.restart();
+        scroll_to_end(&mut ticker);
+        assert_eq!(ticker.offset(), 24);
+        // Unchanged text keeps its place
+        ticker.set_text("abcdefgh");
+        assert_eq!(ticker.offset(), 24);
+        ticker.set_text("abcdefghij");
+        assert_eq!(ticker.offset(), 0);
+        scroll_to_end(&mut ticker);
+        ticker.set_clip(ClipRect::new(0, 0, 80, 8));
+        assert_eq!(ticker.offset(), 0);
+        assert!(!ticker.scrolls());
+    }
+}
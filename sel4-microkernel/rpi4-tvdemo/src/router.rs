@@ -0,0 +1,587 @@
+//! Display router for driving several displays at once
+//!
+//! A board can have the HDMI framebuffer and the SPI LCD attached together,
+//! each with a job: photos on the big screen, status and controls on the
+//! small one. Each display's backend is registered under the
+//! [`DisplayRole`]s it serves, and drawing code asks for a role rather than
+//! a particular display:
+//!
+//! ```no_run
+//! use rpi4_tvdemo::{Color, DamageRect, DisplayBackend, DisplayRole, DisplayRouter, RouteError};
+//!
+//! fn frame(hdmi: &mut dyn DisplayBackend, lcd: &mut dyn DisplayBackend) -> Result<(), RouteError> {
+//!     let mut router = DisplayRouter::new();
+//!     router.register("hdmi", hdmi, &[DisplayRole::Content])?;
+//!     router.register("lcd", lcd, &[DisplayRole::Status])?;
+//!
+//!     if let Some(mut status) = router.route(DisplayRole::Status) {
+//!         status.fill_rect(0, 0, 320, 24, Color::DARK_GRAY);
+//!     }
+//!     // Push only what changed, per display
+//!     router.flush(|_, backend, damage| refresh(backend, damage));
+//!     Ok(())
+//! }
+//! # fn refresh(_: &mut dyn DisplayBackend, _: DamageRect) {}
+//! ```
+//!
+//! A role nobody registered falls back to the first display, so the same
+//! application runs unchanged with only one screen attached. Every display
+//! keeps its own damage rectangle, grown by whatever is drawn through the
+//! router, so a slow SPI panel is only refreshed when something on it
+//! changed, and only over the part that did.
+
+use crate::backend::{Color, DisplayBackend};
+
+/// Displays a router holds at most
+pub const MAX_DISPLAYS: usize = 4;
+
+/// What a display is used for
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisplayRole {
+    /// The main picture: photos, video, the TV menu
+    Content,
+    /// Status lines and on-screen controls
+    Status,
+}
+
+impl DisplayRole {
+    const fn bit(self) -> u8 {
+        match self {
+            DisplayRole::Content => 1 << 0,
+            DisplayRole::Status => 1 << 1,
+        }
+    }
+}
+
+/// A registered display, in registration order
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DisplayId(u8);
+
+impl DisplayId {
+    /// Position in registration order
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Why a display could not be registered
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RouteError {
+    /// [`MAX_DISPLAYS`] are already registered
+    Full,
+    /// Another display already serves this role
+    RoleTaken(DisplayRole),
+}
+
+/// Bounding box of everything drawn on a display since it was last flushed
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DamageRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl DamageRect {
+    pub const fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    /// Grow to cover `x, y, w, h` as clipped to a `width` x `height` screen
+    fn add(&mut self, x: u32, y: u32, w: u32, h: u32, width: u32, height: u32) {
+        if x >= width || y >= height || w == 0 || h == 0 {
+            return;
+        }
+        let right = x.saturating_add(w).min(width);
+        let bottom = y.saturating_add(h).min(height);
+        if self.is_empty() {
+            *self = Self {
+                x,
+                y,
+                w: right - x,
+                h: bottom - y,
+            };
+            return;
+        }
+        let left = self.x.min(x);
+        let top = self.y.min(y);
+        let right = (self.x + self.w).max(right);
+        let bottom = (self.y + self.h).max(bottom);
+        *self = Self {
+            x: left,
+            y: top,
+            w: right - left,
+            h: bottom - top,
+        };
+    }
+}
+
+struct Slot<'a> {
+    name: &'static str,
+    roles: u8,
+    backend: &'a mut dyn DisplayBackend,
+    damage: DamageRect,
+}
+
+/// Backends for every attached display, picked by role
+pub struct DisplayRouter<'a> {
+    slots: [Option<Slot<'a>>; MAX_DISPLAYS],
+    len: usize,
+}
+
+impl<'a> Default for DisplayRouter<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> DisplayRouter<'a> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { None }; MAX_DISPLAYS],
+            len: 0,
+        }
+    }
+
+    /// Add a display serving `roles`. A display may serve several roles; a
+    /// role is served by one display at most.
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        backend: &'a mut dyn DisplayBackend,
+        roles: &[DisplayRole],
+    ) -> Result<DisplayId, RouteError> {
+        if self.len >= MAX_DISPLAYS {
+            return Err(RouteError::Full);
+        }
+        let mut mask = 0;
+        for &role in roles {
+            if self.owner(role).is_some() {
+                return Err(RouteError::RoleTaken(role));
+            }
+            mask |= role.bit();
+        }
+        let id = DisplayId(self.len as u8);
+        self.slots[self.len] = Some(Slot {
+            name,
+            roles: mask,
+            backend,
+            damage: DamageRect::default(),
+        });
+        self.len += 1;
+        Ok(id)
+    }
+
+    /// Displays registered
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The display serving `role`, or the first display if none was
+    /// registered for it
+    pub fn display_for(&self, role: DisplayRole) -> Option<DisplayId> {
+        self.owner(role).or(if self.len > 0 {
+            Some(DisplayId(0))
+        } else {
+            None
+        })
+    }
+
+    /// Draw on the display serving `role`
+    pub fn route(&mut self, role: DisplayRole) -> Option<Routed<'_, 'a>> {
+        let id = self.display_for(role)?;
+        self.display(id)
+    }
+
+    /// Draw on one display
+    pub fn display(&mut self, id: DisplayId) -> Option<Routed<'_, 'a>> {
+        let slot = self.slots.get_mut(id.index())?.as_mut()?;
+        Some(Routed {
+            backend: &mut *slot.backend,
+            damage: &mut slot.damage,
+        })
+    }
+
+    /// Name a display was registered under
+    pub fn name(&self, id: DisplayId) -> Option<&'static str> {
+        self.slot(id).map(|slot| slot.name)
+    }
+
+    /// A display's size in pixels
+    pub fn size(&self, id: DisplayId) -> Option<(u32, u32)> {
+        self.slot(id)
+            .map(|slot| (slot.backend.width(), slot.backend.height()))
+    }
+
+    /// A display's damage so far, left in place
+    pub fn damage(&self, id: DisplayId) -> Option<DamageRect> {
+        self.slot(id)
+            .map(|slot| slot.damage)
+            .filter(|damage| !damage.is_empty())
+    }
+
+    /// A display's damage so far, leaving it undamaged
+    pub fn take_damage(&mut self, id: DisplayId) -> Option<DamageRect> {
+        let slot = self.slots.get_mut(id.index())?.as_mut()?;
+        let damage = core::mem::take(&mut slot.damage);
+        if damage.is_empty() {
+            None
+        } else {
+            Some(damage)
+        }
+    }
+
+    /// Hand every damaged display to `present` with its damage, then mark
+    /// it undamaged. Returns how many displays were presented.
+    pub fn flush(
+        &mut self,
+        mut present: impl FnMut(DisplayId, &mut dyn DisplayBackend, DamageRect),
+    ) -> usize {
+        let mut presented = 0;
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            let Some(slot) = slot else { continue };
+            let damage = core::mem::take(&mut slot.damage);
+            if damage.is_empty() {
+                continue;
+            }
+            present(DisplayId(i as u8), &mut *slot.backend, damage);
+            presented += 1;
+        }
+        presented
+    }
+
+    fn owner(&self, role: DisplayRole) -> Option<DisplayId> {
+        self.slots
+            .iter()
+            .take(self.len)
+            .position(|slot| {
+                slot.as_ref()
+                    .is_some_and(|slot| slot.roles & role.bit() != 0)
+            })
+            .map(|i| DisplayId(i as u8))
+    }
+
+    fn slot(&self, id: DisplayId) -> Option<&Slot<'a>> {
+        self.slots.get(id.index())?.as_ref()
+    }
+}
+
+/// One display borrowed from a [`DisplayRouter`]; drawing through it
+/// records damage on that display
+pub struct Routed<'r, 'a> {
+    backend: &'r mut (dyn DisplayBackend + 'a),
+    damage: &'r mut DamageRect,
+}
+
+impl Routed<'_, '_> {
+    fn mark(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        let (width, height) = (self.backend.width(), self.backend.height());
+        self.damage.add(x, y, w, h, width, height);
+    }
+}
+
+impl DisplayBackend for Routed<'_, '_> {
+    fn width(&self) -> u32 {
+        self.backend.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.backend.height()
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: Color) -> bool {
+        let drawn = self.backend.set_pixel(x, y, color);
+        if drawn {
+            self.mark(x, y, 1, 1);
+        }
+        drawn
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.backend.clear(color);
+        self.mark(0, 0, u32::MAX, u32::MAX);
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) -> bool {
+        let drawn = self.backend.fill_rect(x, y, w, h, color);
+        if drawn {
+            self.mark(x, y, w, h);
+        }
+        drawn
+    }
+
+    // Forwarded so a backend's faster line and shape drawing is kept
+
+    fn hline(&mut self, x: u32, y: u32, len: u32, color: Color) {
+        self.backend.hline(x, y, len, color);
+        self.mark(x, y, len, 1);
+    }
+
+    fn vline(&mut self, x: u32, y: u32, len: u32, color: Color) {
+        self.backend.vline(x, y, len, color);
+        self.mark(x, y, 1, len);
+    }
+
+    fn draw_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) {
+        self.backend.draw_rect(x, y, w, h, color);
+        self.mark(x, y, w, h);
+    }
+
+    fn fill_circle(&mut self, cx: i32, cy: i32, radius: u32, color: Color) {
+        self.backend.fill_circle(cx, cy, radius, color);
+        let r = i32::try_from(radius).unwrap_or(i32::MAX);
+        let left = cx.saturating_sub(r).max(0);
+        let top = cy.saturating_sub(r).max(0);
+        let right = cx.saturating_add(r).saturating_add(1);
+        let bottom = cy.saturating_add(r).saturating_add(1);
+        if right > left && bottom > top {
+            self.mark(
+                left as u32,
+                top as u32,
+                (right - left) as u32,
+                (bottom - top) as u32,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend that only knows its size
+    struct Panel {
+        width: u32,
+        height: u32,
+    }
+
+    impl Panel {
+        const fn new(width: u32, height: u32) -> Self {
+            Self { width, height }
+        }
+    }
+
+    impl DisplayBackend for Panel {
+        fn width(&self) -> u32 {
+            self.width
+        }
+
+        fn height(&self) -> u32 {
+            self.height
+        }
+
+        fn set_pixel(&mut self, x: u32, y: u32, _color: Color) -> bool {
+            x < self.width && y < self.height
+        }
+
+        fn clear(&mut self, _color: Color) {}
+
+        fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, _color: Color) -> bool {
+            x.saturating_add(w) <= self.width && y.saturating_add(h) <= self.height
+        }
+
+        fn fill_circle(&mut self, _cx: i32, _cy: i32, _radius: u32, _color: Color) {}
+    }
+
+    #[test]
+    fn damage_grows_to_the_union() {
+        let mut damage = DamageRect::default();
+        assert!(damage.is_empty());
+        damage.add(10, 20, 5, 5, 320, 240);
+        assert_eq!(
+            damage,
+            DamageRect {
+                x: 10,
+                y: 20,
+                w: 5,
+                h: 5
+            }
+        );
+        damage.add(100, 5, 10, 10, 320, 240);
+        assert_eq!(
+            damage,
+            DamageRect {
+                x: 10,
+                y: 5,
+                w: 100,
+                h: 20
+            }
+        );
+        // Contained: no change
+        damage.add(12, 10, 1, 1, 320, 240);
+        assert_eq!(
+            damage,
+            DamageRect {
+                x: 10,
+                y: 5,
+                w: 100,
+                h: 20
+            }
+        );
+    }
+
+    #[test]
+    fn damage_ignores_empty_and_clips_to_the_screen() {
+        let mut damage = DamageRect::default();
+        damage.add(10, 10, 0, 5, 320, 240);
+        damage.add(10, 10, 5, 0, 320, 240);
+        damage.add(320, 0, 5, 5, 320, 240);
+        damage.add(0, 240, 5, 5, 320, 240);
+        assert!(damage.is_empty());
+
+        damage.add(300, 230, u32::MAX, u32::MAX, 320, 240);
+        assert_eq!(
+            damage,
+            DamageRect {
+                x: 300,
+                y: 230,
+                w: 20,
+                h: 10
+            }
+        );
+    }
+
+    #[test]
+    fn roles_fall_back_to_the_first_display() {
+        let mut hdmi = Panel::new(1280, 720);
+        let mut router = DisplayRouter::new();
+        assert_eq!(router.display_for(DisplayRole::Status), None);
+        assert!(router.route(DisplayRole::Status).is_none());
+
+        let id = router
+            .register("hdmi", &mut hdmi, &[DisplayRole::Content])
+            .unwrap();
+        assert_eq!(router.display_for(DisplayRole::Content), Some(id));
+        assert_eq!(router.display_for(DisplayRole::Status), Some(id));
+        assert_eq!(
+            router
+                .route(DisplayRole::Status)
+                .map(|status| status.width()),
+            Some(1280)
+        );
+    }
+
+    #[test]
+    fn a_role_has_one_display() {
+        let mut hdmi = Panel::new(1280, 720);
+        let mut lcd = Panel::new(320, 240);
+        let mut spare = Panel::new(320, 240);
+        let mut router = DisplayRouter::new();
+        let hdmi_id = router
+            .register("hdmi", &mut hdmi, &[DisplayRole::Content])
+            .unwrap();
+        let lcd_id = router
+            .register("lcd", &mut lcd, &[DisplayRole::Status])
+            .unwrap();
+        assert_eq!(
+            router
+                .register("spare", &mut spare, &[DisplayRole::Status])
+                .err(),
+            Some(RouteError::RoleTaken(DisplayRole::Status))
+        );
+        assert_eq!(router.len(), 2);
+        assert_eq!(router.display_for(DisplayRole::Content), Some(hdmi_id));
+        assert_eq!(router.display_for(DisplayRole::Status), Some(lcd_id));
+        assert_eq!(router.name(lcd_id), Some("lcd"));
+        assert_eq!(router.size(lcd_id), Some((320, 240)));
+    }
+
+    #[test]
+    fn registration_stops_at_max_displays() {
+        let mut panels = [const { Panel::new(320, 240) }; MAX_DISPLAYS + 1];
+        let mut router = DisplayRouter::new();
+        let (last, first) = panels.split_last_mut().unwrap();
+        for panel in first {
+            assert!(router.register("panel", panel, &[]).is_ok());
+        }
+        assert_eq!(
+            router.register("extra", last, &[]).err(),
+            Some(RouteError::Full)
+        );
+    }
+
+    #[test]
+    fn flush_presents_each_damaged_display_once() {
+        let mut hdmi = Panel::new(1280, 720);
+        let mut lcd = Panel::new(320, 240);
+        let mut router = DisplayRouter::new();
+        let hdmi_id = router
+            .register("hdmi", &mut hdmi, &[DisplayRole::Content])
+            .unwrap();
+        let lcd_id = router
+            .register("lcd", &mut lcd, &[DisplayRole::Status])
+            .unwrap();
+
+        let mut status = router.route(DisplayRole::Status).unwrap();
+        assert!(status.fill_rect(0, 0, 320, 24, Color::DARK_GRAY));
+        // Refused by the backend: no damage
+        assert!(!status.fill_rect(0, 230, 320, 24, Color::DARK_GRAY));
+        status.hline(10, 100, 20, Color::WHITE);
+        assert_eq!(router.damage(hdmi_id), None);
+        assert_eq!(
+            router.damage(lcd_id),
+            Some(DamageRect {
+                x: 0,
+                y: 0,
+                w: 320,
+                h: 101
+            })
+        );
+
+        let mut presented = [None; MAX_DISPLAYS];
+        assert_eq!(
+            router.flush(|id, _, damage| presented[id.index()] = Some(damage)),
+            1
+        );
+        assert_eq!(presented[hdmi_id.index()], None);
+        assert_eq!(
+            presented[lcd_id.index()],
+            Some(DamageRect {
+                x: 0,
+                y: 0,
+                w: 320,
+                h: 101
+            })
+        );
+        assert_eq!(router.flush(|_, _, _| {}), 0);
+
+        router
+            .route(DisplayRole::Content)
+            .unwrap()
+            .clear(Color::BLACK);
+        assert_eq!(
+            router.take_damage(hdmi_id),
+            Some(DamageRect {
+                x: 0,
+                y: 0,
+                w: 1280,
+                h: 720
+            })
+        );
+        assert_eq!(router.take_damage(hdmi_id), None);
+    }
+
+    #[test]
+    fn huge_circles_do_not_wrap() {
+        let mut lcd = Panel::new(320, 240);
+        let mut router = DisplayRouter::new();
+        let id = router.register("lcd", &mut lcd, &[]).unwrap();
+        router
+            .display(id)
+            .unwrap()
+            .fill_circle(10, 10, u32::MAX, Color::RED);
+        assert_eq!(
+            router.damage(id),
+            Some(DamageRect {
+                x: 0,
+                y: 0,
+                w: 320,
+                h: 240
+            })
+        );
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::backend::{DisplayBackend, Color};
 use crate::animation::{AnimationPlayer, AnimationType};
-use crate::menu::{Menu, MenuItem, MenuStyle};
+use crate::menu::{ClipRect, Menu, MenuItem, MenuStyle, Ticker};
 use rpi4_input::{InputEvent, KeyCode, KeyState, IrButton, TouchEvent};
 
 /// Demo application state
@@ -47,6 +47,17 @@ mod menu_ids {
     pub const SETTING_BACK: u8 = 29;
 }
 
+/// Lines of the About screen, top to bottom; lines wider than the screen
+/// scroll
+const ABOUT_LINES: [&str; 3] = [
+    "TV Demo",
+    "Running as isolated protection domains on the seL4 microkernel, Raspberry Pi 4",
+    "Display, input and rendering paths checked with Verus; press Back to return to the menu",
+];
+
+/// Horizontal margin of the About text, in pixels
+const ABOUT_MARGIN: u32 = 20;
+
 /// TV Demo application
 pub struct TvDemo {
     /// Current state
@@ -59,6 +70,8 @@ pub struct TvDemo {
     anim_menu: Menu,
     /// Settings menu
     settings_menu: Menu,
+    /// About screen text
+    about_lines: [Ticker; ABOUT_LINES.len()],
     /// Animation player
     player: AnimationPlayer,
     /// Show overlay (controls hint)
@@ -81,6 +94,7 @@ impl TvDemo {
             main_menu: Menu::new(width, height),
             anim_menu: Menu::new(width, height),
             settings_menu: Menu::new(width, height),
+            about_lines: Self::about_lines(width, height),
             player: AnimationPlayer::new(width, height),
             show_overlay: false,
             overlay_timer: 0,
@@ -118,6 +132,15 @@ impl TvDemo {
         self.apply_theme();
     }
 
+    /// About screen tickers, at the rows the placeholder text used to sit
+    fn about_lines(width: u32, height: u32) -> [Ticker; ABOUT_LINES.len()] {
+        let rows = [height / 3, height / 2, height * 3 / 4];
+        let line_width = width.saturating_sub(2 * ABOUT_MARGIN);
+        core::array::from_fn(|i| {
+            Ticker::with_text(ClipRect::new(ABOUT_MARGIN, rows[i], line_width, 10), ABOUT_LINES[i])
+        })
+    }
+
     /// Apply current theme to menus
     fn apply_theme(&mut self) {
         let style = if self.dark_theme {
@@ -314,7 +337,10 @@ impl TvDemo {
                     self.screen = Screen::Settings;
                     self.state = DemoState::Settings;
                 }
-                menu_ids::ABOUT => self.screen = Screen::About,
+                menu_ids::ABOUT => {
+                    self.about_lines.iter_mut().for_each(Ticker::restart);
+                    self.screen = Screen::About;
+                }
 
                 menu_ids::ANIM_BOUNCING_BALL => {
                     self.player.play(AnimationType::BouncingBall);
@@ -392,6 +418,14 @@ impl TvDemo {
         if self.state == DemoState::Playing {
             self.player.update();
         }
+
+        match self.screen {
+            Screen::MainMenu => self.main_menu.tick(),
+            Screen::AnimationSelect => self.anim_menu.tick(),
+            Screen::Settings => self.settings_menu.tick(),
+            Screen::About => self.about_lines.iter_mut().for_each(Ticker::tick),
+            Screen::NowPlaying => {}
+        }
     }
 
     /// Render current view to display
@@ -427,10 +461,10 @@ impl TvDemo {
         display.fill_rect(0, 0, self.width, 30, Color::rgb(40, 80, 160));
 
         // Text placeholders
-        let cx = self.width / 2;
-        display.fill_rect(cx - 60, self.height / 3, 120, 10, fg);
-        display.fill_rect(cx - 80, self.height / 2, 160, 10, fg);
-        display.fill_rect(cx - 90, self.height * 3 / 4, 180, 10, Color::GRAY);
+        let colors = [fg, fg, Color::GRAY];
+        for (line, color) in self.about_lines.iter().zip(colors) {
+            line.render(display, color);
+        }
     }
 
     /// Render playback overlay with controls
@@ -0,0 +1,168 @@
+//! # Watchdog Protection Domain
+//!
+//! Resets the board when a PD it watches stops running. The BCM2711 power
+//! manager's watchdog is mapped into this PD and no other; every PD
+//! registered here pings it on a channel of its own, and the hardware is
+//! fed only while each has pinged within its deadline window
+//! (`rpi4-watchdog-protocol`). What it sees is published on the liveness
+//! page, which the Photoframe PD maps read-only for its health panel:
+//!
+//! ```text
+//! ┌────────────┐  ping (notify)  ┌─────────────┐  PM_WDOG / PM_RSTC  ┌────────────┐
+//! │ Timer PD   │ ──────────────► │ Watchdog PD │ ──────────────────► │ BCM2711 PM │
+//! │ Photoframe │ ──────────────► │             │ ◄── C3, IRQ 99 ──── │ sys timer  │
+//! └────────────┘                 └──────┬──────┘                     └────────────┘
+//!       ▲   liveness_page (rw / r)      │
+//!       └───────────────────────────────┘
+//! ```
+//!
+//! The checks run on system timer compare channel 3, `CHECK_HZ` times a
+//! second. A client that misses its window has the board reset at once;
+//! if this PD itself stops, nothing feeds the hardware and it resets the
+//! board after `HW_TIMEOUT_MS`. Runs at a higher priority than its
+//! clients, so a busy client cannot hold off the check that would catch
+//! it.
+//!
+//! ## Security Properties
+//!
+//! 1. **Isolated Access**: only this PD can reach the power manager; no
+//!    client can reset the board, or keep it from being reset
+//! 2. **Identity by Channel**: a ping counts for the client whose channel
+//!    it came in on, so one PD cannot ping for another
+//! 3. **No Data In**: a ping is a notification and carries nothing, so
+//!    there is nothing for a client to get wrong
+//! 4. **No Callbacks**: this PD cannot call or notify anyone; a
+//!    compromised Watchdog PD can reset the board or fail to, and nothing
+//!    more
+
+#![no_std]
+#![no_main]
+
+mod pm;
+
+use sel4_microkit::{debug_println, protection_domain, Channel, ChannelSet, Handler};
+use rpi4_graphics::frame_timer::{read_counter, Compare};
+use rpi4_graphics::FrameTimer;
+use rpi4_pd_error::{ErrorDomain, ErrorKind, PdError};
+use rpi4_watchdog_protocol::{
+    client_for_channel, Heartbeats, LivenessPage, CHECK_HZ, HW_TIMEOUT_MS, LIVENESS_PAGE_VADDR,
+    WATCHDOG_IRQ_CHANNEL_ID, WATCHDOG_PD_PM_BASE, WATCHDOG_PD_SYSTIMER_BASE,
+};
+
+use pm::PmWatchdog;
+
+/// System timer compare 3 IRQ (must match photoframe.system)
+const IRQ_CHANNEL: Channel = Channel::new(WATCHDOG_IRQ_CHANNEL_ID);
+
+/// The PDs watched: slot (pinging on channel `FIRST_CLIENT_CHANNEL_ID +
+/// slot`, which must match photoframe.system), name and window. The Timer
+/// PD pings from its own tick and should never be late; the Photoframe PD
+/// pings from the tick it handles, and a large photo can keep it from the
+/// next one for a few seconds.
+const CLIENTS: [(usize, &str, u32); 2] = [(0, "TIMER", 2_000), (1, "PHOTO", 10_000)];
+
+struct WatchdogPd {
+    hw: PmWatchdog,
+    beats: Heartbeats,
+    /// The checks; this PD is the only user of compare channel 3
+    checker: FrameTimer,
+}
+
+impl WatchdogPd {
+    /// Microseconds since the system timer started
+    fn now_us(&self) -> u64 {
+        // Safety: photoframe.system maps the system timer registers here
+        unsafe { read_counter(WATCHDOG_PD_SYSTIMER_BASE) }
+    }
+
+    fn publish(&self) {
+        // Safety: the liveness page is mapped here, and this PD is its only
+        // writer
+        unsafe { LivenessPage::publish(LIVENESS_PAGE_VADDR as *mut u8, self.beats.liveness()) };
+    }
+
+    /// Feed the hardware if every client is live, else reset the board
+    fn check(&mut self) {
+        let now_us = self.now_us();
+        match self.beats.check(now_us) {
+            None => {
+                self.hw.feed(HW_TIMEOUT_MS);
+                self.beats.fed();
+                self.publish();
+            }
+            Some(slot) => {
+                self.publish();
+                let client = &self.beats.liveness().clients[slot];
+                debug_println!(
+                    "Watchdog PD: {} missed its {} ms window (last ping {} ms ago, {} pings); resetting",
+                    client.name(), client.window_ms, client.ping_age_ms(now_us), client.pings
+                );
+                self.hw.reset_now();
+            }
+        }
+    }
+}
+
+impl Handler for WatchdogPd {
+    type Error = PdError;
+
+    fn notified(&mut self, channels: ChannelSet) -> Result<(), Self::Error> {
+        // Pings first, so a ping that arrives with the check counts for it
+        let now_us = self.now_us();
+        for channel in channels.iter() {
+            if let Some(slot) = client_for_channel(channel.index()) {
+                self.beats.ping(slot, now_us);
+            }
+        }
+        // Clear the match before acking, or the level IRQ refires at once
+        if channels.contains(IRQ_CHANNEL) {
+            if self.checker.handle_irq() {
+                self.check();
+            }
+            IRQ_CHANNEL.irq_ack().map_err(|_| {
+                PdError::new(ErrorDomain::Kernel, ErrorKind::Failed).context("acking watchdog timer IRQ")
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[protection_domain]
+fn init() -> WatchdogPd {
+    debug_println!("");
+    debug_println!("========================================");
+    debug_println!("  Watchdog Protection Domain Starting");
+    debug_println!("========================================");
+    debug_println!("");
+    debug_println!("Watchdog PD: Power manager at 0x{:x}", WATCHDOG_PD_PM_BASE);
+    debug_println!("Watchdog PD: Liveness page at 0x{:x}", LIVENESS_PAGE_VADDR);
+
+    // Safety: photoframe.system maps the power manager page here and into
+    // no other PD
+    let hw = unsafe { PmWatchdog::new(WATCHDOG_PD_PM_BASE) };
+    if hw.caused_last_reset() {
+        debug_println!("Watchdog PD: The last reset was the watchdog's");
+    }
+
+    let mut beats = Heartbeats::new();
+    // Safety: photoframe.system maps the system timer page here
+    let start_us = unsafe { read_counter(WATCHDOG_PD_SYSTIMER_BASE) };
+    for (slot, name, window_ms) in CLIENTS {
+        if let Err(e) = beats.register(slot, name, window_ms, start_us) {
+            panic!("Watchdog PD: registering {}: {}", name, e);
+        }
+    }
+
+    // Armed before the first check, so a hang from here on is caught too
+    hw.feed(HW_TIMEOUT_MS);
+    // Safety: photoframe.system maps the system timer page here; the Timer
+    // PD, the only other PD given it writable, uses compare channel 1
+    let checker = unsafe { FrameTimer::start_on(WATCHDOG_PD_SYSTIMER_BASE, Compare::C3, CHECK_HZ) };
+    let pd = WatchdogPd { hw, beats, checker };
+    pd.publish();
+    debug_println!(
+        "Watchdog PD: Ready, {} clients, checking at {} Hz, hardware timeout {} ms ({} ms left)",
+        CLIENTS.len(), CHECK_HZ, HW_TIMEOUT_MS, pd.hw.remaining_ms()
+    );
+    pd
+}
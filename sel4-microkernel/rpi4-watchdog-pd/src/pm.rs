@@ -0,0 +1,81 @@
+//! BCM2711 power manager watchdog
+//!
+//! The power manager's watchdog counts `PM_WDOG` down at 65536 Hz once
+//! `PM_RSTC` is set for a full reset, and resets the board when it reaches
+//! zero. Feeding it is arming it again with a fresh count. Every write
+//! must carry [`PM_PASSWORD`] in its top byte or the block ignores it.
+//! The sequence follows Linux's `bcm2835_wdt`.
+
+use core::ptr::{read_volatile, write_volatile};
+use rpi4_watchdog_protocol::{wdog_ticks, PM_PASSWORD, WDOG_TICKS_PER_S, WDOG_TIME_MASK};
+
+/// Power manager registers
+mod regs {
+    /// Reset control
+    pub const RSTC: usize = 0x1c;
+    /// Reset status: why the board last reset
+    pub const RSTS: usize = 0x20;
+    /// Watchdog count
+    pub const WDOG: usize = 0x24;
+    /// Bits below the password byte
+    pub const VALUE_MASK: u32 = 0x00ff_ffff;
+    /// RSTC with the reset configuration bits cleared
+    pub const RSTC_WRCFG_CLR: u32 = 0xffff_ffcf;
+    /// RSTC reset configuration: full reset when the count runs out
+    pub const RSTC_WRCFG_FULL_RESET: u32 = 0x0000_0020;
+    /// RSTS: the last reset was the watchdog's
+    pub const RSTS_HADWRH: u32 = 0x0000_0040;
+}
+
+/// The watchdog, through the power manager register page
+pub struct PmWatchdog {
+    base: usize,
+}
+
+impl PmWatchdog {
+    /// # Safety
+    /// `base` must be the mapped power manager register page, and no other
+    /// code may use it.
+    pub unsafe fn new(base: usize) -> Self {
+        Self { base }
+    }
+
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { read_volatile((self.base + offset) as *const u32) }
+    }
+
+    fn write(&self, offset: usize, value: u32) {
+        unsafe { write_volatile((self.base + offset) as *mut u32, PM_PASSWORD | value) }
+    }
+
+    /// Did the watchdog cause the last reset?
+    pub fn caused_last_reset(&self) -> bool {
+        self.read(regs::RSTS) & regs::RSTS_HADWRH != 0
+    }
+
+    /// Arm the watchdog to reset the board in `timeout_ms`, or give it that
+    /// long again if it is armed; `false`, leaving it as it was, for a
+    /// timeout the count cannot hold
+    pub fn feed(&self, timeout_ms: u32) -> bool {
+        let Some(ticks) = wdog_ticks(timeout_ms) else {
+            return false;
+        };
+        self.write(regs::WDOG, ticks & WDOG_TIME_MASK);
+        let rstc = self.read(regs::RSTC) & regs::RSTC_WRCFG_CLR & regs::VALUE_MASK;
+        self.write(regs::RSTC, rstc | regs::RSTC_WRCFG_FULL_RESET);
+        true
+    }
+
+    /// Reset the board now: arm the shortest count and wait for it
+    pub fn reset_now(&self) -> ! {
+        self.feed(1);
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Milliseconds left before the watchdog resets the board, if armed
+    pub fn remaining_ms(&self) -> u32 {
+        (self.read(regs::WDOG) & WDOG_TIME_MASK) * 1000 / WDOG_TICKS_PER_S
+    }
+}
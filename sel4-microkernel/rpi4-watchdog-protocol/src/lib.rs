@@ -0,0 +1,622 @@
+//! Verified heartbeat protocol between the Watchdog PD and the PDs it
+//! watches.
+//!
+//! The BCM2711 power manager has a watchdog: once armed, it resets the
+//! board unless it is fed again within its timeout. The Watchdog PD is the
+//! only PD that maps it. Every PD registered with it pings it, by notifying
+//! on a channel of its own, at least every [`PING_INTERVAL_MS`]; the
+//! Watchdog PD feeds the hardware only while every registered PD has pinged
+//! within its deadline window, and publishes what it saw on the liveness
+//! page, which the display maps read-only for a health panel:
+//!
+//! ```text
+//! ┌────────────┐  ping (notify)  ┌─────────────┐  PM_WDOG / PM_RSTC  ┌──────────────┐
+//! │ Timer PD   │ ──────────────► │ Watchdog PD │ ──────────────────► │ BCM2711 PM   │
+//! │ Photoframe │ ──────────────► │             │                     │ (full reset) │
+//! └────────────┘                 └──────┬──────┘                     └──────────────┘
+//!       ▲   liveness_page (rw / r)      │
+//!       └───────────────────────────────┘
+//! ```
+//!
+//! A client that misses its window gets the board reset at once. If the
+//! Watchdog PD itself stops running, nothing feeds the hardware and it
+//! resets the board after [`HW_TIMEOUT_MS`].
+//!
+//! ## Timing
+//!
+//! The Watchdog PD wakes [`CHECK_HZ`] times a second on system timer
+//! compare channel 3, checks every client's last ping against its window
+//! and feeds the hardware. Every window is at least
+//! [`MIN_WINDOW_MS`], comfortably more than a ping interval, and the
+//! hardware timeout is several check periods long, so a live system is
+//! never reset and a dead client is caught within its window plus one
+//! check period.
+//!
+//! ## The liveness page
+//!
+//! The page holds a [`Liveness`]: the feeds so far and, per client slot,
+//! its name, window, pings so far, the system timer reading of its last
+//! ping and its [`ClientState`]. It is a sequence lock like the clock page
+//! (`rpi4-clock-protocol`): the writer makes `seq` odd, writes, and makes
+//! it even again; a reader retries a torn read at most [`READ_ATTEMPTS`]
+//! times.
+//!
+//! ## Verification
+//!
+//! [`is_live`] matches its definition for any pair of timer readings,
+//! including a ping that lands after the reading it is checked against.
+//! [`wdog_ticks`] is proven to fit the 20-bit timer field and never to be
+//! zero (zero would reset the board at once), and [`client_for_channel`] to
+//! name a real slot, and only for the channel that slot pings on. The
+//! isolation proofs at the end show the liveness page is all a reader is
+//! granted.
+
+#![no_std]
+#![allow(unused)]
+// Verus proves plain comparisons; `RangeInclusive::contains` it does not know
+#![allow(clippy::manual_range_contains)]
+
+use core::fmt;
+use core::ptr::{addr_of, addr_of_mut, read_volatile, write_volatile};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+use verus_builtin_macros::verus;
+
+verus! {
+
+/// Client slots on the liveness page
+pub const MAX_CLIENTS: usize = 4;
+
+/// Watchdog PD end of slot 0's ping channel; slot `i` pings on
+/// `FIRST_CLIENT_CHANNEL_ID + i`
+pub const FIRST_CLIENT_CHANNEL_ID: usize = 1;
+/// Watchdog PD's system timer compare 3 IRQ (GIC SPI 67)
+pub const WATCHDOG_IRQ_CHANNEL_ID: usize = 0;
+
+/// Client end of the ping channel
+pub const CLIENT_WATCHDOG_CHANNEL_ID: usize = 5;
+
+/// The slot a ping on the Watchdog PD's `channel` is from
+pub fn client_for_channel(channel: usize) -> (c: Option<usize>)
+    ensures
+        c.is_some() <==> FIRST_CLIENT_CHANNEL_ID <= channel && channel < FIRST_CLIENT_CHANNEL_ID + MAX_CLIENTS,
+        c.is_some() ==> c.unwrap() < MAX_CLIENTS && c.unwrap() + FIRST_CLIENT_CHANNEL_ID == channel,
+{
+    if channel >= FIRST_CLIENT_CHANNEL_ID && channel - FIRST_CLIENT_CHANNEL_ID < MAX_CLIENTS {
+        Some(channel - FIRST_CLIENT_CHANNEL_ID)
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// MEMORY REGIONS
+// ============================================================================
+
+/// Liveness page: written by the Watchdog PD, read by the display
+pub const LIVENESS_PAGE_VADDR: usize = 0x5_0F00_0000;
+pub const LIVENESS_PAGE_SIZE: usize = 0x1000;
+
+/// Power manager registers (PM_RSTC, PM_RSTS, PM_WDOG), mapped into the
+/// Watchdog PD only
+pub const WATCHDOG_PD_PM_BASE: usize = 0x5_1000_0000;
+pub const WATCHDOG_PD_PM_SIZE: usize = 0x1000;
+
+/// System timer registers, mapped into the Watchdog PD: read/write, since
+/// it programs compare channel 3 for its checks
+pub const WATCHDOG_PD_SYSTIMER_BASE: usize = 0x5_0A00_0000;
+pub const WATCHDOG_PD_SYSTIMER_SIZE: usize = 0x1000;
+
+pub open spec fn in_liveness_page_region(addr: usize) -> bool {
+    addr >= LIVENESS_PAGE_VADDR && addr < LIVENESS_PAGE_VADDR + LIVENESS_PAGE_SIZE
+}
+
+pub open spec fn in_pm_region(addr: usize) -> bool {
+    addr >= WATCHDOG_PD_PM_BASE && addr < WATCHDOG_PD_PM_BASE + WATCHDOG_PD_PM_SIZE
+}
+
+pub open spec fn in_systimer_region(addr: usize) -> bool {
+    addr >= WATCHDOG_PD_SYSTIMER_BASE && addr < WATCHDOG_PD_SYSTIMER_BASE + WATCHDOG_PD_SYSTIMER_SIZE
+}
+
+/// Specification: can the Watchdog PD access this address?
+pub open spec fn watchdog_pd_can_access(addr: usize) -> bool {
+    in_pm_region(addr) || in_systimer_region(addr) || in_liveness_page_region(addr)
+}
+
+/// Specification: what this protocol grants a reader
+pub open spec fn liveness_reader_can_access(addr: usize) -> bool {
+    in_liveness_page_region(addr)
+}
+
+// ============================================================================
+// TIMING
+// ============================================================================
+
+/// Longest a client may go between pings
+pub const PING_INTERVAL_MS: u32 = 1_000;
+
+/// Shortest deadline window a client may be registered with
+pub const MIN_WINDOW_MS: u32 = 2 * PING_INTERVAL_MS;
+
+/// Checks a second in the Watchdog PD
+pub const CHECK_HZ: u32 = 4;
+
+/// Hardware watchdog timeout: sixteen check periods
+pub const HW_TIMEOUT_MS: u32 = 4_000;
+
+/// Has a client that last pinged at `last_us` pinged within `window_us` of
+/// `now_us`? A ping read after `now_us` was taken counts as on time.
+pub open spec fn live_spec(last_us: u64, now_us: u64, window_us: u64) -> bool {
+    now_us <= last_us || now_us - last_us <= window_us
+}
+
+/// Is a client that last pinged at `last_us` still within `window_ms` at
+/// `now_us` (system timer readings, microseconds)?
+pub fn is_live(last_us: u64, now_us: u64, window_ms: u32) -> (r: bool)
+    ensures
+        r == live_spec(last_us, now_us, window_ms as u64 * 1000),
+{
+    now_us <= last_us || now_us - last_us <= window_ms as u64 * 1000
+}
+
+/// Is `window_ms` a window a client may be registered with?
+pub fn valid_window(window_ms: u32) -> (r: bool)
+    ensures
+        r <==> MIN_WINDOW_MS <= window_ms,
+{
+    window_ms >= MIN_WINDOW_MS
+}
+
+// ============================================================================
+// HARDWARE WATCHDOG
+// ============================================================================
+
+/// PM register writes are ignored without this in the top byte
+pub const PM_PASSWORD: u32 = 0x5a00_0000;
+
+/// PM_WDOG counts down at 65536 Hz
+pub const WDOG_TICKS_PER_S: u32 = 65_536;
+
+/// PM_WDOG's timer field
+pub const WDOG_TIME_MASK: u32 = 0x000f_ffff;
+
+/// Longest timeout the timer field holds: just under 16 seconds
+pub const MAX_WDOG_TIMEOUT_MS: u32 = 15_999;
+
+/// PM_WDOG ticks for `timeout_ms`; `None` for zero, or for more than the
+/// timer field holds
+pub fn wdog_ticks(timeout_ms: u32) -> (t: Option<u32>)
+    ensures
+        t.is_some() <==> 0 < timeout_ms && timeout_ms <= MAX_WDOG_TIMEOUT_MS,
+        t.is_some() ==> 0 < t.unwrap() && t.unwrap() <= WDOG_TIME_MASK,
+        t.is_some() ==> t.unwrap() as int == timeout_ms as int * 65_536 / 1000,
+{
+    if timeout_ms == 0 || timeout_ms > MAX_WDOG_TIMEOUT_MS {
+        return None;
+    }
+    proof {
+        assert(timeout_ms as int * 65_536 / 1000 <= 0x000f_ffff) by (nonlinear_arith)
+            requires timeout_ms <= 15_999;
+        assert(timeout_ms as int * 65_536 / 1000 > 0) by (nonlinear_arith)
+            requires timeout_ms >= 1;
+    }
+    Some((timeout_ms as u64 * WDOG_TICKS_PER_S as u64 / 1000) as u32)
+}
+
+// ============================================================================
+// ISOLATION PROOFS
+// ============================================================================
+
+/// Prove: a reader cannot reach the power manager through this protocol
+proof fn reader_cannot_access_pm()
+    ensures
+        forall|addr: usize| in_pm_region(addr) ==> !liveness_reader_can_access(addr)
+{
+}
+
+/// Prove: the liveness page is the only region the Watchdog PD shares
+/// with a reader
+proof fn watchdog_and_reader_share_only_liveness_page()
+    ensures
+        forall|addr: usize|
+            (watchdog_pd_can_access(addr) && liveness_reader_can_access(addr))
+            ==> in_liveness_page_region(addr)
+{
+}
+
+} // verus!
+
+const _: () = assert!(HW_TIMEOUT_MS <= MAX_WDOG_TIMEOUT_MS);
+// A live system must never let the hardware expire between two checks
+const _: () = assert!(HW_TIMEOUT_MS >= 4 * (1000 / CHECK_HZ));
+const _: () = assert!(MIN_WINDOW_MS > PING_INTERVAL_MS);
+
+// ============================================================================
+// CLIENTS
+// ============================================================================
+
+/// Bytes of a client's name on the liveness page
+pub const NAME_LEN: usize = 8;
+
+/// What the Watchdog PD last saw of a client slot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClientState {
+    /// No PD registered in this slot
+    #[default]
+    Unused,
+    /// Registered, not pinged yet; its window runs from the Watchdog PD's
+    /// start
+    Waiting,
+    /// Pinged within its window
+    Live,
+    /// Missed its window; the board is being reset
+    Late,
+}
+
+impl ClientState {
+    /// Wire value on the liveness page
+    pub const fn to_wire(self) -> u32 {
+        match self {
+            ClientState::Unused => 0,
+            ClientState::Waiting => 1,
+            ClientState::Live => 2,
+            ClientState::Late => 3,
+        }
+    }
+
+    pub const fn from_wire(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(ClientState::Unused),
+            1 => Some(ClientState::Waiting),
+            2 => Some(ClientState::Live),
+            3 => Some(ClientState::Late),
+            _ => None,
+        }
+    }
+
+    /// Health panel text
+    pub const fn name(self) -> &'static str {
+        match self {
+            ClientState::Unused => "UNUSED",
+            ClientState::Waiting => "WAITING",
+            ClientState::Live => "OK",
+            ClientState::Late => "LATE",
+        }
+    }
+}
+
+/// One client slot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClientHealth {
+    /// Short name, NUL-padded
+    pub name: [u8; NAME_LEN],
+    /// Deadline window
+    pub window_ms: u32,
+    /// Pings so far (wraps)
+    pub pings: u32,
+    /// System timer reading of the last ping, or of the Watchdog PD's start
+    /// until the first
+    pub last_ping_us: u64,
+    pub state: ClientState,
+}
+
+impl ClientHealth {
+    /// The name, without its padding
+    pub fn name(&self) -> &str {
+        let len = self.name.iter().position(|&b| b == 0).unwrap_or(NAME_LEN);
+        core::str::from_utf8(&self.name[..len]).unwrap_or("?")
+    }
+
+    /// Milliseconds since the last ping, at system timer reading `now_us`
+    pub fn ping_age_ms(&self, now_us: u64) -> u64 {
+        now_us.saturating_sub(self.last_ping_us) / 1000
+    }
+}
+
+/// Everything the liveness page holds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Liveness {
+    /// Times the hardware watchdog has been fed (wraps)
+    pub feeds: u32,
+    pub clients: [ClientHealth; MAX_CLIENTS],
+}
+
+impl Liveness {
+    /// The registered slots
+    pub fn registered(&self) -> impl Iterator<Item = &ClientHealth> {
+        self.clients.iter().filter(|c| c.state != ClientState::Unused)
+    }
+}
+
+/// Why a client cannot be registered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterError {
+    /// No such slot
+    BadSlot(usize),
+    /// The slot is taken
+    SlotTaken(usize),
+    /// Window shorter than [`MIN_WINDOW_MS`]
+    WindowTooShort(u32),
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterError::BadSlot(slot) => write!(f, "no client slot {}", slot),
+            RegisterError::SlotTaken(slot) => write!(f, "client slot {} is taken", slot),
+            RegisterError::WindowTooShort(ms) => {
+                write!(f, "window {} ms is shorter than {} ms", ms, MIN_WINDOW_MS)
+            }
+        }
+    }
+}
+
+/// The Watchdog PD's view of its clients
+pub struct Heartbeats {
+    liveness: Liveness,
+}
+
+impl Heartbeats {
+    /// No clients yet
+    pub const fn new() -> Self {
+        Heartbeats {
+            liveness: Liveness {
+                feeds: 0,
+                clients: [ClientHealth {
+                    name: [0; NAME_LEN],
+                    window_ms: 0,
+                    pings: 0,
+                    last_ping_us: 0,
+                    state: ClientState::Unused,
+                }; MAX_CLIENTS],
+            },
+        }
+    }
+
+    /// Watch `slot` from `now_us`: it must ping within `window_ms` of it
+    pub fn register(&mut self, slot: usize, name: &str, window_ms: u32, now_us: u64) -> Result<(), RegisterError> {
+        let client = self.liveness.clients.get_mut(slot).ok_or(RegisterError::BadSlot(slot))?;
+        if client.state != ClientState::Unused {
+            return Err(RegisterError::SlotTaken(slot));
+        }
+        if !valid_window(window_ms) {
+            return Err(RegisterError::WindowTooShort(window_ms));
+        }
+        let mut padded = [0; NAME_LEN];
+        let len = name.len().min(NAME_LEN);
+        padded[..len].copy_from_slice(&name.as_bytes()[..len]);
+        *client = ClientHealth { name: padded, window_ms, pings: 0, last_ping_us: now_us, state: ClientState::Waiting };
+        Ok(())
+    }
+
+    /// A ping from `slot`; pings from unregistered slots are ignored
+    pub fn ping(&mut self, slot: usize, now_us: u64) -> bool {
+        match self.liveness.clients.get_mut(slot) {
+            Some(client) if client.state != ClientState::Unused => {
+                client.pings = client.pings.wrapping_add(1);
+                client.last_ping_us = now_us;
+                if client.state == ClientState::Waiting {
+                    client.state = ClientState::Live;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check every registered client at `now_us`: the first that missed
+    /// its window, marked late, or `None` if the hardware may be fed
+    pub fn check(&mut self, now_us: u64) -> Option<usize> {
+        let late = self.liveness.clients.iter().position(|client| {
+            client.state != ClientState::Unused && !is_live(client.last_ping_us, now_us, client.window_ms)
+        })?;
+        self.liveness.clients[late].state = ClientState::Late;
+        Some(late)
+    }
+
+    /// Count a feed of the hardware watchdog
+    pub fn fed(&mut self) {
+        self.liveness.feeds = self.liveness.feeds.wrapping_add(1);
+    }
+
+    pub fn liveness(&self) -> &Liveness {
+        &self.liveness
+    }
+}
+
+impl Default for Heartbeats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// LIVENESS PAGE
+// ============================================================================
+
+/// Marks a liveness page the Watchdog PD has published to ("WDG1")
+pub const LIVENESS_MAGIC: u32 = 0x5744_4731;
+
+/// Tries a reader makes before giving up on a page being rewritten
+pub const READ_ATTEMPTS: usize = 4;
+
+/// Layout of one client slot on the page
+#[repr(C)]
+struct ClientSlot {
+    name: [u8; NAME_LEN],
+    window_ms: u32,
+    pings: u32,
+    last_ping_us: u64,
+    state: u32,
+    _reserved: u32,
+}
+
+/// Layout of the liveness page
+#[repr(C)]
+pub struct LivenessPage {
+    magic: u32,
+    /// Odd while the Watchdog PD is writing
+    seq: AtomicU32,
+    feeds: u32,
+    _reserved: u32,
+    clients: [ClientSlot; MAX_CLIENTS],
+}
+
+const _: () = assert!(core::mem::size_of::<LivenessPage>() <= LIVENESS_PAGE_SIZE);
+
+impl LivenessPage {
+    /// Publish `liveness`
+    ///
+    /// # Safety
+    /// `base` must point to the mapped liveness page, and the caller must
+    /// be its only writer.
+    pub unsafe fn publish(base: *mut u8, liveness: &Liveness) {
+        let page = base as *mut LivenessPage;
+        let seq = &(*page).seq;
+        let writing = seq.load(Ordering::Relaxed).wrapping_add(1) | 1;
+        seq.store(writing, Ordering::Relaxed);
+        fence(Ordering::Release);
+        write_volatile(addr_of_mut!((*page).magic), LIVENESS_MAGIC);
+        write_volatile(addr_of_mut!((*page).feeds), liveness.feeds);
+        for (i, client) in liveness.clients.iter().enumerate() {
+            let slot = addr_of_mut!((*page).clients[i]);
+            write_volatile(addr_of_mut!((*slot).name), client.name);
+            write_volatile(addr_of_mut!((*slot).window_ms), client.window_ms);
+            write_volatile(addr_of_mut!((*slot).pings), client.pings);
+            write_volatile(addr_of_mut!((*slot).last_ping_us), client.last_ping_us);
+            write_volatile(addr_of_mut!((*slot).state), client.state.to_wire());
+        }
+        seq.store(writing.wrapping_add(1), Ordering::Release);
+    }
+
+    /// Read the published liveness; `None` if nothing has been published,
+    /// the page holds a state no Watchdog PD would write, or it was being
+    /// rewritten on every one of [`READ_ATTEMPTS`] tries
+    ///
+    /// # Safety
+    /// `base` must point to the mapped liveness page.
+    pub unsafe fn read(base: *const u8) -> Option<Liveness> {
+        let page = base as *const LivenessPage;
+        let seq = &(*page).seq;
+        for _ in 0..READ_ATTEMPTS {
+            let before = seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let magic = read_volatile(addr_of!((*page).magic));
+            let feeds = read_volatile(addr_of!((*page).feeds));
+            let mut raw = [(0u32, ClientHealth::default()); MAX_CLIENTS];
+            for (i, (state, client)) in raw.iter_mut().enumerate() {
+                let slot = addr_of!((*page).clients[i]);
+                client.name = read_volatile(addr_of!((*slot).name));
+                client.window_ms = read_volatile(addr_of!((*slot).window_ms));
+                client.pings = read_volatile(addr_of!((*slot).pings));
+                client.last_ping_us = read_volatile(addr_of!((*slot).last_ping_us));
+                *state = read_volatile(addr_of!((*slot).state));
+            }
+            fence(Ordering::Acquire);
+            if seq.load(Ordering::Relaxed) != before {
+                continue;
+            }
+            if magic != LIVENESS_MAGIC {
+                return None;
+            }
+            let mut liveness = Liveness { feeds, clients: [ClientHealth::default(); MAX_CLIENTS] };
+            for (out, (state, client)) in liveness.clients.iter_mut().zip(raw) {
+                *out = ClientHealth { state: ClientState::from_wire(state)?, ..client };
+            }
+            return Some(liveness);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1000;
+
+    #[test]
+    fn channels_map_to_slots() {
+        assert_eq!(client_for_channel(FIRST_CLIENT_CHANNEL_ID), Some(0));
+        assert_eq!(client_for_channel(FIRST_CLIENT_CHANNEL_ID + MAX_CLIENTS - 1), Some(MAX_CLIENTS - 1));
+        assert_eq!(client_for_channel(FIRST_CLIENT_CHANNEL_ID + MAX_CLIENTS), None);
+        assert_eq!(client_for_channel(WATCHDOG_IRQ_CHANNEL_ID), None);
+    }
+
+    #[test]
+    fn deadlines() {
+        assert!(is_live(1_000 * MS, 3_000 * MS, 2_000));
+        assert!(!is_live(1_000 * MS, 3_000 * MS + 1, 2_000));
+        // A ping newer than the reading it is checked against
+        assert!(is_live(5_000 * MS, 1_000 * MS, 2_000));
+        assert!(!valid_window(MIN_WINDOW_MS - 1));
+        assert!(valid_window(MIN_WINDOW_MS));
+    }
+
+    #[test]
+    fn hardware_ticks_fit_the_field() {
+        assert_eq!(wdog_ticks(0), None);
+        assert_eq!(wdog_ticks(1), Some(65));
+        assert_eq!(wdog_ticks(1_000), Some(WDOG_TICKS_PER_S));
+        assert!(wdog_ticks(MAX_WDOG_TIMEOUT_MS).is_some_and(|t| t <= WDOG_TIME_MASK));
+        assert_eq!(wdog_ticks(MAX_WDOG_TIMEOUT_MS + 1), None);
+        assert!(wdog_ticks(HW_TIMEOUT_MS).is_some());
+    }
+
+    #[test]
+    fn registration() {
+        let mut beats = Heartbeats::new();
+        assert_eq!(beats.register(0, "TIMER", 2_000, 0), Ok(()));
+        assert_eq!(beats.register(0, "TIMER", 2_000, 0), Err(RegisterError::SlotTaken(0)));
+        assert_eq!(beats.register(MAX_CLIENTS, "X", 2_000, 0), Err(RegisterError::BadSlot(MAX_CLIENTS)));
+        assert_eq!(beats.register(1, "X", 100, 0), Err(RegisterError::WindowTooShort(100)));
+        assert_eq!(beats.register(1, "PHOTOFRAME", 10_000, 0), Ok(()));
+        let names: [&str; 2] = [beats.liveness().clients[0].name(), beats.liveness().clients[1].name()];
+        assert_eq!(names, ["TIMER", "PHOTOFRA"]);
+        assert_eq!(beats.liveness().registered().count(), 2);
+    }
+
+    #[test]
+    fn a_silent_client_is_caught_after_its_window() {
+        let mut beats = Heartbeats::new();
+        beats.register(0, "TIMER", 2_000, 0).unwrap();
+        beats.register(1, "PHOTO", 10_000, 0).unwrap();
+        // Both are given their window from the start
+        assert_eq!(beats.check(1_999 * MS), None);
+        assert!(beats.ping(0, 1_500 * MS));
+        assert!(!beats.ping(2, 1_500 * MS));
+        assert_eq!(beats.liveness().clients[0].state, ClientState::Live);
+        assert_eq!(beats.liveness().clients[1].state, ClientState::Waiting);
+        assert_eq!(beats.check(3_500 * MS), None);
+        // The timer falls silent
+        assert_eq!(beats.check(3_500 * MS + 1), Some(0));
+        assert_eq!(beats.liveness().clients[0].state, ClientState::Late);
+        assert_eq!(beats.liveness().clients[0].pings, 1);
+        assert_eq!(beats.liveness().clients[0].ping_age_ms(4_000 * MS), 2_500);
+    }
+
+    #[test]
+    fn page_round_trip() {
+        let mut page = [0u64; 512];
+        let base = page.as_mut_ptr() as *mut u8;
+        assert_eq!(unsafe { LivenessPage::read(base) }, None);
+
+        let mut beats = Heartbeats::new();
+        beats.register(2, "PHOTO", 10_000, 7).unwrap();
+        beats.ping(2, 1_234_567);
+        beats.fed();
+        unsafe { LivenessPage::publish(base, beats.liveness()) };
+        let read = unsafe { LivenessPage::read(base) }.unwrap();
+        assert_eq!(&read, beats.liveness());
+        assert_eq!(read.feeds, 1);
+        assert_eq!(read.clients[2].name(), "PHOTO");
+
+        // A state no Watchdog PD writes
+        let page_ptr = base as *mut LivenessPage;
+        unsafe { (*page_ptr).clients[0].state = 9 };
+        assert_eq!(unsafe { LivenessPage::read(base) }, None);
+    }
+}
@@ -0,0 +1,337 @@
+//! Generates the embedded bitmap fonts of `rpi4-graphics`.
+//!
+//! ```text
+//! fontgen <DejaVuSans.ttf> <rpi4-graphics/src/font>
+//! ```
+//!
+//! Writes one Rust module per face into the font directory:
+//!
+//! - `pixel8.rs`: the classic 8x8 glyphs from `FONT_8X8` and
+//!   `FONT_8X8_EXTRA` in `mod.rs`, with blank columns trimmed so they space
+//!   proportionally
+//! - `sans16.rs`, `sans24.rs`: DejaVu Sans rasterized to 1 bit per pixel for
+//!   16 and 24 pixel lines, with the font's kerning pairs, covering ASCII,
+//!   Latin-1, arrows and box drawing
+//!
+//! On Debian and Ubuntu the TTF comes with `fonts-dejavu-core`, at
+//! `/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf`.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{env, fs, process};
+
+use fontdue::{Font, FontSettings};
+
+/// Printable ASCII, the range every face covers
+const FIRST: char = ' ';
+const LAST: char = '~';
+
+/// Characters the sans faces cover beyond ASCII: Latin-1, arrows, box
+/// drawing and the replacement character
+const SANS_EXTRA: [(char, char); 4] = [
+    ('\u{A0}', '\u{FF}'),
+    ('\u{2190}', '\u{2195}'),
+    ('\u{2500}', '\u{257F}'),
+    ('\u{FFFD}', '\u{FFFD}'),
+];
+
+/// Coverage at or above which a pixel is set (half, which keeps stems one
+/// or two pixels wide at 16 px without dropping thin strokes)
+const THRESHOLD: u8 = 128;
+
+/// A glyph before packing: ink box relative to the line top and pen
+struct Glyph {
+    rows: Vec<Vec<bool>>,
+    x_offset: i32,
+    y_offset: i32,
+    advance: i32,
+}
+
+impl Glyph {
+    fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    /// Drop blank rows and columns around the ink
+    fn trim(mut self) -> Self {
+        let set = |r: &Vec<bool>| r.iter().any(|&p| p);
+        let Some(top) = self.rows.iter().position(set) else {
+            self.rows.clear();
+            return self;
+        };
+        let bottom = self.rows.iter().rposition(set).unwrap();
+        self.rows = self.rows[top..=bottom].to_vec();
+        self.y_offset += top as i32;
+
+        let column_set = |c: usize| self.rows.iter().any(|r| r[c]);
+        let width = self.width();
+        let left = (0..width).find(|&c| column_set(c)).unwrap();
+        let right = (0..width).rev().find(|&c| column_set(c)).unwrap();
+        for row in &mut self.rows {
+            *row = row[left..=right].to_vec();
+        }
+        self.x_offset += left as i32;
+        self
+    }
+}
+
+/// A face ready to be written out
+struct Face {
+    ident: &'static str,
+    doc: String,
+    line_height: i32,
+    ascent: i32,
+    /// Characters covered, sorted, and their glyphs
+    glyphs: Vec<(char, Glyph)>,
+    kerning: Vec<(char, char, i32)>,
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let [ttf, dir] = args.as_slice() else {
+        eprintln!("usage: fontgen <DejaVuSans.ttf> <rpi4-graphics/src/font>");
+        process::exit(2);
+    };
+    let dir = Path::new(dir);
+
+    let legacy = fs::read_to_string(dir.join("mod.rs")).unwrap_or_else(|e| fail(&format!("mod.rs: {e}")));
+    let data = fs::read(ttf).unwrap_or_else(|e| fail(&format!("{ttf}: {e}")));
+    let font = Font::from_bytes(data, FontSettings::default()).unwrap_or_else(|e| fail(e));
+
+    let faces = [
+        ("pixel8.rs", pixel_face(&legacy)),
+        ("sans16.rs", sans_face(&font, "SANS_16", 16)),
+        ("sans24.rs", sans_face(&font, "SANS_24", 24)),
+    ];
+    for (file, face) in faces {
+        let path = dir.join(file);
+        fs::write(&path, render(&face)).unwrap_or_else(|e| fail(&format!("{}: {e}", path.display())));
+        println!("{}: {} glyphs, {} kerning pairs", path.display(), face.glyphs.len(), face.kerning.len());
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("fontgen: {message}");
+    process::exit(1);
+}
+
+/// Hex bytes in `text`, ignoring `//` comments
+fn hex_bytes(text: &str) -> Vec<u8> {
+    text.lines()
+        .map(|line| line.split("//").next().unwrap())
+        .flat_map(|line| line.split([',', '[', ']', '(', ')']))
+        .filter_map(|token| token.trim().strip_prefix("0x"))
+        .map(|hex| u8::from_str_radix(hex, 16).unwrap())
+        .collect()
+}
+
+/// Body of the `static` array named `name` in `source`
+fn array_body<'a>(source: &'a str, name: &str) -> &'a str {
+    let start = source.find(name).unwrap_or_else(|| fail(&format!("{name} not found in mod.rs")));
+    let start = start + source[start..].find("= [").unwrap() + 3;
+    let end = start + source[start..].find("\n];").unwrap();
+    &source[start..end]
+}
+
+/// The classic 8x8 face: `FONT_8X8` covers space to underscore in
+/// uppercase only, so lowercase reuses the capitals and the remaining
+/// printable ASCII shows as '?'. `FONT_8X8_EXTRA` adds a few symbols.
+fn pixel_face(legacy: &str) -> Face {
+    let bytes = hex_bytes(array_body(legacy, "FONT_8X8: [u8; 512]"));
+    assert_eq!(bytes.len(), 512, "FONT_8X8 should hold 64 glyphs");
+
+    // One `('\u{XXXX}', [8 bytes]),` entry per line
+    let mut cells: Vec<(char, Vec<u8>)> = Vec::new();
+    for line in array_body(legacy, "FONT_8X8_EXTRA:").lines().filter(|l| l.contains("\\u{")) {
+        let hex = line.split("\\u{").nth(1).and_then(|rest| rest.split('}').next()).unwrap();
+        let c = char::from_u32(u32::from_str_radix(hex, 16).unwrap()).unwrap();
+        let rows = hex_bytes(line.split_once(',').unwrap().1);
+        assert_eq!(rows.len(), 8, "FONT_8X8_EXTRA entry for {c} should have 8 rows");
+        cells.push((c, rows));
+    }
+    for c in FIRST..=LAST {
+        let index = match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            FIRST..='_' => c,
+            _ => '?',
+        } as usize
+            - FIRST as usize;
+        cells.push((c, bytes[index * 8..index * 8 + 8].to_vec()));
+    }
+    cells.sort_by_key(|&(c, _)| c);
+
+    let glyph = |(c, cell): &(char, Vec<u8>)| -> (char, Glyph) {
+        let rows = cell.iter().map(|&row| (0..8).map(|col| row & (0x80 >> col) != 0).collect()).collect();
+        let glyph = Glyph { rows, x_offset: 0, y_offset: 0, advance: 8 };
+        // Box drawing and blocks keep the whole cell so lines join up
+        if *c >= '\u{2500}' {
+            return (*c, glyph);
+        }
+        let glyph = glyph.trim();
+        // One blank column between glyphs; space as wide as a narrow letter
+        let advance = if glyph.rows.is_empty() { 4 } else { glyph.width() as i32 + 1 };
+        (*c, Glyph { x_offset: 0, advance, ..glyph })
+    };
+
+    Face {
+        ident: "PIXEL_8",
+        doc: "Classic 8x8 pixel glyphs, proportionally spaced (uppercase only)".into(),
+        line_height: 8,
+        ascent: 7,
+        glyphs: cells.iter().map(glyph).collect(),
+        kerning: Vec::new(),
+    }
+}
+
+/// DejaVu Sans, scaled so ascent plus descent fills `line_height` pixels
+fn sans_face(font: &Font, ident: &'static str, line_height: i32) -> Face {
+    let unit = font.horizontal_line_metrics(1.0).unwrap();
+    let px = line_height as f32 / (unit.ascent - unit.descent);
+    let ascent = (unit.ascent * px).round() as i32;
+
+    let glyph = |c: char| -> (char, Glyph) {
+        let (metrics, coverage) = font.rasterize(c, px);
+        let top = ascent - (metrics.ymin + metrics.height as i32);
+        // Rows outside the line box (box drawing reaches past the ascent
+        // and descent) are clipped, so lines never overlap
+        let rows = coverage
+            .chunks(metrics.width.max(1))
+            .take(metrics.height)
+            .enumerate()
+            .filter(|&(row, _)| (0..line_height).contains(&(top + row as i32)))
+            .map(|(_, row)| row.iter().map(|&a| a >= THRESHOLD).collect())
+            .collect();
+        let glyph = Glyph {
+            rows,
+            x_offset: metrics.xmin,
+            y_offset: top.max(0),
+            advance: metrics.advance_width.round() as i32,
+        };
+        (c, glyph.trim())
+    };
+
+    let mut chars: Vec<char> = (FIRST..=LAST).collect();
+    for (first, last) in SANS_EXTRA {
+        for c in first..=last {
+            if font.lookup_glyph_index(c) == 0 {
+                fail(&format!("{} has no glyph for U+{:04X}", ident, c as u32));
+            }
+            chars.push(c);
+        }
+    }
+
+    let mut kerning = Vec::new();
+    for &left in &chars {
+        for &right in &chars {
+            let kern = font.horizontal_kern(left, right, px).unwrap_or(0.0).round() as i32;
+            if kern != 0 {
+                kerning.push((left, right, kern));
+            }
+        }
+    }
+
+    Face {
+        ident,
+        doc: format!("DejaVu Sans for a {line_height} pixel line (rasterized at {px:.2} px per em)"),
+        line_height,
+        ascent,
+        glyphs: chars.into_iter().map(glyph).collect(),
+        kerning,
+    }
+}
+
+/// `c` as a Rust char literal
+fn char_literal(c: char) -> String {
+    match c {
+        '\'' => "'\\''".to_string(),
+        '\\' => "'\\\\'".to_string(),
+        ' '..='~' => format!("'{c}'"),
+        _ => format!("'\\u{{{:X}}}'", c as u32),
+    }
+}
+
+/// Comment showing `c`, for characters that are escaped in literals
+fn char_comment(c: char) -> String {
+    if c.is_ascii() || c.is_whitespace() || c == '\u{AD}' {
+        String::new()
+    } else {
+        format!(" {c}")
+    }
+}
+
+fn render(face: &Face) -> String {
+    let mut bitmap = Vec::new();
+    let mut glyphs = String::new();
+    let mut ranges: Vec<(char, char, usize)> = Vec::new();
+    for (i, (c, glyph)) in face.glyphs.iter().enumerate() {
+        match ranges.last_mut() {
+            Some((_, last, _)) if *last as u32 + 1 == *c as u32 => *last = *c,
+            _ => ranges.push((*c, *c, i)),
+        }
+        let offset = bitmap.len();
+        for row in &glyph.rows {
+            for chunk in row.chunks(8) {
+                let byte = chunk.iter().enumerate().fold(0u8, |b, (i, &p)| b | ((p as u8) << (7 - i)));
+                bitmap.push(byte);
+            }
+        }
+        writeln!(
+            glyphs,
+            "    Glyph::new({offset}, {}, {}, {}, {}, {}), // {}{}",
+            glyph.width(),
+            glyph.rows.len(),
+            glyph.x_offset,
+            glyph.y_offset,
+            glyph.advance,
+            char_literal(*c),
+            char_comment(*c),
+        )
+        .unwrap();
+    }
+    assert!(bitmap.len() <= u16::MAX as usize, "bitmap offsets are u16");
+    assert!(ranges[0] == (FIRST, LAST, 0), "faces start with printable ASCII");
+
+    let mut out = String::new();
+    writeln!(out, "//! {}", face.doc).unwrap();
+    writeln!(out, "//!").unwrap();
+    writeln!(out, "//! Generated by `tools/fontgen`; do not edit.").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "use super::{{BitmapFont, Glyph, GlyphRange, KernPair}};").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "pub static {}: BitmapFont = BitmapFont {{", face.ident).unwrap();
+    writeln!(out, "    line_height: {},", face.line_height).unwrap();
+    writeln!(out, "    ascent: {},", face.ascent).unwrap();
+    writeln!(out, "    ranges: &RANGES,").unwrap();
+    writeln!(out, "    glyphs: &GLYPHS,").unwrap();
+    writeln!(out, "    bitmap: &BITMAP,").unwrap();
+    writeln!(out, "    kerning: &KERNING,").unwrap();
+    writeln!(out, "}};").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Characters covered, and where their glyphs start").unwrap();
+    writeln!(out, "static RANGES: [GlyphRange; {}] = [", ranges.len()).unwrap();
+    for &(first, last, start) in &ranges {
+        writeln!(out, "    GlyphRange::new({}, {}, {start}),", char_literal(first), char_literal(last)).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Ink box and advance of each glyph, in range order").unwrap();
+    writeln!(out, "static GLYPHS: [Glyph; {}] = [", face.glyphs.len()).unwrap();
+    out.push_str(&glyphs);
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Glyph rows, MSB leftmost, each row padded to a whole byte").unwrap();
+    writeln!(out, "static BITMAP: [u8; {}] = [", bitmap.len()).unwrap();
+    for line in bitmap.chunks(16) {
+        let bytes: Vec<_> = line.iter().map(|b| format!("0x{b:02X}")).collect();
+        writeln!(out, "    {},", bytes.join(", ")).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Kerning pairs, sorted by (left, right)").unwrap();
+    writeln!(out, "static KERNING: [KernPair; {}] = [", face.kerning.len()).unwrap();
+    for &(left, right, kern) in &face.kerning {
+        writeln!(out, "    KernPair::new({}, {}, {kern}),", char_literal(left), char_literal(right)).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
@@ -400,7 +400,18 @@ pub fn validate(graph: &AuthorityGraph, properties: &Properties) -> Vec<Violatio
                 });
             }
         }
-        let common = common.unwrap_or_default();
+        // Regions a wider rule covers (one whose PDs include all of these)
+        // are accounted for there
+        let wider: BTreeSet<String> = properties
+            .shared_only
+            .iter()
+            .filter(|other| {
+                let pds: BTreeSet<_> = other.pds.iter().cloned().collect();
+                pds.is_superset(&expected_pds) && pds != expected_pds
+            })
+            .flat_map(|other| other.regions.iter().cloned())
+            .collect();
+        let common: BTreeSet<_> = common.unwrap_or_default().difference(&wider).cloned().collect();
         if common != expected_regions {
             violations.push(Violation::new(
                 "shared_only",
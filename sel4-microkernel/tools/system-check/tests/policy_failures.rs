@@ -65,6 +65,30 @@ fn widened_mapping_is_rejected() {
         .contains("shared_only"));
 }
 
+#[test]
+fn region_of_a_wider_rule_is_not_counted_against_a_pair() {
+    let xml = BASE
+        .replace(
+            "<memory_region name=\"device\"",
+            "<memory_region name=\"counter\" size=\"0x1000\"/>\n  <memory_region name=\"device\"",
+        )
+        .replace(
+            "<map mr=\"work_ring\" vaddr=\"0x5000\" perms=\"rw\"/>",
+            "<map mr=\"work_ring\" vaddr=\"0x5000\" perms=\"rw\"/><map mr=\"counter\" vaddr=\"0x9000\" perms=\"r\"/>",
+        )
+        .replace(
+            "<map mr=\"device\" vaddr=\"0x6000\" perms=\"rw\"/>",
+            "<map mr=\"device\" vaddr=\"0x6000\" perms=\"rw\"/><map mr=\"counter\" vaddr=\"0x9000\" perms=\"r\"/>",
+        );
+    assert!(check_text(&xml, PROPS)
+        .unwrap_err()
+        .to_string()
+        .contains("shared_only"));
+
+    let props = format!("{PROPS}\n[[shared_only]]\npds = [\"supervisor\", \"worker\", \"policy\"]\nregions = [\"counter\"]\n");
+    check_text(&xml, &props).unwrap();
+}
+
 #[test]
 fn widened_permission_is_rejected() {
     let xml = BASE.replace(
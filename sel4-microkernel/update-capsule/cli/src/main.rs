@@ -8,6 +8,7 @@
 //!                           --version 5 --key-id 1 --key-epoch 1
 //!                           [--load-vaddr 0x40000000] [--entry-offset 0]
 //!                           [--deps-sha256 <hex32>]
+//! update-capsule-cli sign-file --secret sk.bin --in photo.jpg --out photo.signed
 //! update-capsule-cli show   capsule.bin
 //! update-capsule-cli verify capsule.bin --public pk.bin [--platform N]
 //!                           [--counter N] [--region-base N] [--region-size N]
@@ -17,11 +18,15 @@
 //! from the capsule header (overridable via flags) and runs the same
 //! pipeline a verifier PD would. A real verifier pins its profile; it
 //! never derives it from the capsule.
+//!
+//! `sign-file` signs a whole file with no capsule header, appending the
+//! signature and a `SIGNED25` trailer; the photoframe's `signed-photos`
+//! build verifies SD card photos in that form.
 
 use std::process::exit;
 
 use update_capsule::header::{self, HEADER_LEN, SIGNED_PREFIX_LEN};
-use update_capsule::mint::{derive_public_key, mint, CapsuleSpec};
+use update_capsule::mint::{derive_public_key, mint, sign_file, CapsuleSpec};
 use update_capsule::verify::{
     verify_capsule, RollbackStore, SlotPolicy, SystemProfile, TrustedKey,
 };
@@ -31,10 +36,11 @@ fn main() {
     let code = match args.first().map(String::as_str) {
         Some("keygen") => cmd_keygen(&args[1..]),
         Some("sign") => cmd_sign(&args[1..]),
+        Some("sign-file") => cmd_sign_file(&args[1..]),
         Some("show") => cmd_show(&args[1..]),
         Some("verify") => cmd_verify(&args[1..]),
         _ => {
-            eprintln!("usage: update-capsule-cli <keygen|sign|show|verify> [options]");
+            eprintln!("usage: update-capsule-cli <keygen|sign|sign-file|show|verify> [options]");
             eprintln!("       (see crate README for details)");
             2
         }
@@ -242,6 +248,25 @@ fn cmd_sign(args: &[String]) -> i32 {
     }
 }
 
+fn cmd_sign_file(args: &[String]) -> i32 {
+    let opts = Opts(args);
+    let secret = read_key32(&opts.require("--secret"));
+    let data = read_file(&opts.require("--in"));
+    let out = opts.require("--out");
+
+    match sign_file(&data, &secret) {
+        Ok(signed) => {
+            write_file(&out, &signed);
+            println!("wrote {} ({} bytes, data {} bytes)", out, signed.len(), data.len());
+            0
+        }
+        Err(e) => {
+            eprintln!("error: signing failed: {e:?}");
+            1
+        }
+    }
+}
+
 fn cmd_show(args: &[String]) -> i32 {
     let opts = Opts(args);
     let Some(path) = opts.positional() else {
@@ -92,6 +92,27 @@ pub fn mint(
     Ok(capsule)
 }
 
+/// Trailer magic of a detached-signature file (matches `SIGNED_MAGIC` in
+/// `rpi4_graphics::crypto::ed25519`).
+pub const SIGNED_FILE_MAGIC: [u8; 8] = *b"SIGNED25";
+
+/// Sign an arbitrary file for verifiers that have no capsule header:
+/// `data ++ signature ++ SIGNED_FILE_MAGIC`, the signature covering `data`
+/// alone. The photoframe checks SD card photos in this form.
+pub fn sign_file(data: &[u8], signing_key: &[u8; 32]) -> Result<Vec<u8>, MintError> {
+    if data.len() as u64 > PAYLOAD_LEN_MAX {
+        return Err(MintError::PayloadTooLarge);
+    }
+    let signature =
+        libcrux_ed25519::sign(data, signing_key).map_err(|_| MintError::SigningFailed)?;
+
+    let mut out = Vec::with_capacity(data.len() + signature.len() + SIGNED_FILE_MAGIC.len());
+    out.extend_from_slice(data);
+    out.extend_from_slice(&signature);
+    out.extend_from_slice(&SIGNED_FILE_MAGIC);
+    Ok(out)
+}
+
 /// Derive the ed25519 public key for a 32-byte secret key.
 pub fn derive_public_key(signing_key: &[u8; 32]) -> [u8; 32] {
     let mut pk = [0u8; 32];
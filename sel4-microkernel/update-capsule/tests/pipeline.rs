@@ -10,7 +10,7 @@ use common::*;
 use update_capsule::header::{
     PAYLOAD_TYPE_CONFIG, PAYLOAD_TYPE_MODEL_WEIGHTS, PAYLOAD_TYPE_PD_CODE, SIGNED_PREFIX_LEN,
 };
-use update_capsule::mint::mint;
+use update_capsule::mint::{derive_public_key, mint, sign_file, SIGNED_FILE_MAGIC};
 use update_capsule::verify::{verify_capsule, VerifyError};
 
 fn verify_with_counter(
@@ -254,3 +254,28 @@ fn payload_type_1_and_4_differ_only_in_entry_rules() {
     .is_ok());
     assert_eq!(fixture.slots[0].payload_type, PAYLOAD_TYPE_PD_CODE + 3);
 }
+
+#[test]
+fn sign_file_appends_signature_and_trailer() {
+    let signed = sign_file(GOLDEN_PAYLOAD, &TEST_SEED).unwrap();
+    let (data, rest) = signed.split_at(GOLDEN_PAYLOAD.len());
+    let (signature, magic) = rest.split_at(64);
+    assert_eq!(data, GOLDEN_PAYLOAD);
+    assert_eq!(magic, &SIGNED_FILE_MAGIC);
+    assert!(update_capsule::crypto::ed25519_verify(
+        data,
+        &derive_public_key(&TEST_SEED),
+        signature.try_into().unwrap()
+    ));
+
+    // RFC 8032 §7.1 TEST 1: the empty message signs to the published value
+    const TEST1_SIGNATURE: [u8; 64] = [
+        0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e, 0x82, 0x8a,
+        0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65, 0x22, 0x49, 0x01, 0x55,
+        0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e, 0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b,
+        0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24, 0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+    ];
+    let empty = sign_file(b"", &TEST_SEED).unwrap();
+    assert_eq!(empty[..64], TEST1_SIGNATURE);
+    assert_eq!(empty[64..], SIGNED_FILE_MAGIC);
+}
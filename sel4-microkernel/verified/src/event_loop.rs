@@ -0,0 +1,588 @@
+//! # Event-Loop Dispatcher
+//!
+//! A PD's `notified` gets every channel that fired since it last returned,
+//! and nothing else runs in the PD until it returns. A handler that drains
+//! its ring to the end lets one busy channel hold the whole PD: a flood of
+//! received frames keeps the Network PD from ever reading its client's TX
+//! ring.
+//!
+//! An [`EventLoop`] instead runs each channel's handler with a work budget,
+//! the most units (frames, events, ring entries) it may handle in one turn.
+//! A handler that stops with work left returns [`Work::Deferred`], and its
+//! channel queues up again behind every channel already waiting. One `run`
+//! hands out at most `max_turns` turns, so `notified` returns in bounded
+//! time; work still queued then carries over to the next notification.
+//!
+//! ```rust
+//! use verified_microkernel::event_loop::{EventLoop, Work};
+//!
+//! struct Pd {
+//!     events: EventLoop<Pd, (), 2>,
+//!     backlog: u32,
+//! }
+//!
+//! /// Forward at most `budget` of the frames waiting
+//! fn receive(pd: &mut Pd, budget: u32) -> Result<Work, ()> {
+//!     pd.backlog -= pd.backlog.min(budget);
+//!     Ok(if pd.backlog > 0 { Work::Deferred } else { Work::Done })
+//! }
+//!
+//! let mut pd = Pd { events: EventLoop::new(4), backlog: 100 };
+//! pd.events.register(1, 16, receive).unwrap();
+//! // From `notified`, with the channels that fired
+//! EventLoop::run(&mut pd, |pd| &mut pd.events, [1]).unwrap();
+//! assert_eq!(pd.backlog, 36);
+//! assert!(pd.events.pending());
+//! ```
+//!
+//! ## Verification
+//!
+//! [`Dispatcher`] holds the registrations and the queue of channels with
+//! work waiting. Verus proves that no operation puts a channel ahead of
+//! one already waiting (`register`, `notify` and `finish` only append) and
+//! that every turn handed out moves each other waiting channel one place
+//! closer to the head. A waiting channel's place is below `N`, so it gets
+//! a turn within `N` turns however often the other channels fire or
+//! defer, and waits for at most the budgets of the channels ahead of it.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// Channels a Microkit PD can have: ids 0 to 62
+pub const MAX_CHANNELS: usize = 63;
+
+/// `running` when no turn is out
+const NO_SLOT: u8 = 0xFF;
+
+/// What a handler left when its turn ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Work {
+    /// Nothing left until the channel fires again
+    Done,
+    /// The budget ran out with work left; the channel queues up again
+    Deferred,
+}
+
+/// Why a channel could not be registered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventError {
+    /// Not a Microkit channel id
+    InvalidChannel,
+    /// The channel already has a handler
+    Duplicate,
+    /// Every slot is taken
+    Full,
+    /// A budget of 0 would never let the handler work
+    ZeroBudget,
+}
+
+/// One channel's turn, handed out by `Dispatcher::next_turn` and given back to
+/// `Dispatcher::finish`. Not `Clone`: there is one turn out at a time.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Turn {
+    slot: u8,
+    channel: u8,
+    budget: u32,
+}
+
+impl Turn {
+    pub open spec fn slot_spec(&self) -> u8 {
+        self.slot
+    }
+
+    /// Slot the channel was registered in
+    pub fn slot(&self) -> (s: usize)
+        ensures s == self.slot_spec(),
+    {
+        self.slot as usize
+    }
+
+    /// The channel whose turn it is
+    pub fn channel(&self) -> usize {
+        self.channel as usize
+    }
+
+    /// Most units of work the handler may do this turn
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+}
+
+/// Registered channels, their budgets, and the queue of channels with work
+/// waiting
+pub struct Dispatcher<const N: usize> {
+    /// Channel id and budget of each slot; `[..registered]` in use
+    channels: [u8; N],
+    budgets: [u32; N],
+    registered: usize,
+    /// Slots with work waiting, next turn first; `queue[..queued]` in use
+    queue: [u8; N],
+    queued: usize,
+    /// `NO_SLOT`, or the slot whose turn is out
+    running: u8,
+}
+
+impl<const N: usize> Dispatcher<N> {
+    /// Specification: `slot` waits with `pos` turns to go before its own
+    pub open spec fn waits_at(&self, slot: u8, pos: int) -> bool {
+        0 <= pos < self.queued && self.queue[pos] == slot
+    }
+
+    /// Specification: is the dispatcher in a valid state?
+    ///
+    /// Every waiting slot is registered and waits once, and a turn is out
+    /// only for a registered slot.
+    pub open spec fn valid(&self) -> bool {
+        &&& self.registered <= N
+        &&& self.registered <= MAX_CHANNELS
+        &&& self.queued <= N
+        &&& forall|i: int| 0 <= i < self.queued ==> (self.queue[i] as int) < self.registered
+        &&& forall|i: int, j: int| 0 <= i < j < self.queued ==> self.queue[i] != self.queue[j]
+        &&& (self.running == NO_SLOT || (self.running as int) < self.registered)
+    }
+
+    pub open spec fn queued_spec(&self) -> usize {
+        self.queued
+    }
+
+    pub open spec fn running_spec(&self) -> u8 {
+        self.running
+    }
+
+    /// Specification: everyone waiting in `old` still waits in `self`, in
+    /// the same place
+    pub open spec fn keeps_places(&self, old: &Self) -> bool {
+        &&& self.queued >= old.queued
+        &&& forall|p: int| 0 <= p < old.queued ==> self.queue[p] == old.queue[p]
+    }
+
+    /// No channels registered
+    pub fn new() -> (d: Self)
+        ensures
+            d.valid(),
+            d.queued_spec() == 0,
+            d.running_spec() == NO_SLOT,
+    {
+        Dispatcher {
+            channels: [0; N],
+            budgets: [0; N],
+            registered: 0,
+            queue: [0; N],
+            queued: 0,
+            running: NO_SLOT,
+        }
+    }
+
+    /// Register `channel` with `budget` units of work a turn. Returns its
+    /// slot.
+    pub fn register(&mut self, channel: usize, budget: u32) -> (result: Result<usize, EventError>)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.keeps_places(&*old(self)),
+            self.queued_spec() == old(self).queued_spec(),
+            self.running_spec() == old(self).running_spec(),
+    {
+        if channel >= MAX_CHANNELS {
+            return Err(EventError::InvalidChannel);
+        }
+        if budget == 0 {
+            return Err(EventError::ZeroBudget);
+        }
+        if self.slot_of(channel).is_some() {
+            return Err(EventError::Duplicate);
+        }
+        if self.registered >= N || self.registered >= MAX_CHANNELS {
+            return Err(EventError::Full);
+        }
+        let slot = self.registered;
+        self.channels[slot] = channel as u8;
+        self.budgets[slot] = budget;
+        self.registered = slot + 1;
+        Ok(slot)
+    }
+
+    /// Is any channel waiting for a turn?
+    pub fn pending(&self) -> (r: bool)
+        ensures r == (self.queued_spec() > 0),
+    {
+        self.queued > 0
+    }
+
+    /// `channel` fired: queue it unless it already waits. Returns whether
+    /// it is registered.
+    pub fn notify(&mut self, channel: usize) -> (known: bool)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.keeps_places(&*old(self)),
+            self.running_spec() == old(self).running_spec(),
+    {
+        match self.slot_of(channel) {
+            Some(slot) => {
+                self.enqueue(slot as u8);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hand out the turn of the channel at the head of the queue; `None`
+    /// if nobody waits or a turn is already out
+    pub fn next_turn(&mut self) -> (turn: Option<Turn>)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            turn.is_none() ==> self.keeps_places(&*old(self)),
+            turn.is_some() ==> old(self).waits_at(turn.unwrap().slot_spec(), 0)
+                && self.running_spec() == turn.unwrap().slot_spec()
+                && self.queued_spec() == old(self).queued_spec() - 1,
+            // Everyone else moves one place closer to their turn
+            turn.is_some() ==> forall|slot: u8, p: int|
+                old(self).waits_at(slot, p) && p > 0 ==> self.waits_at(slot, p - 1),
+    {
+        if self.running != NO_SLOT || self.queued == 0 {
+            return None;
+        }
+        let slot = self.queue[0];
+        self.pop_front();
+        self.running = slot;
+        Some(Turn {
+            slot,
+            channel: self.channels[slot as usize],
+            budget: self.budgets[slot as usize],
+        })
+    }
+
+    /// End `turn`. A channel with work `Deferred` queues up again, behind
+    /// everyone already waiting. False, changing nothing, if `turn` is not
+    /// the one out.
+    pub fn finish(&mut self, turn: Turn, work: Work) -> (ok: bool)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.keeps_places(&*old(self)),
+            ok ==> self.running_spec() == NO_SLOT,
+            !ok ==> self.running_spec() == old(self).running_spec(),
+    {
+        if self.running == NO_SLOT || turn.slot != self.running {
+            return false;
+        }
+        self.running = NO_SLOT;
+        match work {
+            Work::Deferred => self.enqueue(turn.slot),
+            Work::Done => {},
+        }
+        true
+    }
+
+    /// Slot `channel` is registered in
+    fn slot_of(&self, channel: usize) -> (slot: Option<usize>)
+        requires self.valid(),
+        ensures slot.is_some() ==> slot.unwrap() < self.registered,
+    {
+        let mut i = 0;
+        while i < self.registered
+            invariant
+                self.valid(),
+                i <= self.registered,
+        {
+            if self.channels[i] as usize == channel {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Add `slot` to the back of the queue unless it already waits
+    fn enqueue(&mut self, slot: u8)
+        requires
+            old(self).valid(),
+            (slot as int) < old(self).registered,
+        ensures
+            self.valid(),
+            self.keeps_places(&*old(self)),
+            self.registered == old(self).registered,
+            self.running == old(self).running,
+    {
+        let mut i = 0;
+        while i < self.queued
+            invariant
+                self.valid(),
+                self.queued == old(self).queued,
+                self.queue == old(self).queue,
+                self.registered == old(self).registered,
+                self.running == old(self).running,
+                i <= self.queued,
+                forall|k: int| 0 <= k < i ==> self.queue[k] != slot,
+        {
+            if self.queue[i] == slot {
+                return;
+            }
+            i += 1;
+        }
+        // `queued` distinct slots below `registered`, none of them `slot`,
+        // so there is room
+        if self.queued < N {
+            self.queue[self.queued] = slot;
+            self.queued += 1;
+        }
+    }
+
+    /// Drop the head of the queue
+    fn pop_front(&mut self)
+        requires
+            old(self).valid(),
+            old(self).queued > 0,
+        ensures
+            self.valid(),
+            self.queued == old(self).queued - 1,
+            forall|k: int| 0 <= k < self.queued ==> self.queue[k] == old(self).queue[k + 1],
+            self.registered == old(self).registered,
+            self.running == old(self).running,
+            self.channels == old(self).channels,
+            self.budgets == old(self).budgets,
+    {
+        let mut i = 0;
+        while i + 1 < self.queued
+            invariant
+                old(self).valid(),
+                self.queued == old(self).queued,
+                self.registered == old(self).registered,
+                self.running == old(self).running,
+                self.channels == old(self).channels,
+                self.budgets == old(self).budgets,
+                i + 1 <= self.queued,
+                forall|k: int| 0 <= k < i ==> self.queue[k] == old(self).queue[k + 1],
+                forall|k: int| i <= k < self.queued ==> self.queue[k] == old(self).queue[k],
+        {
+            self.queue[i] = self.queue[i + 1];
+            i += 1;
+        }
+        self.queued -= 1;
+    }
+}
+
+/// No starvation: a waiting channel is at most `N - 1` turns from its own.
+/// Its place never grows (see `keeps_places`) and shrinks with every turn
+/// `next_turn` hands to another channel, so it is served within `N` turns.
+pub proof fn lemma_wait_bounded<const N: usize>(d: Dispatcher<N>, slot: u8, pos: int)
+    requires
+        d.valid(),
+        d.waits_at(slot, pos),
+    ensures
+        pos < N,
+{
+}
+
+} // verus!
+
+/// A channel's handler: do at most `budget` units of the channel's work,
+/// and say whether any is left
+pub type EventHandler<S, E> = fn(&mut S, u32) -> Result<Work, E>;
+
+/// The handlers of up to `N` channels of a PD whose state is `S`
+pub struct EventLoop<S, E, const N: usize> {
+    dispatcher: Dispatcher<N>,
+    handlers: [Option<EventHandler<S, E>>; N],
+    /// Most turns one `run` hands out
+    max_turns: u32,
+}
+
+impl<S, E, const N: usize> EventLoop<S, E, N> {
+    /// No channels, and at most `max_turns` handler turns per notification
+    pub fn new(max_turns: u32) -> Self {
+        Self {
+            dispatcher: Dispatcher::new(),
+            handlers: [None; N],
+            max_turns,
+        }
+    }
+
+    /// Run `handler` when `channel` fires, with at most `budget` units of
+    /// work a turn
+    pub fn register(
+        &mut self,
+        channel: usize,
+        budget: u32,
+        handler: EventHandler<S, E>,
+    ) -> Result<(), EventError> {
+        let slot = self.dispatcher.register(channel, budget)?;
+        self.handlers[slot] = Some(handler);
+        Ok(())
+    }
+
+    /// Is work left over for the next notification?
+    pub fn pending(&self) -> bool {
+        self.dispatcher.pending()
+    }
+
+    /// Queue the `channels` that fired, then hand out turns until nobody
+    /// waits or `max_turns` have run. `events` finds the loop in `state`.
+    /// Channels without a handler are ignored.
+    ///
+    /// A handler's error ends the run and is returned: its channel's work
+    /// is dropped until the channel fires again, and everyone else still
+    /// waiting keeps their place for the next notification.
+    pub fn run(
+        state: &mut S,
+        events: fn(&mut S) -> &mut Self,
+        channels: impl IntoIterator<Item = usize>,
+    ) -> Result<(), E> {
+        for channel in channels {
+            events(state).dispatcher.notify(channel);
+        }
+        for _ in 0..events(state).max_turns {
+            let Some(turn) = events(state).dispatcher.next_turn() else {
+                break;
+            };
+            let result = match events(state).handlers[turn.slot()] {
+                Some(handler) => handler(state, turn.budget()),
+                None => Ok(Work::Done),
+            };
+            let work = match result {
+                Ok(work) => work,
+                Err(_) => Work::Done,
+            };
+            events(state).dispatcher.finish(turn, work);
+            result?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOOD: usize = 1;
+    const QUIET: usize = 2;
+
+    /// A PD with a channel that never runs dry and one with a little work
+    struct Pd {
+        events: EventLoop<Pd, &'static str, 2>,
+        /// Channel of each turn, in order
+        turns: [usize; 16],
+        count: usize,
+        quiet_work: u32,
+    }
+
+    impl Pd {
+        fn new(max_turns: u32) -> Self {
+            let mut pd = Pd {
+                events: EventLoop::new(max_turns),
+                turns: [0; 16],
+                count: 0,
+                quiet_work: 5,
+            };
+            pd.events.register(FLOOD, 8, Pd::flood).unwrap();
+            pd.events.register(QUIET, 2, Pd::quiet).unwrap();
+            pd
+        }
+
+        fn record(&mut self, channel: usize) {
+            self.turns[self.count] = channel;
+            self.count += 1;
+        }
+
+        fn flood(&mut self, _budget: u32) -> Result<Work, &'static str> {
+            self.record(FLOOD);
+            Ok(Work::Deferred)
+        }
+
+        fn quiet(&mut self, budget: u32) -> Result<Work, &'static str> {
+            self.record(QUIET);
+            self.quiet_work -= self.quiet_work.min(budget);
+            if self.quiet_work == 0 {
+                Err("drained")
+            } else {
+                Ok(Work::Deferred)
+            }
+        }
+
+        fn run(&mut self, channels: &[usize]) -> Result<(), &'static str> {
+            EventLoop::run(self, |pd| &mut pd.events, channels.iter().copied())
+        }
+    }
+
+    #[test]
+    fn flood_does_not_starve_other_channels() {
+        let mut pd = Pd::new(4);
+        // The flood fired first and defers every time; the quiet channel
+        // still gets every other turn
+        assert_eq!(pd.run(&[FLOOD, QUIET]), Ok(()));
+        assert_eq!(pd.turns[..pd.count], [FLOOD, QUIET, FLOOD, QUIET]);
+        assert!(pd.events.pending());
+
+        // Leftovers run on the next notification, whatever fired; the
+        // error ends that run and the flood keeps its place
+        assert_eq!(pd.run(&[]), Err("drained"));
+        assert_eq!(
+            pd.turns[..pd.count],
+            [FLOOD, QUIET, FLOOD, QUIET, FLOOD, QUIET]
+        );
+        assert_eq!(pd.run(&[7]), Ok(()));
+        assert_eq!(pd.count, 10);
+        assert!(pd.turns[6..10].iter().all(|&channel| channel == FLOOD));
+    }
+
+    #[test]
+    fn registration_is_checked() {
+        let mut events: EventLoop<Pd, &'static str, 2> = EventLoop::new(1);
+        assert_eq!(
+            events.register(MAX_CHANNELS, 1, Pd::flood),
+            Err(EventError::InvalidChannel)
+        );
+        assert_eq!(
+            events.register(0, 0, Pd::flood),
+            Err(EventError::ZeroBudget)
+        );
+        assert_eq!(events.register(0, 1, Pd::flood), Ok(()));
+        assert_eq!(events.register(0, 1, Pd::quiet), Err(EventError::Duplicate));
+        assert_eq!(events.register(5, 1, Pd::quiet), Ok(()));
+        assert_eq!(events.register(6, 1, Pd::quiet), Err(EventError::Full));
+    }
+
+    #[test]
+    fn dispatcher_serves_in_queue_order() {
+        let mut d = Dispatcher::<4>::new();
+        for (channel, budget) in [(3, 1), (9, 5), (4, 2)] {
+            d.register(channel, budget).unwrap();
+        }
+        assert!(!d.notify(10));
+        assert!(d.notify(9) && d.notify(4) && d.notify(9));
+
+        let turn = d.next_turn().unwrap();
+        assert_eq!((turn.channel(), turn.budget()), (9, 5));
+        // One turn at a time, and only the one out can end
+        assert_eq!(d.next_turn(), None);
+        assert!(!d.finish(
+            Turn {
+                slot: 2,
+                channel: 4,
+                budget: 2
+            },
+            Work::Done
+        ));
+        assert!(d.finish(turn, Work::Deferred));
+
+        d.notify(3);
+        let order: [usize; 3] = core::array::from_fn(|_| {
+            let turn = d.next_turn().unwrap();
+            let channel = turn.channel();
+            d.finish(turn, Work::Done);
+            channel
+        });
+        assert_eq!(order, [4, 9, 3]);
+        assert!(!d.pending());
+    }
+}
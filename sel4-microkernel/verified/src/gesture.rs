@@ -0,0 +1,172 @@
+//! # Touch Gesture Geometry
+//!
+//! The measurements a touch gesture recognizer makes between points: moving
+//! a point onto the panel, the signed and absolute travel along each axis,
+//! and whether a stroke stayed within the tap slop or went far enough to
+//! be a swipe. The SPI display's `GestureRecognizer` builds its taps,
+//! drags and swipes from these; they live here so they build and test on
+//! the host.
+//!
+//! ```rust
+//! use verified_microkernel::gesture::{axis_delta, axis_distance, swipe_axis, StrokeAxis};
+//!
+//! // A stroke from (200, 100) to (150, 110): left, and long enough
+//! assert_eq!(axis_delta(200, 150), -50);
+//! let (dx, dy) = (axis_distance(200, 150), axis_distance(100, 110));
+//! assert_eq!(swipe_axis(dx, dy), Some((StrokeAxis::X, 50)));
+//! ```
+//!
+//! ## Verification
+//!
+//! - A clamped coordinate lies on its axis, and one already on it is kept
+//! - On an axis shorter than [`MAX_AXIS_LEN`], the travel between two
+//!   points is exact in an `i16`, so a drag's `dx`/`dy` never wrap
+//! - A swipe is only reported along its longer axis and at least
+//!   [`SWIPE_MIN_DISTANCE`] long
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// Movement (pixels, per axis) below which a press still counts as a tap
+pub const TAP_SLOP: u16 = 8;
+
+/// Shortest travel (pixels, main axis) that counts as a swipe
+pub const SWIPE_MIN_DISTANCE: u16 = 40;
+
+/// Longest axis on which the travel between any two points fits an `i16`
+pub const MAX_AXIS_LEN: u16 = 0x8000;
+
+/// Axis a stroke mostly travels along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrokeAxis {
+    /// Left or right
+    X,
+    /// Up or down
+    Y,
+}
+
+/// `v` moved onto an axis `len` pixels long: itself if it is on it, the
+/// last pixel otherwise
+pub fn clamp_axis(v: u16, len: u16) -> (r: u16)
+    requires len > 0,
+    ensures
+        r < len,
+        v < len ==> r == v,
+{
+    if v < len { v } else { len - 1 }
+}
+
+/// Signed travel from `from` to `to` along one axis
+pub fn axis_delta(from: u16, to: u16) -> (d: i16)
+    requires
+        from < MAX_AXIS_LEN,
+        to < MAX_AXIS_LEN,
+    ensures d as int == to as int - from as int,
+{
+    (to as i16) - (from as i16)
+}
+
+/// Absolute travel from `from` to `to` along one axis
+// Spelled out: vstd has no spec for `u16::abs_diff`
+#[allow(clippy::manual_abs_diff)]
+pub fn axis_distance(from: u16, to: u16) -> (d: u16)
+    ensures d as int == crate::spec_abs_diff(from as int, to as int),
+{
+    if to >= from { to - from } else { from - to }
+}
+
+/// Did a stroke of `dx` by `dy` pixels stay close enough to be a tap?
+pub fn within_slop(dx: u16, dy: u16) -> (r: bool)
+    ensures r == (dx <= TAP_SLOP && dy <= TAP_SLOP),
+{
+    dx <= TAP_SLOP && dy <= TAP_SLOP
+}
+
+/// The main axis of a stroke of `dx` by `dy` pixels and its travel along
+/// it, if that is far enough for a swipe; a diagonal counts as `X`
+pub fn swipe_axis(dx: u16, dy: u16) -> (r: Option<(StrokeAxis, u16)>)
+    ensures
+        match r {
+            Some((StrokeAxis::X, d)) => d == dx && dx >= dy && dx >= SWIPE_MIN_DISTANCE,
+            Some((StrokeAxis::Y, d)) => d == dy && dy > dx && dy >= SWIPE_MIN_DISTANCE,
+            None => (dx >= dy ==> dx < SWIPE_MIN_DISTANCE)
+                && (dy > dx ==> dy < SWIPE_MIN_DISTANCE),
+        },
+{
+    if dx >= dy && dx >= SWIPE_MIN_DISTANCE {
+        Some((StrokeAxis::X, dx))
+    } else if dy > dx && dy >= SWIPE_MIN_DISTANCE {
+        Some((StrokeAxis::Y, dy))
+    } else {
+        None
+    }
+}
+
+} // verus!
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_at_the_panel_edges() {
+        // 320x240 panel
+        assert_eq!(clamp_axis(0, 320), 0);
+        assert_eq!(clamp_axis(319, 320), 319);
+        assert_eq!(clamp_axis(320, 320), 319);
+        assert_eq!(clamp_axis(u16::MAX, 320), 319);
+        assert_eq!(clamp_axis(239, 240), 239);
+        assert_eq!(clamp_axis(240, 240), 239);
+        assert_eq!(clamp_axis(4095, 240), 239);
+        assert_eq!(clamp_axis(7, 1), 0);
+    }
+
+    #[test]
+    fn deltas_are_signed_and_exact() {
+        assert_eq!(axis_delta(100, 160), 60);
+        assert_eq!(axis_delta(160, 100), -60);
+        assert_eq!(axis_delta(319, 0), -319);
+        assert_eq!(axis_delta(0, 319), 319);
+        assert_eq!(axis_delta(42, 42), 0);
+        assert_eq!(axis_delta(MAX_AXIS_LEN - 1, 0), -32767);
+        assert_eq!(axis_delta(0, MAX_AXIS_LEN - 1), 32767);
+    }
+
+    #[test]
+    fn distance_is_symmetric() {
+        assert_eq!(axis_distance(100, 160), 60);
+        assert_eq!(axis_distance(160, 100), 60);
+        assert_eq!(axis_distance(0, u16::MAX), u16::MAX);
+        assert_eq!(axis_distance(u16::MAX, 0), u16::MAX);
+        assert_eq!(axis_distance(7, 7), 0);
+    }
+
+    #[test]
+    fn tap_slop_threshold() {
+        assert!(within_slop(0, 0));
+        assert!(within_slop(TAP_SLOP, TAP_SLOP));
+        assert!(!within_slop(TAP_SLOP + 1, 0));
+        assert!(!within_slop(0, TAP_SLOP + 1));
+    }
+
+    #[test]
+    fn swipe_distance_threshold() {
+        let min = SWIPE_MIN_DISTANCE;
+        assert_eq!(swipe_axis(min, 0), Some((StrokeAxis::X, min)));
+        assert_eq!(swipe_axis(min - 1, 0), None);
+        assert_eq!(swipe_axis(0, min), Some((StrokeAxis::Y, min)));
+        assert_eq!(swipe_axis(0, min - 1), None);
+        // The longer axis wins, a diagonal goes to X
+        assert_eq!(swipe_axis(30, 50), Some((StrokeAxis::Y, 50)));
+        assert_eq!(swipe_axis(50, 30), Some((StrokeAxis::X, 50)));
+        assert_eq!(swipe_axis(min, min), Some((StrokeAxis::X, min)));
+        // Long enough, but on the shorter axis
+        assert_eq!(swipe_axis(30, 35), None);
+        // Anything a tap allows is far from a swipe
+        assert_eq!(swipe_axis(TAP_SLOP, TAP_SLOP), None);
+    }
+}
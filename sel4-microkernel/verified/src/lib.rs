@@ -28,6 +28,12 @@
 
 use verus_builtin_macros::verus;
 
+pub mod event_loop;
+pub mod gesture;
+pub mod ring;
+pub mod rgb565;
+pub mod rpc;
+
 verus! {
 
 // ============================================================================
@@ -543,8 +549,1201 @@ impl SlotAllocator {
     }
 }
 
+// ============================================================================
+// BOUNDED STRING
+// ============================================================================
+//
+// Fixed-capacity UTF-8 string for no_std UIs and protocols (photo names,
+// SSIDs, HTTP header values, menu labels). The byte-level operations are
+// verified to stay within capacity; the `&str` API below only ever appends
+// whole characters, so the contents are always valid UTF-8.
+
+/// A UTF-8 string stored inline in at most `N` bytes.
+#[derive(Clone, Copy)]
+pub struct BoundedString<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> BoundedString<N> {
+    /// Specification: is the string in a valid state?
+    pub open spec fn valid(&self) -> bool {
+        self.len <= N
+    }
+
+    /// Specification: length in bytes
+    pub open spec fn len_spec(&self) -> usize {
+        self.len
+    }
+
+    /// Create an empty string
+    pub const fn new() -> (s: Self)
+        ensures
+            s.valid(),
+            s.len_spec() == 0,
+    {
+        BoundedString { bytes: [0; N], len: 0 }
+    }
+
+    /// Length in bytes
+    pub fn len(&self) -> (l: usize)
+        requires self.valid(),
+        ensures
+            l == self.len_spec(),
+            l <= N,
+    {
+        self.len
+    }
+
+    /// Check if the string is empty
+    pub fn is_empty(&self) -> (empty: bool)
+        ensures empty == (self.len_spec() == 0),
+    {
+        self.len == 0
+    }
+
+    /// Capacity in bytes
+    pub fn capacity(&self) -> (c: usize)
+        ensures c == N,
+    {
+        N
+    }
+
+    /// Bytes still free
+    pub fn remaining(&self) -> (r: usize)
+        requires self.valid(),
+        ensures r == N - self.len_spec(),
+    {
+        N - self.len
+    }
+
+    /// Clear the string
+    pub fn clear(&mut self)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.len_spec() == 0,
+    {
+        self.len = 0;
+    }
+
+    /// Append the first `count` bytes of `src`
+    fn append_bytes(&mut self, src: &[u8], count: usize)
+        requires
+            old(self).valid(),
+            count <= src@.len(),
+            old(self).len_spec() + count <= N,
+        ensures
+            self.valid(),
+            self.len_spec() == old(self).len_spec() + count,
+    {
+        let mut i: usize = 0;
+        while i < count
+            invariant
+                i <= count,
+                count <= src@.len(),
+                self.len == old(self).len + i,
+                old(self).len + count <= N,
+            decreases count - i,
+        {
+            self.bytes[self.len] = src[i];
+            self.len = self.len + 1;
+            i = i + 1;
+        }
+    }
+
+    /// Shorten to `new_len` bytes; no effect if already shorter
+    fn truncate_bytes(&mut self, new_len: usize)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.len_spec() <= old(self).len_spec(),
+            self.len_spec() <= new_len,
+    {
+        if new_len < self.len {
+            self.len = new_len;
+        }
+    }
+}
+
+/// Is `b` a UTF-8 continuation byte (10xxxxxx)?
+pub open spec fn is_continuation_spec(b: u8) -> bool {
+    (b & 0xC0) == 0x80
+}
+
+/// Longest prefix of `bytes`, at most `max` bytes long, that does not end
+/// inside a UTF-8 character. `bytes` must be valid UTF-8.
+pub fn utf8_prefix_len(bytes: &[u8], max: usize) -> (cut: usize)
+    ensures
+        cut <= bytes@.len(),
+        max >= bytes@.len() ==> cut == bytes@.len(),
+        max < bytes@.len() ==> cut <= max,
+        0 < cut < bytes@.len() ==> !is_continuation_spec(bytes@[cut as int]),
+{
+    if max >= bytes.len() {
+        return bytes.len();
+    }
+    let mut cut = max;
+    while cut > 0 && (bytes[cut] & 0xC0) == 0x80
+        invariant
+            cut <= max,
+            max < bytes@.len(),
+        decreases cut,
+    {
+        cut = cut - 1;
+    }
+    cut
+}
+
+// ============================================================================
+// RING INDEX ARITHMETIC
+// ============================================================================
+//
+// Division-free replacements for `%` in ring-buffer index math. Each helper
+// is proven equal to the `%` expression it replaces, so ring specs can keep
+// stating their indices with `%`. `ring_pop_count` and `ring_push_count`
+// size a batch: never more than is waiting or free, so advancing an index
+// by the count keeps it inside the ring.
+
+/// Specification: is `n` a power of two?
+pub open spec fn is_pow2_spec(n: u32) -> bool {
+    n > 0 && n & ((n - 1) as u32) == 0
+}
+
+/// Masking with `n - 1` equals `% n` when `n` is a power of two
+pub proof fn lemma_pow2_mask_is_mod(x: u32, n: u32)
+    requires is_pow2_spec(n),
+    ensures x & ((n - 1) as u32) == x % n,
+{
+    assert(x & ((n - 1) as u32) == x % n) by (bit_vector)
+        requires n > 0u32 && n & ((n - 1) as u32) == 0u32;
+}
+
+/// Is `n` a power of two?
+pub fn is_pow2(n: u32) -> (result: bool)
+    ensures result == is_pow2_spec(n),
+{
+    n > 0 && n & (n - 1) == 0
+}
+
+/// `x % n`, by masking when `n` is a power of two
+pub fn ring_mod(x: u32, n: u32) -> (r: u32)
+    requires n > 0,
+    ensures
+        r == x % n,
+        r < n,
+{
+    if n & (n - 1) == 0 {
+        proof { lemma_pow2_mask_is_mod(x, n); }
+        x & (n - 1)
+    } else if x < n {
+        x
+    } else {
+        x % n
+    }
+}
+
+/// `(idx + 1) % n` for an index already inside the ring
+pub fn ring_next(idx: u32, n: u32) -> (next: u32)
+    requires idx < n,
+    ensures
+        next == (idx + 1) % (n as int),
+        next < n,
+{
+    if idx + 1 == n { 0 } else { idx + 1 }
+}
+
+/// `(idx + 1) % n` for a power-of-two ring, by masking
+pub fn ring_next_pow2(idx: u32, n: u32) -> (next: u32)
+    requires
+        is_pow2_spec(n),
+        idx < n,
+    ensures
+        next == (idx + 1) % (n as int),
+        next < n,
+{
+    proof { lemma_pow2_mask_is_mod((idx + 1) as u32, n); }
+    (idx + 1) & (n - 1)
+}
+
+/// `(idx + count) % n` for an index inside the ring and a step of at most `n`
+pub fn ring_add(idx: u32, count: u32, n: u32) -> (r: u32)
+    requires
+        idx < n,
+        count <= n,
+    ensures
+        r == (idx + count) % (n as int),
+        r < n,
+{
+    let room = n - idx;
+    if count >= room { count - room } else { idx + count }
+}
+
+/// Entries between `read` and `write` in a ring of `n` slots
+pub fn ring_len(write: u32, read: u32, n: u32) -> (len: u32)
+    requires
+        write < n,
+        read < n,
+    ensures
+        len == (write + n - read) % (n as int),
+        len < n,
+{
+    if write >= read { write - read } else { n - read + write }
+}
+
+/// Entries a reader wanting `want` may take in one batch: all of them, or
+/// as many as are waiting
+pub fn ring_pop_count(write: u32, read: u32, n: u32, want: usize) -> (count: u32)
+    requires
+        write < n,
+        read < n,
+    ensures
+        count <= (write + n - read) % (n as int),
+        count as int <= want as int,
+        count == (write + n - read) % (n as int) || count as int == want as int,
+        count < n,
+{
+    let len = ring_len(write, read, n);
+    if want < len as usize { want as u32 } else { len }
+}
+
+/// Entries a writer holding `want` may add in one batch: all of them, or
+/// as many as fit with one slot left empty to tell full from empty
+pub fn ring_push_count(write: u32, read: u32, n: u32, want: usize) -> (count: u32)
+    requires
+        write < n,
+        read < n,
+    ensures
+        count + (write + n - read) % (n as int) <= n - 1,
+        count as int <= want as int,
+        count + (write + n - read) % (n as int) == n - 1 || count as int == want as int,
+        count < n,
+{
+    let free = n - 1 - ring_len(write, read, n);
+    if want < free as usize { want as u32 } else { free }
+}
+
+/// `(idx + 1) % n` for an index already inside the ring
+pub fn ring_next_usize(idx: usize, n: usize) -> (next: usize)
+    requires idx < n,
+    ensures
+        next == (idx + 1) % (n as int),
+        next < n,
+{
+    if idx + 1 == n { 0 } else { idx + 1 }
+}
+
+/// `(idx + count) % n` for an index inside the ring and a step of at most `n`
+pub fn ring_add_usize(idx: usize, count: usize, n: usize) -> (r: usize)
+    requires
+        idx < n,
+        count <= n,
+    ensures
+        r == (idx + count) % (n as int),
+        r < n,
+{
+    let room = n - idx;
+    if count >= room { count - room } else { idx + count }
+}
+
+// ============================================================================
+// INTEGER MATH
+// ============================================================================
+//
+// Replacements for hand-rolled `.min()`/`.max()` chains with signed/unsigned
+// casts. None of them can panic or overflow: `clamp_*` picks `lo` when
+// `lo > hi` where `Ord::clamp` panics, `abs_diff_i32` and `midpoint_i32`
+// widen internally, and `div_round_up_*` never forms `n + d - 1`.
+
+/// Specification: the smaller of `a` and `b`
+pub open spec fn spec_min(a: int, b: int) -> int {
+    if a <= b { a } else { b }
+}
+
+/// Specification: the larger of `a` and `b`
+pub open spec fn spec_max(a: int, b: int) -> int {
+    if a >= b { a } else { b }
+}
+
+/// Specification: `v` limited to `lo..=hi`, with `lo` winning if `lo > hi`
+pub open spec fn spec_clamp(v: int, lo: int, hi: int) -> int {
+    if v < lo { lo } else if v > hi { hi } else { v }
+}
+
+/// Specification: distance between `a` and `b`
+pub open spec fn spec_abs_diff(a: int, b: int) -> int {
+    if a >= b { a - b } else { b - a }
+}
+
+/// The smaller of `a` and `b`
+pub fn min_i32(a: i32, b: i32) -> (r: i32)
+    ensures r == spec_min(a as int, b as int),
+{
+    if a <= b { a } else { b }
+}
+
+/// The larger of `a` and `b`
+pub fn max_i32(a: i32, b: i32) -> (r: i32)
+    ensures r == spec_max(a as int, b as int),
+{
+    if a >= b { a } else { b }
+}
+
+/// The smaller of `a` and `b`
+pub fn min_u32(a: u32, b: u32) -> (r: u32)
+    ensures r == spec_min(a as int, b as int),
+{
+    if a <= b { a } else { b }
+}
+
+/// The larger of `a` and `b`
+pub fn max_u32(a: u32, b: u32) -> (r: u32)
+    ensures r == spec_max(a as int, b as int),
+{
+    if a >= b { a } else { b }
+}
+
+/// `v` limited to `lo..=hi`
+pub fn clamp_i32(v: i32, lo: i32, hi: i32) -> (r: i32)
+    ensures
+        r == spec_clamp(v as int, lo as int, hi as int),
+        lo <= hi ==> lo <= r <= hi,
+        lo <= v <= hi ==> r == v,
+{
+    if v < lo {
+        lo
+    } else if v > hi {
+        hi
+    } else {
+        v
+    }
+}
+
+/// `v` limited to `lo..=hi`
+pub fn clamp_u32(v: u32, lo: u32, hi: u32) -> (r: u32)
+    ensures
+        r == spec_clamp(v as int, lo as int, hi as int),
+        lo <= hi ==> lo <= r <= hi,
+        lo <= v <= hi ==> r == v,
+{
+    if v < lo {
+        lo
+    } else if v > hi {
+        hi
+    } else {
+        v
+    }
+}
+
+/// `|a - b|`, which always fits a `u32`
+pub fn abs_diff_i32(a: i32, b: i32) -> (r: u32)
+    ensures r == spec_abs_diff(a as int, b as int),
+{
+    if a >= b {
+        (a as i64 - b as i64) as u32
+    } else {
+        (b as i64 - a as i64) as u32
+    }
+}
+
+/// `|a - b|`
+// Spelled out: vstd has no spec for `u32::abs_diff`
+#[allow(clippy::manual_abs_diff)]
+pub fn abs_diff_u32(a: u32, b: u32) -> (r: u32)
+    ensures r == spec_abs_diff(a as int, b as int),
+{
+    if a >= b { a - b } else { b - a }
+}
+
+/// `(a + b) / 2` rounded down, without overflow
+pub fn midpoint_i32(a: i32, b: i32) -> (r: i32)
+    ensures
+        spec_min(a as int, b as int) <= r <= spec_max(a as int, b as int),
+        2 * r <= a + b <= 2 * r + 1,
+{
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let half = (hi as i64 - lo as i64) as u32 / 2;
+    (lo as i64 + half as i64) as i32
+}
+
+/// `(a + b) / 2` rounded down, without overflow
+pub fn midpoint_u32(a: u32, b: u32) -> (r: u32)
+    ensures
+        spec_min(a as int, b as int) <= r <= spec_max(a as int, b as int),
+        2 * r <= a + b <= 2 * r + 1,
+{
+    if a <= b {
+        a + (b - a) / 2
+    } else {
+        b + (a - b) / 2
+    }
+}
+
+/// `n / d` rounded up: the fewest `d`-sized pieces that cover `n`
+pub fn div_round_up_u32(n: u32, d: u32) -> (r: u32)
+    requires d > 0,
+    ensures
+        r * d >= n,
+        (r - 1) * d < n,
+{
+    if n == 0 {
+        return 0;
+    }
+    let q = (n - 1) / d;
+    proof {
+        assert(q <= n - 1 && (q + 1) * d >= n && q * d < n) by (nonlinear_arith)
+            requires q == (n - 1) / d, d > 0, n > 0;
+    }
+    q + 1
+}
+
+/// `n / d` rounded up: the fewest `d`-sized pieces that cover `n`
+pub fn div_round_up_usize(n: usize, d: usize) -> (r: usize)
+    requires d > 0,
+    ensures
+        r * d >= n,
+        (r - 1) * d < n,
+{
+    if n == 0 {
+        return 0;
+    }
+    let q = (n - 1) / d;
+    proof {
+        assert(q <= n - 1 && (q + 1) * d >= n && q * d < n) by (nonlinear_arith)
+            requires q == (n - 1) / d, d > 0, n > 0;
+    }
+    q + 1
+}
+
+/// `v` if it is in `lo..hi`; otherwise the opposite end of the range, for a
+/// position that steps off one edge and reappears at the other
+pub fn wrap_i32(v: i32, lo: i32, hi: i32) -> (r: i32)
+    requires lo < hi,
+    ensures
+        lo <= r < hi,
+        lo <= v < hi ==> r == v,
+        v < lo ==> r == hi - 1,
+        v >= hi ==> r == lo,
+{
+    if v < lo {
+        hi - 1
+    } else if v >= hi {
+        lo
+    } else {
+        v
+    }
+}
+
+// ============================================================================
+// SPI CLOCK NEGOTIATION (Verified)
+// ============================================================================
+//
+// The BCM2711 SPI controllers clock SCLK at `core_clock / CDIV`, where CDIV
+// is an even number from 2 to 65534. Every device on the bus has its own
+// ceiling, so each gets its own divider, computed against the core clock
+// the firmware reports rather than a hard-coded one.
+
+/// Core clock to divide from when the mailbox cannot be asked: the Pi 4
+/// firmware's default `core_freq`
+pub const SPI_DEFAULT_CORE_HZ: u32 = 500_000_000;
+
+/// Fastest SPI divider: SCLK at half the core clock
+pub const SPI_MIN_DIVIDER: u32 = 2;
+
+/// Slowest SPI divider that fits in the CLK register's 16 bits (0 means
+/// 65536, which we never program)
+pub const SPI_MAX_DIVIDER: u32 = 65534;
+
+/// Specification: a divider the CLK register accepts
+pub open spec fn valid_spi_divider(d: u32) -> bool {
+    SPI_MIN_DIVIDER <= d && d <= SPI_MAX_DIVIDER && d % 2 == 0
+}
+
+/// The smallest valid divider that keeps SCLK at or below `max_hz`; the
+/// slowest divider when even that is too fast
+pub fn spi_divider(core_hz: u32, max_hz: u32) -> (d: u32)
+    requires max_hz > 0,
+    ensures
+        valid_spi_divider(d),
+        d < SPI_MAX_DIVIDER ==> core_hz as int <= max_hz as int * d as int,
+        d > SPI_MIN_DIVIDER ==> core_hz as int > max_hz as int * (d - 2) as int,
+{
+    let exact = div_round_up_u32(core_hz, max_hz);
+    if exact >= SPI_MAX_DIVIDER {
+        SPI_MAX_DIVIDER
+    } else if exact <= SPI_MIN_DIVIDER {
+        proof {
+            assert(max_hz as int * exact as int <= max_hz as int * 2) by (nonlinear_arith)
+                requires exact <= 2;
+        }
+        SPI_MIN_DIVIDER
+    } else {
+        // Rounding up to even at most adds one, so the divider below is
+        // still too fast
+        proof {
+            assert(core_hz as int <= max_hz as int * (exact + exact % 2) as int
+                && core_hz as int > max_hz as int * (exact + exact % 2 - 2) as int) by (nonlinear_arith)
+                requires
+                    exact % 2 <= 1,
+                    core_hz as int <= exact as int * max_hz as int,
+                    ((exact - 1) as int * max_hz as int) < (core_hz as int);
+        }
+        exact + exact % 2
+    }
+}
+
+/// The largest valid divider that keeps SCLK at or above `min_hz`; the
+/// fastest divider when even that is too slow
+pub fn spi_floor_divider(core_hz: u32, min_hz: u32) -> (d: u32)
+    requires min_hz > 0,
+    ensures
+        valid_spi_divider(d),
+        d > SPI_MIN_DIVIDER ==> core_hz as int >= min_hz as int * d as int,
+{
+    let exact = core_hz / min_hz;
+    proof {
+        assert(min_hz as int * exact as int <= core_hz as int) by (nonlinear_arith)
+            requires exact == core_hz / min_hz, min_hz > 0;
+    }
+    if exact >= SPI_MAX_DIVIDER {
+        SPI_MAX_DIVIDER
+    } else if exact <= SPI_MIN_DIVIDER {
+        SPI_MIN_DIVIDER
+    } else {
+        proof {
+            assert(min_hz as int * (exact - exact % 2) as int <= min_hz as int * exact as int) by (nonlinear_arith)
+                requires min_hz > 0, exact % 2 <= 1, exact >= 2;
+        }
+        exact - exact % 2
+    }
+}
+
+/// One device's SPI clock: the divider its ceiling allows, and how far it
+/// may be slowed after transfer errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpiClock {
+    core_hz: u32,
+    divider: u32,
+    /// Slowest divider the device is still specified for
+    floor: u32,
+}
+
+impl SpiClock {
+    /// The slowest clock the controller can make, for a device not yet
+    /// negotiated
+    pub const SLOWEST: SpiClock = SpiClock {
+        core_hz: SPI_DEFAULT_CORE_HZ,
+        divider: SPI_MAX_DIVIDER,
+        floor: SPI_MAX_DIVIDER,
+    };
+
+    /// Specification: both dividers valid, and not yet slowed past the floor
+    pub open spec fn valid(&self) -> bool {
+        valid_spi_divider(self.divider) && valid_spi_divider(self.floor) && self.divider <= self.floor
+    }
+
+    pub open spec fn divider_spec(&self) -> u32 {
+        self.divider
+    }
+
+    pub open spec fn floor_spec(&self) -> u32 {
+        self.floor
+    }
+
+    /// Run a device rated for `min_hz..=max_hz` off a `core_hz` core clock,
+    /// as fast as it allows
+    pub fn negotiate(core_hz: u32, max_hz: u32, min_hz: u32) -> (clock: Self)
+        requires 0 < min_hz,
+            min_hz <= max_hz,
+        ensures
+            clock.valid(),
+            clock.divider_spec() < SPI_MAX_DIVIDER ==> core_hz as int <= max_hz as int * clock.divider_spec() as int,
+    {
+        let divider = spi_divider(core_hz, max_hz);
+        let floor = max_u32(spi_floor_divider(core_hz, min_hz), divider);
+        SpiClock { core_hz, divider, floor }
+    }
+
+    /// The CLK register value
+    pub fn divider(&self) -> (d: u32)
+        ensures d == self.divider_spec(),
+    {
+        self.divider
+    }
+
+    /// SCLK frequency
+    pub fn hz(&self) -> u32
+        requires self.valid(),
+    {
+        self.core_hz / self.divider
+    }
+
+    /// Has the clock been slowed as far as the device allows?
+    pub fn at_floor(&self) -> (r: bool)
+        ensures r == (self.divider_spec() == self.floor_spec()),
+    {
+        self.divider == self.floor
+    }
+
+    /// Halve the clock after a transfer error, stopping at the floor; false
+    /// when it was already there
+    pub fn slow_down(&mut self) -> (slowed: bool)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.floor_spec() == old(self).floor_spec(),
+            slowed == (old(self).divider_spec() < old(self).floor_spec()),
+            slowed ==> self.divider_spec() > old(self).divider_spec(),
+            !slowed ==> self.divider_spec() == old(self).divider_spec(),
+    {
+        if self.divider >= self.floor {
+            return false;
+        }
+        self.divider = min_u32(self.divider * 2, self.floor);
+        true
+    }
+}
+
+// ============================================================================
+// SPI BUS ARBITRATION (Verified)
+// ============================================================================
+//
+// The display, touch controller, TPM and SD card can all sit on SPI0, each
+// behind its own chip select. A transfer to one while another is on the
+// wire corrupts both, so drivers take the bus from an arbiter first: one
+// owner at a time, waiting clients served in arrival order, and an owner
+// that holds the bus past its limit loses it.
+
+/// Clients of a shared SPI bus
+pub const SPI_CLIENT_DISPLAY: u8 = 0;
+pub const SPI_CLIENT_TOUCH: u8 = 1;
+pub const SPI_CLIENT_TPM: u8 = 2;
+pub const SPI_CLIENT_SD: u8 = 3;
+
+/// Number of SPI bus clients
+pub const SPI_BUS_CLIENTS: usize = 4;
+
+/// `owner` when the bus is free
+const NO_OWNER: u8 = 0xFF;
+
+/// Why the bus was not granted or released
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusError {
+    /// Not one of the `SPI_CLIENT_*` ids
+    InvalidClient,
+    /// Another client has the bus; this one is queued and will be named by
+    /// `release` or `expire` when its turn comes
+    Queued,
+    /// The client already owns the bus
+    AlreadyOwner,
+    /// The grant was revoked for holding the bus too long
+    Revoked,
+}
+
+/// A client that ran out of time: an owner that held the bus too long (its
+/// transfer should be aborted), or a waiting client that did not take the
+/// bus when its turn came
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Expired {
+    pub client: u8,
+    /// The client whose turn it is now, to be woken
+    pub next: Option<u8>,
+}
+
+/// Proof of owning the bus, handed out by `SpiArbiter::acquire` and given
+/// back to `SpiArbiter::release`. Not `Clone`: there is only ever one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BusGrant {
+    client: u8,
+    generation: u32,
+}
+
+impl BusGrant {
+    pub open spec fn client_spec(&self) -> u8 {
+        self.client
+    }
+
+    /// The client the bus was granted to
+    pub fn client(&self) -> (c: u8)
+        ensures c == self.client_spec(),
+    {
+        self.client
+    }
+}
+
+/// Ownership of a shared SPI bus
+pub struct SpiArbiter {
+    /// `NO_OWNER`, or the client holding the bus
+    owner: u8,
+    /// Tick the owner took the bus at, or the free bus was offered to the
+    /// head of the queue at
+    since: u64,
+    /// Bumped on every hand-over, so a revoked grant no longer matches
+    generation: u32,
+    /// Waiting clients, oldest first; `queue[..queued]` is in use
+    queue: [u8; SPI_BUS_CLIENTS],
+    queued: usize,
+    /// Longest each client may hold the bus, in ticks
+    max_hold: [u64; SPI_BUS_CLIENTS],
+}
+
+impl SpiArbiter {
+    /// Specification: `client` is waiting
+    pub open spec fn waiting(&self, client: u8) -> bool {
+        exists|i: int| 0 <= i < self.queued && self.queue[i] == client
+    }
+
+    /// Specification: is the arbiter in a valid state?
+    ///
+    /// At most one owner, every waiting client is a real one, waits once,
+    /// and is not the owner.
+    pub open spec fn valid(&self) -> bool {
+        &&& self.queued <= SPI_BUS_CLIENTS
+        &&& (self.owner == NO_OWNER || (self.owner as int) < SPI_BUS_CLIENTS)
+        &&& forall|i: int| 0 <= i < self.queued ==> (self.queue[i] as int) < SPI_BUS_CLIENTS
+        &&& forall|i: int| 0 <= i < self.queued ==> self.queue[i] != self.owner
+        &&& forall|i: int, j: int| 0 <= i < j < self.queued ==> self.queue[i] != self.queue[j]
+    }
+
+    pub open spec fn owner_spec(&self) -> u8 {
+        self.owner
+    }
+
+    /// A free bus; client `i` may hold it for `max_hold[i]` ticks
+    pub fn new(max_hold: [u64; SPI_BUS_CLIENTS]) -> (a: Self)
+        ensures
+            a.valid(),
+            a.owner_spec() == NO_OWNER,
+    {
+        SpiArbiter {
+            owner: NO_OWNER,
+            since: 0,
+            generation: 0,
+            queue: [NO_OWNER; SPI_BUS_CLIENTS],
+            queued: 0,
+            max_hold,
+        }
+    }
+
+    /// The client holding the bus
+    pub fn owner(&self) -> Option<u8> {
+        if self.owner == NO_OWNER {
+            None
+        } else {
+            Some(self.owner)
+        }
+    }
+
+    /// Number of clients waiting
+    pub fn waiting_count(&self) -> usize {
+        self.queued
+    }
+
+    /// Is `grant` still the live grant?
+    pub fn holds(&self, grant: &BusGrant) -> (live: bool)
+        ensures live ==> grant.client_spec() == self.owner_spec(),
+    {
+        grant.client == self.owner && grant.generation == self.generation
+    }
+
+    /// Take the bus for `client` at tick `now`
+    ///
+    /// A free bus goes to the client at the head of the queue, or to anyone
+    /// when nobody waits; otherwise `client` joins the queue (once).
+    pub fn acquire(&mut self, client: u8, now: u64) -> (result: Result<BusGrant, BusError>)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            result.is_ok() ==> self.owner_spec() == client,
+            result.is_ok() ==> result.unwrap().client_spec() == client,
+            result.is_ok() ==> old(self).owner_spec() == NO_OWNER,
+    {
+        if client as usize >= SPI_BUS_CLIENTS {
+            return Err(BusError::InvalidClient);
+        }
+        if self.owner == client {
+            return Err(BusError::AlreadyOwner);
+        }
+        if self.owner == NO_OWNER && (self.queued == 0 || self.queue[0] == client) {
+            if self.queued > 0 {
+                self.pop_front();
+            }
+            self.owner = client;
+            self.since = now;
+            self.generation = self.generation.wrapping_add(1);
+            return Ok(BusGrant { client, generation: self.generation });
+        }
+        self.enqueue(client);
+        Err(BusError::Queued)
+    }
+
+    /// Give the bus back at tick `now`; returns the client whose turn it is
+    /// now, to be woken so it retries `acquire`
+    pub fn release(&mut self, grant: BusGrant, now: u64) -> (result: Result<Option<u8>, BusError>)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            result.is_ok() ==> self.owner_spec() == NO_OWNER,
+            result.is_err() ==> self.owner_spec() == old(self).owner_spec(),
+    {
+        if !self.holds(&grant) {
+            return Err(BusError::Revoked);
+        }
+        self.owner = NO_OWNER;
+        self.since = now;
+        Ok(self.next_in_line())
+    }
+
+    /// Enforce the hold limits at tick `now`: revoke the bus from an owner
+    /// that has held it longer than allowed, or pass over a waiting client
+    /// that has not taken a free bus within its own limit
+    pub fn expire(&mut self, now: u64) -> (result: Option<Expired>)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            result.is_some() ==> self.owner_spec() == NO_OWNER,
+            result.is_none() ==> self.owner_spec() == old(self).owner_spec(),
+    {
+        let client = if self.owner != NO_OWNER {
+            self.owner
+        } else if self.queued > 0 {
+            self.queue[0]
+        } else {
+            return None;
+        };
+        if now.wrapping_sub(self.since) <= self.max_hold[client as usize] {
+            return None;
+        }
+        if self.owner != NO_OWNER {
+            self.owner = NO_OWNER;
+            // The revoked grant no longer matches
+            self.generation = self.generation.wrapping_add(1);
+        } else {
+            self.pop_front();
+        }
+        self.since = now;
+        Some(Expired { client, next: self.next_in_line() })
+    }
+
+    /// The client at the head of the queue
+    fn next_in_line(&self) -> (next: Option<u8>)
+        requires self.valid(),
+        ensures next.is_some() ==> self.queued > 0,
+    {
+        if self.queued > 0 {
+            Some(self.queue[0])
+        } else {
+            None
+        }
+    }
+
+    /// Add `client` to the back of the queue unless already waiting
+    fn enqueue(&mut self, client: u8)
+        requires
+            old(self).valid(),
+            (client as int) < SPI_BUS_CLIENTS,
+            client != old(self).owner_spec(),
+        ensures self.valid(),
+    {
+        let mut i = 0;
+        while i < self.queued
+            invariant
+                self.valid(),
+                i <= self.queued,
+                forall|k: int| 0 <= k < i ==> self.queue[k] != client,
+        {
+            if self.queue[i] == client {
+                return;
+            }
+            i += 1;
+        }
+        // `queued` distinct clients below SPI_BUS_CLIENTS, none of them
+        // `client`, so there is room
+        if self.queued < SPI_BUS_CLIENTS {
+            self.queue[self.queued] = client;
+            self.queued += 1;
+        }
+    }
+
+    /// Drop the head of the queue
+    fn pop_front(&mut self)
+        requires
+            old(self).valid(),
+            old(self).queued > 0,
+        ensures
+            self.valid(),
+            self.queued == old(self).queued - 1,
+            self.owner_spec() == old(self).owner_spec(),
+    {
+        let mut i = 0;
+        while i + 1 < self.queued
+            invariant
+                old(self).valid(),
+                self.queued == old(self).queued,
+                self.owner == old(self).owner,
+                i + 1 <= self.queued,
+                forall|k: int| 0 <= k < i ==> self.queue[k] == old(self).queue[k + 1],
+                forall|k: int| i <= k < self.queued ==> self.queue[k] == old(self).queue[k],
+        {
+            self.queue[i] = self.queue[i + 1];
+            i += 1;
+        }
+        self.queued -= 1;
+    }
+}
+
+// ============================================================================
+// ANTI-ROLLBACK (Verified)
+// ============================================================================
+//
+// A monotonic counter in TPM NV holds the lowest security version the board
+// may boot. The ordering is what makes it safe: the running image's version
+// is compared with the counter first, and only an accepted image advances
+// the counter, one NV_Increment at a time, never past its own version. A
+// downgrade never touches the counter, and an upgrade interrupted part way
+// leaves a counter the new image still satisfies.
+
+/// Most NV_Increments one boot performs. NV writes wear the TPM, so a large
+/// version jump is caught up over several boots.
+pub const ROLLBACK_MAX_STEPS: u64 = 16;
+
+/// Specification: `version` may boot with the counter at `counter`
+pub open spec fn rollback_allowed(counter: u64, version: u64) -> bool {
+    counter <= version
+}
+
+/// An image accepted against the NV counter, and how far the counter may
+/// still advance this boot. Only [`RollbackGuard::check`] makes one, so the
+/// counter cannot be advanced before the comparison has passed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RollbackGuard {
+    version: u64,
+    counter: u64,
+    /// Increments left this boot
+    budget: u64,
+}
+
+impl RollbackGuard {
+    /// Specification: the image still satisfies the counter
+    pub open spec fn valid(&self) -> bool {
+        rollback_allowed(self.counter, self.version) && self.budget <= ROLLBACK_MAX_STEPS
+    }
+
+    pub open spec fn version_spec(&self) -> u64 {
+        self.version
+    }
+
+    pub open spec fn counter_spec(&self) -> u64 {
+        self.counter
+    }
+
+    pub open spec fn budget_spec(&self) -> u64 {
+        self.budget
+    }
+
+    /// Compare the running image's `version` with the `counter` read from
+    /// NV; `None` for a downgrade
+    pub fn check(counter: u64, version: u64) -> (guard: Option<Self>)
+        ensures
+            guard.is_some() == rollback_allowed(counter, version),
+            guard.is_some() ==> guard.unwrap().valid()
+                && guard.unwrap().counter_spec() == counter
+                && guard.unwrap().version_spec() == version,
+    {
+        if counter <= version {
+            Some(RollbackGuard { version, counter, budget: ROLLBACK_MAX_STEPS })
+        } else {
+            None
+        }
+    }
+
+    /// The running image's version
+    pub fn version(&self) -> (v: u64)
+        ensures v == self.version_spec(),
+    {
+        self.version
+    }
+
+    /// The counter as last read back
+    pub fn counter(&self) -> (c: u64)
+        ensures c == self.counter_spec(),
+    {
+        self.counter
+    }
+
+    /// Is another NV_Increment due? Only while the counter is below the
+    /// version and this boot's budget lasts.
+    pub fn needs_increment(&self) -> (r: bool)
+        ensures r == (self.counter_spec() < self.version_spec() && self.budget_spec() > 0),
+    {
+        self.counter < self.version && self.budget > 0
+    }
+
+    /// Record the counter read back after an NV_Increment. False, leaving
+    /// the guard unchanged, unless an increment was due and the counter
+    /// moved by exactly one.
+    pub fn record_increment(&mut self, counter: u64) -> (ok: bool)
+        requires old(self).valid(),
+        ensures
+            self.valid(),
+            self.version_spec() == old(self).version_spec(),
+            self.counter_spec() >= old(self).counter_spec(),
+            ok == (old(self).counter_spec() < old(self).version_spec()
+                && old(self).budget_spec() > 0
+                && counter == old(self).counter_spec() + 1),
+            ok ==> self.counter_spec() == counter && self.budget_spec() == old(self).budget_spec() - 1,
+            !ok ==> self.counter_spec() == old(self).counter_spec() && self.budget_spec() == old(self).budget_spec(),
+    {
+        if self.counter < self.version && self.budget > 0 && counter == self.counter + 1 {
+            self.counter = counter;
+            self.budget = self.budget - 1;
+            true
+        } else {
+            false
+        }
+    }
+}
 } // verus!
 
+// ============================================================================
+// BOUNDED STRING: NON-VERIFIED RUNTIME HELPERS
+// ============================================================================
+//
+// `&str` and `core::fmt` glue around the verified byte operations above.
+
+/// A [`BoundedString`] append did not fit; nothing was appended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+impl<const N: usize> BoundedString<N> {
+    /// Copy as much of `s` as fits, cutting at a character boundary
+    pub fn from_str_truncating(s: &str) -> Self {
+        let mut out = Self::new();
+        out.push_str_truncating(s);
+        out
+    }
+
+    /// Render `args` (see [`format_args!`]), or fail if the result does not fit
+    pub fn format(args: core::fmt::Arguments<'_>) -> Result<Self, CapacityError> {
+        let mut out = Self::new();
+        core::fmt::Write::write_fmt(&mut out, args).map_err(|_| CapacityError)?;
+        Ok(out)
+    }
+
+    /// The contents as a string slice
+    pub fn as_str(&self) -> &str {
+        // Only whole characters are ever appended, so this cannot fail
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+    }
+
+    /// The contents as bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Append all of `s`, or nothing if it does not fit
+    pub fn push_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        if s.len() > self.remaining() {
+            return Err(CapacityError);
+        }
+        self.append_bytes(s.as_bytes(), s.len());
+        Ok(())
+    }
+
+    /// Append as much of `s` as fits, cutting at a character boundary.
+    /// Returns the number of bytes appended.
+    pub fn push_str_truncating(&mut self, s: &str) -> usize {
+        let count = utf8_prefix_len(s.as_bytes(), self.remaining());
+        self.append_bytes(s.as_bytes(), count);
+        count
+    }
+
+    /// Append one character, or nothing if it does not fit
+    pub fn push(&mut self, c: char) -> Result<(), CapacityError> {
+        let mut buf = [0u8; 4];
+        self.push_str(c.encode_utf8(&mut buf))
+    }
+
+    /// Shorten to at most `max` bytes, cutting at a character boundary
+    pub fn truncate(&mut self, max: usize) {
+        let cut = utf8_prefix_len(&self.bytes[..self.len], max);
+        self.truncate_bytes(cut);
+    }
+}
+
+impl<const N: usize> Default for BoundedString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> core::ops::Deref for BoundedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq for BoundedString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<const N: usize> Eq for BoundedString<N> {}
+
+impl<const N: usize> PartialEq<str> for BoundedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for BoundedString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Copies `s`, or fails if it is longer than `N` bytes
+impl<const N: usize> core::str::FromStr for BoundedString<N> {
+    type Err = CapacityError;
+
+    fn from_str(s: &str) -> Result<Self, CapacityError> {
+        let mut out = Self::new();
+        out.push_str(s)?;
+        Ok(out)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for BoundedString<N> {
+    type Error = CapacityError;
+
+    fn try_from(s: &str) -> Result<Self, CapacityError> {
+        s.parse()
+    }
+}
+
+/// Writes that overflow fail with [`core::fmt::Error`]; whole `write_str`
+/// pieces that fitted before the failure are kept.
+impl<const N: usize> core::fmt::Write for BoundedString<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push_str(s).map_err(|_| core::fmt::Error)
+    }
+}
+
+impl<const N: usize> core::fmt::Display for BoundedString<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for BoundedString<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -603,4 +1802,221 @@ mod tests {
         assert!(alloc.free(slot1.unwrap()));
         assert!(!alloc.free(slot1.unwrap())); // Double free
     }
+
+    #[test]
+    fn test_bounded_string() {
+        let mut s = BoundedString::<8>::new();
+        assert!(s.is_empty());
+        assert_eq!(s.push_str("menu"), Ok(()));
+        assert_eq!(s.remaining(), 4);
+        assert_eq!(s.push_str("label"), Err(CapacityError));
+        assert_eq!(s, "menu"); // failed push leaves it unchanged
+        assert_eq!(s.push('!'), Ok(()));
+        assert_eq!(s.as_str(), "menu!");
+
+        assert_eq!(
+            "ssid-too-long".parse::<BoundedString<4>>(),
+            Err(CapacityError)
+        );
+        let ok: BoundedString<4> = "ssid".try_into().unwrap();
+        assert_eq!(ok.len(), 4);
+    }
+
+    #[test]
+    fn test_bounded_string_utf8_boundaries() {
+        // "é" is two bytes; a 4-byte string takes "caf" and drops the split "é"
+        let mut s = BoundedString::<4>::from_str_truncating("café!");
+        assert_eq!(s, "caf");
+        assert_eq!(s.push_str_truncating("é"), 0);
+        assert_eq!(s.push('é'), Err(CapacityError));
+
+        let mut s: BoundedString<16> = "naïve ünïcode".parse().unwrap();
+        s.truncate(3);
+        assert_eq!(s, "na");
+        assert_eq!(utf8_prefix_len("€".as_bytes(), 2), 0);
+        assert_eq!(utf8_prefix_len("€".as_bytes(), 3), 3);
+    }
+
+    #[test]
+    fn test_bounded_string_format() {
+        let s = BoundedString::<16>::format(format_args!("{}x{}@{}", 1280, 720, 60)).unwrap();
+        assert_eq!(s, "1280x720@60");
+        assert_eq!(
+            BoundedString::<4>::format(format_args!("{}", 123456)),
+            Err(CapacityError)
+        );
+    }
+
+    #[test]
+    fn test_ring_helpers_match_mod() {
+        for n in [1u32, 2, 3, 7, 8, 64, 1000, 1024] {
+            assert_eq!(is_pow2(n), n.is_power_of_two());
+            for x in (0..3 * n).chain([u32::MAX - 1, u32::MAX]) {
+                assert_eq!(ring_mod(x, n), x % n);
+            }
+            for idx in 0..n {
+                assert_eq!(ring_next(idx, n), (idx + 1) % n);
+                for count in [0, 1, n / 2, n - 1, n] {
+                    assert_eq!(ring_add(idx, count, n), (idx + count) % n);
+                }
+                for write in [0, idx / 2, idx, n - 1] {
+                    assert_eq!(ring_len(write, idx, n), (write + n - idx) % n);
+                }
+                if n.is_power_of_two() {
+                    assert_eq!(ring_next_pow2(idx, n), (idx + 1) % n);
+                }
+                let (idx, n) = (idx as usize, n as usize);
+                assert_eq!(ring_next_usize(idx, n), (idx + 1) % n);
+                for count in [0, 1, n / 2, n - 1, n] {
+                    assert_eq!(ring_add_usize(idx, count, n), (idx + count) % n);
+                }
+            }
+        }
+        assert!(!is_pow2(0));
+    }
+
+    #[test]
+    fn test_ring_batch_counts_stay_in_bounds() {
+        for n in [1u32, 2, 7, 8] {
+            for write in 0..n {
+                for read in 0..n {
+                    let len = (write + n - read) % n;
+                    for want in [0usize, 1, 3, n as usize, usize::MAX] {
+                        let pop = ring_pop_count(write, read, n, want);
+                        assert_eq!(pop as usize, (len as usize).min(want));
+                        let push = ring_push_count(write, read, n, want);
+                        assert_eq!(push as usize, ((n - 1 - len) as usize).min(want));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_clamp_never_panics() {
+        assert_eq!(clamp_i32(-5, 0, 10), 0);
+        assert_eq!(clamp_i32(15, 0, 10), 10);
+        assert_eq!(clamp_i32(7, 0, 10), 7);
+        // An empty range picks `lo` where `Ord::clamp` would panic
+        assert_eq!(clamp_i32(7, 10, 0), 10);
+        assert_eq!(clamp_u32(3, 5, 1), 5);
+    }
+
+    #[test]
+    fn test_abs_diff_and_midpoint_extremes() {
+        assert_eq!(abs_diff_i32(i32::MIN, i32::MAX), u32::MAX);
+        assert_eq!(abs_diff_u32(3, 10), 7);
+        assert_eq!(midpoint_i32(i32::MIN, i32::MAX), -1);
+        assert_eq!(midpoint_i32(-3, 0), -2);
+        assert_eq!(midpoint_u32(u32::MAX, u32::MAX - 2), u32::MAX - 1);
+    }
+
+    #[test]
+    fn test_div_round_up_and_wrap() {
+        assert_eq!(div_round_up_u32(0, 7), 0);
+        assert_eq!(div_round_up_u32(14, 7), 2);
+        assert_eq!(div_round_up_u32(15, 7), 3);
+        assert_eq!(div_round_up_u32(u32::MAX, 2), 1 << 31);
+        assert_eq!(div_round_up_usize(4097, 4096), 2);
+        assert_eq!(wrap_i32(-1, 0, 10), 9);
+        assert_eq!(wrap_i32(10, 0, 10), 0);
+        assert_eq!(wrap_i32(4, 0, 10), 4);
+    }
+
+    #[test]
+    fn test_spi_divider_bounds() {
+        // 500 MHz core: 62.5 MHz for a 64 MHz ceiling, 20.8 MHz for 22.5 MHz
+        assert_eq!(spi_divider(500_000_000, 64_000_000), 8);
+        assert_eq!(spi_divider(500_000_000, 22_500_000), 24);
+        assert_eq!(spi_divider(500_000_000, 2_000_000), 250);
+        assert_eq!(spi_divider(500_000_000, u32::MAX), SPI_MIN_DIVIDER);
+        assert_eq!(spi_divider(u32::MAX, 1), SPI_MAX_DIVIDER);
+        assert_eq!(spi_floor_divider(500_000_000, 32_000_000), 14);
+        assert_eq!(spi_floor_divider(500_000_000, u32::MAX), SPI_MIN_DIVIDER);
+    }
+
+    #[test]
+    fn test_spi_clock_slows_to_floor() {
+        let mut clock = SpiClock::negotiate(500_000_000, 64_000_000, 32_000_000);
+        assert_eq!((clock.divider(), clock.hz()), (8, 62_500_000));
+        assert!(clock.slow_down());
+        assert_eq!(clock.divider(), 14);
+        assert!(clock.at_floor());
+        assert!(!clock.slow_down());
+        assert_eq!(clock.divider(), 14);
+    }
+
+    #[test]
+    fn test_spi_arbiter_queues_in_order() {
+        let mut bus = SpiArbiter::new([10; SPI_BUS_CLIENTS]);
+        let display = bus.acquire(SPI_CLIENT_DISPLAY, 0).unwrap();
+        assert_eq!(bus.acquire(SPI_CLIENT_TPM, 1), Err(BusError::Queued));
+        assert_eq!(bus.acquire(SPI_CLIENT_TOUCH, 1), Err(BusError::Queued));
+        assert_eq!(bus.acquire(SPI_CLIENT_TPM, 2), Err(BusError::Queued));
+        assert_eq!(bus.waiting_count(), 2);
+        assert_eq!(
+            bus.acquire(SPI_CLIENT_DISPLAY, 2),
+            Err(BusError::AlreadyOwner)
+        );
+
+        assert_eq!(bus.release(display, 3), Ok(Some(SPI_CLIENT_TPM)));
+        // The free bus is kept for the head of the queue
+        assert_eq!(bus.acquire(SPI_CLIENT_TOUCH, 3), Err(BusError::Queued));
+        let tpm = bus.acquire(SPI_CLIENT_TPM, 4).unwrap();
+        assert_eq!(bus.owner(), Some(SPI_CLIENT_TPM));
+        assert_eq!(bus.release(tpm, 5), Ok(Some(SPI_CLIENT_TOUCH)));
+        assert_eq!(bus.acquire(4, 5), Err(BusError::InvalidClient));
+    }
+
+    #[test]
+    fn test_spi_arbiter_revokes_long_holds() {
+        let mut bus = SpiArbiter::new([10, 5, 5, 5]);
+        let display = bus.acquire(SPI_CLIENT_DISPLAY, 100).unwrap();
+        assert_eq!(bus.acquire(SPI_CLIENT_TOUCH, 101), Err(BusError::Queued));
+        assert_eq!(bus.expire(110), None);
+        assert_eq!(
+            bus.expire(111),
+            Some(Expired {
+                client: SPI_CLIENT_DISPLAY,
+                next: Some(SPI_CLIENT_TOUCH)
+            })
+        );
+        assert!(!bus.holds(&display));
+        assert_eq!(bus.release(display, 111), Err(BusError::Revoked));
+
+        // Touch never comes for its turn
+        assert_eq!(
+            bus.expire(117),
+            Some(Expired {
+                client: SPI_CLIENT_TOUCH,
+                next: None
+            })
+        );
+        assert_eq!(bus.waiting_count(), 0);
+        assert!(bus.acquire(SPI_CLIENT_SD, 118).is_ok());
+    }
+
+    #[test]
+    fn test_rollback_guard_ordering() {
+        assert_eq!(RollbackGuard::check(5, 4), None);
+
+        let mut guard = RollbackGuard::check(2, 4).unwrap();
+        assert!(guard.needs_increment());
+        // A counter that jumped is refused and changes nothing
+        assert!(!guard.record_increment(4));
+        assert_eq!(guard.counter(), 2);
+        assert!(guard.record_increment(3));
+        assert!(guard.record_increment(4));
+        assert!(!guard.needs_increment());
+        assert!(!guard.record_increment(5));
+
+        // A large jump stops at the boot's budget
+        let mut guard = RollbackGuard::check(0, 100).unwrap();
+        let mut counter = 0;
+        while guard.needs_increment() {
+            counter += 1;
+            assert!(guard.record_increment(counter));
+        }
+        assert_eq!(guard.counter(), ROLLBACK_MAX_STEPS);
+    }
 }
@@ -0,0 +1,283 @@
+//! # RGB888 → RGB565 Quantization
+//!
+//! Decoders hand out 8 bits per channel; 16-bit panels take 5-6-5.
+//! Truncating each channel throws away the low bits, which shows as
+//! visible bands in smooth gradients. These are the integer pieces a
+//! converter is built from: plain truncation, 4×4 ordered (Bayer)
+//! dithering, which adds a share of one quantization step picked by the
+//! pixel's position before truncating, and gamma lookup tables.
+//!
+//! ```rust
+//! use verified_microkernel::rgb565::{bayer_threshold, rgb888_to_rgb565, rgb888_to_rgb565_dithered};
+//!
+//! assert_eq!(rgb888_to_rgb565(255, 0, 0), 0xF800);
+//! // Half a step above black: every other pixel of a 4×4 tile rounds up
+//! let t = bayer_threshold(1, 0);
+//! assert_eq!(rgb888_to_rgb565_dithered(4, 2, 4, t), 0x0821);
+//! ```
+//!
+//! ## Verification
+//!
+//! - Bayer thresholds are below 16 whatever the pixel coordinates
+//! - Dithered channels stay within 5 or 6 bits, so they never spill into
+//!   the neighbouring channel of the packed pixel
+//! - Gamma lookups cannot leave their 256-entry table
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+verus! {
+
+/// 4×4 Bayer threshold matrix, values 0..16
+pub const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Bayer threshold for the pixel at (x, y): `BAYER_4X4[y & 3][x & 3]`,
+/// assembled from the coordinate bits so its bound is provable
+pub fn bayer_threshold(x: usize, y: usize) -> (t: u8)
+    ensures t < 16,
+{
+    let d = (x ^ y) & 3;
+    let r = y & 3;
+    let t = ((d & 1) << 3) | ((r & 1) << 2) | (d & 2) | ((r & 2) >> 1);
+    proof {
+        assert(((d & 1) << 3) | ((r & 1) << 2) | (d & 2) | ((r & 2) >> 1) < 16usize) by (bit_vector);
+    }
+    t as u8
+}
+
+/// Quantize an 8-bit channel to `bits` bits, first adding the share of one
+/// quantization step that `threshold` (0..16) selects
+///
+/// Offsets are spread evenly over 0..step, so averaged over a 4×4 tile the
+/// result rounds neither up nor down.
+pub fn dither_channel(value: u8, threshold: u8, bits: u32) -> (c: u8)
+    requires
+        threshold < 16,
+        bits == 5 || bits == 6,
+    ensures
+        (c as u32) < (1u32 << bits),
+{
+    let shift = 8 - bits;
+    let offset = ((threshold as u16) << shift) >> 4;
+    let sum = value as u16 + offset;
+    let biased = if sum > 255 { 255 } else { sum };
+    proof {
+        assert(biased <= 255u16 && shift == 3u32 ==> (biased >> shift) < 32u16) by (bit_vector);
+        assert(biased <= 255u16 && shift == 2u32 ==> (biased >> shift) < 64u16) by (bit_vector);
+    }
+    (biased >> shift) as u8
+}
+
+/// Pack 5-6-5 channels into one pixel, red in the top bits
+pub fn pack_rgb565(r5: u8, g6: u8, b5: u8) -> (px: u16)
+    requires
+        r5 < 32,
+        g6 < 64,
+        b5 < 32,
+{
+    ((r5 as u16) << 11) | ((g6 as u16) << 5) | (b5 as u16)
+}
+
+/// Truncate an RGB888 colour to RGB565
+pub fn rgb888_to_rgb565(r: u8, g: u8, b: u8) -> (px: u16) {
+    proof {
+        assert(r >> 3u8 < 32u8 && g >> 2u8 < 64u8 && b >> 3u8 < 32u8) by (bit_vector);
+    }
+    pack_rgb565(r >> 3, g >> 2, b >> 3)
+}
+
+/// Dither an RGB888 colour to RGB565 at Bayer threshold `threshold`
+pub fn rgb888_to_rgb565_dithered(r: u8, g: u8, b: u8, threshold: u8) -> (px: u16)
+    requires threshold < 16,
+{
+    pack_rgb565(
+        dither_channel(r, threshold, 5),
+        dither_channel(g, threshold, 6),
+        dither_channel(b, threshold, 5),
+    )
+}
+
+} // verus!
+
+/// Gamma correction lookup table, one entry per 8-bit channel value
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct GammaLut {
+    table: [u8; 256],
+}
+
+impl GammaLut {
+    /// Leave values unchanged
+    pub const IDENTITY: Self = Self::identity();
+
+    /// Exponent 0.8: lifts shadows on panels that crush dark tones
+    pub const BRIGHTEN: Self = Self::from_table(GAMMA_0_8);
+
+    /// Exponent 1.2: deepens shadows on panels that wash them out
+    pub const DARKEN: Self = Self::from_table(GAMMA_1_2);
+
+    const fn identity() -> Self {
+        let mut table = [0u8; 256];
+        let mut i = 0;
+        while i < 256 {
+            table[i] = i as u8;
+            i += 1;
+        }
+        Self { table }
+    }
+
+    /// Use a table computed elsewhere (e.g. measured for a specific panel)
+    pub const fn from_table(table: [u8; 256]) -> Self {
+        Self { table }
+    }
+
+    /// Corrected value for `value`
+    #[inline]
+    pub fn apply(&self, value: u8) -> u8 {
+        // A u8 index cannot leave a 256-entry table
+        self.table[value as usize]
+    }
+}
+
+/// Channel values raised to the power 0.8
+#[rustfmt::skip]
+const GAMMA_0_8: [u8; 256] = [
+      0,   3,   5,   7,   9,  11,  13,  14,  16,  18,  19,  21,  22,  24,  25,  26,
+     28,  29,  31,  32,  33,  35,  36,  37,  39,  40,  41,  42,  44,  45,  46,  47,
+     48,  50,  51,  52,  53,  54,  56,  57,  58,  59,  60,  61,  63,  64,  65,  66,
+     67,  68,  69,  70,  71,  73,  74,  75,  76,  77,  78,  79,  80,  81,  82,  83,
+     84,  85,  86,  88,  89,  90,  91,  92,  93,  94,  95,  96,  97,  98,  99, 100,
+    101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116,
+    117, 118, 119, 120, 121, 122, 123, 123, 124, 125, 126, 127, 128, 129, 130, 131,
+    132, 133, 134, 135, 136, 137, 138, 139, 140, 140, 141, 142, 143, 144, 145, 146,
+    147, 148, 149, 150, 151, 151, 152, 153, 154, 155, 156, 157, 158, 159, 160, 161,
+    161, 162, 163, 164, 165, 166, 167, 168, 169, 169, 170, 171, 172, 173, 174, 175,
+    176, 177, 177, 178, 179, 180, 181, 182, 183, 183, 184, 185, 186, 187, 188, 189,
+    190, 190, 191, 192, 193, 194, 195, 196, 196, 197, 198, 199, 200, 201, 202, 202,
+    203, 204, 205, 206, 207, 207, 208, 209, 210, 211, 212, 212, 213, 214, 215, 216,
+    217, 217, 218, 219, 220, 221, 222, 222, 223, 224, 225, 226, 227, 227, 228, 229,
+    230, 231, 232, 232, 233, 234, 235, 236, 236, 237, 238, 239, 240, 240, 241, 242,
+    243, 244, 245, 245, 246, 247, 248, 249, 249, 250, 251, 252, 253, 253, 254, 255,
+];
+
+/// Channel values raised to the power 1.2
+#[rustfmt::skip]
+const GAMMA_1_2: [u8; 256] = [
+      0,   0,   1,   1,   2,   2,   3,   3,   4,   5,   5,   6,   7,   7,   8,   9,
+      9,  10,  11,  11,  12,  13,  13,  14,  15,  16,  16,  17,  18,  19,  20,  20,
+     21,  22,  23,  24,  24,  25,  26,  27,  28,  28,  29,  30,  31,  32,  33,  34,
+     34,  35,  36,  37,  38,  39,  40,  40,  41,  42,  43,  44,  45,  46,  47,  48,
+     49,  49,  50,  51,  52,  53,  54,  55,  56,  57,  58,  59,  60,  61,  62,  62,
+     63,  64,  65,  66,  67,  68,  69,  70,  71,  72,  73,  74,  75,  76,  77,  78,
+     79,  80,  81,  82,  83,  84,  85,  86,  87,  88,  89,  90,  91,  92,  93,  94,
+     95,  96,  97,  98,  99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+    112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127,
+    128, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 144, 145,
+    146, 147, 148, 149, 150, 151, 152, 153, 155, 156, 157, 158, 159, 160, 161, 162,
+    163, 165, 166, 167, 168, 169, 170, 171, 172, 173, 175, 176, 177, 178, 179, 180,
+    181, 183, 184, 185, 186, 187, 188, 189, 191, 192, 193, 194, 195, 196, 197, 199,
+    200, 201, 202, 203, 204, 205, 207, 208, 209, 210, 211, 212, 214, 215, 216, 217,
+    218, 219, 221, 222, 223, 224, 225, 226, 228, 229, 230, 231, 232, 234, 235, 236,
+    237, 238, 239, 241, 242, 243, 244, 245, 247, 248, 249, 250, 251, 253, 254, 255,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncation_endpoints() {
+        assert_eq!(rgb888_to_rgb565(0, 0, 0), 0x0000);
+        assert_eq!(rgb888_to_rgb565(255, 255, 255), 0xFFFF);
+        assert_eq!(rgb888_to_rgb565(255, 0, 0), 0xF800);
+        assert_eq!(rgb888_to_rgb565(0, 255, 0), 0x07E0);
+        assert_eq!(rgb888_to_rgb565(0, 0, 255), 0x001F);
+        // The low bits are dropped: one step short of the first level is
+        // still black, the first level is one unit in each channel
+        assert_eq!(rgb888_to_rgb565(7, 3, 7), 0x0000);
+        assert_eq!(rgb888_to_rgb565(8, 4, 8), 0x0821);
+        assert_eq!(pack_rgb565(31, 63, 31), 0xFFFF);
+    }
+
+    #[test]
+    fn bayer_threshold_matches_the_matrix() {
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(bayer_threshold(x, y), BAYER_4X4[y & 3][x & 3]);
+            }
+        }
+        assert_eq!(bayer_threshold(usize::MAX, usize::MAX), 5);
+        // Every threshold appears once per tile
+        let mut seen = [false; 16];
+        for row in BAYER_4X4 {
+            for t in row {
+                assert!(!seen[t as usize]);
+                seen[t as usize] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn dither_thresholds() {
+        // Threshold 0 adds nothing: plain truncation
+        for v in 0..=255u8 {
+            assert_eq!(dither_channel(v, 0, 5), v >> 3);
+            assert_eq!(dither_channel(v, 0, 6), v >> 2);
+        }
+        // The offset is threshold / 16 of a step: 8 for 5 bits, 4 for 6
+        assert_eq!(dither_channel(3, 7, 5), 0);
+        assert_eq!(dither_channel(4, 8, 5), 1);
+        assert_eq!(dither_channel(1, 11, 6), 0);
+        assert_eq!(dither_channel(1, 12, 6), 1);
+        // The largest offset still rounds down a value on a level
+        assert_eq!(dither_channel(8, 15, 5), 1);
+        assert_eq!(dither_channel(4, 15, 6), 1);
+        // Full scale stays in range
+        assert_eq!(dither_channel(255, 15, 5), 31);
+        assert_eq!(dither_channel(255, 15, 6), 63);
+    }
+
+    #[test]
+    fn half_a_step_rounds_up_on_half_the_tile() {
+        let ups = |value, bits| {
+            BAYER_4X4
+                .iter()
+                .flatten()
+                .filter(|&&t| dither_channel(value, t, bits) == 1)
+                .count()
+        };
+        assert_eq!(ups(4, 5), 8);
+        assert_eq!(ups(2, 6), 8);
+        // A quarter step: a quarter of the tile
+        assert_eq!(ups(2, 5), 4);
+        assert_eq!(ups(1, 6), 4);
+    }
+
+    #[test]
+    fn dithering_keeps_black_and_white() {
+        for t in 0..16 {
+            assert_eq!(rgb888_to_rgb565_dithered(0, 0, 0, t), 0x0000);
+            assert_eq!(rgb888_to_rgb565_dithered(255, 255, 255, t), 0xFFFF);
+        }
+    }
+
+    #[test]
+    fn gamma_endpoints_and_midtones() {
+        for lut in [GammaLut::IDENTITY, GammaLut::BRIGHTEN, GammaLut::DARKEN] {
+            assert_eq!(lut.apply(0), 0);
+            assert_eq!(lut.apply(255), 255);
+            for v in 1..=255u8 {
+                assert!(lut.apply(v) >= lut.apply(v - 1));
+            }
+        }
+        assert_eq!(GammaLut::IDENTITY.apply(128), 128);
+        assert!(GammaLut::BRIGHTEN.apply(128) > 128);
+        assert!(GammaLut::DARKEN.apply(128) < 128);
+    }
+}
@@ -0,0 +1,576 @@
+//! Single-producer single-consumer rings in shared memory.
+//!
+//! The input ring, the photo frame's command ring and the draw ring are the
+//! same structure with different entries: a header whose first three words
+//! are `write_idx`, `read_idx` and `capacity`, then `capacity` entries at a
+//! fixed offset. The producer owns `write_idx`, the consumer `read_idx`, and
+//! one slot always stays empty to tell a full ring from an empty one.
+//!
+//! ```text
+//! ┌────────────────────────────┬─────────────────┬─────────┬─────────┬───
+//! │ RingHeader (12 bytes)      │ protocol words  │ entry 0 │ entry 1 │ ...
+//! │ write_idx read_idx capacity│ (seq, counters) │         │         │
+//! └────────────────────────────┴─────────────────┴─────────┴─────────┴───
+//! ```
+//!
+//! Each protocol embeds a [`RingHeader`] as the first field of its own
+//! header, for the words only it uses, and moves entries through a
+//! [`VerifiedRing`] over that header and its entry array. The ring trusts
+//! nothing it reads from shared memory: a header whose indices or capacity
+//! are out of range reads as empty to the consumer and full to the
+//! producer, rather than sending either outside the entry array.
+//!
+//! ## Verification
+//!
+//! [`RingIndices`] is the model: Verus proves its empty, full and count
+//! specs agree, that every operation keeps both indices inside the ring,
+//! and that batch advances change the count by exactly the batch. The
+//! runtime ring sizes every batch with `ring_pop_count` and
+//! `ring_push_count`, proven never to exceed what is waiting or free, and
+//! steps indices only with `ring_add`, proven to stay below the capacity.
+//!
+//! With the `pow2-capacity` feature the model takes only power-of-two
+//! capacities and steps an index by masking, chosen at compile time;
+//! without it, by a compare and reset.
+
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::{ring_add, ring_len, ring_pop_count, ring_push_count};
+
+verus! {
+
+/// Capacities the model takes: with `pow2-capacity`, powers of two only
+#[cfg(feature = "pow2-capacity")]
+pub open spec fn capacity_ok(capacity: u32) -> bool {
+    crate::is_pow2_spec(capacity)
+}
+
+/// Capacities the model takes: any but zero
+#[cfg(not(feature = "pow2-capacity"))]
+pub open spec fn capacity_ok(capacity: u32) -> bool {
+    capacity > 0
+}
+
+/// Index state of a ring of at most `N` slots
+pub struct RingIndices<const N: usize> {
+    write_idx: u32,
+    read_idx: u32,
+    capacity: u32,
+}
+
+impl<const N: usize> RingIndices<N> {
+    pub open spec fn valid(&self) -> bool {
+        self.capacity > 0
+            && capacity_ok(self.capacity)
+            && self.capacity as int <= N as int
+            && self.write_idx < self.capacity
+            && self.read_idx < self.capacity
+    }
+
+    pub open spec fn count(&self) -> u32
+        recommends self.valid()
+    {
+        if self.write_idx >= self.read_idx {
+            (self.write_idx - self.read_idx) as u32
+        } else {
+            (self.capacity - self.read_idx + self.write_idx) as u32
+        }
+    }
+
+    pub open spec fn is_empty_spec(&self) -> bool
+        recommends self.valid()
+    {
+        self.write_idx == self.read_idx
+    }
+
+    /// Slots a writer may fill; one slot stays empty to tell full from empty
+    pub open spec fn free_spec(&self) -> u32
+        recommends self.valid()
+    {
+        (self.capacity - 1 - self.count()) as u32
+    }
+
+    pub open spec fn is_full_spec(&self) -> bool
+        recommends self.valid()
+    {
+        (self.write_idx + 1) % (self.capacity as int) == self.read_idx
+    }
+
+    pub fn new(capacity: u32) -> (indices: Self)
+        requires
+            capacity > 0,
+            capacity_ok(capacity),
+            capacity as int <= N as int,
+        ensures
+            indices.valid(),
+            indices.is_empty_spec(),
+    {
+        Self {
+            write_idx: 0,
+            read_idx: 0,
+            capacity,
+        }
+    }
+
+    pub fn is_empty(&self) -> (result: bool)
+        requires self.valid(),
+        ensures result == self.is_empty_spec(),
+    {
+        self.write_idx == self.read_idx
+    }
+
+    /// Index after `idx`, by masking
+    #[cfg(feature = "pow2-capacity")]
+    fn next_index(&self, idx: u32) -> (next: u32)
+        requires
+            self.valid(),
+            idx < self.capacity,
+        ensures
+            next == (idx + 1) % (self.capacity as int),
+            next < self.capacity,
+    {
+        crate::ring_next_pow2(idx, self.capacity)
+    }
+
+    /// Index after `idx`
+    #[cfg(not(feature = "pow2-capacity"))]
+    fn next_index(&self, idx: u32) -> (next: u32)
+        requires
+            self.valid(),
+            idx < self.capacity,
+        ensures
+            next == (idx + 1) % (self.capacity as int),
+            next < self.capacity,
+    {
+        crate::ring_next(idx, self.capacity)
+    }
+
+    pub fn is_full(&self) -> (result: bool)
+        requires self.valid(),
+        ensures result == self.is_full_spec(),
+    {
+        self.next_index(self.write_idx) == self.read_idx
+    }
+
+    pub fn has_data(&self) -> (result: bool)
+        requires self.valid(),
+        ensures result == !self.is_empty_spec(),
+    {
+        self.write_idx != self.read_idx
+    }
+
+    pub fn write_index(&self) -> (result: u32)
+        requires self.valid(),
+        ensures result == self.write_idx, result < self.capacity,
+    {
+        self.write_idx
+    }
+
+    pub fn read_index(&self) -> (result: u32)
+        requires self.valid(),
+        ensures result == self.read_idx, result < self.capacity,
+    {
+        self.read_idx
+    }
+
+    pub fn advance_write(&mut self)
+        requires
+            old(self).valid(),
+            !old(self).is_full_spec(),
+        ensures
+            self.valid(),
+            self.write_idx == (old(self).write_idx + 1) % (old(self).capacity as int),
+            self.read_idx == old(self).read_idx,
+            self.capacity == old(self).capacity,
+    {
+        self.write_idx = self.next_index(self.write_idx);
+    }
+
+    pub fn advance_read(&mut self)
+        requires
+            old(self).valid(),
+            !old(self).is_empty_spec(),
+        ensures
+            self.valid(),
+            self.read_idx == (old(self).read_idx + 1) % (old(self).capacity as int),
+            self.write_idx == old(self).write_idx,
+            self.capacity == old(self).capacity,
+    {
+        self.read_idx = self.next_index(self.read_idx);
+    }
+
+    /// Number of entries waiting to be read
+    pub fn len(&self) -> (result: u32)
+        requires self.valid(),
+        ensures
+            result == self.count(),
+            result < self.capacity,
+    {
+        ring_len(self.write_idx, self.read_idx, self.capacity)
+    }
+
+    /// Number of entries that can be written before the ring is full
+    pub fn free(&self) -> (result: u32)
+        requires self.valid(),
+        ensures result == self.free_spec(),
+    {
+        self.capacity - 1 - self.len()
+    }
+
+    /// Consume `n` entries at once
+    pub fn advance_read_by(&mut self, n: u32)
+        requires
+            old(self).valid(),
+            n <= old(self).count(),
+        ensures
+            self.valid(),
+            self.read_idx == (old(self).read_idx + n) % (old(self).capacity as int),
+            self.write_idx == old(self).write_idx,
+            self.capacity == old(self).capacity,
+            self.count() == old(self).count() - n,
+    {
+        self.read_idx = ring_add(self.read_idx, n, self.capacity);
+    }
+
+    /// Publish `n` entries at once
+    pub fn advance_write_by(&mut self, n: u32)
+        requires
+            old(self).valid(),
+            n <= old(self).free_spec(),
+        ensures
+            self.valid(),
+            self.write_idx == (old(self).write_idx + n) % (old(self).capacity as int),
+            self.read_idx == old(self).read_idx,
+            self.capacity == old(self).capacity,
+            self.count() == old(self).count() + n,
+    {
+        self.write_idx = ring_add(self.write_idx, n, self.capacity);
+    }
+}
+
+/// Bytes a ring region needs: `entries_offset` for the header, then `n`
+/// entries of `entry_size`; `None` if that overflows
+pub fn ring_region_size(entries_offset: usize, entry_size: usize, n: usize) -> (size: Option<usize>)
+    ensures
+        size.is_some() ==> size.unwrap() == entries_offset + entry_size * n,
+        entries_offset + entry_size * n <= usize::MAX ==> size.is_some(),
+{
+    match entry_size.checked_mul(n) {
+        Some(bytes) => entries_offset.checked_add(bytes),
+        None => None,
+    }
+}
+
+} // verus!
+
+/// The words every ring header starts with
+#[repr(C)]
+pub struct RingHeader {
+    pub write_idx: AtomicU32,
+    pub read_idx: AtomicU32,
+    pub capacity: u32,
+}
+
+impl RingHeader {
+    /// An empty ring of `capacity` slots
+    pub const fn new(capacity: u32) -> Self {
+        Self {
+            write_idx: AtomicU32::new(0),
+            read_idx: AtomicU32::new(0),
+            capacity,
+        }
+    }
+
+    /// Snapshot of the shared indices, or `None` if the header is corrupt:
+    /// a capacity of 0 or above `max`, or an index past the capacity
+    pub fn indices(&self, max: u32) -> Option<(u32, u32, u32)> {
+        let capacity = self.capacity;
+        let write = self.write_idx.load(Ordering::Acquire);
+        let read = self.read_idx.load(Ordering::Acquire);
+        (capacity > 0 && capacity <= max && write < capacity && read < capacity)
+            .then_some((write, read, capacity))
+    }
+
+    /// Entries waiting; a corrupt header reads as empty
+    pub fn len(&self, max: u32) -> u32 {
+        self.indices(max)
+            .map_or(0, |(write, read, capacity)| ring_len(write, read, capacity))
+    }
+
+    /// Entries the producer can still add; a corrupt header reads as full
+    pub fn free_slots(&self, max: u32) -> u32 {
+        self.indices(max).map_or(0, |(write, read, capacity)| {
+            capacity - 1 - ring_len(write, read, capacity)
+        })
+    }
+
+    /// Step the write index past one entry the producer placed itself at
+    /// the slot it indexed; no-op on a full or corrupt ring
+    pub fn advance_write(&self, max: u32) {
+        if let Some((write, read, capacity)) = self.indices(max) {
+            if ring_push_count(write, read, capacity, 1) == 1 {
+                self.write_idx
+                    .store(ring_add(write, 1, capacity), Ordering::Release);
+            }
+        }
+    }
+
+    /// Step the read index past one entry; no-op on an empty or corrupt
+    /// ring
+    pub fn advance_read(&self, max: u32) {
+        if let Some((write, read, capacity)) = self.indices(max) {
+            if ring_pop_count(write, read, capacity, 1) == 1 {
+                self.read_idx
+                    .store(ring_add(read, 1, capacity), Ordering::Release);
+            }
+        }
+    }
+}
+
+/// A ring of at most `N` entries of `T`: a [`RingHeader`] and the entry
+/// array it indexes
+///
+/// Copying a `VerifiedRing` copies the view, not the ring. The producer
+/// calls only the `push` and `advance_write` operations, the consumer only
+/// the `pop` and `advance_read` ones.
+pub struct VerifiedRing<T, const N: usize> {
+    header: *const RingHeader,
+    entries: *mut T,
+    _entries: PhantomData<T>,
+}
+
+impl<T, const N: usize> Clone for VerifiedRing<T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for VerifiedRing<T, N> {}
+
+impl<T: Copy, const N: usize> VerifiedRing<T, N> {
+    /// Largest capacity a header may claim: the entry array's length
+    const MAX: u32 = if N > u32::MAX as usize {
+        u32::MAX
+    } else {
+        N as u32
+    };
+
+    /// Bytes a region holding this ring needs, with its entries at
+    /// `entries_offset`
+    pub const fn region_size(entries_offset: usize) -> usize {
+        entries_offset + N * core::mem::size_of::<T>()
+    }
+
+    /// View the ring whose header is at `header` and entries at `entries`
+    ///
+    /// # Safety
+    /// `header` must point at a `RingHeader` and `entries` at an array of
+    /// `N` entries, both mapped and aligned for as long as the view is used.
+    pub const unsafe fn new(header: *const RingHeader, entries: *mut T) -> Self {
+        Self {
+            header,
+            entries,
+            _entries: PhantomData,
+        }
+    }
+
+    fn header(&self) -> &RingHeader {
+        // Safety: `new`'s contract
+        unsafe { &*self.header }
+    }
+
+    /// Snapshot of the shared indices, or `None` if the header is corrupt
+    pub fn indices(&self) -> Option<(u32, u32, u32)> {
+        self.header().indices(Self::MAX)
+    }
+
+    /// Does the shared header still satisfy the ring invariant (the runtime
+    /// counterpart of [`RingIndices::valid`])?
+    pub fn is_consistent(&self) -> bool {
+        self.indices().is_some()
+    }
+
+    /// Entries waiting; a corrupt header reads as empty
+    pub fn len(&self) -> u32 {
+        self.header().len(Self::MAX)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn has_data(&self) -> bool {
+        !self.is_empty()
+    }
+
+    /// Entries the producer can still add; a corrupt header reads as full
+    pub fn free_slots(&self) -> u32 {
+        self.header().free_slots(Self::MAX)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free_slots() == 0
+    }
+
+    /// See [`RingHeader::advance_write`]
+    pub fn advance_write(&self) {
+        self.header().advance_write(Self::MAX)
+    }
+
+    /// See [`RingHeader::advance_read`]
+    pub fn advance_read(&self) {
+        self.header().advance_read(Self::MAX)
+    }
+
+    /// Copy up to `out.len()` entries out of the ring, oldest first
+    ///
+    /// The indices are loaded once and the read index is published once for
+    /// the whole batch. Returns the number copied, never more than were
+    /// waiting.
+    pub fn pop_batch(&self, out: &mut [T]) -> usize {
+        let Some((write, read, capacity)) = self.indices() else {
+            return 0;
+        };
+        let n = ring_pop_count(write, read, capacity, out.len());
+        for (i, slot) in out[..n as usize].iter_mut().enumerate() {
+            let idx = ring_add(read, i as u32, capacity);
+            // Safety: idx < capacity <= N
+            *slot = unsafe { self.entries.add(idx as usize).read_volatile() };
+        }
+        // Entries are copied out before the slots are handed back
+        self.header()
+            .read_idx
+            .store(ring_add(read, n, capacity), Ordering::Release);
+        n as usize
+    }
+
+    /// Write as much of `batch` as fits, oldest first; see
+    /// [`push_batch_with`](Self::push_batch_with)
+    pub fn push_batch(&self, batch: &[T]) -> usize {
+        self.push_batch_with(batch, |_, entry| entry)
+    }
+
+    /// Write as much of `batch` as fits, oldest first, passing each entry
+    /// and its position in the batch through `stamp` on the way in
+    ///
+    /// The write index is published once, after every entry is in place.
+    /// Returns the number written, never more than were free; a corrupt
+    /// header takes nothing.
+    pub fn push_batch_with(&self, batch: &[T], mut stamp: impl FnMut(u32, T) -> T) -> usize {
+        let Some((write, read, capacity)) = self.indices() else {
+            return 0;
+        };
+        let n = ring_push_count(write, read, capacity, batch.len());
+        for (i, entry) in batch[..n as usize].iter().enumerate() {
+            let idx = ring_add(write, i as u32, capacity);
+            // Safety: idx < capacity <= N
+            unsafe {
+                self.entries
+                    .add(idx as usize)
+                    .write_volatile(stamp(i as u32, *entry))
+            };
+        }
+        self.header()
+            .write_idx
+            .store(ring_add(write, n, capacity), Ordering::Release);
+        n as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C, align(16))]
+    struct Region {
+        header: RingHeader,
+        entries: [u64; 8],
+    }
+
+    fn region(capacity: u32) -> Region {
+        Region {
+            header: RingHeader::new(capacity),
+            entries: [0; 8],
+        }
+    }
+
+    fn view(region: &mut Region) -> VerifiedRing<u64, 8> {
+        unsafe { VerifiedRing::new(&region.header, region.entries.as_mut_ptr()) }
+    }
+
+    #[test]
+    fn batches_wrap_and_never_overrun() {
+        let mut r = region(5);
+        let ring = view(&mut r);
+        let mut out = [0u64; 8];
+        assert_eq!(ring.free_slots(), 4);
+        assert_eq!(ring.push_batch(&[1, 2, 3]), 3);
+        assert_eq!(ring.pop_batch(&mut out[..2]), 2);
+        assert_eq!(out[..2], [1, 2]);
+        // Wraps past the end, and stops one short of the reader
+        assert_eq!(ring.push_batch(&[4, 5, 6, 7, 8]), 3);
+        assert!(ring.is_full());
+        assert_eq!(ring.push_batch(&[9]), 0);
+        assert_eq!(ring.pop_batch(&mut out), 4);
+        assert_eq!(out[..4], [3, 4, 5, 6]);
+        assert!(!ring.has_data());
+        assert_eq!(ring.pop_batch(&mut out), 0);
+    }
+
+    #[test]
+    fn stamps_see_batch_positions() {
+        let mut r = region(8);
+        let ring = view(&mut r);
+        let mut out = [0u64; 8];
+        assert_eq!(ring.push_batch_with(&[10, 20, 30], |i, e| e + i as u64), 3);
+        assert_eq!(ring.pop_batch(&mut out), 3);
+        assert_eq!(out[..3], [10, 21, 32]);
+    }
+
+    #[test]
+    fn corrupt_header_is_empty_and_full() {
+        let mut r = region(8);
+        r.header.write_idx.store(9, Ordering::Relaxed);
+        let ring = view(&mut r);
+        assert!(!ring.is_consistent());
+        assert_eq!(ring.len(), 0);
+        assert_eq!(ring.push_batch(&[1]), 0);
+        assert_eq!(ring.pop_batch(&mut [0; 4]), 0);
+
+        // A capacity beyond the entry array is as bad as a wild index
+        let mut r = region(9);
+        assert!(!view(&mut r).is_consistent());
+    }
+
+    #[test]
+    fn single_steps_respect_the_indices() {
+        let mut r = region(2);
+        let ring = view(&mut r);
+        ring.advance_read();
+        assert_eq!(ring.indices(), Some((0, 0, 2)));
+        ring.advance_write();
+        ring.advance_write();
+        assert_eq!(ring.len(), 1);
+        ring.advance_read();
+        assert!(!ring.has_data());
+    }
+
+    #[test]
+    fn model_and_layout() {
+        let mut indices = RingIndices::<8>::new(8);
+        indices.advance_write_by(7);
+        assert!(indices.is_full());
+        indices.advance_read_by(7);
+        assert!(indices.is_empty());
+        assert_eq!(ring_region_size(16, 8, 4), Some(48));
+        assert_eq!(ring_region_size(16, usize::MAX, 2), None);
+        assert_eq!(VerifiedRing::<u64, 8>::region_size(16), 80);
+        assert_eq!(core::mem::size_of::<RingHeader>(), 12);
+    }
+}
@@ -0,0 +1,637 @@
+//! # Request/Response Encoding for Protected Calls
+//!
+//! A protected procedure call carries a label and up to
+//! [`IPC_BUFFER_SIZE`](crate::IPC_BUFFER_SIZE) message words each way. By
+//! convention in these PDs the call's label names the operation and the
+//! reply's label is 0 on success or a nonzero error wire code (the
+//! `rpi4-pd-error` encoding) on failure. This module does the packing so a
+//! PD only describes its calls:
+//!
+//! ```rust
+//! use verified_microkernel::{rpc, rpc_requests, IpcBuffer};
+//!
+//! rpc_requests! {
+//!     /// Calls a sensor PD serves
+//!     pub enum SensorRequest {
+//!         /// Read one channel
+//!         Read { channel: u8 } = 1,
+//!         /// Calibrate a channel against a reference
+//!         Calibrate { channel: u8, reference: [u8; 12] } = 2,
+//!     }
+//! }
+//!
+//! let mut buf = IpcBuffer::new();
+//! let (label, count) = rpc::encode_request(&SensorRequest::Read { channel: 3 }, &mut buf).unwrap();
+//! assert_eq!((label, count), (1, 1));
+//! assert_eq!(rpc::decode_request(label, &buf), Ok(SensorRequest::Read { channel: 3 }));
+//! ```
+//!
+//! [`rpc_requests!`](crate::rpc_requests) writes the enum, its labels and
+//! the field-by-field encoding; fields are any [`Message`] type (integers,
+//! `bool`, byte arrays, tuples of these). Replies are plain [`Message`]
+//! values. Decoding is strict: an unknown label, a missing word, a
+//! leftover word, or a value out of its type's range is an [`RpcError`],
+//! which the PD turns into its own error at the boundary (see
+//! [`RpcError::detail`]).
+//!
+//! ## Verus Verification
+//! Key properties verified:
+//! - `fits` holds exactly when `count` more words fit after `len`, so a
+//!   byte field is checked whole before any of it is written
+//! - `words_for_bytes` is the fewest words that hold `n` bytes
+//!
+//! Every request enum also asserts at compile time that its largest
+//! variant fits the IPC buffer, so encoding a request never runs out of
+//! room.
+
+use verus_builtin_macros::verus;
+
+#[cfg(verus_keep_ghost)]
+use vstd::prelude::*;
+
+use crate::{div_round_up_usize, IpcBuffer, IPC_BUFFER_SIZE};
+
+verus! {
+
+/// Bytes carried per message word
+pub const WORD_BYTES: usize = 8;
+
+/// Can `count` more words follow the `len` already written?
+pub fn fits(len: usize, count: usize) -> (r: bool)
+    requires
+        len <= IPC_BUFFER_SIZE,
+    ensures
+        r == (len + count <= IPC_BUFFER_SIZE),
+{
+    count <= IPC_BUFFER_SIZE - len
+}
+
+/// Words needed to carry `n` bytes, the last one zero-padded
+pub fn words_for_bytes(n: usize) -> (w: usize)
+    ensures
+        w * WORD_BYTES >= n,
+        (w - 1) * WORD_BYTES < n,
+{
+    div_round_up_usize(n, WORD_BYTES)
+}
+
+} // verus!
+
+/// Why a call or its reply could not be encoded or decoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpcError {
+    /// No request has this label
+    UnknownOp(u64),
+    /// The message ended before every field was read
+    Truncated,
+    /// Words were left over after the last field
+    TrailingWords,
+    /// The message does not fit the IPC buffer
+    Overflow,
+    /// The word at `index` is out of range for its field
+    BadValue { index: usize },
+    /// The callee failed with this error wire code
+    Remote(u32),
+}
+
+impl RpcError {
+    /// A small code for an error's detail field: the label for
+    /// [`UnknownOp`](Self::UnknownOp) (truncated to 16 bits), otherwise
+    /// 0xFF01.. by variant
+    pub const fn detail(&self) -> u16 {
+        match self {
+            RpcError::UnknownOp(label) => *label as u16,
+            RpcError::Truncated => 0xFF01,
+            RpcError::TrailingWords => 0xFF02,
+            RpcError::Overflow => 0xFF03,
+            RpcError::BadValue { .. } => 0xFF04,
+            RpcError::Remote(_) => 0xFF05,
+        }
+    }
+}
+
+// ============================================================================
+// MESSAGE WORDS
+// ============================================================================
+
+impl IpcBuffer {
+    /// Replace the contents with `words`; false (and empty) if there are
+    /// more than [`IPC_BUFFER_SIZE`]
+    pub fn load(&mut self, words: &[u64]) -> bool {
+        self.clear();
+        if words.len() > IPC_BUFFER_SIZE {
+            return false;
+        }
+        self.data[..words.len()].copy_from_slice(words);
+        self.len = words.len();
+        true
+    }
+
+    /// The words written so far, to copy into the message registers
+    pub fn words(&self) -> &[u64] {
+        &self.data[..self.len]
+    }
+}
+
+/// Appends fields to a message, from the first word
+pub struct MessageWriter<'a> {
+    buf: &'a mut IpcBuffer,
+}
+
+impl<'a> MessageWriter<'a> {
+    /// Start a message in `buf`, dropping what it held
+    pub fn new(buf: &'a mut IpcBuffer) -> Self {
+        buf.clear();
+        Self { buf }
+    }
+
+    /// Words written so far
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Append one word
+    pub fn word(&mut self, value: u64) -> Result<(), RpcError> {
+        if self.buf.push(value) {
+            Ok(())
+        } else {
+            Err(RpcError::Overflow)
+        }
+    }
+
+    /// Append `bytes`, eight to a word, little-endian; all of them or none
+    pub fn bytes(&mut self, bytes: &[u8]) -> Result<(), RpcError> {
+        if !fits(self.buf.len(), words_for_bytes(bytes.len())) {
+            return Err(RpcError::Overflow);
+        }
+        for chunk in bytes.chunks(WORD_BYTES) {
+            let mut word = [0u8; WORD_BYTES];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.word(u64::from_le_bytes(word))?;
+        }
+        Ok(())
+    }
+
+    /// Append a field
+    pub fn put<T: Message>(&mut self, value: &T) -> Result<(), RpcError> {
+        value.encode(self)
+    }
+}
+
+/// Takes fields from a message, from the first word
+pub struct MessageReader<'a> {
+    buf: &'a IpcBuffer,
+    pos: usize,
+}
+
+impl<'a> MessageReader<'a> {
+    pub fn new(buf: &'a IpcBuffer) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Index of the next word
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Take one word
+    pub fn word(&mut self) -> Result<u64, RpcError> {
+        let value = self.buf.read(self.pos).ok_or(RpcError::Truncated)?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    /// Take a word that must be at most `max`
+    pub fn word_at_most(&mut self, max: u64) -> Result<u64, RpcError> {
+        let index = self.pos;
+        match self.word()? {
+            value if value <= max => Ok(value),
+            _ => Err(RpcError::BadValue { index }),
+        }
+    }
+
+    /// Fill `out` from the words [`MessageWriter::bytes`] wrote; the
+    /// padding must be zero
+    pub fn bytes(&mut self, out: &mut [u8]) -> Result<(), RpcError> {
+        for chunk in out.chunks_mut(WORD_BYTES) {
+            let index = self.pos;
+            let word = self.word()?.to_le_bytes();
+            if word[chunk.len()..].iter().any(|&b| b != 0) {
+                return Err(RpcError::BadValue { index });
+            }
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Ok(())
+    }
+
+    /// Take a field
+    pub fn take<T: Message>(&mut self) -> Result<T, RpcError> {
+        T::decode(self)
+    }
+
+    /// Check that every word was read
+    pub fn finish(self) -> Result<(), RpcError> {
+        if self.pos == self.buf.len() {
+            Ok(())
+        } else {
+            Err(RpcError::TrailingWords)
+        }
+    }
+}
+
+/// A value carried in message words
+pub trait Message: Sized {
+    /// Words the encoding takes
+    const WORDS: usize;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError>;
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError>;
+}
+
+impl Message for () {
+    const WORDS: usize = 0;
+
+    fn encode(&self, _w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        Ok(())
+    }
+
+    fn decode(_r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        Ok(())
+    }
+}
+
+impl Message for u64 {
+    const WORDS: usize = 1;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.word(*self)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        r.word()
+    }
+}
+
+/// Narrow integers take a word each and refuse wider values
+macro_rules! narrow_message {
+    ($($ty:ty),*) => {$(
+        impl Message for $ty {
+            const WORDS: usize = 1;
+
+            fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+                w.word(*self as u64)
+            }
+
+            fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+                Ok(r.word_at_most(<$ty>::MAX as u64)? as $ty)
+            }
+        }
+    )*};
+}
+
+narrow_message!(u8, u16, u32);
+
+/// Signed integers travel sign-extended, so a reader of a wider type sees
+/// the same value
+impl Message for i32 {
+    const WORDS: usize = 1;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.word(*self as i64 as u64)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        let index = r.position();
+        i32::try_from(r.word()? as i64).map_err(|_| RpcError::BadValue { index })
+    }
+}
+
+impl Message for bool {
+    const WORDS: usize = 1;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.word(*self as u64)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        Ok(r.word_at_most(1)? == 1)
+    }
+}
+
+impl<const N: usize> Message for [u8; N] {
+    const WORDS: usize = N.div_ceil(WORD_BYTES);
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.bytes(self)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        let mut out = [0u8; N];
+        r.bytes(&mut out)?;
+        Ok(out)
+    }
+}
+
+impl<A: Message, B: Message> Message for (A, B) {
+    const WORDS: usize = A::WORDS + B::WORDS;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.put(&self.0)?;
+        w.put(&self.1)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        Ok((r.take()?, r.take()?))
+    }
+}
+
+impl<A: Message, B: Message, C: Message> Message for (A, B, C) {
+    const WORDS: usize = A::WORDS + B::WORDS + C::WORDS;
+
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError> {
+        w.put(&self.0)?;
+        w.put(&self.1)?;
+        w.put(&self.2)
+    }
+
+    fn decode(r: &mut MessageReader<'_>) -> Result<Self, RpcError> {
+        Ok((r.take()?, r.take()?, r.take()?))
+    }
+}
+
+// ============================================================================
+// REQUESTS AND REPLIES
+// ============================================================================
+
+/// The calls one PD serves, one label each. Write these with
+/// [`rpc_requests!`](crate::rpc_requests) rather than by hand.
+pub trait Request: Sized {
+    /// Words the largest request takes
+    const MAX_WORDS: usize;
+
+    /// Every label, in declaration order
+    const LABELS: &'static [u64];
+
+    /// The label naming this request's operation
+    fn label(&self) -> u64;
+
+    /// Append the request's fields
+    fn encode(&self, w: &mut MessageWriter<'_>) -> Result<(), RpcError>;
+
+    /// The request `label` names, its fields taken from `r`
+    fn decode(label: u64, r: &mut MessageReader<'_>) -> Result<Self, RpcError>;
+}
+
+/// Encode `request` into `buf`; returns the label and word count for the
+/// call's `MessageInfo`
+pub fn encode_request<R: Request>(
+    request: &R,
+    buf: &mut IpcBuffer,
+) -> Result<(u64, usize), RpcError> {
+    let mut w = MessageWriter::new(buf);
+    request.encode(&mut w)?;
+    Ok((request.label(), w.len()))
+}
+
+/// Decode a call with `label` and the words in `buf`, every one of which
+/// must belong to a field
+pub fn decode_request<R: Request>(label: u64, buf: &IpcBuffer) -> Result<R, RpcError> {
+    let mut r = MessageReader::new(buf);
+    let request = R::decode(label, &mut r)?;
+    r.finish()?;
+    Ok(request)
+}
+
+/// Encode a reply into `buf`; returns the reply label and word count: 0
+/// and the value's words, or `Err`'s nonzero wire code and no words
+pub fn encode_reply<T: Message>(
+    result: Result<&T, u32>,
+    buf: &mut IpcBuffer,
+) -> Result<(u64, usize), RpcError> {
+    const {
+        assert!(
+            T::WORDS <= IPC_BUFFER_SIZE,
+            "reply does not fit the IPC buffer"
+        )
+    };
+    let mut w = MessageWriter::new(buf);
+    match result {
+        Ok(value) => {
+            w.put(value)?;
+            Ok((0, w.len()))
+        }
+        Err(code) => Ok((code as u64, 0)),
+    }
+}
+
+/// Decode a reply with `label` and the words in `buf`; a nonzero label is
+/// the callee's error, [`RpcError::Remote`]
+pub fn decode_reply<T: Message>(label: u64, buf: &IpcBuffer) -> Result<T, RpcError> {
+    match label {
+        0 => {
+            let mut r = MessageReader::new(buf);
+            let value = r.take()?;
+            r.finish()?;
+            Ok(value)
+        }
+        code => Err(RpcError::Remote(
+            u32::try_from(code).map_err(|_| RpcError::BadValue { index: 0 })?,
+        )),
+    }
+}
+
+/// The largest of `words`, for [`Request::MAX_WORDS`]
+pub const fn max_words(words: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < words.len() {
+        if words[i] > max {
+            max = words[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Do all of `labels` differ? Checked at compile time for each request enum.
+pub const fn labels_unique(labels: &[u64]) -> bool {
+    let mut i = 0;
+    while i < labels.len() {
+        let mut j = i + 1;
+        while j < labels.len() {
+            if labels[i] == labels[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declare the calls a PD serves: an enum whose variants are the requests,
+/// each with its label and named [`Message`](crate::rpc::Message) fields. Writes
+/// the enum (`Clone`, `Copy`, `Debug`, `PartialEq`, `Eq`) and its
+/// [`Request`](crate::rpc::Request) impl, and fails to compile if two labels are
+/// equal or a request could overflow the IPC buffer.
+#[macro_export]
+macro_rules! rpc_requests {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident $({ $($field:ident : $ty:ty),* $(,)? })? = $label:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        $vis enum $name {
+            $(
+                $(#[$vmeta])*
+                $variant $({ $($field: $ty),* })?
+            ),*
+        }
+
+        impl $crate::rpc::Request for $name {
+            const MAX_WORDS: usize = $crate::rpc::max_words(&[
+                $(0 $($(+ <$ty as $crate::rpc::Message>::WORDS)*)?),*
+            ]);
+
+            const LABELS: &'static [u64] = &[$($label),*];
+
+            fn label(&self) -> u64 {
+                match self {
+                    $(Self::$variant { .. } => $label,)*
+                }
+            }
+
+            fn encode(&self, w: &mut $crate::rpc::MessageWriter<'_>) -> Result<(), $crate::rpc::RpcError> {
+                match self {
+                    $(Self::$variant { $($($field),*)? } => {
+                        $($(w.put($field)?;)*)?
+                    })*
+                }
+                Ok(())
+            }
+
+            fn decode(label: u64, r: &mut $crate::rpc::MessageReader<'_>) -> Result<Self, $crate::rpc::RpcError> {
+                $(
+                    if label == $label {
+                        return Ok(Self::$variant { $($($field: r.take()?),*)? });
+                    }
+                )*
+                Err($crate::rpc::RpcError::UnknownOp(label))
+            }
+        }
+
+        const _: () = assert!(
+            $crate::rpc::labels_unique(<$name as $crate::rpc::Request>::LABELS),
+            "two requests share a label"
+        );
+        const _: () = assert!(
+            <$name as $crate::rpc::Request>::MAX_WORDS <= $crate::IPC_BUFFER_SIZE,
+            "a request does not fit the IPC buffer"
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::rpc_requests! {
+        enum Call {
+            Ping = 0,
+            Put { slot: u8, value: i32, key: [u8; 10] } = 3,
+            Flag { on: bool } = 7,
+        }
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let mut buf = IpcBuffer::new();
+        let put = Call::Put {
+            slot: 2,
+            value: -5,
+            key: *b"0123456789",
+        };
+        assert_eq!(encode_request(&put, &mut buf), Ok((3, 4)));
+        assert_eq!(buf.words()[2], u64::from_le_bytes(*b"01234567"));
+        assert_eq!(buf.words()[3], u64::from_le_bytes(*b"89\0\0\0\0\0\0"));
+        assert_eq!(decode_request(3, &buf), Ok(put));
+
+        assert_eq!(encode_request(&Call::Ping, &mut buf), Ok((0, 0)));
+        assert_eq!(decode_request(0, &buf), Ok(Call::Ping));
+        assert_eq!(Call::MAX_WORDS, 4);
+        assert_eq!(Call::LABELS, &[0, 3, 7]);
+    }
+
+    #[test]
+    fn decoding_is_strict() {
+        let mut buf = IpcBuffer::new();
+        assert_eq!(decode_request::<Call>(5, &buf), Err(RpcError::UnknownOp(5)));
+        assert_eq!(decode_request::<Call>(7, &buf), Err(RpcError::Truncated));
+        buf.load(&[2]);
+        assert_eq!(
+            decode_request::<Call>(7, &buf),
+            Err(RpcError::BadValue { index: 0 })
+        );
+        buf.load(&[1, 0]);
+        assert_eq!(
+            decode_request::<Call>(7, &buf),
+            Err(RpcError::TrailingWords)
+        );
+        // A u8 slot above 255, an i32 outside its range, key padding set
+        buf.load(&[256, 0, 0, 0]);
+        assert_eq!(
+            decode_request::<Call>(3, &buf),
+            Err(RpcError::BadValue { index: 0 })
+        );
+        buf.load(&[0, 1 << 40, 0, 0]);
+        assert_eq!(
+            decode_request::<Call>(3, &buf),
+            Err(RpcError::BadValue { index: 1 })
+        );
+        buf.load(&[0, 0, 0, 1 << 16]);
+        assert_eq!(
+            decode_request::<Call>(3, &buf),
+            Err(RpcError::BadValue { index: 3 })
+        );
+        assert!(!buf.load(&[0; IPC_BUFFER_SIZE + 1]));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn replies_carry_values_or_error_codes() {
+        let mut buf = IpcBuffer::new();
+        assert_eq!(encode_reply(Ok(&(true, 42u64)), &mut buf), Ok((0, 2)));
+        assert_eq!(decode_reply::<(bool, u64)>(0, &buf), Ok((true, 42)));
+        assert_eq!(decode_reply::<u64>(0, &buf), Err(RpcError::TrailingWords));
+        assert_eq!(
+            encode_reply::<u64>(Err(0x0401_0002), &mut buf),
+            Ok((0x0401_0002, 0))
+        );
+        assert_eq!(
+            decode_reply::<u64>(0x0401_0002, &buf),
+            Err(RpcError::Remote(0x0401_0002))
+        );
+
+        // Byte fields are checked whole before any of them is written
+        let mut w = MessageWriter::new(&mut buf);
+        for _ in 0..IPC_BUFFER_SIZE - 1 {
+            w.word(0).unwrap();
+        }
+        assert_eq!(w.bytes(&[1; 9]), Err(RpcError::Overflow));
+        assert_eq!(w.len(), IPC_BUFFER_SIZE - 1);
+        assert!(fits(IPC_BUFFER_SIZE - 1, 1) && !fits(IPC_BUFFER_SIZE, 1));
+        assert_eq!(words_for_bytes(0), 0);
+        assert_eq!(words_for_bytes(17), 3);
+    }
+}
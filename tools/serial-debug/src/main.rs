@@ -10,6 +10,9 @@
 //! - **Device Profiles**: Built-in profiles for RPi4, STM32, ESP32 with boot stages and error patterns
 //! - **Boot Partition Analysis**: Validate boot files and configuration (for devices with boot partitions)
 //! - **Kernel Image Analysis**: Analyze kernel images for compatibility
+//! - **Telemetry Export**: Extract photo frame health samples from serial logs
+//! - **Memory Report**: Peak use of each PD buffer, to size `.system` regions by
+//! - **Photo Frame Control**: Next, previous, pause and go-to over the console keys
 //!
 //! # Usage
 //!
@@ -31,16 +34,33 @@
 //!
 //! # Generate debug config for device
 //! serial-debug generate config --device rpi4
+//!
+//! # Read a word from a PD's memory via its debug monitor (requires serial feature)
+//! serial-debug debug peek -p /dev/ttyUSB0 0x504000000
+//!
+//! # Show the next photo, or photo 3 (requires serial feature)
+//! serial-debug control -p /dev/ttyUSB0 next
+//! serial-debug control -p /dev/ttyUSB0 goto 3
+//!
+//! # Turn photo frame telemetry in a captured log into CSV
+//! serial-debug telemetry export frame.log -o telemetry.csv
+//!
+//! # Peak memory use per PD buffer, against the region sizes
+//! serial-debug telemetry mem frame.log
 //! ```
 
 mod boot;
 mod devices;
 mod image;
+mod mem;
 #[cfg(feature = "serial")]
 mod serial;
+mod telemetry;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+#[cfg(feature = "serial")]
+use clap::Args;
 use colored::Colorize;
 use std::path::PathBuf;
 
@@ -82,6 +102,21 @@ enum Commands {
     #[command(subcommand)]
     Serial(SerialCommands),
 
+    /// Peek/poke a PD's memory via its debug monitor (requires --features serial)
+    #[cfg(feature = "serial")]
+    #[command(subcommand)]
+    Debug(DebugCommands),
+
+    /// Drive the photo frame over its console keys (requires --features serial)
+    #[cfg(feature = "serial")]
+    Control {
+        #[command(flatten)]
+        target: DebugTarget,
+
+        #[command(subcommand)]
+        action: ControlCommands,
+    },
+
     /// Boot partition operations
     #[command(subcommand)]
     Boot(BootCommands),
@@ -93,6 +128,10 @@ enum Commands {
     /// Generate debug configuration files
     #[command(subcommand)]
     Generate(GenerateCommands),
+
+    /// Photo frame telemetry operations
+    #[command(subcommand)]
+    Telemetry(TelemetryCommands),
 }
 
 #[derive(Subcommand)]
@@ -170,6 +209,116 @@ enum SerialCommands {
     },
 }
 
+/// Serial connection to a target
+#[cfg(feature = "serial")]
+#[derive(Args)]
+struct DebugTarget {
+    /// Serial port path
+    #[arg(short, long)]
+    port: String,
+
+    /// Device profile (for baud rate)
+    #[arg(short, long, default_value = "rpi4")]
+    device: String,
+
+    /// Baud rate (overrides device profile)
+    #[arg(short, long)]
+    baud: Option<u32>,
+}
+
+#[cfg(feature = "serial")]
+#[derive(Subcommand)]
+enum DebugCommands {
+    /// Read a value
+    Peek {
+        #[command(flatten)]
+        target: DebugTarget,
+
+        /// Virtual address in the PD (decimal or 0x hex)
+        #[arg(value_parser = serial::debug::parse_number)]
+        addr: u64,
+
+        /// Access width in bytes (1, 2, 4 or 8)
+        #[arg(short, long, default_value = "4", value_parser = serial::debug::parse_width)]
+        width: u8,
+    },
+
+    /// Write a value
+    Poke {
+        #[command(flatten)]
+        target: DebugTarget,
+
+        /// Virtual address in the PD (decimal or 0x hex)
+        #[arg(value_parser = serial::debug::parse_number)]
+        addr: u64,
+
+        /// Value to write (decimal or 0x hex)
+        #[arg(value_parser = serial::debug::parse_number)]
+        value: u64,
+
+        /// Access width in bytes (1, 2, 4 or 8)
+        #[arg(short, long, default_value = "4", value_parser = serial::debug::parse_width)]
+        width: u8,
+    },
+
+    /// Hex dump a range (at most 256 bytes)
+    Dump {
+        #[command(flatten)]
+        target: DebugTarget,
+
+        /// Virtual address in the PD (decimal or 0x hex)
+        #[arg(value_parser = serial::debug::parse_number)]
+        addr: u64,
+
+        /// Number of bytes
+        #[arg(value_parser = serial::debug::parse_number, default_value = "64")]
+        len: u64,
+    },
+
+    /// Print a value every time it changes
+    Watch {
+        #[command(flatten)]
+        target: DebugTarget,
+
+        /// Virtual address in the PD (decimal or 0x hex)
+        #[arg(value_parser = serial::debug::parse_number)]
+        addr: u64,
+
+        /// Access width in bytes (1, 2, 4 or 8)
+        #[arg(short, long, default_value = "4", value_parser = serial::debug::parse_width)]
+        width: u8,
+
+        /// How long to watch, in seconds
+        #[arg(short, long, default_value = "30")]
+        seconds: u64,
+    },
+
+    /// List the memory regions the monitor may access
+    Regions {
+        #[command(flatten)]
+        target: DebugTarget,
+    },
+}
+
+#[cfg(feature = "serial")]
+#[derive(Subcommand)]
+enum ControlCommands {
+    /// Show the next photo
+    Next,
+
+    /// Show the previous photo
+    Prev,
+
+    /// Pause the slideshow, or resume it if paused
+    Pause,
+
+    /// Press next until a photo comes up
+    Goto {
+        /// Photo number, from 1, as the frame logs it (`Photo 3/7`)
+        photo: u32,
+    },
+}
+
 #[derive(Subcommand)]
 enum BootCommands {
     /// Analyze boot partition structure
@@ -249,6 +398,26 @@ enum GenerateCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum TelemetryCommands {
+    /// Extract `@tlm` telemetry lines from a serial log as CSV
+    Export {
+        /// Captured serial log (e.g. from `serial monitor --log`)
+        log: PathBuf,
+
+        /// Output path (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Report the peak of every `@mem` gauge in a serial log, with a
+    /// suggested size
+    Mem {
+        /// Captured serial log (e.g. from `serial monitor --log`)
+        log: PathBuf,
+    },
+}
+
 fn main() -> Result<()> {
     // Initialize logger
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
@@ -259,9 +428,14 @@ fn main() -> Result<()> {
         Commands::Devices(cmd) => handle_devices(cmd),
         #[cfg(feature = "serial")]
         Commands::Serial(cmd) => handle_serial(cmd),
+        #[cfg(feature = "serial")]
+        Commands::Debug(cmd) => handle_debug(cmd),
+        #[cfg(feature = "serial")]
+        Commands::Control { target, action } => handle_control(&target, action),
         Commands::Boot(cmd) => handle_boot(cmd),
         Commands::Image(cmd) => handle_image(cmd),
         Commands::Generate(cmd) => handle_generate(cmd),
+        Commands::Telemetry(cmd) => handle_telemetry(cmd),
     }
 }
 
@@ -495,6 +669,84 @@ fn handle_serial(cmd: SerialCommands) -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "serial")]
+fn open_target(target: &DebugTarget) -> Result<serial::SerialConnection> {
+    let profile = get_profile(&target.device).ok_or_else(|| {
+        anyhow::anyhow!("Unknown device profile: {}", target.device)
+    })?;
+
+    let baud_rate = target.baud.unwrap_or(profile.serial.baud_rate);
+    let config = PortConfig::new(&target.port).with_baud_rate(baud_rate);
+    serial::SerialConnection::open(config)
+}
+
+#[cfg(feature = "serial")]
+fn open_debug_session(target: &DebugTarget) -> Result<serial::debug::DebugSession> {
+    serial::debug::DebugSession::open(open_target(target)?)
+}
+
+#[cfg(feature = "serial")]
+fn handle_debug(cmd: DebugCommands) -> Result<()> {
+    let (target, command) = match &cmd {
+        DebugCommands::Peek { target, addr, width } => {
+            (target, format!("peek {:#x} {}", addr, width))
+        }
+        DebugCommands::Poke { target, addr, value, width } => {
+            (target, format!("poke {:#x} {:#x} {}", addr, value, width))
+        }
+        DebugCommands::Dump { target, addr, len } => (target, format!("dump {:#x} {}", addr, len)),
+        DebugCommands::Watch { target, addr, width, .. } => {
+            (target, format!("watch {:#x} {}", addr, width))
+        }
+        DebugCommands::Regions { target } => (target, String::from("regions")),
+    };
+
+    let mut session = open_debug_session(target)?;
+    println!("{} {}", "[TX]".cyan().bold(), command);
+    let result = session.run(&command);
+    // Leave monitor mode even if the command failed, so the console goes
+    // back to key input
+    let mut conn = session.close()?;
+    for line in result? {
+        println!("{}", line);
+    }
+
+    if let DebugCommands::Watch { target, addr, seconds, .. } = &cmd {
+        println!(
+            "{} Watching {:#x} for {}s",
+            "[*]".cyan().bold(),
+            addr,
+            seconds
+        );
+        serial::debug::follow_watches(&mut conn, Duration::from_secs(*seconds))?;
+        drop(conn);
+
+        let mut session = open_debug_session(target)?;
+        let result = session.run(&format!("unwatch {:#x}", addr));
+        session.close()?;
+        result?;
+    }
+
+    println!("{}", "[OK] Done".green());
+    Ok(())
+}
+
+#[cfg(feature = "serial")]
+fn handle_control(target: &DebugTarget, action: ControlCommands) -> Result<()> {
+    use serial::control::{Action, Controller};
+
+    let mut controller = Controller::new(open_target(target)?);
+    let shown = match action {
+        ControlCommands::Next => controller.press(Action::Next)?,
+        ControlCommands::Prev => controller.press(Action::Prev)?,
+        ControlCommands::Pause => controller.press(Action::Pause)?,
+        ControlCommands::Goto { photo } => controller.goto(photo)?,
+    };
+
+    println!("{} {}", "[OK]".green().bold(), shown);
+    Ok(())
+}
+
 fn handle_boot(cmd: BootCommands) -> Result<()> {
     match cmd {
         BootCommands::Analyze { path, device } => {
@@ -724,6 +976,79 @@ fn handle_image(cmd: ImageCommands) -> Result<()> {
     Ok(())
 }
 
+fn handle_telemetry(cmd: TelemetryCommands) -> Result<()> {
+    match cmd {
+        TelemetryCommands::Export { log, output } => {
+            let text = telemetry::read_log(&log)?;
+            let samples = telemetry::collect(text.lines());
+
+            match &output {
+                Some(path) => {
+                    let file = std::fs::File::create(path)
+                        .with_context(|| format!("Failed to create {}", path.display()))?;
+                    telemetry::write_csv(&samples, std::io::BufWriter::new(file))?;
+                }
+                None => telemetry::write_csv(&samples, std::io::stdout().lock())?,
+            }
+
+            // Keep stdout clean CSV; the summary goes to stderr
+            let summary = telemetry::summarize(&samples);
+            let range = |t: Option<f64>| t.map_or("n/a".to_string(), |t| format!("{:.1}°C", t));
+            eprintln!(
+                "{} {} samples in {} run(s), temperature {} .. {}",
+                "[OK]".green().bold(),
+                summary.samples,
+                summary.runs,
+                range(summary.temp_min_c),
+                range(summary.temp_max_c)
+            );
+            if summary.throttled_samples > 0 {
+                eprintln!(
+                    "{} {} samples report throttling or under-voltage since boot",
+                    "[WARN]".yellow().bold(),
+                    summary.throttled_samples
+                );
+            }
+            if summary.unavailable_samples > 0 {
+                eprintln!(
+                    "{} {} samples without a temperature or throttle reading",
+                    "[WARN]".yellow().bold(),
+                    summary.unavailable_samples
+                );
+            }
+        }
+        TelemetryCommands::Mem { log } => {
+            let text = telemetry::read_log(&log)?;
+            let gauges = mem::peaks(text.lines());
+            if gauges.is_empty() {
+                println!("{} No @mem lines in {}", "[WARN]".yellow().bold(), log.display());
+                return Ok(());
+            }
+
+            println!(
+                "{:<12} {:<16} {:>6} {:>10} {:>10} {:>7} {:>10}",
+                "PD", "BUFFER", "UNIT", "PEAK", "CAPACITY", "PEAK%", "SUGGESTED"
+            );
+            for gauge in &gauges {
+                let pct = format!("{:.1}", gauge.peak_pct());
+                let pct = if gauge.peak > gauge.capacity { pct.red().bold() } else { pct.normal() };
+                println!(
+                    "{:<12} {:<16} {:>6} {:>10} {:>10} {:>7} {:>10}",
+                    gauge.pd, gauge.name, gauge.unit, gauge.peak, gauge.capacity, pct, gauge.suggested()
+                );
+            }
+            println!();
+            println!(
+                "Suggested sizes leave {}% above the peak; a capture that never exercised a buffer \
+                 (no SD card, no grid) says nothing about it.",
+                mem::HEADROOM_PCT
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn handle_generate(cmd: GenerateCommands) -> Result<()> {
     match cmd {
         GenerateCommands::Config { device, output } => {
@@ -0,0 +1,135 @@
+//! Memory high-water marks from captured serial logs
+//!
+//! The protection domains account for their static buffers, rings and
+//! heaps (`rpi4_pd_error::mem`) and print a gauge as one line whenever its
+//! peak grows by another eighth of capacity; the photo frame also prints
+//! all of its gauges every minute:
+//!
+//! ```text
+//! @mem pd=photoframe name=decoder_heap unit=bytes used=0 peak=421888 cap=16777216 peak_pct=3
+//! ```
+//!
+//! The report keeps the highest peak seen per gauge across the whole
+//! capture, reboots included, and suggests a size for the region with
+//! [`HEADROOM_PCT`] to spare: whole 4 KB pages for bytes, since that is
+//! what a `memory_region` is made of.
+
+use std::collections::BTreeMap;
+
+/// Marker that starts a memory line
+const LINE_MARKER: &str = "@mem ";
+
+/// Spare capacity the suggested size leaves above the peak
+pub const HEADROOM_PCT: u64 = 25;
+
+/// Granule of a `memory_region`
+const PAGE_SIZE: u64 = 4096;
+
+/// One gauge reading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gauge {
+    pub pd: String,
+    pub name: String,
+    /// `bytes` or `slots`
+    pub unit: String,
+    pub used: u64,
+    pub peak: u64,
+    pub capacity: u64,
+}
+
+impl Gauge {
+    /// Peak plus headroom; bytes round up to whole pages
+    pub fn suggested(&self) -> u64 {
+        let size = (self.peak * (100 + HEADROOM_PCT)).div_ceil(100);
+        if self.unit == "bytes" {
+            size.div_ceil(PAGE_SIZE).max(1) * PAGE_SIZE
+        } else {
+            size.max(1)
+        }
+    }
+
+    /// The peak as a percentage of capacity
+    pub fn peak_pct(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.peak as f64 * 100.0 / self.capacity as f64
+    }
+}
+
+/// Parse a console line; `None` unless it carries a complete gauge
+///
+/// The marker may appear anywhere, so timestamped monitor logs work as is.
+pub fn parse_line(line: &str) -> Option<Gauge> {
+    let start = line.find(LINE_MARKER)?;
+    let mut fields: BTreeMap<&str, &str> = BTreeMap::new();
+    for field in line[start + LINE_MARKER.len()..].split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        fields.insert(key, value);
+    }
+    let num = |key: &str| -> Option<u64> { fields.get(key)?.parse().ok() };
+    Some(Gauge {
+        pd: fields.get("pd")?.to_string(),
+        name: fields.get("name")?.to_string(),
+        unit: fields.get("unit")?.to_string(),
+        used: num("used")?,
+        peak: num("peak")?,
+        capacity: num("cap")?,
+    })
+}
+
+/// The highest reading of each gauge in a capture, by PD and name
+pub fn peaks<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<Gauge> {
+    let mut out: BTreeMap<(String, String), Gauge> = BTreeMap::new();
+    for gauge in lines.into_iter().filter_map(parse_line) {
+        let key = (gauge.pd.clone(), gauge.name.clone());
+        match out.get(&key) {
+            Some(best) if best.peak >= gauge.peak => {}
+            _ => {
+                out.insert(key, gauge);
+            }
+        }
+    }
+    out.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = "[00:01:02.003] @mem pd=input name=input_ring unit=slots used=1 peak=3 cap=511 peak_pct=1\r";
+        let gauge = parse_line(line).unwrap();
+        assert_eq!((gauge.pd.as_str(), gauge.name.as_str(), gauge.unit.as_str()), ("input", "input_ring", "slots"));
+        assert_eq!((gauge.used, gauge.peak, gauge.capacity), (1, 3, 511));
+        assert_eq!(parse_line("@mem pd=input name=input_ring"), None);
+        assert_eq!(parse_line("@tlm seq=1 t=60"), None);
+    }
+
+    #[test]
+    fn test_peaks_keep_the_highest() {
+        let capture = [
+            "@mem pd=photoframe name=decoder_heap unit=bytes used=0 peak=100000 cap=16777216 peak_pct=1",
+            "@mem pd=storage name=file_buffer unit=bytes used=0 peak=421888 cap=4194304 peak_pct=11",
+            "@mem pd=photoframe name=decoder_heap unit=bytes used=0 peak=421888 cap=16777216 peak_pct=3",
+            // After a reboot the peak starts over
+            "@mem pd=photoframe name=decoder_heap unit=bytes used=0 peak=4096 cap=16777216 peak_pct=1",
+        ];
+        let gauges = peaks(capture);
+        let keys: Vec<_> = gauges.iter().map(|g| (g.pd.as_str(), g.name.as_str(), g.peak)).collect();
+        assert_eq!(keys, [("photoframe", "decoder_heap", 421888), ("storage", "file_buffer", 421888)]);
+    }
+
+    #[test]
+    fn test_suggested_size() {
+        let mut gauge = parse_line("@mem pd=storage name=file_buffer unit=bytes used=0 peak=421888 cap=4194304 peak_pct=11").unwrap();
+        // 421888 * 1.25 = 527360, 129 pages
+        assert_eq!(gauge.suggested(), 129 * 4096);
+        gauge.peak = 0;
+        assert_eq!(gauge.suggested(), 4096);
+        let ring = parse_line("@mem pd=input name=input_ring unit=slots used=0 peak=3 cap=511 peak_pct=1").unwrap();
+        assert_eq!(ring.suggested(), 4);
+        assert!((ring.peak_pct() - 0.587).abs() < 0.001);
+    }
+}
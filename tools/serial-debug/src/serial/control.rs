@@ -0,0 +1,164 @@
+//! Photo frame control over the serial console
+//!
+//! The photo frame's Input PD turns console bytes into keys (the `uart`
+//! decoder in `rpi4-input`) and the Photoframe PD logs what each key did,
+//! so a command is a key sent and the log line that confirms it:
+//!
+//! | Command | Key           | Confirmation                         |
+//! |---------|---------------|--------------------------------------|
+//! | next    | `ESC [ C` (→) | `Photo 4/7: MOUNTAINS`               |
+//! | prev    | `ESC [ D` (←) | `Photo 3/7: SUNSET`                  |
+//! | pause   | space         | `Paused` or `Slideshow resumed`      |
+//!
+//! `goto N` presses next until photo N is shown, at most once round the
+//! playlist, which also works in shuffle order. The frame has no
+//! brightness control and no network control protocol yet, so neither is
+//! offered here.
+
+use crate::serial::SerialConnection;
+use anyhow::{bail, Result};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the frame to confirm a key; a large photo from
+/// the SD card takes a few seconds to decode before the next key is read
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A key the frame understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Next,
+    Prev,
+    /// Pause or resume the slideshow
+    Pause,
+}
+
+impl Action {
+    /// Bytes the Input PD decodes as this action's key
+    pub fn key(self) -> &'static [u8] {
+        match self {
+            Action::Next => b"\x1b[C",
+            Action::Prev => b"\x1b[D",
+            Action::Pause => b" ",
+        }
+    }
+
+    /// Whether `confirmation` is what this action's key logs
+    fn confirmed_by(self, confirmation: &Confirmation) -> bool {
+        match self {
+            Action::Next | Action::Prev => matches!(confirmation, Confirmation::Photo { .. }),
+            Action::Pause => matches!(confirmation, Confirmation::Paused | Confirmation::Resumed),
+        }
+    }
+}
+
+/// A log line that confirms a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Now showing photo `index` (from 1) of `count`
+    Photo { index: u32, count: u32, name: String },
+    Paused,
+    Resumed,
+}
+
+impl std::fmt::Display for Confirmation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Confirmation::Photo { index, count, name } => write!(f, "photo {}/{}: {}", index, count, name),
+            Confirmation::Paused => write!(f, "paused"),
+            Confirmation::Resumed => write!(f, "slideshow resumed"),
+        }
+    }
+}
+
+/// Classify a console line; `None` for anything that confirms no key
+pub fn parse_confirmation(line: &str) -> Option<Confirmation> {
+    let line = line.trim();
+    match line {
+        "Paused" => return Some(Confirmation::Paused),
+        "Slideshow resumed" => return Some(Confirmation::Resumed),
+        _ => {}
+    }
+    // "Photo 3: favorite on" has no count and is not a move
+    let (position, name) = line.strip_prefix("Photo ")?.split_once(": ")?;
+    let (index, count) = position.split_once('/')?;
+    Some(Confirmation::Photo {
+        index: index.parse().ok()?,
+        count: count.parse().ok()?,
+        name: name.to_string(),
+    })
+}
+
+/// A connection to the frame's key input
+pub struct Controller {
+    conn: SerialConnection,
+}
+
+impl Controller {
+    pub fn new(conn: SerialConnection) -> Self {
+        Self { conn }
+    }
+
+    /// Press `action`'s key and wait for the frame to confirm it
+    pub fn press(&mut self, action: Action) -> Result<Confirmation> {
+        self.conn.write(action.key())?;
+        self.conn.flush()?;
+
+        let deadline = Instant::now() + CONFIRM_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Some(confirmation) = self.conn.read_line()?.as_deref().and_then(parse_confirmation) {
+                if action.confirmed_by(&confirmation) {
+                    return Ok(confirmation);
+                }
+            }
+        }
+        bail!("{:?}: no confirmation from the photo frame (is it in browse mode?)", action)
+    }
+
+    /// Press next until photo `photo` (from 1) is shown
+    pub fn goto(&mut self, photo: u32) -> Result<Confirmation> {
+        if photo == 0 {
+            bail!("photos are numbered from 1");
+        }
+        let first = self.press(Action::Next)?;
+        let Confirmation::Photo { count, .. } = first else {
+            unreachable!("next is only confirmed by a photo line");
+        };
+        if photo > count {
+            bail!("photo {} asked for, but the playlist has {}", photo, count);
+        }
+        let mut shown = first;
+        // Each photo comes up once per round, whatever the order
+        for _ in 0..count {
+            if matches!(shown, Confirmation::Photo { index, .. } if index == photo) {
+                return Ok(shown);
+            }
+            shown = self.press(Action::Next)?;
+        }
+        bail!("photo {} did not come up in a round of the playlist", photo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_confirmation() {
+        assert_eq!(
+            parse_confirmation("Photo 4/7: MOUNTAINS\r"),
+            Some(Confirmation::Photo { index: 4, count: 7, name: "MOUNTAINS".to_string() })
+        );
+        assert_eq!(parse_confirmation("Paused"), Some(Confirmation::Paused));
+        assert_eq!(parse_confirmation("Slideshow resumed"), Some(Confirmation::Resumed));
+        assert_eq!(parse_confirmation("Photo 3: favorite on"), None);
+        assert_eq!(parse_confirmation("Photoframe PD: transition fade"), None);
+    }
+
+    #[test]
+    fn test_keys_match_the_uart_decoder() {
+        assert_eq!(Action::Next.key(), &[0x1B, b'[', b'C']);
+        assert_eq!(Action::Prev.key(), &[0x1B, b'[', b'D']);
+        assert!(Action::Pause.confirmed_by(&Confirmation::Resumed));
+        assert!(!Action::Next.confirmed_by(&Confirmation::Paused));
+    }
+}
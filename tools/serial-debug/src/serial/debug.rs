@@ -0,0 +1,176 @@
+//! Client for the on-target peek/poke debug monitor
+//!
+//! Talks to `rpi4-debug-monitor` running inside a protection domain (the
+//! Input PD when built with CONFIG_DEBUG_MONITOR). The target switches its
+//! console into monitor mode on Ctrl-], answers every command with lines
+//! prefixed `@dbg` and finishes each command with `@dbg ok` or
+//! `@dbg err <reason>`. Anything else on the line is ordinary log output
+//! and is skipped.
+
+use crate::serial::SerialConnection;
+use anyhow::{bail, Result};
+use std::time::{Duration, Instant};
+
+/// Byte that toggles the target monitor on and off (Ctrl-])
+pub const ENTER_BYTE: u8 = 0x1D;
+
+/// Prefix of every monitor reply line
+const REPLY_PREFIX: &str = "@dbg";
+
+/// How long to wait for a command to finish
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// One classified monitor line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// Output line of the running command
+    Line(String),
+    /// Command succeeded
+    Ok,
+    /// Command failed with the given reason
+    Err(String),
+    /// Monitor switched on
+    Ready,
+    /// Monitor switched off by a second Ctrl-]
+    Off,
+}
+
+/// Classify a console line; `None` for anything that is not a monitor reply
+pub fn parse_reply(line: &str) -> Option<Reply> {
+    let rest = line.trim_end().strip_prefix(REPLY_PREFIX)?;
+    let body = rest.strip_prefix(' ').unwrap_or(rest);
+
+    Some(match body {
+        "ok" => Reply::Ok,
+        "monitor off" => Reply::Off,
+        _ if body.starts_with("monitor ready") => Reply::Ready,
+        _ => match body.strip_prefix("err ") {
+            Some(reason) => Reply::Err(reason.to_string()),
+            None => Reply::Line(body.to_string()),
+        },
+    })
+}
+
+/// Parse a decimal or `0x`-prefixed hex number (clap value parser)
+pub fn parse_number(s: &str) -> Result<u64, String> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("not a decimal or 0x-prefixed hex number: {}", s))
+}
+
+/// Parse an access width of 1, 2, 4 or 8 bytes (clap value parser)
+pub fn parse_width(s: &str) -> Result<u8, String> {
+    match s {
+        "1" | "2" | "4" | "8" => Ok(s.parse().unwrap()),
+        _ => Err(format!("width must be 1, 2, 4 or 8 bytes, got {}", s)),
+    }
+}
+
+/// An open monitor session on the target
+pub struct DebugSession {
+    conn: SerialConnection,
+}
+
+impl DebugSession {
+    /// Switch the target console into monitor mode
+    pub fn open(mut conn: SerialConnection) -> Result<Self> {
+        // Ctrl-] toggles, so a monitor left on by an earlier session answers
+        // "monitor off" and needs a second one
+        for _ in 0..2 {
+            conn.write(&[ENTER_BYTE])?;
+            conn.flush()?;
+            match wait_for(&mut conn, |r| matches!(r, Reply::Ready | Reply::Off))? {
+                Some(Reply::Ready) => return Ok(Self { conn }),
+                Some(_) => continue,
+                None => break,
+            }
+        }
+        bail!("No answer from the debug monitor (is the PD built with CONFIG_DEBUG_MONITOR?)")
+    }
+
+    /// Run one command and return its output lines
+    pub fn run(&mut self, command: &str) -> Result<Vec<String>> {
+        self.conn.write_str(command)?;
+        self.conn.write_str("\r")?;
+        self.conn.flush()?;
+
+        let mut lines = Vec::new();
+        let deadline = Instant::now() + REPLY_TIMEOUT;
+        while Instant::now() < deadline {
+            match self.conn.read_line()?.as_deref().and_then(parse_reply) {
+                Some(Reply::Line(line)) => lines.push(line),
+                Some(Reply::Ok) => return Ok(lines),
+                Some(Reply::Err(reason)) => bail!("{}: {}", command, reason),
+                Some(Reply::Ready) | Some(Reply::Off) | None => {}
+            }
+        }
+        bail!("{}: no reply from the debug monitor", command)
+    }
+
+    /// Leave monitor mode; the console goes back to key input
+    pub fn close(mut self) -> Result<SerialConnection> {
+        self.run("exit")?;
+        Ok(self.conn)
+    }
+}
+
+/// Read lines until one matches `want` or the reply timeout passes
+fn wait_for(conn: &mut SerialConnection, want: impl Fn(&Reply) -> bool) -> Result<Option<Reply>> {
+    let deadline = Instant::now() + REPLY_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Some(reply) = conn.read_line()?.as_deref().and_then(parse_reply) {
+            if want(&reply) {
+                return Ok(Some(reply));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Print `watch` reports from the target for `duration`
+///
+/// Watches keep running after the monitor is closed, so this only reads the
+/// console; the target keeps handling key input meanwhile.
+pub fn follow_watches(conn: &mut SerialConnection, duration: Duration) -> Result<()> {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if let Some(Reply::Line(line)) = conn.read_line()?.as_deref().and_then(parse_reply) {
+            if line.starts_with("watch ") {
+                println!("{}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply("@dbg ok\r"), Some(Reply::Ok));
+        assert_eq!(
+            parse_reply("@dbg err 0x0+0x4 is not mapped in this PD"),
+            Some(Reply::Err("0x0+0x4 is not mapped in this PD".to_string()))
+        );
+        assert_eq!(
+            parse_reply("@dbg 0x504000000 = 0x00000003"),
+            Some(Reply::Line("0x504000000 = 0x00000003".to_string()))
+        );
+        assert_eq!(parse_reply("@dbg monitor ready, type help"), Some(Reply::Ready));
+        assert_eq!(parse_reply("@dbg monitor off"), Some(Reply::Off));
+        assert_eq!(parse_reply("Input PD: Ring buffer full, dropping event"), None);
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        assert_eq!(parse_number("0x504000000"), Ok(0x5_0400_0000));
+        assert_eq!(parse_number("256"), Ok(256));
+        assert!(parse_number("0xzz").is_err());
+        assert_eq!(parse_width("8"), Ok(8));
+        assert!(parse_width("3").is_err());
+    }
+}
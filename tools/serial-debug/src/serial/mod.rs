@@ -4,7 +4,11 @@
 //! - Listing available serial ports (USB-to-serial adapters)
 //! - Reading serial output from a device's boot process
 //! - Logging and analyzing boot messages
+//! - Peek/poke/dump against the on-target debug monitor
+//! - Driving the photo frame with the keys its console accepts
 
+pub mod control;
+pub mod debug;
 pub mod monitor;
 pub mod port;
 
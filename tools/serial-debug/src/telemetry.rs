@@ -0,0 +1,244 @@
+//! Telemetry export from captured serial logs
+//!
+//! The photo frame appends a health sample (SoC temperature, firmware
+//! throttle flags, frame statistics) to its telemetry log every minute and
+//! echoes it on the console as one line:
+//!
+//! ```text
+//! @tlm seq=12 t=720 temp_mc=48312 throttled=0x0 frames=145 missed=0 photo=3
+//! ```
+//!
+//! After a PD restart the whole retained log is printed again, so a capture
+//! can hold the same record twice. Duplicates are dropped; a sequence number
+//! that comes back with different contents means the log itself was reset
+//! (the board rebooted), and starts a new run.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Marker that starts a telemetry line
+const LINE_MARKER: &str = "@tlm ";
+
+/// Temperature the target logs when the mailbox query failed
+const TEMP_UNAVAILABLE: i32 = i32::MIN;
+
+/// Throttle flags the target logs when the mailbox query failed
+const THROTTLED_UNAVAILABLE: u32 = u32::MAX;
+
+/// Firmware throttle flags, current state (GET_THROTTLED)
+const UNDER_VOLTAGE: u32 = 1 << 0;
+const THROTTLED_NOW: u32 = 1 << 2;
+/// Sticky "has happened since boot" half of the throttle flags
+const OCCURRED_MASK: u32 = 0xF << 16;
+
+/// One telemetry record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub seq: u32,
+    pub uptime_s: u32,
+    pub temp_milli_c: i32,
+    pub throttled: u32,
+    pub frames: u32,
+    pub missed_ticks: u32,
+    pub photo: u32,
+}
+
+impl Sample {
+    /// Temperature in degrees Celsius, if the target could read it
+    pub fn temp_c(&self) -> Option<f64> {
+        (self.temp_milli_c != TEMP_UNAVAILABLE).then(|| self.temp_milli_c as f64 / 1000.0)
+    }
+
+    /// Throttle flags, if the target could read them
+    pub fn throttle_flags(&self) -> Option<u32> {
+        (self.throttled != THROTTLED_UNAVAILABLE).then_some(self.throttled)
+    }
+}
+
+/// A sample and the run (boot) it belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSample {
+    pub run: u32,
+    pub sample: Sample,
+}
+
+/// Parse a console line; `None` unless it carries a complete telemetry record
+///
+/// The marker may appear anywhere, so timestamped monitor logs work as is.
+pub fn parse_line(line: &str) -> Option<Sample> {
+    let start = line.find(LINE_MARKER)?;
+    let mut fields: HashMap<&str, &str> = HashMap::new();
+    for field in line[start + LINE_MARKER.len()..].split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        fields.insert(key, value);
+    }
+
+    let num = |key: &str| -> Option<u32> {
+        let value = fields.get(key)?;
+        match value.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => value.parse().ok(),
+        }
+    };
+    Some(Sample {
+        seq: num("seq")?,
+        uptime_s: num("t")?,
+        temp_milli_c: fields.get("temp_mc")?.parse().ok()?,
+        throttled: num("throttled")?,
+        frames: num("frames")?,
+        missed_ticks: num("missed")?,
+        photo: num("photo")?,
+    })
+}
+
+/// Collect the samples of a capture, dropping replayed duplicates and
+/// numbering runs
+pub fn collect<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<RunSample> {
+    let mut out = Vec::new();
+    let mut seen: HashMap<u32, Sample> = HashMap::new();
+    let mut run = 0;
+
+    for sample in lines.into_iter().filter_map(parse_line) {
+        match seen.get(&sample.seq) {
+            Some(previous) if *previous == sample => continue,
+            Some(_) => {
+                run += 1;
+                seen.clear();
+            }
+            None => {}
+        }
+        seen.insert(sample.seq, sample);
+        out.push(RunSample { run, sample });
+    }
+    out
+}
+
+/// Write samples as CSV
+pub fn write_csv(samples: &[RunSample], mut out: impl Write) -> Result<()> {
+    writeln!(
+        out,
+        "run,seq,uptime_s,temp_c,throttled,under_voltage,throttled_now,frames,missed_ticks,photo"
+    )?;
+    for RunSample { run, sample } in samples {
+        let temp = sample.temp_c().map(|t| format!("{:.3}", t)).unwrap_or_default();
+        let (flags, under_voltage, throttled_now) = match sample.throttle_flags() {
+            Some(flags) => (
+                format!("{:#x}", flags),
+                (flags & UNDER_VOLTAGE != 0).to_string(),
+                (flags & THROTTLED_NOW != 0).to_string(),
+            ),
+            None => Default::default(),
+        };
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            run, sample.seq, sample.uptime_s, temp, flags, under_voltage, throttled_now,
+            sample.frames, sample.missed_ticks, sample.photo
+        )?;
+    }
+    Ok(())
+}
+
+/// Headline figures of a capture
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub samples: usize,
+    pub runs: u32,
+    pub temp_min_c: Option<f64>,
+    pub temp_max_c: Option<f64>,
+    /// Samples whose sticky throttle flags show any throttling or
+    /// under-voltage since boot
+    pub throttled_samples: usize,
+    /// Samples where the target could not read temperature or flags
+    pub unavailable_samples: usize,
+}
+
+/// Summarize a capture
+pub fn summarize(samples: &[RunSample]) -> Summary {
+    let temps = samples.iter().filter_map(|s| s.sample.temp_c());
+    Summary {
+        samples: samples.len(),
+        runs: samples.last().map_or(0, |s| s.run + 1),
+        temp_min_c: temps.clone().reduce(f64::min),
+        temp_max_c: temps.reduce(f64::max),
+        throttled_samples: samples
+            .iter()
+            .filter(|s| s.sample.throttle_flags().is_some_and(|f| f & OCCURRED_MASK != 0))
+            .count(),
+        unavailable_samples: samples
+            .iter()
+            .filter(|s| s.sample.temp_c().is_none() || s.sample.throttle_flags().is_none())
+            .count(),
+    }
+}
+
+/// Read a captured log; lines that are not valid UTF-8 are skipped
+pub fn read_log(path: &std::path::Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "@tlm seq=12 t=720 temp_mc=48312 throttled=0x50005 frames=145 missed=2 photo=3";
+
+    #[test]
+    fn test_parse_line() {
+        let sample = parse_line(&format!("[12:00:01.123] {}\r", LINE)).unwrap();
+        assert_eq!(sample.seq, 12);
+        assert_eq!(sample.uptime_s, 720);
+        assert_eq!(sample.temp_c(), Some(48.312));
+        assert_eq!(sample.throttle_flags(), Some(0x50005));
+        assert_eq!(sample.missed_ticks, 2);
+        assert_eq!(parse_line("Photo 3/8: Sunset"), None);
+        assert_eq!(parse_line("@tlm seq=1 t=60"), None);
+    }
+
+    #[test]
+    fn test_unavailable_readings() {
+        let line = format!("@tlm seq=0 t=60 temp_mc={} throttled=0xffffffff frames=1 missed=0 photo=0", i32::MIN);
+        let sample = parse_line(&line).unwrap();
+        assert_eq!(sample.temp_c(), None);
+        assert_eq!(sample.throttle_flags(), None);
+    }
+
+    #[test]
+    fn test_collect_drops_replays_and_splits_runs() {
+        let capture = [
+            "@tlm seq=0 t=60 temp_mc=45000 throttled=0x0 frames=10 missed=0 photo=0",
+            "@tlm seq=1 t=120 temp_mc=46000 throttled=0x0 frames=22 missed=0 photo=1",
+            // PD restart: the retained log is replayed
+            "Photoframe PD: Telemetry log resumed, 2 records",
+            "@tlm seq=0 t=60 temp_mc=45000 throttled=0x0 frames=10 missed=0 photo=0",
+            "@tlm seq=1 t=120 temp_mc=46000 throttled=0x0 frames=22 missed=0 photo=1",
+            "@tlm seq=2 t=60 temp_mc=47000 throttled=0x20000 frames=9 missed=1 photo=0",
+            // Reboot: the log starts over
+            "@tlm seq=0 t=60 temp_mc=41000 throttled=0x0 frames=11 missed=0 photo=0",
+        ];
+        let samples = collect(capture);
+        let keys: Vec<_> = samples.iter().map(|s| (s.run, s.sample.seq)).collect();
+        assert_eq!(keys, [(0, 0), (0, 1), (0, 2), (1, 0)]);
+
+        let summary = summarize(&samples);
+        assert_eq!(summary.runs, 2);
+        assert_eq!(summary.temp_min_c, Some(41.0));
+        assert_eq!(summary.temp_max_c, Some(47.0));
+        assert_eq!(summary.throttled_samples, 1);
+        assert_eq!(summary.unavailable_samples, 0);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let samples = collect([LINE]);
+        let mut out = Vec::new();
+        write_csv(&samples, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("0,12,720,48.312,0x50005,true,true,145,2,3")
+        );
+    }
+}